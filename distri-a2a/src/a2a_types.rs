@@ -352,6 +352,17 @@ pub struct MessageSendConfiguration {
     pub history_length: Option<u32>,
     #[serde(default)]
     pub push_notification_config: Option<PushNotificationConfig>,
+    /// Model to run this message with (distri extension). Honoured only
+    /// within the agent's `message_overrides`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    /// Sampling temperature for this message (distri extension).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
+    /// Reasoning effort for this message: `minimal`, `low`, `medium` or
+    /// `high` (distri extension).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reasoning_effort: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
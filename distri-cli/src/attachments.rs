@@ -0,0 +1,375 @@
+//! File attachments in `distri chat`.
+//!
+//! `/attach <path>`, or pasting the path of an image on its own line, queues
+//! the file; the queued files go out as file parts of the next message.
+//! Images larger than [`MAX_IMAGE_DIMENSION`] on a side are scaled down and
+//! re-encoded locally first, so screenshots fit the limits of multimodal
+//! providers. The server hands `image/*` parts to the model as images.
+
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use base64::Engine;
+use distri_a2a::{FileObject, FilePart, Part};
+use image::{imageops::FilterType, DynamicImage, GenericImageView, ImageFormat};
+
+/// Longest side of an attached image, in pixels.
+pub const MAX_IMAGE_DIMENSION: u32 = 1568;
+/// Largest attachment sent, after resizing.
+pub const MAX_ATTACHMENT_BYTES: usize = 5 * 1024 * 1024;
+/// Most files attached to one message.
+pub const MAX_ATTACHMENTS: usize = 10;
+
+const IMAGE_EXTENSIONS: &[(&str, &str)] = &[
+    ("png", "image/png"),
+    ("jpg", "image/jpeg"),
+    ("jpeg", "image/jpeg"),
+    ("gif", "image/gif"),
+    ("webp", "image/webp"),
+];
+
+const FILE_EXTENSIONS: &[(&str, &str)] = &[
+    ("pdf", "application/pdf"),
+    ("txt", "text/plain"),
+    ("md", "text/markdown"),
+    ("csv", "text/csv"),
+    ("json", "application/json"),
+    ("html", "text/html"),
+];
+
+/// A file read from disk, ready to be sent.
+#[derive(Debug, Clone)]
+pub struct Attachment {
+    pub name: String,
+    pub mime_type: String,
+    pub bytes: Vec<u8>,
+    /// Pixel size of an image as sent.
+    pub dimensions: Option<(u32, u32)>,
+    /// Pixel size before resizing, when the image was resized.
+    pub resized_from: Option<(u32, u32)>,
+}
+
+impl Attachment {
+    pub fn load(path: &Path) -> Result<Self> {
+        let bytes = std::fs::read(path).with_context(|| format!("reading {}", path.display()))?;
+        let name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_else(|| path.display().to_string());
+        let mime_type = mime_type(path).to_string();
+        let mut attachment = Self {
+            name,
+            mime_type,
+            bytes,
+            dimensions: None,
+            resized_from: None,
+        };
+        if is_resizable(&attachment.mime_type) {
+            attachment.fit_image()?;
+        }
+        if attachment.bytes.len() > MAX_ATTACHMENT_BYTES {
+            bail!(
+                "{} is {}, larger than the {} limit",
+                attachment.name,
+                format_size(attachment.bytes.len()),
+                format_size(MAX_ATTACHMENT_BYTES)
+            );
+        }
+        Ok(attachment)
+    }
+
+    /// Scale an image down to [`MAX_IMAGE_DIMENSION`] and re-encode it when
+    /// it is too large. PNGs stay PNGs; other images become JPEGs.
+    fn fit_image(&mut self) -> Result<()> {
+        let image = image::load_from_memory(&self.bytes)
+            .with_context(|| format!("decoding image {}", self.name))?;
+        let (width, height) = image.dimensions();
+        self.dimensions = Some((width, height));
+        if width.max(height) <= MAX_IMAGE_DIMENSION && self.bytes.len() <= MAX_ATTACHMENT_BYTES {
+            return Ok(());
+        }
+
+        let resized = if width.max(height) > MAX_IMAGE_DIMENSION {
+            image.resize(
+                MAX_IMAGE_DIMENSION,
+                MAX_IMAGE_DIMENSION,
+                FilterType::Lanczos3,
+            )
+        } else {
+            image
+        };
+        let (format, mime_type, resized) = if self.mime_type == "image/png" {
+            (ImageFormat::Png, "image/png", resized)
+        } else {
+            // JPEG has no alpha channel.
+            let rgb = DynamicImage::ImageRgb8(resized.to_rgb8());
+            (ImageFormat::Jpeg, "image/jpeg", rgb)
+        };
+        let mut bytes = Vec::new();
+        resized
+            .write_to(&mut Cursor::new(&mut bytes), format)
+            .with_context(|| format!("encoding image {}", self.name))?;
+        self.bytes = bytes;
+        self.mime_type = mime_type.to_string();
+        self.resized_from = Some((width, height));
+        self.dimensions = Some(resized.dimensions());
+        Ok(())
+    }
+
+    pub fn is_image(&self) -> bool {
+        self.mime_type.starts_with("image/")
+    }
+
+    /// One line describing the attachment, shown before it is queued.
+    pub fn summary(&self) -> String {
+        let mut summary = format!(
+            "{} ({}, {}",
+            self.name,
+            self.mime_type,
+            format_size(self.bytes.len())
+        );
+        if let Some((width, height)) = self.dimensions {
+            summary.push_str(&format!(", {}x{}", width, height));
+        }
+        if let Some((width, height)) = self.resized_from {
+            summary.push_str(&format!(", resized from {}x{}", width, height));
+        }
+        summary.push(')');
+        summary
+    }
+
+    pub fn to_part(&self) -> Part {
+        Part::File(FilePart {
+            file: FileObject::WithBytes {
+                bytes: base64::engine::general_purpose::STANDARD.encode(&self.bytes),
+                mime_type: Some(self.mime_type.clone()),
+                name: Some(self.name.clone()),
+            },
+            metadata: None,
+        })
+    }
+}
+
+/// Files queued for the next message.
+#[derive(Debug, Default)]
+pub struct PendingAttachments {
+    attachments: Vec<Attachment>,
+}
+
+impl PendingAttachments {
+    pub fn is_empty(&self) -> bool {
+        self.attachments.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.attachments.len()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Attachment> {
+        self.attachments.iter()
+    }
+
+    pub fn push(&mut self, attachment: Attachment) -> Result<()> {
+        if self.attachments.len() >= MAX_ATTACHMENTS {
+            bail!(
+                "at most {} files can be attached to a message",
+                MAX_ATTACHMENTS
+            );
+        }
+        self.attachments.push(attachment);
+        Ok(())
+    }
+
+    pub fn clear(&mut self) {
+        self.attachments.clear();
+    }
+
+    /// The queued files as message parts, emptying the queue.
+    pub fn take_parts(&mut self) -> Vec<Part> {
+        self.attachments.drain(..).map(|a| a.to_part()).collect()
+    }
+}
+
+/// The image a pasted line points at, when the line is nothing but the path
+/// of an existing image file. Terminals quote dropped paths or escape their
+/// spaces, so both forms are accepted.
+pub fn pasted_image_path(input: &str) -> Option<PathBuf> {
+    let path = PathBuf::from(unquote_path(input.trim())?);
+    let is_image = mime_type(&path).starts_with("image/");
+    (is_image && path.is_file()).then_some(path)
+}
+
+/// Undo the quoting and escaping of a path typed or pasted into the chat.
+pub fn unquote_path(input: &str) -> Option<String> {
+    let input = input.trim();
+    for quote in ['\'', '"'] {
+        if let Some(inner) = input
+            .strip_prefix(quote)
+            .and_then(|rest| rest.strip_suffix(quote))
+        {
+            return (!inner.is_empty()).then(|| expand_home(inner));
+        }
+    }
+    let mut path = String::with_capacity(input.len());
+    let mut chars = input.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => path.push(chars.next()?),
+            // An unescaped space means the line is more than a path.
+            c if c.is_whitespace() => return None,
+            c => path.push(c),
+        }
+    }
+    (!path.is_empty()).then(|| expand_home(&path))
+}
+
+fn expand_home(path: &str) -> String {
+    match (path.strip_prefix("~/"), dirs::home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest).display().to_string(),
+        _ => path.to_string(),
+    }
+}
+
+fn mime_type(path: &Path) -> &'static str {
+    let extension = path
+        .extension()
+        .map(|e| e.to_string_lossy().to_ascii_lowercase())
+        .unwrap_or_default();
+    IMAGE_EXTENSIONS
+        .iter()
+        .chain(FILE_EXTENSIONS)
+        .find(|(ext, _)| *ext == extension)
+        .map(|(_, mime)| *mime)
+        .unwrap_or("application/octet-stream")
+}
+
+/// Animated GIFs would lose their frames, so only still formats are resized.
+fn is_resizable(mime_type: &str) -> bool {
+    matches!(mime_type, "image/png" | "image/jpeg" | "image/webp")
+}
+
+fn format_size(bytes: usize) -> String {
+    if bytes >= 1024 * 1024 {
+        format!("{:.1} MB", bytes as f64 / (1024.0 * 1024.0))
+    } else if bytes >= 1024 {
+        format!("{} KB", bytes / 1024)
+    } else {
+        format!("{} B", bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_png(path: &Path, width: u32, height: u32) {
+        image::RgbaImage::from_pixel(width, height, image::Rgba([10, 20, 30, 255]))
+            .save(path)
+            .unwrap();
+    }
+
+    #[test]
+    fn pasted_paths_may_be_quoted_or_escaped() {
+        assert_eq!(
+            unquote_path("'/tmp/a b.png'").as_deref(),
+            Some("/tmp/a b.png")
+        );
+        assert_eq!(
+            unquote_path("\"/tmp/a b.png\"").as_deref(),
+            Some("/tmp/a b.png")
+        );
+        assert_eq!(
+            unquote_path("/tmp/a\\ b.png").as_deref(),
+            Some("/tmp/a b.png")
+        );
+        assert_eq!(unquote_path("what is in /tmp/a.png"), None);
+        assert_eq!(unquote_path("''"), None);
+    }
+
+    #[test]
+    fn only_existing_images_are_taken_from_pasted_lines() {
+        let dir = tempfile::tempdir().unwrap();
+        let image = dir.path().join("shot one.png");
+        write_png(&image, 4, 4);
+        let notes = dir.path().join("notes.txt");
+        std::fs::write(&notes, "hello").unwrap();
+
+        let quoted = format!("'{}'", image.display());
+        assert_eq!(pasted_image_path(&quoted), Some(image.clone()));
+        assert_eq!(pasted_image_path(&notes.display().to_string()), None);
+        let missing = dir.path().join("missing.png");
+        assert_eq!(pasted_image_path(&missing.display().to_string()), None);
+    }
+
+    #[test]
+    fn large_images_are_scaled_down() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("wide.png");
+        write_png(&path, 3136, 1000);
+
+        let attachment = Attachment::load(&path).unwrap();
+        assert_eq!(attachment.mime_type, "image/png");
+        assert_eq!(attachment.resized_from, Some((3136, 1000)));
+        assert_eq!(attachment.dimensions, Some((1568, 500)));
+        let decoded = image::load_from_memory(&attachment.bytes).unwrap();
+        assert_eq!(decoded.dimensions(), (1568, 500));
+        assert!(attachment.summary().contains("resized from 3136x1000"));
+    }
+
+    #[test]
+    fn small_files_are_sent_as_they_are() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("small.png");
+        write_png(&path, 8, 6);
+        let attachment = Attachment::load(&path).unwrap();
+        assert_eq!(attachment.bytes, std::fs::read(&path).unwrap());
+        assert_eq!(attachment.resized_from, None);
+
+        let notes = dir.path().join("notes.md");
+        std::fs::write(&notes, "# notes").unwrap();
+        let attachment = Attachment::load(&notes).unwrap();
+        assert_eq!(attachment.mime_type, "text/markdown");
+        assert!(!attachment.is_image());
+        match attachment.to_part() {
+            Part::File(FilePart {
+                file:
+                    FileObject::WithBytes {
+                        bytes, mime_type, ..
+                    },
+                ..
+            }) => {
+                assert_eq!(bytes, "IyBub3Rlcw==");
+                assert_eq!(mime_type.as_deref(), Some("text/markdown"));
+            }
+            other => panic!("expected a file part, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn oversized_files_are_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("big.bin");
+        std::fs::write(&path, vec![0u8; MAX_ATTACHMENT_BYTES + 1]).unwrap();
+        let err = Attachment::load(&path).unwrap_err();
+        assert!(err.to_string().contains("limit"), "{err}");
+    }
+
+    #[test]
+    fn attachments_per_message_are_limited() {
+        let attachment = Attachment {
+            name: "a.txt".to_string(),
+            mime_type: "text/plain".to_string(),
+            bytes: b"a".to_vec(),
+            dimensions: None,
+            resized_from: None,
+        };
+        let mut pending = PendingAttachments::default();
+        for _ in 0..MAX_ATTACHMENTS {
+            pending.push(attachment.clone()).unwrap();
+        }
+        assert!(pending.push(attachment).is_err());
+        assert_eq!(pending.take_parts().len(), MAX_ATTACHMENTS);
+        assert!(pending.is_empty());
+    }
+}
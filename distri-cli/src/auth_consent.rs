@@ -0,0 +1,169 @@
+// Interactive answer to a tool call's `AuthRequired` consent prompt.
+//
+// The server pauses the tool call and sends the scopes it needs plus a
+// ready-made authorization URL. We show the scopes diff, ask the user, and
+// open the URL. When the URL redirects to localhost we catch the redirect
+// with a local callback server (the same one `distri login` uses) and hand
+// the code back to the server, which exchanges it and resumes the call.
+
+use std::io::{self, Write};
+use std::time::Duration;
+
+use distri::{AuthConsentRequest, AuthConsentResponse};
+use serde::Deserialize;
+use tokio::sync::oneshot;
+use warp::Filter;
+
+use crate::login::open_browser;
+use crate::{COLOR_BRIGHT_GREEN, COLOR_BRIGHT_YELLOW, COLOR_GRAY, COLOR_RESET};
+
+#[derive(Debug, Deserialize)]
+struct OAuthRedirect {
+    #[serde(default)]
+    code: Option<String>,
+    #[serde(default)]
+    state: Option<String>,
+    #[serde(default)]
+    error: Option<String>,
+}
+
+pub async fn handle(request: AuthConsentRequest) -> AuthConsentResponse {
+    let declined = AuthConsentResponse {
+        auth_id: request.auth_id.clone(),
+        granted: false,
+        code: None,
+        state: None,
+    };
+
+    for scope in &request.current_scopes {
+        println!("{}    {}{}", COLOR_GRAY, scope, COLOR_RESET);
+    }
+    for scope in &request.missing_scopes {
+        println!("{}  + {}{}", COLOR_BRIGHT_GREEN, scope, COLOR_RESET);
+    }
+    let Some(authorize_url) = request.authorize_url.clone() else {
+        println!(
+            "The server cannot build an authorization URL for {}; connect it from the UI and retry.",
+            request.provider
+        );
+        return declined;
+    };
+    if !confirm(&format!("Grant {} these scopes? (y/n): ", request.provider)) {
+        println!("Authorization declined.");
+        return declined;
+    }
+
+    let timeout = Duration::from_millis(request.timeout_ms);
+    let redirect = match local_redirect_port(&authorize_url) {
+        Some(port) => catch_redirect(&authorize_url, port, timeout).await,
+        None => {
+            open_or_print(&authorize_url);
+            confirm("Press y once you have approved access in the browser: ");
+            return AuthConsentResponse {
+                granted: true,
+                ..declined
+            };
+        }
+    };
+
+    match redirect {
+        Ok(OAuthRedirect {
+            code: Some(code),
+            state,
+            ..
+        }) => {
+            println!(
+                "{}✓ Authorized {}, resuming {}{}",
+                COLOR_BRIGHT_GREEN, request.provider, request.tool_name, COLOR_RESET
+            );
+            AuthConsentResponse {
+                granted: true,
+                code: Some(code),
+                state,
+                ..declined
+            }
+        }
+        Ok(OAuthRedirect { error, .. }) => {
+            println!(
+                "Authorization failed: {}",
+                error.as_deref().unwrap_or("no code returned")
+            );
+            declined
+        }
+        Err(e) => {
+            println!("Authorization failed: {}", e);
+            declined
+        }
+    }
+}
+
+fn confirm(prompt: &str) -> bool {
+    print!("{}{}{}", COLOR_BRIGHT_YELLOW, prompt, COLOR_RESET);
+    io::stdout().flush().ok();
+    let mut input = String::new();
+    io::stdin().read_line(&mut input).is_ok() && input.trim().eq_ignore_ascii_case("y")
+}
+
+fn open_or_print(url: &str) {
+    if let Err(e) = open_browser(url) {
+        eprintln!("Failed to open browser: {}", e);
+    }
+    println!("If the browser did not open, visit:\n{}", url);
+}
+
+/// Port of the authorization URL's `redirect_uri` when it points back at
+/// this machine.
+fn local_redirect_port(authorize_url: &str) -> Option<u16> {
+    let url = reqwest::Url::parse(authorize_url).ok()?;
+    let redirect = url
+        .query_pairs()
+        .find(|(key, _)| key == "redirect_uri")
+        .map(|(_, value)| value.into_owned())?;
+    let redirect = reqwest::Url::parse(&redirect).ok()?;
+    match redirect.host_str()? {
+        "localhost" | "127.0.0.1" => redirect.port(),
+        _ => None,
+    }
+}
+
+/// Open `authorize_url` and wait for the provider to redirect to
+/// `localhost:<port>`.
+async fn catch_redirect(
+    authorize_url: &str,
+    port: u16,
+    timeout: Duration,
+) -> anyhow::Result<OAuthRedirect> {
+    let (tx, rx) = oneshot::channel::<OAuthRedirect>();
+    let tx = std::sync::Arc::new(std::sync::Mutex::new(Some(tx)));
+    let (shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
+    let shutdown_tx = std::sync::Arc::new(std::sync::Mutex::new(Some(shutdown_tx)));
+
+    let route = warp::query::<OAuthRedirect>().map(move |redirect: OAuthRedirect| {
+        let ok = redirect.code.is_some();
+        if let Some(tx) = tx.lock().unwrap().take() {
+            let _ = tx.send(redirect);
+        }
+        if let Some(tx) = shutdown_tx.lock().unwrap().take() {
+            let _ = tx.send(());
+        }
+        warp::reply::html(if ok {
+            r#"<!DOCTYPE html><html><body style="font-family: system-ui; text-align: center; padding: 50px;"><h1>✓ Access granted</h1><p>You can close this window and return to the terminal.</p></body></html>"#
+        } else {
+            r#"<!DOCTYPE html><html><body>Authorization failed.</body></html>"#
+        })
+    });
+
+    let (_, server) =
+        warp::serve(route).try_bind_with_graceful_shutdown(([127, 0, 0, 1], port), async move {
+            let _ = shutdown_rx.await;
+        })?;
+    let server = tokio::spawn(server);
+
+    open_or_print(authorize_url);
+    let redirect = tokio::time::timeout(timeout, rx).await;
+    server.abort();
+    match redirect {
+        Ok(Ok(redirect)) => Ok(redirect),
+        _ => anyhow::bail!("timed out waiting for the browser"),
+    }
+}
@@ -0,0 +1,172 @@
+//! `distri bench` — drive synthetic load against a server.
+//!
+//! Sends `requests` `message/send` calls to one agent, `concurrency` at a
+//! time, each on a fresh thread. Runs the server turned away under
+//! admission control (`429`) are counted apart from failures, and latency
+//! percentiles cover the completed runs only.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use anyhow::{bail, Result};
+use distri::{ClientError, Distri};
+use distri_types::Message;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Outcome {
+    Completed,
+    Rejected,
+    Failed,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Sample {
+    outcome: Outcome,
+    latency: Duration,
+}
+
+/// Latency at percentile `p` (0-100) of `sorted`, nearest-rank.
+fn percentile(sorted: &[Duration], p: f64) -> Option<Duration> {
+    if sorted.is_empty() {
+        return None;
+    }
+    let rank = ((p / 100.0) * sorted.len() as f64).ceil() as usize;
+    Some(sorted[rank.clamp(1, sorted.len()) - 1])
+}
+
+fn classify(result: &Result<Vec<Message>, ClientError>) -> Outcome {
+    match result {
+        Ok(_) => Outcome::Completed,
+        Err(ClientError::Http(e)) if e.status() == Some(reqwest::StatusCode::TOO_MANY_REQUESTS) => {
+            Outcome::Rejected
+        }
+        Err(_) => Outcome::Failed,
+    }
+}
+
+fn format_latency(latency: Option<Duration>) -> String {
+    latency
+        .map(|l| format!("{:.0} ms", l.as_secs_f64() * 1000.0))
+        .unwrap_or_else(|| "-".to_string())
+}
+
+pub async fn run_bench(
+    client: &Distri,
+    agent: &str,
+    requests: usize,
+    concurrency: usize,
+    message: &str,
+) -> Result<()> {
+    if requests == 0 || concurrency == 0 {
+        bail!("--requests and --concurrency must be at least 1");
+    }
+    let concurrency = concurrency.min(requests);
+    println!(
+        "Sending {} requests to '{}' with concurrency {} ({})",
+        requests,
+        agent,
+        concurrency,
+        client.base_url()
+    );
+
+    let next = Arc::new(AtomicUsize::new(0));
+    let first_error = Arc::new(std::sync::Mutex::new(None::<String>));
+    let started = Instant::now();
+    let workers: Vec<_> = (0..concurrency)
+        .map(|_| {
+            let client = client.clone();
+            let agent = agent.to_string();
+            let message = message.to_string();
+            let next = next.clone();
+            let first_error = first_error.clone();
+            tokio::spawn(async move {
+                let mut samples = Vec::new();
+                while next.fetch_add(1, Ordering::SeqCst) < requests {
+                    let messages = [Message::user(message.clone(), None)];
+                    let sent = Instant::now();
+                    let result = client.invoke(&agent, &messages).await;
+                    let outcome = classify(&result);
+                    if let (Outcome::Failed, Err(e)) = (outcome, &result) {
+                        first_error
+                            .lock()
+                            .unwrap()
+                            .get_or_insert_with(|| e.to_string());
+                    }
+                    samples.push(Sample {
+                        outcome,
+                        latency: sent.elapsed(),
+                    });
+                }
+                samples
+            })
+        })
+        .collect();
+
+    let mut samples = Vec::with_capacity(requests);
+    for worker in workers {
+        samples.extend(worker.await?);
+    }
+    let elapsed = started.elapsed();
+
+    let count = |outcome| samples.iter().filter(|s| s.outcome == outcome).count();
+    let completed = count(Outcome::Completed);
+    let mut latencies: Vec<Duration> = samples
+        .iter()
+        .filter(|s| s.outcome == Outcome::Completed)
+        .map(|s| s.latency)
+        .collect();
+    latencies.sort();
+
+    println!();
+    println!(
+        "Completed {}  rejected (429) {}  failed {}",
+        completed,
+        count(Outcome::Rejected),
+        count(Outcome::Failed)
+    );
+    println!(
+        "Elapsed {:.1}s  throughput {:.2} runs/s",
+        elapsed.as_secs_f64(),
+        completed as f64 / elapsed.as_secs_f64().max(f64::EPSILON)
+    );
+    println!(
+        "Latency p50 {}  p90 {}  p99 {}  max {}",
+        format_latency(percentile(&latencies, 50.0)),
+        format_latency(percentile(&latencies, 90.0)),
+        format_latency(percentile(&latencies, 99.0)),
+        format_latency(latencies.last().copied())
+    );
+    if let Some(error) = first_error.lock().unwrap().take() {
+        println!("First failure: {}", error);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(values: &[u64]) -> Vec<Duration> {
+        values.iter().map(|v| Duration::from_millis(*v)).collect()
+    }
+
+    #[test]
+    fn percentiles_use_the_nearest_rank() {
+        let sorted = ms(&(1..=100).collect::<Vec<_>>());
+        assert_eq!(percentile(&sorted, 50.0), Some(Duration::from_millis(50)));
+        assert_eq!(percentile(&sorted, 99.0), Some(Duration::from_millis(99)));
+        assert_eq!(percentile(&sorted, 100.0), Some(Duration::from_millis(100)));
+
+        let sorted = ms(&[10, 20, 30]);
+        assert_eq!(percentile(&sorted, 0.0), Some(Duration::from_millis(10)));
+        assert_eq!(percentile(&sorted, 90.0), Some(Duration::from_millis(30)));
+        assert_eq!(percentile(&[], 50.0), None);
+    }
+
+    #[test]
+    fn latencies_format_in_milliseconds() {
+        assert_eq!(format_latency(Some(Duration::from_micros(12_400))), "12 ms");
+        assert_eq!(format_latency(None), "-");
+    }
+}
@@ -1,4 +1,4 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::Instant;
@@ -9,12 +9,14 @@ use distri::{
     print_stream_with_health, AgentStreamClient, BuildHttpClient, ContextHealth, Distri,
     DistriClientApp, DistriConfig,
 };
+use distri_types::channel_commands::{is_system_command, SlashCommand};
 use distri_types::configuration::AgentConfig;
-use inquire::Select;
+use inquire::{Confirm, Select};
 use rustyline::error::ReadlineError;
 use rustyline::{Config, Editor, EventHandler, KeyEvent};
 use tokio::sync::RwLock;
 
+use crate::attachments::{pasted_image_path, unquote_path, Attachment, PendingAttachments};
 use crate::config::{load_last_model, save_last_model};
 use crate::input::{DistriHelper, ToggleToolsHandler};
 use crate::threads::{
@@ -30,6 +32,11 @@ pub enum SlashCommandResult {
     Exit,
     ClearContext,
     Resume(String),
+    /// A user-defined command rendered into a message for the agent.
+    Send(String),
+    /// `/attach <path>` queues a file; `/attach` alone lists the queue.
+    Attach(Option<String>),
+    Detach,
 }
 
 #[derive(Clone)]
@@ -132,7 +139,7 @@ pub fn print_separator_with_status(status: &str) {
     );
 }
 
-pub fn print_help_message() {
+pub fn print_help_message(custom_commands: &[SlashCommand]) {
     println!("AGENTS:");
     println!("- Use /agents to select an agent from the server");
     println!("- Use /agent <name> to switch directly");
@@ -149,10 +156,20 @@ pub fn print_help_message() {
     println!("  /resume <id>        - Resume a specific thread by ID");
     println!("  /traces             - List recent traces");
     println!("  /traces <id>        - Show trace detail with Gantt chart");
+    println!("  /attach <path>      - Attach a file or image to the next message");
+    println!("  /attach             - List the files attached to the next message");
+    println!("  /detach             - Remove the attached files");
     println!("  /clear              - Clear the current session context");
     println!("  /help               - Show this help message");
     println!("  /exit               - Exit the chat");
     println!();
+    if !custom_commands.is_empty() {
+        println!("CUSTOM COMMANDS:");
+        for cmd in custom_commands {
+            println!("  {:<19} - {}", cmd.usage(), cmd.description);
+        }
+        println!();
+    }
     println!("KEYBOARD SHORTCUTS:");
     println!("  Ctrl+O              - Toggle tool call output on/off");
     println!("  Tab                 - Autocomplete slash commands");
@@ -162,6 +179,7 @@ pub fn print_help_message() {
     println!("USAGE TIPS:");
     println!("- Type normally; the agent decides the best approach");
     println!("- Paste multi-line text — it stays as one message");
+    println!("- Paste or drop an image path to attach the image to your next message");
     println!("- Thread ID shown at start and exit for /resume");
 }
 
@@ -220,6 +238,7 @@ pub async fn select_agent_menu(app: &mut DistriClientApp) -> Result<Option<Strin
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub async fn handle_slash_command(
     input: &str,
     app: &mut DistriClientApp,
@@ -228,6 +247,7 @@ pub async fn handle_slash_command(
     current_model: &mut Option<String>,
     shared_health: &Arc<RwLock<distri::ContextHealth>>,
     thread_id: &str,
+    custom_commands: &[SlashCommand],
 ) -> Result<SlashCommandResult> {
     let mut parts = input.splitn(2, ' ');
     let command = parts.next().unwrap_or("");
@@ -235,7 +255,7 @@ pub async fn handle_slash_command(
 
     match command {
         "/help" => {
-            print_help_message();
+            print_help_message(custom_commands);
             Ok(SlashCommandResult::Continue)
         }
         "/context" | "/ctx" => {
@@ -245,6 +265,8 @@ pub async fn handle_slash_command(
         }
         "/exit" | "/quit" => Ok(SlashCommandResult::Exit),
         "/clear" => Ok(SlashCommandResult::ClearContext),
+        "/attach" => Ok(SlashCommandResult::Attach(arg.map(str::to_string))),
+        "/detach" => Ok(SlashCommandResult::Detach),
         "/compact" => {
             let client = Distri::from_config(config.clone());
             // Find the most recent task on this thread to compact. The
@@ -444,13 +466,104 @@ pub async fn handle_slash_command(
             }
             Ok(SlashCommandResult::Continue)
         }
-        _ => {
-            println!("Unknown command. Type /help for commands.");
-            Ok(SlashCommandResult::Continue)
+        _ => match custom_commands.iter().find(|c| c.matches(command)) {
+            // Commands bound to a tool run it directly; the others become
+            // a message to the agent.
+            Some(custom) if custom.tool.is_some() => {
+                let client = Distri::from_config(config.clone());
+                match client
+                    .run_command(&custom.name, arg.unwrap_or_default(), Some(current_agent))
+                    .await
+                {
+                    Ok(run) => println!(
+                        "{}",
+                        serde_json::to_string_pretty(&run.result).unwrap_or_default()
+                    ),
+                    Err(err) => eprintln!("{}", err),
+                }
+                Ok(SlashCommandResult::Continue)
+            }
+            Some(custom) => match custom.render(arg.unwrap_or_default()) {
+                Ok(message) => Ok(SlashCommandResult::Send(message)),
+                Err(err) => {
+                    eprintln!("{}", err);
+                    Ok(SlashCommandResult::Continue)
+                }
+            },
+            None => {
+                println!("Unknown command. Type /help for commands.");
+                Ok(SlashCommandResult::Continue)
+            }
+        },
+    }
+}
+
+/// User-defined slash commands for `agent`, minus the built-ins the CLI
+/// already handles itself. Empty when the server doesn't expose any.
+pub async fn fetch_custom_commands(config: &DistriConfig, agent: &str) -> Vec<SlashCommand> {
+    let client = Distri::from_config(config.clone());
+    client
+        .list_commands(Some(agent))
+        .await
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|c| !is_system_command(&c.name))
+        .collect()
+}
+
+/// Load `path`, show what will be sent and queue it once confirmed.
+fn queue_attachment(pending: &mut PendingAttachments, path: &Path) {
+    let attachment = match Attachment::load(path) {
+        Ok(attachment) => attachment,
+        Err(err) => {
+            eprintln!("Cannot attach {}: {:#}", path.display(), err);
+            return;
         }
+    };
+    let kind = if attachment.is_image() {
+        "image"
+    } else {
+        "file"
+    };
+    let confirmed = Confirm::new(&format!("Attach {} {}?", kind, attachment.summary()))
+        .with_default(true)
+        .prompt()
+        .unwrap_or(false);
+    if !confirmed {
+        return;
+    }
+    match pending.push(attachment) {
+        Ok(()) => println!(
+            "{}Attached {} file(s) — sent with your next message (/detach to remove){}",
+            COLOR_GRAY,
+            pending.len(),
+            COLOR_RESET
+        ),
+        Err(err) => eprintln!("{}", err),
     }
 }
 
+fn print_pending_attachments(pending: &PendingAttachments) {
+    if pending.is_empty() {
+        println!(
+            "{}No files attached. Use /attach <path>.{}",
+            COLOR_GRAY, COLOR_RESET
+        );
+        return;
+    }
+    println!("Attached to the next message:");
+    for attachment in pending.iter() {
+        println!("  {}", attachment.summary());
+    }
+}
+
+fn completion_names(commands: &[SlashCommand]) -> Vec<String> {
+    commands
+        .iter()
+        .flat_map(|c| std::iter::once(c.name.clone()).chain(c.aliases.iter().cloned()))
+        .collect()
+}
+
 pub async fn run_interactive_chat(
     app: &mut DistriClientApp,
     config: &DistriConfig,
@@ -518,12 +631,17 @@ pub async fn run_interactive_chat(
         })),
     );
 
+    let mut custom_commands = fetch_custom_commands(config, &current_agent).await;
+    if let Some(helper) = rl.helper_mut() {
+        helper.set_custom_commands(completion_names(&custom_commands));
+    }
+
     // Load history from existing file
     let history_path = get_history_file();
     let _ = rl.load_history(&history_path);
 
     let registry = app.registry();
-    register_approval_handler(&registry);
+    register_approval_handler(&registry, None);
     // Register all local CLI tools (Bash, Read, Write, Edit, Glob, Grep, execute_command)
     let workspace_path = std::env::current_dir().unwrap_or_else(|_| std::path::PathBuf::from("."));
     let tool_defs = register_all(&registry, &current_agent, &workspace_path);
@@ -535,12 +653,14 @@ pub async fn run_interactive_chat(
     // No separate name set needed — `is_external_tool` reads from the registry directly.
     let mut stream_client = AgentStreamClient::from_config(config.clone())
         .with_http_client(http_client)
-        .with_tool_registry(registry);
+        .with_tool_registry(registry)
+        .with_auth_handler(crate::auth_consent::handle);
     for tool in extra_tools {
         stream_client.register_dynamic_tool(tool);
     }
 
     let mut last_interrupt: Option<Instant> = None;
+    let mut pending_attachments = PendingAttachments::default();
     let shared_health: Arc<RwLock<ContextHealth>> = Arc::new(RwLock::new(ContextHealth::default()));
 
     loop {
@@ -585,8 +705,15 @@ pub async fn run_interactive_chat(
             continue;
         }
 
-        if input.starts_with('/') {
-            match handle_slash_command(
+        // Checked before slash commands: absolute paths start with '/'.
+        if let Some(path) = pasted_image_path(input) {
+            queue_attachment(&mut pending_attachments, &path);
+            continue;
+        }
+
+        let message = if input.starts_with('/') {
+            let previous_agent = current_agent.clone();
+            let result = handle_slash_command(
                 input,
                 app,
                 config,
@@ -594,9 +721,16 @@ pub async fn run_interactive_chat(
                 &mut current_model,
                 &shared_health,
                 &thread_id,
+                &custom_commands,
             )
-            .await?
-            {
+            .await?;
+            if current_agent != previous_agent {
+                custom_commands = fetch_custom_commands(config, &current_agent).await;
+                if let Some(helper) = rl.helper_mut() {
+                    helper.set_custom_commands(completion_names(&custom_commands));
+                }
+            }
+            match result {
                 SlashCommandResult::Continue => continue,
                 SlashCommandResult::Exit => break,
                 SlashCommandResult::ClearContext => {
@@ -617,8 +751,26 @@ pub async fn run_interactive_chat(
                     print_thread_history(&history_client, &thread_id).await;
                     continue;
                 }
+                SlashCommandResult::Send(message) => message,
+                SlashCommandResult::Attach(Some(path)) => {
+                    // Unquoted paths with spaces are taken as typed.
+                    let path = unquote_path(&path).unwrap_or(path);
+                    queue_attachment(&mut pending_attachments, Path::new(&path));
+                    continue;
+                }
+                SlashCommandResult::Attach(None) => {
+                    print_pending_attachments(&pending_attachments);
+                    continue;
+                }
+                SlashCommandResult::Detach => {
+                    pending_attachments.clear();
+                    println!("{}Attachments removed{}", COLOR_GRAY, COLOR_RESET);
+                    continue;
+                }
             }
-        }
+        } else {
+            input.to_string()
+        };
 
         // Resolve the canonical name and verify the agent exists. Only the card
         // is needed here — not the full definition — so use the cheap card fetch.
@@ -636,7 +788,7 @@ pub async fn run_interactive_chat(
         let distri_client = Distri::from_config(config.clone());
         let connections_context = build_connections_context(&distri_client).await;
         let mut params = build_message_params(
-            input.to_string(),
+            message,
             Some(&thread_id),
             None,
             current_model.as_deref(),
@@ -646,6 +798,10 @@ pub async fn run_interactive_chat(
             eprintln!("Tool registration error: {}", err);
             continue;
         }
+        params
+            .message
+            .parts
+            .extend(pending_attachments.take_parts());
 
         match print_stream_with_health(
             &stream_client,
@@ -0,0 +1,162 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use distri_types::packages::PackageManifest;
+use distri_types::workspace_config::{self, ConfigChange};
+
+const DISTRI_YAML: &str = "distri.yaml";
+/// The config file name before `distri.yaml`.
+const LEGACY_TOML: &str = "distri.toml";
+
+/// `distri config migrate`: upgrade the workspace config to the current
+/// version. `distri.yaml` is rewritten in place with its comments kept where
+/// possible; a legacy `distri.toml` is converted into a `distri.yaml` next
+/// to it. A `distri.toml` that is a workspace or package manifest is not a
+/// config and is left alone.
+pub fn migrate(file: Option<PathBuf>, workspace: &Path, dry_run: bool) -> Result<()> {
+    let path = config_path(file, workspace)?;
+    let (raw, mut config) = read(&path)?;
+    let is_toml = is_toml(&path);
+
+    let migration = workspace_config::migrate(&mut config)?;
+    for change in &migration.changes {
+        match change {
+            ConfigChange::Removed { key, reason } => println!("  - removed `{}`: {}", key, reason),
+            ConfigChange::Set {
+                key,
+                value,
+                replaces: Some(old),
+            } => println!("  ~ `{}` -> `{}: {}`", old, key, yaml_scalar(value)),
+            ConfigChange::Set { key, value, .. } => {
+                println!("  + `{}: {}`", key, yaml_scalar(value))
+            }
+        }
+    }
+    for key in workspace_config::unknown_keys(&config) {
+        println!(
+            "  ! unknown key `{}` is kept, but the server ignores it",
+            key
+        );
+    }
+    if migration.is_noop() && !is_toml {
+        println!(
+            "{} is already at config version {}",
+            path.display(),
+            migration.to_version
+        );
+        return Ok(());
+    }
+
+    let original = (!is_toml).then_some(raw.as_str());
+    let (text, kept_comments) = workspace_config::render(original, &config, &migration);
+    if dry_run {
+        print!("{}", text);
+        return Ok(());
+    }
+
+    let target = if is_toml {
+        path.with_file_name(DISTRI_YAML)
+    } else {
+        path.clone()
+    };
+    if is_toml && target.exists() {
+        bail!(
+            "{} already exists; merge {} into it by hand",
+            target.display(),
+            path.display()
+        );
+    }
+    std::fs::write(&target, text).with_context(|| format!("writing {}", target.display()))?;
+    println!(
+        "Migrated {} from config version {} to {}",
+        target.display(),
+        migration.from_version,
+        migration.to_version
+    );
+    if is_toml {
+        println!(
+            "Comments in {} were not carried over; the file can be removed.",
+            path.display()
+        );
+    } else if !kept_comments {
+        println!("The file was re-serialized; its comments could not be kept.");
+    }
+    Ok(())
+}
+
+/// `distri config show`: print the effective config — migrated to the
+/// current version, with `profile` (or `DISTRI_PROFILE`) merged in — as the
+/// server would load it.
+pub fn show(file: Option<PathBuf>, workspace: &Path, profile: Option<String>) -> Result<()> {
+    let profile = profile.or_else(|| std::env::var(workspace_config::PROFILE_ENV).ok());
+    let profile = profile.as_deref();
+    let path = config_path(file, workspace)?;
+    let (_, mut config) = read(&path)?;
+    workspace_config::migrate(&mut config)?;
+    let profiles = workspace_config::profile_names(&config);
+    workspace_config::apply_profile(&mut config, profile)?;
+
+    match profile {
+        Some(profile) => println!("# {} with profile `{}`", path.display(), profile),
+        None if profiles.is_empty() => println!("# {}", path.display()),
+        None => println!(
+            "# {} without a profile (profiles: {})",
+            path.display(),
+            profiles.join(", ")
+        ),
+    }
+    print!("{}", serde_yaml::to_string(&config)?);
+    Ok(())
+}
+
+/// `file`, or the workspace's `distri.yaml`, then `distri.toml`.
+fn config_path(file: Option<PathBuf>, workspace: &Path) -> Result<PathBuf> {
+    match file {
+        Some(path) if is_package_manifest(&path) => bail!(
+            "{} is a workspace package manifest, not a server config",
+            path.display()
+        ),
+        Some(path) => Ok(path),
+        None => [DISTRI_YAML, LEGACY_TOML]
+            .iter()
+            .map(|name| workspace.join(name))
+            .find(|path| path.exists() && !is_package_manifest(path))
+            .with_context(|| {
+                format!(
+                    "no {} or {} in {}",
+                    DISTRI_YAML,
+                    LEGACY_TOML,
+                    workspace.display()
+                )
+            }),
+    }
+}
+
+fn read(path: &Path) -> Result<(String, serde_yaml::Value)> {
+    let raw =
+        std::fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+    let config = if is_toml(path) {
+        toml::from_str(&raw).with_context(|| format!("parsing {}", path.display()))?
+    } else {
+        serde_yaml::from_str(&raw).with_context(|| format!("parsing {}", path.display()))?
+    };
+    Ok((raw, config))
+}
+
+fn is_toml(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == "toml")
+}
+
+fn is_package_manifest(path: &Path) -> bool {
+    is_toml(path)
+        && std::fs::read_to_string(path)
+            .ok()
+            .and_then(|raw| PackageManifest::parse(&raw).ok())
+            .is_some_and(|manifest| manifest.is_package_manifest())
+}
+
+fn yaml_scalar(value: &serde_yaml::Value) -> String {
+    serde_yaml::to_string(value)
+        .map(|s| s.trim_end().to_string())
+        .unwrap_or_default()
+}
@@ -0,0 +1,46 @@
+use anyhow::Result;
+use distri::Distri;
+
+use crate::{COLOR_BRIGHT_GREEN, COLOR_GRAY, COLOR_RESET};
+
+/// `distri dev seed`: fill the server's store with sample data so the UI and
+/// CLI have something realistic to show on a fresh install.
+pub async fn seed(client: &Distri) -> Result<()> {
+    let summary = client.dev_seed().await?;
+    let sections = [
+        ("agents", &summary.agents),
+        ("threads", &summary.threads),
+        ("prompt templates", &summary.prompt_templates),
+        ("secrets", &summary.secrets),
+    ];
+    for (label, items) in sections {
+        if items.is_empty() {
+            continue;
+        }
+        println!("{}:", label);
+        for item in items {
+            println!("  - {}", item);
+        }
+    }
+    if !summary.skipped.is_empty() {
+        println!(
+            "{}Already present: {}{}",
+            COLOR_GRAY,
+            summary.skipped.join(", "),
+            COLOR_RESET
+        );
+    }
+    if !summary.secrets.is_empty() {
+        println!(
+            "{}Secrets hold placeholder values; set real ones with `distri secrets set`.{}",
+            COLOR_GRAY, COLOR_RESET
+        );
+    }
+    println!(
+        "{}✔ Seeded {}{}",
+        COLOR_BRIGHT_GREEN,
+        client.base_url(),
+        COLOR_RESET
+    );
+    Ok(())
+}
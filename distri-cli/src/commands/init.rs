@@ -0,0 +1,69 @@
+use std::path::Path;
+
+use anyhow::Result;
+
+use crate::workspace::{self, TemplatePack};
+use crate::{COLOR_BRIGHT_GREEN, COLOR_GRAY, COLOR_RESET};
+
+/// `distri init --list`: print the built-in template packs.
+pub fn list() {
+    for pack in workspace::builtin_packs() {
+        let marker = if pack.name == workspace::DEFAULT_TEMPLATE {
+            " (default)"
+        } else {
+            ""
+        };
+        println!(
+            "  {}{}{}  {}{}{}",
+            COLOR_BRIGHT_GREEN, pack.name, marker, COLOR_GRAY, pack.description, COLOR_RESET
+        );
+    }
+}
+
+/// `distri init`: scaffold a workspace in `dir` from a built-in template
+/// pack or one fetched from a URL.
+pub async fn run(template: Option<String>, dir: &Path, force: bool) -> Result<()> {
+    let template = template.unwrap_or_else(|| workspace::DEFAULT_TEMPLATE.to_string());
+    let pack = workspace::resolve_pack(&template).await?;
+    let written = workspace::scaffold(&pack, dir, force)?;
+    for path in &written {
+        let shown = path.strip_prefix(dir).unwrap_or(path);
+        println!("  + {}", shown.display());
+    }
+    println!(
+        "{}✔ Initialized {} workspace in {}{}",
+        COLOR_BRIGHT_GREEN,
+        pack.name,
+        dir.display(),
+        COLOR_RESET
+    );
+    print_next_steps(&pack, dir);
+    Ok(())
+}
+
+fn print_next_steps(pack: &TemplatePack, dir: &Path) {
+    if !pack.mcp_servers.is_empty() {
+        println!("\nRecommended MCP servers:");
+        for server in &pack.mcp_servers {
+            println!("  - {}: {}", server.name, server.description);
+            for secret in &server.secrets {
+                println!(
+                    "{}      distri secrets set {} <value>{}",
+                    COLOR_GRAY, secret, COLOR_RESET
+                );
+            }
+        }
+        println!(
+            "{}  Enable one in an agent with `[[tools.mcp]] server = \"<name>\"`.{}",
+            COLOR_GRAY, COLOR_RESET
+        );
+    }
+
+    println!("\nNext steps:");
+    if dir != Path::new(".") {
+        println!("  cd {}", dir.display());
+    }
+    for step in &pack.post_init {
+        println!("  {}", step);
+    }
+}
@@ -1,3 +1,6 @@
+pub mod config;
+pub mod dev;
+pub mod init;
 pub mod uninstall;
 pub mod update;
 pub mod version;
@@ -6,9 +9,10 @@ use std::io::Write;
 use std::path::{Path, PathBuf};
 
 use anyhow::{Context, Result};
-use distri::{CreateSkillRequest, Distri};
+use distri::{AgentRegistrationResponse, CreateSkillRequest, Distri};
 use tokio::fs;
 
+use crate::output::{OutputFormat, ProfileRow};
 use crate::{
     ConnectionsCommands, ModelsCommands, ProfileCommands, ProfileConfigCommands, PromptsCommands,
     ProvidersCommands, SecretsCommands, SkillsCommands, COLOR_BRIGHT_GREEN, COLOR_GRAY,
@@ -23,7 +27,17 @@ fn mask_api_key(key: &str) -> String {
     }
 }
 
-pub fn handle_profile_command(command: ProfileCommands) -> Result<()> {
+fn profile_row(name: &str, active: bool, values: &crate::credentials::ProfileValues) -> ProfileRow {
+    ProfileRow {
+        name: name.to_string(),
+        active,
+        api_key: values.api_key.as_deref().map(mask_api_key),
+        workspace_id: values.workspace_id.clone(),
+        api_url: values.api_url.clone(),
+    }
+}
+
+pub fn handle_profile_command(command: ProfileCommands, output: OutputFormat) -> Result<()> {
     use crate::credentials::{
         delete_profile, get_active_profile, list_profiles, load_profile, save_profile,
         set_active_profile, unset_profile_keys, ProfileValues,
@@ -33,6 +47,13 @@ pub fn handle_profile_command(command: ProfileCommands) -> Result<()> {
         ProfileCommands::List => {
             let active = get_active_profile();
             let profiles = list_profiles()?;
+            if !output.is_text() {
+                let rows: Vec<ProfileRow> = profiles
+                    .iter()
+                    .map(|(name, values)| profile_row(name, name == &active, values))
+                    .collect();
+                return output.print_list(&rows, |_| {});
+            }
             if profiles.is_empty() {
                 println!("No profiles found. Run `distri login` or `distri profile config set` to create one.");
                 return Ok(());
@@ -78,6 +99,10 @@ pub fn handle_profile_command(command: ProfileCommands) -> Result<()> {
                         profile_name
                     );
                 }
+                Some(values) if !output.is_text() => {
+                    let active = profile_name == get_active_profile();
+                    output.print_value(&profile_row(&profile_name, active, &values), |_| {})?;
+                }
                 Some(values) => {
                     println!("Profile: {}", profile_name);
                     println!(
@@ -172,14 +197,21 @@ pub fn handle_profile_command(command: ProfileCommands) -> Result<()> {
     Ok(())
 }
 
-pub async fn handle_prompts_command(client: &Distri, command: PromptsCommands) -> Result<()> {
+pub async fn handle_prompts_command(
+    client: &Distri,
+    command: PromptsCommands,
+    output: OutputFormat,
+) -> Result<()> {
     match command {
         PromptsCommands::List => {
-            println!("📋 Listing prompt templates...");
+            if output.is_text() {
+                println!("📋 Listing prompt templates...");
+            }
             let templates = client.list_prompt_templates().await?;
-            if templates.is_empty() {
-                println!("No prompt templates found.");
-            } else {
+            output.print_list(&templates, |templates| {
+                if templates.is_empty() {
+                    println!("No prompt templates found.");
+                }
                 for template in templates {
                     let type_indicator = if template.is_system {
                         "system"
@@ -196,7 +228,7 @@ pub async fn handle_prompts_command(client: &Distri, command: PromptsCommands) -
                             .unwrap_or("(no description)")
                     );
                 }
-            }
+            })?;
         }
         PromptsCommands::Push { path } => {
             if !path.exists() {
@@ -299,10 +331,16 @@ pub async fn load_template_file(path: &Path) -> Result<distri::NewPromptTemplate
     })
 }
 
-pub async fn handle_skills_command(client: &Distri, command: SkillsCommands) -> Result<()> {
+pub async fn handle_skills_command(
+    client: &Distri,
+    command: SkillsCommands,
+    output: OutputFormat,
+) -> Result<()> {
     match command {
         SkillsCommands::List { all } => {
-            println!("Listing skills...");
+            if output.is_text() {
+                println!("Listing skills...");
+            }
             let scope = if all {
                 distri_types::stores::SkillScope::All
             } else {
@@ -314,17 +352,18 @@ pub async fn handle_skills_command(client: &Distri, command: SkillsCommands) ->
                     ..Default::default()
                 })
                 .await?;
-            if response.skills.is_empty() {
-                println!("No skills found.");
-            } else {
-                for skill in response.skills {
+            output.print_list(&response.skills, |skills| {
+                if skills.is_empty() {
+                    println!("No skills found.");
+                }
+                for skill in skills {
                     println!(
                         "{} - {}",
                         skill.name,
                         skill.description.as_deref().unwrap_or("(no description)")
                     );
                 }
-            }
+            })?;
         }
         SkillsCommands::Push { path, all } => {
             if !path.exists() {
@@ -528,38 +567,45 @@ fn create_request(
 pub async fn handle_connections_command(
     client: &Distri,
     command: ConnectionsCommands,
+    output: OutputFormat,
 ) -> Result<()> {
     match command {
         ConnectionsCommands::List => {
             let connections = client.list_connections().await?;
-            if connections.is_empty() {
-                println!("No connections found.");
-            } else {
+            output.print_list(&connections, |connections| {
+                if connections.is_empty() {
+                    println!("No connections found.");
+                }
                 for conn in connections {
                     let status = conn.status.as_deref().unwrap_or("unknown");
                     println!("{} - {} ({})", conn.id, conn.name, status);
                 }
-            }
+            })?;
         }
         ConnectionsCommands::Token { connection_id } => {
             let token = client.get_connection_token(&connection_id).await?;
-            println!("{}", token.access_token);
+            output.print_value(&token, |token| println!("{}", token.access_token))?;
         }
     }
     Ok(())
 }
 
-pub async fn handle_secrets_command(client: &Distri, command: SecretsCommands) -> Result<()> {
+pub async fn handle_secrets_command(
+    client: &Distri,
+    command: SecretsCommands,
+    output: OutputFormat,
+) -> Result<()> {
     match command {
         SecretsCommands::List => {
             let secrets = client.list_secrets().await?;
-            if secrets.is_empty() {
-                println!("No secrets found.");
-            } else {
+            output.print_list(&secrets, |secrets| {
+                if secrets.is_empty() {
+                    println!("No secrets found.");
+                }
                 for secret in secrets {
                     println!("{} = {}", secret.key, secret.masked_value);
                 }
-            }
+            })?;
         }
         SecretsCommands::Set { key, value } => {
             client
@@ -578,18 +624,23 @@ pub async fn handle_secrets_command(client: &Distri, command: SecretsCommands) -
     Ok(())
 }
 
-pub async fn handle_providers_command(client: &Distri, command: ProvidersCommands) -> Result<()> {
+pub async fn handle_providers_command(
+    client: &Distri,
+    command: ProvidersCommands,
+    output: OutputFormat,
+) -> Result<()> {
     match command {
         ProvidersCommands::List => {
             let providers = client.list_model_providers().await?;
-            if providers.is_empty() {
-                println!("No providers configured.");
-            } else {
+            output.print_list(&providers, |providers| {
+                if providers.is_empty() {
+                    println!("No providers configured.");
+                }
                 for p in providers {
                     let custom = if p.is_custom { " (custom)" } else { "" };
                     println!("{:30} {}{}", p.id, p.label, custom);
                 }
-            }
+            })?;
         }
         ProvidersCommands::Set {
             provider_id,
@@ -618,13 +669,18 @@ pub async fn handle_providers_command(client: &Distri, command: ProvidersCommand
     Ok(())
 }
 
-pub async fn handle_models_command(client: &Distri, command: ModelsCommands) -> Result<()> {
+pub async fn handle_models_command(
+    client: &Distri,
+    command: ModelsCommands,
+    output: OutputFormat,
+) -> Result<()> {
     match command {
         ModelsCommands::List => {
             let models = client.list_models().await?;
-            if models.is_empty() {
-                println!("No models available.");
-            } else {
+            output.print_list(&models, |models| {
+                if models.is_empty() {
+                    println!("No models available.");
+                }
                 for provider in models {
                     let status = if provider.configured { "✓" } else { " " };
                     println!(
@@ -635,12 +691,18 @@ pub async fn handle_models_command(client: &Distri, command: ModelsCommands) ->
                         println!("    {}", m.id);
                     }
                 }
-            }
+            })?;
+        }
+        ModelsCommands::GetDefault => {
+            let default_model = client.get_default_model().await?;
+            output.print_value(
+                &serde_json::json!({ "default_model": default_model }),
+                |_| match &default_model {
+                    Some(m) => println!("{m}"),
+                    None => println!("(no default model configured)"),
+                },
+            )?;
         }
-        ModelsCommands::GetDefault => match client.get_default_model().await? {
-            Some(m) => println!("{m}"),
-            None => println!("(no default model configured)"),
-        },
         ModelsCommands::SetDefault { provider_model } => {
             client.set_default_model(&provider_model).await?;
             if provider_model.is_empty() {
@@ -653,6 +715,37 @@ pub async fn handle_models_command(client: &Distri, command: ModelsCommands) ->
     Ok(())
 }
 
+/// Register the agent markdown at `path`. A `response_schema.file` is read
+/// relative to the markdown and sent inline, as the server cannot see it.
+pub async fn register_agent_markdown_file(
+    client: &Distri,
+    path: &Path,
+    markdown: &str,
+) -> Result<AgentRegistrationResponse> {
+    // Anything that does not parse is left to the server to report.
+    let definition = distri_types::parse_agent_markdown_content(markdown)
+        .await
+        .ok()
+        .filter(|def| {
+            def.response_schema
+                .as_ref()
+                .is_some_and(|schema| schema.file.is_some())
+        });
+    let Some(mut definition) = definition else {
+        return Ok(client.register_agent_markdown(markdown).await?);
+    };
+    let dir = path.parent().unwrap_or_else(|| Path::new("."));
+    if let Some(response_schema) = definition.response_schema.as_mut() {
+        response_schema
+            .inline_file(dir)
+            .map_err(|e| anyhow::anyhow!("{}: {}", path.display(), e))?;
+    }
+    let json = serde_json::to_string(&distri_types::configuration::AgentConfig::StandardAgent(
+        definition,
+    ))?;
+    Ok(client.register_agent_json(&json).await?)
+}
+
 pub async fn push_file(client: &Distri, path: &Path) -> Result<()> {
     println!();
     println!("→ Validating configuration...");
@@ -670,7 +763,7 @@ pub async fn push_file(client: &Distri, path: &Path) -> Result<()> {
     let definition = if is_json {
         client.register_agent_json(&content).await?
     } else {
-        client.register_agent_markdown(&content).await?
+        register_agent_markdown_file(client, path, &content).await?
     };
 
     let version = definition.version.as_deref().unwrap_or_default();
@@ -1,23 +1,24 @@
 use anyhow::Result;
 
 use crate::manifest;
+use crate::output::{OutputFormat, VersionInfo};
 
-pub fn run() -> Result<()> {
+pub fn run(output: OutputFormat) -> Result<()> {
     let mf = manifest::read()?;
-    println!("distri-cli    {}", env!("CARGO_PKG_VERSION"));
-    println!(
-        "distri-server {}",
-        mf.server
-            .as_ref()
-            .map(|r| r.version.as_str())
-            .unwrap_or("<not installed>"),
-    );
-    println!(
-        "distri-ui     {}",
-        mf.ui
-            .as_ref()
-            .map(|r| r.version.as_str())
-            .unwrap_or("<not installed>"),
-    );
-    Ok(())
+    let info = VersionInfo {
+        cli: env!("CARGO_PKG_VERSION").to_string(),
+        server: mf.server.as_ref().map(|r| r.version.clone()),
+        ui: mf.ui.as_ref().map(|r| r.version.clone()),
+    };
+    output.print_value(&info, |info| {
+        println!("distri-cli    {}", info.cli);
+        println!(
+            "distri-server {}",
+            info.server.as_deref().unwrap_or("<not installed>"),
+        );
+        println!(
+            "distri-ui     {}",
+            info.ui.as_deref().unwrap_or("<not installed>"),
+        );
+    })
 }
@@ -0,0 +1,151 @@
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use distri::Distri;
+use distri_types::evals::{
+    short_hash, EvalCase, EvalComparison, EvalHistory, EvalRun, EvalScore, EvalVerdict,
+};
+
+use crate::{EvalCommands, COLOR_BRIGHT_GREEN, COLOR_BRIGHT_YELLOW, COLOR_GRAY, COLOR_RESET};
+
+const COLOR_RED: &str = "\x1b[31m";
+
+pub async fn handle_eval_command(client: &Distri, command: EvalCommands) -> Result<()> {
+    match command {
+        EvalCommands::Run { agent, cases } => {
+            let cases = load_cases(&cases)?;
+            eprintln!("Running {} eval cases against '{}'...", cases.len(), agent);
+            let run = client.run_evals(&agent, cases).await?;
+            print_run(&run);
+        }
+        EvalCommands::Compare {
+            agent,
+            baseline,
+            candidate,
+        } => {
+            eprintln!("Judging {} against {}...", candidate, baseline);
+            let comparison = client.compare_evals(&agent, &baseline, &candidate).await?;
+            print_comparison(&comparison);
+            if comparison.score.is_regression() {
+                std::process::exit(1);
+            }
+        }
+        EvalCommands::History { agent } => {
+            let history = client.eval_history(&agent).await?;
+            print_history(&history);
+        }
+    }
+    Ok(())
+}
+
+/// Cases from a JSON array or a JSON-lines file of `{id, task, expected?}`.
+fn load_cases(path: &Path) -> Result<Vec<EvalCase>> {
+    let raw = std::fs::read_to_string(path)
+        .with_context(|| format!("reading eval cases from {}", path.display()))?;
+    if raw.trim_start().starts_with('[') {
+        return serde_json::from_str(&raw)
+            .with_context(|| format!("parsing eval cases in {}", path.display()));
+    }
+    raw.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            serde_json::from_str(line)
+                .with_context(|| format!("parsing {} line {}", path.display(), i + 1))
+        })
+        .collect()
+}
+
+fn print_run(run: &EvalRun) {
+    let summary = run.summary();
+    println!(
+        "Run {} of definition {}: {} cases, {} errors, {}ms avg",
+        run.id,
+        short_hash(&run.definition_hash),
+        summary.cases,
+        summary.errors,
+        summary.avg_duration_ms
+    );
+    for result in &run.results {
+        match &result.error {
+            Some(error) => println!(
+                "  {}{}{}  {}",
+                COLOR_RED, result.case.id, COLOR_RESET, error
+            ),
+            None => println!(
+                "  {}  {}{}ms{}",
+                result.case.id, COLOR_GRAY, result.duration_ms, COLOR_RESET
+            ),
+        }
+    }
+}
+
+fn print_comparison(comparison: &EvalComparison) {
+    println!(
+        "Baseline  {}  (run {})",
+        short_hash(&comparison.baseline.definition_hash),
+        comparison.baseline.run_id
+    );
+    println!(
+        "Candidate {}  (run {})",
+        short_hash(&comparison.candidate.definition_hash),
+        comparison.candidate.run_id
+    );
+    println!();
+    for case in &comparison.cases {
+        let (label, color) = match case.verdict {
+            EvalVerdict::Win => ("WIN ", COLOR_BRIGHT_GREEN),
+            EvalVerdict::Loss => ("LOSS", COLOR_RED),
+            EvalVerdict::Tie => ("TIE ", COLOR_GRAY),
+        };
+        println!("{}{}{}  {}", color, label, COLOR_RESET, case.case_id);
+        if !case.rationale.is_empty() {
+            println!("      {}{}{}", COLOR_GRAY, case.rationale, COLOR_RESET);
+        }
+    }
+    if !comparison.unmatched.is_empty() {
+        println!(
+            "{}Not compared (only in one run): {}{}",
+            COLOR_BRIGHT_YELLOW,
+            comparison.unmatched.join(", "),
+            COLOR_RESET
+        );
+    }
+    println!();
+    println!("{}", score_line(&comparison.score));
+}
+
+fn print_history(history: &EvalHistory) {
+    if history.runs.is_empty() {
+        println!("No eval runs for '{}'.", history.agent_id);
+        return;
+    }
+    println!(
+        "{:<12}  {:<20}  {:>5}  {:>6}  {:>8}  vs baseline",
+        "DEFINITION", "CREATED", "CASES", "ERRORS", "AVG MS"
+    );
+    for point in &history.runs {
+        let run = &point.run;
+        println!(
+            "{:<12}  {:<20}  {:>5}  {:>6}  {:>8}  {}",
+            short_hash(&run.definition_hash),
+            run.created_at.format("%Y-%m-%d %H:%M:%S"),
+            run.cases,
+            run.errors,
+            run.avg_duration_ms,
+            point.score.as_ref().map(score_line).unwrap_or_default()
+        );
+    }
+}
+
+fn score_line(score: &EvalScore) -> String {
+    let color = if score.is_regression() {
+        COLOR_RED
+    } else {
+        COLOR_BRIGHT_GREEN
+    };
+    format!(
+        "{}{}W {}L {}T{}",
+        color, score.wins, score.losses, score.ties, COLOR_RESET
+    )
+}
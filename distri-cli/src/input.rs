@@ -11,6 +11,8 @@ use rustyline::{Cmd, ConditionalEventHandler, Event, EventContext, Helper, Movem
 /// Rustyline helper for Distri CLI — provides slash-command completion and placeholder hint.
 pub struct DistriHelper {
     slash_commands: Vec<String>,
+    /// User-defined commands fetched from the server, refreshed on agent switch.
+    custom_commands: Vec<String>,
     matcher: SkimMatcherV2,
     show_tools: Arc<AtomicBool>,
 }
@@ -25,6 +27,8 @@ impl DistriHelper {
             "/model".to_string(),
             "/available-tools".to_string(),
             "/resume".to_string(),
+            "/attach".to_string(),
+            "/detach".to_string(),
             "/clear".to_string(),
             "/exit".to_string(),
             "/quit".to_string(),
@@ -32,10 +36,19 @@ impl DistriHelper {
 
         Self {
             slash_commands,
+            custom_commands: Vec::new(),
             matcher: SkimMatcherV2::default(),
             show_tools,
         }
     }
+
+    /// Replace the completable user-defined commands (names and aliases).
+    pub fn set_custom_commands(&mut self, names: Vec<String>) {
+        self.custom_commands = names
+            .into_iter()
+            .filter(|name| !self.slash_commands.contains(name))
+            .collect();
+    }
 }
 
 /// Ctrl+O handler — toggles tool output visibility.
@@ -80,6 +93,7 @@ impl Completer for DistriHelper {
         let mut matches: Vec<(i64, &String)> = self
             .slash_commands
             .iter()
+            .chain(self.custom_commands.iter())
             .filter_map(|cmd| {
                 self.matcher
                     .fuzzy_match(cmd, input)
@@ -1,4 +1,5 @@
 use anyhow::{anyhow, Result};
+use std::path::{Path, PathBuf};
 
 #[derive(Debug, Clone, Copy)]
 pub struct Platform {
@@ -29,6 +30,45 @@ impl Platform {
     pub fn ui_artifact(version: &str) -> String {
         format!("distri-ui-{version}.tar.gz")
     }
+
+    pub fn is_windows(&self) -> bool {
+        self.os == "windows"
+    }
+
+    /// Suffix the OS requires on executables (`.exe` on Windows).
+    pub fn exe_suffix(&self) -> &'static str {
+        if self.is_windows() {
+            ".exe"
+        } else {
+            ""
+        }
+    }
+
+    /// Names the server binary may have inside an extracted archive, most
+    /// specific first.
+    pub fn server_binary_names(&self, version: &str) -> [String; 2] {
+        let ext = self.exe_suffix();
+        [
+            format!("distri-server-{version}-{}-{}{ext}", self.os, self.arch),
+            format!("distri-server{ext}"),
+        ]
+    }
+
+    /// `path` with the executable suffix added when it is missing. Cached
+    /// manifests written before the suffix was tracked point at bare names.
+    pub fn executable_path(&self, path: &Path) -> PathBuf {
+        let ext = self.exe_suffix().trim_start_matches('.');
+        if ext.is_empty()
+            || path
+                .extension()
+                .is_some_and(|e| e.eq_ignore_ascii_case(ext))
+        {
+            return path.to_path_buf();
+        }
+        let mut name = path.as_os_str().to_os_string();
+        name.push(self.exe_suffix());
+        PathBuf::from(name)
+    }
 }
 
 #[cfg(test)]
@@ -54,4 +94,37 @@ mod tests {
         );
         assert_eq!(Platform::ui_artifact("0.5.7"), "distri-ui-0.5.7.tar.gz");
     }
+
+    #[test]
+    fn windows_binaries_carry_exe_suffix() {
+        let win = Platform {
+            os: "windows",
+            arch: "x64",
+        };
+        assert_eq!(
+            win.server_binary_names("0.5.3"),
+            [
+                "distri-server-0.5.3-windows-x64.exe".to_string(),
+                "distri-server.exe".to_string()
+            ]
+        );
+        assert_eq!(
+            win.executable_path(Path::new("bin/distri-server")),
+            PathBuf::from("bin/distri-server.exe")
+        );
+        assert_eq!(
+            win.executable_path(Path::new("bin/distri-server.EXE")),
+            PathBuf::from("bin/distri-server.EXE")
+        );
+
+        let linux = Platform {
+            os: "linux",
+            arch: "x64",
+        };
+        assert_eq!(linux.server_binary_names("0.5.3")[1], "distri-server");
+        assert_eq!(
+            linux.executable_path(Path::new("bin/distri-server")),
+            PathBuf::from("bin/distri-server")
+        );
+    }
 }
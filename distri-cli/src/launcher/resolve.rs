@@ -44,8 +44,12 @@ async fn resolve_artifact(
                     Some(pin) => *pin == v,
                     None => req.matches(&v),
                 };
-                if acceptable && rec.path.exists() {
-                    return Ok(rec.path.clone());
+                let path = match stream {
+                    Stream::Server => plat.executable_path(&rec.path),
+                    Stream::Ui => rec.path.clone(),
+                };
+                if acceptable && path.exists() {
+                    return Ok(path);
                 }
             }
         }
@@ -120,12 +124,12 @@ async fn resolve_artifact(
     // 6. Compute the binary path inside the extracted destination + persist manifest.
     let path = match stream {
         Stream::Server => {
-            let bin_name = format!("distri-server-{}-{}-{}", pick.0, plat.os, plat.arch);
-            let candidate = dest.join(&bin_name);
+            let [versioned, plain] = plat.server_binary_names(&pick.0.to_string());
+            let candidate = dest.join(versioned);
             if candidate.exists() {
                 candidate
             } else {
-                dest.join("distri-server")
+                dest.join(plain)
             }
         }
         Stream::Ui => dest.clone(),
@@ -143,7 +143,7 @@ pub async fn handle_login_command(
 }
 
 /// Open the system default browser with the given URL
-fn open_browser(url: &str) -> Result<()> {
+pub(crate) fn open_browser(url: &str) -> Result<()> {
     #[cfg(target_os = "macos")]
     {
         std::process::Command::new("open").arg(url).spawn()?;
@@ -6,20 +6,34 @@ use clap::{Parser, Subcommand};
 use distri::{print_stream_verbose, AgentStreamClient, BuildHttpClient, Distri, DistriClientApp};
 use tokio::fs;
 
+mod attachments;
+mod auth_consent;
+mod bench;
 mod chat;
 mod commands;
 mod config;
 mod credentials;
+mod evals;
 mod input;
 mod launcher;
 mod logging;
 mod login;
 mod manifest;
+mod notify;
+mod output;
+mod preferences;
 mod push;
 mod registries;
+mod runs;
+mod task_diff;
+mod telemetry;
 mod threads;
 mod tools;
+mod top;
 mod traces;
+mod url_handler;
+mod workflows;
+mod workspace;
 
 use chat::run_interactive_chat;
 use commands::{
@@ -29,6 +43,7 @@ use commands::{
 };
 use config::resolve_workspace;
 use distri::run::{build_run_params, resolve_agent_name, RunOptions};
+use output::{AgentRow, OutputFormat};
 use threads::resolve_resume_arg;
 use tools::{register_all, register_approval_handler};
 
@@ -47,6 +62,10 @@ struct Cli {
     #[clap(long, short, global = true)]
     verbose: bool,
 
+    /// Output format: `json` or `jsonl` for scripting (progress goes to stderr)
+    #[clap(long, global = true, value_enum, default_value_t = OutputFormat::Text)]
+    output: OutputFormat,
+
     #[clap(subcommand)]
     command: Option<Commands>,
 }
@@ -103,6 +122,10 @@ enum Commands {
         /// Repeatable: --header x-trace-source=cli
         #[clap(long = "header", value_name = "KEY=VALUE")]
         headers: Vec<String>,
+        /// Show a desktop notification when the run finishes, fails or
+        /// waits for an approval
+        #[clap(long)]
+        notify: bool,
     },
 
     /// Agent-related commands (defaults to list)
@@ -211,6 +234,72 @@ enum Commands {
         command: Option<TracesCommands>,
     },
 
+    /// Live dashboard of active runs: current step, tool in flight,
+    /// tokens/cost so far, and recent errors.
+    Top {
+        /// Seconds between refreshes of the active task list.
+        #[clap(long, default_value = "2")]
+        interval: u64,
+    },
+    /// Drive synthetic load against a server and report latency
+    /// percentiles, counting runs rejected by admission control.
+    Bench {
+        /// Agent to send the requests to.
+        agent: String,
+        /// Total requests to send.
+        #[clap(long, short = 'n', default_value = "50")]
+        requests: usize,
+        /// Requests in flight at once.
+        #[clap(long, short = 'c', default_value = "8")]
+        concurrency: usize,
+        /// Text of every request.
+        #[clap(long, default_value = "Reply with the word ok.")]
+        message: String,
+    },
+    /// Follow a thread's live runs read-only. Without `--token`, mints a
+    /// share token and prints what a teammate runs to join.
+    Watch {
+        thread_id: String,
+        /// Share token from another `distri watch` or `POST /threads/{id}/share`.
+        #[clap(long)]
+        token: Option<String>,
+        /// Lifetime in seconds of a newly minted token (server default 24h).
+        #[clap(long)]
+        ttl: Option<u64>,
+    },
+    /// Eval runs per agent version and regression comparisons between them
+    Eval {
+        #[clap(subcommand)]
+        command: EvalCommands,
+    },
+    /// Compare two tasks of one agent step by step — e.g. a regenerated
+    /// answer and its original — with token and cost deltas
+    Diff {
+        /// Task ID of the baseline
+        baseline: String,
+        /// Task ID of the candidate
+        candidate: String,
+    },
+    /// Re-run a finished workflow run from one step: the step and every
+    /// step downstream of it run again, reusing upstream outputs
+    Rerun {
+        /// Task ID of the workflow run
+        run: String,
+        /// Step to re-run from
+        #[clap(long)]
+        from: String,
+    },
+    /// Runs of agents: retry a failed one
+    Runs {
+        #[clap(subcommand)]
+        command: RunsCommands,
+    },
+    /// Your preferences — tone, language, expertise, timezone, answer
+    /// format — given to agents that opt in
+    Preferences {
+        #[clap(subcommand)]
+        command: PreferencesCommands,
+    },
     /// Auto-optimization commands (analyze traces, suggest improvements)
     Optimize {
         #[clap(subcommand)]
@@ -258,6 +347,61 @@ enum Commands {
         /// Don't open the browser after launch.
         #[clap(long)]
         no_browser: bool,
+        /// `distri.yaml` profile the server applies (e.g. dev, staging,
+        /// prod). The server reads DISTRI_PROFILE when not given
+        #[clap(long)]
+        profile: Option<String>,
+    },
+
+    /// Scaffold a workspace (agents, prompt templates, `distri.yaml`) from
+    /// a template pack
+    Init {
+        /// Directory to create the workspace in
+        #[clap(default_value = ".")]
+        dir: PathBuf,
+        /// Built-in template (research-assistant, coder, support-bot,
+        /// scraper) or a URL serving a template pack as JSON
+        #[clap(long, short)]
+        template: Option<String>,
+        /// Overwrite files that already exist
+        #[clap(long)]
+        force: bool,
+        /// List the built-in templates and exit
+        #[clap(long)]
+        list: bool,
+    },
+
+    /// Workspace config (`distri.yaml`) commands
+    Config {
+        #[clap(subcommand)]
+        command: ConfigCommands,
+    },
+
+    /// Anonymous usage reporting (off unless enabled; defaults to status)
+    Telemetry {
+        #[clap(subcommand)]
+        command: Option<TelemetryCommands>,
+    },
+
+    /// Desktop notification settings (defaults to status)
+    Notify {
+        #[clap(subcommand)]
+        command: Option<NotifyCommands>,
+    },
+
+    /// Open a distri:// link, e.g. distri://thread/<id>, in the web UI
+    Open { url: String },
+
+    /// Register distri as the OS handler of distri:// links
+    UrlHandler {
+        #[clap(subcommand)]
+        command: UrlHandlerCommands,
+    },
+
+    /// Local development helpers
+    Dev {
+        #[clap(subcommand)]
+        command: DevCommands,
     },
 
     /// Pull the latest distri-server and UI within the compat range.
@@ -314,6 +458,13 @@ enum ToolsCommands {
         #[clap(long, help = "Optional session id")]
         session: Option<String>,
     },
+    /// Show which implementation a tool name runs for an agent, and what it shadows
+    Resolve {
+        #[clap(help = "Tool name, alias or qualified package/tool name")]
+        name: String,
+        #[clap(long, default_value = "distri", help = "Agent whose tools to inspect")]
+        agent: String,
+    },
 }
 
 #[derive(Subcommand, Debug, Clone)]
@@ -507,6 +658,36 @@ fn parse_kv(s: &str) -> Result<(String, String), String> {
 pub(crate) enum ThreadsCommands {
     /// List all threads
     List,
+    /// Import conversations from a ChatGPT or Claude data export
+    Import {
+        /// The export's conversations.json
+        file: PathBuf,
+        /// Export format: chatgpt or claude
+        #[clap(long)]
+        format: distri_types::conversation_import::ImportFormat,
+        /// Agent the imported threads belong to
+        #[clap(long, default_value = "distri")]
+        agent: String,
+    },
+    /// Show or change a thread's variables, rendered as `{{name}}` in its
+    /// messages and system prompt
+    Vars {
+        thread_id: String,
+        /// `NAME=VALUE` pairs to set — repeatable.
+        #[clap(long = "set", value_parser = parse_kv)]
+        set: Vec<(String, String)>,
+        /// Variables to remove — repeatable.
+        #[clap(long = "unset")]
+        unset: Vec<String>,
+        /// Remove all variables before applying `--set`.
+        #[clap(long)]
+        clear: bool,
+    },
+    /// Move a thread's history to cold storage; it is restored when the
+    /// thread is opened again
+    Archive { thread_id: String },
+    /// Bring an archived thread's history back
+    Restore { thread_id: String },
 }
 
 #[derive(Subcommand, Debug, Clone)]
@@ -551,6 +732,73 @@ pub(crate) enum TracesCommands {
     },
 }
 
+#[derive(Subcommand, Debug, Clone)]
+pub(crate) enum TelemetryCommands {
+    /// Show whether telemetry is enabled and what is waiting to be sent
+    Status,
+    /// Send anonymous command counts, run success rates and crash reports
+    Enable {
+        /// Report to this URL instead of the default endpoint
+        #[clap(long)]
+        endpoint: Option<String>,
+    },
+    /// Stop collecting and delete anything not yet sent
+    Disable,
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub(crate) enum NotifyCommands {
+    /// Show the notification settings
+    Status,
+    /// Set the notification sound: a sound name of the platform, `default`
+    /// or `none` for silent notifications
+    Sound { name: String },
+    /// Show a test notification
+    Test,
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub(crate) enum UrlHandlerCommands {
+    /// Open distri:// links with this executable
+    Install,
+    /// Remove the distri:// link handler
+    Uninstall,
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub(crate) enum ConfigCommands {
+    /// Upgrade the workspace config to the current version, converting a
+    /// legacy distri.toml into distri.yaml
+    Migrate {
+        /// Config file to migrate (defaults to the workspace's distri.yaml,
+        /// then distri.toml)
+        #[clap(long)]
+        file: Option<PathBuf>,
+        /// Print the migrated file instead of writing it
+        #[clap(long)]
+        dry_run: bool,
+    },
+    /// Print the effective config: migrated, with the profile's overrides
+    /// merged in
+    Show {
+        /// Config file to read (defaults to the workspace's distri.yaml,
+        /// then distri.toml)
+        #[clap(long)]
+        file: Option<PathBuf>,
+        /// Profile to apply (defaults to DISTRI_PROFILE)
+        #[clap(long)]
+        profile: Option<String>,
+    },
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub(crate) enum DevCommands {
+    /// Populate the store with sample agents, threads with multi-step
+    /// histories, prompt templates and placeholder secrets. Safe to re-run:
+    /// existing fixtures are skipped
+    Seed,
+}
+
 #[derive(Subcommand, Debug, Clone)]
 pub(crate) enum OptimizeCommands {
     /// Analyze recent traces for an agent
@@ -588,6 +836,61 @@ pub(crate) enum OptimizeCommands {
     },
 }
 
+#[derive(Subcommand, Debug, Clone)]
+pub(crate) enum EvalCommands {
+    /// Run eval cases against an agent and record the results under its
+    /// current definition hash
+    Run {
+        #[clap(long)]
+        agent: String,
+        /// JSON array or JSON-lines file of `{"id", "task", "expected"?}` cases
+        cases: PathBuf,
+    },
+    /// Judge a candidate run against a baseline, case by case. Exits
+    /// non-zero when the candidate loses more cases than it wins.
+    Compare {
+        #[clap(long)]
+        agent: String,
+        /// Run ID or definition hash prefix (latest run of that definition)
+        baseline: String,
+        /// Run ID or definition hash prefix (latest run of that definition)
+        candidate: String,
+    },
+    /// List an agent's eval runs with their latest comparison scores
+    History {
+        #[clap(long)]
+        agent: String,
+    },
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub(crate) enum RunsCommands {
+    /// Run a failed task again with its original input, telling the agent
+    /// what went wrong the first time. The retry references the failed task
+    Retry {
+        /// Task ID of the failed run
+        task_id: String,
+        /// Model to retry with instead of the agent's
+        #[clap(long)]
+        model: Option<String>,
+    },
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub(crate) enum PreferencesCommands {
+    /// Show your stored preferences
+    Show,
+    /// Set preferences, e.g. `language=de expertise=expert units=metric`.
+    /// Names other than tone, language, expertise, timezone and format are
+    /// custom preferences; an empty value clears one.
+    Set {
+        #[clap(required = true)]
+        values: Vec<String>,
+    },
+    /// Remove all your preferences
+    Clear,
+}
+
 /// Typed context passed via `--context` JSON.
 /// Accepts `envs`, `env_vars`, and `secrets` — all merge into env_vars.
 #[derive(Debug, Clone, Default, serde::Deserialize)]
@@ -620,6 +923,8 @@ async fn main() -> Result<()> {
         resume: None,
         overrides: None,
     });
+    telemetry::install_panic_hook();
+    telemetry::record_command(&telemetry::command_name(&command));
 
     if let Commands::Serve {
         host,
@@ -629,6 +934,7 @@ async fn main() -> Result<()> {
         server_version,
         ui_version,
         no_browser,
+        profile,
     } = &command
     {
         run_distri_server(
@@ -640,6 +946,7 @@ async fn main() -> Result<()> {
             server_version.clone(),
             ui_version.clone(),
             *no_browser,
+            profile.clone(),
         )
         .await?;
         return Ok(());
@@ -693,6 +1000,7 @@ async fn main() -> Result<()> {
             traceparent,
             tags,
             headers,
+            notify,
         } => {
             let extra_tools = parse_cli_overrides(overrides.as_deref());
             let tag_map = parse_key_value_pairs(&tags);
@@ -777,10 +1085,17 @@ async fn main() -> Result<()> {
                 return Err(anyhow::anyhow!("Tool registration error: {}", err));
             }
 
-            println!("Streaming agent '{}' via {}", agent_name, base_url);
+            if cli.output.is_text() {
+                println!("Streaming agent '{}' via {}", agent_name, base_url);
+            } else {
+                eprintln!("Streaming agent '{}' via {}", agent_name, base_url);
+            }
+            let notifier = notify.then(|| {
+                notify::Notifier::new(agent_name.clone(), params.message.context_id.clone())
+            });
             let registry = app.registry();
             if !remote {
-                register_approval_handler(&registry);
+                register_approval_handler(&registry, notifier.clone());
             }
             let mut stream_config = config.clone().with_timeout(600);
             stream_config.traceparent = traceparent;
@@ -793,6 +1108,7 @@ async fn main() -> Result<()> {
                 AgentStreamClient::from_config(config.clone())
                     .with_http_client(http_client)
                     .with_tool_registry(registry)
+                    .with_auth_handler(auth_consent::handle)
             };
             for tool in extra_tools {
                 client.register_dynamic_tool(tool);
@@ -800,21 +1116,35 @@ async fn main() -> Result<()> {
             // print_stream_verbose is a pretty-print wrapper over
             // AgentStreamClient::stream_agent — same underlying call that
             // distri::run::stream_run wraps, just with terminal rendering.
-            print_stream_verbose(
-                &client,
-                &agent_name,
-                params,
-                cli.verbose,
-                Some(agent_name.clone()),
-                true,
-            )
-            .await?;
+            let outcome = if cli.output.is_text() {
+                print_stream_verbose(
+                    &client,
+                    &agent_name,
+                    params,
+                    cli.verbose,
+                    Some(agent_name.clone()),
+                    true,
+                )
+                .await
+            } else {
+                output::stream_run(&client, &agent_name, params, cli.output).await
+            };
+            telemetry::record_agent_run(outcome.is_ok());
+            if let Some(notifier) = &notifier {
+                let error = outcome.as_ref().err().map(|e| e.to_string());
+                notifier.run_finished(error.as_deref());
+            }
+            outcome?;
         }
         Commands::Agents { command } => match command.unwrap_or(AgentsCommands::List) {
             AgentsCommands::List => {
-                for agent in app.list_agents().await? {
-                    println!("{} - {}", agent.get_name(), agent.get_description());
-                }
+                let agents: Vec<AgentRow> = app
+                    .list_agents()
+                    .await?
+                    .iter()
+                    .map(|agent| AgentRow::new(agent.get_name(), agent.get_description()))
+                    .collect();
+                cli.output.print_list(&agents, output::print_agents)?;
             }
             AgentsCommands::Delete { agent, yes } => {
                 if !yes {
@@ -890,9 +1220,11 @@ async fn main() -> Result<()> {
                             || t.description.to_lowercase().contains(&term)
                     });
                 }
-                for tool in tools {
-                    println!("{} - {}", tool.tool_name, tool.description);
-                }
+                cli.output.print_list(&tools, |tools| {
+                    for tool in tools {
+                        println!("{} - {}", tool.tool_name, tool.description);
+                    }
+                })?;
             }
             ToolsCommands::Invoke {
                 name,
@@ -906,11 +1238,18 @@ async fn main() -> Result<()> {
                     None => serde_json::json!({}),
                 };
                 let result = app.call_tool(&name, payload, session).await?;
-                println!("{}", serde_json::to_string_pretty(&result)?);
+                match cli.output {
+                    OutputFormat::Jsonl => println!("{}", serde_json::to_string(&result)?),
+                    _ => println!("{}", serde_json::to_string_pretty(&result)?),
+                }
+            }
+            ToolsCommands::Resolve { name, agent } => {
+                let resolution = client.resolve_agent_tool(&agent, &name).await?;
+                cli.output.print_value(&resolution, print_tool_resolution)?;
             }
         },
         Commands::Profile { command } => {
-            handle_profile_command(command)?;
+            handle_profile_command(command, cli.output)?;
         }
         Commands::Login {
             email,
@@ -921,11 +1260,11 @@ async fn main() -> Result<()> {
         }
         Commands::Prompts { command } => {
             let command = command.unwrap_or(PromptsCommands::List);
-            handle_prompts_command(&client, command).await?;
+            handle_prompts_command(&client, command, cli.output).await?;
         }
         Commands::Skills { command } => {
             let command = command.unwrap_or(SkillsCommands::List { all: false });
-            handle_skills_command(&client, command).await?;
+            handle_skills_command(&client, command, cli.output).await?;
         }
         Commands::Push { path, dry_run } => {
             push::handle_push(&client, path, dry_run).await?;
@@ -944,23 +1283,23 @@ async fn main() -> Result<()> {
         }
         Commands::Connections { command } => {
             let command = command.unwrap_or(ConnectionsCommands::List);
-            handle_connections_command(&client, command).await?;
+            handle_connections_command(&client, command, cli.output).await?;
         }
         Commands::Secrets { command } => {
             let command = command.unwrap_or(SecretsCommands::List);
-            handle_secrets_command(&client, command).await?;
+            handle_secrets_command(&client, command, cli.output).await?;
         }
         Commands::Providers { command } => {
             let command = command.unwrap_or(ProvidersCommands::List);
-            handle_providers_command(&client, command).await?;
+            handle_providers_command(&client, command, cli.output).await?;
         }
         Commands::Models { command } => {
             let command = command.unwrap_or(ModelsCommands::List);
-            handle_models_command(&client, command).await?;
+            handle_models_command(&client, command, cli.output).await?;
         }
         Commands::Threads { command } => {
             let command = command.unwrap_or(ThreadsCommands::List);
-            threads::handle_threads_command(&client, command).await?;
+            threads::handle_threads_command(&client, command, cli.output).await?;
         }
         Commands::Traces { command } => {
             let command = command.unwrap_or(TracesCommands::List {
@@ -968,23 +1307,100 @@ async fn main() -> Result<()> {
                 agent: None,
                 tags: vec![],
             });
-            traces::handle_traces_command(&client, command).await?;
+            traces::handle_traces_command(&client, command, cli.output).await?;
+        }
+        Commands::Top { interval } => {
+            top::run_top(&client, interval).await?;
+        }
+        Commands::Bench {
+            agent,
+            requests,
+            concurrency,
+            message,
+        } => {
+            bench::run_bench(&client, &agent, requests, concurrency, &message).await?;
+        }
+        Commands::Watch {
+            thread_id,
+            token,
+            ttl,
+        } => {
+            threads::watch_thread(&client, &thread_id, token, ttl).await?;
+        }
+        Commands::Eval { command } => {
+            evals::handle_eval_command(&client, command).await?;
+        }
+        Commands::Diff {
+            baseline,
+            candidate,
+        } => {
+            task_diff::compare_tasks(&client, &baseline, &candidate, cli.output).await?;
+        }
+        Commands::Rerun { run, from } => {
+            workflows::rerun_workflow(&client, &run, &from, cli.output).await?;
+        }
+        Commands::Runs { command } => {
+            // The retry runs to completion before the server answers.
+            let client = Distri::from_config(config.clone().with_timeout(600));
+            runs::handle_runs_command(&client, command, cli.output).await?;
+        }
+        Commands::Preferences { command } => {
+            preferences::handle_preferences_command(&client, command, cli.output).await?;
         }
         Commands::Optimize { command } => {
             traces::handle_optimize_command(&client, command).await?;
         }
+        Commands::Init {
+            dir,
+            template,
+            force,
+            list,
+        } => {
+            if list {
+                commands::init::list();
+            } else {
+                commands::init::run(template, &dir, force).await?;
+            }
+        }
+        Commands::Config { command } => match command {
+            ConfigCommands::Migrate { file, dry_run } => {
+                commands::config::migrate(file.or(cli.config.clone()), &workspace, dry_run)?;
+            }
+            ConfigCommands::Show { file, profile } => {
+                commands::config::show(file.or(cli.config.clone()), &workspace, profile)?;
+            }
+        },
+        Commands::Dev { command } => match command {
+            DevCommands::Seed => commands::dev::seed(&client).await?,
+        },
         Commands::Update { pre } => {
             commands::update::run(pre).await?;
         }
         Commands::Version => {
-            commands::version::run()?;
+            commands::version::run(cli.output)?;
         }
         Commands::Uninstall => {
             commands::uninstall::run()?;
         }
+        Commands::Telemetry { command } => {
+            telemetry::handle_telemetry_command(
+                command.unwrap_or(TelemetryCommands::Status),
+                cli.output,
+            )?;
+        }
+        Commands::Notify { command } => {
+            notify::handle_notify_command(command.unwrap_or(NotifyCommands::Status), cli.output)?;
+        }
+        Commands::Open { url } => {
+            url_handler::open_link(&url, &base_url)?;
+        }
+        Commands::UrlHandler { command } => {
+            url_handler::handle_url_handler_command(command)?;
+        }
         Commands::Serve { .. } => unreachable!("serve handled earlier"),
     }
 
+    telemetry::report_if_due().await;
     Ok(())
 }
 
@@ -1001,6 +1417,7 @@ async fn run_distri_server(
     server_version: Option<String>,
     ui_version: Option<String>,
     no_browser: bool,
+    profile: Option<String>,
 ) -> Result<()> {
     // Resolve the server binary via the launcher (downloads if needed).
     let server_opts = launcher::resolve::ResolveOpts {
@@ -1018,6 +1435,12 @@ async fn run_distri_server(
         .await
         .context("resolving distri-server")?;
     eprintln!("Using distri-server: {}", server_bin.display());
+    if !server_bin.is_file() {
+        anyhow::bail!(
+            "distri-server binary not found at {} (unexpected release archive layout)",
+            server_bin.display()
+        );
+    }
 
     // Optionally resolve the UI bundle.
     let ui_path = if no_ui {
@@ -1060,6 +1483,9 @@ async fn run_distri_server(
     if let Some(ref p) = ui_path {
         cmd.arg("--ui-dist").arg(p);
     }
+    if let Some(profile) = profile {
+        cmd.arg("--profile").arg(profile);
+    }
 
     // Open browser before blocking on the child process.
     if !no_browser && !headless {
@@ -1095,6 +1521,42 @@ fn parse_key_value_pairs(pairs: &[String]) -> std::collections::HashMap<String,
     map
 }
 
+fn print_tool_resolution(resolution: &distri_types::tool_catalog::ToolResolution) {
+    use distri_types::tool_catalog::CollisionRule;
+
+    match &resolution.tool {
+        Some(tool) => {
+            println!(
+                "{} -> {} ({})",
+                resolution.requested,
+                tool.qualified_name,
+                tool.source.as_str()
+            );
+            if let Some(target) = &resolution.alias_of {
+                println!("  alias of {}", target);
+            }
+        }
+        None => println!("{}: no callable tool by that name", resolution.requested),
+    }
+    if let Some(collision) = &resolution.collision {
+        let rule = match collision.rule {
+            CollisionRule::Preferred => "tools.collisions.prefer",
+            CollisionRule::Precedence => "source precedence",
+            CollisionRule::FirstRegistered => "registration order",
+        };
+        println!(
+            "  '{}' is provided {} times; {} wins by {}",
+            collision.name,
+            collision.shadowed.len() + 1,
+            collision.winner.qualified_name,
+            rule
+        );
+        for shadowed in &collision.shadowed {
+            println!("  shadowed: {}", shadowed.qualified_name);
+        }
+    }
+}
+
 fn parse_cli_overrides(json: Option<&str>) -> Vec<distri_types::dynamic_tool::DynamicToolFactory> {
     let Some(json) = json else {
         return Vec::new();
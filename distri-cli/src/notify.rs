@@ -0,0 +1,182 @@
+//! Desktop notifications for `distri run --notify`.
+//!
+//! A notification is shown when the run completes or fails, and when the
+//! agent stops to wait for an approval. Each one carries the thread's
+//! `distri://thread/<id>` link (see [`crate::url_handler`]). The sound is
+//! saved in `~/.distri/notifications.json`: a sound name of the platform
+//! (`default` for the system's default sound), or `none` for silent ones.
+
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::output::OutputFormat;
+use crate::url_handler::thread_link;
+use crate::NotifyCommands;
+
+const SETTINGS_FILE: &str = "notifications.json";
+const APP_NAME: &str = "distri";
+const DEFAULT_SOUND: &str = "default";
+const SILENT: &str = "none";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Settings {
+    #[serde(default = "default_sound")]
+    pub sound: String,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            sound: default_sound(),
+        }
+    }
+}
+
+fn default_sound() -> String {
+    DEFAULT_SOUND.to_string()
+}
+
+impl Settings {
+    fn path() -> Result<PathBuf> {
+        Ok(crate::manifest::distri_home()?.join(SETTINGS_FILE))
+    }
+
+    pub fn load() -> Self {
+        Self::path()
+            .ok()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|raw| serde_json::from_str(&raw).ok())
+            .unwrap_or_default()
+    }
+
+    fn save(&self) -> Result<()> {
+        let path = Self::path()?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("creating {}", parent.display()))?;
+        }
+        std::fs::write(&path, serde_json::to_vec_pretty(self)?)
+            .with_context(|| format!("writing {}", path.display()))
+    }
+
+    /// The sound to play, `None` when notifications are silent.
+    fn sound(&self) -> Option<&str> {
+        let sound = self.sound.trim();
+        (!sound.is_empty() && !sound.eq_ignore_ascii_case(SILENT)).then_some(sound)
+    }
+}
+
+/// Shows the notifications of one run.
+#[derive(Debug, Clone)]
+pub struct Notifier {
+    settings: Settings,
+    agent: String,
+    thread_id: Option<String>,
+}
+
+impl Notifier {
+    pub fn new(agent: impl Into<String>, thread_id: Option<String>) -> Self {
+        Self {
+            settings: Settings::load(),
+            agent: agent.into(),
+            thread_id,
+        }
+    }
+
+    /// The run ended: `error` is `None` when it completed.
+    pub fn run_finished(&self, error: Option<&str>) {
+        match error {
+            None => self.show(&format!("{} finished", self.agent), "The run completed."),
+            Some(error) => self.show(
+                &format!("{} failed", self.agent),
+                &format!("The run failed: {}", error),
+            ),
+        }
+    }
+
+    /// The run waits for the user to approve `tools`.
+    pub fn approval_needed(&self, tools: &[String]) {
+        let body = if tools.is_empty() {
+            "The agent is waiting for your approval.".to_string()
+        } else {
+            format!("Approve {} in the terminal to continue.", tools.join(", "))
+        };
+        self.show(&format!("{} needs approval", self.agent), &body);
+    }
+
+    fn show(&self, summary: &str, body: &str) {
+        let mut body = body.to_string();
+        if let Some(thread_id) = &self.thread_id {
+            body.push('\n');
+            body.push_str(&thread_link(thread_id));
+        }
+        let mut notification = notify_rust::Notification::new();
+        notification.appname(APP_NAME).summary(summary).body(&body);
+        if let Some(sound) = self.settings.sound() {
+            #[cfg(any(target_os = "macos", target_os = "windows"))]
+            notification.sound_name(sound);
+            #[cfg(all(unix, not(target_os = "macos")))]
+            notification.hint(notify_rust::Hint::SoundName(sound.to_string()));
+        }
+        // Notifications are a convenience: a desktop without a notification
+        // service must not fail the run.
+        if let Err(e) = notification.show() {
+            tracing::debug!("failed to show desktop notification: {}", e);
+        }
+    }
+}
+
+pub fn handle_notify_command(command: NotifyCommands, output: OutputFormat) -> Result<()> {
+    let mut settings = Settings::load();
+    match command {
+        NotifyCommands::Status => {
+            output.print_value(&settings, |settings| {
+                println!("Sound: {}", settings.sound);
+            })?;
+        }
+        NotifyCommands::Sound { name } => {
+            settings.sound = name;
+            settings.save()?;
+            match settings.sound() {
+                Some(sound) => println!("Notifications will play '{}'.", sound),
+                None => println!("Notifications are silent."),
+            }
+        }
+        NotifyCommands::Test => {
+            Notifier::new("distri", None).show(
+                "distri notifications work",
+                "Run `distri run --notify ...` to be notified when a run needs you.",
+            );
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn none_makes_notifications_silent() {
+        let sound = |name: &str| {
+            Settings {
+                sound: name.to_string(),
+            }
+            .sound()
+            .map(str::to_string)
+        };
+        assert_eq!(sound("default").as_deref(), Some("default"));
+        assert_eq!(sound("Glass").as_deref(), Some("Glass"));
+        assert_eq!(sound("None"), None);
+        assert_eq!(sound(" "), None);
+    }
+
+    #[test]
+    fn settings_default_to_the_system_sound() {
+        let settings: Settings = serde_json::from_str("{}").unwrap();
+        assert_eq!(settings, Settings::default());
+        assert_eq!(settings.sound, "default");
+    }
+}
@@ -0,0 +1,329 @@
+//! Machine-readable output: the global `--output text|json|jsonl` flag.
+//!
+//! `text` (the default) is for people. `json` prints one document on stdout
+//! and `jsonl` one object per line: a list's items, or the events of a
+//! `run`. In both, progress lines go to stderr so stdout always parses.
+//!
+//! Rows the CLI composes itself have a stable shape defined here; lists the
+//! server returns are printed exactly as the API returns them.
+
+use anyhow::Result;
+use distri::a2a::MessageSendParams;
+use distri::{AgentStreamClient, StreamError, StreamItem};
+use distri_types::{AgentEvent, AgentEventType, Message, MessageRole, RunUsage};
+use serde::Serialize;
+
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OutputFormat {
+    #[default]
+    Text,
+    Json,
+    Jsonl,
+}
+
+impl OutputFormat {
+    pub fn is_text(self) -> bool {
+        self == OutputFormat::Text
+    }
+
+    /// Print a list: a JSON array, one object per line, or `text(items)`.
+    pub fn print_list<T: Serialize>(self, items: &[T], text: impl FnOnce(&[T])) -> Result<()> {
+        match self {
+            OutputFormat::Text => text(items),
+            OutputFormat::Json => println!("{}", serde_json::to_string_pretty(items)?),
+            OutputFormat::Jsonl => {
+                for item in items {
+                    println!("{}", serde_json::to_string(item)?);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Print a single value; `jsonl` puts it on one line.
+    pub fn print_value<T: Serialize>(self, value: &T, text: impl FnOnce(&T)) -> Result<()> {
+        match self {
+            OutputFormat::Text => text(value),
+            OutputFormat::Json => println!("{}", serde_json::to_string_pretty(value)?),
+            OutputFormat::Jsonl => println!("{}", serde_json::to_string(value)?),
+        }
+        Ok(())
+    }
+}
+
+/// `agents list` row.
+#[derive(Debug, Serialize)]
+pub struct AgentRow {
+    pub name: String,
+    pub description: String,
+    /// Workspace package of a namespaced (`<package>/<agent>`) agent.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub package: Option<String>,
+}
+
+impl AgentRow {
+    pub fn new(name: &str, description: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            description: description.to_string(),
+            package: name.split_once('/').map(|(package, _)| package.to_string()),
+        }
+    }
+}
+
+/// `agents list` rows grouped per package: agents without one first, then
+/// each package in name order.
+pub fn group_agents_by_package(agents: &[AgentRow]) -> Vec<(Option<&str>, Vec<&AgentRow>)> {
+    let mut groups: std::collections::BTreeMap<Option<&str>, Vec<&AgentRow>> = Default::default();
+    for agent in agents {
+        groups
+            .entry(agent.package.as_deref())
+            .or_default()
+            .push(agent);
+    }
+    groups.into_iter().collect()
+}
+
+/// Text output of `agents list`.
+pub fn print_agents(agents: &[AgentRow]) {
+    for (package, agents) in group_agents_by_package(agents) {
+        match package {
+            None => {
+                for agent in agents {
+                    println!("{} - {}", agent.name, agent.description);
+                }
+            }
+            Some(package) => {
+                println!("\n[{}]", package);
+                for agent in agents {
+                    println!("  {} - {}", agent.name, agent.description);
+                }
+            }
+        }
+    }
+}
+
+/// `profile list` / `profile show` row. The API key is always masked.
+#[derive(Debug, Serialize)]
+pub struct ProfileRow {
+    pub name: String,
+    pub active: bool,
+    pub api_key: Option<String>,
+    pub workspace_id: Option<String>,
+    pub api_url: Option<String>,
+}
+
+/// `version` output; `None` when the component is not installed.
+#[derive(Debug, Serialize)]
+pub struct VersionInfo {
+    pub cli: String,
+    pub server: Option<String>,
+    pub ui: Option<String>,
+}
+
+/// Outcome of `run`: the whole output in `json`, the last line in `jsonl`.
+#[derive(Debug, Default, Serialize)]
+pub struct RunResult {
+    pub agent: String,
+    pub thread_id: Option<String>,
+    pub run_id: Option<String>,
+    /// `false` until the run reports it finished successfully.
+    pub success: bool,
+    /// Text of the agent's last reply.
+    pub text: Option<String>,
+    pub usage: Option<RunUsage>,
+    pub error: Option<String>,
+}
+
+impl RunResult {
+    fn new(agent: &str) -> Self {
+        Self {
+            agent: agent.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn record(&mut self, item: &StreamItem) {
+        if let Some(event) = &item.agent_event {
+            self.record_event(event);
+        }
+        if let Some(message) = &item.message {
+            if message.role == MessageRole::Assistant {
+                if let Some(text) = message.as_text().filter(|t| !t.trim().is_empty()) {
+                    self.text = Some(text);
+                }
+            }
+        }
+    }
+
+    fn record_event(&mut self, event: &AgentEvent) {
+        // Sub-agent runs report their own start and finish on the same stream.
+        if event.parent_task_id.is_some() {
+            return;
+        }
+        if self.thread_id.is_none() {
+            self.thread_id = Some(event.thread_id.clone());
+        }
+        if self.run_id.is_none() {
+            self.run_id = Some(event.run_id.clone());
+        }
+        match &event.event {
+            AgentEventType::RunFinished { success, usage, .. } => {
+                self.success = *success;
+                self.usage = usage.clone();
+            }
+            AgentEventType::RunError { message, usage, .. } => {
+                self.success = false;
+                self.error = Some(message.clone());
+                self.usage = usage.clone();
+            }
+            _ => {}
+        }
+    }
+}
+
+/// One line of `run --output jsonl`.
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum RunLine<'a> {
+    Event { event: &'a AgentEvent },
+    Message { message: &'a Message },
+    Result { result: &'a RunResult },
+}
+
+/// Stream a run without terminal rendering: every event and message as a
+/// line in `jsonl`, then the [`RunResult`] (the only output in `json`).
+pub async fn stream_run(
+    client: &AgentStreamClient,
+    agent: &str,
+    params: MessageSendParams,
+    format: OutputFormat,
+) -> Result<(), StreamError> {
+    let mut result = RunResult::new(agent);
+    client
+        .stream_agent(agent, params, |item: StreamItem| {
+            if format == OutputFormat::Jsonl {
+                if let Some(event) = &item.agent_event {
+                    print_line(&RunLine::Event { event });
+                }
+                if let Some(message) = &item.message {
+                    print_line(&RunLine::Message { message });
+                }
+            }
+            result.record(&item);
+            async {}
+        })
+        .await?;
+    match format {
+        OutputFormat::Jsonl => print_line(&RunLine::Result { result: &result }),
+        _ => {
+            if let Ok(json) = serde_json::to_string_pretty(&result) {
+                println!("{}", json);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn print_line<T: Serialize>(line: &T) {
+    if let Ok(json) = serde_json::to_string(line) {
+        println!("{}", json);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(parent_task_id: Option<&str>, event: AgentEventType) -> AgentEvent {
+        AgentEvent {
+            timestamp: chrono::Utc::now(),
+            thread_id: "thread-1".to_string(),
+            run_id: "run-1".to_string(),
+            event,
+            task_id: "task-1".to_string(),
+            parent_task_id: parent_task_id.map(str::to_string),
+            agent_id: "coder".to_string(),
+            user_id: None,
+            identifier_id: None,
+            workspace_id: None,
+            channel_id: None,
+        }
+    }
+
+    #[test]
+    fn run_result_keeps_root_outcome_and_last_reply() {
+        let mut result = RunResult::new("coder");
+        let finished = |success| AgentEventType::RunFinished {
+            success,
+            total_steps: 1,
+            failed_steps: 0,
+            usage: None,
+            context_budget: None,
+        };
+        result.record_event(&event(None, finished(true)));
+        result.record_event(&event(Some("task-0"), finished(false)));
+        result.record(&StreamItem {
+            message: Some(Message::assistant("done".to_string(), None)),
+            agent_event: None,
+        });
+
+        assert!(result.success);
+        assert_eq!(result.thread_id.as_deref(), Some("thread-1"));
+        assert_eq!(result.run_id.as_deref(), Some("run-1"));
+        assert_eq!(result.text.as_deref(), Some("done"));
+    }
+
+    #[test]
+    fn run_error_is_a_failed_result() {
+        let mut result = RunResult::new("coder");
+        result.record_event(&event(
+            None,
+            AgentEventType::RunError {
+                message: "boom".to_string(),
+                code: None,
+                usage: None,
+            },
+        ));
+        assert!(!result.success);
+        assert_eq!(result.error.as_deref(), Some("boom"));
+    }
+
+    #[test]
+    fn run_lines_are_tagged() {
+        let result = RunResult::new("coder");
+        let line = serde_json::to_value(RunLine::Result { result: &result }).unwrap();
+        assert_eq!(line["type"], "result");
+        assert_eq!(line["result"]["agent"], "coder");
+        assert_eq!(line["result"]["success"], false);
+    }
+
+    #[test]
+    fn agents_group_by_package_with_root_agents_first() {
+        let agents = vec![
+            AgentRow::new("support/triage", "Triage tickets"),
+            AgentRow::new("planner", "Plans"),
+            AgentRow::new("billing/refunds", "Refunds"),
+            AgentRow::new("support/escalation", "Escalates"),
+        ];
+        assert_eq!(agents[1].package, None);
+        assert_eq!(agents[0].package.as_deref(), Some("support"));
+
+        let groups = group_agents_by_package(&agents);
+        let names: Vec<(Option<&str>, Vec<&str>)> = groups
+            .iter()
+            .map(|(package, rows)| (*package, rows.iter().map(|r| r.name.as_str()).collect()))
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                (None, vec!["planner"]),
+                (Some("billing"), vec!["billing/refunds"]),
+                (
+                    Some("support"),
+                    vec!["support/triage", "support/escalation"]
+                ),
+            ]
+        );
+    }
+}
@@ -0,0 +1,37 @@
+use anyhow::{anyhow, Result};
+use distri::Distri;
+use distri_types::user_profile::{describe_profile, UserProfile};
+
+use crate::output::OutputFormat;
+use crate::PreferencesCommands;
+
+pub async fn handle_preferences_command(
+    client: &Distri,
+    command: PreferencesCommands,
+    output: OutputFormat,
+) -> Result<()> {
+    let profile = match command {
+        PreferencesCommands::Show => client.get_user_profile().await?,
+        PreferencesCommands::Set { values } => {
+            let mut profile = client.get_user_profile().await?;
+            for pair in &values {
+                let (name, value) = pair
+                    .split_once('=')
+                    .ok_or_else(|| anyhow!("expected name=value, got '{}'", pair))?;
+                profile.set(name, value).map_err(|e| anyhow!(e))?;
+            }
+            client.set_user_profile(&profile).await?
+        }
+        PreferencesCommands::Clear => client.set_user_profile(&UserProfile::default()).await?,
+    };
+    output.print_value(&profile, print_profile)
+}
+
+fn print_profile(profile: &UserProfile) {
+    if profile.is_empty() {
+        println!("No preferences set.");
+        return;
+    }
+    let value = serde_json::to_value(profile).unwrap_or_default();
+    println!("{}", describe_profile(&value));
+}
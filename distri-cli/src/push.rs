@@ -15,7 +15,7 @@ use anyhow::{Context, Result};
 use distri::Distri;
 use tokio::fs;
 
-use crate::commands::parse_skill_file;
+use crate::commands::{parse_skill_file, register_agent_markdown_file};
 use crate::registries::{
     fetch_skill_markdown, DiscoveredSkill, RegistriesConfig, Registry, RegistryKind,
 };
@@ -111,7 +111,7 @@ async fn push_agent_file(client: &Distri, path: &Path, dry_run: bool) -> Result<
     let resp = if path.extension().and_then(|s| s.to_str()) == Some("json") {
         client.register_agent_json(&raw).await?
     } else {
-        client.register_agent_markdown(&raw).await?
+        register_agent_markdown_file(client, path, &raw).await?
     };
     println!(
         "{}  Pushed agent '{}'{}",
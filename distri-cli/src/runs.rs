@@ -0,0 +1,65 @@
+use anyhow::Result;
+use distri::Distri;
+use distri_types::configuration::DefinitionOverrides;
+use distri_types::task_retry::{RetryRequest, TaskRetry};
+
+use crate::output::OutputFormat;
+use crate::{RunsCommands, COLOR_BRIGHT_GREEN, COLOR_BRIGHT_YELLOW, COLOR_GRAY, COLOR_RESET};
+
+pub async fn handle_runs_command(
+    client: &Distri,
+    command: RunsCommands,
+    output: OutputFormat,
+) -> Result<()> {
+    match command {
+        RunsCommands::Retry { task_id, model } => retry_task(client, &task_id, model, output).await,
+    }
+}
+
+/// `distri runs retry <task_id>`: run a failed task again, told what went
+/// wrong, and print its answer.
+async fn retry_task(
+    client: &Distri,
+    task_id: &str,
+    model: Option<String>,
+    output: OutputFormat,
+) -> Result<()> {
+    if output.is_text() {
+        println!("{}Retrying {}…{}", COLOR_GRAY, task_id, COLOR_RESET);
+    }
+    let request = RetryRequest {
+        overrides: model.map(|model| DefinitionOverrides {
+            model: Some(model),
+            ..Default::default()
+        }),
+    };
+    let retry = client.retry_task(task_id, &request).await?;
+    output.print_value(&retry, print_retry)
+}
+
+fn print_retry(retry: &TaskRetry) {
+    let failure = &retry.failure;
+    if let Some(error) = &failure.error {
+        println!("{}  Previous error: {}{}", COLOR_GRAY, error, COLOR_RESET);
+    }
+    for call in &failure.failed_tool_calls {
+        println!(
+            "{}  Failed tool: {}{}{}",
+            COLOR_BRIGHT_YELLOW,
+            call.tool_name,
+            call.error
+                .as_deref()
+                .map(|e| format!(" — {}", e.lines().next().unwrap_or_default()))
+                .unwrap_or_default(),
+            COLOR_RESET
+        );
+    }
+    println!(
+        "{}✔ Retried {} as {} ({}){}",
+        COLOR_BRIGHT_GREEN, retry.retry_of, retry.task_id, retry.agent_id, COLOR_RESET
+    );
+    if let Some(content) = &retry.content {
+        println!();
+        println!("{}", content);
+    }
+}
@@ -0,0 +1,148 @@
+use anyhow::Result;
+use distri::Distri;
+use distri_types::task_diff::{
+    StepChange, StepField, TaskComparison, TaskTrace, TraceStep, UsageDelta,
+};
+
+use crate::output::OutputFormat;
+use crate::{COLOR_BRIGHT_GREEN, COLOR_BRIGHT_YELLOW, COLOR_GRAY, COLOR_RESET};
+
+const COLOR_RED: &str = "\x1b[31m";
+/// Widest step summary shown in the table.
+const MAX_STEP_WIDTH: usize = 32;
+
+pub async fn compare_tasks(
+    client: &Distri,
+    baseline: &str,
+    candidate: &str,
+    output: OutputFormat,
+) -> Result<()> {
+    let comparison = client.compare_tasks(baseline, candidate).await?;
+    output.print_value(&comparison, print_comparison)
+}
+
+fn print_comparison(comparison: &TaskComparison) {
+    let (baseline, candidate) = (&comparison.baseline, &comparison.candidate);
+    println!("Agent     {}", baseline.agent_id);
+    println!("Baseline  {}  {}", baseline.task_id, outcome(baseline));
+    println!("Candidate {}  {}", candidate.task_id, outcome(candidate));
+    println!();
+    println!(
+        "{:>3}  {:<9}  {:<w$}  {:<w$}  {:<24}  {:>8}  {:>9}",
+        "#",
+        "CHANGE",
+        "BASELINE",
+        "CANDIDATE",
+        "DIFFERS IN",
+        "TOKENS Δ",
+        "COST Δ",
+        w = MAX_STEP_WIDTH
+    );
+    for (i, row) in comparison.steps.iter().enumerate() {
+        let (label, color) = match row.change {
+            StepChange::Unchanged => ("same", COLOR_GRAY),
+            StepChange::Changed => ("changed", COLOR_BRIGHT_YELLOW),
+            StepChange::Removed => ("removed", COLOR_RED),
+            StepChange::Added => ("added", COLOR_BRIGHT_GREEN),
+        };
+        let fields = row
+            .fields
+            .iter()
+            .map(|f| match f {
+                StepField::Text => "text",
+                StepField::ToolCalls => "tool calls",
+                StepField::ToolResults => "results",
+                StepField::Success => "success",
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        println!(
+            "{:>3}  {}{:<9}{}  {:<w$}  {:<w$}  {:<24}  {:>8}  {:>9}",
+            i + 1,
+            color,
+            label,
+            COLOR_RESET,
+            row.baseline
+                .map(|i| summarize(&baseline.steps[i]))
+                .unwrap_or_default(),
+            row.candidate
+                .map(|j| summarize(&candidate.steps[j]))
+                .unwrap_or_default(),
+            fields,
+            signed(row.usage.total_tokens),
+            cost(&row.usage),
+            w = MAX_STEP_WIDTH
+        );
+    }
+    println!();
+    println!(
+        "Steps {} → {}   tokens {} → {} ({})   cost {}",
+        baseline.steps.len(),
+        candidate.steps.len(),
+        baseline.usage.total_tokens,
+        candidate.usage.total_tokens,
+        signed(comparison.usage.total_tokens),
+        cost(&comparison.usage)
+    );
+    if comparison.same_outcome {
+        println!("{}Same outcome{}", COLOR_GRAY, COLOR_RESET);
+    } else {
+        println!("{}Outcome differs{}", COLOR_BRIGHT_YELLOW, COLOR_RESET);
+    }
+}
+
+fn outcome(trace: &TaskTrace) -> String {
+    let status = match trace.outcome.success {
+        Some(true) => format!("{}succeeded{}", COLOR_BRIGHT_GREEN, COLOR_RESET),
+        Some(false) => format!("{}failed{}", COLOR_RED, COLOR_RESET),
+        None => "unfinished".to_string(),
+    };
+    match trace
+        .outcome
+        .error
+        .as_deref()
+        .or(trace.outcome.answer.as_deref())
+    {
+        Some(text) => format!(
+            "{}  {}{}{}",
+            status,
+            COLOR_GRAY,
+            truncate(text.lines().next().unwrap_or_default(), 60),
+            COLOR_RESET
+        ),
+        None => status,
+    }
+}
+
+/// The tools a step called, or its text.
+fn summarize(step: &TraceStep) -> String {
+    let text = if step.tool_calls.is_empty() {
+        step.text.as_deref().unwrap_or("(no output)").to_string()
+    } else {
+        step.tool_calls
+            .iter()
+            .map(|c| c.tool_name.as_str())
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    truncate(text.lines().next().unwrap_or_default(), MAX_STEP_WIDTH)
+}
+
+fn truncate(text: &str, max: usize) -> String {
+    if text.chars().count() <= max {
+        return text.to_string();
+    }
+    let short: String = text.chars().take(max - 1).collect();
+    format!("{}…", short)
+}
+
+fn signed(n: i64) -> String {
+    format!("{:+}", n)
+}
+
+fn cost(usage: &UsageDelta) -> String {
+    usage
+        .cost_usd
+        .map(|c| format!("{:+.4}", c))
+        .unwrap_or_else(|| "-".to_string())
+}
@@ -0,0 +1,512 @@
+//! Opt-in anonymous usage reporting.
+//!
+//! Off until `distri telemetry enable`. While enabled, the CLI counts which
+//! top-level commands run, how many `distri run` invocations succeed or fail,
+//! and records panics, all in a local buffer under `~/.distri/telemetry/`.
+//! About once a day the buffer is posted to the telemetry endpoint and
+//! cleared. Nothing identifying is kept: the install is a random id, command
+//! arguments are never recorded, and panic messages are redacted before they
+//! are written to disk. `DISTRI_TELEMETRY=off` or `DO_NOT_TRACK=1` turn it
+//! off regardless of the saved setting.
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::output::OutputFormat;
+use crate::{TelemetryCommands, COLOR_BRIGHT_GREEN, COLOR_GRAY, COLOR_RESET};
+
+pub const DEFAULT_ENDPOINT: &str = "https://api.distri.dev/v1/telemetry";
+const ENDPOINT_ENV: &str = "DISTRI_TELEMETRY_ENDPOINT";
+const SETTINGS_FILE: &str = "settings.json";
+const BUFFER_FILE: &str = "buffer.json";
+const REPORT_INTERVAL: chrono::Duration = chrono::Duration::hours(24);
+const SEND_TIMEOUT: Duration = Duration::from_secs(3);
+/// Crash reports kept per report; later ones only bump `crashes_dropped`.
+const MAX_CRASHES: usize = 20;
+const MAX_MESSAGE_CHARS: usize = 300;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct Settings {
+    enabled: bool,
+    /// Random id, so reports from one install can be grouped.
+    install_id: Option<String>,
+    endpoint: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RunCounts {
+    pub succeeded: u64,
+    pub failed: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CrashReport {
+    pub at: DateTime<Utc>,
+    /// Redacted panic message.
+    pub message: String,
+    /// `file:line` of the panic inside the CLI's sources.
+    pub location: Option<String>,
+}
+
+/// Everything recorded since the last report.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Buffer {
+    pub since: DateTime<Utc>,
+    pub commands: BTreeMap<String, u64>,
+    pub agent_runs: RunCounts,
+    pub crashes: Vec<CrashReport>,
+    #[serde(default)]
+    pub crashes_dropped: u64,
+}
+
+impl Default for Buffer {
+    fn default() -> Self {
+        Self {
+            since: Utc::now(),
+            commands: BTreeMap::new(),
+            agent_runs: RunCounts::default(),
+            crashes: Vec::new(),
+            crashes_dropped: 0,
+        }
+    }
+}
+
+impl Buffer {
+    fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+            && self.agent_runs == RunCounts::default()
+            && self.crashes.is_empty()
+            && self.crashes_dropped == 0
+    }
+}
+
+/// What is posted to the endpoint.
+#[derive(Debug, Serialize)]
+struct Report<'a> {
+    install_id: &'a str,
+    cli_version: &'static str,
+    os: &'static str,
+    arch: &'static str,
+    period_end: DateTime<Utc>,
+    #[serde(flatten)]
+    buffer: &'a Buffer,
+}
+
+/// Telemetry state stored in one directory.
+pub struct Telemetry {
+    dir: PathBuf,
+}
+
+impl Telemetry {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// `~/.distri/telemetry`.
+    fn home() -> Option<Self> {
+        crate::manifest::distri_home()
+            .ok()
+            .map(|home| Self::new(home.join("telemetry")))
+    }
+
+    /// The telemetry the CLI records to; `None` when the environment turns
+    /// telemetry off.
+    pub fn from_home() -> Option<Self> {
+        if disabled_by_env() {
+            return None;
+        }
+        Self::home()
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.settings().enabled
+    }
+
+    pub fn enable(&self, endpoint: Option<String>) -> Result<()> {
+        let mut settings = self.settings();
+        settings.enabled = true;
+        settings
+            .install_id
+            .get_or_insert_with(|| uuid::Uuid::new_v4().to_string());
+        if endpoint.is_some() {
+            settings.endpoint = endpoint;
+        }
+        self.write(SETTINGS_FILE, &settings)
+    }
+
+    /// Turn telemetry off and drop anything not yet reported.
+    pub fn disable(&self) -> Result<()> {
+        let mut settings = self.settings();
+        settings.enabled = false;
+        self.write(SETTINGS_FILE, &settings)?;
+        match std::fs::remove_file(self.dir.join(BUFFER_FILE)) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn endpoint(&self) -> String {
+        std::env::var(ENDPOINT_ENV)
+            .ok()
+            .filter(|v| !v.trim().is_empty())
+            .or(self.settings().endpoint)
+            .unwrap_or_else(|| DEFAULT_ENDPOINT.to_string())
+    }
+
+    pub fn buffer(&self) -> Buffer {
+        self.read(BUFFER_FILE).unwrap_or_default()
+    }
+
+    /// Apply `change` to the buffer when telemetry is enabled.
+    fn record(&self, change: impl FnOnce(&mut Buffer)) {
+        if !self.is_enabled() {
+            return;
+        }
+        let mut buffer = self.buffer();
+        change(&mut buffer);
+        if let Err(e) = self.write(BUFFER_FILE, &buffer) {
+            tracing::debug!("failed to write telemetry buffer: {}", e);
+        }
+    }
+
+    pub fn record_command(&self, name: &str) {
+        self.record(|b| *b.commands.entry(name.to_string()).or_default() += 1);
+    }
+
+    pub fn record_agent_run(&self, succeeded: bool) {
+        self.record(|b| {
+            if succeeded {
+                b.agent_runs.succeeded += 1;
+            } else {
+                b.agent_runs.failed += 1;
+            }
+        });
+    }
+
+    pub fn record_crash(&self, message: &str, location: Option<String>) {
+        self.record(|b| {
+            if b.crashes.len() >= MAX_CRASHES {
+                b.crashes_dropped += 1;
+                return;
+            }
+            b.crashes.push(CrashReport {
+                at: Utc::now(),
+                message: redact(message),
+                location,
+            });
+        });
+    }
+
+    /// Post the buffer if it is older than a day, clearing it on success.
+    /// Failures keep the buffer for the next attempt.
+    pub async fn report_if_due(&self) {
+        if !self.is_enabled() {
+            return;
+        }
+        let buffer = self.buffer();
+        if buffer.is_empty() || Utc::now() - buffer.since < REPORT_INTERVAL {
+            return;
+        }
+        if let Err(e) = self.send(&buffer).await {
+            tracing::debug!("telemetry report failed: {}", e);
+            return;
+        }
+        if let Err(e) = self.write(BUFFER_FILE, &Buffer::default()) {
+            tracing::debug!("failed to reset telemetry buffer: {}", e);
+        }
+    }
+
+    async fn send(&self, buffer: &Buffer) -> Result<()> {
+        let settings = self.settings();
+        let report = Report {
+            install_id: settings.install_id.as_deref().unwrap_or_default(),
+            cli_version: env!("CARGO_PKG_VERSION"),
+            os: std::env::consts::OS,
+            arch: std::env::consts::ARCH,
+            period_end: Utc::now(),
+            buffer,
+        };
+        reqwest::Client::builder()
+            .timeout(SEND_TIMEOUT)
+            .build()?
+            .post(self.endpoint())
+            .json(&report)
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+
+    fn settings(&self) -> Settings {
+        self.read(SETTINGS_FILE).unwrap_or_default()
+    }
+
+    fn read<T: serde::de::DeserializeOwned>(&self, file: &str) -> Option<T> {
+        let raw = std::fs::read_to_string(self.dir.join(file)).ok()?;
+        serde_json::from_str(&raw).ok()
+    }
+
+    fn write<T: Serialize>(&self, file: &str, value: &T) -> Result<()> {
+        std::fs::create_dir_all(&self.dir)
+            .with_context(|| format!("creating {}", self.dir.display()))?;
+        let path = self.dir.join(file);
+        std::fs::write(&path, serde_json::to_vec_pretty(value)?)
+            .with_context(|| format!("writing {}", path.display()))
+    }
+}
+
+fn disabled_by_env() -> bool {
+    let off = |name: &str| {
+        std::env::var(name)
+            .map(|v| {
+                matches!(
+                    v.trim().to_ascii_lowercase().as_str(),
+                    "0" | "off" | "false"
+                )
+            })
+            .unwrap_or(false)
+    };
+    let do_not_track = std::env::var("DO_NOT_TRACK")
+        .map(|v| matches!(v.trim(), "1" | "true"))
+        .unwrap_or(false);
+    off("DISTRI_TELEMETRY") || do_not_track
+}
+
+/// Strip anything that could identify a user or their data from `text`:
+/// quoted strings, URLs, email addresses, file paths, UUIDs and long
+/// token-like words. The result is cut to a few hundred characters.
+pub fn redact(text: &str) -> String {
+    static PATTERNS: OnceLock<Vec<(Regex, &'static str)>> = OnceLock::new();
+    let patterns =
+        PATTERNS.get_or_init(|| {
+            [
+            (r#""[^"]*"|'[^']*'|`[^`]*`"#, "<str>"),
+            (r"[A-Za-z][A-Za-z0-9+.-]*://\S+", "<url>"),
+            (r"[\w.+-]+@[\w-]+(\.[\w-]+)+", "<email>"),
+            (r"(?:[A-Za-z]:)?(?:~|\.{1,2})?(?:[\\/][^\s\\/:]+){2,}[\\/]?", "<path>"),
+            (
+                r"\b[0-9a-fA-F]{8}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{12}\b",
+                "<id>",
+            ),
+            (r"\b[A-Za-z0-9_\-]{24,}\b", "<redacted>"),
+        ]
+        .into_iter()
+        .map(|(pattern, replacement)| (Regex::new(pattern).expect("valid regex"), replacement))
+        .collect()
+        });
+    let mut text = text.to_string();
+    for (pattern, replacement) in patterns {
+        text = pattern.replace_all(&text, *replacement).into_owned();
+    }
+    match text.char_indices().nth(MAX_MESSAGE_CHARS) {
+        Some((end, _)) => format!("{}…", &text[..end]),
+        None => text,
+    }
+}
+
+/// Record panics as crash reports, then run the default panic output.
+pub fn install_panic_hook() {
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        if let Some(telemetry) = Telemetry::from_home() {
+            let payload = info.payload();
+            let message = payload
+                .downcast_ref::<&str>()
+                .map(|s| s.to_string())
+                .or_else(|| payload.downcast_ref::<String>().cloned())
+                .unwrap_or_default();
+            let location = info
+                .location()
+                .map(|l| format!("{}:{}", source_file(l.file()), l.line()));
+            telemetry.record_crash(&message, location);
+        }
+        previous(info);
+    }));
+}
+
+/// The last two components of a source path, e.g. `src/chat.rs`.
+fn source_file(path: &str) -> String {
+    let parts: Vec<&str> = path.split(['/', '\\']).collect();
+    parts[parts.len().saturating_sub(2)..].join("/")
+}
+
+pub fn record_command(name: &str) {
+    if let Some(telemetry) = Telemetry::from_home() {
+        telemetry.record_command(name);
+    }
+}
+
+pub fn record_agent_run(succeeded: bool) {
+    if let Some(telemetry) = Telemetry::from_home() {
+        telemetry.record_agent_run(succeeded);
+    }
+}
+
+pub async fn report_if_due() {
+    if let Some(telemetry) = Telemetry::from_home() {
+        telemetry.report_if_due().await;
+    }
+}
+
+pub fn handle_telemetry_command(command: TelemetryCommands, output: OutputFormat) -> Result<()> {
+    let telemetry = Telemetry::home().context("Unable to resolve home directory")?;
+    match command {
+        TelemetryCommands::Status => {
+            let enabled = telemetry.is_enabled() && !disabled_by_env();
+            if !output.is_text() {
+                let status = serde_json::json!({
+                    "enabled": enabled,
+                    "disabled_by_env": telemetry.is_enabled() && !enabled,
+                    "endpoint": telemetry.endpoint(),
+                    "pending": enabled.then(|| telemetry.buffer()),
+                });
+                return output.print_value(&status, |_| {});
+            }
+            let state = if enabled {
+                format!("{}enabled{}", COLOR_BRIGHT_GREEN, COLOR_RESET)
+            } else if telemetry.is_enabled() {
+                "disabled (by DISTRI_TELEMETRY / DO_NOT_TRACK)".to_string()
+            } else {
+                "disabled".to_string()
+            };
+            println!("Telemetry: {}", state);
+            println!("Endpoint:  {}", telemetry.endpoint());
+            if enabled {
+                let buffer = telemetry.buffer();
+                println!(
+                    "{}Not yet reported (since {}):{}",
+                    COLOR_GRAY,
+                    buffer.since.format("%Y-%m-%d %H:%M"),
+                    COLOR_RESET
+                );
+                println!("{}", serde_json::to_string_pretty(&buffer)?);
+            }
+        }
+        TelemetryCommands::Enable { endpoint } => {
+            telemetry.enable(endpoint)?;
+            println!(
+                "Telemetry enabled. Anonymous usage counts and crash reports will be sent to {}.",
+                telemetry.endpoint()
+            );
+            println!("Run `distri telemetry status` to see what is collected.");
+        }
+        TelemetryCommands::Disable => {
+            telemetry.disable()?;
+            println!("Telemetry disabled. Unsent data was deleted.");
+        }
+    }
+    Ok(())
+}
+
+/// Lowercased top-level command name, e.g. `run` for `Commands::Run { .. }`.
+/// Taken from the variant name so no argument value can leak into it.
+pub fn command_name(command: &impl std::fmt::Debug) -> String {
+    format!("{:?}", command)
+        .chars()
+        .take_while(|c| c.is_ascii_alphanumeric())
+        .collect::<String>()
+        .to_ascii_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug)]
+    #[allow(dead_code)]
+    enum Sample {
+        Run { task: String },
+        Version,
+    }
+
+    #[test]
+    fn nothing_is_recorded_until_enabled() {
+        let dir = tempfile::tempdir().unwrap();
+        let telemetry = Telemetry::new(dir.path());
+
+        telemetry.record_command("run");
+        assert!(telemetry.buffer().is_empty());
+        assert!(!dir.path().join(BUFFER_FILE).exists());
+
+        telemetry.enable(None).unwrap();
+        telemetry.record_command("run");
+        telemetry.record_command("run");
+        telemetry.record_agent_run(false);
+
+        let buffer = telemetry.buffer();
+        assert_eq!(buffer.commands["run"], 2);
+        assert_eq!(buffer.agent_runs.failed, 1);
+    }
+
+    #[test]
+    fn disabling_drops_the_buffer_but_keeps_the_install_id() {
+        let dir = tempfile::tempdir().unwrap();
+        let telemetry = Telemetry::new(dir.path());
+        telemetry
+            .enable(Some("http://localhost:9/t".to_string()))
+            .unwrap();
+        let install_id = telemetry.settings().install_id;
+        telemetry.record_command("push");
+
+        telemetry.disable().unwrap();
+        telemetry.enable(None).unwrap();
+
+        assert!(telemetry.buffer().is_empty());
+        assert_eq!(telemetry.settings().install_id, install_id);
+        assert_eq!(
+            telemetry.settings().endpoint.as_deref(),
+            Some("http://localhost:9/t")
+        );
+    }
+
+    #[test]
+    fn crash_messages_are_redacted() {
+        let message = "failed to open '/home/alice/notes.md' for alice@example.com \
+             via https://api.example.com/v1?key=abc (token sk_live_0123456789abcdefghijkl, \
+             thread 0b5c6e1a-3f0e-4d8e-9a57-2f7c1d4e8b90)";
+
+        let redacted = redact(message);
+
+        for secret in ["alice", "notes", "example.com", "sk_live", "0b5c6e1a"] {
+            assert!(
+                !redacted.contains(secret),
+                "{} leaked: {}",
+                secret,
+                redacted
+            );
+        }
+        assert!(redacted.starts_with("failed to open <str> for <email> via <url>"));
+    }
+
+    #[test]
+    fn crash_reports_are_capped() {
+        let dir = tempfile::tempdir().unwrap();
+        let telemetry = Telemetry::new(dir.path());
+        telemetry.enable(None).unwrap();
+
+        for _ in 0..MAX_CRASHES + 3 {
+            telemetry.record_crash("index out of bounds", Some("src/chat.rs:10".into()));
+        }
+
+        let buffer = telemetry.buffer();
+        assert_eq!(buffer.crashes.len(), MAX_CRASHES);
+        assert_eq!(buffer.crashes_dropped, 3);
+    }
+
+    #[test]
+    fn command_names_carry_no_arguments() {
+        let run = Sample::Run {
+            task: "summarize my secret plan".to_string(),
+        };
+        assert_eq!(command_name(&run), "run");
+        assert_eq!(command_name(&Sample::Version), "version");
+        assert_eq!(source_file("/build/distri-cli/src/chat.rs"), "src/chat.rs");
+    }
+}
@@ -1,8 +1,11 @@
 use std::path::PathBuf;
+use std::sync::Arc;
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use distri::Distri;
+use tokio::sync::Mutex;
 
+use crate::output::OutputFormat;
 use crate::{ThreadsCommands, COLOR_GRAY, COLOR_RESET};
 
 pub fn get_last_thread_file() -> PathBuf {
@@ -109,20 +112,141 @@ pub async fn print_thread_history(client: &Distri, thread_id: &str) {
     }
 }
 
-pub async fn handle_threads_command(client: &Distri, command: ThreadsCommands) -> Result<()> {
+/// `distri watch`: print a shared thread's live runs until the share ends
+/// or Ctrl+C. Observers can't send input; the token only opens the stream.
+pub async fn watch_thread(
+    client: &Distri,
+    thread_id: &str,
+    token: Option<String>,
+    ttl_secs: Option<u64>,
+) -> Result<()> {
+    let token = match token {
+        Some(token) => token,
+        None => {
+            let share = client.share_thread(thread_id, ttl_secs).await?;
+            println!(
+                "{}Read-only share (expires {}):{}",
+                COLOR_GRAY,
+                share
+                    .expires_at
+                    .with_timezone(&chrono::Local)
+                    .format("%Y-%m-%d %H:%M"),
+                COLOR_RESET
+            );
+            println!("  distri watch {} --token {}", thread_id, share.token);
+            println!("  {}{}", client.base_url(), share.watch_path);
+            share.token
+        }
+    };
+    println!(
+        "{}── Watching {} (Ctrl+C to stop) ──{}",
+        COLOR_GRAY, thread_id, COLOR_RESET
+    );
+
+    let printer = Arc::new(Mutex::new(distri::EventPrinter::new()));
+    let watch = client.watch_thread(thread_id, &token, |event| {
+        let printer = printer.clone();
+        async move { printer.lock().await.handle_event(&event).await }
+    });
+    tokio::select! {
+        result = watch => {
+            result?;
+            println!("{}── Share ended ──{}", COLOR_GRAY, COLOR_RESET);
+        }
+        _ = tokio::signal::ctrl_c() => {}
+    }
+    Ok(())
+}
+
+pub async fn handle_threads_command(
+    client: &Distri,
+    command: ThreadsCommands,
+    output: OutputFormat,
+) -> Result<()> {
     match command {
         ThreadsCommands::List => {
             let threads = client.list_threads().await?;
-            if threads.is_empty() {
-                println!("No threads found.");
-            } else {
+            output.print_list(&threads, |threads| {
+                if threads.is_empty() {
+                    println!("No threads found.");
+                }
                 for thread in threads {
                     let agent = thread.agent_name.as_deref().unwrap_or("unknown");
                     let title = thread.title.as_deref().unwrap_or("(no title)");
-                    println!("{} - {} [{}]", thread.id, title, agent);
+                    let archived = if thread.archived_at.is_some() {
+                        " (archived)"
+                    } else {
+                        ""
+                    };
+                    println!("{} - {} [{}]{}", thread.id, title, agent, archived);
                 }
+            })?;
+        }
+        ThreadsCommands::Import {
+            file,
+            format,
+            agent,
+        } => {
+            let export = std::fs::read_to_string(&file)
+                .with_context(|| format!("failed to read {}", file.display()))?;
+            let summary = client.import_threads(format, &agent, export).await?;
+            for thread in &summary.imported {
+                println!(
+                    "{} - {} ({} messages)",
+                    thread.thread_id, thread.title, thread.message_count
+                );
+            }
+            println!(
+                "Imported {} conversation(s) into '{}', skipped {}.",
+                summary.imported.len(),
+                agent,
+                summary.skipped.len()
+            );
+        }
+        ThreadsCommands::Vars {
+            thread_id,
+            set,
+            unset,
+            clear,
+        } => {
+            let mut thread = client.get_thread(&thread_id).await?;
+            if clear || !set.is_empty() || !unset.is_empty() {
+                let mut variables = if clear {
+                    Default::default()
+                } else {
+                    thread.variables
+                };
+                for name in &unset {
+                    variables.remove(name);
+                }
+                variables.extend(set);
+                thread = client.set_thread_variables(&thread_id, variables).await?;
+            }
+            if thread.variables.is_empty() {
+                println!("No variables set on thread {}.", thread_id);
+            }
+            for (name, value) in &thread.variables {
+                println!("{}={}", name, value);
             }
         }
+        ThreadsCommands::Archive { thread_id } => {
+            let summary = client.archive_thread(&thread_id).await?;
+            output.print_value(&summary, |summary| {
+                println!(
+                    "Archived thread {}: {} task(s), {} message(s), {} artifact(s).",
+                    summary.thread_id, summary.tasks, summary.messages, summary.artifacts
+                );
+            })?;
+        }
+        ThreadsCommands::Restore { thread_id } => {
+            let summary = client.restore_thread(&thread_id).await?;
+            output.print_value(&summary, |summary| {
+                println!(
+                    "Restored thread {}: {} task(s), {} message(s).",
+                    summary.thread_id, summary.tasks, summary.messages
+                );
+            })?;
+        }
     }
     Ok(())
 }
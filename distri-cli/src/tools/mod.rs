@@ -16,6 +16,7 @@ use distri_types::{AgentEvent, Part, ToolCall, ToolDefinition, ToolResponse};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 
+use crate::notify::Notifier;
 use crate::{COLOR_BRIGHT_GREEN, COLOR_BRIGHT_MAGENTA, COLOR_BRIGHT_YELLOW, COLOR_RESET};
 
 /// Register all local CLI tools and return their definitions (with prompts).
@@ -146,49 +147,74 @@ fn tool_definitions() -> Vec<ToolDefinition> {
     ]
 }
 
-pub fn register_approval_handler(registry: &ExternalToolRegistry) {
-    registry.register("*", "approval_request", |call, _event| async move {
-        println!(
-            "{}Calling tool:{} {}",
-            COLOR_BRIGHT_MAGENTA, COLOR_RESET, call.tool_name
-        );
-        println!("{}Approval required{}", COLOR_BRIGHT_YELLOW, COLOR_RESET);
-        print!(
-            "{}Do you approve this operation? (y/n): {}",
-            COLOR_BRIGHT_YELLOW, COLOR_RESET
-        );
-        io::stdout().flush().ok();
-
-        let mut input = String::new();
-        if io::stdin().read_line(&mut input).is_err() {
-            return Err(anyhow::anyhow!("Failed to read approval input"));
-        }
-
-        let approved = input.trim().eq_ignore_ascii_case("y");
-        if approved {
+/// Ask for approvals on the terminal. With a `notifier`, a desktop
+/// notification says the run is waiting.
+pub fn register_approval_handler(registry: &ExternalToolRegistry, notifier: Option<Notifier>) {
+    registry.register("*", "approval_request", move |call, _event| {
+        let notifier = notifier.clone();
+        async move {
+            if let Some(notifier) = &notifier {
+                notifier.approval_needed(&approval_tool_names(&call.input));
+            }
             println!(
-                "{}Operation approved by user.{}",
-                COLOR_BRIGHT_GREEN, COLOR_RESET
+                "{}Calling tool:{} {}",
+                COLOR_BRIGHT_MAGENTA, COLOR_RESET, call.tool_name
             );
-        } else {
-            println!("Operation rejected by user.");
-        }
+            println!("{}Approval required{}", COLOR_BRIGHT_YELLOW, COLOR_RESET);
+            print!(
+                "{}Do you approve this operation? (y/n): {}",
+                COLOR_BRIGHT_YELLOW, COLOR_RESET
+            );
+            io::stdout().flush().ok();
 
-        let tool_calls = call.input.clone();
-        let approval_result = json!({
-            "approved": approved,
-            "reason": if approved { "Approved by user" } else { "Rejected by user" },
-            "tool_calls": tool_calls,
-        });
+            let mut input = String::new();
+            if io::stdin().read_line(&mut input).is_err() {
+                return Err(anyhow::anyhow!("Failed to read approval input"));
+            }
 
-        Ok(ToolResponse::direct(
-            call.tool_call_id.clone(),
-            call.tool_name.clone(),
-            approval_result,
-        ))
+            let approved = input.trim().eq_ignore_ascii_case("y");
+            if approved {
+                println!(
+                    "{}Operation approved by user.{}",
+                    COLOR_BRIGHT_GREEN, COLOR_RESET
+                );
+            } else {
+                println!("Operation rejected by user.");
+            }
+
+            let tool_calls = call.input.clone();
+            let approval_result = json!({
+                "approved": approved,
+                "reason": if approved { "Approved by user" } else { "Rejected by user" },
+                "tool_calls": tool_calls,
+            });
+
+            Ok(ToolResponse::direct(
+                call.tool_call_id.clone(),
+                call.tool_name.clone(),
+                approval_result,
+            ))
+        }
     });
 }
 
+/// Names of the tool calls an `approval_request` asks about; its input is
+/// the calls, or an object holding them under `tool_calls`.
+fn approval_tool_names(input: &serde_json::Value) -> Vec<String> {
+    input
+        .get("tool_calls")
+        .unwrap_or(input)
+        .as_array()
+        .map(|calls| {
+            calls
+                .iter()
+                .filter_map(|c| c.get("tool_name").and_then(|n| n.as_str()))
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
 // ---------------------------------------------------------------------------
 // ExecuteCommandTool — local shell execution (legacy name for backward compat)
 // ---------------------------------------------------------------------------
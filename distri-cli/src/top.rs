@@ -0,0 +1,604 @@
+//! `distri top` — live dashboard of the runs on a server.
+//!
+//! Polls `GET /v1/tasks?status=running` for the active set, follows each
+//! active task's `GET /v1/tasks/{id}/events` stream for step / tool / usage
+//! updates, and shows `GET /v1/home/stats` counters in the header. Useful
+//! when several scheduled or background agents share one server.
+
+use std::collections::{BTreeMap, HashSet, VecDeque};
+use std::io::Stdout;
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use crossterm::execute;
+use crossterm::terminal::{
+    disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen,
+};
+use distri::Distri;
+use distri_types::events::{AgentEvent, AgentEventType, RunUsage};
+use distri_types::stores::{HomeStats, TaskWithActivity};
+use distri_types::TaskStatus;
+use ratatui::backend::CrosstermBackend;
+use ratatui::layout::{Constraint, Direction, Layout};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Cell, List, ListItem, Paragraph, Row, Table};
+use ratatui::{Frame, Terminal};
+use tokio::sync::mpsc;
+
+/// How many recent errors the errors pane keeps.
+const MAX_ERRORS: usize = 50;
+/// Finished tasks stay on screen this long so short runs are still visible.
+const LINGER: chrono::Duration = chrono::Duration::seconds(30);
+/// Upper bound on tasks fetched per refresh.
+const TASK_LIMIT: u32 = 200;
+
+#[derive(Debug, Clone, Default)]
+struct TaskRow {
+    thread_id: String,
+    parent_task_id: Option<String>,
+    agent_id: Option<String>,
+    status: TaskStatus,
+    /// 1-based index of the step in progress.
+    step: Option<usize>,
+    /// `tool_call_id` → tool name for calls that have started but not ended.
+    tools_in_flight: BTreeMap<String, String>,
+    usage: Option<RunUsage>,
+    intent: Option<String>,
+    last_event_at: Option<DateTime<Utc>>,
+    finished_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone)]
+struct ErrorEntry {
+    at: DateTime<Utc>,
+    task_id: String,
+    agent_id: String,
+    message: String,
+}
+
+/// Everything the dashboard renders. Kept free of I/O so the event folding
+/// can be unit-tested.
+#[derive(Debug, Default)]
+struct Dashboard {
+    tasks: BTreeMap<String, TaskRow>,
+    errors: VecDeque<ErrorEntry>,
+    stats: Option<HomeStats>,
+    /// Last failure talking to the server; cleared on the next good refresh.
+    refresh_error: Option<String>,
+}
+
+impl Dashboard {
+    /// Merge the server's current active set. Rows for tasks that are no
+    /// longer active are dropped once they have lingered long enough.
+    fn sync_tasks(&mut self, active: Vec<TaskWithActivity>, now: DateTime<Utc>) {
+        let active_ids: HashSet<String> = active.iter().map(|t| t.task.id.clone()).collect();
+        for entry in active {
+            let row = self.tasks.entry(entry.task.id.clone()).or_default();
+            row.thread_id = entry.task.thread_id;
+            row.parent_task_id = entry.task.parent_task_id;
+            row.status = entry.task.status;
+            if row.intent.is_none() {
+                row.intent = entry.activity.intent;
+            }
+            if row.last_event_at.is_none() {
+                row.last_event_at = entry
+                    .activity
+                    .last_event_at
+                    .and_then(DateTime::from_timestamp_millis);
+            }
+        }
+        self.tasks.retain(|id, row| {
+            active_ids.contains(id) || row.finished_at.is_some_and(|at| now - at < LINGER)
+        });
+    }
+
+    fn apply_event(&mut self, event: &AgentEvent) {
+        let row = self.tasks.entry(event.task_id.clone()).or_default();
+        if row.thread_id.is_empty() {
+            row.thread_id = event.thread_id.clone();
+        }
+        if row.parent_task_id.is_none() {
+            row.parent_task_id = event.parent_task_id.clone();
+        }
+        if !event.agent_id.is_empty() {
+            row.agent_id = Some(event.agent_id.clone());
+        }
+        row.last_event_at = Some(event.timestamp);
+
+        match &event.event {
+            AgentEventType::RunStarted {} => {
+                row.status = TaskStatus::Running;
+                row.finished_at = None;
+            }
+            AgentEventType::StepStarted { step_index, .. } => {
+                row.step = Some(step_index + 1);
+            }
+            AgentEventType::StepCompleted { usage, .. } if usage.is_some() => {
+                row.usage = usage.clone();
+            }
+            AgentEventType::ToolExecutionStart {
+                tool_call_id,
+                tool_call_name,
+                ..
+            } => {
+                row.tools_in_flight
+                    .insert(tool_call_id.clone(), tool_call_name.clone());
+            }
+            AgentEventType::ToolExecutionEnd { tool_call_id, .. } => {
+                row.tools_in_flight.remove(tool_call_id);
+            }
+            AgentEventType::RunFinished { success, usage, .. } => {
+                row.status = if *success {
+                    TaskStatus::Completed
+                } else {
+                    TaskStatus::Failed
+                };
+                if usage.is_some() {
+                    row.usage = usage.clone();
+                }
+                row.tools_in_flight.clear();
+                row.finished_at = Some(event.timestamp);
+            }
+            AgentEventType::RunError { message, usage, .. } => {
+                row.status = TaskStatus::Failed;
+                if usage.is_some() {
+                    row.usage = usage.clone();
+                }
+                row.tools_in_flight.clear();
+                row.finished_at = Some(event.timestamp);
+                self.errors.push_front(ErrorEntry {
+                    at: event.timestamp,
+                    task_id: event.task_id.clone(),
+                    agent_id: event.agent_id.clone(),
+                    message: message.clone(),
+                });
+                self.errors.truncate(MAX_ERRORS);
+            }
+            _ => {}
+        }
+    }
+
+    fn active_count(&self) -> usize {
+        self.tasks
+            .values()
+            .filter(|t| matches!(t.status, TaskStatus::Running | TaskStatus::Pending))
+            .count()
+    }
+
+    /// Tokens and cost summed across the rows on screen.
+    fn totals(&self) -> (u64, f64) {
+        self.tasks
+            .values()
+            .filter_map(|t| t.usage.as_ref())
+            .fold((0, 0.0), |(tokens, cost), u| {
+                (
+                    tokens + u.total_tokens as u64,
+                    cost + u.cost_usd.unwrap_or(0.0),
+                )
+            })
+    }
+}
+
+enum Update {
+    Event(Box<AgentEvent>),
+    /// The task's event stream ended; follow it again if it is still active.
+    Closed(String),
+}
+
+/// Restores the terminal even when the dashboard exits with an error.
+struct TerminalGuard;
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        let _ = disable_raw_mode();
+        let _ = execute!(std::io::stdout(), LeaveAlternateScreen);
+    }
+}
+
+pub async fn run_top(client: &Distri, interval_secs: u64) -> Result<()> {
+    let interval = Duration::from_secs(interval_secs.max(1));
+
+    enable_raw_mode()?;
+    let _guard = TerminalGuard;
+    execute!(std::io::stdout(), EnterAlternateScreen)?;
+    let mut terminal = Terminal::new(CrosstermBackend::new(std::io::stdout()))?;
+
+    let (tx, mut rx) = mpsc::unbounded_channel::<Update>();
+    let mut dashboard = Dashboard::default();
+    let mut following: HashSet<String> = HashSet::new();
+    let mut last_refresh: Option<Instant> = None;
+
+    loop {
+        while let Ok(update) = rx.try_recv() {
+            match update {
+                Update::Event(event) => dashboard.apply_event(&event),
+                Update::Closed(task_id) => {
+                    following.remove(&task_id);
+                }
+            }
+        }
+
+        let refresh_due = match last_refresh {
+            Some(at) => at.elapsed() >= interval,
+            None => true,
+        };
+        if refresh_due {
+            refresh(client, &mut dashboard, &mut following, &tx).await;
+            last_refresh = Some(Instant::now());
+        }
+
+        draw(&mut terminal, &dashboard, client.base_url())?;
+
+        if event::poll(Duration::from_millis(200))? {
+            if let Event::Key(key) = event::read()? {
+                let quit = key.kind == KeyEventKind::Press
+                    && (matches!(key.code, KeyCode::Char('q') | KeyCode::Esc)
+                        || (key.code == KeyCode::Char('c')
+                            && key.modifiers.contains(KeyModifiers::CONTROL)));
+                if quit {
+                    break;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+async fn refresh(
+    client: &Distri,
+    dashboard: &mut Dashboard,
+    following: &mut HashSet<String>,
+    tx: &mpsc::UnboundedSender<Update>,
+) {
+    match client
+        .list_task_activity(Some("running"), Some(TASK_LIMIT))
+        .await
+    {
+        Ok(active) => {
+            for entry in &active {
+                if following.insert(entry.task.id.clone()) {
+                    spawn_follower(client.clone(), entry.task.id.clone(), tx.clone());
+                }
+            }
+            dashboard.sync_tasks(active, Utc::now());
+            dashboard.refresh_error = None;
+        }
+        Err(err) => dashboard.refresh_error = Some(err.to_string()),
+    }
+    // Stats are decoration — a server without them still gets a dashboard.
+    if let Ok(stats) = client.get_home_stats().await {
+        dashboard.stats = Some(stats);
+    }
+}
+
+fn spawn_follower(client: Distri, task_id: String, tx: mpsc::UnboundedSender<Update>) {
+    tokio::spawn(async move {
+        let events_tx = tx.clone();
+        let _ = client
+            .follow_task_events(&task_id, |event| {
+                let _ = events_tx.send(Update::Event(Box::new(event)));
+                async {}
+            })
+            .await;
+        let _ = tx.send(Update::Closed(task_id));
+    });
+}
+
+fn draw(
+    terminal: &mut Terminal<CrosstermBackend<Stdout>>,
+    dashboard: &Dashboard,
+    base_url: &str,
+) -> Result<()> {
+    terminal.draw(|frame| render(frame, dashboard, base_url))?;
+    Ok(())
+}
+
+fn render(frame: &mut Frame, dashboard: &Dashboard, base_url: &str) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(4),
+            Constraint::Min(6),
+            Constraint::Length(8),
+            Constraint::Length(1),
+        ])
+        .split(frame.size());
+
+    frame.render_widget(header(dashboard, base_url), chunks[0]);
+    frame.render_widget(task_table(dashboard), chunks[1]);
+    frame.render_widget(error_list(dashboard), chunks[2]);
+    frame.render_widget(
+        Paragraph::new("q / Esc to quit").style(Style::default().fg(Color::DarkGray)),
+        chunks[3],
+    );
+}
+
+fn header<'a>(dashboard: &Dashboard, base_url: &str) -> Paragraph<'a> {
+    let (tokens, cost) = dashboard.totals();
+    let mut lines = vec![Line::from(vec![
+        Span::styled(
+            format!("{} active", dashboard.active_count()),
+            Style::default().add_modifier(Modifier::BOLD),
+        ),
+        Span::raw(format!("   {} tokens   ${:.4}", tokens, cost)),
+    ])];
+    lines.push(match (&dashboard.refresh_error, &dashboard.stats) {
+        (Some(err), _) => Line::from(Span::styled(
+            format!("refresh failed: {}", err),
+            Style::default().fg(Color::Red),
+        )),
+        (None, Some(stats)) => Line::from(format!(
+            "{} agents   {} threads   {} messages   avg run {}",
+            stats.total_agents,
+            stats.total_threads,
+            stats.total_messages,
+            stats
+                .avg_run_time_ms
+                .map(|ms| format!("{:.1}s", ms / 1000.0))
+                .unwrap_or_else(|| "-".to_string()),
+        )),
+        (None, None) => Line::from(""),
+    });
+    Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(format!(" distri top — {} ", base_url)),
+    )
+}
+
+fn task_table<'a>(dashboard: &Dashboard) -> Table<'a> {
+    let now = Utc::now();
+    let rows = dashboard.tasks.iter().map(|(id, task)| {
+        let (status, color) = status_label(&task.status);
+        let tool = task
+            .tools_in_flight
+            .values()
+            .cloned()
+            .collect::<Vec<_>>()
+            .join(", ");
+        let (tokens, cost) = task
+            .usage
+            .as_ref()
+            .map(|u| {
+                (
+                    u.total_tokens.to_string(),
+                    u.cost_usd
+                        .map(|c| format!("${:.4}", c))
+                        .unwrap_or_else(|| "-".to_string()),
+                )
+            })
+            .unwrap_or_else(|| ("-".to_string(), "-".to_string()));
+        let agent = match &task.parent_task_id {
+            Some(_) => format!("└ {}", task.agent_id.as_deref().unwrap_or("?")),
+            None => task.agent_id.clone().unwrap_or_else(|| "?".to_string()),
+        };
+        Row::new(vec![
+            Cell::from(short_id(id)),
+            Cell::from(agent),
+            Cell::from(status).style(Style::default().fg(color)),
+            Cell::from(task.step.map(|s| s.to_string()).unwrap_or_default()),
+            Cell::from(tool),
+            Cell::from(tokens),
+            Cell::from(cost),
+            Cell::from(
+                task.last_event_at
+                    .map(|at| age(now, at))
+                    .unwrap_or_default(),
+            ),
+            Cell::from(task.intent.clone().unwrap_or_default()),
+        ])
+    });
+    Table::new(
+        rows,
+        [
+            Constraint::Length(10),
+            Constraint::Length(20),
+            Constraint::Length(10),
+            Constraint::Length(5),
+            Constraint::Length(20),
+            Constraint::Length(9),
+            Constraint::Length(10),
+            Constraint::Length(6),
+            Constraint::Min(10),
+        ],
+    )
+    .header(
+        Row::new(vec![
+            "TASK", "AGENT", "STATUS", "STEP", "TOOL", "TOKENS", "COST", "LAST", "INTENT",
+        ])
+        .style(Style::default().add_modifier(Modifier::BOLD)),
+    )
+    .block(Block::default().borders(Borders::ALL).title(" Tasks "))
+}
+
+fn error_list<'a>(dashboard: &Dashboard) -> List<'a> {
+    let items = dashboard.errors.iter().map(|e| {
+        ListItem::new(Line::from(vec![
+            Span::styled(
+                e.at.format("%H:%M:%S ").to_string(),
+                Style::default().fg(Color::DarkGray),
+            ),
+            Span::raw(format!("{} {} ", short_id(&e.task_id), e.agent_id)),
+            Span::styled(e.message.clone(), Style::default().fg(Color::Red)),
+        ]))
+    });
+    List::new(items).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(" Recent errors "),
+    )
+}
+
+fn status_label(status: &TaskStatus) -> (&'static str, Color) {
+    match status {
+        TaskStatus::Pending => ("pending", Color::Gray),
+        TaskStatus::Running => ("running", Color::Green),
+        TaskStatus::InputRequired => ("input", Color::Yellow),
+        TaskStatus::Completed => ("completed", Color::Blue),
+        TaskStatus::Failed => ("failed", Color::Red),
+        TaskStatus::Canceled => ("canceled", Color::DarkGray),
+    }
+}
+
+fn short_id(id: &str) -> String {
+    id.chars().take(8).collect()
+}
+
+fn age(now: DateTime<Utc>, at: DateTime<Utc>) -> String {
+    let secs = (now - at).num_seconds().max(0);
+    if secs < 60 {
+        format!("{}s", secs)
+    } else if secs < 3600 {
+        format!("{}m", secs / 60)
+    } else {
+        format!("{}h", secs / 3600)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use distri_types::stores::TaskActivity;
+    use distri_types::Task;
+
+    fn event(task_id: &str, event: AgentEventType) -> AgentEvent {
+        AgentEvent {
+            timestamp: Utc::now(),
+            thread_id: "thread-1".into(),
+            run_id: "run-1".into(),
+            event,
+            task_id: task_id.into(),
+            parent_task_id: None,
+            agent_id: "researcher".into(),
+            user_id: None,
+            identifier_id: None,
+            workspace_id: None,
+            channel_id: None,
+        }
+    }
+
+    fn usage(tokens: u32, cost: f64) -> RunUsage {
+        RunUsage {
+            total_tokens: tokens,
+            cost_usd: Some(cost),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn events_track_step_tool_and_usage() {
+        let mut dash = Dashboard::default();
+        dash.apply_event(&event("t1", AgentEventType::RunStarted {}));
+        dash.apply_event(&event(
+            "t1",
+            AgentEventType::StepStarted {
+                step_id: "s".into(),
+                step_index: 1,
+            },
+        ));
+        dash.apply_event(&event(
+            "t1",
+            AgentEventType::ToolExecutionStart {
+                step_id: "s".into(),
+                tool_call_id: "c1".into(),
+                tool_call_name: "search".into(),
+                input: serde_json::json!({}),
+            },
+        ));
+
+        let row = &dash.tasks["t1"];
+        assert_eq!(row.status, TaskStatus::Running);
+        assert_eq!(row.step, Some(2));
+        assert_eq!(row.agent_id.as_deref(), Some("researcher"));
+        assert_eq!(
+            row.tools_in_flight.values().collect::<Vec<_>>(),
+            vec!["search"]
+        );
+
+        dash.apply_event(&event(
+            "t1",
+            AgentEventType::ToolExecutionEnd {
+                step_id: "s".into(),
+                tool_call_id: "c1".into(),
+                tool_call_name: "search".into(),
+                success: true,
+            },
+        ));
+        dash.apply_event(&event(
+            "t1",
+            AgentEventType::StepCompleted {
+                step_id: "s".into(),
+                success: true,
+                context_budget: None,
+                usage: Some(usage(1200, 0.01)),
+            },
+        ));
+
+        let row = &dash.tasks["t1"];
+        assert!(row.tools_in_flight.is_empty());
+        assert_eq!(dash.totals(), (1200, 0.01));
+        assert_eq!(dash.active_count(), 1);
+    }
+
+    #[test]
+    fn run_error_is_recorded_and_ends_the_task() {
+        let mut dash = Dashboard::default();
+        dash.apply_event(&event("t1", AgentEventType::RunStarted {}));
+        dash.apply_event(&event(
+            "t1",
+            AgentEventType::RunError {
+                message: "rate limited".into(),
+                code: None,
+                usage: Some(usage(300, 0.002)),
+            },
+        ));
+
+        assert_eq!(dash.tasks["t1"].status, TaskStatus::Failed);
+        assert!(dash.tasks["t1"].finished_at.is_some());
+        assert_eq!(dash.errors.len(), 1);
+        assert_eq!(dash.errors[0].message, "rate limited");
+        assert_eq!(dash.active_count(), 0);
+    }
+
+    #[test]
+    fn sync_drops_inactive_tasks_after_lingering() {
+        let mut dash = Dashboard::default();
+        let now = Utc::now();
+        let running = |id: &str| TaskWithActivity {
+            task: Task {
+                id: id.into(),
+                thread_id: "thread-1".into(),
+                status: TaskStatus::Running,
+                ..Default::default()
+            },
+            activity: TaskActivity {
+                intent: Some("summarize".into()),
+                ..Default::default()
+            },
+        };
+        dash.sync_tasks(vec![running("a"), running("b")], now);
+        assert_eq!(dash.tasks["a"].intent.as_deref(), Some("summarize"));
+
+        dash.apply_event(&event(
+            "b",
+            AgentEventType::RunFinished {
+                success: true,
+                total_steps: 1,
+                failed_steps: 0,
+                usage: None,
+                context_budget: None,
+            },
+        ));
+        // "a" vanished from the active set without a terminal event; "b"
+        // finished just now and lingers.
+        dash.sync_tasks(vec![], now);
+        assert!(!dash.tasks.contains_key("a"));
+        assert!(dash.tasks.contains_key("b"));
+
+        dash.sync_tasks(vec![], now + LINGER + chrono::Duration::seconds(1));
+        assert!(dash.tasks.is_empty());
+    }
+}
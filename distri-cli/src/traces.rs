@@ -4,6 +4,7 @@ use crossterm::terminal;
 use distri::{Distri, TraceSummary};
 use distri_types::{Message, Part};
 
+use crate::output::OutputFormat;
 use crate::{
     OptimizeCommands, TracesCommands, COLOR_BRIGHT_GREEN, COLOR_BRIGHT_MAGENTA, COLOR_GRAY,
     COLOR_RESET,
@@ -1134,7 +1135,11 @@ fn format_value_pretty(text: &str) -> String {
 // Command handler
 // ─────────────────────────────────────────────────────────────────────────────
 
-pub async fn handle_traces_command(client: &Distri, command: TracesCommands) -> Result<()> {
+pub async fn handle_traces_command(
+    client: &Distri,
+    command: TracesCommands,
+    output: OutputFormat,
+) -> Result<()> {
     match command {
         TracesCommands::List { limit, agent, tags } => {
             // Convert repeated key=value tag args into the compact wire form.
@@ -1151,7 +1156,15 @@ pub async fn handle_traces_command(client: &Distri, command: TracesCommands) ->
                         .join(","),
                 )
             };
-            print_trace_list(client, limit, agent.as_deref(), tags_filter.as_deref()).await;
+            if output.is_text() {
+                print_trace_list(client, limit, agent.as_deref(), tags_filter.as_deref()).await;
+            } else {
+                let mut traces = client
+                    .list_traces_filtered(Some(limit), agent.as_deref(), tags_filter.as_deref())
+                    .await?;
+                traces.sort_by_key(|t| t.last_activity_ns);
+                output.print_list(&traces, |_| {})?;
+            }
         }
         TracesCommands::Show {
             id,
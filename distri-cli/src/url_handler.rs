@@ -0,0 +1,269 @@
+//! `distri://` links.
+//!
+//! `distri url-handler install` registers the CLI as the OS handler of the
+//! `distri` URL scheme, so opening `distri://thread/<id>` (e.g. from a
+//! desktop notification) runs `distri open <url>`, which opens the thread in
+//! the web UI of the configured server.
+//!
+//! The web UI is the server's `/ui`, or `https://app.distri.dev` for the
+//! hosted API; `DISTRI_WEB_URL` overrides both.
+
+#[cfg(unix)]
+use std::path::PathBuf;
+use std::process::Command;
+
+use anyhow::{bail, Context, Result};
+
+use crate::UrlHandlerCommands;
+
+pub const SCHEME: &str = "distri";
+const HOSTED_API_HOST: &str = "api.distri.dev";
+const HOSTED_WEB_URL: &str = "https://app.distri.dev";
+const WEB_URL_ENV: &str = "DISTRI_WEB_URL";
+
+/// What a `distri://` link points at.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Link {
+    Thread(String),
+}
+
+impl Link {
+    pub fn parse(url: &str) -> Result<Self> {
+        let Some(rest) = url.trim().strip_prefix(&format!("{}://", SCHEME)) else {
+            bail!("not a {}:// link: {}", SCHEME, url);
+        };
+        let rest = rest.split(['?', '#']).next().unwrap_or_default();
+        match rest.trim_end_matches('/').split_once('/') {
+            Some(("thread", id)) if !id.is_empty() && !id.contains('/') => {
+                let id = urlencoding::decode(id).context("invalid thread id")?;
+                Ok(Link::Thread(id.into_owned()))
+            }
+            _ => bail!(
+                "unsupported link: {} (expected {}://thread/<id>)",
+                url,
+                SCHEME
+            ),
+        }
+    }
+
+    /// The page of the web UI showing the link's target.
+    pub fn web_url(&self, base_url: &str) -> String {
+        match self {
+            Link::Thread(id) => format!(
+                "{}/threads/{}",
+                web_ui_url(base_url),
+                urlencoding::encode(id)
+            ),
+        }
+    }
+}
+
+/// `distri://thread/<thread_id>`.
+pub fn thread_link(thread_id: &str) -> String {
+    format!("{}://thread/{}", SCHEME, urlencoding::encode(thread_id))
+}
+
+/// Root of the web UI belonging to the API at `base_url`.
+pub fn web_ui_url(base_url: &str) -> String {
+    if let Ok(url) = std::env::var(WEB_URL_ENV) {
+        if !url.trim().is_empty() {
+            return url.trim().trim_end_matches('/').to_string();
+        }
+    }
+    let base = base_url.trim_end_matches('/');
+    let host = base
+        .split("://")
+        .nth(1)
+        .and_then(|rest| rest.split(['/', ':']).next());
+    if host == Some(HOSTED_API_HOST) {
+        return HOSTED_WEB_URL.to_string();
+    }
+    format!("{}/ui", base.strip_suffix("/v1").unwrap_or(base))
+}
+
+/// `distri open <url>`: open a `distri://` link in the browser.
+pub fn open_link(url: &str, base_url: &str) -> Result<()> {
+    let page = Link::parse(url)?.web_url(base_url);
+    eprintln!("Opening {}", page);
+    open::that(&page).with_context(|| format!("opening {}", page))
+}
+
+pub fn handle_url_handler_command(command: UrlHandlerCommands) -> Result<()> {
+    match command {
+        UrlHandlerCommands::Install => {
+            let exe = std::env::current_exe().context("locating the distri executable")?;
+            install(&exe)?;
+            println!("{}:// links now open with {}.", SCHEME, exe.display());
+        }
+        UrlHandlerCommands::Uninstall => {
+            uninstall()?;
+            println!("Removed the {}:// link handler.", SCHEME);
+        }
+    }
+    Ok(())
+}
+
+fn run(command: &mut Command) -> Result<()> {
+    let status = command
+        .status()
+        .with_context(|| format!("running {:?}", command))?;
+    if !status.success() {
+        bail!("{:?} exited with {}", command, status);
+    }
+    Ok(())
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+const DESKTOP_FILE: &str = "distri-url-handler.desktop";
+
+#[cfg(all(unix, not(target_os = "macos")))]
+fn desktop_file_path() -> Result<PathBuf> {
+    let data = dirs::data_dir().context("could not resolve the data directory")?;
+    Ok(data.join("applications").join(DESKTOP_FILE))
+}
+
+/// A `.desktop` entry for the scheme, made the default handler with
+/// `xdg-mime`.
+#[cfg(all(unix, not(target_os = "macos")))]
+fn install(exe: &std::path::Path) -> Result<()> {
+    let path = desktop_file_path()?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let entry = format!(
+        "[Desktop Entry]\nType=Application\nName=Distri\nExec=\"{}\" open %u\n\
+         Terminal=false\nNoDisplay=true\nMimeType=x-scheme-handler/{};\n",
+        exe.display(),
+        SCHEME
+    );
+    std::fs::write(&path, entry).with_context(|| format!("writing {}", path.display()))?;
+    run(Command::new("xdg-mime").args([
+        "default",
+        DESKTOP_FILE,
+        &format!("x-scheme-handler/{}", SCHEME),
+    ]))
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+fn uninstall() -> Result<()> {
+    let path = desktop_file_path()?;
+    match std::fs::remove_file(&path) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn app_path() -> Result<PathBuf> {
+    let home = dirs::home_dir().context("could not resolve home dir")?;
+    Ok(home.join("Applications").join("Distri Links.app"))
+}
+
+/// macOS only hands URLs to app bundles: a small AppleScript app declaring
+/// the scheme forwards them to `distri open`.
+#[cfg(target_os = "macos")]
+fn install(exe: &std::path::Path) -> Result<()> {
+    let app = app_path()?;
+    if app.exists() {
+        std::fs::remove_dir_all(&app)?;
+    }
+    let script = format!(
+        "on open location theURL\n  do shell script quoted form of \"{}\" & \" open \" & quoted form of theURL\nend open location",
+        exe.display()
+    );
+    run(Command::new("osacompile")
+        .arg("-o")
+        .arg(&app)
+        .args(["-e", &script]))?;
+    let plist = app.join("Contents").join("Info.plist");
+    for entry in [
+        "Add :CFBundleIdentifier string dev.distri.links".to_string(),
+        "Add :CFBundleURLTypes array".to_string(),
+        "Add :CFBundleURLTypes:0 dict".to_string(),
+        "Add :CFBundleURLTypes:0:CFBundleURLName string Distri".to_string(),
+        "Add :CFBundleURLTypes:0:CFBundleURLSchemes array".to_string(),
+        format!(
+            "Add :CFBundleURLTypes:0:CFBundleURLSchemes:0 string {}",
+            SCHEME
+        ),
+    ] {
+        // osacompile may already set an identifier; only the URL types matter.
+        let _ = run(Command::new("/usr/libexec/PlistBuddy")
+            .args(["-c", &entry])
+            .arg(&plist));
+    }
+    run(Command::new(
+        "/System/Library/Frameworks/CoreServices.framework/Frameworks/LaunchServices.framework/Support/lsregister",
+    )
+    .arg("-f")
+    .arg(&app))
+}
+
+#[cfg(target_os = "macos")]
+fn uninstall() -> Result<()> {
+    let app = app_path()?;
+    if app.exists() {
+        std::fs::remove_dir_all(&app)?;
+    }
+    Ok(())
+}
+
+/// The scheme's keys under `HKCU\Software\Classes`.
+#[cfg(windows)]
+const REGISTRY_KEY: &str = r"HKCU\Software\Classes\distri";
+
+#[cfg(windows)]
+fn install(exe: &std::path::Path) -> Result<()> {
+    let command = format!("\"{}\" open \"%1\"", exe.display());
+    run(Command::new("reg").args(["add", REGISTRY_KEY, "/ve", "/d", "URL:Distri", "/f"]))?;
+    run(Command::new("reg").args(["add", REGISTRY_KEY, "/v", "URL Protocol", "/d", "", "/f"]))?;
+    run(Command::new("reg").args([
+        "add",
+        &format!(r"{}\shell\open\command", REGISTRY_KEY),
+        "/ve",
+        "/d",
+        &command,
+        "/f",
+    ]))
+}
+
+#[cfg(windows)]
+fn uninstall() -> Result<()> {
+    run(Command::new("reg").args(["delete", REGISTRY_KEY, "/f"]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn thread_links_round_trip() {
+        let link = thread_link("t 1");
+        assert_eq!(link, "distri://thread/t%201");
+        assert_eq!(Link::parse(&link).unwrap(), Link::Thread("t 1".to_string()));
+        assert_eq!(
+            Link::parse("distri://thread/abc/?focus=1").unwrap(),
+            Link::Thread("abc".to_string())
+        );
+    }
+
+    #[test]
+    fn other_links_are_rejected() {
+        assert!(Link::parse("https://thread/abc").is_err());
+        assert!(Link::parse("distri://thread/").is_err());
+        assert!(Link::parse("distri://agent/abc").is_err());
+    }
+
+    #[test]
+    fn threads_open_in_the_servers_web_ui() {
+        let link = Link::Thread("abc".to_string());
+        assert_eq!(
+            link.web_url("http://localhost:8080/v1"),
+            "http://localhost:8080/ui/threads/abc"
+        );
+        assert_eq!(
+            link.web_url("https://api.distri.dev/v1/"),
+            "https://app.distri.dev/threads/abc"
+        );
+    }
+}
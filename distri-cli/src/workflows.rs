@@ -0,0 +1,28 @@
+use anyhow::Result;
+use distri::Distri;
+
+use crate::output::OutputFormat;
+use crate::{COLOR_BRIGHT_GREEN, COLOR_GRAY, COLOR_RESET};
+
+/// `distri rerun <run> --from <step>`: start a partial re-run of a
+/// workflow run and list the steps that run again.
+pub async fn rerun_workflow(
+    client: &Distri,
+    run_task_id: &str,
+    from: &str,
+    output: OutputFormat,
+) -> Result<()> {
+    let rerun = client.rerun_workflow(run_task_id, from).await?;
+    output.print_value(&rerun, |rerun| {
+        println!(
+            "{}✔ Re-running {} from '{}'{}",
+            COLOR_BRIGHT_GREEN, rerun.run_task_id, rerun.from, COLOR_RESET
+        );
+        println!(
+            "{}  Steps: {}{}",
+            COLOR_GRAY,
+            rerun.steps.join(" → "),
+            COLOR_RESET
+        );
+    })
+}
@@ -0,0 +1,96 @@
+// Packs embedded in the binary; their files live under `packs/<name>/`.
+
+use super::{RecommendedMcpServer, TemplateFile, TemplatePack};
+
+macro_rules! pack_file {
+    ($pack:literal, $path:literal) => {
+        TemplateFile {
+            path: $path.to_string(),
+            contents: include_str!(concat!("packs/", $pack, "/", $path)).to_string(),
+        }
+    };
+}
+
+fn web_search() -> RecommendedMcpServer {
+    RecommendedMcpServer {
+        name: "web_search".to_string(),
+        description: "Tavily web search with ranked, summarised results".to_string(),
+        secrets: vec!["TAVILY_API_KEY".to_string()],
+    }
+}
+
+pub fn builtin_packs() -> Vec<TemplatePack> {
+    vec![
+        TemplatePack {
+            name: "research-assistant".to_string(),
+            description: "Researches questions on the web and writes sourced reports".to_string(),
+            files: vec![
+                pack_file!("research-assistant", "distri.yaml"),
+                pack_file!("research-assistant", "agents/researcher.md"),
+                pack_file!("research-assistant", "templates/research_report.hbs"),
+            ],
+            mcp_servers: vec![web_search()],
+            post_init: vec![
+                "distri push".to_string(),
+                "distri run --agent researcher --task \"What changed in the EU AI Act in 2025?\""
+                    .to_string(),
+            ],
+        },
+        TemplatePack {
+            name: "coder".to_string(),
+            description: "Reads, changes and tests code in a local project".to_string(),
+            files: vec![
+                pack_file!("coder", "distri.yaml"),
+                pack_file!("coder", "agents/coder.md"),
+                pack_file!("coder", "templates/change_summary.hbs"),
+            ],
+            mcp_servers: vec![RecommendedMcpServer {
+                name: "github".to_string(),
+                description: "Issues, pull requests and code search on GitHub".to_string(),
+                secrets: vec!["GITHUB_TOKEN".to_string()],
+            }],
+            post_init: vec![
+                "distri push".to_string(),
+                "distri tui coder".to_string(),
+            ],
+        },
+        TemplatePack {
+            name: "support-bot".to_string(),
+            description: "Answers customer questions from your docs and escalates the rest"
+                .to_string(),
+            files: vec![
+                pack_file!("support-bot", "distri.yaml"),
+                pack_file!("support-bot", "agents/support.md"),
+                pack_file!("support-bot", "templates/support_reply.hbs"),
+            ],
+            mcp_servers: vec![RecommendedMcpServer {
+                name: "zendesk".to_string(),
+                description: "Read and reply to support tickets".to_string(),
+                secrets: vec![
+                    "ZENDESK_SUBDOMAIN".to_string(),
+                    "ZENDESK_API_TOKEN".to_string(),
+                ],
+            }],
+            post_init: vec![
+                "Point agents/support.md at your documentation site".to_string(),
+                "distri push".to_string(),
+                "distri run --agent support --task \"How do I reset my password?\"".to_string(),
+            ],
+        },
+        TemplatePack {
+            name: "scraper".to_string(),
+            description: "Crawls pages and extracts structured records".to_string(),
+            files: vec![
+                pack_file!("scraper", "distri.yaml"),
+                pack_file!("scraper", "agents/scraper.md"),
+                pack_file!("scraper", "templates/extraction_summary.hbs"),
+            ],
+            mcp_servers: vec![web_search()],
+            post_init: vec![
+                "distri push".to_string(),
+                "distri run --agent scraper --task \"List the title and price of every book on https://books.toscrape.com\""
+                    .to_string(),
+            ],
+        },
+    ]
+}
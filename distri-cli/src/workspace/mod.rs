@@ -0,0 +1,192 @@
+// Workspace template packs for `distri init`.
+//
+// A pack is the set of files a new workspace starts from — agents under
+// `agents/`, prompt templates under `templates/` and a `distri.yaml` — plus
+// the MCP servers its agents work best with and the instructions printed
+// once the files are written. The built-in packs are embedded in the
+// binary; anything else is fetched as a JSON `TemplatePack` from a URL.
+
+mod builtin;
+
+use std::path::{Component, Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+pub use builtin::builtin_packs;
+
+/// Pack used when `distri init` is run without `--template`.
+pub const DEFAULT_TEMPLATE: &str = "coder";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TemplatePack {
+    pub name: String,
+    pub description: String,
+    pub files: Vec<TemplateFile>,
+    #[serde(default)]
+    pub mcp_servers: Vec<RecommendedMcpServer>,
+    /// Printed after the workspace is written.
+    #[serde(default)]
+    pub post_init: Vec<String>,
+}
+
+/// One file of a pack; `path` is relative to the workspace root.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TemplateFile {
+    pub path: String,
+    pub contents: String,
+}
+
+/// An MCP server the pack's agents are written to use. Agents pick it up
+/// through `[[tools.mcp]] server = "<name>"` once it is registered.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecommendedMcpServer {
+    pub name: String,
+    pub description: String,
+    /// Secrets the server needs, e.g. `TAVILY_API_KEY`.
+    #[serde(default)]
+    pub secrets: Vec<String>,
+}
+
+/// Resolve `--template`: a built-in pack name, or an `http(s)://` URL
+/// serving a pack as JSON.
+pub async fn resolve_pack(template: &str) -> Result<TemplatePack> {
+    if template.starts_with("http://") || template.starts_with("https://") {
+        return fetch_pack(template).await;
+    }
+    let packs = builtin_packs();
+    let names: Vec<String> = packs.iter().map(|p| p.name.clone()).collect();
+    packs
+        .into_iter()
+        .find(|p| p.name == template)
+        .with_context(|| {
+            format!(
+                "unknown template '{}'; available: {}",
+                template,
+                names.join(", ")
+            )
+        })
+}
+
+async fn fetch_pack(url: &str) -> Result<TemplatePack> {
+    let resp = reqwest::Client::new()
+        .get(url)
+        .header("User-Agent", "distri-cli")
+        .send()
+        .await
+        .with_context(|| format!("fetching template {}", url))?;
+    if !resp.status().is_success() {
+        bail!("fetching template {}: HTTP {}", url, resp.status());
+    }
+    let pack: TemplatePack = resp
+        .json()
+        .await
+        .with_context(|| format!("parsing template {}", url))?;
+    for file in &pack.files {
+        relative_path(&file.path)?;
+    }
+    Ok(pack)
+}
+
+/// Write `pack` into `dir`. Existing files are left alone unless `force`
+/// is set, and nothing is written if any would be overwritten.
+pub fn scaffold(pack: &TemplatePack, dir: &Path, force: bool) -> Result<Vec<PathBuf>> {
+    let targets = pack
+        .files
+        .iter()
+        .map(|file| Ok((dir.join(relative_path(&file.path)?), file)))
+        .collect::<Result<Vec<_>>>()?;
+    if !force {
+        let existing: Vec<String> = targets
+            .iter()
+            .filter(|(path, _)| path.exists())
+            .map(|(path, _)| path.display().to_string())
+            .collect();
+        if !existing.is_empty() {
+            bail!(
+                "refusing to overwrite existing files (use --force): {}",
+                existing.join(", ")
+            );
+        }
+    }
+
+    let mut written = Vec::new();
+    for (path, file) in targets {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("creating {}", parent.display()))?;
+        }
+        std::fs::write(&path, &file.contents)
+            .with_context(|| format!("writing {}", path.display()))?;
+        written.push(path);
+    }
+    Ok(written)
+}
+
+/// Pack paths come from remote JSON, so only plain relative paths are
+/// accepted.
+fn relative_path(path: &str) -> Result<PathBuf> {
+    let p = Path::new(path);
+    if path.is_empty() || !p.components().all(|c| matches!(c, Component::Normal(_))) {
+        bail!(
+            "template file path '{}' must be relative to the workspace",
+            path
+        );
+    }
+    Ok(p.to_path_buf())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn builtin_packs_configure_their_agents() {
+        for pack in builtin_packs() {
+            let config = pack
+                .files
+                .iter()
+                .find(|f| f.path == "distri.yaml")
+                .unwrap_or_else(|| panic!("{} has no distri.yaml", pack.name));
+            let config: serde_yaml::Value = serde_yaml::from_str(&config.contents).unwrap();
+            let agents = config["agents"].as_sequence().unwrap();
+            assert!(!agents.is_empty(), "{} seeds no agents", pack.name);
+            for agent in agents {
+                let file = agent["file"].as_str().unwrap();
+                assert!(
+                    pack.files.iter().any(|f| f.path == file),
+                    "{} references missing {}",
+                    pack.name,
+                    file
+                );
+            }
+            assert!(!pack.post_init.is_empty());
+        }
+    }
+
+    #[test]
+    fn scaffold_refuses_to_overwrite_without_force() {
+        let tmp = TempDir::new().unwrap();
+        let pack = builtin_packs()
+            .into_iter()
+            .find(|p| p.name == DEFAULT_TEMPLATE)
+            .unwrap();
+        std::fs::write(tmp.path().join("distri.yaml"), "version: 2\n").unwrap();
+
+        assert!(scaffold(&pack, tmp.path(), false).is_err());
+        assert!(!tmp.path().join("agents").exists());
+
+        let written = scaffold(&pack, tmp.path(), true).unwrap();
+        assert_eq!(written.len(), pack.files.len());
+        assert!(tmp.path().join("agents/coder.md").exists());
+    }
+
+    #[test]
+    fn pack_paths_must_stay_inside_the_workspace() {
+        assert!(relative_path("agents/a.md").is_ok());
+        assert!(relative_path("../outside.md").is_err());
+        assert!(relative_path("/etc/passwd").is_err());
+        assert!(relative_path("").is_err());
+    }
+}
@@ -228,6 +228,108 @@ impl Formatter for TextFormatter {
                     self.push_line("Run completed with errors");
                 }
             }
+            AgentEventType::OutputSinkFinished {
+                sink,
+                success: false,
+                error,
+            } => {
+                self.push_line(&format!(
+                    "Output sink {} failed: {}",
+                    sink,
+                    error.as_deref().unwrap_or("unknown error")
+                ));
+            }
+            AgentEventType::EnsembleJudged {
+                branches,
+                selected,
+                rationale,
+                ..
+            } => {
+                let choice = match selected.and_then(|i| branches.get(i)) {
+                    Some(branch) => format!("picked {}", branch.agent),
+                    None => "merged".to_string(),
+                };
+                self.push_line(&format!(
+                    "Ensemble of {} {}{}",
+                    branches.len(),
+                    choice,
+                    rationale
+                        .as_deref()
+                        .map(|r| format!(": {}", r))
+                        .unwrap_or_default()
+                ));
+            }
+            AgentEventType::ToolRecovery {
+                tool_call_name,
+                strategy,
+                error,
+                attempt,
+                recovered,
+                fallback_tool,
+                ..
+            } => {
+                let action = match (strategy.as_str(), fallback_tool) {
+                    ("retry", _) => format!("retry {}", attempt),
+                    ("fallback", Some(tool)) => format!("fallback to {}", tool),
+                    (strategy, _) => strategy.to_string(),
+                };
+                self.push_line(&format!(
+                    "Tool {} failed ({}): {}{}",
+                    tool_call_name,
+                    action,
+                    error,
+                    if *recovered { ", recovered" } else { "" }
+                ));
+            }
+            AgentEventType::ToolTimeout {
+                tool_call_name,
+                timeout_secs,
+                ..
+            } => {
+                self.push_line(&format!(
+                    "Tool {} timed out after {}s",
+                    tool_call_name, timeout_secs
+                ));
+            }
+            AgentEventType::ToolOutputInvalid {
+                tool_call_name,
+                violations,
+                ..
+            } => {
+                self.push_line(&format!(
+                    "Tool {} returned invalid output: {}",
+                    tool_call_name,
+                    violations.join("; ")
+                ));
+            }
+            AgentEventType::SideEffectSimulated { tool_call_name, .. } => {
+                self.push_line(&format!("Dry run: simulated {}", tool_call_name));
+            }
+            AgentEventType::DryRunReport { report } => {
+                self.push_line(&report.to_markdown());
+            }
+            AgentEventType::PromptInjectionDetected {
+                tool_call_name,
+                detections,
+                sanitized,
+                ..
+            } => {
+                self.push_line(&format!(
+                    "Possible prompt injection in {} output: {}{}",
+                    tool_call_name,
+                    detections.join(", "),
+                    if *sanitized { ", sanitized" } else { "" }
+                ));
+            }
+            AgentEventType::StructuredOutputRejected {
+                error, retrying, ..
+            } => {
+                self.push_line(&format!(
+                    "Structured output rejected: {}{}",
+                    error,
+                    if *retrying { ", retrying" } else { "" }
+                ));
+            }
             AgentEventType::RunError { message, code, .. } => {
                 let stamp = Local::now().format("%H:%M:%S").to_string();
                 self.push_line(&format!(
@@ -241,11 +343,22 @@ impl Formatter for TextFormatter {
                     request.hook_id, request.hook,
                 ));
             }
+            AgentEventType::AuthRequired { request } => {
+                self.push_line(&format!(
+                    "{} needs access to {}: {}",
+                    request.tool_name,
+                    request.provider,
+                    request.missing_scopes.join(", ")
+                ));
+            }
             AgentEventType::TodosUpdated {
                 formatted_todos, ..
             } => {
                 self.push_line(&format!("Todos updated:\n{}", formatted_todos));
             }
+            AgentEventType::TodoQueueFinished { summary } => {
+                self.push_line(&summary.format_display());
+            }
             AgentEventType::AgentHandover {
                 from_agent,
                 to_agent,
@@ -137,6 +137,10 @@ pub struct PlanConfig {
     /// The maximum number of iterations allowed during planning
     #[serde(default = "default_plan_max_iterations")]
     pub max_iterations: usize,
+    /// Models tried, in order, when a call to `model_settings` fails (see
+    /// [`StandardDefinition::fallback_model_settings`]).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub model_fallbacks: Vec<ModelSettings>,
 }
 
 impl Default for PlanConfig {
@@ -144,6 +148,7 @@ impl Default for PlanConfig {
         Self {
             model_settings: None,
             max_iterations: default_plan_max_iterations(),
+            model_fallbacks: Vec::new(),
         }
     }
 }
@@ -453,6 +458,66 @@ pub struct CompactionConfig {
     pub prompt_version: Option<String>,
 }
 
+/// JSON Schema the agent's final answer must follow. Sent to the model as
+/// its `response_format` unless `model_settings` already sets one.
+///
+/// ```toml
+/// [response_schema]
+/// name = "triage"
+/// file = "schemas/triage.json"   # or: schema = { type = "object", ... }
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct ResponseSchemaConfig {
+    /// Schema name sent to the provider. Defaults to the agent name.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// The schema itself.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schema: Option<serde_json::Value>,
+    /// A JSON file holding the schema, relative to the agent's markdown
+    /// file. Inlined into `schema` when the agent is loaded from disk or
+    /// pushed with `distri push`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file: Option<String>,
+    /// Ask the provider to enforce the schema strictly (default: true).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub strict: Option<bool>,
+}
+
+impl ResponseSchemaConfig {
+    /// Read `file` relative to `base_dir` into `schema`.
+    pub fn inline_file(&mut self, base_dir: &std::path::Path) -> Result<(), String> {
+        let Some(file) = self.file.take() else {
+            return Ok(());
+        };
+        let path = base_dir.join(&file);
+        let content = std::fs::read_to_string(&path)
+            .map_err(|e| format!("response_schema.file '{}': {}", path.display(), e))?;
+        let schema = serde_json::from_str(&content)
+            .map_err(|e| format!("response_schema.file '{}': {}", path.display(), e))?;
+        self.schema = Some(schema);
+        Ok(())
+    }
+
+    /// The `response_format` of this schema, once it is inline.
+    pub fn response_format(&self, agent_name: &str) -> Option<serde_json::Value> {
+        let schema = self.schema.as_ref()?;
+        let name = self
+            .name
+            .clone()
+            .unwrap_or_else(|| agent_name.replace('/', "_"));
+        Some(serde_json::json!({
+            "type": "json_schema",
+            "json_schema": {
+                "name": name,
+                "schema": schema,
+                "strict": self.strict.unwrap_or(true),
+            }
+        }))
+    }
+}
+
 /// Agent definition - complete configuration for an agent
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
 pub struct StandardDefinition {
@@ -474,6 +539,11 @@ pub struct StandardDefinition {
     /// When `None`, the agent inherits model settings from the orchestrator context defaults.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub model_settings: Option<ModelSettings>,
+    /// Models to try, in order, when a call to the agent's model fails:
+    /// `provider/model` to switch provider, or a bare model name on the
+    /// same provider.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub model_fallbacks: Vec<String>,
     /// Optional lower-level model settings for lightweight analysis helpers
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub analysis_model_settings: Option<ModelSettings>,
@@ -495,6 +565,12 @@ pub struct StandardDefinition {
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub commands: Vec<crate::channel_commands::SlashCommand>,
 
+    /// Memory namespaces this agent may read and write (`user`, `agent`,
+    /// `agent:<name>`, `team:<name>`, `workspace`). When `None` the agent
+    /// only sees the calling user's memories.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memory: Option<crate::memory::MemoryAccessConfig>,
+
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub max_iterations: Option<usize>,
 
@@ -530,6 +606,12 @@ pub struct StandardDefinition {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub tools: Option<ToolsConfig>,
 
+    /// Extra names for tools, alias → tool name or qualified `namespace/tool`
+    /// name. Exposes a tool shadowed by a name collision (see
+    /// [`crate::tool_catalog`]).
+    #[serde(default, skip_serializing_if = "std::collections::BTreeMap::is_empty")]
+    pub tool_aliases: std::collections::BTreeMap<String, String>,
+
     /// Custom handlebars partials (name -> template path) for use in custom prompts
     #[serde(default, skip_serializing_if = "std::collections::HashMap::is_empty")]
     pub partials: std::collections::HashMap<String, String>,
@@ -566,14 +648,81 @@ pub struct StandardDefinition {
     )]
     pub include_scratchpad: Option<bool>,
 
+    /// Optional system prompt layers (workspace policy, runtime context).
+    /// See [`crate::prompt::PromptLayerKind`] for the composition order.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prompt_layers: Option<crate::prompt::PromptLayersConfig>,
+
     /// Optional hook names to attach to this agent
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub hooks: Vec<String>,
 
+    /// After the first exchange on a thread, generate a concise title and
+    /// topical tags with `analysis_model_settings` (default: false)
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub auto_title: bool,
+
+    /// Transformers applied, in order, to the final answer (see
+    /// [`crate::post_process`]).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub post_process: Vec<crate::post_process::PostProcessorConfig>,
+
+    /// Where the final answer is delivered once a run succeeds (see
+    /// [`crate::output_sinks`]).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub output_sinks: Vec<crate::output_sinks::OutputSinkConfig>,
+
+    /// Answer by running the task through several agents and letting a
+    /// judge settle the result (see [`crate::ensemble`]).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ensemble: Option<crate::ensemble::EnsembleConfig>,
+
+    /// Critique the final answer and let the agent revise it once (see
+    /// [`crate::critique`]).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub critique: Option<crate::critique::CritiqueConfig>,
+
+    /// Rules the agent must follow, listed in its system prompt and checked
+    /// by `critique` when it is enabled.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub guardrails: Vec<String>,
+
+    /// Schema of the final answer.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub response_schema: Option<ResponseSchemaConfig>,
+
+    /// Hand requests the agent lacks the tools for to a better-suited agent
+    /// (see [`crate::handoff`]).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub handoff: Option<crate::handoff::HandoffConfig>,
+
+    /// Current date and time in the system prompt, and the timezone and
+    /// business calendar of the `datetime_math` / `timezone_convert` tools
+    /// (see [`crate::datetime`]).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub datetime: Option<crate::datetime::DateTimeConfig>,
+
+    /// Model parameters a client may change per message. Nothing can be
+    /// overridden when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message_overrides: Option<crate::configuration::MessageOverridePolicy>,
+
     /// Custom user message construction (dynamic prompting)
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub user_message_overrides: Option<UserMessageOverrides>,
 
+    /// Cap on the tokens a single model response may produce. Sent to the
+    /// provider as `max_tokens` and enforced while streaming; a response cut
+    /// off at the cap emits a `truncated` event.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_output_tokens: Option<u32>,
+
+    /// When a response is truncated, ask the model to resume where it
+    /// stopped, up to this many times per turn. Unset keeps the truncated
+    /// response as is.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auto_continue: Option<u32>,
+
     /// Whether context compaction is enabled for this agent (default: true)
     #[serde(
         default = "default_compaction_enabled",
@@ -711,6 +860,11 @@ impl StandardDefinition {
         self.browser_config.as_ref().map(|cfg| cfg.runtime_config())
     }
 
+    /// What the agent's browser tools may do, if restricted
+    pub fn browser_policy(&self) -> Option<&crate::browser::BrowserPolicy> {
+        self.browser_config.as_ref().and_then(|cfg| cfg.policy())
+    }
+
     /// Should browser session state be serialized after tool runs
     pub fn should_persist_browser_session(&self) -> bool {
         self.browser_config
@@ -785,6 +939,9 @@ impl StandardDefinition {
             if let Some(temperature) = overrides.temperature {
                 ms.inner.temperature = Some(temperature);
             }
+            if let Some(reasoning_effort) = overrides.reasoning_effort {
+                ms.inner.reasoning_effort = Some(reasoning_effort);
+            }
             if let Some(max_tokens) = overrides.max_tokens {
                 ms.inner.max_tokens = Some(max_tokens);
             }
@@ -868,6 +1025,8 @@ pub const VALID_BUILTIN_TOOLS: &[&str] = &[
     "browsr_browser",
     "browsr_crawl",
     "browser_step",
+    "browser_tabs",
+    "browser_extract_tabs",
     "search",
     // Shell
     "start_shell",
@@ -875,6 +1034,7 @@ pub const VALID_BUILTIN_TOOLS: &[&str] = &[
     "stop_shell",
     // Code execution
     "distri_execute_code",
+    "python_exec",
     // Tool discovery
     "tool_search",
     // Skills (load body into current agent context; sub-agents call this
@@ -892,6 +1052,15 @@ pub const VALID_BUILTIN_TOOLS: &[&str] = &[
     // `get_builtin_tools()` by name and finds nothing). Single
     // canonical name across validation / dispatch / skill bodies.
     "write_todos",
+    // Runs the todo list as sub-tasks, in priority / dependency order.
+    "work_todos",
+    // Date arithmetic and timezone conversion.
+    "datetime_math",
+    "timezone_convert",
+    // Reads an artifact of an earlier turn (see `prompt_layers.runtime`).
+    "load_artifact",
+    // Handover summary of the thread for a human taking over.
+    "summarize_thread",
 ];
 
 /// Tools that always get full schemas, never deferred.
@@ -944,6 +1113,125 @@ pub struct ToolsConfig {
     /// Useful for agent-specific tools that should never be deferred.
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub always_full_schema: Vec<String>,
+
+    /// Memoization of repeated identical tool calls within a task.
+    #[serde(default, skip_serializing_if = "ToolMemoizeConfig::is_default")]
+    pub memoize: ToolMemoizeConfig,
+
+    /// How same-named tools from different sources are settled.
+    #[serde(
+        default,
+        skip_serializing_if = "crate::tool_catalog::ToolCollisionConfig::is_default"
+    )]
+    pub collisions: crate::tool_catalog::ToolCollisionConfig,
+
+    /// How failed tool calls are handled.
+    #[serde(
+        default,
+        skip_serializing_if = "crate::tool_recovery::ToolRecoveryConfig::is_default"
+    )]
+    pub recovery: crate::tool_recovery::ToolRecoveryConfig,
+
+    /// How long tool calls may run.
+    #[serde(
+        default,
+        skip_serializing_if = "crate::tool_timeouts::ToolTimeoutConfig::is_default"
+    )]
+    pub timeouts: crate::tool_timeouts::ToolTimeoutConfig,
+
+    /// How tool results are checked against their output schemas.
+    #[serde(
+        default,
+        skip_serializing_if = "crate::tool_output::ToolOutputValidation::is_default"
+    )]
+    pub output_validation: crate::tool_output::ToolOutputValidation,
+
+    /// How tool results are scanned for prompt injection.
+    #[serde(
+        default,
+        skip_serializing_if = "crate::injection_guard::InjectionGuardConfig::is_default"
+    )]
+    pub injection_guard: crate::injection_guard::InjectionGuardConfig,
+
+    /// Settings of individual tools, tool name → table. Handed to the tool
+    /// as its `tool_metadata` entry; values a request sends take precedence.
+    #[serde(default, skip_serializing_if = "std::collections::BTreeMap::is_empty")]
+    pub config: std::collections::BTreeMap<String, serde_json::Value>,
+}
+
+/// `*` matches any run of characters; everything else matches itself.
+fn wildcard_match(pattern: &str, name: &str) -> bool {
+    let Some((head, rest)) = pattern.split_once('*') else {
+        return pattern == name;
+    };
+    let Some(mut remaining) = name.strip_prefix(head) else {
+        return false;
+    };
+    let mut parts = rest.split('*').peekable();
+    while let Some(part) = parts.next() {
+        if parts.peek().is_none() {
+            return remaining.ends_with(part);
+        }
+        match remaining.find(part) {
+            Some(index) => remaining = &remaining[index + part.len()..],
+            None => return false,
+        }
+    }
+    true
+}
+
+/// Which tools have repeated identical calls (same name and input) within a
+/// task answered from the task's tool call cache instead of running again.
+///
+/// Tools that declare themselves memoizable (search, scrape, schema lookups)
+/// are memoized unless excluded here; `include` opts in other tools, such as
+/// read-only MCP tools, by name.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ToolMemoizeConfig {
+    /// Set to false to turn memoization off for the agent.
+    #[serde(default = "default_memoize_enabled")]
+    pub enabled: bool,
+
+    /// Additional tools to memoize.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub include: Vec<String>,
+
+    /// Tools never to memoize, even if they declare themselves memoizable.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub exclude: Vec<String>,
+}
+
+impl Default for ToolMemoizeConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            include: Vec::new(),
+            exclude: Vec::new(),
+        }
+    }
+}
+
+fn default_memoize_enabled() -> bool {
+    true
+}
+
+impl ToolMemoizeConfig {
+    fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Whether calls to `tool` are memoized.
+    pub fn applies_to(&self, tool: &dyn crate::Tool) -> bool {
+        if !self.enabled {
+            return false;
+        }
+        let name = tool.get_name();
+        if self.exclude.contains(&name) {
+            return false;
+        }
+        tool.memoizable() || self.include.contains(&name)
+    }
 }
 
 fn is_default_delivery_mode(mode: &ToolDeliveryMode) -> bool {
@@ -966,6 +1254,28 @@ impl ToolsConfig {
         CORE_TOOLS.contains(&name) || self.always_full_schema.iter().any(|n| n == name)
     }
 
+    /// Whether the agent's tool list admits `name`. MCP patterns may use
+    /// `*` wildcards.
+    pub fn allows(&self, name: &str) -> bool {
+        self.builtin.iter().any(|tool| tool == name)
+            || self.dynamic.iter().any(|tool| tool.name == name)
+            || self
+                .external
+                .iter()
+                .flatten()
+                .any(|tool| tool == "*" || tool == name)
+            || self.mcp.iter().any(|server| {
+                server
+                    .include
+                    .iter()
+                    .any(|pattern| wildcard_match(pattern, name))
+                    && !server
+                        .exclude
+                        .iter()
+                        .any(|pattern| wildcard_match(pattern, name))
+            })
+    }
+
     /// Effective threshold for automatic tool deferral.
     pub fn effective_threshold(&self) -> usize {
         self.deferred_threshold
@@ -1073,10 +1383,18 @@ pub enum ModelProvider {
         resource: String,
         api_key: Option<String>,
     },
+    /// AWS Bedrock through the Converse API, signed with SigV4.
+    /// `api_key` is the access key id; the secret access key, optional
+    /// session token and region come from the `AWS_SECRET_ACCESS_KEY`,
+    /// `AWS_SESSION_TOKEN` and `AWS_REGION` secrets. `base_url` defaults to
+    /// the region's `bedrock-runtime` endpoint.
     #[serde(rename = "aws_bedrock")]
     AwsBedrock {
+        #[serde(default)]
         base_url: String,
         api_key: Option<String>,
+        #[serde(default)]
+        region: Option<String>,
     },
     #[serde(rename = "google_vertex")]
     GoogleVertex {
@@ -1367,6 +1685,17 @@ impl ModelProvider {
         "2024-06-01".to_string()
     }
 
+    /// Region used for AWS Bedrock when neither the provider nor the
+    /// `AWS_REGION` secret names one.
+    pub fn aws_default_region() -> &'static str {
+        "us-east-1"
+    }
+
+    /// Bedrock runtime endpoint of an AWS region.
+    pub fn aws_bedrock_base_url(region: &str) -> String {
+        format!("https://bedrock-runtime.{}.amazonaws.com", region.trim())
+    }
+
     pub fn alibaba_cloud_base_url() -> String {
         "https://dashscope-intl.aliyuncs.com/compatible-mode/v1".to_string()
     }
@@ -1490,16 +1819,18 @@ impl ModelProvider {
     /// The canonical secret-store key for this provider's endpoint URL, or
     /// `None` if the provider has a fixed endpoint baked into the variant.
     ///
-    /// Only providers that require a tenant-specific endpoint (Azure, Bedrock,
-    /// Vertex) return `Some`; everything else uses a default base URL.
+    /// Only providers that require a tenant-specific endpoint (Azure, Vertex)
+    /// return `Some`; everything else uses a default base URL.
     pub fn endpoint_secret(&self) -> Option<&'static str> {
         match self {
             ModelProvider::AzureOpenAI { .. } => Some("AZURE_OPENAI_ENDPOINT"),
             // Holds the Azure resource name, not a URL — see the variant doc.
             ModelProvider::AzureAiFoundry { .. } => Some("AZURE_AI_FOUNDRY_RESOURCE"),
-            ModelProvider::AwsBedrock { .. } => Some("AWS_BEDROCK_ENDPOINT"),
             ModelProvider::GoogleVertex { .. } => Some("GOOGLE_VERTEX_ENDPOINT"),
-            ModelProvider::OpenAI {}
+            // Bedrock's endpoint follows from the region; `AWS_BEDROCK_ENDPOINT`
+            // is only an optional override, read when the client is built.
+            ModelProvider::AwsBedrock { .. }
+            | ModelProvider::OpenAI {}
             | ModelProvider::OpenAICompatible { .. }
             | ModelProvider::Anthropic { .. }
             | ModelProvider::ZAi { .. }
@@ -1601,9 +1932,9 @@ impl ModelProvider {
             ModelProvider::AzureOpenAI {
                 base_url, api_key, ..
             } => (Some(base_url.clone()), api_key.clone()),
-            ModelProvider::AwsBedrock { base_url, api_key } => {
-                (Some(base_url.clone()), api_key.clone())
-            }
+            ModelProvider::AwsBedrock {
+                base_url, api_key, ..
+            } => (Some(base_url.clone()), api_key.clone()),
             ModelProvider::GoogleVertex {
                 base_url, api_key, ..
             } => (Some(base_url.clone()), api_key.clone()),
@@ -1749,6 +2080,85 @@ pub struct ModelSettingsInner {
     /// Only relevant for OpenAI, OpenAI-compatible, and Azure OpenAI providers.
     #[serde(default, skip_serializing_if = "is_default_api_format")]
     pub api_format: OpenAiApiFormat,
+    /// How much a reasoning model thinks before it answers. Sent to
+    /// OpenAI-family providers; ignored by others.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reasoning_effort: Option<ReasoningEffort>,
+    /// The name the provider knows `model` by, when it differs: an Azure
+    /// OpenAI deployment name or a Bedrock model id / inference profile
+    /// (e.g. `us.anthropic.claude-sonnet-4-20250514-v1:0`). See
+    /// [`ModelSettings::provider_model_id`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provider_model: Option<String>,
+    /// Prompt caching of the stable start of each request (system prompt,
+    /// tool schemas, earlier turns). See [`PromptCache`].
+    #[serde(default, skip_serializing_if = "PromptCache::is_auto")]
+    pub prompt_cache: PromptCache,
+    /// Whether the model may make several tool calls in one reply. Sent to
+    /// Chat Completions providers with native tool calling; ignored by
+    /// others. Set to `false` for a model found unreliable at it (see
+    /// [`crate::capability_probe`]).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parallel_tool_calls: Option<bool>,
+}
+
+/// Whether requests ask the provider to cache their stable prefix.
+///
+/// With `auto`, the system prompt, the tool schemas and the conversation up
+/// to a few turns back are marked cacheable: `cache_control` breakpoints on
+/// Anthropic, cache points on Bedrock, and a `prompt_cache_key` on OpenAI,
+/// which caches on its own. Cache reads and writes show up in the run's
+/// usage either way.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum PromptCache {
+    #[default]
+    Auto,
+    Off,
+}
+
+impl PromptCache {
+    pub fn is_auto(&self) -> bool {
+        *self == PromptCache::Auto
+    }
+}
+
+/// Reasoning effort of a reasoning model.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ReasoningEffort {
+    Minimal,
+    Low,
+    Medium,
+    High,
+}
+
+impl ReasoningEffort {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Minimal => "minimal",
+            Self::Low => "low",
+            Self::Medium => "medium",
+            Self::High => "high",
+        }
+    }
+}
+
+impl std::str::FromStr for ReasoningEffort {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "minimal" => Ok(Self::Minimal),
+            "low" => Ok(Self::Low),
+            "medium" => Ok(Self::Medium),
+            "high" => Ok(Self::High),
+            other => Err(format!(
+                "unknown reasoning effort '{}' (expected minimal, low, medium or high)",
+                other
+            )),
+        }
+    }
 }
 
 impl ModelSettings {
@@ -1787,8 +2197,7 @@ impl ModelSettings {
     /// override) gets the same hydration treatment.
     ///
     /// Errors if a required endpoint secret is missing
-    /// (`AzureOpenAI` / `AzureAiFoundry` / `AwsBedrock` /
-    /// `GoogleVertex` all need a tenant-specific endpoint that has no
+    /// (`AzureOpenAI` / `AzureAiFoundry` / `GoogleVertex` all need a tenant-specific endpoint that has no
     /// safe default — silently dropping it produces an unparseable
     /// base URL and a downstream client panic). Missing api_keys
     /// downgrade to a warning since some providers can fall back to
@@ -1893,6 +2302,7 @@ impl ModelSettings {
             "aws_bedrock" => ModelProvider::AwsBedrock {
                 base_url: String::new(),
                 api_key: None,
+                region: None,
             },
             "google_vertex" => ModelProvider::GoogleVertex {
                 base_url: String::new(),
@@ -1986,6 +2396,11 @@ impl ModelSettings {
             };
             (self.inner.provider.clone(), model)
         };
+        // A provider-side name belongs to the settings the model came from.
+        let provider_model = [override_settings, self]
+            .into_iter()
+            .find(|settings| settings.model == model && settings.inner.provider_model.is_some())
+            .and_then(|settings| settings.inner.provider_model.clone());
 
         if model.is_empty() {
             return None;
@@ -2028,9 +2443,67 @@ impl ModelSettings {
                 } else {
                     self.inner.api_format.clone()
                 },
+                reasoning_effort: override_settings
+                    .inner
+                    .reasoning_effort
+                    .or(self.inner.reasoning_effort),
+                provider_model,
+                prompt_cache: if override_settings.inner.prompt_cache.is_auto() {
+                    self.inner.prompt_cache
+                } else {
+                    override_settings.inner.prompt_cache
+                },
+                parallel_tool_calls: override_settings
+                    .inner
+                    .parallel_tool_calls
+                    .or(self.inner.parallel_tool_calls),
             },
         })
     }
+
+    /// The model name to send to the provider. An explicit `provider_model`
+    /// wins; otherwise Azure OpenAI routes on the provider's `deployment`
+    /// and Bedrock maps the short names it knows to model ids. Everything
+    /// else uses `model` as-is.
+    pub fn provider_model_id(&self) -> String {
+        if let Some(provider_model) = self
+            .inner
+            .provider_model
+            .as_deref()
+            .filter(|m| !m.is_empty())
+        {
+            return provider_model.to_string();
+        }
+        match &self.inner.provider {
+            ModelProvider::AzureOpenAI { deployment, .. } if !deployment.is_empty() => {
+                deployment.clone()
+            }
+            ModelProvider::AwsBedrock { .. } => bedrock_model_id(&self.model)
+                .map(str::to_string)
+                .unwrap_or_else(|| self.model.clone()),
+            _ => self.model.clone(),
+        }
+    }
+}
+
+/// Bedrock model ids for the short model names distri accepts with the
+/// `aws_bedrock/` prefix. Claude and Llama map to the US cross-region
+/// inference profiles; in other regions set `provider_model`. Names not
+/// listed here are sent unchanged, so a full model id or inference profile
+/// ARN always works.
+fn bedrock_model_id(model: &str) -> Option<&'static str> {
+    Some(match model {
+        "claude-sonnet-4" => "us.anthropic.claude-sonnet-4-20250514-v1:0",
+        "claude-opus-4" => "us.anthropic.claude-opus-4-20250514-v1:0",
+        "claude-3-7-sonnet" => "us.anthropic.claude-3-7-sonnet-20250219-v1:0",
+        "claude-3-5-haiku" => "us.anthropic.claude-3-5-haiku-20241022-v1:0",
+        "nova-pro" => "amazon.nova-pro-v1:0",
+        "nova-lite" => "amazon.nova-lite-v1:0",
+        "nova-micro" => "amazon.nova-micro-v1:0",
+        "llama3-3-70b" => "us.meta.llama3-3-70b-instruct-v1:0",
+        "mistral-large" => "mistral.mistral-large-2407-v1:0",
+        _ => return None,
+    })
 }
 
 // Default functions
@@ -2073,6 +2546,25 @@ impl StandardDefinition {
             return Err(anyhow::anyhow!("Agent name cannot be empty"));
         }
 
+        if let Some(ensemble) = &self.ensemble {
+            ensemble.validate(&self.name).map_err(anyhow::Error::msg)?;
+        }
+
+        if let Some(datetime) = &self.datetime {
+            datetime.validate().map_err(anyhow::Error::msg)?;
+        }
+
+        if let Some(policy) = self.browser_policy() {
+            policy.validate().map_err(anyhow::Error::msg)?;
+        }
+
+        if let Some(file) = self.response_schema.as_ref().and_then(|r| r.file.as_ref()) {
+            anyhow::bail!(
+                "response_schema.file '{}' was not inlined; load the agent from its directory or push it with `distri push`",
+                file
+            );
+        }
+
         // Validate reflection configuration
         if let Some(ref reflection) = self.reflection
             && reflection.enabled
@@ -2090,6 +2582,46 @@ impl StandardDefinition {
         Ok(())
     }
 
+    /// The models of `model_fallbacks`, each on the provider settings of
+    /// `model_settings` unless it names its own provider.
+    pub fn fallback_model_settings(&self) -> Vec<ModelSettings> {
+        let Some(primary) = &self.model_settings else {
+            return Vec::new();
+        };
+        self.model_fallbacks
+            .iter()
+            .filter_map(|fallback| {
+                let mut settings = primary.clone();
+                match ModelSettings::from_provider_model_str(fallback) {
+                    Ok(Some(resolved)) => {
+                        if resolved.inner.provider.provider_id()
+                            != primary.inner.provider.provider_id()
+                        {
+                            settings.inner.provider = resolved.inner.provider;
+                        }
+                        settings.model = resolved.model;
+                    }
+                    Ok(None) if !fallback.contains('/') => settings.model = fallback.clone(),
+                    _ => return None,
+                }
+                Some(settings)
+            })
+            .collect()
+    }
+
+    /// The `<guardrails>` section the system prompt ends with, if any.
+    pub fn guardrails_prompt(&self) -> Option<String> {
+        if self.guardrails.is_empty() {
+            return None;
+        }
+        let mut section = String::from("<guardrails>\nYou must follow these rules:\n");
+        for rule in &self.guardrails {
+            section.push_str(&format!("- {}\n", rule.trim()));
+        }
+        section.push_str("</guardrails>");
+        Some(section)
+    }
+
     /// Validate that a reflection agent definition has the "reflect" tool configured.
     /// This is called at registration time when we have access to the full agent config.
     pub fn validate_reflection_agent(agent_def: &StandardDefinition) -> anyhow::Result<()> {
@@ -2165,75 +2697,35 @@ impl ToolsConfig {
     }
 }
 
+/// Parse an agent markdown file: TOML front matter between `---` markers,
+/// then the instructions (see [`crate::agent_frontmatter`]). Errors name the
+/// offending line.
 pub async fn parse_agent_markdown_content(content: &str) -> Result<StandardDefinition, AgentError> {
-    // Split by --- to separate TOML frontmatter from markdown content
-    let parts: Vec<&str> = content.split("---").collect();
+    let front_matter = crate::agent_frontmatter::FrontMatter::split(content)?;
+    let mut agent_def = front_matter
+        .parse()
+        .map_err(|e| crate::agent_frontmatter::to_agent_error(&[e]))?;
 
-    if parts.len() < 3 {
-        return Err(AgentError::Validation(
-            "Invalid agent markdown format. Expected TOML frontmatter between --- markers"
-                .to_string(),
-        ));
+    let errors = front_matter.validate(&agent_def);
+    if !errors.is_empty() {
+        return Err(crate::agent_frontmatter::to_agent_error(&errors));
     }
 
-    // Parse TOML frontmatter (parts[1] is between the first two --- markers)
-    let toml_content = parts[1].trim();
-    let mut agent_def: crate::StandardDefinition =
-        toml::from_str(toml_content).map_err(|e| AgentError::Validation(e.to_string()))?;
-
-    // Validate agent name format using centralized validation
-    if let Err(validation_error) = validate_plugin_name(&agent_def.name) {
-        return Err(AgentError::Validation(format!(
-            "Invalid agent name '{}': {}",
-            agent_def.name, validation_error
-        )));
-    }
-
-    // Validate that agent name characters are valid (alphanumeric, underscore, or single '/' for namespacing)
-    if !agent_def
-        .name
-        .chars()
-        .all(|c| c.is_alphanumeric() || c == '_' || c == '/')
-        || agent_def
-            .name
-            .chars()
-            .next()
-            .is_some_and(|c| c.is_numeric())
-        || agent_def.name.chars().filter(|&c| c == '/').count() > 1
-    {
-        return Err(AgentError::Validation(format!(
-            "Invalid agent name '{}': Agent names must be alphanumeric with underscores, at most one '/' for namespacing (e.g. '_system/plan'), cannot start with number.",
-            agent_def.name
-        )));
-    }
-
-    // Extract markdown instructions (everything after the second ---)
-    let instructions = parts[2..].join("---").trim().to_string();
-
-    // Set the instructions in the agent definition
-    agent_def.instructions = instructions;
+    agent_def.instructions = front_matter.body.trim().to_string();
 
     // Resolve `provider/model` prefix on `model_settings.model`. When the
     // agent author writes `model = "azure_ai_foundry/gpt-5.4"` we need
     // to (a) split the prefix, (b) set `provider` explicitly so
     // ModelSettings::merge() doesn't fall back to the workspace default
     // provider, and (c) rewrite `model` to just the bare model name.
-    // Unknown prefixes error out — the caller is making a clearly
-    // invalid claim ("dispatch to provider X") that must not silently
-    // fall back to "use whatever the workspace default is".
-    if let Some(ref mut ms) = agent_def.model_settings {
-        if ms.model.contains('/') {
-            let resolved = ModelSettings::from_provider_model_str(&ms.model)
-                .map_err(AgentError::Validation)?
-                .ok_or_else(|| {
-                    AgentError::Validation(format!(
-                        "agent '{}': invalid model_settings.model '{}' — empty model name after the provider prefix",
-                        agent_def.name, ms.model
-                    ))
-                })?;
-            ms.model = resolved.model;
-            ms.inner.provider = resolved.inner.provider;
-        }
+    // Unknown prefixes were rejected by `validate` above — the caller is
+    // making a clearly invalid claim ("dispatch to provider X") that must
+    // not silently fall back to "use whatever the workspace default is".
+    if let Some(ref mut ms) = agent_def.model_settings
+        && let Ok(Some(resolved)) = ModelSettings::from_provider_model_str(&ms.model)
+    {
+        ms.model = resolved.model;
+        ms.inner.provider = resolved.inner.provider;
     }
 
     Ok(agent_def)
@@ -3045,6 +3537,30 @@ tool_format = "json_l"
         );
     }
 
+    #[test]
+    fn merge_prompt_cache_off_on_either_side_wins() {
+        let off: ModelSettings =
+            serde_json::from_value(serde_json::json!({"model": "gpt-5.1", "prompt_cache": "off"}))
+                .unwrap();
+        let auto = ModelSettings::new("gpt-4.1-mini");
+        assert!(auto.inner.prompt_cache.is_auto());
+
+        assert_eq!(
+            off.merge(&auto).unwrap().inner.prompt_cache,
+            PromptCache::Off
+        );
+        assert_eq!(
+            auto.merge(&off).unwrap().inner.prompt_cache,
+            PromptCache::Off
+        );
+        assert!(
+            serde_json::to_value(&auto)
+                .unwrap()
+                .get("prompt_cache")
+                .is_none()
+        );
+    }
+
     /// Lock the canonical API-key secret name for every provider variant.
     /// Three layers depend on this: gateway (`provider_config.rs`),
     /// validator (`required_secret_keys`), and workspace resolution
@@ -3093,6 +3609,7 @@ tool_format = "json_l"
             ModelProvider::AwsBedrock {
                 base_url: String::new(),
                 api_key: None,
+                region: None,
             }
             .api_key_secret(),
             "AWS_ACCESS_KEY_ID"
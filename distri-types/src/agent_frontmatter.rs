@@ -0,0 +1,355 @@
+//! The TOML front matter of agent markdown files.
+//!
+//! ```markdown
+//! ---
+//! name = "support"
+//! model_fallbacks = ["anthropic/claude-sonnet-4-5", "gpt-4.1-mini"]
+//! sub_agents = ["billing"]
+//! guardrails = ["Never promise a refund."]
+//!
+//! [tools]
+//! builtin = ["final", "execute_shell"]
+//!
+//! [tools.config.shell]
+//! timeout_secs = 60
+//!
+//! [response_schema]
+//! file = "schemas/ticket.json"
+//! ---
+//! You are a support agent...
+//! ```
+//!
+//! Errors name the line of the markdown file they are about: TOML syntax
+//! errors by their position, invalid values by the line of their key.
+
+use std::collections::HashSet;
+use std::fmt;
+
+use crate::agent::{StandardDefinition, validate_plugin_name};
+use crate::{AgentError, ModelSettings};
+
+/// Front matter and body of an agent markdown file.
+#[derive(Debug, Clone, Copy)]
+pub struct FrontMatter<'a> {
+    /// The TOML between the first two `---` markers.
+    pub toml: &'a str,
+    /// The markdown after the second marker.
+    pub body: &'a str,
+    /// Lines of the file before `toml` starts.
+    line_offset: usize,
+}
+
+/// A problem with the front matter, at a line of the markdown file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FrontMatterError {
+    pub line: usize,
+    pub column: Option<usize>,
+    pub message: String,
+}
+
+impl fmt::Display for FrontMatterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.column {
+            Some(column) => write!(f, "line {}, column {}: {}", self.line, column, self.message),
+            None => write!(f, "line {}: {}", self.line, self.message),
+        }
+    }
+}
+
+/// One `AgentError::Validation` listing every error, a line each.
+pub fn to_agent_error(errors: &[FrontMatterError]) -> AgentError {
+    AgentError::Validation(
+        errors
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join("\n"),
+    )
+}
+
+impl<'a> FrontMatter<'a> {
+    pub fn split(content: &'a str) -> Result<Self, AgentError> {
+        let invalid = || {
+            AgentError::Validation(
+                "Invalid agent markdown format. Expected TOML frontmatter between --- markers"
+                    .to_string(),
+            )
+        };
+        let start = content.find("---").ok_or_else(invalid)? + 3;
+        let end = start + content[start..].find("---").ok_or_else(invalid)?;
+        Ok(Self {
+            toml: &content[start..end],
+            body: &content[end + 3..],
+            line_offset: content[..start].matches('\n').count(),
+        })
+    }
+
+    /// Parse the front matter into a definition, with syntax errors at
+    /// their line.
+    pub fn parse(&self) -> Result<StandardDefinition, FrontMatterError> {
+        toml::from_str(self.toml).map_err(|e| match e.span() {
+            Some(span) => {
+                let (line, column) = self.position(span.start);
+                FrontMatterError {
+                    line,
+                    column: Some(column),
+                    message: e.message().to_string(),
+                }
+            }
+            None => self.error_at(self.line_of(0), e.message()),
+        })
+    }
+
+    /// Line and column (1-based) in the file of byte `offset` of `toml`.
+    fn position(&self, offset: usize) -> (usize, usize) {
+        let before = &self.toml[..offset.min(self.toml.len())];
+        let line = self.line_offset + before.matches('\n').count() + 1;
+        let column = before
+            .rsplit('\n')
+            .next()
+            .unwrap_or_default()
+            .chars()
+            .count()
+            + 1;
+        (line, column)
+    }
+
+    fn line_of(&self, toml_line: usize) -> usize {
+        self.line_offset + toml_line + 1
+    }
+
+    fn error_at(&self, line: usize, message: impl Into<String>) -> FrontMatterError {
+        FrontMatterError {
+            line,
+            column: None,
+            message: message.into(),
+        }
+    }
+
+    /// The file line of the key at `path`, such as `["tools", "config",
+    /// "shell"]`: its `key = ...` line or `[table]` header. Falls back to
+    /// the closest enclosing key, then to the opening marker.
+    pub fn key_line(&self, path: &[&str]) -> usize {
+        let mut best: Option<(usize, usize)> = None;
+        let mut table: Vec<String> = Vec::new();
+        for (index, raw) in self.toml.lines().enumerate() {
+            let line = raw.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let full = if line.starts_with('[') {
+                let header = line.trim_start_matches('[');
+                table = split_key(header.split(']').next().unwrap_or_default());
+                table.clone()
+            } else if let Some((key, _)) = line.split_once('=') {
+                let mut full = table.clone();
+                full.extend(split_key(key));
+                full
+            } else {
+                continue;
+            };
+            let matched = full
+                .iter()
+                .zip(path)
+                .take_while(|(a, b)| a.as_str() == **b)
+                .count();
+            if matched == path.len() {
+                return self.line_of(index);
+            }
+            if matched == full.len() && best.is_none_or(|(_, len)| matched > len) {
+                best = Some((index, matched));
+            }
+        }
+        best.map_or(self.line_of(0), |(index, _)| self.line_of(index))
+    }
+
+    /// The file line of the string `value` in the array at `path`, which
+    /// may span several lines.
+    fn item_line(&self, path: &[&str], value: &str) -> usize {
+        let key_line = self.key_line(path);
+        let quoted = [format!("\"{}\"", value), format!("'{}'", value)];
+        self.toml
+            .lines()
+            .enumerate()
+            .skip(key_line.saturating_sub(self.line_offset + 1))
+            .take_while(|(index, line)| {
+                self.line_of(*index) == key_line || !line.contains('=') && !line.starts_with('[')
+            })
+            .find(|(_, line)| quoted.iter().any(|q| line.contains(q.as_str())))
+            .map_or(key_line, |(index, _)| self.line_of(index))
+    }
+
+    /// Everything wrong with `definition` besides its syntax.
+    pub fn validate(&self, definition: &StandardDefinition) -> Vec<FrontMatterError> {
+        let mut errors = Vec::new();
+        let mut error = |line: usize, message: String| errors.push(self.error_at(line, message));
+
+        if let Err(e) = validate_plugin_name(&definition.name) {
+            error(
+                self.key_line(&["name"]),
+                format!("Invalid agent name '{}': {}", definition.name, e),
+            );
+        } else if !valid_agent_name(&definition.name) {
+            error(
+                self.key_line(&["name"]),
+                format!(
+                    "Invalid agent name '{}': Agent names must be alphanumeric with underscores, at most one '/' for namespacing (e.g. '_system/plan'), cannot start with number.",
+                    definition.name
+                ),
+            );
+        }
+
+        if let Some(model) = definition.model_settings.as_ref().map(|ms| &ms.model)
+            && model.contains('/')
+        {
+            match ModelSettings::from_provider_model_str(model) {
+                Err(e) => error(self.key_line(&["model_settings", "model"]), e),
+                Ok(None) => error(
+                    self.key_line(&["model_settings", "model"]),
+                    format!(
+                        "agent '{}': invalid model_settings.model '{}' — empty model name after the provider prefix",
+                        definition.name, model
+                    ),
+                ),
+                Ok(Some(_)) => {}
+            }
+        }
+
+        let mut seen = HashSet::new();
+        for fallback in &definition.model_fallbacks {
+            let line = self.item_line(&["model_fallbacks"], fallback);
+            if !seen.insert(fallback.as_str()) {
+                error(line, format!("model_fallbacks lists '{}' twice", fallback));
+                continue;
+            }
+            if fallback.trim().is_empty() {
+                error(line, "model_fallbacks entries cannot be empty".to_string());
+                continue;
+            }
+            match ModelSettings::from_provider_model_str(fallback) {
+                Err(e) => error(line, format!("model_fallbacks: {}", e)),
+                Ok(None) if fallback.contains('/') => error(
+                    line,
+                    format!(
+                        "model_fallbacks: '{}' has no model name after the provider prefix",
+                        fallback
+                    ),
+                ),
+                _ => {}
+            }
+        }
+
+        let mut seen = HashSet::new();
+        for sub_agent in &definition.sub_agents {
+            let line = self.item_line(&["sub_agents"], sub_agent);
+            if let Err(e) = validate_plugin_name(sub_agent) {
+                error(line, format!("sub_agents: {}", e));
+            } else if *sub_agent == definition.name {
+                error(line, format!("sub_agents: '{}' is this agent", sub_agent));
+            } else if !seen.insert(sub_agent.as_str()) {
+                error(line, format!("sub_agents lists '{}' twice", sub_agent));
+            }
+        }
+
+        let mut seen = HashSet::new();
+        for skill in &definition.available_skills {
+            let line = self.item_line(&["available_skills"], &skill.id);
+            if skill.id.trim().is_empty() {
+                error(
+                    line,
+                    format!("available_skills: '{}' has no id", skill.name),
+                );
+            } else if !seen.insert(skill.id.as_str()) {
+                error(line, format!("available_skills lists '{}' twice", skill.id));
+            }
+        }
+
+        if let Some(tools) = &definition.tools {
+            let shell = definition.include_shell == Some(true)
+                || tools.builtin.iter().any(|tool| tool.ends_with("_shell"));
+            for (tool, config) in &tools.config {
+                let line = self.key_line(&["tools", "config", tool]);
+                if !config.is_object() {
+                    error(line, format!("tools.config.{} must be a table", tool));
+                } else if !(tools.allows(tool) || tool == "shell" && shell) {
+                    error(
+                        line,
+                        format!(
+                            "tools.config.{}: '{}' is not one of the agent's tools",
+                            tool, tool
+                        ),
+                    );
+                }
+            }
+        }
+
+        if let Some(response_schema) = &definition.response_schema {
+            let line = self.key_line(&["response_schema"]);
+            match (&response_schema.schema, &response_schema.file) {
+                (None, None) => error(
+                    line,
+                    "response_schema needs a `schema` or a `file`".to_string(),
+                ),
+                (Some(_), Some(_)) => error(
+                    line,
+                    "response_schema takes a `schema` or a `file`, not both".to_string(),
+                ),
+                (Some(schema), None) if !schema.is_object() => error(
+                    self.key_line(&["response_schema", "schema"]),
+                    "response_schema.schema must be a table".to_string(),
+                ),
+                _ => {}
+            }
+            if let Some(name) = &response_schema.name
+                && (name.is_empty()
+                    || !name
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-'))
+            {
+                error(
+                    self.key_line(&["response_schema", "name"]),
+                    format!(
+                        "response_schema.name '{}' may only contain letters, digits, '_' and '-'",
+                        name
+                    ),
+                );
+            }
+        }
+
+        for (index, rule) in definition.guardrails.iter().enumerate() {
+            if rule.trim().is_empty() {
+                error(
+                    self.key_line(&["guardrails"]),
+                    format!("guardrails[{}] is empty", index),
+                );
+            }
+        }
+
+        errors
+    }
+}
+
+/// Alphanumeric with underscores, at most one `/`, not starting with a digit.
+fn valid_agent_name(name: &str) -> bool {
+    name.chars()
+        .all(|c| c.is_alphanumeric() || c == '_' || c == '/')
+        && !name.chars().next().is_some_and(|c| c.is_numeric())
+        && name.chars().filter(|&c| c == '/').count() <= 1
+}
+
+/// `a.b."c.d"` → `["a", "b", "c.d"]`.
+fn split_key(key: &str) -> Vec<String> {
+    let mut parts = Vec::new();
+    let mut current = String::new();
+    let mut quote = None;
+    for c in key.chars() {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(q), c) if c == q => quote = None,
+            (None, '.') => parts.push(std::mem::take(&mut current).trim().to_string()),
+            _ => current.push(c),
+        }
+    }
+    parts.push(current.trim().to_string());
+    parts
+}
@@ -0,0 +1,202 @@
+//! Publishing agent cards to an external A2A registry, and negotiating the
+//! card a remote client is served.
+//!
+//! With an `agent_registry` configured the server pushes the card of every
+//! registered agent to `{url}/agents/{name}` (`PUT`, body
+//! [`CardPublication`]), republishes all of them every `refresh_secs` so the
+//! registry can expire cards of servers that went away, and retracts the card
+//! of a deleted agent with `DELETE`. When a `signing_key` is set, each
+//! publication carries an HMAC-SHA256 over [`CardMetadata::signing_input`].
+//!
+//! Clients that fetch a card can state what they support with
+//! [`CapabilityQuery`]; [`negotiate_card`] narrows the card to that.
+
+use std::collections::HashSet;
+
+use distri_a2a::AgentCard;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// Signature scheme written to [`CardMetadata::algorithm`].
+pub const SIGNATURE_ALGORITHM: &str = "hmac-sha256";
+
+/// Header a client lists the A2A extension URIs it supports in, and the
+/// server lists the extensions active on the served card in.
+pub const EXTENSIONS_HEADER: &str = "X-A2A-Extensions";
+
+/// `agent_registry` section of the server config.
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct AgentRegistryConfig {
+    /// Base URL of the registry.
+    pub url: String,
+    /// Public base URL of this server's API (e.g. `https://agents.example.com/v1`),
+    /// used for the `url` of published cards.
+    pub public_url: String,
+    /// Identifies this server to the registry.
+    #[serde(default = "default_publisher")]
+    pub publisher: String,
+    /// Bearer token sent to the registry.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
+    /// Shared secret publications are signed with. Unsigned when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signing_key: Option<String>,
+    /// Interval at which every card is republished.
+    #[serde(default = "default_refresh_secs")]
+    pub refresh_secs: u64,
+}
+
+fn default_publisher() -> String {
+    "distri".to_string()
+}
+
+fn default_refresh_secs() -> u64 {
+    300
+}
+
+/// Body of a publish request.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CardPublication {
+    pub card: AgentCard,
+    pub metadata: CardMetadata,
+}
+
+/// Provenance of a published card.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CardMetadata {
+    pub publisher: String,
+    pub published_at: chrono::DateTime<chrono::Utc>,
+    /// SHA-256 of the card's JSON, hex encoded. See [`card_hash`].
+    pub content_hash: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub algorithm: Option<String>,
+}
+
+impl CardMetadata {
+    /// The bytes the signature covers.
+    pub fn signing_input(&self) -> String {
+        format!(
+            "{}\n{}\n{}",
+            self.publisher,
+            self.published_at.to_rfc3339(),
+            self.content_hash
+        )
+    }
+}
+
+/// SHA-256 of `card`, hex encoded. Object keys are sorted, so the hash does
+/// not depend on map iteration order.
+pub fn card_hash(card: &AgentCard) -> String {
+    let value = serde_json::to_value(card).unwrap_or_default();
+    Sha256::digest(value.to_string().as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// What a client fetching a card supports. Unset fields accept anything.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct CapabilityQuery {
+    /// A2A extension URIs the client understands.
+    #[serde(default, deserialize_with = "comma_separated")]
+    pub extensions: Option<Vec<String>>,
+    /// Output modes (MIME types) the client can consume.
+    #[serde(default, deserialize_with = "comma_separated")]
+    pub output_modes: Option<Vec<String>>,
+    /// Whether the client can consume streamed responses.
+    #[serde(default)]
+    pub streaming: Option<bool>,
+}
+
+fn comma_separated<'de, D>(deserializer: D) -> Result<Option<Vec<String>>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let raw: Option<String> = Option::deserialize(deserializer)?;
+    Ok(raw.map(|s| {
+        s.split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(str::to_string)
+            .collect()
+    }))
+}
+
+/// Why a card cannot be served to a client.
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum NegotiationError {
+    #[error("agent requires extensions the client does not support: {}", .0.join(", "))]
+    MissingRequiredExtensions(Vec<String>),
+    #[error("agent produces none of the accepted output modes: {}", .0.join(", "))]
+    NoCommonOutputMode(Vec<String>),
+}
+
+/// Narrow `card` to what `query` says the client supports:
+///
+/// - only extensions the client listed stay active; a required extension it
+///   did not list fails negotiation;
+/// - output modes are cut to the accepted ones, and skills left with no
+///   output mode are dropped;
+/// - a client that cannot stream is served a card without streaming.
+pub fn negotiate_card(
+    mut card: AgentCard,
+    query: &CapabilityQuery,
+) -> Result<AgentCard, NegotiationError> {
+    if let Some(supported) = &query.extensions {
+        let supported: HashSet<&str> = supported.iter().map(String::as_str).collect();
+        let missing: Vec<String> = card
+            .capabilities
+            .extensions
+            .iter()
+            .filter(|e| e.required && !supported.contains(e.uri.as_str()))
+            .map(|e| e.uri.clone())
+            .collect();
+        if !missing.is_empty() {
+            return Err(NegotiationError::MissingRequiredExtensions(missing));
+        }
+        card.capabilities
+            .extensions
+            .retain(|e| supported.contains(e.uri.as_str()));
+    }
+
+    if let Some(accepted) = &query.output_modes {
+        let accepts = |mode: &String| {
+            accepted
+                .iter()
+                .any(|a| a == "*/*" || a.eq_ignore_ascii_case(mode))
+        };
+        // An empty list means the card does not restrict its output modes.
+        if !card.default_output_modes.is_empty() {
+            card.default_output_modes.retain(|m| accepts(m));
+            if card.default_output_modes.is_empty() {
+                return Err(NegotiationError::NoCommonOutputMode(accepted.clone()));
+            }
+        }
+        card.skills
+            .retain_mut(|skill| match &mut skill.output_modes {
+                Some(modes) => {
+                    modes.retain(|m| accepts(m));
+                    !modes.is_empty()
+                }
+                None => true,
+            });
+    }
+
+    if query.streaming == Some(false) {
+        card.capabilities.streaming = false;
+    }
+    Ok(card)
+}
+
+/// URIs of the extensions active on `card`, for the [`EXTENSIONS_HEADER`]
+/// response header.
+pub fn active_extensions(card: &AgentCard) -> String {
+    card.capabilities
+        .extensions
+        .iter()
+        .map(|e| e.uri.as_str())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
@@ -0,0 +1,358 @@
+//! Wire types of the analytics API, and how stored events feed it.
+//!
+//! Runs and tool calls are counted per UTC day and agent as their events
+//! are stored (see [`AnalyticsDelta::from_event`]), so the endpoints read a
+//! handful of pre-aggregated rows per day rather than the event history:
+//!
+//! ```text
+//! GET /v1/analytics/runs?from=2026-10-01&to=2026-10-17&agent_id=X → RunAnalytics { range, totals, days }
+//! GET /v1/analytics/agents?from=…&to=…                             → AgentAnalytics { range, agents }
+//! GET /v1/analytics/tools?from=…&to=…&agent_id=X&limit=10          → ToolAnalytics { range, tools }
+//! ```
+//!
+//! Ranges are inclusive UTC days; the last 30 days when unset.
+
+use std::collections::{BTreeMap, HashMap};
+
+use chrono::{Duration, NaiveDate};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::{AgentEvent, AgentEventType};
+
+/// Days covered when a query sets no `from`.
+pub const DEFAULT_RANGE_DAYS: i64 = 30;
+/// Longest range a query may cover.
+pub const MAX_RANGE_DAYS: i64 = 366;
+/// Tools listed when a query sets no `limit`.
+pub const DEFAULT_TOOL_LIMIT: usize = 10;
+
+/// Query parameters of the analytics endpoints.
+#[derive(Debug, Clone, Default, Deserialize, ToSchema, JsonSchema)]
+pub struct AnalyticsQuery {
+    /// First day, `YYYY-MM-DD`. Default: 30 days before `to`.
+    #[schemars(with = "Option<String>")]
+    pub from: Option<NaiveDate>,
+    /// Last day, `YYYY-MM-DD`. Default: today.
+    #[schemars(with = "Option<String>")]
+    pub to: Option<NaiveDate>,
+    /// Only count this agent's runs.
+    pub agent_id: Option<String>,
+    /// Most tools listed by `/analytics/tools`. Default: 10.
+    pub limit: Option<usize>,
+}
+
+impl AnalyticsQuery {
+    /// The days the query covers, with `today` as the default end.
+    pub fn range(&self, today: NaiveDate) -> Result<DateRange, String> {
+        let to = self.to.unwrap_or(today);
+        let from = self
+            .from
+            .unwrap_or_else(|| to - Duration::days(DEFAULT_RANGE_DAYS - 1));
+        if from > to {
+            return Err(format!("from ({}) is after to ({})", from, to));
+        }
+        if (to - from).num_days() >= MAX_RANGE_DAYS {
+            return Err(format!("ranges are limited to {} days", MAX_RANGE_DAYS));
+        }
+        Ok(DateRange { from, to })
+    }
+}
+
+/// Inclusive range of UTC days.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema, JsonSchema)]
+pub struct DateRange {
+    #[schemars(with = "String")]
+    pub from: NaiveDate,
+    #[schemars(with = "String")]
+    pub to: NaiveDate,
+}
+
+impl DateRange {
+    pub fn contains(&self, day: NaiveDate) -> bool {
+        self.from <= day && day <= self.to
+    }
+
+    pub fn days(&self) -> impl Iterator<Item = NaiveDate> {
+        let to = self.to;
+        self.from.iter_days().take_while(move |day| *day <= to)
+    }
+}
+
+/// Additive run counters.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, ToSchema, JsonSchema)]
+pub struct RunCounters {
+    pub runs: i64,
+    pub succeeded: i64,
+    pub failed: i64,
+    /// Steps of the runs that finished.
+    pub steps: i64,
+    pub input_tokens: i64,
+    pub output_tokens: i64,
+    /// Estimated cost, from model pricing.
+    pub cost_usd: f64,
+}
+
+impl RunCounters {
+    pub fn add(&mut self, other: &RunCounters) {
+        self.runs += other.runs;
+        self.succeeded += other.succeeded;
+        self.failed += other.failed;
+        self.steps += other.steps;
+        self.input_tokens += other.input_tokens;
+        self.output_tokens += other.output_tokens;
+        self.cost_usd += other.cost_usd;
+    }
+}
+
+/// Run counters and the rates derived from them.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, ToSchema, JsonSchema)]
+pub struct RunStats {
+    #[serde(flatten)]
+    pub counters: RunCounters,
+    /// Share of runs that succeeded; `None` without runs.
+    pub success_rate: Option<f64>,
+    pub avg_steps_per_run: Option<f64>,
+}
+
+impl From<RunCounters> for RunStats {
+    fn from(counters: RunCounters) -> Self {
+        let runs = counters.runs as f64;
+        let (success_rate, avg_steps_per_run) = if counters.runs > 0 {
+            (
+                Some(counters.succeeded as f64 / runs),
+                Some(counters.steps as f64 / runs),
+            )
+        } else {
+            (None, None)
+        };
+        Self {
+            counters,
+            success_rate,
+            avg_steps_per_run,
+        }
+    }
+}
+
+/// Run counters of one agent on one day, as stored.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DailyAgentRuns {
+    pub day: NaiveDate,
+    pub agent_id: String,
+    pub counters: RunCounters,
+}
+
+/// Calls of one tool by one agent on one day, as stored.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DailyAgentTools {
+    pub day: NaiveDate,
+    pub agent_id: String,
+    pub tool_name: String,
+    pub calls: i64,
+    pub failures: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema, JsonSchema)]
+pub struct DailyRunStats {
+    #[schemars(with = "String")]
+    pub day: NaiveDate,
+    #[serde(flatten)]
+    pub stats: RunStats,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema, JsonSchema)]
+pub struct AgentRunStats {
+    pub agent_id: String,
+    #[serde(flatten)]
+    pub stats: RunStats,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema, JsonSchema)]
+pub struct ToolUsageStats {
+    pub tool_name: String,
+    pub calls: i64,
+    pub failures: i64,
+    /// Share of calls that failed; `None` without calls.
+    pub failure_rate: Option<f64>,
+}
+
+/// Response body of `GET /v1/analytics/runs`: totals over the range and
+/// one entry per day, days without runs included.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema, JsonSchema)]
+pub struct RunAnalytics {
+    pub range: DateRange,
+    pub totals: RunStats,
+    pub days: Vec<DailyRunStats>,
+}
+
+impl RunAnalytics {
+    pub fn from_rows(range: DateRange, rows: &[DailyAgentRuns]) -> Self {
+        let mut totals = RunCounters::default();
+        let mut by_day: BTreeMap<NaiveDate, RunCounters> = range
+            .days()
+            .map(|day| (day, RunCounters::default()))
+            .collect();
+        for row in rows.iter().filter(|row| range.contains(row.day)) {
+            totals.add(&row.counters);
+            by_day.entry(row.day).or_default().add(&row.counters);
+        }
+        Self {
+            range,
+            totals: totals.into(),
+            days: by_day
+                .into_iter()
+                .map(|(day, counters)| DailyRunStats {
+                    day,
+                    stats: counters.into(),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Response body of `GET /v1/analytics/agents`: agents with runs in the
+/// range, most runs first.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema, JsonSchema)]
+pub struct AgentAnalytics {
+    pub range: DateRange,
+    pub agents: Vec<AgentRunStats>,
+}
+
+impl AgentAnalytics {
+    pub fn from_rows(range: DateRange, rows: &[DailyAgentRuns]) -> Self {
+        let mut by_agent: HashMap<&str, RunCounters> = HashMap::new();
+        for row in rows.iter().filter(|row| range.contains(row.day)) {
+            by_agent
+                .entry(row.agent_id.as_str())
+                .or_default()
+                .add(&row.counters);
+        }
+        let mut agents: Vec<AgentRunStats> = by_agent
+            .into_iter()
+            .map(|(agent_id, counters)| AgentRunStats {
+                agent_id: agent_id.to_string(),
+                stats: counters.into(),
+            })
+            .collect();
+        agents.sort_by(|a, b| {
+            b.stats
+                .counters
+                .runs
+                .cmp(&a.stats.counters.runs)
+                .then_with(|| a.agent_id.cmp(&b.agent_id))
+        });
+        Self { range, agents }
+    }
+}
+
+/// Response body of `GET /v1/analytics/tools`: the most called tools in the
+/// range.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema, JsonSchema)]
+pub struct ToolAnalytics {
+    pub range: DateRange,
+    pub tools: Vec<ToolUsageStats>,
+}
+
+impl ToolAnalytics {
+    pub fn from_rows(range: DateRange, rows: &[DailyAgentTools], limit: usize) -> Self {
+        let mut by_tool: HashMap<&str, (i64, i64)> = HashMap::new();
+        for row in rows.iter().filter(|row| range.contains(row.day)) {
+            let (calls, failures) = by_tool.entry(row.tool_name.as_str()).or_default();
+            *calls += row.calls;
+            *failures += row.failures;
+        }
+        let mut tools: Vec<ToolUsageStats> = by_tool
+            .into_iter()
+            .map(|(tool_name, (calls, failures))| ToolUsageStats {
+                tool_name: tool_name.to_string(),
+                calls,
+                failures,
+                failure_rate: (calls > 0).then(|| failures as f64 / calls as f64),
+            })
+            .collect();
+        tools.sort_by(|a, b| {
+            b.calls
+                .cmp(&a.calls)
+                .then_with(|| a.tool_name.cmp(&b.tool_name))
+        });
+        tools.truncate(limit);
+        Self { range, tools }
+    }
+}
+
+/// What storing an event adds to the analytics rows.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AnalyticsDelta {
+    Run(DailyAgentRuns),
+    Tool(DailyAgentTools),
+}
+
+impl AnalyticsDelta {
+    /// The delta of `event`, stored under task `stored_under`. `None` for
+    /// events that are not counted, and for events relayed from a sub-agent
+    /// (stored under the parent too), which count under their own task.
+    pub fn from_event(stored_under: &str, event: &AgentEvent) -> Option<Self> {
+        if event.task_id != stored_under {
+            return None;
+        }
+        let day = event.timestamp.date_naive();
+        let agent_id = event.agent_id.clone();
+        let delta = match &event.event {
+            AgentEventType::RunFinished {
+                success,
+                total_steps,
+                usage,
+                ..
+            } => {
+                let mut counters = RunCounters {
+                    runs: 1,
+                    succeeded: i64::from(*success),
+                    failed: i64::from(!*success),
+                    steps: *total_steps as i64,
+                    ..Default::default()
+                };
+                if let Some(usage) = usage {
+                    counters.input_tokens = usage.input_tokens as i64;
+                    counters.output_tokens = usage.output_tokens as i64;
+                    counters.cost_usd = usage.cost_usd.unwrap_or(0.0);
+                }
+                AnalyticsDelta::Run(DailyAgentRuns {
+                    day,
+                    agent_id,
+                    counters,
+                })
+            }
+            AgentEventType::RunError { usage, .. } => {
+                let mut counters = RunCounters {
+                    runs: 1,
+                    failed: 1,
+                    ..Default::default()
+                };
+                if let Some(usage) = usage {
+                    counters.input_tokens = usage.input_tokens as i64;
+                    counters.output_tokens = usage.output_tokens as i64;
+                    counters.cost_usd = usage.cost_usd.unwrap_or(0.0);
+                }
+                AnalyticsDelta::Run(DailyAgentRuns {
+                    day,
+                    agent_id,
+                    counters,
+                })
+            }
+            AgentEventType::ToolExecutionEnd {
+                tool_call_name,
+                success,
+                ..
+            } => AnalyticsDelta::Tool(DailyAgentTools {
+                day,
+                agent_id,
+                tool_name: tool_call_name.clone(),
+                calls: 1,
+                failures: i64::from(!*success),
+            }),
+            _ => return None,
+        };
+        Some(delta)
+    }
+}
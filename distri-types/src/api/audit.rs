@@ -0,0 +1,291 @@
+//! Shared LLM audit-log DTOs used by distri-server and distri-cloud.
+//!
+//! Every outbound LLM call can be recorded as an [`LlmAuditRecord`] —
+//! the exact messages sent to the provider and what came back — so
+//! compliance can answer "what did we send to OpenAI for this user last
+//! Tuesday?". Payloads are run through [`redact_pii`] before they are
+//! stored when [`LlmAuditConfig::redact`] is set (the default).
+
+use chrono::{DateTime, Utc};
+use regex::Regex;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Placeholder written in place of every redacted span.
+pub const REDACTED_MARKER: &str = "[REDACTED]";
+
+/// Audit-log behaviour. Off unless explicitly enabled.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, JsonSchema)]
+pub struct LlmAuditConfig {
+    /// Record outbound LLM requests/responses.
+    #[serde(default)]
+    pub enabled: bool,
+    /// Scrub emails, phone numbers, card numbers and credentials from the
+    /// stored payloads. Defaults to `true`.
+    #[serde(default = "default_redact")]
+    pub redact: bool,
+    /// Drop records older than this many days. `None` keeps everything.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retention_days: Option<u32>,
+}
+
+fn default_redact() -> bool {
+    true
+}
+
+impl Default for LlmAuditConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            redact: default_redact(),
+            retention_days: None,
+        }
+    }
+}
+
+impl LlmAuditConfig {
+    /// Oldest `created_at` a record may have under the retention policy.
+    pub fn retention_cutoff(&self, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        self.retention_days
+            .map(|days| now - chrono::Duration::days(days as i64))
+    }
+}
+
+/// One recorded LLM exchange.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, JsonSchema)]
+pub struct LlmAuditRecord {
+    pub id: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub agent_id: String,
+    pub user_id: String,
+    pub thread_id: String,
+    pub task_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub workspace_id: Option<String>,
+    /// Provider id (`openai`, `anthropic`, …).
+    pub provider: String,
+    pub model: String,
+    /// `true` for `execute_stream`, `false` for a one-shot completion.
+    pub streaming: bool,
+    /// Messages sent to the provider.
+    #[schema(value_type = Object)]
+    pub request: serde_json::Value,
+    /// Assistant content and tool calls returned. `Null` on failure.
+    #[schema(value_type = Object)]
+    pub response: serde_json::Value,
+    /// Provider / transport error, if the call failed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(default)]
+    pub input_tokens: u32,
+    #[serde(default)]
+    pub output_tokens: u32,
+    pub duration_ms: u64,
+    /// Whether `request`/`response` went through [`redact_pii`].
+    pub redacted: bool,
+}
+
+/// Filters for listing / exporting audit records. All optional.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema, JsonSchema)]
+pub struct LlmAuditQuery {
+    pub agent_id: Option<String>,
+    pub user_id: Option<String>,
+    /// Only records created at or after this instant.
+    pub from: Option<DateTime<Utc>>,
+    /// Only records created before this instant.
+    pub to: Option<DateTime<Utc>>,
+    /// Maximum records returned (newest first). Unbounded when absent.
+    pub limit: Option<usize>,
+}
+
+impl LlmAuditQuery {
+    /// Whether `record` passes every filter except `limit`.
+    pub fn matches(&self, record: &LlmAuditRecord) -> bool {
+        self.agent_id
+            .as_deref()
+            .is_none_or(|a| record.agent_id == a)
+            && self.user_id.as_deref().is_none_or(|u| record.user_id == u)
+            && self.from.is_none_or(|from| record.created_at >= from)
+            && self.to.is_none_or(|to| record.created_at < to)
+    }
+}
+
+/// Response wrapper for listing audit records.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, JsonSchema)]
+pub struct ListLlmAuditResponse {
+    pub records: Vec<LlmAuditRecord>,
+}
+
+/// Serialize records as JSON Lines — one record per line, trailing newline.
+pub fn to_jsonl(records: &[LlmAuditRecord]) -> serde_json::Result<String> {
+    let mut out = String::new();
+    for record in records {
+        out.push_str(&serde_json::to_string(record)?);
+        out.push('\n');
+    }
+    Ok(out)
+}
+
+fn pii_patterns() -> &'static [Regex] {
+    static PATTERNS: OnceLock<Vec<Regex>> = OnceLock::new();
+    PATTERNS.get_or_init(|| {
+        [
+            // Bearer / API tokens: `Bearer xyz`, `sk-...`, `ghp_...`, AWS keys.
+            r"(?i)bearer\s+[a-z0-9._\-]{8,}",
+            r"\b(?:sk|pk|rk)-[A-Za-z0-9_\-]{16,}",
+            r"\bgh[pousr]_[A-Za-z0-9]{20,}",
+            r"\bAKIA[0-9A-Z]{16}\b",
+            // Email addresses.
+            r"[A-Za-z0-9._%+\-]+@[A-Za-z0-9.\-]+\.[A-Za-z]{2,}",
+            // Card numbers: 13–19 digits, optionally grouped.
+            r"\b(?:\d[ \-]?){12,18}\d\b",
+            // Phone numbers: optional country code, 9+ digits with separators.
+            r"\+?\d{1,3}[ \-.]?\(?\d{2,4}\)?[ \-.]?\d{3,4}[ \-.]?\d{3,4}\b",
+        ]
+        .iter()
+        .map(|p| Regex::new(p).expect("static PII pattern must compile"))
+        .collect()
+    })
+}
+
+/// Replace credentials and personal identifiers in `text` with
+/// [`REDACTED_MARKER`].
+pub fn redact_text(text: &str) -> String {
+    let mut out = text.to_string();
+    for pattern in pii_patterns() {
+        if pattern.is_match(&out) {
+            out = pattern.replace_all(&out, REDACTED_MARKER).into_owned();
+        }
+    }
+    out
+}
+
+/// Recursively redact every string inside a JSON value. Keys are kept.
+pub fn redact_pii(value: &serde_json::Value) -> serde_json::Value {
+    use serde_json::Value;
+    match value {
+        Value::String(s) => Value::String(redact_text(s)),
+        Value::Array(items) => Value::Array(items.iter().map(redact_pii).collect()),
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(k, v)| (k.clone(), redact_pii(v)))
+                .collect(),
+        ),
+        other => other.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn record(agent: &str, user: &str, at: DateTime<Utc>) -> LlmAuditRecord {
+        LlmAuditRecord {
+            id: Uuid::new_v4(),
+            created_at: at,
+            agent_id: agent.into(),
+            user_id: user.into(),
+            thread_id: "t".into(),
+            task_id: "k".into(),
+            workspace_id: None,
+            provider: "openai".into(),
+            model: "gpt-4.1-mini".into(),
+            streaming: false,
+            request: json!([]),
+            response: json!(null),
+            error: None,
+            input_tokens: 0,
+            output_tokens: 0,
+            duration_ms: 0,
+            redacted: false,
+        }
+    }
+
+    #[test]
+    fn redact_scrubs_credentials_and_contact_details() {
+        let input = json!({
+            "messages": [
+                {"role": "user", "content": "mail me at jane.doe@example.com or call +1 415-555-0132"},
+                {"role": "user", "content": "key sk-abcdefghijklmnopqrstuv, card 4111 1111 1111 1111"},
+                {"role": "user", "content": "Authorization: Bearer abc.def.ghi123"}
+            ],
+            "count": 3
+        });
+        let out = redact_pii(&input).to_string();
+        for leaked in [
+            "jane.doe@example.com",
+            "415-555-0132",
+            "sk-abcdefghijklmnopqrstuv",
+            "4111 1111 1111 1111",
+            "abc.def.ghi123",
+        ] {
+            assert!(
+                !out.contains(leaked),
+                "`{leaked}` survived redaction: {out}"
+            );
+        }
+        assert!(out.contains(REDACTED_MARKER));
+        assert!(
+            out.contains("\"count\":3"),
+            "non-string values are untouched"
+        );
+    }
+
+    #[test]
+    fn redact_leaves_ordinary_text_alone() {
+        let text = "Summarize the Q3 report in 5 bullet points.";
+        assert_eq!(redact_text(text), text);
+    }
+
+    #[test]
+    fn query_filters_by_agent_user_and_window() {
+        let now = Utc::now();
+        let r = record("research", "u1", now);
+        assert!(LlmAuditQuery::default().matches(&r));
+        assert!(
+            !LlmAuditQuery {
+                agent_id: Some("writer".into()),
+                ..Default::default()
+            }
+            .matches(&r)
+        );
+        assert!(
+            !LlmAuditQuery {
+                user_id: Some("u2".into()),
+                ..Default::default()
+            }
+            .matches(&r)
+        );
+        assert!(
+            LlmAuditQuery {
+                from: Some(now - chrono::Duration::hours(1)),
+                to: Some(now + chrono::Duration::hours(1)),
+                ..Default::default()
+            }
+            .matches(&r)
+        );
+        assert!(
+            !LlmAuditQuery {
+                to: Some(now),
+                ..Default::default()
+            }
+            .matches(&r)
+        );
+    }
+
+    #[test]
+    fn jsonl_export_is_one_record_per_line() {
+        let now = Utc::now();
+        let out = to_jsonl(&[record("a", "u", now), record("b", "u", now)]).unwrap();
+        let lines: Vec<_> = out.lines().collect();
+        assert_eq!(lines.len(), 2);
+        for line in lines {
+            let back: LlmAuditRecord = serde_json::from_str(line).unwrap();
+            assert_eq!(back.user_id, "u");
+        }
+    }
+}
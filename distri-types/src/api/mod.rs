@@ -1,4 +1,8 @@
+pub mod analytics;
+pub mod audit;
 pub mod connections;
 pub mod notes;
+pub mod preview;
+pub mod share;
 pub mod spans;
 pub mod usage;
@@ -0,0 +1,73 @@
+//! Prompt preview DTOs — `POST /agents/{id}/preview`.
+//!
+//! A preview assembles the exact prompt an agent would send for a message
+//! (rendered system prompt, packed thread history, tool schemas) without
+//! calling the model. Tokens are counted with the model's tokenizer, as the
+//! runtime context budget counts them; for model families without a public
+//! tokenizer the counts are estimates, and `exact` is false.
+//! The system prompt is also returned broken into its composition layers.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::{Message, ToolCallFormat, ToolDefinition, prompt::PromptLayer};
+
+/// Request body for a prompt preview.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, JsonSchema)]
+#[schema(example = json!({"message": "Summarize yesterday's tickets", "thread_id": "b6f1..."}))]
+pub struct AgentPromptPreviewRequest {
+    /// The hypothetical user message.
+    pub message: String,
+    /// Pack history from this thread. Omit to preview a fresh conversation.
+    #[serde(default)]
+    pub thread_id: Option<String>,
+}
+
+/// Tokens per prompt section.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema, JsonSchema)]
+pub struct PromptSectionTokens {
+    /// Agent instructions (the cacheable part of the system prompt).
+    pub system_static: usize,
+    /// Everything else the template rendered into the system prompt.
+    pub system_dynamic: usize,
+    /// Schemas of tools sent with the request.
+    pub tool_schemas: usize,
+    /// Name/description listing of deferred tools.
+    pub deferred_tools: usize,
+    /// Available-skills listing.
+    pub skills: usize,
+    /// Packed thread history, excluding the previewed message.
+    pub history: usize,
+    /// The previewed user message, after template additions.
+    pub user_message: usize,
+    /// Tool results carried in history.
+    pub tool_results: usize,
+    pub total: usize,
+    /// Context window of the agent's model, when known.
+    pub context_window: usize,
+    /// Whether the counts come from the model's own tokenizer rather than
+    /// an estimate.
+    pub exact: bool,
+}
+
+/// The assembled prompt and its token breakdown.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, JsonSchema)]
+pub struct AgentPromptPreviewResponse {
+    pub agent_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub thread_id: Option<String>,
+    #[schema(value_type = String)]
+    pub tool_format: ToolCallFormat,
+    /// Messages in send order; the first is the rendered system prompt.
+    pub messages: Vec<Message>,
+    /// Estimated tokens of each entry in `messages`.
+    pub message_tokens: Vec<usize>,
+    /// Tool schemas sent alongside the messages. Empty for formats that
+    /// describe tools inside the system prompt instead.
+    pub tools: Vec<ToolDefinition>,
+    pub tokens: PromptSectionTokens,
+    /// The layers the system prompt was composed from, in render order.
+    #[serde(default)]
+    pub layers: Vec<PromptLayer>,
+}
@@ -0,0 +1,99 @@
+//! Read-only observer shares — `POST /threads/{id}/share` and
+//! `GET /threads/{id}/watch`.
+//!
+//! A share token lets a teammate follow a thread's live runs without being
+//! able to send input. Only a SHA-256 of each token is stored, in the thread
+//! metadata under [`crate::THREAD_OBSERVERS_KEY`]; the token itself is
+//! returned once, when it is minted.
+
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use utoipa::ToSchema;
+
+/// Default lifetime of a share token.
+pub const DEFAULT_SHARE_TTL_SECS: u64 = 24 * 60 * 60;
+/// Longest lifetime a share token can be minted with.
+pub const MAX_SHARE_TTL_SECS: u64 = 7 * 24 * 60 * 60;
+
+/// Request body for minting a share token.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema, JsonSchema)]
+pub struct CreateThreadShareRequest {
+    /// Token lifetime in seconds. Defaults to 24 hours, capped at 7 days.
+    #[serde(default)]
+    pub ttl_secs: Option<u64>,
+}
+
+impl CreateThreadShareRequest {
+    pub fn ttl_secs(&self) -> u64 {
+        self.ttl_secs
+            .unwrap_or(DEFAULT_SHARE_TTL_SECS)
+            .clamp(1, MAX_SHARE_TTL_SECS)
+    }
+}
+
+/// A freshly minted share token.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, JsonSchema)]
+pub struct ThreadShareResponse {
+    pub thread_id: String,
+    /// Shown once; the server keeps only its hash.
+    pub token: String,
+    /// Path of the read-only event stream, relative to the API base
+    /// (e.g. `/threads/{id}/watch?token=...`).
+    pub watch_path: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// A stored observer grant.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema, JsonSchema)]
+pub struct ObserverGrant {
+    /// Hex SHA-256 of the token.
+    pub token_hash: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+impl ObserverGrant {
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at <= now
+    }
+
+    /// Whether `token` is this grant's token and the grant is still live.
+    pub fn admits(&self, token: &str, now: DateTime<Utc>) -> bool {
+        !self.is_expired(now) && self.token_hash == hash_share_token(token)
+    }
+}
+
+/// Hex SHA-256 of a share token, as stored in [`ObserverGrant::token_hash`].
+pub fn hash_share_token(token: &str) -> String {
+    format!("{:x}", Sha256::digest(token.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn grant_admits_only_its_live_token() {
+        let now = Utc::now();
+        let grant = ObserverGrant {
+            token_hash: hash_share_token("secret"),
+            expires_at: now + chrono::Duration::minutes(5),
+        };
+        assert!(grant.admits("secret", now));
+        assert!(!grant.admits("other", now));
+        assert!(!grant.admits("secret", now + chrono::Duration::minutes(6)));
+    }
+
+    #[test]
+    fn ttl_defaults_and_is_capped() {
+        assert_eq!(
+            CreateThreadShareRequest::default().ttl_secs(),
+            DEFAULT_SHARE_TTL_SECS
+        );
+        let long = CreateThreadShareRequest {
+            ttl_secs: Some(MAX_SHARE_TTL_SECS * 2),
+        };
+        assert_eq!(long.ttl_secs(), MAX_SHARE_TTL_SECS);
+    }
+}
@@ -315,6 +315,81 @@ pub struct SecretFieldSpec {
     pub optional: bool,
 }
 
+/// A tool call that needs OAuth scopes the user's session does not grant.
+/// Tools return it (through `anyhow`) to pause the call for a consent
+/// prompt instead of failing with a plain error string.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, thiserror::Error)]
+#[error("{provider} needs additional scopes: {}", self.missing_scopes().join(", "))]
+pub struct ScopesRequired {
+    pub provider: String,
+    /// Scopes the current session already has.
+    #[serde(default)]
+    pub current_scopes: Vec<String>,
+    /// Scopes the tool needs.
+    pub required_scopes: Vec<String>,
+}
+
+impl ScopesRequired {
+    /// Required scopes the current session lacks.
+    pub fn missing_scopes(&self) -> Vec<String> {
+        self.required_scopes
+            .iter()
+            .filter(|scope| !self.current_scopes.contains(scope))
+            .cloned()
+            .collect()
+    }
+
+    /// Scopes to request on re-authorization: the current ones plus the
+    /// missing ones, so the new token keeps what the old one could do.
+    pub fn requested_scopes(&self) -> Vec<String> {
+        let mut scopes = self.current_scopes.clone();
+        scopes.extend(self.missing_scopes());
+        scopes
+    }
+
+    /// The tool result reported to the model when consent is not given.
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "error": "auth_required",
+            "provider": self.provider,
+            "missing_scopes": self.missing_scopes(),
+            "message": self.to_string(),
+        })
+    }
+}
+
+/// Emitted (as `AgentEventType::AuthRequired`) while a tool call waits for
+/// the user to grant more scopes. Answered with an [`AuthConsentResponse`]
+/// on `POST /event/auth`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuthConsentRequest {
+    pub auth_id: String,
+    pub tool_call_id: String,
+    pub tool_name: String,
+    pub provider: String,
+    pub current_scopes: Vec<String>,
+    pub required_scopes: Vec<String>,
+    pub missing_scopes: Vec<String>,
+    /// Authorization URL requesting the current and missing scopes. `None`
+    /// when the server has no OAuth handler for the provider.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub authorize_url: Option<String>,
+    pub timeout_ms: u64,
+}
+
+/// The user's answer to an [`AuthConsentRequest`]. When the client caught
+/// the OAuth redirect itself it forwards `code` and `state` for the server
+/// to exchange; otherwise `granted` means the flow was finished elsewhere.
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct AuthConsentResponse {
+    pub auth_id: String,
+    pub granted: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub state: Option<String>,
+}
+
 /// OAuth2 flow state for managing authorization flows
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OAuth2State {
@@ -467,6 +542,24 @@ pub trait ToolAuthStore: Send + Sync {
     ) -> Result<HashMap<String, AuthSession>, AuthError>;
 }
 
+/// Per-tool session lookup in the legacy [`McpSession`] shape.
+///
+/// Embedders that still resolve MCP tokens by tool name can keep this
+/// interface while the tokens themselves live in a [`ToolAuthStore`]; see
+/// `distri_auth::ProviderSessionStore` for the adapter.
+#[async_trait]
+pub trait ToolSessionStore: Send + Sync {
+    async fn get_session(
+        &self,
+        tool_name: &str,
+        user_id: &str,
+    ) -> Result<Option<McpSession>, AuthError>;
+}
+
+/// Secret key under which plain session tokens (no OAuth metadata) are
+/// stored for an auth entity.
+pub const SESSION_TOKEN_SECRET_KEY: &str = "session_token";
+
 /// OAuth handler that works with any AuthStore implementation
 #[derive(Clone)]
 pub struct OAuthHandler {
@@ -598,6 +691,33 @@ impl OAuthHandler {
         }
     }
 
+    /// Authorization URL for a registry provider, using the auth config the
+    /// registry holds for it. Used to re-authorize with more scopes when a
+    /// tool reports [`ScopesRequired`].
+    pub async fn get_consent_url(
+        &self,
+        auth_entity: &str,
+        user_id: &str,
+        scopes: &[String],
+    ) -> Result<String, AuthError> {
+        let registry = self.provider_registry.as_ref().ok_or_else(|| {
+            AuthError::InvalidConfig("No provider registry configured".to_string())
+        })?;
+        let auth_config = registry
+            .get_auth_type(auth_entity)
+            .await
+            .ok_or_else(|| AuthError::ProviderNotFound(auth_entity.to_string()))?;
+        self.get_auth_url(
+            auth_entity,
+            user_id,
+            &auth_config,
+            scopes,
+            &HashMap::new(),
+            None,
+        )
+        .await
+    }
+
     /// Handle OAuth2 callback and exchange code for tokens. `provider_override`
     /// lets the caller (e.g. cloud's ConnectionService) plug in a per-connection
     /// provider built from BYOK creds or discovered metadata.
@@ -1,4 +1,7 @@
+pub mod policy;
+
 pub use browsr_types::BrowsrClientConfig;
+pub use policy::{BrowserPolicy, BrowserPolicyRule, BrowserPolicyViolation};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
@@ -11,6 +14,10 @@ pub struct BrowserAgentConfig {
     pub enabled: bool,
     /// Persist and restore session cookies/state between runs
     pub persist_session: bool,
+    /// What the agent's browser tools may do. Unset allows anything the
+    /// egress policy allows.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub policy: Option<BrowserPolicy>,
     /// Optional runtime overrides for the Chromium driver
     #[serde(skip_serializing_if = "Option::is_none", flatten)]
     pub runtime: Option<BrowsrClientConfig>,
@@ -28,4 +35,8 @@ impl BrowserAgentConfig {
     pub fn runtime_config(&self) -> BrowsrClientConfig {
         self.runtime.clone().unwrap_or_default()
     }
+
+    pub fn policy(&self) -> Option<&BrowserPolicy> {
+        self.policy.as_ref()
+    }
 }
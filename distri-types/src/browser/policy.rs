@@ -0,0 +1,227 @@
+//! What an agent's browser tools may do: `browser_config.policy` of the
+//! agent definition.
+//!
+//! ```toml
+//! [browser_config]
+//! enabled = true
+//!
+//! [browser_config.policy]
+//! allowed_domains = ["*.example.com", "docs.rs"]
+//! read_only = true          # only load, read and scroll pages
+//! downloads = ["pdf", "csv"] # file types that may be loaded; [] blocks all
+//! max_pages_per_task = 20
+//! ```
+//!
+//! The browser tools check every command before it reaches the browser, on
+//! top of the server's egress policy. A URL whose last path segment has a
+//! file extension other than a web page's (`report.pdf`, `setup.exe`) is a
+//! download. Pages are counted as they are loaded by URL (`navigate_to`, a
+//! scraped URL, a tab opened at a URL). A blocked command fails with
+//! [`BrowserPolicyViolation`], which is reported to the model as a
+//! structured `browser_policy_violation` result.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::plugin_capabilities::{host_matches, url_host};
+
+/// Commands that only load, read or look at pages, the only ones read-only
+/// agents may send. Anything else — clicks, typing, scripts, cookies and storage
+/// writes, reloads, and commands added to the browser later — is refused.
+pub const READ_ONLY_COMMANDS: &[&str] = &[
+    "navigate_to",
+    "wait_for_navigation",
+    "wait_for_element",
+    "get_content",
+    "get_text",
+    "get_attribute",
+    "get_title",
+    "get_basic_info",
+    "extract_structured_content",
+    "get_bounding_boxes",
+    "get_element_bounding_box",
+    "inspect_element",
+    "scroll_to",
+    "scroll_into_view",
+    "screenshot",
+    "element_screenshot",
+    "get_cookies",
+    "get_local_storage",
+    "get_session_storage",
+    "capture_state",
+];
+
+/// Extensions of URLs that serve pages rather than files.
+const PAGE_EXTENSIONS: &[&str] = &[
+    "html", "htm", "xhtml", "shtml", "php", "asp", "aspx", "jsp", "cgi",
+];
+
+/// `browser_config.policy` of an agent.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(default, deny_unknown_fields)]
+pub struct BrowserPolicy {
+    /// Hosts the browser may load; `*.example.com` also matches
+    /// `example.com`. Empty allows any host the egress policy allows.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub allowed_domains: Vec<String>,
+    /// Only load and read pages ([`READ_ONLY_COMMANDS`]): no clicks,
+    /// typing, form controls, scripts, cookie or storage changes.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub read_only: bool,
+    /// File types (extensions) that may be downloaded; `[]` blocks
+    /// downloads. Unset allows any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub downloads: Option<Vec<String>>,
+    /// Most pages a task may load.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_pages_per_task: Option<usize>,
+}
+
+impl BrowserPolicy {
+    /// Fails on a domain that is not a host pattern.
+    pub fn validate(&self) -> Result<(), String> {
+        for pattern in &self.allowed_domains {
+            let host = pattern.trim();
+            let host = host.strip_prefix("*.").unwrap_or(host);
+            if host.is_empty() || host.contains(['/', ':', '?', '#', ' ']) {
+                return Err(format!(
+                    "browser policy: '{}' is not a domain (use e.g. example.com or *.example.com)",
+                    pattern
+                ));
+            }
+        }
+        if self.max_pages_per_task == Some(0) {
+            return Err("browser policy: max_pages_per_task must be at least 1".to_string());
+        }
+        Ok(())
+    }
+
+    /// Whether the browser may load `url`.
+    pub fn check_url(&self, url: &str) -> Result<(), BrowserPolicyViolation> {
+        let host = url_host(url);
+        if !self.allowed_domains.is_empty()
+            && !self.allowed_domains.iter().any(|p| host_matches(p, &host))
+        {
+            return Err(BrowserPolicyViolation::new(
+                BrowserPolicyRule::AllowedDomains,
+                format!("'{}' is not in the allowed domains", host),
+            )
+            .with_url(url));
+        }
+        if let (Some(allowed), Some(file_type)) = (&self.downloads, download_type(url))
+            && !allowed
+                .iter()
+                .any(|ext| ext.trim_start_matches('.').eq_ignore_ascii_case(&file_type))
+        {
+            return Err(BrowserPolicyViolation::new(
+                BrowserPolicyRule::Downloads,
+                format!("downloading .{} files is not allowed", file_type),
+            )
+            .with_url(url));
+        }
+        Ok(())
+    }
+
+    /// Whether the browser may run `command`, given in its wire form
+    /// (`{"command": "...", "data": {...}}`).
+    pub fn check_command(&self, command: &Value) -> Result<(), BrowserPolicyViolation> {
+        let name = command.get("command").and_then(Value::as_str).unwrap_or("");
+        if self.read_only && !READ_ONLY_COMMANDS.contains(&name) {
+            return Err(BrowserPolicyViolation::new(
+                BrowserPolicyRule::ReadOnly,
+                format!("'{}' is not allowed in read-only mode", name),
+            )
+            .with_command(name));
+        }
+        if let Some(url) = command.pointer("/data/url").and_then(Value::as_str) {
+            self.check_url(url).map_err(|v| v.with_command(name))?;
+        }
+        Ok(())
+    }
+
+    /// Whether a task that has loaded `loaded` pages may load `more`.
+    pub fn check_pages(&self, loaded: usize, more: usize) -> Result<(), BrowserPolicyViolation> {
+        match self.max_pages_per_task {
+            Some(max) if loaded + more > max => Err(BrowserPolicyViolation::new(
+                BrowserPolicyRule::MaxPages,
+                format!(
+                    "the task has loaded {} of its {} pages; {} more would exceed the limit",
+                    loaded, max, more
+                ),
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// The file type `url` downloads, if it names a file rather than a page.
+pub fn download_type(url: &str) -> Option<String> {
+    let path = url::Url::parse(url).ok()?.path().to_string();
+    let (_, ext) = path.rsplit('/').next()?.rsplit_once('.')?;
+    let ext = ext.to_ascii_lowercase();
+    let is_file_type = !ext.is_empty()
+        && ext.len() <= 8
+        && ext.chars().all(|c| c.is_ascii_alphanumeric())
+        && !ext.chars().all(|c| c.is_ascii_digit());
+    (is_file_type && !PAGE_EXTENSIONS.contains(&ext.as_str())).then_some(ext)
+}
+
+/// The rule a blocked browser command broke.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BrowserPolicyRule {
+    AllowedDomains,
+    ReadOnly,
+    Downloads,
+    MaxPages,
+}
+
+/// A browser command the agent's [`BrowserPolicy`] blocks.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, thiserror::Error)]
+#[error("browser policy: {message}")]
+pub struct BrowserPolicyViolation {
+    pub rule: BrowserPolicyRule,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    pub message: String,
+}
+
+impl BrowserPolicyViolation {
+    pub fn new(rule: BrowserPolicyRule, message: impl Into<String>) -> Self {
+        Self {
+            rule,
+            command: None,
+            url: None,
+            message: message.into(),
+        }
+    }
+
+    pub fn with_command(mut self, command: impl Into<String>) -> Self {
+        self.command = Some(command.into());
+        self
+    }
+
+    pub fn with_url(mut self, url: impl Into<String>) -> Self {
+        self.url = Some(url.into());
+        self
+    }
+
+    /// The tool result reported to the model.
+    pub fn to_json(&self) -> Value {
+        let mut json = serde_json::json!({
+            "error": "browser_policy_violation",
+            "rule": self.rule,
+            "message": self.to_string(),
+        });
+        if let Some(command) = &self.command {
+            json["command"] = command.clone().into();
+        }
+        if let Some(url) = &self.url {
+            json["url"] = url.clone().into();
+        }
+        json
+    }
+}
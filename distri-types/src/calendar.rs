@@ -0,0 +1,108 @@
+//! Settings of the built-in `calendar` MCP server.
+//!
+//! The server reads and writes a Google or Microsoft calendar through the
+//! workspace's OAuth connection to that provider. Times the model passes
+//! without an offset are read in the call's `timezone`, else
+//! `default_timezone`. See `distri_core::servers::calendar` for the tools it
+//! exposes.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Calendar service the server talks to.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CalendarProvider {
+    /// Google Calendar API v3.
+    #[default]
+    Google,
+    /// Outlook calendars through Microsoft Graph.
+    Microsoft,
+}
+
+impl CalendarProvider {
+    /// Name of the OAuth provider, which is also the default connection.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CalendarProvider::Google => "google",
+            CalendarProvider::Microsoft => "microsoft",
+        }
+    }
+}
+
+/// `calendar` section of the server config.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct CalendarMcpConfig {
+    #[serde(default)]
+    pub provider: CalendarProvider,
+    /// Name of the workspace connection holding the OAuth token. The
+    /// provider's name (`google` or `microsoft`) when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub connection: Option<String>,
+    /// IANA timezone of times given without an offset, and of the times the
+    /// tools return, when a call names none.
+    #[serde(default = "default_timezone")]
+    pub default_timezone: String,
+    /// Calendar used when a call names none.
+    #[serde(default = "default_calendar")]
+    pub default_calendar: String,
+    /// Most events a `calendar_list_events` call returns.
+    #[serde(default = "default_max_results")]
+    pub max_results: usize,
+    /// Per-request timeout.
+    #[serde(default = "default_timeout_secs")]
+    pub timeout_secs: u64,
+    /// Base URL of the provider's API, for proxies. The public endpoint
+    /// when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_url: Option<String>,
+}
+
+impl Default for CalendarMcpConfig {
+    fn default() -> Self {
+        Self {
+            provider: CalendarProvider::default(),
+            connection: None,
+            default_timezone: default_timezone(),
+            default_calendar: default_calendar(),
+            max_results: default_max_results(),
+            timeout_secs: default_timeout_secs(),
+            api_url: None,
+        }
+    }
+}
+
+impl CalendarMcpConfig {
+    /// The connection the server authenticates with.
+    pub fn effective_connection(&self) -> &str {
+        self.connection
+            .as_deref()
+            .unwrap_or_else(|| self.provider.as_str())
+    }
+
+    /// The provider's API base URL.
+    pub fn effective_api_url(&self) -> &str {
+        match (&self.api_url, self.provider) {
+            (Some(url), _) => url.trim_end_matches('/'),
+            (None, CalendarProvider::Google) => "https://www.googleapis.com/calendar/v3",
+            (None, CalendarProvider::Microsoft) => "https://graph.microsoft.com/v1.0",
+        }
+    }
+}
+
+fn default_timezone() -> String {
+    "UTC".to_string()
+}
+
+fn default_calendar() -> String {
+    "primary".to_string()
+}
+
+fn default_max_results() -> usize {
+    50
+}
+
+fn default_timeout_secs() -> u64 {
+    30
+}
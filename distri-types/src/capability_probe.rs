@@ -0,0 +1,180 @@
+//! Probing what a model can reliably do before agents depend on it.
+//!
+//! Small local models — especially quantized ones — often accept tool
+//! schemas and then return malformed or missing tool calls. With
+//! `capability_probe` in the server config, the first run of an agent with
+//! `tool_format = "provider"` on a probed model tests it once:
+//!
+//! - **native tool calls**: one call to a probe tool, with valid arguments;
+//! - **parallel tool calls**: two calls to it in a single reply;
+//! - **structured output**: a JSON reply matching a `json_schema`.
+//!
+//! The result is cached per model (and, with `cache_file`, across
+//! restarts). When native tool calls fail the agent runs with the XML tool
+//! format instead; when only parallel calls fail, requests ask for one tool
+//! call at a time (`parallel_tool_calls = false`).
+//!
+//! ```yaml
+//! capability_probe:
+//!   providers: [openai_compat]     # the default; "*" probes every provider
+//!   cache_file: .distri/model_capabilities.json
+//! ```
+
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+use crate::{ModelProvider, ModelSettings, ToolCall};
+
+/// `capability_probe` section of the server config.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct CapabilityProbeConfig {
+    /// Provider ids whose models are probed (`openai_compat`, `openai`,
+    /// ...), or `*` for all.
+    #[serde(default = "default_providers")]
+    pub providers: Vec<String>,
+    /// JSON file results are kept in, so each model is probed once.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_file: Option<String>,
+    /// Longest wait for one probe request; a request that takes longer
+    /// fails its test.
+    #[serde(default = "default_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+impl Default for CapabilityProbeConfig {
+    fn default() -> Self {
+        Self {
+            providers: default_providers(),
+            cache_file: None,
+            timeout_secs: default_timeout_secs(),
+        }
+    }
+}
+
+fn default_providers() -> Vec<String> {
+    vec!["openai_compat".to_string()]
+}
+
+fn default_timeout_secs() -> u64 {
+    60
+}
+
+impl CapabilityProbeConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.providers.is_empty() {
+            return Err("capability_probe.providers is empty".to_string());
+        }
+        if self.timeout_secs == 0 {
+            return Err("capability_probe.timeout_secs must be positive".to_string());
+        }
+        Ok(())
+    }
+
+    /// Whether models of `provider` are probed.
+    pub fn probes(&self, provider: &ModelProvider) -> bool {
+        self.providers
+            .iter()
+            .any(|p| p == "*" || p == provider.provider_id())
+    }
+}
+
+/// What a probe found a model can do.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ModelCapabilities {
+    pub native_tool_calls: bool,
+    pub parallel_tool_calls: bool,
+    pub structured_output: bool,
+    #[schemars(with = "String")]
+    pub probed_at: DateTime<Utc>,
+}
+
+/// Cache key of a model: its provider, its server for an OpenAI-compatible
+/// one, and its name.
+pub fn model_key(settings: &ModelSettings) -> String {
+    let model = settings.provider_model_id();
+    match &settings.inner.provider {
+        ModelProvider::OpenAICompatible { base_url, .. } => {
+            format!("openai_compat:{}:{}", base_url.trim_end_matches('/'), model)
+        }
+        provider => format!("{}:{}", provider.provider_id(), model),
+    }
+}
+
+/// Name of the tool the probes ask for.
+pub const PROBE_TOOL: &str = "get_weather";
+
+pub fn probe_tool_description() -> &'static str {
+    "Get the current weather for a city."
+}
+
+pub fn probe_tool_parameters() -> Value {
+    json!({
+        "type": "object",
+        "properties": {
+            "city": { "type": "string", "description": "City name, e.g. Paris" }
+        },
+        "required": ["city"]
+    })
+}
+
+pub const TOOL_CALL_PROMPT: &str =
+    "What is the weather in Paris right now? Call the get_weather tool to find out.";
+
+pub const PARALLEL_TOOL_CALL_PROMPT: &str = "What is the weather in Paris and in Tokyo? Call \
+     the get_weather tool once for each city, both calls in this one reply.";
+
+pub const STRUCTURED_OUTPUT_PROMPT: &str =
+    "What is 2 + 3? Reply with a JSON object whose `answer` is the number.";
+
+/// `response_format` of the structured output probe.
+pub fn structured_output_format() -> Value {
+    json!({
+        "type": "json_schema",
+        "json_schema": {
+            "name": "capability_probe",
+            "schema": {
+                "type": "object",
+                "properties": { "answer": { "type": "integer" } },
+                "required": ["answer"],
+                "additionalProperties": false
+            },
+            "strict": true
+        }
+    })
+}
+
+/// Whether `calls` are well-formed probe calls covering every one of
+/// `cities`.
+pub fn tool_calls_pass(calls: &[ToolCall], cities: &[&str]) -> bool {
+    let city = |call: &ToolCall| {
+        (call.tool_name == PROBE_TOOL)
+            .then(|| call.input.get("city").and_then(Value::as_str))
+            .flatten()
+            .map(str::to_lowercase)
+    };
+    let named: Option<Vec<String>> = calls.iter().map(city).collect();
+    let Some(named) = named else {
+        return false;
+    };
+    cities
+        .iter()
+        .all(|c| named.iter().any(|n| n.contains(&c.to_lowercase())))
+}
+
+/// Whether `content` is the structured output probe's answer. A reply
+/// wrapped in a ```json fence still passes.
+pub fn structured_output_passes(content: &str) -> bool {
+    let trimmed = content.trim();
+    let body = trimmed
+        .strip_prefix("```json")
+        .or_else(|| trimmed.strip_prefix("```"))
+        .and_then(|rest| rest.strip_suffix("```"))
+        .unwrap_or(trimmed);
+    serde_json::from_str::<Value>(body.trim())
+        .ok()
+        .and_then(|v| v.get("answer").and_then(Value::as_i64))
+        == Some(5)
+}
@@ -8,10 +8,12 @@
 //! channel triggers together with manual / schedule / webhook / event
 //! / tool triggers into one enum.
 
+use crate::AgentError;
 use crate::channels::ChannelProvider;
 use crate::configuration::AgentConfig;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::path::Path;
 use utoipa::ToSchema;
 
 /// Built-in slash commands every distri agent supports without declaring them.
@@ -119,8 +121,232 @@ pub struct SlashCommand {
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub channels: Vec<ChannelProvider>,
     /// Preset prompt sent to the agent when this command is invoked. Any text
-    /// the user typed after the command is appended.
+    /// the user typed after the command is appended — unless the command
+    /// declares `args` or the prompt uses `{{...}}` placeholders, in which
+    /// case it is rendered as a handlebars template (see [`Self::render`]).
     pub prompt: String,
+    /// Named arguments, bound positionally from the text after the command.
+    /// The last argument takes the rest of the line.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub args: Vec<SlashCommandArg>,
+    /// Tool of the agent the command runs instead of messaging it. It is
+    /// called directly with the bound args (see [`Self::tool_input`]) by
+    /// `POST /v1/commands/run`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool: Option<String>,
+}
+
+/// One named argument of a [`SlashCommand`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema, JsonSchema)]
+pub struct SlashCommandArg {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub description: String,
+    #[serde(default)]
+    pub required: bool,
+    /// Value used when the argument is omitted.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default: Option<String>,
+}
+
+impl SlashCommand {
+    /// Whether `name` (with leading `/`) invokes this command.
+    pub fn matches(&self, name: &str) -> bool {
+        self.name == name || self.aliases.iter().any(|a| a == name)
+    }
+
+    /// One-line usage string, e.g. `/review <path> [focus]`.
+    pub fn usage(&self) -> String {
+        let mut out = self.name.clone();
+        for arg in &self.args {
+            if arg.required {
+                out.push_str(&format!(" <{}>", arg.name));
+            } else {
+                out.push_str(&format!(" [{}]", arg.name));
+            }
+        }
+        out
+    }
+
+    /// Render the message sent to the agent for `/name <input>`.
+    ///
+    /// Commands without `args` or placeholders keep the plain behaviour:
+    /// `input` is appended to `prompt`. Otherwise `input` is bound to the
+    /// declared args (see [`Self::bind_args`]) and the prompt is rendered as
+    /// a handlebars template with each arg plus `{{input}}` (the raw text).
+    pub fn render(&self, input: &str) -> Result<String, AgentError> {
+        let input = input.trim();
+        if self.args.is_empty() && !self.prompt.contains("{{") {
+            return Ok(if input.is_empty() {
+                self.prompt.clone()
+            } else if self.prompt.is_empty() {
+                input.to_string()
+            } else {
+                format!("{}\n\n{}", self.prompt, input)
+            });
+        }
+        let mut data = self.bind_args(input)?;
+        data.insert("input".into(), input.into());
+        let mut handlebars = handlebars::Handlebars::new();
+        handlebars.register_escape_fn(handlebars::no_escape);
+        handlebars
+            .render_template(&self.prompt, &data)
+            .map_err(|e| AgentError::Validation(format!("Failed to render {}: {}", self.name, e)))
+    }
+
+    /// Input of the bound `tool` for `/name <input>`: the declared args as
+    /// an object, or `{"input": <text>}` when the command declares none.
+    pub fn tool_input(&self, input: &str) -> Result<serde_json::Value, AgentError> {
+        let input = input.trim();
+        if self.args.is_empty() {
+            return Ok(serde_json::json!({ "input": input }));
+        }
+        Ok(self.bind_args(input)?.into())
+    }
+
+    /// Split `input` on whitespace into the declared args; the last one takes
+    /// the rest of the line. Omitted args fall back to their default, and
+    /// optional ones without a default are left out.
+    fn bind_args(
+        &self,
+        input: &str,
+    ) -> Result<serde_json::Map<String, serde_json::Value>, AgentError> {
+        let mut data = serde_json::Map::new();
+        let mut rest = input;
+        for (i, arg) in self.args.iter().enumerate() {
+            let value = if i + 1 == self.args.len() {
+                std::mem::take(&mut rest)
+            } else {
+                let (head, tail) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+                rest = tail.trim_start();
+                head
+            };
+            let value = match (value.is_empty(), &arg.default) {
+                (false, _) => value.to_string(),
+                (true, Some(default)) => default.clone(),
+                (true, None) if arg.required => {
+                    return Err(AgentError::Validation(format!(
+                        "missing argument `{}` — usage: {}",
+                        arg.name,
+                        self.usage()
+                    )));
+                }
+                (true, None) => continue,
+            };
+            data.insert(arg.name.clone(), value.into());
+        }
+        Ok(data)
+    }
+}
+
+/// Parse a user-defined slash command file.
+///
+/// - `*.toml` — a `SlashCommand` table.
+/// - `*.md` — TOML frontmatter between `---` markers; the markdown body is
+///   the prompt template.
+///
+/// `name` defaults to the file stem and gains a leading `/` if missing.
+pub fn parse_slash_command_file(path: &Path, content: &str) -> Result<SlashCommand, AgentError> {
+    let mut table: toml::Table = match path.extension().and_then(|e| e.to_str()) {
+        Some("toml") => {
+            toml::from_str(content).map_err(|e| AgentError::Validation(e.to_string()))?
+        }
+        Some("md") => {
+            let parts: Vec<&str> = content.splitn(3, "---").collect();
+            if parts.len() < 3 || !parts[0].trim().is_empty() {
+                return Err(AgentError::Validation(
+                    "Invalid command markdown format. Expected TOML frontmatter between --- markers"
+                        .to_string(),
+                ));
+            }
+            let mut table: toml::Table = toml::from_str(parts[1].trim())
+                .map_err(|e| AgentError::Validation(e.to_string()))?;
+            table.insert("prompt".into(), parts[2].trim().to_string().into());
+            table
+        }
+        _ => {
+            return Err(AgentError::Validation(format!(
+                "Unsupported command file {} (expected .md or .toml)",
+                path.display()
+            )));
+        }
+    };
+    if !table.contains_key("name") {
+        let stem = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or_default()
+            .to_string();
+        table.insert("name".into(), stem.into());
+    }
+    let mut command: SlashCommand = table
+        .try_into()
+        .map_err(|e: toml::de::Error| AgentError::Validation(e.to_string()))?;
+    if !command.name.starts_with('/') {
+        command.name = format!("/{}", command.name);
+    }
+    Ok(command)
+}
+
+/// Load every `*.md` / `*.toml` command in `dir`, sorted by file name.
+/// Files that fail to parse are logged and skipped. A missing directory
+/// yields an empty list.
+pub fn load_slash_commands_from_dir(dir: &Path) -> Vec<SlashCommand> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut paths: Vec<_> = entries
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| matches!(p.extension().and_then(|e| e.to_str()), Some("md" | "toml")))
+        .collect();
+    paths.sort();
+
+    let mut commands = Vec::new();
+    for path in paths {
+        let parsed = std::fs::read_to_string(&path)
+            .map_err(|e| AgentError::Validation(e.to_string()))
+            .and_then(|content| parse_slash_command_file(&path, &content));
+        match parsed {
+            Ok(command) => commands.push(command),
+            Err(e) => tracing::warn!("skipping slash command {}: {}", path.display(), e),
+        }
+    }
+    commands
+}
+
+/// Response wrapper for `GET /v1/commands`.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, JsonSchema)]
+pub struct ListSlashCommandsResponse {
+    pub commands: Vec<SlashCommand>,
+}
+
+/// Body of `POST /v1/commands/render` and `POST /v1/commands/run`.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, JsonSchema)]
+pub struct RenderSlashCommandRequest {
+    /// Command name with leading `/`, or an alias.
+    pub name: String,
+    /// Text typed after the command.
+    #[serde(default)]
+    pub input: String,
+    /// Resolve agent-declared commands for this agent as well.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub agent: Option<String>,
+}
+
+/// Result of `POST /v1/commands/render`.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, JsonSchema)]
+pub struct RenderSlashCommandResponse {
+    /// The user message to send to the agent.
+    pub message: String,
+}
+
+/// Result of `POST /v1/commands/run`.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, JsonSchema)]
+pub struct RunSlashCommandResponse {
+    /// Name of the tool the command is bound to.
+    pub tool: String,
+    /// What the tool returned.
+    pub result: serde_json::Value,
 }
 
 /// Convert a `SystemCommand` into the `SlashCommand` shape that channel
@@ -135,6 +361,8 @@ pub fn system_command_as_slash(cmd: &SystemCommand) -> SlashCommand {
         aliases: vec![],
         channels: vec![],
         prompt: String::new(),
+        args: vec![],
+        tool: None,
     }
 }
 
@@ -343,6 +571,8 @@ mod tests {
             aliases: vec![],
             channels: vec![],
             prompt: String::new(),
+            args: vec![],
+            tool: None,
         }];
         let resolved = resolve_commands_with(&cfg, &extra);
         let names: Vec<&str> = resolved.iter().map(|c| c.name.as_str()).collect();
@@ -365,6 +595,8 @@ mod tests {
             aliases: vec![],
             channels: vec![],
             prompt: String::new(),
+            args: vec![],
+            tool: None,
         }];
         let resolved = resolve_commands_with(&cfg, &extra);
         let help: Vec<&SlashCommand> = resolved.iter().filter(|c| c.name == "/help").collect();
@@ -455,4 +687,66 @@ mod tests {
         assert_eq!(back.text, "Your classes:");
         assert_eq!(back.buttons[0].len(), 1);
     }
+
+    #[test]
+    fn plain_command_appends_input() {
+        let cmd: SlashCommand =
+            serde_json::from_value(serde_json::json!({"name":"/summary","prompt":"Summarize."}))
+                .unwrap();
+        assert_eq!(cmd.render("").unwrap(), "Summarize.");
+        assert_eq!(
+            cmd.render(" the intro ").unwrap(),
+            "Summarize.\n\nthe intro"
+        );
+    }
+
+    #[test]
+    fn markdown_command_binds_args_and_tool() {
+        let md = r#"---
+description = "Review a file"
+tool = "Read"
+[[args]]
+name = "path"
+required = true
+[[args]]
+name = "focus"
+default = "correctness"
+---
+Review {{path}} with a focus on {{focus}}.
+"#;
+        let cmd = parse_slash_command_file(Path::new("commands/review.md"), md).unwrap();
+        assert_eq!(cmd.name, "/review");
+        assert_eq!(cmd.usage(), "/review <path> [focus]");
+        assert_eq!(
+            cmd.render("src/main.rs").unwrap(),
+            "Review src/main.rs with a focus on correctness."
+        );
+        assert_eq!(cmd.tool.as_deref(), Some("Read"));
+        assert_eq!(
+            cmd.tool_input("src/a.rs error handling").unwrap(),
+            serde_json::json!({"path": "src/a.rs", "focus": "error handling"})
+        );
+        assert_eq!(
+            cmd.tool_input("src/main.rs").unwrap(),
+            serde_json::json!({"path": "src/main.rs", "focus": "correctness"})
+        );
+        assert!(cmd.tool_input("").is_err(), "required arg enforced");
+        assert!(
+            cmd.render("src/a.rs error handling & naming")
+                .unwrap()
+                .starts_with("Review src/a.rs with a focus on error handling & naming.")
+        );
+        assert!(cmd.render("").is_err(), "required arg enforced");
+    }
+
+    #[test]
+    fn toml_command_parses() {
+        let cmd = parse_slash_command_file(
+            Path::new("standup.toml"),
+            "name = \"/standup\"\naliases = [\"/su\"]\nprompt = \"Draft my standup for {{input}}\"\n",
+        )
+        .unwrap();
+        assert!(cmd.matches("/su"));
+        assert_eq!(cmd.render("today").unwrap(), "Draft my standup for today");
+    }
 }
@@ -1,4 +1,7 @@
 use crate::a2a::{AgentCapabilities, AgentProvider, SecurityScheme};
+use crate::memory_anonymization::MemoryAnonymizationConfig;
+use crate::plugin_storage::PluginStorageConfig;
+use crate::user_quotas::UserQuotaLimits;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -47,6 +50,9 @@ pub struct MetadataStoreConfig {
     pub store_type: StoreType,
     #[serde(default)]
     pub db_config: Option<DbConnectionConfig>,
+    /// Quotas of the plugins' key-value storage
+    #[serde(default)]
+    pub plugin_storage: PluginStorageConfig,
 }
 
 impl Default for MetadataStoreConfig {
@@ -54,6 +60,7 @@ impl Default for MetadataStoreConfig {
         Self {
             store_type: StoreType::Sqlite,
             db_config: Some(DbConnectionConfig::default()),
+            plugin_storage: PluginStorageConfig::default(),
         }
     }
 }
@@ -71,6 +78,10 @@ pub struct MemoryStoreConfig {
     #[serde(default = "default_max_results")]
     pub max_results: usize,
     pub openai_api_key: Option<String>,
+    /// Pseudonymize personal identifiers before memories are stored. See
+    /// [`crate::memory_anonymization`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub anonymization: Option<MemoryAnonymizationConfig>,
 }
 
 impl Default for MemoryStoreConfig {
@@ -82,6 +93,7 @@ impl Default for MemoryStoreConfig {
             similarity_threshold: default_similarity_threshold(),
             max_results: default_max_results(),
             openai_api_key: None,
+            anonymization: None,
         }
     }
 }
@@ -98,6 +110,9 @@ pub struct SessionStoreConfig {
     /// Database config (only used when ephemeral=false)
     #[serde(default)]
     pub db_config: Option<DbConnectionConfig>,
+    /// Batching of task event writes
+    #[serde(default)]
+    pub event_writes: EventWriteConfig,
 }
 
 fn default_ephemeral() -> bool {
@@ -110,6 +125,39 @@ impl Default for SessionStoreConfig {
             ephemeral: true,
             store_type: StoreType::Sqlite,
             db_config: Some(DbConnectionConfig::default()),
+            event_writes: EventWriteConfig::default(),
+        }
+    }
+}
+
+/// How task events are written. By default each event is stored as it
+/// comes; with a `max_batch` above 1 events are queued and stored in
+/// batches, so a fast token stream is not one INSERT per event. Events
+/// ending a run are stored at once.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, ToSchema, PartialEq, Eq)]
+pub struct EventWriteConfig {
+    /// Most events queued before they are written; `1` (the default)
+    /// writes each event as it comes.
+    #[serde(default = "default_event_batch_size")]
+    pub max_batch: usize,
+    /// Longest time an event stays queued, in milliseconds.
+    #[serde(default = "default_event_flush_interval_ms")]
+    pub flush_interval_ms: u64,
+}
+
+fn default_event_batch_size() -> usize {
+    1
+}
+
+fn default_event_flush_interval_ms() -> u64 {
+    200
+}
+
+impl Default for EventWriteConfig {
+    fn default() -> Self {
+        Self {
+            max_batch: default_event_batch_size(),
+            flush_interval_ms: default_event_flush_interval_ms(),
         }
     }
 }
@@ -249,6 +297,107 @@ pub struct ServerConfig {
     pub preferred_transport: Option<String>,
     #[serde(default = "default_documentation_url")]
     pub documentation_url: Option<String>,
+    /// Limits on files attached to A2A messages.
+    #[serde(default)]
+    pub uploads: UploadLimits,
+    /// Limits on concurrent agent runs.
+    #[serde(default)]
+    pub admission: AdmissionLimits,
+    /// Per-user limits on concurrent runs, runs per day and tokens per day.
+    #[serde(default)]
+    pub user_quotas: UserQuotaLimits,
+}
+
+/// Size and type limits for files attached to A2A messages, either as base64
+/// `file` parts or as multipart form fields.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, ToSchema)]
+pub struct UploadLimits {
+    /// Largest accepted file, in decoded bytes.
+    #[serde(default = "default_max_upload_bytes")]
+    pub max_file_bytes: u64,
+    /// Most files accepted in a single message.
+    #[serde(default = "default_max_upload_files")]
+    pub max_files: usize,
+    /// Accepted MIME types. `image/*` style entries match a whole family;
+    /// an empty list accepts any type.
+    #[serde(default)]
+    pub allowed_mime_types: Vec<String>,
+}
+
+impl Default for UploadLimits {
+    fn default() -> Self {
+        Self {
+            max_file_bytes: default_max_upload_bytes(),
+            max_files: default_max_upload_files(),
+            allowed_mime_types: vec![],
+        }
+    }
+}
+
+impl UploadLimits {
+    /// Whether a file of `mime_type` may be uploaded.
+    pub fn allows_mime_type(&self, mime_type: &str) -> bool {
+        let mime_type = mime_type.to_ascii_lowercase();
+        self.allowed_mime_types.is_empty()
+            || self.allowed_mime_types.iter().any(|allowed| {
+                let allowed = allowed.to_ascii_lowercase();
+                match allowed.strip_suffix("/*") {
+                    Some(family) => mime_type.split_once('/').is_some_and(|(f, _)| f == family),
+                    None => allowed == mime_type,
+                }
+            })
+    }
+}
+
+/// Admission control for `message/send` and `message/stream`. Runs beyond
+/// `max_concurrent_runs` wait in a queue; once `max_queued` are waiting, or a
+/// run has waited `queue_timeout_secs`, the request is rejected with `429 Too
+/// Many Requests` and a `Retry-After` of `retry_after_secs`.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, ToSchema)]
+pub struct AdmissionLimits {
+    /// Most runs executing at once. Unset admits every run.
+    #[serde(default)]
+    pub max_concurrent_runs: Option<usize>,
+    /// Most runs waiting for a slot.
+    #[serde(default = "default_max_queued_runs")]
+    pub max_queued: usize,
+    /// How long a run waits for a slot before it is rejected.
+    #[serde(default = "default_queue_timeout_secs")]
+    pub queue_timeout_secs: u64,
+    /// `Retry-After` sent with a rejection, in seconds.
+    #[serde(default = "default_retry_after_secs")]
+    pub retry_after_secs: u64,
+}
+
+impl Default for AdmissionLimits {
+    fn default() -> Self {
+        Self {
+            max_concurrent_runs: None,
+            max_queued: default_max_queued_runs(),
+            queue_timeout_secs: default_queue_timeout_secs(),
+            retry_after_secs: default_retry_after_secs(),
+        }
+    }
+}
+
+fn default_max_queued_runs() -> usize {
+    100
+}
+
+fn default_queue_timeout_secs() -> u64 {
+    30
+}
+
+fn default_retry_after_secs() -> u64 {
+    5
+}
+
+fn default_max_upload_bytes() -> u64 {
+    20 * 1024 * 1024
+}
+
+fn default_max_upload_files() -> usize {
+    10
 }
 
 fn default_capabilities() -> AgentCapabilities {
@@ -274,6 +423,9 @@ impl Default for ServerConfig {
             host: None,
             preferred_transport: default_preferred_transport(),
             documentation_url: default_documentation_url(),
+            uploads: UploadLimits::default(),
+            admission: AdmissionLimits::default(),
+            user_quotas: UserQuotaLimits::default(),
         }
     }
 }
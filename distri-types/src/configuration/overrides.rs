@@ -1,5 +1,6 @@
-use crate::agent::{RuntimeMode, ToolsConfig};
+use crate::agent::{ReasoningEffort, RuntimeMode, ToolsConfig};
 use crate::dynamic_tool::DynamicToolFactory;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
@@ -10,6 +11,10 @@ pub struct DefinitionOverrides {
     pub model: Option<String>,
     /// Override the temperature
     pub temperature: Option<f32>,
+    /// Override the reasoning effort
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<String>)]
+    pub reasoning_effort: Option<ReasoningEffort>,
     /// Override max tokens
     pub max_tokens: Option<u32>,
     /// Override max iterations
@@ -77,6 +82,11 @@ impl DefinitionOverrides {
         self
     }
 
+    pub fn with_reasoning_effort(mut self, reasoning_effort: ReasoningEffort) -> Self {
+        self.reasoning_effort = Some(reasoning_effort);
+        self
+    }
+
     pub fn with_max_tokens(mut self, max_tokens: u32) -> Self {
         self.max_tokens = Some(max_tokens);
         self
@@ -144,3 +154,111 @@ impl DefinitionOverrides {
         self
     }
 }
+
+/// Model parameters a client asks for on a single message, through
+/// `MessageSendParams.configuration`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MessageModelOverrides {
+    pub model: Option<String>,
+    pub temperature: Option<f32>,
+    pub reasoning_effort: Option<ReasoningEffort>,
+}
+
+impl MessageModelOverrides {
+    pub fn from_send_configuration(
+        configuration: &distri_a2a::MessageSendConfiguration,
+    ) -> Result<Self, String> {
+        Ok(Self {
+            model: configuration
+                .model
+                .as_deref()
+                .map(str::trim)
+                .filter(|m| !m.is_empty())
+                .map(str::to_string),
+            temperature: configuration.temperature,
+            reasoning_effort: configuration
+                .reasoning_effort
+                .as_deref()
+                .map(str::parse)
+                .transpose()?,
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.model.is_none() && self.temperature.is_none() && self.reasoning_effort.is_none()
+    }
+
+    /// Copy the requested parameters onto `overrides`.
+    pub fn apply_to(self, overrides: &mut DefinitionOverrides) {
+        if self.model.is_some() {
+            overrides.model = self.model;
+        }
+        if self.temperature.is_some() {
+            overrides.temperature = self.temperature;
+        }
+        if self.reasoning_effort.is_some() {
+            overrides.reasoning_effort = self.reasoning_effort;
+        }
+    }
+}
+
+/// `message_overrides` of an agent definition: the model parameters a
+/// client may change per message. A parameter without an entry cannot be
+/// changed.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct MessageOverridePolicy {
+    /// Models a message may switch to, as `provider/model` or a bare model
+    /// name.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub models: Vec<String>,
+    /// Inclusive temperature range a message may pick from.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<TemperatureRange>,
+    /// Reasoning efforts a message may pick.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub reasoning_efforts: Vec<ReasoningEffort>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct TemperatureRange {
+    pub min: f32,
+    pub max: f32,
+}
+
+impl MessageOverridePolicy {
+    /// Check `requested` against the allowlist, naming the first parameter
+    /// that is not allowed.
+    pub fn check(&self, requested: &MessageModelOverrides) -> Result<(), String> {
+        if let Some(model) = &requested.model {
+            let allowed = self
+                .models
+                .iter()
+                .any(|m| m == model || m.split_once('/').is_some_and(|(_, name)| name == model));
+            if !allowed {
+                return Err(format!("model '{}' is not allowed", model));
+            }
+        }
+        if let Some(temperature) = requested.temperature {
+            match self.temperature {
+                Some(range) if range.min <= temperature && temperature <= range.max => {}
+                Some(range) => {
+                    return Err(format!(
+                        "temperature {} is outside the allowed range {}..={}",
+                        temperature, range.min, range.max
+                    ));
+                }
+                None => return Err("temperature cannot be overridden".to_string()),
+            }
+        }
+        if let Some(effort) = requested.reasoning_effort
+            && !self.reasoning_efforts.contains(&effort)
+        {
+            return Err(format!(
+                "reasoning effort '{}' is not allowed",
+                effort.as_str()
+            ));
+        }
+        Ok(())
+    }
+}
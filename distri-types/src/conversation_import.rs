@@ -0,0 +1,402 @@
+//! Importing conversation history from other assistants' data exports.
+//!
+//! Both ChatGPT ("Export data" in settings) and Claude ship a
+//! `conversations.json` holding every conversation of the account. The
+//! parsers here turn such a file into [`ImportedConversation`]s: plain
+//! user/assistant [`Message`]s in conversation order, ready to be written
+//! into a thread.
+//!
+//! Attachments are kept where the export carries their content (Claude's
+//! extracted text becomes a [`Part::File`]); otherwise the message notes the
+//! attachment by name, since the files themselves are not in the JSON.
+
+use std::fmt;
+use std::str::FromStr;
+
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use utoipa::ToSchema;
+
+use crate::{FileType, Message, MessageRole, Part};
+
+/// The export a file comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportFormat {
+    Chatgpt,
+    Claude,
+}
+
+impl ImportFormat {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Chatgpt => "chatgpt",
+            Self::Claude => "claude",
+        }
+    }
+}
+
+impl fmt::Display for ImportFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ImportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "chatgpt" | "openai" => Ok(Self::Chatgpt),
+            "claude" | "anthropic" => Ok(Self::Claude),
+            other => Err(format!(
+                "unknown import format '{}', expected 'chatgpt' or 'claude'",
+                other
+            )),
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ImportError {
+    #[error("invalid export JSON: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("not a {format} export: {reason}")]
+    Format {
+        format: ImportFormat,
+        reason: String,
+    },
+}
+
+/// One conversation read from an export.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportedConversation {
+    /// The conversation's id in the source, stored as the thread's external
+    /// id (`chatgpt:<id>` / `claude:<id>`) so re-imports are skipped.
+    pub external_id: String,
+    pub title: String,
+    /// Epoch milliseconds.
+    pub created_at: i64,
+    pub messages: Vec<Message>,
+}
+
+/// A thread created by an import.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct ImportedThread {
+    pub thread_id: String,
+    pub external_id: String,
+    pub title: String,
+    pub message_count: usize,
+}
+
+/// Result of importing an export file.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct ConversationImportSummary {
+    pub imported: Vec<ImportedThread>,
+    /// External ids of conversations that were imported before, or had no
+    /// messages.
+    pub skipped: Vec<String>,
+}
+
+/// Parse an export file's JSON. Accepts the full `conversations.json` array
+/// or a single conversation object.
+pub fn parse_export(
+    format: ImportFormat,
+    json: &str,
+) -> Result<Vec<ImportedConversation>, ImportError> {
+    let value: Value = serde_json::from_str(json)?;
+    let conversations = match value {
+        Value::Array(items) => items,
+        obj @ Value::Object(_) => vec![obj],
+        _ => {
+            return Err(ImportError::Format {
+                format,
+                reason: "expected an array of conversations".to_string(),
+            });
+        }
+    };
+    conversations
+        .iter()
+        .map(|conversation| match format {
+            ImportFormat::Chatgpt => parse_chatgpt(conversation),
+            ImportFormat::Claude => parse_claude(conversation),
+        })
+        .collect()
+}
+
+fn parse_chatgpt(conversation: &Value) -> Result<ImportedConversation, ImportError> {
+    let invalid = |reason: &str| ImportError::Format {
+        format: ImportFormat::Chatgpt,
+        reason: reason.to_string(),
+    };
+    let id = str_field(conversation, "conversation_id")
+        .or_else(|| str_field(conversation, "id"))
+        .ok_or_else(|| invalid("conversation without an id"))?;
+    let mapping = conversation
+        .get("mapping")
+        .and_then(Value::as_object)
+        .ok_or_else(|| invalid("conversation without a mapping"))?;
+
+    // The mapping is a tree of edits and regenerations; the conversation as
+    // last seen is the path from the root to `current_node`.
+    let leaf = str_field(conversation, "current_node")
+        .filter(|node| mapping.contains_key(*node))
+        .map(str::to_string)
+        .or_else(|| {
+            let root = mapping
+                .iter()
+                .find(|(_, node)| node.get("parent").is_none_or(Value::is_null))
+                .map(|(id, _)| id.clone())?;
+            let mut leaf = root;
+            for _ in 0..mapping.len() {
+                match mapping[&leaf]
+                    .get("children")
+                    .and_then(Value::as_array)
+                    .and_then(|c| c.last())
+                    .and_then(Value::as_str)
+                    .filter(|child| mapping.contains_key(*child))
+                {
+                    Some(child) => leaf = child.to_string(),
+                    None => break,
+                }
+            }
+            Some(leaf)
+        })
+        .ok_or_else(|| invalid("conversation without messages"))?;
+
+    let mut path = Vec::new();
+    let mut next = Some(leaf.as_str());
+    while let Some(node_id) = next {
+        // Guard against a malformed mapping with a parent cycle.
+        if path.len() > mapping.len() {
+            return Err(invalid("cyclic mapping"));
+        }
+        let Some(node) = mapping.get(node_id) else {
+            break;
+        };
+        path.push(node);
+        next = node.get("parent").and_then(Value::as_str);
+    }
+    path.reverse();
+
+    let messages = path
+        .iter()
+        .filter_map(|node| node.get("message"))
+        .filter_map(chatgpt_message)
+        .collect();
+
+    Ok(ImportedConversation {
+        external_id: format!("{}:{}", ImportFormat::Chatgpt, id),
+        title: title_or_default(str_field(conversation, "title")),
+        created_at: conversation
+            .get("create_time")
+            .and_then(Value::as_f64)
+            .map(|secs| (secs * 1000.0) as i64)
+            .unwrap_or_default(),
+        messages,
+    })
+}
+
+fn chatgpt_message(message: &Value) -> Option<Message> {
+    let metadata = message.get("metadata");
+    if metadata
+        .and_then(|m| m.get("is_visually_hidden_from_conversation"))
+        .and_then(Value::as_bool)
+        .unwrap_or(false)
+    {
+        return None;
+    }
+    // Tool outputs (browsing, code execution) are kept as assistant text: the
+    // export does not record the calls that produced them.
+    let role = match message.pointer("/author/role").and_then(Value::as_str)? {
+        "user" => MessageRole::User,
+        "assistant" | "tool" => MessageRole::Assistant,
+        _ => return None,
+    };
+
+    let content = message.get("content")?;
+    let mut texts = Vec::new();
+    let mut attachments = Vec::new();
+    match content.get("content_type").and_then(Value::as_str) {
+        Some("text") | Some("multimodal_text") => {
+            for part in content
+                .get("parts")
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+            {
+                match part {
+                    Value::String(text) => texts.push(text.clone()),
+                    Value::Object(_) => {
+                        if let Some(pointer) = str_field(part, "asset_pointer") {
+                            attachments.push(pointer.to_string());
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+        Some("code") => {
+            if let Some(code) = str_field(content, "text") {
+                texts.push(format!("```\n{}\n```", code));
+            }
+        }
+        Some("execution_output") | Some("tether_quote") => {
+            if let Some(text) = str_field(content, "text") {
+                texts.push(text.to_string());
+            }
+        }
+        // Custom instructions, browsing displays and reasoning summaries are
+        // not part of the visible conversation.
+        _ => {}
+    }
+    // Uploaded files carry a name here; image parts above only a pointer.
+    let named: Vec<String> = metadata
+        .and_then(|m| m.get("attachments"))
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|a| str_field(a, "name").map(str::to_string))
+        .collect();
+    if !named.is_empty() {
+        attachments = named;
+    }
+
+    let mut parts: Vec<Part> = texts
+        .into_iter()
+        .filter(|text| !text.trim().is_empty())
+        .map(Part::Text)
+        .collect();
+    parts.extend(attachments.iter().map(|name| attachment_note(name)));
+    if parts.is_empty() {
+        return None;
+    }
+
+    Some(Message {
+        id: str_field(message, "id")
+            .map(str::to_string)
+            .unwrap_or_else(|| uuid::Uuid::new_v4().to_string()),
+        name: None,
+        role,
+        parts,
+        created_at: message
+            .get("create_time")
+            .and_then(Value::as_f64)
+            .map(|secs| (secs * 1000.0) as i64)
+            .unwrap_or_default(),
+        agent_id: None,
+        parts_metadata: None,
+    })
+}
+
+fn parse_claude(conversation: &Value) -> Result<ImportedConversation, ImportError> {
+    let invalid = |reason: &str| ImportError::Format {
+        format: ImportFormat::Claude,
+        reason: reason.to_string(),
+    };
+    let id =
+        str_field(conversation, "uuid").ok_or_else(|| invalid("conversation without a uuid"))?;
+    let chat_messages = conversation
+        .get("chat_messages")
+        .and_then(Value::as_array)
+        .ok_or_else(|| invalid("conversation without chat_messages"))?;
+
+    Ok(ImportedConversation {
+        external_id: format!("{}:{}", ImportFormat::Claude, id),
+        title: title_or_default(str_field(conversation, "name")),
+        created_at: timestamp_ms(conversation.get("created_at")),
+        messages: chat_messages.iter().filter_map(claude_message).collect(),
+    })
+}
+
+fn claude_message(message: &Value) -> Option<Message> {
+    let role = match str_field(message, "sender")? {
+        "human" => MessageRole::User,
+        "assistant" => MessageRole::Assistant,
+        _ => return None,
+    };
+
+    // Newer exports split the message into typed blocks; `text` then holds
+    // the same text flattened. Thinking and tool blocks are dropped.
+    let text = match message.get("content").and_then(Value::as_array) {
+        Some(blocks) if !blocks.is_empty() => blocks
+            .iter()
+            .filter(|b| str_field(b, "type") == Some("text"))
+            .filter_map(|b| str_field(b, "text"))
+            .collect::<Vec<_>>()
+            .join("\n\n"),
+        _ => str_field(message, "text").unwrap_or_default().to_string(),
+    };
+
+    let mut parts = Vec::new();
+    if !text.trim().is_empty() {
+        parts.push(Part::Text(text));
+    }
+    for attachment in message
+        .get("attachments")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+    {
+        let name = str_field(attachment, "file_name").unwrap_or("attachment");
+        match str_field(attachment, "extracted_content").filter(|c| !c.is_empty()) {
+            Some(content) => parts.push(Part::File(FileType::Bytes {
+                bytes: base64::engine::general_purpose::STANDARD.encode(content),
+                mime_type: "text/plain".to_string(),
+                name: Some(name.to_string()),
+            })),
+            None => parts.push(attachment_note(name)),
+        }
+    }
+    for file in message
+        .get("files")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+    {
+        if let Some(name) = str_field(file, "file_name") {
+            parts.push(attachment_note(name));
+        }
+    }
+    if parts.is_empty() {
+        return None;
+    }
+
+    Some(Message {
+        id: str_field(message, "uuid")
+            .map(str::to_string)
+            .unwrap_or_else(|| uuid::Uuid::new_v4().to_string()),
+        name: None,
+        role,
+        parts,
+        created_at: timestamp_ms(message.get("created_at")),
+        agent_id: None,
+        parts_metadata: None,
+    })
+}
+
+fn str_field<'a>(value: &'a Value, key: &str) -> Option<&'a str> {
+    value.get(key).and_then(Value::as_str)
+}
+
+fn title_or_default(title: Option<&str>) -> String {
+    title
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+        .unwrap_or("Imported conversation")
+        .to_string()
+}
+
+/// An RFC 3339 timestamp in epoch milliseconds, or 0.
+fn timestamp_ms(value: Option<&Value>) -> i64 {
+    value
+        .and_then(Value::as_str)
+        .and_then(|s| chrono::DateTime::parse_from_rfc3339(s).ok())
+        .map(|t| t.timestamp_millis())
+        .unwrap_or_default()
+}
+
+fn attachment_note(name: &str) -> Part {
+    Part::Text(format!("[Attachment: {}]", name))
+}
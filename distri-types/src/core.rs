@@ -3,7 +3,10 @@ use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::{self, Value, json};
 use std::default::Default;
-use std::{collections::HashMap, time::SystemTime};
+use std::{
+    collections::{BTreeMap, HashMap},
+    time::SystemTime,
+};
 use utoipa::ToSchema;
 
 use crate::filesystem::FileMetadata;
@@ -542,6 +545,12 @@ impl ToolResponse {
         }
     }
 
+    /// The typed output of the tool: its only `data` part or, without one,
+    /// its only text part if that is JSON.
+    pub fn structured_output(&self) -> Option<serde_json::Value> {
+        crate::tool_output::structured_output(&self.parts)
+    }
+
     /// Get all artifacts from this response
     pub fn get_artifacts(&self) -> Vec<&FileMetadata> {
         self.parts
@@ -591,6 +600,14 @@ pub struct Task {
     pub status: TaskStatus,
     pub created_at: i64,
     pub updated_at: i64,
+    /// When the task was archived by a regeneration. Archived tasks are
+    /// kept but left out of the thread history.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub archived_at: Option<i64>,
+    /// Tasks this one was derived from, e.g. the task a regeneration
+    /// replaced.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub reference_task_ids: Vec<String>,
 }
 
 /// Lifecycle state of a task. Snake-case in JSON to match the
@@ -706,6 +723,15 @@ pub struct Thread {
     /// context breakdown without a live event stream.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub last_context_budget: Option<serde_json::Value>,
+    /// Template variables of the thread. `{{name}}` in the thread's user
+    /// messages and in the agent's system prompt renders to the value at
+    /// send time (see [`crate::prompt::PromptRegistry::render_variables`]).
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub variables: BTreeMap<String, String>,
+    /// When the thread's tasks were moved to cold storage (see
+    /// [`crate::thread_archive`]). Reading the thread restores them.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub archived_at: Option<chrono::DateTime<chrono::Utc>>,
 }
 
 impl Thread {
@@ -735,6 +761,8 @@ impl Thread {
             total_tokens: 0,
             active_task_id: None,
             last_context_budget: None,
+            variables: BTreeMap::new(),
+            archived_at: None,
         }
     }
 
@@ -756,8 +784,48 @@ impl Thread {
             }
         }
     }
+
+    /// Topical tags stored under [`THREAD_TAGS_KEY`] in the thread metadata.
+    pub fn tags(&self) -> Vec<String> {
+        self.metadata
+            .get(THREAD_TAGS_KEY)
+            .and_then(|v| serde_json::from_value(v.clone()).ok())
+            .unwrap_or_default()
+    }
+
+    /// Observer grants stored under [`THREAD_OBSERVERS_KEY`], expired ones
+    /// included.
+    pub fn observer_grants(&self) -> Vec<crate::api::share::ObserverGrant> {
+        self.metadata
+            .get(THREAD_OBSERVERS_KEY)
+            .and_then(|v| serde_json::from_value(v.clone()).ok())
+            .unwrap_or_default()
+    }
+
+    /// Case-insensitive match of `query` against the title, last message and
+    /// tags.
+    pub fn matches_search(&self, query: &str) -> bool {
+        let query = query.to_lowercase();
+        self.title.to_lowercase().contains(&query)
+            || self
+                .last_message
+                .as_ref()
+                .is_some_and(|m| m.to_lowercase().contains(&query))
+            || self
+                .tags()
+                .iter()
+                .any(|t| t.to_lowercase().contains(&query))
+    }
 }
 
+/// Thread metadata key holding the thread's topical tags (`Vec<String>`).
+pub const THREAD_TAGS_KEY: &str = "tags";
+/// Thread metadata key set once the contextual titling pass has run.
+pub const THREAD_AUTO_TITLED_KEY: &str = "auto_titled";
+/// Thread metadata key holding the read-only observer grants
+/// (`Vec<ObserverGrant>`).
+pub const THREAD_OBSERVERS_KEY: &str = "observers";
+
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, ToSchema)]
 pub struct ThreadSummary {
     pub id: String,
@@ -787,6 +855,9 @@ pub struct ThreadSummary {
     /// Total tokens used across all runs in this thread
     #[serde(default)]
     pub total_tokens: u64,
+    /// Set while the thread is archived.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub archived_at: Option<chrono::DateTime<chrono::Utc>>,
 }
 
 // CreateThreadRequest removed - threads are now auto-created from first messages
@@ -812,6 +883,9 @@ pub struct UpdateThreadRequest {
     pub metadata: Option<HashMap<String, serde_json::Value>>,
     pub attributes: Option<serde_json::Value>,
     pub user_id: Option<String>,
+    /// Replaces the thread's template variables.
+    #[serde(default)]
+    pub variables: Option<BTreeMap<String, String>>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, JsonSchema, PartialEq, ToSchema)]
@@ -868,6 +942,13 @@ pub struct TraceContext {
     pub parent_span_id: String,
 }
 
+impl TraceContext {
+    /// The context as a W3C `traceparent` header value (sampled).
+    pub fn traceparent(&self) -> String {
+        format!("00-{}-{}-01", self.trace_id, self.parent_span_id)
+    }
+}
+
 /// Additional attributes for thread/task metadata.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct AdditionalAttributes {
@@ -0,0 +1,84 @@
+//! Settings of the built-in `crawl` MCP server.
+//!
+//! Every page the server fetches goes through a shared on-disk HTTP cache
+//! and a per-domain rate limit, and robots.txt is respected unless turned
+//! off. See `distri_core::servers::crawl` for the tools it exposes.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// `crawl` section of the server config.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct CrawlMcpConfig {
+    /// Directory of the response cache. Relative paths resolve against the
+    /// workspace; `.distri/cache/http` when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_dir: Option<String>,
+    /// Size limit of the cache. The least recently used responses are
+    /// evicted past it.
+    #[serde(default = "default_max_cache_bytes")]
+    pub max_cache_bytes: u64,
+    /// Minimum time between two requests to the same host.
+    #[serde(default = "default_min_request_interval_ms")]
+    pub min_request_interval_ms: u64,
+    /// Skip URLs the site's robots.txt disallows for `user_agent`.
+    #[serde(default = "default_respect_robots")]
+    pub respect_robots: bool,
+    /// `User-Agent` sent with every request, and matched against robots.txt
+    /// groups by its product token.
+    #[serde(default = "default_user_agent")]
+    pub user_agent: String,
+    /// Per-request timeout.
+    #[serde(default = "default_timeout_secs")]
+    pub timeout_secs: u64,
+    /// Most pages a single `crawl_site` call visits.
+    #[serde(default = "default_max_pages")]
+    pub max_pages: usize,
+    /// Page bodies returned to the model are cut to this many bytes.
+    #[serde(default = "default_max_output_bytes")]
+    pub max_output_bytes: usize,
+}
+
+impl Default for CrawlMcpConfig {
+    fn default() -> Self {
+        Self {
+            cache_dir: None,
+            max_cache_bytes: default_max_cache_bytes(),
+            min_request_interval_ms: default_min_request_interval_ms(),
+            respect_robots: default_respect_robots(),
+            user_agent: default_user_agent(),
+            timeout_secs: default_timeout_secs(),
+            max_pages: default_max_pages(),
+            max_output_bytes: default_max_output_bytes(),
+        }
+    }
+}
+
+fn default_max_cache_bytes() -> u64 {
+    256 * 1024 * 1024
+}
+
+fn default_min_request_interval_ms() -> u64 {
+    1000
+}
+
+fn default_respect_robots() -> bool {
+    true
+}
+
+fn default_user_agent() -> String {
+    "distri-crawl/1.0".to_string()
+}
+
+fn default_timeout_secs() -> u64 {
+    30
+}
+
+fn default_max_pages() -> usize {
+    20
+}
+
+fn default_max_output_bytes() -> usize {
+    64 * 1024
+}
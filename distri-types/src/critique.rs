@@ -0,0 +1,126 @@
+//! Self-critique: `critique` of an agent definition.
+//!
+//! Once an agent with a `critique` produces its final answer, that answer is
+//! a draft. A critique pass — one call to the agent's `model`, or by default
+//! its analysis model — checks the draft against the task and the
+//! `guardrails`. If it finds issues, the agent gets one more turn to revise
+//! the draft before finishing; otherwise the draft stands. The draft and the
+//! critique are stored in the scratchpad as the `critique_draft` and
+//! `critique` steps, so a run can be inspected afterwards.
+//!
+//! ```toml
+//! [critique]
+//! model = "analysis"
+//! guardrails = [
+//!   "Never promise a delivery date.",
+//!   "Cite the source of every figure.",
+//! ]
+//! ```
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Scratchpad step of the draft answer.
+pub const CRITIQUE_DRAFT_STEP: &str = "critique_draft";
+/// Scratchpad step of the critique.
+pub const CRITIQUE_STEP: &str = "critique";
+
+/// `critique` of an agent definition.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct CritiqueConfig {
+    #[serde(default)]
+    pub model: CritiqueModel,
+    /// Rules the answer must follow, besides answering the task.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub guardrails: Vec<String>,
+}
+
+/// Which of the agent's models critiques the draft.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CritiqueModel {
+    /// `analysis_model_settings`, falling back to `model_settings`.
+    #[default]
+    Analysis,
+    /// `model_settings`.
+    Same,
+}
+
+/// The critique's reply.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct CritiqueVerdict {
+    /// Whether the draft can be returned as is.
+    #[serde(default)]
+    pub approved: bool,
+    /// What the revision must fix.
+    #[serde(default)]
+    pub issues: Vec<String>,
+}
+
+impl CritiqueVerdict {
+    /// Read a verdict from the critique's reply: a JSON object, or text
+    /// holding one (possibly in a code fence).
+    pub fn from_value(value: &Value) -> Option<Self> {
+        match value {
+            Value::Object(_) => serde_json::from_value(value.clone()).ok(),
+            Value::String(text) => {
+                let start = text.find('{')?;
+                let end = text.rfind('}')?;
+                serde_json::from_str(text.get(start..=end)?).ok()
+            }
+            _ => None,
+        }
+    }
+
+    /// Whether the agent revises the draft: it was not approved, or issues
+    /// were raised anyway.
+    pub fn needs_revision(&self) -> bool {
+        !self.approved || !self.issues.is_empty()
+    }
+
+    /// The `critique` scratchpad entry, which the agent reads when revising.
+    pub fn to_note(&self) -> String {
+        if !self.needs_revision() {
+            return "Critique: the draft answer was approved.".to_string();
+        }
+        let mut note = "Critique of the draft answer. Revise it once to fix these issues, \
+                        then give the final answer:"
+            .to_string();
+        if self.issues.is_empty() {
+            note.push_str("\n- The draft does not fully answer the task.");
+        }
+        for issue in &self.issues {
+            note.push_str(&format!("\n- {}", issue));
+        }
+        note
+    }
+}
+
+/// The prompt of the critique pass: the task, the rules and the draft.
+pub fn critique_brief(task: &str, draft: &str, guardrails: &[String]) -> String {
+    let mut brief = format!(
+        "Review a draft answer before it is sent. Check that it answers the task completely \
+         and correctly{}.\n\n<task>\n{}\n</task>\n",
+        if guardrails.is_empty() {
+            ""
+        } else {
+            " and follows every guardrail"
+        },
+        task
+    );
+    if !guardrails.is_empty() {
+        brief.push_str("\n<guardrails>\n");
+        for guardrail in guardrails {
+            brief.push_str(&format!("- {}\n", guardrail));
+        }
+        brief.push_str("</guardrails>\n");
+    }
+    brief.push_str(&format!("\n<draft>\n{}\n</draft>\n", draft));
+    brief.push_str(
+        "\nReply with only a JSON object: {\"approved\": <true if the draft can be sent as is>, \
+         \"issues\": [\"<what must change>\", ...]}",
+    );
+    brief
+}
@@ -0,0 +1,545 @@
+//! Date and time awareness: `datetime` of an agent definition.
+//!
+//! An agent with a `datetime` sees the current date and time in the runtime
+//! layer of its system prompt on every step, and with a `business_calendar`
+//! whether today is a business day and when the next one is. The
+//! `datetime_math` and `timezone_convert` builtin tools work in the same
+//! timezone and calendar.
+//!
+//! The timezone is the `timezone` of the user's profile (see
+//! [`crate::user_profile`]) when it names an IANA zone, else the agent's
+//! `timezone`, else UTC.
+//!
+//! ```toml
+//! [datetime]
+//! timezone = "Europe/Berlin"
+//!
+//! [datetime.business_calendar]
+//! working_days = ["mon", "tue", "wed", "thu", "fri"]
+//! hours = "09:00-17:00"
+//! holidays = ["2026-12-25", "2026-12-26"]
+//! ```
+
+use chrono::{
+    DateTime, Datelike, Duration, NaiveDate, NaiveDateTime, NaiveTime, SecondsFormat, TimeZone,
+    Utc, Weekday,
+};
+pub use chrono_tz::Tz;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+/// How far ahead holidays are listed in the prompt.
+const UPCOMING_HOLIDAY_DAYS: i64 = 30;
+/// Longest run of non-business days a calendar may have.
+const MAX_CLOSED_DAYS: usize = 366;
+
+/// `datetime` of an agent definition.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct DateTimeConfig {
+    /// IANA timezone (`Europe/Berlin`) used when the user profile names
+    /// none. Defaults to UTC.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timezone: Option<String>,
+    /// Working days, hours and holidays.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub business_calendar: Option<BusinessCalendar>,
+}
+
+/// Business days of a [`DateTimeConfig`], in its timezone.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct BusinessCalendar {
+    /// Weekdays that are business days (`mon`, `tuesday`, ...). Defaults to
+    /// Monday to Friday.
+    #[serde(default = "default_working_days")]
+    #[schemars(with = "Vec<String>")]
+    pub working_days: Vec<Weekday>,
+    /// Business hours as `HH:MM-HH:MM`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hours: Option<String>,
+    /// Dates (`YYYY-MM-DD`) that are not business days.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[schemars(with = "Vec<String>")]
+    pub holidays: Vec<NaiveDate>,
+}
+
+fn default_working_days() -> Vec<Weekday> {
+    vec![
+        Weekday::Mon,
+        Weekday::Tue,
+        Weekday::Wed,
+        Weekday::Thu,
+        Weekday::Fri,
+    ]
+}
+
+impl Default for BusinessCalendar {
+    fn default() -> Self {
+        Self {
+            working_days: default_working_days(),
+            hours: None,
+            holidays: Vec::new(),
+        }
+    }
+}
+
+/// Parse an IANA timezone name.
+pub fn parse_timezone(name: &str) -> Result<Tz, String> {
+    name.trim().parse::<Tz>().map_err(|_| {
+        format!(
+            "unknown timezone '{}': use an IANA name such as Europe/Berlin",
+            name
+        )
+    })
+}
+
+/// The `timezone` of a `user_profile` session value, when it is an object
+/// that has one.
+pub fn profile_timezone(profile: &Value) -> Option<&str> {
+    profile.get("timezone").and_then(Value::as_str)
+}
+
+impl DateTimeConfig {
+    /// Check the config (used when the agent definition is validated).
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(timezone) = &self.timezone {
+            parse_timezone(timezone)?;
+        }
+        if let Some(calendar) = &self.business_calendar {
+            calendar.validate()?;
+        }
+        Ok(())
+    }
+
+    /// The timezone of a run: the user profile's when it names a valid zone,
+    /// else the configured one, else UTC.
+    pub fn resolve_timezone(&self, profile_timezone: Option<&str>) -> Tz {
+        profile_timezone
+            .into_iter()
+            .chain(self.timezone.as_deref())
+            .find_map(|name| parse_timezone(name).ok())
+            .unwrap_or(Tz::UTC)
+    }
+
+    /// The runtime layer's text for `now` in `tz`.
+    pub fn describe(&self, now: DateTime<Utc>, tz: Tz) -> String {
+        let local = now.with_timezone(&tz);
+        let mut lines = vec![format!(
+            "{} ({}), {} (UTC{})",
+            local.format("%Y-%m-%d %H:%M"),
+            local.format("%A"),
+            tz.name(),
+            local.format("%:z")
+        )];
+        if let Some(calendar) = &self.business_calendar {
+            lines.extend(calendar.describe(local.naive_local()));
+        }
+        lines.join("\n")
+    }
+}
+
+impl BusinessCalendar {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.working_days.is_empty() {
+            return Err("business_calendar.working_days must name at least one day".to_string());
+        }
+        self.business_hours()?;
+        Ok(())
+    }
+
+    /// Opening and closing time, when `hours` is set.
+    pub fn business_hours(&self) -> Result<Option<(NaiveTime, NaiveTime)>, String> {
+        let Some(hours) = &self.hours else {
+            return Ok(None);
+        };
+        let invalid = || {
+            format!(
+                "business_calendar.hours must be HH:MM-HH:MM, got '{}'",
+                hours
+            )
+        };
+        let (open, close) = hours.split_once('-').ok_or_else(invalid)?;
+        let open = NaiveTime::parse_from_str(open.trim(), "%H:%M").map_err(|_| invalid())?;
+        let close = NaiveTime::parse_from_str(close.trim(), "%H:%M").map_err(|_| invalid())?;
+        if open >= close {
+            return Err(format!(
+                "business_calendar.hours must open before they close, got '{}'",
+                hours
+            ));
+        }
+        Ok(Some((open, close)))
+    }
+
+    pub fn is_business_day(&self, date: NaiveDate) -> bool {
+        self.working_days.contains(&date.weekday()) && !self.holidays.contains(&date)
+    }
+
+    /// Whether `local` falls on a business day, within the business hours
+    /// when they are set.
+    pub fn is_open(&self, local: NaiveDateTime) -> bool {
+        if !self.is_business_day(local.date()) {
+            return false;
+        }
+        match self.business_hours() {
+            Ok(Some((open, close))) => local.time() >= open && local.time() < close,
+            _ => true,
+        }
+    }
+
+    /// First business day after `date`.
+    pub fn next_business_day(&self, date: NaiveDate) -> Option<NaiveDate> {
+        date.iter_days()
+            .skip(1)
+            .take(MAX_CLOSED_DAYS)
+            .find(|day| self.is_business_day(*day))
+    }
+
+    /// `date` moved by `days` business days; negative moves back. A move
+    /// from a non-business day counts from that day, so one business day
+    /// after a Saturday is the Monday.
+    pub fn add_business_days(&self, date: NaiveDate, days: i64) -> Result<NaiveDate, String> {
+        let step = if days < 0 { -1 } else { 1 };
+        let mut current = date;
+        for _ in 0..days.unsigned_abs() {
+            let mut closed = 0;
+            loop {
+                current = current
+                    .checked_add_signed(Duration::days(step))
+                    .ok_or("date is out of range")?;
+                if self.is_business_day(current) {
+                    break;
+                }
+                closed += 1;
+                if closed > MAX_CLOSED_DAYS {
+                    return Err("the business calendar has no business days".to_string());
+                }
+            }
+        }
+        Ok(current)
+    }
+
+    /// Business days after `from` up to and including `to`; negative when
+    /// `to` is earlier.
+    pub fn business_days_between(&self, from: NaiveDate, to: NaiveDate) -> i64 {
+        let (start, end, sign) = if to >= from {
+            (from, to, 1)
+        } else {
+            (to, from, -1)
+        };
+        let count = start
+            .iter_days()
+            .skip(1)
+            .take_while(|day| *day <= end)
+            .filter(|day| self.is_business_day(*day))
+            .count() as i64;
+        sign * count
+    }
+
+    fn describe(&self, local: NaiveDateTime) -> Vec<String> {
+        let today = local.date();
+        let days = self
+            .working_days
+            .iter()
+            .map(|day| day.to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+        let hours = self.business_hours().ok().flatten();
+        let mut lines = vec![match hours {
+            Some((open, close)) => format!(
+                "Business days: {}, {}-{}.",
+                days,
+                open.format("%H:%M"),
+                close.format("%H:%M")
+            ),
+            None => format!("Business days: {}.", days),
+        }];
+
+        lines.push(if self.holidays.contains(&today) {
+            "Today is a holiday.".to_string()
+        } else if !self.is_business_day(today) {
+            "Today is not a business day.".to_string()
+        } else if hours.is_some() && !self.is_open(local) {
+            "Today is a business day; it is outside business hours now.".to_string()
+        } else if hours.is_some() {
+            "Today is a business day; it is within business hours now.".to_string()
+        } else {
+            "Today is a business day.".to_string()
+        });
+        if let Some(next) = self.next_business_day(today) {
+            lines.push(format!(
+                "Next business day: {} ({}).",
+                next.format("%Y-%m-%d"),
+                next.format("%A")
+            ));
+        }
+
+        let mut upcoming: Vec<&NaiveDate> = self
+            .holidays
+            .iter()
+            .filter(|day| {
+                let ahead = (**day - today).num_days();
+                ahead > 0 && ahead <= UPCOMING_HOLIDAY_DAYS
+            })
+            .collect();
+        upcoming.sort();
+        if !upcoming.is_empty() {
+            lines.push(format!(
+                "Upcoming holidays: {}.",
+                upcoming
+                    .iter()
+                    .map(|day| day.format("%Y-%m-%d").to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ));
+        }
+        lines
+    }
+}
+
+/// Parse `now`, an RFC 3339 timestamp, `YYYY-MM-DD HH:MM[:SS]` (or with a
+/// `T`) or `YYYY-MM-DD` (midnight). Times without an offset are in `tz`.
+pub fn parse_datetime(text: &str, tz: Tz, now: DateTime<Utc>) -> Result<DateTime<Tz>, String> {
+    let text = text.trim();
+    if text.is_empty() || text.eq_ignore_ascii_case("now") {
+        return Ok(now.with_timezone(&tz));
+    }
+    if let Ok(parsed) = DateTime::parse_from_rfc3339(text) {
+        return Ok(parsed.with_timezone(&tz));
+    }
+    let naive = [
+        "%Y-%m-%d %H:%M:%S",
+        "%Y-%m-%dT%H:%M:%S",
+        "%Y-%m-%d %H:%M",
+        "%Y-%m-%dT%H:%M",
+    ]
+    .iter()
+    .find_map(|format| NaiveDateTime::parse_from_str(text, format).ok())
+    .or_else(|| {
+        NaiveDate::parse_from_str(text, "%Y-%m-%d")
+            .ok()
+            .map(|date| date.and_time(NaiveTime::MIN))
+    })
+    .ok_or_else(|| {
+        format!(
+            "cannot parse '{}': use RFC 3339, YYYY-MM-DD HH:MM[:SS], YYYY-MM-DD or now",
+            text
+        )
+    })?;
+    localize(naive, tz)
+}
+
+/// `naive` read in `tz`. A time repeated by a DST change is its earlier
+/// instance; a time skipped by one is an error.
+pub fn localize(naive: NaiveDateTime, tz: Tz) -> Result<DateTime<Tz>, String> {
+    tz.from_local_datetime(&naive).earliest().ok_or_else(|| {
+        format!(
+            "{} does not exist in {} (skipped by a daylight saving change)",
+            naive.format("%Y-%m-%d %H:%M"),
+            tz.name()
+        )
+    })
+}
+
+/// A date and time as the tools report it.
+pub fn instant_json(datetime: &DateTime<Tz>) -> Value {
+    json!({
+        "datetime": datetime.to_rfc3339_opts(SecondsFormat::Secs, false),
+        "date": datetime.format("%Y-%m-%d").to_string(),
+        "time": datetime.format("%H:%M:%S").to_string(),
+        "weekday": datetime.format("%A").to_string(),
+        "timezone": datetime.timezone().name(),
+        "abbreviation": datetime.format("%Z").to_string(),
+        "utc_offset": datetime.format("%:z").to_string(),
+    })
+}
+
+/// Operation of a `datetime_math` call.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DateTimeOperation {
+    Add,
+    Subtract,
+    Diff,
+}
+
+/// Input of a `datetime_math` call.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DateTimeMathInput {
+    pub operation: DateTimeOperation,
+    /// Start of the operation; defaults to now.
+    #[serde(default)]
+    pub datetime: Option<String>,
+    /// End of a `diff`.
+    #[serde(default)]
+    pub to: Option<String>,
+    /// Timezone of the input and result; defaults to the run's.
+    #[serde(default)]
+    pub timezone: Option<String>,
+    #[serde(default)]
+    pub years: i32,
+    #[serde(default)]
+    pub months: i32,
+    #[serde(default)]
+    pub weeks: i64,
+    #[serde(default)]
+    pub days: i64,
+    #[serde(default)]
+    pub business_days: i64,
+    #[serde(default)]
+    pub hours: i64,
+    #[serde(default)]
+    pub minutes: i64,
+    #[serde(default)]
+    pub seconds: i64,
+}
+
+/// Run a `datetime_math` call. Calendar units (years, months, weeks, days,
+/// business days) keep the local time of day; hours, minutes and seconds
+/// are elapsed time. Business days follow `calendar`, or Monday to Friday
+/// without one.
+pub fn datetime_math(
+    input: &DateTimeMathInput,
+    tz: Tz,
+    calendar: Option<&BusinessCalendar>,
+    now: DateTime<Utc>,
+) -> Result<Value, String> {
+    let tz = match &input.timezone {
+        Some(name) => parse_timezone(name)?,
+        None => tz,
+    };
+    let default_calendar = BusinessCalendar::default();
+    let calendar = calendar.unwrap_or(&default_calendar);
+    let start = parse_datetime(input.datetime.as_deref().unwrap_or("now"), tz, now)?;
+
+    if input.operation == DateTimeOperation::Diff {
+        let to = input
+            .to
+            .as_deref()
+            .ok_or("diff needs `to`, the end date and time")?;
+        let end = parse_datetime(to, tz, now)?;
+        let elapsed = end.signed_duration_since(start);
+        return Ok(json!({
+            "from": instant_json(&start),
+            "to": instant_json(&end),
+            "seconds": elapsed.num_seconds(),
+            "hours": (elapsed.num_seconds() as f64 / 36.0).round() / 100.0,
+            "calendar_days": (end.date_naive() - start.date_naive()).num_days(),
+            "business_days": calendar.business_days_between(start.date_naive(), end.date_naive()),
+            "duration": format_duration(elapsed),
+        }));
+    }
+
+    let sign: i64 = if input.operation == DateTimeOperation::Subtract {
+        -1
+    } else {
+        1
+    };
+    let out_of_range = || "the result is out of range".to_string();
+    let mut local = start.naive_local();
+    let months = sign * (i64::from(input.years) * 12 + i64::from(input.months));
+    if months != 0 {
+        let shift =
+            chrono::Months::new(u32::try_from(months.unsigned_abs()).map_err(|_| out_of_range())?);
+        local = if months > 0 {
+            local.checked_add_months(shift)
+        } else {
+            local.checked_sub_months(shift)
+        }
+        .ok_or_else(out_of_range)?;
+    }
+    let days = sign * (input.weeks * 7 + input.days);
+    local = local
+        .checked_add_signed(Duration::try_days(days).ok_or_else(out_of_range)?)
+        .ok_or_else(out_of_range)?;
+    if input.business_days != 0 {
+        let date = calendar.add_business_days(local.date(), sign * input.business_days)?;
+        local = date.and_time(local.time());
+    }
+    let elapsed = [
+        Duration::try_hours(input.hours),
+        Duration::try_minutes(input.minutes),
+        Duration::try_seconds(input.seconds),
+    ]
+    .into_iter()
+    .try_fold(Duration::zero(), |total, part| {
+        part.and_then(|part| total.checked_add(&part))
+    })
+    .ok_or_else(out_of_range)?;
+    let result = localize(local, tz)?
+        .checked_add_signed(elapsed * sign as i32)
+        .ok_or_else(out_of_range)?;
+
+    let mut output = instant_json(&result);
+    output["is_business_day"] = json!(calendar.is_business_day(result.date_naive()));
+    Ok(output)
+}
+
+/// `2 days, 4 hours, 30 minutes`; negative durations are prefixed with `-`.
+fn format_duration(duration: Duration) -> String {
+    let total = duration.num_seconds();
+    let seconds = total.unsigned_abs();
+    let parts: Vec<String> = [
+        (seconds / 86_400, "day"),
+        (seconds % 86_400 / 3_600, "hour"),
+        (seconds % 3_600 / 60, "minute"),
+        (seconds % 60, "second"),
+    ]
+    .into_iter()
+    .filter(|(count, _)| *count > 0)
+    .map(|(count, unit)| format!("{} {}{}", count, unit, if count == 1 { "" } else { "s" }))
+    .collect();
+    match (parts.is_empty(), total < 0) {
+        (true, _) => "0 seconds".to_string(),
+        (false, true) => format!("-{}", parts.join(", ")),
+        (false, false) => parts.join(", "),
+    }
+}
+
+/// Target zones of a `timezone_convert` call: one name or several.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum TimezoneTargets {
+    One(String),
+    Many(Vec<String>),
+}
+
+/// Input of a `timezone_convert` call.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TimezoneConvertInput {
+    /// Date and time to convert; defaults to now.
+    #[serde(default)]
+    pub datetime: Option<String>,
+    /// Timezone of a `datetime` without an offset; defaults to the run's.
+    #[serde(default)]
+    pub from: Option<String>,
+    pub to: TimezoneTargets,
+}
+
+/// Run a `timezone_convert` call.
+pub fn timezone_convert(
+    input: &TimezoneConvertInput,
+    tz: Tz,
+    now: DateTime<Utc>,
+) -> Result<Value, String> {
+    let from = match &input.from {
+        Some(name) => parse_timezone(name)?,
+        None => tz,
+    };
+    let source = parse_datetime(input.datetime.as_deref().unwrap_or("now"), from, now)?;
+    let targets = match &input.to {
+        TimezoneTargets::One(name) => vec![name.clone()],
+        TimezoneTargets::Many(names) => names.clone(),
+    };
+    if targets.is_empty() {
+        return Err("`to` must name at least one timezone".to_string());
+    }
+    let converted = targets
+        .iter()
+        .map(|name| parse_timezone(name).map(|zone| instant_json(&source.with_timezone(&zone))))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(json!({ "source": instant_json(&source), "converted": converted }))
+}
@@ -0,0 +1,18 @@
+//! Sample data for local development (`distri dev seed`).
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// What `POST /dev/seed` wrote. Seeding is idempotent: items that already
+/// exist are left untouched and listed under `skipped`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct DevSeedSummary {
+    pub agents: Vec<String>,
+    /// Ids of the threads created.
+    pub threads: Vec<String>,
+    pub prompt_templates: Vec<String>,
+    /// Keys of the placeholder secrets created.
+    pub secrets: Vec<String>,
+    /// Threads, templates and secrets that already existed.
+    pub skipped: Vec<String>,
+}
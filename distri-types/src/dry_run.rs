@@ -0,0 +1,75 @@
+//! Dry runs: what an agent would do, without its side effects.
+//!
+//! A run with `dry_run` in its metadata executes tools without side effects
+//! as usual and intercepts the rest. An intercepted call gets a simulated
+//! result and is recorded as a `side_effect_simulated` event; output sinks
+//! are not delivered. Just before `run_finished`, a `dry_run_report` event
+//! lists every side effect the run would have had.
+//!
+//! Tools declare their side effects with
+//! [`Tool::side_effect`](crate::Tool::side_effect). A tool that does not
+//! declare them is intercepted unless it is one of the built-in read-only
+//! tools; external tools, run by the client, are always intercepted.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Prefix of the `tool` of a side effect of an output sink.
+pub const OUTPUT_SINK_PREFIX: &str = "output_sink:";
+
+/// A side effect a dry run intercepted.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SimulatedSideEffect {
+    pub agent_id: String,
+    pub task_id: String,
+    /// The tool called, or `output_sink:<sink>` for an output sink.
+    pub tool: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_call_id: Option<String>,
+    /// Input of the call; the delivered content for an output sink.
+    pub input: Value,
+}
+
+/// The side effects of a dry run, in the order they were intercepted.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DryRunReport {
+    pub side_effects: Vec<SimulatedSideEffect>,
+}
+
+impl DryRunReport {
+    /// Markdown list of the side effects, one line each.
+    pub fn to_markdown(&self) -> String {
+        if self.side_effects.is_empty() {
+            return "Dry run: no side effects would have occurred.".to_string();
+        }
+        let count = self.side_effects.len();
+        let mut out = format!(
+            "Dry run: {} side effect{} would have occurred:",
+            count,
+            if count == 1 { "" } else { "s" }
+        );
+        for effect in &self.side_effects {
+            out.push_str(&format!(
+                "\n- `{}` ({}): {}",
+                effect.tool,
+                effect.agent_id,
+                compact(&effect.input)
+            ));
+        }
+        out
+    }
+}
+
+/// `input` on one line, cut to 200 characters.
+fn compact(input: &Value) -> String {
+    const MAX_CHARS: usize = 200;
+    let text = match input {
+        Value::String(text) => text.replace('\n', " "),
+        other => other.to_string(),
+    };
+    if text.chars().count() <= MAX_CHARS {
+        return text;
+    }
+    let cut: String = text.chars().take(MAX_CHARS).collect();
+    format!("{}…", cut)
+}
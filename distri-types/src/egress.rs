@@ -0,0 +1,187 @@
+//! Where agents may connect: `egress` of the server config.
+//!
+//! Outbound requests made on an agent's behalf — plugin tools (through
+//! [`ToolContext::check_network`](crate::ToolContext::check_network)),
+//! inline HTTP tools, the browser tools and the built-in crawl and search
+//! servers — are checked against domain rules before they are sent.
+//! `deny` always wins; a non-empty `allow` list admits only the hosts it
+//! matches. `agents.<name>` adds rules for one agent on top of the global
+//! ones, so an agent can be narrowed but never widened. The shared crawl and
+//! search servers serve every agent and apply the global rules only.
+//!
+//! ```yaml
+//! egress:
+//!   deny: ["169.254.169.254", "*.internal"]
+//!   agents:
+//!     researcher:
+//!       allow: ["*.wikipedia.org", "arxiv.org"]
+//! ```
+//!
+//! Patterns are host names; `*.example.com` also matches `example.com` and
+//! its subdomains, and `*` matches any host. A blocked request fails with
+//! [`EgressDenied`], which is reported to the model as a structured
+//! `egress_denied` result.
+
+use std::collections::BTreeMap;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::CapabilityDenied;
+use crate::plugin_capabilities::{host_matches, url_host};
+
+/// `egress` section of the server config.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct EgressPolicyConfig {
+    /// Hosts every agent may reach. Empty allows any host not denied.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allow: Vec<String>,
+    /// Hosts no agent may reach.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub deny: Vec<String>,
+    /// Further rules for the named agent, applied after the global ones.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub agents: BTreeMap<String, EgressRules>,
+}
+
+/// Allow and deny lists of one scope.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct EgressRules {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allow: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub deny: Vec<String>,
+}
+
+impl EgressRules {
+    /// Why `host` is blocked, if it is.
+    fn blocks(&self, host: &str) -> Option<String> {
+        if let Some(pattern) = self.deny.iter().find(|p| host_matches(p, host)) {
+            return Some(format!("matches deny rule '{}'", pattern.trim()));
+        }
+        if !self.allow.is_empty() && !self.allow.iter().any(|p| host_matches(p, host)) {
+            return Some("not in the allow list".to_string());
+        }
+        None
+    }
+
+    fn validate(&self, scope: &str) -> Result<(), String> {
+        for pattern in self.allow.iter().chain(&self.deny) {
+            let pattern = pattern.trim();
+            let host = pattern.strip_prefix("*.").unwrap_or(pattern);
+            // IPv6 literals keep their brackets, as in URLs: `[::1]`.
+            let separators: &[char] = if host.starts_with('[') {
+                &['/', '?', '#', ' ']
+            } else {
+                &['/', ':', '?', '#', ' ']
+            };
+            if host.is_empty() || host.contains(separators) {
+                return Err(format!(
+                    "egress {}: '{}' is not a host pattern (use e.g. api.example.com or *.example.com)",
+                    scope, pattern
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl EgressPolicyConfig {
+    /// Fails on a pattern that is not a host name, such as a full URL.
+    pub fn validate(&self) -> Result<(), String> {
+        self.global().validate("global")?;
+        for (agent, rules) in &self.agents {
+            rules.validate(&format!("agent '{}'", agent))?;
+        }
+        Ok(())
+    }
+
+    fn global(&self) -> EgressRules {
+        EgressRules {
+            allow: self.allow.clone(),
+            deny: self.deny.clone(),
+        }
+    }
+}
+
+/// The checks of an [`EgressPolicyConfig`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EgressPolicy {
+    global: EgressRules,
+    agents: BTreeMap<String, EgressRules>,
+}
+
+impl EgressPolicy {
+    pub fn new(config: &EgressPolicyConfig) -> Self {
+        Self {
+            global: config.global(),
+            agents: config.agents.clone(),
+        }
+    }
+
+    /// Whether `agent` may connect to `url` (or a bare host name). `None`
+    /// checks the global rules only.
+    pub fn check(&self, agent: Option<&str>, url: &str) -> Result<(), EgressDenied> {
+        let host = url_host(url);
+        let agent_rules = agent.and_then(|a| self.agents.get(a));
+        let reason = self
+            .global
+            .blocks(&host)
+            .or_else(|| agent_rules.and_then(|rules| rules.blocks(&host)));
+        match reason {
+            None => Ok(()),
+            Some(reason) => Err(EgressDenied {
+                agent: agent.map(str::to_string),
+                host,
+                url: url.to_string(),
+                reason,
+            }),
+        }
+    }
+}
+
+/// A request the egress policy blocks.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, thiserror::Error)]
+#[error("egress to '{host}' is blocked: {reason}")]
+pub struct EgressDenied {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub agent: Option<String>,
+    pub host: String,
+    pub url: String,
+    pub reason: String,
+}
+
+impl EgressDenied {
+    /// The tool result reported to the model.
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "error": "egress_denied",
+            "host": self.host,
+            "url": self.url,
+            "reason": self.reason,
+            "message": self.to_string(),
+        })
+    }
+}
+
+/// Why a tool may not connect to a URL: its plugin lacks the capability, or
+/// the egress policy blocks it.
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum NetworkDenied {
+    #[error(transparent)]
+    Capability(#[from] CapabilityDenied),
+    #[error(transparent)]
+    Egress(#[from] EgressDenied),
+}
+
+impl NetworkDenied {
+    /// The tool result reported to the model.
+    pub fn to_json(&self) -> serde_json::Value {
+        match self {
+            NetworkDenied::Capability(denied) => denied.to_json(),
+            NetworkDenied::Egress(denied) => denied.to_json(),
+        }
+    }
+}
@@ -0,0 +1,140 @@
+//! Shared embeddings: settings of the server's embedding service and the
+//! request/response of `POST /embeddings`.
+//!
+//! Every feature that needs vectors goes through one service (see
+//! `distri_core::llm::embeddings`). It sends inputs to an OpenAI-compatible
+//! `/embeddings` endpoint in batches of `batch_size` and keeps each vector
+//! in an on-disk cache keyed by the model and a hash of the text, so the
+//! same text is only embedded once.
+//!
+//! ```yaml
+//! embeddings:
+//!   provider: { name: openai }
+//!   model: text-embedding-3-small
+//!   dimensions: 512
+//! ```
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::ModelProvider;
+
+/// `embeddings` section of the server config.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct EmbeddingsConfig {
+    /// Provider serving the `/embeddings` endpoint. Any OpenAI-compatible
+    /// provider works; Anthropic has no embeddings API.
+    #[serde(default)]
+    pub provider: ModelProvider,
+    /// Model used when a request names none.
+    #[serde(default = "default_model")]
+    pub model: String,
+    /// Length of the returned vectors, for models that can shorten them.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dimensions: Option<u32>,
+    /// Most inputs sent to the provider in one request.
+    #[serde(default = "default_batch_size")]
+    pub batch_size: usize,
+    /// Directory of the vector cache. Relative paths resolve against the
+    /// workspace; `.distri/cache/embeddings` when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_dir: Option<String>,
+    /// Set to `false` to always ask the provider.
+    #[serde(default = "default_cache")]
+    pub cache: bool,
+    /// Embed artifacts as they are saved, for the `semantic_search_artifacts`
+    /// tool. Every saved text artifact costs an embeddings request.
+    #[serde(default)]
+    pub index_artifacts: bool,
+}
+
+impl Default for EmbeddingsConfig {
+    fn default() -> Self {
+        Self {
+            provider: ModelProvider::default(),
+            model: default_model(),
+            dimensions: None,
+            batch_size: default_batch_size(),
+            cache_dir: None,
+            cache: default_cache(),
+            index_artifacts: false,
+        }
+    }
+}
+
+fn default_model() -> String {
+    "text-embedding-3-small".to_string()
+}
+
+fn default_batch_size() -> usize {
+    96
+}
+
+fn default_cache() -> bool {
+    true
+}
+
+/// Text to embed: one string or a list of them.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema, ToSchema)]
+#[serde(untagged)]
+pub enum EmbeddingInput {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl EmbeddingInput {
+    pub fn into_vec(self) -> Vec<String> {
+        match self {
+            EmbeddingInput::One(text) => vec![text],
+            EmbeddingInput::Many(texts) => texts,
+        }
+    }
+}
+
+/// Body of `POST /embeddings`, shaped like OpenAI's.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema, ToSchema)]
+pub struct EmbeddingRequest {
+    pub input: EmbeddingInput,
+    /// Defaults to the configured model.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    /// Defaults to the configured dimensions.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dimensions: Option<u32>,
+}
+
+impl EmbeddingRequest {
+    pub fn new(input: Vec<String>) -> Self {
+        Self {
+            input: EmbeddingInput::Many(input),
+            model: None,
+            dimensions: None,
+        }
+    }
+}
+
+/// One vector of an [`EmbeddingResponse`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema, ToSchema)]
+pub struct Embedding {
+    /// Position of the input it embeds.
+    pub index: usize,
+    pub embedding: Vec<f32>,
+}
+
+/// Vectors of an [`EmbeddingRequest`], in input order.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema, ToSchema)]
+pub struct EmbeddingResponse {
+    pub model: String,
+    pub data: Vec<Embedding>,
+    /// How many of the vectors came from the cache.
+    #[serde(default)]
+    pub cached: usize,
+}
+
+impl EmbeddingResponse {
+    pub fn into_vectors(self) -> Vec<Vec<f32>> {
+        self.data.into_iter().map(|e| e.embedding).collect()
+    }
+}
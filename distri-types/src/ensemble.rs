@@ -0,0 +1,140 @@
+//! Ensemble execution: `ensemble` of an agent definition.
+//!
+//! An agent with an `ensemble` does not answer the task itself. Each of its
+//! `branches` — another agent, optionally on a different model — gets the
+//! task in parallel as a child task of the run. The `judge` agent then reads
+//! the answers and, depending on `mode`, picks the best one or merges them.
+//! The judge's choice and rationale are recorded in an `ensemble_judged`
+//! event; its answer is the run's final answer.
+//!
+//! ```toml
+//! [ensemble]
+//! judge = "reviewer"
+//! mode = "merge"
+//! branches = [
+//!   { agent = "analyst" },
+//!   { agent = "analyst", model = "claude-sonnet-4-5" },
+//!   { agent = "skeptic" },
+//! ]
+//! ```
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// `ensemble` of an agent definition.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct EnsembleConfig {
+    /// Agents the task is sent to, in parallel.
+    pub branches: Vec<EnsembleBranch>,
+    /// Agent that settles the answer.
+    pub judge: String,
+    #[serde(default)]
+    pub mode: EnsembleMode,
+}
+
+/// One agent of an ensemble.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct EnsembleBranch {
+    pub agent: String,
+    /// Model the agent runs on instead of its own.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+}
+
+impl EnsembleBranch {
+    /// `agent`, or `agent (model)`.
+    pub fn label(&self) -> String {
+        match &self.model {
+            Some(model) => format!("{} ({})", self.agent, model),
+            None => self.agent.clone(),
+        }
+    }
+}
+
+/// What the judge does with the branches' answers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum EnsembleMode {
+    /// Return the best answer as is.
+    #[default]
+    Pick,
+    /// Combine the answers into one.
+    Merge,
+}
+
+impl EnsembleConfig {
+    /// Checks an ensemble of the agent `agent`: an agent can be neither its
+    /// own branch nor its own judge.
+    pub fn validate(&self, agent: &str) -> Result<(), String> {
+        if self.branches.is_empty() {
+            return Err("ensemble needs at least one branch".to_string());
+        }
+        if self.judge == agent || self.branches.iter().any(|b| b.agent == agent) {
+            return Err(format!(
+                "agent '{}' cannot be a branch or the judge of its own ensemble",
+                agent
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// The judge's reply.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct EnsembleVerdict {
+    /// Number (from 1) of the chosen candidate; `None` for a merged answer.
+    #[serde(default)]
+    pub selected: Option<usize>,
+    #[serde(default)]
+    pub rationale: Option<String>,
+    /// The final answer. When picking, defaults to the chosen candidate's.
+    #[serde(default)]
+    pub answer: Option<String>,
+}
+
+impl EnsembleVerdict {
+    /// Read a verdict from the judge's final result: a JSON object, or text
+    /// holding one (possibly in a code fence).
+    pub fn from_value(value: &Value) -> Option<Self> {
+        match value {
+            Value::Object(_) => serde_json::from_value(value.clone()).ok(),
+            Value::String(text) => {
+                let start = text.find('{')?;
+                let end = text.rfind('}')?;
+                serde_json::from_str(text.get(start..=end)?).ok()
+            }
+            _ => None,
+        }
+    }
+}
+
+/// The task given to the judge: the original `task` and each candidate
+/// answer, numbered from 1, as `(branch label, answer)`.
+pub fn judge_brief(mode: EnsembleMode, task: &str, candidates: &[(String, String)]) -> String {
+    let instruction = match mode {
+        EnsembleMode::Pick => "Choose the best answer.",
+        EnsembleMode::Merge => {
+            "Combine them into one answer, keeping what each gets right and dropping what is wrong."
+        }
+    };
+    let mut brief = format!(
+        "Several agents answered the task below independently. {}\n\n<task>\n{}\n</task>\n",
+        instruction, task
+    );
+    for (i, (label, answer)) in candidates.iter().enumerate() {
+        brief.push_str(&format!(
+            "\n<candidate number=\"{}\" agent=\"{}\">\n{}\n</candidate>\n",
+            i + 1,
+            label,
+            answer
+        ));
+    }
+    brief.push_str(
+        "\nReply with only a JSON object: {\"selected\": <number of the chosen candidate, \
+         or null for a merged answer>, \"rationale\": \"<why>\", \"answer\": \"<the final answer>\"}",
+    );
+    brief
+}
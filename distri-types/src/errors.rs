@@ -8,6 +8,12 @@ pub enum AgentError {
     ToolExecutionFailed(String),
     #[error("Tool execution error: {0}")]
     ToolExecution(String),
+    /// The egress policy blocked a request the tool was about to send.
+    #[error(transparent)]
+    EgressDenied(#[from] crate::egress::EgressDenied),
+    /// The agent's browser policy blocked a browser command.
+    #[error(transparent)]
+    BrowserPolicy(#[from] crate::browser::BrowserPolicyViolation),
     #[error("Tool response processing failed: {0}")]
     ToolResponseProcessing(String),
     #[error("Authentication required: {0}")]
@@ -38,6 +44,10 @@ pub enum AgentError {
     XmlParsingFailed(String, String),
     #[error("JSON parsing failed, content: {0}, error: {1}")]
     JsonParsingFailed(String, String),
+    /// A streamed response diverged from the `response_format` schema;
+    /// holds the text received before it was aborted, and why.
+    #[error("Structured output does not match the schema: {1}")]
+    StructuredOutput(String, String),
     #[error("Storage error: {0}")]
     Storage(String),
     #[error("Other error: {0}")]
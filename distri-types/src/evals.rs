@@ -0,0 +1,189 @@
+//! Eval runs and regression comparisons between agent versions.
+//!
+//! An eval run sends a fixed set of cases to an agent and records what it
+//! answered, keyed by the hash of the agent definition it ran with. Two runs
+//! of the same cases, typically before and after a prompt edit, are then
+//! compared case by case by a judge model, giving a win/loss/tie per case
+//! from the candidate's point of view.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::configuration::AgentConfig;
+
+/// Length of the definition hash prefix shown to users.
+pub const SHORT_HASH_LEN: usize = 12;
+
+/// One input an agent is evaluated on.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EvalCase {
+    /// Stable id; cases are matched across runs by it.
+    pub id: String,
+    /// The message sent to the agent.
+    pub task: String,
+    /// What a good answer contains, shown to the judge.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expected: Option<String>,
+}
+
+/// Body of `POST /agents/{id}/evals`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunEvalsRequest {
+    pub cases: Vec<EvalCase>,
+}
+
+/// What the agent answered for one case.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EvalCaseResult {
+    pub case: EvalCase,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output: Option<String>,
+    /// Set when the run failed instead of answering.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub duration_ms: u64,
+}
+
+/// The results of one eval run.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EvalRun {
+    pub id: String,
+    pub agent_id: String,
+    /// See [`definition_hash`].
+    pub definition_hash: String,
+    pub created_at: DateTime<Utc>,
+    pub results: Vec<EvalCaseResult>,
+}
+
+impl EvalRun {
+    pub fn summary(&self) -> EvalRunSummary {
+        let errors = self.results.iter().filter(|r| r.error.is_some()).count();
+        let total_ms: u64 = self.results.iter().map(|r| r.duration_ms).sum();
+        EvalRunSummary {
+            run_id: self.id.clone(),
+            definition_hash: self.definition_hash.clone(),
+            created_at: self.created_at,
+            cases: self.results.len(),
+            errors,
+            avg_duration_ms: total_ms / self.results.len().max(1) as u64,
+        }
+    }
+
+    /// Whether `reference` names this run: its id, or a prefix of its
+    /// definition hash.
+    pub fn matches(&self, reference: &str) -> bool {
+        self.id == reference
+            || (reference.len() >= 4 && self.definition_hash.starts_with(reference))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EvalRunSummary {
+    pub run_id: String,
+    pub definition_hash: String,
+    pub created_at: DateTime<Utc>,
+    pub cases: usize,
+    pub errors: usize,
+    pub avg_duration_ms: u64,
+}
+
+/// Body of `POST /agents/{id}/evals/compare`. Each side is a run id or a
+/// definition hash prefix, which picks the latest run of that definition.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompareEvalsRequest {
+    pub baseline: String,
+    pub candidate: String,
+}
+
+/// The judge's call on one case, from the candidate's point of view.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EvalVerdict {
+    Win,
+    Loss,
+    Tie,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EvalCaseComparison {
+    pub case_id: String,
+    pub verdict: EvalVerdict,
+    pub rationale: String,
+}
+
+/// Win/loss/tie counts of a comparison.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EvalScore {
+    pub wins: usize,
+    pub losses: usize,
+    pub ties: usize,
+}
+
+impl EvalScore {
+    pub fn tally(cases: &[EvalCaseComparison]) -> Self {
+        let mut score = Self::default();
+        for case in cases {
+            match case.verdict {
+                EvalVerdict::Win => score.wins += 1,
+                EvalVerdict::Loss => score.losses += 1,
+                EvalVerdict::Tie => score.ties += 1,
+            }
+        }
+        score
+    }
+
+    /// More losses than wins.
+    pub fn is_regression(&self) -> bool {
+        self.losses > self.wins
+    }
+}
+
+/// A candidate run judged against a baseline run.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EvalComparison {
+    pub id: String,
+    pub agent_id: String,
+    pub created_at: DateTime<Utc>,
+    pub baseline: EvalRunSummary,
+    pub candidate: EvalRunSummary,
+    pub score: EvalScore,
+    pub cases: Vec<EvalCaseComparison>,
+    /// Case ids only one of the runs has; they are not judged.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub unmatched: Vec<String>,
+}
+
+/// One run in an agent's eval history.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EvalTrendPoint {
+    #[serde(flatten)]
+    pub run: EvalRunSummary,
+    /// The latest comparison with this run as the candidate.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compared_to: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub score: Option<EvalScore>,
+}
+
+/// Response of `GET /agents/{id}/evals`: the agent's runs, oldest first.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EvalHistory {
+    pub agent_id: String,
+    pub runs: Vec<EvalTrendPoint>,
+}
+
+/// SHA-256 of an agent definition, hex encoded. Object keys are sorted, so
+/// the hash only changes when the definition does.
+pub fn definition_hash(config: &AgentConfig) -> String {
+    let value = serde_json::to_value(config).unwrap_or_default();
+    Sha256::digest(value.to_string().as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// The prefix of a definition hash shown to users.
+pub fn short_hash(hash: &str) -> &str {
+    &hash[..hash.len().min(SHORT_HASH_LEN)]
+}
@@ -0,0 +1,223 @@
+//! Publishing agent activity to a message broker.
+//!
+//! With `event_export` in the server config, every agent event the server
+//! records — run lifecycle (`run_started`, `run_finished`, `run_error`),
+//! plans, steps, tool calls and results — is also published to NATS or
+//! Kafka, so downstream systems can react to it:
+//!
+//! ```yaml
+//! event_export:
+//!   broker:
+//!     type: nats
+//!     url: nats://localhost:4222
+//!     subject: distri.events      # published as distri.events.<event type>
+//!   format: cloudevents           # or json (the default)
+//!   events: [run_started, run_finished, run_error]   # all when empty
+//! ```
+//!
+//! A message is the event as streamed to clients ([`AgentEvent`]) in
+//! `json`, or that event as the `data` of a structured-mode CloudEvents 1.0
+//! envelope in `cloudevents`.
+//!
+//! Delivery is at most once: events are buffered in memory and published in
+//! the background, so a slow or unreachable broker never stalls a run. Once
+//! `buffer_size` events are waiting, new ones are dropped and counted. A
+//! failed publish is retried `max_retries` times with backoff, then dropped.
+//! With `jetstream` (NATS) or Kafka, a publish waits for the broker's ack,
+//! so an event that was not dropped is stored by the broker. Events of a
+//! thread are published in order; on Kafka they share a partition.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+use crate::AgentEvent;
+
+/// `event_export` section of the server config.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct EventExportConfig {
+    pub broker: BrokerConfig,
+    #[serde(default)]
+    pub format: EventFormat,
+    /// Event types to publish, e.g. `run_finished`. Every recorded event
+    /// when empty. Text and tool argument deltas are never published.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub events: Vec<String>,
+    /// Most events waiting to be published before new ones are dropped.
+    #[serde(default = "default_buffer_size")]
+    pub buffer_size: usize,
+    /// Most events published in one batch.
+    #[serde(default = "default_batch_size")]
+    pub batch_size: usize,
+    /// Retries of a failed publish before its events are dropped.
+    #[serde(default = "default_max_retries")]
+    pub max_retries: u32,
+    /// `source` of CloudEvents.
+    #[serde(default = "default_source")]
+    pub source: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum BrokerConfig {
+    Nats {
+        /// Server URL, e.g. `nats://localhost:4222`.
+        url: String,
+        /// Subject prefix; events are published to `<subject>.<event type>`.
+        #[serde(default = "default_subject")]
+        subject: String,
+        /// Publish through JetStream and wait for each ack. The subjects
+        /// must belong to a stream.
+        #[serde(default)]
+        jetstream: bool,
+        /// `.creds` file to authenticate with.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        credentials_file: Option<String>,
+    },
+    Kafka {
+        /// Bootstrap brokers, `host:port`.
+        brokers: Vec<String>,
+        topic: String,
+        /// Partitions of `topic`. A thread's events go to one of them,
+        /// chosen from its id.
+        #[serde(default = "default_partitions")]
+        partitions: i32,
+    },
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum EventFormat {
+    /// The [`AgentEvent`] as JSON.
+    #[default]
+    Json,
+    /// A structured-mode CloudEvents 1.0 JSON envelope around the event.
+    Cloudevents,
+}
+
+fn default_buffer_size() -> usize {
+    10_000
+}
+
+fn default_batch_size() -> usize {
+    100
+}
+
+fn default_max_retries() -> u32 {
+    5
+}
+
+fn default_source() -> String {
+    "distri".to_string()
+}
+
+fn default_subject() -> String {
+    "distri.events".to_string()
+}
+
+fn default_partitions() -> i32 {
+    1
+}
+
+/// Content type of an encoded event.
+pub const CLOUDEVENTS_CONTENT_TYPE: &str = "application/cloudevents+json";
+pub const JSON_CONTENT_TYPE: &str = "application/json";
+
+/// An event ready to publish.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExportMessage {
+    /// The event type, e.g. `run_finished`.
+    pub event_type: String,
+    /// Ordering key: the thread id.
+    pub key: String,
+    pub payload: Vec<u8>,
+    pub content_type: &'static str,
+}
+
+/// The `type` tag of an event, e.g. `run_finished`.
+pub fn event_type(event: &AgentEvent) -> String {
+    serde_json::to_value(&event.event)
+        .ok()
+        .and_then(|v| v.get("type").and_then(Value::as_str).map(str::to_string))
+        .unwrap_or_default()
+}
+
+impl EventExportConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.buffer_size == 0 || self.batch_size == 0 {
+            return Err("event_export buffer_size and batch_size must be positive".to_string());
+        }
+        match &self.broker {
+            BrokerConfig::Nats { url, subject, .. } => {
+                if url.trim().is_empty() {
+                    return Err("event_export NATS url is empty".to_string());
+                }
+                if subject.is_empty()
+                    || subject.contains(|c: char| c.is_whitespace() || c == '*' || c == '>')
+                {
+                    return Err(format!(
+                        "event_export subject '{}' must be a plain NATS subject",
+                        subject
+                    ));
+                }
+            }
+            BrokerConfig::Kafka {
+                brokers,
+                topic,
+                partitions,
+            } => {
+                if brokers.is_empty() || topic.trim().is_empty() {
+                    return Err("event_export Kafka needs brokers and a topic".to_string());
+                }
+                if *partitions < 1 {
+                    return Err("event_export Kafka partitions must be at least 1".to_string());
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether events of `event_type` are published: recorded and selected.
+    pub fn selects(&self, event_type: &str) -> bool {
+        !matches!(event_type, "text_message_content" | "tool_call_args")
+            && (self.events.is_empty() || self.events.iter().any(|e| e == event_type))
+    }
+
+    /// Encode `event` in the configured format, or `None` when it is not
+    /// published.
+    pub fn encode(&self, event: &AgentEvent) -> Option<ExportMessage> {
+        let event_type = event_type(event);
+        if !self.selects(&event_type) {
+            return None;
+        }
+        let (body, content_type) = match self.format {
+            EventFormat::Json => (serde_json::to_vec(event).ok()?, JSON_CONTENT_TYPE),
+            EventFormat::Cloudevents => (
+                serde_json::to_vec(&self.cloud_event(&event_type, event)).ok()?,
+                CLOUDEVENTS_CONTENT_TYPE,
+            ),
+        };
+        Some(ExportMessage {
+            event_type,
+            key: event.thread_id.clone(),
+            payload: body,
+            content_type,
+        })
+    }
+
+    fn cloud_event(&self, event_type: &str, event: &AgentEvent) -> Value {
+        json!({
+            "specversion": "1.0",
+            "id": uuid::Uuid::new_v4().to_string(),
+            "source": format!("{}/agents/{}", self.source, event.agent_id),
+            "type": format!("ai.distri.{}", event_type),
+            "subject": event.task_id,
+            "time": event.timestamp.to_rfc3339(),
+            "datacontenttype": JSON_CONTENT_TYPE,
+            "distrithreadid": event.thread_id,
+            "distrirunid": event.run_id,
+            "data": event,
+        })
+    }
+}
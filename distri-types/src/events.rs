@@ -6,7 +6,7 @@ use crate::execution::ContextBudget;
 use crate::hooks::InlineHookRequest;
 
 /// Token usage information for a run
-#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
 pub struct RunUsage {
     /// Actual tokens used (from LLM response)
     pub total_tokens: u32,
@@ -15,6 +15,9 @@ pub struct RunUsage {
     /// Tokens read from provider cache (e.g., Anthropic prompt caching)
     #[serde(default)]
     pub cached_tokens: u32,
+    /// Tokens written to the provider cache (Anthropic, Bedrock)
+    #[serde(default)]
+    pub cache_write_tokens: u32,
     /// Estimated tokens (pre-call estimate)
     pub estimated_tokens: u32,
     /// Model used for this run (e.g., "gpt-5.1", "claude-sonnet-4")
@@ -168,6 +171,28 @@ pub enum AgentEventType {
         reason: Option<String>,
     },
 
+    /// An ensemble's judge settled the answer (see [`crate::ensemble`]).
+    EnsembleJudged {
+        /// Every branch, in the order of the agent's `ensemble.branches`.
+        branches: Vec<EnsembleBranchOutcome>,
+        judge_task_id: String,
+        /// Index into `branches` of the picked answer; `None` when merged.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        selected: Option<usize>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        rationale: Option<String>,
+    },
+
+    /// The final answer was delivered to one of the agent's `output_sinks`,
+    /// or failed to be. Emitted after `RunFinished`, once per sink.
+    OutputSinkFinished {
+        /// The sink's label (its `name`, or its type).
+        sink: String,
+        success: bool,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        error: Option<String>,
+    },
+
     // Tool execution events
     ToolExecutionStart {
         step_id: String,
@@ -181,6 +206,82 @@ pub enum AgentEventType {
         tool_call_name: String,
         success: bool,
     },
+    /// Spans a plugin tool recorded while it ran, emitted before its
+    /// `ToolExecutionEnd`.
+    PluginSpans {
+        step_id: String,
+        tool_call_id: String,
+        tool_call_name: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        plugin: Option<String>,
+        spans: Vec<crate::PluginSpanRecord>,
+    },
+
+    /// A failed tool call was handled by its `tools.recovery` strategy.
+    /// Emitted before the call's `ToolExecutionEnd`, which has the outcome
+    /// after recovery; a retry emits one per attempt.
+    ToolRecovery {
+        step_id: String,
+        tool_call_id: String,
+        tool_call_name: String,
+        /// `retry`, `correct`, `fallback` or `fail`.
+        strategy: String,
+        /// Error of the failed call.
+        error: String,
+        /// Retry attempt, starting at 1; 0 for the other strategies.
+        attempt: u32,
+        /// Whether the call succeeded after all (a retry or the fallback tool).
+        recovered: bool,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        fallback_tool: Option<String>,
+    },
+
+    /// A tool call ran past its `tools.timeouts` limit and was abandoned.
+    /// Emitted before the call's `ToolExecutionEnd`; a retry of the call
+    /// may time out again.
+    ToolTimeout {
+        step_id: String,
+        tool_call_id: String,
+        tool_call_name: String,
+        timeout_secs: u64,
+    },
+
+    /// A tool result broke the tool's output schema. Emitted before the
+    /// call's `ToolExecutionEnd` when `tools.output_validation` is `dev`;
+    /// the result still goes to the model.
+    ToolOutputInvalid {
+        step_id: String,
+        tool_call_id: String,
+        tool_call_name: String,
+        violations: Vec<String>,
+    },
+
+    /// A dry run intercepted a call of a tool with side effects; the model
+    /// got a simulated result.
+    SideEffectSimulated {
+        step_id: String,
+        tool_call_id: String,
+        tool_call_name: String,
+        input: Value,
+    },
+
+    /// The side effects a dry run intercepted. Emitted before
+    /// `RunFinished`.
+    DryRunReport {
+        report: crate::dry_run::DryRunReport,
+    },
+
+    /// A tool result looks like a prompt injection: it is marked as
+    /// untrusted for the model and, when `sanitize`d, the matched lines are
+    /// removed. Emitted before the step's `ToolResults`. `detections` names
+    /// the heuristic patterns that matched, or `classifier`.
+    PromptInjectionDetected {
+        step_id: String,
+        tool_call_id: String,
+        tool_call_name: String,
+        detections: Vec<String>,
+        sanitized: bool,
+    },
 
     // Message events for streaming
     TextMessageStart {
@@ -200,6 +301,40 @@ pub enum AgentEventType {
         step_id: String,
     },
 
+    /// A top-level field of a structured (`response_format`) response was
+    /// fully streamed and matches its schema.
+    StructuredFieldCompleted {
+        message_id: String,
+        step_id: String,
+        field: String,
+        value: Value,
+    },
+    /// A streamed structured response diverged from its schema and was
+    /// aborted.
+    StructuredOutputRejected {
+        error: String,
+        /// Attempt of the turn, starting at 1.
+        attempt: usize,
+        /// Whether the model is asked for the response again.
+        retrying: bool,
+    },
+
+    /// A tool call the model has started writing. Its arguments follow as
+    /// `ToolCallArgs`; the finished call arrives in `ToolCalls` with the
+    /// same `tool_call_id`.
+    ToolCallStart {
+        step_id: String,
+        tool_call_id: String,
+        tool_call_name: String,
+    },
+    /// The next chunk of a streaming tool call's raw argument text (JSON, or
+    /// XML for the xml tool format). Not persisted.
+    ToolCallArgs {
+        step_id: String,
+        tool_call_id: String,
+        delta: String,
+    },
+
     // Tool call events with parent/child relationships
     ToolCalls {
         step_id: String,
@@ -251,6 +386,11 @@ pub enum AgentEventType {
         request: InlineHookRequest,
     },
 
+    /// A tool call is paused until the user grants the scopes it needs.
+    AuthRequired {
+        request: crate::auth::AuthConsentRequest,
+    },
+
     // TODO events
     TodosUpdated {
         formatted_todos: String,
@@ -266,6 +406,11 @@ pub enum AgentEventType {
         changes: Vec<crate::todos::TodoChange>,
     },
 
+    /// `work_todos` finished working the todo queue.
+    TodoQueueFinished {
+        summary: crate::todos::TodoQueueSummary,
+    },
+
     // Context management events
     ContextCompaction {
         tier: CompactionTier,
@@ -303,6 +448,35 @@ pub enum AgentEventType {
         is_critical: bool,
     },
 
+    /// A request was cut down to fit the model's context window before it
+    /// was sent: long tool results shortened, then the oldest turns dropped.
+    ContextTrimmed {
+        model: String,
+        context_limit: u32,
+        /// Tokens left for messages once tool definitions and the output
+        /// budget are set aside.
+        budget: usize,
+        /// Tokens of the messages before and after trimming.
+        tokens_before: usize,
+        tokens_after: usize,
+        tool_results_truncated: usize,
+        messages_dropped: usize,
+    },
+
+    /// A model response was cut off at its output budget
+    /// (`max_output_tokens`, or the provider's own limit).
+    Truncated {
+        message_id: String,
+        /// Estimated tokens of the cut-off response.
+        output_tokens: u32,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        max_output_tokens: Option<u32>,
+        /// Resumptions already requested this turn.
+        continuation: u32,
+        /// Whether the model is asked to resume (`auto_continue`).
+        continuing: bool,
+    },
+
     /// A structured channel reply emitted by a workflow `StepKind::Reply`
     /// step. The gateway renders it per channel; non-channel consumers
     /// (CLI, web) render `reply.text` and ignore buttons they can't show.
@@ -311,6 +485,21 @@ pub enum AgentEventType {
     },
 }
 
+/// One branch of an `EnsembleJudged` event.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EnsembleBranchOutcome {
+    pub agent: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    /// Child task of the branch; `None` when it could not be started.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub task_id: Option<String>,
+    /// Whether the branch produced an answer for the judge.
+    pub answered: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
 fn default_compaction_source() -> String {
     "auto".to_string()
 }
@@ -316,6 +316,9 @@ pub struct ContextUsage {
     /// Tokens read from provider cache (e.g., Anthropic prompt caching)
     #[serde(default)]
     pub cached_tokens: u32,
+    /// Tokens written to provider cache
+    #[serde(default)]
+    pub cache_write_tokens: u32,
     pub current_iteration: usize,
     pub context_size: ContextSize,
     /// Model used for LLM calls in this context
@@ -331,6 +334,15 @@ pub struct ContextUsage {
     pub step_output_start: u32,
     #[serde(default)]
     pub step_cached_start: u32,
+    #[serde(default)]
+    pub step_cache_write_start: u32,
+    /// Whether the latest model response was cut off at its output budget
+    /// and not resumed.
+    #[serde(default)]
+    pub truncated: bool,
+    /// Times the model was asked to resume a truncated response.
+    #[serde(default)]
+    pub continuations: u32,
 }
 
 /// Tracks token usage by component for context optimization.
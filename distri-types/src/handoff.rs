@@ -0,0 +1,200 @@
+//! Capability handoff: `handoff` of an agent definition.
+//!
+//! Before an agent with a `handoff` starts on a request, it checks whether
+//! another registered agent is better suited to it. A request matching the
+//! keywords of one of the `intents` goes to that intent's agent; otherwise,
+//! with `classify` on, the agent's analysis model compares the agent's tools
+//! with those of the candidate `agents` and names the one to hand to when
+//! the agent lacks a tool the request needs. With `confirm` on, the user is
+//! asked first through an `approval_request` tool call; a declined handoff
+//! leaves the agent to answer.
+//!
+//! The chosen agent continues the same task and its answer is the run's. The
+//! handoff is emitted as an `agent_handover` event, stored in the scratchpad
+//! as the `handoff` step and appended to the thread's `handoffs` metadata.
+//!
+//! ```toml
+//! [handoff]
+//! agents = ["*"]
+//! confirm = true
+//!
+//! [[handoff.intents]]
+//! agent = "billing"
+//! keywords = ["refund", "invoice"]
+//! ```
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Scratchpad step of a handoff, offered or made.
+pub const HANDOFF_STEP: &str = "handoff";
+/// Thread metadata key holding the thread's [`HandoffRecord`]s.
+pub const THREAD_HANDOFFS_KEY: &str = "handoffs";
+
+fn default_true() -> bool {
+    true
+}
+
+/// `handoff` of an agent definition.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct HandoffConfig {
+    /// Agents a request may be handed to; `["*"]` for every registered
+    /// agent. The intents' agents are always candidates.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub agents: Vec<String>,
+    /// Requests that go to an agent without classifying.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub intents: Vec<HandoffIntent>,
+    /// Classify requests matching no intent with the analysis model.
+    #[serde(default = "default_true")]
+    pub classify: bool,
+    /// Ask the user before handing off.
+    #[serde(default = "default_true")]
+    pub confirm: bool,
+}
+
+impl Default for HandoffConfig {
+    fn default() -> Self {
+        Self {
+            agents: vec![],
+            intents: vec![],
+            classify: true,
+            confirm: true,
+        }
+    }
+}
+
+impl HandoffConfig {
+    /// Names of the candidate agents, `"*"` included when set.
+    pub fn candidates(&self) -> Vec<String> {
+        let mut names = self.agents.clone();
+        for intent in &self.intents {
+            if !names.contains(&intent.agent) {
+                names.push(intent.agent.clone());
+            }
+        }
+        names
+    }
+
+    /// The first intent with a keyword in `request`, ignoring case.
+    pub fn match_intent(&self, request: &str) -> Option<&HandoffIntent> {
+        let request = request.to_lowercase();
+        self.intents.iter().find(|intent| {
+            intent
+                .keywords
+                .iter()
+                .any(|keyword| !keyword.is_empty() && request.contains(&keyword.to_lowercase()))
+        })
+    }
+}
+
+/// Requests an agent declares it handles.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct HandoffIntent {
+    pub agent: String,
+    /// Words or phrases that route a request to `agent`.
+    pub keywords: Vec<String>,
+}
+
+/// An agent the classifier may hand a request to.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HandoffCandidate {
+    pub name: String,
+    pub description: String,
+    /// Tools the agent declares.
+    pub tools: Vec<String>,
+}
+
+/// The classifier's reply.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct HandoffVerdict {
+    /// Agent to hand the request to; `None` when the agent can answer it.
+    #[serde(default)]
+    pub agent: Option<String>,
+    /// Tools the request needs that the agent lacks.
+    #[serde(default)]
+    pub missing: Vec<String>,
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
+impl HandoffVerdict {
+    /// Read a verdict from the classifier's reply: a JSON object, or text
+    /// holding one (possibly in a code fence).
+    pub fn from_value(value: &Value) -> Option<Self> {
+        match value {
+            Value::Object(_) => serde_json::from_value(value.clone()).ok(),
+            Value::String(text) => {
+                let start = text.find('{')?;
+                let end = text.rfind('}')?;
+                serde_json::from_str(text.get(start..=end)?).ok()
+            }
+            _ => None,
+        }
+    }
+
+    /// Why the request is handed off, for the user and the record.
+    pub fn describe(&self, to_agent: &str) -> String {
+        let mut reason = self
+            .reason
+            .clone()
+            .unwrap_or_else(|| format!("{} is better suited to this request", to_agent));
+        if !self.missing.is_empty() {
+            reason.push_str(&format!(" (needs {})", self.missing.join(", ")));
+        }
+        reason
+    }
+}
+
+/// A handoff offered on a thread, kept under [`THREAD_HANDOFFS_KEY`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HandoffRecord {
+    pub task_id: String,
+    pub from_agent: String,
+    pub to_agent: String,
+    pub reason: String,
+    /// Whether the request was handed off; `false` when the user declined.
+    pub accepted: bool,
+    /// Unix milliseconds.
+    pub timestamp: i64,
+}
+
+/// The prompt of the classifier: the request, the agent's tools and the
+/// candidates'.
+pub fn handoff_brief(
+    request: &str,
+    agent: &str,
+    tools: &[String],
+    candidates: &[HandoffCandidate],
+) -> String {
+    let mut brief = format!(
+        "Decide whether the agent `{}` can handle a request with its tools, or whether the \
+         request needs a tool only another agent has.\n\n<request>\n{}\n</request>\n\n\
+         <tools agent=\"{}\">\n{}\n</tools>\n\n<agents>\n",
+        agent,
+        request,
+        agent,
+        tools.join(", ")
+    );
+    for candidate in candidates {
+        brief.push_str(&format!(
+            "- {}: {} (tools: {})\n",
+            candidate.name,
+            candidate.description,
+            if candidate.tools.is_empty() {
+                "none declared".to_string()
+            } else {
+                candidate.tools.join(", ")
+            }
+        ));
+    }
+    brief.push_str(
+        "</agents>\n\nReply with only a JSON object: {\"agent\": <name of the agent to hand the \
+         request to, or null if it can handle it>, \"missing\": [\"<tool it lacks>\", ...], \
+         \"reason\": \"<one sentence>\"}",
+    );
+    brief
+}
@@ -0,0 +1,119 @@
+//! Handover summaries: a recap of a thread for the person taking over from
+//! the agent — what the customer wants, what was done, which tools were
+//! used and what is blocking.
+//!
+//! Served by `GET /threads/{thread_id}/summary` and the `summarize_thread`
+//! tool. The summary is written by the analysis model of the thread's agent
+//! and cached in the thread metadata under [`THREAD_HANDOVER_SUMMARY_KEY`]
+//! for the [`thread_version`] it was made from, so it is regenerated only
+//! once the thread has moved on.
+
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::{Message, MessageRole, Part};
+
+/// Name of the builtin tool summarizing the current thread.
+pub const SUMMARIZE_THREAD_TOOL_NAME: &str = "summarize_thread";
+/// Thread metadata key holding the cached [`HandoverSummary`].
+pub const THREAD_HANDOVER_SUMMARY_KEY: &str = "handover_summary";
+
+/// A thread recapped for a human taking over.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema, ToSchema)]
+pub struct HandoverSummary {
+    pub thread_id: String,
+    /// What the customer is trying to get done.
+    pub customer_intent: String,
+    /// What the agent did so far, in order.
+    pub steps_taken: Vec<String>,
+    /// Tools the agent called, in the order first used.
+    pub tools_used: Vec<String>,
+    /// What keeps the request from being resolved, if anything.
+    pub current_blocker: Option<String>,
+    /// [`thread_version`] of the thread summarized.
+    pub thread_version: String,
+    pub generated_at: DateTime<Utc>,
+}
+
+/// The part of a [`HandoverSummary`] the model writes.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize)]
+pub struct GeneratedHandover {
+    #[serde(default)]
+    pub customer_intent: String,
+    #[serde(default)]
+    pub steps_taken: Vec<String>,
+    #[serde(default)]
+    pub current_blocker: Option<String>,
+}
+
+impl GeneratedHandover {
+    /// Parse the model's JSON reply, tolerating code fences and surrounding
+    /// prose. An empty or `"none"` blocker is no blocker.
+    pub fn parse(raw: &str) -> Option<Self> {
+        let json = match (raw.find('{'), raw.rfind('}')) {
+            (Some(start), Some(end)) if start < end => &raw[start..=end],
+            _ => return None,
+        };
+        let mut generated: Self = serde_json::from_str(json).ok()?;
+        generated.customer_intent = generated.customer_intent.trim().to_string();
+        generated.steps_taken.retain(|step| !step.trim().is_empty());
+        generated.current_blocker = generated
+            .current_blocker
+            .map(|blocker| blocker.trim().to_string())
+            .filter(|blocker| !blocker.is_empty() && !blocker.eq_ignore_ascii_case("none"));
+        Some(generated)
+    }
+}
+
+/// Version of a thread's history: its message count and last message id.
+/// Any new, edited or regenerated message changes it.
+pub fn thread_version(messages: &[Message]) -> String {
+    let last = messages.last().map(|m| m.id.as_str()).unwrap_or_default();
+    format!("{}:{}", messages.len(), last)
+}
+
+/// Names of the tools called in `messages`, in the order first used. The
+/// `final` tool ending each run is left out.
+pub fn tools_used(messages: &[Message]) -> Vec<String> {
+    let mut tools: Vec<String> = Vec::new();
+    for tool_call in messages.iter().flat_map(Message::tool_calls) {
+        if tool_call.tool_name != "final" && !tools.contains(&tool_call.tool_name) {
+            tools.push(tool_call.tool_name);
+        }
+    }
+    tools
+}
+
+/// The conversation as plain text for the summarizing model: one line per
+/// message and tool call, each message cut to `max_message_chars`.
+pub fn handover_transcript(messages: &[Message], max_message_chars: usize) -> String {
+    let mut lines = Vec::new();
+    for message in messages {
+        let speaker = match message.role {
+            MessageRole::User => "Customer",
+            MessageRole::Assistant => "Agent",
+            MessageRole::System | MessageRole::Developer => continue,
+            MessageRole::Tool => "Tool",
+        };
+        if let Some(text) = message.as_text() {
+            let text: String = text.trim().chars().take(max_message_chars).collect();
+            if !text.is_empty() {
+                lines.push(format!("{}: {}", speaker, text));
+            }
+        }
+        for part in &message.parts {
+            if let Part::ToolCall(tool_call) = part {
+                let input: String = tool_call
+                    .input
+                    .to_string()
+                    .chars()
+                    .take(max_message_chars)
+                    .collect();
+                lines.push(format!("Agent called {}({})", tool_call.tool_name, input));
+            }
+        }
+    }
+    lines.join("\n")
+}
@@ -0,0 +1,39 @@
+//! Settings of idle thread hibernation.
+//!
+//! While a thread is in use the server keeps its heavy resources (the MCP
+//! connection pool, the browser session) alive between messages. Once the
+//! thread has been idle for `idle_secs` they are torn down; the next message
+//! re-creates them. See `distri_core::agent::hibernation`.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// `hibernation` section of the server config.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct HibernationConfig {
+    /// How long a thread may go without a message before its resources are
+    /// released.
+    #[serde(default = "default_idle_secs")]
+    pub idle_secs: u64,
+    /// How often idle threads are looked for.
+    #[serde(default = "default_sweep_interval_secs")]
+    pub sweep_interval_secs: u64,
+}
+
+fn default_idle_secs() -> u64 {
+    900
+}
+
+fn default_sweep_interval_secs() -> u64 {
+    60
+}
+
+impl Default for HibernationConfig {
+    fn default() -> Self {
+        Self {
+            idle_secs: default_idle_secs(),
+            sweep_interval_secs: default_sweep_interval_secs(),
+        }
+    }
+}
@@ -0,0 +1,213 @@
+//! Typed config for the `http_endpoint` dynamic-tool factory.
+//!
+//! An `HttpEndpointConfig` declares one REST call as a tool, inline in the
+//! agent definition — no plugin needed:
+//!
+//! ```toml
+//! [[tools.dynamic]]
+//! name = "get_issue"
+//! type = "http_endpoint"
+//! description = "Fetch a GitHub issue."
+//!
+//! [tools.dynamic.config]
+//! method = "GET"
+//! url = "https://api.github.com/repos/{owner}/{repo}/issues/{number}"
+//! headers = { Authorization = "Bearer $GITHUB_TOKEN" }
+//! parameters = { type = "object", required = ["owner", "repo", "number"], properties = { owner = { type = "string" }, repo = { type = "string" }, number = { type = "integer" } } }
+//! ```
+//!
+//! `{name}` placeholders in the URL are filled from the call input. Input
+//! fields the URL does not use go to the query string for `GET` and
+//! `DELETE` and to the JSON body otherwise. `$VAR_NAME` references in the
+//! URL template and headers are resolved from the agent's env vars, then the
+//! secret store; values from the call input are never resolved. The input is
+//! checked against `parameters` before the call and a successful response
+//! body against `response`, when set.
+
+use std::collections::HashMap;
+
+use regex::Regex;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use url::form_urlencoded;
+
+use crate::http_request::{HttpMethod, HttpRequestInput};
+
+/// Inline definition of one HTTP endpoint tool.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct HttpEndpointConfig {
+    /// HTTP method. Defaults to GET.
+    #[serde(default)]
+    pub method: HttpMethod,
+    /// Absolute URL with `{name}` placeholders for input fields. May contain
+    /// `$VAR_NAME`, including as its base (`$API_URL/orders/{id}`).
+    pub url: String,
+    /// Headers sent with every call. May contain `$VAR_NAME`; set
+    /// `x-connection-id` to inject a connection's OAuth token.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub headers: HashMap<String, String>,
+    /// JSON Schema of the tool input. Every URL placeholder must be one of
+    /// its properties.
+    #[serde(default = "default_parameters")]
+    pub parameters: Value,
+    /// JSON Schema a successful response body must match.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub response: Option<Value>,
+}
+
+fn default_parameters() -> Value {
+    serde_json::json!({"type": "object", "properties": {}})
+}
+
+fn placeholder_regex() -> Regex {
+    Regex::new(r"\{([A-Za-z_][A-Za-z0-9_]*)\}").unwrap()
+}
+
+/// Percent-encode everything but RFC 3986 unreserved characters, so an
+/// input value stays within one path segment.
+fn encode_segment(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+fn scalar_text(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+fn schema_errors(schema: &Value, value: &Value) -> Result<Vec<String>, String> {
+    let validator = jsonschema::validator_for(schema).map_err(|e| e.to_string())?;
+    Ok(validator
+        .iter_errors(value)
+        .map(|e| e.to_string())
+        .collect())
+}
+
+impl HttpEndpointConfig {
+    /// Names of the `{name}` placeholders in the URL template, in order.
+    pub fn placeholders(&self) -> Vec<String> {
+        placeholder_regex()
+            .captures_iter(&self.url)
+            .map(|cap| cap[1].to_string())
+            .collect()
+    }
+
+    /// Check the config (used at agent push time and when the tool is made).
+    pub fn validate(&self) -> Result<(), String> {
+        let absolute = ["http://", "https://", "$"]
+            .iter()
+            .any(|prefix| self.url.starts_with(prefix));
+        if !absolute {
+            return Err(format!(
+                "url must be an absolute http(s) URL or start with a $VAR_NAME, got '{}'",
+                self.url
+            ));
+        }
+        jsonschema::validator_for(&self.parameters)
+            .map_err(|e| format!("invalid parameters schema: {}", e))?;
+        if let Some(response) = &self.response {
+            jsonschema::validator_for(response)
+                .map_err(|e| format!("invalid response schema: {}", e))?;
+        }
+        let properties = self.parameters.get("properties").and_then(Value::as_object);
+        for name in self.placeholders() {
+            if !properties.is_some_and(|p| p.contains_key(&name)) {
+                return Err(format!(
+                    "url placeholder '{{{}}}' is not a property of parameters",
+                    name
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Build the request for one call. `$VAR_NAME` references from the
+    /// config are left in place for the caller to resolve.
+    pub fn build_request(&self, input: &Value) -> Result<HttpRequestInput, String> {
+        let mut fields: Map<String, Value> = match input {
+            Value::Object(map) => map.clone(),
+            Value::Null => Map::new(),
+            _ => return Err("invalid input: expected an object".to_string()),
+        };
+        let errors = schema_errors(&self.parameters, &Value::Object(fields.clone()))?;
+        if !errors.is_empty() {
+            return Err(format!("invalid input: {}", errors.join("; ")));
+        }
+
+        let mut missing = None;
+        let mut url = placeholder_regex()
+            .replace_all(&self.url, |cap: &regex::Captures| {
+                match fields.remove(&cap[1]).filter(|v| !v.is_null()) {
+                    Some(value) => encode_segment(&scalar_text(&value)),
+                    None => {
+                        missing.get_or_insert_with(|| cap[1].to_string());
+                        String::new()
+                    }
+                }
+            })
+            .to_string();
+        if let Some(name) = missing {
+            return Err(format!("invalid input: '{}' is required by the url", name));
+        }
+
+        let body = match self.method {
+            HttpMethod::GET | HttpMethod::DELETE => {
+                let mut query = form_urlencoded::Serializer::new(String::new());
+                for (name, value) in fields.iter().filter(|(_, v)| !v.is_null()) {
+                    match value {
+                        Value::Array(items) => {
+                            for item in items {
+                                query.append_pair(name, &scalar_text(item));
+                            }
+                        }
+                        other => {
+                            query.append_pair(name, &scalar_text(other));
+                        }
+                    }
+                }
+                let query = query.finish();
+                if !query.is_empty() {
+                    url.push(if url.contains('?') { '&' } else { '?' });
+                    url.push_str(&query);
+                }
+                None
+            }
+            _ if fields.is_empty() => None,
+            _ => Some(Value::Object(fields)),
+        };
+
+        Ok(HttpRequestInput {
+            url,
+            method: self.method.clone(),
+            headers: self.headers.clone(),
+            body,
+        })
+    }
+
+    /// Check a successful response body against `response`.
+    pub fn check_response(&self, body: &Value) -> Result<(), String> {
+        let Some(schema) = &self.response else {
+            return Ok(());
+        };
+        let errors = schema_errors(schema, body)?;
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(format!(
+                "response does not match the response schema: {}",
+                errors.join("; ")
+            ))
+        }
+    }
+}
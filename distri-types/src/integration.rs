@@ -3,7 +3,8 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Arc;
 
-use crate::{AuthMetadata, Tool};
+use crate::renderer_manifest::ToolRendererManifest;
+use crate::{AuthMetadata, PluginCapabilities, Tool};
 
 /// OAuth provider configuration for dynamic registration
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -60,6 +61,18 @@ pub trait Integration: Send + Sync + std::fmt::Debug {
     /// Get all tools provided by this integration
     fn get_tools(&self) -> Vec<Arc<dyn Tool>>;
 
+    /// What the integration's tools may access. See
+    /// [`crate::plugin_capabilities`]. Default: nothing.
+    fn get_capabilities(&self) -> PluginCapabilities {
+        PluginCapabilities::default()
+    }
+
+    /// How the web UI renders the integration's tools, one manifest per
+    /// tool. See [`crate::renderer_manifest`]. Default: none.
+    fn get_ui_renderers(&self) -> Vec<ToolRendererManifest> {
+        Vec::new()
+    }
+
     /// Get callback schema (JSON schema for callbacks)
     fn get_callbacks(&self) -> HashMap<String, serde_json::Value> {
         HashMap::new() // Default: no callbacks
@@ -94,6 +107,10 @@ pub struct IntegrationTool {
     tool: Arc<dyn Tool>,
     /// The integration this tool belongs to
     integration_name: String,
+    /// What the integration declared its tools may access
+    capabilities: PluginCapabilities,
+    /// How the integration asked the UI to render this tool
+    ui_renderer: Option<ToolRendererManifest>,
 }
 
 impl IntegrationTool {
@@ -102,9 +119,49 @@ impl IntegrationTool {
         Self {
             tool,
             integration_name,
+            capabilities: PluginCapabilities::default(),
+            ui_renderer: None,
         }
     }
 
+    /// Wrap every tool of `integration`, carrying its capability manifest
+    /// and its renderer manifest. Invalid renderer manifests are dropped.
+    pub fn from_integration(integration: &dyn Integration) -> Vec<Arc<dyn Tool>> {
+        let name = integration.get_name();
+        let capabilities = integration.get_capabilities();
+        let mut renderers: HashMap<String, ToolRendererManifest> = HashMap::new();
+        for renderer in integration.get_ui_renderers() {
+            match renderer.validate() {
+                Ok(()) => {
+                    renderers.insert(renderer.tool.clone(), renderer);
+                }
+                Err(e) => tracing::warn!(plugin = %name, "Ignoring UI renderer: {}", e),
+            }
+        }
+        integration
+            .get_tools()
+            .into_iter()
+            .map(|tool| {
+                let renderer = renderers.remove(&tool.get_name());
+                Arc::new(
+                    Self::new(tool, name.clone())
+                        .with_capabilities(capabilities.clone())
+                        .with_ui_renderer(renderer),
+                ) as Arc<dyn Tool>
+            })
+            .collect()
+    }
+
+    pub fn with_capabilities(mut self, capabilities: PluginCapabilities) -> Self {
+        self.capabilities = capabilities;
+        self
+    }
+
+    pub fn with_ui_renderer(mut self, renderer: Option<ToolRendererManifest>) -> Self {
+        self.ui_renderer = renderer;
+        self
+    }
+
     /// Get the integration name this tool belongs to
     pub fn get_integration_name(&self) -> &str {
         &self.integration_name
@@ -146,6 +203,10 @@ impl Tool for IntegrationTool {
         self.tool.is_final()
     }
 
+    fn side_effect(&self) -> Option<bool> {
+        self.tool.side_effect()
+    }
+
     fn needs_executor_context(&self) -> bool {
         self.tool.needs_executor_context()
     }
@@ -162,6 +223,16 @@ impl Tool for IntegrationTool {
         Some(self.integration_name.clone())
     }
 
+    fn get_plugin_capabilities(&self) -> Option<PluginCapabilities> {
+        Some(self.capabilities.clone())
+    }
+
+    fn get_ui_renderer(&self) -> Option<ToolRendererManifest> {
+        self.ui_renderer
+            .clone()
+            .or_else(|| self.tool.get_ui_renderer())
+    }
+
     async fn execute(
         &self,
         tool_call: crate::ToolCall,
@@ -86,6 +86,10 @@ pub enum AgentRef {
         agent_id: String,
         #[serde(default, skip_serializing_if = "Option::is_none")]
         instructions_overlay: Option<String>,
+        /// Model the agent runs on for this invocation instead of its own
+        /// (an ensemble's model variants).
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        model: Option<String>,
     },
     /// Ad-hoc agent built on the fly. The `system_prompt` is appended to
     /// `_adhoc_base.md`'s body; tools (if `Some`) replace the seeded
@@ -103,6 +107,7 @@ impl AgentRef {
         AgentRef::Named {
             agent_id: agent_id.into(),
             instructions_overlay: None,
+            model: None,
         }
     }
 
@@ -112,6 +117,7 @@ impl AgentRef {
         AgentRef::Named {
             agent_id: agent_id.into(),
             instructions_overlay: Some(overlay.into()),
+            model: None,
         }
     }
 }
@@ -390,6 +396,15 @@ impl Target {
         }
     }
 
+    /// Run a named target on `model` instead of the agent's own model.
+    /// No-op for ad-hoc targets.
+    pub fn with_model(mut self, model: impl Into<String>) -> Self {
+        if let AgentRef::Named { model: m, .. } = &mut self.agent {
+            *m = Some(model.into());
+        }
+        self
+    }
+
     pub fn adhoc(system_prompt: impl Into<String>, message: Message) -> Self {
         Self {
             agent: AgentRef::AdHoc {
@@ -603,6 +618,7 @@ mod tests {
             AgentRef::Named {
                 agent_id,
                 instructions_overlay,
+                ..
             } => {
                 assert_eq!(agent_id, "w");
                 assert_eq!(instructions_overlay.as_deref(), Some("do the skill"));
@@ -620,6 +636,7 @@ mod tests {
             AgentRef::Named {
                 agent_id,
                 instructions_overlay,
+                ..
             } => {
                 assert_eq!(agent_id, "legacy");
                 assert!(instructions_overlay.is_none());
@@ -0,0 +1,174 @@
+//! Background job queue for non-blocking `message/send`.
+//!
+//! When background jobs are enabled, a `message/send` whose
+//! `configuration.blocking` is `false` is persisted as a [`BackgroundJob`]
+//! and acknowledged right away with a `submitted` task. Workers claim jobs
+//! under a lease and run them independently of any HTTP connection,
+//! renewing the lease while the agent runs. A job whose lease lapses (its
+//! worker died) is re-queued until `max_attempts` is used up. Clients
+//! attach to a running job with `tasks/resubscribe` and detach at will.
+
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum BackgroundJobStatus {
+    Queued,
+    Running,
+    Completed,
+    Failed,
+    Canceled,
+}
+
+impl BackgroundJobStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BackgroundJobStatus::Queued => "queued",
+            BackgroundJobStatus::Running => "running",
+            BackgroundJobStatus::Completed => "completed",
+            BackgroundJobStatus::Failed => "failed",
+            BackgroundJobStatus::Canceled => "canceled",
+        }
+    }
+
+    pub fn is_terminal(&self) -> bool {
+        matches!(
+            self,
+            BackgroundJobStatus::Completed
+                | BackgroundJobStatus::Failed
+                | BackgroundJobStatus::Canceled
+        )
+    }
+}
+
+impl std::str::FromStr for BackgroundJobStatus {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "queued" => Ok(BackgroundJobStatus::Queued),
+            "running" => Ok(BackgroundJobStatus::Running),
+            "completed" => Ok(BackgroundJobStatus::Completed),
+            "failed" => Ok(BackgroundJobStatus::Failed),
+            "canceled" => Ok(BackgroundJobStatus::Canceled),
+            other => Err(anyhow::anyhow!("unknown background job status: {other}")),
+        }
+    }
+}
+
+/// A queued agent run.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct BackgroundJob {
+    pub id: String,
+    /// A second enqueue with the same key returns this job instead of
+    /// starting another run.
+    pub idempotency_key: String,
+    pub agent_id: String,
+    pub user_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub workspace_id: Option<String>,
+    /// Task id handed to the client at enqueue time; every attempt runs
+    /// under it so `tasks/get` and `tasks/resubscribe` keep working.
+    pub task_id: String,
+    pub thread_id: String,
+    /// `MessageSendParams` of the original request.
+    pub params: serde_json::Value,
+    pub status: BackgroundJobStatus,
+    /// Claims so far, including the current one.
+    pub attempts: u32,
+    pub max_attempts: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub worker_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lease_expires_at: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Input for `BackgroundJobStore::enqueue`.
+#[derive(Debug, Clone)]
+pub struct NewBackgroundJob {
+    pub idempotency_key: String,
+    pub agent_id: String,
+    pub user_id: String,
+    pub workspace_id: Option<String>,
+    pub task_id: String,
+    pub thread_id: String,
+    pub params: serde_json::Value,
+    pub max_attempts: u32,
+}
+
+impl BackgroundJob {
+    /// A fresh `queued` job for `new`.
+    pub fn queued(new: NewBackgroundJob) -> Self {
+        let now = Utc::now();
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            idempotency_key: new.idempotency_key,
+            agent_id: new.agent_id,
+            user_id: new.user_id,
+            workspace_id: new.workspace_id,
+            task_id: new.task_id,
+            thread_id: new.thread_id,
+            params: new.params,
+            status: BackgroundJobStatus::Queued,
+            attempts: 0,
+            max_attempts: new.max_attempts,
+            worker_id: None,
+            lease_expires_at: None,
+            last_error: None,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+}
+
+/// `background_jobs` section of the server config.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct BackgroundJobsConfig {
+    /// Concurrent workers per server process.
+    #[serde(default = "default_workers")]
+    pub workers: usize,
+    /// How long a claim stays valid without renewal. Workers renew at a
+    /// third of this; a job is considered orphaned once it lapses.
+    #[serde(default = "default_lease_secs")]
+    pub lease_secs: u64,
+    /// Claims allowed before an orphaned job is marked failed.
+    #[serde(default = "default_max_attempts")]
+    pub max_attempts: u32,
+    /// Idle workers re-check the queue at this interval even without a
+    /// wake-up (jobs enqueued by another process).
+    #[serde(default = "default_poll_interval_ms")]
+    pub poll_interval_ms: u64,
+}
+
+fn default_workers() -> usize {
+    2
+}
+
+fn default_lease_secs() -> u64 {
+    60
+}
+
+fn default_max_attempts() -> u32 {
+    3
+}
+
+fn default_poll_interval_ms() -> u64 {
+    1000
+}
+
+impl Default for BackgroundJobsConfig {
+    fn default() -> Self {
+        Self {
+            workers: default_workers(),
+            lease_secs: default_lease_secs(),
+            max_attempts: default_max_attempts(),
+            poll_interval_ms: default_poll_interval_ms(),
+        }
+    }
+}
@@ -0,0 +1,82 @@
+//! Settings of the built-in `k8s` MCP server.
+//!
+//! The server is read-only unless `allow_mutations` is set, and even then
+//! every mutation needs approval before it runs. See
+//! `distri_core::servers::k8s` for the tools it exposes.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// `k8s` section of the server config.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct K8sMcpConfig {
+    /// Kubeconfig file to use. When unset, `$KUBECONFIG`, then
+    /// `~/.kube/config`, then the in-cluster service account are tried.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kubeconfig: Option<String>,
+    /// Kubeconfig context. The current context when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context: Option<String>,
+    /// Namespaces the tools may touch. Any namespace when empty.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub namespaces: Vec<String>,
+    /// Namespace used when a call names none. Defaults to the first of
+    /// `namespaces`, or `default`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_namespace: Option<String>,
+    /// Expose the mutating tools (scale, rollout restart, delete pod).
+    #[serde(default)]
+    pub allow_mutations: bool,
+    /// `kubectl` binary to run.
+    #[serde(default = "default_kubectl")]
+    pub kubectl: String,
+    /// Per-command timeout.
+    #[serde(default = "default_timeout_secs")]
+    pub timeout_secs: u64,
+    /// Output returned to the model is cut to this many bytes.
+    #[serde(default = "default_max_output_bytes")]
+    pub max_output_bytes: usize,
+}
+
+impl Default for K8sMcpConfig {
+    fn default() -> Self {
+        Self {
+            kubeconfig: None,
+            context: None,
+            namespaces: Vec::new(),
+            default_namespace: None,
+            allow_mutations: false,
+            kubectl: default_kubectl(),
+            timeout_secs: default_timeout_secs(),
+            max_output_bytes: default_max_output_bytes(),
+        }
+    }
+}
+
+fn default_kubectl() -> String {
+    "kubectl".to_string()
+}
+
+fn default_timeout_secs() -> u64 {
+    30
+}
+
+fn default_max_output_bytes() -> usize {
+    64 * 1024
+}
+
+impl K8sMcpConfig {
+    /// The namespace a call without one runs in.
+    pub fn effective_default_namespace(&self) -> &str {
+        self.default_namespace
+            .as_deref()
+            .or(self.namespaces.first().map(String::as_str))
+            .unwrap_or("default")
+    }
+
+    /// Whether the tools may touch `namespace`.
+    pub fn allows_namespace(&self, namespace: &str) -> bool {
+        self.namespaces.is_empty() || self.namespaces.iter().any(|n| n == namespace)
+    }
+}
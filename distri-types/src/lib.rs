@@ -41,6 +41,8 @@ pub use hooks::*;
 pub use mcp::*;
 pub use tenant_context::*;
 pub mod a2a_converters;
+pub mod agent_frontmatter;
+pub mod agent_registry;
 pub mod thinking;
 
 mod execution;
@@ -69,12 +71,58 @@ mod client_config;
 pub use client_config::DistriConfig;
 
 pub mod api;
+pub mod calendar;
+pub mod capability_probe;
 pub mod channel_commands;
 pub mod connections;
+pub mod conversation_import;
+pub mod crawl;
+pub mod critique;
+pub mod datetime;
+pub mod dev_seed;
+pub mod dry_run;
 pub mod dynamic_tool;
+pub mod egress;
+pub mod embeddings;
+pub mod ensemble;
+pub mod evals;
+pub mod event_export;
+pub mod handoff;
+pub mod handover_summary;
+pub mod hibernation;
+pub mod http_endpoint;
 pub mod http_request;
+pub mod injection_guard;
+pub mod jobs;
+pub mod k8s;
+pub mod llm_metrics;
+pub mod mcp_servers;
+pub mod memory;
+pub mod memory_anonymization;
 pub mod mock_tool;
+pub mod output_sinks;
+pub mod packages;
+pub mod post_process;
+pub mod prompt_locale;
+pub mod python_exec;
+pub mod regenerate;
+pub mod renderer_manifest;
 pub mod resolve;
+pub mod secret_ref;
+pub mod sql;
+pub mod stream_control;
+pub mod structured_stream;
+pub mod task_diff;
+pub mod task_retry;
+pub mod thread_archive;
+pub mod tool_catalog;
+pub mod tool_output;
+pub mod tool_recovery;
+pub mod tool_redaction;
+pub mod tool_timeouts;
+pub mod user_profile;
+pub mod user_quotas;
+pub mod warm_sessions;
 
 pub mod models;
 pub use models::*;
@@ -85,8 +133,22 @@ pub mod tool_result_store;
 pub use tool_result_store::{
     CacheCheck, ContentFormat, ContentReplacementState, FILE_UNCHANGED_STUB, FileReadCache,
     MAX_TOOL_RESULT_CHARS, MAX_TOOL_RESULTS_PER_MESSAGE_CHARS, PERSIST_THRESHOLD_BYTES,
-    PREVIEW_SIZE_BYTES, PersistedToolResult, Preview, ReplacementDecision,
+    PREVIEW_SIZE_BYTES, PersistedToolResult, Preview, ReplacementDecision, TOOL_CALL_CACHED_MARKER,
+    ToolCallCache,
 };
 
+pub mod workspace_config;
+
+pub mod plugin_trace;
+pub use plugin_trace::{PluginSpan, PluginSpanLog, PluginSpanRecord, PluginSpanRecorder};
+
+pub mod plugin_capabilities;
+pub use plugin_capabilities::{
+    Capability, CapabilityDenied, CapabilityGuard, FilesystemGrant, PluginCapabilities, PluginGrant,
+};
+
+pub mod plugin_storage;
+pub use plugin_storage::{PluginStorage, PluginStorageError};
+
 #[cfg(test)]
 mod tests;
@@ -0,0 +1,285 @@
+//! Latency of LLM calls, per provider and model.
+//!
+//! Every call an orchestrator makes is recorded as an [`LlmCallTiming`]:
+//! its total duration, the time to its first token when it streamed, and
+//! the output tokens it produced. [`LlmMetrics`] keeps running totals per
+//! provider and model plus a window of the latest calls for percentiles,
+//! and renders them as [`LlmLatencyStats`] (home stats) or in the
+//! Prometheus text format (`/metrics`).
+
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt::Write as _;
+use std::sync::Mutex;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Latest calls per model kept for percentiles.
+pub const LATENCY_WINDOW: usize = 1000;
+
+/// One LLM call.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LlmCallTiming {
+    pub provider: String,
+    pub model: String,
+    pub success: bool,
+    pub duration_ms: u64,
+    /// Time to the first text or tool call delta; streamed calls only.
+    pub ttft_ms: Option<u64>,
+    pub output_tokens: u32,
+}
+
+impl LlmCallTiming {
+    /// Output tokens per second of generation: the time after the first
+    /// token for streamed calls, the whole call otherwise.
+    pub fn tokens_per_sec(&self) -> Option<f64> {
+        let generation_ms = self.duration_ms - self.ttft_ms.unwrap_or(0).min(self.duration_ms);
+        (self.success && self.output_tokens > 0 && generation_ms > 0)
+            .then(|| self.output_tokens as f64 * 1000.0 / generation_ms as f64)
+    }
+}
+
+/// Distribution of a latency over the recent calls of a model.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema, JsonSchema)]
+pub struct LatencySummary {
+    pub avg_ms: f64,
+    pub p50_ms: u64,
+    pub p95_ms: u64,
+    pub max_ms: u64,
+}
+
+impl LatencySummary {
+    fn of(values: impl Iterator<Item = u64>) -> Option<Self> {
+        let mut values: Vec<u64> = values.collect();
+        if values.is_empty() {
+            return None;
+        }
+        values.sort_unstable();
+        let percentile = |p: f64| values[((values.len() - 1) as f64 * p).round() as usize];
+        Some(Self {
+            avg_ms: values.iter().sum::<u64>() as f64 / values.len() as f64,
+            p50_ms: percentile(0.5),
+            p95_ms: percentile(0.95),
+            max_ms: *values.last().expect("not empty"),
+        })
+    }
+}
+
+/// Latency of the LLM calls to one model.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema, JsonSchema)]
+pub struct LlmLatencyStats {
+    pub provider: String,
+    pub model: String,
+    /// Calls since the server started.
+    pub calls: u64,
+    pub errors: u64,
+    pub output_tokens: u64,
+    /// Over the latest [`LATENCY_WINDOW`] successful calls.
+    pub duration: Option<LatencySummary>,
+    /// Over the latest successful streamed calls.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time_to_first_token: Option<LatencySummary>,
+    /// Average over the latest successful calls.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tokens_per_sec: Option<f64>,
+}
+
+#[derive(Debug, Default)]
+struct ModelLatency {
+    calls: u64,
+    errors: u64,
+    output_tokens: u64,
+    duration_ms_sum: u64,
+    ttft_ms_sum: u64,
+    ttft_count: u64,
+    recent: VecDeque<LlmCallTiming>,
+}
+
+/// Running latency of LLM calls per `(provider, model)`.
+#[derive(Debug, Default)]
+pub struct LlmMetrics {
+    models: Mutex<Models>,
+}
+
+impl LlmMetrics {
+    pub fn record(&self, call: LlmCallTiming) {
+        let mut models = self.models.lock().expect("llm metrics lock");
+        let model = models
+            .entry((call.provider.clone(), call.model.clone()))
+            .or_default();
+        model.calls += 1;
+        if !call.success {
+            model.errors += 1;
+            return;
+        }
+        model.output_tokens += call.output_tokens as u64;
+        model.duration_ms_sum += call.duration_ms;
+        if let Some(ttft) = call.ttft_ms {
+            model.ttft_ms_sum += ttft;
+            model.ttft_count += 1;
+        }
+        if model.recent.len() == LATENCY_WINDOW {
+            model.recent.pop_front();
+        }
+        model.recent.push_back(call);
+    }
+
+    /// Stats of every model called so far, by provider and model.
+    pub fn snapshot(&self) -> Vec<LlmLatencyStats> {
+        let models = self.models.lock().expect("llm metrics lock");
+        models
+            .iter()
+            .map(|((provider, model), latency)| {
+                let rates: Vec<f64> = latency
+                    .recent
+                    .iter()
+                    .filter_map(LlmCallTiming::tokens_per_sec)
+                    .collect();
+                LlmLatencyStats {
+                    provider: provider.clone(),
+                    model: model.clone(),
+                    calls: latency.calls,
+                    errors: latency.errors,
+                    output_tokens: latency.output_tokens,
+                    duration: LatencySummary::of(latency.recent.iter().map(|c| c.duration_ms)),
+                    time_to_first_token: LatencySummary::of(
+                        latency.recent.iter().filter_map(|c| c.ttft_ms),
+                    ),
+                    tokens_per_sec: (!rates.is_empty())
+                        .then(|| rates.iter().sum::<f64>() / rates.len() as f64),
+                }
+            })
+            .collect()
+    }
+
+    /// The metrics in the Prometheus text exposition format.
+    pub fn prometheus(&self) -> String {
+        let mut out = String::new();
+        {
+            let models = self.models.lock().expect("llm metrics lock");
+            counter(
+                &mut out,
+                "distri_llm_calls_total",
+                "LLM calls.",
+                &models,
+                |l| l.calls,
+            );
+            counter(
+                &mut out,
+                "distri_llm_errors_total",
+                "Failed LLM calls.",
+                &models,
+                |l| l.errors,
+            );
+            counter(
+                &mut out,
+                "distri_llm_output_tokens_total",
+                "Output tokens of successful LLM calls.",
+                &models,
+                |l| l.output_tokens,
+            );
+            summary(
+                &mut out,
+                "distri_llm_duration_seconds",
+                "Duration of successful LLM calls.",
+                &models,
+                |l| {
+                    (
+                        LatencySummary::of(l.recent.iter().map(|c| c.duration_ms)),
+                        l.duration_ms_sum,
+                        l.calls - l.errors,
+                    )
+                },
+            );
+            summary(
+                &mut out,
+                "distri_llm_time_to_first_token_seconds",
+                "Time to the first token of successful streamed LLM calls.",
+                &models,
+                |l| {
+                    (
+                        LatencySummary::of(l.recent.iter().filter_map(|c| c.ttft_ms)),
+                        l.ttft_ms_sum,
+                        l.ttft_count,
+                    )
+                },
+            );
+        }
+
+        let name = "distri_llm_tokens_per_second";
+        let _ = writeln!(
+            out,
+            "# HELP {name} Average output tokens per second of recent LLM calls.\n# TYPE {name} gauge"
+        );
+        for stats in self.snapshot() {
+            if let Some(rate) = stats.tokens_per_sec {
+                let labels = labels(&stats.provider, &stats.model);
+                let _ = writeln!(out, "{name}{{{labels}}} {rate:.3}");
+            }
+        }
+        out
+    }
+}
+
+type Models = BTreeMap<(String, String), ModelLatency>;
+
+fn counter(
+    out: &mut String,
+    name: &str,
+    help: &str,
+    models: &Models,
+    value: impl Fn(&ModelLatency) -> u64,
+) {
+    let _ = writeln!(out, "# HELP {name} {help}\n# TYPE {name} counter");
+    for ((provider, model), latency) in models {
+        let labels = labels(provider, model);
+        let _ = writeln!(out, "{name}{{{labels}}} {}", value(latency));
+    }
+}
+
+/// A summary with the p50 and p95 of the recent calls, and the sum and
+/// count of all of them.
+fn summary(
+    out: &mut String,
+    name: &str,
+    help: &str,
+    models: &Models,
+    values: impl Fn(&ModelLatency) -> (Option<LatencySummary>, u64, u64),
+) {
+    let _ = writeln!(out, "# HELP {name} {help}\n# TYPE {name} summary");
+    for ((provider, model), latency) in models {
+        let labels = labels(provider, model);
+        let (summary, sum_ms, count) = values(latency);
+        if let Some(summary) = summary {
+            for (quantile, ms) in [("0.5", summary.p50_ms), ("0.95", summary.p95_ms)] {
+                let _ = writeln!(
+                    out,
+                    "{name}{{{labels},quantile=\"{quantile}\"}} {}",
+                    seconds(ms)
+                );
+            }
+        }
+        let _ = writeln!(out, "{name}_sum{{{labels}}} {}", seconds(sum_ms));
+        let _ = writeln!(out, "{name}_count{{{labels}}} {count}");
+    }
+}
+
+fn labels(provider: &str, model: &str) -> String {
+    format!(
+        "provider=\"{}\",model=\"{}\"",
+        escape_label(provider),
+        escape_label(model)
+    )
+}
+
+fn seconds(ms: u64) -> f64 {
+    ms as f64 / 1000.0
+}
+
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
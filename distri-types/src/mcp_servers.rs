@@ -0,0 +1,184 @@
+//! MCP servers declared in the server config (`mcp_servers`).
+//!
+//! Each entry says how to reach one external MCP server — a command spoken
+//! to over stdio, or a Streamable HTTP / SSE endpoint — which auth provider
+//! authenticates it, whether it starts with the server or on first use, and
+//! how it is checked at startup. Agents use a declared server like any other
+//! (`tools.mcp: [{ server: <name> }]`). See `distri_core::servers::declared`
+//! for how the entries are started.
+
+use std::collections::HashMap;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::McpClientTransport;
+
+/// One entry of the `mcp_servers` section.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct McpServerConfig {
+    /// Name agents refer to in `tools.mcp[].server`.
+    pub name: String,
+    pub transport: McpServerTransport,
+    /// Command to run (`stdio`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub args: Vec<String>,
+    /// Extra environment variables of the command (`stdio`). Values may
+    /// reference secrets as `{{secret:NAME}}`.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub env: HashMap<String, String>,
+    /// Endpoint of the server (`streamable_http`, `sse`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// Headers sent with every request (`streamable_http`, `sse`). Values
+    /// may reference secrets as `{{secret:NAME}}`.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub headers: HashMap<String, String>,
+    /// Connection credentials the requests are authenticated with. Remote
+    /// servers only.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auth: Option<McpServerAuth>,
+    #[serde(default)]
+    pub start: McpStartMode,
+    #[serde(default)]
+    pub health_check: McpHealthCheck,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum McpServerTransport {
+    /// A local command speaking MCP on stdin/stdout.
+    Stdio,
+    StreamableHttp,
+    Sse,
+}
+
+/// Binds a remote server to the connection of an auth provider. The
+/// connection is resolved for the user of each run, and its headers
+/// (usually `Authorization: Bearer …`) are sent with the requests.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct McpServerAuth {
+    /// Provider of the connection, e.g. `github`.
+    pub provider: String,
+}
+
+/// When a declared server is connected.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum McpStartMode {
+    /// On the first run that uses the server.
+    #[default]
+    Lazy,
+    /// When the server boots, followed by the health check.
+    Eager,
+}
+
+/// Startup check of an eagerly started server: it is connected and must
+/// list its tools within `timeout_secs`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct McpHealthCheck {
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    #[serde(default = "default_health_timeout_secs")]
+    pub timeout_secs: u64,
+    /// Fail the server startup when the check fails. Otherwise the failure
+    /// is logged and the server is retried on first use.
+    #[serde(default)]
+    pub required: bool,
+}
+
+impl Default for McpHealthCheck {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            timeout_secs: default_health_timeout_secs(),
+            required: false,
+        }
+    }
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+fn default_health_timeout_secs() -> u64 {
+    10
+}
+
+impl McpServerConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.name.trim().is_empty()
+            || !self
+                .name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+        {
+            return Err(format!(
+                "MCP server name '{}' must be non-empty and alphanumeric/underscore/dash only",
+                self.name
+            ));
+        }
+        match self.transport {
+            McpServerTransport::Stdio => {
+                if self.command.as_deref().is_none_or(|c| c.trim().is_empty()) {
+                    return Err(format!(
+                        "MCP server '{}': stdio needs a `command`",
+                        self.name
+                    ));
+                }
+                if self.url.is_some() || !self.headers.is_empty() || self.auth.is_some() {
+                    return Err(format!(
+                        "MCP server '{}': `url`, `headers` and `auth` only apply to remote \
+                         servers; pass credentials to a stdio command through `env`",
+                        self.name
+                    ));
+                }
+            }
+            McpServerTransport::StreamableHttp | McpServerTransport::Sse => {
+                if self.command.is_some() || !self.args.is_empty() || !self.env.is_empty() {
+                    return Err(format!(
+                        "MCP server '{}': `command`, `args` and `env` only apply to stdio servers",
+                        self.name
+                    ));
+                }
+                let Some(transport) = self.client_transport() else {
+                    return Err(format!("MCP server '{}' needs a `url`", self.name));
+                };
+                // Secret references are only resolved when connecting.
+                if !transport.url().contains("{{") {
+                    transport
+                        .validate()
+                        .map_err(|e| format!("MCP server '{}': {}", self.name, e))?;
+                }
+                if self.auth.is_some() && self.start == McpStartMode::Eager {
+                    return Err(format!(
+                        "MCP server '{}': servers with `auth` connect per user and cannot \
+                         start eagerly",
+                        self.name
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// The remote transport of a `streamable_http` or `sse` server.
+    pub fn client_transport(&self) -> Option<McpClientTransport> {
+        let url = self.url.clone()?;
+        let headers = (!self.headers.is_empty()).then(|| self.headers.clone());
+        match self.transport {
+            McpServerTransport::Stdio => None,
+            McpServerTransport::StreamableHttp => {
+                Some(McpClientTransport::StreamableHttp { url, headers })
+            }
+            McpServerTransport::Sse => Some(McpClientTransport::Sse { url, headers }),
+        }
+    }
+}
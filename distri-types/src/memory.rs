@@ -0,0 +1,443 @@
+//! Memory namespaces and per-agent access grants.
+//!
+//! Permanent memories used to be keyed only by `user_id`. A namespace lets
+//! agents deliberately share findings — a "research" agent writes to
+//! `team:analysts`, a "writer" agent on the same team reads them — while
+//! `user` and `agent` memories stay private.
+//!
+//! Grants are declared on the agent definition:
+//!
+//! ```toml
+//! [memory]
+//! read = ["user", "agent", "team:analysts", "agent:research"]
+//! write = ["agent", "team:analysts"]
+//! ```
+//!
+//! Grant forms: `user`, `agent` (this agent), `agent:<name>`,
+//! `team:<name>`, `workspace`. Without a `[memory]` section an agent reads
+//! and writes `user` memory only — the pre-namespace behaviour.
+
+use crate::stores::{MemoryStore, SessionMemory};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Workspace id used when a caller has none (single-tenant OSS server).
+pub const DEFAULT_MEMORY_WORKSPACE: &str = "default";
+
+/// Visibility level of a memory.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum MemoryScope {
+    /// Private to one user, shared by every agent acting for them.
+    User,
+    /// Private to one agent within a workspace.
+    Agent,
+    /// Shared by the agents that declare the same team.
+    Team,
+    /// Shared by every agent in the workspace.
+    Workspace,
+}
+
+/// One entry of a `read` / `write` grant list, e.g. `team:analysts`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct MemoryGrant {
+    pub scope: MemoryScope,
+    /// Agent or team name. `None` for `user`, `workspace` and the calling
+    /// agent's own `agent` scope.
+    pub name: Option<String>,
+}
+
+impl MemoryGrant {
+    pub fn user() -> Self {
+        Self {
+            scope: MemoryScope::User,
+            name: None,
+        }
+    }
+
+    pub fn own_agent() -> Self {
+        Self {
+            scope: MemoryScope::Agent,
+            name: None,
+        }
+    }
+
+    pub fn team(name: impl Into<String>) -> Self {
+        Self {
+            scope: MemoryScope::Team,
+            name: Some(name.into()),
+        }
+    }
+
+    pub fn workspace() -> Self {
+        Self {
+            scope: MemoryScope::Workspace,
+            name: None,
+        }
+    }
+
+    /// Resolve this grant to a concrete namespace for `identity`.
+    pub fn namespace(&self, identity: &MemoryIdentity) -> MemoryNamespace {
+        let workspace = identity
+            .workspace_id
+            .as_deref()
+            .unwrap_or(DEFAULT_MEMORY_WORKSPACE);
+        let owner = match self.scope {
+            MemoryScope::User => identity.user_id.clone(),
+            MemoryScope::Agent => format!(
+                "{workspace}/{}",
+                self.name.as_deref().unwrap_or(&identity.agent_id)
+            ),
+            MemoryScope::Team => {
+                format!("{workspace}/{}", self.name.as_deref().unwrap_or_default())
+            }
+            MemoryScope::Workspace => workspace.to_string(),
+        };
+        MemoryNamespace {
+            scope: self.scope,
+            owner,
+        }
+    }
+}
+
+impl fmt::Display for MemoryGrant {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let scope = match self.scope {
+            MemoryScope::User => "user",
+            MemoryScope::Agent => "agent",
+            MemoryScope::Team => "team",
+            MemoryScope::Workspace => "workspace",
+        };
+        match &self.name {
+            Some(name) => write!(f, "{scope}:{name}"),
+            None => f.write_str(scope),
+        }
+    }
+}
+
+impl std::str::FromStr for MemoryGrant {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (scope, name) = match s.split_once(':') {
+            Some((scope, name)) if !name.trim().is_empty() => (scope, Some(name.trim())),
+            Some(_) => return Err(format!("memory grant `{s}` is missing a name")),
+            None => (s, None),
+        };
+        let scope = match (scope.trim(), name) {
+            ("user", None) => MemoryScope::User,
+            ("agent", _) => MemoryScope::Agent,
+            ("team", Some(_)) => MemoryScope::Team,
+            ("workspace", None) => MemoryScope::Workspace,
+            ("team", None) => return Err("`team` grants need a name, e.g. `team:analysts`".into()),
+            ("user" | "workspace", Some(_)) => {
+                return Err(format!("`{scope}` grants do not take a name"));
+            }
+            _ => {
+                return Err(format!(
+                    "unknown memory grant `{s}` (expected user, agent, agent:<name>, team:<name> or workspace)"
+                ));
+            }
+        };
+        Ok(Self {
+            scope,
+            name: name.map(str::to_string),
+        })
+    }
+}
+
+impl TryFrom<String> for MemoryGrant {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<MemoryGrant> for String {
+    fn from(grant: MemoryGrant) -> Self {
+        grant.to_string()
+    }
+}
+
+/// `[memory]` section of an agent definition.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct MemoryAccessConfig {
+    /// Namespaces this agent may search. Defaults to `["user"]`.
+    #[serde(default = "default_grants")]
+    #[schemars(with = "Vec<String>")]
+    pub read: Vec<MemoryGrant>,
+    /// Namespaces this agent may store to. Defaults to `["user"]`.
+    #[serde(default = "default_grants")]
+    #[schemars(with = "Vec<String>")]
+    pub write: Vec<MemoryGrant>,
+}
+
+fn default_grants() -> Vec<MemoryGrant> {
+    vec![MemoryGrant::user()]
+}
+
+impl Default for MemoryAccessConfig {
+    fn default() -> Self {
+        Self {
+            read: default_grants(),
+            write: default_grants(),
+        }
+    }
+}
+
+impl MemoryAccessConfig {
+    pub fn can_read(&self, grant: &MemoryGrant) -> bool {
+        self.read.contains(grant)
+    }
+
+    pub fn can_write(&self, grant: &MemoryGrant) -> bool {
+        self.write.contains(grant)
+    }
+}
+
+/// Who is reading or writing memory.
+#[derive(Debug, Clone)]
+pub struct MemoryIdentity {
+    pub user_id: String,
+    pub agent_id: String,
+    pub workspace_id: Option<String>,
+}
+
+/// A resolved storage namespace.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MemoryNamespace {
+    pub scope: MemoryScope,
+    pub owner: String,
+}
+
+impl MemoryNamespace {
+    /// Key passed to [`MemoryStore`] in place of `user_id`. `user`
+    /// namespaces keep the bare user id so existing memories stay visible.
+    pub fn key(&self) -> String {
+        match self.scope {
+            MemoryScope::User => self.owner.clone(),
+            MemoryScope::Agent => format!("agent:{}", self.owner),
+            MemoryScope::Team => format!("team:{}", self.owner),
+            MemoryScope::Workspace => format!("workspace:{}", self.owner),
+        }
+    }
+}
+
+/// Memory access for one agent run, with its grants enforced.
+pub struct ScopedMemory<'a> {
+    store: &'a dyn MemoryStore,
+    access: MemoryAccessConfig,
+    identity: MemoryIdentity,
+}
+
+impl<'a> ScopedMemory<'a> {
+    pub fn new(
+        store: &'a dyn MemoryStore,
+        access: MemoryAccessConfig,
+        identity: MemoryIdentity,
+    ) -> Self {
+        Self {
+            store,
+            access,
+            identity,
+        }
+    }
+
+    /// Store `memory` in the namespace named by `grant`. Fails if the agent
+    /// has no write grant for it.
+    pub async fn store(&self, grant: &MemoryGrant, memory: SessionMemory) -> anyhow::Result<()> {
+        if !self.access.can_write(grant) {
+            anyhow::bail!(
+                "agent `{}` has no write grant for memory namespace `{grant}`",
+                self.identity.agent_id
+            );
+        }
+        let namespace = grant.namespace(&self.identity);
+        self.store.store_memory(&namespace.key(), memory).await
+    }
+
+    /// Search every namespace the agent may read, in grant order, up to
+    /// `limit` results overall.
+    pub async fn search(&self, query: &str, limit: Option<usize>) -> anyhow::Result<Vec<String>> {
+        let mut results: Vec<String> = Vec::new();
+        for grant in &self.access.read {
+            let remaining = limit.map(|l| l.saturating_sub(results.len()));
+            if remaining == Some(0) {
+                break;
+            }
+            let key = grant.namespace(&self.identity).key();
+            for hit in self.store.search_memories(&key, query, remaining).await? {
+                if !results.contains(&hit) {
+                    results.push(hit);
+                }
+            }
+        }
+        if let Some(limit) = limit {
+            results.truncate(limit);
+        }
+        Ok(results)
+    }
+
+    /// The newest memories of every namespace the agent may read, in grant
+    /// order, up to `limit` overall.
+    pub async fn recall(&self, limit: usize) -> anyhow::Result<Vec<String>> {
+        let mut results: Vec<String> = Vec::new();
+        for grant in &self.access.read {
+            if results.len() >= limit {
+                break;
+            }
+            let key = grant.namespace(&self.identity).key();
+            for memory in self.store.get_user_memories(&key).await? {
+                if !results.contains(&memory) {
+                    results.push(memory);
+                }
+            }
+        }
+        results.truncate(limit);
+        Ok(results)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use tokio::sync::Mutex;
+
+    #[derive(Default)]
+    struct MapStore(Mutex<HashMap<String, Vec<String>>>);
+
+    #[async_trait::async_trait]
+    impl MemoryStore for MapStore {
+        async fn store_memory(&self, user_id: &str, m: SessionMemory) -> anyhow::Result<()> {
+            self.0
+                .lock()
+                .await
+                .entry(user_id.to_string())
+                .or_default()
+                .push(m.session_summary);
+            Ok(())
+        }
+        async fn search_memories(
+            &self,
+            user_id: &str,
+            query: &str,
+            _limit: Option<usize>,
+        ) -> anyhow::Result<Vec<String>> {
+            Ok(self
+                .0
+                .lock()
+                .await
+                .get(user_id)
+                .into_iter()
+                .flatten()
+                .filter(|m| m.contains(query))
+                .cloned()
+                .collect())
+        }
+        async fn get_user_memories(&self, user_id: &str) -> anyhow::Result<Vec<String>> {
+            Ok(self
+                .0
+                .lock()
+                .await
+                .get(user_id)
+                .cloned()
+                .unwrap_or_default())
+        }
+        async fn clear_user_memories(&self, user_id: &str) -> anyhow::Result<()> {
+            self.0.lock().await.remove(user_id);
+            Ok(())
+        }
+    }
+
+    fn memory(summary: &str) -> SessionMemory {
+        SessionMemory {
+            agent_id: "a".into(),
+            thread_id: "t".into(),
+            session_summary: summary.into(),
+            key_insights: vec![],
+            important_facts: vec![],
+            timestamp: chrono::Utc::now(),
+        }
+    }
+
+    fn identity(agent: &str) -> MemoryIdentity {
+        MemoryIdentity {
+            user_id: "u1".into(),
+            agent_id: agent.into(),
+            workspace_id: None,
+        }
+    }
+
+    #[test]
+    fn grants_parse_and_round_trip() {
+        let access: MemoryAccessConfig = toml::from_str(
+            r#"
+read = ["user", "agent", "agent:research", "team:analysts", "workspace"]
+write = ["team:analysts"]
+"#,
+        )
+        .unwrap();
+        assert_eq!(access.read.len(), 5);
+        assert!(access.can_write(&MemoryGrant::team("analysts")));
+        assert!(!access.can_write(&MemoryGrant::user()));
+        let json = serde_json::to_value(&access).unwrap();
+        assert_eq!(json["read"][2], "agent:research");
+
+        for bad in ["team", "user:bob", "workspace:x", "galaxy", "team:"] {
+            assert!(
+                bad.parse::<MemoryGrant>().is_err(),
+                "{bad} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn user_namespace_keeps_bare_user_id() {
+        let key = MemoryGrant::user().namespace(&identity("a")).key();
+        assert_eq!(key, "u1");
+        let key = MemoryGrant::own_agent().namespace(&identity("a")).key();
+        assert_eq!(key, "agent:default/a");
+    }
+
+    #[tokio::test]
+    async fn team_memories_are_shared_but_agent_memories_are_private() {
+        let store = MapStore::default();
+        let research = ScopedMemory::new(
+            &store,
+            MemoryAccessConfig {
+                read: vec![MemoryGrant::own_agent()],
+                write: vec![MemoryGrant::own_agent(), MemoryGrant::team("analysts")],
+            },
+            identity("research"),
+        );
+        research
+            .store(&MemoryGrant::team("analysts"), memory("finding: churn up"))
+            .await
+            .unwrap();
+        research
+            .store(&MemoryGrant::own_agent(), memory("finding: draft notes"))
+            .await
+            .unwrap();
+        assert!(
+            research
+                .store(&MemoryGrant::workspace(), memory("nope"))
+                .await
+                .is_err()
+        );
+
+        let writer = ScopedMemory::new(
+            &store,
+            MemoryAccessConfig {
+                read: vec![MemoryGrant::own_agent(), MemoryGrant::team("analysts")],
+                write: vec![MemoryGrant::own_agent()],
+            },
+            identity("writer"),
+        );
+        let hits = writer.search("finding", None).await.unwrap();
+        assert_eq!(hits, vec!["finding: churn up".to_string()]);
+    }
+}
@@ -0,0 +1,359 @@
+//! Anonymization of long-term memory: `memory.anonymization` of the store
+//! config.
+//!
+//! Before a memory is stored, the personal identifiers in it (emails, phone
+//! numbers, card numbers, names…) are replaced with pseudonyms such as
+//! `[EMAIL_3f9a2c1b07de]`. A pseudonym is a keyed hash of the identifier and
+//! its owner, so the same person gets the same pseudonym in every memory of
+//! a user and a search for the raw identifier still finds them, while two
+//! users' pseudonyms for it differ.
+//!
+//! Outside `strict` mode, each pseudonym's value is kept, encrypted, apart
+//! from the memories, and memories are read back with their identifiers
+//! restored. In `strict` mode no mapping is kept: raw identifiers are never
+//! persisted, and memories are read back with their pseudonyms.
+//!
+//! ```yaml
+//! stores:
+//!   memory:
+//!     anonymization:
+//!       entities: [email, phone, person]   # every kind when empty
+//!       patterns:
+//!         - pattern: "CUST-[0-9]{6}"
+//!           label: customer_id
+//!       strict: false
+//!       key_env: DISTRI_MEMORY_KEY
+//! ```
+//!
+//! Detection is pattern based: `person` only finds names introduced as such
+//! ("my name is …", "Dr. …").
+
+use std::sync::OnceLock;
+
+use anyhow::anyhow;
+use regex::Regex;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// `memory.anonymization` section of the store config.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct MemoryAnonymizationConfig {
+    /// Kinds of identifiers replaced. Every built-in kind when empty.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub entities: Vec<EntityKind>,
+    /// Identifiers of other kinds, detected by regex.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub patterns: Vec<EntityPattern>,
+    /// Never persist raw identifiers: no mapping is kept, so the pseudonyms
+    /// in stored memories cannot be turned back into their values.
+    #[serde(default)]
+    pub strict: bool,
+    /// Environment variable holding the secret that keys the pseudonyms and
+    /// encrypts the mapping. Changing it orphans existing pseudonyms.
+    #[serde(default = "default_key_env")]
+    pub key_env: String,
+}
+
+impl Default for MemoryAnonymizationConfig {
+    fn default() -> Self {
+        Self {
+            entities: Vec::new(),
+            patterns: Vec::new(),
+            strict: false,
+            key_env: default_key_env(),
+        }
+    }
+}
+
+fn default_key_env() -> String {
+    "DISTRI_MEMORY_KEY".to_string()
+}
+
+/// A built-in kind of identifier.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum EntityKind {
+    Email,
+    Phone,
+    /// Card numbers passing the Luhn check.
+    CreditCard,
+    /// IPv4 addresses.
+    IpAddress,
+    /// IBANs passing the mod-97 check.
+    Iban,
+    /// US social security numbers (`123-45-6789`).
+    Ssn,
+    /// Names introduced as such: after "my name is", "name is" or a title
+    /// (Mr, Mrs, Ms, Miss, Dr, Prof).
+    Person,
+}
+
+impl EntityKind {
+    pub const ALL: [EntityKind; 7] = [
+        EntityKind::Email,
+        EntityKind::CreditCard,
+        EntityKind::Iban,
+        EntityKind::Ssn,
+        EntityKind::IpAddress,
+        EntityKind::Phone,
+        EntityKind::Person,
+    ];
+
+    /// Label of the kind's pseudonyms.
+    pub fn label(&self) -> &'static str {
+        match self {
+            EntityKind::Email => "EMAIL",
+            EntityKind::Phone => "PHONE",
+            EntityKind::CreditCard => "CARD",
+            EntityKind::IpAddress => "IP",
+            EntityKind::Iban => "IBAN",
+            EntityKind::Ssn => "SSN",
+            EntityKind::Person => "PERSON",
+        }
+    }
+
+    fn pattern(&self) -> &'static str {
+        match self {
+            EntityKind::Email => r"(?i)\b[A-Z0-9._%+-]+@[A-Z0-9.-]+\.[A-Z]{2,}\b",
+            EntityKind::Phone => {
+                r"(?:\+\d{1,3}[\s.-]?)?(?:\(\d{1,4}\)[\s.-]?)?\d{2,4}(?:[\s.-]\d{2,4}){1,4}|\+\d{7,15}"
+            }
+            EntityKind::CreditCard => r"\b\d(?:[ -]?\d){12,18}\b",
+            EntityKind::IpAddress => {
+                r"\b(?:(?:25[0-5]|2[0-4]\d|1?\d?\d)\.){3}(?:25[0-5]|2[0-4]\d|1?\d?\d)\b"
+            }
+            EntityKind::Iban => r"\b[A-Z]{2}\d{2}(?: ?[A-Z0-9]{4}){2,7}(?: ?[A-Z0-9]{1,3})?\b",
+            EntityKind::Ssn => r"\b\d{3}-\d{2}-\d{4}\b",
+            EntityKind::Person => {
+                r"(?:\b(?:Mr|Mrs|Ms|Miss|Dr|Prof)\.?|\b(?i:my name is|name is))\s+([A-Z][\p{L}'-]+(?:\s+[A-Z][\p{L}'-]+)?)"
+            }
+        }
+    }
+
+    /// Whether `text`, a match of the kind's pattern, is one.
+    fn accepts(&self, text: &str) -> bool {
+        let digits: Vec<u32> = text.chars().filter_map(|c| c.to_digit(10)).collect();
+        match self {
+            // Dates have the shape of a phone number too.
+            EntityKind::Phone => {
+                (7..=15).contains(&digits.len())
+                    && chrono::NaiveDate::parse_from_str(text, "%Y-%m-%d").is_err()
+            }
+            EntityKind::CreditCard => luhn(&digits),
+            EntityKind::Iban => iban_checksum(text),
+            _ => true,
+        }
+    }
+}
+
+/// An identifier detected by regex: `pattern`'s matches (or their first
+/// group, when it has one) become `[<LABEL>_…]` pseudonyms.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct EntityPattern {
+    pub pattern: String,
+    pub label: String,
+}
+
+/// An identifier found in a text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entity {
+    /// `EMAIL`, `PERSON`…, or an uppercased pattern label.
+    pub label: String,
+    /// Byte range in the text.
+    pub start: usize,
+    pub end: usize,
+    pub text: String,
+}
+
+impl Entity {
+    /// The identifier as compared across memories: case, and the
+    /// separators of numbers, do not make two identifiers different.
+    pub fn normalized(&self) -> String {
+        let text = self.text.trim();
+        if matches!(self.label.as_str(), "PHONE" | "CARD" | "IBAN" | "SSN") {
+            text.chars()
+                .filter(|c| c.is_ascii_alphanumeric())
+                .collect::<String>()
+                .to_uppercase()
+        } else {
+            text.split_whitespace()
+                .collect::<Vec<_>>()
+                .join(" ")
+                .to_lowercase()
+        }
+    }
+}
+
+struct Detector {
+    label: String,
+    regex: Regex,
+    kind: Option<EntityKind>,
+}
+
+/// The compiled detectors of a [`MemoryAnonymizationConfig`].
+pub struct EntityDetector {
+    detectors: Vec<Detector>,
+}
+
+impl EntityDetector {
+    /// Fails on an invalid `patterns` regex or label.
+    pub fn new(config: &MemoryAnonymizationConfig) -> anyhow::Result<Self> {
+        let mut detectors = Vec::new();
+        for kind in EntityKind::ALL {
+            if config.entities.is_empty() || config.entities.contains(&kind) {
+                detectors.push(Detector {
+                    label: kind.label().to_string(),
+                    regex: Regex::new(kind.pattern()).expect("built-in entity pattern"),
+                    kind: Some(kind),
+                });
+            }
+        }
+        for (i, pattern) in config.patterns.iter().enumerate() {
+            let label = pattern.label.trim().to_uppercase().replace([' ', '-'], "_");
+            if label.is_empty() || !label.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+                return Err(anyhow!(
+                    "memory anonymization pattern {}: label must be alphanumeric",
+                    i
+                ));
+            }
+            let regex = Regex::new(&pattern.pattern)
+                .map_err(|e| anyhow!("memory anonymization pattern {}: {}", i, e))?;
+            detectors.push(Detector {
+                label,
+                regex,
+                kind: None,
+            });
+        }
+        Ok(Self { detectors })
+    }
+
+    /// The identifiers in `text`, in order and not overlapping: where two
+    /// overlap, the one starting first (or the longer) is kept. Existing
+    /// pseudonyms are not detected again.
+    pub fn detect(&self, text: &str) -> Vec<Entity> {
+        let pseudonyms: Vec<(usize, usize)> = pseudonym_regex()
+            .find_iter(text)
+            .map(|m| (m.start(), m.end()))
+            .collect();
+        let mut found = Vec::new();
+        for detector in &self.detectors {
+            for captures in detector.regex.captures_iter(text) {
+                let Some(m) = captures.get(1).or_else(|| captures.get(0)) else {
+                    continue;
+                };
+                if m.as_str().trim().is_empty()
+                    || detector.kind.is_some_and(|kind| !kind.accepts(m.as_str()))
+                    || pseudonyms
+                        .iter()
+                        .any(|&(start, end)| m.start() < end && start < m.end())
+                {
+                    continue;
+                }
+                found.push(Entity {
+                    label: detector.label.clone(),
+                    start: m.start(),
+                    end: m.end(),
+                    text: m.as_str().to_string(),
+                });
+            }
+        }
+        found.sort_by(|a, b| a.start.cmp(&b.start).then(b.end.cmp(&a.end)));
+        let mut entities: Vec<Entity> = Vec::new();
+        for entity in found {
+            if entities.last().is_none_or(|last| entity.start >= last.end) {
+                entities.push(entity);
+            }
+        }
+        entities
+    }
+
+    /// `text` with each identifier replaced by `pseudonym(entity)`, and the
+    /// `(pseudonym, entity)` pairs used.
+    pub fn pseudonymize(
+        &self,
+        text: &str,
+        mut pseudonym: impl FnMut(&Entity) -> String,
+    ) -> (String, Vec<(String, Entity)>) {
+        let mut out = String::with_capacity(text.len());
+        let mut replaced = Vec::new();
+        let mut cursor = 0;
+        for entity in self.detect(text) {
+            let token = pseudonym(&entity);
+            out.push_str(&text[cursor..entity.start]);
+            out.push_str(&token);
+            cursor = entity.end;
+            replaced.push((token, entity));
+        }
+        out.push_str(&text[cursor..]);
+        (out, replaced)
+    }
+}
+
+/// The pseudonym of an entity labelled `label` whose keyed hash is `id`.
+pub fn pseudonym(label: &str, id: &str) -> String {
+    format!("[{}_{}]", label, id)
+}
+
+/// `text` with the pseudonyms `value` knows replaced by their values.
+/// Unknown pseudonyms are kept.
+pub fn reidentify(text: &str, mut value: impl FnMut(&str) -> Option<String>) -> String {
+    pseudonym_regex()
+        .replace_all(text, |captures: &regex::Captures| {
+            let token = &captures[0];
+            value(token).unwrap_or_else(|| token.to_string())
+        })
+        .into_owned()
+}
+
+/// The pseudonyms in `text`.
+pub fn pseudonyms(text: &str) -> Vec<String> {
+    pseudonym_regex()
+        .find_iter(text)
+        .map(|m| m.as_str().to_string())
+        .collect()
+}
+
+fn pseudonym_regex() -> &'static Regex {
+    static PSEUDONYM: OnceLock<Regex> = OnceLock::new();
+    PSEUDONYM.get_or_init(|| Regex::new(r"\[[A-Z][A-Z0-9_]*_[0-9a-f]{12}\]").unwrap())
+}
+
+fn luhn(digits: &[u32]) -> bool {
+    if !(13..=19).contains(&digits.len()) {
+        return false;
+    }
+    let sum: u32 = digits
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(i, &d)| match (i % 2, d * 2) {
+            (0, _) => d,
+            (_, doubled) if doubled > 9 => doubled - 9,
+            (_, doubled) => doubled,
+        })
+        .sum();
+    sum.is_multiple_of(10)
+}
+
+fn iban_checksum(text: &str) -> bool {
+    let compact: String = text.chars().filter(|c| !c.is_whitespace()).collect();
+    if !(15..=34).contains(&compact.len()) {
+        return false;
+    }
+    let (head, tail) = compact.split_at(4);
+    let mut remainder = 0u32;
+    for c in tail.chars().chain(head.chars()) {
+        let Some(value) = c.to_digit(36) else {
+            return false;
+        };
+        remainder = if value < 10 {
+            (remainder * 10 + value) % 97
+        } else {
+            (remainder * 100 + value) % 97
+        };
+    }
+    remainder == 1
+}
@@ -0,0 +1,147 @@
+//! Delivery of an agent's final answer to external sinks.
+//!
+//! An agent lists sinks under `output_sinks` in its definition. When a run
+//! finishes successfully with a text answer, the orchestrator delivers the
+//! answer (after `post_process`) to each sink in order, right after
+//! `RunFinished`, and emits an `OutputSinkFinished` event per sink. A failing
+//! sink is reported in its event and never fails the run.
+//!
+//! ```toml
+//! [[output_sinks]]
+//! type = "webhook"
+//! url = "https://hooks.example.com/runs"
+//! headers = { Authorization = "Bearer {{secret:HOOK_TOKEN}}" }
+//!
+//! [[output_sinks]]
+//! type = "artifact"
+//! filename = "reports.md"
+//!
+//! [[output_sinks]]
+//! type = "email"
+//! to = ["team@example.com"]
+//! subject = "Report from {{agent}}"
+//!
+//! [[output_sinks]]
+//! name = "notion"
+//! type = "document"
+//! provider = "notion"
+//! url = "https://api.notion.com/v1/pages"
+//! body = { parent = { page_id = "…" }, properties = { title = { title = [{ text = { content = "{{agent}} report" } }] } } }
+//! ```
+//!
+//! `subject` and the string values of `body` may use `{{agent}}`,
+//! `{{thread_id}}`, `{{task_id}}` and `{{content}}`.
+
+use std::collections::HashMap;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// One entry of an agent's `output_sinks`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct OutputSinkConfig {
+    /// Label of the sink in events and logs. Defaults to its type.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(flatten)]
+    pub kind: OutputSinkKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum OutputSinkKind {
+    /// POST an [`OutputPayload`] as JSON to `url`.
+    Webhook {
+        url: String,
+        /// Values may reference secrets as `{{secret:NAME}}`.
+        #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+        headers: HashMap<String, String>,
+    },
+    /// Append the answer to a text artifact of the thread, so every run of
+    /// the thread adds to the same file.
+    Artifact { filename: String },
+    /// Mail the answer as plain text through the Gmail API, sent from the
+    /// run user's connection of `provider` (it needs the `gmail.send`
+    /// scope).
+    Email {
+        to: Vec<String>,
+        #[serde(default = "default_subject")]
+        subject: String,
+        #[serde(default = "default_email_provider")]
+        provider: String,
+    },
+    /// POST `body` to a document API (Notion, Google Docs, Confluence…),
+    /// authenticated with the run user's connection of `provider`.
+    Document {
+        provider: String,
+        url: String,
+        /// Request body. Defaults to `{ "title": …, "content": "{{content}}" }`.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        body: Option<Value>,
+    },
+}
+
+fn default_subject() -> String {
+    "Result from {{agent}}".to_string()
+}
+
+fn default_email_provider() -> String {
+    "gmail".to_string()
+}
+
+impl OutputSinkConfig {
+    pub fn label(&self) -> String {
+        self.name
+            .clone()
+            .unwrap_or_else(|| self.kind.type_name().to_string())
+    }
+}
+
+impl OutputSinkKind {
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            OutputSinkKind::Webhook { .. } => "webhook",
+            OutputSinkKind::Artifact { .. } => "artifact",
+            OutputSinkKind::Email { .. } => "email",
+            OutputSinkKind::Document { .. } => "document",
+        }
+    }
+}
+
+/// What is delivered: the final answer of a run and where it came from.
+/// Sent as-is to webhooks.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct OutputPayload {
+    pub agent: String,
+    pub thread_id: String,
+    pub task_id: String,
+    pub content: String,
+}
+
+impl OutputPayload {
+    /// Replace the `{{…}}` placeholders in `template`.
+    pub fn render(&self, template: &str) -> String {
+        template
+            .replace("{{agent}}", &self.agent)
+            .replace("{{thread_id}}", &self.thread_id)
+            .replace("{{task_id}}", &self.task_id)
+            .replace("{{content}}", &self.content)
+    }
+
+    /// [`Self::render`] applied to every string in `value`.
+    pub fn render_value(&self, value: &Value) -> Value {
+        match value {
+            Value::String(s) => Value::String(self.render(s)),
+            Value::Array(items) => {
+                Value::Array(items.iter().map(|v| self.render_value(v)).collect())
+            }
+            Value::Object(map) => Value::Object(
+                map.iter()
+                    .map(|(k, v)| (k.clone(), self.render_value(v)))
+                    .collect(),
+            ),
+            other => other.clone(),
+        }
+    }
+}
@@ -0,0 +1,162 @@
+//! Multi-package workspaces: `distri.toml` manifests.
+//!
+//! A workspace can keep several packages, each with its own agents, in one
+//! repository. The root `distri.toml` lists the member directories; a
+//! member ending in `/*` stands for every directory under it that has a
+//! `distri.toml`:
+//!
+//! ```toml
+//! [workspace]
+//! members = ["support", "teams/*"]
+//! ```
+//!
+//! Each member's `distri.toml` names its package, and its agents live under
+//! its own `agents/` directory:
+//!
+//! ```toml
+//! [package]
+//! name = "support"
+//! version = "0.3.0"
+//! description = "Customer support agents"
+//! ```
+//!
+//! Building the workspace registers the agents of the root `agents/` under
+//! their own names and those of a member as `<package>/<agent>`. A member's
+//! agent without a `version` takes the package's, and `sub_agents` naming
+//! another agent of the same package are namespaced along with it.
+
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::{AgentError, StandardDefinition};
+
+/// File name of workspace and package manifests.
+pub const MANIFEST_FILE: &str = "distri.toml";
+
+/// A `distri.toml`: a workspace root, a member package, or both.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PackageManifest {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub workspace: Option<WorkspaceMembers>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub package: Option<PackageInfo>,
+}
+
+/// `[workspace]` of a root manifest.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WorkspaceMembers {
+    /// Member directories, relative to the root.
+    #[serde(default)]
+    pub members: Vec<String>,
+}
+
+/// `[package]` of a member manifest.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct PackageInfo {
+    /// Namespace of the package's agents.
+    pub name: String,
+    /// `MAJOR.MINOR.PATCH`, optionally with a pre-release or build suffix.
+    pub version: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+impl PackageManifest {
+    pub fn parse(text: &str) -> Result<Self, AgentError> {
+        toml::from_str(text).map_err(|e| AgentError::Validation(e.to_string()))
+    }
+
+    /// Whether the manifest declares a workspace or a package. A legacy
+    /// server config `distri.toml` declares neither.
+    pub fn is_package_manifest(&self) -> bool {
+        self.workspace.is_some() || self.package.is_some()
+    }
+}
+
+impl PackageInfo {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.name.contains('/') {
+            return Err(format!(
+                "package name '{}' cannot contain '/'; it is the namespace of its agents",
+                self.name
+            ));
+        }
+        crate::validate_plugin_name(&self.name)
+            .map_err(|e| format!("invalid package name: {}", e))?;
+        let core = self.version.split(['-', '+']).next().unwrap_or_default();
+        let parts: Vec<&str> = core.split('.').collect();
+        let numeric = |part: &&str| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit());
+        if parts.len() != 3 || !parts.iter().all(numeric) {
+            return Err(format!(
+                "package '{}' has version '{}'; use MAJOR.MINOR.PATCH",
+                self.name, self.version
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Registered name of `agent` of `package`.
+pub fn namespaced_name(package: &str, agent: &str) -> String {
+    format!("{}/{}", package, agent)
+}
+
+/// Namespace the agents of a member package in place (see the module docs).
+pub fn namespace_agents(
+    package: &PackageInfo,
+    agents: &mut [StandardDefinition],
+) -> Result<(), AgentError> {
+    let siblings: HashSet<String> = agents.iter().map(|a| a.name.clone()).collect();
+    for agent in agents.iter_mut() {
+        if agent.name.contains('/') {
+            return Err(AgentError::Validation(format!(
+                "agent '{}' of package '{}' is already namespaced; name it without a '/'",
+                agent.name, package.name
+            )));
+        }
+        agent.name = namespaced_name(&package.name, &agent.name);
+        if agent.version.is_none() {
+            agent.version = Some(package.version.clone());
+        }
+        for sub_agent in &mut agent.sub_agents {
+            if siblings.contains(sub_agent.as_str()) {
+                *sub_agent = namespaced_name(&package.name, sub_agent);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// What building a workspace registered.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct WorkspaceBuild {
+    /// Agents of the root `agents/` directory.
+    #[serde(default)]
+    pub agents: Vec<String>,
+    #[serde(default)]
+    pub packages: Vec<PackageBuild>,
+}
+
+/// One member package of a [`WorkspaceBuild`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct PackageBuild {
+    pub package: PackageInfo,
+    /// Member directory, relative to the workspace root.
+    pub path: String,
+    /// Registered (namespaced) agent names.
+    pub agents: Vec<String>,
+}
+
+impl WorkspaceBuild {
+    /// Registered agent names, the root's first.
+    pub fn agent_names(&self) -> impl Iterator<Item = &str> {
+        self.agents
+            .iter()
+            .chain(self.packages.iter().flat_map(|p| p.agents.iter()))
+            .map(String::as_str)
+    }
+}
@@ -0,0 +1,231 @@
+//! Capability manifests of plugins.
+//!
+//! A plugin ([`Integration`](crate::integration::Integration)) declares what
+//! its tools may touch: network hosts, filesystem paths, environment
+//! variables and secrets by provider. When the agent runs a plugin tool, the
+//! executor attaches a [`CapabilityGuard`] for the plugin to the tool's
+//! [`ToolContext`](crate::ToolContext), and the tool asks the context before
+//! each access:
+//!
+//! ```ignore
+//! context.check_network(&url)?;
+//! let token = context.env_var("ACME_TOKEN")?;
+//! ```
+//!
+//! Anything the manifest does not declare fails with [`CapabilityDenied`],
+//! which the executor returns to the model as a structured
+//! `capability_denied` result. A plugin without a manifest is granted
+//! nothing. Tools that are not part of a plugin run without a guard.
+
+use std::path::{Component, Path, PathBuf};
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// What a plugin's tools may access. Empty lists grant nothing.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct PluginCapabilities {
+    /// Hosts the plugin may connect to. `*.example.com` also matches
+    /// subdomains; `*` matches any host.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub network: Vec<String>,
+    /// Paths (and everything below them) the plugin may read, or write when
+    /// `write` is set.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub filesystem: Vec<FilesystemGrant>,
+    /// Environment variables the plugin may read.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub env: Vec<String>,
+    /// Secret providers (e.g. `openai`) whose secrets the plugin may read.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub secrets: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct FilesystemGrant {
+    pub path: String,
+    #[serde(default)]
+    pub write: bool,
+}
+
+/// A plugin's granted capabilities, for review.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct PluginGrant {
+    pub plugin: String,
+    /// Agents the plugin's tools are registered for.
+    pub agents: Vec<String>,
+    pub tools: Vec<String>,
+    pub capabilities: PluginCapabilities,
+}
+
+/// A kind of access a plugin can be granted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Capability {
+    Network,
+    FilesystemRead,
+    FilesystemWrite,
+    Env,
+    Secret,
+}
+
+impl Capability {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Network => "network",
+            Self::FilesystemRead => "filesystem_read",
+            Self::FilesystemWrite => "filesystem_write",
+            Self::Env => "env",
+            Self::Secret => "secret",
+        }
+    }
+}
+
+/// An access the plugin's manifest does not declare.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, thiserror::Error)]
+#[error("plugin '{plugin}' has no {} capability for '{target}'", capability.as_str())]
+pub struct CapabilityDenied {
+    pub plugin: String,
+    pub capability: Capability,
+    pub target: String,
+}
+
+impl CapabilityDenied {
+    /// The tool result reported to the model.
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "error": "capability_denied",
+            "plugin": self.plugin,
+            "capability": self.capability,
+            "target": self.target,
+            "message": self.to_string(),
+        })
+    }
+}
+
+/// Checks a plugin's accesses against its manifest.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CapabilityGuard {
+    plugin: String,
+    capabilities: PluginCapabilities,
+}
+
+impl CapabilityGuard {
+    pub fn new(plugin: impl Into<String>, capabilities: PluginCapabilities) -> Self {
+        Self {
+            plugin: plugin.into(),
+            capabilities,
+        }
+    }
+
+    pub fn plugin(&self) -> &str {
+        &self.plugin
+    }
+
+    pub fn capabilities(&self) -> &PluginCapabilities {
+        &self.capabilities
+    }
+
+    /// Whether the plugin may connect to `url` (or a bare host name).
+    pub fn check_network(&self, url: &str) -> Result<(), CapabilityDenied> {
+        let host = url_host(url);
+        let allowed = self
+            .capabilities
+            .network
+            .iter()
+            .any(|pattern| host_matches(pattern, &host));
+        self.allow(allowed, Capability::Network, url)
+    }
+
+    /// Whether the plugin may read `path`, or write it when `write`.
+    pub fn check_path(&self, path: &Path, write: bool) -> Result<(), CapabilityDenied> {
+        let path = normalize(path);
+        let allowed = self.capabilities.filesystem.iter().any(|grant| {
+            (grant.write || !write) && path.starts_with(normalize(Path::new(&grant.path)))
+        });
+        let capability = if write {
+            Capability::FilesystemWrite
+        } else {
+            Capability::FilesystemRead
+        };
+        self.allow(allowed, capability, &path.display().to_string())
+    }
+
+    /// Whether the plugin may read environment variable `name`.
+    pub fn check_env(&self, name: &str) -> Result<(), CapabilityDenied> {
+        let allowed = self.capabilities.env.iter().any(|e| e == name);
+        self.allow(allowed, Capability::Env, name)
+    }
+
+    /// Whether the plugin may read the secrets of `provider`.
+    pub fn check_secret(&self, provider: &str) -> Result<(), CapabilityDenied> {
+        let allowed = self
+            .capabilities
+            .secrets
+            .iter()
+            .any(|p| p.eq_ignore_ascii_case(provider));
+        self.allow(allowed, Capability::Secret, provider)
+    }
+
+    fn allow(
+        &self,
+        allowed: bool,
+        capability: Capability,
+        target: &str,
+    ) -> Result<(), CapabilityDenied> {
+        if allowed {
+            return Ok(());
+        }
+        Err(CapabilityDenied {
+            plugin: self.plugin.clone(),
+            capability,
+            target: target.to_string(),
+        })
+    }
+}
+
+/// Lower-cased host of `url`; a bare host name is its own host. The
+/// trailing dot of a fully qualified name (`metadata.internal.`) is dropped.
+pub(crate) fn url_host(url: &str) -> String {
+    url::Url::parse(url)
+        .ok()
+        .and_then(|u| u.host_str().map(str::to_string))
+        .unwrap_or_else(|| url.to_string())
+        .trim_end_matches('.')
+        .to_ascii_lowercase()
+}
+
+pub(crate) fn host_matches(pattern: &str, host: &str) -> bool {
+    let pattern = pattern.trim().trim_end_matches('.').to_ascii_lowercase();
+    let host = host.trim_end_matches('.');
+    if pattern == "*" {
+        return true;
+    }
+    match pattern.strip_prefix("*.") {
+        Some(domain) => host == domain || host.ends_with(&format!(".{}", domain)),
+        None => host == pattern,
+    }
+}
+
+/// `path` made absolute (against the working directory) with `.` and `..`
+/// resolved, so `..` cannot step out of a granted directory.
+fn normalize(path: &Path) -> PathBuf {
+    let path = if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        std::env::current_dir().unwrap_or_default().join(path)
+    };
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            Component::CurDir => {}
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
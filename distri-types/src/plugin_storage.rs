@@ -0,0 +1,183 @@
+//! Persistent key-value storage of plugins.
+//!
+//! Each plugin ([`Integration`](crate::integration::Integration)) gets its
+//! own namespace of JSON values that outlives the call, the run and the
+//! server process — cursors, caches, rate-limit windows. Plugin tools reach
+//! it through their [`ToolContext`](crate::ToolContext):
+//!
+//! ```ignore
+//! let storage = context.storage()?;
+//! let cursor = storage.get("orders_cursor").await?;
+//! storage.set("orders_cursor", &json!("c_1042")).await?;
+//! ```
+//!
+//! A plugin only ever sees its own keys. The store enforces the quotas of
+//! `stores.metadata.plugin_storage`: the size of a value, and the number of
+//! keys and bytes of a plugin. Tools that are not part of a plugin get no
+//! storage.
+
+use std::sync::Arc;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use utoipa::ToSchema;
+
+use crate::stores::PluginKvStore;
+
+/// Longest key, in bytes.
+pub const MAX_KEY_BYTES: usize = 256;
+
+/// Quotas of each plugin's storage.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, ToSchema, PartialEq, Eq)]
+pub struct PluginStorageConfig {
+    /// Largest value, in bytes of JSON.
+    #[serde(default = "default_max_value_bytes")]
+    pub max_value_bytes: usize,
+    /// Most keys a plugin may store.
+    #[serde(default = "default_max_keys")]
+    pub max_keys: usize,
+    /// Most bytes, keys and values together, a plugin may store.
+    #[serde(default = "default_max_total_bytes")]
+    pub max_total_bytes: usize,
+}
+
+fn default_max_value_bytes() -> usize {
+    64 * 1024
+}
+
+fn default_max_keys() -> usize {
+    1000
+}
+
+fn default_max_total_bytes() -> usize {
+    5 * 1024 * 1024
+}
+
+impl Default for PluginStorageConfig {
+    fn default() -> Self {
+        Self {
+            max_value_bytes: default_max_value_bytes(),
+            max_keys: default_max_keys(),
+            max_total_bytes: default_max_total_bytes(),
+        }
+    }
+}
+
+/// Keys and bytes a plugin stores.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PluginStorageUsage {
+    pub keys: usize,
+    pub bytes: usize,
+}
+
+impl PluginStorageConfig {
+    /// Whether `plugin` may store a value of `value_bytes`, leaving it with
+    /// `usage` once stored.
+    pub fn check(
+        &self,
+        plugin: &str,
+        value_bytes: usize,
+        usage: PluginStorageUsage,
+    ) -> Result<(), PluginStorageError> {
+        let exceeded = |reason: String| {
+            Err(PluginStorageError::QuotaExceeded {
+                plugin: plugin.to_string(),
+                reason,
+            })
+        };
+        if value_bytes > self.max_value_bytes {
+            return exceeded(format!(
+                "value of {} bytes is over the {} byte limit",
+                value_bytes, self.max_value_bytes
+            ));
+        }
+        if usage.keys > self.max_keys {
+            return exceeded(format!("more than {} keys", self.max_keys));
+        }
+        if usage.bytes > self.max_total_bytes {
+            return exceeded(format!("more than {} bytes stored", self.max_total_bytes));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum PluginStorageError {
+    /// The tool is not part of a plugin, or the server has no plugin store.
+    #[error("plugin storage is not available to this tool")]
+    Unavailable,
+    #[error("invalid plugin storage key '{0}': keys are 1 to {max} bytes", max = MAX_KEY_BYTES)]
+    InvalidKey(String),
+    #[error("plugin '{plugin}' storage quota exceeded: {reason}")]
+    QuotaExceeded { plugin: String, reason: String },
+    #[error(transparent)]
+    Store(anyhow::Error),
+}
+
+impl From<anyhow::Error> for PluginStorageError {
+    /// Quota errors raised by the store keep their kind.
+    fn from(error: anyhow::Error) -> Self {
+        match error.downcast::<PluginStorageError>() {
+            Ok(error) => error,
+            Err(error) => Self::Store(error),
+        }
+    }
+}
+
+/// The storage of one plugin.
+#[derive(Clone)]
+pub struct PluginStorage {
+    plugin: String,
+    store: Arc<dyn PluginKvStore>,
+}
+
+impl std::fmt::Debug for PluginStorage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PluginStorage")
+            .field("plugin", &self.plugin)
+            .finish_non_exhaustive()
+    }
+}
+
+impl PluginStorage {
+    pub fn new(plugin: impl Into<String>, store: Arc<dyn PluginKvStore>) -> Self {
+        Self {
+            plugin: plugin.into(),
+            store,
+        }
+    }
+
+    pub fn plugin(&self) -> &str {
+        &self.plugin
+    }
+
+    pub async fn get(&self, key: &str) -> Result<Option<Value>, PluginStorageError> {
+        check_key(key)?;
+        Ok(self.store.get(&self.plugin, key).await?)
+    }
+
+    /// Store `value` under `key`, replacing any value it had.
+    pub async fn set(&self, key: &str, value: &Value) -> Result<(), PluginStorageError> {
+        check_key(key)?;
+        Ok(self.store.set(&self.plugin, key, value).await?)
+    }
+
+    /// Remove `key`; whether it was stored.
+    pub async fn delete(&self, key: &str) -> Result<bool, PluginStorageError> {
+        check_key(key)?;
+        Ok(self.store.delete(&self.plugin, key).await?)
+    }
+
+    /// Stored keys starting with `prefix`, in order.
+    pub async fn list(&self, prefix: &str) -> Result<Vec<String>, PluginStorageError> {
+        Ok(self.store.list(&self.plugin, prefix).await?)
+    }
+}
+
+fn check_key(key: &str) -> Result<(), PluginStorageError> {
+    if key.is_empty() || key.len() > MAX_KEY_BYTES {
+        return Err(PluginStorageError::InvalidKey(key.to_string()));
+    }
+    Ok(())
+}
@@ -0,0 +1,160 @@
+//! Spans recorded by plugin tools.
+//!
+//! A tool gets the trace of the run it executes in through
+//! [`ToolContext::trace`](crate::ToolContext::trace) and can pass it on to the
+//! services it calls with [`ToolContext::traceparent`](crate::ToolContext::traceparent).
+//! To make its own steps visible it opens spans with
+//! [`ToolContext::span`](crate::ToolContext::span):
+//!
+//! ```ignore
+//! let mut span = context.span("fetch_rows");
+//! span.set_attribute("table", "orders");
+//! let rows = fetch().await?;
+//! span.log(format!("{} rows", rows.len()));
+//! span.end();
+//! ```
+//!
+//! Finished spans are collected by the context's [`PluginSpanRecorder`]. When
+//! the tool returns, the agent emits them as one `plugin_spans` event, which is
+//! stored with the task and exported as child spans of the tool call.
+
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// A message a span logged, `offset_ms` after the span started.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PluginSpanLog {
+    pub offset_ms: u64,
+    pub message: String,
+}
+
+/// A finished plugin span.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PluginSpanRecord {
+    pub name: String,
+    /// W3C span-id, 16 lowercase hex chars.
+    pub span_id: String,
+    /// The enclosing plugin span, or the caller's span for top-level spans.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent_span_id: Option<String>,
+    /// Start of the span, relative to the start of the tool call.
+    pub start_offset_ms: u64,
+    pub duration_ms: u64,
+    /// Set when the span was finished with [`PluginSpan::fail`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(default, skip_serializing_if = "Map::is_empty")]
+    pub attributes: Map<String, Value>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub logs: Vec<PluginSpanLog>,
+}
+
+/// Collects the spans of one tool call. Clones share the same records.
+#[derive(Debug, Clone)]
+pub struct PluginSpanRecorder {
+    started: Instant,
+    spans: Arc<Mutex<Vec<(Instant, PluginSpanRecord)>>>,
+}
+
+impl Default for PluginSpanRecorder {
+    fn default() -> Self {
+        Self {
+            started: Instant::now(),
+            spans: Arc::default(),
+        }
+    }
+}
+
+impl PluginSpanRecorder {
+    /// Start a span. `parent_span_id` is the span it nests under, if any.
+    pub fn start(&self, name: impl Into<String>, parent_span_id: Option<String>) -> PluginSpan {
+        let started = Instant::now();
+        PluginSpan {
+            record: PluginSpanRecord {
+                name: name.into(),
+                span_id: new_span_id(),
+                parent_span_id,
+                start_offset_ms: started.duration_since(self.started).as_millis() as u64,
+                ..Default::default()
+            },
+            started,
+            recorder: self.clone(),
+        }
+    }
+
+    /// Remove and return the finished spans, in the order they started.
+    pub fn take(&self) -> Vec<PluginSpanRecord> {
+        let mut spans = match self.spans.lock() {
+            Ok(mut guard) => std::mem::take(&mut *guard),
+            Err(_) => return Vec::new(),
+        };
+        spans.sort_by_key(|(started, _)| *started);
+        spans.into_iter().map(|(_, record)| record).collect()
+    }
+
+    fn finish(&self, started: Instant, record: PluginSpanRecord) {
+        if let Ok(mut guard) = self.spans.lock() {
+            guard.push((started, record));
+        }
+    }
+}
+
+/// An open plugin span. It is recorded when it is ended, failed, or dropped.
+#[derive(Debug)]
+pub struct PluginSpan {
+    record: PluginSpanRecord,
+    started: Instant,
+    recorder: PluginSpanRecorder,
+}
+
+impl PluginSpan {
+    pub fn span_id(&self) -> &str {
+        &self.record.span_id
+    }
+
+    /// Start a span nested under this one.
+    pub fn child(&self, name: impl Into<String>) -> PluginSpan {
+        self.recorder.start(name, Some(self.record.span_id.clone()))
+    }
+
+    pub fn set_attribute(&mut self, key: impl Into<String>, value: impl Into<Value>) {
+        self.record.attributes.insert(key.into(), value.into());
+    }
+
+    /// Record a log event on the span.
+    pub fn log(&mut self, message: impl Into<String>) {
+        self.record.logs.push(PluginSpanLog {
+            offset_ms: self.started.elapsed().as_millis() as u64,
+            message: message.into(),
+        });
+    }
+
+    /// Finish the span successfully.
+    pub fn end(self) {}
+
+    /// Finish the span as failed.
+    pub fn fail(mut self, error: impl Into<String>) {
+        self.record.error = Some(error.into());
+    }
+}
+
+impl Drop for PluginSpan {
+    fn drop(&mut self) {
+        let mut record = std::mem::take(&mut self.record);
+        record.duration_ms = self.started.elapsed().as_millis() as u64;
+        self.recorder.finish(self.started, record);
+    }
+}
+
+fn new_span_id() -> String {
+    let mut bytes = [0u8; 8];
+    rand::fill(&mut bytes);
+    // All-zero span ids are invalid in W3C trace context.
+    if bytes.iter().all(|&b| b == 0) {
+        bytes[7] = 1;
+    }
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
@@ -0,0 +1,134 @@
+//! Post-processing of an agent's final answer.
+//!
+//! An agent lists processors under `post_process` in its definition; they run
+//! in order on the final text once the run finishes, before the answer is
+//! returned and `RunFinished` is emitted. Structured (non-text) final results
+//! are left untouched.
+//!
+//! ```toml
+//! [[post_process]]
+//! type = "rewrite_links"
+//! rules = [{ from = "/docs/", to = "https://distri.dev/docs/" }]
+//!
+//! [[post_process]]
+//! type = "extract_code_blocks"
+//! languages = ["python", "sql"]
+//!
+//! [[post_process]]
+//! type = "wrap"
+//! template = "{{response}}\n\n— {{agent}}"
+//! ```
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// One step of an agent's `post_process` chain.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PostProcessorConfig {
+    /// Rewrite markdown link and image targets by prefix. The first matching
+    /// rule wins.
+    RewriteLinks { rules: Vec<LinkRewriteRule> },
+    /// Move fenced code blocks into task artifacts and leave a link to the
+    /// artifact in their place.
+    ExtractCodeBlocks {
+        /// Only extract blocks tagged with one of these languages. Empty
+        /// extracts every block.
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        languages: Vec<String>,
+        /// Blocks shorter than this stay inline.
+        #[serde(default = "default_min_lines")]
+        min_lines: usize,
+    },
+    /// Wrap the answer in a template. `{{response}}` is replaced by the
+    /// answer and `{{agent}}` by the agent name.
+    Wrap { template: String },
+    /// A transformer the hosting application registered on the orchestrator
+    /// under `name`. `options` is passed through untouched.
+    Custom {
+        name: String,
+        #[serde(default, skip_serializing_if = "Value::is_null")]
+        options: Value,
+    },
+}
+
+fn default_min_lines() -> usize {
+    1
+}
+
+/// `from` is matched as a prefix of the link target.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct LinkRewriteRule {
+    pub from: String,
+    pub to: String,
+}
+
+impl LinkRewriteRule {
+    /// The rewritten target, or `None` when the rule does not apply.
+    pub fn apply(&self, target: &str) -> Option<String> {
+        target
+            .strip_prefix(&self.from)
+            .map(|rest| format!("{}{}", self.to, rest))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_each_processor_kind() {
+        let toml = r#"
+[[post_process]]
+type = "rewrite_links"
+rules = [{ from = "/docs/", to = "https://distri.dev/docs/" }]
+
+[[post_process]]
+type = "extract_code_blocks"
+languages = ["python"]
+
+[[post_process]]
+type = "wrap"
+template = "{{response}}\n-- bot"
+
+[[post_process]]
+type = "custom"
+name = "redact"
+options = { level = "strict" }
+"#;
+        #[derive(Deserialize)]
+        struct Doc {
+            post_process: Vec<PostProcessorConfig>,
+        }
+        let doc: Doc = toml::from_str(toml).unwrap();
+        assert_eq!(doc.post_process.len(), 4);
+        assert_eq!(
+            doc.post_process[1],
+            PostProcessorConfig::ExtractCodeBlocks {
+                languages: vec!["python".to_string()],
+                min_lines: 1,
+            }
+        );
+        match &doc.post_process[3] {
+            PostProcessorConfig::Custom { name, options } => {
+                assert_eq!(name, "redact");
+                assert_eq!(options["level"], "strict");
+            }
+            other => panic!("unexpected {other:?}"),
+        }
+    }
+
+    #[test]
+    fn link_rule_matches_by_prefix() {
+        let rule = LinkRewriteRule {
+            from: "/docs/".to_string(),
+            to: "https://distri.dev/docs/".to_string(),
+        };
+        assert_eq!(
+            rule.apply("/docs/agents").as_deref(),
+            Some("https://distri.dev/docs/agents")
+        );
+        assert_eq!(rule.apply("https://example.com"), None);
+    }
+}
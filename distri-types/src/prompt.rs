@@ -1,11 +1,13 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::path::Path;
 use std::sync::Arc;
 
 use handlebars::Handlebars;
 use handlebars::handlebars_helper;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use tokio::sync::RwLock;
+use utoipa::ToSchema;
 
 use crate::{AgentError, ContextBudget, Message, Part};
 
@@ -90,6 +92,111 @@ pub struct TemplateData<'a> {
     /// `agent::strategy::planning::formatter::build_messages`.
     #[serde(default)]
     pub runtime_mode: &'a str,
+    /// Values for the `runtime_context` partial (the runtime layer). Fields
+    /// the agent did not opt into are empty.
+    #[serde(default)]
+    pub runtime_context: RuntimeContextData,
+}
+
+/// Runtime-injected context rendered by the `runtime_context` partial.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RuntimeContextData {
+    pub date: String,
+    /// Date, time, timezone and business calendar of an agent with a
+    /// `datetime` (see [`crate::datetime`]).
+    #[serde(default)]
+    pub datetime: String,
+    pub user_profile: String,
+    pub memory_summary: String,
+    /// Recent artifacts of the thread, one per line.
+    #[serde(default)]
+    pub artifacts: String,
+}
+
+/// Layers of a composed system prompt. Declaration order is render order:
+/// the stable, cacheable layers come first.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+    JsonSchema,
+    ToSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum PromptLayerKind {
+    /// The agent's own instructions.
+    Persona,
+    /// Workspace-wide policy from the server config.
+    WorkspacePolicy,
+    /// The built-in planning template (tools, steps, reasoning).
+    Framework,
+    /// The available-skills listing.
+    Skills,
+    /// Per-run context: date and time, user profile, memory summary.
+    Runtime,
+}
+
+/// One rendered layer of a system prompt.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, ToSchema)]
+pub struct PromptLayer {
+    pub kind: PromptLayerKind,
+    pub content: String,
+    /// Estimated tokens of `content`.
+    pub tokens: usize,
+}
+
+/// Join rendered layers into the system prompt, in [`PromptLayerKind`]
+/// order. Empty layers are dropped.
+pub fn compose_prompt_layers(layers: &[PromptLayer]) -> String {
+    let mut ordered: Vec<&PromptLayer> = layers
+        .iter()
+        .filter(|l| !l.content.trim().is_empty())
+        .collect();
+    ordered.sort_by_key(|l| l.kind);
+    ordered
+        .iter()
+        .map(|l| l.content.trim_end())
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+/// Per-agent choice of optional prompt layers.
+///
+/// ```toml
+/// [prompt_layers]
+/// runtime = ["date", "memory_summary"]
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct PromptLayersConfig {
+    /// Leave out the workspace policy layer.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub skip_workspace_policy: bool,
+    /// Context injected into the runtime layer. Empty omits the layer.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub runtime: Vec<RuntimeContextItem>,
+}
+
+/// An item of the runtime layer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RuntimeContextItem {
+    /// Today's date (UTC).
+    Date,
+    /// The user's preferences (see [`crate::user_profile`]).
+    UserProfile,
+    /// The user's permanent memories.
+    MemorySummary,
+    /// Artifacts saved earlier in the thread — name, summary and id — for
+    /// the `load_artifact` tool.
+    Artifacts,
 }
 
 /// A single tool's prompt entry for template iteration.
@@ -215,6 +322,10 @@ impl PromptRegistry {
                 "channel_formatting",
                 include_str!("../prompt_templates/partials/channel_formatting.hbs"),
             ),
+            (
+                "runtime_context",
+                include_str!("../prompt_templates/partials/runtime_context.hbs"),
+            ),
         ];
 
         let mut partials_lock = self.partials.write().await;
@@ -428,6 +539,26 @@ impl PromptRegistry {
         templates.get(name).cloned()
     }
 
+    /// The variant of template `name` to render in `locale`, falling back to
+    /// broader locales and then the default template (see
+    /// [`crate::prompt_locale`]).
+    pub async fn get_localized_template(
+        &self,
+        name: &str,
+        locale: Option<&str>,
+    ) -> Option<PromptTemplate> {
+        let templates = self.templates.read().await;
+        let key =
+            crate::prompt_locale::best_variant(templates.keys().map(String::as_str), name, locale)?;
+        templates.get(key).cloned()
+    }
+
+    /// Which locales each registered template is translated into.
+    pub async fn translation_coverage(&self) -> Vec<crate::prompt_locale::TemplateCoverage> {
+        let templates = self.templates.read().await;
+        crate::prompt_locale::translation_coverage(templates.keys().map(String::as_str))
+    }
+
     pub async fn get_partial(&self, name: &str) -> Option<String> {
         let partials = self.partials.read().await;
         partials.get(name).cloned()
@@ -532,6 +663,45 @@ impl PromptRegistry {
             .map(|_| ())
             .map_err(|e| AgentError::Planning(format!("Failed to render template: {}", e)))
     }
+
+    /// Replace `{{name}}` (or `{{{name}}}`) with the value of `name` in
+    /// `variables`, as-is. Other placeholders are left untouched, so text
+    /// that merely contains braces renders unchanged. Used for a thread's
+    /// variables in its user messages and system prompt.
+    pub fn render_variables(text: &str, variables: &BTreeMap<String, String>) -> String {
+        if variables.is_empty() || !text.contains("{{") {
+            return text.to_string();
+        }
+        let mut rendered = String::with_capacity(text.len());
+        let mut rest = text;
+        while let Some(start) = rest.find("{{") {
+            rendered.push_str(&rest[..start]);
+            let tail = &rest[start..];
+            let (open, close) = if tail.starts_with("{{{") {
+                ("{{{", "}}}")
+            } else {
+                ("{{", "}}")
+            };
+            let body = &tail[open.len()..];
+            let value = body.find(close).and_then(|end| {
+                variables
+                    .get(body[..end].trim())
+                    .map(|value| (value, open.len() + end + close.len()))
+            });
+            match value {
+                Some((value, consumed)) => {
+                    rendered.push_str(value);
+                    rest = &tail[consumed..];
+                }
+                None => {
+                    rendered.push_str(open);
+                    rest = body;
+                }
+            }
+        }
+        rendered.push_str(rest);
+        rendered
+    }
 }
 
 /// Parse-only validation for a user-authored handlebars template (skill
@@ -884,6 +1054,7 @@ mod tests {
             deferred_tools_listing: None,
             channel_kind: None,
             runtime_mode: "",
+            runtime_context: Default::default(),
         };
         let msgs = build_prompt_messages(
             &registry,
@@ -1006,6 +1177,45 @@ mod tests {
         assert!(!result.budget.is_warning());
     }
 
+    #[test]
+    fn compose_prompt_layers_orders_by_kind_and_drops_empty() {
+        let layer = |kind, content: &str| PromptLayer {
+            kind,
+            content: content.to_string(),
+            tokens: rough_token_count(content),
+        };
+        let composed = compose_prompt_layers(&[
+            layer(PromptLayerKind::Runtime, "# CURRENT DATE\n2025-01-01\n"),
+            layer(PromptLayerKind::Skills, "  \n"),
+            layer(PromptLayerKind::Persona, "You are a helper."),
+            layer(PromptLayerKind::WorkspacePolicy, "Never share secrets."),
+        ]);
+        assert_eq!(
+            composed,
+            "You are a helper.\n\nNever share secrets.\n\n# CURRENT DATE\n2025-01-01"
+        );
+    }
+
+    #[tokio::test]
+    async fn runtime_context_partial_renders_only_filled_items() {
+        let registry = PromptRegistry::with_defaults().await.unwrap();
+        let data = TemplateData {
+            runtime_context: RuntimeContextData {
+                date: "2025-01-01 (Wednesday)".into(),
+                memory_summary: "- prefers metric units".into(),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let rendered = registry
+            .render_template("{{> runtime_context}}", &data)
+            .await
+            .unwrap();
+        assert!(rendered.contains("2025-01-01 (Wednesday)"));
+        assert!(rendered.contains("- prefers metric units"));
+        assert!(!rendered.contains("USER PROFILE"));
+    }
+
     #[test]
     fn test_compute_hash_deterministic() {
         let hash1 = compute_hash("test content");
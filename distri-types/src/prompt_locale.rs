@@ -0,0 +1,255 @@
+//! Localized prompt templates.
+//!
+//! A template registered as `name@locale` (`planning@de`, `user@pt-BR`) is a
+//! translation of `name`. At render time the run's locale — the user
+//! profile's `locale`, else the language the incoming message is written in
+//! — picks the most specific variant: `planning@de-AT`, then `planning@de`,
+//! then the default `planning`.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use utoipa::ToSchema;
+
+/// Separates a template name from its locale: `planning@de`.
+pub const LOCALE_SEPARATOR: char = '@';
+
+/// Canonical form of a locale tag: `de_at` → `de-AT`, `ZH-hant` → `zh-Hant`.
+/// `None` when `tag` is not a language tag.
+pub fn normalize_locale(tag: &str) -> Option<String> {
+    let mut parts = tag.trim().split(['-', '_']);
+    let language = parts.next()?;
+    if !(2..=3).contains(&language.len()) || !language.chars().all(|c| c.is_ascii_alphabetic()) {
+        return None;
+    }
+    let mut normalized = language.to_ascii_lowercase();
+    for part in parts {
+        if part.is_empty() || part.len() > 8 || !part.chars().all(|c| c.is_ascii_alphanumeric()) {
+            return None;
+        }
+        normalized.push('-');
+        match part.len() {
+            2 => normalized.push_str(&part.to_ascii_uppercase()),
+            4 if part.chars().all(|c| c.is_ascii_alphabetic()) => {
+                normalized.push_str(&part[..1].to_ascii_uppercase());
+                normalized.push_str(&part[1..].to_ascii_lowercase());
+            }
+            _ => normalized.push_str(&part.to_ascii_lowercase()),
+        }
+    }
+    Some(normalized)
+}
+
+/// The locales to try for `locale`, most specific first:
+/// `zh-Hant-TW` → `zh-Hant-TW`, `zh-Hant`, `zh`.
+pub fn locale_candidates(locale: &str) -> Vec<String> {
+    let Some(locale) = normalize_locale(locale) else {
+        return vec![];
+    };
+    let parts: Vec<&str> = locale.split('-').collect();
+    (1..=parts.len())
+        .rev()
+        .map(|n| parts[..n].join("-"))
+        .collect()
+}
+
+/// Split a template name into its base name and locale. Names without a
+/// valid locale suffix are default templates.
+pub fn split_localized_name(name: &str) -> (&str, Option<String>) {
+    match name.rsplit_once(LOCALE_SEPARATOR) {
+        Some((base, locale)) if !base.is_empty() => match normalize_locale(locale) {
+            Some(locale) => (base, Some(locale)),
+            None => (name, None),
+        },
+        _ => (name, None),
+    }
+}
+
+/// How well the template registered as `key` serves `name` in `locale`:
+/// `0` for the exact locale, larger for broader ones, largest for the default
+/// template. `None` when `key` is another template or another language.
+pub fn variant_rank(key: &str, name: &str, locale: Option<&str>) -> Option<usize> {
+    let candidates = locale.map(locale_candidates).unwrap_or_default();
+    match split_localized_name(key) {
+        (base, None) if base == name => Some(candidates.len()),
+        (base, Some(variant)) if base == name => candidates.iter().position(|c| *c == variant),
+        _ => None,
+    }
+}
+
+/// Of the registered template `keys`, the one to render `name` with in
+/// `locale`.
+pub fn best_variant<'a>(
+    keys: impl IntoIterator<Item = &'a str>,
+    name: &str,
+    locale: Option<&str>,
+) -> Option<&'a str> {
+    keys.into_iter()
+        .filter_map(|key| variant_rank(key, name, locale).map(|rank| (rank, key)))
+        .min_by_key(|(rank, _)| *rank)
+        .map(|(_, key)| key)
+}
+
+/// The locale a `user_profile` session value asks for, from its `locale` or
+/// `language` field.
+pub fn profile_locale(profile: &Value) -> Option<String> {
+    ["locale", "language"]
+        .iter()
+        .filter_map(|field| profile.get(field).and_then(Value::as_str))
+        .find_map(normalize_locale)
+}
+
+/// Common short words of the Latin-script languages [`detect_locale`]
+/// recognises.
+const STOPWORDS: &[(&str, &[&str])] = &[
+    (
+        "en",
+        &[
+            "the", "and", "is", "are", "you", "what", "how", "with", "this", "that", "for",
+            "please", "can", "of", "to",
+        ],
+    ),
+    (
+        "de",
+        &[
+            "der", "die", "das", "und", "ist", "nicht", "ich", "sie", "mit", "ein", "eine", "wie",
+            "was", "bitte", "für", "auf", "zu",
+        ],
+    ),
+    (
+        "fr",
+        &[
+            "le", "la", "les", "et", "est", "pas", "je", "vous", "une", "des", "avec", "pour",
+            "que", "qui", "merci", "du", "dans",
+        ],
+    ),
+    (
+        "es",
+        &[
+            "el", "los", "las", "y", "es", "por", "una", "con", "para", "que", "qué", "cómo",
+            "gracias", "está", "del", "en",
+        ],
+    ),
+    (
+        "it",
+        &[
+            "il", "gli", "e", "è", "non", "una", "per", "che", "sono", "come", "grazie", "della",
+            "questo", "di",
+        ],
+    ),
+    (
+        "pt",
+        &[
+            "os", "não", "uma", "com", "para", "que", "você", "obrigado", "obrigada", "está", "é",
+            "do", "da", "em",
+        ],
+    ),
+    (
+        "nl",
+        &[
+            "het", "een", "en", "niet", "ik", "je", "met", "voor", "van", "dat", "wat", "hoe",
+            "bedankt", "op",
+        ],
+    ),
+];
+
+/// Guess the language `text` is written in. Scripts identify most non-Latin
+/// languages outright; Latin-script text is matched against common words and
+/// needs two more hits for one language than for any other. `None` when
+/// unsure.
+pub fn detect_locale(text: &str) -> Option<&'static str> {
+    let mut latin = 0;
+    let mut scripts: BTreeMap<&'static str, usize> = BTreeMap::new();
+    for c in text.chars().filter(|c| c.is_alphabetic()) {
+        let script = match c as u32 {
+            0x3040..=0x30FF => "ja",
+            0xAC00..=0xD7AF | 0x1100..=0x11FF => "ko",
+            0x4E00..=0x9FFF => "zh",
+            0x0400..=0x04FF => "ru",
+            0x0600..=0x06FF => "ar",
+            0x0590..=0x05FF => "he",
+            0x0370..=0x03FF => "el",
+            0x0900..=0x097F => "hi",
+            0x0E00..=0x0E7F => "th",
+            _ => {
+                latin += 1;
+                continue;
+            }
+        };
+        *scripts.entry(script).or_default() += 1;
+    }
+    // Japanese mixes kana with Han characters.
+    if scripts.contains_key("ja") {
+        return Some("ja");
+    }
+    let dominant = scripts
+        .into_iter()
+        .max_by_key(|(_, count)| *count)
+        .filter(|(_, count)| *count >= latin);
+    if let Some((script, _)) = dominant {
+        return Some(script);
+    }
+
+    let words: Vec<String> = text
+        .split(|c: char| !c.is_alphabetic())
+        .filter(|w| !w.is_empty())
+        .map(str::to_lowercase)
+        .collect();
+    let mut scores: Vec<(usize, &'static str)> = STOPWORDS
+        .iter()
+        .map(|(locale, stopwords)| {
+            let hits = words
+                .iter()
+                .filter(|w| stopwords.contains(&w.as_str()))
+                .count();
+            (hits, *locale)
+        })
+        .collect();
+    scores.sort_by_key(|(hits, _)| std::cmp::Reverse(*hits));
+    match scores.as_slice() {
+        [(best, locale), (second, _), ..] if *best >= second + 2 => Some(locale),
+        _ => None,
+    }
+}
+
+/// Translation coverage of one template.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema, ToSchema)]
+pub struct TemplateCoverage {
+    pub name: String,
+    /// Whether the template has a default variant to fall back to.
+    pub has_default: bool,
+    /// Locales the template is translated into.
+    pub locales: Vec<String>,
+    /// Locales other templates are translated into but this one is not.
+    pub missing: Vec<String>,
+}
+
+/// Coverage of every template among the registered `names`, by name.
+pub fn translation_coverage<'a>(names: impl IntoIterator<Item = &'a str>) -> Vec<TemplateCoverage> {
+    let mut templates: BTreeMap<&str, (bool, BTreeSet<String>)> = BTreeMap::new();
+    for name in names {
+        let (base, locale) = split_localized_name(name);
+        let entry = templates.entry(base).or_default();
+        match locale {
+            Some(locale) => {
+                entry.1.insert(locale);
+            }
+            None => entry.0 = true,
+        }
+    }
+    let all: BTreeSet<String> = templates
+        .values()
+        .flat_map(|(_, locales)| locales.iter().cloned())
+        .collect();
+    templates
+        .into_iter()
+        .map(|(name, (has_default, locales))| TemplateCoverage {
+            name: name.to_string(),
+            has_default,
+            missing: all.difference(&locales).cloned().collect(),
+            locales: locales.into_iter().collect(),
+        })
+        .collect()
+}
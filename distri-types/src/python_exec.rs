@@ -0,0 +1,101 @@
+//! Settings of the `python_exec` builtin tool.
+//!
+//! The tool runs model-written Python with a local CPython, wrapped in
+//! firejail (no network, the run's scratch directory as the only writable
+//! home). Calls fail when firejail is missing unless the config opts out
+//! with `sandbox: none`. CPU time and address space are capped with
+//! rlimits, wall time by the server, and top-level imports of the code are
+//! limited to the standard library plus `allowed_packages`. See
+//! `distri_core::tools::python_exec`.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// `python_exec` section of the server config.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct PythonExecConfig {
+    /// Python interpreter to run.
+    #[serde(default = "default_interpreter")]
+    pub interpreter: String,
+    #[serde(default)]
+    pub sandbox: PythonSandbox,
+    /// Third-party packages the code may import, by top-level module name
+    /// (`sklearn`, not `scikit-learn`). The standard library is always
+    /// allowed; the packages' own dependencies are not checked.
+    #[serde(default = "default_allowed_packages")]
+    pub allowed_packages: Vec<String>,
+    /// Wall-clock limit of one call.
+    #[serde(default = "default_timeout_secs")]
+    pub timeout_secs: u64,
+    /// CPU-time limit of one call.
+    #[serde(default = "default_cpu_secs")]
+    pub cpu_secs: u64,
+    /// Address-space limit of one call.
+    #[serde(default = "default_memory_mb")]
+    pub memory_mb: u64,
+    /// Bytes of stdout and of stderr returned to the model; the rest is cut.
+    #[serde(default = "default_max_output_bytes")]
+    pub max_output_bytes: usize,
+    /// Matplotlib figures saved as artifacts per call.
+    #[serde(default = "default_max_figures")]
+    pub max_figures: usize,
+}
+
+/// How the interpreter is isolated.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PythonSandbox {
+    /// Always firejail; calls fail when it is missing.
+    #[default]
+    Firejail,
+    /// The bare interpreter in a scratch directory, with rlimits only.
+    /// Development setups only.
+    None,
+}
+
+fn default_interpreter() -> String {
+    "python3".to_string()
+}
+
+fn default_allowed_packages() -> Vec<String> {
+    ["numpy", "pandas", "matplotlib", "scipy"]
+        .into_iter()
+        .map(String::from)
+        .collect()
+}
+
+fn default_timeout_secs() -> u64 {
+    30
+}
+
+fn default_cpu_secs() -> u64 {
+    20
+}
+
+fn default_memory_mb() -> u64 {
+    1024
+}
+
+fn default_max_output_bytes() -> usize {
+    64 * 1024
+}
+
+fn default_max_figures() -> usize {
+    8
+}
+
+impl Default for PythonExecConfig {
+    fn default() -> Self {
+        Self {
+            interpreter: default_interpreter(),
+            sandbox: PythonSandbox::default(),
+            allowed_packages: default_allowed_packages(),
+            timeout_secs: default_timeout_secs(),
+            cpu_secs: default_cpu_secs(),
+            memory_mb: default_memory_mb(),
+            max_output_bytes: default_max_output_bytes(),
+            max_figures: default_max_figures(),
+        }
+    }
+}
@@ -0,0 +1,35 @@
+//! "Regenerate from here": re-running a thread from one of its messages.
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::configuration::DefinitionOverrides;
+
+/// Body of `POST /threads/{thread_id}/regenerate`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct RegenerateRequest {
+    /// Message to regenerate from. Its task and every later task of the
+    /// thread are archived, and the task's user message is run again.
+    pub message_id: String,
+    /// Replaces the text of the user message that is run again.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    /// Agent parameters to change for the new run (model, temperature…).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub overrides: Option<DefinitionOverrides>,
+}
+
+/// Outcome of a regeneration.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct Regeneration {
+    pub thread_id: String,
+    /// The new task.
+    pub task_id: String,
+    /// The task the new one replaces.
+    pub reference_task_ids: Vec<String>,
+    /// Tasks hidden from the thread history, oldest first.
+    pub archived_task_ids: Vec<String>,
+    /// Final answer of the new run.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+}
@@ -0,0 +1,206 @@
+//! Renderer manifests: how the web UI shows the calls of a plugin's tools.
+//!
+//! The renderers in [`crate::ui_tool_renderers`] are compiled in. A plugin
+//! ([`Integration`](crate::integration::Integration)) can instead ship a
+//! manifest per tool with
+//! [`get_ui_renderers`](crate::integration::Integration::get_ui_renderers):
+//! an icon, a title, a card mapping the tool's structured output to a
+//! layout, and when the card starts collapsed. The server serves the
+//! manifests of every registered tool at `GET /v1/tool-renderers`, so the
+//! UI picks up a new tool without a frontend release.
+//!
+//! ```yaml
+//! tool: crm_get_orders
+//! icon: shopping-cart
+//! title: "Orders of {{input.customer_id}}"
+//! card:
+//!   kind: table
+//!   items: /orders
+//!   fields:
+//!     - { label: Order, path: /id }
+//!     - { label: Total, path: /total, format: number }
+//!     - { label: Placed, path: /placed_at, format: date }
+//! collapse:
+//!   collapsed: true
+//! ```
+//!
+//! Paths are JSON pointers into the tool's structured output (see
+//! [`crate::tool_output::structured_output`]); for `table` and `list` cards
+//! field paths are relative to each item of `items`. `title` may use
+//! `{{input.<field>}}` placeholders, filled from the call's input.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// How the UI renders the calls of one tool.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ToolRendererManifest {
+    /// Name of the tool.
+    pub tool: String,
+    /// Icon name from the UI's icon set, or an image URL.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub icon: Option<String>,
+    /// Title of the card; defaults to the tool name.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub card: CardLayout,
+    #[serde(default)]
+    pub collapse: CollapseRule,
+}
+
+/// How the structured output of a call is laid out.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct CardLayout {
+    #[serde(default)]
+    pub kind: CardKind,
+    /// Pointer to the array shown by `table` and `list` cards.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub items: Option<String>,
+    /// Values shown, in order.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fields: Vec<CardField>,
+}
+
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema, ToSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum CardKind {
+    /// The output as collapsible JSON; needs no fields.
+    #[default]
+    Json,
+    /// One row per field.
+    KeyValue,
+    /// One row per item of `items`, one column per field.
+    Table,
+    /// One entry per item of `items`, its fields stacked.
+    List,
+    /// The string at the first field's path, rendered as markdown.
+    Markdown,
+    /// The image URL at the first field's path.
+    Image,
+}
+
+/// A value of the output shown on the card.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct CardField {
+    pub label: String,
+    /// JSON pointer to the value.
+    pub path: String,
+    #[serde(default, skip_serializing_if = "FieldFormat::is_default")]
+    pub format: FieldFormat,
+}
+
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema, ToSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum FieldFormat {
+    #[default]
+    Text,
+    Number,
+    Date,
+    Link,
+    Code,
+    Badge,
+}
+
+impl FieldFormat {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Whether the card starts collapsed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct CollapseRule {
+    #[serde(default)]
+    pub collapsed: bool,
+    /// Collapse outputs longer than this many lines.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_lines: Option<u32>,
+    /// Show a failed call expanded even when it would be collapsed.
+    #[serde(default = "default_expand_on_error")]
+    pub expand_on_error: bool,
+}
+
+fn default_expand_on_error() -> bool {
+    true
+}
+
+impl Default for CollapseRule {
+    fn default() -> Self {
+        Self {
+            collapsed: false,
+            max_lines: None,
+            expand_on_error: default_expand_on_error(),
+        }
+    }
+}
+
+impl ToolRendererManifest {
+    /// Why the manifest cannot be rendered, if it cannot.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.tool.trim().is_empty() {
+            return Err("renderer manifest has no tool".to_string());
+        }
+        let invalid = |why: String| Err(format!("renderer of '{}': {}", self.tool, why));
+        let card = &self.card;
+        if let Some(items) = &card.items
+            && !is_pointer(items)
+        {
+            return invalid(format!("items '{}' is not a JSON pointer", items));
+        }
+        for field in &card.fields {
+            if !is_pointer(&field.path) {
+                return invalid(format!(
+                    "path '{}' of field '{}' is not a JSON pointer",
+                    field.path, field.label
+                ));
+            }
+        }
+        match card.kind {
+            CardKind::Table | CardKind::List if card.items.is_none() => {
+                invalid(format!("a {} card needs items", card.kind.as_str()))
+            }
+            CardKind::Json => Ok(()),
+            kind if card.fields.is_empty() => {
+                invalid(format!("a {} card needs fields", kind.as_str()))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+impl CardKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CardKind::Json => "json",
+            CardKind::KeyValue => "key_value",
+            CardKind::Table => "table",
+            CardKind::List => "list",
+            CardKind::Markdown => "markdown",
+            CardKind::Image => "image",
+        }
+    }
+}
+
+/// `""` (the whole value) or `/`-separated reference tokens.
+fn is_pointer(path: &str) -> bool {
+    path.is_empty() || path.starts_with('/')
+}
+
+/// A renderer served to the UI, with the plugin that shipped it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct ToolRenderer {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub plugin: Option<String>,
+    #[serde(flatten)]
+    pub manifest: ToolRendererManifest,
+}
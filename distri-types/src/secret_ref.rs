@@ -0,0 +1,213 @@
+//! `{{secret:NAME}}` references to workspace secrets.
+//!
+//! Agent definitions and MCP server configs can refer to a secret instead of
+//! embedding its value, e.g. `base_url = "https://api.example.com/{{secret:EXAMPLE_TOKEN}}"`.
+//! The stored config keeps the reference; it is replaced with the secret's
+//! value (from the secret store, falling back to the environment) only when
+//! the config is used for a run or a connection.
+//!
+//! In agent definitions only the fields of [`AGENT_SECRET_FIELDS`] may hold
+//! references, so a secret never ends up in a prompt; a reference anywhere
+//! else is rejected.
+
+use std::collections::{BTreeSet, HashMap};
+use std::ops::Range;
+
+use serde_json::Value;
+
+const OPEN: &str = "{{";
+const CLOSE: &str = "}}";
+const SCHEME: &str = "secret:";
+
+/// Fields of an agent definition that may reference secrets, as JSON
+/// pointers in which `*` matches any key or index: the connection settings
+/// of the model providers and the config (URLs, headers) of dynamic tools.
+pub const AGENT_SECRET_FIELDS: &[&str] = &[
+    "/model_settings/provider",
+    "/analysis_model_settings/provider",
+    "/tools/dynamic/*/config",
+];
+
+/// The secret references in `s`: the byte range of each `{{secret:NAME}}`
+/// and its name. Whitespace inside the braces is allowed.
+fn find_refs(s: &str) -> Vec<(Range<usize>, &str)> {
+    let mut refs = Vec::new();
+    let mut from = 0;
+    while let Some(open) = s[from..].find(OPEN).map(|i| from + i) {
+        let inner_start = open + OPEN.len();
+        let Some(close) = s[inner_start..].find(CLOSE).map(|i| inner_start + i) else {
+            break;
+        };
+        let name = s[inner_start..close]
+            .trim()
+            .strip_prefix(SCHEME)
+            .map(str::trim)
+            .filter(|name| is_valid_name(name));
+        match name {
+            Some(name) => {
+                refs.push((open..close + CLOSE.len(), name));
+                from = close + CLOSE.len();
+            }
+            // Not a secret reference (e.g. a prompt template variable).
+            None => from = inner_start,
+        }
+    }
+    refs
+}
+
+fn is_valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'))
+}
+
+/// Names of the secrets referenced in `s`, in order of appearance.
+pub fn secret_ref_names(s: &str) -> Vec<&str> {
+    find_refs(s).into_iter().map(|(_, name)| name).collect()
+}
+
+/// Names of the secrets referenced anywhere in the string values of `value`.
+pub fn collect_secret_refs(value: &Value) -> BTreeSet<String> {
+    let mut names = BTreeSet::new();
+    visit_strings(value, &mut |s| {
+        names.extend(secret_ref_names(s).into_iter().map(str::to_string));
+    });
+    names
+}
+
+/// Names of the secrets referenced in the string values under `fields`.
+pub fn collect_secret_refs_in(value: &Value, fields: &[&str]) -> BTreeSet<String> {
+    let mut names = BTreeSet::new();
+    visit_strings_at(value, &mut Vec::new(), &mut |path, s| {
+        if in_fields(path, fields) {
+            names.extend(secret_ref_names(s).into_iter().map(str::to_string));
+        }
+    });
+    names
+}
+
+/// JSON pointers of the string values outside `fields` that reference a
+/// secret.
+pub fn secret_refs_outside(value: &Value, fields: &[&str]) -> Vec<String> {
+    let mut pointers = Vec::new();
+    visit_strings_at(value, &mut Vec::new(), &mut |path, s| {
+        if !in_fields(path, fields) && !find_refs(s).is_empty() {
+            pointers.push(format!("/{}", path.join("/")));
+        }
+    });
+    pointers
+}
+
+/// Replace the references in `s` with their values. References without a
+/// value in `secrets` are left as they are.
+pub fn replace_secret_refs(s: &str, secrets: &HashMap<String, String>) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut last = 0;
+    for (range, name) in find_refs(s) {
+        if let Some(value) = secrets.get(name) {
+            out.push_str(&s[last..range.start]);
+            out.push_str(value);
+            last = range.end;
+        }
+    }
+    out.push_str(&s[last..]);
+    out
+}
+
+/// [`replace_secret_refs`] on every string value in `value`.
+pub fn substitute_secret_refs(value: &mut Value, secrets: &HashMap<String, String>) {
+    match value {
+        Value::String(s) if !find_refs(s).is_empty() => {
+            *s = replace_secret_refs(s, secrets);
+        }
+        Value::Array(items) => items
+            .iter_mut()
+            .for_each(|item| substitute_secret_refs(item, secrets)),
+        Value::Object(map) => map
+            .values_mut()
+            .for_each(|item| substitute_secret_refs(item, secrets)),
+        _ => {}
+    }
+}
+
+/// [`substitute_secret_refs`] on the values under `fields` only.
+pub fn substitute_secret_refs_in(
+    value: &mut Value,
+    fields: &[&str],
+    secrets: &HashMap<String, String>,
+) {
+    for field in fields {
+        let pattern: Vec<&str> = field.split('/').skip(1).collect();
+        for target in pointees_mut(value, &pattern) {
+            substitute_secret_refs(target, secrets);
+        }
+    }
+}
+
+/// Whether `path` is at or below one of `fields`.
+fn in_fields(path: &[String], fields: &[&str]) -> bool {
+    fields.iter().any(|field| {
+        let pattern: Vec<&str> = field.split('/').skip(1).collect();
+        pattern.len() <= path.len()
+            && pattern
+                .iter()
+                .zip(path)
+                .all(|(segment, key)| *segment == "*" || segment == key)
+    })
+}
+
+/// The values of `value` that `pattern` (pointer segments, `*` for any)
+/// points at.
+fn pointees_mut<'a>(value: &'a mut Value, pattern: &[&str]) -> Vec<&'a mut Value> {
+    let Some((segment, rest)) = pattern.split_first() else {
+        return vec![value];
+    };
+    let children: Vec<&mut Value> = match (value, *segment) {
+        (Value::Object(map), "*") => map.values_mut().collect(),
+        (Value::Array(items), "*") => items.iter_mut().collect(),
+        (Value::Object(map), key) => map.get_mut(key).into_iter().collect(),
+        (Value::Array(items), index) => index
+            .parse::<usize>()
+            .ok()
+            .and_then(|i| items.get_mut(i))
+            .into_iter()
+            .collect(),
+        _ => Vec::new(),
+    };
+    children
+        .into_iter()
+        .flat_map(|child| pointees_mut(child, rest))
+        .collect()
+}
+
+fn visit_strings(value: &Value, f: &mut impl FnMut(&str)) {
+    match value {
+        Value::String(s) => f(s),
+        Value::Array(items) => items.iter().for_each(|item| visit_strings(item, f)),
+        Value::Object(map) => map.values().for_each(|item| visit_strings(item, f)),
+        _ => {}
+    }
+}
+
+/// [`visit_strings`] with the path of keys and indices to each string.
+fn visit_strings_at(value: &Value, path: &mut Vec<String>, f: &mut impl FnMut(&[String], &str)) {
+    match value {
+        Value::String(s) => f(path, s),
+        Value::Array(items) => {
+            for (i, item) in items.iter().enumerate() {
+                path.push(i.to_string());
+                visit_strings_at(item, path, f);
+                path.pop();
+            }
+        }
+        Value::Object(map) => {
+            for (key, item) in map {
+                path.push(key.clone());
+                visit_strings_at(item, path, f);
+                path.pop();
+            }
+        }
+        _ => {}
+    }
+}
@@ -0,0 +1,272 @@
+//! Managed SQL connections for the `sql_query` / `sql_schema` builtin tools.
+//!
+//! Connections are declared in server config (`sql_connections` in
+//! `distri.yaml`) — the model only ever sees connection names, never URLs.
+//! Connections are read-only unless `read_only: false` is set: statements
+//! are screened with [`check_read_only_statement`] and the session itself
+//! is opened read-only, so a statement that slips past the screen is still
+//! rejected by the database.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use utoipa::ToSchema;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum SqlDriver {
+    Postgres,
+    Mysql,
+    Sqlite,
+}
+
+impl std::fmt::Display for SqlDriver {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SqlDriver::Postgres => write!(f, "postgres"),
+            SqlDriver::Mysql => write!(f, "mysql"),
+            SqlDriver::Sqlite => write!(f, "sqlite"),
+        }
+    }
+}
+
+/// A named database connection agents may query.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, ToSchema)]
+pub struct SqlConnectionConfig {
+    /// Name the model passes as `connection`.
+    pub name: String,
+    pub driver: SqlDriver,
+    /// Connection URL. `${VAR}` references are expanded from the
+    /// environment so credentials can stay out of the config file.
+    pub url: String,
+    /// Shown to the model next to the connection name.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Reject anything but reads. Defaults to `true`.
+    #[serde(default = "default_read_only")]
+    pub read_only: bool,
+    /// Maximum rows returned per query.
+    #[serde(default = "default_max_rows")]
+    pub max_rows: usize,
+    /// Maximum serialized size of the returned rows, in bytes.
+    #[serde(default = "default_max_bytes")]
+    pub max_bytes: usize,
+    /// Per-query timeout.
+    #[serde(default = "default_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+fn default_read_only() -> bool {
+    true
+}
+
+fn default_max_rows() -> usize {
+    200
+}
+
+fn default_max_bytes() -> usize {
+    256 * 1024
+}
+
+fn default_timeout_secs() -> u64 {
+    30
+}
+
+impl SqlConnectionConfig {
+    /// The connection URL with `${VAR}` references expanded.
+    pub fn resolved_url(&self) -> anyhow::Result<String> {
+        let mut out = String::with_capacity(self.url.len());
+        let mut rest = self.url.as_str();
+        while let Some(start) = rest.find("${") {
+            out.push_str(&rest[..start]);
+            let after = &rest[start + 2..];
+            let end = after.find('}').ok_or_else(|| {
+                anyhow::anyhow!("unterminated `${{` in url of connection '{}'", self.name)
+            })?;
+            let var = &after[..end];
+            let value = std::env::var(var).map_err(|_| {
+                anyhow::anyhow!(
+                    "env var '{}' referenced by connection '{}' is not set",
+                    var,
+                    self.name
+                )
+            })?;
+            out.push_str(&value);
+            rest = &after[end + 1..];
+        }
+        out.push_str(rest);
+        Ok(out)
+    }
+}
+
+/// Tabular result of a `sql_query` call, returned as a `Part::Data`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema, ToSchema)]
+pub struct SqlQueryResult {
+    pub connection: String,
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<Value>>,
+    pub row_count: usize,
+    /// `true` when `max_rows` or `max_bytes` cut the result short.
+    pub truncated: bool,
+    /// Set for statements that do not return rows (writable connections only).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rows_affected: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, ToSchema)]
+pub struct SqlColumnSchema {
+    pub name: String,
+    pub data_type: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, ToSchema)]
+pub struct SqlTableSchema {
+    pub name: String,
+    pub columns: Vec<SqlColumnSchema>,
+}
+
+/// Keywords a read-only statement may start with.
+const READ_KEYWORDS: &[&str] = &[
+    "select", "with", "show", "explain", "describe", "desc", "values", "pragma", "table",
+];
+
+/// Keywords that never belong in a read-only statement, wherever they
+/// appear (e.g. a data-modifying CTE in Postgres).
+const WRITE_KEYWORDS: &[&str] = &[
+    "insert", "update", "delete", "drop", "alter", "create", "truncate", "grant", "revoke",
+    "merge", "attach", "detach", "vacuum", "copy", "call",
+];
+
+/// Screen `sql` for a read-only connection: a single statement that starts
+/// with a read keyword and contains no write keyword outside string
+/// literals and comments.
+pub fn check_read_only_statement(sql: &str) -> Result<(), String> {
+    let words = sql_words(sql)?;
+    let Some(first) = words.first() else {
+        return Err("empty SQL statement".to_string());
+    };
+    if !READ_KEYWORDS.contains(&first.as_str()) {
+        return Err(format!(
+            "connection is read-only; `{}` statements are not allowed",
+            first.to_uppercase()
+        ));
+    }
+    if first == "pragma" && sql.contains('=') {
+        return Err("connection is read-only; PRAGMA assignments are not allowed".to_string());
+    }
+    if let Some(word) = words.iter().find(|w| WRITE_KEYWORDS.contains(&w.as_str())) {
+        return Err(format!(
+            "connection is read-only; `{}` is not allowed",
+            word.to_uppercase()
+        ));
+    }
+    Ok(())
+}
+
+/// Lower-cased bare words of `sql`, skipping string literals, quoted
+/// identifiers and comments. Fails on more than one statement.
+fn sql_words(sql: &str) -> Result<Vec<String>, String> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let mut chars = sql.chars().peekable();
+    let mut ended = false;
+
+    let flush = |current: &mut String, words: &mut Vec<String>| {
+        if !current.is_empty() {
+            words.push(current.to_lowercase());
+            current.clear();
+        }
+    };
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' | '"' | '`' => {
+                flush(&mut current, &mut words);
+                for next in chars.by_ref() {
+                    if next == c {
+                        break;
+                    }
+                }
+            }
+            '-' if chars.peek() == Some(&'-') => {
+                flush(&mut current, &mut words);
+                for next in chars.by_ref() {
+                    if next == '\n' {
+                        break;
+                    }
+                }
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                flush(&mut current, &mut words);
+                chars.next();
+                let mut prev = ' ';
+                for next in chars.by_ref() {
+                    if prev == '*' && next == '/' {
+                        break;
+                    }
+                    prev = next;
+                }
+            }
+            ';' => {
+                flush(&mut current, &mut words);
+                ended = true;
+            }
+            c if c.is_alphanumeric() || c == '_' => {
+                if ended {
+                    return Err("only one SQL statement may be run at a time".to_string());
+                }
+                current.push(c);
+            }
+            _ => flush(&mut current, &mut words),
+        }
+    }
+    flush(&mut current, &mut words);
+    Ok(words)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_only_screen() {
+        for ok in [
+            "SELECT * FROM users",
+            "  -- top customers\nselect name from customers limit 5;",
+            "WITH t AS (SELECT 1) SELECT * FROM t",
+            "select 'drop table users' as s",
+            "EXPLAIN SELECT 1",
+            "PRAGMA table_info(users)",
+        ] {
+            assert!(check_read_only_statement(ok).is_ok(), "{ok}");
+        }
+        for bad in [
+            "DELETE FROM users",
+            "WITH gone AS (DELETE FROM users RETURNING *) SELECT * FROM gone",
+            "SELECT 1; DROP TABLE users",
+            "PRAGMA journal_mode = WAL",
+            "",
+            "/* hi */",
+        ] {
+            assert!(check_read_only_statement(bad).is_err(), "{bad}");
+        }
+    }
+
+    #[test]
+    fn config_defaults_and_env_expansion() {
+        let config: SqlConnectionConfig = serde_yaml::from_str(
+            "name: analytics\ndriver: postgres\nurl: postgres://ro:${DISTRI_SQL_TEST_PW}@db/analytics\n",
+        )
+        .unwrap();
+        assert!(config.read_only);
+        assert_eq!(config.max_rows, 200);
+
+        assert!(config.resolved_url().is_err());
+        // SAFETY: the variable name is unique to this test.
+        unsafe { std::env::set_var("DISTRI_SQL_TEST_PW", "s3cret") };
+        assert_eq!(
+            config.resolved_url().unwrap(),
+            "postgres://ro:s3cret@db/analytics"
+        );
+    }
+}
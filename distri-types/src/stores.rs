@@ -1,4 +1,5 @@
 use crate::connections::{Connection, ConnectionStatus, ConnectionToken, NewConnection};
+use crate::thread_archive::{ArchivedTask, ArchivedTaskMessage};
 use crate::{ScratchpadEntry, ToolAuthStore, ToolResponse};
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
@@ -47,6 +48,8 @@ pub struct CreateTaskInput {
     /// `Executor` and any `RunnerConfig`. Stored as JSONB in Pg /
     /// TEXT in sqlite. Default is `{}` until invoke() is wired.
     pub invocation: serde_json::Value,
+    /// Tasks the new one was derived from.
+    pub reference_task_ids: Vec<String>,
 }
 
 impl CreateTaskInput {
@@ -61,6 +64,7 @@ impl CreateTaskInput {
             remote: false,
             inner_task_id: None,
             invocation: serde_json::Value::Object(Default::default()),
+            reference_task_ids: Vec::new(),
         }
     }
 
@@ -84,6 +88,11 @@ impl CreateTaskInput {
         self
     }
 
+    pub fn with_reference_tasks(mut self, reference_task_ids: Vec<String>) -> Self {
+        self.reference_task_ids = reference_task_ids;
+        self
+    }
+
     /// Marks the task as remote-executed and sets the inner task id
     /// the runner has assigned. The runner kind + its private config
     /// live in `invocation` (typed `Executor::Remote { runner }`).
@@ -157,6 +166,17 @@ pub struct InitializedStores {
     /// multi-tenant cloud, which registers a workspace-scoped `ProviderStore`
     /// separately rather than through `InitializedStores`.
     pub provider_store: Option<Arc<dyn ProviderStore>>,
+    /// Outbound LLM request/response audit log. `None` = auditing disabled.
+    pub llm_audit_store: Option<Arc<dyn LlmAuditStore>>,
+    /// Persistent queue behind non-blocking `message/send`. `None` when the
+    /// backend has no queue table.
+    pub background_job_store: Option<Arc<dyn BackgroundJobStore>>,
+    /// Persistent key-value storage of plugins. `None` when the backend has
+    /// no plugin table.
+    pub plugin_kv_store: Option<Arc<dyn PluginKvStore>>,
+    /// Daily run and tool counters behind `/v1/analytics`. `None` when the
+    /// task store does not maintain them.
+    pub analytics_store: Option<Arc<dyn AnalyticsStore>>,
 }
 impl InitializedStores {
     pub fn set_tool_auth_store(&mut self, tool_auth_store: Arc<dyn ToolAuthStore>) {
@@ -264,7 +284,9 @@ pub trait SessionStoreExt: SessionStore {
 }
 impl<T: SessionStore + ?Sized> SessionStoreExt for T {}
 
-// Higher-level MemoryStore trait - manages cross-session permanent memory using user_id
+// Higher-level MemoryStore trait - manages cross-session permanent memory using user_id.
+// Shared namespaces (agent/team/workspace) reuse the same key via
+// `crate::memory::MemoryNamespace::key`; see `crate::memory::ScopedMemory`.
 #[async_trait::async_trait]
 pub trait MemoryStore: Send + Sync {
     /// Store permanent memory from a session for cross-session access
@@ -331,6 +353,8 @@ pub trait TaskStore: Send + Sync {
             updated_at: chrono::Utc::now().timestamp_millis(),
             thread_id: input.thread_id.clone(),
             parent_task_id: input.parent_task_id.clone(),
+            archived_at: None,
+            reference_task_ids: input.reference_task_ids.clone(),
         }
     }
 
@@ -358,6 +382,20 @@ pub trait TaskStore: Send + Sync {
     async fn get_task(&self, task_id: &str) -> anyhow::Result<Option<Task>>;
     async fn update_task_status(&self, task_id: &str, status: TaskStatus) -> anyhow::Result<()>;
     async fn add_event_to_task(&self, task_id: &str, event: AgentEvent) -> anyhow::Result<()>;
+
+    /// Store a batch of `(task_id, event)` pairs, in order. Stores that can
+    /// write them together (one transaction) override this.
+    async fn add_task_events(&self, events: Vec<(String, AgentEvent)>) -> anyhow::Result<()> {
+        for (task_id, event) in events {
+            self.add_event_to_task(&task_id, event).await?;
+        }
+        Ok(())
+    }
+
+    /// Store anything still buffered; called once before the process exits.
+    async fn shutdown(&self) -> anyhow::Result<()> {
+        Ok(())
+    }
     async fn add_message_to_task(&self, task_id: &str, message: &Message) -> anyhow::Result<()>;
     async fn cancel_task(&self, task_id: &str) -> anyhow::Result<Task>;
 
@@ -394,12 +432,55 @@ pub trait TaskStore: Send + Sync {
     async fn list_running_tasks(&self, thread_id: Option<&str>) -> anyhow::Result<Vec<Task>>;
     async fn list_tasks(&self, thread_id: Option<&str>) -> anyhow::Result<Vec<Task>>;
 
+    /// Tasks of `thread_id` with their messages, oldest first. Archived
+    /// tasks are left out.
     async fn get_history(
         &self,
         thread_id: &str,
         filter: Option<MessageFilter>,
     ) -> anyhow::Result<Vec<(Task, Vec<TaskMessage>)>>;
 
+    /// Soft-archive `task_id` and every task of its thread created after
+    /// it, hiding them from [`get_history`](Self::get_history). The rows
+    /// and their messages are kept. Returns the archived ids, oldest first.
+    async fn archive_tasks_from(
+        &self,
+        _thread_id: &str,
+        _task_id: &str,
+    ) -> anyhow::Result<Vec<String>> {
+        anyhow::bail!("this task store does not support archiving tasks")
+    }
+
+    /// Every task of `thread_id` with its messages and events, as stored,
+    /// for moving the thread to cold storage.
+    async fn export_thread_tasks(
+        &self,
+        _thread_id: &str,
+    ) -> anyhow::Result<(Vec<ArchivedTask>, Vec<ArchivedTaskMessage>)> {
+        anyhow::bail!("this task store does not support thread archival")
+    }
+
+    /// Insert tasks and messages exported by
+    /// [`export_thread_tasks`](Self::export_thread_tasks).
+    async fn import_thread_tasks(
+        &self,
+        _tasks: &[ArchivedTask],
+        _messages: &[ArchivedTaskMessage],
+    ) -> anyhow::Result<()> {
+        anyhow::bail!("this task store does not support thread archival")
+    }
+
+    /// Delete the tasks `task_ids` of `thread_id` and their messages, so
+    /// tasks started since they were exported are kept. Returns how many
+    /// tasks were deleted.
+    async fn delete_thread_tasks(
+        &self,
+        _thread_id: &str,
+        _task_ids: &[String],
+    ) -> anyhow::Result<usize> {
+        anyhow::bail!("this task store does not support thread archival")
+    }
+
     async fn update_parent_task(
         &self,
         task_id: &str,
@@ -429,7 +510,7 @@ pub trait TaskStore: Send + Sync {
 
 /// Wire shape for `GET /v1/tasks*`: the task row flattened together with its
 /// monitor projection ([`TaskActivity`]).
-#[derive(Debug, Clone, serde::Serialize)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct TaskWithActivity {
     #[serde(flatten)]
     pub task: Task,
@@ -461,6 +542,26 @@ pub trait ThreadStore: Send + Sync {
     ) -> anyhow::Result<Thread>;
     async fn delete_thread(&self, thread_id: &str) -> anyhow::Result<()>;
 
+    /// Mark the thread as archived at `archived_at`, or as restored with
+    /// `None`.
+    async fn set_thread_archived(
+        &self,
+        _thread_id: &str,
+        _archived_at: Option<DateTime<Utc>>,
+    ) -> anyhow::Result<()> {
+        anyhow::bail!("this thread store does not support thread archival")
+    }
+
+    /// Ids of up to `limit` threads not archived and not updated since
+    /// `before`, oldest first.
+    async fn list_archivable_threads(
+        &self,
+        _before: DateTime<Utc>,
+        _limit: u32,
+    ) -> anyhow::Result<Vec<String>> {
+        Ok(Vec::new())
+    }
+
     /// List threads with pagination and filtering
     /// Returns a paginated response with total count
     async fn list_threads(
@@ -582,6 +683,9 @@ pub struct HomeStats {
     /// Key is the metric name (e.g., "usage"), value is the metric data
     #[serde(skip_serializing_if = "Option::is_none")]
     pub custom_metrics: Option<std::collections::HashMap<String, CustomMetric>>,
+    /// Latency of the LLM calls made since the server started, per model
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub llm_latency: Option<Vec<crate::llm_metrics::LlmLatencyStats>>,
 }
 
 /// A custom metric for display in the stats overview
@@ -1729,6 +1833,48 @@ pub trait NoteStore: Send + Sync + 'static {
     async fn search(&self, query: &str) -> anyhow::Result<Vec<crate::api::notes::NoteRecord>>;
 }
 
+// ========== Plugin KV Store ==========
+
+/// Persistent key-value storage of plugins, namespaced by plugin name. See
+/// [`crate::plugin_storage`]; plugins use it through
+/// [`PluginStorage`](crate::plugin_storage::PluginStorage).
+#[async_trait]
+pub trait PluginKvStore: Send + Sync + 'static {
+    async fn get(&self, plugin: &str, key: &str) -> anyhow::Result<Option<Value>>;
+
+    /// Store `value` under `key`. Fails with
+    /// [`PluginStorageError::QuotaExceeded`](crate::plugin_storage::PluginStorageError::QuotaExceeded)
+    /// when that takes the plugin over its quota.
+    async fn set(&self, plugin: &str, key: &str, value: &Value) -> anyhow::Result<()>;
+
+    /// Remove `key`; whether it was stored.
+    async fn delete(&self, plugin: &str, key: &str) -> anyhow::Result<bool>;
+
+    /// The plugin's keys starting with `prefix`, in order.
+    async fn list(&self, plugin: &str, prefix: &str) -> anyhow::Result<Vec<String>>;
+}
+
+// ========== Analytics Store ==========
+
+/// Reads the per-day counters a task store maintains as it stores run and
+/// tool events (see [`AnalyticsDelta`](crate::api::analytics::AnalyticsDelta)).
+#[async_trait]
+pub trait AnalyticsStore: Send + Sync + 'static {
+    /// Run counters of the days in `range`, of one agent or all of them.
+    async fn run_counters(
+        &self,
+        range: &crate::api::analytics::DateRange,
+        agent_id: Option<&str>,
+    ) -> anyhow::Result<Vec<crate::api::analytics::DailyAgentRuns>>;
+
+    /// Tool call counters of the days in `range`, of one agent or all of them.
+    async fn tool_counters(
+        &self,
+        range: &crate::api::analytics::DateRange,
+        agent_id: Option<&str>,
+    ) -> anyhow::Result<Vec<crate::api::analytics::DailyAgentTools>>;
+}
+
 // ========== Span Store ==========
 
 /// Query selector for listing spans.
@@ -1763,6 +1909,110 @@ pub trait SpanStore: Send + Sync + 'static {
     ) -> anyhow::Result<Vec<crate::api::spans::TraceRecord>>;
 }
 
+// ========== LLM Audit Store ==========
+
+/// Append-only log of outbound LLM requests and responses.
+///
+/// Records arrive already redacted (see `api::audit::redact_pii`); the
+/// store only persists, filters and expires them.
+#[async_trait]
+pub trait LlmAuditStore: Send + Sync + 'static {
+    /// Whether callers should run payloads through `redact_pii` before
+    /// calling [`record`](Self::record). Defaults to `true`.
+    fn redact(&self) -> bool {
+        true
+    }
+
+    /// Append one record.
+    async fn record(&self, record: crate::api::audit::LlmAuditRecord) -> anyhow::Result<()>;
+
+    /// Records matching `query`, newest first.
+    async fn list(
+        &self,
+        query: &crate::api::audit::LlmAuditQuery,
+    ) -> anyhow::Result<Vec<crate::api::audit::LlmAuditRecord>>;
+
+    /// Delete records created before `cutoff`. Returns how many were removed.
+    async fn purge_before(&self, cutoff: DateTime<Utc>) -> anyhow::Result<usize>;
+}
+
+/// History of eval runs and comparisons (see [`crate::evals`]).
+#[async_trait]
+pub trait EvalStore: Send + Sync + 'static {
+    async fn record_run(&self, run: &crate::evals::EvalRun) -> anyhow::Result<()>;
+
+    /// Runs of `agent_id`, oldest first.
+    async fn list_runs(&self, agent_id: &str) -> anyhow::Result<Vec<crate::evals::EvalRun>>;
+
+    async fn record_comparison(
+        &self,
+        comparison: &crate::evals::EvalComparison,
+    ) -> anyhow::Result<()>;
+
+    /// Comparisons of `agent_id`'s runs, oldest first.
+    async fn list_comparisons(
+        &self,
+        agent_id: &str,
+    ) -> anyhow::Result<Vec<crate::evals::EvalComparison>>;
+}
+
+/// Persistent queue of background agent runs (see [`crate::jobs`]).
+///
+/// Claims are leases: a worker owns a job until `lease_expires_at` and must
+/// [`renew`](Self::renew) before then. [`requeue_expired`](Self::requeue_expired)
+/// hands lapsed jobs to the next worker.
+#[async_trait]
+pub trait BackgroundJobStore: Send + Sync + 'static {
+    /// Insert a queued job, or return the existing job with the same
+    /// idempotency key (whatever its status).
+    async fn enqueue(
+        &self,
+        job: crate::jobs::NewBackgroundJob,
+    ) -> anyhow::Result<crate::jobs::BackgroundJob>;
+
+    async fn get(&self, job_id: &str) -> anyhow::Result<Option<crate::jobs::BackgroundJob>>;
+
+    async fn get_by_task_id(
+        &self,
+        task_id: &str,
+    ) -> anyhow::Result<Option<crate::jobs::BackgroundJob>>;
+
+    /// Atomically move the oldest queued job to `running` for `worker_id`,
+    /// leased until `lease_until`. Increments `attempts`.
+    async fn claim(
+        &self,
+        worker_id: &str,
+        lease_until: DateTime<Utc>,
+    ) -> anyhow::Result<Option<crate::jobs::BackgroundJob>>;
+
+    /// Extend the lease. Returns `false` when `worker_id` no longer holds
+    /// the job (it was re-queued or finished elsewhere).
+    async fn renew(
+        &self,
+        job_id: &str,
+        worker_id: &str,
+        lease_until: DateTime<Utc>,
+    ) -> anyhow::Result<bool>;
+
+    /// Record the outcome of a run held by `worker_id`.
+    async fn finish(
+        &self,
+        job_id: &str,
+        worker_id: &str,
+        status: crate::jobs::BackgroundJobStatus,
+        error: Option<String>,
+    ) -> anyhow::Result<()>;
+
+    /// Cancel a job that no worker has claimed yet. Returns `false` when the
+    /// job is already running or finished.
+    async fn cancel_queued(&self, job_id: &str) -> anyhow::Result<bool>;
+
+    /// Re-queue running jobs whose lease ended before `now`; jobs that have
+    /// used all their attempts are marked failed instead. Returns how many
+    /// jobs were touched.
+    async fn requeue_expired(&self, now: DateTime<Utc>) -> anyhow::Result<usize>;
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,125 @@
+//! Bidirectional run streams: the WebSocket at `/agents/{id}/ws`.
+//!
+//! The client opens the socket and sends one JSON-RPC request,
+//! `message/stream` or `tasks/resubscribe`, as its first text frame. The
+//! server answers with the JSON-RPC frames the SSE stream would carry, one
+//! per text frame, and closes the socket when the stream ends. While the run
+//! streams, the client may send [`StreamControl`] frames on the same socket;
+//! each is answered with a [`StreamControlReply`]. Cancelling or answering a
+//! prompt therefore no longer needs a separate HTTP call racing the stream.
+//!
+//! ```json
+//! {"type": "approve", "tool_call_id": "call_1", "approved": true}
+//! {"type": "control_reply", "control": "approve"}
+//! ```
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+use crate::{AuthConsentResponse, ToolResponse};
+
+/// Path of the WebSocket stream under `/agents/{id}`.
+pub const STREAM_WS_PATH: &str = "ws";
+
+/// Name of the tool that asks the user to approve tool calls.
+const APPROVAL_TOOL_NAME: &str = "approval_request";
+
+/// A frame the client sends on a run's WebSocket.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum StreamControl {
+    /// Cancel a task; the streamed run's task when `task_id` is unset.
+    Cancel {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        task_id: Option<String>,
+    },
+    /// Answer an `approval_request` tool call.
+    Approve {
+        tool_call_id: String,
+        approved: bool,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        reason: Option<String>,
+    },
+    /// Answer a tool call waiting for the user with `input`.
+    UserInput {
+        tool_call_id: String,
+        tool_name: String,
+        input: Value,
+    },
+    /// Complete an external tool call.
+    ToolResult { tool_response: ToolResponse },
+    /// Answer an `AuthRequired` consent prompt.
+    Auth { response: AuthConsentResponse },
+}
+
+impl StreamControl {
+    /// The `type` of the frame.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Cancel { .. } => "cancel",
+            Self::Approve { .. } => "approve",
+            Self::UserInput { .. } => "user_input",
+            Self::ToolResult { .. } => "tool_result",
+            Self::Auth { .. } => "auth",
+        }
+    }
+
+    /// The tool response the frame completes a tool call with; `None` for
+    /// cancellation and auth consent.
+    pub fn tool_response(&self) -> Option<ToolResponse> {
+        match self {
+            Self::Approve {
+                tool_call_id,
+                approved,
+                reason,
+            } => {
+                let reason = reason.clone().unwrap_or_else(|| {
+                    if *approved {
+                        "Approved by user".to_string()
+                    } else {
+                        "Rejected by user".to_string()
+                    }
+                });
+                Some(ToolResponse::direct(
+                    tool_call_id.clone(),
+                    APPROVAL_TOOL_NAME.to_string(),
+                    json!({ "approved": approved, "reason": reason }),
+                ))
+            }
+            Self::UserInput {
+                tool_call_id,
+                tool_name,
+                input,
+            } => Some(ToolResponse::direct(
+                tool_call_id.clone(),
+                tool_name.clone(),
+                input.clone(),
+            )),
+            Self::ToolResult { tool_response } => Some(tool_response.clone()),
+            Self::Cancel { .. } | Self::Auth { .. } => None,
+        }
+    }
+}
+
+/// The server's answer to a [`StreamControl`] frame.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "type", rename = "control_reply")]
+pub struct StreamControlReply {
+    /// [`StreamControl::name`] of the frame answered.
+    pub control: String,
+    /// Why the frame could not be applied.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl StreamControlReply {
+    /// Read a reply from a text frame; `None` for the JSON-RPC frames of the
+    /// stream itself.
+    pub fn parse(text: &str) -> Option<Self> {
+        if !text.contains("\"control_reply\"") {
+            return None;
+        }
+        serde_json::from_str(text).ok()
+    }
+}
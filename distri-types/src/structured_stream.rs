@@ -0,0 +1,274 @@
+//! Incremental validation of a structured response while it streams.
+//!
+//! When a model's `response_format` carries a JSON schema, the response is
+//! fed to a [`StructuredStream`] chunk by chunk. Each top-level field of the
+//! JSON object is parsed and checked against its property schema as soon as
+//! its value is complete, and reported as a [`CompletedField`]. The stream
+//! diverges — and the response is aborted and retried — on the first
+//! character that cannot belong to a matching object: a non-object start, a
+//! field the schema does not allow, a value of the wrong type (judged from
+//! its first character) or a completed value failing its schema.
+
+use serde_json::{Map, Value};
+
+/// A top-level field whose value was fully received and matches its schema.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompletedField {
+    pub name: String,
+    pub value: Value,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum State {
+    Start,
+    /// Before a key; `first` when no field was read yet.
+    ExpectKey {
+        first: bool,
+    },
+    InKey,
+    ExpectColon,
+    ExpectValue,
+    InValue,
+    Done,
+    /// The root is not an object: the text is only checked by `finish`.
+    Opaque,
+}
+
+/// Validator of one streamed response. See the module docs.
+#[derive(Debug, Clone)]
+pub struct StructuredStream {
+    schema: Value,
+    properties: Map<String, Value>,
+    additional_properties: bool,
+    state: State,
+    text: String,
+    key: String,
+    value: String,
+    depth: usize,
+    in_string: bool,
+    escaped: bool,
+}
+
+impl StructuredStream {
+    pub fn new(schema: Value) -> Self {
+        let is_object = schema.get("type").and_then(Value::as_str) == Some("object")
+            || schema.get("properties").is_some();
+        let properties = schema
+            .get("properties")
+            .and_then(Value::as_object)
+            .cloned()
+            .unwrap_or_default();
+        let additional_properties = schema.get("additionalProperties") != Some(&Value::Bool(false));
+        Self {
+            schema,
+            properties,
+            additional_properties,
+            state: if is_object {
+                State::Start
+            } else {
+                State::Opaque
+            },
+            text: String::new(),
+            key: String::new(),
+            value: String::new(),
+            depth: 0,
+            in_string: false,
+            escaped: false,
+        }
+    }
+
+    /// The schema of a model's `response_format`, as sent to the provider
+    /// (`{ type: "json_schema", json_schema: { name, schema } }`, or a bare
+    /// object schema). `None` for plain text or `json_object` formats.
+    pub fn from_response_format(response_format: &Value) -> Option<Self> {
+        if let Some(json_schema) = response_format.get("json_schema") {
+            let schema = json_schema.get("schema").unwrap_or(json_schema);
+            return Some(Self::new(schema.clone()));
+        }
+        (response_format.get("type").and_then(Value::as_str) == Some("object"))
+            .then(|| Self::new(response_format.clone()))
+    }
+
+    /// Everything received so far.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Feed the next chunk. Returns the fields it completed, or why the
+    /// response can no longer match the schema.
+    pub fn push(&mut self, chunk: &str) -> Result<Vec<CompletedField>, String> {
+        match self.feed(chunk) {
+            (completed, None) => Ok(completed),
+            (_, Some(error)) => Err(error),
+        }
+    }
+
+    /// Feed the next chunk. Returns the fields it completed, those before a
+    /// divergence included, and why the response can no longer match the
+    /// schema if it diverged.
+    pub fn feed(&mut self, chunk: &str) -> (Vec<CompletedField>, Option<String>) {
+        self.text.push_str(chunk);
+        let mut completed = Vec::new();
+        for c in chunk.chars() {
+            match self.step(c) {
+                Ok(Some(field)) => completed.push(field),
+                Ok(None) => {}
+                Err(error) => return (completed, Some(error)),
+            }
+        }
+        (completed, None)
+    }
+
+    /// Check the complete response against the whole schema (required
+    /// fields included) and return it parsed.
+    pub fn finish(&self) -> Result<Value, String> {
+        if !matches!(self.state, State::Done | State::Opaque) {
+            return Err("the response ended before the JSON object was complete".to_string());
+        }
+        let value: Value = serde_json::from_str(self.text.trim())
+            .map_err(|e| format!("the response is not valid JSON: {}", e))?;
+        validate(&self.schema, &value)?;
+        Ok(value)
+    }
+
+    fn step(&mut self, c: char) -> Result<Option<CompletedField>, String> {
+        match self.state {
+            State::Opaque => {}
+            State::Start => match c {
+                c if c.is_whitespace() => {}
+                '{' => self.state = State::ExpectKey { first: true },
+                c => return Err(format!("expected a JSON object, got '{}'", c)),
+            },
+            State::ExpectKey { first } => match c {
+                c if c.is_whitespace() => {}
+                '"' => {
+                    self.key.clear();
+                    self.state = State::InKey;
+                }
+                '}' if first => self.state = State::Done,
+                c => return Err(format!("expected a field name, got '{}'", c)),
+            },
+            State::InKey => {
+                if self.escaped {
+                    self.escaped = false;
+                    self.key.push(c);
+                } else if c == '\\' {
+                    self.escaped = true;
+                    self.key.push(c);
+                } else if c == '"' {
+                    self.key = serde_json::from_str(&format!("\"{}\"", self.key))
+                        .map_err(|e| format!("invalid field name: {}", e))?;
+                    if !self.additional_properties && !self.properties.contains_key(&self.key) {
+                        return Err(format!("unexpected field '{}'", self.key));
+                    }
+                    self.state = State::ExpectColon;
+                } else {
+                    self.key.push(c);
+                }
+            }
+            State::ExpectColon => match c {
+                c if c.is_whitespace() => {}
+                ':' => self.state = State::ExpectValue,
+                c => return Err(format!("expected ':' after '{}', got '{}'", self.key, c)),
+            },
+            State::ExpectValue => {
+                if c.is_whitespace() {
+                    return Ok(None);
+                }
+                if let Some(expected) = self
+                    .properties
+                    .get(&self.key)
+                    .and_then(|s| mismatched_type(s, c))
+                {
+                    return Err(format!(
+                        "field '{}' should be {}, got '{}'",
+                        self.key, expected, c
+                    ));
+                }
+                self.value.clear();
+                self.depth = 0;
+                self.state = State::InValue;
+                return self.step(c);
+            }
+            State::InValue => {
+                if self.in_string {
+                    if self.escaped {
+                        self.escaped = false;
+                    } else if c == '\\' {
+                        self.escaped = true;
+                    } else if c == '"' {
+                        self.in_string = false;
+                    }
+                } else {
+                    match c {
+                        ',' | '}' if self.depth == 0 => {
+                            let field = self.complete_value()?;
+                            self.state = if c == ',' {
+                                State::ExpectKey { first: false }
+                            } else {
+                                State::Done
+                            };
+                            return Ok(Some(field));
+                        }
+                        '"' => self.in_string = true,
+                        '{' | '[' => self.depth += 1,
+                        '}' | ']' => {
+                            self.depth = self
+                                .depth
+                                .checked_sub(1)
+                                .ok_or_else(|| format!("unbalanced '{}' in '{}'", c, self.key))?;
+                        }
+                        _ => {}
+                    }
+                }
+                self.value.push(c);
+            }
+            State::Done => {
+                if !c.is_whitespace() {
+                    return Err(format!(
+                        "unexpected '{}' after the end of the JSON object",
+                        c
+                    ));
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    fn complete_value(&mut self) -> Result<CompletedField, String> {
+        let value: Value = serde_json::from_str(self.value.trim())
+            .map_err(|e| format!("field '{}' is not valid JSON: {}", self.key, e))?;
+        if let Some(schema) = self.properties.get(&self.key) {
+            validate(schema, &value).map_err(|e| format!("field '{}': {}", self.key, e))?;
+        }
+        Ok(CompletedField {
+            name: self.key.clone(),
+            value,
+        })
+    }
+}
+
+fn validate(schema: &Value, value: &Value) -> Result<(), String> {
+    let validator = jsonschema::validator_for(schema).map_err(|e| e.to_string())?;
+    validator.validate(value).map_err(|e| e.to_string())
+}
+
+/// The type(s) `schema` expects when a value starting with `first` cannot be
+/// one of them; `None` when it may match (or the schema names no type).
+fn mismatched_type(schema: &Value, first: char) -> Option<String> {
+    let types: Vec<&str> = match schema.get("type")? {
+        Value::String(t) => vec![t.as_str()],
+        Value::Array(ts) => ts.iter().filter_map(Value::as_str).collect(),
+        _ => return None,
+    };
+    let fits = |t: &str| match t {
+        "string" => first == '"',
+        "number" | "integer" => first == '-' || first.is_ascii_digit(),
+        "boolean" => first == 't' || first == 'f',
+        "null" => first == 'n',
+        "object" => first == '{',
+        "array" => first == '[',
+        _ => true,
+    };
+    (!types.iter().any(|t| fits(t))).then(|| types.join(" or "))
+}
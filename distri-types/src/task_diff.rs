@@ -0,0 +1,422 @@
+//! Comparing two executions of the same agent.
+//!
+//! A task's stored messages and events are folded into a [`TaskTrace`]: its
+//! input, one [`TraceStep`] per agent loop iteration (assistant text, tool
+//! calls and results, token usage) and its outcome. Two traces — a replay
+//! and its original, or runs of two prompt versions — are then aligned step
+//! by step: steps calling the same tools are matched in order (a longest
+//! common subsequence), and the unmatched steps between two matches are
+//! paired up as changed, the rest reported as removed or added.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{
+    AgentEventType, MessageRole, Part, RunUsage, Task, TaskMessage, TaskStatus, ToolCall,
+    ToolResponse,
+};
+
+/// Query of `GET /tasks/compare`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompareTasksQuery {
+    pub baseline: String,
+    pub candidate: String,
+}
+
+/// One agent loop iteration of a task.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TraceStep {
+    /// Assistant text of the step, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tool_calls: Vec<ToolCall>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tool_results: Vec<ToolResponse>,
+    /// `None` while the step never completed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub success: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usage: Option<RunUsage>,
+}
+
+/// How a task ended.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TaskOutcome {
+    /// `None` while the task has not finished.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub success: Option<bool>,
+    /// The `final` tool's answer, or else the last assistant text.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub answer: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// A task as recorded, step by step.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TaskTrace {
+    pub task_id: String,
+    pub thread_id: String,
+    pub agent_id: String,
+    pub status: TaskStatus,
+    /// The user message the task ran on.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub input: Option<String>,
+    pub steps: Vec<TraceStep>,
+    pub outcome: TaskOutcome,
+    /// Usage of the whole run: the run's own total when it recorded one,
+    /// the sum of its steps otherwise.
+    pub usage: RunUsage,
+}
+
+impl TaskTrace {
+    /// Fold `task`'s stored messages and events, oldest first, into a trace.
+    pub fn from_history(task: &Task, agent_id: &str, history: &[TaskMessage]) -> Self {
+        let mut trace = TaskTrace {
+            task_id: task.id.clone(),
+            thread_id: task.thread_id.clone(),
+            agent_id: agent_id.to_string(),
+            status: task.status.clone(),
+            input: None,
+            steps: Vec::new(),
+            outcome: TaskOutcome::default(),
+            usage: RunUsage::default(),
+        };
+        let mut run_usage = None;
+        // The step events and messages are recorded in order, so whatever
+        // is recorded while a step is open belongs to it.
+        let mut open = false;
+        let mut last_text = None;
+
+        for entry in history {
+            match entry {
+                TaskMessage::Message(message) => {
+                    let text = message.as_text().filter(|t| !t.trim().is_empty());
+                    match message.role {
+                        MessageRole::User if trace.input.is_none() => trace.input = text,
+                        MessageRole::Assistant => {
+                            if let Some(text) = &text {
+                                last_text = Some(text.clone());
+                            }
+                            if let (Some(step), Some(text)) = (current(&mut trace, open), text) {
+                                step.text = Some(match step.text.take() {
+                                    Some(earlier) => format!("{}\n{}", earlier, text),
+                                    None => text,
+                                });
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+                TaskMessage::Event(event) => match &event.event {
+                    AgentEventType::StepStarted { .. } => {
+                        trace.steps.push(TraceStep::default());
+                        open = true;
+                    }
+                    AgentEventType::StepCompleted { success, usage, .. } => {
+                        if let Some(step) = current(&mut trace, open) {
+                            step.success = Some(*success);
+                            step.usage = usage.clone();
+                        }
+                        open = false;
+                    }
+                    AgentEventType::ToolCalls { tool_calls, .. } => {
+                        for call in tool_calls {
+                            if call.tool_name == "final" {
+                                trace.outcome.answer = Some(answer_text(&call.input));
+                            }
+                        }
+                        if let Some(step) = current(&mut trace, open) {
+                            step.tool_calls.extend(tool_calls.iter().cloned());
+                        }
+                    }
+                    AgentEventType::ToolResults { results, .. } => {
+                        if let Some(step) = current(&mut trace, open) {
+                            step.tool_results.extend(results.iter().cloned());
+                        }
+                    }
+                    AgentEventType::RunFinished { success, usage, .. } => {
+                        trace.outcome.success = Some(*success);
+                        run_usage = usage.clone();
+                    }
+                    AgentEventType::RunError { message, usage, .. } => {
+                        trace.outcome.success = Some(false);
+                        trace.outcome.error = Some(message.clone());
+                        if usage.is_some() {
+                            run_usage = usage.clone();
+                        }
+                    }
+                    _ => {}
+                },
+            }
+        }
+
+        if trace.outcome.answer.is_none() {
+            trace.outcome.answer = last_text;
+        }
+        trace.usage = run_usage.unwrap_or_else(|| sum_usage(&trace.steps));
+        trace
+    }
+}
+
+fn current(trace: &mut TaskTrace, open: bool) -> Option<&mut TraceStep> {
+    open.then(|| trace.steps.last_mut()).flatten()
+}
+
+fn answer_text(input: &Value) -> String {
+    match input {
+        Value::String(text) => text.clone(),
+        other => other.to_string(),
+    }
+}
+
+fn sum_usage(steps: &[TraceStep]) -> RunUsage {
+    let mut total = RunUsage::default();
+    for usage in steps.iter().filter_map(|s| s.usage.as_ref()) {
+        total.input_tokens += usage.input_tokens;
+        total.output_tokens += usage.output_tokens;
+        total.total_tokens += usage.total_tokens;
+        total.cached_tokens += usage.cached_tokens;
+        total.cache_write_tokens += usage.cache_write_tokens;
+        total.model = total.model.or_else(|| usage.model.clone());
+        total.cost_usd = match (total.cost_usd, usage.cost_usd) {
+            (Some(a), Some(b)) => Some(a + b),
+            (a, b) => a.or(b),
+        };
+    }
+    total
+}
+
+/// How a step differs between the two tasks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StepChange {
+    Unchanged,
+    Changed,
+    /// Only in the baseline.
+    Removed,
+    /// Only in the candidate.
+    Added,
+}
+
+/// What differs in a changed step.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StepField {
+    Text,
+    ToolCalls,
+    ToolResults,
+    Success,
+}
+
+/// Candidate minus baseline.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct UsageDelta {
+    pub input_tokens: i64,
+    pub output_tokens: i64,
+    pub total_tokens: i64,
+    /// `None` unless both costs are known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cost_usd: Option<f64>,
+}
+
+impl UsageDelta {
+    pub fn between(baseline: Option<&RunUsage>, candidate: Option<&RunUsage>) -> Self {
+        let tokens = |usage: Option<&RunUsage>, f: fn(&RunUsage) -> u32| {
+            usage.map(f).unwrap_or_default() as i64
+        };
+        Self {
+            input_tokens: tokens(candidate, |u| u.input_tokens)
+                - tokens(baseline, |u| u.input_tokens),
+            output_tokens: tokens(candidate, |u| u.output_tokens)
+                - tokens(baseline, |u| u.output_tokens),
+            total_tokens: tokens(candidate, |u| u.total_tokens)
+                - tokens(baseline, |u| u.total_tokens),
+            cost_usd: match (
+                baseline.map_or(Some(0.0), |u| u.cost_usd),
+                candidate.map_or(Some(0.0), |u| u.cost_usd),
+            ) {
+                (Some(b), Some(c)) => Some(c - b),
+                _ => None,
+            },
+        }
+    }
+}
+
+/// One row of the aligned steps.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StepDiff {
+    pub change: StepChange,
+    /// Index into the baseline's `steps`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub baseline: Option<usize>,
+    /// Index into the candidate's `steps`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub candidate: Option<usize>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fields: Vec<StepField>,
+    pub usage: UsageDelta,
+}
+
+/// Response of `GET /tasks/compare`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TaskComparison {
+    pub baseline: TaskTrace,
+    pub candidate: TaskTrace,
+    pub steps: Vec<StepDiff>,
+    /// Whether the two tasks ended the same way: success and answer.
+    pub same_outcome: bool,
+    pub usage: UsageDelta,
+}
+
+impl TaskComparison {
+    pub fn new(baseline: TaskTrace, candidate: TaskTrace) -> Self {
+        let steps = align(&baseline.steps, &candidate.steps);
+        let same_outcome = baseline.outcome.success == candidate.outcome.success
+            && baseline.outcome.answer == candidate.outcome.answer;
+        let usage = UsageDelta::between(Some(&baseline.usage), Some(&candidate.usage));
+        Self {
+            baseline,
+            candidate,
+            steps,
+            same_outcome,
+            usage,
+        }
+    }
+}
+
+/// Steps are matched on the tools they call; a step without tool calls
+/// matches another one without.
+fn step_key(step: &TraceStep) -> Vec<&str> {
+    let mut names: Vec<&str> = step
+        .tool_calls
+        .iter()
+        .map(|c| c.tool_name.as_str())
+        .collect();
+    names.sort_unstable();
+    names
+}
+
+fn align(baseline: &[TraceStep], candidate: &[TraceStep]) -> Vec<StepDiff> {
+    let (n, m) = (baseline.len(), candidate.len());
+    let keys_b: Vec<_> = baseline.iter().map(step_key).collect();
+    let keys_c: Vec<_> = candidate.iter().map(step_key).collect();
+    // lcs[i][j]: length of the longest common subsequence of the keys of
+    // baseline[i..] and candidate[j..].
+    let mut lcs = vec![vec![0usize; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i][j] = if keys_b[i] == keys_c[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut rows = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    let (mut gap_b, mut gap_c) = (Vec::new(), Vec::new());
+    while i < n || j < m {
+        if i < n && j < m && keys_b[i] == keys_c[j] && lcs[i][j] == lcs[i + 1][j + 1] + 1 {
+            flush_gap(baseline, candidate, &mut gap_b, &mut gap_c, &mut rows);
+            rows.push(compare_steps(baseline, candidate, Some(i), Some(j)));
+            i += 1;
+            j += 1;
+        } else if j == m || (i < n && lcs[i + 1][j] >= lcs[i][j + 1]) {
+            gap_b.push(i);
+            i += 1;
+        } else {
+            gap_c.push(j);
+            j += 1;
+        }
+    }
+    flush_gap(baseline, candidate, &mut gap_b, &mut gap_c, &mut rows);
+    rows
+}
+
+/// Pair the unmatched steps between two matches in order; the leftovers
+/// are removed or added.
+fn flush_gap(
+    baseline: &[TraceStep],
+    candidate: &[TraceStep],
+    gap_b: &mut Vec<usize>,
+    gap_c: &mut Vec<usize>,
+    rows: &mut Vec<StepDiff>,
+) {
+    let len = gap_b.len().max(gap_c.len());
+    for k in 0..len {
+        rows.push(compare_steps(
+            baseline,
+            candidate,
+            gap_b.get(k).copied(),
+            gap_c.get(k).copied(),
+        ));
+    }
+    gap_b.clear();
+    gap_c.clear();
+}
+
+fn compare_steps(
+    baseline: &[TraceStep],
+    candidate: &[TraceStep],
+    b: Option<usize>,
+    c: Option<usize>,
+) -> StepDiff {
+    let (left, right) = (b.map(|i| &baseline[i]), c.map(|j| &candidate[j]));
+    let usage = UsageDelta::between(
+        left.and_then(|s| s.usage.as_ref()),
+        right.and_then(|s| s.usage.as_ref()),
+    );
+    let (change, fields) = match (left, right) {
+        (Some(left), Some(right)) => {
+            let fields = changed_fields(left, right);
+            let change = if fields.is_empty() {
+                StepChange::Unchanged
+            } else {
+                StepChange::Changed
+            };
+            (change, fields)
+        }
+        (Some(_), None) => (StepChange::Removed, vec![]),
+        _ => (StepChange::Added, vec![]),
+    };
+    StepDiff {
+        change,
+        baseline: b,
+        candidate: c,
+        fields,
+        usage,
+    }
+}
+
+fn changed_fields(left: &TraceStep, right: &TraceStep) -> Vec<StepField> {
+    // Tool call ids differ between runs; compare names and arguments.
+    let calls = |step: &TraceStep| -> Vec<(String, Value)> {
+        step.tool_calls
+            .iter()
+            .map(|c| (c.tool_name.clone(), c.input.clone()))
+            .collect()
+    };
+    let results = |step: &TraceStep| -> Vec<(String, Vec<Part>)> {
+        step.tool_results
+            .iter()
+            .map(|r| (r.tool_name.clone(), r.parts.clone()))
+            .collect()
+    };
+    let mut fields = Vec::new();
+    if left.text != right.text {
+        fields.push(StepField::Text);
+    }
+    if calls(left) != calls(right) {
+        fields.push(StepField::ToolCalls);
+    }
+    if results(left) != results(right) {
+        fields.push(StepField::ToolResults);
+    }
+    if left.success != right.success {
+        fields.push(StepField::Success);
+    }
+    fields
+}
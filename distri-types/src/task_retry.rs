@@ -0,0 +1,190 @@
+//! Retrying a failed task with what went wrong.
+//!
+//! `POST /tasks/{task_id}/retry` runs the failed task's user message again
+//! as a new task on the same thread, referencing the failed one. The
+//! agent's instructions are extended with a [`FailureSummary`] of the
+//! failed run — its error and the tool calls that failed — so the model can
+//! route around the problem instead of repeating it. When the failed task
+//! is the thread's last, it is archived: the summary stands in for it.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use utoipa::ToSchema;
+
+use crate::configuration::DefinitionOverrides;
+use crate::{AgentEventType, Part, TaskMessage};
+
+/// Longest tool error kept in a summary, in characters.
+const MAX_ERROR_CHARS: usize = 500;
+
+/// Body of `POST /tasks/{task_id}/retry`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct RetryRequest {
+    /// Agent parameters to change for the retry (model, temperature…).
+    /// An `instructions_append` is kept, followed by the failure summary.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub overrides: Option<DefinitionOverrides>,
+}
+
+/// A tool call of the failed run that did not succeed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct FailedToolCall {
+    pub tool_call_id: String,
+    pub tool_name: String,
+    #[serde(default)]
+    pub input: Value,
+    /// What the tool returned or raised, cut to 500 characters.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// What went wrong in a failed run.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct FailureSummary {
+    /// Message of the run's `run_error` event.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
+    /// Steps the run started before it failed.
+    pub steps: usize,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub failed_tool_calls: Vec<FailedToolCall>,
+}
+
+impl FailureSummary {
+    /// Fold a task's stored messages and events, oldest first, into a
+    /// summary of its failure.
+    pub fn from_history(history: &[TaskMessage]) -> Self {
+        let mut summary = FailureSummary::default();
+        let mut inputs = Vec::new();
+        let mut results = Vec::new();
+        for entry in history {
+            let TaskMessage::Event(event) = entry else {
+                continue;
+            };
+            match &event.event {
+                AgentEventType::StepStarted { .. } => summary.steps += 1,
+                AgentEventType::ToolCalls { tool_calls, .. } => {
+                    inputs.extend(
+                        tool_calls
+                            .iter()
+                            .map(|c| (c.tool_call_id.clone(), c.input.clone())),
+                    );
+                }
+                AgentEventType::ToolResults { results: r, .. } => {
+                    results.extend(
+                        r.iter()
+                            .map(|r| (r.tool_call_id.clone(), result_text(&r.parts))),
+                    );
+                }
+                AgentEventType::ToolRecovery {
+                    tool_call_id,
+                    error,
+                    ..
+                } => results.push((tool_call_id.clone(), error.clone())),
+                AgentEventType::ToolExecutionEnd {
+                    tool_call_id,
+                    tool_call_name,
+                    success: false,
+                    ..
+                } => summary.failed_tool_calls.push(FailedToolCall {
+                    tool_call_id: tool_call_id.clone(),
+                    tool_name: tool_call_name.clone(),
+                    input: Value::Null,
+                    error: None,
+                }),
+                // A failed run is closed with a validation error that only
+                // says it failed; the error before it tells why.
+                AgentEventType::RunError { code, .. }
+                    if summary.error.is_some() && code.as_deref() == Some("VALIDATION_ERROR") => {}
+                AgentEventType::RunError { message, code, .. } => {
+                    summary.error = Some(message.clone());
+                    summary.code = code.clone();
+                }
+                _ => {}
+            }
+        }
+        for call in &mut summary.failed_tool_calls {
+            if let Some((_, input)) = inputs.iter().find(|(id, _)| *id == call.tool_call_id) {
+                call.input = input.clone();
+            }
+            // The call's last result: after a recovery, its final outcome.
+            call.error = results
+                .iter()
+                .rev()
+                .find(|(id, text)| *id == call.tool_call_id && !text.is_empty())
+                .map(|(_, text)| truncate(text));
+        }
+        summary
+    }
+
+    /// The summary as instructions for the retry.
+    pub fn to_context(&self) -> String {
+        let mut out = String::from(
+            "## Previous attempt failed\n\nThis task was attempted before and failed. \
+             Avoid repeating what went wrong: try another approach or other tools, and \
+             explain in your answer if the problem cannot be worked around.",
+        );
+        match (&self.error, &self.code) {
+            (Some(error), Some(code)) => out.push_str(&format!("\n\nError ({}): {}", code, error)),
+            (Some(error), None) => out.push_str(&format!("\n\nError: {}", error)),
+            _ => {}
+        }
+        out.push_str(&format!(
+            "\n\nThe attempt ran {} step{} before failing.",
+            self.steps,
+            if self.steps == 1 { "" } else { "s" }
+        ));
+        if !self.failed_tool_calls.is_empty() {
+            out.push_str("\n\nFailed tool calls:");
+            for call in &self.failed_tool_calls {
+                out.push_str(&format!("\n- `{}` with {}", call.tool_name, call.input));
+                if let Some(error) = &call.error {
+                    out.push_str(&format!(": {}", error.replace('\n', " ")));
+                }
+            }
+        }
+        out
+    }
+}
+
+/// Outcome of a retry.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct TaskRetry {
+    pub thread_id: String,
+    /// The new task.
+    pub task_id: String,
+    /// The failed task; the new task's reference.
+    pub retry_of: String,
+    pub agent_id: String,
+    /// The failed task when it was the last of its thread: hidden from the
+    /// history, which the failure summary stands in for.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub archived_task_ids: Vec<String>,
+    /// What the retry was told about the failure.
+    pub failure: FailureSummary,
+    /// Final answer of the retry.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+}
+
+fn result_text(parts: &[Part]) -> String {
+    parts
+        .iter()
+        .filter_map(|part| match part {
+            Part::Text(text) => Some(text.clone()),
+            Part::Data(data) => Some(data.to_string()),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn truncate(text: &str) -> String {
+    if text.chars().count() <= MAX_ERROR_CHARS {
+        return text.to_string();
+    }
+    let cut: String = text.chars().take(MAX_ERROR_CHARS).collect();
+    format!("{}…", cut)
+}
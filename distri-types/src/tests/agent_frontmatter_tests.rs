@@ -0,0 +1,216 @@
+use serde_json::json;
+
+use crate::agent_frontmatter::FrontMatter;
+use crate::{
+    AgentError, ModelProvider, ModelSettings, ResponseSchemaConfig, parse_agent_markdown_content,
+};
+
+const AGENT: &str = r#"---
+name = "support"
+description = "Answers support tickets"
+model_fallbacks = ["anthropic/claude-sonnet-4-5", "gpt-4.1-mini"]
+sub_agents = ["billing", "shipping"]
+guardrails = ["Never promise a refund."]
+
+[model_settings]
+model = "gpt-4.1"
+
+[tools]
+builtin = ["final", "execute_shell"]
+dynamic = [{ name = "lookup_order", type = "http", config = {} }]
+
+[tools.config.lookup_order]
+base_url = "https://orders.internal"
+
+[tools.config.shell]
+timeout_secs = 60
+
+[[available_skills]]
+id = "refunds"
+name = "Refund policy"
+
+[response_schema]
+name = "ticket_reply"
+schema = { type = "object", properties = { reply = { type = "string" } } }
+---
+You answer support tickets.
+"#;
+
+async fn parse_error(markdown: &str) -> String {
+    match parse_agent_markdown_content(markdown).await {
+        Err(AgentError::Validation(message)) => message,
+        other => panic!("expected a validation error, got {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn the_expanded_front_matter_parses() {
+    let definition = parse_agent_markdown_content(AGENT).await.unwrap();
+
+    assert_eq!(definition.instructions, "You answer support tickets.");
+    assert_eq!(definition.sub_agents, ["billing", "shipping"]);
+    assert_eq!(definition.guardrails, ["Never promise a refund."]);
+    assert_eq!(definition.available_skills[0].id, "refunds");
+    let tools = definition.tools.as_ref().unwrap();
+    assert_eq!(
+        tools.config["lookup_order"],
+        json!({ "base_url": "https://orders.internal" })
+    );
+    assert_eq!(tools.config["shell"], json!({ "timeout_secs": 60 }));
+    assert_eq!(
+        definition
+            .response_schema
+            .as_ref()
+            .unwrap()
+            .response_format("support"),
+        Some(json!({
+            "type": "json_schema",
+            "json_schema": {
+                "name": "ticket_reply",
+                "schema": { "type": "object", "properties": { "reply": { "type": "string" } } },
+                "strict": true,
+            }
+        }))
+    );
+}
+
+#[tokio::test]
+async fn syntax_errors_point_at_their_line() {
+    let markdown = "---\nname = \"support\"\ndescription = \"unterminated\n---\nbody\n";
+    let error = parse_error(markdown).await;
+    assert!(error.starts_with("line 3, column"), "{error}");
+
+    // Lines count from the top of the file, leading text included.
+    let markdown = "<!-- agent -->\n\n---\nname = \"support\"\nmax_iterations = \"ten\"\n---\n";
+    let error = parse_error(markdown).await;
+    assert!(error.starts_with("line 5, column"), "{error}");
+}
+
+#[tokio::test]
+async fn invalid_values_point_at_their_key() {
+    let markdown = r#"---
+name = "support"
+sub_agents = [
+  "billing",
+  "bad-name",
+]
+model_fallbacks = ["azure_foundry/gpt-5"]
+
+[tools]
+builtin = ["final"]
+
+[tools.config.web_search]
+max_results = 5
+
+[response_schema]
+name = "reply"
+---
+"#;
+    let error = parse_error(markdown).await;
+    let lines: Vec<&str> = error.lines().collect();
+    assert_eq!(lines.len(), 4, "{error}");
+    assert!(lines[0].starts_with("line 7: model_fallbacks:"), "{error}");
+    assert!(lines[0].contains("azure_foundry"), "{error}");
+    assert!(lines[1].starts_with("line 5: sub_agents:"), "{error}");
+    assert_eq!(
+        lines[2],
+        "line 12: tools.config.web_search: 'web_search' is not one of the agent's tools"
+    );
+    assert_eq!(
+        lines[3],
+        "line 15: response_schema needs a `schema` or a `file`"
+    );
+}
+
+#[tokio::test]
+async fn shell_settings_need_the_shell_tools() {
+    let markdown = "---\nname = \"a\"\n[tools]\nbuiltin = [\"final\"]\n[tools.config.shell]\nimage = \"x\"\n---\n";
+    let error = parse_error(markdown).await;
+    assert!(error.starts_with("line 5: tools.config.shell"), "{error}");
+
+    let markdown = "---\nname = \"a\"\ninclude_shell = true\n[tools]\n[tools.config.shell]\nimage = \"x\"\n---\n";
+    assert!(parse_agent_markdown_content(markdown).await.is_ok());
+}
+
+#[test]
+fn key_lines_follow_tables_and_dotted_keys() {
+    let front_matter = FrontMatter::split(AGENT).unwrap();
+    assert_eq!(front_matter.key_line(&["name"]), 2);
+    assert_eq!(front_matter.key_line(&["model_settings", "model"]), 9);
+    assert_eq!(front_matter.key_line(&["tools", "config", "shell"]), 18);
+    assert_eq!(front_matter.key_line(&["response_schema", "schema"]), 27);
+    // A key that is not written falls back to its enclosing table.
+    assert_eq!(front_matter.key_line(&["tools", "external"]), 11);
+}
+
+#[test]
+fn fallbacks_reuse_the_primary_provider_unless_they_name_one() {
+    let mut definition = crate::StandardDefinition {
+        name: "support".to_string(),
+        model_settings: Some(ModelSettings {
+            model: "gpt-4.1".to_string(),
+            inner: Default::default(),
+        }),
+        model_fallbacks: vec![
+            "gpt-4.1-mini".to_string(),
+            "anthropic/claude-sonnet-4-5".to_string(),
+        ],
+        ..Default::default()
+    };
+
+    let fallbacks = definition.fallback_model_settings();
+    assert_eq!(fallbacks.len(), 2);
+    assert_eq!(fallbacks[0].model, "gpt-4.1-mini");
+    assert_eq!(fallbacks[0].inner.provider.provider_id(), "openai");
+    assert_eq!(fallbacks[1].model, "claude-sonnet-4-5");
+    assert!(matches!(
+        fallbacks[1].inner.provider,
+        ModelProvider::Anthropic { .. }
+    ));
+
+    definition.model_settings = None;
+    assert!(definition.fallback_model_settings().is_empty());
+}
+
+#[test]
+fn response_schema_files_are_inlined_from_the_agent_directory() {
+    let dir = std::env::temp_dir().join(format!("distri-schema-{}", std::process::id()));
+    std::fs::create_dir_all(dir.join("schemas")).unwrap();
+    std::fs::write(dir.join("schemas/reply.json"), r#"{ "type": "object" }"#).unwrap();
+
+    let mut config = ResponseSchemaConfig {
+        file: Some("schemas/reply.json".to_string()),
+        ..Default::default()
+    };
+    config.inline_file(&dir).unwrap();
+    assert_eq!(config.file, None);
+    assert_eq!(config.schema, Some(json!({ "type": "object" })));
+    assert_eq!(
+        config.response_format("pkg/support").unwrap()["json_schema"]["name"],
+        "pkg_support"
+    );
+
+    let mut missing = ResponseSchemaConfig {
+        file: Some("schemas/none.json".to_string()),
+        ..Default::default()
+    };
+    assert!(missing.inline_file(&dir).is_err());
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn guardrails_render_as_a_prompt_section() {
+    let definition = crate::StandardDefinition {
+        guardrails: vec!["Never promise a refund.".to_string()],
+        ..Default::default()
+    };
+    assert_eq!(
+        definition.guardrails_prompt().unwrap(),
+        "<guardrails>\nYou must follow these rules:\n- Never promise a refund.\n</guardrails>"
+    );
+    assert!(
+        crate::StandardDefinition::default()
+            .guardrails_prompt()
+            .is_none()
+    );
+}
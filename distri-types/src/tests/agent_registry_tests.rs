@@ -0,0 +1,119 @@
+use distri_a2a::{AgentCard, AgentExtension, AgentSkill};
+
+use crate::StandardDefinition;
+use crate::agent_registry::{
+    CapabilityQuery, NegotiationError, active_extensions, card_hash, negotiate_card,
+};
+use crate::configuration::{AgentConfig, ServerConfig};
+
+fn card() -> AgentCard {
+    let mut card = AgentConfig::StandardAgent(StandardDefinition {
+        name: "analyst".to_string(),
+        ..Default::default()
+    })
+    .to_card(&ServerConfig::default());
+    card.default_output_modes = vec!["text/plain".to_string(), "application/json".to_string()];
+    card.capabilities.extensions = vec![
+        extension("https://example.com/ext/tracing", false),
+        extension("https://example.com/ext/billing", true),
+    ];
+    card.skills = vec![
+        skill("summarize", None),
+        skill("chart", Some(vec!["image/png".to_string()])),
+    ];
+    card
+}
+
+fn extension(uri: &str, required: bool) -> AgentExtension {
+    AgentExtension {
+        uri: uri.to_string(),
+        description: None,
+        required,
+        params: None,
+    }
+}
+
+fn skill(id: &str, output_modes: Option<Vec<String>>) -> AgentSkill {
+    AgentSkill {
+        id: id.to_string(),
+        name: id.to_string(),
+        description: String::new(),
+        tags: vec![],
+        examples: vec![],
+        input_modes: None,
+        output_modes,
+    }
+}
+
+#[test]
+fn query_lists_are_comma_separated() {
+    let query: CapabilityQuery = serde_json::from_value(serde_json::json!({
+        "extensions": "https://example.com/ext/billing, ",
+        "streaming": false,
+    }))
+    .unwrap();
+    assert_eq!(
+        query.extensions,
+        Some(vec!["https://example.com/ext/billing".to_string()])
+    );
+    assert_eq!(query.output_modes, None);
+    assert_eq!(query.streaming, Some(false));
+}
+
+#[test]
+fn negotiation_keeps_only_supported_extensions() {
+    let query = CapabilityQuery {
+        extensions: Some(vec!["https://example.com/ext/billing".to_string()]),
+        ..Default::default()
+    };
+    let negotiated = negotiate_card(card(), &query).unwrap();
+    assert_eq!(
+        active_extensions(&negotiated),
+        "https://example.com/ext/billing"
+    );
+
+    let query = CapabilityQuery {
+        extensions: Some(vec!["https://example.com/ext/tracing".to_string()]),
+        ..Default::default()
+    };
+    assert_eq!(
+        negotiate_card(card(), &query).unwrap_err(),
+        NegotiationError::MissingRequiredExtensions(vec![
+            "https://example.com/ext/billing".to_string()
+        ])
+    );
+}
+
+#[test]
+fn negotiation_narrows_output_modes_and_skills() {
+    let query = CapabilityQuery {
+        output_modes: Some(vec!["application/json".to_string()]),
+        streaming: Some(false),
+        ..Default::default()
+    };
+    let negotiated = negotiate_card(card(), &query).unwrap();
+    assert_eq!(negotiated.default_output_modes, vec!["application/json"]);
+    let skills: Vec<&str> = negotiated.skills.iter().map(|s| s.id.as_str()).collect();
+    assert_eq!(skills, vec!["summarize"]);
+    assert!(!negotiated.capabilities.streaming);
+    // Untouched without an extensions list.
+    assert_eq!(negotiated.capabilities.extensions.len(), 2);
+
+    let query = CapabilityQuery {
+        output_modes: Some(vec!["audio/mpeg".to_string()]),
+        ..Default::default()
+    };
+    assert!(matches!(
+        negotiate_card(card(), &query),
+        Err(NegotiationError::NoCommonOutputMode(_))
+    ));
+}
+
+#[test]
+fn card_hash_tracks_card_content() {
+    let mut changed = card();
+    assert_eq!(card_hash(&card()), card_hash(&card()));
+    changed.description = "Answers questions about sales".to_string();
+    assert_ne!(card_hash(&card()), card_hash(&changed));
+    assert_eq!(card_hash(&changed).len(), 64);
+}
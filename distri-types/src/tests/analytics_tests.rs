@@ -0,0 +1,205 @@
+use chrono::{NaiveDate, TimeZone, Utc};
+
+use crate::api::analytics::{
+    AgentAnalytics, AnalyticsDelta, AnalyticsQuery, DailyAgentRuns, DailyAgentTools, DateRange,
+    RunAnalytics, RunCounters, ToolAnalytics,
+};
+use crate::{AgentEvent, AgentEventType, RunUsage};
+
+fn day(d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(2026, 10, d).unwrap()
+}
+
+fn event(task_id: &str, event: AgentEventType) -> AgentEvent {
+    AgentEvent {
+        timestamp: Utc.with_ymd_and_hms(2026, 10, 3, 23, 59, 0).unwrap(),
+        thread_id: "thread-1".to_string(),
+        run_id: "run-1".to_string(),
+        event,
+        task_id: task_id.to_string(),
+        parent_task_id: None,
+        agent_id: "researcher".to_string(),
+        user_id: None,
+        identifier_id: None,
+        workspace_id: None,
+        channel_id: None,
+    }
+}
+
+fn runs(d: u32, agent_id: &str, runs: i64, succeeded: i64, steps: i64) -> DailyAgentRuns {
+    DailyAgentRuns {
+        day: day(d),
+        agent_id: agent_id.to_string(),
+        counters: RunCounters {
+            runs,
+            succeeded,
+            failed: runs - succeeded,
+            steps,
+            ..Default::default()
+        },
+    }
+}
+
+#[test]
+fn query_range_defaults_and_validation() {
+    let today = day(17);
+    let range = AnalyticsQuery::default().range(today).unwrap();
+    assert_eq!(range.to, today);
+    assert_eq!(range.days().count(), 30);
+
+    let query = AnalyticsQuery {
+        from: Some(day(5)),
+        to: Some(day(1)),
+        ..Default::default()
+    };
+    assert!(query.range(today).is_err());
+
+    let query = AnalyticsQuery {
+        from: Some(NaiveDate::from_ymd_opt(2025, 1, 1).unwrap()),
+        ..Default::default()
+    };
+    assert!(query.range(today).is_err());
+}
+
+#[test]
+fn deltas_count_runs_and_tool_calls_of_the_storing_task() {
+    let finished = event(
+        "task-1",
+        AgentEventType::RunFinished {
+            success: true,
+            total_steps: 4,
+            failed_steps: 0,
+            usage: Some(RunUsage {
+                total_tokens: 150,
+                input_tokens: 100,
+                output_tokens: 50,
+                cached_tokens: 0,
+                cache_write_tokens: 0,
+                estimated_tokens: 0,
+                model: None,
+                cost_usd: Some(0.25),
+            }),
+            context_budget: None,
+        },
+    );
+    let Some(AnalyticsDelta::Run(row)) = AnalyticsDelta::from_event("task-1", &finished) else {
+        panic!("expected a run delta");
+    };
+    assert_eq!(row.day, day(3));
+    assert_eq!(row.agent_id, "researcher");
+    assert_eq!(
+        row.counters,
+        RunCounters {
+            runs: 1,
+            succeeded: 1,
+            failed: 0,
+            steps: 4,
+            input_tokens: 100,
+            output_tokens: 50,
+            cost_usd: 0.25,
+        }
+    );
+
+    // Relayed into the parent task's log: counted under the child's own.
+    assert_eq!(AnalyticsDelta::from_event("parent", &finished), None);
+
+    let errored = event(
+        "task-1",
+        AgentEventType::RunError {
+            message: "boom".to_string(),
+            code: None,
+            usage: None,
+        },
+    );
+    let Some(AnalyticsDelta::Run(row)) = AnalyticsDelta::from_event("task-1", &errored) else {
+        panic!("expected a run delta");
+    };
+    assert_eq!((row.counters.runs, row.counters.failed), (1, 1));
+
+    let tool = event(
+        "task-1",
+        AgentEventType::ToolExecutionEnd {
+            step_id: "s".to_string(),
+            tool_call_id: "c".to_string(),
+            tool_call_name: "search".to_string(),
+            success: false,
+        },
+    );
+    assert_eq!(
+        AnalyticsDelta::from_event("task-1", &tool),
+        Some(AnalyticsDelta::Tool(DailyAgentTools {
+            day: day(3),
+            agent_id: "researcher".to_string(),
+            tool_name: "search".to_string(),
+            calls: 1,
+            failures: 1,
+        }))
+    );
+
+    let started = event("task-1", AgentEventType::RunStarted {});
+    assert_eq!(AnalyticsDelta::from_event("task-1", &started), None);
+}
+
+#[test]
+fn responses_aggregate_rows() {
+    let range = DateRange {
+        from: day(1),
+        to: day(3),
+    };
+    let rows = vec![
+        runs(1, "a", 2, 1, 6),
+        runs(1, "b", 1, 1, 2),
+        runs(3, "b", 3, 3, 3),
+        runs(9, "a", 5, 5, 5),
+    ];
+
+    let by_day = RunAnalytics::from_rows(range, &rows);
+    assert_eq!(by_day.totals.counters.runs, 6);
+    assert_eq!(by_day.totals.success_rate, Some(5.0 / 6.0));
+    assert_eq!(by_day.totals.avg_steps_per_run, Some(11.0 / 6.0));
+    let days: Vec<_> = by_day
+        .days
+        .iter()
+        .map(|d| (d.day, d.stats.counters.runs))
+        .collect();
+    assert_eq!(days, vec![(day(1), 3), (day(2), 0), (day(3), 3)]);
+    assert_eq!(by_day.days[1].stats.success_rate, None);
+
+    let json = serde_json::to_value(&by_day.totals).unwrap();
+    assert_eq!(json["runs"], 6);
+
+    let by_agent = AgentAnalytics::from_rows(range, &rows);
+    let agents: Vec<_> = by_agent
+        .agents
+        .iter()
+        .map(|a| (a.agent_id.as_str(), a.stats.counters.runs))
+        .collect();
+    assert_eq!(agents, vec![("b", 4), ("a", 2)]);
+
+    let tool = |d, name: &str, calls, failures| DailyAgentTools {
+        day: day(d),
+        agent_id: "a".to_string(),
+        tool_name: name.to_string(),
+        calls,
+        failures,
+    };
+    let tools = ToolAnalytics::from_rows(
+        range,
+        &[
+            tool(1, "search", 3, 1),
+            tool(2, "search", 1, 0),
+            tool(2, "fetch", 5, 0),
+            tool(3, "write", 1, 1),
+        ],
+        2,
+    );
+    let names: Vec<_> = tools
+        .tools
+        .iter()
+        .map(|t| (t.tool_name.as_str(), t.calls, t.failure_rate))
+        .collect();
+    assert_eq!(
+        names,
+        vec![("fetch", 5, Some(0.0)), ("search", 4, Some(0.25))]
+    );
+}
@@ -0,0 +1,149 @@
+use serde_json::json;
+
+use crate::browser::policy::download_type;
+use crate::browser::{BrowserAgentConfig, BrowserPolicy, BrowserPolicyRule};
+
+fn policy() -> BrowserPolicy {
+    serde_json::from_value(json!({
+        "allowed_domains": ["*.example.com", "docs.rs"],
+        "read_only": true,
+        "downloads": ["pdf"],
+        "max_pages_per_task": 3,
+    }))
+    .unwrap()
+}
+
+#[test]
+fn policy_is_read_from_browser_config() {
+    let config: BrowserAgentConfig = serde_json::from_value(json!({
+        "enabled": true,
+        "policy": {"read_only": true},
+    }))
+    .unwrap();
+
+    assert!(config.policy().unwrap().read_only);
+    assert!(serde_json::from_value::<BrowserPolicy>(json!({"allowed_hosts": ["a.com"]})).is_err());
+}
+
+#[test]
+fn only_allowed_domains_load() {
+    let policy = policy();
+
+    assert!(policy.check_url("https://example.com/a").is_ok());
+    assert!(policy.check_url("https://shop.example.com/a").is_ok());
+    assert!(policy.check_url("https://docs.rs/serde").is_ok());
+
+    let violation = policy.check_url("https://evil.com/").unwrap_err();
+    assert_eq!(violation.rule, BrowserPolicyRule::AllowedDomains);
+    assert_eq!(violation.url.as_deref(), Some("https://evil.com/"));
+}
+
+#[test]
+fn downloads_are_limited_to_the_listed_types() {
+    let policy = policy();
+
+    assert!(policy.check_url("https://example.com/report.PDF").is_ok());
+    assert!(policy.check_url("https://example.com/index.html").is_ok());
+    assert!(policy.check_url("https://example.com/v1.2/docs").is_ok());
+    let violation = policy
+        .check_url("https://example.com/setup.exe")
+        .unwrap_err();
+    assert_eq!(violation.rule, BrowserPolicyRule::Downloads);
+
+    assert_eq!(
+        download_type("https://a.com/data.csv?x=1").as_deref(),
+        Some("csv")
+    );
+    assert_eq!(download_type("https://a.com/page.php"), None);
+    assert_eq!(download_type("https://a.com/release/1.2"), None);
+}
+
+#[test]
+fn read_only_blocks_interactive_commands() {
+    let policy = policy();
+
+    assert!(
+        policy
+            .check_command(&json!({"command": "get_content"}))
+            .is_ok()
+    );
+    assert!(
+        policy
+            .check_command(&json!({"command": "navigate_to", "data": {"url": "https://docs.rs"}}))
+            .is_ok()
+    );
+
+    let violation = policy
+        .check_command(&json!({"command": "click", "data": {"selector": "#buy"}}))
+        .unwrap_err();
+    assert_eq!(violation.rule, BrowserPolicyRule::ReadOnly);
+    assert_eq!(violation.command.as_deref(), Some("click"));
+    for command in [
+        "set_cookie",
+        "delete_cookie",
+        "set_local_storage",
+        "clear_session_storage",
+        "refresh",
+        "a_command_added_later",
+    ] {
+        assert_eq!(
+            policy
+                .check_command(&json!({ "command": command }))
+                .unwrap_err()
+                .rule,
+            BrowserPolicyRule::ReadOnly,
+            "{command}"
+        );
+    }
+
+    let violation = policy
+        .check_command(&json!({"command": "navigate_to", "data": {"url": "https://evil.com"}}))
+        .unwrap_err();
+    assert_eq!(violation.rule, BrowserPolicyRule::AllowedDomains);
+    assert_eq!(violation.command.as_deref(), Some("navigate_to"));
+}
+
+#[test]
+fn page_budget_is_enforced() {
+    let policy = policy();
+
+    assert!(policy.check_pages(0, 3).is_ok());
+    assert!(policy.check_pages(2, 1).is_ok());
+    assert_eq!(
+        policy.check_pages(3, 1).unwrap_err().rule,
+        BrowserPolicyRule::MaxPages
+    );
+    assert!(BrowserPolicy::default().check_pages(1000, 1).is_ok());
+}
+
+#[test]
+fn violations_are_reported_as_structured_results() {
+    let violation = policy()
+        .check_command(&json!({"command": "type_text", "data": {"selector": "#q", "text": "x"}}))
+        .unwrap_err();
+
+    assert_eq!(
+        violation.to_json(),
+        json!({
+            "error": "browser_policy_violation",
+            "rule": "read_only",
+            "command": "type_text",
+            "message": "browser policy: 'type_text' is not allowed in read-only mode",
+        })
+    );
+}
+
+#[test]
+fn invalid_policies_are_rejected() {
+    assert!(policy().validate().is_ok());
+    let bad_domain = BrowserPolicy {
+        allowed_domains: vec!["https://example.com/".to_string()],
+        ..Default::default()
+    };
+    assert!(bad_domain.validate().unwrap_err().contains("not a domain"));
+    let no_pages = BrowserPolicy {
+        max_pages_per_task: Some(0),
+        ..Default::default()
+    };
+    assert!(no_pages.validate().is_err());
+}
@@ -0,0 +1,113 @@
+use serde_json::json;
+
+use crate::capability_probe::{
+    CapabilityProbeConfig, PROBE_TOOL, model_key, structured_output_passes, tool_calls_pass,
+};
+use crate::{ModelProvider, ModelSettings, ToolCall};
+
+fn local(model: &str) -> ModelSettings {
+    let mut settings = ModelSettings::new(model);
+    settings.inner.provider = ModelProvider::OpenAICompatible {
+        base_url: "http://localhost:11434/v1/".to_string(),
+        api_key: None,
+        project_id: None,
+    };
+    settings
+}
+
+fn call(tool_name: &str, input: serde_json::Value) -> ToolCall {
+    ToolCall {
+        tool_call_id: "call-1".to_string(),
+        tool_name: tool_name.to_string(),
+        input,
+    }
+}
+
+#[test]
+fn config_defaults_and_validation() {
+    let config: CapabilityProbeConfig = serde_yaml::from_str("{}").unwrap();
+    assert_eq!(config, CapabilityProbeConfig::default());
+    assert_eq!(config.providers, ["openai_compat"]);
+    assert_eq!(config.timeout_secs, 60);
+    config.validate().unwrap();
+
+    assert!(serde_yaml::from_str::<CapabilityProbeConfig>("retries: 3").is_err());
+    let empty = CapabilityProbeConfig {
+        providers: vec![],
+        ..Default::default()
+    };
+    assert!(empty.validate().is_err());
+    let no_timeout = CapabilityProbeConfig {
+        timeout_secs: 0,
+        ..Default::default()
+    };
+    assert!(no_timeout.validate().is_err());
+}
+
+#[test]
+fn probes_configured_providers() {
+    let config = CapabilityProbeConfig::default();
+    assert!(config.probes(&local("qwen2.5:7b").inner.provider));
+    assert!(!config.probes(&ModelProvider::OpenAI {}));
+
+    let all = CapabilityProbeConfig {
+        providers: vec!["*".to_string()],
+        ..Default::default()
+    };
+    assert!(all.probes(&ModelProvider::OpenAI {}));
+}
+
+#[test]
+fn model_key_includes_the_server_of_local_models() {
+    assert_eq!(
+        model_key(&local("qwen2.5:7b-q4")),
+        "openai_compat:http://localhost:11434/v1:qwen2.5:7b-q4"
+    );
+    assert_ne!(
+        model_key(&local("qwen2.5:7b-q4")),
+        model_key(&local("qwen2.5:7b-q8"))
+    );
+
+    let mut hosted = ModelSettings::new("gpt-4.1-mini");
+    hosted.inner.provider = ModelProvider::OpenAI {};
+    assert_eq!(model_key(&hosted), "openai:gpt-4.1-mini");
+}
+
+#[test]
+fn tool_calls_pass_only_when_well_formed() {
+    let paris = call(PROBE_TOOL, json!({ "city": "Paris" }));
+    let tokyo = call(PROBE_TOOL, json!({ "city": "Tokyo, Japan" }));
+    assert!(tool_calls_pass(std::slice::from_ref(&paris), &["paris"]));
+    assert!(tool_calls_pass(
+        &[paris.clone(), tokyo],
+        &["paris", "tokyo"]
+    ));
+
+    // Missing, misnamed or malformed calls fail.
+    assert!(!tool_calls_pass(&[], &["paris"]));
+    assert!(!tool_calls_pass(
+        std::slice::from_ref(&paris),
+        &["paris", "tokyo"]
+    ));
+    assert!(!tool_calls_pass(
+        &[call("weather", json!({ "city": "Paris" }))],
+        &["paris"]
+    ));
+    assert!(!tool_calls_pass(
+        &[call(PROBE_TOOL, json!({ "location": "Paris" }))],
+        &["paris"]
+    ));
+    assert!(!tool_calls_pass(
+        &[paris, call(PROBE_TOOL, json!("Tokyo"))],
+        &["paris"]
+    ));
+}
+
+#[test]
+fn structured_output_passes_on_the_answer() {
+    assert!(structured_output_passes(r#"{"answer": 5}"#));
+    assert!(structured_output_passes("```json\n{\"answer\": 5}\n```"));
+    assert!(!structured_output_passes(r#"{"answer": "5"}"#));
+    assert!(!structured_output_passes(r#"{"answer": 6}"#));
+    assert!(!structured_output_passes("The answer is 5."));
+}
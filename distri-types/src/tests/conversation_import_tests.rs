@@ -0,0 +1,181 @@
+use serde_json::json;
+
+use crate::conversation_import::{ImportFormat, parse_export};
+use crate::{FileType, MessageRole, Part};
+
+fn texts(parts: &[Part]) -> Vec<&str> {
+    parts
+        .iter()
+        .filter_map(|p| match p {
+            Part::Text(t) => Some(t.as_str()),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn chatgpt_export_follows_current_branch() {
+    let export = json!([{
+        "title": "Trip plan",
+        "create_time": 1700000000.5,
+        "conversation_id": "c-1",
+        "current_node": "a2",
+        "mapping": {
+            "root": { "id": "root", "message": null, "parent": null, "children": ["sys"] },
+            "sys": {
+                "id": "sys", "parent": "root", "children": ["u1"],
+                "message": {
+                    "id": "sys", "author": { "role": "system" },
+                    "content": { "content_type": "text", "parts": [""] }
+                }
+            },
+            "u1": {
+                "id": "u1", "parent": "sys", "children": ["a1", "a2"],
+                "message": {
+                    "id": "u1", "author": { "role": "user" }, "create_time": 1700000001.0,
+                    "content": { "content_type": "multimodal_text", "parts": [
+                        { "content_type": "image_asset_pointer", "asset_pointer": "file-service://file-abc" },
+                        "Plan a trip to Lisbon"
+                    ] }
+                }
+            },
+            "a1": {
+                "id": "a1", "parent": "u1", "children": [],
+                "message": {
+                    "id": "a1", "author": { "role": "assistant" },
+                    "content": { "content_type": "text", "parts": ["Regenerated away"] }
+                }
+            },
+            "a2": {
+                "id": "a2", "parent": "u1", "children": [],
+                "message": {
+                    "id": "a2", "author": { "role": "assistant" },
+                    "content": { "content_type": "text", "parts": ["Day 1: Alfama"] }
+                }
+            }
+        }
+    }]);
+
+    let conversations = parse_export(ImportFormat::Chatgpt, &export.to_string()).unwrap();
+    assert_eq!(conversations.len(), 1);
+    let conversation = &conversations[0];
+    assert_eq!(conversation.external_id, "chatgpt:c-1");
+    assert_eq!(conversation.title, "Trip plan");
+    assert_eq!(conversation.created_at, 1700000000500);
+
+    let messages = &conversation.messages;
+    assert_eq!(messages.len(), 2);
+    assert_eq!(messages[0].role, MessageRole::User);
+    assert_eq!(messages[0].created_at, 1700000001000);
+    assert_eq!(
+        texts(&messages[0].parts),
+        vec![
+            "Plan a trip to Lisbon",
+            "[Attachment: file-service://file-abc]"
+        ]
+    );
+    assert_eq!(messages[1].role, MessageRole::Assistant);
+    assert_eq!(texts(&messages[1].parts), vec!["Day 1: Alfama"]);
+}
+
+#[test]
+fn chatgpt_export_without_current_node_takes_latest_branch() {
+    let export = json!({
+        "id": "c-2",
+        "mapping": {
+            "u1": {
+                "parent": null, "children": ["t1"],
+                "message": {
+                    "author": { "role": "user" },
+                    "content": { "content_type": "text", "parts": ["Run it"] },
+                    "metadata": { "attachments": [{ "name": "data.csv", "mime_type": "text/csv" }] }
+                }
+            },
+            "t1": {
+                "parent": "u1", "children": [],
+                "message": {
+                    "author": { "role": "tool", "name": "python" },
+                    "content": { "content_type": "execution_output", "text": "42" }
+                }
+            }
+        }
+    });
+
+    let conversations = parse_export(ImportFormat::Chatgpt, &export.to_string()).unwrap();
+    let messages = &conversations[0].messages;
+    assert_eq!(conversations[0].title, "Imported conversation");
+    assert_eq!(
+        texts(&messages[0].parts),
+        vec!["Run it", "[Attachment: data.csv]"]
+    );
+    assert_eq!(messages[1].role, MessageRole::Assistant);
+    assert_eq!(texts(&messages[1].parts), vec!["42"]);
+}
+
+#[test]
+fn claude_export_maps_senders_and_attachments() {
+    let export = json!([{
+        "uuid": "c-3",
+        "name": "Refactor",
+        "created_at": "2024-05-01T10:00:00.000000+00:00",
+        "chat_messages": [
+            {
+                "uuid": "m1", "sender": "human", "text": "Review this",
+                "created_at": "2024-05-01T10:00:01Z",
+                "attachments": [{ "file_name": "main.rs", "extracted_content": "fn main() {}" }],
+                "files": [{ "file_name": "diagram.png" }]
+            },
+            {
+                "uuid": "m2", "sender": "assistant", "text": "ignored",
+                "content": [
+                    { "type": "thinking", "thinking": "hmm" },
+                    { "type": "text", "text": "Looks good" }
+                ]
+            },
+            { "uuid": "m3", "sender": "assistant", "text": "" }
+        ]
+    }]);
+
+    let conversations = parse_export(ImportFormat::Claude, &export.to_string()).unwrap();
+    let conversation = &conversations[0];
+    assert_eq!(conversation.external_id, "claude:c-3");
+    assert_eq!(conversation.title, "Refactor");
+    assert_eq!(conversation.created_at, 1714557600000);
+
+    let messages = &conversation.messages;
+    assert_eq!(messages.len(), 2);
+    assert_eq!(messages[0].role, MessageRole::User);
+    assert_eq!(messages[0].id, "m1");
+    assert_eq!(
+        texts(&messages[0].parts),
+        vec!["Review this", "[Attachment: diagram.png]"]
+    );
+    match &messages[0].parts[1] {
+        Part::File(FileType::Bytes {
+            bytes,
+            mime_type,
+            name,
+        }) => {
+            assert_eq!(bytes, "Zm4gbWFpbigpIHt9");
+            assert_eq!(mime_type, "text/plain");
+            assert_eq!(name.as_deref(), Some("main.rs"));
+        }
+        other => panic!("expected a file part, got {:?}", other),
+    }
+    assert_eq!(messages[1].role, MessageRole::Assistant);
+    assert_eq!(texts(&messages[1].parts), vec!["Looks good"]);
+}
+
+#[test]
+fn rejects_export_of_the_other_format() {
+    let claude = json!([{ "uuid": "c-4", "chat_messages": [] }]);
+    let err = parse_export(ImportFormat::Chatgpt, &claude.to_string()).unwrap_err();
+    assert!(err.to_string().contains("not a chatgpt export"), "{}", err);
+
+    assert!(parse_export(ImportFormat::Claude, "\"text\"").is_err());
+    assert_eq!(
+        "anthropic".parse::<ImportFormat>().unwrap(),
+        ImportFormat::Claude
+    );
+    assert!("gemini".parse::<ImportFormat>().is_err());
+}
@@ -0,0 +1,61 @@
+use serde_json::json;
+
+use crate::StandardDefinition;
+use crate::critique::{CritiqueModel, CritiqueVerdict, critique_brief};
+
+#[test]
+fn critique_parses_from_an_agent_definition() {
+    let definition: StandardDefinition = toml::from_str(
+        r#"
+name = "support"
+
+[critique]
+model = "same"
+guardrails = ["Never promise a delivery date."]
+"#,
+    )
+    .unwrap();
+
+    let critique = definition.critique.unwrap();
+    assert_eq!(critique.model, CritiqueModel::Same);
+    assert_eq!(critique.guardrails, ["Never promise a delivery date."]);
+
+    let definition: StandardDefinition =
+        toml::from_str("name = \"support\"\n[critique]\n").unwrap();
+    assert_eq!(definition.critique.unwrap().model, CritiqueModel::Analysis);
+}
+
+#[test]
+fn verdicts_are_read_from_json_or_fenced_text() {
+    let verdict = CritiqueVerdict::from_value(&json!({ "approved": true })).unwrap();
+    assert!(!verdict.needs_revision());
+
+    let text = "```json\n{\"approved\": false, \"issues\": [\"promises a date\"]}\n```";
+    let verdict = CritiqueVerdict::from_value(&json!(text)).unwrap();
+    assert!(verdict.needs_revision());
+    let note = verdict.to_note();
+    assert!(note.contains("- promises a date"), "{note}");
+
+    // Issues raised on an approved draft still get it revised.
+    let verdict = CritiqueVerdict {
+        approved: true,
+        issues: vec!["typo".to_string()],
+    };
+    assert!(verdict.needs_revision());
+    assert!(CritiqueVerdict::from_value(&json!("looks fine")).is_none());
+}
+
+#[test]
+fn the_brief_holds_the_task_guardrails_and_draft() {
+    let brief = critique_brief(
+        "When will my order arrive?",
+        "Tomorrow, guaranteed.",
+        &["Never promise a delivery date.".to_string()],
+    );
+    assert!(brief.contains("<task>\nWhen will my order arrive?\n</task>"));
+    assert!(brief.contains("<guardrails>\n- Never promise a delivery date.\n</guardrails>"));
+    assert!(brief.contains("<draft>\nTomorrow, guaranteed.\n</draft>"));
+
+    let brief = critique_brief("task", "draft", &[]);
+    assert!(!brief.contains("guardrail"));
+}
@@ -0,0 +1,211 @@
+use chrono::{DateTime, NaiveDate, TimeZone, Utc};
+use serde_json::json;
+
+use crate::StandardDefinition;
+use crate::datetime::{
+    BusinessCalendar, DateTimeConfig, DateTimeMathInput, TimezoneConvertInput, Tz, datetime_math,
+    parse_datetime, timezone_convert,
+};
+
+fn date(text: &str) -> NaiveDate {
+    NaiveDate::parse_from_str(text, "%Y-%m-%d").unwrap()
+}
+
+/// Saturday 2026-10-17 12:00 UTC.
+fn now() -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2026, 10, 17, 12, 0, 0).unwrap()
+}
+
+fn calendar() -> BusinessCalendar {
+    BusinessCalendar {
+        hours: Some("09:00-17:00".to_string()),
+        holidays: vec![date("2026-10-19"), date("2026-11-01")],
+        ..Default::default()
+    }
+}
+
+#[test]
+fn datetime_parses_from_an_agent_definition() {
+    let definition: StandardDefinition = toml::from_str(
+        r#"
+name = "scheduler"
+
+[datetime]
+timezone = "Europe/Berlin"
+
+[datetime.business_calendar]
+working_days = ["mon", "Tuesday", "wed"]
+hours = "08:30-16:00"
+holidays = ["2026-12-25"]
+"#,
+    )
+    .unwrap();
+    let datetime = definition.datetime.clone().unwrap();
+    let calendar = datetime.business_calendar.clone().unwrap();
+    assert_eq!(calendar.working_days.len(), 3);
+    assert_eq!(calendar.holidays, vec![date("2026-12-25")]);
+    definition.validate().unwrap();
+
+    let invalid: StandardDefinition = toml::from_str(
+        r#"
+name = "scheduler"
+
+[datetime]
+timezone = "Mars/Olympus"
+"#,
+    )
+    .unwrap();
+    let err = invalid.validate().unwrap_err();
+    assert!(err.to_string().contains("unknown timezone"), "{err}");
+
+    let hours = BusinessCalendar {
+        hours: Some("17:00-09:00".to_string()),
+        ..Default::default()
+    };
+    assert!(hours.validate().unwrap_err().contains("open before"));
+}
+
+#[test]
+fn profile_timezone_wins_over_the_configured_one() {
+    let config = DateTimeConfig {
+        timezone: Some("Europe/Berlin".to_string()),
+        business_calendar: None,
+    };
+    assert_eq!(config.resolve_timezone(None), Tz::Europe__Berlin);
+    assert_eq!(config.resolve_timezone(Some("Asia/Tokyo")), Tz::Asia__Tokyo);
+    // An unknown profile zone falls back to the configured one.
+    assert_eq!(config.resolve_timezone(Some("nowhere")), Tz::Europe__Berlin);
+    assert_eq!(DateTimeConfig::default().resolve_timezone(None), Tz::UTC);
+}
+
+#[test]
+fn describe_includes_offset_and_business_calendar() {
+    let config = DateTimeConfig {
+        timezone: None,
+        business_calendar: Some(calendar()),
+    };
+    let text = config.describe(now(), Tz::Europe__Berlin);
+    assert!(
+        text.starts_with("2026-10-17 14:00 (Saturday), Europe/Berlin (UTC+02:00)"),
+        "{text}"
+    );
+    assert!(text.contains("Business days: Mon, Tue, Wed, Thu, Fri, 09:00-17:00."));
+    assert!(text.contains("Today is not a business day."));
+    // Monday is a holiday.
+    assert!(
+        text.contains("Next business day: 2026-10-20 (Tuesday)."),
+        "{text}"
+    );
+    assert!(
+        text.contains("Upcoming holidays: 2026-10-19, 2026-11-01."),
+        "{text}"
+    );
+}
+
+#[test]
+fn business_days_skip_weekends_and_holidays() {
+    let calendar = calendar();
+    // Friday + 1 business day skips the weekend and the Monday holiday.
+    assert_eq!(
+        calendar.add_business_days(date("2026-10-16"), 1).unwrap(),
+        date("2026-10-20")
+    );
+    assert_eq!(
+        calendar.add_business_days(date("2026-10-20"), -1).unwrap(),
+        date("2026-10-16")
+    );
+    assert_eq!(
+        calendar.business_days_between(date("2026-10-16"), date("2026-10-23")),
+        4
+    );
+    assert_eq!(
+        calendar.business_days_between(date("2026-10-23"), date("2026-10-16")),
+        -4
+    );
+}
+
+#[test]
+fn datetime_math_adds_calendar_and_elapsed_units() {
+    let input: DateTimeMathInput = serde_json::from_value(json!({
+        "operation": "add",
+        "datetime": "2026-01-31 10:00",
+        "months": 1,
+        "hours": 2
+    }))
+    .unwrap();
+    let result = datetime_math(&input, Tz::America__New_York, None, now()).unwrap();
+    assert_eq!(result["datetime"], "2026-02-28T12:00:00-05:00");
+    assert_eq!(result["weekday"], "Saturday");
+    assert_eq!(result["is_business_day"], false);
+
+    // Days keep the wall-clock time across a DST change.
+    let input: DateTimeMathInput = serde_json::from_value(json!({
+        "operation": "add",
+        "datetime": "2026-03-07 09:00",
+        "days": 1,
+        "timezone": "America/New_York"
+    }))
+    .unwrap();
+    let result = datetime_math(&input, Tz::UTC, None, now()).unwrap();
+    assert_eq!(result["datetime"], "2026-03-08T09:00:00-04:00");
+
+    let input: DateTimeMathInput = serde_json::from_value(json!({
+        "operation": "subtract",
+        "business_days": 3
+    }))
+    .unwrap();
+    let result = datetime_math(&input, Tz::UTC, Some(&calendar()), now()).unwrap();
+    assert_eq!(result["date"], "2026-10-14");
+}
+
+#[test]
+fn datetime_math_diffs_two_instants() {
+    let input: DateTimeMathInput = serde_json::from_value(json!({
+        "operation": "diff",
+        "datetime": "2026-10-16T09:00:00Z",
+        "to": "2026-10-20 13:30"
+    }))
+    .unwrap();
+    let result = datetime_math(&input, Tz::UTC, Some(&calendar()), now()).unwrap();
+    assert_eq!(result["calendar_days"], 4);
+    assert_eq!(result["business_days"], 1);
+    assert_eq!(result["hours"], 100.5);
+    assert_eq!(result["duration"], "4 days, 4 hours, 30 minutes");
+
+    let missing: DateTimeMathInput =
+        serde_json::from_value(json!({ "operation": "diff" })).unwrap();
+    assert!(datetime_math(&missing, Tz::UTC, None, now()).is_err());
+}
+
+#[test]
+fn timezone_convert_reports_each_target() {
+    let input: TimezoneConvertInput = serde_json::from_value(json!({
+        "datetime": "2026-10-17 09:00",
+        "from": "America/Los_Angeles",
+        "to": ["Europe/London", "Asia/Kolkata"]
+    }))
+    .unwrap();
+    let result = timezone_convert(&input, Tz::UTC, now()).unwrap();
+    assert_eq!(result["source"]["utc_offset"], "-07:00");
+    assert_eq!(
+        result["converted"][0]["datetime"],
+        "2026-10-17T17:00:00+01:00"
+    );
+    assert_eq!(result["converted"][1]["time"], "21:30:00");
+
+    let input: TimezoneConvertInput =
+        serde_json::from_value(json!({ "to": "Nowhere/City" })).unwrap();
+    assert!(
+        timezone_convert(&input, Tz::UTC, now())
+            .unwrap_err()
+            .contains("unknown timezone")
+    );
+}
+
+#[test]
+fn times_skipped_by_dst_are_rejected() {
+    let err = parse_datetime("2026-03-08 02:30", Tz::America__New_York, now()).unwrap_err();
+    assert!(err.contains("daylight saving"), "{err}");
+    let parsed = parse_datetime("now", Tz::Asia__Tokyo, now()).unwrap();
+    assert_eq!(parsed.to_rfc3339(), "2026-10-17T21:00:00+09:00");
+}
@@ -0,0 +1,43 @@
+use serde_json::json;
+
+use crate::dry_run::{DryRunReport, SimulatedSideEffect};
+
+fn effect(tool: &str, input: serde_json::Value) -> SimulatedSideEffect {
+    SimulatedSideEffect {
+        agent_id: "support".to_string(),
+        task_id: "t1".to_string(),
+        tool: tool.to_string(),
+        tool_call_id: None,
+        input,
+    }
+}
+
+#[test]
+fn the_report_lists_each_side_effect() {
+    assert_eq!(
+        DryRunReport::default().to_markdown(),
+        "Dry run: no side effects would have occurred."
+    );
+
+    let report = DryRunReport {
+        side_effects: vec![
+            effect("send_email", json!({ "to": "ana@example.com" })),
+            effect("output_sink:audit", json!("line one\nline two")),
+        ],
+    };
+    assert_eq!(
+        report.to_markdown(),
+        "Dry run: 2 side effects would have occurred:\n\
+         - `send_email` (support): {\"to\":\"ana@example.com\"}\n\
+         - `output_sink:audit` (support): line one line two"
+    );
+}
+
+#[test]
+fn long_inputs_are_cut() {
+    let report = DryRunReport {
+        side_effects: vec![effect("post", json!("x".repeat(300)))],
+    };
+    let line = report.to_markdown();
+    assert!(line.ends_with(&format!("{}…", "x".repeat(200))), "{line}");
+}
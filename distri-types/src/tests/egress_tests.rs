@@ -0,0 +1,137 @@
+use std::collections::BTreeMap;
+
+use crate::egress::{EgressPolicy, EgressPolicyConfig, EgressRules, NetworkDenied};
+use crate::{CapabilityDenied, plugin_capabilities::Capability};
+
+fn policy() -> EgressPolicy {
+    EgressPolicy::new(&EgressPolicyConfig {
+        allow: vec![],
+        deny: vec!["169.254.169.254".to_string(), "*.internal".to_string()],
+        agents: BTreeMap::from([
+            (
+                "researcher".to_string(),
+                EgressRules {
+                    allow: vec!["*.wikipedia.org".to_string(), "arxiv.org".to_string()],
+                    deny: vec![],
+                },
+            ),
+            (
+                "scraper".to_string(),
+                EgressRules {
+                    allow: vec![],
+                    deny: vec!["*.bank.com".to_string()],
+                },
+            ),
+        ]),
+    })
+}
+
+#[test]
+fn deny_wins_and_allow_lists_restrict() {
+    let policy = policy();
+    assert!(policy.check(None, "https://example.com/a").is_ok());
+    assert!(policy.check(None, "http://169.254.169.254/latest").is_err());
+    assert!(policy.check(None, "https://db.internal:5432").is_err());
+    assert!(policy.check(None, "internal").is_err());
+    // A fully qualified name resolves to the same host.
+    assert!(policy.check(None, "http://metadata.internal./").is_err());
+    assert!(
+        policy
+            .check(None, "http://169.254.169.254./latest")
+            .is_err()
+    );
+
+    assert!(
+        policy
+            .check(Some("researcher"), "https://en.wikipedia.org/wiki/Rust")
+            .is_ok()
+    );
+    assert!(policy.check(Some("researcher"), "arxiv.org").is_ok());
+    let outside = policy
+        .check(Some("researcher"), "https://example.com/")
+        .unwrap_err();
+    assert_eq!(outside.reason, "not in the allow list");
+    // An agent's rules never widen the global ones.
+    let denied = policy
+        .check(Some("researcher"), "https://wiki.internal")
+        .unwrap_err();
+    assert_eq!(denied.reason, "matches deny rule '*.internal'");
+
+    assert!(policy.check(Some("scraper"), "https://example.com").is_ok());
+    assert!(
+        policy
+            .check(Some("scraper"), "https://online.bank.com")
+            .is_err()
+    );
+    // Other agents only get the global rules.
+    assert!(
+        policy
+            .check(Some("writer"), "https://online.bank.com")
+            .is_ok()
+    );
+}
+
+#[test]
+fn denials_are_reported_as_structured_errors() {
+    let denied = policy()
+        .check(Some("researcher"), "https://Example.com/upload?x=1")
+        .unwrap_err();
+    assert_eq!(denied.agent.as_deref(), Some("researcher"));
+    assert_eq!(denied.host, "example.com");
+    assert_eq!(
+        denied.to_json(),
+        serde_json::json!({
+            "error": "egress_denied",
+            "host": "example.com",
+            "url": "https://Example.com/upload?x=1",
+            "reason": "not in the allow list",
+            "message": "egress to 'example.com' is blocked: not in the allow list",
+        })
+    );
+    assert_eq!(
+        NetworkDenied::from(denied.clone()).to_json(),
+        denied.to_json()
+    );
+
+    let capability = CapabilityDenied {
+        plugin: "acme".to_string(),
+        capability: Capability::Network,
+        target: "https://evil.net".to_string(),
+    };
+    assert_eq!(
+        NetworkDenied::from(capability.clone()).to_json()["error"],
+        "capability_denied"
+    );
+}
+
+#[test]
+fn config_parses_and_rejects_urls_as_patterns() {
+    let config: EgressPolicyConfig = serde_yaml::from_str(
+        r#"
+deny: ["169.254.169.254", "*.internal", "[::1]"]
+agents:
+  researcher:
+    allow: ["*.wikipedia.org"]
+"#,
+    )
+    .unwrap();
+    assert!(config.validate().is_ok());
+    assert_eq!(config.agents["researcher"].allow, ["*.wikipedia.org"]);
+
+    let url = EgressPolicyConfig {
+        allow: vec!["https://api.example.com/v1".to_string()],
+        ..Default::default()
+    };
+    assert!(url.validate().unwrap_err().contains("not a host pattern"));
+    let agent = EgressPolicyConfig {
+        agents: BTreeMap::from([(
+            "researcher".to_string(),
+            EgressRules {
+                deny: vec!["".to_string()],
+                ..Default::default()
+            },
+        )]),
+        ..Default::default()
+    };
+    assert!(agent.validate().unwrap_err().contains("agent 'researcher'"));
+}
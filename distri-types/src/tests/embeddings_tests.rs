@@ -0,0 +1,38 @@
+use serde_json::json;
+
+use crate::ModelProvider;
+use crate::embeddings::{EmbeddingInput, EmbeddingRequest, EmbeddingsConfig};
+
+#[test]
+fn config_defaults() {
+    let config: EmbeddingsConfig = serde_json::from_value(json!({})).unwrap();
+    assert!(matches!(config.provider, ModelProvider::OpenAI {}));
+    assert_eq!(config.model, "text-embedding-3-small");
+    assert_eq!(config.batch_size, 96);
+    assert_eq!(config.dimensions, None);
+    assert!(config.cache);
+    assert!(!config.index_artifacts);
+}
+
+#[test]
+fn config_rejects_unknown_fields() {
+    let result: Result<EmbeddingsConfig, _> = serde_json::from_value(json!({ "batch": 10 }));
+    assert!(result.is_err());
+}
+
+#[test]
+fn request_input_is_a_string_or_a_list() {
+    let one: EmbeddingRequest = serde_json::from_value(json!({ "input": "hello" })).unwrap();
+    assert_eq!(one.input, EmbeddingInput::One("hello".to_string()));
+    assert_eq!(one.input.into_vec(), vec!["hello".to_string()]);
+
+    let many: EmbeddingRequest = serde_json::from_value(json!({
+        "input": ["a", "b"],
+        "model": "text-embedding-3-large",
+        "dimensions": 256
+    }))
+    .unwrap();
+    assert_eq!(many.model.as_deref(), Some("text-embedding-3-large"));
+    assert_eq!(many.dimensions, Some(256));
+    assert_eq!(many.input.into_vec(), vec!["a", "b"]);
+}
@@ -0,0 +1,72 @@
+use serde_json::json;
+
+use crate::StandardDefinition;
+use crate::ensemble::{EnsembleMode, EnsembleVerdict, judge_brief};
+
+#[test]
+fn ensemble_parses_from_an_agent_definition() {
+    let definition: StandardDefinition = toml::from_str(
+        r#"
+name = "panel"
+
+[ensemble]
+judge = "reviewer"
+mode = "merge"
+branches = [
+  { agent = "analyst" },
+  { agent = "analyst", model = "gpt-4.1" },
+]
+"#,
+    )
+    .unwrap();
+
+    let ensemble = definition.ensemble.unwrap();
+    assert_eq!(ensemble.judge, "reviewer");
+    assert_eq!(ensemble.mode, EnsembleMode::Merge);
+    let labels: Vec<String> = ensemble.branches.iter().map(|b| b.label()).collect();
+    assert_eq!(labels, ["analyst", "analyst (gpt-4.1)"]);
+}
+
+#[test]
+fn an_agent_cannot_be_in_its_own_ensemble() {
+    let definition: StandardDefinition = toml::from_str(
+        r#"
+name = "panel"
+
+[ensemble]
+judge = "panel"
+branches = [{ agent = "analyst" }]
+"#,
+    )
+    .unwrap();
+
+    let err = definition.validate().unwrap_err();
+    assert!(err.to_string().contains("its own ensemble"), "{err}");
+}
+
+#[test]
+fn verdicts_are_read_from_fenced_text() {
+    let reply =
+        json!("Here you go:\n```json\n{\"selected\": 2, \"rationale\": \"cites sources\"}\n```");
+    let verdict = EnsembleVerdict::from_value(&reply).unwrap();
+    assert_eq!(verdict.selected, Some(2));
+    assert_eq!(verdict.rationale.as_deref(), Some("cites sources"));
+    assert_eq!(verdict.answer, None);
+
+    assert_eq!(EnsembleVerdict::from_value(&json!("no idea")), None);
+}
+
+#[test]
+fn judge_brief_numbers_the_candidates() {
+    let brief = judge_brief(
+        EnsembleMode::Pick,
+        "What is 2 + 2?",
+        &[
+            ("analyst".to_string(), "4".to_string()),
+            ("skeptic".to_string(), "5".to_string()),
+        ],
+    );
+    assert!(brief.contains("<task>\nWhat is 2 + 2?\n</task>"));
+    assert!(brief.contains("<candidate number=\"1\" agent=\"analyst\">\n4\n"));
+    assert!(brief.contains("<candidate number=\"2\" agent=\"skeptic\">\n5\n"));
+}
@@ -0,0 +1,76 @@
+use chrono::Utc;
+
+use crate::StandardDefinition;
+use crate::configuration::AgentConfig;
+use crate::evals::{
+    EvalCase, EvalCaseComparison, EvalCaseResult, EvalRun, EvalScore, EvalVerdict, definition_hash,
+};
+
+fn agent(instructions: &str) -> AgentConfig {
+    AgentConfig::StandardAgent(StandardDefinition {
+        name: "support".to_string(),
+        instructions: instructions.to_string(),
+        ..Default::default()
+    })
+}
+
+#[test]
+fn definition_hash_changes_with_the_prompt() {
+    let hash = definition_hash(&agent("Be concise."));
+
+    assert_eq!(hash, definition_hash(&agent("Be concise.")));
+    assert_ne!(hash, definition_hash(&agent("Be thorough.")));
+    assert_eq!(hash.len(), 64);
+}
+
+#[test]
+fn runs_match_by_id_or_hash_prefix() {
+    let run = EvalRun {
+        id: "run-1".to_string(),
+        agent_id: "support".to_string(),
+        definition_hash: "abcdef0123".to_string(),
+        created_at: Utc::now(),
+        results: vec![EvalCaseResult {
+            case: EvalCase {
+                id: "refund".to_string(),
+                task: "How do I get a refund?".to_string(),
+                expected: None,
+            },
+            output: None,
+            error: Some("provider unavailable".to_string()),
+            duration_ms: 40,
+        }],
+    };
+
+    assert!(run.matches("run-1"));
+    assert!(run.matches("abcd"));
+    assert!(!run.matches("abc"));
+    assert!(!run.matches("bcde"));
+    assert_eq!(run.summary().errors, 1);
+}
+
+#[test]
+fn score_counts_verdicts() {
+    let case = |verdict| EvalCaseComparison {
+        case_id: "c".to_string(),
+        verdict,
+        rationale: String::new(),
+    };
+
+    let score = EvalScore::tally(&[
+        case(EvalVerdict::Loss),
+        case(EvalVerdict::Loss),
+        case(EvalVerdict::Win),
+        case(EvalVerdict::Tie),
+    ]);
+
+    assert_eq!(
+        score,
+        EvalScore {
+            wins: 1,
+            losses: 2,
+            ties: 1
+        }
+    );
+    assert!(score.is_regression());
+}
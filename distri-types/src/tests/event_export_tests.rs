@@ -0,0 +1,109 @@
+use serde_json::Value;
+
+use crate::event_export::{BrokerConfig, EventExportConfig, EventFormat, event_type};
+use crate::{AgentEvent, AgentEventType};
+
+fn config(yaml: &str) -> EventExportConfig {
+    serde_yaml::from_str(yaml).unwrap()
+}
+
+fn event(event: AgentEventType) -> AgentEvent {
+    AgentEvent {
+        timestamp: chrono::Utc::now(),
+        thread_id: "thread-1".to_string(),
+        run_id: "run-1".to_string(),
+        event,
+        task_id: "task-1".to_string(),
+        parent_task_id: None,
+        agent_id: "researcher".to_string(),
+        user_id: None,
+        identifier_id: None,
+        workspace_id: None,
+        channel_id: None,
+    }
+}
+
+#[test]
+fn config_defaults_and_validation() {
+    let nats = config("broker: { type: nats, url: 'nats://localhost:4222' }");
+    assert_eq!(
+        nats.broker,
+        BrokerConfig::Nats {
+            url: "nats://localhost:4222".to_string(),
+            subject: "distri.events".to_string(),
+            jetstream: false,
+            credentials_file: None,
+        }
+    );
+    assert_eq!(nats.format, EventFormat::Json);
+    assert_eq!(
+        (nats.buffer_size, nats.batch_size, nats.max_retries),
+        (10_000, 100, 5)
+    );
+    nats.validate().unwrap();
+
+    let wildcard = config("broker: { type: nats, url: 'nats://x', subject: 'distri.>' }");
+    assert!(
+        wildcard
+            .validate()
+            .unwrap_err()
+            .contains("plain NATS subject")
+    );
+    let no_partitions =
+        config("broker: { type: kafka, brokers: ['k:9092'], topic: events, partitions: 0 }");
+    assert!(no_partitions.validate().is_err());
+    assert!(
+        serde_yaml::from_str::<EventExportConfig>(
+            "broker: { type: kafka, brokers: [], topic: t }\nbufer_size: 1"
+        )
+        .is_err()
+    );
+}
+
+#[test]
+fn deltas_and_unselected_events_are_not_encoded() {
+    let all = config("broker: { type: nats, url: 'nats://x' }");
+    assert!(all.encode(&event(AgentEventType::RunStarted {})).is_some());
+    assert!(
+        all.encode(&event(AgentEventType::TextMessageContent {
+            message_id: "m".to_string(),
+            step_id: "s".to_string(),
+            delta: "hi".to_string(),
+            stripped_content: None,
+        }))
+        .is_none()
+    );
+
+    let lifecycle = config("broker: { type: nats, url: 'nats://x' }\nevents: [run_finished]");
+    assert!(
+        lifecycle
+            .encode(&event(AgentEventType::RunStarted {}))
+            .is_none()
+    );
+}
+
+#[test]
+fn json_is_the_event_and_cloudevents_wrap_it() {
+    let started = event(AgentEventType::RunStarted {});
+    assert_eq!(event_type(&started), "run_started");
+
+    let json = config("broker: { type: nats, url: 'nats://x' }")
+        .encode(&started)
+        .unwrap();
+    assert_eq!(json.key, "thread-1");
+    assert_eq!(json.content_type, "application/json");
+    let body: Value = serde_json::from_slice(&json.payload).unwrap();
+    assert_eq!(body["event"]["type"], "run_started");
+    assert_eq!(body["agent_id"], "researcher");
+
+    let cloud = config("broker: { type: nats, url: 'nats://x' }\nformat: cloudevents")
+        .encode(&started)
+        .unwrap();
+    assert_eq!(cloud.content_type, "application/cloudevents+json");
+    let body: Value = serde_json::from_slice(&cloud.payload).unwrap();
+    assert_eq!(body["specversion"], "1.0");
+    assert_eq!(body["type"], "ai.distri.run_started");
+    assert_eq!(body["source"], "distri/agents/researcher");
+    assert_eq!(body["subject"], "task-1");
+    assert_eq!(body["data"]["run_id"], "run-1");
+}
@@ -0,0 +1,84 @@
+use serde_json::json;
+
+use crate::StandardDefinition;
+use crate::handoff::{HandoffCandidate, HandoffVerdict, handoff_brief};
+
+#[test]
+fn handoff_parses_from_an_agent_definition() {
+    let definition: StandardDefinition = toml::from_str(
+        r#"
+name = "support"
+
+[handoff]
+agents = ["research"]
+confirm = false
+
+[[handoff.intents]]
+agent = "billing"
+keywords = ["refund", "invoice"]
+"#,
+    )
+    .unwrap();
+
+    let handoff = definition.handoff.unwrap();
+    assert!(handoff.classify);
+    assert!(!handoff.confirm);
+    assert_eq!(handoff.candidates(), ["research", "billing"]);
+
+    let definition: StandardDefinition = toml::from_str("name = \"support\"\n[handoff]\n").unwrap();
+    let handoff = definition.handoff.unwrap();
+    assert!(handoff.classify && handoff.confirm);
+}
+
+#[test]
+fn intents_match_keywords_ignoring_case() {
+    let definition: StandardDefinition = toml::from_str(
+        r#"
+name = "support"
+
+[[handoff.intents]]
+agent = "billing"
+keywords = ["refund"]
+"#,
+    )
+    .unwrap();
+    let handoff = definition.handoff.unwrap();
+
+    let intent = handoff
+        .match_intent("I want a REFUND for order 12")
+        .unwrap();
+    assert_eq!(intent.agent, "billing");
+    assert!(handoff.match_intent("Where is my order?").is_none());
+}
+
+#[test]
+fn verdicts_are_read_from_json_or_fenced_text() {
+    let verdict = HandoffVerdict::from_value(&json!({ "agent": null })).unwrap();
+    assert_eq!(verdict.agent, None);
+
+    let text = "```json\n{\"agent\": \"research\", \"missing\": [\"search\"]}\n```";
+    let verdict = HandoffVerdict::from_value(&json!(text)).unwrap();
+    assert_eq!(verdict.agent.as_deref(), Some("research"));
+    assert_eq!(
+        verdict.describe("research"),
+        "research is better suited to this request (needs search)"
+    );
+    assert!(HandoffVerdict::from_value(&json!("no idea")).is_none());
+}
+
+#[test]
+fn the_brief_lists_the_tools_of_every_agent() {
+    let brief = handoff_brief(
+        "Find this week's news on Rust.",
+        "support",
+        &["final".to_string()],
+        &[HandoffCandidate {
+            name: "research".to_string(),
+            description: "Searches the web".to_string(),
+            tools: vec!["search".to_string()],
+        }],
+    );
+    assert!(brief.contains("<request>\nFind this week's news on Rust.\n</request>"));
+    assert!(brief.contains("<tools agent=\"support\">\nfinal\n</tools>"));
+    assert!(brief.contains("- research: Searches the web (tools: search)"));
+}
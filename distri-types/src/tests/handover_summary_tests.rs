@@ -0,0 +1,66 @@
+use serde_json::json;
+
+use crate::handover_summary::{GeneratedHandover, handover_transcript, thread_version, tools_used};
+use crate::{Message, Part, ToolCall};
+
+fn agent_calling(tools: &[&str]) -> Message {
+    let mut message = Message::assistant("Let me check.".to_string(), None);
+    for tool in tools {
+        message.parts.push(Part::ToolCall(ToolCall {
+            tool_call_id: format!("call-{tool}"),
+            tool_name: tool.to_string(),
+            input: json!({ "order": 42 }),
+        }));
+    }
+    message
+}
+
+#[test]
+fn parses_a_fenced_reply() {
+    let raw = "```json\n{\"customer_intent\": \" Refund order 42 \", \"steps_taken\": [\"Looked up the order\", \" \"], \"current_blocker\": \"None\"}\n```";
+    assert_eq!(
+        GeneratedHandover::parse(raw),
+        Some(GeneratedHandover {
+            customer_intent: "Refund order 42".to_string(),
+            steps_taken: vec!["Looked up the order".to_string()],
+            current_blocker: None,
+        })
+    );
+    assert_eq!(
+        GeneratedHandover::parse("I could not summarize this."),
+        None
+    );
+}
+
+#[test]
+fn tools_are_listed_once_in_order_of_use() {
+    let messages = vec![
+        Message::user("Where is my refund?".to_string(), None),
+        agent_calling(&["lookup_order", "refund_status"]),
+        agent_calling(&["lookup_order", "final"]),
+    ];
+    assert_eq!(tools_used(&messages), ["lookup_order", "refund_status"]);
+}
+
+#[test]
+fn transcript_names_speakers_and_tool_calls() {
+    let messages = vec![
+        Message::system("You are support.".to_string(), None),
+        Message::user("Where is my refund?".to_string(), None),
+        agent_calling(&["lookup_order"]),
+    ];
+    assert_eq!(
+        handover_transcript(&messages, 100),
+        "Customer: Where is my refund?\nAgent: Let me check.\nAgent called lookup_order({\"order\":42})"
+    );
+    assert_eq!(handover_transcript(&messages[1..2], 5), "Customer: Where");
+}
+
+#[test]
+fn the_version_changes_with_every_new_message() {
+    let mut messages = vec![Message::user("Hi".to_string(), None)];
+    let first = thread_version(&messages);
+    messages.push(Message::assistant("Hello".to_string(), None));
+    assert_ne!(thread_version(&messages), first);
+    assert_eq!(thread_version(&messages), thread_version(&messages.clone()));
+}
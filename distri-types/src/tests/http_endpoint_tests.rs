@@ -0,0 +1,102 @@
+use serde_json::json;
+
+use crate::StandardDefinition;
+use crate::http_endpoint::HttpEndpointConfig;
+use crate::http_request::HttpMethod;
+
+fn endpoint(method: &str, url: &str) -> HttpEndpointConfig {
+    serde_json::from_value(json!({
+        "method": method,
+        "url": url,
+        "headers": { "Authorization": "Bearer $TOKEN" },
+        "parameters": {
+            "type": "object",
+            "required": ["id"],
+            "properties": {
+                "id": { "type": "string" },
+                "expand": { "type": "boolean" },
+                "note": { "type": "string" }
+            }
+        },
+        "response": { "type": "object", "required": ["status"] }
+    }))
+    .unwrap()
+}
+
+#[test]
+fn endpoints_parse_from_an_agent_definition() {
+    let definition: StandardDefinition = toml::from_str(
+        r#"
+name = "orders"
+
+[[tools.dynamic]]
+name = "get_order"
+type = "http_endpoint"
+
+[tools.dynamic.config]
+url = "https://api.example.com/orders/{id}"
+parameters = { type = "object", properties = { id = { type = "string" } } }
+"#,
+    )
+    .unwrap();
+    let factory = &definition.tools.unwrap().dynamic[0];
+    let config: HttpEndpointConfig = serde_json::from_value(factory.config.clone()).unwrap();
+    assert!(matches!(config.method, HttpMethod::GET));
+    assert_eq!(config.placeholders(), ["id"]);
+    assert!(config.validate().is_ok());
+}
+
+#[test]
+fn get_fills_the_path_and_sends_the_rest_as_query() {
+    let config = endpoint("GET", "https://api.example.com/orders/{id}?v=2");
+    let request = config
+        .build_request(&json!({ "id": "a/b $TOKEN", "expand": true }))
+        .unwrap();
+
+    assert_eq!(
+        request.url,
+        "https://api.example.com/orders/a%2Fb%20%24TOKEN?v=2&expand=true"
+    );
+    assert_eq!(request.headers["Authorization"], "Bearer $TOKEN");
+    assert!(request.body.is_none());
+}
+
+#[test]
+fn post_sends_the_rest_as_a_json_body() {
+    let config = endpoint("post", "$API_URL/orders/{id}/notes");
+    let request = config
+        .build_request(&json!({ "id": "42", "note": "late" }))
+        .unwrap();
+
+    assert_eq!(request.url, "$API_URL/orders/42/notes");
+    assert_eq!(request.body, Some(json!({ "note": "late" })));
+}
+
+#[test]
+fn input_and_response_are_checked_against_their_schemas() {
+    let config = endpoint("GET", "https://api.example.com/orders/{id}");
+    let err = config
+        .build_request(&json!({ "expand": "yes" }))
+        .unwrap_err();
+    assert!(err.starts_with("invalid input"), "{err}");
+
+    assert!(
+        config
+            .check_response(&json!({ "status": "shipped" }))
+            .is_ok()
+    );
+    let err = config
+        .check_response(&json!({ "state": "shipped" }))
+        .unwrap_err();
+    assert!(err.contains("response schema"), "{err}");
+}
+
+#[test]
+fn placeholders_must_be_declared_parameters() {
+    let mut config = endpoint("GET", "https://api.example.com/orders/{order_id}");
+    let err = config.validate().unwrap_err();
+    assert!(err.contains("{order_id}"), "{err}");
+
+    config.url = "api.example.com/orders/{id}".to_string();
+    assert!(config.validate().unwrap_err().contains("absolute"));
+}
@@ -0,0 +1,72 @@
+use crate::llm_metrics::{LlmCallTiming, LlmMetrics};
+
+fn call(model: &str, duration_ms: u64, ttft_ms: Option<u64>, output_tokens: u32) -> LlmCallTiming {
+    LlmCallTiming {
+        provider: "openai".to_string(),
+        model: model.to_string(),
+        success: true,
+        duration_ms,
+        ttft_ms,
+        output_tokens,
+    }
+}
+
+#[test]
+fn calls_are_summarized_per_model() {
+    let metrics = LlmMetrics::default();
+    for ms in 1..=100 {
+        metrics.record(call("gpt-4o", ms * 10, Some(ms), 50));
+    }
+    metrics.record(LlmCallTiming {
+        success: false,
+        ..call("gpt-4o", 5, None, 0)
+    });
+    metrics.record(call("gpt-4o-mini", 200, None, 0));
+
+    let stats = metrics.snapshot();
+    assert_eq!(stats.len(), 2);
+    let gpt = &stats[0];
+    assert_eq!(
+        (gpt.model.as_str(), gpt.calls, gpt.errors),
+        ("gpt-4o", 101, 1)
+    );
+    assert_eq!(gpt.output_tokens, 5000);
+    let duration = gpt.duration.as_ref().unwrap();
+    assert_eq!(
+        (duration.p50_ms, duration.p95_ms, duration.max_ms),
+        (510, 950, 1000)
+    );
+    assert_eq!(duration.avg_ms, 505.0);
+    let ttft = gpt.time_to_first_token.as_ref().unwrap();
+    assert_eq!((ttft.p50_ms, ttft.max_ms), (51, 100));
+
+    let mini = &stats[1];
+    assert!(mini.time_to_first_token.is_none());
+    assert!(mini.tokens_per_sec.is_none());
+}
+
+#[test]
+fn tokens_per_second_leave_out_the_wait_for_the_first_token() {
+    assert_eq!(
+        call("m", 3000, Some(1000), 100).tokens_per_sec(),
+        Some(50.0)
+    );
+    assert_eq!(call("m", 2000, None, 100).tokens_per_sec(), Some(50.0));
+    assert_eq!(call("m", 2000, None, 0).tokens_per_sec(), None);
+}
+
+#[test]
+fn metrics_render_for_prometheus() {
+    let metrics = LlmMetrics::default();
+    metrics.record(call("claude \"sonnet\"", 2000, Some(500), 30));
+
+    let text = metrics.prometheus();
+    let labels = r#"provider="openai",model="claude \"sonnet\"""#;
+    assert!(text.contains("# TYPE distri_llm_calls_total counter"));
+    assert!(text.contains(&format!("distri_llm_calls_total{{{labels}}} 1")));
+    assert!(text.contains(&format!(
+        "distri_llm_time_to_first_token_seconds{{{labels},quantile=\"0.95\"}} 0.5"
+    )));
+    assert!(text.contains(&format!("distri_llm_duration_seconds_sum{{{labels}}} 2")));
+    assert!(text.contains(&format!("distri_llm_tokens_per_second{{{labels}}} 20.000")));
+}
@@ -0,0 +1,97 @@
+use crate::McpClientTransport;
+use crate::mcp_servers::{McpServerConfig, McpServerTransport, McpStartMode};
+
+fn parse(raw: &str) -> Vec<McpServerConfig> {
+    serde_yaml::from_str(raw).unwrap()
+}
+
+#[test]
+fn stdio_and_remote_servers_parse_with_defaults() {
+    let servers = parse(
+        r#"
+- name: github
+  transport: stdio
+  command: npx
+  args: ["-y", "@modelcontextprotocol/server-github"]
+  env:
+    GITHUB_PERSONAL_ACCESS_TOKEN: "{{secret:GITHUB_TOKEN}}"
+  start: eager
+  health_check:
+    timeout_secs: 20
+    required: true
+- name: linear
+  transport: streamable_http
+  url: https://mcp.linear.app/mcp
+  auth:
+    provider: linear
+"#,
+    );
+
+    let github = &servers[0];
+    assert_eq!(github.transport, McpServerTransport::Stdio);
+    assert_eq!(github.start, McpStartMode::Eager);
+    assert!(github.health_check.enabled && github.health_check.required);
+    assert_eq!(github.health_check.timeout_secs, 20);
+    assert_eq!(github.client_transport(), None);
+    github.validate().unwrap();
+
+    let linear = &servers[1];
+    assert_eq!(linear.start, McpStartMode::Lazy);
+    assert!(linear.enabled);
+    assert_eq!(linear.health_check.timeout_secs, 10);
+    assert_eq!(linear.auth.as_ref().unwrap().provider, "linear");
+    assert_eq!(
+        linear.client_transport(),
+        Some(McpClientTransport::StreamableHttp {
+            url: "https://mcp.linear.app/mcp".to_string(),
+            headers: None,
+        })
+    );
+    linear.validate().unwrap();
+}
+
+#[test]
+fn fields_of_the_other_transport_are_rejected() {
+    let servers = parse(
+        r#"
+- name: fs
+  transport: stdio
+  command: mcp-fs
+  url: http://localhost:9000
+- name: docs
+  transport: sse
+  url: https://docs.example.com/sse
+  command: docs-mcp
+- name: no-url
+  transport: streamable_http
+- name: crm
+  transport: streamable_http
+  url: https://crm.example.com/mcp
+  auth: { provider: crm }
+  start: eager
+"#,
+    );
+
+    let errors: Vec<String> = servers.iter().map(|s| s.validate().unwrap_err()).collect();
+    assert!(
+        errors[0].contains("only apply to remote servers"),
+        "{}",
+        errors[0]
+    );
+    assert!(
+        errors[1].contains("only apply to stdio servers"),
+        "{}",
+        errors[1]
+    );
+    assert!(errors[2].contains("needs a `url`"), "{}", errors[2]);
+    assert!(errors[3].contains("cannot start eagerly"), "{}", errors[3]);
+}
+
+#[test]
+fn unknown_keys_are_rejected() {
+    let err = serde_yaml::from_str::<Vec<McpServerConfig>>(
+        "- name: fs\n  transport: stdio\n  command: mcp-fs\n  lazy: true\n",
+    )
+    .unwrap_err();
+    assert!(err.to_string().contains("lazy"), "{err}");
+}
@@ -0,0 +1,102 @@
+use crate::memory_anonymization::{
+    EntityDetector, EntityKind, EntityPattern, MemoryAnonymizationConfig, pseudonym, pseudonyms,
+    reidentify,
+};
+
+fn detector(config: MemoryAnonymizationConfig) -> EntityDetector {
+    EntityDetector::new(&config).unwrap()
+}
+
+/// `text` with every identifier replaced by `[<LABEL>_000000000000]`.
+fn masked(detector: &EntityDetector, text: &str) -> String {
+    detector
+        .pseudonymize(text, |entity| pseudonym(&entity.label, "000000000000"))
+        .0
+}
+
+#[test]
+fn identifiers_are_replaced_with_pseudonyms() {
+    let detector = detector(MemoryAnonymizationConfig::default());
+    let text = "My name is Ana Silva, reach me at ana.silva@example.com or +1 415 555 0132. \
+                Card 4111 1111 1111 1111, IBAN DE89 3704 0044 0532 0130 00, \
+                SSN 123-45-6789, server 10.0.0.12. Met Dr. Okafor on 2026-10-17.";
+
+    let (out, replaced) =
+        detector.pseudonymize(text, |entity| pseudonym(&entity.label, "000000000000"));
+
+    assert_eq!(
+        out,
+        "My name is [PERSON_000000000000], reach me at [EMAIL_000000000000] or \
+         [PHONE_000000000000]. Card [CARD_000000000000], IBAN [IBAN_000000000000], \
+         SSN [SSN_000000000000], server [IP_000000000000]. Met Dr. [PERSON_000000000000] \
+         on 2026-10-17."
+    );
+    let values: Vec<&str> = replaced.iter().map(|(_, e)| e.text.as_str()).collect();
+    assert_eq!(values[0], "Ana Silva");
+    assert_eq!(values[2], "+1 415 555 0132");
+}
+
+#[test]
+fn numbers_failing_their_checksum_are_kept() {
+    let detector = detector(MemoryAnonymizationConfig::default());
+    let text = "Order 4111 1111 1111 1112 ships from DE00 3704 0044 0532 0130 00.";
+    assert_eq!(masked(&detector, text), text);
+}
+
+#[test]
+fn entities_and_patterns_select_what_is_replaced() {
+    let detector = detector(MemoryAnonymizationConfig {
+        entities: vec![EntityKind::Email],
+        patterns: vec![EntityPattern {
+            pattern: "CUST-[0-9]{6}".to_string(),
+            label: "customer id".to_string(),
+        }],
+        ..Default::default()
+    });
+
+    assert_eq!(
+        masked(&detector, "CUST-004211 (bob@example.com, +1 415 555 0132)"),
+        "[CUSTOMER_ID_000000000000] ([EMAIL_000000000000], +1 415 555 0132)"
+    );
+
+    let invalid = EntityDetector::new(&MemoryAnonymizationConfig {
+        patterns: vec![EntityPattern {
+            pattern: "x".to_string(),
+            label: "!".to_string(),
+        }],
+        ..Default::default()
+    });
+    assert!(invalid.is_err());
+}
+
+#[test]
+fn pseudonyms_are_not_detected_again_and_can_be_reversed() {
+    let detector = detector(MemoryAnonymizationConfig::default());
+    let stored = "[EMAIL_3f9a2c1b07de] wrote to [PHONE_0123456789ab] and [PERSON_aaaaaaaaaaaa]";
+    assert_eq!(masked(&detector, stored), stored);
+    assert_eq!(pseudonyms(stored).len(), 3);
+
+    let restored = reidentify(stored, |token| match token {
+        "[EMAIL_3f9a2c1b07de]" => Some("ana@example.com".to_string()),
+        "[PHONE_0123456789ab]" => Some("+1 415 555 0132".to_string()),
+        _ => None,
+    });
+    assert_eq!(
+        restored,
+        "ana@example.com wrote to +1 415 555 0132 and [PERSON_aaaaaaaaaaaa]"
+    );
+}
+
+#[test]
+fn formatting_does_not_change_the_normalized_identifier() {
+    let detector = detector(MemoryAnonymizationConfig::default());
+    let normalized = |text: &str| detector.detect(text)[0].normalized();
+    assert_eq!(
+        normalized("+1 (415) 555-0132"),
+        normalized("+1 415 555 0132")
+    );
+    assert_eq!(
+        normalized("Ana.Silva@Example.com"),
+        normalized("ana.silva@example.com")
+    );
+}
@@ -0,0 +1,83 @@
+use crate::ReasoningEffort;
+use crate::configuration::{MessageModelOverrides, MessageOverridePolicy, TemperatureRange};
+
+fn policy() -> MessageOverridePolicy {
+    MessageOverridePolicy {
+        models: vec!["openai/gpt-4.1-mini".to_string(), "gpt-5".to_string()],
+        temperature: Some(TemperatureRange { min: 0.0, max: 1.0 }),
+        reasoning_efforts: vec![ReasoningEffort::Low, ReasoningEffort::High],
+    }
+}
+
+#[test]
+fn overrides_within_the_policy_pass() {
+    let requested = MessageModelOverrides {
+        model: Some("gpt-4.1-mini".to_string()),
+        temperature: Some(1.0),
+        reasoning_effort: Some(ReasoningEffort::High),
+    };
+    assert_eq!(policy().check(&requested), Ok(()));
+
+    let requested = MessageModelOverrides {
+        model: Some("openai/gpt-4.1-mini".to_string()),
+        ..Default::default()
+    };
+    assert_eq!(policy().check(&requested), Ok(()));
+}
+
+#[test]
+fn overrides_outside_the_policy_are_named() {
+    let model = MessageModelOverrides {
+        model: Some("gpt-4o".to_string()),
+        ..Default::default()
+    };
+    assert_eq!(
+        policy().check(&model),
+        Err("model 'gpt-4o' is not allowed".to_string())
+    );
+
+    let temperature = MessageModelOverrides {
+        temperature: Some(1.5),
+        ..Default::default()
+    };
+    assert!(policy().check(&temperature).unwrap_err().contains("1.5"));
+
+    let effort = MessageModelOverrides {
+        reasoning_effort: Some(ReasoningEffort::Medium),
+        ..Default::default()
+    };
+    assert!(policy().check(&effort).unwrap_err().contains("medium"));
+
+    // Nothing is overridable by default.
+    assert!(
+        MessageOverridePolicy::default()
+            .check(&temperature)
+            .is_err()
+    );
+}
+
+#[test]
+fn overrides_are_read_from_the_send_configuration() {
+    let configuration: distri_a2a::MessageSendConfiguration =
+        serde_json::from_value(serde_json::json!({
+            "acceptedOutputModes": [],
+            "temperature": 0.2,
+            "reasoningEffort": "LOW",
+        }))
+        .unwrap();
+    let requested = MessageModelOverrides::from_send_configuration(&configuration).unwrap();
+    assert_eq!(
+        requested,
+        MessageModelOverrides {
+            model: None,
+            temperature: Some(0.2),
+            reasoning_effort: Some(ReasoningEffort::Low),
+        }
+    );
+
+    let configuration = distri_a2a::MessageSendConfiguration {
+        reasoning_effort: Some("extreme".to_string()),
+        ..Default::default()
+    };
+    assert!(MessageModelOverrides::from_send_configuration(&configuration).is_err());
+}
@@ -1,7 +1,52 @@
+mod agent_frontmatter_tests;
+mod agent_registry_tests;
+mod analytics_tests;
+mod browser_policy_tests;
+mod capability_probe_tests;
 mod context_budget_tests;
+mod critique_tests;
+mod dry_run_tests;
+mod egress_tests;
+mod datetime_tests;
+mod embeddings_tests;
+mod ensemble_tests;
+mod conversation_import_tests;
+mod eval_tests;
+mod event_export_tests;
 mod event_tests;
+mod handoff_tests;
+mod handover_summary_tests;
+mod http_endpoint_tests;
+mod injection_guard_tests;
+mod llm_metrics_tests;
+mod mcp_servers_tests;
+mod memory_anonymization_tests;
+mod message_override_tests;
+mod output_sinks_tests;
+mod packages_tests;
 mod part_file_tests;
+mod plugin_capability_tests;
+mod plugin_storage_tests;
+mod plugin_trace_tests;
 mod prompt_cache_tests;
+mod prompt_locale_tests;
+mod renderer_manifest_tests;
+mod secret_ref_tests;
 mod skill_metadata_tests;
+mod stream_control_tests;
+mod structured_stream_tests;
+mod task_diff_tests;
+mod task_retry_tests;
+mod thread_archive_tests;
+mod thread_variables_tests;
+mod todo_queue_tests;
+mod tool_catalog_tests;
 mod tool_delivery_tests;
+mod tool_output_tests;
+mod tool_recovery_tests;
+mod tool_redaction_tests;
 mod tool_result_storage_tests;
+mod tool_timeouts_tests;
+mod user_profile_tests;
+mod user_quotas_tests;
+mod workspace_config_tests;
@@ -0,0 +1,87 @@
+use serde_json::json;
+
+use crate::StandardDefinition;
+use crate::output_sinks::{OutputPayload, OutputSinkKind};
+
+fn payload() -> OutputPayload {
+    OutputPayload {
+        agent: "reporter".to_string(),
+        thread_id: "t1".to_string(),
+        task_id: "k1".to_string(),
+        content: "Sales are up {{agent}}".to_string(),
+    }
+}
+
+#[test]
+fn sinks_parse_from_an_agent_definition() {
+    let definition: StandardDefinition = toml::from_str(
+        r#"
+name = "reporter"
+
+[[output_sinks]]
+type = "webhook"
+url = "https://hooks.example.com/runs"
+headers = { Authorization = "Bearer {{secret:HOOK_TOKEN}}" }
+
+[[output_sinks]]
+name = "log"
+type = "artifact"
+filename = "reports.md"
+
+[[output_sinks]]
+type = "email"
+to = ["team@example.com"]
+
+[[output_sinks]]
+type = "document"
+provider = "notion"
+url = "https://api.notion.com/v1/pages"
+"#,
+    )
+    .unwrap();
+
+    let labels: Vec<String> = definition.output_sinks.iter().map(|s| s.label()).collect();
+    assert_eq!(labels, ["webhook", "log", "email", "document"]);
+    match &definition.output_sinks[2].kind {
+        OutputSinkKind::Email {
+            to,
+            subject,
+            provider,
+        } => {
+            assert_eq!(to, &["team@example.com"]);
+            assert_eq!(subject, "Result from {{agent}}");
+            assert_eq!(provider, "gmail");
+        }
+        other => panic!("unexpected {other:?}"),
+    }
+    assert_eq!(
+        definition.output_sinks[3].kind,
+        OutputSinkKind::Document {
+            provider: "notion".to_string(),
+            url: "https://api.notion.com/v1/pages".to_string(),
+            body: None,
+        }
+    );
+}
+
+#[test]
+fn templates_render_without_expanding_the_answer() {
+    let payload = payload();
+    assert_eq!(
+        payload.render("{{agent}} on {{thread_id}}/{{task_id}}"),
+        "reporter on t1/k1"
+    );
+    let body = payload.render_value(&json!({
+        "title": "{{agent}} report",
+        "blocks": [{ "text": "{{content}}" }],
+        "archived": false,
+    }));
+    assert_eq!(
+        body,
+        json!({
+            "title": "reporter report",
+            "blocks": [{ "text": "Sales are up {{agent}}" }],
+            "archived": false,
+        })
+    );
+}
@@ -0,0 +1,101 @@
+use crate::StandardDefinition;
+use crate::packages::{PackageInfo, PackageManifest, WorkspaceBuild, namespace_agents};
+
+fn package(name: &str, version: &str) -> PackageInfo {
+    PackageInfo {
+        name: name.to_string(),
+        version: version.to_string(),
+        description: None,
+    }
+}
+
+fn agent(name: &str, sub_agents: &[&str]) -> StandardDefinition {
+    StandardDefinition {
+        name: name.to_string(),
+        sub_agents: sub_agents.iter().map(|s| s.to_string()).collect(),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn manifests_parse_as_workspace_or_package() {
+    let root =
+        PackageManifest::parse("[workspace]\nmembers = [\"support\", \"teams/*\"]\n").unwrap();
+    assert!(root.is_package_manifest());
+    assert_eq!(root.workspace.unwrap().members, vec!["support", "teams/*"]);
+
+    let member = PackageManifest::parse(
+        "[package]\nname = \"support\"\nversion = \"0.3.0\"\ndescription = \"Support agents\"\n",
+    )
+    .unwrap();
+    let info = member.package.unwrap();
+    assert_eq!(info.name, "support");
+    info.validate().unwrap();
+
+    // A legacy server config is neither.
+    let legacy = PackageManifest::parse("name = \"my-workspace\"\nversion = \"0.1.0\"\n").unwrap();
+    assert!(!legacy.is_package_manifest());
+
+    assert!(
+        PackageManifest::parse(
+            "[package]\nname = \"support\"\nversion = \"0.3.0\"\nauthor = \"x\"\n"
+        )
+        .is_err()
+    );
+}
+
+#[test]
+fn package_names_and_versions_are_validated() {
+    package("support", "1.2.3").validate().unwrap();
+    package("support", "1.2.3-beta.1+build.5")
+        .validate()
+        .unwrap();
+    assert!(package("support", "1.2").validate().is_err());
+    assert!(package("support", "v1.2.3").validate().is_err());
+    assert!(
+        package("team/support", "1.2.3")
+            .validate()
+            .unwrap_err()
+            .contains("cannot contain '/'")
+    );
+    assert!(package("", "1.2.3").validate().is_err());
+}
+
+#[test]
+fn namespacing_prefixes_names_versions_and_sibling_sub_agents() {
+    let mut agents = vec![
+        agent("triage", &["escalation", "planner"]),
+        StandardDefinition {
+            version: Some("2.0.0".to_string()),
+            ..agent("escalation", &[])
+        },
+    ];
+    namespace_agents(&package("support", "0.3.0"), &mut agents).unwrap();
+    assert_eq!(agents[0].name, "support/triage");
+    assert_eq!(agents[0].version.as_deref(), Some("0.3.0"));
+    assert_eq!(agents[0].sub_agents, vec!["support/escalation", "planner"]);
+    assert_eq!(agents[1].name, "support/escalation");
+    assert_eq!(agents[1].version.as_deref(), Some("2.0.0"));
+
+    let mut namespaced = vec![agent("other/triage", &[])];
+    let err = namespace_agents(&package("support", "0.3.0"), &mut namespaced).unwrap_err();
+    assert!(err.to_string().contains("already namespaced"), "{err}");
+}
+
+#[test]
+fn workspace_build_lists_root_agents_first() {
+    let build: WorkspaceBuild = serde_json::from_value(serde_json::json!({
+        "status": "built",
+        "agents": ["planner"],
+        "packages": [{
+            "package": { "name": "support", "version": "0.3.0" },
+            "path": "support",
+            "agents": ["support/triage"]
+        }]
+    }))
+    .unwrap();
+    assert_eq!(
+        build.agent_names().collect::<Vec<_>>(),
+        vec!["planner", "support/triage"]
+    );
+}
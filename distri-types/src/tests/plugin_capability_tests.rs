@@ -0,0 +1,100 @@
+use std::path::Path;
+
+use crate::plugin_capabilities::Capability;
+use crate::{CapabilityGuard, FilesystemGrant, PluginCapabilities};
+
+fn guard() -> CapabilityGuard {
+    CapabilityGuard::new(
+        "acme",
+        PluginCapabilities {
+            network: vec!["*.acme.com".to_string(), "status.example.org".to_string()],
+            filesystem: vec![
+                FilesystemGrant {
+                    path: "/srv/acme/cache".to_string(),
+                    write: true,
+                },
+                FilesystemGrant {
+                    path: "/etc/acme".to_string(),
+                    write: false,
+                },
+            ],
+            env: vec!["ACME_REGION".to_string()],
+            secrets: vec!["acme".to_string()],
+        },
+    )
+}
+
+#[test]
+fn network_access_matches_declared_hosts() {
+    let guard = guard();
+    assert!(guard.check_network("https://api.acme.com/v1").is_ok());
+    assert!(guard.check_network("https://acme.com").is_ok());
+    assert!(guard.check_network("status.example.org").is_ok());
+    assert!(guard.check_network("https://acme.com.evil.net").is_err());
+    assert!(guard.check_network("https://example.org").is_err());
+
+    let denied = guard.check_network("https://evil.net/x").unwrap_err();
+    assert_eq!(denied.capability, Capability::Network);
+    assert_eq!(
+        denied.to_json(),
+        serde_json::json!({
+            "error": "capability_denied",
+            "plugin": "acme",
+            "capability": "network",
+            "target": "https://evil.net/x",
+            "message": "plugin 'acme' has no network capability for 'https://evil.net/x'",
+        })
+    );
+}
+
+#[test]
+fn filesystem_access_respects_paths_and_write_flag() {
+    let guard = guard();
+    assert!(
+        guard
+            .check_path(Path::new("/srv/acme/cache/a.json"), true)
+            .is_ok()
+    );
+    assert!(
+        guard
+            .check_path(Path::new("/etc/acme/config.toml"), false)
+            .is_ok()
+    );
+
+    let denied = guard
+        .check_path(Path::new("/etc/acme/config.toml"), true)
+        .unwrap_err();
+    assert_eq!(denied.capability, Capability::FilesystemWrite);
+    // `..` cannot leave a granted directory.
+    assert!(
+        guard
+            .check_path(Path::new("/srv/acme/cache/../../../etc/passwd"), false)
+            .is_err()
+    );
+    // A sibling sharing the prefix is not below the grant.
+    assert!(
+        guard
+            .check_path(Path::new("/etc/acme-other/key"), false)
+            .is_err()
+    );
+}
+
+#[test]
+fn env_and_secrets_must_be_declared() {
+    let guard = guard();
+    assert!(guard.check_env("ACME_REGION").is_ok());
+    assert!(guard.check_env("AWS_SECRET_ACCESS_KEY").is_err());
+    assert!(guard.check_secret("ACME").is_ok());
+    assert!(guard.check_secret("openai").is_err());
+
+    let nothing = CapabilityGuard::new("bare", PluginCapabilities::default());
+    assert!(nothing.check_network("https://api.acme.com").is_err());
+    assert!(nothing.check_path(Path::new("/tmp/x"), false).is_err());
+}
+
+#[test]
+fn manifest_rejects_unknown_fields() {
+    let manifest: Result<PluginCapabilities, _> =
+        serde_json::from_value(serde_json::json!({ "network": ["*"], "processes": true }));
+    assert!(manifest.is_err());
+}
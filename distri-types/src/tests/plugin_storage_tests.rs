@@ -0,0 +1,57 @@
+use crate::plugin_storage::{PluginStorageConfig, PluginStorageError, PluginStorageUsage};
+
+fn quota() -> PluginStorageConfig {
+    PluginStorageConfig {
+        max_value_bytes: 10,
+        max_keys: 2,
+        max_total_bytes: 30,
+    }
+}
+
+fn usage(keys: usize, bytes: usize) -> PluginStorageUsage {
+    PluginStorageUsage { keys, bytes }
+}
+
+#[test]
+fn writes_within_the_quota_are_allowed() {
+    assert!(quota().check("acme", 10, usage(2, 30)).is_ok());
+}
+
+#[test]
+fn each_limit_is_enforced() {
+    let reason = |result: Result<(), PluginStorageError>| match result {
+        Err(PluginStorageError::QuotaExceeded { plugin, reason }) => {
+            assert_eq!(plugin, "acme");
+            reason
+        }
+        other => panic!("expected a quota error, got {other:?}"),
+    };
+    assert_eq!(
+        reason(quota().check("acme", 11, usage(1, 11))),
+        "value of 11 bytes is over the 10 byte limit"
+    );
+    assert_eq!(
+        reason(quota().check("acme", 1, usage(3, 3))),
+        "more than 2 keys"
+    );
+    assert_eq!(
+        reason(quota().check("acme", 1, usage(1, 31))),
+        "more than 30 bytes stored"
+    );
+}
+
+#[test]
+fn store_errors_keep_their_kind() {
+    let quota_error = anyhow::Error::new(PluginStorageError::QuotaExceeded {
+        plugin: "acme".to_string(),
+        reason: "more than 2 keys".to_string(),
+    });
+    assert!(matches!(
+        PluginStorageError::from(quota_error),
+        PluginStorageError::QuotaExceeded { .. }
+    ));
+    assert!(matches!(
+        PluginStorageError::from(anyhow::anyhow!("database is locked")),
+        PluginStorageError::Store(_)
+    ));
+}
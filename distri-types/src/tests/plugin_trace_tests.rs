@@ -0,0 +1,65 @@
+use crate::events::AgentEventType;
+use crate::{PluginSpanRecorder, TraceContext};
+
+#[test]
+fn traceparent_is_w3c_formatted() {
+    let trace = TraceContext {
+        trace_id: "4bf92f3577b34da6a3ce929d0e0e4736".to_string(),
+        parent_span_id: "00f067aa0ba902b7".to_string(),
+    };
+    assert_eq!(
+        trace.traceparent(),
+        "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01"
+    );
+}
+
+#[test]
+fn dropped_spans_are_recorded_parents_first() {
+    let recorder = PluginSpanRecorder::default();
+    let outer = recorder.start("outer", Some("00f067aa0ba902b7".to_string()));
+    {
+        let mut inner = outer.child("inner");
+        inner.set_attribute("rows", 3);
+        // Dropped without `end`, e.g. on an early `?` return.
+    }
+    outer.fail("timeout");
+
+    let spans = recorder.take();
+    assert_eq!(spans.len(), 2);
+    assert_eq!(spans[0].name, "outer");
+    assert_eq!(spans[0].parent_span_id.as_deref(), Some("00f067aa0ba902b7"));
+    assert_eq!(spans[0].error.as_deref(), Some("timeout"));
+    assert_eq!(spans[0].span_id.len(), 16);
+    assert_eq!(
+        spans[1].parent_span_id.as_deref(),
+        Some(spans[0].span_id.as_str())
+    );
+    assert_eq!(spans[1].error, None);
+    assert_eq!(spans[1].attributes["rows"], 3);
+    assert!(recorder.take().is_empty());
+}
+
+#[test]
+fn plugin_spans_event_round_trips() {
+    let recorder = PluginSpanRecorder::default();
+    recorder.start("fetch", None).end();
+    let event = AgentEventType::PluginSpans {
+        step_id: "s1".to_string(),
+        tool_call_id: "call_1".to_string(),
+        tool_call_name: "crm_query".to_string(),
+        plugin: Some("crm".to_string()),
+        spans: recorder.take(),
+    };
+
+    let json = serde_json::to_value(&event).unwrap();
+    assert_eq!(json["type"], "plugin_spans");
+    assert!(json["spans"][0].get("error").is_none());
+    let decoded: AgentEventType = serde_json::from_value(json).unwrap();
+    match decoded {
+        AgentEventType::PluginSpans { plugin, spans, .. } => {
+            assert_eq!(plugin.as_deref(), Some("crm"));
+            assert_eq!(spans[0].name, "fetch");
+        }
+        other => panic!("unexpected event: {:?}", other),
+    }
+}
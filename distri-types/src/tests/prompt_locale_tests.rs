@@ -0,0 +1,134 @@
+use serde_json::json;
+
+use crate::prompt::PromptRegistry;
+use crate::prompt_locale::{
+    TemplateCoverage, best_variant, detect_locale, locale_candidates, normalize_locale,
+    profile_locale, translation_coverage,
+};
+
+#[test]
+fn locales_are_normalized_and_widened() {
+    assert_eq!(normalize_locale("de_at").as_deref(), Some("de-AT"));
+    assert_eq!(normalize_locale("ZH-hant").as_deref(), Some("zh-Hant"));
+    assert_eq!(normalize_locale("german"), None);
+    assert_eq!(normalize_locale(""), None);
+    assert_eq!(
+        locale_candidates("zh-Hant-TW"),
+        ["zh-Hant-TW", "zh-Hant", "zh"]
+    );
+    assert_eq!(
+        profile_locale(&json!({ "name": "Sam", "language": "fr_CA" })).as_deref(),
+        Some("fr-CA")
+    );
+    assert_eq!(profile_locale(&json!("prefers German")), None);
+}
+
+#[test]
+fn the_most_specific_variant_wins() {
+    let keys = [
+        "planning",
+        "planning@de",
+        "planning@de-AT",
+        "planning@fr",
+        "user",
+    ];
+    let pick = |locale| best_variant(keys, "planning", locale);
+    assert_eq!(pick(Some("de-AT")), Some("planning@de-AT"));
+    assert_eq!(pick(Some("de-CH")), Some("planning@de"));
+    assert_eq!(pick(Some("es")), Some("planning"));
+    assert_eq!(pick(None), Some("planning"));
+    // A variant alone serves its locale but is no default.
+    assert_eq!(
+        best_variant(["intro@de"], "intro", Some("de")),
+        Some("intro@de")
+    );
+    assert_eq!(best_variant(["intro@de"], "intro", Some("fr")), None);
+    // `@` not followed by a locale is part of the name.
+    assert_eq!(
+        best_variant(["team@support"], "team@support", Some("de")),
+        Some("team@support")
+    );
+}
+
+#[test]
+fn detects_the_language_of_a_message() {
+    assert_eq!(
+        detect_locale("Kannst du mir bitte sagen, wie das Wetter in Berlin ist?"),
+        Some("de")
+    );
+    assert_eq!(
+        detect_locale("Pouvez-vous me dire quel temps il fait dans la ville pour le week-end ?"),
+        Some("fr")
+    );
+    assert_eq!(
+        detect_locale("What is the weather like in Berlin this weekend?"),
+        Some("en")
+    );
+    assert_eq!(detect_locale("東京の天気を教えてください"), Some("ja"));
+    assert_eq!(detect_locale("Какая погода в Москве?"), Some("ru"));
+    assert_eq!(detect_locale("Berlin weather"), None);
+}
+
+#[test]
+fn coverage_lists_missing_translations() {
+    let coverage = translation_coverage([
+        "planning",
+        "planning@de",
+        "planning@fr",
+        "user",
+        "user@de",
+        "intro@es",
+    ]);
+    assert_eq!(
+        coverage,
+        vec![
+            TemplateCoverage {
+                name: "intro".to_string(),
+                has_default: false,
+                locales: vec!["es".to_string()],
+                missing: vec!["de".to_string(), "fr".to_string()],
+            },
+            TemplateCoverage {
+                name: "planning".to_string(),
+                has_default: true,
+                locales: vec!["de".to_string(), "fr".to_string()],
+                missing: vec!["es".to_string()],
+            },
+            TemplateCoverage {
+                name: "user".to_string(),
+                has_default: true,
+                locales: vec!["de".to_string()],
+                missing: vec!["es".to_string(), "fr".to_string()],
+            },
+        ]
+    );
+}
+
+#[tokio::test]
+async fn registry_falls_back_to_the_default_template() {
+    let registry = PromptRegistry::new();
+    for (name, content) in [("greeting", "Hello"), ("greeting@de", "Hallo")] {
+        registry
+            .register_template_string(name.to_string(), content.to_string(), None, None)
+            .await
+            .unwrap();
+    }
+    let content = |template: Option<crate::prompt::PromptTemplate>| template.unwrap().content;
+    assert_eq!(
+        content(
+            registry
+                .get_localized_template("greeting", Some("de-DE"))
+                .await
+        ),
+        "Hallo"
+    );
+    assert_eq!(
+        content(
+            registry
+                .get_localized_template("greeting", Some("it"))
+                .await
+        ),
+        "Hello"
+    );
+    assert_eq!(registry.translation_coverage().await[0].locales, ["de"]);
+}
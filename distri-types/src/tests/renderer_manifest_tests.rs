@@ -0,0 +1,77 @@
+use serde_json::json;
+
+use crate::renderer_manifest::{CardKind, FieldFormat, ToolRenderer, ToolRendererManifest};
+
+fn orders_manifest() -> ToolRendererManifest {
+    serde_yaml::from_str(
+        r#"
+tool: crm_get_orders
+icon: shopping-cart
+title: "Orders of {{input.customer_id}}"
+card:
+  kind: table
+  items: /orders
+  fields:
+    - { label: Order, path: /id }
+    - { label: Total, path: /total, format: number }
+collapse:
+  collapsed: true
+"#,
+    )
+    .unwrap()
+}
+
+#[test]
+fn manifests_parse_with_defaults() {
+    let manifest = orders_manifest();
+    assert_eq!(manifest.card.kind, CardKind::Table);
+    assert_eq!(manifest.card.fields[1].format, FieldFormat::Number);
+    assert!(manifest.collapse.collapsed);
+    assert!(manifest.collapse.expand_on_error);
+    assert_eq!(manifest.validate(), Ok(()));
+
+    let bare: ToolRendererManifest = serde_json::from_value(json!({ "tool": "ping" })).unwrap();
+    assert_eq!(bare.card.kind, CardKind::Json);
+    assert!(!bare.collapse.collapsed);
+    assert_eq!(bare.validate(), Ok(()));
+}
+
+#[test]
+fn invalid_manifests_are_rejected() {
+    let mut no_items = orders_manifest();
+    no_items.card.items = None;
+    assert!(no_items.validate().unwrap_err().contains("needs items"));
+
+    let mut bad_path = orders_manifest();
+    bad_path.card.fields[0].path = "$.id".to_string();
+    assert!(
+        bad_path
+            .validate()
+            .unwrap_err()
+            .contains("not a JSON pointer")
+    );
+
+    let mut no_fields = orders_manifest();
+    no_fields.card.kind = CardKind::KeyValue;
+    no_fields.card.fields.clear();
+    assert!(no_fields.validate().unwrap_err().contains("needs fields"));
+
+    let unknown =
+        serde_json::from_value::<ToolRendererManifest>(json!({ "tool": "ping", "layout": "grid" }));
+    assert!(unknown.is_err());
+}
+
+#[test]
+fn served_renderers_carry_their_plugin() {
+    let renderer = ToolRenderer {
+        plugin: Some("crm".to_string()),
+        manifest: orders_manifest(),
+    };
+    let value = serde_json::to_value(&renderer).unwrap();
+    assert_eq!(value["plugin"], "crm");
+    assert_eq!(value["tool"], "crm_get_orders");
+    assert_eq!(value["card"]["items"], "/orders");
+
+    let back: ToolRenderer = serde_json::from_value(value).unwrap();
+    assert_eq!(back, renderer);
+}
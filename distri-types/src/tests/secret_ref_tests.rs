@@ -0,0 +1,71 @@
+use std::collections::HashMap;
+
+use crate::secret_ref::{
+    AGENT_SECRET_FIELDS, collect_secret_refs, collect_secret_refs_in, replace_secret_refs,
+    secret_ref_names, secret_refs_outside, substitute_secret_refs, substitute_secret_refs_in,
+};
+
+#[test]
+fn finds_secret_refs_and_ignores_template_variables() {
+    let s = "https://api.example.com/{{secret:API_TOKEN}}/{{ secret: region.key }}?q={{query}}";
+    assert_eq!(secret_ref_names(s), vec!["API_TOKEN", "region.key"]);
+    assert!(secret_ref_names("{{secret:}} {{secret:has space}} {{secret:X").is_empty());
+}
+
+#[test]
+fn replaces_known_refs_and_keeps_unknown_ones() {
+    let secrets = HashMap::from([("TOKEN".to_string(), "abc".to_string())]);
+    assert_eq!(
+        replace_secret_refs("Bearer {{secret:TOKEN}} {{secret:OTHER}}", &secrets),
+        "Bearer abc {{secret:OTHER}}"
+    );
+}
+
+#[test]
+fn collects_and_substitutes_nested_values() {
+    let mut config = serde_json::json!({
+        "model_settings": { "base_url": "https://x/{{secret:A}}" },
+        "headers": [{ "Authorization": "Bearer {{secret:B}}" }],
+        "max_iterations": 3,
+    });
+    let names: Vec<String> = collect_secret_refs(&config).into_iter().collect();
+    assert_eq!(names, vec!["A", "B"]);
+
+    let secrets = HashMap::from([
+        ("A".to_string(), "a1".to_string()),
+        ("B".to_string(), "b2".to_string()),
+    ]);
+    substitute_secret_refs(&mut config, &secrets);
+    assert_eq!(config["model_settings"]["base_url"], "https://x/a1");
+    assert_eq!(config["headers"][0]["Authorization"], "Bearer b2");
+    assert_eq!(config["max_iterations"], 3);
+}
+
+#[test]
+fn agent_refs_are_only_resolved_in_transport_and_tool_config() {
+    let mut config = serde_json::json!({
+        "instructions": "Use {{secret:A}} for the API.",
+        "model_settings": { "provider": { "api_key": "{{secret:A}}" } },
+        "tools": { "dynamic": [{ "config": { "headers": { "X-Key": "{{secret:B}}" } } }] },
+    });
+    assert_eq!(
+        secret_refs_outside(&config, AGENT_SECRET_FIELDS),
+        vec!["/instructions"]
+    );
+    let names: Vec<String> = collect_secret_refs_in(&config, AGENT_SECRET_FIELDS)
+        .into_iter()
+        .collect();
+    assert_eq!(names, vec!["A", "B"]);
+
+    let secrets = HashMap::from([
+        ("A".to_string(), "a1".to_string()),
+        ("B".to_string(), "b2".to_string()),
+    ]);
+    substitute_secret_refs_in(&mut config, AGENT_SECRET_FIELDS, &secrets);
+    assert_eq!(config["model_settings"]["provider"]["api_key"], "a1");
+    assert_eq!(
+        config["tools"]["dynamic"][0]["config"]["headers"]["X-Key"],
+        "b2"
+    );
+    assert_eq!(config["instructions"], "Use {{secret:A}} for the API.");
+}
@@ -0,0 +1,46 @@
+use serde_json::json;
+
+use crate::Part;
+use crate::stream_control::{StreamControl, StreamControlReply};
+
+#[test]
+fn approvals_complete_the_approval_request_call() {
+    let control: StreamControl = serde_json::from_value(json!({
+        "type": "approve",
+        "tool_call_id": "call_1",
+        "approved": false,
+    }))
+    .unwrap();
+    assert_eq!(control.name(), "approve");
+
+    let response = control.tool_response().unwrap();
+    assert_eq!(response.tool_call_id, "call_1");
+    assert_eq!(response.tool_name, "approval_request");
+    assert_eq!(
+        response.parts,
+        [Part::Data(
+            json!({ "approved": false, "reason": "Rejected by user" })
+        )]
+    );
+
+    let cancel: StreamControl = serde_json::from_value(json!({ "type": "cancel" })).unwrap();
+    assert!(matches!(cancel, StreamControl::Cancel { task_id: None }));
+    assert!(cancel.tool_response().is_none());
+}
+
+#[test]
+fn replies_are_told_apart_from_stream_frames() {
+    let reply = StreamControlReply {
+        control: "cancel".to_string(),
+        error: Some("the stream has no task to cancel yet".to_string()),
+    };
+    let text = serde_json::to_string(&reply).unwrap();
+    assert!(text.contains("\"type\":\"control_reply\""), "{text}");
+    assert_eq!(StreamControlReply::parse(&text), Some(reply));
+
+    let frame = json!({
+        "jsonrpc": "2.0",
+        "result": { "kind": "message", "parts": [{ "kind": "text", "text": "control_reply" }] },
+    });
+    assert_eq!(StreamControlReply::parse(&frame.to_string()), None);
+}
@@ -0,0 +1,110 @@
+use serde_json::json;
+
+use crate::structured_stream::StructuredStream;
+
+fn contact_stream() -> StructuredStream {
+    StructuredStream::from_response_format(&json!({
+        "type": "json_schema",
+        "json_schema": {
+            "name": "contact",
+            "schema": {
+                "type": "object",
+                "properties": {
+                    "name": { "type": "string" },
+                    "age": { "type": "integer", "minimum": 0 },
+                    "tags": { "type": "array", "items": { "type": "string" } },
+                    "address": { "type": "object" }
+                },
+                "required": ["name", "age"],
+                "additionalProperties": false
+            }
+        }
+    }))
+    .expect("json_schema format")
+}
+
+fn names(fields: &[crate::structured_stream::CompletedField]) -> Vec<&str> {
+    fields.iter().map(|f| f.name.as_str()).collect()
+}
+
+#[test]
+fn fields_complete_as_they_stream() {
+    let mut stream = contact_stream();
+    assert!(stream.push("{\"na").unwrap().is_empty());
+    assert!(
+        stream
+            .push("me\": \"Ada, \\\"the\\\" {first}\"")
+            .unwrap()
+            .is_empty()
+    );
+    let fields = stream.push(", \"tags\": [\"a,b\", \"]\"], \"addr").unwrap();
+    assert_eq!(names(&fields), ["name", "tags"]);
+    assert_eq!(fields[0].value, json!("Ada, \"the\" {first}"));
+    assert_eq!(fields[1].value, json!(["a,b", "]"]));
+
+    let fields = stream
+        .push("ess\": {\"city\": \"Paris\"}, \"age\": 36}\n")
+        .unwrap();
+    assert_eq!(names(&fields), ["address", "age"]);
+    assert_eq!(fields[1].value, json!(36));
+    assert_eq!(stream.finish().unwrap()["age"], json!(36));
+}
+
+#[test]
+fn divergence_is_reported_at_the_first_bad_character() {
+    let mut stream = contact_stream();
+    let error = stream
+        .push("{\"name\": \"Ada\", \"age\": \"for")
+        .unwrap_err();
+    assert!(error.contains("'age' should be integer"), "{}", error);
+
+    let mut stream = contact_stream();
+    let error = stream.push("{\"nickname\"").unwrap_err();
+    assert!(error.contains("unexpected field 'nickname'"), "{}", error);
+
+    let mut stream = contact_stream();
+    let error = stream.push("Sure! {\"name\"").unwrap_err();
+    assert!(error.contains("expected a JSON object"), "{}", error);
+
+    let mut stream = contact_stream();
+    let error = stream.push("{\"name\": \"Ada\", \"age\": -1,").unwrap_err();
+    assert!(error.starts_with("field 'age':"), "{}", error);
+
+    let mut stream = contact_stream();
+    let error = stream.push("{\"name\": \"Ada\"} and more").unwrap_err();
+    assert!(error.contains("after the end"), "{}", error);
+}
+
+#[test]
+fn fields_before_a_divergence_are_kept() {
+    let mut stream = contact_stream();
+    let (fields, error) = stream.feed("{\"name\": \"Ada\", \"age\": \"for");
+    assert_eq!(names(&fields), ["name"]);
+    assert!(error.unwrap().contains("'age' should be integer"));
+}
+
+#[test]
+fn finish_checks_the_whole_schema() {
+    let mut stream = contact_stream();
+    stream.push("{\"name\": \"Ada\"}").unwrap();
+    assert!(stream.finish().unwrap_err().contains("age"));
+
+    let mut stream = contact_stream();
+    stream.push("{\"name\": \"Ada\", ").unwrap();
+    assert!(stream.finish().unwrap_err().contains("ended before"));
+}
+
+#[test]
+fn only_schema_formats_are_validated() {
+    assert!(StructuredStream::from_response_format(&json!({ "type": "text" })).is_none());
+    assert!(StructuredStream::from_response_format(&json!({ "type": "json_object" })).is_none());
+
+    // A non-object root is only checked once complete.
+    let mut stream = StructuredStream::from_response_format(&json!({
+        "json_schema": { "schema": { "type": "array", "items": { "type": "integer" } } }
+    }))
+    .unwrap();
+    assert!(stream.push("[1, 2,").unwrap().is_empty());
+    stream.push(" 3]").unwrap();
+    assert_eq!(stream.finish().unwrap(), json!([1, 2, 3]));
+}
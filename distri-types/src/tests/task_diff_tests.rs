@@ -0,0 +1,174 @@
+use serde_json::{Value, json};
+
+use crate::task_diff::{StepChange, StepField, TaskComparison, TaskTrace};
+use crate::{
+    AgentEventType, Message, Part, RunUsage, Task, TaskEvent, TaskMessage, ToolCall, ToolResponse,
+};
+
+fn event(event: AgentEventType) -> TaskMessage {
+    TaskMessage::Event(TaskEvent {
+        event,
+        created_at: 0,
+        is_final: false,
+    })
+}
+
+fn usage(total_tokens: u32, cost_usd: f64) -> RunUsage {
+    RunUsage {
+        total_tokens,
+        input_tokens: total_tokens,
+        cost_usd: Some(cost_usd),
+        ..Default::default()
+    }
+}
+
+/// One step calling `tool` with `input` and getting `result` back.
+fn tool_step(tool: &str, input: Value, result: &str, tokens: u32) -> Vec<TaskMessage> {
+    let call = ToolCall {
+        tool_call_id: format!("call-{}", uuid::Uuid::new_v4()),
+        tool_name: tool.to_string(),
+        input,
+    };
+    vec![
+        event(AgentEventType::StepStarted {
+            step_id: "step".to_string(),
+            step_index: 0,
+        }),
+        TaskMessage::Message(Message::assistant(String::new(), None)),
+        event(AgentEventType::ToolCalls {
+            step_id: "step".to_string(),
+            parent_message_id: None,
+            tool_calls: vec![call.clone()],
+        }),
+        event(AgentEventType::ToolResults {
+            step_id: "step".to_string(),
+            parent_message_id: None,
+            results: vec![ToolResponse {
+                tool_call_id: call.tool_call_id,
+                tool_name: tool.to_string(),
+                parts: vec![Part::Text(result.to_string())],
+                parts_metadata: None,
+            }],
+        }),
+        event(AgentEventType::StepCompleted {
+            step_id: "step".to_string(),
+            success: true,
+            context_budget: None,
+            usage: Some(usage(tokens, tokens as f64 / 1000.0)),
+        }),
+    ]
+}
+
+fn trace(task_id: &str, steps: Vec<Vec<TaskMessage>>, answer: &str) -> TaskTrace {
+    let mut history = vec![TaskMessage::Message(Message::user(
+        "What's the weather in Paris?".to_string(),
+        None,
+    ))];
+    history.extend(steps.into_iter().flatten());
+    history.extend(tool_step("final", json!(answer), "", 10));
+    let total = 1000;
+    history.push(event(AgentEventType::RunFinished {
+        success: true,
+        total_steps: 0,
+        failed_steps: 0,
+        usage: Some(usage(total, 1.0)),
+        context_budget: None,
+    }));
+    let task = Task {
+        id: task_id.to_string(),
+        thread_id: "thread-1".to_string(),
+        ..Default::default()
+    };
+    TaskTrace::from_history(&task, "weather", &history)
+}
+
+#[test]
+fn trace_folds_history_into_steps() {
+    let trace = trace(
+        "task-1",
+        vec![tool_step("search", json!({ "q": "paris" }), "sunny", 100)],
+        "It is sunny.",
+    );
+    assert_eq!(trace.input.as_deref(), Some("What's the weather in Paris?"));
+    assert_eq!(trace.steps.len(), 2);
+    let search = &trace.steps[0];
+    assert_eq!(search.tool_calls[0].tool_name, "search");
+    assert_eq!(
+        search.tool_results[0].parts,
+        [Part::Text("sunny".to_string())]
+    );
+    assert_eq!(search.success, Some(true));
+    assert_eq!(search.usage.as_ref().unwrap().total_tokens, 100);
+    assert_eq!(trace.outcome.success, Some(true));
+    assert_eq!(trace.outcome.answer.as_deref(), Some("It is sunny."));
+    assert_eq!(trace.usage.total_tokens, 1000, "the run's own total");
+}
+
+#[test]
+fn steps_align_on_the_tools_they_call() {
+    let baseline = trace(
+        "original",
+        vec![
+            tool_step("search", json!({ "q": "paris" }), "sunny", 100),
+            tool_step("fetch", json!({ "url": "a" }), "page", 200),
+        ],
+        "It is sunny.",
+    );
+    let candidate = trace(
+        "replay",
+        vec![
+            tool_step("geocode", json!({ "city": "paris" }), "48.8,2.3", 50),
+            tool_step("search", json!({ "q": "paris weather" }), "sunny", 120),
+        ],
+        "It is sunny.",
+    );
+
+    let diff = TaskComparison::new(baseline, candidate);
+    let rows: Vec<_> = diff
+        .steps
+        .iter()
+        .map(|r| (r.change, r.baseline, r.candidate))
+        .collect();
+    assert_eq!(
+        rows,
+        [
+            (StepChange::Added, None, Some(0)),
+            (StepChange::Changed, Some(0), Some(1)),
+            (StepChange::Removed, Some(1), None),
+            (StepChange::Unchanged, Some(2), Some(2)),
+        ]
+    );
+    assert_eq!(diff.steps[1].fields, [StepField::ToolCalls]);
+    assert_eq!(diff.steps[1].usage.total_tokens, 20);
+    assert_eq!(diff.steps[2].usage.total_tokens, -200);
+    assert!(diff.same_outcome);
+    assert_eq!(diff.usage.total_tokens, 0);
+    assert_eq!(diff.usage.cost_usd, Some(0.0));
+}
+
+#[test]
+fn unmatched_steps_between_matches_pair_up_as_changed() {
+    let baseline = trace(
+        "a",
+        vec![tool_step("search", json!({ "q": "x" }), "1", 10)],
+        "first",
+    );
+    let candidate = trace(
+        "b",
+        vec![tool_step("browse", json!({ "url": "x" }), "1", 10)],
+        "second",
+    );
+
+    let diff = TaskComparison::new(baseline, candidate);
+    assert_eq!(diff.steps[0].change, StepChange::Changed);
+    assert_eq!(
+        diff.steps[0].fields,
+        [StepField::ToolCalls, StepField::ToolResults]
+    );
+    assert_eq!(
+        diff.steps[1].change,
+        StepChange::Changed,
+        "final answers differ"
+    );
+    assert!(!diff.same_outcome);
+}
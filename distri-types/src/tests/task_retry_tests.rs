@@ -0,0 +1,149 @@
+use serde_json::json;
+
+use crate::task_retry::FailureSummary;
+use crate::{AgentEventType, Message, Part, TaskEvent, TaskMessage, ToolCall, ToolResponse};
+
+fn event(event: AgentEventType) -> TaskMessage {
+    TaskMessage::Event(TaskEvent {
+        event,
+        created_at: 0,
+        is_final: false,
+    })
+}
+
+fn step(index: usize) -> TaskMessage {
+    event(AgentEventType::StepStarted {
+        step_id: format!("step-{index}"),
+        step_index: index,
+    })
+}
+
+/// A call of `tool` that ended with `result`.
+fn tool_call(id: &str, tool: &str, result: &str, success: bool) -> Vec<TaskMessage> {
+    vec![
+        event(AgentEventType::ToolCalls {
+            step_id: "step".to_string(),
+            parent_message_id: None,
+            tool_calls: vec![ToolCall {
+                tool_call_id: id.to_string(),
+                tool_name: tool.to_string(),
+                input: json!({ "url": "https://example.com" }),
+            }],
+        }),
+        event(AgentEventType::ToolExecutionEnd {
+            step_id: "step".to_string(),
+            tool_call_id: id.to_string(),
+            tool_call_name: tool.to_string(),
+            success,
+        }),
+        event(AgentEventType::ToolResults {
+            step_id: "step".to_string(),
+            parent_message_id: None,
+            results: vec![ToolResponse::from_parts(
+                id.to_string(),
+                tool.to_string(),
+                vec![Part::Text(result.to_string())],
+            )],
+        }),
+    ]
+}
+
+#[test]
+fn summary_keeps_the_error_and_the_failed_calls() {
+    let mut history = vec![
+        TaskMessage::Message(Message::user("Summarize example.com".to_string(), None)),
+        step(0),
+    ];
+    history.extend(tool_call(
+        "call_1",
+        "fetch",
+        "Error: connection refused",
+        false,
+    ));
+    history.push(step(1));
+    history.extend(tool_call("call_2", "search", "3 results", true));
+    history.push(event(AgentEventType::RunError {
+        message: "Step execution failed: provider overloaded".to_string(),
+        code: Some("EXECUTION_ERROR".to_string()),
+        usage: None,
+    }));
+
+    let summary = FailureSummary::from_history(&history);
+
+    assert_eq!(summary.steps, 2);
+    assert_eq!(summary.code.as_deref(), Some("EXECUTION_ERROR"));
+    assert_eq!(summary.failed_tool_calls.len(), 1);
+    let call = &summary.failed_tool_calls[0];
+    assert_eq!(call.tool_name, "fetch");
+    assert_eq!(call.input, json!({ "url": "https://example.com" }));
+    assert_eq!(call.error.as_deref(), Some("Error: connection refused"));
+
+    let context = summary.to_context();
+    assert!(
+        context.contains("Error (EXECUTION_ERROR): Step execution failed: provider overloaded")
+    );
+    assert!(context.contains("ran 2 steps"));
+    assert!(
+        context.contains(
+            "- `fetch` with {\"url\":\"https://example.com\"}: Error: connection refused"
+        )
+    );
+    assert!(!context.contains("search"));
+}
+
+#[test]
+fn the_closing_validation_error_does_not_hide_the_cause() {
+    let history = vec![
+        event(AgentEventType::RunError {
+            message: "Planning failed: LLM error: provider overloaded".to_string(),
+            code: Some("PLANNING_ERROR".to_string()),
+            usage: None,
+        }),
+        event(AgentEventType::RunError {
+            message: "Planning error: Agent execution completed with failures".to_string(),
+            code: Some("VALIDATION_ERROR".to_string()),
+            usage: None,
+        }),
+    ];
+
+    let summary = FailureSummary::from_history(&history);
+
+    assert_eq!(summary.code.as_deref(), Some("PLANNING_ERROR"));
+    assert!(summary.error.unwrap().contains("provider overloaded"));
+}
+
+#[test]
+fn a_recovered_call_reports_its_last_error() {
+    let mut history = tool_call("call_1", "fetch", "", false);
+    history.insert(
+        1,
+        event(AgentEventType::ToolRecovery {
+            step_id: "step".to_string(),
+            tool_call_id: "call_1".to_string(),
+            tool_call_name: "fetch".to_string(),
+            strategy: "retry".to_string(),
+            error: "timed out".to_string(),
+            attempt: 1,
+            recovered: false,
+            fallback_tool: None,
+        }),
+    );
+
+    let summary = FailureSummary::from_history(&history);
+
+    assert_eq!(summary.error, None);
+    assert_eq!(
+        summary.failed_tool_calls[0].error.as_deref(),
+        Some("timed out"),
+        "an empty result does not hide the recovery's error"
+    );
+}
+
+#[test]
+fn long_tool_errors_are_cut() {
+    let long = "x".repeat(2_000);
+    let summary = FailureSummary::from_history(&tool_call("call_1", "fetch", &long, false));
+    let error = summary.failed_tool_calls[0].error.as_deref().unwrap();
+    assert_eq!(error.chars().count(), 501);
+    assert!(error.ends_with('…'));
+}
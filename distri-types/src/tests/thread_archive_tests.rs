@@ -0,0 +1,85 @@
+use serde_json::json;
+
+use crate::core::{Task, TaskStatus, Thread};
+use crate::thread_archive::{
+    ArchivedTask, ArchivedTaskMessage, ThreadArchive, ThreadArchiveConfig,
+};
+
+fn archive() -> ThreadArchive {
+    let mut thread = Thread::new(
+        "assistant".to_string(),
+        Some("Quarterly report".to_string()),
+        Some("thread-1".to_string()),
+        None,
+        None,
+    );
+    thread.message_count = 2;
+    let task = ArchivedTask {
+        task: Task {
+            id: "task-1".to_string(),
+            thread_id: "thread-1".to_string(),
+            status: TaskStatus::Completed,
+            created_at: 1,
+            updated_at: 2,
+            reference_task_ids: vec!["task-0".to_string()],
+            ..Default::default()
+        },
+        remote: false,
+        inner_task_id: None,
+        ended_at: Some(2),
+        invocation: json!({ "agent": "assistant" }),
+    };
+    let message = ArchivedTaskMessage {
+        task_id: "task-1".to_string(),
+        kind: "message".to_string(),
+        payload: json!({ "role": "user", "parts": [] }),
+        created_at: 1,
+    };
+    ThreadArchive {
+        thread,
+        tasks: vec![task],
+        messages: vec![message],
+    }
+}
+
+#[test]
+fn archives_round_trip_through_jsonl() {
+    let jsonl = archive().to_jsonl().unwrap();
+    let lines: Vec<&str> = jsonl.lines().collect();
+    assert_eq!(lines.len(), 3);
+    assert!(lines[0].contains("\"type\":\"thread\""), "{}", lines[0]);
+    assert!(lines[1].contains("\"type\":\"task\""), "{}", lines[1]);
+    assert!(lines[2].contains("\"type\":\"message\""), "{}", lines[2]);
+
+    let restored = ThreadArchive::from_jsonl(&jsonl).unwrap();
+    assert_eq!(restored.thread.id, "thread-1");
+    assert_eq!(restored.thread.title, "Quarterly report");
+    assert_eq!(restored.thread.message_count, 2);
+    assert_eq!(restored.tasks.len(), 1);
+    let task = &restored.tasks[0];
+    assert_eq!(task.task.id, "task-1");
+    assert_eq!(task.task.status, TaskStatus::Completed);
+    assert_eq!(task.task.reference_task_ids, ["task-0"]);
+    assert_eq!(task.ended_at, Some(2));
+    assert_eq!(task.invocation, json!({ "agent": "assistant" }));
+    assert_eq!(restored.messages, archive().messages);
+}
+
+#[test]
+fn archives_need_a_thread_line() {
+    let jsonl = archive().to_jsonl().unwrap();
+    let without_thread = jsonl.lines().skip(1).collect::<Vec<_>>().join("\n");
+    let err = ThreadArchive::from_jsonl(&without_thread).unwrap_err();
+    assert!(err.to_string().contains("no thread line"), "{err}");
+    assert!(ThreadArchive::from_jsonl("{\"type\":\"unknown\"}").is_err());
+}
+
+#[test]
+fn config_defaults_apply_to_missing_fields() {
+    let config: ThreadArchiveConfig =
+        serde_json::from_value(json!({ "retention_days": 30 })).unwrap();
+    assert_eq!(config.retention_days, 30);
+    assert_eq!(config.sweep_interval_secs, 3600);
+    assert_eq!(config.batch_size, 50);
+    assert!(serde_json::from_value::<ThreadArchiveConfig>(json!({ "days": 30 })).is_err());
+}
@@ -0,0 +1,53 @@
+use std::collections::BTreeMap;
+
+use crate::core::{Thread, UpdateThreadRequest};
+use crate::prompt::PromptRegistry;
+
+fn variables() -> BTreeMap<String, String> {
+    BTreeMap::from([
+        ("customer_name".to_string(), "O'Brien & Co".to_string()),
+        ("environment".to_string(), "staging".to_string()),
+    ])
+}
+
+#[test]
+fn render_variables_replaces_known_names() {
+    let rendered = PromptRegistry::render_variables(
+        "Deploy {{environment}} for {{ customer_name }} ({{{customer_name}}})",
+        &variables(),
+    );
+    assert_eq!(rendered, "Deploy staging for O'Brien & Co (O'Brien & Co)");
+}
+
+#[test]
+fn render_variables_leaves_other_braces() {
+    let text = "{{#if repo}}{{repo}}{{/if}} {\"a\": {{unknown}}} {{ unclosed";
+    assert_eq!(PromptRegistry::render_variables(text, &variables()), text);
+    assert_eq!(
+        PromptRegistry::render_variables("{{ {{environment}}", &variables()),
+        "{{ staging"
+    );
+    assert_eq!(
+        PromptRegistry::render_variables("{{environment}}", &BTreeMap::new()),
+        "{{environment}}"
+    );
+}
+
+#[test]
+fn thread_variables_serde() {
+    let mut thread = Thread::new("agent".into(), None, Some("t-1".into()), None, None);
+    let json = serde_json::to_value(&thread).unwrap();
+    assert!(json.get("variables").is_none());
+
+    thread.variables = variables();
+    let json = serde_json::to_value(&thread).unwrap();
+    assert_eq!(json["variables"]["environment"], "staging");
+    let back: Thread = serde_json::from_value(json).unwrap();
+    assert_eq!(back.variables, variables());
+
+    let request: UpdateThreadRequest = serde_json::from_str(
+        r#"{"title": null, "metadata": null, "attributes": null, "user_id": null}"#,
+    )
+    .unwrap();
+    assert!(request.variables.is_none());
+}
@@ -0,0 +1,84 @@
+use crate::todos::{SimpleTodo, TodoItem, TodoList, TodoPriority, TodoStatus};
+
+fn todo(content: &str, priority: TodoPriority, depends_on: &[&str]) -> SimpleTodo {
+    SimpleTodo {
+        content: content.to_string(),
+        status: TodoStatus::Open,
+        priority,
+        depends_on: depends_on.iter().map(|d| d.to_string()).collect(),
+    }
+}
+
+fn mark_done(list: &mut TodoList, title: &str) {
+    let id = list
+        .items
+        .iter()
+        .find(|i| i.title == title)
+        .unwrap()
+        .id
+        .clone();
+    list.update(&id, None, None, Some(TodoStatus::Done));
+}
+
+#[test]
+fn next_ready_respects_dependencies_then_priority() {
+    let mut list = TodoList::new();
+    list.write_todos(vec![
+        todo("lint", TodoPriority::Low, &[]),
+        todo("deploy", TodoPriority::High, &["build"]),
+        todo("build", TodoPriority::Medium, &[]),
+        todo("test", TodoPriority::Medium, &[]),
+    ]);
+
+    let mut order = Vec::new();
+    while let Some(item) = list.next_ready() {
+        let title = item.title.clone();
+        mark_done(&mut list, &title);
+        order.push(title);
+    }
+
+    assert_eq!(order, vec!["build", "deploy", "test", "lint"]);
+}
+
+#[test]
+fn failed_dependency_leaves_dependents_blocked() {
+    let mut list = TodoList::new();
+    list.write_todos(vec![
+        todo("migrate", TodoPriority::Medium, &[]),
+        todo("backfill", TodoPriority::Medium, &["migrate"]),
+    ]);
+    let id = list.items[0].id.clone();
+    list.update(&id, None, None, Some(TodoStatus::Failed));
+
+    assert!(list.next_ready().is_none());
+    assert_eq!(list.get_by_status(TodoStatus::Open).len(), 1);
+}
+
+#[test]
+fn unknown_dependencies_are_reported() {
+    let mut list = TodoList::new();
+    list.write_todos(vec![todo("ship", TodoPriority::Medium, &["review"])]);
+
+    assert_eq!(
+        list.unknown_dependencies(),
+        vec![("ship".to_string(), "review".to_string())]
+    );
+}
+
+#[test]
+fn items_stored_before_priorities_still_load() {
+    let stored = serde_json::json!({
+        "id": "1",
+        "title": "old item",
+        "notes": null,
+        "status": "pending",
+        "created_at": "2026-01-01T00:00:00Z",
+        "updated_at": "2026-01-01T00:00:00Z"
+    });
+
+    let item: TodoItem = serde_json::from_value(stored).unwrap();
+
+    assert_eq!(item.status, TodoStatus::Open);
+    assert_eq!(item.priority, TodoPriority::Medium);
+    assert!(item.depends_on.is_empty());
+}
@@ -0,0 +1,81 @@
+use crate::StandardDefinition;
+use crate::tool_catalog::{
+    CollisionRule, ToolCandidate, ToolCatalog, ToolCollision, ToolSourceKind,
+};
+
+#[test]
+fn collisions_and_aliases_parse_from_an_agent_definition() {
+    let definition: StandardDefinition = toml::from_str(
+        r#"
+name = "researcher"
+
+[tools.collisions]
+precedence = ["plugin", "builtin"]
+prefer = { search = "acme" }
+
+[tool_aliases]
+web_search = "builtin/search"
+"#,
+    )
+    .unwrap();
+
+    let collisions = definition.tools.unwrap().collisions;
+    assert_eq!(
+        collisions.precedence,
+        [ToolSourceKind::Plugin, ToolSourceKind::Builtin]
+    );
+    assert_eq!(collisions.prefer["search"], "acme");
+    assert!(!collisions.strict);
+    assert_eq!(definition.tool_aliases["web_search"], "builtin/search");
+}
+
+fn catalog() -> ToolCatalog {
+    let acme = ToolCandidate::new("search", "acme", "search", ToolSourceKind::Plugin);
+    let builtin = ToolCandidate::new("search", "builtin", "search", ToolSourceKind::Builtin);
+    ToolCatalog {
+        tools: vec![
+            ToolCandidate::new("final", "builtin", "final", ToolSourceKind::Builtin),
+            acme.clone(),
+            ToolCandidate {
+                name: "web_search".to_string(),
+                ..builtin.clone()
+            },
+        ],
+        aliases: [("web_search".to_string(), "builtin/search".to_string())].into(),
+        collisions: vec![ToolCollision {
+            name: "search".to_string(),
+            winner: acme,
+            shadowed: vec![builtin],
+            rule: CollisionRule::Preferred,
+        }],
+    }
+}
+
+#[test]
+fn resolve_names_aliases_and_qualified_names() {
+    let catalog = catalog();
+
+    let search = catalog.resolve("search");
+    assert_eq!(search.tool.unwrap().qualified_name, "acme/search");
+    assert_eq!(search.collision.unwrap().rule, CollisionRule::Preferred);
+
+    let alias = catalog.resolve("web_search");
+    assert_eq!(alias.alias_of.as_deref(), Some("builtin/search"));
+    assert_eq!(alias.tool.unwrap().source, ToolSourceKind::Builtin);
+    assert!(alias.collision.is_some());
+
+    // A shadowed tool is reachable by its qualified name only through an
+    // alias.
+    let shadowed = catalog.resolve("builtin/search");
+    assert_eq!(
+        shadowed.tool.map(|t| t.name),
+        Some("web_search".to_string())
+    );
+    assert_eq!(shadowed.alias_of.as_deref(), Some("builtin/search"));
+
+    let missing = catalog.resolve("globex/search");
+    assert!(missing.tool.is_none() && missing.collision.is_none());
+
+    let final_tool = catalog.resolve("final");
+    assert!(final_tool.collision.is_none());
+}
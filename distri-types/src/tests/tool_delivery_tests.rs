@@ -83,3 +83,68 @@ fn names_only_defers_everything_except_core() {
     assert!(config.is_core_tool("final"));
     assert!(!config.is_core_tool("browsr_scrape"));
 }
+
+#[derive(Debug)]
+struct LookupTool {
+    name: &'static str,
+    memoizable: bool,
+}
+
+#[async_trait::async_trait]
+impl crate::Tool for LookupTool {
+    fn get_name(&self) -> String {
+        self.name.to_string()
+    }
+
+    fn get_description(&self) -> String {
+        String::new()
+    }
+
+    fn get_parameters(&self) -> serde_json::Value {
+        serde_json::json!({})
+    }
+
+    fn memoizable(&self) -> bool {
+        self.memoizable
+    }
+
+    async fn execute(
+        &self,
+        _tool_call: crate::ToolCall,
+        _context: std::sync::Arc<crate::ToolContext>,
+    ) -> Result<Vec<crate::Part>, anyhow::Error> {
+        Ok(vec![])
+    }
+}
+
+#[test]
+fn memoize_follows_tool_opt_in_and_overrides() {
+    let search = LookupTool {
+        name: "search",
+        memoizable: true,
+    };
+    let mcp_fetch = LookupTool {
+        name: "mcp_fetch",
+        memoizable: false,
+    };
+
+    let config = ToolsConfig::default();
+    assert!(config.memoize.applies_to(&search));
+    assert!(!config.memoize.applies_to(&mcp_fetch));
+
+    let config: ToolsConfig =
+        serde_json::from_str(r#"{"memoize": {"include": ["mcp_fetch"], "exclude": ["search"]}}"#)
+            .unwrap();
+    assert!(config.memoize.enabled);
+    assert!(!config.memoize.applies_to(&search));
+    assert!(config.memoize.applies_to(&mcp_fetch));
+
+    let config: ToolsConfig = serde_json::from_str(r#"{"memoize": {"enabled": false}}"#).unwrap();
+    assert!(!config.memoize.applies_to(&search));
+}
+
+#[test]
+fn default_memoize_config_is_not_serialized() {
+    let json = serde_json::to_value(ToolsConfig::default()).unwrap();
+    assert!(json.get("memoize").is_none());
+}
@@ -0,0 +1,64 @@
+use serde_json::json;
+
+use crate::tool_output::{ToolOutputValidation, output_violations, structured_output};
+use crate::{Part, StandardDefinition, ToolResponse};
+
+fn schema() -> serde_json::Value {
+    json!({
+        "type": "object",
+        "properties": { "total": { "type": "number" } },
+        "required": ["total"]
+    })
+}
+
+#[test]
+fn the_only_data_part_is_the_structured_output() {
+    let parts = vec![
+        Part::Text("Found 3 orders".to_string()),
+        Part::Data(json!({ "total": 3 })),
+    ];
+    assert_eq!(structured_output(&parts), Some(json!({ "total": 3 })));
+
+    let response = ToolResponse::from_parts("call-1".into(), "orders".into(), parts);
+    assert_eq!(response.structured_output(), Some(json!({ "total": 3 })));
+}
+
+#[test]
+fn json_text_is_a_structured_output() {
+    let parts = vec![Part::Text(" {\"total\": 3}\n".to_string())];
+    assert_eq!(structured_output(&parts), Some(json!({ "total": 3 })));
+
+    assert_eq!(structured_output(&[Part::Text("3 orders".into())]), None);
+    let two = vec![Part::Data(json!(1)), Part::Data(json!(2))];
+    assert_eq!(structured_output(&two), None);
+}
+
+#[test]
+fn violations_describe_how_the_output_breaks_the_schema() {
+    assert!(output_violations(&schema(), &[Part::Data(json!({ "total": 3 }))]).is_empty());
+
+    let violations = output_violations(&schema(), &[Part::Data(json!({ "total": "3" }))]);
+    assert_eq!(violations.len(), 1);
+    assert!(violations[0].contains("\"3\""), "{violations:?}");
+
+    let violations = output_violations(&schema(), &[Part::Text("three".into())]);
+    assert!(violations[0].contains("no structured output"));
+}
+
+#[test]
+fn validation_parses_from_an_agent_definition() {
+    let definition: StandardDefinition = toml::from_str(
+        r#"
+name = "analyst"
+
+[tools]
+output_validation = "dev"
+"#,
+    )
+    .unwrap();
+    assert_eq!(
+        definition.tools.unwrap().output_validation,
+        ToolOutputValidation::Dev
+    );
+    assert!(ToolOutputValidation::Log.is_default());
+}
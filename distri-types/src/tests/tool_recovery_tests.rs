@@ -0,0 +1,78 @@
+use serde_json::json;
+
+use crate::StandardDefinition;
+use crate::tool_recovery::{ToolRecoveryStrategy, correction_message};
+
+#[test]
+fn strategies_parse_from_an_agent_definition() {
+    let definition: StandardDefinition = toml::from_str(
+        r#"
+name = "reader"
+
+[tools.recovery]
+default = { strategy = "correct" }
+
+[tools.recovery.tools]
+fetch = { strategy = "retry", backoff_ms = 500 }
+search = { strategy = "fallback", tool = "web_search" }
+deploy = { strategy = "fail" }
+"#,
+    )
+    .unwrap();
+
+    let recovery = definition.tools.unwrap().recovery;
+    assert_eq!(
+        recovery.strategy_for("fetch"),
+        &ToolRecoveryStrategy::Retry {
+            attempts: 1,
+            backoff_ms: 500
+        }
+    );
+    assert_eq!(
+        recovery.strategy_for("search"),
+        &ToolRecoveryStrategy::Fallback {
+            tool: "web_search".to_string()
+        }
+    );
+    assert_eq!(recovery.strategy_for("deploy").as_str(), "fail");
+    assert_eq!(
+        recovery.strategy_for("other"),
+        &ToolRecoveryStrategy::Correct
+    );
+}
+
+#[test]
+fn default_recovery_is_not_serialized() {
+    let definition = StandardDefinition {
+        name: "reader".to_string(),
+        tools: Some(Default::default()),
+        ..Default::default()
+    };
+    let value = serde_json::to_value(&definition).unwrap();
+    assert!(value["tools"].get("recovery").is_none());
+}
+
+#[test]
+fn correction_message_lists_violations_and_the_schema() {
+    let parameters = json!({
+        "type": "object",
+        "properties": { "limit": { "type": "integer" } },
+        "required": ["query"]
+    });
+    let message = correction_message(
+        "search",
+        "bad request",
+        &parameters,
+        &json!({ "limit": "ten" }),
+    );
+    assert!(message.starts_with("Tool 'search' failed: bad request"));
+    assert!(
+        message.contains("\"query\" is a required property"),
+        "{message}"
+    );
+    assert!(message.contains("is not of type \"integer\""), "{message}");
+    assert!(message.contains("\"required\""));
+
+    let valid = correction_message("search", "timeout", &parameters, &json!({ "query": "x" }));
+    assert!(valid.contains("The arguments match the tool's parameters"));
+}
@@ -0,0 +1,122 @@
+use serde_json::json;
+
+use crate::tool_redaction::{RedactionRule, ToolRedactionConfig, ToolRedactor};
+use crate::{Part, ToolResponse};
+
+fn config() -> ToolRedactionConfig {
+    serde_yaml::from_str(
+        r#"
+rules:
+  - pattern: "sk-[A-Za-z0-9]{8,}"
+    label: api_key
+tools:
+  crm__get_customer:
+    - path: "$.customer.email"
+      label: email
+    - path: "$..phone"
+    - path: "$.orders[*].card"
+      label: card
+"#,
+    )
+    .unwrap()
+}
+
+fn response(tool: &str, parts: Vec<Part>) -> ToolResponse {
+    ToolResponse::from_parts("call-1".to_string(), tool.to_string(), parts)
+}
+
+#[test]
+fn patterns_apply_to_every_tool() {
+    let redactor = ToolRedactor::new(&config()).unwrap();
+    let mut result = response(
+        "fetch",
+        vec![Part::Text(
+            "token sk-abcdef123456 and sk-zyxwvu987654".to_string(),
+        )],
+    );
+    assert_eq!(redactor.redact_response(&mut result), 2);
+    assert!(
+        matches!(&result.parts[0], Part::Text(text) if text == "token [REDACTED:api_key] and [REDACTED:api_key]")
+    );
+}
+
+#[test]
+fn paths_apply_to_the_tools_they_are_configured_for() {
+    let redactor = ToolRedactor::new(&config()).unwrap();
+    let output = json!({
+        "customer": {
+            "name": "Ada",
+            "email": "ada@example.com",
+            "contact": { "phone": "555-0100" }
+        },
+        "orders": [
+            { "id": 1, "card": "4111", "note": "key sk-abcdef123456" },
+            { "id": 2, "card": "4242" }
+        ]
+    });
+
+    let mut result = response("crm__get_customer", vec![Part::Data(output.clone())]);
+    assert_eq!(redactor.redact_response(&mut result), 5);
+    let Part::Data(redacted) = &result.parts[0] else {
+        panic!("expected a data part");
+    };
+    assert_eq!(redacted["customer"]["name"], "Ada");
+    assert_eq!(redacted["customer"]["email"], "[REDACTED:email]");
+    assert_eq!(redacted["customer"]["contact"]["phone"], "[REDACTED]");
+    assert_eq!(redacted["orders"][0]["card"], "[REDACTED:card]");
+    assert_eq!(redacted["orders"][1]["card"], "[REDACTED:card]");
+    assert_eq!(redacted["orders"][0]["note"], "key [REDACTED:api_key]");
+
+    // JSON returned as text is redacted the same way.
+    let mut result = response("crm__get_customer", vec![Part::Text(output.to_string())]);
+    assert_eq!(redactor.redact_response(&mut result), 5);
+    let Part::Text(text) = &result.parts[0] else {
+        panic!("expected a text part");
+    };
+    assert!(!text.contains("ada@example.com"), "{text}");
+
+    let mut other = response("other_tool", vec![Part::Data(output.clone())]);
+    assert_eq!(
+        redactor.redact_response(&mut other),
+        1,
+        "only the pattern applies"
+    );
+    assert!(
+        matches!(&other.parts[0], Part::Data(v) if v["customer"]["email"] == "ada@example.com")
+    );
+}
+
+#[test]
+fn invalid_rules_are_rejected() {
+    let invalid = |rule: RedactionRule| {
+        ToolRedactor::new(&ToolRedactionConfig {
+            rules: vec![rule],
+            ..Default::default()
+        })
+        .unwrap_err()
+        .to_string()
+    };
+    let both = invalid(RedactionRule {
+        pattern: Some("a".to_string()),
+        path: Some("$.a".to_string()),
+        label: None,
+    });
+    assert!(both.contains("exactly one"), "{both}");
+    let regex = invalid(RedactionRule {
+        pattern: Some("(".to_string()),
+        ..Default::default()
+    });
+    assert!(regex.contains("invalid pattern"), "{regex}");
+    for path in ["a.b", "$", "$.a[", "$.a[x]", "$.."] {
+        let err = invalid(RedactionRule {
+            path: Some(path.to_string()),
+            ..Default::default()
+        });
+        assert!(err.contains("invalid path"), "{path}: {err}");
+    }
+    assert!(
+        ToolRedactor::new(&ToolRedactionConfig::default())
+            .unwrap()
+            .is_empty()
+    );
+}
@@ -0,0 +1,59 @@
+use std::time::Duration;
+
+use crate::StandardDefinition;
+use crate::tool_timeouts::{ToolTimedOut, ToolTimeoutConfig};
+
+#[test]
+fn limits_parse_from_an_agent_definition() {
+    let definition: StandardDefinition = toml::from_str(
+        r#"
+name = "researcher"
+
+[tools.timeouts]
+default_secs = 60
+
+[tools.timeouts.tools]
+web_search = 15
+run_migrations = 0
+"#,
+    )
+    .unwrap();
+
+    let timeouts = definition.tools.unwrap().timeouts;
+    assert_eq!(
+        timeouts.timeout_for("web_search"),
+        Some(Duration::from_secs(15))
+    );
+    assert_eq!(timeouts.timeout_for("run_migrations"), None);
+    assert_eq!(timeouts.timeout_for("other"), Some(Duration::from_secs(60)));
+}
+
+#[test]
+fn tools_are_unlimited_without_a_default() {
+    let timeouts = ToolTimeoutConfig::default();
+    assert!(timeouts.is_default());
+    assert_eq!(timeouts.timeout_for("web_search"), None);
+}
+
+#[test]
+fn unknown_keys_are_rejected() {
+    let err = toml::from_str::<ToolTimeoutConfig>("default = 60").unwrap_err();
+    assert!(err.to_string().contains("unknown field"), "{err}");
+}
+
+#[test]
+fn a_timeout_tells_the_model_what_happened() {
+    let result = ToolTimedOut {
+        tool: "web_search".to_string(),
+        timeout_secs: 15,
+    }
+    .to_json();
+    assert_eq!(result["error"], "tool_timeout");
+    assert_eq!(result["timeout_secs"], 15);
+    assert!(
+        result["message"]
+            .as_str()
+            .unwrap()
+            .starts_with("tool 'web_search' did not finish within 15s")
+    );
+}
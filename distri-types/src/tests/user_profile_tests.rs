@@ -0,0 +1,103 @@
+use serde_json::json;
+
+use crate::user_profile::{ExpertiseLevel, UserProfile, describe_profile, effective_profile};
+
+#[test]
+fn validation_normalizes_language_and_rejects_bad_values() {
+    let profile = UserProfile {
+        language: Some("pt_br".to_string()),
+        timezone: Some("America/Sao_Paulo".to_string()),
+        ..Default::default()
+    }
+    .validated()
+    .unwrap();
+    assert_eq!(profile.language.as_deref(), Some("pt-BR"));
+
+    let language = UserProfile {
+        language: Some("Portuguese".to_string()),
+        ..Default::default()
+    };
+    assert!(
+        language
+            .validated()
+            .unwrap_err()
+            .contains("unknown language")
+    );
+    let timezone = UserProfile {
+        timezone: Some("Mars/Olympus".to_string()),
+        ..Default::default()
+    };
+    assert!(timezone.validated().is_err());
+    let mut long = UserProfile::default();
+    long.custom.insert("notes".to_string(), "x".repeat(501));
+    assert!(long.validated().unwrap_err().contains("'notes'"));
+
+    assert!(serde_json::from_value::<UserProfile>(json!({ "mood": "happy" })).is_err());
+}
+
+#[test]
+fn set_and_merge_update_single_preferences() {
+    let mut profile = UserProfile::default();
+    profile.set("tone", " concise ").unwrap();
+    profile.set("expertise", "Expert").unwrap();
+    profile.set("units", "metric").unwrap();
+    assert_eq!(profile.tone.as_deref(), Some("concise"));
+    assert_eq!(profile.expertise, Some(ExpertiseLevel::Expert));
+    assert_eq!(profile.custom["units"], "metric");
+    assert!(profile.set("expertise", "guru").is_err());
+    assert!(profile.set("", "x").is_err());
+
+    profile.set("expertise", "").unwrap();
+    profile.set("units", "").unwrap();
+    assert_eq!(profile.expertise, None);
+    assert!(profile.custom.is_empty());
+
+    profile.merge(UserProfile {
+        tone: Some(String::new()),
+        format: Some("tables".to_string()),
+        ..Default::default()
+    });
+    assert_eq!(profile.tone, None);
+    assert_eq!(profile.format.as_deref(), Some("tables"));
+}
+
+#[test]
+fn thread_values_override_the_stored_profile() {
+    let stored = UserProfile {
+        tone: Some("concise".to_string()),
+        timezone: Some("Europe/Berlin".to_string()),
+        ..Default::default()
+    };
+    assert_eq!(effective_profile(None, None), None);
+    assert_eq!(effective_profile(Some(&UserProfile::default()), None), None);
+    assert_eq!(
+        effective_profile(
+            Some(&stored),
+            Some(&json!({ "tone": "formal", "name": "Sam" }))
+        ),
+        Some(json!({ "tone": "formal", "timezone": "Europe/Berlin", "name": "Sam" }))
+    );
+    assert_eq!(
+        effective_profile(Some(&stored), Some(&json!("Prefers short answers"))),
+        Some(json!("Prefers short answers"))
+    );
+}
+
+#[test]
+fn profiles_are_described_one_preference_per_line() {
+    let profile = json!({
+        "format": "bullet points",
+        "tone": "concise",
+        "expertise": "beginner",
+        "custom": { "units": "metric" },
+        "name": "Sam",
+    });
+    assert_eq!(
+        describe_profile(&profile),
+        "- Tone: concise\n- Expertise: beginner\n- Answer format: bullet points\n- units: metric\n- name: Sam"
+    );
+    assert_eq!(
+        describe_profile(&json!("Prefers short answers")),
+        "Prefers short answers"
+    );
+}
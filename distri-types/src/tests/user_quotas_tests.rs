@@ -0,0 +1,69 @@
+use chrono::{TimeZone, Utc};
+
+use crate::user_quotas::{RaiseQuotaRequest, UserQuotaLimits, next_reset};
+
+#[test]
+fn config_parses_and_unset_limits_stay_unset() {
+    let limits: UserQuotaLimits =
+        serde_yaml::from_str("max_concurrent_tasks: 2\nmax_tokens_per_day: 50000\n").unwrap();
+    assert_eq!(limits.max_concurrent_tasks, Some(2));
+    assert_eq!(limits.max_tasks_per_day, None);
+    assert!(serde_yaml::from_str::<UserQuotaLimits>("max_runs: 2\n").is_err());
+}
+
+#[test]
+fn raises_expire_after_their_duration_and_saturate() {
+    let now = Utc.with_ymd_and_hms(2026, 5, 1, 12, 0, 0).unwrap();
+    let raise = RaiseQuotaRequest {
+        extra_concurrent_tasks: 1,
+        duration_secs: 90,
+        ..Default::default()
+    }
+    .raise_at(now);
+    assert_eq!(
+        raise.expires_at,
+        Utc.with_ymd_and_hms(2026, 5, 1, 12, 1, 30).unwrap()
+    );
+
+    let limits = UserQuotaLimits {
+        max_concurrent_tasks: Some(2),
+        ..Default::default()
+    }
+    .raised(&raise);
+    assert_eq!(limits.max_concurrent_tasks, Some(3));
+    assert_eq!(limits.max_tasks_per_day, None);
+
+    let forever = RaiseQuotaRequest {
+        duration_secs: u64::MAX,
+        ..Default::default()
+    }
+    .raise_at(now);
+    assert!(forever.expires_at > now);
+
+    let huge = RaiseQuotaRequest {
+        extra_tokens_per_day: u64::MAX,
+        duration_secs: 60,
+        ..Default::default()
+    }
+    .raise_at(now);
+    let limits = UserQuotaLimits {
+        max_tokens_per_day: Some(50_000),
+        ..Default::default()
+    }
+    .raised(&huge);
+    assert_eq!(limits.max_tokens_per_day, Some(u64::MAX));
+}
+
+#[test]
+fn daily_quotas_reset_at_the_next_utc_midnight() {
+    let late = Utc.with_ymd_and_hms(2026, 12, 31, 23, 59, 59).unwrap();
+    assert_eq!(
+        next_reset(late),
+        Utc.with_ymd_and_hms(2027, 1, 1, 0, 0, 0).unwrap()
+    );
+    let midnight = Utc.with_ymd_and_hms(2027, 1, 1, 0, 0, 0).unwrap();
+    assert_eq!(
+        next_reset(midnight),
+        Utc.with_ymd_and_hms(2027, 1, 2, 0, 0, 0).unwrap()
+    );
+}
@@ -0,0 +1,177 @@
+use serde_yaml::Value;
+
+use crate::workspace_config::{self, CURRENT_VERSION, ConfigChange};
+
+const LEGACY: &str = r#"name: example-workspace
+version: "0.1.0"
+
+# Default model for every agent.
+model_settings:
+  model: gpt-4.1-mini
+  temperature: 0.2
+  provider:
+    name: openai
+
+analysis_model_settings:
+  model: gpt-4.1-mini
+
+# Agents to seed on startup.
+agents:
+  - file: agents/coder.md
+"#;
+
+#[test]
+fn legacy_config_migrates_to_current_version() {
+    let mut config: Value = serde_yaml::from_str(LEGACY).unwrap();
+    assert_eq!(workspace_config::version_of(&config), 1);
+    let deprecated: Vec<&str> = workspace_config::deprecated_keys(&config)
+        .iter()
+        .map(|d| d.key)
+        .collect();
+    assert_eq!(
+        deprecated,
+        vec!["model_settings", "analysis_model_settings", "name"]
+    );
+
+    let migration = workspace_config::migrate(&mut config).unwrap();
+
+    assert_eq!((migration.from_version, migration.to_version), (1, 2));
+    assert_eq!(workspace_config::version_of(&config), CURRENT_VERSION);
+    assert_eq!(
+        config.get("default_model").and_then(Value::as_str),
+        Some("openai/gpt-4.1-mini")
+    );
+    assert!(config.get("model_settings").is_none());
+    assert!(config.get("name").is_none());
+    assert!(workspace_config::unknown_keys(&config).is_empty());
+    assert!(migration.changes.iter().any(|c| matches!(
+        c,
+        ConfigChange::Set { key, replaces: Some(from), .. }
+            if key == "default_model" && from == "model_settings"
+    )));
+}
+
+#[test]
+fn current_config_is_left_alone() {
+    let mut config: Value =
+        serde_yaml::from_str("version: 2\ndefault_model: openai/gpt-4.1\n").unwrap();
+    let before = config.clone();
+    let migration = workspace_config::migrate(&mut config).unwrap();
+    assert!(migration.is_noop());
+    assert_eq!(config, before);
+
+    let mut newer: Value = serde_yaml::from_str("version: 99\n").unwrap();
+    assert!(workspace_config::migrate(&mut newer).is_err());
+}
+
+#[test]
+fn render_keeps_comments_of_untouched_entries() {
+    let mut config: Value = serde_yaml::from_str(LEGACY).unwrap();
+    let migration = workspace_config::migrate(&mut config).unwrap();
+
+    let (text, kept) = workspace_config::render(Some(LEGACY), &config, &migration);
+
+    assert!(kept);
+    assert!(text.contains("# Default model for every agent.\ndefault_model: openai/gpt-4.1-mini"));
+    assert!(text.contains("# Agents to seed on startup.\nagents:"));
+    assert!(text.starts_with("version: 2\n"));
+    assert!(!text.contains("analysis_model_settings"));
+    assert_eq!(serde_yaml::from_str::<Value>(&text).unwrap(), config);
+}
+
+#[test]
+fn unknown_keys_are_reported() {
+    let config: Value = serde_yaml::from_str("version: 2\ndefualt_model: x\nagents: []\n").unwrap();
+    assert_eq!(
+        workspace_config::unknown_keys(&config),
+        vec!["defualt_model"]
+    );
+}
+
+const WITH_PROFILES: &str = r#"version: 2
+default_model: openai/gpt-4.1-mini
+llm_audit:
+  enabled: true
+  retention_days: 30
+mcp_servers:
+  - { name: github, command: github-mcp }
+  - { name: jira, command: jira-mcp }
+profiles:
+  prod:
+    default_model: anthropic/claude-sonnet-4
+    llm_audit:
+      retention_days: 90
+    mcp_servers:
+      - { name: github, url: "https://mcp.example.com/github" }
+    stores:
+      metadata:
+        store_type: { type: postgres }
+        db_config: { database_url: "postgres://distri@db/distri" }
+  dev:
+    llm_audit: ~
+"#;
+
+#[test]
+fn profile_overrides_are_merged_over_the_config() {
+    let mut config: Value = serde_yaml::from_str(WITH_PROFILES).unwrap();
+    assert_eq!(workspace_config::profile_names(&config), ["prod", "dev"]);
+
+    workspace_config::apply_profile(&mut config, Some("prod")).unwrap();
+
+    let expected: Value = serde_yaml::from_str(
+        r#"version: 2
+default_model: anthropic/claude-sonnet-4
+llm_audit:
+  enabled: true
+  retention_days: 90
+mcp_servers:
+  - { name: github, url: "https://mcp.example.com/github" }
+stores:
+  metadata:
+    store_type: { type: postgres }
+    db_config: { database_url: "postgres://distri@db/distri" }
+"#,
+    )
+    .unwrap();
+    assert_eq!(config, expected);
+    assert!(workspace_config::unknown_keys(&config).is_empty());
+}
+
+#[test]
+fn null_in_a_profile_removes_the_setting() {
+    let mut config: Value = serde_yaml::from_str(WITH_PROFILES).unwrap();
+    workspace_config::apply_profile(&mut config, Some("dev")).unwrap();
+    assert!(config.get("llm_audit").is_none());
+    assert!(config.get("profiles").is_none());
+    assert_eq!(
+        config.get("default_model").and_then(Value::as_str),
+        Some("openai/gpt-4.1-mini")
+    );
+}
+
+#[test]
+fn without_a_profile_only_the_profiles_are_dropped() {
+    let mut config: Value = serde_yaml::from_str(WITH_PROFILES).unwrap();
+    let mut expected = config.clone();
+    expected.as_mapping_mut().unwrap().remove("profiles");
+    workspace_config::apply_profile(&mut config, None).unwrap();
+    assert_eq!(config, expected);
+}
+
+#[test]
+fn unknown_or_invalid_profiles_are_rejected() {
+    let mut config: Value = serde_yaml::from_str(WITH_PROFILES).unwrap();
+    let err = workspace_config::apply_profile(&mut config, Some("staging"))
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("available profiles: prod, dev"), "{err}");
+
+    let mut config: Value = serde_yaml::from_str("default_model: openai/gpt-4.1\n").unwrap();
+    let err = workspace_config::apply_profile(&mut config, Some("prod"))
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("defines no profiles"), "{err}");
+
+    let mut config: Value = serde_yaml::from_str("profiles:\n  prod:\n    version: 1\n").unwrap();
+    assert!(workspace_config::apply_profile(&mut config, Some("prod")).is_err());
+}
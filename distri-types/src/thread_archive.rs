@@ -0,0 +1,161 @@
+//! Thread archival: moving old threads to cold storage.
+//!
+//! An archived thread keeps its row in the `threads` table, marked with
+//! `archived_at`, so it is still listed. Its tasks and their messages are
+//! written to the session object store as a JSONL [`ThreadArchive`] plus a
+//! [`ThreadArchiveManifest`], and removed from the database. Reading the
+//! thread or sending it a message restores them. See
+//! `distri_core::agent::thread_archive`.
+
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::{Task, Thread};
+
+/// `thread_archive` section of the server config.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ThreadArchiveConfig {
+    /// Threads not updated for this many days are archived.
+    #[serde(default = "default_retention_days")]
+    pub retention_days: u64,
+    /// How often threads to archive are looked for.
+    #[serde(default = "default_sweep_interval_secs")]
+    pub sweep_interval_secs: u64,
+    /// Most threads archived by one sweep.
+    #[serde(default = "default_batch_size")]
+    pub batch_size: u32,
+}
+
+fn default_retention_days() -> u64 {
+    90
+}
+
+fn default_sweep_interval_secs() -> u64 {
+    3600
+}
+
+fn default_batch_size() -> u32 {
+    50
+}
+
+impl Default for ThreadArchiveConfig {
+    fn default() -> Self {
+        Self {
+            retention_days: default_retention_days(),
+            sweep_interval_secs: default_sweep_interval_secs(),
+            batch_size: default_batch_size(),
+        }
+    }
+}
+
+/// A task row as archived, with the columns [`Task`] leaves out.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchivedTask {
+    #[serde(flatten)]
+    pub task: Task,
+    #[serde(default)]
+    pub remote: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub inner_task_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ended_at: Option<i64>,
+    #[serde(default)]
+    pub invocation: serde_json::Value,
+}
+
+/// A stored task message or event, kept as stored.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArchivedTaskMessage {
+    pub task_id: String,
+    /// `message` or `event`.
+    pub kind: String,
+    pub payload: serde_json::Value,
+    pub created_at: i64,
+}
+
+/// One line of an archive.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+#[allow(clippy::large_enum_variant)]
+enum ArchiveRecord {
+    Thread(Thread),
+    Task(ArchivedTask),
+    Message(ArchivedTaskMessage),
+}
+
+/// Everything of a thread that leaves the database when it is archived.
+#[derive(Debug, Clone)]
+pub struct ThreadArchive {
+    pub thread: Thread,
+    pub tasks: Vec<ArchivedTask>,
+    pub messages: Vec<ArchivedTaskMessage>,
+}
+
+impl ThreadArchive {
+    /// The thread first, then its tasks, then their messages; one JSON
+    /// object per line.
+    pub fn to_jsonl(&self) -> anyhow::Result<String> {
+        let mut out = String::new();
+        let records = std::iter::once(ArchiveRecord::Thread(self.thread.clone()))
+            .chain(self.tasks.iter().cloned().map(ArchiveRecord::Task))
+            .chain(self.messages.iter().cloned().map(ArchiveRecord::Message));
+        for record in records {
+            out.push_str(&serde_json::to_string(&record)?);
+            out.push('\n');
+        }
+        Ok(out)
+    }
+
+    pub fn from_jsonl(text: &str) -> anyhow::Result<Self> {
+        let mut thread = None;
+        let mut tasks = Vec::new();
+        let mut messages = Vec::new();
+        for (number, line) in text.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let record: ArchiveRecord = serde_json::from_str(line)
+                .map_err(|e| anyhow::anyhow!("line {} of the archive: {}", number + 1, e))?;
+            match record {
+                ArchiveRecord::Thread(t) => thread = Some(t),
+                ArchiveRecord::Task(task) => tasks.push(task),
+                ArchiveRecord::Message(message) => messages.push(message),
+            }
+        }
+        let thread = thread.ok_or_else(|| anyhow::anyhow!("the archive has no thread line"))?;
+        Ok(Self {
+            thread,
+            tasks,
+            messages,
+        })
+    }
+}
+
+/// Written next to the archive; describes what it holds.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema, ToSchema)]
+pub struct ThreadArchiveManifest {
+    pub thread_id: String,
+    pub archived_at: DateTime<Utc>,
+    /// Number of archived tasks.
+    pub tasks: usize,
+    /// Number of archived task messages and events.
+    pub messages: usize,
+    /// Object store paths of the thread's artifacts. Artifacts already live
+    /// in the object store, so they stay where they are.
+    #[serde(default)]
+    pub artifacts: Vec<String>,
+}
+
+/// Result of archiving or restoring a thread.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema, ToSchema)]
+pub struct ThreadArchiveSummary {
+    pub thread_id: String,
+    /// Set while the thread is archived.
+    pub archived_at: Option<DateTime<Utc>>,
+    pub tasks: usize,
+    pub messages: usize,
+    pub artifacts: usize,
+}
@@ -15,6 +15,20 @@ pub enum TodoStatus {
     InProgress,
     #[serde(alias = "completed")]
     Done,
+    /// The queue ran the item and its sub-task did not complete.
+    Failed,
+}
+
+/// Order in which [`TodoList::next_ready`] picks items.
+#[derive(
+    Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, JsonSchema, Default,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum TodoPriority {
+    Low,
+    #[default]
+    Medium,
+    High,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -23,6 +37,17 @@ pub struct TodoItem {
     pub title: String,
     pub notes: Option<String>,
     pub status: TodoStatus,
+    #[serde(default)]
+    pub priority: TodoPriority,
+    /// Titles of the items that must be `Done` before this one can run.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub depends_on: Vec<String>,
+    /// Sub-task that worked the item, set by the todo queue.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub task_id: Option<String>,
+    /// What the sub-task reported back, or why it failed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result: Option<String>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -35,6 +60,10 @@ impl TodoItem {
             title,
             notes,
             status: TodoStatus::Open,
+            priority: TodoPriority::default(),
+            depends_on: Vec::new(),
+            task_id: None,
+            result: None,
             created_at: now,
             updated_at: now,
         }
@@ -99,15 +128,22 @@ impl TodoList {
                     TodoStatus::Done => "■",
                     TodoStatus::InProgress => "◐",
                     TodoStatus::Open => "□",
+                    TodoStatus::Failed => "✗",
                 };
 
                 let mut line = format!("{} {}", icon, item.title.trim());
+                if item.priority == TodoPriority::High {
+                    line.push_str(" !");
+                }
                 if let Some(notes) = &item.notes {
                     let trimmed = notes.trim();
                     if !trimmed.is_empty() {
                         line.push_str(&format!(" ({})", trimmed));
                     }
                 }
+                if !item.depends_on.is_empty() {
+                    line.push_str(&format!(" [after: {}]", item.depends_on.join(", ")));
+                }
 
                 line
             })
@@ -145,11 +181,46 @@ impl TodoList {
         for simple_todo in simple_todos {
             let mut item = TodoItem::new(simple_todo.content, None);
             item.status = simple_todo.status;
+            item.priority = simple_todo.priority;
+            item.depends_on = simple_todo.depends_on;
             item.updated_at = chrono::Utc::now();
             self.items.push(item);
         }
     }
 
+    /// The next `Open` item whose dependencies are all `Done`: highest
+    /// priority first, list order among equals.
+    pub fn next_ready(&self) -> Option<&TodoItem> {
+        self.items
+            .iter()
+            .filter(|item| item.status == TodoStatus::Open && self.dependencies_done(item))
+            .fold(None, |best: Option<&TodoItem>, item| match best {
+                Some(best) if best.priority >= item.priority => Some(best),
+                _ => Some(item),
+            })
+    }
+
+    fn dependencies_done(&self, item: &TodoItem) -> bool {
+        item.depends_on.iter().all(|dep| {
+            self.items
+                .iter()
+                .any(|other| &other.title == dep && other.status == TodoStatus::Done)
+        })
+    }
+
+    /// Dependencies that name no item in the list, as `(item, dependency)`.
+    pub fn unknown_dependencies(&self) -> Vec<(String, String)> {
+        self.items
+            .iter()
+            .flat_map(|item| {
+                item.depends_on
+                    .iter()
+                    .filter(|dep| !self.items.iter().any(|other| &other.title == *dep))
+                    .map(|dep| (item.title.clone(), dep.clone()))
+            })
+            .collect()
+    }
+
     /// Diff `self` (the new list, after a `write_todos` overwrite)
     /// against `prev` (the list as it was before this call). Match
     /// items by `title` (the LLM passes them through as `content`,
@@ -224,6 +295,56 @@ pub struct SimpleTodo {
     pub content: String,
     #[serde(default)]
     pub status: TodoStatus,
+    #[serde(default)]
+    pub priority: TodoPriority,
+    /// `content` of the todos that must be done first.
+    #[serde(default)]
+    pub depends_on: Vec<String>,
+}
+
+/// What working the todo queue did, emitted with
+/// `AgentEventType::TodoQueueFinished` and returned to the caller.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct TodoQueueSummary {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub completed: Vec<TodoOutcome>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub failed: Vec<TodoOutcome>,
+    /// Items left open because a dependency failed, is missing, or is
+    /// part of a cycle.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub blocked: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct TodoOutcome {
+    pub title: String,
+    pub task_id: Option<String>,
+    pub result: Option<String>,
+}
+
+impl TodoQueueSummary {
+    pub fn format_display(&self) -> String {
+        let mut lines = vec![format!(
+            "Todo queue: {} done, {} failed, {} blocked",
+            self.completed.len(),
+            self.failed.len(),
+            self.blocked.len()
+        )];
+        for outcome in &self.completed {
+            lines.push(format!("■ {}", outcome.title));
+        }
+        for outcome in &self.failed {
+            match &outcome.result {
+                Some(reason) => lines.push(format!("✗ {} ({})", outcome.title, reason)),
+                None => lines.push(format!("✗ {}", outcome.title)),
+            }
+        }
+        for title in &self.blocked {
+            lines.push(format!("□ {} (blocked)", title));
+        }
+        lines.join("\n")
+    }
 }
 
 /// Tool parameters for todos operations - uses Serde for proper deserialization
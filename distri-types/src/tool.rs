@@ -3,8 +3,11 @@ use std::{collections::HashMap, sync::Arc};
 use tokio::sync::mpsc;
 
 use crate::Part;
+use crate::egress::{EgressPolicy, NetworkDenied};
 use crate::{
-    ToolCall, ToolDefinition, auth::AuthMetadata, events::AgentEvent, stores::SessionStore,
+    CapabilityDenied, CapabilityGuard, PluginCapabilities, PluginSpan, PluginSpanRecorder,
+    PluginStorage, PluginStorageError, ToolCall, ToolDefinition, TraceContext, auth::AuthMetadata,
+    events::AgentEvent, stores::SessionStore,
 };
 
 /// Tool execution context - lighter weight than ExecutorContext
@@ -29,6 +32,73 @@ pub struct ToolContext {
 
     /// Additional metadata for the tool. Useful in direct inline agent invocation.
     pub metadata: Option<HashMap<String, serde_json::Value>>,
+    /// Trace of the run, pointing at the span the tool executes under.
+    pub trace: Option<TraceContext>,
+    /// Spans the tool records with [`ToolContext::span`].
+    pub spans: PluginSpanRecorder,
+    /// Capabilities granted to the tool's plugin. `None` for tools that are
+    /// not part of a plugin. See [`crate::plugin_capabilities`].
+    pub capabilities: Option<Arc<CapabilityGuard>>,
+    /// Server egress policy, checked for `agent_id` on top of the plugin's
+    /// capabilities. See [`crate::egress`].
+    pub egress: Option<Arc<EgressPolicy>>,
+    /// Persistent storage of the tool's plugin. `None` for tools that are
+    /// not part of a plugin. See [`crate::plugin_storage`].
+    pub storage: Option<PluginStorage>,
+}
+
+impl ToolContext {
+    /// W3C `traceparent` header value for requests the tool makes, so the
+    /// services it calls join the run's trace.
+    pub fn traceparent(&self) -> Option<String> {
+        self.trace.as_ref().map(TraceContext::traceparent)
+    }
+
+    /// Start a span for a step of the tool's work. See [`crate::plugin_trace`].
+    pub fn span(&self, name: impl Into<String>) -> PluginSpan {
+        let parent = self.trace.as_ref().map(|t| t.parent_span_id.clone());
+        self.spans.start(name, parent)
+    }
+
+    /// Whether the tool may connect to `url`.
+    pub fn check_network(&self, url: &str) -> Result<(), NetworkDenied> {
+        if let Some(policy) = &self.egress {
+            policy.check(Some(&self.agent_id), url)?;
+        }
+        if let Some(guard) = &self.capabilities {
+            guard.check_network(url)?;
+        }
+        Ok(())
+    }
+
+    /// Whether the tool may read `path`, or write it when `write`.
+    pub fn check_path(&self, path: &std::path::Path, write: bool) -> Result<(), CapabilityDenied> {
+        match &self.capabilities {
+            Some(guard) => guard.check_path(path, write),
+            None => Ok(()),
+        }
+    }
+
+    /// Whether the tool may read the secrets of `provider`.
+    pub fn check_secret(&self, provider: &str) -> Result<(), CapabilityDenied> {
+        match &self.capabilities {
+            Some(guard) => guard.check_secret(provider),
+            None => Ok(()),
+        }
+    }
+
+    /// The persistent storage of the tool's plugin.
+    pub fn storage(&self) -> Result<&PluginStorage, PluginStorageError> {
+        self.storage.as_ref().ok_or(PluginStorageError::Unavailable)
+    }
+
+    /// Environment variable `name`, if the tool may read it.
+    pub fn env_var(&self, name: &str) -> Result<Option<String>, CapabilityDenied> {
+        if let Some(guard) = &self.capabilities {
+            guard.check_env(name)?;
+        }
+        Ok(std::env::var(name).ok())
+    }
 }
 
 /// Tool trait for implementing tools that can be called by agents
@@ -42,7 +112,7 @@ pub trait Tool: Send + Sync + std::fmt::Debug + std::any::Any {
             name: self.get_name(),
             description: self.get_description(),
             parameters: self.get_parameters(),
-            output_schema: None,
+            output_schema: self.get_output_schema(),
             examples: self.get_tool_examples(),
             prompt: self.prompt(),
         }
@@ -51,6 +121,12 @@ pub trait Tool: Send + Sync + std::fmt::Debug + std::any::Any {
     fn get_parameters(&self) -> serde_json::Value;
     fn get_description(&self) -> String;
 
+    /// JSON schema of the tool's structured output, if it declares one.
+    /// Results are checked against it as `tools.output_validation` says.
+    fn get_output_schema(&self) -> Option<serde_json::Value> {
+        None
+    }
+
     fn get_tool_examples(&self) -> Option<String> {
         None
     }
@@ -93,6 +169,24 @@ pub trait Tool: Send + Sync + std::fmt::Debug + std::any::Any {
         true // Default: most tools are read-only / independent
     }
 
+    /// Whether a repeated identical call (same input) within a task may be
+    /// answered from the task's tool call cache instead of running again.
+    ///
+    /// Opt in for idempotent lookups (search, scrape, schema reads) whose
+    /// result is not expected to change during a run. Agents can still
+    /// exclude the tool through `tools.memoize`.
+    fn memoizable(&self) -> bool {
+        false // Default: calls may have side effects or changing results
+    }
+
+    /// Whether a call changes anything outside the run (sends a message,
+    /// writes a record). Dry runs intercept calls of tools with side effects
+    /// (see [`crate::dry_run`]); `None`, the default, leaves it to the
+    /// built-in list of read-only tools.
+    fn side_effect(&self) -> Option<bool> {
+        None
+    }
+
     /// Check if this tool needs ExecutorContext instead of ToolContext
     fn needs_executor_context(&self) -> bool {
         false // Default to false - most tools use ToolContext
@@ -110,6 +204,19 @@ pub trait Tool: Send + Sync + std::fmt::Debug + std::any::Any {
         None // Default to standalone tool
     }
 
+    /// Capability manifest of the plugin this tool belongs to. A plugin tool
+    /// without one is granted nothing.
+    fn get_plugin_capabilities(&self) -> Option<PluginCapabilities> {
+        None
+    }
+
+    /// How the web UI renders calls of this tool (see
+    /// [`crate::renderer_manifest`]). Without one the UI falls back to its
+    /// generic tool card.
+    fn get_ui_renderer(&self) -> Option<crate::renderer_manifest::ToolRendererManifest> {
+        None
+    }
+
     /// Execute the tool with given arguments, returning content parts
     async fn execute(
         &self,
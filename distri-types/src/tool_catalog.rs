@@ -0,0 +1,272 @@
+//! Namespacing and collision resolution of an agent's tool catalog.
+//!
+//! Every tool an agent gets comes from a source — the caller's external
+//! tools, the agent's dynamic factories, the server builtins, a plugin or an
+//! MCP server — and has a qualified name `namespace/tool`, where the
+//! namespace is the plugin or MCP server name, or the source itself
+//! (`builtin/final`, `external/open_file`, `dynamic/crm_lookup`).
+//!
+//! When several tools share a name, one of them is kept and the others are
+//! shadowed: the `prefer` entry of `tools.collisions` decides first, then the
+//! `precedence` order of sources, then registration order. With
+//! `strict = true` any collision not settled by `prefer` fails the agent
+//! instead. A shadowed tool stays reachable through an alias of the agent
+//! definition (`tool_aliases`).
+//!
+//! ```toml
+//! [tools.collisions]
+//! precedence = ["external", "plugin", "dynamic", "builtin", "mcp"]
+//! prefer = { search = "acme" }
+//!
+//! [tool_aliases]
+//! web_search = "builtin/search"
+//! ```
+
+use std::collections::BTreeMap;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Where a tool of the catalog comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ToolSourceKind {
+    /// Provided by the caller of the run.
+    External,
+    /// Built from the agent's `tools.dynamic` factories.
+    Dynamic,
+    /// Provided by the server.
+    Builtin,
+    /// Registered by a plugin or by the host application.
+    Plugin,
+    /// Listed by an MCP server.
+    Mcp,
+}
+
+impl ToolSourceKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ToolSourceKind::External => "external",
+            ToolSourceKind::Dynamic => "dynamic",
+            ToolSourceKind::Builtin => "builtin",
+            ToolSourceKind::Plugin => "plugin",
+            ToolSourceKind::Mcp => "mcp",
+        }
+    }
+}
+
+/// `tools.collisions` of an agent definition.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ToolCollisionConfig {
+    /// Sources from the highest priority to the lowest. Sources left out
+    /// rank below the listed ones, in the default order.
+    #[serde(default = "default_precedence")]
+    pub precedence: Vec<ToolSourceKind>,
+    /// Tool name → namespace (or source) whose tool wins that name.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub prefer: BTreeMap<String, String>,
+    /// Fail the agent on collisions that `prefer` does not settle.
+    #[serde(default)]
+    pub strict: bool,
+}
+
+fn default_precedence() -> Vec<ToolSourceKind> {
+    vec![
+        ToolSourceKind::External,
+        ToolSourceKind::Dynamic,
+        ToolSourceKind::Builtin,
+        ToolSourceKind::Plugin,
+        ToolSourceKind::Mcp,
+    ]
+}
+
+impl Default for ToolCollisionConfig {
+    fn default() -> Self {
+        Self {
+            precedence: default_precedence(),
+            prefer: BTreeMap::new(),
+            strict: false,
+        }
+    }
+}
+
+impl ToolCollisionConfig {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    fn rank(&self, source: ToolSourceKind) -> usize {
+        self.precedence
+            .iter()
+            .chain(default_precedence().iter())
+            .position(|s| *s == source)
+            .unwrap_or(usize::MAX)
+    }
+
+    /// Pick the tool that keeps `name` among `candidates` (at least two, in
+    /// registration order). Returns its index and the rule that decided.
+    pub fn select(
+        &self,
+        name: &str,
+        candidates: &[ToolCandidate],
+    ) -> Result<(usize, CollisionRule), String> {
+        if let Some(preferred) = self.prefer.get(name) {
+            return candidates
+                .iter()
+                .position(|c| c.namespace == *preferred || c.source.as_str() == preferred)
+                .map(|index| (index, CollisionRule::Preferred))
+                .ok_or_else(|| {
+                    format!(
+                        "tools.collisions.prefer picks '{}' for tool '{}', but it is only provided by {}",
+                        preferred,
+                        name,
+                        qualified_names(candidates)
+                    )
+                });
+        }
+        if self.strict {
+            return Err(format!(
+                "tool '{}' is provided by {}; pick one in tools.collisions.prefer",
+                name,
+                qualified_names(candidates)
+            ));
+        }
+        let best = candidates
+            .iter()
+            .map(|c| self.rank(c.source))
+            .min()
+            .unwrap_or_default();
+        let tied = candidates
+            .iter()
+            .filter(|c| self.rank(c.source) == best)
+            .count();
+        let index = candidates
+            .iter()
+            .position(|c| self.rank(c.source) == best)
+            .unwrap_or_default();
+        let rule = if tied > 1 {
+            CollisionRule::FirstRegistered
+        } else {
+            CollisionRule::Precedence
+        };
+        Ok((index, rule))
+    }
+}
+
+fn qualified_names(candidates: &[ToolCandidate]) -> String {
+    candidates
+        .iter()
+        .map(|c| c.qualified_name.as_str())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// One tool of the catalog.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema, ToSchema)]
+pub struct ToolCandidate {
+    /// Name the model calls the tool by.
+    pub name: String,
+    pub namespace: String,
+    /// `namespace/tool`.
+    pub qualified_name: String,
+    pub source: ToolSourceKind,
+}
+
+impl ToolCandidate {
+    pub fn new(
+        name: impl Into<String>,
+        namespace: impl Into<String>,
+        tool: &str,
+        source: ToolSourceKind,
+    ) -> Self {
+        let namespace = namespace.into();
+        Self {
+            name: name.into(),
+            qualified_name: format!("{}/{}", namespace, tool),
+            namespace,
+            source,
+        }
+    }
+}
+
+/// How a collision was settled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CollisionRule {
+    /// `tools.collisions.prefer`.
+    Preferred,
+    /// `tools.collisions.precedence`.
+    Precedence,
+    /// Same source: the first registered tool.
+    FirstRegistered,
+}
+
+/// Several tools with one name, and which of them runs.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema, ToSchema)]
+pub struct ToolCollision {
+    pub name: String,
+    pub winner: ToolCandidate,
+    pub shadowed: Vec<ToolCandidate>,
+    pub rule: CollisionRule,
+}
+
+/// An agent's tools after collision resolution.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ToolCatalog {
+    /// The tools the model sees, aliases included.
+    pub tools: Vec<ToolCandidate>,
+    /// Alias → qualified name of the tool it runs.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub aliases: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub collisions: Vec<ToolCollision>,
+}
+
+impl ToolCatalog {
+    /// Which tool a call to `name` (a tool name, an alias or a qualified
+    /// name) runs.
+    pub fn resolve(&self, name: &str) -> ToolResolution {
+        let tool = self
+            .tools
+            .iter()
+            .find(|t| t.name == name)
+            .or_else(|| self.tools.iter().find(|t| t.qualified_name == name))
+            .cloned();
+        let alias_of = tool
+            .as_ref()
+            .and_then(|t| self.aliases.get(&t.name))
+            .cloned();
+        let target = tool.as_ref().map_or(name, |t| t.qualified_name.as_str());
+        let collision = self
+            .collisions
+            .iter()
+            .find(|c| {
+                c.winner.qualified_name == target
+                    || c.shadowed.iter().any(|s| s.qualified_name == target)
+            })
+            .cloned();
+        ToolResolution {
+            requested: name.to_string(),
+            tool,
+            alias_of,
+            collision,
+        }
+    }
+}
+
+/// Answer of [`ToolCatalog::resolve`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema, ToSchema)]
+pub struct ToolResolution {
+    pub requested: String,
+    /// The tool that runs, or `None` when no callable tool has that name
+    /// (including a shadowed tool's qualified name).
+    pub tool: Option<ToolCandidate>,
+    /// Qualified name the alias points to, when `requested` is an alias.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alias_of: Option<String>,
+    /// The collision over the tool's name, if there was one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub collision: Option<ToolCollision>,
+}
@@ -0,0 +1,83 @@
+//! Typed tool results: the output schemas tools declare and how results are
+//! checked against them, `tools.output_validation` of an agent definition.
+//!
+//! A tool declares the shape of its result with
+//! [`Tool::get_output_schema`](crate::Tool::get_output_schema). Its
+//! structured output, a single `data` part or a single text part holding
+//! JSON, is checked against that schema after every call:
+//!
+//! - `off`: results are not checked;
+//! - `log` (default): violations are logged;
+//! - `dev`: violations are also reported as a `tool_output_invalid` event,
+//!   so the author of a plugin or MCP server sees them while developing it.
+//!
+//! A violation never fails the call; the model gets the result as it is.
+//!
+//! ```toml
+//! [tools]
+//! output_validation = "dev"
+//! ```
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::Part;
+
+/// `tools.output_validation` of an agent definition.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ToolOutputValidation {
+    /// Results are not checked.
+    Off,
+    /// Violations are logged.
+    #[default]
+    Log,
+    /// Violations are logged and reported as `tool_output_invalid` events.
+    Dev,
+}
+
+impl ToolOutputValidation {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// The structured output of a tool result: its only `data` part or, without
+/// one, its only text part if that is JSON.
+pub fn structured_output(parts: &[Part]) -> Option<Value> {
+    let mut data = parts.iter().filter_map(|part| match part {
+        Part::Data(value) => Some(value),
+        _ => None,
+    });
+    match (data.next(), data.next()) {
+        (Some(value), None) => return Some(value.clone()),
+        (Some(_), Some(_)) => return None,
+        _ => {}
+    }
+    let mut texts = parts.iter().filter_map(|part| match part {
+        Part::Text(text) => Some(text),
+        _ => None,
+    });
+    match (texts.next(), texts.next()) {
+        (Some(text), None) => serde_json::from_str(text.trim()).ok(),
+        _ => None,
+    }
+}
+
+/// How the result `parts` of a tool break its output `schema`; empty when
+/// they conform. A schema that does not compile is reported as a violation.
+pub fn output_violations(schema: &Value, parts: &[Part]) -> Vec<String> {
+    let Some(output) = structured_output(parts) else {
+        return vec![
+            "the result has no structured output (a single data part or JSON text)".to_string(),
+        ];
+    };
+    match jsonschema::validator_for(schema) {
+        Ok(validator) => validator
+            .iter_errors(&output)
+            .map(|e| e.to_string())
+            .collect(),
+        Err(e) => vec![format!("invalid output schema: {e}")],
+    }
+}
@@ -0,0 +1,118 @@
+//! What happens when a tool call fails: `tools.recovery` of an agent
+//! definition.
+//!
+//! By default the tool's error goes back to the model as its result
+//! (`report`). A strategy, for all tools or per tool, can instead:
+//!
+//! - `retry`: run the call again with the same arguments, up to `attempts`
+//!   more times;
+//! - `correct`: check the arguments against the tool's parameters schema and
+//!   return the violations along with the schema, so the model can fix them;
+//! - `fallback`: call another tool with the same arguments;
+//! - `fail`: stop the run.
+//!
+//! Every strategy applied is reported as a `tool_recovery` event.
+//!
+//! ```toml
+//! [tools.recovery]
+//! default = { strategy = "correct" }
+//!
+//! [tools.recovery.tools]
+//! fetch = { strategy = "retry", attempts = 2, backoff_ms = 500 }
+//! search = { strategy = "fallback", tool = "web_search" }
+//! deploy = { strategy = "fail" }
+//! ```
+
+use std::collections::BTreeMap;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// How a failed call of a tool is handled.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "strategy", rename_all = "snake_case")]
+pub enum ToolRecoveryStrategy {
+    /// Return the error to the model as is.
+    #[default]
+    Report,
+    /// Run the call again with the same arguments.
+    Retry {
+        /// Retries after the first failure.
+        #[serde(default = "default_retry_attempts")]
+        attempts: u32,
+        /// Wait between attempts.
+        #[serde(default)]
+        backoff_ms: u64,
+    },
+    /// Return the error with the schema violations of the arguments.
+    Correct,
+    /// Call `tool` with the same arguments. Its own failure is reported.
+    Fallback { tool: String },
+    /// Fail the run.
+    Fail,
+}
+
+fn default_retry_attempts() -> u32 {
+    1
+}
+
+impl ToolRecoveryStrategy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ToolRecoveryStrategy::Report => "report",
+            ToolRecoveryStrategy::Retry { .. } => "retry",
+            ToolRecoveryStrategy::Correct => "correct",
+            ToolRecoveryStrategy::Fallback { .. } => "fallback",
+            ToolRecoveryStrategy::Fail => "fail",
+        }
+    }
+}
+
+/// `tools.recovery` of an agent definition.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ToolRecoveryConfig {
+    /// Strategy of the tools not listed in `tools`.
+    #[serde(default)]
+    pub default: ToolRecoveryStrategy,
+    /// Tool name → strategy.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub tools: BTreeMap<String, ToolRecoveryStrategy>,
+}
+
+impl ToolRecoveryConfig {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    pub fn strategy_for(&self, tool: &str) -> &ToolRecoveryStrategy {
+        self.tools.get(tool).unwrap_or(&self.default)
+    }
+}
+
+/// Result returned to the model by the `correct` strategy: the error, what
+/// in `input` breaks the tool's `parameters` schema, and the schema itself.
+pub fn correction_message(tool: &str, error: &str, parameters: &Value, input: &Value) -> String {
+    let violations: Vec<String> = match jsonschema::validator_for(parameters) {
+        Ok(validator) => validator
+            .iter_errors(input)
+            .map(|e| format!("- {}", e))
+            .collect(),
+        Err(_) => Vec::new(),
+    };
+    let mut message = format!("Tool '{}' failed: {}\n\n", tool, error);
+    if violations.is_empty() {
+        message.push_str("The arguments match the tool's parameters; check their values.\n");
+    } else {
+        message.push_str("The arguments do not match the tool's parameters:\n");
+        message.push_str(&violations.join("\n"));
+        message.push('\n');
+    }
+    message.push_str(&format!(
+        "\nParameters schema:\n{}\n\nCall '{}' again with corrected arguments.",
+        serde_json::to_string_pretty(parameters).unwrap_or_default(),
+        tool
+    ));
+    message
+}
@@ -0,0 +1,315 @@
+//! Redaction of tool output: `tool_redaction` of the server config.
+//!
+//! The rules run on every tool result before it is added to the
+//! conversation, so tokens and personal data a tool returns do not reach
+//! the LLM provider. `rules` apply to every tool; `tools.<name>` apply to
+//! one tool on top of them. A rule has either a regex `pattern`, whose
+//! matches in the result's text are replaced, or a JSONPath `path`, whose
+//! values in JSON results are replaced. Each redacted span becomes
+//! `[REDACTED:<label>]` (`[REDACTED]` without a label), so the model knows
+//! content was removed.
+//!
+//! ```yaml
+//! tool_redaction:
+//!   rules:
+//!     - pattern: "sk-[A-Za-z0-9]{20,}"
+//!       label: api_key
+//!   tools:
+//!     crm__get_customer:
+//!       - path: "$.customer.email"
+//!         label: email
+//!       - path: "$..phone"
+//! ```
+//!
+//! Paths support `$`, `.key`, `['key']`, `[index]`, `[*]` or `.*`, and
+//! `..key` (the key at any depth).
+
+use std::collections::{BTreeMap, HashMap};
+
+use anyhow::{anyhow, bail};
+use regex::{NoExpand, Regex};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{Part, ToolResponse};
+
+/// `tool_redaction` section of the server config.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ToolRedactionConfig {
+    /// Applied to the output of every tool.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub rules: Vec<RedactionRule>,
+    /// Applied to the output of the named tool, after `rules`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub tools: BTreeMap<String, Vec<RedactionRule>>,
+}
+
+/// One redaction rule: either `pattern` or `path`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct RedactionRule {
+    /// Regex whose matches are redacted, in text and in the strings of JSON
+    /// output.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<String>,
+    /// JSONPath of the values redacted in JSON output.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    /// Shown in the marker: `[REDACTED:<label>]`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+}
+
+impl RedactionRule {
+    /// What a redacted span is replaced with.
+    pub fn marker(&self) -> String {
+        match self.label.as_deref().map(str::trim) {
+            Some(label) if !label.is_empty() => format!("[REDACTED:{}]", label),
+            _ => "[REDACTED]".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum PathSegment {
+    Key(String),
+    Index(usize),
+    Wildcard,
+    /// `..key`: `key` at any depth.
+    Descendant(String),
+}
+
+#[derive(Debug, Clone)]
+enum Matcher {
+    Pattern(Regex),
+    Path(Vec<PathSegment>),
+}
+
+#[derive(Debug, Clone)]
+struct CompiledRule {
+    matcher: Matcher,
+    marker: String,
+}
+
+/// The compiled rules of a [`ToolRedactionConfig`].
+#[derive(Debug, Clone, Default)]
+pub struct ToolRedactor {
+    rules: Vec<CompiledRule>,
+    tools: HashMap<String, Vec<CompiledRule>>,
+}
+
+impl ToolRedactor {
+    /// Fails on a rule without exactly one of `pattern` and `path`, or with
+    /// an invalid one.
+    pub fn new(config: &ToolRedactionConfig) -> anyhow::Result<Self> {
+        let compile_all = |rules: &[RedactionRule], scope: &str| {
+            rules
+                .iter()
+                .enumerate()
+                .map(|(i, rule)| {
+                    compile(rule).map_err(|e| anyhow!("tool_redaction {} rule {}: {}", scope, i, e))
+                })
+                .collect::<anyhow::Result<Vec<_>>>()
+        };
+        let rules = compile_all(&config.rules, "global")?;
+        let tools = config
+            .tools
+            .iter()
+            .map(|(tool, rules)| Ok((tool.clone(), compile_all(rules, tool)?)))
+            .collect::<anyhow::Result<_>>()?;
+        Ok(Self { rules, tools })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty() && self.tools.values().all(Vec::is_empty)
+    }
+
+    /// Redact the parts of `response` in place. Returns the number of spans
+    /// redacted.
+    pub fn redact_response(&self, response: &mut ToolResponse) -> usize {
+        let tool_rules = self.tools.get(&response.tool_name);
+        let rules: Vec<&CompiledRule> = self
+            .rules
+            .iter()
+            .chain(tool_rules.into_iter().flatten())
+            .collect();
+        if rules.is_empty() {
+            return 0;
+        }
+        response
+            .parts
+            .iter_mut()
+            .map(|part| redact_part(part, &rules))
+            .sum()
+    }
+}
+
+fn compile(rule: &RedactionRule) -> anyhow::Result<CompiledRule> {
+    let matcher = match (&rule.pattern, &rule.path) {
+        (Some(pattern), None) => {
+            Matcher::Pattern(Regex::new(pattern).map_err(|e| anyhow!("invalid pattern: {}", e))?)
+        }
+        (None, Some(path)) => Matcher::Path(parse_path(path)?),
+        _ => bail!("set exactly one of `pattern` and `path`"),
+    };
+    Ok(CompiledRule {
+        matcher,
+        marker: rule.marker(),
+    })
+}
+
+fn parse_path(path: &str) -> anyhow::Result<Vec<PathSegment>> {
+    let invalid = |why: &str| anyhow!("invalid path '{}': {}", path, why);
+    let Some(mut rest) = path.trim().strip_prefix('$') else {
+        return Err(invalid("it must start with `$`"));
+    };
+    let mut segments = Vec::new();
+    while !rest.is_empty() {
+        if let Some(after) = rest.strip_prefix("..") {
+            let (key, tail) = split_key(after);
+            if key.is_empty() {
+                return Err(invalid("`..` must be followed by a key"));
+            }
+            segments.push(PathSegment::Descendant(key.to_string()));
+            rest = tail;
+        } else if let Some(after) = rest.strip_prefix('.') {
+            let (key, tail) = split_key(after);
+            segments.push(match key {
+                "" => return Err(invalid("`.` must be followed by a key")),
+                "*" => PathSegment::Wildcard,
+                key => PathSegment::Key(key.to_string()),
+            });
+            rest = tail;
+        } else if let Some(after) = rest.strip_prefix('[') {
+            let (inner, tail) = after
+                .split_once(']')
+                .ok_or_else(|| invalid("unclosed `[`"))?;
+            let inner = inner.trim();
+            let quoted = inner
+                .strip_prefix('\'')
+                .and_then(|s| s.strip_suffix('\''))
+                .or_else(|| inner.strip_prefix('"').and_then(|s| s.strip_suffix('"')));
+            segments.push(if inner == "*" {
+                PathSegment::Wildcard
+            } else if let Some(key) = quoted {
+                PathSegment::Key(key.to_string())
+            } else {
+                PathSegment::Index(
+                    inner
+                        .parse()
+                        .map_err(|_| invalid("brackets hold an index, a quoted key or `*`"))?,
+                )
+            });
+            rest = tail;
+        } else {
+            return Err(invalid("expected `.` or `[`"));
+        }
+    }
+    if segments.is_empty() {
+        return Err(invalid("it selects the whole output"));
+    }
+    Ok(segments)
+}
+
+/// The key at the start of `text` and what follows it.
+fn split_key(text: &str) -> (&str, &str) {
+    let end = text.find(['.', '[']).unwrap_or(text.len());
+    text.split_at(end)
+}
+
+fn redact_part(part: &mut Part, rules: &[&CompiledRule]) -> usize {
+    match part {
+        Part::Text(text) => {
+            let mut count = 0;
+            if rules.iter().any(|r| matches!(r.matcher, Matcher::Path(_)))
+                && let Ok(mut value) = serde_json::from_str::<Value>(text)
+                && (value.is_object() || value.is_array())
+            {
+                let redacted = redact_paths(&mut value, rules);
+                if redacted > 0 {
+                    *text = value.to_string();
+                    count += redacted;
+                }
+            }
+            count + redact_patterns(text, rules)
+        }
+        Part::Data(value) => redact_paths(value, rules) + redact_strings(value, rules),
+        _ => 0,
+    }
+}
+
+fn redact_paths(value: &mut Value, rules: &[&CompiledRule]) -> usize {
+    rules
+        .iter()
+        .map(|rule| match &rule.matcher {
+            Matcher::Path(segments) => redact_at(value, segments, &rule.marker),
+            Matcher::Pattern(_) => 0,
+        })
+        .sum()
+}
+
+fn redact_at(value: &mut Value, segments: &[PathSegment], marker: &str) -> usize {
+    let Some((first, rest)) = segments.split_first() else {
+        if value.as_str() == Some(marker) {
+            return 0;
+        }
+        *value = Value::String(marker.to_string());
+        return 1;
+    };
+    match (first, value) {
+        (PathSegment::Key(key), Value::Object(map)) => map
+            .get_mut(key)
+            .map_or(0, |child| redact_at(child, rest, marker)),
+        (PathSegment::Index(index), Value::Array(items)) => items
+            .get_mut(*index)
+            .map_or(0, |child| redact_at(child, rest, marker)),
+        (PathSegment::Wildcard, Value::Object(map)) => map
+            .values_mut()
+            .map(|child| redact_at(child, rest, marker))
+            .sum(),
+        (PathSegment::Wildcard, Value::Array(items)) => items
+            .iter_mut()
+            .map(|child| redact_at(child, rest, marker))
+            .sum(),
+        (PathSegment::Descendant(key), Value::Object(map)) => {
+            let mut count = map
+                .get_mut(key)
+                .map_or(0, |child| redact_at(child, rest, marker));
+            for child in map.values_mut() {
+                count += redact_at(child, segments, marker);
+            }
+            count
+        }
+        (PathSegment::Descendant(_), Value::Array(items)) => items
+            .iter_mut()
+            .map(|child| redact_at(child, segments, marker))
+            .sum(),
+        _ => 0,
+    }
+}
+
+fn redact_strings(value: &mut Value, rules: &[&CompiledRule]) -> usize {
+    match value {
+        Value::String(text) => redact_patterns(text, rules),
+        Value::Array(items) => items.iter_mut().map(|v| redact_strings(v, rules)).sum(),
+        Value::Object(map) => map.values_mut().map(|v| redact_strings(v, rules)).sum(),
+        _ => 0,
+    }
+}
+
+fn redact_patterns(text: &mut String, rules: &[&CompiledRule]) -> usize {
+    let mut count = 0;
+    for rule in rules {
+        let Matcher::Pattern(regex) = &rule.matcher else {
+            continue;
+        };
+        let matches = regex.find_iter(text).count();
+        if matches > 0 {
+            *text = regex.replace_all(text, NoExpand(&rule.marker)).into_owned();
+            count += matches;
+        }
+    }
+    count
+}
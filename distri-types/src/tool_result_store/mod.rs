@@ -9,6 +9,7 @@
 //! - Format-specific previews (markdown headings, JSON structure, CSV headers)
 //! - Binary file detection
 //! - LRU file read cache with FILE_UNCHANGED_STUB deduplication
+//! - Per-task memoization of repeated identical tool calls
 
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, VecDeque};
@@ -32,6 +33,10 @@ pub const MAX_TOOL_RESULTS_PER_MESSAGE_CHARS: usize = 200_000;
 pub const FILE_UNCHANGED_STUB: &str =
     "[File content unchanged since last read — using cached version]";
 
+/// Marker prepended to a tool result served from the per-task tool call cache
+pub const TOOL_CALL_CACHED_MARKER: &str =
+    "[cached — identical call already made in this task, returning its earlier result]";
+
 // ── Content Format Detection ─────────────────────────────────────────────────
 
 /// Detected content format for format-specific preview generation
@@ -591,6 +596,94 @@ impl FileReadCache {
     }
 }
 
+// ── Tool Call Cache (LRU) ────────────────────────────────────────────────────
+
+/// LRU cache of tool results for memoizing repeated identical calls within a task.
+///
+/// Entries are keyed by tool name + input, with object keys sorted so the
+/// same arguments in a different order hit the same entry. Only successful
+/// results of memoizable tools are recorded.
+#[derive(Debug, Clone, Default)]
+pub struct ToolCallCache {
+    entries: HashMap<String, Vec<crate::Part>>,
+    access_order: VecDeque<String>,
+    max_entries: usize,
+}
+
+impl ToolCallCache {
+    pub fn new(max_entries: usize) -> Self {
+        Self {
+            entries: HashMap::new(),
+            access_order: VecDeque::new(),
+            max_entries,
+        }
+    }
+
+    /// The call as canonical JSON (`["tool", input]` with object keys
+    /// sorted), so the same arguments in a different order give the same key
+    /// and different calls never share one.
+    pub fn call_key(tool_name: &str, input: &serde_json::Value) -> String {
+        serde_json::Value::Array(vec![tool_name.into(), canonical_json(input)]).to_string()
+    }
+
+    /// Result of an earlier identical call, if any.
+    pub fn get(&mut self, tool_name: &str, input: &serde_json::Value) -> Option<Vec<crate::Part>> {
+        let key = Self::call_key(tool_name, input);
+        let parts = self.entries.get(&key)?.clone();
+        self.access_order.retain(|k| *k != key);
+        self.access_order.push_front(key);
+        Some(parts)
+    }
+
+    /// Record the result of a call.
+    pub fn record(&mut self, tool_name: &str, input: &serde_json::Value, parts: Vec<crate::Part>) {
+        let key = Self::call_key(tool_name, input);
+        self.access_order.retain(|k| *k != key);
+        self.access_order.push_front(key.clone());
+        self.entries.insert(key, parts);
+
+        while self.entries.len() > self.max_entries {
+            if let Some(old_key) = self.access_order.pop_back() {
+                self.entries.remove(&old_key);
+            }
+        }
+    }
+
+    /// Drop every entry (called after a tool that may have changed state).
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.access_order.clear();
+    }
+
+    /// Number of entries in the cache
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether the cache is empty
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+/// `value` with the keys of every object inserted in sorted order.
+fn canonical_json(value: &serde_json::Value) -> serde_json::Value {
+    use serde_json::Value;
+    match value {
+        Value::Array(items) => Value::Array(items.iter().map(canonical_json).collect()),
+        Value::Object(map) => {
+            let mut keys: Vec<&String> = map.keys().collect();
+            keys.sort();
+            Value::Object(
+                keys.into_iter()
+                    .map(|key| (key.clone(), canonical_json(&map[key])))
+                    .collect(),
+            )
+        }
+        other => other.clone(),
+    }
+}
+
 // ── Content Replacement State ────────────────────────────────────────────────
 
 /// Tracks which tool results have been replaced with persisted previews.
@@ -306,6 +306,75 @@ fn cache_lru_access_refreshes_order() {
     assert_eq!(cache.check("/b.rs", None, None, Some(2)), CacheCheck::Miss);
 }
 
+// ── Tool Call Cache ──────────────────────────────────────────────────────────
+
+#[test]
+fn tool_call_cache_hits_identical_calls() {
+    let mut cache = ToolCallCache::new(10);
+    let input = serde_json::json!({"query": "rust lru", "limit": 5});
+    assert!(cache.get("search", &input).is_none());
+
+    cache.record("search", &input, vec![crate::Part::Text("results".into())]);
+    let cached = cache.get("search", &input).unwrap();
+    assert_eq!(cached, vec![crate::Part::Text("results".into())]);
+
+    // Same arguments to a different tool, or different arguments, miss.
+    assert!(cache.get("scrape", &input).is_none());
+    let other = serde_json::json!({"query": "rust lru", "limit": 6});
+    assert!(cache.get("search", &other).is_none());
+}
+
+#[test]
+fn tool_call_cache_key_ignores_object_key_order() {
+    let a: serde_json::Value =
+        serde_json::from_str(r#"{"q": "x", "opts": {"a": 1, "b": [1, 2]}}"#).unwrap();
+    let b: serde_json::Value =
+        serde_json::from_str(r#"{"opts": {"b": [1, 2], "a": 1}, "q": "x"}"#).unwrap();
+    assert_eq!(
+        ToolCallCache::call_key("search", &a),
+        ToolCallCache::call_key("search", &b)
+    );
+
+    let reordered_array: serde_json::Value =
+        serde_json::from_str(r#"{"q": "x", "opts": {"a": 1, "b": [2, 1]}}"#).unwrap();
+    assert_ne!(
+        ToolCallCache::call_key("search", &a),
+        ToolCallCache::call_key("search", &reordered_array)
+    );
+}
+
+#[test]
+fn tool_call_cache_key_is_the_whole_call() {
+    // Values that only differ in type or in where a string ends must not
+    // share an entry.
+    let mut cache = ToolCallCache::new(10);
+    cache.record("t", &serde_json::json!({"n": 1}), vec![]);
+    assert!(cache.get("t", &serde_json::json!({"n": "1"})).is_none());
+    assert!(cache.get("t", &serde_json::json!({"n": 1.0})).is_none());
+    assert_ne!(
+        ToolCallCache::call_key("a\",b", &serde_json::json!("c")),
+        ToolCallCache::call_key("a", &serde_json::json!("b\",\"c"))
+    );
+}
+
+#[test]
+fn tool_call_cache_evicts_least_recently_used() {
+    let mut cache = ToolCallCache::new(2);
+    let call = |n: u32| serde_json::json!({ "n": n });
+    cache.record("t", &call(1), vec![]);
+    cache.record("t", &call(2), vec![]);
+    // Touch 1 so 2 is the oldest.
+    assert!(cache.get("t", &call(1)).is_some());
+    cache.record("t", &call(3), vec![]);
+
+    assert_eq!(cache.len(), 2);
+    assert!(cache.get("t", &call(2)).is_none());
+    assert!(cache.get("t", &call(1)).is_some());
+
+    cache.clear();
+    assert!(cache.is_empty());
+}
+
 // ── Content Replacement State ────────────────────────────────────────────────
 
 #[test]
@@ -0,0 +1,71 @@
+//! Time limits of tool calls: `tools.timeouts` of an agent definition.
+//!
+//! A call still running at its limit is abandoned. The model gets a
+//! [`ToolTimedOut`] result it can act on, and a `tool_timeout` event marks
+//! the call, so a slow MCP server shows up instead of stalling the run.
+//! `tools.recovery` then applies as to any other failed call.
+//!
+//! Without a `default_secs`, tools not listed run without a limit; `0`
+//! lifts the limit of one tool.
+//!
+//! ```toml
+//! [tools.timeouts]
+//! default_secs = 60
+//!
+//! [tools.timeouts.tools]
+//! web_search = 15
+//! run_migrations = 0
+//! ```
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// `tools.timeouts` of an agent definition.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ToolTimeoutConfig {
+    /// Limit in seconds of the tools not listed in `tools`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_secs: Option<u64>,
+    /// Tool name → limit in seconds; `0` for no limit.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub tools: BTreeMap<String, u64>,
+}
+
+impl ToolTimeoutConfig {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// How long a call of `tool` may run, if it is limited.
+    pub fn timeout_for(&self, tool: &str) -> Option<Duration> {
+        let secs = self.tools.get(tool).copied().or(self.default_secs)?;
+        (secs > 0).then(|| Duration::from_secs(secs))
+    }
+}
+
+/// A tool call abandoned at its time limit.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, thiserror::Error)]
+#[error("tool '{tool}' did not finish within {timeout_secs}s")]
+pub struct ToolTimedOut {
+    pub tool: String,
+    pub timeout_secs: u64,
+}
+
+impl ToolTimedOut {
+    /// The tool result reported to the model.
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "error": "tool_timeout",
+            "tool": self.tool,
+            "timeout_secs": self.timeout_secs,
+            "message": format!(
+                "{}. It may be slow or unavailable; retry with a narrower request, use another tool, or continue without it.",
+                self
+            ),
+        })
+    }
+}
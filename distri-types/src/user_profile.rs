@@ -0,0 +1,246 @@
+//! User profiles: how a user likes to be answered — tone, language,
+//! expertise, timezone and answer format — kept per user so agents stop
+//! asking.
+//!
+//! Profiles are edited through `/v1/users/me/profile` or `distri profile`
+//! and stored in the session store under [`profile_namespace`]. A run sees
+//! the profile of its user, with the thread's own `user_profile` session
+//! value, when set, overriding single fields. Agents that list `user_profile`
+//! in `[prompt_layers] runtime` get it in their system prompt; its `timezone`
+//! and `language` also pick the `[datetime]` timezone and localized prompt
+//! templates.
+//!
+//! ```json
+//! {
+//!   "tone": "concise and direct",
+//!   "language": "de",
+//!   "expertise": "expert",
+//!   "timezone": "Europe/Berlin",
+//!   "format": "bullet points, code in fenced blocks",
+//!   "custom": { "units": "metric" }
+//! }
+//! ```
+
+use std::collections::BTreeMap;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use utoipa::ToSchema;
+
+/// Session store key of a stored profile.
+pub const PROFILE_KEY: &str = "profile";
+
+/// Longest value of a single preference.
+pub const MAX_PREFERENCE_LEN: usize = 500;
+
+/// Session store namespace of `user_id`'s profile.
+pub fn profile_namespace(user_id: &str) -> String {
+    format!("user:{}", user_id)
+}
+
+/// A user's preferences. Every field is optional.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct UserProfile {
+    /// How answers should sound, e.g. "concise and direct".
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tone: Option<String>,
+    /// Language to answer in, as a locale tag such as `de` or `pt-BR`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expertise: Option<ExpertiseLevel>,
+    /// IANA timezone, e.g. `Europe/Berlin`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timezone: Option<String>,
+    /// How answers should be formatted, e.g. "bullet points, no headings".
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub format: Option<String>,
+    /// Further preferences, by name.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub custom: BTreeMap<String, String>,
+}
+
+/// How much the user knows about the subject at hand.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExpertiseLevel {
+    Beginner,
+    Intermediate,
+    Expert,
+}
+
+impl ExpertiseLevel {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ExpertiseLevel::Beginner => "beginner",
+            ExpertiseLevel::Intermediate => "intermediate",
+            ExpertiseLevel::Expert => "expert",
+        }
+    }
+}
+
+impl std::str::FromStr for ExpertiseLevel {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "beginner" => Ok(ExpertiseLevel::Beginner),
+            "intermediate" => Ok(ExpertiseLevel::Intermediate),
+            "expert" => Ok(ExpertiseLevel::Expert),
+            other => Err(format!(
+                "unknown expertise '{}': use beginner, intermediate or expert",
+                other
+            )),
+        }
+    }
+}
+
+impl UserProfile {
+    pub fn is_empty(&self) -> bool {
+        *self == UserProfile::default()
+    }
+
+    /// The profile as it is stored: checked, with `language` as a canonical
+    /// locale tag.
+    pub fn validated(mut self) -> Result<Self, String> {
+        if let Some(language) = &self.language {
+            self.language = Some(crate::prompt_locale::normalize_locale(language).ok_or_else(
+                || {
+                    format!(
+                        "unknown language '{}': use a locale tag such as de or pt-BR",
+                        language
+                    )
+                },
+            )?);
+        }
+        if let Some(timezone) = &self.timezone {
+            crate::datetime::parse_timezone(timezone)?;
+        }
+        let texts = [("tone", &self.tone), ("format", &self.format)]
+            .into_iter()
+            .filter_map(|(name, value)| value.as_deref().map(|v| (name, v)))
+            .chain(self.custom.iter().map(|(k, v)| (k.as_str(), v.as_str())));
+        for (name, value) in texts {
+            if value.len() > MAX_PREFERENCE_LEN {
+                return Err(format!(
+                    "'{}' is longer than {} bytes",
+                    name, MAX_PREFERENCE_LEN
+                ));
+            }
+        }
+        Ok(self)
+    }
+
+    /// Take the fields `patch` sets; an empty string clears a field.
+    pub fn merge(&mut self, patch: UserProfile) {
+        fn take(field: &mut Option<String>, value: Option<String>) {
+            match value {
+                Some(v) if v.is_empty() => *field = None,
+                Some(v) => *field = Some(v),
+                None => {}
+            }
+        }
+        take(&mut self.tone, patch.tone);
+        take(&mut self.language, patch.language);
+        take(&mut self.timezone, patch.timezone);
+        take(&mut self.format, patch.format);
+        if patch.expertise.is_some() {
+            self.expertise = patch.expertise;
+        }
+        for (key, value) in patch.custom {
+            if value.is_empty() {
+                self.custom.remove(&key);
+            } else {
+                self.custom.insert(key, value);
+            }
+        }
+    }
+
+    /// Set one preference by name, as `distri profile set name=value` does.
+    /// Names other than the profile's fields are custom preferences; an
+    /// empty value clears.
+    pub fn set(&mut self, name: &str, value: &str) -> Result<(), String> {
+        let value = value.trim().to_string();
+        let mut patch = UserProfile::default();
+        match name.trim() {
+            "tone" => patch.tone = Some(value),
+            "language" => patch.language = Some(value),
+            "timezone" => patch.timezone = Some(value),
+            "format" => patch.format = Some(value),
+            "expertise" if value.is_empty() => {
+                self.expertise = None;
+                return Ok(());
+            }
+            "expertise" => patch.expertise = Some(value.parse()?),
+            "" => return Err("preference name is empty".to_string()),
+            custom => {
+                patch.custom.insert(custom.to_string(), value);
+            }
+        }
+        self.merge(patch);
+        Ok(())
+    }
+}
+
+/// The `user_profile` of a run: the user's stored profile with the fields
+/// of the thread's `user_profile` session value on top. A thread value that
+/// is not an object replaces the stored profile.
+pub fn effective_profile(stored: Option<&UserProfile>, thread: Option<&Value>) -> Option<Value> {
+    let stored = stored
+        .filter(|p| !p.is_empty())
+        .and_then(|p| serde_json::to_value(p).ok());
+    match (stored, thread) {
+        (Some(Value::Object(mut stored)), Some(Value::Object(thread))) => {
+            stored.extend(thread.iter().map(|(k, v)| (k.clone(), v.clone())));
+            Some(Value::Object(stored))
+        }
+        (_, Some(thread)) => Some(thread.clone()),
+        (stored, None) => stored,
+    }
+}
+
+/// Labels of the profile fields in the system prompt, in display order.
+const FIELD_LABELS: &[(&str, &str)] = &[
+    ("tone", "Tone"),
+    ("language", "Language"),
+    ("expertise", "Expertise"),
+    ("timezone", "Timezone"),
+    ("format", "Answer format"),
+];
+
+/// A `user_profile` value as the runtime layer of the system prompt shows
+/// it: one `- Label: value` line per preference.
+pub fn describe_profile(profile: &Value) -> String {
+    let Value::Object(fields) = profile else {
+        return match profile {
+            Value::String(text) => text.clone(),
+            other => other.to_string(),
+        };
+    };
+    let text = |value: &Value| match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    };
+    let known = FIELD_LABELS
+        .iter()
+        .filter_map(|(key, label)| fields.get(*key).map(|v| (label.to_string(), text(v))));
+    let custom = fields
+        .get("custom")
+        .and_then(Value::as_object)
+        .into_iter()
+        .flatten()
+        .map(|(k, v)| (k.clone(), text(v)));
+    let others = fields
+        .iter()
+        .filter(|(k, _)| *k != "custom" && !FIELD_LABELS.iter().any(|(key, _)| *key == k.as_str()))
+        .map(|(k, v)| (k.clone(), text(v)));
+    known
+        .chain(custom)
+        .chain(others)
+        .filter(|(_, v)| !v.is_empty() && v != "null")
+        .map(|(label, value)| format!("- {}: {}", label, value))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
@@ -0,0 +1,170 @@
+//! Per-user quotas: `user_quotas` of the server config.
+//!
+//! Admission control caps the runs of the whole server, so one user can
+//! still take all of it. With `user_quotas` set, each user is limited to
+//! `max_concurrent_tasks` runs at once, `max_tasks_per_day` runs and
+//! `max_tokens_per_day` model tokens per UTC day. A `message/send` or
+//! `message/stream` over a quota is answered with `429` and a JSON-RPC error
+//! whose `data` is a [`QuotaExceeded`]. Tokens are counted as the models
+//! report them, so a run that starts under the token quota finishes even if
+//! it ends over it. See `distri_core::agent::user_quotas`.
+//!
+//! `GET /admin/users/{user_id}/quota` shows a user's usage; a `POST` of a
+//! [`RaiseQuotaRequest`] to it raises the user's quotas for a while.
+//!
+//! ```yaml
+//! user_quotas:
+//!   max_concurrent_tasks: 2
+//!   max_tasks_per_day: 200
+//!   max_tokens_per_day: 2000000
+//! ```
+
+use chrono::{DateTime, Duration, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// `user_quotas` section of the server config. Unset limits are not
+/// enforced.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct UserQuotaLimits {
+    /// Most runs of one user executing at once.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_concurrent_tasks: Option<usize>,
+    /// Most runs one user starts per UTC day.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_tasks_per_day: Option<u64>,
+    /// Most model tokens (input and output) one user's runs use per UTC day.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_tokens_per_day: Option<u64>,
+}
+
+impl UserQuotaLimits {
+    /// The limits with `raise` added. A raise does not limit what is
+    /// unlimited.
+    pub fn raised(&self, raise: &QuotaRaise) -> Self {
+        Self {
+            max_concurrent_tasks: self
+                .max_concurrent_tasks
+                .map(|max| max.saturating_add(raise.extra_concurrent_tasks)),
+            max_tasks_per_day: self
+                .max_tasks_per_day
+                .map(|max| max.saturating_add(raise.extra_tasks_per_day)),
+            max_tokens_per_day: self
+                .max_tokens_per_day
+                .map(|max| max.saturating_add(raise.extra_tokens_per_day)),
+        }
+    }
+}
+
+/// One of the quotas of [`UserQuotaLimits`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QuotaKind {
+    ConcurrentTasks,
+    TasksPerDay,
+    TokensPerDay,
+}
+
+impl QuotaKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            QuotaKind::ConcurrentTasks => "concurrent_tasks",
+            QuotaKind::TasksPerDay => "tasks_per_day",
+            QuotaKind::TokensPerDay => "tokens_per_day",
+        }
+    }
+}
+
+/// A run refused because its user is over a quota; the `data` of the
+/// JSON-RPC error.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema, ToSchema)]
+pub struct QuotaExceeded {
+    pub user_id: String,
+    pub quota: QuotaKind,
+    /// The limit in effect, raise included.
+    pub limit: u64,
+    pub used: u64,
+    /// When a daily quota starts over. `None` for `concurrent_tasks`, which
+    /// frees up as the user's runs finish.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resets_at: Option<DateTime<Utc>>,
+}
+
+impl std::fmt::Display for QuotaExceeded {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "user '{}' is over the {} quota ({} of {})",
+            self.user_id,
+            self.quota.as_str(),
+            self.used,
+            self.limit
+        )
+    }
+}
+
+/// A temporary raise of a user's quotas, added to the configured limits.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema, ToSchema)]
+pub struct QuotaRaise {
+    #[serde(default)]
+    pub extra_concurrent_tasks: usize,
+    #[serde(default)]
+    pub extra_tasks_per_day: u64,
+    #[serde(default)]
+    pub extra_tokens_per_day: u64,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Body of `POST /admin/users/{user_id}/quota`. Replaces the user's
+/// current raise, if any.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct RaiseQuotaRequest {
+    #[serde(default)]
+    pub extra_concurrent_tasks: usize,
+    #[serde(default)]
+    pub extra_tasks_per_day: u64,
+    #[serde(default)]
+    pub extra_tokens_per_day: u64,
+    /// How long the raise lasts.
+    pub duration_secs: u64,
+}
+
+impl RaiseQuotaRequest {
+    /// The raise, lasting `duration_secs` from `now`.
+    pub fn raise_at(&self, now: DateTime<Utc>) -> QuotaRaise {
+        let expires_at =
+            Duration::try_seconds(i64::try_from(self.duration_secs).unwrap_or(i64::MAX))
+                .and_then(|duration| now.checked_add_signed(duration))
+                .unwrap_or(DateTime::<Utc>::MAX_UTC);
+        QuotaRaise {
+            extra_concurrent_tasks: self.extra_concurrent_tasks,
+            extra_tasks_per_day: self.extra_tasks_per_day,
+            extra_tokens_per_day: self.extra_tokens_per_day,
+            expires_at,
+        }
+    }
+}
+
+/// Response of `GET /admin/users/{user_id}/quota`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema, ToSchema)]
+pub struct UserQuotaStatus {
+    pub user_id: String,
+    pub running_tasks: usize,
+    pub tasks_today: u64,
+    pub tokens_today: u64,
+    /// The limits in effect, raise included.
+    pub limits: UserQuotaLimits,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub raise: Option<QuotaRaise>,
+    /// When the daily counts start over.
+    pub resets_at: DateTime<Utc>,
+}
+
+/// The UTC midnight after `now`, when daily quotas start over.
+pub fn next_reset(now: DateTime<Utc>) -> DateTime<Utc> {
+    let tomorrow = now.date_naive() + Duration::days(1);
+    tomorrow.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc()
+}
@@ -0,0 +1,70 @@
+//! Warm sessions: threads whose assembled agent stays in memory.
+//!
+//! Every message normally assembles its agent's tool registry from scratch
+//! (tool resolution, MCP discovery, collision handling). With
+//! `warm_sessions` configured, the server keeps the registry of the most
+//! recently used threads in an LRU of `capacity` entries, so rapid
+//! back-and-forth on a thread skips that work. Pinned threads
+//! (`POST /threads/{id}/warm`) are never evicted. See
+//! `distri_core::agent::warm_sessions`.
+//!
+//! ```yaml
+//! warm_sessions:
+//!   capacity: 64
+//! ```
+
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// `warm_sessions` section of the server config.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct WarmSessionsConfig {
+    /// Most threads kept warm. Pinned threads count towards it but are
+    /// never evicted.
+    #[serde(default = "default_capacity")]
+    pub capacity: usize,
+}
+
+fn default_capacity() -> usize {
+    64
+}
+
+impl Default for WarmSessionsConfig {
+    fn default() -> Self {
+        Self {
+            capacity: default_capacity(),
+        }
+    }
+}
+
+/// One thread in the warm session cache.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema, JsonSchema)]
+pub struct WarmSession {
+    pub thread_id: String,
+    /// Agent whose registry is kept. `None` for a pinned thread that has
+    /// not run since it was pinned.
+    pub agent_id: Option<String>,
+    pub pinned: bool,
+    /// How long assembling the registry took, i.e. what each hit saves.
+    pub build_ms: u64,
+    /// Messages served from the warm registry.
+    pub hits: u64,
+    pub last_used: DateTime<Utc>,
+}
+
+/// Response of `GET /warm-sessions`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema, JsonSchema)]
+pub struct WarmSessionsStatus {
+    pub capacity: usize,
+    /// Runs that reused a warm registry.
+    pub hits: u64,
+    /// Runs that had to assemble one.
+    pub misses: u64,
+    /// Assembly time skipped by all hits.
+    pub saved_ms: u64,
+    /// Most recently used first.
+    pub sessions: Vec<WarmSession>,
+}
@@ -0,0 +1,425 @@
+//! Versioned schema of the workspace config file (`distri.yaml`).
+//!
+//! A file declares its schema with an integer top-level `version`; files
+//! without one are version 1, the layout the old `distri.toml` used. Each
+//! version has a migration from the one before it, applied to the raw YAML
+//! before it is deserialized, so older files keep loading instead of having
+//! their keys silently dropped. The server warns about deprecated and
+//! unknown keys at startup, and `distri config migrate` rewrites the file at
+//! [`CURRENT_VERSION`], keeping comments wherever the edits allow.
+//!
+//! `profiles` holds named overrides of the other keys, e.g. a `prod` profile
+//! with its own `default_model`, `stores` and `mcp_servers`. The profile is
+//! picked with `--profile` or [`PROFILE_ENV`] and merged over the rest of
+//! the file by [`apply_profile`]; `distri config show --profile <name>`
+//! prints the result.
+
+use serde_yaml::{Mapping, Value};
+
+use crate::AgentError;
+
+/// Schema version written by `distri config migrate`.
+pub const CURRENT_VERSION: u32 = 2;
+
+/// Env var selecting the profile when `--profile` is not given.
+pub const PROFILE_ENV: &str = "DISTRI_PROFILE";
+
+/// Top-level keys of the current schema.
+pub const KNOWN_KEYS: &[&str] = &[
+    "version",
+    "profiles",
+    "model_providers",
+    "model_providers_path",
+    "default_model",
+    "agents",
+    "llm_audit",
+    "sql_connections",
+    "background_jobs",
+    "prompt_policy",
+    "agent_registry",
+    "k8s",
+    "crawl",
+    "calendar",
+    "mcp_servers",
+    "hibernation",
+    "python_exec",
+    "embeddings",
+    "warm_sessions",
+    "thread_archive",
+    "tool_redaction",
+    "admission",
+    "user_quotas",
+    "event_export",
+    "capability_probe",
+    "egress",
+    "stores",
+    "secrets",
+];
+
+/// A top-level key an older schema version used.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DeprecatedKey {
+    pub key: &'static str,
+    /// The key that replaces it, if any.
+    pub replacement: Option<&'static str>,
+    /// How to carry the setting over.
+    pub hint: &'static str,
+}
+
+/// Keys removed by a migration, with their replacements.
+pub const DEPRECATED_KEYS: &[DeprecatedKey] = &[
+    DeprecatedKey {
+        key: "model_settings",
+        replacement: Some("default_model"),
+        hint: "set `default_model: provider/model`",
+    },
+    DeprecatedKey {
+        key: "analysis_model_settings",
+        replacement: None,
+        hint: "set `model_settings` on the agents that need a different model",
+    },
+    DeprecatedKey {
+        key: "name",
+        replacement: None,
+        hint: "the workspace name is not part of the server config",
+    },
+];
+
+/// One edit a migration made to the top level of the file.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConfigChange {
+    Removed {
+        key: String,
+        reason: String,
+    },
+    /// `key` was set to `value`, taking the place of `replaces` if given.
+    Set {
+        key: String,
+        value: Value,
+        replaces: Option<String>,
+    },
+}
+
+/// The outcome of [`migrate`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Migration {
+    pub from_version: u32,
+    pub to_version: u32,
+    pub changes: Vec<ConfigChange>,
+}
+
+impl Migration {
+    pub fn is_noop(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
+type MigrationStep = fn(&mut Mapping, &mut Vec<ConfigChange>);
+
+/// `STEPS[i]` upgrades version `i + 1` to `i + 2`.
+const STEPS: &[MigrationStep] = &[v1_to_v2];
+
+/// The schema version `config` declares.
+pub fn version_of(config: &Value) -> u32 {
+    config
+        .get("version")
+        .and_then(Value::as_u64)
+        .map_or(1, |v| v as u32)
+}
+
+/// Upgrade `config` in place to [`CURRENT_VERSION`].
+pub fn migrate(config: &mut Value) -> Result<Migration, AgentError> {
+    if config.is_null() {
+        *config = Value::Mapping(Mapping::new());
+    }
+    let from_version = version_of(config);
+    if from_version > CURRENT_VERSION {
+        return Err(AgentError::Validation(format!(
+            "config schema version {} is newer than this build supports ({})",
+            from_version, CURRENT_VERSION
+        )));
+    }
+    let Value::Mapping(map) = config else {
+        return Err(AgentError::Validation(
+            "config must be a mapping of keys to values".to_string(),
+        ));
+    };
+
+    let mut changes = Vec::new();
+    for step in STEPS.iter().skip(from_version.saturating_sub(1) as usize) {
+        step(map, &mut changes);
+    }
+    if from_version < CURRENT_VERSION {
+        let value = Value::from(CURRENT_VERSION);
+        map.insert(Value::from("version"), value.clone());
+        changes.push(ConfigChange::Set {
+            key: "version".to_string(),
+            value,
+            replaces: None,
+        });
+    }
+    Ok(Migration {
+        from_version,
+        to_version: CURRENT_VERSION,
+        changes,
+    })
+}
+
+/// Deprecated keys present in `config`.
+pub fn deprecated_keys(config: &Value) -> Vec<&'static DeprecatedKey> {
+    DEPRECATED_KEYS
+        .iter()
+        .filter(|d| config.get(d.key).is_some())
+        .collect()
+}
+
+/// Top-level keys of `config` the current schema does not know. Run it on a
+/// migrated config; deprecated keys are reported by [`deprecated_keys`].
+pub fn unknown_keys(config: &Value) -> Vec<String> {
+    let Value::Mapping(map) = config else {
+        return Vec::new();
+    };
+    map.keys()
+        .filter_map(Value::as_str)
+        .filter(|k| !KNOWN_KEYS.contains(k))
+        .map(str::to_string)
+        .collect()
+}
+
+/// Names of the profiles `config` defines.
+pub fn profile_names(config: &Value) -> Vec<String> {
+    match config.get("profiles") {
+        Some(Value::Mapping(profiles)) => profiles
+            .keys()
+            .filter_map(Value::as_str)
+            .map(str::to_string)
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Replace `config` with its effective settings under `profile`: the
+/// `profiles` key is dropped and the named profile, if any, is merged over
+/// the rest. Mappings merge key by key; any other value, lists included,
+/// replaces the one it overrides, and `null` removes it. Run it on a
+/// migrated config.
+pub fn apply_profile(config: &mut Value, profile: Option<&str>) -> Result<(), AgentError> {
+    let names = profile_names(config);
+    let profiles = match config {
+        Value::Mapping(map) => map.remove("profiles"),
+        _ => None,
+    };
+    let Some(name) = profile else {
+        return Ok(());
+    };
+    let overrides = match profiles {
+        Some(Value::Mapping(mut profiles)) => profiles.remove(name),
+        _ => None,
+    };
+    let Some(overrides) = overrides else {
+        return Err(AgentError::Validation(if names.is_empty() {
+            format!(
+                "profile `{}` not found: the config defines no profiles",
+                name
+            )
+        } else {
+            format!(
+                "profile `{}` not found; available profiles: {}",
+                name,
+                names.join(", ")
+            )
+        }));
+    };
+    match &overrides {
+        Value::Mapping(keys) => {
+            if let Some(key) = ["version", "profiles"]
+                .into_iter()
+                .find(|key| keys.contains_key(*key))
+            {
+                return Err(AgentError::Validation(format!(
+                    "profile `{}` cannot set `{}`",
+                    name, key
+                )));
+            }
+        }
+        Value::Null => {}
+        _ => {
+            return Err(AgentError::Validation(format!(
+                "profile `{}` must be a mapping of keys to values",
+                name
+            )));
+        }
+    }
+    merge(config, overrides);
+    Ok(())
+}
+
+fn merge(base: &mut Value, overrides: Value) {
+    match (base, overrides) {
+        (Value::Mapping(base), Value::Mapping(overrides)) => {
+            for (key, value) in overrides {
+                if value.is_null() {
+                    base.remove(&key);
+                } else if let Some(existing) = base.get_mut(&key) {
+                    merge(existing, value);
+                } else {
+                    base.insert(key, value);
+                }
+            }
+        }
+        (_, Value::Null) => {}
+        (base, value) => *base = value,
+    }
+}
+
+/// Version 1 is the `distri.toml` layout: a workspace `name` and package
+/// `version` string, and full `model_settings` blocks.
+fn v1_to_v2(map: &mut Mapping, changes: &mut Vec<ConfigChange>) {
+    let mut remove = |map: &mut Mapping, key: &str, reason: &str| {
+        if map.remove(key).is_some() {
+            changes.push(ConfigChange::Removed {
+                key: key.to_string(),
+                reason: reason.to_string(),
+            });
+        }
+    };
+    if map.get("version").is_some_and(|v| !v.is_u64()) {
+        remove(
+            map,
+            "version",
+            "package version, replaced by the schema version",
+        );
+    }
+    remove(
+        map,
+        "name",
+        "the workspace name is not part of the server config",
+    );
+    remove(
+        map,
+        "analysis_model_settings",
+        "no server-wide equivalent; set model_settings on the agent",
+    );
+
+    let Some(settings) = map.remove("model_settings") else {
+        return;
+    };
+    let model = settings.get("model").and_then(Value::as_str);
+    let provider = settings
+        .get("provider")
+        .and_then(|p| p.get("name"))
+        .and_then(Value::as_str);
+    match (provider, model) {
+        (Some(provider), Some(model)) if map.get("default_model").is_none() => {
+            let value = Value::from(format!("{}/{}", provider, model));
+            map.insert(Value::from("default_model"), value.clone());
+            changes.push(ConfigChange::Set {
+                key: "default_model".to_string(),
+                value,
+                replaces: Some("model_settings".to_string()),
+            });
+        }
+        _ => changes.push(ConfigChange::Removed {
+            key: "model_settings".to_string(),
+            reason: "replaced by `default_model`".to_string(),
+        }),
+    }
+}
+
+/// Render `migrated` as YAML. When `original` is the YAML it was migrated
+/// from, the migration's edits are applied to that text line by line so
+/// comments and layout survive; the result is checked against `migrated`
+/// and plain re-serialization is used if it differs. Returns the text and
+/// whether comments were kept.
+pub fn render(original: Option<&str>, migrated: &Value, migration: &Migration) -> (String, bool) {
+    if let Some(text) = original.and_then(|o| edit_lines(o, &migration.changes))
+        && serde_yaml::from_str::<Value>(&text).ok().as_ref() == Some(migrated)
+    {
+        return (text, true);
+    }
+    (serde_yaml::to_string(migrated).unwrap_or_default(), false)
+}
+
+fn edit_lines(original: &str, changes: &[ConfigChange]) -> Option<String> {
+    let mut lines: Vec<String> = original.lines().map(str::to_string).collect();
+    for change in changes {
+        match change {
+            ConfigChange::Removed { key, .. } => {
+                if let Some(block) = top_level_block(&lines, key) {
+                    lines.drain(block.clone());
+                    // Don't leave a double blank line where the entry was.
+                    let at = block.start;
+                    if lines.get(at).is_some_and(|l| l.trim().is_empty())
+                        && (at == 0 || lines[at - 1].trim().is_empty())
+                    {
+                        lines.remove(at);
+                    }
+                }
+            }
+            ConfigChange::Set {
+                key,
+                value,
+                replaces,
+            } => {
+                let rendered = serde_yaml::to_string(value).ok()?;
+                let rendered = rendered.trim_end();
+                if rendered.contains('\n') {
+                    return None;
+                }
+                let line = format!("{}: {}", key, rendered);
+                let existing = replaces
+                    .as_deref()
+                    .and_then(|r| top_level_block(&lines, r))
+                    .or_else(|| top_level_block(&lines, key));
+                match existing {
+                    Some(block) => {
+                        lines.drain(block.clone());
+                        lines.insert(block.start, line);
+                    }
+                    // New keys go before the first entry and the comments
+                    // leading into it.
+                    None => match lines.iter().position(|l| top_level_key(l).is_some()) {
+                        Some(first) => {
+                            let at = (0..first)
+                                .rev()
+                                .take_while(|&i| lines[i].starts_with('#'))
+                                .last()
+                                .unwrap_or(first);
+                            lines.insert(at, String::new());
+                            lines.insert(at, line);
+                        }
+                        None => lines.push(line),
+                    },
+                }
+            }
+        }
+    }
+    Some(lines.join("\n") + "\n")
+}
+
+/// The key a line starts at column 0, if it does.
+fn top_level_key(line: &str) -> Option<&str> {
+    if line.starts_with([' ', '\t', '#', '-']) {
+        return None;
+    }
+    let (key, _) = line.split_once(':')?;
+    Some(key.trim().trim_matches(['"', '\'']))
+}
+
+/// Lines of the `key:` entry, without the comments and blank lines that
+/// lead into the next entry.
+fn top_level_block(lines: &[String], key: &str) -> Option<std::ops::Range<usize>> {
+    let start = lines.iter().position(|l| top_level_key(l) == Some(key))?;
+    let next = lines[start + 1..]
+        .iter()
+        .position(|l| top_level_key(l).is_some())
+        .map_or(lines.len(), |i| start + 1 + i);
+    let end = (start + 1..next)
+        .rev()
+        .find(|&i| {
+            let trimmed = lines[i].trim_start();
+            !trimmed.is_empty() && !trimmed.starts_with('#')
+        })
+        .map_or(start + 1, |i| i + 1);
+    Some(start..end)
+}
@@ -166,4 +166,113 @@ mod tests {
         assert!(!resolve::evaluate_skip_condition("!{input.flag}", &ctx));
         assert!(resolve::evaluate_skip_condition("!{input.empty}", &ctx));
     }
+
+    /// fetch → parse → report, with `notify` on its own branch after fetch.
+    fn pipeline() -> WorkflowDefinition {
+        WorkflowDefinition::new(vec![
+            WorkflowStep::checkpoint("fetch", "Fetch", "ok"),
+            WorkflowStep::checkpoint("parse", "Parse", "ok").with_depends_on(vec!["fetch"]),
+            WorkflowStep::checkpoint("report", "Report", "ok").with_depends_on(vec!["parse"]),
+            WorkflowStep::checkpoint("notify", "Notify", "ok").with_depends_on(vec!["fetch"]),
+        ])
+    }
+
+    /// A finished run of `pipeline()` where `parse` failed.
+    fn failed_at_parse() -> WorkflowRun {
+        let mut run = WorkflowRun::new(pipeline());
+        for (step_id, status) in [
+            ("fetch", TaskStatus::Completed),
+            ("parse", TaskStatus::Failed),
+            ("report", TaskStatus::Pending),
+            ("notify", TaskStatus::Completed),
+        ] {
+            let step_run = run.step_run_by_id_mut(step_id).unwrap();
+            step_run.status = status;
+            step_run.result = Some(serde_json::json!({ "from": step_id }));
+        }
+        run.context = serde_json::json!({
+            "steps": { "fetch": { "from": "fetch" }, "parse": { "from": "parse" } }
+        });
+        run.status = TaskStatus::Failed;
+        run
+    }
+
+    #[test]
+    fn upstream_of_follows_dependencies_back() {
+        let upstream = pipeline().upstream_of("report");
+        assert_eq!(upstream.len(), 2);
+        assert!(upstream.contains("fetch") && upstream.contains("parse"));
+        assert!(pipeline().upstream_of("fetch").is_empty());
+    }
+
+    #[test]
+    fn rerun_from_resets_the_subtree_and_keeps_upstream_outputs() {
+        let run = failed_at_parse().rerun_from(pipeline(), "parse").unwrap();
+
+        assert_eq!(run.status, TaskStatus::Running);
+        let fetch = run.step_run_by_id("fetch").unwrap();
+        assert_eq!(fetch.status, TaskStatus::Completed);
+        assert_eq!(fetch.result, Some(serde_json::json!({ "from": "fetch" })));
+        assert_eq!(
+            run.step_run_by_id("notify").unwrap().status,
+            TaskStatus::Completed
+        );
+        for step_id in ["parse", "report"] {
+            let step_run = run.step_run_by_id(step_id).unwrap();
+            assert_eq!(step_run.status, TaskStatus::Pending);
+            assert!(step_run.result.is_none());
+        }
+        assert!(run.context["steps"].get("parse").is_none());
+        assert_eq!(run.context["steps"]["fetch"]["from"], "fetch");
+
+        let runnable: Vec<&str> = run
+            .runnable_steps()
+            .into_iter()
+            .map(|(_, s)| s.id.as_str())
+            .collect();
+        assert_eq!(runnable, vec!["parse"]);
+    }
+
+    #[test]
+    fn rerun_from_accepts_changes_to_the_rerun_steps() {
+        let mut definition = pipeline();
+        definition.steps[1] =
+            WorkflowStep::checkpoint("parse", "Parse", "fixed").with_depends_on(vec!["fetch"]);
+        assert!(failed_at_parse().rerun_from(definition, "parse").is_ok());
+    }
+
+    #[test]
+    fn rerun_from_rejects_changed_upstream_steps() {
+        let mut definition = pipeline();
+        definition.steps[0] = WorkflowStep::checkpoint("fetch", "Fetch", "other source");
+        let err = failed_at_parse()
+            .rerun_from(definition, "parse")
+            .unwrap_err();
+        assert!(err.contains("Upstream steps changed"), "{err}");
+        assert!(err.contains("fetch"), "{err}");
+
+        // A new dependency has no stored output either.
+        let mut definition = pipeline();
+        definition
+            .steps
+            .push(WorkflowStep::checkpoint("auth", "Auth", "ok"));
+        definition.steps[1].depends_on.push("auth".into());
+        let err = failed_at_parse()
+            .rerun_from(definition, "parse")
+            .unwrap_err();
+        assert!(err.contains("auth"), "{err}");
+    }
+
+    #[test]
+    fn rerun_from_needs_upstream_outputs() {
+        let err = failed_at_parse()
+            .rerun_from(pipeline(), "report")
+            .unwrap_err();
+        assert!(err.contains("'parse' has no output"), "{err}");
+
+        let err = failed_at_parse()
+            .rerun_from(pipeline(), "missing")
+            .unwrap_err();
+        assert!(err.contains("does not exist"), "{err}");
+    }
 }
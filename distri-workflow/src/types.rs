@@ -126,6 +126,29 @@ impl WorkflowDefinition {
         reachable
     }
 
+    /// Find all step IDs the given step depends on, directly or through
+    /// other steps (exclusive). The reverse of `reachable_from`; used by
+    /// partial re-runs to find the outputs they reuse.
+    pub fn upstream_of(&self, step_id: &str) -> std::collections::HashSet<String> {
+        use std::collections::{HashSet, VecDeque};
+
+        let mut upstream = HashSet::new();
+        let mut queue: VecDeque<&str> = VecDeque::from([step_id]);
+
+        while let Some(current) = queue.pop_front() {
+            let Some(step) = self.steps.iter().find(|s| s.id == current) else {
+                continue;
+            };
+            for dep in &step.depends_on {
+                if upstream.insert(dep.clone()) {
+                    queue.push_back(dep);
+                }
+            }
+        }
+
+        upstream
+    }
+
     /// Validate the channel-command surface declared by entry-point
     /// triggers. Returns a precise error string on the first problem.
     pub fn validate_channel_surface(&self) -> Result<(), String> {
@@ -463,6 +486,102 @@ impl WorkflowRun {
         Ok(self)
     }
 
+    /// Prepare a partial re-run from `from_step`. `definition` (the
+    /// workflow as it is now) replaces the snapshot; `from_step` and every
+    /// step downstream of it go back to `Pending` and lose their results,
+    /// while every other step keeps its status and result, so the re-run
+    /// reuses the outputs upstream steps already produced.
+    ///
+    /// Fails when a step upstream of `from_step` was changed, added or
+    /// removed since this run — its stored output may not be what it
+    /// would produce now — or has no output to reuse. Root-level
+    /// `context_updates` written by the reset steps are not rolled back.
+    pub fn rerun_from(
+        self,
+        definition: WorkflowDefinition,
+        from_step: &str,
+    ) -> Result<Self, String> {
+        if !definition.steps.iter().any(|s| s.id == from_step) {
+            return Err(format!("Step '{}' does not exist", from_step));
+        }
+        definition.detect_cycles()?;
+
+        let previous = |step_id: &str| {
+            self.definition
+                .steps
+                .iter()
+                .zip(self.step_runs.iter())
+                .find(|(s, _)| s.id == step_id)
+        };
+
+        let mut changed: Vec<String> = definition
+            .upstream_of(from_step)
+            .into_iter()
+            .filter(|id| {
+                let current = definition.steps.iter().find(|s| &s.id == id);
+                match (current, previous(id)) {
+                    (Some(current), Some((original, _))) => {
+                        serde_json::to_value(current).ok() != serde_json::to_value(original).ok()
+                    }
+                    _ => true,
+                }
+            })
+            .collect();
+        // Upstream of `from_step` in the original run but no longer.
+        changed.extend(
+            self.definition
+                .upstream_of(from_step)
+                .into_iter()
+                .filter(|id| !definition.steps.iter().any(|s| &s.id == id)),
+        );
+        if !changed.is_empty() {
+            changed.sort();
+            return Err(format!(
+                "Upstream steps changed since the original run: {}. Their stored outputs \
+                 may be stale; re-run from one of them instead",
+                changed.join(", ")
+            ));
+        }
+
+        let upstream = definition.upstream_of(from_step);
+        for step in definition.steps.iter().filter(|s| upstream.contains(&s.id)) {
+            if let Some((_, step_run)) = previous(&step.id) {
+                if !matches!(
+                    step_run.status,
+                    TaskStatus::Completed | TaskStatus::Canceled
+                ) {
+                    return Err(format!(
+                        "Upstream step '{}' has no output to reuse ({:?}); re-run from it instead",
+                        step.id, step_run.status
+                    ));
+                }
+            }
+        }
+
+        let rerun = definition.reachable_from(from_step);
+        let mut run = WorkflowRun::new(definition);
+        run.context = self.context.clone();
+        run.notes = self.notes.clone();
+        run.created_at = self.created_at;
+        for i in 0..run.definition.steps.len() {
+            let step_id = run.definition.steps[i].id.clone();
+            if rerun.contains(&step_id) {
+                if let Some(steps) = run
+                    .context
+                    .get_mut("steps")
+                    .and_then(|steps| steps.as_object_mut())
+                {
+                    steps.remove(&step_id);
+                }
+            } else if let Some((_, step_run)) = previous(&step_id) {
+                run.step_runs[i] = step_run.clone();
+            }
+        }
+        run.status = TaskStatus::Running;
+        run.add_note(from_step, "Re-running from this step");
+        Ok(run)
+    }
+
     /// Initialize the run with validated input. Input is validated
     /// against `definition.input_schema` if present, then merged into
     /// `context`. Status flips to Running.
@@ -1211,6 +1330,26 @@ pub struct WorkflowNote {
     pub at: DateTime<Utc>,
 }
 
+// ============================================================================
+// Partial re-run
+// ============================================================================
+
+/// Body of `POST /tasks/{task_id}/workflow/rerun`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkflowRerunRequest {
+    /// Step to re-run, along with every step downstream of it.
+    pub from: String,
+}
+
+/// A partial re-run that was started.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkflowRerunResponse {
+    pub run_task_id: String,
+    pub from: String,
+    /// The steps that run again, in definition order.
+    pub steps: Vec<String>,
+}
+
 // ============================================================================
 // Workflow Run Summary (returned at end of execution)
 // ============================================================================
@@ -6,14 +6,20 @@ use distri_a2a::{
     MessageSendConfiguration, MessageSendParams, Role, SendMessageResult,
 };
 use distri_types::api::notes::{CreateNoteRequest, NoteRecord, UpdateNoteRequest};
+use distri_types::conversation_import::{ConversationImportSummary, ImportFormat};
+use distri_types::dev_seed::DevSeedSummary;
+use distri_types::regenerate::{RegenerateRequest, Regeneration};
+use distri_types::thread_archive::ThreadArchiveSummary;
+use distri_types::tool_catalog::ToolResolution;
 use distri_types::{
     ExternalTool, LLmContext, LlmDefinition, Message, MessageRole, Model, ModelProviderDefinition,
-    ProviderType, TokenResponse, ToolCall, a2a_converters::MessageMetadata, prompt::PromptSection,
+    ProviderType, Thread, TokenResponse, ToolCall, UpdateThreadRequest,
+    a2a_converters::MessageMetadata, prompt::PromptSection,
 };
 use distri_types::{StandardDefinition, ToolResponse, configuration::AgentConfigWithTools};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 /// Minimal response from agent registration - ignores extra fields like cloud-only `id`
 #[derive(Debug, Clone, Deserialize)]
@@ -365,6 +371,142 @@ impl Distri {
         Ok(resp.json().await?)
     }
 
+    /// List tasks with their latest-activity projection, optionally filtered
+    /// by `status` (`running`, `failed`, …). Hits
+    /// `GET /v1/tasks?status=…&limit=…`.
+    pub async fn list_task_activity(
+        &self,
+        status: Option<&str>,
+        limit: Option<u32>,
+    ) -> Result<Vec<distri_types::stores::TaskWithActivity>, ClientError> {
+        let mut url = reqwest::Url::parse(&format!("{}/tasks", self.base_url))
+            .map_err(|e| ClientError::InvalidResponse(e.to_string()))?;
+        {
+            let mut q = url.query_pairs_mut();
+            if let Some(s) = status {
+                q.append_pair("status", s);
+            }
+            if let Some(l) = limit {
+                q.append_pair("limit", &l.to_string());
+            }
+        }
+        let resp = self.http.get(url).send().await?;
+        if !resp.status().is_success() {
+            let text = resp.text().await.unwrap_or_default();
+            return Err(ClientError::InvalidResponse(format!(
+                "failed to list tasks: {text}"
+            )));
+        }
+        Ok(resp.json().await?)
+    }
+
+    /// Follow a task's live `AgentEvent`s via `GET /v1/tasks/{task_id}/events`.
+    /// Returns when the server closes the stream (the task reached a terminal
+    /// state) or the connection drops.
+    pub async fn follow_task_events<H, Fut>(
+        &self,
+        task_id: &str,
+        on_event: H,
+    ) -> Result<(), ClientError>
+    where
+        H: FnMut(distri_types::events::AgentEvent) -> Fut,
+        Fut: std::future::Future<Output = ()>,
+    {
+        let url = format!("{}/tasks/{}/events", self.base_url, task_id);
+        let resp = self
+            .http
+            .get(&url)
+            .header("Accept", "text/event-stream")
+            .send()
+            .await?;
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let body = resp.text().await.unwrap_or_default();
+            return Err(ClientError::InvalidResponse(format!(
+                "task events failed (status {status}): {body}"
+            )));
+        }
+        read_agent_event_stream(resp, on_event).await
+    }
+
+    /// Mint a read-only share token for a thread via
+    /// `POST /v1/threads/{thread_id}/share`. `ttl_secs` defaults to 24 hours
+    /// on the server.
+    pub async fn share_thread(
+        &self,
+        thread_id: &str,
+        ttl_secs: Option<u64>,
+    ) -> Result<distri_types::api::share::ThreadShareResponse, ClientError> {
+        let url = format!("{}/threads/{}/share", self.base_url, thread_id);
+        let body = distri_types::api::share::CreateThreadShareRequest { ttl_secs };
+        let resp = self.http.post(&url).json(&body).send().await?;
+        if !resp.status().is_success() {
+            let text = resp.text().await.unwrap_or_default();
+            return Err(ClientError::InvalidResponse(format!(
+                "failed to share thread: {text}"
+            )));
+        }
+        Ok(resp.json().await?)
+    }
+
+    /// Revoke every share token of a thread. Open watches end shortly after.
+    pub async fn revoke_thread_shares(&self, thread_id: &str) -> Result<(), ClientError> {
+        let url = format!("{}/threads/{}/share", self.base_url, thread_id);
+        let resp = self.http.delete(&url).send().await?;
+        if !resp.status().is_success() {
+            let text = resp.text().await.unwrap_or_default();
+            return Err(ClientError::InvalidResponse(format!(
+                "failed to revoke thread shares: {text}"
+            )));
+        }
+        Ok(())
+    }
+
+    /// Watch a shared thread's live runs via
+    /// `GET /v1/threads/{thread_id}/watch?token=…`. Read-only: the token
+    /// grants no input. Returns when the share expires or is revoked, or the
+    /// connection drops.
+    pub async fn watch_thread<H, Fut>(
+        &self,
+        thread_id: &str,
+        token: &str,
+        on_event: H,
+    ) -> Result<(), ClientError>
+    where
+        H: FnMut(distri_types::events::AgentEvent) -> Fut,
+        Fut: std::future::Future<Output = ()>,
+    {
+        let url = format!("{}/threads/{}/watch", self.base_url, thread_id);
+        let resp = self
+            .http
+            .get(&url)
+            .query(&[("token", token)])
+            .header("Accept", "text/event-stream")
+            .send()
+            .await?;
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let body = resp.text().await.unwrap_or_default();
+            return Err(ClientError::InvalidResponse(format!(
+                "watch failed (status {status}): {body}"
+            )));
+        }
+        read_agent_event_stream(resp, on_event).await
+    }
+
+    /// Workspace overview counters from `GET /v1/home/stats`.
+    pub async fn get_home_stats(&self) -> Result<distri_types::stores::HomeStats, ClientError> {
+        let url = format!("{}/home/stats", self.base_url);
+        let resp = self.http.get(&url).send().await?;
+        if !resp.status().is_success() {
+            let text = resp.text().await.unwrap_or_default();
+            return Err(ClientError::InvalidResponse(format!(
+                "failed to load home stats: {text}"
+            )));
+        }
+        Ok(resp.json().await?)
+    }
+
     pub async fn complete_tool(
         &self,
         agent: impl AsRef<str>,
@@ -393,6 +535,158 @@ impl Distri {
         )))
     }
 
+    /// Run `cases` against `agent` and record the results under its current
+    /// definition hash.
+    pub async fn run_evals(
+        &self,
+        agent: &str,
+        cases: Vec<distri_types::evals::EvalCase>,
+    ) -> Result<distri_types::evals::EvalRun, ClientError> {
+        let url = format!("{}/agents/{}/evals", self.base_url, agent);
+        let body = distri_types::evals::RunEvalsRequest { cases };
+        let resp = self.http.post(&url).json(&body).send().await?;
+        if !resp.status().is_success() {
+            let text = resp.text().await.unwrap_or_default();
+            return Err(ClientError::InvalidResponse(format!(
+                "failed to run evals: {text}"
+            )));
+        }
+        Ok(resp.json().await?)
+    }
+
+    /// Judge `candidate` against `baseline`; each is a run id or a
+    /// definition hash prefix.
+    pub async fn compare_evals(
+        &self,
+        agent: &str,
+        baseline: &str,
+        candidate: &str,
+    ) -> Result<distri_types::evals::EvalComparison, ClientError> {
+        let url = format!("{}/agents/{}/evals/compare", self.base_url, agent);
+        let body = distri_types::evals::CompareEvalsRequest {
+            baseline: baseline.to_string(),
+            candidate: candidate.to_string(),
+        };
+        let resp = self.http.post(&url).json(&body).send().await?;
+        if !resp.status().is_success() {
+            let text = resp.text().await.unwrap_or_default();
+            return Err(ClientError::InvalidResponse(format!(
+                "failed to compare evals: {text}"
+            )));
+        }
+        Ok(resp.json().await?)
+    }
+
+    /// Step-by-step diff of two tasks of the same agent, e.g. a replay and
+    /// its original.
+    pub async fn compare_tasks(
+        &self,
+        baseline: &str,
+        candidate: &str,
+    ) -> Result<distri_types::task_diff::TaskComparison, ClientError> {
+        let url = format!("{}/tasks/compare", self.base_url);
+        let query = distri_types::task_diff::CompareTasksQuery {
+            baseline: baseline.to_string(),
+            candidate: candidate.to_string(),
+        };
+        let resp = self.http.get(&url).query(&query).send().await?;
+        if !resp.status().is_success() {
+            let text = resp.text().await.unwrap_or_default();
+            return Err(ClientError::InvalidResponse(format!(
+                "failed to compare tasks: {text}"
+            )));
+        }
+        Ok(resp.json().await?)
+    }
+
+    /// Re-run a finished workflow run from step `from`: that step and
+    /// everything downstream of it run again, reusing the stored outputs
+    /// of the steps upstream. Hits `POST /v1/tasks/{task_id}/workflow/rerun`.
+    pub async fn rerun_workflow(
+        &self,
+        run_task_id: &str,
+        from: &str,
+    ) -> Result<distri_workflow::WorkflowRerunResponse, ClientError> {
+        let url = format!("{}/tasks/{}/workflow/rerun", self.base_url, run_task_id);
+        let body = distri_workflow::WorkflowRerunRequest {
+            from: from.to_string(),
+        };
+        let resp = self.http.post(&url).json(&body).send().await?;
+        if !resp.status().is_success() {
+            let text = resp.text().await.unwrap_or_default();
+            return Err(ClientError::InvalidResponse(format!(
+                "failed to re-run workflow: {text}"
+            )));
+        }
+        Ok(resp.json().await?)
+    }
+
+    /// Run failed task `task_id` again, with a summary of its failure in
+    /// the agent's instructions. Waits for the retry to finish. Hits
+    /// `POST /v1/tasks/{task_id}/retry`.
+    pub async fn retry_task(
+        &self,
+        task_id: &str,
+        request: &distri_types::task_retry::RetryRequest,
+    ) -> Result<distri_types::task_retry::TaskRetry, ClientError> {
+        let url = format!("{}/tasks/{}/retry", self.base_url, task_id);
+        let resp = self.http.post(&url).json(request).send().await?;
+        if !resp.status().is_success() {
+            let text = resp.text().await.unwrap_or_default();
+            return Err(ClientError::InvalidResponse(format!(
+                "failed to retry task: {text}"
+            )));
+        }
+        Ok(resp.json().await?)
+    }
+
+    /// The caller's stored profile; empty when none is stored.
+    pub async fn get_user_profile(
+        &self,
+    ) -> Result<distri_types::user_profile::UserProfile, ClientError> {
+        let url = format!("{}/users/me/profile", self.base_url);
+        let resp = self.http.get(&url).send().await?;
+        if !resp.status().is_success() {
+            let text = resp.text().await.unwrap_or_default();
+            return Err(ClientError::InvalidResponse(format!(
+                "failed to load user profile: {text}"
+            )));
+        }
+        Ok(resp.json().await?)
+    }
+
+    /// Replace the caller's profile; an empty profile removes it.
+    pub async fn set_user_profile(
+        &self,
+        profile: &distri_types::user_profile::UserProfile,
+    ) -> Result<distri_types::user_profile::UserProfile, ClientError> {
+        let url = format!("{}/users/me/profile", self.base_url);
+        let resp = self.http.put(&url).json(profile).send().await?;
+        if !resp.status().is_success() {
+            let text = resp.text().await.unwrap_or_default();
+            return Err(ClientError::InvalidResponse(format!(
+                "failed to save user profile: {text}"
+            )));
+        }
+        Ok(resp.json().await?)
+    }
+
+    /// `agent`'s eval runs, oldest first.
+    pub async fn eval_history(
+        &self,
+        agent: &str,
+    ) -> Result<distri_types::evals::EvalHistory, ClientError> {
+        let url = format!("{}/agents/{}/evals", self.base_url, agent);
+        let resp = self.http.get(&url).send().await?;
+        if !resp.status().is_success() {
+            let text = resp.text().await.unwrap_or_default();
+            return Err(ClientError::InvalidResponse(format!(
+                "failed to load eval history: {text}"
+            )));
+        }
+        Ok(resp.json().await?)
+    }
+
     /// Set a session value (optionally with expiry ISO timestamp)
     pub async fn set_session_value(
         &self,
@@ -932,6 +1226,26 @@ impl Distri {
         serde_json::from_str(&body).map_err(ClientError::Serialization)
     }
 
+    /// Embed texts via `/embeddings`, with the server's configured model
+    /// unless the request names one. Vectors come back in input order.
+    pub async fn embeddings(
+        &self,
+        request: &distri_types::embeddings::EmbeddingRequest,
+    ) -> Result<distri_types::embeddings::EmbeddingResponse, ClientError> {
+        let url = format!("{}/embeddings", self.base_url);
+        let resp = self.http.post(url).json(request).send().await?;
+        let status = resp.status();
+        let body = resp.text().await.unwrap_or_default();
+        if !status.is_success() {
+            return Err(ClientError::InvalidResponse(format!(
+                "embeddings failed {}: {}",
+                status, body
+            )));
+        }
+
+        serde_json::from_str(&body).map_err(ClientError::Serialization)
+    }
+
     // ─────────────────────────────────────────────────────────────────────────
     // A2A task API (cancel / resubscribe)
     // ─────────────────────────────────────────────────────────────────────────
@@ -1431,6 +1745,7 @@ fn build_params(
             blocking: true,
             history_length: None,
             push_notification_config: None,
+            ..Default::default()
         })
     } else {
         None
@@ -1509,6 +1824,43 @@ fn parse_send_message_response(
     }
 }
 
+/// Feed each `data:` frame of an SSE response of `AgentEvent`s to
+/// `on_event` until the server closes the stream. Frames that don't decode
+/// (keep-alives, future variants) are skipped rather than ending the stream.
+async fn read_agent_event_stream<H, Fut>(
+    resp: reqwest::Response,
+    mut on_event: H,
+) -> Result<(), ClientError>
+where
+    H: FnMut(distri_types::events::AgentEvent) -> Fut,
+    Fut: std::future::Future<Output = ()>,
+{
+    use futures_util::StreamExt;
+
+    let mut stream = resp.bytes_stream();
+    let mut buf = String::new();
+    while let Some(chunk) = stream.next().await {
+        buf.push_str(&String::from_utf8_lossy(&chunk?));
+        while let Some(pos) = buf.find("\n\n") {
+            let block = buf[..pos].to_string();
+            buf = buf[pos + 2..].to_string();
+            let data = block
+                .lines()
+                .filter_map(|line| line.strip_prefix("data:"))
+                .map(str::trim_start)
+                .collect::<Vec<_>>()
+                .join("\n");
+            if data.is_empty() {
+                continue;
+            }
+            if let Ok(event) = serde_json::from_str(&data) {
+                on_event(event).await;
+            }
+        }
+    }
+    Ok(())
+}
+
 fn parse_invoke_result(result: SendMessageResult) -> Result<Vec<Message>, ClientError> {
     let a2a_msg = match result {
         SendMessageResult::Message(msg) => Some(msg),
@@ -2221,6 +2573,11 @@ pub struct ThreadSummary {
     pub message_count: Option<u32>,
     #[serde(default)]
     pub last_message: Option<String>,
+    #[serde(default)]
+    pub tags: Option<Vec<String>>,
+    /// Set while the thread is archived.
+    #[serde(default)]
+    pub archived_at: Option<String>,
 }
 
 impl Distri {
@@ -2256,6 +2613,26 @@ impl Distri {
         }
     }
 
+    /// Which of `agent`'s tools a call to `name` runs — a tool name, an
+    /// alias or a qualified `namespace/tool` name.
+    pub async fn resolve_agent_tool(
+        &self,
+        agent: &str,
+        name: &str,
+    ) -> Result<ToolResolution, ClientError> {
+        let url = format!("{}/agents/{}/tools/resolve", self.base_url, agent);
+        let resp = self.http.get(&url).query(&[("name", name)]).send().await?;
+        if resp.status().is_success() {
+            Ok(resp.json().await?)
+        } else {
+            let text = resp.text().await.unwrap_or_default();
+            Err(ClientError::InvalidResponse(format!(
+                "failed to resolve tool '{}': {}",
+                name, text
+            )))
+        }
+    }
+
     // ========== Connections API ==========
 
     pub async fn list_connections(&self) -> Result<Vec<ConnectionSummary>, ClientError> {
@@ -2418,6 +2795,54 @@ impl Distri {
         }
     }
 
+    /// List the slash commands available in chat — built-ins, user-defined
+    /// workspace commands and, when `agent` is given, that agent's own.
+    pub async fn list_commands(
+        &self,
+        agent: Option<&str>,
+    ) -> Result<Vec<distri_types::channel_commands::SlashCommand>, ClientError> {
+        let url = match agent {
+            Some(agent) => format!(
+                "{}/commands?agent={}",
+                self.base_url,
+                urlencoding::encode(agent)
+            ),
+            None => format!("{}/commands", self.base_url),
+        };
+        let resp = self.http.get(&url).send().await?;
+        if resp.status().is_success() {
+            let body: distri_types::channel_commands::ListSlashCommandsResponse =
+                resp.json().await?;
+            Ok(body.commands)
+        } else {
+            Ok(vec![])
+        }
+    }
+
+    /// Run a slash command bound to a tool via `POST /v1/commands/run`.
+    pub async fn run_command(
+        &self,
+        name: &str,
+        input: &str,
+        agent: Option<&str>,
+    ) -> Result<distri_types::channel_commands::RunSlashCommandResponse, ClientError> {
+        let url = format!("{}/commands/run", self.base_url);
+        let body = distri_types::channel_commands::RenderSlashCommandRequest {
+            name: name.to_string(),
+            input: input.to_string(),
+            agent: agent.map(str::to_string),
+        };
+        let resp = self.http.post(&url).json(&body).send().await?;
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let body = resp.text().await.unwrap_or_default();
+            return Err(ClientError::InvalidResponse(format!(
+                "{name} failed (status {status}): {body}"
+            )));
+        }
+        Ok(resp.json().await?)
+    }
+
     /// Get the workspace default model name (if configured).
     pub async fn get_default_model(&self) -> Result<Option<String>, ClientError> {
         let url = format!("{}/providers/default-model", self.base_url);
@@ -2788,6 +3213,112 @@ impl Distri {
             .map_err(|e| ClientError::InvalidResponse(format!("failed to parse threads: {}", e)))
     }
 
+    /// Search threads by title, last message and tags.
+    pub async fn search_threads(&self, query: &str) -> Result<Vec<ThreadSummary>, ClientError> {
+        let url = format!("{}/threads/search", self.base_url);
+        let resp = self.http.get(&url).query(&[("q", query)]).send().await?;
+        if !resp.status().is_success() {
+            let text = resp.text().await.unwrap_or_default();
+            return Err(ClientError::InvalidResponse(format!(
+                "failed to search threads: {}",
+                text
+            )));
+        }
+        let body: serde_json::Value = resp.json().await?;
+        let arr = body.get("threads").cloned().unwrap_or_default();
+        serde_json::from_value(arr)
+            .map_err(|e| ClientError::InvalidResponse(format!("failed to parse threads: {}", e)))
+    }
+
+    pub async fn get_thread(&self, thread_id: &str) -> Result<Thread, ClientError> {
+        let url = format!("{}/threads/{}", self.base_url, thread_id);
+        let resp = self.http.get(&url).send().await?;
+        if !resp.status().is_success() {
+            let text = resp.text().await.unwrap_or_default();
+            return Err(ClientError::InvalidResponse(format!(
+                "failed to get thread: {}",
+                text
+            )));
+        }
+        resp.json()
+            .await
+            .map_err(|e| ClientError::InvalidResponse(format!("failed to parse thread: {}", e)))
+    }
+
+    /// Replace the thread's template variables, rendered as `{{name}}` in
+    /// its user messages and system prompt.
+    pub async fn set_thread_variables(
+        &self,
+        thread_id: &str,
+        variables: BTreeMap<String, String>,
+    ) -> Result<Thread, ClientError> {
+        let url = format!("{}/threads/{}", self.base_url, thread_id);
+        let request = UpdateThreadRequest {
+            title: None,
+            metadata: None,
+            attributes: None,
+            user_id: None,
+            variables: Some(variables),
+        };
+        let resp = self.http.put(&url).json(&request).send().await?;
+        if !resp.status().is_success() {
+            let text = resp.text().await.unwrap_or_default();
+            return Err(ClientError::InvalidResponse(format!(
+                "failed to set thread variables: {}",
+                text
+            )));
+        }
+        resp.json()
+            .await
+            .map_err(|e| ClientError::InvalidResponse(format!("failed to parse thread: {}", e)))
+    }
+
+    /// Upload a ChatGPT or Claude `conversations.json` export; its
+    /// conversations become threads of `agent_id`.
+    pub async fn import_threads(
+        &self,
+        format: ImportFormat,
+        agent_id: &str,
+        export_json: String,
+    ) -> Result<ConversationImportSummary, ClientError> {
+        let url = format!("{}/threads/import", self.base_url);
+        let resp = self
+            .http
+            .post(&url)
+            .query(&[("format", format.as_str()), ("agent_id", agent_id)])
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(export_json)
+            .send()
+            .await?;
+        if !resp.status().is_success() {
+            let text = resp.text().await.unwrap_or_default();
+            return Err(ClientError::InvalidResponse(format!(
+                "failed to import threads: {}",
+                text
+            )));
+        }
+        resp.json().await.map_err(|e| {
+            ClientError::InvalidResponse(format!("failed to parse import summary: {}", e))
+        })
+    }
+
+    /// Populate the store with sample agents, threads, prompt templates and
+    /// placeholder secrets. Fixtures that already exist are left untouched.
+    pub async fn dev_seed(&self) -> Result<DevSeedSummary, ClientError> {
+        let url = format!("{}/dev/seed", self.base_url);
+        let resp = self.http.post(&url).send().await?;
+        if !resp.status().is_success() {
+            let text = resp.text().await.unwrap_or_default();
+            return Err(ClientError::InvalidResponse(format!(
+                "failed to seed dev fixtures: {}",
+                text
+            )));
+        }
+        resp.json().await.map_err(|e| {
+            ClientError::InvalidResponse(format!("failed to parse seed summary: {}", e))
+        })
+    }
+
     /// Fetch messages for a thread, optionally filtered to only user/assistant messages.
     /// Fetch thread history as distri `TaskMessage`s (messages + events).
     ///
@@ -2823,6 +3354,64 @@ impl Distri {
         Ok(items)
     }
 
+    /// Archive the thread's history from `request.message_id` on and run
+    /// that message's task again.
+    pub async fn regenerate(
+        &self,
+        thread_id: &str,
+        request: &RegenerateRequest,
+    ) -> Result<Regeneration, ClientError> {
+        let url = format!("{}/threads/{}/regenerate", self.base_url, thread_id);
+        let resp = self.http.post(&url).json(request).send().await?;
+        if !resp.status().is_success() {
+            let text = resp.text().await.unwrap_or_default();
+            return Err(ClientError::InvalidResponse(format!(
+                "failed to regenerate thread: {}",
+                text
+            )));
+        }
+        resp.json().await.map_err(|e| {
+            ClientError::InvalidResponse(format!("failed to parse regeneration: {}", e))
+        })
+    }
+
+    /// Move the thread's tasks and messages to cold storage.
+    pub async fn archive_thread(
+        &self,
+        thread_id: &str,
+    ) -> Result<ThreadArchiveSummary, ClientError> {
+        let url = format!("{}/threads/{}/archive", self.base_url, thread_id);
+        let resp = self.http.post(&url).send().await?;
+        Self::thread_archive_response(resp, "archive").await
+    }
+
+    /// Bring an archived thread's tasks and messages back. Reading the
+    /// thread restores it too.
+    pub async fn restore_thread(
+        &self,
+        thread_id: &str,
+    ) -> Result<ThreadArchiveSummary, ClientError> {
+        let url = format!("{}/threads/{}/archive", self.base_url, thread_id);
+        let resp = self.http.delete(&url).send().await?;
+        Self::thread_archive_response(resp, "restore").await
+    }
+
+    async fn thread_archive_response(
+        resp: reqwest::Response,
+        action: &str,
+    ) -> Result<ThreadArchiveSummary, ClientError> {
+        if !resp.status().is_success() {
+            let text = resp.text().await.unwrap_or_default();
+            return Err(ClientError::InvalidResponse(format!(
+                "failed to {} thread: {}",
+                action, text
+            )));
+        }
+        resp.json().await.map_err(|e| {
+            ClientError::InvalidResponse(format!("failed to parse archive summary: {}", e))
+        })
+    }
+
     // ========== Traces API ==========
 
     pub async fn list_traces(&self, limit: Option<i64>) -> Result<Vec<TraceSummary>, ClientError> {
@@ -4,6 +4,7 @@ use std::future::Future;
 use anyhow::Result;
 use distri_a2a::{AgentCard, MessageSendParams};
 use distri_types::configuration::AgentConfigWithTools;
+use distri_types::packages::WorkspaceBuild;
 use distri_types::{
     AgentEvent, ToolCall, ToolDefinition, ToolResponse, configuration::AgentConfig,
 };
@@ -328,15 +329,19 @@ impl DistriClientApp {
         Ok(wrapper.tools)
     }
 
-    pub async fn build_workspace(&self) -> Result<(), ClientError> {
+    /// Register the agents of the server's workspace and its member
+    /// packages again.
+    pub async fn build_workspace(&self) -> Result<WorkspaceBuild, ClientError> {
         let url = format!("{}/build", self.base());
         let resp = self.http.post(url).send().await?;
-        if resp.status().is_success() {
-            Ok(())
+        let status = resp.status();
+        if status.is_success() {
+            Ok(resp.json::<WorkspaceBuild>().await?)
         } else {
+            let body = resp.text().await.unwrap_or_default();
             Err(ClientError::InvalidResponse(format!(
-                "build failed: {}",
-                resp.status()
+                "build failed: {} {}",
+                status, body
             )))
         }
     }
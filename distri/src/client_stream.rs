@@ -4,10 +4,23 @@ use distri_a2a::{
 };
 use distri_types::dynamic_tool::DynamicToolFactory;
 use distri_types::http_request::HttpFactoryConfig;
-use distri_types::{AgentEvent, AgentEventType, Message, ToolCall, ToolResponse};
-use futures_util::StreamExt;
+use distri_types::stream_control::{STREAM_WS_PATH, StreamControl, StreamControlReply};
+use distri_types::{
+    AgentEvent, AgentEventType, AuthConsentRequest, AuthConsentResponse, Message, ToolCall,
+    ToolResponse,
+};
+use futures_util::stream::SplitSink;
+use futures_util::{SinkExt, StreamExt};
 use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
 use thiserror::Error;
+use tokio::net::TcpStream;
+use tokio::sync::mpsc;
+use tokio_tungstenite::tungstenite::Message as WsMessage;
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
 use uuid::Uuid;
 
 use crate::config::{self, BuildHttpClient};
@@ -35,6 +48,42 @@ pub struct StreamItem {
     pub agent_event: Option<AgentEvent>,
 }
 
+/// Answers a tool call's `AuthRequired` consent prompt, e.g. by asking the
+/// user and catching the OAuth redirect locally.
+pub type AuthConsentHandler = Arc<
+    dyn Fn(AuthConsentRequest) -> Pin<Box<dyn Future<Output = AuthConsentResponse> + Send>>
+        + Send
+        + Sync,
+>;
+
+type WsStream = WebSocketStream<MaybeTlsStream<TcpStream>>;
+type WsSink = SplitSink<WsStream, WsMessage>;
+
+/// Sends [`StreamControl`]s to a run streamed with
+/// [`AgentStreamClient::stream_agent_with_controls`].
+#[derive(Debug, Clone)]
+pub struct StreamControls {
+    tx: mpsc::UnboundedSender<StreamControl>,
+}
+
+impl StreamControls {
+    /// Returns `false` once the stream has ended.
+    pub fn send(&self, control: StreamControl) -> bool {
+        self.tx.send(control).is_ok()
+    }
+
+    /// Cancel the streamed run.
+    pub fn cancel(&self) -> bool {
+        self.send(StreamControl::Cancel { task_id: None })
+    }
+}
+
+/// A [`StreamControls`] handle and the receiver to stream with.
+pub fn stream_controls() -> (StreamControls, mpsc::UnboundedReceiver<StreamControl>) {
+    let (tx, rx) = mpsc::unbounded_channel();
+    (StreamControls { tx }, rx)
+}
+
 #[derive(Clone)]
 pub struct AgentStreamClient {
     base_url: String,
@@ -43,7 +92,11 @@ pub struct AgentStreamClient {
     /// `is_external_tool(name)` answers from `has_tool("*", name)`.
     tool_registry: Option<ExternalToolRegistry>,
     hook_registry: Option<HookRegistry>,
+    auth_handler: Option<AuthConsentHandler>,
     registered_tools: Vec<DynamicToolFactory>,
+    /// Stream over the WebSocket when the server offers it.
+    websocket: bool,
+    ws_headers: reqwest::header::HeaderMap,
 }
 
 impl AgentStreamClient {
@@ -64,7 +117,10 @@ impl AgentStreamClient {
             http,
             tool_registry: None,
             hook_registry: None,
+            auth_handler: None,
             registered_tools: vec![platform_tool],
+            websocket: true,
+            ws_headers: cfg.request_headers(),
         }
     }
 
@@ -73,6 +129,12 @@ impl AgentStreamClient {
         self
     }
 
+    /// Whether to try the WebSocket stream before SSE (default: true).
+    pub fn with_websocket(mut self, enabled: bool) -> Self {
+        self.websocket = enabled;
+        self
+    }
+
     pub fn with_tool_registry(mut self, registry: ExternalToolRegistry) -> Self {
         self.tool_registry = Some(registry);
         self
@@ -83,6 +145,17 @@ impl AgentStreamClient {
         self
     }
 
+    /// Answer `AuthRequired` prompts with `handler`. Without one they are
+    /// left for another client and the paused tool call eventually fails.
+    pub fn with_auth_handler<F, Fut>(mut self, handler: F) -> Self
+    where
+        F: Fn(AuthConsentRequest) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = AuthConsentResponse> + Send + 'static,
+    {
+        self.auth_handler = Some(Arc::new(move |request| Box::pin(handler(request))));
+        self
+    }
+
     pub fn register_dynamic_tool(&mut self, factory: DynamicToolFactory) {
         if let Some(pos) = self
             .registered_tools
@@ -149,18 +222,31 @@ impl AgentStreamClient {
         &self,
         agent_id: &str,
         params: MessageSendParams,
-        mut on_event: H,
+        on_event: H,
     ) -> Result<(), StreamError>
     where
         H: FnMut(StreamItem) -> Fut,
         Fut: std::future::Future<Output = ()> + Send,
     {
-        let url = format!(
-            "{}/agents/{}",
-            self.base_url.trim_end_matches('/'),
-            agent_id
-        );
+        self.stream_agent_with_controls(agent_id, params, None, on_event)
+            .await
+    }
 
+    /// Like [`Self::stream_agent`], and also sends the [`StreamControl`]s
+    /// received on `controls` (see [`stream_controls`]) to the run. Over the
+    /// WebSocket they travel on the stream's socket; when the server only
+    /// offers SSE, each becomes the matching HTTP call.
+    pub async fn stream_agent_with_controls<H, Fut>(
+        &self,
+        agent_id: &str,
+        params: MessageSendParams,
+        mut controls: Option<mpsc::UnboundedReceiver<StreamControl>>,
+        mut on_event: H,
+    ) -> Result<(), StreamError>
+    where
+        H: FnMut(StreamItem) -> Fut,
+        Fut: std::future::Future<Output = ()> + Send,
+    {
         let params = self.merge_registered_tools(params);
 
         let rpc = JsonRpcRequest {
@@ -170,6 +256,23 @@ impl AgentStreamClient {
             params: serde_json::to_value(params)?,
         };
 
+        if self.websocket {
+            match self.connect_ws(agent_id).await {
+                Ok(socket) => {
+                    return self
+                        .run_ws(agent_id, socket, &rpc, controls, on_event)
+                        .await;
+                }
+                Err(e) => tracing::debug!("WebSocket stream unavailable, using SSE: {}", e),
+            }
+        }
+
+        let url = format!(
+            "{}/agents/{}",
+            self.base_url.trim_end_matches('/'),
+            agent_id
+        );
+
         let resp = self
             .http
             .post(url)
@@ -189,9 +292,22 @@ impl AgentStreamClient {
 
         let mut stream = resp.bytes_stream();
         let mut buf = String::new();
-
-        while let Some(chunk) = stream.next().await {
-            let chunk = chunk.map_err(|e| StreamError::Event(e.to_string()))?;
+        let mut task_id = None;
+
+        loop {
+            let chunk = tokio::select! {
+                chunk = stream.next() => match chunk {
+                    Some(chunk) => chunk.map_err(|e| StreamError::Event(e.to_string()))?,
+                    None => break,
+                },
+                control = next_control(&mut controls) => {
+                    let name = control.name();
+                    if let Err(e) = self.send_control(agent_id, control, None, &task_id).await {
+                        tracing::warn!("{} control failed: {}", name, e);
+                    }
+                    continue;
+                }
+            };
             buf.push_str(&String::from_utf8_lossy(&chunk));
 
             while let Some(pos) = buf.find("\n\n") {
@@ -213,47 +329,183 @@ impl AgentStreamClient {
                 let Some(item) = parse_sse_data(agent_id, &data)? else {
                     continue;
                 };
+                self.handle_item(agent_id, item, &mut on_event, None, &mut task_id)
+                    .await?;
+            }
+        }
 
-                if let Some(ref agent_event) = item.agent_event {
-                    // Fire-and-forget hook execution
-                    if let AgentEventType::InlineHookRequested { request } = &agent_event.event
-                        && let Some(registry) = &self.hook_registry
-                    {
-                        registry.try_handle(agent_id, request).await;
-                    }
+        Ok(())
+    }
 
-                    // The server includes _agent_id (agent name) in event metadata.
-                    // Use it for tool registry lookups. Fall back to stream agent_id.
-                    let tool_agent = &agent_event.agent_id;
-
-                    // ToolCalls: handle external tools. The server emits ToolCalls
-                    // BEFORE registering the pending call, so complete_tool retries
-                    // until the server is ready.
-                    if let AgentEventType::ToolCalls { tool_calls, .. } = &agent_event.event {
-                        let external_calls: Vec<_> = tool_calls
-                            .iter()
-                            .filter(|c| self.is_external_tool(&c.tool_name))
-                            .cloned()
-                            .collect();
-                        for call in &external_calls {
-                            self.execute_and_complete_external_tool(
-                                tool_agent,
-                                agent_id,
-                                agent_event,
-                                call,
-                            )
-                            .await?;
+    /// Open the run's WebSocket. Fails when the server does not offer one.
+    async fn connect_ws(&self, agent_id: &str) -> Result<WsStream, StreamError> {
+        let url = ws_url(&self.base_url, agent_id)
+            .ok_or_else(|| StreamError::Event(format!("no WebSocket URL for {}", self.base_url)))?;
+        let mut request = url
+            .into_client_request()
+            .map_err(|e| StreamError::Event(e.to_string()))?;
+        request.headers_mut().extend(self.ws_headers.clone());
+        let (socket, _) = tokio_tungstenite::connect_async(request)
+            .await
+            .map_err(|e| StreamError::Event(format!("WebSocket connection failed: {e}")))?;
+        Ok(socket)
+    }
+
+    /// Send `rpc` on the socket and handle the frames of the stream until
+    /// the server closes it.
+    async fn run_ws<H, Fut>(
+        &self,
+        agent_id: &str,
+        socket: WsStream,
+        rpc: &JsonRpcRequest,
+        mut controls: Option<mpsc::UnboundedReceiver<StreamControl>>,
+        mut on_event: H,
+    ) -> Result<(), StreamError>
+    where
+        H: FnMut(StreamItem) -> Fut,
+        Fut: std::future::Future<Output = ()> + Send,
+    {
+        let (mut sink, mut source) = socket.split();
+        sink.send(WsMessage::Text(serde_json::to_string(rpc)?.into()))
+            .await
+            .map_err(|e| StreamError::Event(format!("WebSocket send failed: {e}")))?;
+        let mut task_id = None;
+
+        loop {
+            tokio::select! {
+                frame = source.next() => match frame {
+                    Some(Ok(WsMessage::Text(text))) => {
+                        if let Some(reply) = StreamControlReply::parse(text.as_str()) {
+                            if let Some(error) = reply.error {
+                                tracing::warn!("{} control frame failed: {}", reply.control, error);
+                            }
+                            continue;
                         }
+                        let Some(item) = parse_sse_data(agent_id, text.as_str())? else {
+                            continue;
+                        };
+                        self.handle_item(agent_id, item, &mut on_event, Some(&mut sink), &mut task_id)
+                            .await?;
                     }
+                    Some(Ok(WsMessage::Close(_))) | None => break,
+                    Some(Ok(_)) => {}
+                    Some(Err(e)) => {
+                        return Err(StreamError::Event(format!("WebSocket stream failed: {e}")));
+                    }
+                },
+                control = next_control(&mut controls) => {
+                    self.send_control(agent_id, control, Some(&mut sink), &task_id).await?;
                 }
+            }
+        }
 
-                on_event(item).await;
+        Ok(())
+    }
+
+    /// Run the client side of a streamed item (hooks, external tools, auth
+    /// consent) and pass it to `on_event`. Answers go on `sink` when the
+    /// stream is a WebSocket.
+    async fn handle_item<H, Fut>(
+        &self,
+        agent_id: &str,
+        item: StreamItem,
+        on_event: &mut H,
+        mut sink: Option<&mut WsSink>,
+        task_id: &mut Option<String>,
+    ) -> Result<(), StreamError>
+    where
+        H: FnMut(StreamItem) -> Fut,
+        Fut: std::future::Future<Output = ()> + Send,
+    {
+        // Answered after the event is rendered, so the prompt
+        // follows the notice.
+        let auth_request = match item.agent_event.as_ref().map(|e| &e.event) {
+            Some(AgentEventType::AuthRequired { request }) => Some(request.clone()),
+            _ => None,
+        };
+
+        if let Some(ref agent_event) = item.agent_event {
+            if task_id.is_none() && agent_event.task_id != "unknown_task" {
+                *task_id = Some(agent_event.task_id.clone());
+            }
+
+            // Fire-and-forget hook execution
+            if let AgentEventType::InlineHookRequested { request } = &agent_event.event
+                && let Some(registry) = &self.hook_registry
+            {
+                registry.try_handle(agent_id, request).await;
+            }
+
+            // The server includes _agent_id (agent name) in event metadata.
+            // Use it for tool registry lookups. Fall back to stream agent_id.
+            let tool_agent = &agent_event.agent_id;
+
+            // ToolCalls: handle external tools. The server emits ToolCalls
+            // BEFORE registering the pending call, so completions are
+            // retried until the server is ready.
+            if let AgentEventType::ToolCalls { tool_calls, .. } = &agent_event.event {
+                let external_calls: Vec<_> = tool_calls
+                    .iter()
+                    .filter(|c| self.is_external_tool(&c.tool_name))
+                    .cloned()
+                    .collect();
+                for call in &external_calls {
+                    let tool_response = self
+                        .execute_external_tool(tool_agent, agent_event, call)
+                        .await?;
+                    self.send_control(
+                        agent_id,
+                        StreamControl::ToolResult { tool_response },
+                        sink.as_deref_mut(),
+                        task_id,
+                    )
+                    .await?;
+                }
             }
         }
 
+        on_event(item).await;
+
+        if let (Some(request), Some(handler)) = (auth_request, &self.auth_handler) {
+            let response = handler(request).await;
+            self.send_control(agent_id, StreamControl::Auth { response }, sink, task_id)
+                .await?;
+        }
         Ok(())
     }
 
+    /// Send a control to the run: on the WebSocket `sink`, or as the HTTP
+    /// call it stands for.
+    async fn send_control(
+        &self,
+        agent_id: &str,
+        control: StreamControl,
+        sink: Option<&mut WsSink>,
+        task_id: &Option<String>,
+    ) -> Result<(), StreamError> {
+        if let Some(sink) = sink {
+            return sink
+                .send(WsMessage::Text(serde_json::to_string(&control)?.into()))
+                .await
+                .map_err(|e| StreamError::Event(format!("WebSocket send failed: {e}")));
+        }
+        match control {
+            StreamControl::Cancel { task_id: target } => {
+                let Some(id) = target.or_else(|| task_id.clone()) else {
+                    return Err(StreamError::Event(
+                        "the stream has no task to cancel yet".to_string(),
+                    ));
+                };
+                self.cancel_task(agent_id, &id).await.map(|_| ())
+            }
+            StreamControl::Auth { response } => self.complete_auth_consent(&response).await,
+            control => match control.tool_response() {
+                Some(tool_response) => self.complete_tool_with_retry(agent_id, tool_response).await,
+                None => Ok(()),
+            },
+        }
+    }
+
     /// Resubscribe to the SSE stream of an existing task. Yields `StreamItem`s
     /// via `on_event` until the stream closes. If the task was already
     /// terminal when the server received the request, the server emits a
@@ -399,16 +651,14 @@ impl AgentStreamClient {
         Ok(())
     }
 
-    /// Execute an external tool locally and send the result to the server.
-    /// The server emits ToolCalls before registering the pending call, so
-    /// complete_tool retries with backoff until the server is ready.
-    async fn execute_and_complete_external_tool(
+    /// Execute an external tool locally. Failures become an error result
+    /// for the model rather than failing the stream.
+    async fn execute_external_tool(
         &self,
         tool_agent: &str,
-        stream_agent_id: &str,
         agent_event: &AgentEvent,
         call: &ToolCall,
-    ) -> Result<(), StreamError> {
+    ) -> Result<ToolResponse, StreamError> {
         let Some(registry) = &self.tool_registry else {
             return Err(StreamError::ExternalTool(format!(
                 "No tool registry but external tool '{}' called (agent='{}')",
@@ -444,12 +694,20 @@ impl AgentStreamClient {
                 )
             }
         };
+        Ok(response)
+    }
 
-        // Retry complete_tool — server registers the pending call AFTER
-        // emitting ToolCalls, so the first attempt may get "No pending".
+    /// Send a tool result to the server. The server emits ToolCalls before
+    /// registering the pending call, so complete_tool retries with backoff
+    /// until the server is ready.
+    async fn complete_tool_with_retry(
+        &self,
+        stream_agent_id: &str,
+        response: ToolResponse,
+    ) -> Result<(), StreamError> {
         for attempt in 0..10u32 {
             match self
-                .complete_tool(stream_agent_id, &call.tool_call_id, response.clone())
+                .complete_tool(stream_agent_id, &response.tool_call_id, response.clone())
                 .await
             {
                 Ok(()) => return Ok(()),
@@ -457,7 +715,7 @@ impl AgentStreamClient {
                     let delay = std::time::Duration::from_millis(100 * (1 << attempt.min(4)));
                     tracing::debug!(
                         "complete_tool '{}': server not ready (attempt {}), retrying in {:?}",
-                        call.tool_name,
+                        response.tool_name,
                         attempt + 1,
                         delay
                     );
@@ -469,7 +727,7 @@ impl AgentStreamClient {
 
         Err(StreamError::ExternalTool(format!(
             "complete_tool for '{}' timed out after retries — server never registered the pending call",
-            call.tool_name
+            response.tool_name
         )))
     }
 
@@ -499,6 +757,22 @@ impl AgentStreamClient {
             tool_call_id, body
         )))
     }
+
+    async fn complete_auth_consent(
+        &self,
+        response: &AuthConsentResponse,
+    ) -> Result<(), StreamError> {
+        let url = format!("{}/event/auth", self.base_url.trim_end_matches('/'));
+        let resp = self.http.post(&url).json(response).send().await?;
+        if resp.status().is_success() {
+            return Ok(());
+        }
+        let body = resp.text().await.unwrap_or_default();
+        Err(StreamError::InvalidResponse(format!(
+            "auth consent failed for '{}': {}",
+            response.auth_id, body
+        )))
+    }
 }
 
 /// Build an AgentEvent from SSE metadata. The server serializes a typed
@@ -593,3 +867,63 @@ pub fn parse_sse_data(agent_id: &str, data: &str) -> Result<Option<StreamItem>,
         agent_event,
     }))
 }
+
+/// The next control, or never once `controls` is closed or absent.
+async fn next_control(
+    controls: &mut Option<mpsc::UnboundedReceiver<StreamControl>>,
+) -> StreamControl {
+    if let Some(rx) = controls.as_mut() {
+        if let Some(control) = rx.recv().await {
+            return control;
+        }
+        *controls = None;
+    }
+    std::future::pending().await
+}
+
+/// `ws://` or `wss://` URL of an agent's WebSocket stream.
+fn ws_url(base_url: &str, agent_id: &str) -> Option<String> {
+    let base = base_url.trim_end_matches('/');
+    let rest = base
+        .strip_prefix("https://")
+        .map(|rest| format!("wss://{rest}"))
+        .or_else(|| {
+            base.strip_prefix("http://")
+                .map(|rest| format!("ws://{rest}"))
+        })?;
+    Some(format!("{rest}/agents/{agent_id}/{STREAM_WS_PATH}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ws_urls_follow_the_base_url_scheme() {
+        assert_eq!(
+            ws_url("http://localhost:8080/v1/", "assistant").as_deref(),
+            Some("ws://localhost:8080/v1/agents/assistant/ws")
+        );
+        assert_eq!(
+            ws_url("https://api.distri.dev/v1", "assistant").as_deref(),
+            Some("wss://api.distri.dev/v1/agents/assistant/ws")
+        );
+        assert_eq!(ws_url("unix:///tmp/distri.sock", "assistant"), None);
+    }
+
+    #[tokio::test]
+    async fn controls_end_with_their_handles() {
+        let (controls, rx) = stream_controls();
+        let mut rx = Some(rx);
+        assert!(controls.cancel());
+        assert!(matches!(
+            next_control(&mut rx).await,
+            StreamControl::Cancel { task_id: None }
+        ));
+        drop(controls);
+        let pending =
+            tokio::time::timeout(std::time::Duration::from_millis(10), next_control(&mut rx)).await;
+        assert!(pending.is_err());
+        assert!(rx.is_none());
+    }
+}
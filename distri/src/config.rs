@@ -11,6 +11,10 @@ pub use distri_types::DistriConfig;
 pub trait BuildHttpClient {
     /// Build a reqwest client with the configured settings.
     fn build_http_client(&self) -> Result<reqwest::Client, reqwest::Error>;
+
+    /// Headers sent with every request: auth, workspace, tracing and extra
+    /// headers.
+    fn request_headers(&self) -> reqwest::header::HeaderMap;
 }
 
 impl BuildHttpClient for DistriConfig {
@@ -18,6 +22,15 @@ impl BuildHttpClient for DistriConfig {
         let mut builder =
             reqwest::Client::builder().timeout(std::time::Duration::from_secs(self.timeout_secs));
 
+        let headers = self.request_headers();
+        if !headers.is_empty() {
+            builder = builder.default_headers(headers);
+        }
+
+        builder.build()
+    }
+
+    fn request_headers(&self) -> reqwest::header::HeaderMap {
         let mut headers = reqwest::header::HeaderMap::new();
 
         // Add API key / token header if configured
@@ -70,11 +83,7 @@ impl BuildHttpClient for DistriConfig {
             }
         }
 
-        if !headers.is_empty() {
-            builder = builder.default_headers(headers);
-        }
-
-        builder.build()
+        headers
     }
 }
 
@@ -29,13 +29,17 @@ pub use client::{
     UpdateSkillRequest, ValidatePluginResponse, WorkspaceResponse,
 };
 pub use client_app::{AppError, DistriClientApp, ToolListItem};
-pub use client_stream::{AgentStreamClient, StreamError, StreamItem, parse_sse_data};
+pub use client_stream::{
+    AgentStreamClient, AuthConsentHandler, StreamControls, StreamError, StreamItem, parse_sse_data,
+    stream_controls,
+};
 pub use config::{BuildHttpClient, DistriConfig};
 pub use hooks_runtime::*;
 
 pub use distri_types::{
-    HookContext, HookKind, HookMutation, InlineHookRequest, InlineHookResponse, Model,
-    ModelProviderDefinition, ProviderKeyDefinition, ProviderType, TokenResponse, TtsVoiceInfo,
+    AuthConsentRequest, AuthConsentResponse, HookContext, HookKind, HookMutation,
+    InlineHookRequest, InlineHookResponse, Model, ModelProviderDefinition, ProviderKeyDefinition,
+    ProviderType, TokenResponse, TtsVoiceInfo,
 };
 pub use printer::{
     ContextHealth, EventPrinter, format_context_breakdown, print_stream, print_stream_verbose,
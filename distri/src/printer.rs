@@ -261,6 +261,7 @@ fn status_icon(s: &distri_types::todos::TodoStatus) -> &'static str {
         TodoStatus::Done => "■",
         TodoStatus::InProgress => "◐",
         TodoStatus::Open => "□",
+        TodoStatus::Failed => "✗",
     }
 }
 
@@ -274,6 +275,12 @@ fn print_usage_line(u: &distri_types::RunUsage) {
             format_token_count(u.cached_tokens as usize)
         ));
     }
+    if u.cache_write_tokens > 0 {
+        parts.push(format!(
+            "{} cache write",
+            format_token_count(u.cache_write_tokens as usize)
+        ));
+    }
     if let Some(cost) = u.cost_usd {
         parts.push(format!("${:.4}", cost));
     }
@@ -495,7 +502,10 @@ impl EventPrinter {
 
         // Clear the spinner before any other event prints output
         match &event.event {
-            AgentEventType::PlanStarted { .. } | AgentEventType::PlanFinished { .. } => {}
+            AgentEventType::PlanStarted { .. }
+            | AgentEventType::PlanFinished { .. }
+            | AgentEventType::ToolCallStart { .. }
+            | AgentEventType::ToolCallArgs { .. } => {}
             _ => self.clear_planning_line(),
         }
 
@@ -582,6 +592,20 @@ impl EventPrinter {
             AgentEventType::TextMessageEnd { message_id, .. } => {
                 self.finish_message(message_id);
             }
+            AgentEventType::ToolCallStart {
+                tool_call_id,
+                tool_call_name,
+                ..
+            } => {
+                self.tool_pending(tool_call_id, tool_call_name);
+            }
+            AgentEventType::ToolCallArgs {
+                tool_call_id,
+                delta,
+                ..
+            } => {
+                self.tool_pending_args(tool_call_id, delta);
+            }
             AgentEventType::ToolExecutionStart {
                 tool_call_id,
                 tool_call_name,
@@ -627,6 +651,23 @@ impl EventPrinter {
                     println!("{}Run completed with errors{}", COLOR_RED, COLOR_RESET);
                 }
             }
+            AgentEventType::OutputSinkFinished {
+                sink,
+                success,
+                error,
+            } => {
+                if *success {
+                    println!("{}Delivered to {}{}", COLOR_GRAY, sink, COLOR_RESET);
+                } else {
+                    println!(
+                        "{}Output sink {} failed: {}{}",
+                        COLOR_RED,
+                        sink,
+                        error.as_deref().unwrap_or("unknown error"),
+                        COLOR_RESET
+                    );
+                }
+            }
 
             AgentEventType::ContextBudgetUpdate {
                 budget,
@@ -707,6 +748,16 @@ impl EventPrinter {
                     COLOR_RESET
                 );
             }
+            AgentEventType::AuthRequired { request } => {
+                println!(
+                    "{}{} needs more access to {}: {}{}",
+                    COLOR_YELLOW,
+                    request.tool_name,
+                    request.provider,
+                    request.missing_scopes.join(", "),
+                    COLOR_RESET
+                );
+            }
             AgentEventType::TodosUpdated {
                 formatted_todos,
                 changes,
@@ -729,6 +780,9 @@ impl EventPrinter {
                     }
                 }
             }
+            AgentEventType::TodoQueueFinished { summary } => {
+                println!("{}{}{}", COLOR_GRAY, summary.format_display(), COLOR_RESET);
+            }
             AgentEventType::AgentHandover {
                 from_agent,
                 to_agent,
@@ -774,6 +828,166 @@ impl EventPrinter {
                     COLOR_RESET
                 );
             }
+            AgentEventType::Truncated {
+                output_tokens,
+                max_output_tokens,
+                continuing,
+                ..
+            } => {
+                let limit = max_output_tokens
+                    .map(|max| format!(" (limit {})", format_token_count(max as usize)))
+                    .unwrap_or_default();
+                println!(
+                    "{}[truncated] response cut off after ~{} tokens{}{}{}",
+                    COLOR_GRAY,
+                    format_token_count(*output_tokens as usize),
+                    limit,
+                    if *continuing { ", continuing" } else { "" },
+                    COLOR_RESET
+                );
+            }
+            AgentEventType::ContextTrimmed {
+                tokens_before,
+                tokens_after,
+                tool_results_truncated,
+                messages_dropped,
+                ..
+            } => {
+                println!(
+                    "{}[context] request trimmed to fit: {} → {} tokens ({} tool results cut, {} messages dropped){}",
+                    COLOR_GRAY,
+                    format_token_count(*tokens_before),
+                    format_token_count(*tokens_after),
+                    tool_results_truncated,
+                    messages_dropped,
+                    COLOR_RESET
+                );
+            }
+            AgentEventType::EnsembleJudged {
+                branches,
+                selected,
+                rationale,
+                ..
+            } => {
+                let choice = match selected.and_then(|i| branches.get(i)) {
+                    Some(branch) => format!("picked {}", branch.agent),
+                    None => "merged".to_string(),
+                };
+                println!(
+                    "{}[ensemble] {} of {} branches{}{}",
+                    COLOR_GRAY,
+                    choice,
+                    branches.len(),
+                    rationale
+                        .as_deref()
+                        .map(|r| format!(": {}", r))
+                        .unwrap_or_default(),
+                    COLOR_RESET
+                );
+            }
+            AgentEventType::ToolRecovery {
+                tool_call_name,
+                strategy,
+                error,
+                attempt,
+                recovered,
+                fallback_tool,
+                ..
+            } => {
+                let action = match (strategy.as_str(), fallback_tool) {
+                    ("retry", _) => format!("retry {}", attempt),
+                    ("fallback", Some(tool)) => format!("fallback to {}", tool),
+                    (strategy, _) => strategy.to_string(),
+                };
+                println!(
+                    "{}[recovery] {} failed ({}): {}{}{}",
+                    if *recovered { COLOR_GRAY } else { COLOR_RED },
+                    tool_call_name,
+                    action,
+                    error,
+                    if *recovered { ", recovered" } else { "" },
+                    COLOR_RESET
+                );
+            }
+            AgentEventType::ToolTimeout {
+                tool_call_name,
+                timeout_secs,
+                ..
+            } => {
+                println!(
+                    "{}[timeout] {} did not finish within {}s{}",
+                    COLOR_RED, tool_call_name, timeout_secs, COLOR_RESET
+                );
+            }
+            AgentEventType::ToolOutputInvalid {
+                tool_call_name,
+                violations,
+                ..
+            } => {
+                println!(
+                    "{}[output schema] {} returned invalid output: {}{}",
+                    COLOR_RED,
+                    tool_call_name,
+                    violations.join("; "),
+                    COLOR_RESET
+                );
+            }
+            AgentEventType::SideEffectSimulated { tool_call_name, .. } => {
+                println!(
+                    "{}[dry run] simulated {}{}",
+                    COLOR_GRAY, tool_call_name, COLOR_RESET
+                );
+            }
+            AgentEventType::DryRunReport { report } => {
+                println!("{}{}{}", COLOR_YELLOW, report.to_markdown(), COLOR_RESET);
+            }
+            AgentEventType::PromptInjectionDetected {
+                tool_call_name,
+                detections,
+                sanitized,
+                ..
+            } => {
+                println!(
+                    "{}[injection] {} output looks like a prompt injection: {}{}{}",
+                    COLOR_RED,
+                    tool_call_name,
+                    detections.join(", "),
+                    if *sanitized { ", sanitized" } else { "" },
+                    COLOR_RESET
+                );
+            }
+            AgentEventType::StructuredOutputRejected {
+                error, retrying, ..
+            } => {
+                println!(
+                    "{}[structured output] rejected: {}{}{}",
+                    COLOR_GRAY,
+                    error,
+                    if *retrying { ", retrying" } else { "" },
+                    COLOR_RESET
+                );
+            }
+            AgentEventType::PluginSpans {
+                tool_call_name,
+                spans,
+                ..
+            } if self.verbose => {
+                for span in spans {
+                    let status = match &span.error {
+                        Some(error) => format!(" failed: {}", error),
+                        None => String::new(),
+                    };
+                    println!(
+                        "{}[span] {} › {} {}ms{}{}",
+                        COLOR_GRAY,
+                        tool_call_name,
+                        span.name,
+                        span.duration_ms,
+                        status,
+                        COLOR_RESET
+                    );
+                }
+            }
             AgentEventType::DiagnosticLog { message } => {
                 if self.verbose {
                     println!("{}[dbg] {}{}", COLOR_GRAY, message, COLOR_RESET);
@@ -868,6 +1082,58 @@ impl EventPrinter {
         }
     }
 
+    /// A tool call the model is still writing. Shown on the transient line
+    /// until `ToolExecutionStart` prints the final call.
+    fn tool_pending(&mut self, tool_call_id: &str, name: &str) {
+        self.state.tool_calls.insert(
+            tool_call_id.to_string(),
+            ToolCallState {
+                tool_call_id: tool_call_id.to_string(),
+                tool_name: name.to_string(),
+                input: serde_json::Value::String(String::new()),
+                status: ToolCallStatus::Pending,
+                result: None,
+                error: None,
+                start_time: None,
+                end_time: None,
+            },
+        );
+        self.show_pending_tool(tool_call_id);
+    }
+
+    fn tool_pending_args(&mut self, tool_call_id: &str, delta: &str) {
+        let Some(state) = self.state.tool_calls.get_mut(tool_call_id) else {
+            return;
+        };
+        if state.status != ToolCallStatus::Pending {
+            return;
+        }
+        if let serde_json::Value::String(args) = &mut state.input {
+            args.push_str(delta);
+        }
+        self.show_pending_tool(tool_call_id);
+    }
+
+    fn show_pending_tool(&mut self, tool_call_id: &str) {
+        if !self.show_tools {
+            return;
+        }
+        let Some(state) = self.state.tool_calls.get(tool_call_id) else {
+            return;
+        };
+        let args = state.input.as_str().unwrap_or_default();
+        let args: String = args.split_whitespace().collect::<Vec<_>>().join(" ");
+        // Keep the tail of the arguments so the line shows what is arriving.
+        let tail: String = if args.chars().count() > 60 {
+            let skip = args.chars().count() - 60;
+            format!("…{}", args.chars().skip(skip).collect::<String>())
+        } else {
+            args
+        };
+        let text = format!("⏺ {}({})", state.tool_name, tail);
+        self.show_planning(text);
+    }
+
     fn tool_start(&mut self, tool_call_id: &str, name: &str, input: &serde_json::Value) {
         if self.show_tools && !distri_formatter::state::is_probe_call(name, input) {
             println!(
@@ -26,6 +26,7 @@ fn make_usage(input: u32, output: u32) -> RunUsage {
         output_tokens: output,
         total_tokens: input + output,
         cached_tokens: 0,
+        cache_write_tokens: 0,
         estimated_tokens: 0,
         model: Some("gpt-5.1".to_string()),
         cost_usd: None,
@@ -126,6 +127,7 @@ fn update_from_usage_sets_model() {
         output_tokens: 50,
         total_tokens: 150,
         cached_tokens: 0,
+        cache_write_tokens: 0,
         estimated_tokens: 0,
         cost_usd: None,
     };
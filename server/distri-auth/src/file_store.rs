@@ -1,13 +1,32 @@
+//! `FileToolAuthStore`: auth sessions, secrets and OAuth2 states in
+//! `~/.distri/auth_sessions.json`, shared by every distri process of the
+//! user — an agent run and `distri auth login` may write at the same time.
+//!
+//! Writers take an exclusive advisory lock on `auth_sessions.json.lock`,
+//! re-read the file, apply their change and replace the file atomically
+//! (write a temp file, then rename), so no write is lost or torn. Before
+//! each write the previous contents are kept in `auth_sessions.json.bak`; a
+//! file that no longer parses — cut short by a crash or edited by hand — is
+//! recovered from it, and kept as `auth_sessions.json.corrupt` for a look.
+//! Readers take a shared lock and reload only when the file changed.
+
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
 use tokio::sync::RwLock;
 use tracing::{debug, info, warn};
 
 use distri_types::auth::{AuthError, AuthSecret, AuthSession, OAuth2State, ToolAuthStore};
 
+/// Layout version of the auth file this build writes. Files from before
+/// versioning are version 1; files of a newer version are read but never
+/// overwritten.
+pub const AUTH_FILE_SCHEMA_VERSION: u32 = 2;
+
 /// File-based persistent implementation of AuthStore
 /// Stores authentication sessions in JSON files in the user's home directory
 #[derive(Clone)]
@@ -18,19 +37,153 @@ pub struct FileToolAuthStore {
     sessions_cache: std::sync::Arc<RwLock<HashMap<String, AuthSession>>>,
     secrets_cache: std::sync::Arc<RwLock<HashMap<String, AuthSecret>>>,
     oauth2_states_cache: std::sync::Arc<RwLock<HashMap<String, OAuth2State>>>,
+    /// Modification time and length of the file the caches were loaded from.
+    loaded: std::sync::Arc<std::sync::Mutex<Option<FileStamp>>>,
 }
 
+type FileStamp = (SystemTime, u64);
+
 /// Serializable format for storing auth sessions in JSON
 #[derive(Debug, Serialize, Deserialize)]
 struct StoredAuthData {
+    #[serde(default = "legacy_schema_version")]
+    schema_version: u32,
+    #[serde(default)]
     sessions: HashMap<String, AuthSession>,
+    #[serde(default)]
     secrets: HashMap<String, AuthSecret>,
+    #[serde(default)]
     oauth2_states: HashMap<String, OAuth2State>,
+    /// Layout tag that builds from before `schema_version` require.
     version: String,
     created_at: chrono::DateTime<chrono::Utc>,
     updated_at: chrono::DateTime<chrono::Utc>,
 }
 
+fn legacy_schema_version() -> u32 {
+    1
+}
+
+impl StoredAuthData {
+    fn empty() -> Self {
+        let now = chrono::Utc::now();
+        Self {
+            schema_version: AUTH_FILE_SCHEMA_VERSION,
+            sessions: HashMap::new(),
+            secrets: HashMap::new(),
+            oauth2_states: HashMap::new(),
+            version: "1.0".to_string(),
+            created_at: now,
+            updated_at: now,
+        }
+    }
+}
+
+/// Why the auth file could not be read.
+enum ReadError {
+    Io(std::io::Error),
+    Parse(serde_json::Error),
+}
+
+/// `path` with `suffix` appended to its file name.
+fn sibling(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(suffix);
+    path.with_file_name(name)
+}
+
+fn file_stamp(path: &Path) -> Option<FileStamp> {
+    let metadata = fs::metadata(path).ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
+}
+
+/// Take the advisory lock every process sharing `path` honours: shared to
+/// read, exclusive to write. Released when the returned file is dropped.
+fn lock(path: &Path, exclusive: bool) -> std::io::Result<fs::File> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let file = fs::OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .read(true)
+        .write(true)
+        .open(sibling(path, ".lock"))?;
+    if exclusive {
+        file.lock()?;
+    } else {
+        file.lock_shared()?;
+    }
+    Ok(file)
+}
+
+fn read(path: &Path) -> Result<Option<StoredAuthData>, ReadError> {
+    match fs::read_to_string(path) {
+        Ok(content) => serde_json::from_str(&content)
+            .map(Some)
+            .map_err(ReadError::Parse),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(ReadError::Io(e)),
+    }
+}
+
+/// Load the auth file; the caller holds a lock. A file that does not parse
+/// is replaced by its backup, or by empty data without one; the flag says
+/// whether that happened.
+fn load(path: &Path) -> anyhow::Result<(StoredAuthData, bool)> {
+    let error = match read(path) {
+        Ok(Some(data)) => return Ok((data, false)),
+        Ok(None) => return Ok((StoredAuthData::empty(), false)),
+        Err(ReadError::Io(e)) => {
+            return Err(anyhow::anyhow!("Failed to read auth sessions file: {}", e))
+        }
+        Err(ReadError::Parse(e)) => e,
+    };
+    let backup = sibling(path, ".bak");
+    match read(&backup) {
+        Ok(Some(data)) => {
+            warn!(
+                "Auth sessions file {} is corrupt ({}), recovered it from {}",
+                path.display(),
+                error,
+                backup.display()
+            );
+            Ok((data, true))
+        }
+        _ => {
+            warn!(
+                "Auth sessions file {} is corrupt ({}) and has no usable backup, starting fresh",
+                path.display(),
+                error
+            );
+            Ok((StoredAuthData::empty(), true))
+        }
+    }
+}
+
+/// Replace `path` with `data` in one step: write a private temp file, sync
+/// it and rename it over `path`.
+fn write_atomic(path: &Path, data: &StoredAuthData) -> std::io::Result<()> {
+    let content = serde_json::to_vec_pretty(data)?;
+    let tmp = sibling(path, ".tmp");
+    let mut options = fs::OpenOptions::new();
+    options.create(true).truncate(true).write(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    let mut file = options.open(&tmp)?;
+    file.write_all(&content)?;
+    file.sync_all()?;
+    drop(file);
+    fs::rename(&tmp, path)?;
+    // Persist the rename itself; directories cannot be opened on Windows.
+    if let Some(dir) = path.parent() {
+        if let Ok(dir) = fs::File::open(dir) {
+            let _ = dir.sync_all();
+        }
+    }
+    Ok(())
+}
+
 impl FileToolAuthStore {
     /// Create a new file-based auth store
     /// Sessions will be stored in ~/.distri/auth_sessions.json
@@ -47,16 +200,18 @@ impl FileToolAuthStore {
             })?;
         }
 
-        let sessions_file = distri_dir.join("auth_sessions.json");
+        Ok(Self::with_file(distri_dir.join("auth_sessions.json")))
+    }
 
-        let store = Self {
-            auth_file: sessions_file,
+    /// A store kept in `auth_file` instead of the default location.
+    pub fn with_file(auth_file: impl Into<PathBuf>) -> Self {
+        Self {
+            auth_file: auth_file.into(),
             sessions_cache: std::sync::Arc::new(RwLock::new(HashMap::new())),
             secrets_cache: std::sync::Arc::new(RwLock::new(HashMap::new())),
             oauth2_states_cache: std::sync::Arc::new(RwLock::new(HashMap::new())),
-        };
-
-        Ok(store)
+            loaded: std::sync::Arc::new(std::sync::Mutex::new(None)),
+        }
     }
 
     /// Initialize and load existing data from files
@@ -68,78 +223,82 @@ impl FileToolAuthStore {
 
     /// Load all data from files into cache
     async fn load_from_files(&self) -> Result<(), AuthError> {
-        // Load sessions
-        if self.auth_file.exists() {
-            match fs::read_to_string(&self.auth_file) {
-                Ok(content) => {
-                    match serde_json::from_str::<StoredAuthData>(&content) {
-                        Ok(stored_data) => {
-                            let mut sessions = self.sessions_cache.write().await;
-                            *sessions = stored_data.sessions;
-
-                            let mut secrets = self.secrets_cache.write().await;
-                            *secrets = stored_data.secrets;
-
-                            let mut states = self.oauth2_states_cache.write().await;
-                            *states = stored_data.oauth2_states;
-
-                            debug!(
-                                "Loaded {} sessions, {} secrets, {} states from files",
-                                sessions.len(),
-                                secrets.len(),
-                                states.len()
-                            );
-                        }
-                        Err(e) => {
-                            warn!("Failed to parse auth sessions file ({}), starting fresh", e);
-                            // If we can't parse the file, delete it to start fresh
-                            let _ = fs::remove_file(&self.auth_file);
-                        }
-                    }
-                }
-                Err(e) => {
-                    warn!("Failed to read auth sessions file: {}, starting fresh", e);
-                }
-            }
-        } else {
-            debug!("Auth sessions file does not exist, starting fresh");
-        }
-
+        let path = self.auth_file.clone();
+        let (data, stamp) = tokio::task::spawn_blocking(move || -> anyhow::Result<_> {
+            let _lock = lock(&path, false)
+                .map_err(|e| anyhow::anyhow!("Failed to lock auth sessions file: {}", e))?;
+            let stamp = file_stamp(&path);
+            let (data, _) = load(&path)?;
+            Ok((data, stamp))
+        })
+        .await
+        .map_err(|e| AuthError::Storage(anyhow::anyhow!("Auth file task failed: {}", e)))??;
+        self.fill_caches(data, stamp).await;
         Ok(())
     }
 
-    /// Save all cached data to files
-    async fn save_to_files(&self) -> Result<(), AuthError> {
-        let sessions = self.sessions_cache.read().await;
-        let secrets = self.secrets_cache.read().await;
-        let states = self.oauth2_states_cache.read().await;
-
-        let stored_data = StoredAuthData {
-            sessions: sessions.clone(),
-            secrets: secrets.clone(),
-            oauth2_states: states.clone(),
-            version: "1.0".to_string(),
-            created_at: chrono::Utc::now(),
-            updated_at: chrono::Utc::now(),
-        };
-
-        let json_content = serde_json::to_string_pretty(&stored_data).map_err(|e| {
-            AuthError::Storage(anyhow::anyhow!("Failed to serialize auth data: {}", e))
-        })?;
-
-        fs::write(&self.auth_file, json_content).map_err(|e| {
-            AuthError::Storage(anyhow::anyhow!("Failed to write auth sessions file: {}", e))
-        })?;
+    /// Reload the caches when another process changed the file.
+    async fn refresh(&self) -> Result<(), AuthError> {
+        let current = file_stamp(&self.auth_file);
+        if *self.loaded.lock().unwrap() == current {
+            return Ok(());
+        }
+        self.load_from_files().await
+    }
 
+    async fn fill_caches(&self, data: StoredAuthData, stamp: Option<FileStamp>) {
+        let mut sessions = self.sessions_cache.write().await;
+        let mut secrets = self.secrets_cache.write().await;
+        let mut states = self.oauth2_states_cache.write().await;
+        *sessions = data.sessions;
+        *secrets = data.secrets;
+        *states = data.oauth2_states;
+        *self.loaded.lock().unwrap() = stamp;
         debug!(
-            "Saved {} sessions, {} secrets, {} states to {}",
+            "Loaded {} sessions, {} secrets, {} states from {}",
             sessions.len(),
             secrets.len(),
             states.len(),
             self.auth_file.display()
         );
+    }
 
-        Ok(())
+    /// Apply `change` to the file's current contents under the exclusive
+    /// lock, so concurrent writers never drop each other's changes.
+    async fn update<R, F>(&self, change: F) -> Result<R, AuthError>
+    where
+        F: FnOnce(&mut StoredAuthData) -> R + Send + 'static,
+        R: Send + 'static,
+    {
+        let path = self.auth_file.clone();
+        let (data, result, stamp) = tokio::task::spawn_blocking(move || -> anyhow::Result<_> {
+            let _lock = lock(&path, true)
+                .map_err(|e| anyhow::anyhow!("Failed to lock auth sessions file: {}", e))?;
+            let (mut data, recovered) = load(&path)?;
+            if data.schema_version > AUTH_FILE_SCHEMA_VERSION {
+                anyhow::bail!(
+                    "{} was written by a newer distri (schema version {}); upgrade to change it",
+                    path.display(),
+                    data.schema_version
+                );
+            }
+            if recovered {
+                let _ = fs::copy(&path, sibling(&path, ".corrupt"));
+            }
+            write_atomic(&sibling(&path, ".bak"), &data)
+                .map_err(|e| anyhow::anyhow!("Failed to back up auth sessions file: {}", e))?;
+
+            let result = change(&mut data);
+            data.schema_version = AUTH_FILE_SCHEMA_VERSION;
+            data.updated_at = chrono::Utc::now();
+            write_atomic(&path, &data)
+                .map_err(|e| anyhow::anyhow!("Failed to write auth sessions file: {}", e))?;
+            Ok((data, result, file_stamp(&path)))
+        })
+        .await
+        .map_err(|e| AuthError::Storage(anyhow::anyhow!("Auth file task failed: {}", e)))??;
+        self.fill_caches(data, stamp).await;
+        Ok(result)
     }
 
     /// Create session key from provider and user_id
@@ -159,13 +318,10 @@ impl ToolAuthStore for FileToolAuthStore {
         let session_key = self.make_session_key(auth_entity, user_id);
         debug!("Storing auth session for key: {}", session_key);
 
-        {
-            let mut sessions = self.sessions_cache.write().await;
-            sessions.insert(session_key, session);
-        }
-
-        self.save_to_files().await?;
-        Ok(())
+        self.update(move |data| {
+            data.sessions.insert(session_key, session);
+        })
+        .await
     }
 
     async fn get_session(
@@ -176,6 +332,7 @@ impl ToolAuthStore for FileToolAuthStore {
         let session_key = self.make_session_key(auth_entity, user_id);
         debug!("Getting auth session for key: {}", session_key);
 
+        self.refresh().await?;
         let sessions = self.sessions_cache.read().await;
         if let Some(session) = sessions.get(&session_key) {
             Ok(Some(session.clone()))
@@ -189,15 +346,8 @@ impl ToolAuthStore for FileToolAuthStore {
         let session_key = self.make_session_key(auth_entity, user_id);
         debug!("Removing auth session for key: {}", session_key);
 
-        let removed = {
-            let mut sessions = self.sessions_cache.write().await;
-            sessions.remove(&session_key).is_some()
-        };
-
-        if removed {
-            self.save_to_files().await?;
-        }
-        Ok(removed)
+        self.update(move |data| data.sessions.remove(&session_key).is_some())
+            .await
     }
 
     async fn store_secret(
@@ -214,13 +364,10 @@ impl ToolAuthStore for FileToolAuthStore {
 
         debug!("Storing secret with storage key: {}", storage_key);
 
-        {
-            let mut secrets = self.secrets_cache.write().await;
-            secrets.insert(storage_key, secret);
-        }
-
-        self.save_to_files().await?;
-        Ok(())
+        self.update(move |data| {
+            data.secrets.insert(storage_key, secret);
+        })
+        .await
     }
 
     async fn get_secret(
@@ -236,6 +383,7 @@ impl ToolAuthStore for FileToolAuthStore {
 
         debug!("Getting secret for storage key: {}", storage_key);
 
+        self.refresh().await?;
         let secrets = self.secrets_cache.read().await;
         Ok(secrets.get(&storage_key).cloned())
     }
@@ -253,32 +401,23 @@ impl ToolAuthStore for FileToolAuthStore {
 
         debug!("Removing secret for storage key: {}", storage_key);
 
-        let removed = {
-            let mut secrets = self.secrets_cache.write().await;
-            secrets.remove(&storage_key).is_some()
-        };
-
-        if removed {
-            self.save_to_files().await?;
-        }
-        Ok(removed)
+        self.update(move |data| data.secrets.remove(&storage_key).is_some())
+            .await
     }
 
     async fn store_oauth2_state(&self, state: OAuth2State) -> Result<(), AuthError> {
         debug!("Storing OAuth2 state: {}", state.state);
 
-        {
-            let mut states = self.oauth2_states_cache.write().await;
-            states.insert(state.state.clone(), state);
-        }
-
-        self.save_to_files().await?;
-        Ok(())
+        self.update(move |data| {
+            data.oauth2_states.insert(state.state.clone(), state);
+        })
+        .await
     }
 
     async fn get_oauth2_state(&self, state: &str) -> Result<Option<OAuth2State>, AuthError> {
         debug!("Getting OAuth2 state: {}", state);
 
+        self.refresh().await?;
         let states = self.oauth2_states_cache.read().await;
         if let Some(oauth_state) = states.get(state) {
             // Check if state is expired (10 minutes default)
@@ -295,25 +434,25 @@ impl ToolAuthStore for FileToolAuthStore {
     async fn remove_oauth2_state(&self, state: &str) -> Result<(), AuthError> {
         debug!("Removing OAuth2 state: {}", state);
 
-        {
-            let mut states = self.oauth2_states_cache.write().await;
-            states.remove(state);
-        }
-
-        self.save_to_files().await?;
-        Ok(())
+        let state = state.to_string();
+        self.update(move |data| {
+            data.oauth2_states.remove(&state);
+        })
+        .await
     }
     /// Get all stored sessions (for debugging/status)
     async fn list_sessions(
         &self,
         _user_id: &str,
     ) -> Result<HashMap<String, AuthSession>, AuthError> {
+        self.refresh().await?;
         let sessions = self.sessions_cache.read().await;
         Ok(sessions.clone())
     }
 
     /// Get all stored secrets (for loading into context)
     async fn list_secrets(&self, _user_id: &str) -> Result<HashMap<String, AuthSecret>, AuthError> {
+        self.refresh().await?;
         let secrets = self.secrets_cache.read().await;
         Ok(secrets.clone())
     }
@@ -329,21 +468,12 @@ impl FileToolAuthStore {
     pub async fn clear_all(&self) -> Result<(), AuthError> {
         info!("Clearing all authentication data");
 
-        {
-            let mut sessions = self.sessions_cache.write().await;
-            sessions.clear();
-        }
-        {
-            let mut secrets = self.secrets_cache.write().await;
-            secrets.clear();
-        }
-        {
-            let mut states = self.oauth2_states_cache.write().await;
-            states.clear();
-        }
-
-        self.save_to_files().await?;
-        Ok(())
+        self.update(|data| {
+            data.sessions.clear();
+            data.secrets.clear();
+            data.oauth2_states.clear();
+        })
+        .await
     }
 
     /// Remove a specific session  
@@ -355,20 +485,15 @@ impl FileToolAuthStore {
         let session_key = self.make_session_key(auth_entity, user_id);
         debug!("Removing session for key: {}", session_key);
 
-        let removed = {
-            let mut sessions = self.sessions_cache.write().await;
-            sessions.remove(&session_key).is_some()
-        };
-
-        if removed {
-            self.save_to_files().await?;
-        }
-
-        Ok(removed)
+        self.update(move |data| data.sessions.remove(&session_key).is_some())
+            .await
     }
 
     /// Get status of all stored authentication
     pub async fn get_auth_status(&self) -> HashMap<String, AuthSession> {
+        if let Err(e) = self.refresh().await {
+            warn!("Failed to reload auth sessions file: {}", e);
+        }
         let sessions = self.sessions_cache.read().await;
         sessions.clone()
     }
@@ -9,7 +9,7 @@ pub mod providers;
 // Re-export commonly used types and traits from distri-types
 pub use distri_types::auth::{
     AuthProvider, AuthSecret, AuthSession, AuthType, OAuth2FlowType, OAuth2State, OAuthHandler,
-    ProviderRegistry as BaseProviderRegistry,
+    ProviderRegistry as BaseProviderRegistry, ToolSessionStore, SESSION_TOKEN_SECRET_KEY,
 };
 
 pub use context::{current_workspace_id, UserContext};
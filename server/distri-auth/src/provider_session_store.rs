@@ -4,8 +4,11 @@ use tokio::sync::RwLock;
 use tracing::{debug, info};
 
 use crate::provider_registry::ProviderRegistry;
-use distri_types::auth::{AuthError, OAuthHandler, ProviderRegistry as BaseProviderRegistry};
-use distri_types::AuthSession;
+use distri_types::auth::{
+    AuthError, AuthSecret, OAuthHandler, ProviderRegistry as BaseProviderRegistry,
+    ToolSessionStore, SESSION_TOKEN_SECRET_KEY,
+};
+use distri_types::{AuthSession, McpSession};
 
 /// Provider-based session store that integrates authentication with MCP sessions
 /// This allows tools to authenticate via providers (google, github, etc.) rather than tool names
@@ -137,6 +140,113 @@ impl ProviderSessionStore {
 
         status
     }
+
+    /// Import legacy env-var sessions (`<TOOL>_SESSION_TOKEN`, see
+    /// [`legacy_session_env_var`]) for every registered tool into the auth
+    /// store as secrets of the tool's provider. Providers that already hold a
+    /// session or token are left alone. Returns the tools that were imported.
+    ///
+    /// `lookup` resolves an env var name; pass `|k| std::env::var(k).ok()`
+    /// to read the process environment.
+    pub async fn import_env_sessions(
+        &self,
+        user_id: &str,
+        lookup: impl Fn(&str) -> Option<String>,
+    ) -> Result<Vec<String>, AuthError> {
+        let mut imported = Vec::new();
+        for (tool_name, provider_name) in self.list_tool_providers().await {
+            let Some(token) = lookup(&legacy_session_env_var(&tool_name)) else {
+                continue;
+            };
+            if token.trim().is_empty() {
+                continue;
+            }
+            if self
+                .auth_handler
+                .get_session(&provider_name, user_id)
+                .await?
+                .is_some()
+                || self
+                    .auth_handler
+                    .get_secret(user_id, Some(&provider_name), SESSION_TOKEN_SECRET_KEY)
+                    .await?
+                    .is_some()
+            {
+                debug!(
+                    "Skipping env session for tool '{}': provider '{}' already authenticated",
+                    tool_name, provider_name
+                );
+                continue;
+            }
+            self.auth_handler
+                .store_secret(
+                    user_id,
+                    Some(&provider_name),
+                    AuthSecret {
+                        key: SESSION_TOKEN_SECRET_KEY.to_string(),
+                        secret: token.trim().to_string(),
+                    },
+                )
+                .await?;
+            info!(
+                "Imported env session for tool '{}' into provider '{}'",
+                tool_name, provider_name
+            );
+            imported.push(tool_name);
+        }
+        imported.sort();
+        Ok(imported)
+    }
+}
+
+/// Legacy samples read a tool's MCP token from `<TOOL>_SESSION_TOKEN`, with
+/// the tool name upper-cased and non-alphanumerics replaced by `_`.
+pub fn legacy_session_env_var(tool_name: &str) -> String {
+    let name: String = tool_name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_uppercase()
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    format!("{}_SESSION_TOKEN", name)
+}
+
+/// Resolves legacy per-tool lookups through the tool's provider: an OAuth
+/// session (refreshed when due) for registered providers, otherwise a
+/// [`SESSION_TOKEN_SECRET_KEY`] secret. Unmapped tools use their own name as
+/// the provider.
+#[async_trait::async_trait]
+impl ToolSessionStore for ProviderSessionStore {
+    async fn get_session(
+        &self,
+        tool_name: &str,
+        user_id: &str,
+    ) -> Result<Option<McpSession>, AuthError> {
+        let provider_name = self
+            .get_tool_provider(tool_name)
+            .await
+            .unwrap_or_else(|| tool_name.to_string());
+
+        if let Some(auth_type) = self.provider_registry.get_auth_type(&provider_name).await {
+            if let Some(session) = self
+                .auth_handler
+                .refresh_get_session(&provider_name, user_id, &auth_type, None)
+                .await?
+            {
+                return Ok(Some(session.into()));
+            }
+        }
+
+        Ok(self
+            .auth_handler
+            .get_secret(user_id, Some(&provider_name), SESSION_TOKEN_SECRET_KEY)
+            .await?
+            .map(Into::into))
+    }
 }
 
 /// Authentication status for a tool
@@ -146,3 +256,209 @@ pub struct AuthToolStatus {
     pub authenticated: bool,
     pub requires_auth: bool,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use distri_types::auth::{OAuth2State, ToolAuthStore};
+
+    #[derive(Default)]
+    struct MemoryAuthStore {
+        sessions: RwLock<HashMap<String, AuthSession>>,
+        secrets: RwLock<HashMap<String, AuthSecret>>,
+    }
+
+    fn secret_key(user_id: &str, auth_entity: Option<&str>, key: &str) -> String {
+        format!("{}:{}:{}", user_id, auth_entity.unwrap_or("global"), key)
+    }
+
+    #[async_trait]
+    impl ToolAuthStore for MemoryAuthStore {
+        async fn get_session(
+            &self,
+            auth_entity: &str,
+            user_id: &str,
+        ) -> Result<Option<AuthSession>, AuthError> {
+            let key = format!("{}:{}", user_id, auth_entity);
+            Ok(self.sessions.read().await.get(&key).cloned())
+        }
+
+        async fn store_session(
+            &self,
+            auth_entity: &str,
+            user_id: &str,
+            session: AuthSession,
+        ) -> Result<(), AuthError> {
+            let key = format!("{}:{}", user_id, auth_entity);
+            self.sessions.write().await.insert(key, session);
+            Ok(())
+        }
+
+        async fn remove_session(
+            &self,
+            auth_entity: &str,
+            user_id: &str,
+        ) -> Result<bool, AuthError> {
+            let key = format!("{}:{}", user_id, auth_entity);
+            Ok(self.sessions.write().await.remove(&key).is_some())
+        }
+
+        async fn store_secret(
+            &self,
+            user_id: &str,
+            auth_entity: Option<&str>,
+            secret: AuthSecret,
+        ) -> Result<(), AuthError> {
+            let key = secret_key(user_id, auth_entity, &secret.key);
+            self.secrets.write().await.insert(key, secret);
+            Ok(())
+        }
+
+        async fn get_secret(
+            &self,
+            user_id: &str,
+            auth_entity: Option<&str>,
+            key: &str,
+        ) -> Result<Option<AuthSecret>, AuthError> {
+            let key = secret_key(user_id, auth_entity, key);
+            Ok(self.secrets.read().await.get(&key).cloned())
+        }
+
+        async fn remove_secret(
+            &self,
+            user_id: &str,
+            auth_entity: Option<&str>,
+            key: &str,
+        ) -> Result<bool, AuthError> {
+            let key = secret_key(user_id, auth_entity, key);
+            Ok(self.secrets.write().await.remove(&key).is_some())
+        }
+
+        async fn store_oauth2_state(&self, _state: OAuth2State) -> Result<(), AuthError> {
+            Ok(())
+        }
+
+        async fn get_oauth2_state(&self, _state: &str) -> Result<Option<OAuth2State>, AuthError> {
+            Ok(None)
+        }
+
+        async fn remove_oauth2_state(&self, _state: &str) -> Result<(), AuthError> {
+            Ok(())
+        }
+
+        async fn list_secrets(
+            &self,
+            _user_id: &str,
+        ) -> Result<HashMap<String, AuthSecret>, AuthError> {
+            Ok(self.secrets.read().await.clone())
+        }
+
+        async fn list_sessions(
+            &self,
+            _user_id: &str,
+        ) -> Result<HashMap<String, AuthSession>, AuthError> {
+            Ok(self.sessions.read().await.clone())
+        }
+    }
+
+    async fn store_with_defaults() -> (ProviderSessionStore, Arc<OAuthHandler>) {
+        let registry = Arc::new(ProviderRegistry::new("http://localhost/cb"));
+        registry.load_default_providers().await.unwrap();
+        let handler = Arc::new(OAuthHandler::new(
+            Arc::new(MemoryAuthStore::default()),
+            "http://localhost/cb".to_string(),
+        ));
+        (
+            ProviderSessionStore::new(registry, handler.clone()),
+            handler,
+        )
+    }
+
+    #[test]
+    fn legacy_env_var_names() {
+        assert_eq!(legacy_session_env_var("github"), "GITHUB_SESSION_TOKEN");
+        assert_eq!(
+            legacy_session_env_var("google-drive.search"),
+            "GOOGLE_DRIVE_SEARCH_SESSION_TOKEN"
+        );
+    }
+
+    #[tokio::test]
+    async fn legacy_lookup_resolves_oauth_session_through_provider() {
+        let (store, handler) = store_with_defaults().await;
+        store
+            .register_tool_provider("search_drive".to_string(), "google".to_string())
+            .await;
+        handler
+            .store_session(
+                "google",
+                "user-1",
+                AuthSession::new("access-123".to_string(), None, None, None, vec![]),
+            )
+            .await
+            .unwrap();
+
+        let session = ToolSessionStore::get_session(&store, "search_drive", "user-1")
+            .await
+            .unwrap()
+            .expect("session");
+        assert_eq!(session.token, "access-123");
+        assert!(
+            ToolSessionStore::get_session(&store, "search_drive", "user-2")
+                .await
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[tokio::test]
+    async fn env_sessions_import_once_and_resolve_as_secrets() {
+        let (store, handler) = store_with_defaults().await;
+        store
+            .register_tools(HashMap::from([
+                ("internal_api".to_string(), "internal".to_string()),
+                ("search_drive".to_string(), "google".to_string()),
+            ]))
+            .await;
+        handler
+            .store_session(
+                "google",
+                "user-1",
+                AuthSession::new("existing".to_string(), None, None, None, vec![]),
+            )
+            .await
+            .unwrap();
+
+        let env = HashMap::from([
+            ("INTERNAL_API_SESSION_TOKEN", "legacy-token"),
+            ("SEARCH_DRIVE_SESSION_TOKEN", "stale"),
+        ]);
+        let imported = store
+            .import_env_sessions("user-1", |k| env.get(k).map(|v| v.to_string()))
+            .await
+            .unwrap();
+        assert_eq!(imported, vec!["internal_api".to_string()]);
+
+        let session = ToolSessionStore::get_session(&store, "internal_api", "user-1")
+            .await
+            .unwrap()
+            .expect("session");
+        assert_eq!(session.token, "legacy-token");
+        assert!(session.expiry.is_none());
+
+        // The existing OAuth session wins over the env token.
+        let drive = ToolSessionStore::get_session(&store, "search_drive", "user-1")
+            .await
+            .unwrap()
+            .expect("session");
+        assert_eq!(drive.token, "existing");
+
+        // Re-running is a no-op.
+        let again = store
+            .import_env_sessions("user-1", |k| env.get(k).map(|v| v.to_string()))
+            .await
+            .unwrap();
+        assert!(again.is_empty());
+    }
+}
@@ -0,0 +1,168 @@
+//! `FileToolAuthStore` against a real file: concurrent writers from
+//! separate store instances, recovery of a damaged file from its backup,
+//! and the schema version guard.
+
+use std::path::Path;
+
+use distri_auth::file_store::{FileToolAuthStore, AUTH_FILE_SCHEMA_VERSION};
+use distri_types::auth::{AuthSecret, AuthSession, ToolAuthStore};
+use serde_json::json;
+
+const USER: &str = "local";
+
+fn session(token: &str) -> AuthSession {
+    AuthSession {
+        access_token: token.to_string(),
+        refresh_token: None,
+        expires_at: None,
+        token_type: "Bearer".to_string(),
+        scopes: vec![],
+    }
+}
+
+fn secret(key: &str, value: &str) -> AuthSecret {
+    AuthSecret::new(key.to_string(), value.to_string())
+}
+
+fn sibling(path: &Path, suffix: &str) -> std::path::PathBuf {
+    let mut name = path.file_name().unwrap().to_os_string();
+    name.push(suffix);
+    path.with_file_name(name)
+}
+
+#[tokio::test]
+async fn concurrent_writers_keep_every_entry() {
+    let dir = tempfile::tempdir().unwrap();
+    let file = dir.path().join("auth_sessions.json");
+
+    let mut tasks = Vec::new();
+    for i in 0..16 {
+        // A fresh instance per writer, as separate processes would have.
+        let store = FileToolAuthStore::with_file(&file);
+        tasks.push(tokio::spawn(async move {
+            store
+                .store_secret(USER, None, secret(&format!("KEY_{i}"), &format!("v{i}")))
+                .await
+                .unwrap();
+            store
+                .store_session(&format!("provider_{i}"), USER, session(&format!("t{i}")))
+                .await
+                .unwrap();
+        }));
+    }
+    for task in tasks {
+        task.await.unwrap();
+    }
+
+    let store = FileToolAuthStore::with_file(&file);
+    let secrets = store.list_secrets(USER).await.unwrap();
+    let sessions = store.list_sessions(USER).await.unwrap();
+    assert_eq!(secrets.len(), 16);
+    assert_eq!(sessions.len(), 16);
+    assert_eq!(secrets["KEY_7"].secret, "v7");
+}
+
+#[tokio::test]
+async fn reads_see_other_instances_writes() {
+    let dir = tempfile::tempdir().unwrap();
+    let file = dir.path().join("auth_sessions.json");
+    let reader = FileToolAuthStore::with_file(&file);
+
+    let writer = FileToolAuthStore::with_file(&file);
+    writer
+        .store_secret(USER, Some("github"), secret("TOKEN", "abc"))
+        .await
+        .unwrap();
+
+    let found = reader
+        .get_secret(USER, Some("github"), "TOKEN")
+        .await
+        .unwrap();
+    assert_eq!(found.map(|s| s.secret), Some("abc".to_string()));
+}
+
+#[tokio::test]
+async fn truncated_file_is_recovered_from_backup() {
+    let dir = tempfile::tempdir().unwrap();
+    let file = dir.path().join("auth_sessions.json");
+    let store = FileToolAuthStore::with_file(&file);
+    store
+        .store_secret(USER, None, secret("FIRST", "1"))
+        .await
+        .unwrap();
+    store
+        .store_secret(USER, None, secret("SECOND", "2"))
+        .await
+        .unwrap();
+
+    // Cut the file short, as a crash mid-write without the rename would.
+    let contents = std::fs::read(&file).unwrap();
+    std::fs::write(&file, &contents[..contents.len() / 2]).unwrap();
+
+    let store = FileToolAuthStore::with_file(&file);
+    let secrets = store.list_secrets(USER).await.unwrap();
+    // The backup holds the file as it was before the last write.
+    assert!(secrets.contains_key("FIRST"));
+
+    store
+        .store_secret(USER, None, secret("THIRD", "3"))
+        .await
+        .unwrap();
+    assert!(sibling(&file, ".corrupt").exists());
+    let reloaded = FileToolAuthStore::with_file(&file);
+    let secrets = reloaded.list_secrets(USER).await.unwrap();
+    assert!(secrets.contains_key("FIRST"));
+    assert!(secrets.contains_key("THIRD"));
+}
+
+#[tokio::test]
+async fn legacy_file_without_schema_version_loads_and_upgrades() {
+    let dir = tempfile::tempdir().unwrap();
+    let file = dir.path().join("auth_sessions.json");
+    let legacy = json!({
+        "sessions": {},
+        "secrets": { "OPENAI_API_KEY": { "key": "OPENAI_API_KEY", "secret": "sk-1" } },
+        "oauth2_states": {},
+        "version": "1.0",
+        "created_at": "2025-01-01T00:00:00Z",
+        "updated_at": "2025-01-01T00:00:00Z"
+    });
+    std::fs::write(&file, serde_json::to_vec(&legacy).unwrap()).unwrap();
+
+    let store = FileToolAuthStore::with_file(&file);
+    let found = store
+        .get_secret(USER, None, "OPENAI_API_KEY")
+        .await
+        .unwrap();
+    assert_eq!(found.map(|s| s.secret), Some("sk-1".to_string()));
+
+    store
+        .store_secret(USER, None, secret("OTHER", "x"))
+        .await
+        .unwrap();
+    let written: serde_json::Value =
+        serde_json::from_slice(&std::fs::read(&file).unwrap()).unwrap();
+    assert_eq!(written["schema_version"], json!(AUTH_FILE_SCHEMA_VERSION));
+}
+
+#[tokio::test]
+async fn newer_schema_version_is_not_overwritten() {
+    let dir = tempfile::tempdir().unwrap();
+    let file = dir.path().join("auth_sessions.json");
+    let newer = json!({
+        "schema_version": AUTH_FILE_SCHEMA_VERSION + 1,
+        "sessions": {},
+        "secrets": {},
+        "oauth2_states": {},
+        "version": "1.0",
+        "created_at": "2025-01-01T00:00:00Z",
+        "updated_at": "2025-01-01T00:00:00Z"
+    });
+    let original = serde_json::to_vec(&newer).unwrap();
+    std::fs::write(&file, &original).unwrap();
+
+    let store = FileToolAuthStore::with_file(&file);
+    let result = store.store_secret(USER, None, secret("KEY", "v")).await;
+    assert!(result.is_err());
+    assert_eq!(std::fs::read(&file).unwrap(), original);
+}
@@ -11,6 +11,8 @@ pub use service::{
 use thiserror::Error;
 pub mod mapper;
 pub mod messages;
+pub mod registry;
+pub mod uploads;
 
 /// Validate that a `Message` is well-formed before dispatching to an agent.
 ///
@@ -0,0 +1,187 @@
+//! Publishes agent cards to the configured A2A registry.
+//!
+//! See [`distri_types::agent_registry`] for the protocol. The publisher keeps
+//! the hash of every card it pushed: a change notification
+//! ([`AgentOrchestrator::notify_agents_changed`]) pushes only new or changed
+//! cards and retracts removed ones, while the periodic refresh pushes every
+//! card again so the registry sees the server is still serving them.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use distri_types::agent_registry::{
+    card_hash, AgentRegistryConfig, CardMetadata, CardPublication, SIGNATURE_ALGORITHM,
+};
+use distri_types::configuration::ServerConfig;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use tokio::sync::Mutex;
+
+use crate::AgentOrchestrator;
+
+/// Agents read from the store per page.
+const PAGE_SIZE: usize = 100;
+
+pub struct RegistryPublisher {
+    orchestrator: Arc<AgentOrchestrator>,
+    config: AgentRegistryConfig,
+    server_config: ServerConfig,
+    client: reqwest::Client,
+    /// Card hash per agent name, as last accepted by the registry.
+    published: Mutex<HashMap<String, String>>,
+}
+
+impl RegistryPublisher {
+    /// `None` when no registry is configured.
+    pub fn new(orchestrator: Arc<AgentOrchestrator>) -> Option<Self> {
+        let config = orchestrator.agent_registry.clone()?;
+        let server_config = ServerConfig {
+            base_url: config.public_url.trim_end_matches('/').to_string(),
+            ..Default::default()
+        };
+        Some(Self {
+            orchestrator,
+            config,
+            server_config,
+            client: reqwest::Client::new(),
+            published: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Publish every card now, then keep the registry in sync.
+    pub fn start(self) -> tokio::task::JoinHandle<()> {
+        tracing::info!(registry = %self.config.url, "agent card publisher started");
+        tokio::spawn(async move { self.run().await })
+    }
+
+    async fn run(&self) {
+        let refresh = Duration::from_secs(self.config.refresh_secs.max(10));
+        let mut force = true;
+        loop {
+            self.sync(force).await;
+            force = tokio::select! {
+                _ = self.orchestrator.agents_changed.notified() => false,
+                _ = tokio::time::sleep(refresh) => true,
+            };
+        }
+    }
+
+    /// Push new and changed cards (every card when `force`) and retract the
+    /// cards of agents that no longer exist. Failed requests are retried on
+    /// the next sync.
+    pub async fn sync(&self, force: bool) {
+        let cards = self.current_cards().await;
+        let mut published = self.published.lock().await;
+
+        let removed: Vec<String> = published
+            .keys()
+            .filter(|name| !cards.contains_key(*name))
+            .cloned()
+            .collect();
+        for (name, card) in cards {
+            let hash = card_hash(&card);
+            if !force && published.get(&name) == Some(&hash) {
+                continue;
+            }
+            match self.publish(&name, card, &hash).await {
+                Ok(()) => {
+                    published.insert(name, hash);
+                }
+                Err(e) => tracing::warn!(agent = %name, "failed to publish agent card: {}", e),
+            }
+        }
+
+        for name in removed {
+            match self.retract(&name).await {
+                Ok(()) => {
+                    published.remove(&name);
+                }
+                Err(e) => tracing::warn!(agent = %name, "failed to retract agent card: {}", e),
+            }
+        }
+    }
+
+    async fn current_cards(&self) -> HashMap<String, distri_a2a::AgentCard> {
+        let store = &self.orchestrator.stores.agent_store;
+        let mut cards = HashMap::new();
+        let mut cursor = None;
+        loop {
+            let (agents, next) = store.list(cursor, Some(PAGE_SIZE)).await;
+            let last_page = agents.is_empty() || next.is_none();
+            for agent in agents {
+                cards.insert(
+                    agent.get_name().to_string(),
+                    agent.to_card(&self.server_config),
+                );
+            }
+            if last_page {
+                break;
+            }
+            cursor = next;
+        }
+        cards
+    }
+
+    async fn publish(
+        &self,
+        name: &str,
+        card: distri_a2a::AgentCard,
+        hash: &str,
+    ) -> anyhow::Result<()> {
+        let metadata = self.sign(CardMetadata {
+            publisher: self.config.publisher.clone(),
+            published_at: chrono::Utc::now(),
+            content_hash: hash.to_string(),
+            signature: None,
+            algorithm: None,
+        });
+        self.request(reqwest::Method::PUT, name)
+            .json(&CardPublication { card, metadata })
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+
+    async fn retract(&self, name: &str) -> anyhow::Result<()> {
+        let response = self.request(reqwest::Method::DELETE, name).send().await?;
+        // Already gone is as good as retracted.
+        if response.status() != reqwest::StatusCode::NOT_FOUND {
+            response.error_for_status()?;
+        }
+        Ok(())
+    }
+
+    fn request(&self, method: reqwest::Method, name: &str) -> reqwest::RequestBuilder {
+        let url = format!("{}/agents/{}", self.config.url.trim_end_matches('/'), name);
+        let builder = self
+            .client
+            .request(method, url)
+            .timeout(Duration::from_secs(30));
+        match &self.config.token {
+            Some(token) => builder.bearer_auth(token),
+            None => builder,
+        }
+    }
+
+    fn sign(&self, mut metadata: CardMetadata) -> CardMetadata {
+        if let Some(key) = &self.config.signing_key {
+            metadata.signature = Some(sign(key, &metadata.signing_input()));
+            metadata.algorithm = Some(SIGNATURE_ALGORITHM.to_string());
+        }
+        metadata
+    }
+}
+
+/// HMAC-SHA256 of `input` under `key`, hex encoded.
+pub fn sign(key: &str, input: &str) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(key.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(input.as_bytes());
+    mac.finalize()
+        .into_bytes()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
@@ -12,8 +12,8 @@
 
 use crate::a2a::mapper::map_agent_event;
 use crate::a2a::stream::{
-    init_thread_get_message, prepare_execution, spawn_background_execution,
-    validate_provider_secrets,
+    init_thread_get_message, message_model_overrides, prepare_execution,
+    spawn_background_execution, validate_provider_secrets,
 };
 use crate::a2a::{agent_error_to_jsonrpc, single_error_frame_stream, A2AError, SseMessage};
 use crate::agent::types::ExecutorContextMetadata;
@@ -150,6 +150,17 @@ impl A2AService {
                     agent_error_to_jsonrpc(e),
                 )) as BoxedSseStream),
             },
+            "message/send" if self.should_enqueue(&input.req) => {
+                match self.enqueue_message(input).await {
+                    Ok(task) => Either::Right(JsonRpcResponse::success(
+                        req_id,
+                        serde_json::to_value(task).unwrap_or_default(),
+                    )),
+                    Err(e) => {
+                        Either::Right(JsonRpcResponse::error(req_id, agent_error_to_jsonrpc(e)))
+                    }
+                }
+            }
             "message/send" => match self.send_message(input).await {
                 Ok(task) => Either::Right(JsonRpcResponse::success(
                     req_id,
@@ -253,6 +264,102 @@ impl A2AService {
         Ok(updated_task)
     }
 
+    /// `message/send` with `configuration.blocking: false` goes to the
+    /// background job queue when one is configured.
+    fn should_enqueue(&self, req: &JsonRpcRequest) -> bool {
+        if self.orchestrator.background_jobs.is_none()
+            || self.orchestrator.stores.background_job_store.is_none()
+        {
+            return false;
+        }
+        req.params
+            .get("configuration")
+            .filter(|c| c.is_object())
+            .map(|c| !c.get("blocking").and_then(|b| b.as_bool()).unwrap_or(false))
+            .unwrap_or(false)
+    }
+
+    /// Persist a `message/send` as a background job and acknowledge it with
+    /// a `submitted` task. The client follows progress with `tasks/get` or
+    /// `tasks/resubscribe`. Re-sending the same message (same `messageId`,
+    /// or same `metadata.idempotency_key`) returns the existing job.
+    pub async fn enqueue_message(&self, input: ServiceRequest) -> Result<Task, AgentError> {
+        let config =
+            self.orchestrator.background_jobs.as_ref().ok_or_else(|| {
+                AgentError::Validation("background jobs are not enabled".to_string())
+            })?;
+        let store = self
+            .orchestrator
+            .stores
+            .background_job_store
+            .clone()
+            .ok_or_else(|| {
+                AgentError::Validation(
+                    "the configured store does not support background jobs".to_string(),
+                )
+            })?;
+
+        let mut params: MessageSendParams = serde_json::from_value(input.req.params)
+            .map_err(|e| AgentError::Validation(format!("Invalid params: {}", e)))?;
+        validate_provider_secrets(&self.orchestrator, &input.agent_id).await?;
+        // Rejected now rather than when a worker picks the job up.
+        let agent = self.orchestrator.get_agent(&input.agent_id).await;
+        message_model_overrides(agent.as_ref(), &params)?;
+        let agent_id = self.orchestrator.resolve_agent_name(&input.agent_id).await;
+
+        let thread_id = params
+            .message
+            .context_id
+            .clone()
+            .unwrap_or_else(|| Uuid::new_v4().to_string());
+        params.message.context_id = Some(thread_id.clone());
+        let task_id = params
+            .message
+            .task_id
+            .clone()
+            .unwrap_or_else(|| Uuid::new_v4().to_string());
+        let client_key = params
+            .metadata
+            .as_ref()
+            .and_then(|m| m.get("idempotency_key"))
+            .and_then(|k| k.as_str())
+            .map(String::from)
+            .unwrap_or_else(|| params.message.message_id.clone());
+
+        let job = store
+            .enqueue(distri_types::jobs::NewBackgroundJob {
+                idempotency_key: format!("{}:{}:{}", input.user_id, agent_id, client_key),
+                agent_id,
+                user_id: input.user_id,
+                workspace_id: input.workspace_id,
+                task_id,
+                thread_id,
+                params: serde_json::to_value(&params)?,
+                max_attempts: config.max_attempts,
+            })
+            .await
+            .map_err(|e| AgentError::Session(format!("Failed to enqueue job: {}", e)))?;
+        self.orchestrator.background_jobs_wake.notify_one();
+
+        Ok(background_job_task(&job))
+    }
+
+    /// The queued job behind `task_id`, if it has not produced a task
+    /// record yet.
+    async fn pending_background_job(
+        &self,
+        task_id: &str,
+    ) -> Option<distri_types::jobs::BackgroundJob> {
+        let store = self.orchestrator.stores.background_job_store.as_ref()?;
+        match store.get_by_task_id(task_id).await {
+            Ok(job) => job,
+            Err(e) => {
+                tracing::warn!("Failed to look up background job for {}: {}", task_id, e);
+                None
+            }
+        }
+    }
+
     pub async fn get_task(&self, params: serde_json::Value) -> Result<Task, A2AError> {
         let params: TaskIdParams = serde_json::from_value(params)?;
 
@@ -264,7 +371,10 @@ impl A2AService {
             .await
         {
             Ok(Some(task)) => Ok(task.into()),
-            Ok(None) => Err(A2AError::ApiError("Task not found".to_string())),
+            Ok(None) => match self.pending_background_job(&params.id).await {
+                Some(job) => Ok(background_job_task(&job)),
+                None => Err(A2AError::ApiError("Task not found".to_string())),
+            },
             Err(e) => Err(A2AError::ApiError(format!("Failed to get task: {}", e))),
         }
     }
@@ -272,6 +382,22 @@ impl A2AService {
     pub async fn cancel_task(&self, params: serde_json::Value) -> Result<Task, AgentError> {
         let params: TaskIdParams = serde_json::from_value(params)?;
 
+        // A job still waiting in the queue has no run to signal yet.
+        if let Some(mut job) = self.pending_background_job(&params.id).await {
+            if job.status == distri_types::jobs::BackgroundJobStatus::Queued {
+                if let Some(store) = &self.orchestrator.stores.background_job_store {
+                    if store
+                        .cancel_queued(&job.id)
+                        .await
+                        .map_err(|e| AgentError::Session(e.to_string()))?
+                    {
+                        job.status = distri_types::jobs::BackgroundJobStatus::Canceled;
+                        return Ok(background_job_task(&job));
+                    }
+                }
+            }
+        }
+
         // Signal abort via coordinator (sends CancellationSignal, works across nodes)
         if let Err(e) = self
             .orchestrator
@@ -375,7 +501,7 @@ impl A2AService {
         validate_provider_secrets(&self.orchestrator, &agent_id).await?;
 
         // Step 4: Init thread + build the core Message.
-        let (thread_id, message) = init_thread_get_message(
+        let (thread_id, mut message) = init_thread_get_message(
             agent_id.clone(),
             self.orchestrator.clone(),
             &params,
@@ -384,11 +510,21 @@ impl A2AService {
         .await?;
 
         // Step 5: Prepare execution context (metadata, browser, overrides).
-        let (exec_ctx, definition_overrides) =
-            prepare_execution(&agent_id, &params, &self.orchestrator, &executor_context).await?;
+        let (exec_ctx, definition_overrides) = prepare_execution(
+            &agent_id,
+            &thread_id,
+            &params,
+            &self.orchestrator,
+            &executor_context,
+        )
+        .await?;
 
         let task_id = exec_ctx.task_id.clone();
 
+        // Attached files become task artifacts before the agent sees them.
+        super::uploads::store_message_files(&self.orchestrator, &thread_id, &task_id, &mut message)
+            .await?;
+
         // Step 6: Register the task — wires cancellation + mailbox into ctx.
         let (executor_context_arc, event_rx) = self
             .orchestrator
@@ -740,6 +876,39 @@ pub fn map_agent_error(e: AgentError) -> JsonRpcError {
     agent_error_to_jsonrpc(e)
 }
 
+/// A2A view of a background job that has no task record yet (queued, or
+/// finished without ever starting).
+fn background_job_task(job: &distri_types::jobs::BackgroundJob) -> Task {
+    use distri_types::jobs::BackgroundJobStatus;
+    let state = match job.status {
+        BackgroundJobStatus::Queued => distri_a2a::TaskState::Submitted,
+        BackgroundJobStatus::Running => distri_a2a::TaskState::Working,
+        BackgroundJobStatus::Completed => distri_a2a::TaskState::Completed,
+        BackgroundJobStatus::Failed => distri_a2a::TaskState::Failed,
+        BackgroundJobStatus::Canceled => distri_a2a::TaskState::Canceled,
+    };
+    Task {
+        kind: distri_a2a::EventKind::Task,
+        id: job.task_id.clone(),
+        context_id: job.thread_id.clone(),
+        status: distri_a2a::TaskStatus {
+            state,
+            message: None,
+            timestamp: Some(job.updated_at.to_rfc3339()),
+        },
+        artifacts: vec![],
+        history: vec![],
+        metadata: Some(serde_json::json!({
+            "background_job": {
+                "id": job.id,
+                "status": job.status,
+                "attempts": job.attempts,
+                "last_error": job.last_error,
+            }
+        })),
+    }
+}
+
 /// Build the terminal assistant `Message` from the completed task's final
 /// result. Shared between `send_message` (as `Task.status.message`) and
 /// `run_streaming_session` (as the trailing SSE frame) so both response
@@ -748,6 +917,9 @@ pub fn map_agent_error(e: AgentError) -> JsonRpcError {
 /// Returns `None` when the agent never called `final` (or the final result
 /// was empty) — consumers should leave the corresponding slot unset in
 /// that case.
+///
+/// When the model's output was cut off at its budget, the message metadata
+/// carries `truncated` and `continuations` so clients can flag it.
 pub async fn build_final_message(
     executor_context: &ExecutorContext,
 ) -> Option<distri_a2a::Message> {
@@ -759,6 +931,13 @@ pub async fn build_final_message(
     if text.is_empty() {
         return None;
     }
+    let usage = executor_context.get_usage().await;
+    let metadata = (usage.truncated || usage.continuations > 0).then(|| {
+        serde_json::json!({
+            "truncated": usage.truncated,
+            "continuations": usage.continuations,
+        })
+    });
     Some(distri_a2a::Message {
         kind: distri_a2a::EventKind::Message,
         message_id: Uuid::new_v4().to_string(),
@@ -768,6 +947,6 @@ pub async fn build_final_message(
         task_id: Some(executor_context.task_id.clone()),
         reference_task_ids: vec![],
         extensions: vec![],
-        metadata: None,
+        metadata,
     })
 }
@@ -15,7 +15,7 @@ use crate::AgentError;
 use anyhow::anyhow;
 use distri_a2a::MessageSendParams;
 use distri_auth::context::with_user_and_workspace;
-use distri_types::configuration::{AgentConfig, DefinitionOverrides};
+use distri_types::configuration::{AgentConfig, DefinitionOverrides, MessageModelOverrides};
 
 use std::sync::Arc;
 
@@ -127,6 +127,7 @@ pub async fn init_thread_get_message(
 /// Returns `(ExecutorContext, definition_overrides)` or an error.
 pub async fn prepare_execution(
     agent_id: &str,
+    thread_id: &str,
     params: &MessageSendParams,
     executor: &Arc<AgentOrchestrator>,
     executor_context: &Arc<ExecutorContext>,
@@ -183,8 +184,13 @@ pub async fn prepare_execution(
         definition_overrides = overrides;
     }
 
+    let agent = executor.get_agent(agent_id).await;
+    if let Some(requested) = message_model_overrides(agent.as_ref(), params)? {
+        requested.apply_to(&mut definition_overrides);
+    }
+
     // Determine if browser should be used
-    let mut should_stream_browser = match executor.get_agent(agent_id).await {
+    let mut should_stream_browser = match &agent {
         Some(AgentConfig::StandardAgent(def)) => def.should_use_browser(),
         _ => false,
     };
@@ -193,16 +199,57 @@ pub async fn prepare_execution(
         should_stream_browser = flag;
     }
 
-    // If browser is needed but no session from UI, create one now
+    // If browser is needed but no session from UI, reuse the thread's
+    // session (kept while hibernation is on) or create one now
     if should_stream_browser && exec_ctx.browser_session_id.is_none() {
-        if let Some((session_id, _frame_url, _sse_url)) = create_browser_session().await {
-            exec_ctx.browser_session_id = Some(session_id);
+        let keep_session = executor.hibernation.is_some();
+        if keep_session {
+            exec_ctx.browser_session_id = executor.thread_resources.browser_session(thread_id);
+        }
+        if exec_ctx.browser_session_id.is_none() {
+            if let Some((session_id, _frame_url, _sse_url)) = create_browser_session().await {
+                if keep_session {
+                    executor
+                        .thread_resources
+                        .set_browser_session(thread_id, session_id.clone());
+                }
+                exec_ctx.browser_session_id = Some(session_id);
+            }
         }
     }
 
     Ok((exec_ctx, definition_overrides))
 }
 
+/// The model parameters `params.configuration` asks for, checked against
+/// the agent's `message_overrides`. `None` when none are requested.
+pub fn message_model_overrides(
+    agent: Option<&AgentConfig>,
+    params: &MessageSendParams,
+) -> Result<Option<MessageModelOverrides>, AgentError> {
+    let Some(configuration) = &params.configuration else {
+        return Ok(None);
+    };
+    let requested = MessageModelOverrides::from_send_configuration(configuration)
+        .map_err(AgentError::Validation)?;
+    if requested.is_empty() {
+        return Ok(None);
+    }
+    let policy = match agent {
+        Some(AgentConfig::StandardAgent(def)) => def.message_overrides.as_ref(),
+        _ => None,
+    };
+    let Some(policy) = policy else {
+        return Err(AgentError::Validation(
+            "this agent does not accept per-message model overrides".to_string(),
+        ));
+    };
+    policy
+        .check(&requested)
+        .map_err(|e| AgentError::Validation(format!("invalid model override: {}", e)))?;
+    Ok(Some(requested))
+}
+
 /// Spawn the agent execution in the background, publishing events to the worker pool.
 /// This is the core of the background-first execution model.
 pub(crate) fn spawn_background_execution(
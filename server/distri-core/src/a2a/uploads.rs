@@ -0,0 +1,351 @@
+//! Files attached to A2A messages.
+//!
+//! Clients attach files as base64 `file` parts, or as multipart form fields
+//! that the server folds into the same parts with [`attach_files`].
+//! [`check_upload_limits`] enforces the server's [`UploadLimits`] before
+//! dispatch. Once the task exists, [`store_message_files`] saves every inline
+//! file into the task's artifact namespace and rewrites the message for the
+//! model:
+//!
+//! - images and PDFs stay inline so multimodal providers receive them;
+//! - any other file is replaced by a `Part::Artifact` reference;
+//! - a text part listing each stored file and its artifact path is appended,
+//!   so the model can hand the path to file and artifact tools.
+//!
+//! Files are stored base64-encoded, like every other artifact written here.
+
+use base64::{engine::general_purpose, Engine as _};
+use distri_a2a::{FileObject, FilePart, JsonRpcRequest};
+use distri_types::configuration::UploadLimits;
+use distri_types::{FileMetadata, FileType, Message, Part};
+
+use crate::{AgentError, AgentOrchestrator};
+
+/// Characters of a text file kept as the artifact preview.
+const PREVIEW_CHARS: usize = 500;
+
+/// Reject a `message/send` or `message/stream` request whose attached files
+/// break `limits`. Requests without a message are left alone.
+pub fn check_upload_limits(req: &JsonRpcRequest, limits: &UploadLimits) -> Result<(), AgentError> {
+    let Some(message) = req.params.get("message") else {
+        return Ok(());
+    };
+    let message: distri_a2a::Message = serde_json::from_value(message.clone())
+        .map_err(|e| AgentError::Validation(format!("Invalid message: {}", e)))?;
+
+    let files: Vec<&FilePart> = message
+        .parts
+        .iter()
+        .filter_map(|part| match part {
+            distri_a2a::Part::File(file) => Some(file),
+            _ => None,
+        })
+        .collect();
+    if files.len() > limits.max_files {
+        return Err(AgentError::Validation(format!(
+            "Too many files: {} attached, at most {} allowed",
+            files.len(),
+            limits.max_files
+        )));
+    }
+    for file in files {
+        let FileObject::WithBytes {
+            bytes,
+            mime_type,
+            name,
+        } = &file.file
+        else {
+            continue;
+        };
+        let label = name.as_deref().unwrap_or("file");
+        let mime_type = mime_type.as_deref().unwrap_or("application/octet-stream");
+        if !limits.allows_mime_type(mime_type) {
+            return Err(AgentError::Validation(format!(
+                "{}: file type '{}' is not accepted",
+                label, mime_type
+            )));
+        }
+        let size = decoded_len(bytes);
+        if size > limits.max_file_bytes {
+            return Err(AgentError::Validation(format!(
+                "{}: {} bytes exceeds the {} byte limit",
+                label, size, limits.max_file_bytes
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Append `files` to the parts of the request's message.
+pub fn attach_files(req: &mut JsonRpcRequest, files: Vec<FilePart>) -> Result<(), AgentError> {
+    if files.is_empty() {
+        return Ok(());
+    }
+    let parts = req
+        .params
+        .get_mut("message")
+        .and_then(|m| m.get_mut("parts"))
+        .and_then(|p| p.as_array_mut())
+        .ok_or_else(|| AgentError::Validation("Request has no message parts".to_string()))?;
+    for file in files {
+        parts.push(serde_json::to_value(distri_a2a::Part::File(file))?);
+    }
+    Ok(())
+}
+
+/// Save the inline files of `message` as artifacts of the task and rewrite
+/// the message as described in the module docs.
+pub async fn store_message_files(
+    orchestrator: &AgentOrchestrator,
+    thread_id: &str,
+    task_id: &str,
+    message: &mut Message,
+) -> Result<(), AgentError> {
+    let has_inline_files = message.parts.iter().any(|part| {
+        matches!(
+            part,
+            Part::Image(FileType::Bytes { .. }) | Part::File(FileType::Bytes { .. })
+        )
+    });
+    if !has_inline_files {
+        return Ok(());
+    }
+
+    let wrapper = orchestrator
+        .session_filesystem
+        .create_artifact_wrapper(distri_filesystem::ArtifactWrapper::task_namespace(
+            thread_id, task_id,
+        ))
+        .await
+        .map_err(|e| AgentError::Session(format!("Failed to open artifact store: {}", e)))?;
+
+    let mut stored: Vec<FileMetadata> = Vec::new();
+    let mut parts = Vec::with_capacity(message.parts.len() + 1);
+    for part in std::mem::take(&mut message.parts) {
+        let (bytes, mime_type, name) = match &part {
+            Part::Image(FileType::Bytes {
+                bytes,
+                mime_type,
+                name,
+            })
+            | Part::File(FileType::Bytes {
+                bytes,
+                mime_type,
+                name,
+            }) => (bytes, mime_type, name),
+            _ => {
+                parts.push(part);
+                continue;
+            }
+        };
+
+        let decoded = general_purpose::STANDARD
+            .decode(bytes)
+            .map_err(|e| AgentError::Validation(format!("Invalid base64 file data: {}", e)))?;
+        let filename = unique_filename(name.as_deref(), mime_type, stored.len() + 1, &stored);
+        let preview = is_text_like(mime_type).then(|| {
+            String::from_utf8_lossy(&decoded)
+                .chars()
+                .take(PREVIEW_CHARS)
+                .collect()
+        });
+        wrapper
+            .save_artifact(&filename, bytes)
+            .await
+            .map_err(|e| AgentError::Session(format!("Failed to save {}: {}", filename, e)))?;
+
+        let now = chrono::Utc::now();
+        let metadata = FileMetadata {
+            file_id: filename.clone(),
+            relative_path: format!("{}/content/{}", wrapper.prefix_path(), filename),
+            size: decoded.len() as u64,
+            content_type: Some(mime_type.clone()).filter(|m| !m.is_empty()),
+            original_filename: name.clone(),
+            created_at: now,
+            updated_at: now,
+            checksum: None,
+            stats: None,
+            preview,
+        };
+
+        if keeps_inline(mime_type) {
+            parts.push(part);
+        } else {
+            parts.push(Part::Artifact(metadata.clone()));
+        }
+        stored.push(metadata);
+    }
+
+    parts.push(Part::Text(attachment_note(&stored)));
+    message.parts = parts;
+    Ok(())
+}
+
+/// What the model is told about the stored files.
+fn attachment_note(stored: &[FileMetadata]) -> String {
+    let mut note = String::from("Attached files, saved as artifacts:");
+    for file in stored {
+        note.push_str(&format!(
+            "\n- {} ({}, {} bytes): {}",
+            file.file_id,
+            file.content_type.as_deref().unwrap_or("unknown type"),
+            file.size,
+            file.relative_path
+        ));
+    }
+    note
+}
+
+/// Providers accept images and PDFs directly; other files go through tools.
+fn keeps_inline(mime_type: &str) -> bool {
+    mime_type.starts_with("image/") || mime_type == "application/pdf"
+}
+
+fn is_text_like(mime_type: &str) -> bool {
+    mime_type.starts_with("text/")
+        || matches!(
+            mime_type,
+            "application/json"
+                | "application/xml"
+                | "application/yaml"
+                | "application/x-yaml"
+                | "application/csv"
+        )
+}
+
+/// A safe artifact filename: the client's name without any path, or
+/// `upload-{n}.{ext}`, made unique among files already stored.
+fn unique_filename(
+    name: Option<&str>,
+    mime_type: &str,
+    n: usize,
+    stored: &[FileMetadata],
+) -> String {
+    let base = name
+        .and_then(|s| s.rsplit(['/', '\\']).next())
+        .map(|s| {
+            s.chars()
+                .map(|c| {
+                    if c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_') {
+                        c
+                    } else {
+                        '_'
+                    }
+                })
+                .collect::<String>()
+        })
+        .filter(|s| !s.trim_matches(['.', '_']).is_empty())
+        .unwrap_or_else(|| format!("upload-{}.{}", n, extension_for(mime_type)));
+    if !stored.iter().any(|f| f.file_id == base) {
+        return base;
+    }
+    let (stem, ext) = match base.rsplit_once('.') {
+        Some((stem, ext)) => (stem.to_string(), format!(".{}", ext)),
+        None => (base.clone(), String::new()),
+    };
+    (2..)
+        .map(|i| format!("{}-{}{}", stem, i, ext))
+        .find(|candidate| !stored.iter().any(|f| &f.file_id == candidate))
+        .unwrap_or(base)
+}
+
+fn extension_for(mime_type: &str) -> &'static str {
+    match mime_type {
+        "image/png" => "png",
+        "image/jpeg" => "jpg",
+        "image/gif" => "gif",
+        "image/webp" => "webp",
+        "application/pdf" => "pdf",
+        "application/json" => "json",
+        "text/csv" | "application/csv" => "csv",
+        "text/markdown" => "md",
+        "text/html" => "html",
+        "text/plain" => "txt",
+        _ => "bin",
+    }
+}
+
+/// Decoded size of base64 `data`, without decoding it.
+fn decoded_len(data: &str) -> u64 {
+    let data = data.trim_end();
+    let padding = data.bytes().rev().take_while(|b| *b == b'=').count() as u64;
+    (data.len() as u64 / 4 * 3).saturating_sub(padding)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request_with_file(mime_type: &str, bytes: &[u8]) -> JsonRpcRequest {
+        let mut req = JsonRpcRequest {
+            jsonrpc: "2.0".to_string(),
+            method: "message/send".to_string(),
+            params: serde_json::json!({
+                "message": {
+                    "kind": "message",
+                    "messageId": "m1",
+                    "role": "user",
+                    "parts": [{ "kind": "text", "text": "see attached" }]
+                }
+            }),
+            id: None,
+        };
+        attach_files(
+            &mut req,
+            vec![FilePart {
+                file: FileObject::WithBytes {
+                    bytes: general_purpose::STANDARD.encode(bytes),
+                    mime_type: Some(mime_type.to_string()),
+                    name: Some("data.csv".to_string()),
+                },
+                metadata: None,
+            }],
+        )
+        .unwrap();
+        req
+    }
+
+    #[test]
+    fn limits_reject_large_and_disallowed_files() {
+        let limits = UploadLimits {
+            max_file_bytes: 4,
+            max_files: 1,
+            allowed_mime_types: vec!["text/*".to_string()],
+        };
+        assert!(check_upload_limits(&request_with_file("text/csv", b"a,b"), &limits).is_ok());
+        assert!(check_upload_limits(&request_with_file("text/csv", b"a,b,c"), &limits).is_err());
+        assert!(check_upload_limits(&request_with_file("image/png", b"png"), &limits).is_err());
+    }
+
+    #[test]
+    fn decoded_len_accounts_for_padding() {
+        for data in [&b""[..], b"a", b"ab", b"abc", b"abcd"] {
+            let encoded = general_purpose::STANDARD.encode(data);
+            assert_eq!(decoded_len(&encoded), data.len() as u64);
+        }
+    }
+
+    #[test]
+    fn filenames_drop_paths_and_stay_unique() {
+        let stored = vec![FileMetadata {
+            file_id: "report.csv".to_string(),
+            relative_path: String::new(),
+            size: 0,
+            content_type: None,
+            original_filename: None,
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+            checksum: None,
+            stats: None,
+            preview: None,
+        }];
+        assert_eq!(
+            unique_filename(Some("../../etc/report.csv"), "text/csv", 2, &stored),
+            "report-2.csv"
+        );
+        assert_eq!(
+            unique_filename(None, "image/png", 3, &stored),
+            "upload-3.png"
+        );
+    }
+}
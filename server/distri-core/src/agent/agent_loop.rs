@@ -181,9 +181,56 @@ impl AgentLoop {
 
         let mut execution_history = context.get_execution_history().await;
 
+        // A handed-off request is answered by the other agent, which records
+        // it; this run only returns that answer.
+        if let Some(handoff) = &self.agent_def.handoff {
+            let target =
+                match crate::agent::handoff::check(handoff, &self.agent_def, &message, &context)
+                    .await
+                {
+                    Ok(target) => target,
+                    // A failed check leaves the request to this agent.
+                    Err(e) => {
+                        tracing::warn!("Handoff check failed: {}", e);
+                        None
+                    }
+                };
+            if let Some((to_agent, reason)) = target {
+                let answer = crate::agent::handoff::forward(
+                    &self.agent_def,
+                    &to_agent,
+                    reason,
+                    message,
+                    &context,
+                )
+                .await?;
+                context.set_final_result(Some(Value::String(answer))).await;
+                return Ok(context.get_final_result().await);
+            }
+        }
+
         // Save the initial message through orchestrator if available
         self.process_message(&message, context.clone()).await?;
 
+        // An ensemble settles the final answer before the loop, which then
+        // stops right away.
+        if let Some(ensemble) = &self.agent_def.ensemble {
+            if let Err(e) = crate::agent::ensemble::run(ensemble, &message, &context).await {
+                tracing::error!("Ensemble failed: {}", e);
+                context
+                    .update_status(crate::types::TaskStatus::Failed)
+                    .await;
+                context
+                    .emit(AgentEventType::RunError {
+                        message: format!("Ensemble failed: {}", e),
+                        code: Some("ENSEMBLE_ERROR".to_string()),
+                        usage: Some(context.get_step_usage().await),
+                    })
+                    .await;
+                return Err(e);
+            }
+        }
+
         // Calculate context size after message is saved but before LLM calls
         if let Err(e) = context.calculate_context_size().await {
             tracing::warn!("Failed to calculate context size: {}", e);
@@ -218,6 +265,8 @@ impl AgentLoop {
         let mut error_iterations = 0;
         let mut step_index = 0;
         let mut plan_cycle: usize = 0;
+        // The final answer is critiqued once; the revision is not.
+        let mut critiqued = false;
         loop {
             // Snapshot at the top of each iteration so per-step deltas cover the
             // planning LLM call + any tools executed before the next plan cycle.
@@ -555,6 +604,24 @@ impl AgentLoop {
                 .await;
 
             if !should_continue {
+                if let Some(critique) = self.agent_def.critique.as_ref().filter(|_| !critiqued) {
+                    critiqued = true;
+                    match crate::agent::critique::run(critique, &self.agent_def, &message, &context)
+                        .await
+                    {
+                        // The revision is planned afresh, with the draft and
+                        // the critique in the scratchpad.
+                        Ok(true) => {
+                            context.set_current_plan(None).await;
+                            current_plan = None;
+                            step_index = 0;
+                            continue;
+                        }
+                        Ok(false) => {}
+                        // A failed critique keeps the draft.
+                        Err(e) => tracing::warn!("Critique failed: {}", e),
+                    }
+                }
                 // Subagent-based reflection (if enabled)
                 if self.agent_def.is_reflection_enabled() {
                     // Use a simple heuristic: if we have more than 5 iterations or already have reflection results, skip
@@ -616,6 +683,19 @@ impl AgentLoop {
             let final_status = last_result.status.clone().into();
             context.update_status(final_status).await;
         }
+        if !self.agent_def.post_process.is_empty() {
+            if let Some(Value::String(text)) = context.get_final_result().await {
+                let processed = crate::agent::post_process::apply_post_processors(
+                    &self.agent_def.post_process,
+                    text,
+                    context.clone(),
+                )
+                .await;
+                context
+                    .set_final_result(Some(Value::String(processed)))
+                    .await;
+            }
+        }
         let final_result = context.get_final_result().await;
         verbose_log!(
             context.verbose,
@@ -628,6 +708,31 @@ impl AgentLoop {
             final_result
         );
 
+        if context.dry_run {
+            // Output sinks are side effects too: report them instead.
+            if final_success {
+                if let Some(Value::String(text)) = &final_result {
+                    for sink in &self.agent_def.output_sinks {
+                        context
+                            .record_side_effect(
+                                format!(
+                                    "{}{}",
+                                    distri_types::dry_run::OUTPUT_SINK_PREFIX,
+                                    sink.label()
+                                ),
+                                None,
+                                Value::String(text.clone()),
+                            )
+                            .await;
+                    }
+                }
+            }
+            context
+                .emit(AgentEventType::DryRunReport {
+                    report: context.dry_run_report().await,
+                })
+                .await;
+        }
         context
             .emit(AgentEventType::RunFinished {
                 success: final_success,
@@ -637,6 +742,16 @@ impl AgentLoop {
                 context_budget: Some(context.get_usage().await.context_budget.clone()),
             })
             .await;
+        if final_success && !context.dry_run && !self.agent_def.output_sinks.is_empty() {
+            if let Some(Value::String(text)) = &final_result {
+                crate::agent::output_sinks::deliver_outputs(
+                    &self.agent_def.output_sinks,
+                    text,
+                    context.clone(),
+                )
+                .await;
+            }
+        }
         // Return validation error if completion was invalid (to maintain existing behavior)
         if let Err(e) = validation_result {
             // Emit RunError event so UI can display the validation error
@@ -735,15 +850,17 @@ impl AgentLoop {
         if let Some(crate::types::TaskStatus::InputRequired) = context.get_status().await {
             return Ok(());
         }
-        if history.is_empty() {
+        // Check if any execution result is marked as final
+        let has_final_call = context.get_final_result().await.is_some();
+
+        // The planner can settle the answer itself, e.g. with a truncated
+        // reply, without a step being executed.
+        if history.is_empty() && !has_final_call {
             return Err(AgentError::Planning(
                 "Agent completed without executing any steps".to_string(),
             ));
         }
 
-        // Check if any execution result is marked as final
-        let has_final_call = context.get_final_result().await.is_some();
-
         // If no final tool was called, check if all steps completed successfully
         if !has_final_call {
             let all_successful = history.iter().all(|result| result.is_success());
@@ -0,0 +1,101 @@
+//! Scope consent for paused tool calls.
+//!
+//! A tool that needs OAuth scopes the user has not granted fails with
+//! [`ScopesRequired`]. Rather than handing the model that error, the executor
+//! pauses the call: [`request_consent`] emits `AuthRequired` with the scopes
+//! diff and an authorization URL, then waits for the client to answer on
+//! `POST /event/auth`. When the scopes are granted the call is retried.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use distri_types::{AgentEventType, AuthConsentRequest, AuthConsentResponse, ScopesRequired};
+use uuid::Uuid;
+
+use crate::agent::{AgentOrchestrator, ExecutorContext};
+use crate::types::ToolCall;
+
+/// How long a tool call waits for the user to finish authorizing.
+const AUTH_CONSENT_TIMEOUT: Duration = Duration::from_secs(300);
+
+impl AgentOrchestrator {
+    /// Answer a pending consent prompt. An OAuth `code`/`state` caught by the
+    /// client is exchanged for a new session before the paused tool call
+    /// resumes.
+    pub async fn complete_auth_consent(&self, response: AuthConsentResponse) -> Result<(), String> {
+        let Some((_, tx)) = self.auth_consents.remove(&response.auth_id) else {
+            return Err(format!("Auth consent {} not found", response.auth_id));
+        };
+        if let (true, Some(code), Some(state)) = (response.granted, &response.code, &response.state)
+        {
+            let exchanged = match &self.oauth_handler {
+                Some(handler) => handler
+                    .handle_callback(code, state, None)
+                    .await
+                    .map(|_| ())
+                    .map_err(|e| e.to_string()),
+                None => Err("OAuth is not configured on this server".to_string()),
+            };
+            if let Err(e) = exchanged {
+                let _ = tx.send(false);
+                return Err(format!("Failed to complete authorization: {}", e));
+            }
+        }
+        let _ = tx.send(response.granted);
+        Ok(())
+    }
+}
+
+/// Pause `tool_call` until the user answers the consent prompt for
+/// `required`. Returns whether the scopes were granted; a missing
+/// orchestrator or a timeout count as declined.
+pub(crate) async fn request_consent(
+    context: &Arc<ExecutorContext>,
+    tool_call: &ToolCall,
+    required: &ScopesRequired,
+) -> bool {
+    let Some(orchestrator) = context.orchestrator.clone() else {
+        return false;
+    };
+    let authorize_url = match &orchestrator.oauth_handler {
+        Some(handler) => handler
+            .get_consent_url(
+                &required.provider,
+                &context.user_id,
+                &required.requested_scopes(),
+            )
+            .await
+            .map_err(|e| {
+                tracing::warn!(provider = %required.provider, "Failed to build authorize URL: {}", e)
+            })
+            .ok(),
+        None => None,
+    };
+
+    let auth_id = Uuid::new_v4().to_string();
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    orchestrator.auth_consents.insert(auth_id.clone(), tx);
+    context
+        .emit(AgentEventType::AuthRequired {
+            request: AuthConsentRequest {
+                auth_id: auth_id.clone(),
+                tool_call_id: tool_call.tool_call_id.clone(),
+                tool_name: tool_call.tool_name.clone(),
+                provider: required.provider.clone(),
+                current_scopes: required.current_scopes.clone(),
+                required_scopes: required.required_scopes.clone(),
+                missing_scopes: required.missing_scopes(),
+                authorize_url,
+                timeout_ms: AUTH_CONSENT_TIMEOUT.as_millis() as u64,
+            },
+        })
+        .await;
+
+    match tokio::time::timeout(AUTH_CONSENT_TIMEOUT, rx).await {
+        Ok(Ok(granted)) => granted,
+        _ => {
+            orchestrator.auth_consents.remove(&auth_id);
+            false
+        }
+    }
+}
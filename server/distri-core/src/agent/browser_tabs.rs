@@ -0,0 +1,190 @@
+//! Browser tabs per thread.
+//!
+//! Each tab is its own browsr session, so tabs have separate page contexts
+//! and can be driven in parallel. The thread's main browser session (the one
+//! `browser_step` uses by default) is adopted as the `main` tab when the
+//! first extra tab is opened. [`BrowserTabs`] only keeps the bookkeeping;
+//! creating and destroying sessions is up to the `browser_tabs` tool.
+
+use std::time::{Duration, Instant};
+
+use dashmap::DashMap;
+use serde::Serialize;
+
+/// Name of the tab that wraps the thread's main browser session.
+pub const MAIN_TAB: &str = "main";
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BrowserTab {
+    pub name: String,
+    pub session_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// Whether the tab wraps the thread's main session, which outlives it.
+    #[serde(skip)]
+    pub borrowed: bool,
+}
+
+struct ThreadTabs {
+    tabs: Vec<BrowserTab>,
+    active: Option<String>,
+    last_used: Instant,
+}
+
+impl Default for ThreadTabs {
+    fn default() -> Self {
+        Self {
+            tabs: Vec::new(),
+            active: None,
+            last_used: Instant::now(),
+        }
+    }
+}
+
+/// A thread's tabs in opening order, and which one is active.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct TabList {
+    pub tabs: Vec<BrowserTab>,
+    pub active: Option<String>,
+}
+
+#[derive(Default)]
+pub struct BrowserTabs {
+    threads: DashMap<String, ThreadTabs>,
+}
+
+impl BrowserTabs {
+    /// Record a new tab on `thread_id` and make it active. `main_session`
+    /// is adopted as the `main` tab first if the thread has no tabs yet.
+    pub fn open(
+        &self,
+        thread_id: &str,
+        tab: BrowserTab,
+        main_session: Option<String>,
+    ) -> Result<TabList, String> {
+        let mut thread = self.touch(thread_id);
+        if thread.tabs.iter().any(|t| t.name == tab.name) {
+            return Err(format!("Tab '{}' is already open", tab.name));
+        }
+        if thread.tabs.is_empty() {
+            if let Some(session_id) = main_session {
+                thread.tabs.push(BrowserTab {
+                    name: MAIN_TAB.to_string(),
+                    session_id,
+                    url: None,
+                    borrowed: true,
+                });
+            }
+        }
+        thread.active = Some(tab.name.clone());
+        thread.tabs.push(tab);
+        Ok(list(&thread))
+    }
+
+    pub fn switch(&self, thread_id: &str, name: &str) -> Result<TabList, String> {
+        let mut thread = self.touch(thread_id);
+        if !thread.tabs.iter().any(|t| t.name == name) {
+            return Err(unknown_tab(name, &thread));
+        }
+        thread.active = Some(name.to_string());
+        Ok(list(&thread))
+    }
+
+    /// Forget a tab. The most recently opened remaining tab becomes active
+    /// when the closed one was.
+    pub fn close(&self, thread_id: &str, name: &str) -> Result<(BrowserTab, TabList), String> {
+        let mut thread = self.touch(thread_id);
+        let Some(index) = thread.tabs.iter().position(|t| t.name == name) else {
+            return Err(unknown_tab(name, &thread));
+        };
+        let tab = thread.tabs.remove(index);
+        if thread.active.as_deref() == Some(name) {
+            thread.active = thread.tabs.last().map(|t| t.name.clone());
+        }
+        Ok((tab, list(&thread)))
+    }
+
+    pub fn list(&self, thread_id: &str) -> TabList {
+        self.threads
+            .get(thread_id)
+            .map(|thread| list(&thread))
+            .unwrap_or_default()
+    }
+
+    /// Remember the page a tab is on.
+    pub fn set_url(&self, thread_id: &str, name: &str, url: String) {
+        if let Some(mut thread) = self.threads.get_mut(thread_id) {
+            if let Some(tab) = thread.tabs.iter_mut().find(|t| t.name == name) {
+                tab.url = Some(url);
+            }
+        }
+    }
+
+    /// The session `tab` names, or the active tab's session when `tab` is
+    /// `None`. `Ok(None)` when the thread has no tabs.
+    pub fn session(&self, thread_id: &str, tab: Option<&str>) -> Result<Option<String>, String> {
+        let Some(mut thread) = self.threads.get_mut(thread_id) else {
+            return match tab {
+                Some(name) => Err(format!("Tab '{}' is not open", name)),
+                None => Ok(None),
+            };
+        };
+        thread.last_used = Instant::now();
+        let Some(name) = tab.map(str::to_string).or_else(|| thread.active.clone()) else {
+            return Ok(None);
+        };
+        match thread.tabs.iter().find(|t| t.name == name) {
+            Some(tab) => Ok(Some(tab.session_id.clone())),
+            None => Err(unknown_tab(&name, &thread)),
+        }
+    }
+
+    /// Remove the tabs of threads unused for at least `idle`, returning the
+    /// sessions to destroy (borrowed main sessions excluded).
+    pub fn take_idle(&self, idle: Duration) -> Vec<(String, BrowserTab)> {
+        let idle_ids: Vec<String> = self
+            .threads
+            .iter()
+            .filter(|entry| entry.last_used.elapsed() >= idle)
+            .map(|entry| entry.key().clone())
+            .collect();
+        let mut taken = Vec::new();
+        for thread_id in idle_ids {
+            if let Some((_, thread)) = self
+                .threads
+                .remove_if(&thread_id, |_, t| t.last_used.elapsed() >= idle)
+            {
+                taken.extend(
+                    thread
+                        .tabs
+                        .into_iter()
+                        .filter(|t| !t.borrowed)
+                        .map(|t| (thread_id.clone(), t)),
+                );
+            }
+        }
+        taken
+    }
+
+    fn touch(&self, thread_id: &str) -> dashmap::mapref::one::RefMut<'_, String, ThreadTabs> {
+        let mut thread = self.threads.entry(thread_id.to_string()).or_default();
+        thread.last_used = Instant::now();
+        thread
+    }
+}
+
+fn list(thread: &ThreadTabs) -> TabList {
+    TabList {
+        tabs: thread.tabs.clone(),
+        active: thread.active.clone(),
+    }
+}
+
+fn unknown_tab(name: &str, thread: &ThreadTabs) -> String {
+    let open: Vec<&str> = thread.tabs.iter().map(|t| t.name.as_str()).collect();
+    format!(
+        "Tab '{}' is not open (open tabs: {})",
+        name,
+        open.join(", ")
+    )
+}
@@ -0,0 +1,273 @@
+//! Running the model capability probes (see
+//! [`distri_types::capability_probe`]) and adapting agents to the result.
+//!
+//! Probes go through the orchestrator's LLM executors like any other model
+//! call, on a detached context: nothing is recorded in the run's thread.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use distri_types::capability_probe::{
+    self as probe, CapabilityProbeConfig, ModelCapabilities, PROBE_TOOL,
+};
+use distri_types::{
+    LlmDefinition, Message, ModelSettings, Part, StandardDefinition, Tool, ToolCallFormat,
+    ToolContext,
+};
+use serde_json::Value;
+use tokio::sync::OnceCell;
+
+use crate::agent::ExecutorContext;
+use crate::llm::{LLMExecutorTrait, LLMResponse, LlmExecutorFactory};
+use crate::AgentError;
+
+/// Probes models once and remembers the result.
+#[derive(Debug)]
+pub struct CapabilityProber {
+    config: CapabilityProbeConfig,
+    results: Mutex<HashMap<String, Arc<OnceCell<ModelCapabilities>>>>,
+}
+
+impl CapabilityProber {
+    /// A prober with the results already in `config.cache_file`, if any.
+    pub fn new(config: CapabilityProbeConfig) -> Self {
+        let cached: HashMap<String, ModelCapabilities> = config
+            .cache_file
+            .as_ref()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|text| match serde_json::from_str(&text) {
+                Ok(cached) => Some(cached),
+                Err(e) => {
+                    tracing::warn!("ignoring unreadable capability probe cache: {}", e);
+                    None
+                }
+            })
+            .unwrap_or_default();
+        let results = cached
+            .into_iter()
+            .map(|(key, capabilities)| (key, Arc::new(OnceCell::new_with(Some(capabilities)))))
+            .collect();
+        Self {
+            config,
+            results: Mutex::new(results),
+        }
+    }
+
+    /// Capabilities of the model of `settings`, probing it on first use.
+    /// `None` for a provider that is not probed.
+    pub async fn capabilities(
+        &self,
+        settings: &ModelSettings,
+        factory: Option<Arc<dyn LlmExecutorFactory>>,
+    ) -> Option<ModelCapabilities> {
+        if !self.config.probes(&settings.inner.provider) {
+            return None;
+        }
+        let key = probe::model_key(settings);
+        let cell = self
+            .results
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .entry(key.clone())
+            .or_default()
+            .clone();
+        let mut probed = false;
+        let probed_now = &mut probed;
+        let capabilities = cell
+            .get_or_init(|| async move {
+                *probed_now = true;
+                self.probe(settings, factory).await
+            })
+            .await
+            .clone();
+        if probed {
+            tracing::info!(
+                model = %key,
+                native_tool_calls = capabilities.native_tool_calls,
+                parallel_tool_calls = capabilities.parallel_tool_calls,
+                structured_output = capabilities.structured_output,
+                "probed model capabilities"
+            );
+            self.save().await;
+        }
+        Some(capabilities)
+    }
+
+    async fn probe(
+        &self,
+        settings: &ModelSettings,
+        factory: Option<Arc<dyn LlmExecutorFactory>>,
+    ) -> ModelCapabilities {
+        let timeout = Duration::from_secs(self.config.timeout_secs);
+        let call = |prompt: &'static str, tools: bool, settings: ModelSettings| {
+            let factory = factory.clone();
+            async move {
+                let executor = executor(settings, tools, factory)?;
+                tokio::time::timeout(
+                    timeout,
+                    executor.execute(&[Message::user(prompt.to_string(), None)]),
+                )
+                .await
+                .map_err(|_| AgentError::LLMError("probe timed out".to_string()))?
+            }
+        };
+
+        let native_tool_calls = passes(
+            call(probe::TOOL_CALL_PROMPT, true, settings.clone()).await,
+            |response| probe::tool_calls_pass(&response.tool_calls, &["paris"]),
+        );
+        // Parallel calls only matter once single calls work.
+        let parallel_tool_calls = native_tool_calls
+            && passes(
+                call(probe::PARALLEL_TOOL_CALL_PROMPT, true, settings.clone()).await,
+                |response| probe::tool_calls_pass(&response.tool_calls, &["paris", "tokyo"]),
+            );
+        let mut structured = settings.clone();
+        structured.inner.response_format = Some(probe::structured_output_format());
+        let structured_output = passes(
+            call(probe::STRUCTURED_OUTPUT_PROMPT, false, structured).await,
+            |response| probe::structured_output_passes(&response.content),
+        );
+
+        ModelCapabilities {
+            native_tool_calls,
+            parallel_tool_calls,
+            structured_output,
+            probed_at: chrono::Utc::now(),
+        }
+    }
+
+    /// Write every result to `cache_file`.
+    async fn save(&self) {
+        let Some(path) = &self.config.cache_file else {
+            return;
+        };
+        let results: HashMap<String, ModelCapabilities> = self
+            .results
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .filter_map(|(key, cell)| cell.get().map(|c| (key.clone(), c.clone())))
+            .collect();
+        let text = match serde_json::to_string_pretty(&results) {
+            Ok(text) => text,
+            Err(e) => {
+                tracing::warn!("failed to encode capability probe cache: {}", e);
+                return;
+            }
+        };
+        if let Some(parent) = std::path::Path::new(path).parent() {
+            let _ = tokio::fs::create_dir_all(parent).await;
+        }
+        if let Err(e) = tokio::fs::write(path, text).await {
+            tracing::warn!("failed to write capability probe cache {}: {}", path, e);
+        }
+    }
+}
+
+/// Adapt `definition` to what its model can do: the XML tool format when
+/// native tool calls are unreliable, one tool call per reply when parallel
+/// ones are. Returns whether anything changed.
+pub fn adapt_to_capabilities(
+    definition: &mut StandardDefinition,
+    capabilities: &ModelCapabilities,
+) -> bool {
+    if definition.tool_format != ToolCallFormat::Provider {
+        return false;
+    }
+    if !capabilities.native_tool_calls {
+        tracing::info!(
+            agent = %definition.name,
+            "model is unreliable at native tool calls; using the XML tool format"
+        );
+        definition.tool_format = ToolCallFormat::Xml;
+        return true;
+    }
+    match definition.model_settings.as_mut() {
+        Some(settings)
+            if !capabilities.parallel_tool_calls
+                && settings.inner.parallel_tool_calls.is_none() =>
+        {
+            tracing::info!(
+                agent = %definition.name,
+                "model is unreliable at parallel tool calls; asking for one at a time"
+            );
+            settings.inner.parallel_tool_calls = Some(false);
+            true
+        }
+        _ => false,
+    }
+}
+
+fn passes(
+    response: Result<LLMResponse, AgentError>,
+    check: impl FnOnce(&LLMResponse) -> bool,
+) -> bool {
+    match response {
+        Ok(response) => check(&response),
+        Err(e) => {
+            tracing::debug!("capability probe failed: {}", e);
+            false
+        }
+    }
+}
+
+fn executor(
+    settings: ModelSettings,
+    tools: bool,
+    factory: Option<Arc<dyn LlmExecutorFactory>>,
+) -> Result<Box<dyn LLMExecutorTrait>, AgentError> {
+    let llm_def = LlmDefinition {
+        name: "capability_probe".to_string(),
+        model_settings: Some(settings),
+        tool_format: ToolCallFormat::Provider,
+        tool_delivery_mode: Default::default(),
+    };
+    let tools: Vec<Arc<dyn Tool>> = if tools {
+        vec![Arc::new(ProbeTool)]
+    } else {
+        vec![]
+    };
+    let context = Arc::new(ExecutorContext {
+        agent_id: "capability_probe".to_string(),
+        ..Default::default()
+    });
+    match factory {
+        Some(factory) => factory.create_executor(&llm_def, &tools, context),
+        None => crate::llm::create_llm_executor(
+            llm_def,
+            tools,
+            context,
+            None,
+            Some("capability_probe".to_string()),
+        ),
+    }
+}
+
+/// The tool the probes offer; it is never run.
+#[derive(Debug)]
+struct ProbeTool;
+
+#[async_trait::async_trait]
+impl Tool for ProbeTool {
+    fn get_name(&self) -> String {
+        PROBE_TOOL.to_string()
+    }
+
+    fn get_description(&self) -> String {
+        probe::probe_tool_description().to_string()
+    }
+
+    fn get_parameters(&self) -> Value {
+        probe::probe_tool_parameters()
+    }
+
+    async fn execute(
+        &self,
+        _tool_call: distri_types::ToolCall,
+        _context: Arc<ToolContext>,
+    ) -> Result<Vec<Part>, anyhow::Error> {
+        anyhow::bail!("{} only exists to probe models", PROBE_TOOL)
+    }
+}
@@ -150,6 +150,8 @@ pub struct ExecutorContext {
     pub runtime_mode: distri_types::RuntimeMode,
     /// LRU cache for file read deduplication (returns FILE_UNCHANGED_STUB for unchanged files)
     pub file_read_cache: Arc<RwLock<distri_types::FileReadCache>>,
+    /// Results of memoizable tool calls in this task, for answering repeated identical calls
+    pub tool_call_cache: Arc<RwLock<distri_types::ToolCallCache>>,
     /// Tracks which tool results have been replaced with persisted previews (for prompt cache stability)
     pub content_replacement_state: Arc<RwLock<distri_types::ContentReplacementState>>,
     /// Agent span created by OtelHooks::before_execute; consumed once by StandardAgent::invoke_stream.
@@ -177,6 +179,11 @@ pub struct ExecutorContext {
     /// proxy). The orchestrator uses this post-run to warn when an agent
     /// declared `connections: [...]` but never used them.
     pub connections_used: Arc<RwLock<HashSet<String>>>,
+    /// Pages the browser tools loaded for this task, counted against the
+    /// agent's `browser_config.policy.max_pages_per_task`.
+    pub browser_pages: Arc<std::sync::atomic::AtomicUsize>,
+    /// Side effects intercepted by a dry run, shared with child contexts.
+    pub dry_run_side_effects: Arc<RwLock<Vec<distri_types::dry_run::SimulatedSideEffect>>>,
     /// Arbitrary caller-supplied tags. Recorded on the agent span and merged
     /// into the thread's attributes. Empty by default.
     pub tags: HashMap<String, String>,
@@ -263,6 +270,7 @@ impl Default for ExecutorContext {
             dry_run: false,
             runtime_mode: distri_types::RuntimeMode::default(),
             file_read_cache: Arc::new(RwLock::new(distri_types::FileReadCache::new(200))),
+            tool_call_cache: Arc::new(RwLock::new(distri_types::ToolCallCache::new(200))),
             content_replacement_state: Arc::new(RwLock::new(
                 distri_types::ContentReplacementState::default(),
             )),
@@ -274,6 +282,8 @@ impl Default for ExecutorContext {
             mailbox: None,
             is_sandbox: false,
             connections_used: Arc::new(RwLock::new(HashSet::new())),
+            browser_pages: Arc::default(),
+            dry_run_side_effects: Arc::new(RwLock::new(Vec::new())),
             tags: HashMap::new(),
             agent_version: None,
             trace_context: None,
@@ -347,6 +357,32 @@ impl ExecutorContext {
         self.connections_used.read().await.clone()
     }
 
+    /// Record a side effect this dry run intercepted.
+    pub async fn record_side_effect(
+        &self,
+        tool: String,
+        tool_call_id: Option<String>,
+        input: Value,
+    ) {
+        self.dry_run_side_effects
+            .write()
+            .await
+            .push(distri_types::dry_run::SimulatedSideEffect {
+                agent_id: self.agent_id.clone(),
+                task_id: self.task_id.clone(),
+                tool,
+                tool_call_id,
+                input,
+            });
+    }
+
+    /// The side effects this dry run intercepted so far.
+    pub async fn dry_run_report(&self) -> distri_types::dry_run::DryRunReport {
+        distri_types::dry_run::DryRunReport {
+            side_effects: self.dry_run_side_effects.read().await.clone(),
+        }
+    }
+
     /// Set the names of deferred tools (for tool_search awareness).
     pub async fn set_deferred_tool_names(&self, names: HashSet<String>) {
         if !names.is_empty() {
@@ -438,6 +474,15 @@ impl ExecutorContext {
         Ok(orchestrator)
     }
 
+    /// Whether tools of this agent may connect to `url` under the server's
+    /// egress policy.
+    pub fn check_egress(&self, url: &str) -> Result<(), AgentError> {
+        match crate::tools::request::EgressCheck::for_context(self) {
+            Some(egress) => Ok(egress.check(url)?),
+            None => Ok(()),
+        }
+    }
+
     /// Get the browser session ID if set.
     /// Returns None if no session exists - browsr will auto-create one.
     pub fn get_browser_session_id(&self) -> Option<String> {
@@ -497,8 +542,11 @@ impl ExecutorContext {
 
         // Skip saving artifacts to the task store through events
         // as they are saved separately
-        // And text deltas
-        if matches!(event.event, AgentEventType::TextMessageContent { .. }) {
+        // And text and tool-call argument deltas
+        if matches!(
+            event.event,
+            AgentEventType::TextMessageContent { .. } | AgentEventType::ToolCallArgs { .. }
+        ) {
             return;
         }
 
@@ -560,8 +608,11 @@ impl ExecutorContext {
             }
         }
 
-        // Skip persisting text deltas (matches `emit()`).
-        if matches!(event.event, AgentEventType::TextMessageContent { .. }) {
+        // Skip persisting text and tool-call argument deltas (matches `emit()`).
+        if matches!(
+            event.event,
+            AgentEventType::TextMessageContent { .. } | AgentEventType::ToolCallArgs { .. }
+        ) {
             return;
         }
 
@@ -618,6 +669,28 @@ impl ExecutorContext {
         usage.input_tokens += input_tokens;
         usage.output_tokens += output_tokens;
         usage.cached_tokens += cached_tokens;
+        drop(usage);
+        if let Some(orchestrator) = &self.orchestrator {
+            orchestrator
+                .user_quotas
+                .record_tokens(&self.user_id, u64::from(input_tokens + output_tokens));
+        }
+    }
+
+    /// Count tokens the provider wrote to its prompt cache. They are part of
+    /// the input tokens already counted; this only surfaces them.
+    pub async fn increment_cache_writes(&self, cache_write_tokens: u32) {
+        self.usage.write().await.cache_write_tokens += cache_write_tokens;
+    }
+
+    /// Record whether the latest model response ended truncated at its output
+    /// budget; `continued` counts a request for the model to resume.
+    pub async fn record_truncation(&self, truncated: bool, continued: bool) {
+        let mut u = self.usage.write().await;
+        u.truncated = truncated;
+        if continued {
+            u.continuations += 1;
+        }
     }
 
     /// Update the context budget breakdown (populated after each prompt build).
@@ -650,6 +723,7 @@ impl ExecutorContext {
         u.step_input_start = u.input_tokens;
         u.step_output_start = u.output_tokens;
         u.step_cached_start = u.cached_tokens;
+        u.step_cache_write_start = u.cache_write_tokens;
     }
 
     /// Returns a `RunUsage` with per-step deltas (tokens used only in this step) and
@@ -668,6 +742,9 @@ impl ExecutorContext {
             input_tokens: delta_input,
             output_tokens: delta_output,
             cached_tokens: delta_cached,
+            cache_write_tokens: u
+                .cache_write_tokens
+                .saturating_sub(u.step_cache_write_start),
             estimated_tokens: 0,
             model: u.model.clone(),
             cost_usd: cost,
@@ -685,6 +762,7 @@ impl ExecutorContext {
             input_tokens: u.input_tokens,
             output_tokens: u.output_tokens,
             cached_tokens: u.cached_tokens,
+            cache_write_tokens: u.cache_write_tokens,
             estimated_tokens: u.context_size.total_estimated_tokens as u32,
             model: u.model.clone(),
             cost_usd: cost,
@@ -1184,6 +1262,7 @@ impl ExecutorContext {
             channel_kind: self.channel_kind.clone(),
             tenant_context: self.tenant_context.clone(),
             usage: self.usage.clone(),
+            browser_pages: self.browser_pages.clone(),
             verbose: self.verbose,
             orchestrator: self.orchestrator.clone(),
             stores: self
@@ -1226,6 +1305,12 @@ impl ExecutorContext {
                 forked_context.run_id = uuid::Uuid::new_v4().to_string();
             }
         }
+        // Memoized tool results and browser page counts are per task.
+        if !matches!(options.fork_type, ForkType::NewRun) {
+            forked_context.tool_call_cache =
+                Arc::new(RwLock::new(distri_types::ToolCallCache::new(200)));
+            forked_context.browser_pages = Arc::default();
+        }
 
         // History is managed in stores, not in context
         // copy_history_limit option can be handled by individual store implementations if needed
@@ -1321,6 +1406,7 @@ impl ExecutorContext {
             dry_run: self.dry_run,
             runtime_mode: self.runtime_mode.clone(),
             file_read_cache: self.file_read_cache.clone(),
+            tool_call_cache: self.tool_call_cache.clone(),
             content_replacement_state: self.content_replacement_state.clone(),
             otel_agent_span: self.otel_agent_span.clone(),
             skill_tracker: self.skill_tracker.clone(),
@@ -1328,6 +1414,8 @@ impl ExecutorContext {
             mailbox: self.mailbox.clone(),
             is_sandbox: self.is_sandbox,
             connections_used: self.connections_used.clone(),
+            browser_pages: self.browser_pages.clone(),
+            dry_run_side_effects: self.dry_run_side_effects.clone(),
             tags: self.tags.clone(),
             agent_version: self.agent_version.clone(),
             trace_context: self.trace_context.clone(),
@@ -2028,6 +2116,26 @@ impl ExecutorContext {
         self.agent_id = agent_id;
     }
 
+    /// The run's `user_profile`: the user's stored profile, with the
+    /// thread's `user_profile` session value on top.
+    pub async fn user_profile(&self) -> Option<Value> {
+        let store = self.get_session_store().ok()?;
+        let stored: Option<distri_types::user_profile::UserProfile> = store
+            .get(
+                &distri_types::user_profile::profile_namespace(&self.user_id),
+                distri_types::user_profile::PROFILE_KEY,
+            )
+            .await
+            .ok()
+            .flatten();
+        let thread = store
+            .get_value(&self.thread_id, "user_profile")
+            .await
+            .ok()
+            .flatten();
+        distri_types::user_profile::effective_profile(stored.as_ref(), thread.as_ref())
+    }
+
     pub fn get_session_store(
         &self,
     ) -> Result<&Arc<dyn distri_types::stores::SessionStore>, AgentError> {
@@ -0,0 +1,396 @@
+//! Fitting a request into the model's context window.
+//!
+//! Before a request is sent, [`fit_to_context`] counts its messages, tool
+//! definitions and output budget with the model's tokenizer
+//! ([`tokenizer_for`]). When they exceed the context window, the largest
+//! tool results are cut down first, then the oldest turns are dropped and
+//! replaced by a note naming the tools they called. The system prompt, the
+//! first user message and the latest turn are always kept. A
+//! `ContextTrimmed` event reports the trimming; a request that still does
+//! not fit fails before it reaches the provider.
+
+use std::borrow::Cow;
+use std::sync::Arc;
+
+use distri_types::ModelSettings;
+
+use crate::agent::tokenizer::{tokenizer_for, Tokenizer};
+use crate::agent::{AgentEventType, ExecutorContext};
+use crate::tools::Tool;
+use crate::types::{Message, MessageRole, Part, ToolResponse};
+use crate::AgentError;
+
+/// Tokens charged per message for its role and framing.
+const MESSAGE_OVERHEAD: usize = 4;
+/// Tokens charged per image or file, which are not sent as text.
+const ATTACHMENT_TOKENS: usize = 170;
+/// Tool results are not cut below this many tokens.
+const MIN_TOOL_RESULT_TOKENS: usize = 512;
+
+/// Messages trimmed by [`fit_messages`], and what was removed.
+#[derive(Debug, Clone)]
+pub struct ContextFit {
+    pub messages: Vec<Message>,
+    pub tokens_before: usize,
+    pub tokens_after: usize,
+    pub tool_results_truncated: usize,
+    pub messages_dropped: usize,
+}
+
+/// Tokens `message` takes under `tokenizer`.
+pub fn count_message_tokens(message: &Message, tokenizer: &dyn Tokenizer) -> usize {
+    MESSAGE_OVERHEAD
+        + message
+            .parts
+            .iter()
+            .map(|part| count_part_tokens(part, tokenizer))
+            .sum::<usize>()
+}
+
+fn count_part_tokens(part: &Part, tokenizer: &dyn Tokenizer) -> usize {
+    match part {
+        Part::Text(text) => tokenizer.count(text),
+        Part::ToolCall(call) => {
+            tokenizer.count(&call.tool_name) + tokenizer.count(&call.input.to_string())
+        }
+        Part::ToolResult(result) => {
+            tokenizer.count(&result.tool_name)
+                + result
+                    .parts
+                    .iter()
+                    .map(|part| count_part_tokens(part, tokenizer))
+                    .sum::<usize>()
+        }
+        Part::Image(_) | Part::File(_) => ATTACHMENT_TOKENS,
+        Part::Data(value) => tokenizer.count(&value.to_string()),
+        Part::Artifact(metadata) => tokenizer.count(&format!(
+            "Artifact: {} ({})",
+            metadata.file_id,
+            metadata.content_type.as_deref().unwrap_or("unknown")
+        )),
+        Part::ResourceLink(link) => tokenizer.count(&format!(
+            "{} {}",
+            link.uri,
+            link.text.as_deref().unwrap_or("")
+        )),
+    }
+}
+
+/// `messages` cut down to at most `budget` tokens, or `None` when they
+/// already fit. The result can still be over `budget` when the kept
+/// messages alone exceed it.
+pub fn fit_messages(
+    messages: &[Message],
+    budget: usize,
+    tokenizer: &dyn Tokenizer,
+) -> Option<ContextFit> {
+    let mut counts: Vec<usize> = messages
+        .iter()
+        .map(|message| count_message_tokens(message, tokenizer))
+        .collect();
+    let tokens_before: usize = counts.iter().sum();
+    if tokens_before <= budget {
+        return None;
+    }
+    let mut messages = messages.to_vec();
+    let mut total = tokens_before;
+
+    // Cut the largest tool results first, each only as far as needed.
+    let mut results: Vec<(usize, usize, usize)> = messages
+        .iter()
+        .enumerate()
+        .flat_map(|(i, message)| {
+            message
+                .parts
+                .iter()
+                .enumerate()
+                .filter(|(_, part)| matches!(part, Part::ToolResult(_)))
+                .map(move |(j, part)| (i, j, count_part_tokens(part, tokenizer)))
+        })
+        .collect();
+    results.sort_by_key(|(_, _, tokens)| std::cmp::Reverse(*tokens));
+    let mut tool_results_truncated = 0;
+    for (i, j, tokens) in results {
+        if total <= budget {
+            break;
+        }
+        let target = tokens
+            .saturating_sub(total - budget)
+            .max(MIN_TOOL_RESULT_TOKENS);
+        let Part::ToolResult(result) = &mut messages[i].parts[j] else {
+            continue;
+        };
+        if target >= tokens || !truncate_tool_result(result, tokens, target, tokenizer) {
+            continue;
+        }
+        let after = count_part_tokens(&messages[i].parts[j], tokenizer);
+        total = total - tokens + after;
+        counts[i] = counts[i] - tokens + after;
+        tool_results_truncated += 1;
+    }
+
+    // Then drop the oldest turns, keeping the system prompt, the task and
+    // the latest turn.
+    let mut messages_dropped = 0;
+    if total > budget {
+        let mut head = messages
+            .iter()
+            .take_while(|m| matches!(m.role, MessageRole::System | MessageRole::Developer))
+            .count();
+        head = (head + 1).min(messages.len());
+        while head < messages.len() && is_tool_response(&messages[head]) {
+            head += 1;
+        }
+        let starts: Vec<usize> = (head..messages.len())
+            .filter(|&i| i == head || !is_tool_response(&messages[i]))
+            .collect();
+        let mut end = head;
+        let mut tools: Vec<String> = Vec::new();
+        for turn in starts.windows(2) {
+            if total <= budget {
+                break;
+            }
+            let turn = turn[0]..turn[1];
+            total -= counts[turn.clone()].iter().sum::<usize>();
+            for call in messages[turn.clone()].iter().flat_map(Message::tool_calls) {
+                if !tools.contains(&call.tool_name) {
+                    tools.push(call.tool_name);
+                }
+            }
+            end = turn.end;
+        }
+        messages_dropped = end - head;
+        if messages_dropped > 0 {
+            let mut note = format!(
+                "[{} earlier messages were dropped to fit the context window",
+                messages_dropped
+            );
+            if !tools.is_empty() {
+                note.push_str(&format!("; they called {}", tools.join(", ")));
+            }
+            note.push(']');
+            let note = Message::developer(note, None);
+            total += count_message_tokens(&note, tokenizer);
+            messages.drain(head..end);
+            messages.insert(head, note);
+        }
+    }
+
+    Some(ContextFit {
+        messages,
+        tokens_before,
+        tokens_after: total,
+        tool_results_truncated,
+        messages_dropped,
+    })
+}
+
+/// Whether `message` only carries tool results, and so belongs to the turn
+/// of the call before it.
+fn is_tool_response(message: &Message) -> bool {
+    message.role == MessageRole::Tool
+        || (!message.parts.is_empty()
+            && message
+                .parts
+                .iter()
+                .all(|part| matches!(part, Part::ToolResult(_))))
+}
+
+/// Cut the text of `result`, which takes `tokens`, down to about `target`
+/// tokens. Returns false when it has no text to cut.
+fn truncate_tool_result(
+    result: &mut ToolResponse,
+    tokens: usize,
+    target: usize,
+    tokenizer: &dyn Tokenizer,
+) -> bool {
+    let (textual, other): (Vec<Part>, Vec<Part>) = result
+        .parts
+        .drain(..)
+        .partition(|part| matches!(part, Part::Text(_) | Part::Data(_)));
+    if textual.is_empty() {
+        result.parts = other;
+        return false;
+    }
+    let text = textual
+        .iter()
+        .map(|part| match part {
+            Part::Text(text) => text.clone(),
+            Part::Data(value) => value.to_string(),
+            _ => unreachable!(),
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    let marker = |removed: usize| {
+        format!(
+            "\n[… {} more tokens of this result were cut to fit the context window]",
+            removed
+        )
+    };
+    let text_tokens = tokenizer.count(&text).max(1);
+    // The tool name and other parts stay, and the marker is added.
+    let fixed = tokens.saturating_sub(text_tokens) + tokenizer.count(&marker(text_tokens));
+    let room = target.saturating_sub(fixed);
+    let mut cut = (text.len() as u128 * room as u128 / text_tokens as u128) as usize;
+    cut = cut.min(text.len());
+    while !text.is_char_boundary(cut) {
+        cut -= 1;
+    }
+    let kept = &text[..cut];
+    let removed = text_tokens.saturating_sub(tokenizer.count(kept));
+    result.parts = vec![Part::Text(format!("{}{}", kept, marker(removed)))];
+    result.parts.extend(other);
+    result.parts_metadata = None;
+    true
+}
+
+/// `messages` trimmed to fit `settings`' context window next to the
+/// definitions of `tools` and the output budget. Emits `ContextTrimmed`
+/// when anything was removed, and fails when the request cannot fit.
+pub(crate) async fn fit_to_context<'a>(
+    context: &ExecutorContext,
+    settings: &ModelSettings,
+    tools: &[Arc<dyn Tool>],
+    messages: &'a [Message],
+) -> Result<Cow<'a, [Message]>, AgentError> {
+    let tokenizer = tokenizer_for(&settings.model);
+    let context_limit = settings.effective_context_size();
+    let tool_tokens: usize = tools
+        .iter()
+        .map(|tool| {
+            tokenizer.count(&serde_json::to_string(&tool.get_tool_definition()).unwrap_or_default())
+        })
+        .sum();
+    let output_tokens = settings.inner.max_tokens.unwrap_or(0) as usize;
+    // Room for request framing the counts miss, and for estimation error.
+    let margin = context_limit as usize / if tokenizer.is_exact() { 50 } else { 20 };
+    let budget = (context_limit as usize).saturating_sub(tool_tokens + output_tokens + margin);
+
+    let Some(fit) = fit_messages(messages, budget, tokenizer.as_ref()) else {
+        return Ok(Cow::Borrowed(messages));
+    };
+    if fit.tokens_after > budget {
+        return Err(AgentError::LLMError(format!(
+            "Context size exceeded: {} tokens of messages left after trimming, {} available in the {} token context window of {}",
+            fit.tokens_after, budget, context_limit, settings.model
+        )));
+    }
+    tracing::warn!(
+        target: "llm.context",
+        "Trimmed request for {} from {} to {} tokens ({} tool results cut, {} messages dropped)",
+        settings.model,
+        fit.tokens_before,
+        fit.tokens_after,
+        fit.tool_results_truncated,
+        fit.messages_dropped
+    );
+    context
+        .emit(AgentEventType::ContextTrimmed {
+            model: settings.model.clone(),
+            context_limit,
+            budget,
+            tokens_before: fit.tokens_before,
+            tokens_after: fit.tokens_after,
+            tool_results_truncated: fit.tool_results_truncated,
+            messages_dropped: fit.messages_dropped,
+        })
+        .await;
+    Ok(Cow::Owned(fit.messages))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::tokenizer::RatioTokenizer;
+    use crate::types::ToolCall;
+
+    /// One token per character.
+    const CHARS: RatioTokenizer = RatioTokenizer {
+        chars_per_token: 1.0,
+    };
+
+    fn call(id: &str, tool: &str) -> Message {
+        Message {
+            role: MessageRole::Assistant,
+            parts: vec![Part::ToolCall(ToolCall {
+                tool_call_id: id.to_string(),
+                tool_name: tool.to_string(),
+                input: serde_json::json!({}),
+            })],
+            ..Default::default()
+        }
+    }
+
+    fn result(id: &str, tool: &str, text: &str) -> Message {
+        Message::tool_response(id.to_string(), tool.to_string(), &serde_json::json!(text))
+    }
+
+    fn conversation(result_len: usize) -> Vec<Message> {
+        vec![
+            Message::system("You are helpful.".to_string(), None),
+            Message::user("Summarize the logs.".to_string(), None),
+            call("1", "list_files"),
+            result("1", "list_files", "app.log"),
+            call("2", "read_file"),
+            result("2", "read_file", &"x".repeat(result_len)),
+        ]
+    }
+
+    fn total(messages: &[Message]) -> usize {
+        messages
+            .iter()
+            .map(|m| count_message_tokens(m, &CHARS))
+            .sum()
+    }
+
+    #[test]
+    fn messages_within_budget_are_untouched() {
+        let messages = conversation(100);
+        assert!(fit_messages(&messages, total(&messages), &CHARS).is_none());
+    }
+
+    #[test]
+    fn long_tool_results_are_cut_first() {
+        let messages = conversation(5_000);
+        let budget = total(&messages) - 3_000;
+
+        let fit = fit_messages(&messages, budget, &CHARS).unwrap();
+
+        assert_eq!(fit.tool_results_truncated, 1);
+        assert_eq!(fit.messages_dropped, 0);
+        assert!(fit.tokens_after <= budget);
+        assert_eq!(fit.tokens_after, total(&fit.messages));
+        let Part::ToolResult(cut) = &fit.messages[5].parts[0] else {
+            panic!("expected a tool result");
+        };
+        let Part::Text(text) = &cut.parts[0] else {
+            panic!("expected text");
+        };
+        assert!(
+            text.ends_with("were cut to fit the context window]"),
+            "{}",
+            text
+        );
+    }
+
+    #[test]
+    fn oldest_turns_are_dropped_when_cutting_is_not_enough() {
+        let mut messages = conversation(400);
+        messages.extend([call("3", "grep"), result("3", "grep", &"y".repeat(400))]);
+        // Room for the system prompt, the task, the note and the latest turn.
+        let budget = total(&messages[..2]) + total(&messages[6..]) + 200;
+
+        let fit = fit_messages(&messages, budget, &CHARS).unwrap();
+
+        assert_eq!(fit.messages_dropped, 4);
+        assert!(fit.tokens_after <= budget);
+        assert_eq!(fit.messages.len(), 5);
+        let ids = |messages: &[Message]| messages.iter().map(|m| m.id.clone()).collect::<Vec<_>>();
+        assert_eq!(ids(&fit.messages[..2]), ids(&messages[..2]));
+        assert_eq!(
+            fit.messages[2].as_text().unwrap(),
+            "[4 earlier messages were dropped to fit the context window; they called list_files, read_file]"
+        );
+        assert_eq!(ids(&fit.messages[3..]), ids(&messages[6..]));
+    }
+}
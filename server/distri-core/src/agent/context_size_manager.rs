@@ -195,147 +195,6 @@ impl ContextSizeManager {
         )
     }
 
-    /// Validate that messages don't exceed context size limit
-    pub fn validate_context_size(
-        &self,
-        messages: &[crate::types::Message],
-        context_limit: u32,
-    ) -> Result<(), crate::AgentError> {
-        tracing::debug!(
-            "🔍 Starting token estimation for {} messages",
-            messages.len()
-        );
-        let mut total_tokens = 0;
-
-        // Estimate tokens for each message
-        for (i, message) in messages.iter().enumerate() {
-            tracing::debug!(
-                "📝 Processing message {}/{}: {} parts",
-                i + 1,
-                messages.len(),
-                message.parts.len()
-            );
-            let message_tokens = self.estimate_message_tokens(message);
-            total_tokens += message_tokens;
-            tracing::debug!(
-                "📊 Message {} tokens: {} (total so far: {})",
-                i + 1,
-                message_tokens,
-                total_tokens
-            );
-        }
-
-        tracing::debug!("🏁 Token estimation complete");
-
-        tracing::debug!(
-            "🔢 Token count estimate: {} tokens (context limit: {})",
-            total_tokens,
-            context_limit
-        );
-
-        if total_tokens > context_limit as usize {
-            let err = format!(
-                "Context size exceeded: {} tokens > {} limit. Consider reducing message history or using artifacts.",
-                total_tokens,
-                context_limit
-            );
-            tracing::warn!("{err}");
-            // return Err(crate::AgentError::LLMError(err));
-        }
-
-        Ok(())
-    }
-
-    /// Estimate tokens for a single Message
-    fn estimate_message_tokens(&self, message: &crate::types::Message) -> usize {
-        let mut total_tokens = 0;
-
-        // Add tokens for message text content
-        for part in &message.parts {
-            match part {
-                crate::types::Part::Text(text) => {
-                    let estimate = TokenEstimator::estimate_tokens(
-                        text,
-                        self.config.estimation_method.clone(),
-                    );
-                    if let Ok(estimate) = estimate {
-                        total_tokens += estimate.estimated_tokens;
-                    }
-                }
-                crate::types::Part::ToolCall(tool_call) => {
-                    // Estimate tokens for tool call name and input
-                    let tool_call_text = format!(
-                        "{}: {}",
-                        tool_call.tool_name,
-                        serde_json::to_string(&tool_call.input).unwrap_or_default()
-                    );
-                    let estimate = TokenEstimator::estimate_tokens(
-                        &tool_call_text,
-                        self.config.estimation_method.clone(),
-                    );
-                    if let Ok(estimate) = estimate {
-                        total_tokens += estimate.estimated_tokens;
-                    }
-                }
-                crate::types::Part::ToolResult(tool_result) => {
-                    // Estimate tokens for tool result
-                    let result_text = serde_json::to_string(tool_result).unwrap_or_default();
-                    let estimate = TokenEstimator::estimate_tokens(
-                        &result_text,
-                        self.config.estimation_method.clone(),
-                    );
-                    if let Ok(estimate) = estimate {
-                        total_tokens += estimate.estimated_tokens;
-                    }
-                }
-                crate::types::Part::Image(_) => {
-                    // Images are roughly equivalent to ~170 tokens for vision models
-                    total_tokens += 170;
-                }
-                crate::types::Part::File(_) => {
-                    total_tokens += 170;
-                }
-                crate::types::Part::Data(value) => {
-                    let data_text = serde_json::to_string(value).unwrap_or_default();
-                    let estimate = TokenEstimator::estimate_tokens(
-                        &data_text,
-                        self.config.estimation_method.clone(),
-                    );
-                    if let Ok(estimate) = estimate {
-                        total_tokens += estimate.estimated_tokens;
-                    }
-                }
-                crate::types::Part::Artifact(metadata) => {
-                    // Artifact references are small, just the metadata
-                    let metadata_text = format!(
-                        "Artifact: {} ({})",
-                        metadata.file_id,
-                        metadata.content_type.as_deref().unwrap_or("unknown")
-                    );
-                    let estimate = TokenEstimator::estimate_tokens(
-                        &metadata_text,
-                        self.config.estimation_method.clone(),
-                    );
-                    if let Ok(estimate) = estimate {
-                        total_tokens += estimate.estimated_tokens;
-                    }
-                }
-                crate::types::Part::ResourceLink(link) => {
-                    let link_text = format!("{} {}", link.uri, link.text.as_deref().unwrap_or(""));
-                    let estimate = TokenEstimator::estimate_tokens(
-                        &link_text,
-                        self.config.estimation_method.clone(),
-                    );
-                    if let Ok(estimate) = estimate {
-                        total_tokens += estimate.estimated_tokens;
-                    }
-                }
-            }
-        }
-
-        total_tokens
-    }
-
     /// Evaluate whether compaction is needed and apply the appropriate tier.
     ///
     /// Returns a `CompactionResult` describing what happened:
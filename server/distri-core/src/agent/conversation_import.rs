@@ -0,0 +1,147 @@
+//! Writing imported conversations into threads.
+//!
+//! Each [`ImportedConversation`] becomes a thread of the target agent, with
+//! one completed task per exchange: a user message and the assistant replies
+//! that follow it. The thread's external id records the source conversation,
+//! so importing the same export again only adds conversations not seen yet.
+
+use distri_types::conversation_import::{
+    ConversationImportSummary, ImportedConversation, ImportedThread,
+};
+use distri_types::stores::{CreateTaskInput, ThreadListFilter};
+use distri_types::{CreateThreadRequest, Message, MessageRole, TaskStatus};
+
+use crate::agent::orchestrator::AgentOrchestrator;
+use crate::AgentError;
+
+impl AgentOrchestrator {
+    /// Store `conversations` as threads of `agent_id`.
+    pub async fn import_conversations(
+        &self,
+        agent_id: &str,
+        conversations: Vec<ImportedConversation>,
+    ) -> Result<ConversationImportSummary, AgentError> {
+        if self.get_agent(agent_id).await.is_none() {
+            return Err(AgentError::AgentNotFound(agent_id.to_string()));
+        }
+
+        let mut summary = ConversationImportSummary::default();
+        for conversation in conversations {
+            if conversation.messages.is_empty() || self.already_imported(&conversation).await? {
+                summary.skipped.push(conversation.external_id);
+                continue;
+            }
+            summary
+                .imported
+                .push(self.import_conversation(agent_id, conversation).await?);
+        }
+        Ok(summary)
+    }
+
+    async fn already_imported(
+        &self,
+        conversation: &ImportedConversation,
+    ) -> Result<bool, AgentError> {
+        let filter = ThreadListFilter {
+            external_id: Some(conversation.external_id.clone()),
+            ..Default::default()
+        };
+        let existing = self.list_threads(&filter, Some(1), None).await?;
+        Ok(!existing.threads.is_empty())
+    }
+
+    async fn import_conversation(
+        &self,
+        agent_id: &str,
+        conversation: ImportedConversation,
+    ) -> Result<ImportedThread, AgentError> {
+        let session = |e: anyhow::Error| AgentError::Session(e.to_string());
+        let thread = self
+            .create_thread(CreateThreadRequest {
+                agent_id: agent_id.to_string(),
+                title: Some(conversation.title.clone()),
+                thread_id: None,
+                attributes: None,
+                user_id: None,
+                external_id: Some(conversation.external_id.clone()),
+                channel_id: None,
+            })
+            .await?;
+
+        let task_store = &self.stores.task_store;
+        let message_count = conversation.messages.len();
+        // History is ordered by timestamp; keep the export's order where it
+        // has missing or equal timestamps.
+        let mut clock = conversation.created_at;
+        for exchange in exchanges(conversation.messages) {
+            let task = task_store
+                .create_task(CreateTaskInput::local(&thread.id).with_status(TaskStatus::Completed))
+                .await
+                .map_err(session)?;
+            for mut message in exchange {
+                if message.created_at <= clock {
+                    message.created_at = clock + 1;
+                }
+                clock = message.created_at;
+                if message.role == MessageRole::Assistant {
+                    message.agent_id = Some(agent_id.to_string());
+                }
+                task_store
+                    .add_message_to_task(&task.id, &message)
+                    .await
+                    .map_err(session)?;
+                self.stores
+                    .thread_store
+                    .update_thread_with_message(&thread.id, &message.as_text().unwrap_or_default())
+                    .await
+                    .map_err(session)?;
+            }
+        }
+
+        Ok(ImportedThread {
+            thread_id: thread.id,
+            external_id: conversation.external_id,
+            title: conversation.title,
+            message_count,
+        })
+    }
+}
+
+/// Split a conversation into exchanges, each starting at a user message.
+/// Assistant messages before the first user message open their own exchange.
+fn exchanges(messages: Vec<Message>) -> Vec<Vec<Message>> {
+    let mut exchanges: Vec<Vec<Message>> = Vec::new();
+    for message in messages {
+        match exchanges.last_mut() {
+            Some(current) if message.role != MessageRole::User => current.push(message),
+            _ => exchanges.push(vec![message]),
+        }
+    }
+    exchanges
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(role: MessageRole, text: &str) -> Message {
+        Message {
+            role,
+            parts: vec![distri_types::Part::Text(text.to_string())],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn exchanges_start_at_user_messages() {
+        let grouped = exchanges(vec![
+            message(MessageRole::Assistant, "hi"),
+            message(MessageRole::User, "q1"),
+            message(MessageRole::Assistant, "a1"),
+            message(MessageRole::Assistant, "a1 more"),
+            message(MessageRole::User, "q2"),
+        ]);
+        let sizes: Vec<usize> = grouped.iter().map(Vec::len).collect();
+        assert_eq!(sizes, vec![1, 3, 1]);
+    }
+}
@@ -0,0 +1,105 @@
+//! Self-critique of the final answer (see [`distri_types::critique`]).
+//!
+//! The critique is a single call to the chosen model, with no tools. When it
+//! asks for a revision the final result is cleared and the loop runs once
+//! more, with the draft and the critique in the scratchpad.
+
+use std::sync::Arc;
+
+use distri_types::critique::{
+    critique_brief, CritiqueConfig, CritiqueModel, CritiqueVerdict, CRITIQUE_DRAFT_STEP,
+    CRITIQUE_STEP,
+};
+use distri_types::{
+    ExecutionResult, ExecutionStatus, LlmDefinition, Message, Part, StandardDefinition,
+    ToolCallFormat,
+};
+use serde_json::Value;
+
+use crate::agent::ExecutorContext;
+use crate::AgentError;
+
+/// Critique the run's final answer. Returns whether the agent should revise
+/// it; `false` when there is no answer yet.
+pub async fn run(
+    config: &CritiqueConfig,
+    definition: &StandardDefinition,
+    message: &Message,
+    context: &Arc<ExecutorContext>,
+) -> Result<bool, AgentError> {
+    let draft = match context.get_final_result().await {
+        None | Some(Value::Null) => return Ok(false),
+        Some(Value::String(text)) => text,
+        Some(other) => other.to_string(),
+    };
+    let model_settings = match config.model {
+        CritiqueModel::Analysis => definition.analysis_model_settings_config(),
+        CritiqueModel::Same => definition.model_settings(),
+    }
+    .cloned()
+    .ok_or_else(|| {
+        AgentError::InvalidConfiguration(format!(
+            "agent '{}' has no model settings to critique with",
+            definition.name
+        ))
+    })?;
+
+    let llm_def = LlmDefinition {
+        name: "critique".to_string(),
+        model_settings: Some(model_settings),
+        tool_format: ToolCallFormat::Provider,
+        tool_delivery_mode: Default::default(),
+    };
+    let executor = crate::llm::create_llm_executor(
+        llm_def,
+        vec![],
+        context.clone(),
+        None,
+        Some("critique".to_string()),
+    )?;
+    let task = message.as_text().unwrap_or_default();
+    let guardrails: Vec<String> = definition
+        .guardrails
+        .iter()
+        .chain(&config.guardrails)
+        .cloned()
+        .collect();
+    let brief = critique_brief(&task, &draft, &guardrails);
+    let reply = executor.execute(&[Message::user(brief, None)]).await?;
+
+    let verdict = CritiqueVerdict::from_value(&Value::String(reply.content.clone()));
+    if verdict.is_none() {
+        tracing::warn!(agent = %definition.name, "critique did not reply with a verdict");
+    }
+    // Without a usable verdict the draft stands.
+    let verdict = verdict.unwrap_or(CritiqueVerdict {
+        approved: true,
+        issues: vec![],
+    });
+
+    let now = chrono::Utc::now().timestamp_millis();
+    context
+        .store_execution_result(&ExecutionResult {
+            step_id: CRITIQUE_DRAFT_STEP.to_string(),
+            status: ExecutionStatus::Success,
+            parts: vec![Part::Text(draft)],
+            timestamp: now,
+            reason: Some("Draft answer".to_string()),
+        })
+        .await?;
+    context
+        .store_execution_result(&ExecutionResult {
+            step_id: CRITIQUE_STEP.to_string(),
+            status: ExecutionStatus::Success,
+            parts: vec![Part::Text(verdict.to_note())],
+            timestamp: now,
+            reason: Some("Critique of the draft answer".to_string()),
+        })
+        .await?;
+
+    let revise = verdict.needs_revision();
+    if revise {
+        context.set_final_result(None).await;
+    }
+    Ok(revise)
+}
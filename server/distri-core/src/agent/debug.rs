@@ -1,8 +1,12 @@
 use anyhow::Result;
+use distri_types::api::preview::{AgentPromptPreviewResponse, PromptSectionTokens};
 use std::sync::Arc;
 
 use crate::{
-    agent::{strategy::planning::UnifiedPlanner, ExecutorContext, PlanningStrategy},
+    agent::{
+        context_fit::count_message_tokens, strategy::planning::UnifiedPlanner,
+        tokenizer::tokenizer_for, ExecutorContext, PlanningStrategy,
+    },
     llm::StreamResult,
     types::Message,
     AgentOrchestrator,
@@ -106,3 +110,108 @@ pub async fn generate_agent_response(
         .await
         .map_err(|e| anyhow::anyhow!("Failed to generate LLM response: {}", e))
 }
+
+/// Assemble the exact prompt `agent_name` would send for `message` — with
+/// the agent's resolved tools and, when `thread_id` is given, that thread's
+/// packed history — plus per-section token counts from the model's
+/// tokenizer. No model is called.
+pub async fn preview_agent_prompt(
+    executor: Arc<AgentOrchestrator>,
+    agent_name: &str,
+    message: &str,
+    thread_id: Option<String>,
+    user_id: Option<String>,
+) -> Result<AgentPromptPreviewResponse> {
+    let agent_config = executor
+        .get_agent(agent_name)
+        .await
+        .ok_or_else(|| anyhow::anyhow!("Agent '{}' not found", agent_name))?;
+    let agent_def = match &agent_config {
+        distri_types::configuration::AgentConfig::StandardAgent(def) => def.clone(),
+        distri_types::configuration::AgentConfig::WorkflowAgent(_) => {
+            return Err(anyhow::anyhow!(
+                "Prompt preview is not supported for workflow agents"
+            ));
+        }
+    };
+
+    let default = ExecutorContext::default();
+    let context = Arc::new(ExecutorContext {
+        agent_id: agent_name.to_string(),
+        thread_id: thread_id.clone().unwrap_or(default.thread_id.clone()),
+        user_id: user_id.unwrap_or(default.user_id.clone()),
+        orchestrator: Some(executor.clone()),
+        stores: Some(executor.stores.clone()),
+        ..default
+    });
+
+    // Resolves the agent's tools (and deferred/skill listings) into the
+    // context exactly as a real run does.
+    executor
+        .create_agent_from_config(agent_config, context.clone())
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to resolve agent: {}", e))?;
+
+    let strategy = agent_def.strategy.clone().unwrap_or_default();
+    let planner = UnifiedPlanner::new(agent_def.clone(), strategy);
+    let user_message = Message::user(message.to_string(), None);
+    let (messages, budget, layers) = planner
+        .generate_prompt_with_layers(&user_message, &context)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to generate prompt: {}", e))?;
+
+    let model = agent_def
+        .model_settings()
+        .or(context.default_model_settings.as_ref())
+        .map(|settings| settings.model.as_str())
+        .unwrap_or_default();
+    let tokenizer = tokenizer_for(model);
+    let message_tokens: Vec<usize> = messages
+        .iter()
+        .map(|m| count_message_tokens(m, tokenizer.as_ref()))
+        .collect();
+    // Counted as the budget counts history, so that `history` is the rest.
+    let user_message_tokens = messages
+        .iter()
+        .find(|m| m.id == user_message.id)
+        .map(|m| tokenizer.count(&m.as_text().unwrap_or_default()))
+        .unwrap_or_default();
+
+    let tools = if agent_def.tool_format == distri_types::ToolCallFormat::Provider {
+        context
+            .get_tools_for_llm()
+            .await
+            .iter()
+            .map(|t| t.get_tool_definition())
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    let tokens = PromptSectionTokens {
+        system_static: budget.system_prompt_static_tokens,
+        system_dynamic: budget.system_prompt_dynamic_tokens,
+        tool_schemas: budget.tool_schema_tokens,
+        deferred_tools: budget.deferred_tool_tokens,
+        skills: budget.skill_listing_tokens,
+        history: budget
+            .conversation_tokens
+            .saturating_sub(user_message_tokens),
+        user_message: user_message_tokens,
+        tool_results: budget.tool_result_tokens,
+        total: budget.total_tokens(),
+        context_window: budget.context_window_size,
+        exact: tokenizer.is_exact(),
+    };
+
+    Ok(AgentPromptPreviewResponse {
+        agent_id: agent_name.to_string(),
+        thread_id,
+        tool_format: agent_def.tool_format,
+        messages,
+        message_tokens,
+        tools,
+        tokens,
+        layers,
+    })
+}
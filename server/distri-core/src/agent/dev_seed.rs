@@ -0,0 +1,558 @@
+//! Sample data for local development (`distri dev seed`).
+//!
+//! Fills the stores with a couple of sample agents, prompt templates,
+//! placeholder secrets and threads whose histories look like real runs —
+//! multi-step tool calls, a saved artifact, a failed tool call and a failed
+//! run — so UI and renderer work can start without running agents first.
+//! Threads have fixed ids and everything is only created when missing, so
+//! seeding twice changes nothing.
+
+use base64::{engine::general_purpose, Engine as _};
+use distri_types::configuration::AgentConfig;
+use distri_types::dev_seed::DevSeedSummary;
+use distri_types::stores::{CreateTaskInput, NewPromptTemplate, NewSecret};
+use distri_types::{
+    AgentEvent, AgentEventType, CreateThreadRequest, FileMetadata, Message, MessageRole, Part,
+    TaskStatus, ToolCall, ToolResponse,
+};
+use serde_json::{json, Value};
+
+use crate::agent::orchestrator::AgentOrchestrator;
+use crate::AgentError;
+
+const SAMPLE_AGENTS: &[&str] = &[
+    r#"---
+name = "sample_researcher"
+description = "Sample agent seeded by `distri dev seed`: answers questions from web searches."
+max_iterations = 6
+
+[tools]
+builtin = ["search", "final"]
+---
+
+# ROLE
+You research questions with web searches and answer with cited sources.
+
+# TASK
+{{task}}
+"#,
+    r#"---
+name = "sample_analyst"
+description = "Sample agent seeded by `distri dev seed`: turns data into reports saved as artifacts."
+max_iterations = 6
+
+[tools]
+builtin = ["save_artifact", "final"]
+---
+
+# ROLE
+You analyse the data you are given and save reports as artifacts.
+
+# TASK
+{{task}}
+"#,
+];
+
+const SAMPLE_TEMPLATES: &[(&str, &str, &str)] = &[
+    (
+        "sample_summary",
+        "Summarise the conversation in {{max_sentences}} sentences:\n\n{{conversation}}",
+        "Sample template seeded by `distri dev seed`",
+    ),
+    (
+        "sample_report",
+        "# {{title}}\n\n{{#each findings}}- {{this}}\n{{/each}}",
+        "Sample template seeded by `distri dev seed`",
+    ),
+];
+
+/// Placeholders only; replace the values to run the sample agents for real.
+const SAMPLE_SECRETS: &[&str] = &["SAMPLE_SEARCH_API_KEY", "SAMPLE_REPORTS_TOKEN"];
+
+const PLACEHOLDER_SECRET: &str = "replace-me";
+
+struct SampleThread {
+    id: &'static str,
+    agent: &'static str,
+    title: &'static str,
+    runs: Vec<SampleRun>,
+}
+
+/// One user message and the agent's work on it.
+struct SampleRun {
+    user: &'static str,
+    steps: Vec<SampleStep>,
+    /// Final answer; `None` ends the run with `error`.
+    reply: Option<&'static str>,
+    error: Option<&'static str>,
+}
+
+struct SampleStep {
+    thought: &'static str,
+    tool: &'static str,
+    input: Value,
+    outcome: Outcome,
+}
+
+enum Outcome {
+    Data(Value),
+    Failed(&'static str),
+    Artifact {
+        filename: &'static str,
+        content_type: &'static str,
+        content: &'static str,
+    },
+}
+
+fn sample_threads() -> Vec<SampleThread> {
+    vec![
+        SampleThread {
+            id: "sample-thread-research",
+            agent: "sample_researcher",
+            title: "Comparing Rust async runtimes",
+            runs: vec![
+                SampleRun {
+                    user: "Which async runtime should I use for a Rust web service?",
+                    steps: vec![SampleStep {
+                        thought: "Let me look up the current state of the main runtimes.",
+                        tool: "search",
+                        input: json!({ "query": "tokio vs async-std vs smol 2024" }),
+                        outcome: Outcome::Data(json!({
+                            "results": [
+                                { "title": "Tokio - An asynchronous Rust runtime", "url": "https://tokio.rs" },
+                                { "title": "async-std has been discontinued", "url": "https://github.com/async-rs/async-std" },
+                                { "title": "smol - A small and fast async runtime", "url": "https://github.com/smol-rs/smol" }
+                            ]
+                        })),
+                    }],
+                    reply: Some(
+                        "Use **Tokio**. It has the largest ecosystem (axum, tonic, reqwest) and is \
+                         actively maintained; async-std is discontinued and smol fits small \
+                         embedded-style services better.",
+                    ),
+                    error: None,
+                },
+                SampleRun {
+                    user: "How does its scheduler balance work across threads?",
+                    steps: vec![
+                        SampleStep {
+                            thought: "Searching for the scheduler design write-up.",
+                            tool: "search",
+                            input: json!({ "query": "tokio work stealing scheduler" }),
+                            outcome: Outcome::Failed("search provider returned 429 Too Many Requests"),
+                        },
+                        SampleStep {
+                            thought: "The search was rate limited, retrying with a narrower query.",
+                            tool: "search",
+                            input: json!({ "query": "tokio blog making the scheduler 10x faster" }),
+                            outcome: Outcome::Data(json!({
+                                "results": [
+                                    { "title": "Making the Tokio scheduler 10x faster", "url": "https://tokio.rs/blog/2019-10-scheduler" }
+                                ]
+                            })),
+                        },
+                    ],
+                    reply: Some(
+                        "Each worker thread has a local run queue; idle workers **steal** half of \
+                         a busy worker's queue. A LIFO slot keeps freshly woken tasks on the same \
+                         thread for cache locality.",
+                    ),
+                    error: None,
+                },
+            ],
+        },
+        SampleThread {
+            id: "sample-thread-report",
+            agent: "sample_analyst",
+            title: "Quarterly sales report",
+            runs: vec![SampleRun {
+                user: "Summarise Q3 sales per region and save it as a CSV.",
+                steps: vec![SampleStep {
+                    thought: "Saving the per-region totals.",
+                    tool: "save_artifact",
+                    input: json!({ "filename": "q3_sales.csv", "content": "region,revenue\n..." }),
+                    outcome: Outcome::Artifact {
+                        filename: "q3_sales.csv",
+                        content_type: "text/csv",
+                        content: "region,revenue\nEMEA,1240000\nAMER,1890000\nAPAC,760000\n",
+                    },
+                }],
+                reply: Some("Saved `q3_sales.csv`. AMER leads with $1.89M, APAC trails at $0.76M."),
+                error: None,
+            }],
+        },
+        SampleThread {
+            id: "sample-thread-failed",
+            agent: "sample_researcher",
+            title: "Release notes lookup",
+            runs: vec![SampleRun {
+                user: "What changed in the latest release of our internal SDK?",
+                steps: vec![SampleStep {
+                    thought: "Searching for the release notes.",
+                    tool: "search",
+                    input: json!({ "query": "acme sdk release notes" }),
+                    outcome: Outcome::Failed("SAMPLE_SEARCH_API_KEY is not a valid API key"),
+                }],
+                reply: None,
+                error: Some("Tool 'search' failed: SAMPLE_SEARCH_API_KEY is not a valid API key"),
+            }],
+        },
+    ]
+}
+
+impl AgentOrchestrator {
+    /// Write the sample agents, templates, secrets and threads that are not
+    /// in the stores yet.
+    pub async fn seed_dev_fixtures(&self) -> Result<DevSeedSummary, AgentError> {
+        let session = |e: anyhow::Error| AgentError::Session(e.to_string());
+        let mut summary = DevSeedSummary::default();
+
+        for markdown in SAMPLE_AGENTS {
+            let definition = distri_types::parse_agent_markdown_content(markdown)
+                .await
+                .map_err(|e| AgentError::Other(format!("invalid sample agent: {}", e)))?;
+            let name = definition.name.clone();
+            self.stores
+                .agent_store
+                .register(AgentConfig::StandardAgent(definition))
+                .await
+                .map_err(session)?;
+            summary.agents.push(name);
+        }
+        self.notify_agents_changed();
+
+        if let Some(store) = &self.stores.prompt_template_store {
+            let names: Vec<String> = SAMPLE_TEMPLATES.iter().map(|t| t.0.to_string()).collect();
+            let existing = store.get_by_names(&names).await.map_err(session)?;
+            for (name, template, description) in SAMPLE_TEMPLATES {
+                if existing.iter().any(|t| t.name == *name) {
+                    summary.skipped.push(name.to_string());
+                    continue;
+                }
+                store
+                    .create(NewPromptTemplate {
+                        name: name.to_string(),
+                        template: template.to_string(),
+                        description: Some(description.to_string()),
+                        version: None,
+                        is_system: false,
+                    })
+                    .await
+                    .map_err(session)?;
+                summary.prompt_templates.push(name.to_string());
+            }
+        }
+
+        if let Some(store) = &self.stores.secret_store {
+            for key in SAMPLE_SECRETS {
+                if store.get(key).await.map_err(session)?.is_some() {
+                    summary.skipped.push(key.to_string());
+                    continue;
+                }
+                store
+                    .create(NewSecret {
+                        key: key.to_string(),
+                        value: PLACEHOLDER_SECRET.to_string(),
+                    })
+                    .await
+                    .map_err(session)?;
+                summary.secrets.push(key.to_string());
+            }
+        }
+
+        for thread in sample_threads() {
+            if self.get_thread(thread.id).await?.is_some() {
+                summary.skipped.push(thread.id.to_string());
+                continue;
+            }
+            self.seed_thread(&thread).await?;
+            summary.threads.push(thread.id.to_string());
+        }
+
+        Ok(summary)
+    }
+
+    async fn seed_thread(&self, thread: &SampleThread) -> Result<(), AgentError> {
+        let session = |e: anyhow::Error| AgentError::Session(e.to_string());
+        self.create_thread(CreateThreadRequest {
+            agent_id: thread.agent.to_string(),
+            title: Some(thread.title.to_string()),
+            thread_id: Some(thread.id.to_string()),
+            attributes: Some(json!({ "tags": { "source": "dev_seed" } })),
+            user_id: None,
+            external_id: None,
+            channel_id: None,
+        })
+        .await?;
+
+        // Spread the history over the last hour so it sorts like a real one.
+        let mut clock = chrono::Utc::now().timestamp_millis() - 60 * 60 * 1000;
+        let task_store = &self.stores.task_store;
+        for run in &thread.runs {
+            let status = if run.error.is_some() {
+                TaskStatus::Failed
+            } else {
+                TaskStatus::Completed
+            };
+            let task = task_store
+                .create_task(CreateTaskInput::local(thread.id).with_status(status))
+                .await
+                .map_err(session)?;
+            let writer = RunWriter {
+                orchestrator: self,
+                thread,
+                task_id: task.id,
+                run_id: uuid::Uuid::new_v4().to_string(),
+            };
+
+            writer
+                .message(
+                    &mut clock,
+                    MessageRole::User,
+                    vec![Part::Text(run.user.to_string())],
+                )
+                .await?;
+            writer
+                .event(&mut clock, AgentEventType::RunStarted {})
+                .await?;
+            let mut failed_steps = 0;
+            for step in &run.steps {
+                if matches!(step.outcome, Outcome::Failed(_)) {
+                    failed_steps += 1;
+                }
+                writer.step(&mut clock, step).await?;
+            }
+
+            match (run.reply, run.error) {
+                (Some(reply), _) => {
+                    writer
+                        .message(
+                            &mut clock,
+                            MessageRole::Assistant,
+                            vec![Part::Text(reply.to_string())],
+                        )
+                        .await?;
+                    writer
+                        .event(
+                            &mut clock,
+                            AgentEventType::RunFinished {
+                                success: true,
+                                total_steps: run.steps.len() + 1,
+                                failed_steps,
+                                usage: None,
+                                context_budget: None,
+                            },
+                        )
+                        .await?;
+                }
+                (None, error) => {
+                    writer
+                        .event(
+                            &mut clock,
+                            AgentEventType::RunError {
+                                message: error.unwrap_or("Run failed").to_string(),
+                                code: Some("tool_failed".to_string()),
+                                usage: None,
+                            },
+                        )
+                        .await?;
+                }
+            }
+            if let Some(reply) = run.reply {
+                self.stores
+                    .thread_store
+                    .update_thread_with_message(thread.id, reply)
+                    .await
+                    .map_err(session)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Writes one run's messages and events with increasing timestamps.
+struct RunWriter<'a> {
+    orchestrator: &'a AgentOrchestrator,
+    thread: &'a SampleThread,
+    task_id: String,
+    run_id: String,
+}
+
+impl RunWriter<'_> {
+    async fn message(
+        &self,
+        clock: &mut i64,
+        role: MessageRole,
+        parts: Vec<Part>,
+    ) -> Result<String, AgentError> {
+        *clock += 1500;
+        let agent_id = (role == MessageRole::Assistant).then(|| self.thread.agent.to_string());
+        let message = Message {
+            role,
+            parts,
+            created_at: *clock,
+            agent_id,
+            ..Default::default()
+        };
+        self.orchestrator
+            .stores
+            .task_store
+            .add_message_to_task(&self.task_id, &message)
+            .await
+            .map_err(|e| AgentError::Session(e.to_string()))?;
+        Ok(message.id)
+    }
+
+    async fn event(&self, clock: &mut i64, event: AgentEventType) -> Result<(), AgentError> {
+        *clock += 200;
+        let mut event = AgentEvent::with_context(
+            event,
+            self.thread.id.to_string(),
+            self.run_id.clone(),
+            self.task_id.clone(),
+            self.thread.agent.to_string(),
+        );
+        event.timestamp = chrono::DateTime::from_timestamp_millis(*clock).unwrap_or_default();
+        self.orchestrator
+            .stores
+            .task_store
+            .add_event_to_task(&self.task_id, event)
+            .await
+            .map_err(|e| AgentError::Session(e.to_string()))
+    }
+
+    /// A tool call and its result, as the agent loop records them.
+    async fn step(&self, clock: &mut i64, step: &SampleStep) -> Result<(), AgentError> {
+        let step_id = uuid::Uuid::new_v4().to_string();
+        let tool_call = ToolCall {
+            tool_call_id: uuid::Uuid::new_v4().to_string(),
+            tool_name: step.tool.to_string(),
+            input: step.input.clone(),
+        };
+        let message_id = self
+            .message(
+                clock,
+                MessageRole::Assistant,
+                vec![
+                    Part::Text(step.thought.to_string()),
+                    Part::ToolCall(tool_call.clone()),
+                ],
+            )
+            .await?;
+        self.event(
+            clock,
+            AgentEventType::ToolCalls {
+                step_id: step_id.clone(),
+                parent_message_id: Some(message_id.clone()),
+                tool_calls: vec![tool_call.clone()],
+            },
+        )
+        .await?;
+        self.event(
+            clock,
+            AgentEventType::ToolExecutionStart {
+                step_id: step_id.clone(),
+                tool_call_id: tool_call.tool_call_id.clone(),
+                tool_call_name: tool_call.tool_name.clone(),
+                input: tool_call.input.clone(),
+            },
+        )
+        .await?;
+
+        let parts = match &step.outcome {
+            Outcome::Data(value) => vec![Part::Data(value.clone())],
+            Outcome::Failed(error) => vec![Part::Data(json!({ "error": error }))],
+            Outcome::Artifact {
+                filename,
+                content_type,
+                content,
+            } => vec![Part::Artifact(
+                self.save_artifact(filename, content_type, content).await,
+            )],
+        };
+        let response = ToolResponse::from_parts(
+            tool_call.tool_call_id.clone(),
+            tool_call.tool_name.clone(),
+            parts,
+        );
+        self.event(
+            clock,
+            AgentEventType::ToolExecutionEnd {
+                step_id: step_id.clone(),
+                tool_call_id: tool_call.tool_call_id.clone(),
+                tool_call_name: tool_call.tool_name.clone(),
+                success: !matches!(step.outcome, Outcome::Failed(_)),
+            },
+        )
+        .await?;
+        self.event(
+            clock,
+            AgentEventType::ToolResults {
+                step_id,
+                parent_message_id: Some(message_id),
+                results: vec![response.clone()],
+            },
+        )
+        .await?;
+        self.message(clock, MessageRole::Tool, vec![Part::ToolResult(response)])
+            .await?;
+        Ok(())
+    }
+
+    /// Store `content` where `save_artifact` would and describe it.
+    async fn save_artifact(
+        &self,
+        filename: &str,
+        content_type: &str,
+        content: &str,
+    ) -> FileMetadata {
+        let namespace =
+            distri_filesystem::ArtifactWrapper::task_namespace(self.thread.id, &self.task_id);
+        let mut relative_path = String::new();
+        match self
+            .orchestrator
+            .session_filesystem
+            .create_artifact_wrapper(namespace)
+            .await
+        {
+            Ok(wrapper) => {
+                let encoded = general_purpose::STANDARD.encode(content);
+                match wrapper.save_artifact(filename, &encoded).await {
+                    Ok(()) => {
+                        relative_path = format!("{}/content/{}", wrapper.prefix_path(), filename)
+                    }
+                    Err(e) => tracing::warn!("Failed to save sample artifact: {}", e),
+                }
+            }
+            Err(e) => tracing::warn!("Failed to create artifact wrapper: {}", e),
+        }
+        let now = chrono::Utc::now();
+        FileMetadata {
+            file_id: filename.to_string(),
+            relative_path,
+            size: content.len() as u64,
+            content_type: Some(content_type.to_string()),
+            original_filename: Some(filename.to_string()),
+            created_at: now,
+            updated_at: now,
+            checksum: None,
+            stats: None,
+            preview: Some(content.lines().take(3).collect::<Vec<_>>().join("\n")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn sample_agents_parse() {
+        for markdown in SAMPLE_AGENTS {
+            let definition = distri_types::parse_agent_markdown_content(markdown)
+                .await
+                .expect("sample agent parses");
+            assert!(definition.name.starts_with("sample_"));
+        }
+    }
+}
@@ -0,0 +1,151 @@
+//! Ensemble runs (see [`distri_types::ensemble`]).
+//!
+//! Every branch is a `Join::Single` invocation of its own, so a failing
+//! branch only drops its answer instead of failing the others; all of them
+//! and the judge are child tasks of the ensemble's task.
+
+use std::sync::Arc;
+
+use distri_types::ensemble::{judge_brief, EnsembleConfig, EnsembleVerdict};
+use distri_types::invocation::{AgentResult, Invocation, InvocationResult, Target};
+use distri_types::{
+    EnsembleBranchOutcome, ExecutionResult, ExecutionStatus, Message, Part, TaskStatus,
+};
+use serde_json::Value;
+
+use crate::agent::{AgentEventType, ExecutorContext};
+use crate::AgentError;
+
+/// Run `message` through the ensemble and set the judge's answer as the
+/// final result.
+pub async fn run(
+    ensemble: &EnsembleConfig,
+    message: &Message,
+    context: &Arc<ExecutorContext>,
+) -> Result<(), AgentError> {
+    let orchestrator = context.get_orchestrator()?.clone();
+    let task = message.as_text().unwrap_or_default();
+
+    let results = futures::future::join_all(ensemble.branches.iter().map(|branch| {
+        let mut target = Target::named(&branch.agent, Message::user(task.clone(), None));
+        if let Some(model) = &branch.model {
+            target = target.with_model(model);
+        }
+        invoke_single(&orchestrator, target, context)
+    }))
+    .await;
+
+    let mut outcomes = Vec::with_capacity(results.len());
+    // (index into `outcomes`, branch label, answer)
+    let mut candidates: Vec<(usize, String, String)> = Vec::new();
+    for (index, (branch, result)) in ensemble.branches.iter().zip(results).enumerate() {
+        let mut outcome = EnsembleBranchOutcome {
+            agent: branch.agent.clone(),
+            model: branch.model.clone(),
+            task_id: None,
+            answered: false,
+            error: None,
+        };
+        match result {
+            Ok(result) => {
+                outcome.task_id = Some(result.task_id.clone());
+                match answer_text(&result) {
+                    Some(answer) => {
+                        outcome.answered = true;
+                        candidates.push((index, branch.label(), answer));
+                    }
+                    None => outcome.error = Some(format!("ended as {:?}", result.status)),
+                }
+            }
+            Err(e) => outcome.error = Some(e.to_string()),
+        }
+        if let Some(error) = &outcome.error {
+            tracing::warn!(agent = %branch.agent, "ensemble branch failed: {}", error);
+        }
+        outcomes.push(outcome);
+    }
+    if candidates.is_empty() {
+        return Err(AgentError::Execution(
+            "no ensemble branch produced an answer".to_string(),
+        ));
+    }
+
+    let numbered: Vec<(String, String)> = candidates
+        .iter()
+        .map(|(_, label, answer)| (label.clone(), answer.clone()))
+        .collect();
+    let brief = judge_brief(ensemble.mode, &task, &numbered);
+    let judged = invoke_single(
+        &orchestrator,
+        Target::named(&ensemble.judge, Message::user(brief, None)),
+        context,
+    )
+    .await?;
+
+    let verdict = EnsembleVerdict::from_value(&judged.content);
+    if verdict.is_none() {
+        tracing::warn!(judge = %ensemble.judge, "ensemble judge did not reply with a verdict");
+    }
+    let chosen = verdict
+        .as_ref()
+        .and_then(|v| v.selected)
+        .and_then(|n| n.checked_sub(1))
+        .and_then(|i| candidates.get(i));
+    let answer = verdict
+        .as_ref()
+        .and_then(|v| v.answer.clone())
+        .or_else(|| chosen.map(|(_, _, answer)| answer.clone()))
+        // Without a usable verdict the judge's reply is the answer.
+        .or_else(|| answer_text(&judged))
+        .unwrap_or_default();
+
+    context
+        .emit(AgentEventType::EnsembleJudged {
+            branches: outcomes,
+            judge_task_id: judged.task_id.clone(),
+            selected: chosen.map(|(index, _, _)| *index),
+            rationale: verdict.and_then(|v| v.rationale),
+        })
+        .await;
+
+    context
+        .store_execution_result(&ExecutionResult {
+            step_id: "ensemble".to_string(),
+            status: ExecutionStatus::Success,
+            parts: vec![Part::Text(answer.clone())],
+            timestamp: chrono::Utc::now().timestamp_millis(),
+            reason: None,
+        })
+        .await?;
+    context.set_final_result(Some(Value::String(answer))).await;
+    Ok(())
+}
+
+async fn invoke_single(
+    orchestrator: &Arc<crate::AgentOrchestrator>,
+    target: Target,
+    context: &Arc<ExecutorContext>,
+) -> Result<AgentResult, AgentError> {
+    match orchestrator
+        .invoke(Invocation::single(target), context.clone())
+        .await?
+    {
+        InvocationResult::Scalar { result } => Ok(result),
+        other => Err(AgentError::Session(format!(
+            "ensemble expected a Scalar result from Join::Single, got {other:?}"
+        ))),
+    }
+}
+
+/// The text of a completed child's answer.
+fn answer_text(result: &AgentResult) -> Option<String> {
+    if result.status != TaskStatus::Completed {
+        return None;
+    }
+    match &result.content {
+        Value::Null => None,
+        Value::String(text) if text.trim().is_empty() => None,
+        Value::String(text) => Some(text.clone()),
+        other => Some(other.to_string()),
+    }
+}
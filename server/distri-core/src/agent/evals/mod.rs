@@ -0,0 +1,290 @@
+//! Eval runs and regression comparisons.
+//!
+//! [`run_evals`] sends each case to the agent on its own thread and records
+//! the answers under the hash of the agent's current definition.
+//! [`compare_evals`] has a judge model decide, case by case, whether a
+//! candidate run answered better or worse than a baseline run, and
+//! [`eval_history`] lists an agent's runs with the outcome of the latest
+//! comparison each one was the candidate of.
+
+use std::sync::Arc;
+use std::time::Instant;
+
+use anyhow::{anyhow, Context, Result};
+use chrono::Utc;
+use distri_types::configuration::AgentConfig;
+use distri_types::evals::{
+    definition_hash, CompareEvalsRequest, EvalCase, EvalCaseComparison, EvalCaseResult,
+    EvalComparison, EvalHistory, EvalRun, EvalScore, EvalTrendPoint, EvalVerdict,
+};
+use distri_types::{Message, ModelSettings, StandardDefinition};
+
+use crate::agent::{parse_agent_markdown_content, ExecutorContext};
+use crate::AgentOrchestrator;
+
+/// Longest answer shown to the judge, per side.
+const MAX_JUDGED_CHARS: usize = 8_000;
+
+pub async fn judge_agent_definition() -> Result<StandardDefinition> {
+    parse_agent_markdown_content(include_str!("./judge_agent.md"))
+        .await
+        .map_err(|e| anyhow!("Invalid judge agent definition: {}", e))
+}
+
+/// Run `cases` against `agent_name` and record the run.
+pub async fn run_evals(
+    executor: Arc<AgentOrchestrator>,
+    agent_name: &str,
+    cases: Vec<EvalCase>,
+    user_id: Option<String>,
+    model_settings: Option<ModelSettings>,
+) -> Result<EvalRun> {
+    let config = executor
+        .get_agent(agent_name)
+        .await
+        .ok_or_else(|| anyhow!("Agent '{}' not found", agent_name))?;
+    let agent_id = config.get_name().to_string();
+
+    let mut results = Vec::with_capacity(cases.len());
+    for case in cases {
+        let context = context(&executor, &agent_id, &user_id, &model_settings);
+        let started = Instant::now();
+        let outcome = executor
+            .execute(
+                &agent_id,
+                Message::user(case.task.clone(), None),
+                Arc::new(context),
+                None,
+            )
+            .await;
+        let duration_ms = started.elapsed().as_millis() as u64;
+        let (output, error) = match outcome {
+            Ok(result) => (Some(result.content.unwrap_or_default()), None),
+            Err(e) => (None, Some(e.to_string())),
+        };
+        results.push(EvalCaseResult {
+            case,
+            output,
+            error,
+            duration_ms,
+        });
+    }
+
+    let run = EvalRun {
+        id: uuid::Uuid::new_v4().to_string(),
+        agent_id,
+        definition_hash: definition_hash(&config),
+        created_at: Utc::now(),
+        results,
+    };
+    executor
+        .eval_store
+        .record_run(&run)
+        .await
+        .context("recording eval run")?;
+    Ok(run)
+}
+
+/// Judge the candidate run against the baseline run, case by case, and
+/// record the comparison.
+pub async fn compare_evals(
+    executor: Arc<AgentOrchestrator>,
+    agent_name: &str,
+    request: &CompareEvalsRequest,
+    user_id: Option<String>,
+    model_settings: Option<ModelSettings>,
+) -> Result<EvalComparison> {
+    let agent_id = executor.resolve_agent_name(agent_name).await;
+    let runs = executor.eval_store.list_runs(&agent_id).await?;
+    let baseline = find_run(&runs, &request.baseline)?;
+    let candidate = find_run(&runs, &request.candidate)?;
+
+    let mut cases = Vec::new();
+    let mut unmatched = Vec::new();
+    for result in &candidate.results {
+        let Some(before) = baseline
+            .results
+            .iter()
+            .find(|r| r.case.id == result.case.id)
+        else {
+            unmatched.push(result.case.id.clone());
+            continue;
+        };
+        let (verdict, rationale) = match (&before.output, &result.output) {
+            (Some(a), Some(b)) => {
+                judge(&executor, &result.case, a, b, &user_id, &model_settings).await
+            }
+            (Some(_), None) => (
+                EvalVerdict::Loss,
+                format!(
+                    "Candidate failed: {}",
+                    result.error.as_deref().unwrap_or("no answer")
+                ),
+            ),
+            (None, Some(_)) => (
+                EvalVerdict::Win,
+                format!(
+                    "Baseline failed: {}",
+                    before.error.as_deref().unwrap_or("no answer")
+                ),
+            ),
+            (None, None) => (EvalVerdict::Tie, "Both runs failed".to_string()),
+        };
+        cases.push(EvalCaseComparison {
+            case_id: result.case.id.clone(),
+            verdict,
+            rationale,
+        });
+    }
+    unmatched.extend(
+        baseline
+            .results
+            .iter()
+            .filter(|r| !candidate.results.iter().any(|c| c.case.id == r.case.id))
+            .map(|r| r.case.id.clone()),
+    );
+
+    let comparison = EvalComparison {
+        id: uuid::Uuid::new_v4().to_string(),
+        agent_id,
+        created_at: Utc::now(),
+        baseline: baseline.summary(),
+        candidate: candidate.summary(),
+        score: EvalScore::tally(&cases),
+        cases,
+        unmatched,
+    };
+    executor
+        .eval_store
+        .record_comparison(&comparison)
+        .await
+        .context("recording eval comparison")?;
+    Ok(comparison)
+}
+
+/// `agent_name`'s eval runs, oldest first.
+pub async fn eval_history(
+    executor: Arc<AgentOrchestrator>,
+    agent_name: &str,
+) -> Result<EvalHistory> {
+    let agent_id = executor.resolve_agent_name(agent_name).await;
+    let runs = executor.eval_store.list_runs(&agent_id).await?;
+    let comparisons = executor.eval_store.list_comparisons(&agent_id).await?;
+    let runs = runs
+        .iter()
+        .map(|run| {
+            let latest = comparisons
+                .iter()
+                .rev()
+                .find(|c| c.candidate.run_id == run.id);
+            EvalTrendPoint {
+                run: run.summary(),
+                compared_to: latest.map(|c| c.baseline.run_id.clone()),
+                score: latest.map(|c| c.score),
+            }
+        })
+        .collect();
+    Ok(EvalHistory { agent_id, runs })
+}
+
+/// The run `reference` names: a run id, or the latest run of a definition
+/// hash prefix.
+fn find_run<'a>(runs: &'a [EvalRun], reference: &str) -> Result<&'a EvalRun> {
+    runs.iter()
+        .rev()
+        .find(|run| run.matches(reference))
+        .ok_or_else(|| anyhow!("No eval run matches '{}'", reference))
+}
+
+fn context(
+    executor: &Arc<AgentOrchestrator>,
+    agent_id: &str,
+    user_id: &Option<String>,
+    model_settings: &Option<ModelSettings>,
+) -> ExecutorContext {
+    let mut context = ExecutorContext {
+        agent_id: agent_id.to_string(),
+        orchestrator: Some(executor.clone()),
+        default_model_settings: model_settings.clone(),
+        ..Default::default()
+    };
+    if let Some(user_id) = user_id {
+        context.user_id = user_id.clone();
+    }
+    context
+}
+
+/// Ask the judge whether `candidate` answered `case` better than
+/// `baseline`. A judge that fails or gives no verdict counts as a tie.
+async fn judge(
+    executor: &Arc<AgentOrchestrator>,
+    case: &EvalCase,
+    baseline: &str,
+    candidate: &str,
+    user_id: &Option<String>,
+    model_settings: &Option<ModelSettings>,
+) -> (EvalVerdict, String) {
+    let mut prompt = format!("## Task\n{}\n", case.task);
+    if let Some(expected) = &case.expected {
+        prompt.push_str(&format!("\n## Expected answer\n{}\n", expected));
+    }
+    prompt.push_str(&format!(
+        "\n## Answer A\n{}\n\n## Answer B\n{}\n",
+        clip(baseline),
+        clip(candidate)
+    ));
+
+    let reply = match judge_agent_definition().await {
+        Ok(judge) => {
+            let context = context(executor, &judge.name, user_id, model_settings);
+            executor
+                .run_inline_agent(
+                    AgentConfig::StandardAgent(judge),
+                    &prompt,
+                    Arc::new(context),
+                )
+                .await
+                .map(|r| r.content.unwrap_or_default())
+                .map_err(|e| e.to_string())
+        }
+        Err(e) => Err(e.to_string()),
+    };
+    match reply {
+        Ok(reply) => parse_verdict(&reply),
+        Err(e) => (EvalVerdict::Tie, format!("Judge failed: {}", e)),
+    }
+}
+
+/// Read the judge's `{"winner": ..., "rationale": ...}` reply.
+fn parse_verdict(reply: &str) -> (EvalVerdict, String) {
+    let parsed = reply
+        .find('{')
+        .zip(reply.rfind('}'))
+        .and_then(|(start, end)| {
+            serde_json::from_str::<serde_json::Value>(&reply[start..=end]).ok()
+        });
+    let Some(parsed) = parsed else {
+        return (
+            EvalVerdict::Tie,
+            format!("Judge gave no verdict: {}", reply.trim()),
+        );
+    };
+    let rationale = parsed["rationale"].as_str().unwrap_or_default().to_string();
+    let verdict = match parsed["winner"]
+        .as_str()
+        .map(str::to_ascii_lowercase)
+        .as_deref()
+    {
+        Some("b") => EvalVerdict::Win,
+        Some("a") => EvalVerdict::Loss,
+        _ => EvalVerdict::Tie,
+    };
+    (verdict, rationale)
+}
+
+fn clip(answer: &str) -> String {
+    match answer.char_indices().nth(MAX_JUDGED_CHARS) {
+        Some((end, _)) => format!("{}\n[answer truncated]", &answer[..end]),
+        None => answer.to_string(),
+    }
+}
@@ -0,0 +1,338 @@
+//! Capability handoff (see [`distri_types::handoff`]).
+//!
+//! The check runs before the agent records the request, so a handed-off
+//! request is recorded once, by the agent that answers it. That agent
+//! continues the same task; the `handoff` step it finds in the shared
+//! scratchpad keeps it from handing the request on again.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use distri_types::configuration::AgentConfig;
+use distri_types::handoff::{
+    handoff_brief, HandoffCandidate, HandoffConfig, HandoffRecord, HandoffVerdict, HANDOFF_STEP,
+    THREAD_HANDOFFS_KEY,
+};
+use distri_types::{
+    ExecutionResult, ExecutionStatus, LlmDefinition, Message, Part, StandardDefinition,
+    ToolCallFormat, UpdateThreadRequest,
+};
+use serde_json::{json, Value};
+
+use crate::agent::{AgentEventType, AgentOrchestrator, ExecutorContext};
+use crate::tools::APPROVAL_REQUEST_TOOL_NAME;
+use crate::types::ToolCall;
+use crate::AgentError;
+
+/// How long a handoff waits for the user to confirm it.
+const HANDOFF_CONFIRM_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Pick the agent to hand `message` to, confirmed by the user when the
+/// config asks for it, and record the choice. Returns the agent and why;
+/// `None` when the agent keeps the request.
+pub async fn check(
+    config: &HandoffConfig,
+    definition: &StandardDefinition,
+    message: &Message,
+    context: &Arc<ExecutorContext>,
+) -> Result<Option<(String, String)>, AgentError> {
+    let Some(request) = message.as_text().filter(|text| !text.trim().is_empty()) else {
+        return Ok(None);
+    };
+    if context
+        .get_execution_history()
+        .await
+        .iter()
+        .any(|result| result.step_id == HANDOFF_STEP)
+    {
+        return Ok(None);
+    }
+    let orchestrator = context.get_orchestrator()?.clone();
+
+    let (to_agent, reason) = match config.match_intent(&request) {
+        Some(intent) => (
+            intent.agent.clone(),
+            format!("the request matches the intents of {}", intent.agent),
+        ),
+        None if config.classify => {
+            let candidates = candidates(config, definition, &orchestrator).await;
+            if candidates.is_empty() {
+                return Ok(None);
+            }
+            let verdict = classify(definition, &request, &candidates, context).await?;
+            match verdict
+                .agent
+                .as_ref()
+                .filter(|agent| candidates.iter().any(|c| &c.name == *agent))
+            {
+                Some(agent) => (agent.clone(), verdict.describe(agent)),
+                None => return Ok(None),
+            }
+        }
+        None => return Ok(None),
+    };
+    if to_agent == definition.name || orchestrator.get_agent(&to_agent).await.is_none() {
+        return Ok(None);
+    }
+
+    let accepted = !config.confirm || confirm(&orchestrator, context, &to_agent, &reason).await;
+    record(
+        &orchestrator,
+        context,
+        definition,
+        &to_agent,
+        &reason,
+        accepted,
+    )
+    .await?;
+    Ok(accepted.then_some((to_agent, reason)))
+}
+
+/// Continue the task as `to_agent` and return its answer.
+pub async fn forward(
+    definition: &StandardDefinition,
+    to_agent: &str,
+    reason: String,
+    message: Message,
+    context: &Arc<ExecutorContext>,
+) -> Result<String, AgentError> {
+    context
+        .emit(AgentEventType::AgentHandover {
+            from_agent: definition.name.clone(),
+            to_agent: to_agent.to_string(),
+            reason: Some(reason),
+        })
+        .await;
+    let continuation = Arc::new(context.continue_as(to_agent).await);
+    let result = context
+        .get_orchestrator()?
+        .call_agent_stream(to_agent, message, continuation, None)
+        .await?;
+    Ok(result.content.unwrap_or_default())
+}
+
+/// The candidate agents, besides this one, with the tools they declare.
+async fn candidates(
+    config: &HandoffConfig,
+    definition: &StandardDefinition,
+    orchestrator: &Arc<AgentOrchestrator>,
+) -> Vec<HandoffCandidate> {
+    let mut configs = Vec::new();
+    for name in config.candidates() {
+        if name == "*" {
+            configs.extend(orchestrator.list_agents(None, None).await.0);
+        } else if let Some(agent) = orchestrator.get_agent(&name).await {
+            configs.push(agent);
+        }
+    }
+
+    let mut candidates: Vec<HandoffCandidate> = Vec::new();
+    for agent in configs {
+        let candidate = match agent {
+            AgentConfig::StandardAgent(def) => HandoffCandidate {
+                tools: declared_tools(&def),
+                name: def.name,
+                description: def.description,
+            },
+            AgentConfig::WorkflowAgent(def) => HandoffCandidate {
+                name: def.name,
+                description: def.description,
+                tools: vec![],
+            },
+        };
+        if candidate.name != definition.name && !candidates.iter().any(|c| c.name == candidate.name)
+        {
+            candidates.push(candidate);
+        }
+    }
+    candidates
+}
+
+/// Tools an agent declares: builtin and external tools by name, MCP servers
+/// as `server/*`.
+fn declared_tools(definition: &StandardDefinition) -> Vec<String> {
+    let Some(tools) = &definition.tools else {
+        return vec![];
+    };
+    let mut names = tools.builtin.clone();
+    names.extend(tools.mcp.iter().map(|mcp| format!("{}/*", mcp.server)));
+    names.extend(tools.external.iter().flatten().cloned());
+    names
+}
+
+/// Ask the analysis model which agent, if any, the request belongs to.
+async fn classify(
+    definition: &StandardDefinition,
+    request: &str,
+    candidates: &[HandoffCandidate],
+    context: &Arc<ExecutorContext>,
+) -> Result<HandoffVerdict, AgentError> {
+    let model_settings = definition
+        .analysis_model_settings_config()
+        .cloned()
+        .ok_or_else(|| {
+            AgentError::InvalidConfiguration(format!(
+                "agent '{}' has no model settings to classify requests with",
+                definition.name
+            ))
+        })?;
+    let llm_def = LlmDefinition {
+        name: "handoff".to_string(),
+        model_settings: Some(model_settings),
+        tool_format: ToolCallFormat::Provider,
+        tool_delivery_mode: Default::default(),
+    };
+    let executor = crate::llm::create_llm_executor(
+        llm_def,
+        vec![],
+        context.clone(),
+        None,
+        Some("handoff".to_string()),
+    )?;
+    let tools: Vec<String> = context
+        .get_tools()
+        .await
+        .iter()
+        .map(|tool| tool.get_name())
+        .collect();
+    let brief = handoff_brief(request, &definition.name, &tools, candidates);
+    let reply = executor.execute(&[Message::user(brief, None)]).await?;
+
+    let verdict = HandoffVerdict::from_value(&Value::String(reply.content));
+    if verdict.is_none() {
+        tracing::warn!(agent = %definition.name, "handoff classifier did not reply with a verdict");
+    }
+    // Without a usable verdict the agent keeps the request.
+    Ok(verdict.unwrap_or_default())
+}
+
+/// Ask the user to approve the handoff with an `approval_request` tool
+/// call. A timeout counts as declined.
+async fn confirm(
+    orchestrator: &Arc<AgentOrchestrator>,
+    context: &Arc<ExecutorContext>,
+    to_agent: &str,
+    reason: &str,
+) -> bool {
+    let tool_call = ToolCall {
+        tool_call_id: uuid::Uuid::new_v4().to_string(),
+        tool_name: APPROVAL_REQUEST_TOOL_NAME.to_string(),
+        input: json!({
+            "tool_calls": [{
+                "tool_name": "transfer_to_agent",
+                "input": { "agent": to_agent, "reason": reason },
+            }],
+            "message": format!("Hand this request to {}? {}", to_agent, reason),
+        }),
+    };
+    // Registered before the call is announced, so the answer cannot race it.
+    let rx = match orchestrator
+        .stores
+        .external_tool_calls_store
+        .register_external_tool_call(&tool_call.tool_call_id)
+        .await
+    {
+        Ok(rx) => rx,
+        Err(e) => {
+            tracing::warn!("Failed to register the handoff approval: {}", e);
+            return false;
+        }
+    };
+    context
+        .emit(AgentEventType::ToolCalls {
+            step_id: HANDOFF_STEP.to_string(),
+            parent_message_id: None,
+            tool_calls: vec![tool_call.clone()],
+        })
+        .await;
+
+    match tokio::time::timeout(HANDOFF_CONFIRM_TIMEOUT, rx).await {
+        Ok(Ok(response)) => response
+            .result()
+            .get("approved")
+            .and_then(Value::as_bool)
+            .unwrap_or(false),
+        _ => {
+            let _ = orchestrator
+                .stores
+                .external_tool_calls_store
+                .remove_tool_call(&tool_call.tool_call_id)
+                .await;
+            false
+        }
+    }
+}
+
+/// Record the handoff in the scratchpad and the thread's metadata.
+async fn record(
+    orchestrator: &Arc<AgentOrchestrator>,
+    context: &Arc<ExecutorContext>,
+    definition: &StandardDefinition,
+    to_agent: &str,
+    reason: &str,
+    accepted: bool,
+) -> Result<(), AgentError> {
+    let timestamp = chrono::Utc::now().timestamp_millis();
+    let note = if accepted {
+        format!(
+            "Handed off from {} to {}: {}",
+            definition.name, to_agent, reason
+        )
+    } else {
+        format!(
+            "The user declined handing this request to {} ({}); answer it yourself.",
+            to_agent, reason
+        )
+    };
+    context
+        .store_execution_result(&ExecutionResult {
+            step_id: HANDOFF_STEP.to_string(),
+            status: ExecutionStatus::Success,
+            parts: vec![Part::Text(note)],
+            timestamp,
+            reason: Some("Agent handoff".to_string()),
+        })
+        .await?;
+
+    let record = HandoffRecord {
+        task_id: context.task_id.clone(),
+        from_agent: definition.name.clone(),
+        to_agent: to_agent.to_string(),
+        reason: reason.to_string(),
+        accepted,
+        timestamp,
+    };
+    let thread_store = &context
+        .stores
+        .as_ref()
+        .unwrap_or(&orchestrator.stores)
+        .thread_store;
+    let mut handoffs = match thread_store.get_thread(&context.thread_id).await {
+        Ok(Some(thread)) => match thread.metadata.get(THREAD_HANDOFFS_KEY) {
+            Some(Value::Array(handoffs)) => handoffs.clone(),
+            _ => vec![],
+        },
+        _ => vec![],
+    };
+    handoffs.push(serde_json::to_value(&record)?);
+    let result = thread_store
+        .update_thread(
+            &context.thread_id,
+            UpdateThreadRequest {
+                title: None,
+                metadata: Some(HashMap::from([(
+                    THREAD_HANDOFFS_KEY.to_string(),
+                    Value::Array(handoffs),
+                )])),
+                attributes: None,
+                user_id: None,
+                variables: None,
+            },
+        )
+        .await;
+    if let Err(e) = result {
+        tracing::warn!(thread_id = %context.thread_id, "Failed to record the handoff: {}", e);
+    }
+    Ok(())
+}
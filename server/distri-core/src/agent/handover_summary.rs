@@ -0,0 +1,159 @@
+//! Handover summaries of threads (see [`distri_types::handover_summary`]).
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use distri_stores::InitializedStores;
+use distri_types::configuration::AgentConfig;
+use distri_types::handover_summary::{
+    handover_transcript, thread_version, tools_used, GeneratedHandover, HandoverSummary,
+    THREAD_HANDOVER_SUMMARY_KEY,
+};
+use distri_types::{LlmDefinition, Message, TaskMessage, ToolCallFormat, UpdateThreadRequest};
+
+use crate::agent::orchestrator::AgentOrchestrator;
+use crate::agent::ExecutorContext;
+use crate::AgentError;
+
+const HANDOVER_PROMPT: &str = r#"A support agent is handing this conversation over to a human colleague. Write the handover note.
+
+Respond with JSON only, no prose:
+{"customer_intent": "<one sentence: what the customer wants>", "steps_taken": ["<what the agent did, one short step each, in order>"], "current_blocker": "<what keeps the request from being resolved, or null if nothing>"}"#;
+
+/// Characters of each message sent to the summarizing model.
+const MAX_MESSAGE_CHARS: usize = 1500;
+
+impl AgentOrchestrator {
+    /// The handover summary of `thread_id`: the cached one while the thread
+    /// is unchanged, unless `refresh`, or a new one from the analysis model
+    /// of the thread's agent.
+    pub async fn handover_summary(
+        self: &Arc<Self>,
+        thread_id: &str,
+        refresh: bool,
+    ) -> Result<HandoverSummary, AgentError> {
+        self.handover_summary_in(&self.stores, thread_id, refresh)
+            .await
+    }
+
+    /// [`Self::handover_summary`] of a thread kept in `stores`, such as the
+    /// stores of the run asking for it.
+    pub async fn handover_summary_in(
+        self: &Arc<Self>,
+        stores: &InitializedStores,
+        thread_id: &str,
+        refresh: bool,
+    ) -> Result<HandoverSummary, AgentError> {
+        let session = |e: anyhow::Error| AgentError::Session(e.to_string());
+        let mut thread = stores
+            .thread_store
+            .get_thread(thread_id)
+            .await
+            .map_err(session)?
+            .ok_or_else(|| AgentError::NotFound(format!("Thread {} not found", thread_id)))?;
+        // Restores the thread's history when it is archived.
+        self.ensure_thread_restored(&mut thread).await?;
+        let messages: Vec<Message> = stores
+            .task_store
+            .get_history(thread_id, None)
+            .await
+            .map_err(session)?
+            .into_iter()
+            .flat_map(|(_, messages)| messages)
+            .filter_map(|m| match m {
+                TaskMessage::Message(message) => Some(message),
+                TaskMessage::Event(_) => None,
+            })
+            .collect();
+        if messages.is_empty() {
+            return Err(AgentError::Validation(format!(
+                "Thread {} has no messages to summarize",
+                thread_id
+            )));
+        }
+
+        let version = thread_version(&messages);
+        if !refresh {
+            let cached = thread
+                .metadata
+                .get(THREAD_HANDOVER_SUMMARY_KEY)
+                .and_then(|v| serde_json::from_value::<HandoverSummary>(v.clone()).ok());
+            if let Some(summary) = cached.filter(|s| s.thread_version == version) {
+                return Ok(summary);
+            }
+        }
+
+        let model_settings = match self.get_agent(&thread.agent_id).await {
+            Some(AgentConfig::StandardAgent(definition)) => {
+                definition.analysis_model_settings_config().cloned()
+            }
+            _ => None,
+        }
+        .ok_or_else(|| {
+            AgentError::InvalidConfiguration(format!(
+                "agent '{}' has no model settings to summarize threads with",
+                thread.agent_id
+            ))
+        })?;
+        let llm_def = LlmDefinition {
+            name: "handover_summary".to_string(),
+            model_settings: Some(model_settings),
+            tool_format: ToolCallFormat::Provider,
+            tool_delivery_mode: Default::default(),
+        };
+        let context = ExecutorContext {
+            thread_id: thread_id.to_string(),
+            agent_id: thread.agent_id.clone(),
+            orchestrator: Some(self.clone()),
+            ..Default::default()
+        };
+        let executor = crate::llm::create_llm_executor(
+            llm_def,
+            vec![],
+            Arc::new(context),
+            None,
+            Some("handover_summary".to_string()),
+        )?;
+        let reply = executor
+            .execute(&[
+                Message::system(HANDOVER_PROMPT.to_string(), None),
+                Message::user(handover_transcript(&messages, MAX_MESSAGE_CHARS), None),
+            ])
+            .await?;
+        let generated = GeneratedHandover::parse(&reply.content).ok_or_else(|| {
+            AgentError::LLMError("the model did not reply with a handover summary".to_string())
+        })?;
+
+        let summary = HandoverSummary {
+            thread_id: thread_id.to_string(),
+            customer_intent: generated.customer_intent,
+            steps_taken: generated.steps_taken,
+            tools_used: tools_used(&messages),
+            current_blocker: generated.current_blocker,
+            thread_version: version,
+            generated_at: chrono::Utc::now(),
+        };
+        let mut metadata = HashMap::new();
+        metadata.insert(
+            THREAD_HANDOVER_SUMMARY_KEY.to_string(),
+            serde_json::to_value(&summary).unwrap_or_default(),
+        );
+        if let Err(e) = stores
+            .thread_store
+            .update_thread(
+                thread_id,
+                UpdateThreadRequest {
+                    title: None,
+                    metadata: Some(metadata),
+                    attributes: None,
+                    user_id: None,
+                    variables: None,
+                },
+            )
+            .await
+        {
+            tracing::warn!(thread_id = %thread_id, "Failed to cache the handover summary: {}", e);
+        }
+        Ok(summary)
+    }
+}
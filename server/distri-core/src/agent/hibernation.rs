@@ -0,0 +1,273 @@
+//! Idle thread hibernation.
+//!
+//! With `hibernation` configured, a thread's MCP connection pool and browser
+//! session live in [`ThreadResources`] between messages instead of being
+//! created for every run. [`Hibernator`] periodically releases them for
+//! threads that have been idle longer than `idle_secs`. Everything else a
+//! thread needs — messages, tasks, attributes — is already in the stores, so
+//! the next message simply re-creates the resources on demand and the
+//! thread wakes up without the caller noticing.
+//!
+//! Both transitions are logged (`thread hibernated` / `thread woke from
+//! hibernation`) with the thread id and what was released.
+
+use std::future::Future;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use dashmap::DashMap;
+use distri_types::hibernation::HibernationConfig;
+
+use crate::agent::AgentOrchestrator;
+use crate::servers::McpClientPool;
+
+/// Heavy per-thread resources kept alive between messages.
+#[derive(Default)]
+pub struct ThreadResources {
+    threads: DashMap<String, ThreadEntry>,
+    hibernated: DashMap<String, HibernatedThread>,
+}
+
+struct ThreadEntry {
+    last_active: Instant,
+    /// Runs executing on the thread; it is not hibernated while any are.
+    running: usize,
+    mcp_pool: Option<Arc<McpClientPool>>,
+    browser_session_id: Option<String>,
+}
+
+impl Default for ThreadEntry {
+    fn default() -> Self {
+        Self {
+            last_active: Instant::now(),
+            running: 0,
+            mcp_pool: None,
+            browser_session_id: None,
+        }
+    }
+}
+
+/// What a hibernated thread had released, kept for the wake log.
+#[derive(Debug, Clone)]
+struct HibernatedThread {
+    at: Instant,
+    had_mcp_pool: bool,
+    had_browser: bool,
+}
+
+/// Resources taken from a thread that went idle, to be torn down.
+pub struct IdleThread {
+    pub thread_id: String,
+    pub idle: Duration,
+    pub mcp_pool: Option<Arc<McpClientPool>>,
+    pub browser_session_id: Option<String>,
+}
+
+/// Marks a run on a thread; the thread counts as active until it is dropped.
+pub struct ThreadRun {
+    resources: Arc<ThreadResources>,
+    thread_id: String,
+}
+
+impl Drop for ThreadRun {
+    fn drop(&mut self) {
+        if let Some(mut entry) = self.resources.threads.get_mut(&self.thread_id) {
+            entry.running = entry.running.saturating_sub(1);
+            entry.last_active = Instant::now();
+        }
+    }
+}
+
+impl ThreadResources {
+    /// Start a run on `thread_id`, waking the thread if it was hibernated.
+    pub fn enter(self: &Arc<Self>, thread_id: &str) -> ThreadRun {
+        self.wake(thread_id);
+        let mut entry = self.threads.entry(thread_id.to_string()).or_default();
+        entry.running += 1;
+        entry.last_active = Instant::now();
+        ThreadRun {
+            resources: self.clone(),
+            thread_id: thread_id.to_string(),
+        }
+    }
+
+    /// The thread's MCP pool, built with `build` on first use (or first use
+    /// after hibernation).
+    pub async fn mcp_pool<F>(&self, thread_id: &str, build: F) -> Option<Arc<McpClientPool>>
+    where
+        F: Future<Output = Option<Arc<McpClientPool>>>,
+    {
+        if let Some(pool) = self.touch(thread_id).mcp_pool.clone() {
+            return Some(pool);
+        }
+        let pool = build.await?;
+        let mut entry = self.touch(thread_id);
+        Some(entry.mcp_pool.get_or_insert(pool).clone())
+    }
+
+    pub fn browser_session(&self, thread_id: &str) -> Option<String> {
+        self.touch(thread_id).browser_session_id.clone()
+    }
+
+    pub fn set_browser_session(&self, thread_id: &str, session_id: String) {
+        self.touch(thread_id).browser_session_id = Some(session_id);
+    }
+
+    pub fn is_hibernated(&self, thread_id: &str) -> bool {
+        self.hibernated.contains_key(thread_id)
+    }
+
+    /// Remove the threads idle for at least `idle` with no run in progress.
+    /// Threads that held resources are marked hibernated and returned for
+    /// teardown.
+    pub fn take_idle(&self, idle: Duration) -> Vec<IdleThread> {
+        self.take_idle_except(idle, |_| false)
+    }
+
+    /// [`Self::take_idle`], leaving alone the threads `keep` returns true for.
+    pub fn take_idle_except(&self, idle: Duration, keep: impl Fn(&str) -> bool) -> Vec<IdleThread> {
+        let idle_ids: Vec<String> = self
+            .threads
+            .iter()
+            .filter(|entry| entry.running == 0 && entry.last_active.elapsed() >= idle)
+            .filter(|entry| !keep(entry.key()))
+            .map(|entry| entry.key().clone())
+            .collect();
+
+        let mut taken = Vec::new();
+        for thread_id in idle_ids {
+            // Re-checked under the removal lock: a run may have started since.
+            let Some((_, entry)) = self.threads.remove_if(&thread_id, |_, entry| {
+                entry.running == 0 && entry.last_active.elapsed() >= idle
+            }) else {
+                continue;
+            };
+            if entry.mcp_pool.is_none() && entry.browser_session_id.is_none() {
+                continue;
+            }
+            self.hibernated.insert(
+                thread_id.clone(),
+                HibernatedThread {
+                    at: Instant::now(),
+                    had_mcp_pool: entry.mcp_pool.is_some(),
+                    had_browser: entry.browser_session_id.is_some(),
+                },
+            );
+            taken.push(IdleThread {
+                thread_id,
+                idle: entry.last_active.elapsed(),
+                mcp_pool: entry.mcp_pool,
+                browser_session_id: entry.browser_session_id,
+            });
+        }
+        taken
+    }
+
+    fn wake(&self, thread_id: &str) {
+        if let Some((_, hibernated)) = self.hibernated.remove(thread_id) {
+            tracing::info!(
+                thread_id,
+                hibernated_secs = hibernated.at.elapsed().as_secs(),
+                mcp = hibernated.had_mcp_pool,
+                browser = hibernated.had_browser,
+                "thread woke from hibernation"
+            );
+        }
+    }
+
+    fn touch(&self, thread_id: &str) -> dashmap::mapref::one::RefMut<'_, String, ThreadEntry> {
+        self.wake(thread_id);
+        let mut entry = self.threads.entry(thread_id.to_string()).or_default();
+        entry.last_active = Instant::now();
+        entry
+    }
+}
+
+/// Periodically hibernates idle threads of an orchestrator.
+pub struct Hibernator {
+    orchestrator: Arc<AgentOrchestrator>,
+    config: HibernationConfig,
+}
+
+impl Hibernator {
+    /// `None` when hibernation is not configured.
+    pub fn new(orchestrator: Arc<AgentOrchestrator>) -> Option<Self> {
+        let config = orchestrator.hibernation.clone()?;
+        Some(Self {
+            orchestrator,
+            config,
+        })
+    }
+
+    pub fn start(self) -> tokio::task::JoinHandle<()> {
+        tracing::info!(
+            idle_secs = self.config.idle_secs,
+            "thread hibernation enabled"
+        );
+        tokio::spawn(async move {
+            let interval = Duration::from_secs(self.config.sweep_interval_secs.max(1));
+            loop {
+                tokio::time::sleep(interval).await;
+                self.sweep().await;
+            }
+        })
+    }
+
+    /// Hibernate the threads idle longer than `idle_secs`. Returns how many
+    /// were hibernated.
+    pub async fn sweep(&self) -> usize {
+        let resources = &self.orchestrator.thread_resources;
+        let warm = self.orchestrator.warm_sessions.as_ref();
+        // Pinned warm sessions stay up however long they idle.
+        let idle = resources.take_idle_except(Duration::from_secs(self.config.idle_secs), |id| {
+            warm.is_some_and(|w| w.is_pinned(id))
+        });
+        let count = idle.len();
+        for thread in idle {
+            // A warm registry would keep the thread's MCP pool alive.
+            if let Some(warm) = warm {
+                warm.remove(&thread.thread_id);
+            }
+            let mcp_servers = match &thread.mcp_pool {
+                Some(pool) => pool.connected_servers().await,
+                None => Vec::new(),
+            };
+            // Connections close once the last reference to the pool is gone.
+            drop(thread.mcp_pool);
+            if let Some(session_id) = &thread.browser_session_id {
+                let client = browsr_client::BrowsrClient::from_env();
+                if let Err(e) = client.destroy_session(session_id).await {
+                    tracing::warn!(
+                        thread_id = %thread.thread_id,
+                        "failed to close browser session {}: {}",
+                        session_id,
+                        e
+                    );
+                }
+            }
+            tracing::info!(
+                thread_id = %thread.thread_id,
+                idle_secs = thread.idle.as_secs(),
+                mcp_servers = ?mcp_servers,
+                browser_session = ?thread.browser_session_id,
+                "thread hibernated"
+            );
+        }
+        let tabs = self
+            .orchestrator
+            .browser_tabs
+            .take_idle(Duration::from_secs(self.config.idle_secs));
+        for (thread_id, tab) in tabs {
+            let client = browsr_client::BrowsrClient::from_env();
+            if let Err(e) = client.destroy_session(&tab.session_id).await {
+                tracing::warn!(
+                    thread_id = %thread_id,
+                    "failed to close browser tab '{}': {}",
+                    tab.name,
+                    e
+                );
+            }
+        }
+        count
+    }
+}
@@ -0,0 +1,344 @@
+//! Publishing agent events to NATS or Kafka (see
+//! [`distri_types::event_export`]).
+//!
+//! [`EventExporter`] is a system hook: `on_event` encodes the event and
+//! hands it to a bounded queue without waiting, and a background task
+//! publishes the queue in batches. A full queue drops the event rather than
+//! slowing the run down; [`EventExporter::dropped`] counts what was lost.
+//!
+//! The brokers are behind the `nats` and `kafka` features.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use distri_types::event_export::{EventExportConfig, ExportMessage};
+use tokio::sync::mpsc;
+
+use crate::agent::types::AgentHooks;
+use crate::types::AgentEvent;
+use crate::AgentError;
+
+/// First wait before retrying a failed publish; doubled on each retry.
+const RETRY_BACKOFF: Duration = Duration::from_millis(200);
+const MAX_RETRY_BACKOFF: Duration = Duration::from_secs(10);
+/// A dropped event is logged once per this many.
+const DROP_LOG_INTERVAL: u64 = 1000;
+
+/// Publishes encoded events to a broker.
+#[async_trait::async_trait]
+pub trait EventPublisher: Send + Sync {
+    /// Publish `batch` in order. An error means none of it can be assumed
+    /// delivered; the batch is retried as a whole.
+    async fn publish(&self, batch: &[ExportMessage]) -> anyhow::Result<()>;
+}
+
+/// The [`AgentHooks`] that queues events for export.
+#[derive(Debug)]
+pub struct EventExporter {
+    config: EventExportConfig,
+    queue: mpsc::Sender<ExportMessage>,
+    dropped: AtomicU64,
+}
+
+impl EventExporter {
+    /// Connect to the configured broker in the background and start
+    /// publishing. Fails only on an invalid config or a broker this build
+    /// does not support.
+    pub fn start(config: EventExportConfig) -> anyhow::Result<Arc<Self>> {
+        config.validate().map_err(anyhow::Error::msg)?;
+        let connect = connector(&config)?;
+        let (exporter, queue) = Self::new(config);
+        let max_retries = exporter.config.max_retries;
+        let batch_size = exporter.config.batch_size;
+        tokio::spawn(async move {
+            let publisher = connect_with_retry(connect).await;
+            run(publisher, queue, batch_size, max_retries).await;
+        });
+        Ok(exporter)
+    }
+
+    /// Start publishing to `publisher`.
+    pub fn with_publisher(
+        config: EventExportConfig,
+        publisher: Arc<dyn EventPublisher>,
+    ) -> Arc<Self> {
+        let (exporter, queue) = Self::new(config);
+        let max_retries = exporter.config.max_retries;
+        let batch_size = exporter.config.batch_size;
+        tokio::spawn(run(publisher, queue, batch_size, max_retries));
+        exporter
+    }
+
+    fn new(config: EventExportConfig) -> (Arc<Self>, mpsc::Receiver<ExportMessage>) {
+        let (queue, receiver) = mpsc::channel(config.buffer_size);
+        let exporter = Arc::new(Self {
+            config,
+            queue,
+            dropped: AtomicU64::new(0),
+        });
+        (exporter, receiver)
+    }
+
+    /// Events dropped because the queue was full.
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+}
+
+#[async_trait::async_trait]
+impl AgentHooks for EventExporter {
+    async fn on_event(&self, event: &AgentEvent) -> Result<(), AgentError> {
+        let Some(message) = self.config.encode(event) else {
+            return Ok(());
+        };
+        if let Err(mpsc::error::TrySendError::Full(_)) = self.queue.try_send(message) {
+            let dropped = self.dropped.fetch_add(1, Ordering::Relaxed) + 1;
+            if dropped % DROP_LOG_INTERVAL == 1 {
+                tracing::warn!(
+                    "event export queue is full; {} event(s) dropped so far",
+                    dropped
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+async fn run(
+    publisher: Arc<dyn EventPublisher>,
+    mut queue: mpsc::Receiver<ExportMessage>,
+    batch_size: usize,
+    max_retries: u32,
+) {
+    let mut batch = Vec::with_capacity(batch_size);
+    while queue.recv_many(&mut batch, batch_size).await > 0 {
+        let mut backoff = RETRY_BACKOFF;
+        let mut attempt = 0;
+        while let Err(e) = publisher.publish(&batch).await {
+            if attempt == max_retries {
+                tracing::warn!(
+                    "dropping {} event(s) after {} failed publish attempt(s): {}",
+                    batch.len(),
+                    attempt + 1,
+                    e
+                );
+                break;
+            }
+            attempt += 1;
+            tokio::time::sleep(backoff).await;
+            backoff = (backoff * 2).min(MAX_RETRY_BACKOFF);
+        }
+        batch.clear();
+    }
+}
+
+type Connect = Box<
+    dyn Fn() -> futures::future::BoxFuture<'static, anyhow::Result<Arc<dyn EventPublisher>>>
+        + Send
+        + Sync,
+>;
+
+/// Retry connecting until the broker answers, so the server starts without
+/// it. Events queue (and, once full, drop) in the meantime.
+async fn connect_with_retry(connect: Connect) -> Arc<dyn EventPublisher> {
+    let mut backoff = RETRY_BACKOFF;
+    loop {
+        match connect().await {
+            Ok(publisher) => return publisher,
+            Err(e) => {
+                tracing::warn!(
+                    "event export broker unavailable, retrying in {:?}: {}",
+                    backoff,
+                    e
+                );
+                tokio::time::sleep(backoff).await;
+                backoff = (backoff * 2).min(MAX_RETRY_BACKOFF);
+            }
+        }
+    }
+}
+
+fn connector(config: &EventExportConfig) -> anyhow::Result<Connect> {
+    use distri_types::event_export::BrokerConfig;
+
+    match config.broker.clone() {
+        #[cfg(feature = "nats")]
+        BrokerConfig::Nats {
+            url,
+            subject,
+            jetstream,
+            credentials_file,
+        } => Ok(Box::new(move || {
+            let (url, subject, credentials_file) =
+                (url.clone(), subject.clone(), credentials_file.clone());
+            Box::pin(async move {
+                let publisher =
+                    nats::NatsPublisher::connect(&url, subject, jetstream, credentials_file)
+                        .await?;
+                Ok(Arc::new(publisher) as Arc<dyn EventPublisher>)
+            })
+        })),
+        #[cfg(feature = "kafka")]
+        BrokerConfig::Kafka {
+            brokers,
+            topic,
+            partitions,
+        } => Ok(Box::new(move || {
+            let (brokers, topic) = (brokers.clone(), topic.clone());
+            Box::pin(async move {
+                let publisher = kafka::KafkaPublisher::connect(brokers, topic, partitions).await?;
+                Ok(Arc::new(publisher) as Arc<dyn EventPublisher>)
+            })
+        })),
+        #[allow(unreachable_patterns)]
+        broker => anyhow::bail!(
+            "event_export: this server was built without support for {}",
+            match broker {
+                BrokerConfig::Nats { .. } => "NATS (the `nats` feature)",
+                BrokerConfig::Kafka { .. } => "Kafka (the `kafka` feature)",
+            }
+        ),
+    }
+}
+
+/// Partition of `topic` for the events of thread `key`: a stable FNV-1a
+/// hash, so a thread keeps its partition across restarts.
+pub fn partition_for(key: &str, partitions: i32) -> i32 {
+    let hash = key.bytes().fold(0xcbf29ce484222325u64, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3)
+    });
+    (hash % partitions.max(1) as u64) as i32
+}
+
+#[cfg(feature = "nats")]
+mod nats {
+    use distri_types::event_export::ExportMessage;
+
+    use super::EventPublisher;
+
+    pub struct NatsPublisher {
+        client: async_nats::Client,
+        jetstream: Option<async_nats::jetstream::Context>,
+        subject: String,
+    }
+
+    impl NatsPublisher {
+        pub async fn connect(
+            url: &str,
+            subject: String,
+            jetstream: bool,
+            credentials_file: Option<String>,
+        ) -> anyhow::Result<Self> {
+            let mut options = async_nats::ConnectOptions::new();
+            if let Some(path) = credentials_file {
+                options = options.credentials_file(path).await?;
+            }
+            let client = options.connect(url).await?;
+            let jetstream = jetstream.then(|| async_nats::jetstream::new(client.clone()));
+            Ok(Self {
+                client,
+                jetstream,
+                subject,
+            })
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl EventPublisher for NatsPublisher {
+        async fn publish(&self, batch: &[ExportMessage]) -> anyhow::Result<()> {
+            for message in batch {
+                let subject = format!("{}.{}", self.subject, message.event_type);
+                let mut headers = async_nats::HeaderMap::new();
+                headers.insert("Content-Type", message.content_type);
+                headers.insert("Distri-Thread-Id", message.key.as_str());
+                let payload = message.payload.clone().into();
+                match &self.jetstream {
+                    Some(jetstream) => {
+                        jetstream
+                            .publish_with_headers(subject, headers, payload)
+                            .await?
+                            .await?;
+                    }
+                    None => {
+                        self.client
+                            .publish_with_headers(subject, headers, payload)
+                            .await?;
+                    }
+                }
+            }
+            if self.jetstream.is_none() {
+                self.client.flush().await?;
+            }
+            Ok(())
+        }
+    }
+}
+
+#[cfg(feature = "kafka")]
+mod kafka {
+    use std::collections::BTreeMap;
+    use std::sync::Arc;
+
+    use distri_types::event_export::ExportMessage;
+    use rskafka::client::partition::{Compression, PartitionClient, UnknownTopicHandling};
+    use rskafka::client::ClientBuilder;
+    use rskafka::record::Record;
+
+    use super::{partition_for, EventPublisher};
+
+    pub struct KafkaPublisher {
+        partitions: Vec<Arc<PartitionClient>>,
+    }
+
+    impl KafkaPublisher {
+        pub async fn connect(
+            brokers: Vec<String>,
+            topic: String,
+            partitions: i32,
+        ) -> anyhow::Result<Self> {
+            let client = ClientBuilder::new(brokers).build().await?;
+            let mut clients = Vec::new();
+            for partition in 0..partitions {
+                let partition = client
+                    .partition_client(topic.clone(), partition, UnknownTopicHandling::Retry)
+                    .await?;
+                clients.push(Arc::new(partition));
+            }
+            Ok(Self {
+                partitions: clients,
+            })
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl EventPublisher for KafkaPublisher {
+        async fn publish(&self, batch: &[ExportMessage]) -> anyhow::Result<()> {
+            let mut records: BTreeMap<i32, Vec<Record>> = BTreeMap::new();
+            for message in batch {
+                let partition = partition_for(&message.key, self.partitions.len() as i32);
+                records.entry(partition).or_default().push(Record {
+                    key: Some(message.key.clone().into_bytes()),
+                    value: Some(message.payload.clone()),
+                    headers: BTreeMap::from([
+                        (
+                            "content-type".to_string(),
+                            message.content_type.as_bytes().to_vec(),
+                        ),
+                        (
+                            "distri-event-type".to_string(),
+                            message.event_type.clone().into_bytes(),
+                        ),
+                    ]),
+                    timestamp: chrono::Utc::now(),
+                });
+            }
+            for (partition, records) in records {
+                self.partitions[partition as usize]
+                    .produce(records, Compression::NoCompression)
+                    .await?;
+            }
+            Ok(())
+        }
+    }
+}
@@ -3,6 +3,7 @@ use std::sync::Arc;
 use crate::agent::types::AgentHooks;
 use crate::AgentError;
 
+pub mod event_export;
 pub mod inline;
 pub mod otel;
 
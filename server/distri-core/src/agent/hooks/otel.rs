@@ -13,7 +13,7 @@
 //! 6. on_event(RunFinished) → read final_result from stashed context, record output.value,
 //!    record aggregate usage, drop agent span clone
 
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc};
 
 use async_trait::async_trait;
 use dashmap::DashMap;
@@ -448,6 +448,58 @@ impl AgentHooks for OtelHooks {
                     }
                 }
             }
+            AgentEventType::PluginSpans {
+                tool_call_id,
+                plugin,
+                spans,
+                ..
+            } => {
+                // Spans arrive finished and parents first; nest each under its
+                // parent plugin span, or the tool span for top-level spans.
+                use tracing_opentelemetry::OpenTelemetrySpanExt as _;
+
+                let Some(tool_span) = self.tool_spans.get(tool_call_id.as_str()) else {
+                    return Ok(());
+                };
+                let mut created: HashMap<&str, tracing::Span> = HashMap::new();
+                for record in spans {
+                    let parent = record
+                        .parent_span_id
+                        .as_deref()
+                        .and_then(|id| created.get(id))
+                        .unwrap_or(tool_span.value());
+                    let span = parent.in_scope(|| {
+                        builder::plugin_span(
+                            &record.name,
+                            plugin.as_deref(),
+                            &record.span_id,
+                            record.duration_ms,
+                        )
+                    });
+                    for (key, value) in &record.attributes {
+                        let value = match value {
+                            serde_json::Value::String(s) => s.clone(),
+                            other => other.to_string(),
+                        };
+                        span.set_attribute(format!("distri.plugin.{}", key), value);
+                    }
+                    span.in_scope(|| {
+                        for log in &record.logs {
+                            tracing::info!(
+                                target: "gen_ai",
+                                offset_ms = log.offset_ms,
+                                "{}",
+                                log.message
+                            );
+                        }
+                    });
+                    if let Some(error) = &record.error {
+                        span.record("otel.status_code", "ERROR");
+                        span.record("error.message", error.as_str());
+                    }
+                    created.insert(record.span_id.as_str(), span);
+                }
+            }
             AgentEventType::ToolExecutionEnd {
                 tool_call_id,
                 success,
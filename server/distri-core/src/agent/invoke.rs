@@ -856,17 +856,20 @@ impl ResolvedTarget {
             AgentRef::Named {
                 agent_id,
                 instructions_overlay,
+                model,
             } => Self {
                 agent_id: agent_id.clone(),
                 // A skill-fork: same agent, skill body APPENDED below its own
                 // instructions for this run only. No tool inheritance — the
-                // named agent already carries its own tools.
-                definition_overrides: instructions_overlay.as_ref().map(|overlay| {
-                    distri_types::configuration::DefinitionOverrides {
-                        instructions_append: Some(overlay.clone()),
+                // named agent already carries its own tools. An ensemble
+                // branch may swap the model.
+                definition_overrides: (instructions_overlay.is_some() || model.is_some()).then(
+                    || distri_types::configuration::DefinitionOverrides {
+                        instructions_append: instructions_overlay.clone(),
+                        model: model.clone(),
                         ..Default::default()
-                    }
-                }),
+                    },
+                ),
             },
             AgentRef::AdHoc {
                 system_prompt,
@@ -899,12 +902,13 @@ impl ResolvedTarget {
 }
 
 /// Strip tools that should NOT cross from a parent into a worker
-/// dispatched via `invoke_agent`. Currently a single-element list:
-/// `write_todos`. Workers that inherit it create and update their
-/// own top-level todos, which pollute the parent's todo state and
-/// defeat the point of using `invoke_agent` for isolation.
+/// dispatched via `invoke_agent`: the todo tools. Workers that inherit
+/// `write_todos` create and update their own top-level todos, which
+/// pollute the parent's todo state and defeat the point of using
+/// `invoke_agent` for isolation; a worker running `work_todos` would
+/// re-enter the queue it is an item of.
 fn filter_for_subtask(mut tools: distri_types::ToolsConfig) -> distri_types::ToolsConfig {
-    const NON_INHERITED: &[&str] = &["write_todos"];
+    const NON_INHERITED: &[&str] = &["write_todos", "work_todos"];
     tools
         .builtin
         .retain(|name| !NON_INHERITED.contains(&name.as_str()));
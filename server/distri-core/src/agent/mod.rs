@@ -1,38 +1,61 @@
 pub mod agent_loop;
+mod auth_consent;
 pub mod browser_sessions;
+pub mod browser_tabs;
+pub mod capability_probe;
 pub mod compaction;
 pub mod context;
+pub mod context_fit;
 pub mod context_size_manager;
+mod conversation_import;
+mod critique;
 pub mod debug;
+mod ensemble;
+mod dev_seed;
+pub mod evals;
 pub mod file;
+mod handoff;
+mod handover_summary;
+pub mod hibernation;
 pub mod hooks;
 pub mod invoke;
 pub mod log;
 pub mod memory;
+pub mod observer;
 pub mod orchestrator;
+pub mod output_sinks;
 mod parser;
+pub mod post_process;
 pub(crate) mod pricing;
 pub mod prompt_registry {
     pub use distri_types::prompt::*;
 }
 pub mod prompt_validation;
 pub mod reflection;
+mod regenerate;
 pub mod remote;
 pub mod server;
 pub mod skill_tracker;
 pub mod standard;
 pub mod strategy;
+mod task_diff;
+mod task_retry;
+pub mod thread_archive;
+mod thread_title;
 pub mod todos;
 pub mod token_estimator;
+pub mod tokenizer;
 pub mod tool_lookup;
 pub mod types;
+pub mod user_quotas;
+pub mod warm_sessions;
 pub mod workflow_agent;
 mod workflow_driver;
 mod workflow_step_exec;
 // Export specific items to avoid conflicts
 pub use agent_loop::*;
 pub use distri_types::parse_agent_markdown_content;
-pub use parser::load_agents_from_dir;
+pub use parser::{load_agents_from_dir, load_workspace, load_workspace_packages, WorkspacePackage};
 pub use prompt_validation::{
     builtin_partials, extract_partial_references, format_validation_table, validate_agent_prompt,
     validate_agent_prompt_with_partials, validate_partial_references, Criticality, ValidationIssue,
@@ -0,0 +1,170 @@
+//! Read-only observers for shared threads.
+//!
+//! A thread owner mints a share token ([`create_thread_share`]); anyone
+//! holding it can follow the thread's live runs through [`watch_thread`] but
+//! never send input. Grants live in the thread metadata (see
+//! [`distri_types::api::share`]), so revoking or letting one expire needs no
+//! extra store.
+//!
+//! A watch follows every top-level task that runs on the thread while the
+//! observer is connected, fanning in their broadcaster streams. Sub-agent
+//! events arrive through their parent's stream. Events published before a
+//! task is picked up by the next poll are not replayed by the in-process
+//! broadcaster.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::Utc;
+use distri_types::api::share::{hash_share_token, ObserverGrant, ThreadShareResponse};
+use distri_types::{AgentEvent, UpdateThreadRequest, THREAD_OBSERVERS_KEY};
+use futures_util::stream::{BoxStream, SelectAll};
+use futures_util::StreamExt;
+
+use crate::{AgentError, AgentOrchestrator};
+
+/// How often a watch looks for new runs on the thread and re-checks that
+/// its grant is still valid.
+const WATCH_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Mint a share token for `thread_id`, valid for `ttl_secs`. Expired grants
+/// are pruned on the way.
+pub async fn create_thread_share(
+    executor: &AgentOrchestrator,
+    thread_id: &str,
+    ttl_secs: u64,
+) -> Result<ThreadShareResponse, AgentError> {
+    let thread = executor
+        .get_thread(thread_id)
+        .await?
+        .ok_or_else(|| AgentError::NotFound(format!("thread '{}'", thread_id)))?;
+
+    let now = Utc::now();
+    let token = format!(
+        "{}{}",
+        uuid::Uuid::new_v4().simple(),
+        uuid::Uuid::new_v4().simple()
+    );
+    let expires_at = now + chrono::Duration::seconds(ttl_secs as i64);
+    let mut grants: Vec<ObserverGrant> = thread
+        .observer_grants()
+        .into_iter()
+        .filter(|g| !g.is_expired(now))
+        .collect();
+    grants.push(ObserverGrant {
+        token_hash: hash_share_token(&token),
+        expires_at,
+    });
+    store_grants(executor, thread_id, &grants).await?;
+
+    Ok(ThreadShareResponse {
+        thread_id: thread_id.to_string(),
+        watch_path: format!("/threads/{}/watch?token={}", thread_id, token),
+        token,
+        expires_at,
+    })
+}
+
+/// Revoke every share token of `thread_id`. Open watches end within one
+/// poll interval.
+pub async fn revoke_thread_shares(
+    executor: &AgentOrchestrator,
+    thread_id: &str,
+) -> Result<(), AgentError> {
+    if executor.get_thread(thread_id).await?.is_none() {
+        return Err(AgentError::NotFound(format!("thread '{}'", thread_id)));
+    }
+    store_grants(executor, thread_id, &[]).await
+}
+
+/// Whether `token` is a live share token of `thread_id`. Unknown threads
+/// are simply not admitted.
+pub async fn verify_thread_share(
+    executor: &AgentOrchestrator,
+    thread_id: &str,
+    token: &str,
+) -> Result<bool, AgentError> {
+    let thread = executor
+        .stores
+        .thread_store
+        .get_thread(thread_id)
+        .await
+        .map_err(|e| AgentError::Session(e.to_string()))?;
+    let now = Utc::now();
+    Ok(thread.is_some_and(|t| t.observer_grants().iter().any(|g| g.admits(token, now))))
+}
+
+/// Live events of every top-level run on `thread_id`, for as long as the
+/// caller keeps the stream and `token` stays valid.
+pub fn watch_thread(
+    executor: Arc<AgentOrchestrator>,
+    thread_id: String,
+    token: String,
+) -> BoxStream<'static, AgentEvent> {
+    Box::pin(async_stream::stream! {
+        let broadcaster = executor.runtime.broadcaster_arc();
+        let mut followed: HashSet<String> = HashSet::new();
+        let mut runs: SelectAll<BoxStream<'static, AgentEvent>> = SelectAll::new();
+        let mut ticker = tokio::time::interval(WATCH_POLL_INTERVAL);
+        loop {
+            let event = tokio::select! {
+                _ = ticker.tick() => {
+                    match verify_thread_share(&executor, &thread_id, &token).await {
+                        Ok(true) => {}
+                        Ok(false) => break,
+                        Err(e) => {
+                            tracing::warn!(thread_id = %thread_id, "observer grant check failed: {}", e);
+                            continue;
+                        }
+                    }
+                    let tasks = executor
+                        .stores
+                        .task_store
+                        .list_running_tasks(Some(&thread_id))
+                        .await
+                        .unwrap_or_default();
+                    for task in tasks {
+                        if task.parent_task_id.is_some() || !followed.insert(task.id.clone()) {
+                            continue;
+                        }
+                        match broadcaster.follow_stream(&task.id).await {
+                            Ok(stream) => runs.push(stream),
+                            Err(e) => tracing::warn!(task_id = %task.id, "observer subscribe failed: {}", e),
+                        }
+                    }
+                    None
+                }
+                Some(event) = runs.next(), if !runs.is_empty() => Some(event),
+            };
+            if let Some(event) = event {
+                yield event;
+            }
+        }
+    })
+}
+
+async fn store_grants(
+    executor: &AgentOrchestrator,
+    thread_id: &str,
+    grants: &[ObserverGrant],
+) -> Result<(), AgentError> {
+    let value = if grants.is_empty() {
+        serde_json::Value::Null
+    } else {
+        serde_json::to_value(grants).map_err(|e| AgentError::Session(e.to_string()))?
+    };
+    executor
+        .update_thread(
+            thread_id,
+            UpdateThreadRequest {
+                title: None,
+                metadata: Some(HashMap::from([(THREAD_OBSERVERS_KEY.to_string(), value)])),
+                attributes: None,
+                user_id: None,
+                variables: None,
+            },
+        )
+        .await?;
+    Ok(())
+}
@@ -16,6 +16,8 @@ use distri_stores::{initialize_stores, InitializedStores};
 pub use distri_stores::{AgentStore, ThreadStore};
 use distri_types::configuration::AgentConfig;
 use distri_types::stores::{PromptTemplateStore, SecretStore};
+use distri_types::tool_catalog::{ToolResolution, ToolSourceKind};
+use distri_types::user_profile::{profile_namespace, UserProfile, PROFILE_KEY};
 use distri_types::{browser::BrowsrClientConfig, configuration::StoreConfig, HookMutation};
 use distri_types::{
     configuration::{DefinitionOverrides, ObjectStorageConfig},
@@ -37,6 +39,9 @@ pub struct AgentOrchestrator {
     /// Optional workspace filesystem for HTTP file routes (not used by agent tools).
     /// Set by the hosting application if workspace file APIs are needed.
     pub workspace_filesystem: Option<Arc<FileSystem>>,
+    /// Workspace directory `build_workspace` loads agents and member
+    /// packages from. `None` when the host has no local workspace.
+    pub workspace_root: Option<std::path::PathBuf>,
     pub browser_config: Arc<RwLock<BrowsrClientConfig>>,
     pub additional_tools: Arc<RwLock<HashMap<String, Vec<Arc<dyn Tool>>>>>,
     pub prompt_registry: Arc<PromptRegistry>,
@@ -46,6 +51,9 @@ pub struct AgentOrchestrator {
     pub stores: InitializedStores,
     pub hooks: Arc<RwLock<HashMap<String, Arc<dyn crate::agent::types::AgentHooks>>>>,
     pub inline_hooks: Arc<dashmap::DashMap<String, tokio::sync::oneshot::Sender<HookMutation>>>,
+    /// Tool calls paused on an `AuthRequired` consent prompt, keyed by
+    /// `auth_id`. Resolved with whether the scopes were granted.
+    pub auth_consents: Arc<dashmap::DashMap<String, tokio::sync::oneshot::Sender<bool>>>,
     pub hook_registry: HookRegistry,
     pub system_hooks: Vec<Arc<dyn crate::agent::types::AgentHooks>>,
 
@@ -76,6 +84,73 @@ pub struct AgentOrchestrator {
     /// scheduler tick, event bus, and workflow-as-tool A2A dispatch
     /// consult to find the workflow run a stimulus targets.
     pub workflow_trigger_registry: Option<Arc<dyn distri_workflow::WorkflowTriggerRegistry>>,
+    /// User-defined slash commands (e.g. the OSS server's workspace
+    /// `commands/` directory). Layered over the built-ins by
+    /// `resolve_commands_with`; agent-declared commands still win.
+    pub slash_commands: Arc<RwLock<Vec<distri_types::channel_commands::SlashCommand>>>,
+    /// Databases reachable through the `sql_query` / `sql_schema` builtin
+    /// tools. Empty unless configured by the hosting application.
+    pub sql_connections: Arc<crate::tools::sql::SqlConnections>,
+    /// Interpreter, sandbox and limits of the `python_exec` builtin tool.
+    pub python_exec: distri_types::python_exec::PythonExecConfig,
+    /// Enables the background job queue for non-blocking `message/send`.
+    /// `None` keeps every `message/send` on the request's own task.
+    pub background_jobs: Option<distri_types::jobs::BackgroundJobsConfig>,
+    /// Wakes idle background workers when a job is enqueued.
+    pub background_jobs_wake: Arc<tokio::sync::Notify>,
+    /// Registry agent cards are published to (started with
+    /// `crate::a2a::registry::RegistryPublisher`). `None` publishes nothing.
+    pub agent_registry: Option<distri_types::agent_registry::AgentRegistryConfig>,
+    /// Signalled when an agent is registered, updated or deleted.
+    pub agents_changed: Arc<tokio::sync::Notify>,
+    /// Named transformers an agent's `post_process` chain can reference as
+    /// `{ type = "custom", name = "..." }`.
+    pub response_transformers:
+        Arc<RwLock<HashMap<String, Arc<dyn crate::agent::post_process::ResponseTransformer>>>>,
+    /// Workspace policy layer of every agent's system prompt, rendered after
+    /// the agent's own instructions. Agents opt out with
+    /// `prompt_layers.skip_workspace_policy`.
+    pub prompt_policy: Option<String>,
+    /// Replaces provider dispatch for every LLM call this orchestrator makes.
+    /// `None` in production; set by test harnesses to script responses.
+    pub llm_executor_factory: Option<Arc<dyn crate::llm::LlmExecutorFactory>>,
+    /// Keeps threads' MCP pools and browser sessions between messages and
+    /// releases them once idle (started with
+    /// `crate::agent::hibernation::Hibernator`). `None` creates them per run.
+    pub hibernation: Option<distri_types::hibernation::HibernationConfig>,
+    /// Per-thread resources kept while `hibernation` is set.
+    pub thread_resources: Arc<crate::agent::hibernation::ThreadResources>,
+    /// Browser tabs opened by the `browser_tabs` tool, per thread. Idle
+    /// threads' tabs are closed along with their other resources when
+    /// `hibernation` is set.
+    pub browser_tabs: Arc<crate::agent::browser_tabs::BrowserTabs>,
+    /// History of eval runs and comparisons (see `crate::agent::evals`).
+    pub eval_store: Arc<dyn distri_types::stores::EvalStore>,
+    /// Embeddings shared by the server's features and `POST /embeddings`.
+    pub embeddings: Arc<crate::llm::embeddings::EmbeddingService>,
+    /// Tool registries of recently used threads, reused by their next
+    /// message. `None` assembles the registry on every run.
+    pub warm_sessions: Option<Arc<crate::agent::warm_sessions::WarmSessions>>,
+    /// Archives threads not updated within the retention period (started
+    /// with `crate::agent::thread_archive::Archiver`). Archived threads are
+    /// restored on access either way.
+    pub thread_archive: Option<distri_types::thread_archive::ThreadArchiveConfig>,
+    /// Serializes archiving and restoring threads.
+    pub(crate) thread_archive_lock: Arc<tokio::sync::Mutex<()>>,
+    /// Redaction rules run on every tool result before it enters the
+    /// conversation. `None` when none are configured.
+    pub tool_redactor: Option<Arc<distri_types::tool_redaction::ToolRedactor>>,
+    /// Probes the models of agents with native tool calling and adapts the
+    /// agents to what they can do (see `crate::agent::capability_probe`).
+    pub capability_prober: Option<Arc<crate::agent::capability_probe::CapabilityProber>>,
+    /// Domain rules for outbound requests of tools and plugins. `None` lets
+    /// them reach any host.
+    pub egress_policy: Option<Arc<distri_types::egress::EgressPolicy>>,
+    /// Latency of the LLM calls made since start, per provider and model.
+    pub llm_metrics: Arc<distri_types::llm_metrics::LlmMetrics>,
+    /// Per-user run and token counts and their limits. Unlimited until the
+    /// server sets its `user_quotas`.
+    pub user_quotas: Arc<crate::agent::user_quotas::UserQuotas>,
 }
 
 impl std::fmt::Debug for AgentOrchestrator {
@@ -111,6 +186,7 @@ pub struct AgentOrchestratorBuilder {
     session_filesystem: Option<Arc<FileSystem>>,
     session_storage_path: Option<std::path::PathBuf>,
     workspace_filesystem: Option<Arc<FileSystem>>,
+    workspace_root: Option<std::path::PathBuf>,
     browser_config: Option<BrowsrClientConfig>,
     stores: Option<InitializedStores>,
     prompt_registry: Option<Arc<PromptRegistry>>,
@@ -123,6 +199,24 @@ pub struct AgentOrchestratorBuilder {
     mcp_pool_provider: Option<Arc<dyn crate::servers::McpPoolProvider>>,
     workflow_store: Option<Arc<dyn distri_workflow::WorkflowStore>>,
     workflow_trigger_registry: Option<Arc<dyn distri_workflow::WorkflowTriggerRegistry>>,
+    sql_connections: Vec<distri_types::sql::SqlConnectionConfig>,
+    python_exec: Option<distri_types::python_exec::PythonExecConfig>,
+    background_jobs: Option<distri_types::jobs::BackgroundJobsConfig>,
+    agent_registry: Option<distri_types::agent_registry::AgentRegistryConfig>,
+    response_transformers:
+        HashMap<String, Arc<dyn crate::agent::post_process::ResponseTransformer>>,
+    prompt_policy: Option<String>,
+    llm_executor_factory: Option<Arc<dyn crate::llm::LlmExecutorFactory>>,
+    hibernation: Option<distri_types::hibernation::HibernationConfig>,
+    eval_store: Option<Arc<dyn distri_types::stores::EvalStore>>,
+    embeddings: Option<distri_types::embeddings::EmbeddingsConfig>,
+    embedding_provider: Option<Arc<dyn crate::llm::embeddings::EmbeddingProvider>>,
+    warm_sessions: Option<distri_types::warm_sessions::WarmSessionsConfig>,
+    thread_archive: Option<distri_types::thread_archive::ThreadArchiveConfig>,
+    tool_redaction: Option<distri_types::tool_redaction::ToolRedactionConfig>,
+    event_export: Option<distri_types::event_export::EventExportConfig>,
+    capability_probe: Option<distri_types::capability_probe::CapabilityProbeConfig>,
+    egress: Option<distri_types::egress::EgressPolicyConfig>,
 }
 
 impl AgentOrchestratorBuilder {
@@ -161,6 +255,12 @@ impl AgentOrchestratorBuilder {
         self
     }
 
+    /// Workspace directory of [`AgentOrchestrator::build_workspace`].
+    pub fn with_workspace_root(mut self, root: std::path::PathBuf) -> Self {
+        self.workspace_root = Some(root);
+        self
+    }
+
     pub fn with_prompt_registry(mut self, prompt_registry: Arc<PromptRegistry>) -> Self {
         self.prompt_registry = Some(prompt_registry);
         self
@@ -180,6 +280,16 @@ impl AgentOrchestratorBuilder {
         self
     }
 
+    pub fn with_llm_audit_store(
+        mut self,
+        store: Arc<dyn distri_types::stores::LlmAuditStore>,
+    ) -> Self {
+        if let Some(stores) = &mut self.stores {
+            stores.llm_audit_store = Some(store);
+        }
+        self
+    }
+
     pub fn with_stores(mut self, stores: InitializedStores) -> Self {
         self.stores = Some(stores);
         self
@@ -264,6 +374,165 @@ impl AgentOrchestratorBuilder {
         self
     }
 
+    /// Databases the `sql_query` / `sql_schema` tools may query.
+    pub fn with_sql_connections(
+        mut self,
+        connections: Vec<distri_types::sql::SqlConnectionConfig>,
+    ) -> Self {
+        self.sql_connections = connections;
+        self
+    }
+
+    /// Settings of the `python_exec` tool. Defaults apply when `None`.
+    pub fn with_python_exec(
+        mut self,
+        config: Option<distri_types::python_exec::PythonExecConfig>,
+    ) -> Self {
+        self.python_exec = config;
+        self
+    }
+
+    /// Queue non-blocking `message/send` requests for background workers
+    /// (started with `crate::worker::BackgroundRunner`).
+    pub fn with_background_jobs(
+        mut self,
+        config: Option<distri_types::jobs::BackgroundJobsConfig>,
+    ) -> Self {
+        self.background_jobs = config;
+        self
+    }
+
+    /// Publish agent cards to an A2A registry (started with
+    /// `crate::a2a::registry::RegistryPublisher`).
+    pub fn with_agent_registry(
+        mut self,
+        config: Option<distri_types::agent_registry::AgentRegistryConfig>,
+    ) -> Self {
+        self.agent_registry = config;
+        self
+    }
+
+    /// Workspace policy prompt layered into every agent's system prompt.
+    pub fn with_prompt_policy(mut self, policy: Option<String>) -> Self {
+        self.prompt_policy = policy.filter(|p| !p.trim().is_empty());
+        self
+    }
+
+    pub fn with_response_transformers(
+        mut self,
+        transformers: HashMap<String, Arc<dyn crate::agent::post_process::ResponseTransformer>>,
+    ) -> Self {
+        self.response_transformers = transformers;
+        self
+    }
+
+    /// Route every LLM call through `factory` instead of the configured
+    /// provider.
+    pub fn with_llm_executor_factory(
+        mut self,
+        factory: Arc<dyn crate::llm::LlmExecutorFactory>,
+    ) -> Self {
+        self.llm_executor_factory = Some(factory);
+        self
+    }
+
+    /// Keep per-thread resources between messages and hibernate idle threads
+    /// (started with `crate::agent::hibernation::Hibernator`).
+    pub fn with_hibernation(
+        mut self,
+        config: Option<distri_types::hibernation::HibernationConfig>,
+    ) -> Self {
+        self.hibernation = config;
+        self
+    }
+
+    /// Where eval runs are recorded. Defaults to files under
+    /// `/tmp/distri-evals`.
+    pub fn with_eval_store(mut self, store: Arc<dyn distri_types::stores::EvalStore>) -> Self {
+        self.eval_store = Some(store);
+        self
+    }
+
+    /// Settings of the embedding service. Defaults apply when `None`.
+    pub fn with_embeddings(
+        mut self,
+        config: Option<distri_types::embeddings::EmbeddingsConfig>,
+    ) -> Self {
+        self.embeddings = config;
+        self
+    }
+
+    /// Replaces the configured provider of the embedding service; batching
+    /// and caching still apply.
+    pub fn with_embedding_provider(
+        mut self,
+        provider: Arc<dyn crate::llm::embeddings::EmbeddingProvider>,
+    ) -> Self {
+        self.embedding_provider = Some(provider);
+        self
+    }
+
+    /// Keep the tool registries of recently used threads between messages
+    /// (see `crate::agent::warm_sessions`).
+    pub fn with_warm_sessions(
+        mut self,
+        config: Option<distri_types::warm_sessions::WarmSessionsConfig>,
+    ) -> Self {
+        self.warm_sessions = config;
+        self
+    }
+
+    /// Archive threads not updated within the retention period (see
+    /// `crate::agent::thread_archive`).
+    pub fn with_thread_archive(
+        mut self,
+        config: Option<distri_types::thread_archive::ThreadArchiveConfig>,
+    ) -> Self {
+        self.thread_archive = config;
+        self
+    }
+
+    /// Redact tool output before it reaches the model (see
+    /// [`distri_types::tool_redaction`]). Invalid rules fail `build`.
+    pub fn with_tool_redaction(
+        mut self,
+        config: Option<distri_types::tool_redaction::ToolRedactionConfig>,
+    ) -> Self {
+        self.tool_redaction = config;
+        self
+    }
+
+    /// Publish agent events to NATS or Kafka (see
+    /// [`distri_types::event_export`]). An invalid config, or a broker this
+    /// build lacks the feature for, fails `build`.
+    pub fn with_event_export(
+        mut self,
+        config: Option<distri_types::event_export::EventExportConfig>,
+    ) -> Self {
+        self.event_export = config;
+        self
+    }
+
+    /// Probe models before agents rely on native tool calls (see
+    /// [`distri_types::capability_probe`]). An invalid config fails `build`.
+    pub fn with_capability_probe(
+        mut self,
+        config: Option<distri_types::capability_probe::CapabilityProbeConfig>,
+    ) -> Self {
+        self.capability_probe = config;
+        self
+    }
+
+    /// Limit where tools and plugins may connect (see
+    /// [`distri_types::egress`]). An invalid config fails `build`.
+    pub fn with_egress_policy(
+        mut self,
+        config: Option<distri_types::egress::EgressPolicyConfig>,
+    ) -> Self {
+        self.egress = config;
+        self
+    }
+
     pub async fn build(self) -> anyhow::Result<AgentOrchestrator> {
         let browser_config = self.browser_config.unwrap_or_default();
 
@@ -307,6 +576,14 @@ impl AgentOrchestratorBuilder {
 
         let browser_config = Arc::new(RwLock::new(browser_config));
 
+        let tool_redactor = self
+            .tool_redaction
+            .as_ref()
+            .map(distri_types::tool_redaction::ToolRedactor::new)
+            .transpose()?
+            .filter(|redactor| !redactor.is_empty())
+            .map(Arc::new);
+
         // Initialize prompt registry with defaults only (no auto-discovery)
         // User-specific partials are loaded at render time in formatter.rs
         let prompt_registry = if let Some(registry) = self.prompt_registry {
@@ -324,18 +601,63 @@ impl AgentOrchestratorBuilder {
             ))
         });
 
+        let embeddings_config = self.embeddings.unwrap_or_default();
+        let embeddings = match self.embedding_provider {
+            Some(provider) => {
+                crate::llm::embeddings::EmbeddingService::new(embeddings_config, provider)
+            }
+            None => crate::llm::embeddings::EmbeddingService::from_config(
+                embeddings_config,
+                stores.secret_store.clone(),
+            ),
+        };
+
+        let embeddings = Arc::new(embeddings);
+        let session_filesystem = if embeddings.config().index_artifacts {
+            let index = distri_filesystem::ArtifactIndex::new(embeddings.clone());
+            Arc::new((*session_filesystem).clone().with_artifact_index(index))
+        } else {
+            session_filesystem
+        };
+
+        let mut system_hooks = self.system_hooks;
+        if let Some(config) = self.event_export {
+            let exporter = crate::agent::hooks::event_export::EventExporter::start(config)?;
+            system_hooks.push(exporter);
+        }
+
+        let capability_prober = match self.capability_probe {
+            Some(config) => {
+                config.validate().map_err(anyhow::Error::msg)?;
+                Some(Arc::new(
+                    crate::agent::capability_probe::CapabilityProber::new(config),
+                ))
+            }
+            None => None,
+        };
+
+        let egress_policy = match self.egress {
+            Some(config) => {
+                config.validate().map_err(anyhow::Error::msg)?;
+                Some(Arc::new(distri_types::egress::EgressPolicy::new(&config)))
+            }
+            None => None,
+        };
+
         let orchestrator = AgentOrchestrator {
             mcp_registry: registry,
             session_filesystem,
             workspace_filesystem: self.workspace_filesystem,
+            workspace_root: self.workspace_root,
             browser_config,
             additional_tools: Arc::new(RwLock::new(self.additional_tools.unwrap_or_default())),
             prompt_registry,
             store_config,
             stores,
-            system_hooks: self.system_hooks,
+            system_hooks,
             hooks: hooks.clone(),
             inline_hooks: Arc::new(dashmap::DashMap::new()),
+            auth_consents: Arc::new(dashmap::DashMap::new()),
             hook_registry: HookRegistry::new(),
             runtime,
             remote_task_runner: self.remote_task_runner,
@@ -343,6 +665,34 @@ impl AgentOrchestratorBuilder {
             mcp_pool_provider: self.mcp_pool_provider,
             workflow_store: self.workflow_store,
             workflow_trigger_registry: self.workflow_trigger_registry,
+            slash_commands: Arc::new(RwLock::new(Vec::new())),
+            sql_connections: Arc::new(crate::tools::sql::SqlConnections::new(self.sql_connections)),
+            python_exec: self.python_exec.unwrap_or_default(),
+            background_jobs: self.background_jobs,
+            background_jobs_wake: Arc::new(tokio::sync::Notify::new()),
+            agent_registry: self.agent_registry,
+            agents_changed: Arc::new(tokio::sync::Notify::new()),
+            response_transformers: Arc::new(RwLock::new(self.response_transformers)),
+            prompt_policy: self.prompt_policy,
+            llm_executor_factory: self.llm_executor_factory,
+            hibernation: self.hibernation,
+            thread_resources: Arc::new(crate::agent::hibernation::ThreadResources::default()),
+            browser_tabs: Arc::new(crate::agent::browser_tabs::BrowserTabs::default()),
+            eval_store: self.eval_store.unwrap_or_else(|| {
+                Arc::new(distri_stores::FileEvalStore::new("/tmp/distri-evals"))
+            }),
+            embeddings,
+            warm_sessions: self
+                .warm_sessions
+                .as_ref()
+                .map(|config| Arc::new(crate::agent::warm_sessions::WarmSessions::new(config))),
+            thread_archive: self.thread_archive,
+            thread_archive_lock: Arc::new(tokio::sync::Mutex::new(())),
+            tool_redactor,
+            capability_prober,
+            egress_policy,
+            llm_metrics: Arc::default(),
+            user_quotas: Arc::default(),
         };
 
         // Sync system prompts to the store
@@ -401,19 +751,33 @@ impl AgentOrchestrator {
     pub async fn register_mcp_server(&self, name: String, server: ServerMetadataWrapper) {
         let registry = self.mcp_registry.clone();
         registry.write().await.register(name, server);
+        self.invalidate_warm_sessions();
+    }
+
+    /// Drop the warm tool registries after a change they may not reflect.
+    fn invalidate_warm_sessions(&self) {
+        if let Some(warm) = &self.warm_sessions {
+            warm.invalidate_all();
+        }
     }
 
     /// Resolve the per-run MCP pool for an `ExecutorContext` via the attached
     /// provider. Called from inside `create_agent_from_config`, where tool
     /// resolution happens — this is the single place a run's MCP pool comes
     /// from. Returns `None` when no provider is configured (OSS standalone)
-    /// or when the provider declines.
+    /// or when the provider declines. With `hibernation` set, the pool is
+    /// built once per thread and reused until the thread hibernates.
     pub async fn resolve_mcp_pool(
         &self,
         ctx: &ExecutorContext,
     ) -> Option<Arc<crate::servers::McpClientPool>> {
         let provider = self.mcp_pool_provider.as_ref()?;
-        provider.build_pool(ctx).await
+        if self.hibernation.is_none() {
+            return provider.build_pool(ctx).await;
+        }
+        self.thread_resources
+            .mcp_pool(&ctx.thread_id, provider.build_pool(ctx))
+            .await
     }
 
     pub async fn register_agent_definition(
@@ -424,6 +788,21 @@ impl AgentOrchestrator {
         self.register_agent_config(config).await
     }
 
+    /// Load the agents of the workspace root and of its member packages
+    /// (see [`distri_types::packages`]) and register them. Member agents
+    /// are registered as `<package>/<agent>`.
+    pub async fn build_workspace(&self) -> anyhow::Result<distri_types::packages::WorkspaceBuild> {
+        let root = self
+            .workspace_root
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("no workspace directory is configured"))?;
+        let (agents, build) = crate::agent::load_workspace(root).await?;
+        for definition in agents {
+            self.register_agent_definition(definition).await?;
+        }
+        Ok(build)
+    }
+
     /// Variant-aware registration. Workflow agents skip the partial-template
     /// hookup (workflows have no prompt templates), but still go through the
     /// store so they appear in `list_agents` and the workspace tree.
@@ -450,11 +829,77 @@ impl AgentOrchestrator {
             }
         }
 
+        self.check_secret_refs(&config).await?;
         self.stores
             .agent_store
             .register(config)
             .await
-            .map_err(|e| anyhow::anyhow!(e))
+            .map_err(|e| anyhow::anyhow!(e))?;
+        self.notify_agents_changed();
+        Ok(())
+    }
+
+    /// Reject a config that references (`{{secret:NAME}}`) secrets outside
+    /// the fields that may hold them, or secrets which are not configured,
+    /// naming all of them.
+    async fn check_secret_refs(&self, config: &AgentConfig) -> anyhow::Result<()> {
+        use distri_types::secret_ref::{secret_refs_outside, AGENT_SECRET_FIELDS};
+
+        let outside = secret_refs_outside(&serde_json::to_value(config)?, AGENT_SECRET_FIELDS);
+        if !outside.is_empty() {
+            anyhow::bail!(
+                "agent '{}': {}",
+                config.get_name(),
+                crate::secrets::SecretResolver::format_misplaced_refs_error(
+                    &outside,
+                    AGENT_SECRET_FIELDS
+                )
+            );
+        }
+        let missing = crate::secrets::SecretResolver::new(self.stores.secret_store.clone())
+            .missing_secret_refs(config)
+            .await;
+        if !missing.is_empty() {
+            anyhow::bail!(
+                "agent '{}' references secrets that are not configured: {}",
+                config.get_name(),
+                missing.join(", ")
+            );
+        }
+        Ok(())
+    }
+
+    /// Tell the registry publisher the set of agents or their cards changed.
+    /// Callers that write to the agent store directly call this afterwards.
+    pub fn notify_agents_changed(&self) {
+        self.agents_changed.notify_one();
+    }
+
+    /// Register user-defined slash commands. A command with the same name
+    /// as an already-registered one replaces it.
+    pub async fn register_slash_commands(
+        &self,
+        commands: Vec<distri_types::channel_commands::SlashCommand>,
+    ) {
+        let mut registered = self.slash_commands.write().await;
+        for command in commands {
+            tracing::debug!("Registering slash command: {}", command.name);
+            registered.retain(|c| c.name != command.name);
+            registered.push(command);
+        }
+    }
+
+    /// Register a transformer for agents' `post_process` chains. Replaces
+    /// any transformer already registered under `name`.
+    pub async fn register_response_transformer(
+        &self,
+        name: impl Into<String>,
+        transformer: Arc<dyn crate::agent::post_process::ResponseTransformer>,
+    ) {
+        self.response_transformers
+            .write()
+            .await
+            .insert(name.into(), transformer);
     }
 
     pub async fn register_tool(&self, agent_id: &str, tool: Arc<dyn Tool>) {
@@ -463,6 +908,76 @@ impl AgentOrchestrator {
             .entry(agent_id.to_string())
             .or_insert(vec![])
             .push(tool);
+        self.invalidate_warm_sessions();
+    }
+
+    /// Register every tool of a plugin for `agent_id`. The tools run limited
+    /// to the plugin's capability manifest.
+    pub async fn register_integration(
+        &self,
+        agent_id: &str,
+        integration: &dyn distri_types::integration::Integration,
+    ) {
+        for tool in distri_types::integration::IntegrationTool::from_integration(integration) {
+            self.register_tool(agent_id, tool).await;
+        }
+    }
+
+    /// The capabilities granted to each registered plugin, by plugin name.
+    pub async fn plugin_grants(&self) -> Vec<distri_types::PluginGrant> {
+        use distri_types::PluginGrant;
+
+        let additional_tools = self.additional_tools.read().await;
+        let mut grants: std::collections::BTreeMap<String, PluginGrant> = Default::default();
+        for (agent, tools) in additional_tools.iter() {
+            for tool in tools {
+                let Some(plugin) = tool.get_plugin_name() else {
+                    continue;
+                };
+                let grant = grants.entry(plugin.clone()).or_insert_with(|| PluginGrant {
+                    plugin,
+                    agents: Vec::new(),
+                    tools: Vec::new(),
+                    capabilities: tool.get_plugin_capabilities().unwrap_or_default(),
+                });
+                if !grant.agents.contains(agent) {
+                    grant.agents.push(agent.clone());
+                }
+                let name = tool.get_name();
+                if !grant.tools.contains(&name) {
+                    grant.tools.push(name);
+                }
+            }
+        }
+        grants
+            .into_values()
+            .map(|mut grant| {
+                grant.agents.sort();
+                grant.tools.sort();
+                grant
+            })
+            .collect()
+    }
+
+    /// The UI renderer manifests of the registered tools, by tool name.
+    pub async fn tool_renderers(&self) -> Vec<distri_types::renderer_manifest::ToolRenderer> {
+        use distri_types::renderer_manifest::ToolRenderer;
+
+        let additional_tools = self.additional_tools.read().await;
+        let mut renderers: std::collections::BTreeMap<String, ToolRenderer> = Default::default();
+        for tool in additional_tools.values().flatten() {
+            let Some(mut manifest) = tool.get_ui_renderer() else {
+                continue;
+            };
+            manifest.tool = tool.get_name();
+            renderers
+                .entry(manifest.tool.clone())
+                .or_insert_with(|| ToolRenderer {
+                    plugin: tool.get_plugin_name(),
+                    manifest,
+                });
+        }
+        renderers.into_values().collect()
     }
 
     /// Get the prompt registry for registering/accessing prompt templates
@@ -488,23 +1003,119 @@ impl AgentOrchestrator {
         &self,
         name: &str,
     ) -> Option<crate::agent::prompt_registry::PromptTemplate> {
+        self.get_localized_prompt_template(name, None).await
+    }
+
+    /// Get the variant of a prompt template to render in `locale`: the most
+    /// specific `name@locale` translation, else the default template. At the
+    /// same specificity a stored template wins over the registry's.
+    pub async fn get_localized_prompt_template(
+        &self,
+        name: &str,
+        locale: Option<&str>,
+    ) -> Option<crate::agent::prompt_registry::PromptTemplate> {
+        use distri_types::prompt_locale::variant_rank;
+
         // Try the store first
+        let stored = match &self.stores.prompt_template_store {
+            Some(store) => store
+                .list()
+                .await
+                .ok()
+                .into_iter()
+                .flatten()
+                .filter_map(|t| variant_rank(&t.name, name, locale).map(|rank| (rank, t)))
+                .min_by_key(|(rank, _)| *rank),
+            None => None,
+        };
+        let registered = self
+            .prompt_registry
+            .get_localized_template(name, locale)
+            .await
+            .and_then(|t| variant_rank(&t.name, name, locale).map(|rank| (rank, t)));
+
+        if let Some((stored_rank, record)) = stored {
+            if registered
+                .as_ref()
+                .is_none_or(|(rank, _)| stored_rank <= *rank)
+            {
+                return Some(crate::agent::prompt_registry::PromptTemplate {
+                    name: record.name,
+                    content: record.template,
+                    description: record.description,
+                    version: record.version,
+                });
+            }
+        }
+
+        // Fallback to registry
+        registered.map(|(_, template)| template)
+    }
+
+    /// Which locales each prompt template, stored or registered, is
+    /// translated into.
+    pub async fn prompt_translation_coverage(
+        &self,
+    ) -> Vec<distri_types::prompt_locale::TemplateCoverage> {
+        let mut names: std::collections::BTreeSet<String> = self
+            .prompt_registry
+            .list_templates()
+            .await
+            .into_iter()
+            .collect();
         if let Some(store) = &self.stores.prompt_template_store {
-            // Find by name
             if let Ok(templates) = store.list().await {
-                if let Some(record) = templates.into_iter().find(|t| t.name == name) {
-                    return Some(crate::agent::prompt_registry::PromptTemplate {
-                        name: record.name,
-                        content: record.template,
-                        description: record.description,
-                        version: record.version,
-                    });
-                }
+                names.extend(templates.into_iter().map(|t| t.name));
             }
         }
+        distri_types::prompt_locale::translation_coverage(names.iter().map(String::as_str))
+    }
 
-        // Fallback to registry
-        self.prompt_registry.get_template(name).await
+    /// The stored profile of `user_id`; empty when none is stored.
+    pub async fn get_user_profile(&self, user_id: &str) -> Result<UserProfile, AgentError> {
+        let value = self
+            .stores
+            .session_store
+            .get_value(&profile_namespace(user_id), PROFILE_KEY)
+            .await
+            .map_err(|e| AgentError::Session(format!("Failed to load user profile: {}", e)))?;
+        match value {
+            Some(value) => serde_json::from_value(value)
+                .map_err(|e| AgentError::Parsing(format!("Invalid stored user profile: {}", e))),
+            None => Ok(UserProfile::default()),
+        }
+    }
+
+    /// Replace the profile of `user_id`. An empty profile removes it.
+    pub async fn set_user_profile(
+        &self,
+        user_id: &str,
+        profile: UserProfile,
+    ) -> Result<UserProfile, AgentError> {
+        let profile = profile.validated().map_err(AgentError::Validation)?;
+        let store = &self.stores.session_store;
+        let namespace = profile_namespace(user_id);
+        let saved = if profile.is_empty() {
+            store.delete_value(&namespace, PROFILE_KEY).await
+        } else {
+            store
+                .set_value(&namespace, PROFILE_KEY, &serde_json::to_value(&profile)?)
+                .await
+        };
+        saved.map_err(|e| AgentError::Session(format!("Failed to save user profile: {}", e)))?;
+        Ok(profile)
+    }
+
+    /// Change the preferences `patch` sets in the profile of `user_id`; an
+    /// empty string clears one.
+    pub async fn update_user_profile(
+        &self,
+        user_id: &str,
+        patch: UserProfile,
+    ) -> Result<UserProfile, AgentError> {
+        let mut profile = self.get_user_profile(user_id).await?;
+        profile.merge(patch);
+        self.set_user_profile(user_id, profile).await
     }
 
     /// Register a prompt partial dynamically
@@ -562,6 +1173,7 @@ impl AgentOrchestrator {
             let tools = self.additional_tools.read().await;
             tools.get(&definition.name).unwrap_or(&vec![]).clone()
         };
+        let registered = tools.len()..tools.len() + additional_tools.len();
         tools.extend(additional_tools.iter().cloned());
 
         // Add TodosDelegateTool if todos are enabled for this agent
@@ -644,11 +1256,111 @@ impl AgentOrchestrator {
             }
         }
 
+        // Keep one tool per name and add the definition's aliases.
+        let entries = tools
+            .into_iter()
+            .enumerate()
+            .map(|(index, tool)| {
+                let fallback = if registered.contains(&index) {
+                    ToolSourceKind::Plugin
+                } else {
+                    ToolSourceKind::Builtin
+                };
+                let candidate = crate::tools::catalog::tool_candidate(&tool, fallback);
+                (tool, candidate)
+            })
+            .collect();
+        let (tools, catalog) = crate::tools::catalog::resolve_catalog(
+            entries,
+            &tools_config.collisions,
+            &definition.tool_aliases,
+        )
+        .map_err(|e| {
+            AgentError::InvalidConfiguration(format!("Agent '{}': {}", definition.name, e))
+        })?;
+        resolved
+            .deferred_tools
+            .retain(|d| catalog.tools.iter().any(|c| c.name == d.name));
+        resolved
+            .full_schema_tools
+            .retain(|t| tools.iter().any(|kept| Arc::ptr_eq(kept, t)));
+
         resolved.all_tools = tools;
+        resolved.catalog = catalog;
         Ok(resolved)
     }
 
+    /// Which tool of `agent_id` a call to `name` runs. See
+    /// [`distri_types::tool_catalog`].
+    pub async fn resolve_agent_tool(
+        self: &Arc<Self>,
+        agent_id: &str,
+        name: &str,
+    ) -> Result<ToolResolution, AgentError> {
+        let definition = match self.get_agent(agent_id).await {
+            Some(AgentConfig::StandardAgent(definition)) => definition,
+            Some(_) => {
+                return Err(AgentError::Validation(format!(
+                    "Agent '{}' is a workflow agent and has no tools",
+                    agent_id
+                )))
+            }
+            None => return Err(AgentError::NotFound(format!("Agent '{}'", agent_id))),
+        };
+        let context = ExecutorContext {
+            agent_id: agent_id.to_string(),
+            orchestrator: Some(self.clone()),
+            ..Default::default()
+        };
+        let mcp_pool = self.resolve_mcp_pool(&context).await;
+        let resolved = self
+            .get_agent_tools_with_pool(&definition, &[], mcp_pool)
+            .await?;
+        Ok(resolved.catalog.resolve(name))
+    }
+
     /// Create an agent instance from a config using the factory
+    /// The run's tool registry: kept from the thread's previous message when
+    /// warm sessions are on and nothing it was built from changed, else
+    /// assembled (and kept for the next one).
+    async fn resolve_run_tools(
+        &self,
+        definition: &crate::types::StandardDefinition,
+        external_tools: &[Arc<dyn Tool>],
+        context: &ExecutorContext,
+    ) -> Result<crate::tools::ResolvedTools, AgentError> {
+        let warm = self
+            .warm_sessions
+            .as_ref()
+            .filter(|_| context.parent_task_id.is_none())
+            .map(|warm| {
+                let fingerprint =
+                    crate::agent::warm_sessions::fingerprint(definition, external_tools);
+                (warm, fingerprint)
+            });
+        if let Some((warm, fingerprint)) = warm {
+            if let Some(resolved) = warm.get(&context.thread_id, fingerprint) {
+                return Ok(resolved);
+            }
+        }
+
+        let started = std::time::Instant::now();
+        let mcp_pool = self.resolve_mcp_pool(context).await;
+        let resolved = self
+            .get_agent_tools_with_pool(definition, external_tools, mcp_pool)
+            .await?;
+        if let Some((warm, fingerprint)) = warm {
+            warm.insert(
+                &context.thread_id,
+                &definition.name,
+                fingerprint,
+                resolved.clone(),
+                started.elapsed(),
+            );
+        }
+        Ok(resolved)
+    }
+
     pub async fn create_agent_from_config(
         &self,
         config: distri_types::configuration::AgentConfig,
@@ -670,9 +1382,8 @@ impl AgentOrchestrator {
                 // the *single* source of truth for which MCP servers a run
                 // sees. Standalone hosts that don't attach a provider get
                 // `None` and the static `[[tools.mcp]]` registry still works.
-                let mcp_pool = self.resolve_mcp_pool(&context).await;
                 let resolved = self
-                    .get_agent_tools_with_pool(&definition, &external_tools, mcp_pool)
+                    .resolve_run_tools(&definition, &external_tools, &context)
                     .await?;
                 let deferred_names: std::collections::HashSet<String> = resolved
                     .deferred_tools
@@ -1015,11 +1726,14 @@ impl AgentOrchestrator {
     ) -> anyhow::Result<()> {
         let agent_config =
             distri_types::configuration::AgentConfig::StandardAgent(definition.clone());
+        self.check_secret_refs(&agent_config).await?;
         self.stores
             .agent_store
             .update(agent_config)
             .await
-            .map_err(|e| anyhow::anyhow!(e))
+            .map_err(|e| anyhow::anyhow!(e))?;
+        self.notify_agents_changed();
+        Ok(())
     }
 
     async fn call_agent(
@@ -1041,13 +1755,16 @@ impl AgentOrchestrator {
             &context.default_model_settings,
         );
         self.hydrate_agent_model_settings(&mut agent_config).await?;
+        self.resolve_agent_secret_refs(&mut agent_config).await?;
         Self::validate_agent_model(&agent_config)?;
+        self.apply_model_capabilities(&mut agent_config).await;
 
         let declared_definition = match &agent_config {
             distri_types::configuration::AgentConfig::StandardAgent(def) => Some(def.clone()),
             _ => None,
         };
 
+        let context = with_tool_config(context, &agent_config);
         let agent = self
             .create_agent_from_config(agent_config, context.clone())
             .await?;
@@ -1056,8 +1773,11 @@ impl AgentOrchestrator {
         let handle = tokio::spawn(async move { while let Some(_) = rx.recv().await {} });
         let result = agent
             .invoke_stream(message, Arc::new(context_with_tx))
-            .await?;
-        let _ = handle.await;
+            .await;
+        // Clones of the run's context can outlive it and keep the channel
+        // open, so the drain stops with the run instead of waiting for it.
+        handle.abort();
+        let result = result?;
 
         if let Some(def) = declared_definition {
             warn_unused_connections(&def, &context).await;
@@ -1086,6 +1806,8 @@ impl AgentOrchestrator {
             &context.default_model_settings,
         );
         self.hydrate_agent_model_settings(&mut agent_config).await?;
+        self.resolve_agent_secret_refs(&mut agent_config).await?;
+        self.apply_model_capabilities(&mut agent_config).await;
 
         // Runtime-constraint dispatch decision. Single source of truth
         // lives in `crate::agent::invoke::decide_dispatch` — both this
@@ -1159,6 +1881,7 @@ impl AgentOrchestrator {
             "Creating agent from config: {:?}",
             std::mem::discriminant(&agent_config)
         );
+        let context = with_tool_config(context, &agent_config);
         let agent: Box<dyn BaseAgent> = self
             .create_agent_from_config(agent_config, context.clone())
             .await?;
@@ -1231,6 +1954,7 @@ impl AgentOrchestrator {
         // in this thread. Never persisted to the DB; clients use it to decide
         // whether to resubscribe on thread reopen.
         if let Some(mut t) = thread {
+            self.ensure_thread_restored(&mut t).await?;
             if let Ok(tasks) = self.stores.task_store.list_tasks(Some(thread_id)).await {
                 t.active_task_id = tasks
                     .into_iter()
@@ -1329,13 +2053,15 @@ impl AgentOrchestrator {
         };
 
         match thread {
-            Some(existing) => {
+            Some(mut existing) => {
+                self.ensure_thread_restored(&mut existing).await?;
                 if let Some(attrs) = attributes {
                     let update_req = crate::types::UpdateThreadRequest {
                         title: None,
                         metadata: None,
                         attributes: Some(attrs),
                         user_id: None,
+                        variables: None,
                     };
                     let updated = thread_store
                         .update_thread(&existing.id, update_req)
@@ -1392,6 +2118,10 @@ impl AgentOrchestrator {
     ) -> Result<InvokeResult, AgentError> {
         // Prepare context with ephemeral stores if needed
         let context = self.prepare_execution_context(context).await?;
+        let _thread_run = self
+            .hibernation
+            .is_some()
+            .then(|| self.thread_resources.enter(&context.thread_id));
 
         // Use context stores if provided, otherwise use orchestrator stores
         let stores = context.stores.as_ref().unwrap_or(&self.stores);
@@ -1575,6 +2305,10 @@ impl AgentOrchestrator {
     ) -> Result<InvokeResult, AgentError> {
         // Prepare context with ephemeral stores if needed
         let context = self.prepare_execution_context(context).await?;
+        let _thread_run = self
+            .hibernation
+            .is_some()
+            .then(|| self.thread_resources.enter(&context.thread_id));
 
         // Use context stores if provided, otherwise use orchestrator stores
         let stores = context.stores.as_ref().unwrap_or(&self.stores);
@@ -1621,11 +2355,30 @@ impl AgentOrchestrator {
         };
 
         self.validate_user_message(&message)?;
+        let user_text = message.as_text();
 
         let res = self
             .call_agent_stream(agent_name, message, context.clone(), definition_overrides)
             .await?;
 
+        // Only top-level runs name the thread; sub-agents share it.
+        if context.parent_task_id.is_none() {
+            if let (Some(user_text), Some(content), Some(AgentConfig::StandardAgent(def))) = (
+                user_text,
+                res.content.clone(),
+                self.get_agent(agent_name).await,
+            ) {
+                let stores = context.stores.as_ref().unwrap_or(&self.stores);
+                crate::agent::thread_title::spawn_auto_title(
+                    &def,
+                    stores.thread_store.clone(),
+                    &context,
+                    user_text,
+                    content,
+                );
+            }
+        }
+
         Ok(res)
     }
 
@@ -1712,6 +2465,47 @@ impl AgentOrchestrator {
         Ok(())
     }
 
+    /// Replace the `{{secret:NAME}}` references in an agent config with the
+    /// secrets' values. Only the per-run copy is resolved; the stored
+    /// definition keeps the references. Fails on references outside
+    /// [`AGENT_SECRET_FIELDS`](distri_types::secret_ref::AGENT_SECRET_FIELDS).
+    pub async fn resolve_agent_secret_refs(
+        &self,
+        agent_config: &mut distri_types::configuration::AgentConfig,
+    ) -> Result<(), AgentError> {
+        crate::secrets::SecretResolver::new(self.stores.secret_store.clone())
+            .resolve_secret_refs_in(agent_config, distri_types::secret_ref::AGENT_SECRET_FIELDS)
+            .await
+    }
+
+    /// Adapt an agent with native tool calls to its model's probed
+    /// capabilities, probing the model on first use. No-op without
+    /// `capability_probe`.
+    pub async fn apply_model_capabilities(
+        &self,
+        agent_config: &mut distri_types::configuration::AgentConfig,
+    ) {
+        let Some(prober) = &self.capability_prober else {
+            return;
+        };
+        let definition = match agent_config {
+            distri_types::configuration::AgentConfig::StandardAgent(def) => def,
+            distri_types::configuration::AgentConfig::WorkflowAgent(_) => return,
+        };
+        if definition.tool_format != distri_types::ToolCallFormat::Provider {
+            return;
+        }
+        let Some(settings) = definition.model_settings.clone() else {
+            return;
+        };
+        if let Some(capabilities) = prober
+            .capabilities(&settings, self.llm_executor_factory.clone())
+            .await
+        {
+            crate::agent::capability_probe::adapt_to_capabilities(definition, &capabilities);
+        }
+    }
+
     pub fn apply_agent_overrides(
         agent_config: &mut distri_types::configuration::AgentConfig,
         definition_overrides: Option<DefinitionOverrides>,
@@ -1737,6 +2531,17 @@ impl AgentOrchestrator {
             (None, None) => None,
         };
         definition.model_settings = merged;
+        // A declared response schema applies unless the model settings
+        // already carry a response format.
+        if let (Some(ms), Some(format)) = (
+            definition.model_settings.as_mut(),
+            definition
+                .response_schema
+                .as_ref()
+                .and_then(|schema| schema.response_format(&definition.name)),
+        ) {
+            ms.inner.response_format.get_or_insert(format);
+        }
 
         let default_analysis_settings = default_model_settings.clone();
         definition.analysis_model_settings = match (
@@ -2037,6 +2842,122 @@ impl AgentOrchestrator {
         Ok(())
     }
 
+    /// Re-run a finished workflow run from `from_step`: that step and
+    /// every step downstream of it run again against the agent's current
+    /// workflow definition, reusing the stored outputs of the steps
+    /// upstream of it. Refused while the run is still in progress, and
+    /// when an upstream step's definition changed since the run (see
+    /// `WorkflowRun::rerun_from`).
+    ///
+    /// Resets the step rows, replaces the definition snapshot, flips the
+    /// run task `Running` and spawns the re-drive like a wait completion.
+    /// Returns the steps that run again, in definition order.
+    pub async fn rerun_workflow(
+        self: Arc<Self>,
+        run_task_id: &str,
+        from_step: &str,
+    ) -> Result<Vec<String>, AgentError> {
+        let workflow_store = self.workflow_store.clone().ok_or_else(|| {
+            AgentError::InvalidConfiguration("workflow_store not configured".to_string())
+        })?;
+        let state = workflow_store
+            .get_run(run_task_id)
+            .await
+            .map_err(|e| AgentError::Storage(e.to_string()))?
+            .ok_or_else(|| AgentError::NotFound(format!("no workflow run {run_task_id}")))?;
+
+        if let Ok(Some(task)) = self.stores.task_store.get_task(run_task_id).await {
+            if matches!(
+                task.status,
+                distri_types::TaskStatus::Pending
+                    | distri_types::TaskStatus::Running
+                    | distri_types::TaskStatus::InputRequired
+            ) {
+                return Err(AgentError::Validation(format!(
+                    "workflow run {run_task_id} is still in progress ({:?})",
+                    task.status
+                )));
+            }
+        }
+
+        let definition = match self.get_agent(&state.agent_id).await {
+            Some(AgentConfig::WorkflowAgent(agent)) => serde_json::from_value::<
+                distri_workflow::WorkflowDefinition,
+            >(agent.definition)
+            .map_err(|e| {
+                AgentError::InvalidConfiguration(format!("Invalid workflow definition: {e}"))
+            })?,
+            Some(_) => {
+                return Err(AgentError::Validation(format!(
+                    "agent '{}' is not a workflow",
+                    state.agent_id
+                )))
+            }
+            None => return Err(AgentError::AgentNotFound(state.agent_id)),
+        };
+
+        let step_states = workflow_store
+            .list_steps(run_task_id)
+            .await
+            .map_err(|e| AgentError::Storage(e.to_string()))?;
+        let run = crate::agent::workflow_agent::hydrate_run(state.clone(), step_states)
+            .rerun_from(definition, from_step)
+            .map_err(AgentError::Validation)?;
+        let reset = run.definition.reachable_from(from_step);
+        let rerun: Vec<String> = run
+            .steps()
+            .iter()
+            .filter(|step| reset.contains(&step.id))
+            .map(|step| step.id.clone())
+            .collect();
+
+        workflow_store
+            .create_run(distri_workflow::WorkflowExecutionState {
+                definition: run.definition.clone(),
+                context: run.context.clone(),
+                updated_at: chrono::Utc::now(),
+                ..state
+            })
+            .await
+            .map_err(|e| AgentError::Storage(e.to_string()))?;
+        for step_id in &rerun {
+            workflow_store
+                .upsert_step(
+                    run_task_id,
+                    distri_workflow::WorkflowStepState {
+                        step_id: step_id.clone(),
+                        ..Default::default()
+                    },
+                )
+                .await
+                .map_err(|e| AgentError::Storage(e.to_string()))?;
+        }
+
+        self.stores
+            .task_store
+            .update_task_status(run_task_id, distri_types::TaskStatus::Running)
+            .await
+            .map_err(|e| AgentError::Storage(e.to_string()))?;
+
+        tracing::info!(
+            target: "workflow.rerun",
+            run_task_id = %run_task_id,
+            from_step = %from_step,
+            steps = rerun.len(),
+            "workflow re-run prepared; spawning re-drive"
+        );
+
+        let self_for_spawn = self.clone();
+        let run_task_id_owned = run_task_id.to_string();
+        tokio::spawn(async move {
+            if let Err(e) = self_for_spawn.resume_workflow_run(run_task_id_owned).await {
+                tracing::warn!(error = %e, "workflow re-run re-drive failed");
+            }
+        });
+
+        Ok(rerun)
+    }
+
     /// Spawn a background execution that re-enters a parked workflow
     /// run. Reads `thread_id` / `user_id` / `workspace_id` from the
     /// snapshotted `WorkflowExecutionState`, builds a minimal
@@ -2138,7 +3059,39 @@ impl AgentOrchestrator {
         let tool = self
             .find_tool_by_name(&tools, &tool_call.tool_name)
             .ok_or_else(|| anyhow::anyhow!("Tool {} not found", tool_call.tool_name))?;
+        Self::execute_tool_call(tool, tool_call, context).await
+    }
 
+    /// Like [`Self::call_tool_with_context`], but only among the tools
+    /// `definition` is given, the way a run of the agent resolves them.
+    pub async fn call_agent_tool_with_context(
+        self: &Arc<Self>,
+        definition: &crate::types::StandardDefinition,
+        tool_call: &ToolCall,
+        context: Arc<ExecutorContext>,
+    ) -> anyhow::Result<serde_json::Value> {
+        let mcp_pool = self.resolve_mcp_pool(&context).await;
+        let resolved = self
+            .get_agent_tools_with_pool(definition, &[], mcp_pool)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to resolve the agent's tools: {}", e))?;
+        let tool = self
+            .find_tool_by_name(&resolved.all_tools, &tool_call.tool_name)
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Agent {} has no tool {}",
+                    definition.name,
+                    tool_call.tool_name
+                )
+            })?;
+        Self::execute_tool_call(tool, tool_call, context).await
+    }
+
+    async fn execute_tool_call(
+        tool: Arc<dyn crate::tools::Tool>,
+        tool_call: &ToolCall,
+        context: Arc<ExecutorContext>,
+    ) -> anyhow::Result<serde_json::Value> {
         let result = if tool.needs_executor_context() {
             crate::tools::execute_tool_with_executor_context(
                 tool.as_ref(),
@@ -2148,7 +3101,7 @@ impl AgentOrchestrator {
             .await
             .map_err(|e| anyhow::anyhow!("Failed to execute tool: {}", e))?
         } else {
-            let tool_context = crate::tools::context::to_tool_context(&context);
+            let tool_context = crate::tools::context::to_tool_context_for(&context, tool.as_ref());
             tool.execute(tool_call.clone(), Arc::new(tool_context))
                 .await?
         };
@@ -2668,6 +3621,43 @@ async fn resolve_declared_connections(
     Ok(())
 }
 
+/// `context` with the agent's `tools.config` added to its tool metadata.
+/// Settings the request sent for the same tool win key by key.
+fn with_tool_config(
+    context: Arc<ExecutorContext>,
+    agent_config: &AgentConfig,
+) -> Arc<ExecutorContext> {
+    let AgentConfig::StandardAgent(definition) = agent_config else {
+        return context;
+    };
+    let Some(config) = definition
+        .tools
+        .as_ref()
+        .map(|tools| &tools.config)
+        .filter(|config| !config.is_empty())
+    else {
+        return context;
+    };
+    let mut metadata = context.tool_metadata.clone().unwrap_or_default();
+    for (tool, settings) in config {
+        let merged = match metadata.remove(tool) {
+            Some(serde_json::Value::Object(request)) => {
+                let mut merged = settings.clone();
+                if let serde_json::Value::Object(map) = &mut merged {
+                    map.extend(request);
+                }
+                merged
+            }
+            Some(request) => request,
+            None => settings.clone(),
+        };
+        metadata.insert(tool.clone(), merged);
+    }
+    let mut context = (*context).clone();
+    context.tool_metadata = Some(metadata);
+    Arc::new(context)
+}
+
 /// After a run finishes, warn when an agent declared `connections: [...]` but
 /// none of them were resolved (no `inject_connection_env`, no `x-connection-id`,
 /// no proxy hit). Helps catch definitions that carry unused connections.
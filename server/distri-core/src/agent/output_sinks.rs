@@ -0,0 +1,160 @@
+//! Delivery of the final answer to the agent's `output_sinks` (see
+//! [`distri_types::output_sinks`]).
+//!
+//! Runs after `RunFinished` for successful runs with a text answer. Sinks are
+//! delivered in order and each one reports an `OutputSinkFinished` event; a
+//! failing sink is logged and never fails the run.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{anyhow, Context, Result};
+use base64::{engine::general_purpose, Engine as _};
+use distri_types::output_sinks::{OutputPayload, OutputSinkConfig, OutputSinkKind};
+use distri_types::AgentEventType;
+use serde_json::{json, Value};
+
+use crate::agent::ExecutorContext;
+use crate::connections::provider_http_headers;
+
+const SINK_TIMEOUT: Duration = Duration::from_secs(30);
+const GMAIL_SEND_URL: &str = "https://gmail.googleapis.com/gmail/v1/users/me/messages/send";
+
+/// Deliver `content` to every sink of `sinks`, in order.
+pub async fn deliver_outputs(
+    sinks: &[OutputSinkConfig],
+    content: &str,
+    context: Arc<ExecutorContext>,
+) {
+    let payload = OutputPayload {
+        agent: context.agent_id.clone(),
+        thread_id: context.thread_id.clone(),
+        task_id: context.task_id.clone(),
+        content: content.to_string(),
+    };
+    for sink in sinks {
+        let label = sink.label();
+        let result = tokio::time::timeout(SINK_TIMEOUT, deliver(&sink.kind, &payload, &context))
+            .await
+            .unwrap_or_else(|_| Err(anyhow!("timed out after {}s", SINK_TIMEOUT.as_secs())));
+        let error = result.err().map(|e| format!("{e:#}"));
+        if let Some(error) = &error {
+            tracing::warn!(
+                agent = %context.agent_id,
+                sink = %label,
+                error = %error,
+                "Output sink failed"
+            );
+        }
+        context
+            .emit(AgentEventType::OutputSinkFinished {
+                sink: label,
+                success: error.is_none(),
+                error,
+            })
+            .await;
+    }
+}
+
+async fn deliver(
+    kind: &OutputSinkKind,
+    payload: &OutputPayload,
+    context: &ExecutorContext,
+) -> Result<()> {
+    match kind {
+        OutputSinkKind::Webhook { url, headers } => {
+            let mut request = reqwest::Client::new().post(url).json(payload);
+            for (name, value) in headers {
+                request = request.header(name, value);
+            }
+            request.send().await?.error_for_status()?;
+        }
+        OutputSinkKind::Artifact { filename } => {
+            let orchestrator = context.get_orchestrator()?;
+            let wrapper = orchestrator
+                .session_filesystem
+                .create_artifact_wrapper(distri_filesystem::ArtifactWrapper::thread_namespace(
+                    &payload.thread_id,
+                ))
+                .await?;
+            let mut text = wrapper
+                .read_artifact_raw(filename)
+                .await
+                .unwrap_or_default();
+            if !text.is_empty() {
+                text.push_str("\n\n");
+            }
+            text.push_str(&payload.content);
+            wrapper.save_artifact(filename, &text).await?;
+        }
+        OutputSinkKind::Email {
+            to,
+            subject,
+            provider,
+        } => {
+            if to.is_empty() {
+                return Err(anyhow!("no recipients"));
+            }
+            let message = email_message(to, &payload.render(subject), &payload.content);
+            let raw = general_purpose::URL_SAFE_NO_PAD.encode(message);
+            post_with_connection(context, provider, GMAIL_SEND_URL, &json!({ "raw": raw })).await?;
+        }
+        OutputSinkKind::Document {
+            provider,
+            url,
+            body,
+        } => {
+            let body = body.clone().unwrap_or_else(
+                || json!({ "title": "{{agent}} — {{task_id}}", "content": "{{content}}" }),
+            );
+            post_with_connection(context, provider, url, &payload.render_value(&body)).await?;
+        }
+    }
+    Ok(())
+}
+
+/// POST `body` with the headers of the run user's `provider` connection.
+async fn post_with_connection(
+    context: &ExecutorContext,
+    provider: &str,
+    url: &str,
+    body: &Value,
+) -> Result<()> {
+    let headers = provider_http_headers(context, provider)
+        .await
+        .with_context(|| format!("resolving the '{}' connection", provider))?;
+    let mut request = reqwest::Client::new().post(url).json(body);
+    for (name, value) in &headers {
+        request = request.header(name, value);
+    }
+    request.send().await?.error_for_status()?;
+    Ok(())
+}
+
+/// Plain-text RFC 822 message; the subject is encoded so it may hold any
+/// UTF-8.
+fn email_message(to: &[String], subject: &str, body: &str) -> String {
+    format!(
+        "To: {}\r\nSubject: =?UTF-8?B?{}?=\r\nMIME-Version: 1.0\r\nContent-Type: text/plain; charset=\"UTF-8\"\r\n\r\n{}",
+        to.join(", "),
+        general_purpose::STANDARD.encode(subject),
+        body
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn email_subject_is_encoded() {
+        let message = email_message(
+            &["a@example.com".to_string(), "b@example.com".to_string()],
+            "Résumé",
+            "Hello",
+        );
+        assert!(message.starts_with("To: a@example.com, b@example.com\r\n"));
+        assert!(message.contains("Subject: =?UTF-8?B?UsOpc3Vtw6k=?=\r\n"));
+        assert!(message.ends_with("\r\n\r\nHello"));
+    }
+}
@@ -1,5 +1,9 @@
+use distri_types::packages::{
+    namespace_agents, PackageBuild, PackageInfo, PackageManifest, WorkspaceBuild, MANIFEST_FILE,
+};
 use distri_types::{parse_agent_markdown_content, AgentError};
-use std::path::Path;
+use std::collections::HashSet;
+use std::path::{Component, Path, PathBuf};
 use tokio::fs;
 
 pub async fn load_agents_from_dir<P: AsRef<Path>>(
@@ -61,13 +65,149 @@ pub async fn load_agents_from_dir<P: AsRef<Path>>(
             ))
         })?;
 
-        let definition = parse_agent_markdown_content(&contents).await?;
+        let mut definition = parse_agent_markdown_content(&contents).await?;
+        if let Some(response_schema) = definition.response_schema.as_mut() {
+            response_schema.inline_file(dir_path).map_err(|e| {
+                AgentError::InvalidConfiguration(format!("{}: {}", path.display(), e))
+            })?;
+        }
         agents.push(definition);
     }
 
     Ok(agents)
 }
 
+/// A member package of a workspace.
+#[derive(Debug, Clone)]
+pub struct WorkspacePackage {
+    pub info: PackageInfo,
+    /// Member directory, relative to the workspace root.
+    pub path: String,
+    pub dir: PathBuf,
+}
+
+async fn read_manifest(path: &Path) -> Result<Option<PackageManifest>, AgentError> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let contents = fs::read_to_string(path).await.map_err(|e| {
+        AgentError::InvalidConfiguration(format!("Failed to read {}: {}", path.display(), e))
+    })?;
+    PackageManifest::parse(&contents)
+        .map(Some)
+        .map_err(|e| AgentError::InvalidConfiguration(format!("{}: {}", path.display(), e)))
+}
+
+/// Member directories of `members`, expanding `dir/*` to the directories
+/// under `dir` that have a manifest, in name order.
+async fn member_paths(root: &Path, members: &[String]) -> Result<Vec<String>, AgentError> {
+    let mut paths = Vec::new();
+    for member in members {
+        let member = member.trim_end_matches('/');
+        let relative = Path::new(member);
+        if member.is_empty()
+            || !relative
+                .components()
+                .all(|c| matches!(c, Component::Normal(_)))
+        {
+            return Err(AgentError::InvalidConfiguration(format!(
+                "workspace member '{}' must be a directory inside the workspace",
+                member
+            )));
+        }
+        let Some(parent) = member.strip_suffix("/*") else {
+            paths.push(member.to_string());
+            continue;
+        };
+        let mut entries = fs::read_dir(root.join(parent)).await.map_err(|e| {
+            AgentError::InvalidConfiguration(format!(
+                "Failed to read workspace members '{}': {}",
+                member, e
+            ))
+        })?;
+        let mut expanded = Vec::new();
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            if entry.path().join(MANIFEST_FILE).exists() {
+                expanded.push(format!(
+                    "{}/{}",
+                    parent,
+                    entry.file_name().to_string_lossy()
+                ));
+            }
+        }
+        expanded.sort();
+        paths.extend(expanded);
+    }
+    Ok(paths)
+}
+
+/// Member packages of the workspace at `root`, in `members` order. Empty
+/// when the root has no workspace `distri.toml`.
+pub async fn load_workspace_packages(root: &Path) -> Result<Vec<WorkspacePackage>, AgentError> {
+    let Some(workspace) = read_manifest(&root.join(MANIFEST_FILE))
+        .await?
+        .and_then(|m| m.workspace)
+    else {
+        return Ok(Vec::new());
+    };
+
+    let mut packages = Vec::new();
+    let mut names = HashSet::new();
+    for path in member_paths(root, &workspace.members).await? {
+        let dir = root.join(&path);
+        let info = read_manifest(&dir.join(MANIFEST_FILE))
+            .await?
+            .and_then(|m| m.package)
+            .ok_or_else(|| {
+                AgentError::InvalidConfiguration(format!(
+                    "workspace member '{}' has no {} with a [package] table",
+                    path, MANIFEST_FILE
+                ))
+            })?;
+        info.validate().map_err(AgentError::InvalidConfiguration)?;
+        if !names.insert(info.name.clone()) {
+            return Err(AgentError::InvalidConfiguration(format!(
+                "package '{}' is declared by more than one workspace member",
+                info.name
+            )));
+        }
+        packages.push(WorkspacePackage { info, path, dir });
+    }
+    Ok(packages)
+}
+
+/// Agents of the workspace at `root`: those of its `agents/` directory and,
+/// namespaced by package, those of every member package (see
+/// [`distri_types::packages`]).
+pub async fn load_workspace(
+    root: &Path,
+) -> Result<(Vec<distri_types::StandardDefinition>, WorkspaceBuild), AgentError> {
+    let mut agents = load_agents_from_dir(root.join("agents")).await?;
+    let mut build = WorkspaceBuild {
+        agents: agents.iter().map(|a| a.name.clone()).collect(),
+        packages: Vec::new(),
+    };
+    for package in load_workspace_packages(root).await? {
+        let mut package_agents = load_agents_from_dir(package.dir.join("agents")).await?;
+        namespace_agents(&package.info, &mut package_agents)?;
+        build.packages.push(PackageBuild {
+            package: package.info,
+            path: package.path,
+            agents: package_agents.iter().map(|a| a.name.clone()).collect(),
+        });
+        agents.extend(package_agents);
+    }
+
+    let mut seen = HashSet::new();
+    if let Some(name) = build.agent_names().find(|name| !seen.insert(*name)) {
+        return Err(AgentError::InvalidConfiguration(format!(
+            "agent '{}' is defined more than once in the workspace",
+            name
+        )));
+    }
+    Ok((agents, build))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,246 @@
+//! Final-answer post-processing chain.
+//!
+//! Runs the agent's `post_process` list (see
+//! [`distri_types::post_process`]) over the text final result when the agent
+//! loop finishes. Built-in processors cover link rewriting, code block
+//! extraction into artifacts, and template wrapping; anything else is a
+//! [`ResponseTransformer`] registered on the orchestrator by name.
+//!
+//! A failing processor is logged and skipped — post-processing never turns a
+//! finished run into a failed one.
+
+use std::sync::{Arc, LazyLock};
+
+use base64::{engine::general_purpose, Engine as _};
+use distri_types::post_process::{LinkRewriteRule, PostProcessorConfig};
+use serde_json::Value;
+
+use crate::agent::ExecutorContext;
+use crate::AgentError;
+
+/// A named, host-provided transformer referenced from an agent definition as
+/// `{ type = "custom", name = "..." }`.
+#[async_trait::async_trait]
+pub trait ResponseTransformer: Send + Sync + std::fmt::Debug {
+    async fn transform(
+        &self,
+        response: String,
+        options: &Value,
+        context: Arc<ExecutorContext>,
+    ) -> Result<String, AgentError>;
+}
+
+/// Apply `processors` in order to `response`.
+pub async fn apply_post_processors(
+    processors: &[PostProcessorConfig],
+    response: String,
+    context: Arc<ExecutorContext>,
+) -> String {
+    let mut response = response;
+    for processor in processors {
+        match apply_one(processor, response.clone(), context.clone()).await {
+            Ok(next) => response = next,
+            Err(e) => tracing::warn!(
+                agent_id = %context.agent_id,
+                task_id = %context.task_id,
+                "post-processor {:?} failed, skipping: {}",
+                processor,
+                e
+            ),
+        }
+    }
+    response
+}
+
+async fn apply_one(
+    processor: &PostProcessorConfig,
+    response: String,
+    context: Arc<ExecutorContext>,
+) -> Result<String, AgentError> {
+    match processor {
+        PostProcessorConfig::RewriteLinks { rules } => Ok(rewrite_links(&response, rules)),
+        PostProcessorConfig::ExtractCodeBlocks {
+            languages,
+            min_lines,
+        } => extract_code_blocks(&response, languages, *min_lines, &context).await,
+        PostProcessorConfig::Wrap { template } => Ok(template
+            .replace("{{response}}", &response)
+            .replace("{{agent}}", &context.agent_id)),
+        PostProcessorConfig::Custom { name, options } => {
+            let transformer = context
+                .get_orchestrator()?
+                .response_transformers
+                .read()
+                .await
+                .get(name)
+                .cloned()
+                .ok_or_else(|| AgentError::NotFound(format!("response transformer '{}'", name)))?;
+            transformer.transform(response, options, context).await
+        }
+    }
+}
+
+/// `](target rest)` of a markdown link or image.
+static LINK_TARGET: LazyLock<regex::Regex> =
+    LazyLock::new(|| regex::Regex::new(r"\]\(([^)\s]+)([^)]*)\)").unwrap());
+
+/// Rewrite `](target)` link and image targets using the first matching rule.
+fn rewrite_links(text: &str, rules: &[LinkRewriteRule]) -> String {
+    LINK_TARGET
+        .replace_all(text, |caps: &regex::Captures| {
+            let target = &caps[1];
+            let rewritten = rules
+                .iter()
+                .find_map(|rule| rule.apply(target))
+                .unwrap_or_else(|| target.to_string());
+            format!("]({}{})", rewritten, &caps[2])
+        })
+        .into_owned()
+}
+
+/// A fenced code block located in the answer.
+#[derive(Debug, PartialEq)]
+struct CodeBlock {
+    /// Byte range of the whole fence, including the closing ``` line.
+    range: std::ops::Range<usize>,
+    language: String,
+    body: String,
+}
+
+/// Find top-level ``` fenced blocks. Unterminated fences are ignored.
+fn find_code_blocks(text: &str) -> Vec<CodeBlock> {
+    let mut blocks = Vec::new();
+    let mut open: Option<(usize, String, usize)> = None; // (fence start, language, body start)
+    let mut offset = 0;
+    for line in text.split_inclusive('\n') {
+        let trimmed = line.trim_end_matches(['\n', '\r']);
+        let line_end = offset + line.len();
+        match &open {
+            None => {
+                if let Some(info) = trimmed.strip_prefix("```") {
+                    let language = info.split_whitespace().next().unwrap_or("");
+                    open = Some((offset, language.to_string(), line_end));
+                }
+            }
+            Some((start, language, body_start)) => {
+                if trimmed.trim() == "```" {
+                    blocks.push(CodeBlock {
+                        range: *start..line_end,
+                        language: language.clone(),
+                        body: text[*body_start..offset].to_string(),
+                    });
+                    open = None;
+                }
+            }
+        }
+        offset = line_end;
+    }
+    blocks
+}
+
+async fn extract_code_blocks(
+    text: &str,
+    languages: &[String],
+    min_lines: usize,
+    context: &Arc<ExecutorContext>,
+) -> Result<String, AgentError> {
+    let blocks: Vec<CodeBlock> = find_code_blocks(text)
+        .into_iter()
+        .filter(|b| {
+            languages.is_empty()
+                || languages
+                    .iter()
+                    .any(|l| l.eq_ignore_ascii_case(&b.language))
+        })
+        .filter(|b| b.body.lines().count() >= min_lines)
+        .collect();
+    if blocks.is_empty() {
+        return Ok(text.to_string());
+    }
+
+    let orchestrator = context.get_orchestrator()?;
+    let wrapper = orchestrator
+        .session_filesystem
+        .create_artifact_wrapper(distri_filesystem::ArtifactWrapper::task_namespace(
+            &context.thread_id,
+            &context.task_id,
+        ))
+        .await
+        .map_err(|e| AgentError::Session(format!("Failed to open artifact store: {}", e)))?;
+
+    let mut out = String::with_capacity(text.len());
+    let mut cursor = 0;
+    for (n, block) in blocks.iter().enumerate() {
+        let filename = format!("snippet-{}.{}", n + 1, extension_for(&block.language));
+        let encoded = general_purpose::STANDARD.encode(block.body.as_bytes());
+        wrapper
+            .save_artifact(&filename, &encoded)
+            .await
+            .map_err(|e| AgentError::Session(format!("Failed to save {}: {}", filename, e)))?;
+        out.push_str(&text[cursor..block.range.start]);
+        out.push_str(&format!(
+            "[{}]({}/content/{})\n",
+            filename,
+            wrapper.prefix_path(),
+            filename
+        ));
+        cursor = block.range.end;
+    }
+    out.push_str(&text[cursor..]);
+    Ok(out)
+}
+
+fn extension_for(language: &str) -> &'static str {
+    match language.to_ascii_lowercase().as_str() {
+        "python" | "py" => "py",
+        "rust" | "rs" => "rs",
+        "javascript" | "js" => "js",
+        "typescript" | "ts" => "ts",
+        "bash" | "sh" | "shell" => "sh",
+        "sql" => "sql",
+        "json" => "json",
+        "yaml" | "yml" => "yaml",
+        "toml" => "toml",
+        "html" => "html",
+        "markdown" | "md" => "md",
+        _ => "txt",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rewrites_matching_links_and_keeps_titles() {
+        let rules = vec![LinkRewriteRule {
+            from: "/docs/".to_string(),
+            to: "https://distri.dev/docs/".to_string(),
+        }];
+        let text = "See [agents](/docs/agents \"Agents\") and ![logo](/img/logo.png).";
+        assert_eq!(
+            rewrite_links(text, &rules),
+            "See [agents](https://distri.dev/docs/agents \"Agents\") and ![logo](/img/logo.png)."
+        );
+    }
+
+    #[test]
+    fn finds_fenced_blocks_with_language() {
+        let text = "Intro\n```python\nprint(1)\nprint(2)\n```\nmiddle\n```\nraw\n```\ntail ```open";
+        let blocks = find_code_blocks(text);
+        assert_eq!(blocks.len(), 2);
+        assert_eq!(blocks[0].language, "python");
+        assert_eq!(blocks[0].body, "print(1)\nprint(2)\n");
+        assert_eq!(
+            &text[blocks[0].range.clone()],
+            "```python\nprint(1)\nprint(2)\n```\n"
+        );
+        assert_eq!(blocks[1].language, "");
+        assert_eq!(blocks[1].body, "raw\n");
+    }
+
+    #[test]
+    fn unterminated_fence_is_left_alone() {
+        assert!(find_code_blocks("```rust\nfn main() {}\n").is_empty());
+    }
+}
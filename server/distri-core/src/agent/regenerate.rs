@@ -0,0 +1,115 @@
+//! "Regenerate from here".
+//!
+//! The task holding the chosen message and every later task of the thread
+//! are soft-archived — hidden from the history the agent and the UI see,
+//! but kept in the store. The task's user message (optionally edited) is
+//! then run again as a new task that references the one it replaces.
+
+use std::sync::Arc;
+
+use distri_types::regenerate::{RegenerateRequest, Regeneration};
+use distri_types::stores::CreateTaskInput;
+use distri_types::{Message, MessageRole, ModelSettings, Part, TaskMessage, TaskStatus};
+
+use crate::agent::orchestrator::AgentOrchestrator;
+use crate::agent::ExecutorContext;
+use crate::AgentError;
+
+impl AgentOrchestrator {
+    /// Regenerate `thread_id` from `request.message_id` with the thread's
+    /// agent.
+    pub async fn regenerate_from(
+        self: &Arc<Self>,
+        thread_id: &str,
+        request: RegenerateRequest,
+        user_id: Option<String>,
+        model_settings: Option<ModelSettings>,
+    ) -> Result<Regeneration, AgentError> {
+        let session = |e: anyhow::Error| AgentError::Session(e.to_string());
+        let thread = self
+            .stores
+            .thread_store
+            .get_thread(thread_id)
+            .await
+            .map_err(session)?
+            .ok_or_else(|| AgentError::NotFound(format!("Thread {} not found", thread_id)))?;
+
+        let task_store = &self.stores.task_store;
+        let history = task_store
+            .get_history(thread_id, None)
+            .await
+            .map_err(session)?;
+        let (task, messages) = history
+            .iter()
+            .find(|(_, messages)| {
+                messages.iter().any(|m| {
+                    matches!(m, TaskMessage::Message(message) if message.id == request.message_id)
+                })
+            })
+            .ok_or_else(|| {
+                AgentError::NotFound(format!(
+                    "Message {} not found in thread {}",
+                    request.message_id, thread_id
+                ))
+            })?;
+        let prompt = messages
+            .iter()
+            .find_map(|m| match m {
+                TaskMessage::Message(message) if message.role == MessageRole::User => Some(message),
+                _ => None,
+            })
+            .ok_or_else(|| {
+                AgentError::Validation(format!("Task {} has no user message to run again", task.id))
+            })?;
+        let mut message = Message {
+            id: uuid::Uuid::new_v4().to_string(),
+            created_at: chrono::Utc::now().timestamp_millis(),
+            ..prompt.clone()
+        };
+        if let Some(text) = request.message {
+            message.parts = vec![Part::Text(text)];
+        }
+
+        let archived_task_ids = task_store
+            .archive_tasks_from(thread_id, &task.id)
+            .await
+            .map_err(session)?;
+        let reference_task_ids = vec![task.id.clone()];
+        let new_task = task_store
+            .create_task(
+                CreateTaskInput::local(thread_id)
+                    .with_status(TaskStatus::Running)
+                    .with_reference_tasks(reference_task_ids.clone()),
+            )
+            .await
+            .map_err(session)?;
+
+        let mut context = ExecutorContext {
+            thread_id: thread_id.to_string(),
+            task_id: new_task.id.clone(),
+            agent_id: thread.agent_id.clone(),
+            orchestrator: Some(self.clone()),
+            default_model_settings: model_settings,
+            ..Default::default()
+        };
+        if let Some(user_id) = user_id {
+            context.user_id = user_id;
+        }
+        let result = self
+            .execute(
+                &thread.agent_id,
+                message,
+                Arc::new(context),
+                request.overrides,
+            )
+            .await?;
+
+        Ok(Regeneration {
+            thread_id: thread_id.to_string(),
+            task_id: new_task.id,
+            reference_task_ids,
+            archived_task_ids,
+            content: result.content,
+        })
+    }
+}
@@ -1,3 +1,4 @@
+use super::recovery::{recover, Recovery};
 use crate::{
     agent::{
         strategy::execution::{ExecutionResult, ExecutionStrategy},
@@ -7,10 +8,19 @@ use crate::{
     AgentError,
 };
 use distri_types::{
-    Action, ExecutionStatus, Part, PlanStep, StandardDefinition, ToolResponse, ToolResultWithSkip,
-    DEFAULT_EXTERNAL_TOOL_TIMEOUT_SECS,
+    injection_guard::{self, InjectionGuardConfig, InjectionGuardMode},
+    tool_output::{output_violations, ToolOutputValidation},
+    tool_recovery::ToolRecoveryConfig,
+    tool_redaction::ToolRedactor,
+    tool_timeouts::{ToolTimedOut, ToolTimeoutConfig},
+    Action, ExecutionStatus, LlmDefinition, Message, Part, PlanStep, StandardDefinition,
+    ToolCallFormat, ToolMemoizeConfig, ToolResponse, ToolResultWithSkip,
+    DEFAULT_EXTERNAL_TOOL_TIMEOUT_SECS, TOOL_CALL_CACHED_MARKER,
 };
-use std::{sync::Arc, time::Duration};
+use std::{borrow::Cow, sync::Arc, time::Duration};
+
+/// Characters of a tool result the injection classifier reads.
+const MAX_CLASSIFIED_CHARS: usize = 8000;
 
 /// Unified AgentExecutor that combines functionality from all execution strategies
 pub struct AgentExecutor {
@@ -56,6 +66,23 @@ impl AgentExecutor {
             .map(|s| s.get_external_tool_timeout_secs())
             .unwrap_or(DEFAULT_EXTERNAL_TOOL_TIMEOUT_SECS);
 
+        let tools_config = self
+            .agent_definition
+            .as_ref()
+            .and_then(|def| def.tools.as_ref());
+        let memoize = tools_config
+            .map(|tools| tools.memoize.clone())
+            .unwrap_or_default();
+        let recovery = tools_config
+            .map(|tools| tools.recovery.clone())
+            .unwrap_or_default();
+        let timeouts = tools_config
+            .map(|tools| tools.timeouts.clone())
+            .unwrap_or_default();
+        let output_validation = tools_config
+            .map(|tools| tools.output_validation)
+            .unwrap_or_default();
+
         // NOTE: the ToolCalls event is emitted inside
         // `execute_tool_calls_with_timeout` AFTER all external tool calls are
         // pre-registered — this closes the race where a client receiving the
@@ -71,6 +98,10 @@ impl AgentExecutor {
             &enhanced_tools,
             step_id,
             external_tool_timeout_secs,
+            &memoize,
+            &recovery,
+            &timeouts,
+            output_validation,
         )
         .await?;
 
@@ -123,6 +154,8 @@ impl AgentExecutor {
                         ExecutionStatus::Success
                     };
                 }
+                // A tool whose recovery strategy is `fail` fails the run.
+                Err(e @ AgentError::ToolExecutionFailed(_)) => return Err(e),
                 Err(e) => {
                     status = ExecutionStatus::Failed;
                     reason = Some(e.to_string());
@@ -160,11 +193,29 @@ impl AgentExecutor {
         }
 
         let mut processed_tool_results: Vec<crate::types::ToolResponse> = Vec::new();
+        let redactor = context
+            .get_orchestrator()
+            .ok()
+            .and_then(|orchestrator| orchestrator.tool_redactor.clone());
+        let injection_guard = self
+            .agent_definition
+            .as_ref()
+            .and_then(|def| def.tools.as_ref())
+            .map(|tools| tools.injection_guard.clone())
+            .unwrap_or_default();
 
         let mut input_required = false;
         for result in tool_results {
             match result {
                 ToolResultWithSkip::ToolResult(tool_result) => {
+                    // Redact before the result is persisted, shown or sent
+                    // to the model.
+                    let tool_result = redact_tool_result(tool_result, redactor.as_deref());
+                    // Then screen what is left for instructions aimed at the
+                    // model.
+                    let tool_result = &*self
+                        .guard_tool_result(tool_result, &injection_guard, &context, step_id)
+                        .await;
                     let fields = distri_formatter::extract::extract_fields(tool_result);
                     let content_size = fields.content_size();
 
@@ -216,6 +267,121 @@ impl AgentExecutor {
         })
     }
 
+    /// `tool_result` screened for prompt injection by `guard`: with a
+    /// detection it is marked as untrusted, sanitized in `sanitize` mode and
+    /// reported as a `PromptInjectionDetected` event.
+    async fn guard_tool_result<'a>(
+        &self,
+        tool_result: Cow<'a, ToolResponse>,
+        guard: &InjectionGuardConfig,
+        context: &Arc<ExecutorContext>,
+        step_id: &str,
+    ) -> Cow<'a, ToolResponse> {
+        if !guard.applies_to(&tool_result.tool_name) {
+            return tool_result;
+        }
+        let matched = injection_guard::scan_parts(&tool_result.parts);
+        let detections: Vec<String> = if !matched.is_empty() {
+            matched.into_iter().map(str::to_string).collect()
+        } else if guard.classifier && self.classify_injection(&tool_result, context).await {
+            vec![injection_guard::CLASSIFIER_DETECTION.to_string()]
+        } else {
+            return tool_result;
+        };
+        // Only heuristic matches have lines to remove.
+        let sanitized = guard.mode == InjectionGuardMode::Sanitize
+            && detections[0] != injection_guard::CLASSIFIER_DETECTION;
+        tracing::warn!(
+            tool = %tool_result.tool_name,
+            agent = %context.agent_id,
+            sanitized,
+            "Possible prompt injection in tool output: {}",
+            detections.join(", ")
+        );
+
+        let mut guarded = tool_result.into_owned();
+        if sanitized {
+            injection_guard::sanitize_parts(&mut guarded.parts);
+        }
+        guarded.parts.insert(
+            0,
+            Part::Text(injection_guard::untrusted_marker(&detections)),
+        );
+        context
+            .emit(AgentEventType::PromptInjectionDetected {
+                step_id: step_id.to_string(),
+                tool_call_id: guarded.tool_call_id.clone(),
+                tool_call_name: guarded.tool_name.clone(),
+                detections,
+                sanitized,
+            })
+            .await;
+        Cow::Owned(guarded)
+    }
+
+    /// Whether the agent's analysis model judges `tool_result` to be a
+    /// prompt injection. Failures count as no.
+    async fn classify_injection(
+        &self,
+        tool_result: &ToolResponse,
+        context: &Arc<ExecutorContext>,
+    ) -> bool {
+        let text = injection_guard::parts_text(&tool_result.parts);
+        if text.trim().is_empty() {
+            return false;
+        }
+        let Some(model_settings) = self
+            .agent_definition
+            .as_ref()
+            .and_then(|def| def.analysis_model_settings_config())
+            .cloned()
+        else {
+            tracing::warn!(
+                agent = %context.agent_id,
+                "injection_guard.classifier is set but the agent has no model settings"
+            );
+            return false;
+        };
+        let llm_def = LlmDefinition {
+            name: "injection_classifier".to_string(),
+            model_settings: Some(model_settings),
+            tool_format: ToolCallFormat::Provider,
+            tool_delivery_mode: Default::default(),
+        };
+        let text: String = text.chars().take(MAX_CLASSIFIED_CHARS).collect();
+        let reply = match crate::llm::create_llm_executor(
+            llm_def,
+            vec![],
+            context.clone(),
+            None,
+            Some("injection_classifier".to_string()),
+        ) {
+            Ok(executor) => {
+                executor
+                    .execute(&[
+                        Message::system(injection_guard::CLASSIFIER_PROMPT.to_string(), None),
+                        Message::user(text, None),
+                    ])
+                    .await
+            }
+            Err(e) => Err(e),
+        };
+        match reply.map(|r| injection_guard::ClassifierVerdict::parse(&r.content)) {
+            Ok(Some(verdict)) => verdict.injection,
+            Ok(None) => {
+                tracing::warn!(
+                    tool = %tool_result.tool_name,
+                    "injection classifier did not reply with a verdict"
+                );
+                false
+            }
+            Err(e) => {
+                tracing::warn!(tool = %tool_result.tool_name, "injection classifier failed: {}", e);
+                false
+            }
+        }
+    }
+
     /// Persist a large tool result to disk via Write tool or artifact storage fallback.
     /// Returns the file path/reference if persistence succeeded.
     async fn persist_large_result(
@@ -395,6 +561,23 @@ impl ExecutionStrategy for AgentExecutor {
     }
 }
 
+/// `tool_result` with the configured redaction rules applied.
+fn redact_tool_result<'a>(
+    tool_result: &'a ToolResponse,
+    redactor: Option<&ToolRedactor>,
+) -> Cow<'a, ToolResponse> {
+    let Some(redactor) = redactor else {
+        return Cow::Borrowed(tool_result);
+    };
+    let mut redacted = tool_result.clone();
+    let spans = redactor.redact_response(&mut redacted);
+    if spans == 0 {
+        return Cow::Borrowed(tool_result);
+    }
+    tracing::debug!(tool = %redacted.tool_name, spans, "redacted tool output");
+    Cow::Owned(redacted)
+}
+
 /// True when the last step's parts request the agent's turn to END — i.e. a
 /// checkpoint tool returned `should_continue: false` in a `Part::Data`.
 ///
@@ -496,10 +679,15 @@ pub async fn execute_tool_calls(
         tools,
         step_id,
         DEFAULT_EXTERNAL_TOOL_TIMEOUT_SECS,
+        &ToolMemoizeConfig::default(),
+        &ToolRecoveryConfig::default(),
+        &ToolTimeoutConfig::default(),
+        ToolOutputValidation::default(),
     )
     .await
 }
 
+#[allow(clippy::too_many_arguments)]
 pub async fn execute_tool_calls_with_timeout(
     external_tool_calls_store: Arc<dyn distri_types::stores::ExternalToolCallsStore>,
     tool_calls: &[crate::types::ToolCall],
@@ -507,6 +695,10 @@ pub async fn execute_tool_calls_with_timeout(
     tools: &[Arc<dyn Tool>],
     step_id: &str,
     external_tool_timeout_secs: u64,
+    memoize: &ToolMemoizeConfig,
+    recovery: &ToolRecoveryConfig,
+    timeouts: &ToolTimeoutConfig,
+    output_validation: ToolOutputValidation,
 ) -> Result<Vec<ToolResultWithSkip>, AgentError> {
     // separate internal tool calls only
 
@@ -621,11 +813,8 @@ pub async fn execute_tool_calls_with_timeout(
             let _permit = semaphore.acquire().await.ok();
             let (tool, tool_call) = tuple;
 
-            // Dry-run mode: simulate external and unsafe tools via LLM
-            if context.dry_run
-                && (tool.is_external()
-                    || !crate::tools::simulator::is_safe_tool(&tool_call.tool_name))
-            {
+            // Dry-run mode: simulate tools with side effects via LLM
+            if context.dry_run && crate::tools::simulator::simulates_in_dry_run(tool.as_ref()) {
                 context
                     .emit(AgentEventType::ToolExecutionStart {
                         step_id: step_id.clone(),
@@ -648,6 +837,7 @@ pub async fn execute_tool_calls_with_timeout(
                 )
                 .await
                 .unwrap_or_else(|e| vec![Part::Text(format!("Simulation error: {e}"))]);
+                record_simulated_side_effect(tool_call, &context, &step_id).await;
 
                 context
                     .emit(AgentEventType::ToolExecutionEnd {
@@ -658,10 +848,12 @@ pub async fn execute_tool_calls_with_timeout(
                     })
                     .await;
 
-                return ToolResultWithSkip::ToolResult(crate::types::ToolResponse::from_parts(
-                    tool_call.tool_call_id.clone(),
-                    tool_call.tool_name.clone(),
-                    parts,
+                return Ok(ToolResultWithSkip::ToolResult(
+                    crate::types::ToolResponse::from_parts(
+                        tool_call.tool_call_id.clone(),
+                        tool_call.tool_name.clone(),
+                        parts,
+                    ),
                 ));
             }
 
@@ -670,7 +862,7 @@ pub async fn execute_tool_calls_with_timeout(
                     .lock()
                     .await
                     .remove(&tool_call.tool_call_id);
-                return handle_external_tool_inline(
+                return Ok(handle_external_tool_inline(
                     external_tool_calls_store.clone(),
                     tool_call.clone(),
                     context.clone(),
@@ -678,7 +870,7 @@ pub async fn execute_tool_calls_with_timeout(
                     timeout,
                     pre_rx,
                 )
-                .await;
+                .await);
             }
 
             context
@@ -690,8 +882,9 @@ pub async fn execute_tool_calls_with_timeout(
                 })
                 .await;
 
-            // Dry-run mode for non-external unsafe tools (shouldn't reach here often)
-            if context.dry_run && !crate::tools::simulator::is_safe_tool(&tool_call.tool_name) {
+            // Dry-run mode for non-external tools with side effects
+            // (shouldn't reach here often)
+            if context.dry_run && crate::tools::simulator::simulates_in_dry_run(tool.as_ref()) {
                 tracing::info!(
                     tool = %tool_call.tool_name,
                     "Dry-run: simulating tool response via LLM"
@@ -704,6 +897,7 @@ pub async fn execute_tool_calls_with_timeout(
                 )
                 .await
                 .unwrap_or_else(|e| vec![Part::Text(format!("Simulation error: {e}"))]);
+                record_simulated_side_effect(tool_call, &context, &step_id).await;
 
                 context
                     .emit(AgentEventType::ToolExecutionEnd {
@@ -714,37 +908,119 @@ pub async fn execute_tool_calls_with_timeout(
                     })
                     .await;
 
-                return ToolResultWithSkip::ToolResult(crate::types::ToolResponse::from_parts(
-                    tool_call.tool_call_id.clone(),
-                    tool_call.tool_name.clone(),
-                    parts,
+                return Ok(ToolResultWithSkip::ToolResult(
+                    crate::types::ToolResponse::from_parts(
+                        tool_call.tool_call_id.clone(),
+                        tool_call.tool_name.clone(),
+                        parts,
+                    ),
                 ));
             }
 
-            // Execute the tool based on its type
-            let (parts, success) = if tool.needs_executor_context() {
-                // ExecutorContext-based tool
-                match execute_executor_context_tool(
+            // A repeat of an earlier identical call in this task is answered
+            // from the task's tool call cache, marked as such for the model.
+            let memoized = memoize.applies_to(tool.as_ref());
+            if memoized {
+                let cached = context
+                    .tool_call_cache
+                    .write()
+                    .await
+                    .get(&tool_call.tool_name, &tool_call.input);
+                if let Some(cached) = cached {
+                    tracing::debug!(
+                        tool = %tool_call.tool_name,
+                        "Tool call served from the task's tool call cache"
+                    );
+                    context
+                        .emit(AgentEventType::ToolExecutionEnd {
+                            step_id: step_id.clone(),
+                            tool_call_id: tool_call.tool_call_id.clone(),
+                            tool_call_name: tool_call.tool_name.clone(),
+                            success: true,
+                        })
+                        .await;
+
+                    let mut parts = vec![Part::Text(TOOL_CALL_CACHED_MARKER.to_string())];
+                    parts.extend(cached);
+                    return Ok(ToolResultWithSkip::ToolResult(
+                        crate::types::ToolResponse::from_parts(
+                            tool_call.tool_call_id.clone(),
+                            tool_call.tool_name.clone(),
+                            parts,
+                        ),
+                    ));
+                }
+            }
+
+            let (mut parts, outcome) = run_tool(
+                tool.as_ref(),
+                tool_call,
+                &context,
+                &step_id,
+                timeouts.timeout_for(&tool_call.tool_name),
+            )
+            .await;
+            let mut success = matches!(outcome, ToolOutcome::Success);
+            // Only the tool's own result may answer a later identical call.
+            let mut from_tool = true;
+            if let ToolOutcome::Failed(error) = outcome {
+                match recover(
+                    recovery.strategy_for(&tool_call.tool_name),
                     tool.as_ref(),
-                    tool_call.clone(),
-                    context.clone(),
+                    tool_call,
+                    error,
+                    parts,
+                    tools,
+                    timeouts,
+                    &context,
+                    &step_id,
                 )
                 .await
                 {
-                    Ok(parts) => (parts, true),
-                    Err(e) => (vec![Part::Text(e.to_string())], false),
-                }
-            } else {
-                // ToolContext-based tool
-                let tool_context = crate::tools::context::to_tool_context(context.as_ref());
-                match tool
-                    .execute(tool_call.clone(), Arc::new(tool_context))
-                    .await
-                {
-                    Ok(parts) => (parts, true),
-                    Err(e) => (vec![Part::Text(e.to_string())], false),
+                    Recovery::Result {
+                        parts: recovered,
+                        success: ok,
+                        from_fallback,
+                    } => {
+                        parts = recovered;
+                        success = ok;
+                        from_tool = !from_fallback;
+                    }
+                    Recovery::Fail(e) => {
+                        context
+                            .emit(AgentEventType::ToolExecutionEnd {
+                                step_id: step_id.clone(),
+                                tool_call_id: tool_call.tool_call_id.clone(),
+                                tool_call_name: tool_call.tool_name.clone(),
+                                success: false,
+                            })
+                            .await;
+                        return Err(e);
+                    }
                 }
-            };
+            }
+            if success && from_tool {
+                check_output(
+                    tool.as_ref(),
+                    tool_call,
+                    &parts,
+                    output_validation,
+                    &context,
+                    &step_id,
+                )
+                .await;
+            }
+            if memoized && success && from_tool {
+                context.tool_call_cache.write().await.record(
+                    &tool_call.tool_name,
+                    &tool_call.input,
+                    parts.clone(),
+                );
+            } else if !tool.concurrency_safe() {
+                // A tool that mutates shared state can change what earlier
+                // lookups would return now.
+                context.tool_call_cache.write().await.clear();
+            }
             // Emit completion event
             context
                 .emit(AgentEventType::ToolExecutionEnd {
@@ -755,16 +1031,267 @@ pub async fn execute_tool_calls_with_timeout(
                 })
                 .await;
             // Wrap parts into ToolResponse
-            ToolResultWithSkip::ToolResult(crate::types::ToolResponse::from_parts(
-                tool_call.tool_call_id.clone(),
-                tool_call.tool_name.clone(),
-                parts,
+            Ok(ToolResultWithSkip::ToolResult(
+                crate::types::ToolResponse::from_parts(
+                    tool_call.tool_call_id.clone(),
+                    tool_call.tool_name.clone(),
+                    parts,
+                ),
             ))
         }
     }))
     .await;
 
-    Ok(results)
+    // A call whose recovery strategy is `fail` fails the whole step.
+    results.into_iter().collect()
+}
+
+/// Record a call a dry run intercepted, for its report.
+async fn record_simulated_side_effect(
+    tool_call: &crate::types::ToolCall,
+    context: &Arc<ExecutorContext>,
+    step_id: &str,
+) {
+    context
+        .record_side_effect(
+            tool_call.tool_name.clone(),
+            Some(tool_call.tool_call_id.clone()),
+            tool_call.input.clone(),
+        )
+        .await;
+    context
+        .emit(AgentEventType::SideEffectSimulated {
+            step_id: step_id.to_string(),
+            tool_call_id: tool_call.tool_call_id.clone(),
+            tool_call_name: tool_call.tool_name.clone(),
+            input: tool_call.input.clone(),
+        })
+        .await;
+}
+
+/// Check a successful result of `tool` against its output schema. Violations
+/// are logged and, with `dev` validation, reported as a `ToolOutputInvalid`
+/// event; the result goes to the model either way.
+async fn check_output(
+    tool: &dyn Tool,
+    tool_call: &crate::types::ToolCall,
+    parts: &[Part],
+    validation: ToolOutputValidation,
+    context: &Arc<ExecutorContext>,
+    step_id: &str,
+) {
+    if validation == ToolOutputValidation::Off {
+        return;
+    }
+    let Some(schema) = tool.get_output_schema() else {
+        return;
+    };
+    let violations = output_violations(&schema, parts);
+    if violations.is_empty() {
+        return;
+    }
+    tracing::warn!(
+        tool = %tool_call.tool_name,
+        agent = %context.agent_id,
+        "Tool output does not match its output schema: {}",
+        violations.join("; ")
+    );
+    if validation == ToolOutputValidation::Dev {
+        context
+            .emit(AgentEventType::ToolOutputInvalid {
+                step_id: step_id.to_string(),
+                tool_call_id: tool_call.tool_call_id.clone(),
+                tool_call_name: tool_call.tool_name.clone(),
+                violations,
+            })
+            .await;
+    }
+}
+
+/// How a tool call ended.
+pub(super) enum ToolOutcome {
+    Success,
+    /// The tool returned this error; `tools.recovery` applies.
+    Failed(String),
+    /// The tool lacks a capability or OAuth scopes; reported as is.
+    Denied,
+    /// The run was canceled while the tool ran; reported as is.
+    Canceled,
+}
+
+/// Why a tool call was abandoned before it finished.
+enum Interrupted {
+    TimedOut(Duration),
+    Canceled,
+}
+
+impl Interrupted {
+    /// The result of the abandoned call. A timeout is reported to the
+    /// model as a `ToolTimedOut` result and a `ToolTimeout` event, and
+    /// `tools.recovery` applies to it.
+    async fn into_result(
+        self,
+        tool_call: &crate::types::ToolCall,
+        context: &Arc<ExecutorContext>,
+        step_id: &str,
+    ) -> (Vec<Part>, ToolOutcome) {
+        match self {
+            Interrupted::TimedOut(limit) => {
+                let timed_out = ToolTimedOut {
+                    tool: tool_call.tool_name.clone(),
+                    timeout_secs: limit.as_secs(),
+                };
+                tracing::warn!(
+                    tool = %tool_call.tool_name,
+                    agent = %context.agent_id,
+                    "{}",
+                    timed_out
+                );
+                context
+                    .emit(AgentEventType::ToolTimeout {
+                        step_id: step_id.to_string(),
+                        tool_call_id: tool_call.tool_call_id.clone(),
+                        tool_call_name: tool_call.tool_name.clone(),
+                        timeout_secs: timed_out.timeout_secs,
+                    })
+                    .await;
+                (
+                    vec![Part::Data(timed_out.to_json())],
+                    ToolOutcome::Failed(timed_out.to_string()),
+                )
+            }
+            Interrupted::Canceled => (
+                vec![Part::Text(format!(
+                    "Tool '{}' was stopped: the run was canceled",
+                    tool_call.tool_name
+                ))],
+                ToolOutcome::Canceled,
+            ),
+        }
+    }
+}
+
+/// Await `call` unless `timeout` passes or the run is canceled first.
+async fn bounded<T>(
+    call: impl std::future::Future<Output = T>,
+    timeout: Option<Duration>,
+    context: &ExecutorContext,
+) -> Result<T, Interrupted> {
+    let limited = async {
+        match timeout {
+            Some(limit) => tokio::time::timeout(limit, call)
+                .await
+                .map_err(|_| Interrupted::TimedOut(limit)),
+            None => Ok(call.await),
+        }
+    };
+    let canceled = async {
+        match &context.cancellation_signal {
+            Some(signal) => signal.cancelled().await,
+            None => std::future::pending().await,
+        }
+    };
+    tokio::select! {
+        result = limited => result,
+        _ = canceled => Err(Interrupted::Canceled),
+    }
+}
+
+/// Run an internal tool once, returning the parts of its result. The call
+/// is abandoned after `timeout` or when the run is canceled; time spent
+/// waiting for the user's OAuth consent does not count.
+pub(super) async fn run_tool(
+    tool: &dyn Tool,
+    tool_call: &crate::types::ToolCall,
+    context: &Arc<ExecutorContext>,
+    step_id: &str,
+    timeout: Option<Duration>,
+) -> (Vec<Part>, ToolOutcome) {
+    if tool.needs_executor_context() {
+        // ExecutorContext-based tool
+        let call = execute_executor_context_tool(tool, tool_call.clone(), context.clone());
+        let result = match bounded(call, timeout, context).await {
+            Ok(result) => result,
+            Err(interrupted) => return interrupted.into_result(tool_call, context, step_id).await,
+        };
+        return match result {
+            Ok(parts) => (parts, ToolOutcome::Success),
+            Err(AgentError::EgressDenied(denied)) => {
+                (vec![Part::Data(denied.to_json())], ToolOutcome::Denied)
+            }
+            Err(AgentError::BrowserPolicy(violation)) => {
+                (vec![Part::Data(violation.to_json())], ToolOutcome::Denied)
+            }
+            Err(e) => (
+                vec![Part::Text(e.to_string())],
+                ToolOutcome::Failed(e.to_string()),
+            ),
+        };
+    }
+
+    // ToolContext-based tool
+    let tool_context = crate::tools::context::to_tool_context_for(context.as_ref(), tool);
+    let spans = tool_context.spans.clone();
+    let tool_context = Arc::new(tool_context);
+    let call = tool.execute(tool_call.clone(), tool_context.clone());
+    let mut outcome = match bounded(call, timeout, context).await {
+        Ok(outcome) => outcome,
+        Err(interrupted) => return interrupted.into_result(tool_call, context, step_id).await,
+    };
+    // A tool short of OAuth scopes pauses for the user's consent
+    // and is retried once they are granted.
+    let required = outcome
+        .as_ref()
+        .err()
+        .and_then(|e| e.downcast_ref::<distri_types::ScopesRequired>().cloned());
+    if let Some(required) = required {
+        if crate::agent::auth_consent::request_consent(context, tool_call, &required).await {
+            let call = tool.execute(tool_call.clone(), tool_context);
+            outcome = match bounded(call, timeout, context).await {
+                Ok(outcome) => outcome,
+                Err(interrupted) => {
+                    return interrupted.into_result(tool_call, context, step_id).await
+                }
+            };
+        }
+    }
+    let result = match outcome {
+        Ok(parts) => (parts, ToolOutcome::Success),
+        Err(e) => {
+            if let Some(denied) = e.downcast_ref::<distri_types::CapabilityDenied>() {
+                tracing::warn!(tool = %tool_call.tool_name, "{}", denied);
+                (vec![Part::Data(denied.to_json())], ToolOutcome::Denied)
+            } else if let Some(denied) = e.downcast_ref::<distri_types::egress::NetworkDenied>() {
+                tracing::warn!(
+                    tool = %tool_call.tool_name,
+                    agent = %context.agent_id,
+                    "{}",
+                    denied
+                );
+                (vec![Part::Data(denied.to_json())], ToolOutcome::Denied)
+            } else if let Some(required) = e.downcast_ref::<distri_types::ScopesRequired>() {
+                (vec![Part::Data(required.to_json())], ToolOutcome::Denied)
+            } else {
+                (
+                    vec![Part::Text(e.to_string())],
+                    ToolOutcome::Failed(e.to_string()),
+                )
+            }
+        }
+    };
+    let spans = spans.take();
+    if !spans.is_empty() {
+        context
+            .emit(AgentEventType::PluginSpans {
+                step_id: step_id.to_string(),
+                tool_call_id: tool_call.tool_call_id.clone(),
+                tool_call_name: tool_call.tool_name.clone(),
+                plugin: tool.get_plugin_name(),
+                spans,
+            })
+            .await;
+    }
+    result
 }
 
 /// Handle external tool execution with inline behavior - waits for response from client.
@@ -52,6 +52,7 @@ pub trait ExecutionStrategy: Send + Sync + std::fmt::Debug {
 }
 
 pub mod default;
+mod recovery;
 
 pub use default::AgentExecutor;
 
@@ -0,0 +1,191 @@
+//! Applies the `tools.recovery` strategy of a failed tool call; see
+//! `distri_types::tool_recovery`.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use distri_types::tool_recovery::{correction_message, ToolRecoveryStrategy};
+use distri_types::tool_timeouts::ToolTimeoutConfig;
+use distri_types::Part;
+
+use super::default::{run_tool, ToolOutcome};
+use crate::agent::{AgentEventType, ExecutorContext};
+use crate::tools::Tool;
+use crate::types::ToolCall;
+use crate::AgentError;
+
+/// What a failed call turns into.
+pub(super) enum Recovery {
+    /// The call's result, after recovery.
+    Result {
+        parts: Vec<Part>,
+        success: bool,
+        /// The parts come from the fallback tool.
+        from_fallback: bool,
+    },
+    /// Fail the run.
+    Fail(AgentError),
+}
+
+/// Recover the failed call `tool_call` of `tool`, whose result was `parts`.
+#[allow(clippy::too_many_arguments)]
+pub(super) async fn recover(
+    strategy: &ToolRecoveryStrategy,
+    tool: &dyn Tool,
+    tool_call: &ToolCall,
+    error: String,
+    parts: Vec<Part>,
+    tools: &[Arc<dyn Tool>],
+    timeouts: &ToolTimeoutConfig,
+    context: &Arc<ExecutorContext>,
+    step_id: &str,
+) -> Recovery {
+    let report = Report {
+        context,
+        step_id,
+        tool_call,
+        strategy,
+    };
+    match strategy {
+        ToolRecoveryStrategy::Report => Recovery::Result {
+            parts,
+            success: false,
+            from_fallback: false,
+        },
+        ToolRecoveryStrategy::Retry {
+            attempts,
+            backoff_ms,
+        } => {
+            let (mut parts, mut error) = (parts, error);
+            for attempt in 1..=*attempts {
+                if *backoff_ms > 0 {
+                    tokio::time::sleep(Duration::from_millis(*backoff_ms)).await;
+                }
+                let (retried, outcome) = run_tool(
+                    tool,
+                    tool_call,
+                    context,
+                    step_id,
+                    timeouts.timeout_for(&tool_call.tool_name),
+                )
+                .await;
+                let recovered = matches!(outcome, ToolOutcome::Success);
+                report.emit(&error, attempt, recovered, None).await;
+                parts = retried;
+                match outcome {
+                    ToolOutcome::Success => {
+                        return Recovery::Result {
+                            parts,
+                            success: true,
+                            from_fallback: false,
+                        }
+                    }
+                    ToolOutcome::Failed(e) => error = e,
+                    ToolOutcome::Denied | ToolOutcome::Canceled => break,
+                }
+            }
+            Recovery::Result {
+                parts,
+                success: false,
+                from_fallback: false,
+            }
+        }
+        ToolRecoveryStrategy::Correct => {
+            report.emit(&error, 0, false, None).await;
+            let message = correction_message(
+                &tool_call.tool_name,
+                &error,
+                &tool.get_parameters(),
+                &tool_call.input,
+            );
+            Recovery::Result {
+                parts: vec![Part::Text(message)],
+                success: false,
+                from_fallback: false,
+            }
+        }
+        ToolRecoveryStrategy::Fallback { tool: name } => {
+            // External tools run in the client, outside this call's lifecycle.
+            let fallback = tools
+                .iter()
+                .find(|t| t.get_name() == *name && !t.is_external());
+            let Some(fallback) = fallback else {
+                tracing::warn!(
+                    tool = %tool_call.tool_name,
+                    fallback = %name,
+                    "fallback tool is not available"
+                );
+                report.emit(&error, 0, false, Some(name)).await;
+                return Recovery::Result {
+                    parts,
+                    success: false,
+                    from_fallback: false,
+                };
+            };
+            let call = ToolCall {
+                tool_name: name.clone(),
+                ..tool_call.clone()
+            };
+            let (fallback_parts, outcome) = run_tool(
+                fallback.as_ref(),
+                &call,
+                context,
+                step_id,
+                timeouts.timeout_for(name),
+            )
+            .await;
+            let success = matches!(outcome, ToolOutcome::Success);
+            report.emit(&error, 0, success, Some(name)).await;
+            let mut parts = vec![Part::Text(format!(
+                "'{}' failed ({}); result of '{}' with the same arguments:",
+                tool_call.tool_name, error, name
+            ))];
+            parts.extend(fallback_parts);
+            Recovery::Result {
+                parts,
+                success,
+                from_fallback: true,
+            }
+        }
+        ToolRecoveryStrategy::Fail => {
+            report.emit(&error, 0, false, None).await;
+            Recovery::Fail(AgentError::ToolExecutionFailed(format!(
+                "'{}': {}",
+                tool_call.tool_name, error
+            )))
+        }
+    }
+}
+
+/// Emits the `ToolRecovery` events of one call.
+struct Report<'a> {
+    context: &'a Arc<ExecutorContext>,
+    step_id: &'a str,
+    tool_call: &'a ToolCall,
+    strategy: &'a ToolRecoveryStrategy,
+}
+
+impl Report<'_> {
+    async fn emit(&self, error: &str, attempt: u32, recovered: bool, fallback: Option<&String>) {
+        tracing::info!(
+            tool = %self.tool_call.tool_name,
+            strategy = self.strategy.as_str(),
+            attempt,
+            recovered,
+            "recovering failed tool call: {}",
+            error
+        );
+        self.context
+            .emit(AgentEventType::ToolRecovery {
+                step_id: self.step_id.to_string(),
+                tool_call_id: self.tool_call.tool_call_id.clone(),
+                tool_call_name: self.tool_call.tool_name.clone(),
+                strategy: self.strategy.as_str().to_string(),
+                error: error.to_string(),
+                attempt,
+                recovered,
+                fallback_tool: fallback.cloned(),
+            })
+            .await;
+    }
+}
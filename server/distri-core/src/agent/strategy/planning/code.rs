@@ -232,6 +232,7 @@ impl PlanningStrategy for CodePlanner {
             })?;
         let mut plan_config = crate::types::PlanConfig::default();
         plan_config.model_settings = self.agent_def.model_settings().cloned();
+        plan_config.model_fallbacks = self.agent_def.fallback_model_settings();
 
         let mut messages = vec![Message::system(prompt, None)];
         // Only include additional user message if has images
@@ -1,7 +1,12 @@
-use std::{collections::HashSet, env, fs, sync::Arc};
+use std::{
+    collections::{BTreeMap, HashSet},
+    env, fs,
+    sync::Arc,
+};
 
 use chrono::Utc;
 use distri_parsers;
+use distri_types::memory::{MemoryIdentity, ScopedMemory};
 use distri_types::{
     ContextBudget, ExecutionResult, MessageRole, Part, ScratchpadEntry, ScratchpadEntryType,
     ToolCallFormat,
@@ -10,7 +15,12 @@ use tracing::warn;
 
 use crate::{
     agent::{
-        prompt_registry::TemplateData, token_estimator::TokenEstimator, types::MAX_ITERATIONS,
+        prompt_registry::{
+            compose_prompt_layers, PromptLayer, PromptLayerKind, PromptRegistry,
+            RuntimeContextData, RuntimeContextItem, TemplateData,
+        },
+        tokenizer::{tokenizer_for, Tokenizer},
+        types::MAX_ITERATIONS,
         ExecutorContext,
     },
     AgentError,
@@ -18,6 +28,10 @@ use crate::{
 
 const MIN_SCRATCHPAD_ENTRY_LIMIT: usize = 10;
 const MAX_SCRATCHPAD_ENTRY_LIMIT: usize = 100;
+/// Memories listed in the runtime layer's memory summary.
+const MAX_RUNTIME_MEMORIES: usize = 20;
+/// Artifacts listed in the runtime layer, most recent first.
+const MAX_RUNTIME_ARTIFACTS: usize = 20;
 
 /// Helper that builds model-ready message sequences for planning prompts.
 pub struct MessageFormatter<'a> {
@@ -44,6 +58,25 @@ impl<'a> MessageFormatter<'a> {
         user_template: &str,
         todos: Option<String>,
     ) -> Result<(Vec<crate::types::Message>, ContextBudget), AgentError> {
+        let (messages, budget, _layers) = self
+            .build_messages_with_layers(message, context, template, user_template, todos)
+            .await?;
+        Ok((messages, budget))
+    }
+
+    /// Like [`Self::build_messages`], but also returns the rendered layers the
+    /// system prompt was composed from. `template` is the framework layer; the
+    /// agent's instructions, the workspace policy, the skills listing and the
+    /// runtime context are layered around it (see [`PromptLayerKind`]).
+    pub async fn build_messages_with_layers(
+        &self,
+        message: &crate::types::Message,
+        context: &Arc<ExecutorContext>,
+        template: &str,
+        user_template: &str,
+        todos: Option<String>,
+    ) -> Result<(Vec<crate::types::Message>, ContextBudget, Vec<PromptLayer>), AgentError> {
+        let tokenizer = self.tokenizer(context);
         let tools = context.get_tools().await;
         let tool_defs = tools
             .iter()
@@ -87,6 +120,15 @@ impl<'a> MessageFormatter<'a> {
 
         // Fetch session values from the session store
         let session_values = Self::load_session_values(context).await;
+        let thread_variables = Self::load_thread_variables(context).await;
+        // Thread variables are also template values, so helpers such as
+        // `{{#if customer_name}}` see them; they never shadow built-in values.
+        for (name, value) in &thread_variables {
+            dynamic_values
+                .entry(name.clone())
+                .or_insert_with(|| serde_json::Value::String(value.clone()));
+        }
+        let runtime_context = self.load_runtime_context(context).await;
 
         // Extract available_skills from dynamic_values if present
         let available_skills = dynamic_values
@@ -145,27 +187,32 @@ impl<'a> MessageFormatter<'a> {
             deferred_tools_listing,
             channel_kind: context.channel_kind.clone(),
             runtime_mode: runtime_mode_name,
+            runtime_context,
         };
 
-        let template_to_use = hook_state
-            .template_override
-            .system
-            .as_deref()
-            .unwrap_or(template);
         let user_template_to_use = hook_state
             .template_override
             .user
             .as_deref()
             .unwrap_or(user_template);
 
-        let rendered_prompt = render_prompt(context, template_to_use, &template_data).await?;
+        let layers = self
+            .render_prompt_layers(
+                context,
+                template,
+                hook_state.template_override.system.as_deref(),
+                &template_data,
+                &thread_variables,
+            )
+            .await?;
+        let rendered_prompt = compose_prompt_layers(&layers);
 
         let user_additional_data =
             render_prompt(context, user_template_to_use, &template_data).await?;
         self.log_prompt_if_needed(&rendered_prompt);
 
         // Compute rendered prompt token count before moving the string.
-        let rendered_prompt_tokens = TokenEstimator::rough_token_count(&rendered_prompt);
+        let rendered_prompt_tokens = tokenizer.count(&rendered_prompt);
 
         let mut formatted = vec![crate::types::Message::system(rendered_prompt, None)];
 
@@ -200,12 +247,12 @@ impl<'a> MessageFormatter<'a> {
         if !formatted.iter().any(|m| m.id == user_message.id) {
             formatted.push(user_message);
         }
+        Self::render_thread_variables(&mut formatted, &thread_variables);
 
         // Compute context budget breakdown from the built prompt components.
         let budget = {
             // System prompt: static portion = agent instructions (fixed across sessions)
-            let system_prompt_static_tokens =
-                TokenEstimator::rough_token_count(&self.agent_def.instructions);
+            let system_prompt_static_tokens = tokenizer.count(&self.agent_def.instructions);
 
             // Dynamic portion = rendered prompt minus the static instructions size
             let system_prompt_dynamic_tokens =
@@ -217,7 +264,7 @@ impl<'a> MessageFormatter<'a> {
                 .filter(|def| !deferred_names.contains(&def.name))
                 .map(|def| {
                     let json = serde_json::to_string(def).unwrap_or_default();
-                    TokenEstimator::rough_token_count(&json)
+                    tokenizer.count(&json)
                 })
                 .sum();
 
@@ -225,14 +272,14 @@ impl<'a> MessageFormatter<'a> {
             let deferred_tool_tokens = template_data
                 .deferred_tools_listing
                 .as_deref()
-                .map(TokenEstimator::rough_token_count)
+                .map(|text| tokenizer.count(text))
                 .unwrap_or(0);
 
             // Skill listing tokens
             let skill_listing_tokens = template_data
                 .available_skills
                 .as_deref()
-                .map(TokenEstimator::rough_token_count)
+                .map(|text| tokenizer.count(text))
                 .unwrap_or(0);
 
             // Conversation history: all non-system messages in `formatted`
@@ -241,7 +288,7 @@ impl<'a> MessageFormatter<'a> {
                 .filter(|m| !matches!(m.role, MessageRole::System))
                 .map(|m| {
                     let text = m.as_text().unwrap_or_default();
-                    TokenEstimator::rough_token_count(&text)
+                    tokenizer.count(&text)
                 })
                 .sum();
 
@@ -252,7 +299,7 @@ impl<'a> MessageFormatter<'a> {
                 .filter_map(|p| {
                     if let Part::ToolResult(tr) = p {
                         let json = serde_json::to_string(&tr.result()).unwrap_or_default();
-                        Some(TokenEstimator::rough_token_count(&json))
+                        Some(tokenizer.count(&json))
                     } else {
                         None
                     }
@@ -276,7 +323,169 @@ impl<'a> MessageFormatter<'a> {
             }
         };
 
-        Ok((formatted, budget))
+        Ok((formatted, budget, layers))
+    }
+
+    /// Tokenizer of the model the prompt is sent to.
+    fn tokenizer(&self, context: &ExecutorContext) -> Arc<dyn Tokenizer> {
+        let model = self
+            .agent_def
+            .model_settings()
+            .or(context.default_model_settings.as_ref())
+            .map(|settings| settings.model.as_str())
+            .unwrap_or_default();
+        tokenizer_for(model)
+    }
+
+    /// Render each system prompt layer against `data`, in composition order.
+    /// A hook-provided system template replaces the persona, framework and
+    /// skills layers; the workspace policy and runtime layers still apply.
+    /// The thread's variables are substituted verbatim into the persona
+    /// before it is rendered, so their values are not HTML-escaped.
+    async fn render_prompt_layers(
+        &self,
+        context: &Arc<ExecutorContext>,
+        framework_template: &str,
+        system_override: Option<&str>,
+        data: &TemplateData<'_>,
+        thread_variables: &BTreeMap<String, String>,
+    ) -> Result<Vec<PromptLayer>, AgentError> {
+        let tokenizer = self.tokenizer(context);
+        let config = self.agent_def.prompt_layers.clone().unwrap_or_default();
+        // `\{{` keeps braces inside a value literal for handlebars.
+        let escaped_variables: BTreeMap<String, String> = thread_variables
+            .iter()
+            .map(|(name, value)| (name.clone(), value.replace("{{", "\\{{")))
+            .collect();
+        let guardrails = self
+            .agent_def
+            .guardrails_prompt()
+            .map(|rules| rules.replace("{{", "\\{{"));
+        // The agent's guardrails close its persona, override or not.
+        let persona = |template: &str| {
+            let persona = PromptRegistry::render_variables(template, &escaped_variables);
+            match &guardrails {
+                Some(rules) => format!("{}\n\n{}", persona.trim_end(), rules),
+                None => persona,
+            }
+        };
+        let mut sources: Vec<(PromptLayerKind, String)> = Vec::new();
+        match system_override {
+            Some(system) => sources.push((PromptLayerKind::Persona, persona(system))),
+            None => {
+                sources.push((
+                    PromptLayerKind::Persona,
+                    persona(&self.agent_def.instructions),
+                ));
+                if !framework_template.trim().is_empty() {
+                    sources.push((PromptLayerKind::Framework, framework_template.to_string()));
+                    if data.available_skills.is_some() {
+                        sources.push((PromptLayerKind::Skills, "{{> skills}}".to_string()));
+                    }
+                }
+            }
+        }
+        if !config.skip_workspace_policy {
+            if let Some(policy) = context
+                .orchestrator
+                .as_ref()
+                .and_then(|o| o.prompt_policy.clone())
+            {
+                sources.push((PromptLayerKind::WorkspacePolicy, policy));
+            }
+        }
+        if !config.runtime.is_empty() || self.agent_def.datetime.is_some() {
+            sources.push((
+                PromptLayerKind::Runtime,
+                "{{> runtime_context}}".to_string(),
+            ));
+        }
+        sources.sort_by_key(|(kind, _)| *kind);
+
+        let mut layers = Vec::with_capacity(sources.len());
+        for (kind, template) in sources {
+            let content = render_prompt(context, &template, data).await?;
+            let tokens = tokenizer.count(&content);
+            layers.push(PromptLayer {
+                kind,
+                content,
+                tokens,
+            });
+        }
+        Ok(layers)
+    }
+
+    /// Fill the runtime layer with the items the agent opted into.
+    async fn load_runtime_context(&self, context: &Arc<ExecutorContext>) -> RuntimeContextData {
+        let mut data = RuntimeContextData::default();
+        let profile = context.user_profile().await;
+        if let Some(datetime) = &self.agent_def.datetime {
+            let timezone = datetime.resolve_timezone(
+                profile
+                    .as_ref()
+                    .and_then(distri_types::datetime::profile_timezone),
+            );
+            data.datetime = datetime.describe(Utc::now(), timezone);
+        }
+        let Some(config) = &self.agent_def.prompt_layers else {
+            return data;
+        };
+        for item in &config.runtime {
+            match item {
+                RuntimeContextItem::Date => {
+                    data.date = Utc::now().format("%Y-%m-%d (%A)").to_string();
+                }
+                RuntimeContextItem::UserProfile => {
+                    data.user_profile = profile
+                        .as_ref()
+                        .map(distri_types::user_profile::describe_profile)
+                        .unwrap_or_default();
+                }
+                RuntimeContextItem::MemorySummary => {
+                    let memory_store = context
+                        .stores
+                        .as_ref()
+                        .and_then(|s| s.memory_store.clone())
+                        .or_else(|| {
+                            context
+                                .orchestrator
+                                .as_ref()
+                                .and_then(|o| o.stores.memory_store.clone())
+                        });
+                    let Some(store) = memory_store else {
+                        continue;
+                    };
+                    // Only the namespaces the agent's `[memory]` grants let
+                    // it read; without grants, the user's own memories.
+                    let memory = ScopedMemory::new(
+                        store.as_ref(),
+                        self.agent_def.memory.clone().unwrap_or_default(),
+                        MemoryIdentity {
+                            user_id: context.user_id.clone(),
+                            agent_id: self.agent_def.name.clone(),
+                            workspace_id: context.workspace_id.clone(),
+                        },
+                    );
+                    match memory.recall(MAX_RUNTIME_MEMORIES).await {
+                        Ok(memories) => {
+                            data.memory_summary = memories
+                                .iter()
+                                .map(|m| format!("- {}", m.trim()))
+                                .collect::<Vec<_>>()
+                                .join("\n");
+                        }
+                        Err(e) => warn!("Failed to load memories for runtime context: {}", e),
+                    }
+                }
+                RuntimeContextItem::Artifacts => {
+                    let artifacts = crate::tools::load_artifact::thread_artifacts(context).await;
+                    data.artifacts = crate::tools::load_artifact::describe_artifacts(
+                        &artifacts[..artifacts.len().min(MAX_RUNTIME_ARTIFACTS)],
+                    );
+                }
+            }
+        }
+        data
     }
 
     fn reasoning_depth_name(strategy: &crate::types::AgentStrategy) -> &'static str {
@@ -445,6 +654,52 @@ impl<'a> MessageFormatter<'a> {
         }
     }
 
+    /// Variables of the run's thread (empty when there is no thread store).
+    async fn load_thread_variables(context: &Arc<ExecutorContext>) -> BTreeMap<String, String> {
+        let thread_store = context
+            .stores
+            .as_ref()
+            .map(|s| s.thread_store.clone())
+            .or_else(|| {
+                context
+                    .orchestrator
+                    .as_ref()
+                    .map(|o| o.stores.thread_store.clone())
+            });
+        let Some(store) = thread_store else {
+            return BTreeMap::new();
+        };
+        match store.get_thread(&context.thread_id).await {
+            Ok(thread) => thread.map(|t| t.variables).unwrap_or_default(),
+            Err(e) => {
+                warn!("Failed to load thread variables: {}", e);
+                BTreeMap::new()
+            }
+        }
+    }
+
+    /// Render the thread's `{{variables}}` in the text of user messages. The
+    /// stored messages keep their placeholders, so a variable changed later
+    /// applies to the whole history.
+    fn render_thread_variables(
+        messages: &mut [crate::types::Message],
+        variables: &BTreeMap<String, String>,
+    ) {
+        if variables.is_empty() {
+            return;
+        }
+        for message in messages
+            .iter_mut()
+            .filter(|m| matches!(m.role, MessageRole::User))
+        {
+            for part in &mut message.parts {
+                if let Part::Text(text) = part {
+                    *text = PromptRegistry::render_variables(text, variables);
+                }
+            }
+        }
+    }
+
     async fn load_task_user_messages(context: &Arc<ExecutorContext>) -> Vec<crate::types::Message> {
         let Ok(history) = context.get_conversation_history().await else {
             return Vec::new();
@@ -397,6 +397,37 @@ async fn non_openai_prefers_system_and_user_only() {
     assert!(user_text.contains("user_templ"));
 }
 
+#[tokio::test]
+async fn system_prompt_puts_instructions_before_framework_layer() {
+    let agent_def = base_agent_definition(ModelProvider::OpenAI {}, ToolCallFormat::Provider);
+    let strategy = AgentStrategy::default();
+    let formatter = MessageFormatter::new(&agent_def, &strategy);
+    let context = Arc::new(ExecutorContext::default());
+    let user_msg = Message::user("Plan".to_string(), None);
+
+    let (messages, _, layers) = formatter
+        .build_messages_with_layers(&user_msg, &context, "tmpl", "user_templ", None)
+        .await
+        .expect("formatter should succeed");
+    let kinds: Vec<_> = layers.iter().map(|l| l.kind).collect();
+    assert_eq!(
+        kinds,
+        vec![PromptLayerKind::Persona, PromptLayerKind::Framework]
+    );
+    assert_eq!(
+        messages[0].as_text().unwrap_or_default(),
+        "Be helpful\n\ntmpl"
+    );
+
+    // Opting out of default instructions leaves only the persona.
+    let (messages, _, layers) = formatter
+        .build_messages_with_layers(&user_msg, &context, "", "user_templ", None)
+        .await
+        .expect("formatter should succeed");
+    assert_eq!(layers.len(), 1);
+    assert_eq!(messages[0].as_text().unwrap_or_default(), "Be helpful");
+}
+
 #[test]
 fn collect_tool_prompts_skips_deferred() {
     let defs = vec![
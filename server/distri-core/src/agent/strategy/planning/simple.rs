@@ -107,6 +107,7 @@ impl PlanningStrategy for SimplePlanner {
 
         let mut plan_config = crate::types::PlanConfig::default();
         plan_config.model_settings = self.agent_def.model_settings().cloned();
+        plan_config.model_fallbacks = self.agent_def.fallback_model_settings();
 
         let mut messages = vec![Message::system(prompt, None)];
         // Only include additional user message if has images
@@ -190,6 +191,7 @@ impl PlanningStrategy for SimplePlanner {
 
         let mut plan_config = crate::types::PlanConfig::default();
         plan_config.model_settings = self.agent_def.model_settings().cloned();
+        plan_config.model_fallbacks = self.agent_def.fallback_model_settings();
 
         let response = self
             .llm_stream(
@@ -102,13 +102,28 @@ pub trait PlanningStrategy: Send + Sync + std::fmt::Debug {
     ) -> Result<Box<dyn crate::llm::LLMExecutorTrait>, AgentError> {
         let agent_name = context.agent_id.clone();
         let tools = context.get_tools_for_llm().await;
-        crate::llm::create_llm_executor(
-            get_planning_definition(agent_name, plan_config.model_settings.clone(), format),
-            tools,
-            context,
+        let executor = crate::llm::create_llm_executor(
+            get_planning_definition(
+                agent_name.clone(),
+                plan_config.model_settings.clone(),
+                format.clone(),
+            ),
+            tools.clone(),
+            context.clone(),
             None,
             None,
-        )
+        )?;
+        if plan_config.model_fallbacks.is_empty() {
+            return Ok(executor);
+        }
+        let fallbacks = plan_config
+            .model_fallbacks
+            .iter()
+            .map(|ms| get_planning_definition(agent_name.clone(), Some(ms.clone()), format.clone()))
+            .collect();
+        Ok(Box::new(crate::llm_fallback::FallbackLlmExecutor::new(
+            executor, fallbacks, tools, context,
+        )))
     }
 
     async fn llm(
@@ -1,9 +1,16 @@
 use std::sync::Arc;
 
 use distri_stores::SessionStoreExt;
-use distri_types::{AgentPlan, ExecutionResult, ExecutionStatus};
+use distri_types::{
+    prompt::PromptLayer,
+    prompt_locale::{detect_locale, profile_locale},
+    AgentEventType, AgentPlan, ExecutionResult, ExecutionStatus,
+};
 
-use crate::{agent::ExecutorContext, agent::PlanningStrategy, AgentError};
+use crate::{
+    agent::{token_estimator::TokenEstimator, ExecutorContext, PlanningStrategy},
+    AgentError,
+};
 
 use super::formatter::MessageFormatter;
 
@@ -68,15 +75,20 @@ impl UnifiedPlanner {
 
         cleaned.trim().to_string()
     }
-    /// Get a template from the prompt registry
+    /// Get a template from the prompt registry, in the variant for `locale`
+    /// when it has one.
     async fn get_template_from_registry(
         &self,
         context: &Arc<ExecutorContext>,
         template_name: &str,
+        locale: Option<&str>,
     ) -> Result<String, AgentError> {
         if let Some(orchestrator) = &context.orchestrator {
-            if let Some(template) = orchestrator.get_prompt_template(template_name).await {
-                tracing::debug!("Using template '{}' from prompt registry", template_name);
+            if let Some(template) = orchestrator
+                .get_localized_prompt_template(template_name, locale)
+                .await
+            {
+                tracing::debug!("Using template '{}' from prompt registry", template.name);
                 return Ok(template.content);
             }
         }
@@ -94,24 +106,76 @@ impl UnifiedPlanner {
         message: &crate::types::Message,
         context: &Arc<ExecutorContext>,
     ) -> Result<Vec<crate::types::Message>, AgentError> {
-        // Determine template based on prompt strategy (same logic as in plan method)
-        let user_template = self.get_template_from_registry(&context, "user").await?;
-        let template = match self.agent_def.append_default_instructions {
-            Some(false) => self.agent_def.instructions.clone(),
+        let (messages, _budget) = self.generate_prompt_with_budget(message, context).await?;
+        Ok(messages)
+    }
+
+    /// Like [`Self::generate_prompt_for_display`], but also returns the
+    /// per-section token budget of the assembled prompt.
+    pub async fn generate_prompt_with_budget(
+        &self,
+        message: &crate::types::Message,
+        context: &Arc<ExecutorContext>,
+    ) -> Result<(Vec<crate::types::Message>, distri_types::ContextBudget), AgentError> {
+        let (messages, budget, _layers) =
+            self.generate_prompt_with_layers(message, context).await?;
+        Ok((messages, budget))
+    }
+
+    /// Like [`Self::generate_prompt_with_budget`], but also returns the
+    /// rendered layers the system prompt was composed from.
+    pub async fn generate_prompt_with_layers(
+        &self,
+        message: &crate::types::Message,
+        context: &Arc<ExecutorContext>,
+    ) -> Result<
+        (
+            Vec<crate::types::Message>,
+            distri_types::ContextBudget,
+            Vec<PromptLayer>,
+        ),
+        AgentError,
+    > {
+        let locale = self.prompt_locale(message, context).await;
+        let user_template = self
+            .get_template_from_registry(context, "user", locale.as_deref())
+            .await?;
+        let template = self.framework_template(context, locale.as_deref()).await?;
+        self.build_messages(message, context, &template, &user_template)
+            .await
+    }
+
+    /// The framework layer of the system prompt: the built-in planning
+    /// template, or nothing when the agent opted out of default instructions.
+    async fn framework_template(
+        &self,
+        context: &Arc<ExecutorContext>,
+        locale: Option<&str>,
+    ) -> Result<String, AgentError> {
+        match self.agent_def.append_default_instructions {
+            Some(false) => Ok(String::new()),
             _ => {
-                let mut instructions = self.agent_def.instructions.clone();
-                let planning_template =
-                    self.get_template_from_registry(context, "planning").await?;
-                instructions.push_str(&format!("\n\n{}", planning_template));
-                instructions
+                self.get_template_from_registry(context, "planning", locale)
+                    .await
             }
-        };
+        }
+    }
 
-        // Call the private build_planning_prompt method (discard the budget for display purposes)
-        let (messages, _budget) = self
-            .build_messages(message, context, &template, &user_template)
-            .await?;
-        Ok(messages)
+    /// The locale to render prompt templates in: the user profile's
+    /// `locale` or `language`, else the language the message is written in.
+    async fn prompt_locale(
+        &self,
+        message: &crate::types::Message,
+        context: &Arc<ExecutorContext>,
+    ) -> Option<String> {
+        let profile = context.user_profile().await;
+        profile.as_ref().and_then(profile_locale).or_else(|| {
+            message
+                .as_text()
+                .as_deref()
+                .and_then(detect_locale)
+                .map(str::to_string)
+        })
     }
 
     /// Shared function to format TODOs from context using session values
@@ -140,6 +204,10 @@ impl UnifiedPlanner {
 
 const MAX_RETRIES: usize = 2;
 
+/// Sent after a response cut off at the output budget when `auto_continue`
+/// allows another attempt.
+const CONTINUE_PROMPT: &str = "Your previous response was cut off because it reached the output limit. Continue exactly where you stopped, without repeating what you already wrote.";
+
 #[async_trait::async_trait]
 impl PlanningStrategy for UnifiedPlanner {
     async fn build_summary_executor(
@@ -179,20 +247,13 @@ impl PlanningStrategy for UnifiedPlanner {
                 code_planner.plan(message, context).await
             }
             crate::types::ExecutionMode::Tools => {
-                let user_template = self.get_template_from_registry(&context, "user").await?;
-                let template = match self.agent_def.append_default_instructions {
-                    Some(false) => self.agent_def.instructions.clone(),
-                    _ => {
-                        let mut instructions = self.agent_def.instructions.clone();
-                        let planning_template = self
-                            .get_template_from_registry(&context, "planning")
-                            .await?;
-                        instructions.push_str(&format!("\n\n{}", planning_template));
-                        instructions
-                    }
-                };
+                let locale = self.prompt_locale(message, &context).await;
+                let user_template = self
+                    .get_template_from_registry(&context, "user", locale.as_deref())
+                    .await?;
+                let template = self.framework_template(&context, locale.as_deref()).await?;
                 // Build planning prompt with agent instructions and context
-                let (mut messages, context_budget) = self
+                let (mut messages, context_budget, _layers) = self
                     .build_messages(message, &context, &template, &user_template)
                     .await?;
                 context.update_context_budget(context_budget).await;
@@ -202,9 +263,17 @@ impl PlanningStrategy for UnifiedPlanner {
                 // Get LLM response with retry logic for XML parsing failures
                 let mut plan_config = crate::types::PlanConfig::default();
                 plan_config.model_settings = self.agent_def.model_settings().cloned();
+                plan_config.model_fallbacks = self.agent_def.fallback_model_settings();
                 // Ensure we use the agent's effective context size, not the default
-                if let Some(ref mut ms) = plan_config.model_settings {
+                for ms in plan_config
+                    .model_settings
+                    .iter_mut()
+                    .chain(plan_config.model_fallbacks.iter_mut())
+                {
                     ms.inner.context_size = Some(self.agent_def.get_effective_context_size());
+                    if let Some(max) = self.agent_def.max_output_tokens {
+                        ms.inner.max_tokens = Some(ms.inner.max_tokens.map_or(max, |m| m.min(max)));
+                    }
                 }
 
                 let response = {
@@ -213,17 +282,24 @@ impl PlanningStrategy for UnifiedPlanner {
                         attempt += 1;
 
                         match self
-                            .llm_stream(
-                                &messages,
-                                &plan_config,
-                                context.clone(),
-                                self.agent_def.tool_format.clone(),
-                            )
+                            .stream_with_continuation(&messages, &plan_config, &context)
                             .await
                         {
                             Ok(response) => {
                                 if !response.tool_calls.is_empty() {
                                     break Ok(response);
+                                } else if response.finish_reason
+                                    == async_openai::types::chat::FinishReason::Length
+                                {
+                                    // Truncated text with no continuation left:
+                                    // retrying would only produce another
+                                    // over-long answer, so keep what we have.
+                                    context
+                                        .set_final_result(Some(serde_json::Value::String(
+                                            response.content.clone(),
+                                        )))
+                                        .await;
+                                    break Ok(response);
                                 } else if attempt < MAX_RETRIES {
                                     let err = "You always need to return tool calls in the response, but got:";
                                     messages.push(crate::types::Message::assistant(
@@ -284,6 +360,30 @@ impl PlanningStrategy for UnifiedPlanner {
                                 ));
                                 continue;
                             }
+                            Err(AgentError::StructuredOutput(content, err)) => {
+                                // The stream was aborted at the divergence;
+                                // ask again with the reason.
+                                let retrying = attempt < MAX_RETRIES;
+                                context
+                                    .emit(AgentEventType::StructuredOutputRejected {
+                                        error: err.clone(),
+                                        attempt,
+                                        retrying,
+                                    })
+                                    .await;
+                                if !retrying {
+                                    break Err(AgentError::StructuredOutput(content, err));
+                                }
+                                messages.push(crate::types::Message::assistant(content, None));
+                                messages.push(crate::types::Message::user(
+                                    format!(
+                                        "Your response does not match the required JSON schema: {}. Reply again with only JSON that matches it.",
+                                        err
+                                    ),
+                                    None,
+                                ));
+                                continue;
+                            }
                             Err(e) => {
                                 break Err(e);
                             }
@@ -344,15 +444,106 @@ impl PlanningStrategy for UnifiedPlanner {
 }
 
 impl UnifiedPlanner {
+    /// Stream a planning response. While it is cut off at the output budget,
+    /// emit `Truncated` and, if the agent's `auto_continue` allows, ask the
+    /// model to resume, joining the pieces into one response.
+    async fn stream_with_continuation(
+        &self,
+        messages: &[crate::types::Message],
+        plan_config: &crate::types::PlanConfig,
+        context: &Arc<ExecutorContext>,
+    ) -> Result<crate::llm::StreamResult, AgentError> {
+        let max_continuations = self.agent_def.auto_continue.unwrap_or(0);
+        let max_output_tokens = plan_config
+            .model_settings
+            .as_ref()
+            .and_then(|ms| ms.inner.max_tokens);
+        let mut messages = messages.to_vec();
+        let mut response = self
+            .llm_stream(
+                &messages,
+                plan_config,
+                context.clone(),
+                self.agent_def.tool_format.clone(),
+            )
+            .await?;
+
+        let mut continuation = 0;
+        loop {
+            if response.finish_reason != async_openai::types::chat::FinishReason::Length {
+                if continuation > 0 {
+                    context.record_truncation(false, false).await;
+                }
+                return Ok(response);
+            }
+
+            // Complete tool calls are executed as they are; only text is resumed.
+            let continuing = response.tool_calls.is_empty() && continuation < max_continuations;
+            context
+                .emit(AgentEventType::Truncated {
+                    message_id: context.get_current_message_id().await.unwrap_or_default(),
+                    output_tokens: TokenEstimator::rough_token_count(&response.content) as u32,
+                    max_output_tokens,
+                    continuation,
+                    continuing,
+                })
+                .await;
+            context.record_truncation(true, continuing).await;
+            if !continuing {
+                return Ok(response);
+            }
+
+            continuation += 1;
+            messages.push(crate::types::Message::assistant(
+                response.content.clone(),
+                None,
+            ));
+            messages.push(crate::types::Message::user(
+                CONTINUE_PROMPT.to_string(),
+                None,
+            ));
+            let next = self
+                .llm_stream(
+                    &messages,
+                    plan_config,
+                    context.clone(),
+                    self.agent_def.tool_format.clone(),
+                )
+                .await?;
+            response = crate::llm::StreamResult {
+                finish_reason: next.finish_reason,
+                tool_calls: next.tool_calls,
+                content: response.content + &next.content,
+                usage: match (response.usage, next.usage) {
+                    (Some(a), Some(b)) => Some(distri_types::TokenUsage {
+                        input_tokens: a.input_tokens + b.input_tokens,
+                        output_tokens: a.output_tokens + b.output_tokens,
+                        total_tokens: a.total_tokens + b.total_tokens,
+                    }),
+                    (a, b) => a.or(b),
+                },
+                first_token_at: response.first_token_at.or(next.first_token_at),
+            };
+        }
+    }
+
     /// Build the complete planning prompt with context.
-    /// Returns the message list and a ContextBudget with per-component token estimates.
+    /// Returns the message list, a ContextBudget with per-component token
+    /// estimates, and the layers the system prompt was composed from.
     async fn build_messages(
         &self,
         message: &crate::types::Message,
         context: &Arc<ExecutorContext>,
         template: &str,
         user_template: &str,
-    ) -> Result<(Vec<crate::types::Message>, distri_types::ContextBudget), AgentError> {
+    ) -> Result<
+        (
+            Vec<crate::types::Message>,
+            distri_types::ContextBudget,
+            Vec<PromptLayer>,
+        ),
+        AgentError,
+    > {
         let todos = if self.agent_def.is_todos_enabled() {
             Self::format_todos_from_context(&context).await?
         } else {
@@ -361,7 +552,7 @@ impl UnifiedPlanner {
 
         let formatter = MessageFormatter::new(&self.agent_def, &self.strategy);
         formatter
-            .build_messages(message, context, template, user_template, todos)
+            .build_messages_with_layers(message, context, template, user_template, todos)
             .await
     }
 }
@@ -0,0 +1,103 @@
+//! Comparing two tasks of the same agent (see [`distri_types::task_diff`]).
+
+use distri_types::task_diff::{TaskComparison, TaskTrace};
+use distri_types::{MessageRole, Task, TaskEvent, TaskMessage};
+
+use crate::agent::orchestrator::AgentOrchestrator;
+use crate::AgentError;
+
+impl AgentOrchestrator {
+    /// Align `candidate` against `baseline` step by step. Both tasks must
+    /// have been run by the same agent.
+    pub async fn compare_tasks(
+        &self,
+        baseline: &str,
+        candidate: &str,
+    ) -> Result<TaskComparison, AgentError> {
+        let baseline = self.task_trace(baseline).await?;
+        let candidate = self.task_trace(candidate).await?;
+        if baseline.agent_id != candidate.agent_id {
+            return Err(AgentError::Validation(format!(
+                "tasks {} and {} were run by different agents ({} and {})",
+                baseline.task_id, candidate.task_id, baseline.agent_id, candidate.agent_id
+            )));
+        }
+        Ok(TaskComparison::new(baseline, candidate))
+    }
+
+    async fn task_trace(&self, task_id: &str) -> Result<TaskTrace, AgentError> {
+        let (task, agent_id, messages) = self.task_history(task_id).await?;
+        Ok(TaskTrace::from_history(&task, &agent_id, &messages))
+    }
+
+    /// `task_id`'s task, the agent that ran it and its messages and events,
+    /// oldest first.
+    pub(crate) async fn task_history(
+        &self,
+        task_id: &str,
+    ) -> Result<(Task, String, Vec<TaskMessage>), AgentError> {
+        let session = |e: anyhow::Error| AgentError::Session(e.to_string());
+        let task_store = &self.stores.task_store;
+        let task = task_store
+            .get_task(task_id)
+            .await
+            .map_err(session)?
+            .ok_or_else(|| AgentError::NotFound(format!("Task {} not found", task_id)))?;
+        // Restores the thread's history when it is archived.
+        let thread = self
+            .get_thread(&task.thread_id)
+            .await?
+            .ok_or_else(|| AgentError::NotFound(format!("Thread {} not found", task.thread_id)))?;
+
+        let history = task_store
+            .get_history(&task.thread_id, None)
+            .await
+            .map_err(session)?;
+        let messages = match history.into_iter().find(|(t, _)| t.id == task.id) {
+            Some((_, messages)) => messages,
+            // A task replaced by a regeneration is left out of the history.
+            None => {
+                self.archived_task_messages(&task.thread_id, &task.id)
+                    .await?
+            }
+        };
+
+        let agent_id = messages
+            .iter()
+            .find_map(|m| match m {
+                TaskMessage::Message(message) if message.role == MessageRole::Assistant => {
+                    message.agent_id.clone()
+                }
+                _ => None,
+            })
+            .unwrap_or(thread.agent_id);
+        Ok((task, agent_id, messages))
+    }
+
+    async fn archived_task_messages(
+        &self,
+        thread_id: &str,
+        task_id: &str,
+    ) -> Result<Vec<TaskMessage>, AgentError> {
+        let (_, mut stored) = self
+            .stores
+            .task_store
+            .export_thread_tasks(thread_id)
+            .await
+            .map_err(|e| AgentError::Session(e.to_string()))?;
+        stored.retain(|m| m.task_id == task_id);
+        stored.sort_by_key(|m| m.created_at);
+        Ok(stored
+            .into_iter()
+            .filter_map(|m| match m.kind.as_str() {
+                "message" => serde_json::from_value(m.payload)
+                    .ok()
+                    .map(TaskMessage::Message),
+                "event" => serde_json::from_value::<TaskEvent>(m.payload)
+                    .ok()
+                    .map(TaskMessage::Event),
+                _ => None,
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,111 @@
+//! Retrying a failed task (see [`distri_types::task_retry`]).
+//!
+//! The failed task's user message is run again as a new task that
+//! references it, with a summary of the failure appended to the agent's
+//! instructions.
+
+use std::sync::Arc;
+
+use distri_types::stores::CreateTaskInput;
+use distri_types::task_retry::{FailureSummary, RetryRequest, TaskRetry};
+use distri_types::{Message, MessageRole, ModelSettings, TaskMessage, TaskStatus};
+
+use crate::agent::orchestrator::AgentOrchestrator;
+use crate::agent::ExecutorContext;
+use crate::AgentError;
+
+impl AgentOrchestrator {
+    /// Run failed task `task_id` again with the agent that ran it.
+    pub async fn retry_task(
+        self: &Arc<Self>,
+        task_id: &str,
+        request: RetryRequest,
+        user_id: Option<String>,
+        model_settings: Option<ModelSettings>,
+    ) -> Result<TaskRetry, AgentError> {
+        let session = |e: anyhow::Error| AgentError::Session(e.to_string());
+        let (task, agent_id, messages) = self.task_history(task_id).await?;
+        if task.status != TaskStatus::Failed {
+            return Err(AgentError::Validation(format!(
+                "Task {} has not failed; only failed tasks can be retried",
+                task.id
+            )));
+        }
+        let prompt = messages
+            .iter()
+            .find_map(|m| match m {
+                TaskMessage::Message(message) if message.role == MessageRole::User => Some(message),
+                _ => None,
+            })
+            .ok_or_else(|| {
+                AgentError::Validation(format!("Task {} has no user message to run again", task.id))
+            })?;
+        let message = Message {
+            id: uuid::Uuid::new_v4().to_string(),
+            created_at: chrono::Utc::now().timestamp_millis(),
+            ..prompt.clone()
+        };
+
+        let failure = FailureSummary::from_history(&messages);
+        let mut overrides = request.overrides.unwrap_or_default();
+        overrides.instructions_append = Some(match overrides.instructions_append.take() {
+            Some(instructions) => format!("{}\n\n{}", instructions, failure.to_context()),
+            None => failure.to_context(),
+        });
+
+        let task_store = &self.stores.task_store;
+        let history = task_store
+            .get_history(&task.thread_id, None)
+            .await
+            .map_err(session)?;
+        let archived_task_ids = if history.last().is_some_and(|(last, _)| last.id == task.id) {
+            task_store
+                .archive_tasks_from(&task.thread_id, &task.id)
+                .await
+                .map_err(session)?
+        } else {
+            Vec::new()
+        };
+        let new_task = task_store
+            .create_task(
+                CreateTaskInput::local(&task.thread_id)
+                    .with_status(TaskStatus::Running)
+                    .with_reference_tasks(vec![task.id.clone()]),
+            )
+            .await
+            .map_err(session)?;
+        tracing::info!(
+            target: "task.retry",
+            retry_of = %task.id,
+            task_id = %new_task.id,
+            agent_id = %agent_id,
+            failed_tool_calls = failure.failed_tool_calls.len(),
+            "retrying failed task"
+        );
+
+        let mut context = ExecutorContext {
+            thread_id: task.thread_id.clone(),
+            task_id: new_task.id.clone(),
+            agent_id: agent_id.clone(),
+            orchestrator: Some(self.clone()),
+            default_model_settings: model_settings,
+            ..Default::default()
+        };
+        if let Some(user_id) = user_id {
+            context.user_id = user_id;
+        }
+        let result = self
+            .execute(&agent_id, message, Arc::new(context), Some(overrides))
+            .await?;
+
+        Ok(TaskRetry {
+            thread_id: task.thread_id,
+            task_id: new_task.id,
+            retry_of: task.id,
+            agent_id,
+            archived_task_ids,
+            failure,
+            content: result.content,
+        })
+    }
+}
@@ -0,0 +1,290 @@
+//! Thread archival (see [`distri_types::thread_archive`]).
+//!
+//! Archiving a thread writes `thread.jsonl` and `manifest.json` under
+//! `archive/threads/<thread id>/` of the session filesystem, marks the
+//! thread archived and deletes its tasks from the database. Restoring does
+//! the reverse; it happens on its own when an archived thread is read or
+//! sent a message. With `thread_archive` configured, [`Archiver`]
+//! periodically archives the threads not updated within `retention_days`.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::Utc;
+use distri_filesystem::ArtifactWrapper;
+use distri_types::filesystem::FileSystemOps;
+use distri_types::thread_archive::{
+    ThreadArchive, ThreadArchiveConfig, ThreadArchiveManifest, ThreadArchiveSummary,
+};
+use distri_types::Thread;
+
+use crate::agent::AgentOrchestrator;
+use crate::AgentError;
+
+const ARCHIVE_ROOT: &str = "archive/threads";
+const ARCHIVE_FILE: &str = "thread.jsonl";
+const MANIFEST_FILE: &str = "manifest.json";
+
+fn archive_dir(thread_id: &str) -> String {
+    format!("{}/{}", ARCHIVE_ROOT, thread_id)
+}
+
+fn storage(e: anyhow::Error) -> AgentError {
+    AgentError::Storage(e.to_string())
+}
+
+impl AgentOrchestrator {
+    /// Move the tasks and messages of `thread_id` to the object store.
+    pub async fn archive_thread(
+        &self,
+        thread_id: &str,
+    ) -> Result<ThreadArchiveSummary, AgentError> {
+        let _guard = self.thread_archive_lock.lock().await;
+        let thread = self.stored_thread(thread_id).await?;
+        if thread.archived_at.is_some() {
+            return Err(AgentError::Validation(format!(
+                "thread {} is already archived",
+                thread_id
+            )));
+        }
+        let task_store = &self.stores.task_store;
+        let running = task_store
+            .list_running_tasks(Some(thread_id))
+            .await
+            .map_err(storage)?;
+        if !running.is_empty() {
+            return Err(AgentError::Validation(format!(
+                "thread {} has running tasks",
+                thread_id
+            )));
+        }
+
+        let (tasks, messages) = task_store
+            .export_thread_tasks(thread_id)
+            .await
+            .map_err(storage)?;
+        let archived_at = Utc::now();
+        let manifest = ThreadArchiveManifest {
+            thread_id: thread_id.to_string(),
+            archived_at,
+            tasks: tasks.len(),
+            messages: messages.len(),
+            artifacts: self.thread_artifacts(thread_id).await,
+        };
+        let archived_task_ids: Vec<String> = tasks.iter().map(|t| t.task.id.clone()).collect();
+        let archive = ThreadArchive {
+            thread,
+            tasks,
+            messages,
+        };
+        let dir = archive_dir(thread_id);
+        let fs = &self.session_filesystem;
+        fs.write(
+            &format!("{}/{}", dir, ARCHIVE_FILE),
+            &archive.to_jsonl().map_err(storage)?,
+        )
+        .await
+        .map_err(storage)?;
+        fs.write(
+            &format!("{}/{}", dir, MANIFEST_FILE),
+            &serde_json::to_string_pretty(&manifest)?,
+        )
+        .await
+        .map_err(storage)?;
+
+        // Nothing leaves the database before the archive is written; a run
+        // started since the export keeps its task.
+        self.stores
+            .thread_store
+            .set_thread_archived(thread_id, Some(archived_at))
+            .await
+            .map_err(storage)?;
+        task_store
+            .delete_thread_tasks(thread_id, &archived_task_ids)
+            .await
+            .map_err(storage)?;
+        tracing::info!(
+            thread_id,
+            tasks = manifest.tasks,
+            messages = manifest.messages,
+            "thread archived"
+        );
+        Ok(ThreadArchiveSummary {
+            thread_id: thread_id.to_string(),
+            archived_at: Some(archived_at),
+            tasks: manifest.tasks,
+            messages: manifest.messages,
+            artifacts: manifest.artifacts.len(),
+        })
+    }
+
+    /// Bring an archived thread's tasks and messages back into the
+    /// database.
+    pub async fn restore_thread(
+        &self,
+        thread_id: &str,
+    ) -> Result<ThreadArchiveSummary, AgentError> {
+        let _guard = self.thread_archive_lock.lock().await;
+        if self.stored_thread(thread_id).await?.archived_at.is_none() {
+            return Err(AgentError::Validation(format!(
+                "thread {} is not archived",
+                thread_id
+            )));
+        }
+        self.restore_archive(thread_id).await
+    }
+
+    /// Restore `thread` if it is archived, before it is read or run.
+    pub async fn ensure_thread_restored(&self, thread: &mut Thread) -> Result<(), AgentError> {
+        if thread.archived_at.is_none() {
+            return Ok(());
+        }
+        let _guard = self.thread_archive_lock.lock().await;
+        // Another request may have restored it while this one waited.
+        if self.stored_thread(&thread.id).await?.archived_at.is_some() {
+            self.restore_archive(&thread.id).await?;
+        }
+        thread.archived_at = None;
+        Ok(())
+    }
+
+    async fn restore_archive(&self, thread_id: &str) -> Result<ThreadArchiveSummary, AgentError> {
+        let dir = archive_dir(thread_id);
+        let fs = &self.session_filesystem;
+        let text = fs
+            .read_raw(&format!("{}/{}", dir, ARCHIVE_FILE))
+            .await
+            .map_err(storage)?;
+        let archive = ThreadArchive::from_jsonl(&text).map_err(storage)?;
+        let artifacts = fs
+            .read_raw(&format!("{}/{}", dir, MANIFEST_FILE))
+            .await
+            .ok()
+            .and_then(|raw| serde_json::from_str::<ThreadArchiveManifest>(&raw).ok())
+            .map_or(0, |manifest| manifest.artifacts.len());
+
+        let task_store = &self.stores.task_store;
+        // Rows left by an interrupted archive or restore are replaced.
+        let archived_task_ids: Vec<String> =
+            archive.tasks.iter().map(|t| t.task.id.clone()).collect();
+        task_store
+            .delete_thread_tasks(thread_id, &archived_task_ids)
+            .await
+            .map_err(storage)?;
+        task_store
+            .import_thread_tasks(&archive.tasks, &archive.messages)
+            .await
+            .map_err(storage)?;
+        self.stores
+            .thread_store
+            .set_thread_archived(thread_id, None)
+            .await
+            .map_err(storage)?;
+        if let Err(e) = fs.delete(&dir, true).await {
+            tracing::warn!(thread_id, "failed to delete the thread's archive: {}", e);
+        }
+        tracing::info!(
+            thread_id,
+            tasks = archive.tasks.len(),
+            messages = archive.messages.len(),
+            "thread restored"
+        );
+        Ok(ThreadArchiveSummary {
+            thread_id: thread_id.to_string(),
+            archived_at: None,
+            tasks: archive.tasks.len(),
+            messages: archive.messages.len(),
+            artifacts,
+        })
+    }
+
+    /// The thread as stored, without restoring it.
+    async fn stored_thread(&self, thread_id: &str) -> Result<Thread, AgentError> {
+        self.stores
+            .thread_store
+            .get_thread(thread_id)
+            .await
+            .map_err(storage)?
+            .ok_or_else(|| AgentError::NotFound(format!("thread {}", thread_id)))
+    }
+
+    /// Object store paths of the files in the thread's artifact namespace.
+    async fn thread_artifacts(&self, thread_id: &str) -> Vec<String> {
+        let mut artifacts = Vec::new();
+        let mut dirs = vec![ArtifactWrapper::thread_namespace(thread_id)];
+        while let Some(dir) = dirs.pop() {
+            let Ok(listing) = self.session_filesystem.list(&dir).await else {
+                continue;
+            };
+            for entry in listing.entries {
+                let path = format!("{}/{}", dir, entry.name);
+                if entry.is_dir {
+                    dirs.push(path);
+                } else {
+                    artifacts.push(path);
+                }
+            }
+        }
+        artifacts.sort();
+        artifacts
+    }
+}
+
+/// Periodically archives the threads of an orchestrator not updated within
+/// the retention period.
+pub struct Archiver {
+    orchestrator: Arc<AgentOrchestrator>,
+    config: ThreadArchiveConfig,
+}
+
+impl Archiver {
+    /// `None` when thread archival is not configured.
+    pub fn new(orchestrator: Arc<AgentOrchestrator>) -> Option<Self> {
+        let config = orchestrator.thread_archive.clone()?;
+        Some(Self {
+            orchestrator,
+            config,
+        })
+    }
+
+    pub fn start(self) -> tokio::task::JoinHandle<()> {
+        tracing::info!(
+            retention_days = self.config.retention_days,
+            "thread archival enabled"
+        );
+        tokio::spawn(async move {
+            let interval = Duration::from_secs(self.config.sweep_interval_secs.max(1));
+            loop {
+                tokio::time::sleep(interval).await;
+                self.sweep().await;
+            }
+        })
+    }
+
+    /// Archive up to `batch_size` threads not updated for `retention_days`.
+    /// Returns how many were archived.
+    pub async fn sweep(&self) -> usize {
+        let before = Utc::now() - chrono::Duration::days(self.config.retention_days as i64);
+        let thread_ids = match self
+            .orchestrator
+            .stores
+            .thread_store
+            .list_archivable_threads(before, self.config.batch_size)
+            .await
+        {
+            Ok(ids) => ids,
+            Err(e) => {
+                tracing::warn!("failed to list threads to archive: {}", e);
+                return 0;
+            }
+        };
+        let mut archived = 0;
+        for thread_id in thread_ids {
+            match self.orchestrator.archive_thread(&thread_id).await {
+                Ok(_) => archived += 1,
+                Err(e) => tracing::warn!(thread_id = %thread_id, "failed to archive thread: {}", e),
+            }
+        }
+        archived
+    }
+}
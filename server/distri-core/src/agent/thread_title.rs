@@ -0,0 +1,222 @@
+//! Contextual thread titles.
+//!
+//! New threads are titled with their first message, truncated. Agents with
+//! `auto_title = true` replace that after the first exchange with a concise
+//! title and a few topical tags, generated by `analysis_model_settings` in the
+//! background. Tags are stored under [`THREAD_TAGS_KEY`] in the thread
+//! metadata so `/threads/search` can match them.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use distri_types::stores::ThreadStore;
+use distri_types::{
+    LlmDefinition, Message, ModelSettings, StandardDefinition, ToolCallFormat, UpdateThreadRequest,
+    THREAD_AUTO_TITLED_KEY, THREAD_TAGS_KEY,
+};
+use serde::Deserialize;
+
+use crate::agent::ExecutorContext;
+use crate::AgentError;
+
+const TITLE_PROMPT: &str = r#"Give this conversation a short title and up to 5 topical tags.
+
+Respond with JSON only, no prose:
+{"title": "<at most 8 words, no trailing punctuation>", "tags": ["<lowercase>", "..."]}"#;
+
+const MAX_TITLE_CHARS: usize = 80;
+const MAX_TAGS: usize = 5;
+/// Characters of each side of the exchange sent to the titling model.
+const MAX_EXCHANGE_CHARS: usize = 2000;
+
+#[derive(Debug, Default, Deserialize, PartialEq)]
+struct GeneratedTitle {
+    #[serde(default)]
+    title: String,
+    #[serde(default)]
+    tags: Vec<String>,
+}
+
+/// Title the thread in the background if the agent opted in and the thread
+/// has not been titled yet. Failures are logged; the run is never affected.
+pub(crate) fn spawn_auto_title(
+    definition: &StandardDefinition,
+    thread_store: Arc<dyn ThreadStore>,
+    context: &ExecutorContext,
+    user_message: String,
+    response: String,
+) {
+    if !definition.auto_title || user_message.trim().is_empty() || response.trim().is_empty() {
+        return;
+    }
+    let Some(model_settings) = definition.analysis_model_settings_config().cloned() else {
+        return;
+    };
+    let thread_id = context.thread_id.clone();
+    let user_id = context.user_id.clone();
+    let workspace_id = context.workspace_id.clone();
+    let ws_uuid = workspace_id
+        .as_deref()
+        .and_then(|s| uuid::Uuid::parse_str(s).ok());
+    let agent_name = definition.name.clone();
+
+    tokio::spawn(distri_auth::context::with_user_and_workspace(
+        user_id.clone(),
+        ws_uuid,
+        async move {
+            let ctx = ExecutorContext {
+                thread_id: thread_id.clone(),
+                user_id,
+                workspace_id,
+                agent_id: agent_name,
+                ..Default::default()
+            };
+            if let Err(e) = auto_title_thread(
+                thread_store,
+                model_settings,
+                Arc::new(ctx),
+                &user_message,
+                &response,
+            )
+            .await
+            {
+                tracing::warn!(thread_id = %thread_id, "auto-title failed: {}", e);
+            }
+        },
+    ));
+}
+
+async fn auto_title_thread(
+    thread_store: Arc<dyn ThreadStore>,
+    model_settings: ModelSettings,
+    context: Arc<ExecutorContext>,
+    user_message: &str,
+    response: &str,
+) -> Result<(), AgentError> {
+    let thread_id = context.thread_id.clone();
+    let thread = thread_store
+        .get_thread(&thread_id)
+        .await
+        .map_err(|e| AgentError::Session(e.to_string()))?;
+    // Only the first exchange is titled; later runs keep whatever is there.
+    match thread {
+        Some(t) if t.message_count <= 1 && !t.metadata.contains_key(THREAD_AUTO_TITLED_KEY) => {}
+        _ => return Ok(()),
+    }
+
+    let llm_def = LlmDefinition {
+        name: "thread_title".to_string(),
+        model_settings: Some(model_settings),
+        tool_format: ToolCallFormat::Provider,
+        tool_delivery_mode: Default::default(),
+    };
+    let executor = crate::llm::create_llm_executor(
+        llm_def,
+        vec![],
+        context,
+        None,
+        Some("thread_title".to_string()),
+    )?;
+    let messages = vec![
+        Message::system(TITLE_PROMPT.to_string(), None),
+        Message::user(
+            format!(
+                "User: {}\n\nAssistant: {}",
+                truncate(user_message, MAX_EXCHANGE_CHARS),
+                truncate(response, MAX_EXCHANGE_CHARS)
+            ),
+            None,
+        ),
+    ];
+    let generated = parse_generated_title(&executor.execute(&messages).await?.content);
+
+    let mut metadata = HashMap::new();
+    metadata.insert(THREAD_AUTO_TITLED_KEY.to_string(), serde_json::json!(true));
+    if !generated.tags.is_empty() {
+        metadata.insert(
+            THREAD_TAGS_KEY.to_string(),
+            serde_json::json!(generated.tags),
+        );
+    }
+    thread_store
+        .update_thread(
+            &thread_id,
+            UpdateThreadRequest {
+                title: Some(generated.title).filter(|t| !t.is_empty()),
+                metadata: Some(metadata),
+                attributes: None,
+                user_id: None,
+                variables: None,
+            },
+        )
+        .await
+        .map_err(|e| AgentError::Session(e.to_string()))?;
+    Ok(())
+}
+
+/// Parse the model's JSON reply, tolerating code fences and surrounding prose.
+/// Titles are trimmed and capped; tags are lowercased and deduplicated.
+fn parse_generated_title(raw: &str) -> GeneratedTitle {
+    let json = match (raw.find('{'), raw.rfind('}')) {
+        (Some(start), Some(end)) if start < end => &raw[start..=end],
+        _ => return GeneratedTitle::default(),
+    };
+    let mut generated: GeneratedTitle = serde_json::from_str(json).unwrap_or_default();
+
+    generated.title = truncate(
+        generated
+            .title
+            .trim()
+            .trim_matches('"')
+            .trim_end_matches(['.', '!', '?']),
+        MAX_TITLE_CHARS,
+    );
+
+    let mut tags: Vec<String> = Vec::new();
+    for tag in generated.tags {
+        let tag = tag.trim().trim_start_matches('#').to_lowercase();
+        if !tag.is_empty() && !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+    tags.truncate(MAX_TAGS);
+    generated.tags = tags;
+    generated
+}
+
+fn truncate(text: &str, max_chars: usize) -> String {
+    text.chars()
+        .take(max_chars)
+        .collect::<String>()
+        .trim()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_fenced_reply_and_normalizes_tags() {
+        let raw = "```json\n{\"title\": \"Debugging the login flow.\", \"tags\": [\"Auth\", \"#bugfix\", \"auth\", \" \"]}\n```";
+        assert_eq!(
+            parse_generated_title(raw),
+            GeneratedTitle {
+                title: "Debugging the login flow".to_string(),
+                tags: vec!["auth".to_string(), "bugfix".to_string()],
+            }
+        );
+    }
+
+    #[test]
+    fn unparseable_reply_yields_nothing() {
+        assert_eq!(
+            parse_generated_title("Sure! Here's a title: Login"),
+            GeneratedTitle::default()
+        );
+        assert_eq!(
+            parse_generated_title("{not json}"),
+            GeneratedTitle::default()
+        );
+    }
+}
@@ -1,2 +1,3 @@
+mod queue;
 mod tools;
-pub use tools::TodosTool;
+pub use tools::{TodosTool, WorkTodosTool};
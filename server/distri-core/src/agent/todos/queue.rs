@@ -0,0 +1,171 @@
+//! Todo queue execution.
+//!
+//! `write_todos` only records a plan. [`AgentOrchestrator::work_todo_queue`]
+//! executes it: it repeatedly takes the next ready item
+//! ([`TodoList::next_ready`] — open, dependencies done, highest priority),
+//! runs it as an ad-hoc sub-task via [`AgentOrchestrator::invoke`], and
+//! records the outcome on the item. Every status change is persisted and
+//! emitted as `TodosUpdated`, and the run ends with a `TodoQueueFinished`
+//! summary.
+//!
+//! Items whose dependencies failed (or never resolve) stay open and are
+//! reported as blocked.
+
+use std::sync::Arc;
+
+use distri_stores::SessionStoreExt;
+use distri_types::invocation::{Invocation, InvocationResult, Target};
+use distri_types::{
+    AgentEventType, Message, TaskStatus, TodoList, TodoOutcome, TodoQueueSummary, TodoStatus,
+};
+
+use crate::agent::orchestrator::AgentOrchestrator;
+use crate::agent::ExecutorContext;
+use crate::AgentError;
+
+/// Session key `write_todos` stores the list under.
+const TODOS_KEY: &str = "todos";
+
+const WORKER_PROMPT: &str = "You are working one item of a larger todo list. \
+Complete only the item you are given, then call `final` with a short report of \
+what you did and anything the next items need to know.";
+
+impl AgentOrchestrator {
+    /// Work the todo queue of `parent_ctx`'s task until no item is ready.
+    pub async fn work_todo_queue(
+        self: &Arc<Self>,
+        parent_ctx: &Arc<ExecutorContext>,
+    ) -> Result<TodoQueueSummary, AgentError> {
+        let mut todos = load_todos(parent_ctx).await?;
+        if let Some((item, dep)) = todos.unknown_dependencies().into_iter().next() {
+            return Err(AgentError::Validation(format!(
+                "todo '{}' depends on unknown todo '{}'",
+                item, dep
+            )));
+        }
+
+        let mut summary = TodoQueueSummary::default();
+        while let Some(item) = todos.next_ready().cloned() {
+            todos.update(&item.id, None, None, Some(TodoStatus::InProgress));
+            save_todos(parent_ctx, &todos).await?;
+
+            let message = Message::user(worker_brief(&todos, &item.id), None);
+            let invocation = Invocation::single(Target::adhoc(WORKER_PROMPT, message));
+            let (status, task_id, result) = match self.invoke(invocation, parent_ctx.clone()).await
+            {
+                Ok(InvocationResult::Scalar { result }) => {
+                    let report = match &result.content {
+                        serde_json::Value::Null => None,
+                        serde_json::Value::String(s) => Some(s.clone()),
+                        other => Some(other.to_string()),
+                    };
+                    let status = if result.status == TaskStatus::Completed {
+                        TodoStatus::Done
+                    } else {
+                        TodoStatus::Failed
+                    };
+                    (status, Some(result.task_id), report)
+                }
+                Ok(other) => (
+                    TodoStatus::Failed,
+                    None,
+                    Some(format!("unexpected invocation result: {:?}", other)),
+                ),
+                Err(e) => (TodoStatus::Failed, None, Some(e.to_string())),
+            };
+
+            if let Some(entry) = todos.items.iter_mut().find(|i| i.id == item.id) {
+                entry.task_id = task_id.clone();
+                entry.result = result.clone();
+            }
+            todos.update(&item.id, None, None, Some(status.clone()));
+            save_todos(parent_ctx, &todos).await?;
+
+            let outcome = TodoOutcome {
+                title: item.title,
+                task_id,
+                result,
+            };
+            if status == TodoStatus::Done {
+                summary.completed.push(outcome);
+            } else {
+                summary.failed.push(outcome);
+            }
+        }
+
+        summary.blocked = todos
+            .get_by_status(TodoStatus::Open)
+            .into_iter()
+            .map(|item| item.title.clone())
+            .collect();
+
+        parent_ctx
+            .emit(AgentEventType::TodoQueueFinished {
+                summary: summary.clone(),
+            })
+            .await;
+        Ok(summary)
+    }
+}
+
+/// The sub-task's first message: the item, plus what its dependencies
+/// reported.
+fn worker_brief(todos: &TodoList, item_id: &str) -> String {
+    let Some(item) = todos.items.iter().find(|i| i.id == item_id) else {
+        return String::new();
+    };
+    let mut brief = format!("Todo: {}", item.title);
+    if let Some(notes) = item.notes.as_deref().filter(|n| !n.trim().is_empty()) {
+        brief.push_str(&format!("\n\nNotes: {}", notes));
+    }
+    let reports: Vec<String> = item
+        .depends_on
+        .iter()
+        .filter_map(|dep| todos.items.iter().find(|other| &other.title == dep))
+        .filter_map(|dep| {
+            dep.result
+                .as_ref()
+                .map(|result| format!("- {}: {}", dep.title, result))
+        })
+        .collect();
+    if !reports.is_empty() {
+        brief.push_str("\n\nResults of the todos this one depends on:\n");
+        brief.push_str(&reports.join("\n"));
+    }
+    brief
+}
+
+/// The todo list lives on the root task, shared with `write_todos`.
+fn todos_task_id(context: &ExecutorContext) -> &str {
+    context
+        .parent_task_id
+        .as_deref()
+        .unwrap_or(&context.task_id)
+}
+
+async fn load_todos(context: &Arc<ExecutorContext>) -> Result<TodoList, AgentError> {
+    context
+        .get_session_store()?
+        .get(todos_task_id(context), TODOS_KEY)
+        .await
+        .map_err(|e| AgentError::Session(format!("Failed to get todos from session: {}", e)))
+        .map(Option::unwrap_or_default)
+}
+
+async fn save_todos(context: &Arc<ExecutorContext>, todos: &TodoList) -> Result<(), AgentError> {
+    let prev = load_todos(context).await.unwrap_or_default();
+    context
+        .get_session_store()?
+        .set(todos_task_id(context), TODOS_KEY, todos)
+        .await
+        .map_err(|e| AgentError::Session(format!("Failed to set todos in session: {}", e)))?;
+    context
+        .emit(AgentEventType::TodosUpdated {
+            formatted_todos: todos.format_display(),
+            action: "work_todos".to_string(),
+            todo_count: todos.items.len(),
+            changes: todos.diff_against(&prev),
+        })
+        .await;
+    Ok(())
+}
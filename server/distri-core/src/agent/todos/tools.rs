@@ -37,8 +37,18 @@ impl Tool for TodosTool {
                             },
                             "status": {
                                 "type": "string",
-                                "enum": ["open", "in_progress", "done"],
+                                "enum": ["open", "in_progress", "done", "failed"],
                                 "description": "Optional status override. Defaults to 'open'."
+                            },
+                            "priority": {
+                                "type": "string",
+                                "enum": ["low", "medium", "high"],
+                                "description": "Order for work_todos. Defaults to 'medium'."
+                            },
+                            "depends_on": {
+                                "type": "array",
+                                "items": { "type": "string" },
+                                "description": "Content of the TODOs that must be done before this one."
                             }
                         },
                         "required": ["content"]
@@ -124,3 +134,59 @@ impl ExecutorContextTool for TodosTool {
         Ok(vec![distri_types::Part::Data(Value::Null)])
     }
 }
+
+/// Works the todo queue: runs each ready todo as a sub-task, in priority
+/// and dependency order, and returns the summary.
+#[derive(Debug, Clone)]
+pub struct WorkTodosTool;
+
+#[async_trait::async_trait]
+impl Tool for WorkTodosTool {
+    fn get_name(&self) -> String {
+        "work_todos".to_string()
+    }
+
+    fn get_parameters(&self) -> Value {
+        json!({
+            "type": "object",
+            "additionalProperties": false,
+            "properties": {}
+        })
+    }
+
+    fn get_description(&self) -> String {
+        "Execute the current TODO list. Each open TODO whose dependencies are done runs as a sub-task, highest priority first; statuses are updated as they finish. Returns a summary of completed, failed and blocked TODOs."
+            .to_string()
+    }
+
+    fn needs_executor_context(&self) -> bool {
+        true
+    }
+
+    async fn execute(
+        &self,
+        _tool_call: ToolCall,
+        _context: Arc<ToolContext>,
+    ) -> Result<Vec<distri_types::Part>, anyhow::Error> {
+        Err(anyhow::anyhow!(
+            "WorkTodosTool requires ExecutorContext, not ToolContext"
+        ))
+    }
+}
+
+#[async_trait::async_trait]
+impl ExecutorContextTool for WorkTodosTool {
+    async fn execute_with_executor_context(
+        &self,
+        _tool_call: ToolCall,
+        context: Arc<ExecutorContext>,
+    ) -> Result<Vec<distri_types::Part>, AgentError> {
+        let orchestrator = context.orchestrator.clone().ok_or_else(|| {
+            AgentError::ToolExecution("work_todos requires an orchestrator".into())
+        })?;
+        let summary = orchestrator.work_todo_queue(&context).await?;
+        Ok(vec![distri_types::Part::Data(
+            serde_json::to_value(&summary).unwrap_or(Value::Null),
+        )])
+    }
+}
@@ -0,0 +1,183 @@
+//! Per-model token counting.
+//!
+//! [`tokenizer_for`] picks a model's tokenizer by name: OpenAI's BPE
+//! encodings for GPT and o-series models, and a calibrated
+//! characters-per-token ratio for families whose tokenizer is not public.
+//! Models with no entry fall back to [`TokenEstimator::rough_token_count`].
+//! [`register_tokenizer`] adds or overrides entries.
+
+use std::sync::{Arc, OnceLock, RwLock};
+
+use tiktoken_rs::CoreBPE;
+
+use crate::agent::token_estimator::TokenEstimator;
+
+/// Counts the tokens a model sees for a piece of text.
+pub trait Tokenizer: Send + Sync {
+    fn count(&self, text: &str) -> usize;
+
+    /// Whether `count` is the model's own count rather than an estimate.
+    fn is_exact(&self) -> bool {
+        false
+    }
+}
+
+/// A BPE encoding shipped with `tiktoken`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BpeEncoding {
+    /// GPT-4o, GPT-4.1, GPT-5 and the o-series.
+    O200kBase,
+    /// GPT-4 and GPT-3.5.
+    Cl100kBase,
+}
+
+impl BpeEncoding {
+    /// The loaded encoding, or `None` when it failed to load.
+    fn bpe(self) -> Option<&'static CoreBPE> {
+        static O200K: OnceLock<Option<CoreBPE>> = OnceLock::new();
+        static CL100K: OnceLock<Option<CoreBPE>> = OnceLock::new();
+        let (cell, load): (_, fn() -> anyhow::Result<CoreBPE>) = match self {
+            BpeEncoding::O200kBase => (&O200K, tiktoken_rs::o200k_base),
+            BpeEncoding::Cl100kBase => (&CL100K, tiktoken_rs::cl100k_base),
+        };
+        cell.get_or_init(|| {
+            load()
+                .inspect_err(|e| tracing::warn!("failed to load {:?} encoding: {}", self, e))
+                .ok()
+        })
+        .as_ref()
+    }
+}
+
+/// Exact counts from a BPE encoding.
+#[derive(Debug, Clone, Copy)]
+pub struct BpeTokenizer(pub BpeEncoding);
+
+impl Tokenizer for BpeTokenizer {
+    fn count(&self, text: &str) -> usize {
+        match self.0.bpe() {
+            Some(bpe) => bpe.encode_with_special_tokens(text).len(),
+            None => TokenEstimator::rough_token_count(text),
+        }
+    }
+
+    fn is_exact(&self) -> bool {
+        self.0.bpe().is_some()
+    }
+}
+
+/// Estimates from an average number of characters per token.
+#[derive(Debug, Clone, Copy)]
+pub struct RatioTokenizer {
+    pub chars_per_token: f64,
+}
+
+impl Tokenizer for RatioTokenizer {
+    fn count(&self, text: &str) -> usize {
+        (text.chars().count() as f64 / self.chars_per_token).ceil() as usize
+    }
+}
+
+type Entries = Vec<(String, Arc<dyn Tokenizer>)>;
+
+fn registry() -> &'static RwLock<Entries> {
+    static REGISTRY: OnceLock<RwLock<Entries>> = OnceLock::new();
+    REGISTRY.get_or_init(|| {
+        let o200k: Arc<dyn Tokenizer> = Arc::new(BpeTokenizer(BpeEncoding::O200kBase));
+        let cl100k: Arc<dyn Tokenizer> = Arc::new(BpeTokenizer(BpeEncoding::Cl100kBase));
+        let mut entries: Entries = ["gpt-4o", "gpt-4.1", "gpt-4.5", "gpt-5", "o1", "o3", "o4"]
+            .into_iter()
+            .map(|prefix| (prefix.to_string(), o200k.clone()))
+            .collect();
+        entries.extend(
+            ["gpt-4", "gpt-3.5"]
+                .into_iter()
+                .map(|prefix| (prefix.to_string(), cl100k.clone())),
+        );
+        // Anthropic's and Google's tokenizers split text finer than
+        // OpenAI's; these ratios lean towards over-counting.
+        entries.push((
+            "claude".to_string(),
+            Arc::new(RatioTokenizer {
+                chars_per_token: 3.2,
+            }),
+        ));
+        entries.push((
+            "gemini".to_string(),
+            Arc::new(RatioTokenizer {
+                chars_per_token: 3.6,
+            }),
+        ));
+        RwLock::new(entries)
+    })
+}
+
+/// Use `tokenizer` for models whose name starts with `prefix`, replacing
+/// any tokenizer registered for the same prefix.
+pub fn register_tokenizer(prefix: impl Into<String>, tokenizer: Arc<dyn Tokenizer>) {
+    let prefix = prefix.into().to_lowercase();
+    let mut entries = registry().write().unwrap_or_else(|e| e.into_inner());
+    entries.retain(|(existing, _)| *existing != prefix);
+    entries.push((prefix, tokenizer));
+}
+
+/// The tokenizer of `model`: the entry with the longest prefix of its
+/// name. Vendor and region qualifiers (`openai/gpt-4o`,
+/// `us.anthropic.claude-…`) are looked through.
+pub fn tokenizer_for(model: &str) -> Arc<dyn Tokenizer> {
+    let model = model.to_lowercase();
+    let entries = registry().read().unwrap_or_else(|e| e.into_inner());
+    entries
+        .iter()
+        .filter(|(prefix, _)| {
+            model.starts_with(prefix.as_str())
+                || model.contains(&format!("/{}", prefix))
+                || model.contains(&format!(".{}", prefix))
+        })
+        .max_by_key(|(prefix, _)| prefix.len())
+        .map(|(_, tokenizer)| tokenizer.clone())
+        .unwrap_or_else(|| Arc::new(RoughTokenizer))
+}
+
+/// The ~4 characters per token fallback.
+struct RoughTokenizer;
+
+impl Tokenizer for RoughTokenizer {
+    fn count(&self, text: &str) -> usize {
+        TokenEstimator::rough_token_count(text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn openai_models_are_counted_exactly() {
+        let tokenizer = tokenizer_for("gpt-4o-mini");
+        assert!(tokenizer.is_exact());
+        assert_eq!(tokenizer.count("hello world"), 2);
+        assert!(tokenizer_for("openai/gpt-4.1").is_exact());
+        assert!(tokenizer_for("gpt-3.5-turbo").is_exact());
+    }
+
+    #[test]
+    fn other_families_are_estimated() {
+        let claude = tokenizer_for("us.anthropic.claude-sonnet-4-20250514-v1:0");
+        assert!(!claude.is_exact());
+        assert_eq!(claude.count(&"a".repeat(32)), 10);
+        assert_eq!(tokenizer_for("llama3").count(&"a".repeat(32)), 8);
+    }
+
+    #[test]
+    fn registered_tokenizers_take_precedence_by_prefix_length() {
+        register_tokenizer(
+            "claude-test-registry",
+            Arc::new(RatioTokenizer {
+                chars_per_token: 1.0,
+            }),
+        );
+        assert_eq!(tokenizer_for("claude-test-registry-1").count("abcd"), 4);
+        assert_eq!(tokenizer_for("claude-3-haiku").count("abcd"), 2);
+    }
+}
@@ -0,0 +1,227 @@
+//! Per-user quotas.
+//!
+//! [`UserQuotas`] counts, per user, the runs executing now and the runs
+//! started and model tokens used since UTC midnight, and checks them against
+//! the server's `user_quotas` limits (see [`distri_types::user_quotas`]).
+//! The server admits each run it starts (A2A messages, WebSocket sessions,
+//! MCP tool calls, retries and regenerations) with [`UserQuotas::admit`]
+//! and holds the returned permit until the run ends;
+//! tokens are counted by `ExecutorContext::increment_usage_with_cache`, so
+//! sub-agents and helper calls count towards their user too.
+//!
+//! Counts are kept in memory and start over when the server restarts.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, RwLock};
+
+use chrono::{DateTime, NaiveDate, Utc};
+use distri_types::user_quotas::{
+    next_reset, QuotaExceeded, QuotaKind, QuotaRaise, RaiseQuotaRequest, UserQuotaLimits,
+    UserQuotaStatus,
+};
+
+/// Per-user run and token counts, checked against the configured limits.
+#[derive(Default)]
+pub struct UserQuotas {
+    limits: RwLock<UserQuotaLimits>,
+    users: Mutex<HashMap<String, Usage>>,
+}
+
+#[derive(Default)]
+struct Usage {
+    running: usize,
+    /// Day `tasks` and `tokens` were counted on.
+    day: Option<NaiveDate>,
+    tasks: u64,
+    tokens: u64,
+    raise: Option<QuotaRaise>,
+}
+
+impl Usage {
+    /// Start the daily counts over on a new day and drop an expired raise.
+    fn roll(&mut self, now: DateTime<Utc>) {
+        let today = now.date_naive();
+        if self.day != Some(today) {
+            self.day = Some(today);
+            self.tasks = 0;
+            self.tokens = 0;
+        }
+        if self.raise.as_ref().is_some_and(|r| r.expires_at <= now) {
+            self.raise = None;
+        }
+    }
+}
+
+/// Held by an admitted run; dropping it frees the user's run slot.
+pub struct QuotaPermit {
+    quotas: Arc<UserQuotas>,
+    user_id: String,
+}
+
+impl QuotaPermit {
+    /// Take back the run counted by [`UserQuotas::admit`], for a run that
+    /// was refused after its quota was checked.
+    pub fn refund(self) {
+        if let Some(usage) = self.quotas.lock().get_mut(&self.user_id) {
+            usage.tasks = usage.tasks.saturating_sub(1);
+        }
+    }
+}
+
+impl Drop for QuotaPermit {
+    fn drop(&mut self) {
+        if let Some(usage) = self.quotas.lock().get_mut(&self.user_id) {
+            usage.running = usage.running.saturating_sub(1);
+        }
+    }
+}
+
+impl UserQuotas {
+    pub fn new(limits: UserQuotaLimits) -> Self {
+        Self {
+            limits: RwLock::new(limits),
+            users: Mutex::default(),
+        }
+    }
+
+    /// Replace the limits; counts are kept.
+    pub fn set_limits(&self, limits: UserQuotaLimits) {
+        *self.limits.write().unwrap_or_else(|e| e.into_inner()) = limits;
+    }
+
+    pub fn limits(&self) -> UserQuotaLimits {
+        self.limits
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// Start a run of `user_id`, or refuse it when the user is over a quota.
+    pub fn admit(self: &Arc<Self>, user_id: &str) -> Result<QuotaPermit, QuotaExceeded> {
+        self.admit_at(user_id, Utc::now())
+    }
+
+    pub(crate) fn admit_at(
+        self: &Arc<Self>,
+        user_id: &str,
+        now: DateTime<Utc>,
+    ) -> Result<QuotaPermit, QuotaExceeded> {
+        let limits = self.limits();
+        let mut users = self.lock();
+        let usage = users.entry(user_id.to_string()).or_default();
+        usage.roll(now);
+        let limits = match &usage.raise {
+            Some(raise) => limits.raised(raise),
+            None => limits,
+        };
+        let exceeded = |quota, limit: u64, used: u64, resets_at| QuotaExceeded {
+            user_id: user_id.to_string(),
+            quota,
+            limit,
+            used,
+            resets_at,
+        };
+        if let Some(max) = limits.max_concurrent_tasks {
+            if usage.running >= max {
+                return Err(exceeded(
+                    QuotaKind::ConcurrentTasks,
+                    max as u64,
+                    usage.running as u64,
+                    None,
+                ));
+            }
+        }
+        if let Some(max) = limits.max_tasks_per_day {
+            if usage.tasks >= max {
+                return Err(exceeded(
+                    QuotaKind::TasksPerDay,
+                    max,
+                    usage.tasks,
+                    Some(next_reset(now)),
+                ));
+            }
+        }
+        if let Some(max) = limits.max_tokens_per_day {
+            if usage.tokens >= max {
+                return Err(exceeded(
+                    QuotaKind::TokensPerDay,
+                    max,
+                    usage.tokens,
+                    Some(next_reset(now)),
+                ));
+            }
+        }
+        usage.running += 1;
+        usage.tasks += 1;
+        Ok(QuotaPermit {
+            quotas: self.clone(),
+            user_id: user_id.to_string(),
+        })
+    }
+
+    /// Count `tokens` used by a model call of `user_id`'s run.
+    pub fn record_tokens(&self, user_id: &str, tokens: u64) {
+        self.record_tokens_at(user_id, tokens, Utc::now());
+    }
+
+    pub(crate) fn record_tokens_at(&self, user_id: &str, tokens: u64, now: DateTime<Utc>) {
+        if tokens == 0 {
+            return;
+        }
+        let mut users = self.lock();
+        let usage = users.entry(user_id.to_string()).or_default();
+        usage.roll(now);
+        usage.tokens += tokens;
+    }
+
+    /// Raise `user_id`'s quotas for `request.duration_secs`.
+    pub fn raise(&self, user_id: &str, request: &RaiseQuotaRequest) -> UserQuotaStatus {
+        let now = Utc::now();
+        {
+            let mut users = self.lock();
+            let usage = users.entry(user_id.to_string()).or_default();
+            usage.roll(now);
+            usage.raise = Some(request.raise_at(now));
+        }
+        tracing::info!(user_id, ?request, "raised user quota");
+        self.status_at(user_id, now)
+    }
+
+    /// `user_id`'s counts and the limits in effect for them.
+    pub fn status(&self, user_id: &str) -> UserQuotaStatus {
+        self.status_at(user_id, Utc::now())
+    }
+
+    pub(crate) fn status_at(&self, user_id: &str, now: DateTime<Utc>) -> UserQuotaStatus {
+        let limits = self.limits();
+        let mut users = self.lock();
+        let (running_tasks, tasks_today, tokens_today, raise) = match users.get_mut(user_id) {
+            Some(usage) => {
+                usage.roll(now);
+                (
+                    usage.running,
+                    usage.tasks,
+                    usage.tokens,
+                    usage.raise.clone(),
+                )
+            }
+            None => (0, 0, 0, None),
+        };
+        UserQuotaStatus {
+            user_id: user_id.to_string(),
+            running_tasks,
+            tasks_today,
+            tokens_today,
+            limits: match &raise {
+                Some(raise) => limits.raised(raise),
+                None => limits,
+            },
+            raise,
+            resets_at: next_reset(now),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, Usage>> {
+        self.users.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
@@ -0,0 +1,280 @@
+//! Warm sessions.
+//!
+//! With `warm_sessions` configured, the tool registry a run assembled for
+//! its thread (`AgentOrchestrator::get_agent_tools_with_pool`, MCP pool
+//! included) is kept in [`WarmSessions`] and reused by the next message on
+//! the thread, as long as the agent definition and the client's external
+//! tools are unchanged. Only top-level runs are kept; sub-agents share their
+//! parent's thread and would evict it.
+//!
+//! The cache is an LRU of `capacity` threads. Pinned threads are never
+//! evicted, and are not hibernated either. Registering tools or MCP servers
+//! drops every warm registry, since any of them may be out of date.
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use distri_types::warm_sessions::{WarmSession, WarmSessionsConfig, WarmSessionsStatus};
+use distri_types::Tool;
+
+use crate::tools::ResolvedTools;
+use crate::types::StandardDefinition;
+
+/// Per-thread tool registries kept between messages.
+pub struct WarmSessions {
+    capacity: usize,
+    inner: Mutex<Inner>,
+}
+
+#[derive(Default)]
+struct Inner {
+    threads: HashMap<String, Entry>,
+    /// Incremented on every use; an entry's `tick` orders the LRU.
+    clock: u64,
+    hits: u64,
+    misses: u64,
+    saved: Duration,
+}
+
+struct Entry {
+    pinned: bool,
+    tick: u64,
+    last_used: DateTime<Utc>,
+    hits: u64,
+    warm: Option<WarmRegistry>,
+}
+
+struct WarmRegistry {
+    agent_id: String,
+    fingerprint: u64,
+    tools: ResolvedTools,
+    build_time: Duration,
+}
+
+impl WarmSessions {
+    pub fn new(config: &WarmSessionsConfig) -> Self {
+        Self {
+            capacity: config.capacity.max(1),
+            inner: Mutex::new(Inner::default()),
+        }
+    }
+
+    /// The registry kept for `thread_id`, if it was built for the same
+    /// `fingerprint` (see [`fingerprint`]). Counts a hit or a miss.
+    pub fn get(&self, thread_id: &str, fingerprint: u64) -> Option<ResolvedTools> {
+        let mut inner = self.lock();
+        let tick = inner.tick();
+        let hit = inner.threads.get_mut(thread_id).and_then(|entry| {
+            let warm = entry
+                .warm
+                .as_ref()
+                .filter(|w| w.fingerprint == fingerprint)?;
+            entry.tick = tick;
+            entry.last_used = Utc::now();
+            entry.hits += 1;
+            Some((warm.tools.clone(), warm.build_time))
+        });
+        match hit {
+            Some((tools, build_time)) => {
+                inner.hits += 1;
+                inner.saved += build_time;
+                tracing::debug!(
+                    thread_id,
+                    saved_ms = build_time.as_millis() as u64,
+                    "warm session hit"
+                );
+                Some(tools)
+            }
+            None => {
+                inner.misses += 1;
+                None
+            }
+        }
+    }
+
+    /// Keep the registry a run on `thread_id` assembled in `build_time`.
+    pub fn insert(
+        &self,
+        thread_id: &str,
+        agent_id: &str,
+        fingerprint: u64,
+        tools: ResolvedTools,
+        build_time: Duration,
+    ) {
+        let mut inner = self.lock();
+        let tick = inner.tick();
+        let entry = inner.entry(thread_id, tick);
+        entry.warm = Some(WarmRegistry {
+            agent_id: agent_id.to_string(),
+            fingerprint,
+            tools,
+            build_time,
+        });
+        inner.evict(self.capacity);
+    }
+
+    /// Keep `thread_id` warm until it is unpinned. A thread that has not run
+    /// yet is kept from its next message on.
+    pub fn pin(&self, thread_id: &str) -> WarmSession {
+        let mut inner = self.lock();
+        let tick = inner.tick();
+        let entry = inner.entry(thread_id, tick);
+        entry.pinned = true;
+        let session = entry.session(thread_id);
+        inner.evict(self.capacity);
+        session
+    }
+
+    /// Make `thread_id` evictable again. `false` if it was not pinned.
+    pub fn unpin(&self, thread_id: &str) -> bool {
+        let mut inner = self.lock();
+        let Some(entry) = inner.threads.get_mut(thread_id) else {
+            return false;
+        };
+        let was_pinned = std::mem::take(&mut entry.pinned);
+        if entry.warm.is_none() {
+            inner.threads.remove(thread_id);
+        }
+        inner.evict(self.capacity);
+        was_pinned
+    }
+
+    pub fn is_pinned(&self, thread_id: &str) -> bool {
+        self.lock()
+            .threads
+            .get(thread_id)
+            .is_some_and(|entry| entry.pinned)
+    }
+
+    /// Forget `thread_id`, pinned or not.
+    pub fn remove(&self, thread_id: &str) {
+        self.lock().threads.remove(thread_id);
+    }
+
+    /// Drop every kept registry; pins stay.
+    pub fn invalidate_all(&self) {
+        let mut inner = self.lock();
+        inner.threads.retain(|_, entry| {
+            entry.warm = None;
+            entry.pinned
+        });
+    }
+
+    pub fn status(&self) -> WarmSessionsStatus {
+        let inner = self.lock();
+        let mut entries: Vec<(&String, &Entry)> = inner.threads.iter().collect();
+        entries.sort_by_key(|(_, entry)| std::cmp::Reverse(entry.tick));
+        WarmSessionsStatus {
+            capacity: self.capacity,
+            hits: inner.hits,
+            misses: inner.misses,
+            saved_ms: inner.saved.as_millis() as u64,
+            sessions: entries
+                .into_iter()
+                .map(|(thread_id, entry)| entry.session(thread_id))
+                .collect(),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Inner> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Inner {
+    fn tick(&mut self) -> u64 {
+        self.clock += 1;
+        self.clock
+    }
+
+    fn entry(&mut self, thread_id: &str, tick: u64) -> &mut Entry {
+        let entry = self
+            .threads
+            .entry(thread_id.to_string())
+            .or_insert_with(|| Entry {
+                pinned: false,
+                tick,
+                last_used: Utc::now(),
+                hits: 0,
+                warm: None,
+            });
+        entry.tick = tick;
+        entry.last_used = Utc::now();
+        entry
+    }
+
+    /// Drop least recently used unpinned threads until at most `capacity`
+    /// remain (or only pinned ones do).
+    fn evict(&mut self, capacity: usize) {
+        while self.threads.len() > capacity {
+            let oldest = self
+                .threads
+                .iter()
+                .filter(|(_, entry)| !entry.pinned)
+                .min_by_key(|(_, entry)| entry.tick)
+                .map(|(thread_id, _)| thread_id.clone());
+            let Some(thread_id) = oldest else {
+                break;
+            };
+            self.threads.remove(&thread_id);
+            tracing::debug!(thread_id = %thread_id, "warm session evicted");
+        }
+    }
+}
+
+impl Entry {
+    fn session(&self, thread_id: &str) -> WarmSession {
+        WarmSession {
+            thread_id: thread_id.to_string(),
+            agent_id: self.warm.as_ref().map(|w| w.agent_id.clone()),
+            pinned: self.pinned,
+            build_ms: self
+                .warm
+                .as_ref()
+                .map(|w| w.build_time.as_millis() as u64)
+                .unwrap_or_default(),
+            hits: self.hits,
+            last_used: self.last_used,
+        }
+    }
+}
+
+/// What a registry was built from: the (overridden) agent definition and
+/// the names of the client's external tools.
+pub fn fingerprint(definition: &StandardDefinition, external_tools: &[Arc<dyn Tool>]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    hash_json(
+        &serde_json::to_value(definition).unwrap_or_default(),
+        &mut hasher,
+    );
+    for tool in external_tools {
+        tool.get_name().hash(&mut hasher);
+    }
+    hasher.finish()
+}
+
+/// Hash with object keys sorted: the definition's maps serialize in
+/// iteration order, which differs between copies of the same definition.
+fn hash_json(value: &serde_json::Value, hasher: &mut DefaultHasher) {
+    match value {
+        serde_json::Value::Object(map) => {
+            let mut keys: Vec<&String> = map.keys().collect();
+            keys.sort();
+            for key in keys {
+                key.hash(hasher);
+                hash_json(&map[key], hasher);
+            }
+        }
+        serde_json::Value::Array(items) => {
+            items.len().hash(hasher);
+            for item in items {
+                hash_json(item, hasher);
+            }
+        }
+        other => other.to_string().hash(hasher),
+    }
+}
@@ -257,7 +257,10 @@ impl BaseAgent for WorkflowAgent {
 /// flips the parked run back) and each `step_run` is populated from
 /// the corresponding stored `WorkflowStepState` by `step_id`. Steps
 /// with no stored row are left at their fresh defaults (`Pending`).
-fn hydrate_run(state: WorkflowExecutionState, step_states: Vec<WorkflowStepState>) -> WorkflowRun {
+pub(crate) fn hydrate_run(
+    state: WorkflowExecutionState,
+    step_states: Vec<WorkflowStepState>,
+) -> WorkflowRun {
     let mut run = WorkflowRun::new(state.definition);
     run.context = state.context;
     run.status = distri_types::TaskStatus::Running;
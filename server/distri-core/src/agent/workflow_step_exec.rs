@@ -185,6 +185,12 @@ pub(crate) async fn execute_step(
                     .await
                     {
                         Ok(parts) => {
+                            // A typed result is handed on as is.
+                            if let Some(output) =
+                                distri_types::tool_output::structured_output(&parts)
+                            {
+                                return Ok(StepResult::done(output));
+                            }
                             let result_text = parts
                                 .iter()
                                 .filter_map(|p| {
@@ -230,6 +236,17 @@ pub(crate) async fn execute_step(
                 .collect();
 
             let mut cmd = match shell {
+                // No shell requested: Windows has no bash on PATH by default.
+                None if cfg!(target_os = "windows") => {
+                    let mut c = tokio::process::Command::new("cmd");
+                    c.arg("/C");
+                    if resolved_args.is_empty() {
+                        c.arg(&resolved_command);
+                    } else {
+                        c.arg(format!("{} {}", resolved_command, resolved_args.join(" ")));
+                    }
+                    c
+                }
                 Some(ShellType::Bash) | None => {
                     let mut c = tokio::process::Command::new("bash");
                     c.arg("-c");
@@ -0,0 +1,1061 @@
+//! AWS Bedrock LLM executor - runs agents against the Bedrock Converse API.
+//!
+//! Bedrock fronts many model families (Anthropic, Amazon Nova, Meta Llama,
+//! Mistral) behind one request shape. Requests are SigV4-signed with the
+//! `AWS_ACCESS_KEY_ID` / `AWS_SECRET_ACCESS_KEY` (and optional
+//! `AWS_SESSION_TOKEN`) secrets, and the distri model name is mapped to a
+//! Bedrock model id through [`distri_types::ModelSettings::provider_model_id`].
+//!
+//! Tool calling uses Converse's native `toolConfig` when the agent's tool
+//! format is `provider`; other formats go through the text parsers, as with
+//! the other executors.
+//!
+//! Models with prompt caching get cache points where [`CacheHints`] puts
+//! them; other models reject them.
+
+use std::{collections::HashMap, sync::Arc};
+
+use crate::{
+    agent::{AgentEventType, ExecutorContext},
+    bedrock_client::{
+        BedrockClient, BedrockMessage, BedrockTool, BytesSource, CachePoint, ContentBlock,
+        ConverseRequest, ConverseStreamEvent, DocumentBlock, ImageBlock, InferenceConfig,
+        SystemBlock, ToolChoice, ToolConfig, ToolInputSchema, ToolResultBlock, ToolResultContent,
+        ToolSpec, ToolUseBlock,
+    },
+    llm::prompt_cache::CacheHints,
+    tools::Tool,
+    types::{Message, MessageRole, Part, ToolCall},
+    AgentError,
+};
+use distri_parsers::{StreamParseResult, ToolCallDelta, ToolCallParser};
+use distri_types::{FileType, LlmDefinition, ModelProvider, ToolCallFormat};
+use futures::StreamExt;
+use llm_gateway::sigv4::AwsCredentials;
+use serde_json::Value;
+use tracing::Instrument as _;
+
+#[derive(Debug)]
+pub struct BedrockLLMExecutor {
+    llm_def: LlmDefinition,
+    tools: Vec<Arc<dyn Tool>>,
+    context: Arc<ExecutorContext>,
+    additional_headers: Option<HashMap<String, String>>,
+    label: Option<String>,
+    format: ToolCallFormat,
+}
+
+impl BedrockLLMExecutor {
+    pub fn new(
+        llm_def: LlmDefinition,
+        tools: Vec<Arc<dyn Tool>>,
+        context: Arc<ExecutorContext>,
+        additional_headers: Option<HashMap<String, String>>,
+        label: Option<String>,
+    ) -> Self {
+        let name = &llm_def.name;
+        tracing::debug!(
+            "Initializing Bedrock LLM {name} with {} server tools",
+            tools.len()
+        );
+
+        let format = llm_def.tool_format.clone();
+
+        Self {
+            llm_def,
+            tools,
+            context,
+            additional_headers,
+            label,
+            format,
+        }
+    }
+
+    /// Build the Bedrock client from config and the AWS secrets
+    async fn build_client(&self) -> Result<BedrockClient, AgentError> {
+        let secret_store = get_secret_store(&self.context);
+        let secret_resolver = crate::secrets::SecretResolver::new(secret_store);
+
+        let ms = self
+            .llm_def
+            .ms()
+            .map_err(AgentError::InvalidConfiguration)?;
+
+        let (base_url, config_access_key, config_region) = match &ms.inner.provider {
+            ModelProvider::AwsBedrock {
+                base_url,
+                api_key,
+                region,
+            } => (base_url.clone(), api_key.clone(), region.clone()),
+            other => {
+                return Err(AgentError::InvalidConfiguration(format!(
+                    "BedrockLLMExecutor requires the aws_bedrock provider, got {:?}",
+                    other
+                )));
+            }
+        };
+
+        tracing::info!(
+            target: "llm.call",
+            llm_name = %self.llm_def.name,
+            model = %ms.model,
+            model_id = %ms.provider_model_id(),
+            provider = %crate::llm::provider_label(ms),
+            thread_id = %self.context.thread_id,
+            task_id = %self.context.task_id,
+            agent_id = %self.context.agent_id,
+            "building LLM client (bedrock)"
+        );
+
+        secret_resolver
+            .validate_provider(&ms.inner.provider)
+            .await?;
+
+        let access_key_id = match config_access_key {
+            Some(key) => key,
+            None => {
+                secret_resolver
+                    .resolve_or_empty(ms.inner.provider.api_key_secret())
+                    .await
+            }
+        };
+        let secret_access_key = secret_resolver
+            .resolve_or_empty("AWS_SECRET_ACCESS_KEY")
+            .await;
+        if access_key_id.is_empty() || secret_access_key.is_empty() {
+            return Err(AgentError::InvalidConfiguration(
+                "AWS Bedrock requires AWS_ACCESS_KEY_ID and AWS_SECRET_ACCESS_KEY".to_string(),
+            ));
+        }
+        let session_token = secret_resolver
+            .resolve("AWS_SESSION_TOKEN")
+            .await
+            .map(|secret| secret.value)
+            .filter(|token| !token.is_empty());
+
+        let region = match config_region.filter(|region| !region.is_empty()) {
+            Some(region) => region,
+            None => secret_resolver
+                .resolve("AWS_REGION")
+                .await
+                .map(|secret| secret.value)
+                .filter(|region| !region.is_empty())
+                .unwrap_or_else(|| ModelProvider::aws_default_region().to_string()),
+        };
+        let base_url = if base_url.is_empty() {
+            secret_resolver
+                .resolve("AWS_BEDROCK_ENDPOINT")
+                .await
+                .map(|secret| secret.value)
+        } else {
+            Some(base_url)
+        };
+
+        let mut headers = self.additional_headers.clone().unwrap_or_default();
+        if let Some(label) = &self.label {
+            headers.insert("X-Label".to_string(), label.clone());
+        } else {
+            headers.insert("X-Label".to_string(), self.llm_def.name.clone());
+        }
+        headers.insert("X-Thread-Id".to_string(), self.context.thread_id.clone());
+        headers.insert("X-Run-Id".to_string(), self.context.run_id.clone());
+
+        Ok(BedrockClient::new(
+            AwsCredentials {
+                access_key_id,
+                secret_access_key,
+                session_token,
+            },
+            region,
+            base_url,
+            headers,
+        ))
+    }
+
+    pub async fn get_parser(&self) -> Option<Box<dyn ToolCallParser>> {
+        let tools = self.context.get_tools().await;
+        distri_parsers::ParserFactory::create_parser(
+            &self.format,
+            tools.iter().map(|t| t.get_tool_definition().name).collect(),
+        )
+    }
+
+    // ─── Message Mapping ─────────────────────────────────────────────────
+
+    /// Convert internal messages to Converse format, extracting system messages
+    fn map_messages(messages: &[Message]) -> (Vec<SystemBlock>, Vec<BedrockMessage>) {
+        let mut system = Vec::new();
+        let mut bedrock_messages: Vec<BedrockMessage> = Vec::new();
+
+        for message in messages {
+            let (role, content) = match message.role {
+                MessageRole::System | MessageRole::Developer => {
+                    if let Some(text) = message.as_text() {
+                        system.push(SystemBlock::Text(text));
+                    }
+                    continue;
+                }
+                MessageRole::User => ("user", map_user_content(message)),
+                MessageRole::Assistant => ("assistant", map_assistant_content(message)),
+                // Tool results go as user messages in Converse
+                MessageRole::Tool => ("user", map_tool_result_content(message)),
+            };
+            // Converse rejects messages without content
+            if content.is_empty() {
+                continue;
+            }
+            // Merge consecutive same-role messages (Converse requires alternating roles)
+            match bedrock_messages.last_mut() {
+                Some(last) if last.role == role => last.content.extend(content),
+                _ => bedrock_messages.push(BedrockMessage {
+                    role: role.to_string(),
+                    content,
+                }),
+            }
+        }
+
+        (system, bedrock_messages)
+    }
+
+    // ─── Tool Mapping ────────────────────────────────────────────────────
+
+    fn map_tools(&self) -> Vec<BedrockTool> {
+        self.tools
+            .iter()
+            .map(|tool| {
+                let def = tool.get_tool_definition();
+                let mut json = def.parameters.clone();
+
+                // Ensure it's a valid object schema
+                if !json.is_object() || json.get("type").and_then(|t| t.as_str()) != Some("object")
+                {
+                    json = serde_json::json!({
+                        "type": "object",
+                        "properties": {
+                            "input": json
+                        },
+                        "required": ["input"]
+                    });
+                }
+
+                BedrockTool::ToolSpec(ToolSpec {
+                    name: def.name,
+                    description: def.description,
+                    input_schema: ToolInputSchema { json },
+                })
+            })
+            .collect()
+    }
+
+    fn build_request(&self, messages: &[Message], model_id: &str) -> ConverseRequest {
+        let ms = self.llm_def.ms().ok();
+        let (mut system, mut messages) = Self::map_messages(messages);
+
+        let hints = if supports_cache_points(model_id) {
+            let prompt_cache = ms.as_ref().map(|ms| ms.inner.prompt_cache);
+            CacheHints::plan(prompt_cache.unwrap_or_default(), messages.len())
+        } else {
+            CacheHints::default()
+        };
+        if hints.system && !system.is_empty() {
+            system.push(SystemBlock::CachePoint(CachePoint::default()));
+        }
+        if let Some(message) = hints.conversation.and_then(|i| messages.get_mut(i)) {
+            message
+                .content
+                .push(ContentBlock::CachePoint(CachePoint::default()));
+        }
+
+        let tool_config = if self.format == ToolCallFormat::Provider {
+            let mut tools = self.map_tools();
+            if hints.tools && !tools.is_empty() {
+                tools.push(BedrockTool::CachePoint(CachePoint::default()));
+            }
+            if tools.is_empty() {
+                None
+            } else {
+                // Only some model families accept `any`; the rest default to auto.
+                let tool_choice = model_id
+                    .contains("anthropic.")
+                    .then_some(ToolChoice::Any {});
+                Some(ToolConfig { tools, tool_choice })
+            }
+        } else {
+            None
+        };
+
+        ConverseRequest {
+            messages,
+            system,
+            inference_config: ms.map(|ms| InferenceConfig {
+                max_tokens: ms.inner.max_tokens,
+                temperature: ms.inner.temperature,
+                top_p: ms.inner.top_p,
+            }),
+            tool_config,
+        }
+    }
+
+    // ─── Execution ───────────────────────────────────────────────────────
+
+    /// Non-streaming execution
+    pub async fn execute(
+        &self,
+        messages: &[Message],
+    ) -> Result<super::llm::LLMResponse, AgentError> {
+        let ms = self
+            .llm_def
+            .ms()
+            .map_err(AgentError::InvalidConfiguration)?;
+        let ctx_fields = llm_gateway::observability::ContextFields {
+            thread_id: &self.context.thread_id,
+            task_id: &self.context.task_id,
+            run_id: &self.context.run_id,
+            agent_id: &self.context.agent_id,
+            user_id: &self.context.user_id,
+            workspace_id: self.context.workspace_id.as_deref(),
+            channel_id: self.context.channel_id.as_deref(),
+        };
+        let inf_attrs =
+            llm_gateway::observability::GenAiInferenceSpan::from_model_settings(ms, &ctx_fields);
+        let span = llm_gateway::observability::builder::inference_span(&inf_attrs);
+        let start = std::time::Instant::now();
+        let model_id = ms.provider_model_id();
+
+        tracing::info!(
+            target: "bedrock_llm.execute",
+            "Bedrock LLM request model={}, max_tokens={:?}, tools={}, messages={}",
+            model_id,
+            ms.inner.max_tokens,
+            self.tools.len(),
+            messages.len()
+        );
+
+        llm_gateway::observability::recorder::record_inference_input(&span, messages);
+
+        // Trim the request to the model's context window
+        let fitted =
+            crate::agent::context_fit::fit_to_context(&self.context, ms, &self.tools, messages)
+                .await?;
+        let messages = &*fitted;
+
+        let request = self.build_request(messages, &model_id);
+        let client = self.build_client().await?;
+        let response = client
+            .converse(&model_id, &request)
+            .instrument(span.clone())
+            .await
+            .map_err(|e| {
+                tracing::error!("LLM request failed: {}", e);
+                let elapsed = start.elapsed().as_millis() as u64;
+                llm_gateway::observability::recorder::record_inference_response(
+                    &span,
+                    Some(model_id.as_str()),
+                    None,
+                    &["error".to_string()],
+                    None,
+                    None,
+                    None,
+                    None,
+                    elapsed,
+                    None,
+                );
+                e
+            })?;
+
+        let input_tokens = response.usage.input_tokens;
+        let output_tokens = response.usage.output_tokens;
+        let cached_tokens = response.usage.cache_read_input_tokens.unwrap_or(0);
+        let cache_created = response.usage.cache_write_input_tokens.unwrap_or(0);
+        self.context
+            .increment_usage_with_cache(input_tokens, output_tokens, cached_tokens)
+            .await;
+        self.context.increment_cache_writes(cache_created).await;
+
+        if self.context.verbose {
+            self.context
+                .emit_verbose(format!(
+                    "[LLM] {}: {} in, {} out",
+                    model_id, input_tokens, output_tokens
+                ))
+                .await;
+        }
+
+        let usage = Some(distri_types::TokenUsage {
+            input_tokens,
+            output_tokens,
+            total_tokens: input_tokens + output_tokens,
+        });
+
+        // Extract content and tool calls from response
+        let mut content = String::new();
+        let mut tool_calls: Vec<ToolCall> = Vec::new();
+
+        for block in response.output.message.content {
+            if let Some(text) = block.text {
+                content.push_str(&text);
+            }
+            if let Some(tool_use) = block.tool_use {
+                tool_calls.push(ToolCall {
+                    tool_call_id: tool_use.tool_use_id,
+                    tool_name: tool_use.name,
+                    input: tool_use.input,
+                });
+            }
+        }
+
+        // If not using provider tool calling, parse from text content
+        if self.format != ToolCallFormat::Provider && tool_calls.is_empty() {
+            if let Some(parser) = self.get_parser().await {
+                if let Ok(parsed) =
+                    crate::llm::LLMExecutor::parse_tool_calls_by_format(&content, &parser)
+                {
+                    tool_calls = parsed;
+                }
+            }
+        }
+
+        // Ensure tool_call_ids
+        for tc in &mut tool_calls {
+            if tc.tool_call_id.is_empty() {
+                tc.tool_call_id = uuid::Uuid::new_v4().to_string();
+            }
+        }
+
+        // Emit events
+        let message_id = uuid::Uuid::new_v4().to_string();
+        let step_id = self.context.get_current_step_id().await.unwrap_or_default();
+
+        self.context
+            .emit(AgentEventType::TextMessageStart {
+                message_id: message_id.clone(),
+                role: MessageRole::Assistant,
+                is_final: Some(true),
+                step_id: step_id.clone(),
+            })
+            .await;
+
+        if !content.is_empty() {
+            self.context
+                .emit(AgentEventType::TextMessageContent {
+                    message_id: message_id.clone(),
+                    step_id: step_id.clone(),
+                    delta: content.clone(),
+                    stripped_content: None,
+                })
+                .await;
+        }
+
+        self.context
+            .emit(AgentEventType::TextMessageEnd {
+                message_id: message_id.clone(),
+                step_id: step_id.clone(),
+            })
+            .await;
+
+        self.save_assistant_message(&content, &tool_calls).await;
+
+        let finish_reason = match response.stop_reason.as_str() {
+            "tool_use" => async_openai::types::chat::FinishReason::ToolCalls,
+            "max_tokens" => async_openai::types::chat::FinishReason::Length,
+            _ => async_openai::types::chat::FinishReason::Stop,
+        };
+
+        let elapsed = start.elapsed().as_millis() as u64;
+        let cost = crate::agent::pricing::estimate_cost(
+            &ms.model,
+            input_tokens,
+            output_tokens,
+            cached_tokens,
+        );
+
+        {
+            use llm_gateway::observability::recorder::{
+                nonzero_tokens, record_context_window, record_inference_output,
+                record_inference_response,
+            };
+            record_inference_output(&span, &content, &tool_calls);
+            record_context_window(&span, ms.effective_context_size(), input_tokens);
+            record_inference_response(
+                &span,
+                Some(model_id.as_str()),
+                None,
+                &[format!("{:?}", finish_reason)],
+                nonzero_tokens(input_tokens),
+                nonzero_tokens(output_tokens),
+                nonzero_tokens(cached_tokens),
+                nonzero_tokens(cache_created),
+                elapsed,
+                cost,
+            );
+        }
+
+        Ok(super::llm::LLMResponse {
+            finish_reason,
+            tool_calls,
+            content,
+            usage,
+        })
+    }
+
+    /// Streaming execution
+    pub async fn execute_stream(
+        &self,
+        messages: &[Message],
+        context: Arc<ExecutorContext>,
+    ) -> Result<super::llm::StreamResult, AgentError> {
+        let ms = self
+            .llm_def
+            .ms()
+            .map_err(AgentError::InvalidConfiguration)?;
+        let ctx_fields = llm_gateway::observability::ContextFields {
+            thread_id: &self.context.thread_id,
+            task_id: &self.context.task_id,
+            run_id: &self.context.run_id,
+            agent_id: &self.context.agent_id,
+            user_id: &self.context.user_id,
+            workspace_id: self.context.workspace_id.as_deref(),
+            channel_id: self.context.channel_id.as_deref(),
+        };
+        let inf_attrs =
+            llm_gateway::observability::GenAiInferenceSpan::from_model_settings(ms, &ctx_fields);
+        let span = llm_gateway::observability::builder::inference_span(&inf_attrs);
+        let start = std::time::Instant::now();
+        let model_id = ms.provider_model_id();
+
+        tracing::info!(
+            target: "bedrock_llm.execute_stream",
+            "Bedrock LLM stream request model={}, max_tokens={:?}, tools={}, messages={}",
+            model_id,
+            ms.inner.max_tokens,
+            self.tools.len(),
+            messages.len()
+        );
+
+        llm_gateway::observability::recorder::record_inference_input(&span, messages);
+
+        // Trim the request to the model's context window
+        let fitted =
+            crate::agent::context_fit::fit_to_context(&context, ms, &self.tools, messages).await?;
+        let messages = &*fitted;
+
+        let step_id = context.get_current_step_id().await.unwrap_or_default();
+        let request = self.build_request(messages, &model_id);
+        let client = self.build_client().await?;
+        let stream = client
+            .converse_stream(&model_id, &request)
+            .instrument(span.clone())
+            .await?;
+
+        let message_id = uuid::Uuid::new_v4().to_string();
+        let mut current_content = String::new();
+        let mut tool_calls: Vec<ToolCall> = Vec::new();
+        let mut text_started = false;
+        let mut parser = self.get_parser().await;
+        let mut stream_input_tokens: u32 = 0;
+        let mut stream_output_tokens: u32 = 0;
+        let mut stream_cached_tokens: u32 = 0;
+        let mut stream_cache_created: u32 = 0;
+
+        // Track partial tool use blocks
+        struct PartialToolUse {
+            id: String,
+            name: String,
+            json_accum: String,
+        }
+        let mut current_tool: Option<PartialToolUse> = None;
+        let mut truncated = false;
+        let mut first_token_at = None;
+
+        tokio::pin!(stream);
+
+        while let Some(event) = stream.next().await {
+            let event = event?;
+            if first_token_at.is_none()
+                && matches!(event, ConverseStreamEvent::ContentBlockDelta { .. })
+            {
+                first_token_at = Some(std::time::Instant::now());
+            }
+            match event {
+                ConverseStreamEvent::MessageStart { .. } => {}
+                ConverseStreamEvent::ContentBlockStart { start, .. } => {
+                    if let Some(tool_use) = start.tool_use {
+                        crate::llm::emit_tool_call_delta(
+                            &context,
+                            &step_id,
+                            ToolCallDelta::Start {
+                                tool_call_id: tool_use.tool_use_id.clone(),
+                                tool_name: tool_use.name.clone(),
+                            },
+                        )
+                        .await;
+                        current_tool = Some(PartialToolUse {
+                            id: tool_use.tool_use_id,
+                            name: tool_use.name,
+                            json_accum: String::new(),
+                        });
+                    }
+                }
+                ConverseStreamEvent::ContentBlockDelta { delta, .. } => {
+                    if let Some(text) = delta.text {
+                        if !text_started {
+                            text_started = true;
+                            context
+                                .emit(AgentEventType::TextMessageStart {
+                                    message_id: message_id.clone(),
+                                    role: MessageRole::Assistant,
+                                    is_final: None,
+                                    step_id: message_id.clone(),
+                                })
+                                .await;
+                        }
+
+                        // Process with streaming parser for non-provider formats
+                        let (delta_to_emit, verbose_blocks) = match parser
+                            .as_mut()
+                            .map(|p| p.process_chunk(&text))
+                            .unwrap_or(Ok(StreamParseResult::default()))
+                        {
+                            Ok(parse_result) => {
+                                for delta in parse_result.tool_call_deltas.clone() {
+                                    crate::llm::emit_tool_call_delta(&context, &step_id, delta)
+                                        .await;
+                                }
+                                tool_calls.extend(parse_result.new_tool_calls.clone());
+
+                                let clean_content = if let Some(ref blocks) =
+                                    parse_result.stripped_content_blocks
+                                {
+                                    let clean: String = blocks
+                                        .iter()
+                                        .filter_map(|(_, content)| {
+                                            if content.trim_start().starts_with('<')
+                                                && content.contains('>')
+                                            {
+                                                None
+                                            } else {
+                                                Some(content.as_str())
+                                            }
+                                        })
+                                        .collect();
+
+                                    if !clean.trim().is_empty() {
+                                        clean
+                                    } else if parse_result.has_partial_tool_call {
+                                        String::new()
+                                    } else {
+                                        text.clone()
+                                    }
+                                } else if parse_result.has_partial_tool_call {
+                                    String::new()
+                                } else {
+                                    text.clone()
+                                };
+
+                                let verbose = if context.verbose {
+                                    parse_result.stripped_content_blocks
+                                } else {
+                                    None
+                                };
+
+                                (clean_content, verbose)
+                            }
+                            Err(e) => {
+                                tracing::warn!("Streaming parser error: {}", e);
+                                (text.clone(), None)
+                            }
+                        };
+
+                        if !delta_to_emit.is_empty() {
+                            current_content.push_str(&delta_to_emit);
+                        }
+
+                        if !delta_to_emit.is_empty() || verbose_blocks.is_some() {
+                            context
+                                .emit(AgentEventType::TextMessageContent {
+                                    message_id: message_id.clone(),
+                                    step_id: step_id.clone(),
+                                    delta: delta_to_emit,
+                                    stripped_content: verbose_blocks,
+                                })
+                                .await;
+                        }
+                    }
+                    if let (Some(tool_delta), Some(tool)) = (delta.tool_use, current_tool.as_mut())
+                    {
+                        tool.json_accum.push_str(&tool_delta.input);
+                        crate::llm::emit_tool_call_delta(
+                            &context,
+                            &step_id,
+                            ToolCallDelta::Args {
+                                tool_call_id: tool.id.clone(),
+                                delta: tool_delta.input,
+                            },
+                        )
+                        .await;
+                    }
+                }
+                ConverseStreamEvent::ContentBlockStop { .. } => {
+                    // Finalize any in-progress tool use
+                    if let Some(tool) = current_tool.take() {
+                        let input: Value = if tool.json_accum.is_empty() {
+                            Value::Object(Default::default())
+                        } else {
+                            serde_json::from_str(&tool.json_accum)
+                                .unwrap_or_else(|_| Value::String(tool.json_accum.clone()))
+                        };
+                        tool_calls.push(ToolCall {
+                            tool_call_id: tool.id,
+                            tool_name: tool.name,
+                            input,
+                        });
+                    }
+                }
+                ConverseStreamEvent::MessageStop { stop_reason } => {
+                    // Other stop reasons are handled via tool_calls presence
+                    if stop_reason == "max_tokens" {
+                        truncated = true;
+                    }
+                }
+                ConverseStreamEvent::Metadata { usage } => {
+                    let cached = usage.cache_read_input_tokens.unwrap_or(0);
+                    let created = usage.cache_write_input_tokens.unwrap_or(0);
+                    self.context
+                        .increment_usage_with_cache(usage.input_tokens, usage.output_tokens, cached)
+                        .await;
+                    self.context.increment_cache_writes(created).await;
+                    stream_input_tokens += usage.input_tokens;
+                    stream_output_tokens += usage.output_tokens;
+                    stream_cached_tokens += cached;
+                    stream_cache_created += created;
+                }
+            }
+        }
+
+        if context.verbose {
+            context
+                .emit_verbose(format!(
+                    "[LLM] {}: {} in, {} out",
+                    model_id, stream_input_tokens, stream_output_tokens
+                ))
+                .await;
+        }
+
+        // Finalize parser
+        tool_calls.extend(
+            parser
+                .as_mut()
+                .map(|p| p.finalize())
+                .transpose()?
+                .unwrap_or_default(),
+        );
+
+        if text_started {
+            context
+                .emit(AgentEventType::TextMessageEnd {
+                    message_id: message_id.clone(),
+                    step_id: step_id.clone(),
+                })
+                .await;
+        }
+
+        let content = current_content;
+
+        if truncated {
+            // A toolUse block cut off mid-input never parsed into an object
+            // and cannot be executed.
+            tool_calls.retain(|tc| !tc.input.is_string());
+        }
+
+        // Ensure tool_call_ids
+        for tc in &mut tool_calls {
+            if tc.tool_call_id.is_empty() {
+                tc.tool_call_id = uuid::Uuid::new_v4().to_string();
+            }
+        }
+
+        self.save_assistant_message(&content, &tool_calls).await;
+
+        let finish_reason = if truncated {
+            async_openai::types::chat::FinishReason::Length
+        } else if !tool_calls.is_empty() {
+            async_openai::types::chat::FinishReason::ToolCalls
+        } else {
+            async_openai::types::chat::FinishReason::Stop
+        };
+
+        let elapsed = start.elapsed().as_millis() as u64;
+        let cost = crate::agent::pricing::estimate_cost(
+            &ms.model,
+            stream_input_tokens,
+            stream_output_tokens,
+            stream_cached_tokens,
+        );
+
+        {
+            use llm_gateway::observability::recorder::{
+                nonzero_tokens, record_context_window, record_inference_output,
+                record_inference_response,
+            };
+            record_inference_output(&span, &content, &tool_calls);
+            record_context_window(&span, ms.effective_context_size(), stream_input_tokens);
+            record_inference_response(
+                &span,
+                Some(model_id.as_str()),
+                None,
+                &[format!("{:?}", finish_reason)],
+                nonzero_tokens(stream_input_tokens),
+                nonzero_tokens(stream_output_tokens),
+                nonzero_tokens(stream_cached_tokens),
+                nonzero_tokens(stream_cache_created),
+                elapsed,
+                cost,
+            );
+        }
+
+        Ok(super::llm::StreamResult {
+            finish_reason,
+            tool_calls,
+            content,
+            usage: super::llm::stream_usage(stream_input_tokens, stream_output_tokens),
+            first_token_at,
+        })
+    }
+
+    async fn save_assistant_message(&self, content: &str, tool_calls: &[ToolCall]) {
+        let mut assistant_msg = Message::assistant(content.to_string(), None);
+        assistant_msg.agent_id = Some(self.context.agent_id.clone());
+        for tc in tool_calls {
+            assistant_msg.parts.push(Part::ToolCall(tc.clone()));
+        }
+        self.context.save_message(&assistant_msg).await;
+        self.context
+            .set_current_message_id(Some(assistant_msg.id.clone()))
+            .await;
+    }
+}
+
+/// Whether the model behind `model_id` accepts cache points: Claude 3.5
+/// Haiku, 3.7 Sonnet and later, and Amazon Nova.
+fn supports_cache_points(model_id: &str) -> bool {
+    [
+        "claude-3-5-haiku",
+        "claude-3-7-sonnet",
+        "claude-sonnet-4",
+        "claude-opus-4",
+        "claude-haiku-4",
+        "amazon.nova",
+    ]
+    .iter()
+    .any(|family| model_id.contains(family))
+}
+
+fn map_user_content(message: &Message) -> Vec<ContentBlock> {
+    message
+        .parts
+        .iter()
+        .filter_map(|part| match part {
+            Part::Text(text) if !text.is_empty() => Some(ContentBlock::Text(text.clone())),
+            Part::Image(file_type) => file_type.as_image_url().map(|url| {
+                image_block(&url)
+                    .map(ContentBlock::Image)
+                    // Converse only takes inline image bytes
+                    .unwrap_or_else(|| ContentBlock::Text(format!("[Image: {}]", url)))
+            }),
+            Part::File(file) => Some(file_to_content_block(file)),
+            _ => None,
+        })
+        .collect()
+}
+
+fn map_assistant_content(message: &Message) -> Vec<ContentBlock> {
+    let mut blocks = Vec::new();
+    if let Some(text) = message.as_text().filter(|text| !text.is_empty()) {
+        blocks.push(ContentBlock::Text(text));
+    }
+    for tc in message.tool_calls() {
+        blocks.push(ContentBlock::ToolUse(ToolUseBlock {
+            tool_use_id: tc.tool_call_id.clone(),
+            name: tc.tool_name.clone(),
+            input: tc.input.clone(),
+        }));
+    }
+    blocks
+}
+
+fn map_tool_result_content(message: &Message) -> Vec<ContentBlock> {
+    message
+        .tool_responses()
+        .into_iter()
+        .map(|response| {
+            let mut content = Vec::new();
+            for part in &response.parts {
+                match part {
+                    Part::Text(text) => content.push(ToolResultContent::Text(text.clone())),
+                    Part::Data(data) => content.push(ToolResultContent::Json(data.clone())),
+                    Part::Image(file_type) => {
+                        if let Some(image) =
+                            file_type.as_image_url().and_then(|url| image_block(&url))
+                        {
+                            content.push(ToolResultContent::Image(image));
+                        }
+                    }
+                    Part::File(file) => content.push(ToolResultContent::Text(format!(
+                        "[File: {} ({})]",
+                        file.name().unwrap_or("<file>"),
+                        file.mime_type()
+                    ))),
+                    Part::Artifact(artifact) => {
+                        content.push(ToolResultContent::Text(match &artifact.preview {
+                            Some(preview) => {
+                                format!("[Artifact: {}]\n{}", artifact.file_id, preview)
+                            }
+                            None => {
+                                format!("[Artifact: {}] {}", artifact.file_id, artifact.summary())
+                            }
+                        }))
+                    }
+                    _ => {}
+                }
+            }
+            if content.is_empty() {
+                content.push(ToolResultContent::Text(
+                    "Tool executed successfully".to_string(),
+                ));
+            }
+            ContentBlock::ToolResult(ToolResultBlock {
+                tool_use_id: response.tool_call_id.clone(),
+                content,
+                status: None,
+            })
+        })
+        .collect()
+}
+
+/// An inline image block from a base64 data URL.
+fn image_block(url: &str) -> Option<ImageBlock> {
+    let (header, data) = url.strip_prefix("data:")?.split_once(',')?;
+    let media_type = header.strip_suffix(";base64").unwrap_or("image/png");
+    let format = media_type.strip_prefix("image/").unwrap_or("png");
+    Some(ImageBlock {
+        format: if format == "jpg" { "jpeg" } else { format }.to_string(),
+        source: BytesSource {
+            bytes: data.to_string(),
+        },
+    })
+}
+
+fn file_to_content_block(file: &FileType) -> ContentBlock {
+    match file {
+        FileType::Bytes { bytes, .. } => {
+            let format = match file.mime_type() {
+                "application/pdf" => "pdf",
+                "text/csv" => "csv",
+                "text/html" => "html",
+                "text/markdown" => "md",
+                "application/msword" => "doc",
+                "application/vnd.openxmlformats-officedocument.wordprocessingml.document" => "docx",
+                "application/vnd.ms-excel" => "xls",
+                "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet" => "xlsx",
+                _ => "txt",
+            };
+            // Document names are restricted to a small character set.
+            let name: String = file
+                .name()
+                .unwrap_or("document")
+                .chars()
+                .map(|c| {
+                    if c.is_ascii_alphanumeric() || " -()[]".contains(c) {
+                        c
+                    } else {
+                        '-'
+                    }
+                })
+                .collect();
+            ContentBlock::Document(DocumentBlock {
+                format: format.to_string(),
+                name,
+                source: BytesSource {
+                    bytes: bytes.clone(),
+                },
+            })
+        }
+        // Converse has no URL document source.
+        FileType::Url { url, .. } => {
+            ContentBlock::Text(format!("[File: {} ({})]", url, file.mime_type()))
+        }
+    }
+}
+
+/// Get the secret store from the executor context (same as in llm.rs)
+fn get_secret_store(
+    context: &Arc<ExecutorContext>,
+) -> Option<Arc<dyn distri_types::stores::SecretStore>> {
+    if let Some(ref stores) = context.stores {
+        return stores.secret_store.clone();
+    }
+    context
+        .orchestrator
+        .as_ref()
+        .and_then(|o| o.stores.secret_store.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tool_results_follow_tool_use_as_user_turn() {
+        let mut assistant = Message::assistant("checking".to_string(), None);
+        assistant.parts.push(Part::ToolCall(ToolCall {
+            tool_call_id: "call_1".to_string(),
+            tool_name: "search".to_string(),
+            input: serde_json::json!({"q": "rust"}),
+        }));
+        let mut tool = Message::tool_response(
+            "call_1".to_string(),
+            "search".to_string(),
+            &serde_json::json!("found"),
+        );
+        tool.role = MessageRole::Tool;
+        let messages = vec![
+            Message::system("be brief".to_string(), None),
+            Message::user("find rust".to_string(), None),
+            assistant,
+            tool,
+        ];
+
+        let (system, mapped) = BedrockLLMExecutor::map_messages(&messages);
+        assert_eq!(system.len(), 1);
+        let roles: Vec<&str> = mapped.iter().map(|m| m.role.as_str()).collect();
+        assert_eq!(roles, vec!["user", "assistant", "user"]);
+        assert!(matches!(
+            &mapped[1].content[1],
+            ContentBlock::ToolUse(ToolUseBlock { tool_use_id, .. }) if tool_use_id == "call_1"
+        ));
+        assert!(matches!(
+            &mapped[2].content[0],
+            ContentBlock::ToolResult(ToolResultBlock { tool_use_id, .. }) if tool_use_id == "call_1"
+        ));
+    }
+
+    #[test]
+    fn cache_points_only_for_models_with_prompt_caching() {
+        assert!(supports_cache_points(
+            "us.anthropic.claude-sonnet-4-20250514-v1:0"
+        ));
+        assert!(supports_cache_points("amazon.nova-pro-v1:0"));
+        assert!(!supports_cache_points(
+            "anthropic.claude-3-haiku-20240307-v1:0"
+        ));
+        assert!(!supports_cache_points("meta.llama3-70b-instruct-v1:0"));
+    }
+
+    #[test]
+    fn data_url_images_become_inline_bytes() {
+        let image = image_block("data:image/jpg;base64,AAAA").unwrap();
+        assert_eq!(image.format, "jpeg");
+        assert_eq!(image.source.bytes, "AAAA");
+        assert!(image_block("https://example.com/a.png").is_none());
+    }
+}
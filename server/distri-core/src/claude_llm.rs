@@ -6,7 +6,8 @@
 //!
 //! ## Prompt Caching Strategy
 //!
-//! Claude's prompt caching allows caching up to 4 breakpoints. We use them strategically:
+//! Claude's prompt caching allows caching up to 4 breakpoints. We use them strategically,
+//! where [`CacheHints`] puts them (and not at all with `prompt_cache: off`):
 //! 1. System prompt (stable across turns) - cache_control on last system block
 //! 2. Tool definitions (stable across turns) - cache_control on last tool
 //! 3. Long conversation prefix - cache_control on a message near the boundary
@@ -26,19 +27,17 @@ use crate::{
         StreamContentBlock, StreamDelta, StreamEvent, SystemBlock, SystemPrompt, ToolResultBlock,
         ToolResultContent,
     },
+    llm::prompt_cache::CacheHints,
     tools::Tool,
     types::{Message, MessageRole, Part, ToolCall},
     AgentError,
 };
-use distri_parsers::{StreamParseResult, ToolCallParser};
+use distri_parsers::{StreamParseResult, ToolCallDelta, ToolCallParser};
 use distri_types::{FileType, LlmDefinition, ToolCallFormat};
 use futures::StreamExt;
 use serde_json::Value;
 use tracing::Instrument as _;
 
-/// How many messages from the end to place the conversation cache breakpoint
-const CACHE_CONVERSATION_BREAKPOINT_OFFSET: usize = 4;
-
 #[derive(Debug)]
 pub struct ClaudeLLMExecutor {
     llm_def: LlmDefinition,
@@ -194,29 +193,16 @@ impl ClaudeLLMExecutor {
         // Merge consecutive same-role messages (Claude requires alternating roles)
         claude_messages = Self::merge_consecutive_messages(claude_messages);
 
-        // Build system prompt with caching on the last block
+        // Blocks rather than a string, so the last one can carry cache_control
         let system = if system_parts.is_empty() {
             None
-        } else if system_parts.len() == 1 {
-            Some(SystemPrompt::Blocks(vec![SystemBlock {
-                block_type: "text".to_string(),
-                text: system_parts.into_iter().next().unwrap(),
-                cache_control: Some(CacheControl::ephemeral()),
-            }]))
         } else {
-            let len = system_parts.len();
             let blocks: Vec<SystemBlock> = system_parts
                 .into_iter()
-                .enumerate()
-                .map(|(i, text)| SystemBlock {
+                .map(|text| SystemBlock {
                     block_type: "text".to_string(),
                     text,
-                    // Cache the last system block
-                    cache_control: if i == len - 1 {
-                        Some(CacheControl::ephemeral())
-                    } else {
-                        None
-                    },
+                    cache_control: None,
                 })
                 .collect();
             Some(SystemPrompt::Blocks(blocks))
@@ -447,8 +433,9 @@ impl ClaudeLLMExecutor {
 
     // ─── Tool Mapping ────────────────────────────────────────────────────
 
-    /// Convert internal tool definitions to Claude tool format with caching
-    fn map_tools(&self) -> Vec<ClaudeTool> {
+    /// Convert internal tool definitions to Claude tool format, caching the
+    /// last one when `cache` is set
+    fn map_tools(&self, cache: bool) -> Vec<ClaudeTool> {
         let tool_count = self.tools.len();
         self.tools
             .iter()
@@ -475,7 +462,7 @@ impl ClaudeLLMExecutor {
                     description: def.description,
                     input_schema,
                     // Cache the last tool definition (tools are stable across turns)
-                    cache_control: if i == tool_count - 1 {
+                    cache_control: if cache && i == tool_count - 1 {
                         Some(CacheControl::ephemeral())
                     } else {
                         None
@@ -496,18 +483,25 @@ impl ClaudeLLMExecutor {
         summary
     }
 
-    /// Apply conversation caching breakpoint to messages
-    fn apply_conversation_cache(messages: &mut [ClaudeMessage]) {
-        if messages.len() < CACHE_CONVERSATION_BREAKPOINT_OFFSET + 1 {
-            return;
+    /// Place the system and conversation cache breakpoints of `hints`
+    fn apply_cache_hints(
+        hints: CacheHints,
+        system: &mut Option<SystemPrompt>,
+        messages: &mut [ClaudeMessage],
+    ) {
+        if hints.system {
+            if let Some(SystemPrompt::Blocks(blocks)) = system {
+                if let Some(last) = blocks.last_mut() {
+                    last.cache_control = Some(CacheControl::ephemeral());
+                }
+            }
         }
 
-        // Place cache breakpoint a few messages from the end
-        // This caches the conversation prefix while keeping recent messages fresh
-        let cache_idx = messages
-            .len()
-            .saturating_sub(CACHE_CONVERSATION_BREAKPOINT_OFFSET);
-        let msg = &mut messages[cache_idx];
+        // The conversation breakpoint sits a few messages from the end:
+        // this caches the conversation prefix while keeping recent messages fresh
+        let Some(msg) = hints.conversation.and_then(|i| messages.get_mut(i)) else {
+            return;
+        };
 
         // Add cache_control to the last content block of this message
         match &mut msg.content {
@@ -573,15 +567,18 @@ impl ClaudeLLMExecutor {
 
         llm_gateway::observability::recorder::record_inference_input(&span, messages);
 
-        // Validate context size
-        let context_manager = crate::agent::context_size_manager::ContextSizeManager::default();
-        context_manager.validate_context_size(messages, ms.effective_context_size())?;
+        // Trim the request to the model's context window
+        let fitted =
+            crate::agent::context_fit::fit_to_context(&self.context, ms, &self.tools, messages)
+                .await?;
+        let messages = &*fitted;
 
-        let (system, mut claude_messages) = self.map_messages(messages);
-        Self::apply_conversation_cache(&mut claude_messages);
+        let (mut system, mut claude_messages) = self.map_messages(messages);
+        let hints = CacheHints::plan(ms.inner.prompt_cache, claude_messages.len());
+        Self::apply_cache_hints(hints, &mut system, &mut claude_messages);
 
         let tools = if self.format == ToolCallFormat::Provider {
-            let mapped = self.map_tools();
+            let mapped = self.map_tools(hints.tools);
             if mapped.is_empty() {
                 None
             } else {
@@ -654,6 +651,7 @@ impl ClaudeLLMExecutor {
         self.context
             .increment_usage_with_cache(input_tokens, output_tokens, cached_tokens)
             .await;
+        self.context.increment_cache_writes(cache_created).await;
 
         // Verbose: per-call LLM summary
         if self.context.verbose {
@@ -773,6 +771,7 @@ impl ClaudeLLMExecutor {
 
         let finish_reason = match response.stop_reason.as_deref() {
             Some("tool_use") => async_openai::types::chat::FinishReason::ToolCalls,
+            Some("max_tokens") => async_openai::types::chat::FinishReason::Length,
             _ => async_openai::types::chat::FinishReason::Stop,
         };
 
@@ -848,16 +847,18 @@ impl ClaudeLLMExecutor {
 
         llm_gateway::observability::recorder::record_inference_input(&span, messages);
 
-        // Validate context size
-        let context_manager = crate::agent::context_size_manager::ContextSizeManager::default();
-        context_manager.validate_context_size(messages, ms.effective_context_size())?;
+        // Trim the request to the model's context window
+        let fitted =
+            crate::agent::context_fit::fit_to_context(&context, ms, &self.tools, messages).await?;
+        let messages = &*fitted;
 
         let step_id = context.get_current_step_id().await.unwrap_or_default();
-        let (system, mut claude_messages) = self.map_messages(messages);
-        Self::apply_conversation_cache(&mut claude_messages);
+        let (mut system, mut claude_messages) = self.map_messages(messages);
+        let hints = CacheHints::plan(ms.inner.prompt_cache, claude_messages.len());
+        Self::apply_cache_hints(hints, &mut system, &mut claude_messages);
 
         let tools = if self.format == ToolCallFormat::Provider {
-            let mapped = self.map_tools();
+            let mapped = self.map_tools(hints.tools);
             if mapped.is_empty() {
                 None
             } else {
@@ -913,10 +914,17 @@ impl ClaudeLLMExecutor {
             json_accum: String,
         }
         let mut current_tool: Option<PartialToolUse> = None;
+        let mut truncated = false;
+        let mut first_token_at = None;
 
         tokio::pin!(stream);
 
         while let Some(event_result) = stream.next().await {
+            if first_token_at.is_none()
+                && matches!(event_result, Ok(StreamEvent::ContentBlockDelta { .. }))
+            {
+                first_token_at = Some(std::time::Instant::now());
+            }
             match event_result {
                 Ok(event) => match event {
                     StreamEvent::MessageStart { message } => {
@@ -930,6 +938,7 @@ impl ClaudeLLMExecutor {
                                     cached,
                                 )
                                 .await;
+                            self.context.increment_cache_writes(created).await;
                             stream_input_tokens += usage.input_tokens;
                             stream_output_tokens += usage.output_tokens;
                             stream_cached_tokens += cached;
@@ -942,6 +951,15 @@ impl ClaudeLLMExecutor {
                                 // Text block starting
                             }
                             StreamContentBlock::ToolUse { id, name } => {
+                                crate::llm::emit_tool_call_delta(
+                                    &context,
+                                    &step_id,
+                                    ToolCallDelta::Start {
+                                        tool_call_id: id.clone(),
+                                        tool_name: name.clone(),
+                                    },
+                                )
+                                .await;
                                 current_tool = Some(PartialToolUse {
                                     id,
                                     name,
@@ -968,12 +986,13 @@ impl ClaudeLLMExecutor {
                             let (delta_to_emit, verbose_blocks) = match parser
                                 .as_mut()
                                 .map(|p| p.process_chunk(&text))
-                                .unwrap_or(Ok(StreamParseResult {
-                                    new_tool_calls: Vec::new(),
-                                    stripped_content_blocks: None,
-                                    has_partial_tool_call: false,
-                                })) {
+                                .unwrap_or(Ok(StreamParseResult::default()))
+                            {
                                 Ok(parse_result) => {
+                                    for delta in parse_result.tool_call_deltas.clone() {
+                                        crate::llm::emit_tool_call_delta(&context, &step_id, delta)
+                                            .await;
+                                    }
                                     if !parse_result.new_tool_calls.is_empty() {
                                         tool_calls.extend(parse_result.new_tool_calls.clone());
                                     }
@@ -1039,6 +1058,15 @@ impl ClaudeLLMExecutor {
                         StreamDelta::InputJsonDelta { partial_json } => {
                             if let Some(ref mut tool) = current_tool {
                                 tool.json_accum.push_str(&partial_json);
+                                crate::llm::emit_tool_call_delta(
+                                    &context,
+                                    &step_id,
+                                    ToolCallDelta::Args {
+                                        tool_call_id: tool.id.clone(),
+                                        delta: partial_json,
+                                    },
+                                )
+                                .await;
                             }
                         }
                     },
@@ -1054,14 +1082,17 @@ impl ClaudeLLMExecutor {
                             });
                         }
                     }
-                    StreamEvent::MessageDelta { delta: _, usage } => {
+                    StreamEvent::MessageDelta { delta, usage } => {
                         if let Some(usage) = usage {
                             self.context
                                 .increment_usage(usage.input_tokens, usage.output_tokens)
                                 .await;
                             stream_output_tokens += usage.output_tokens;
                         }
-                        // stop_reason is in delta.stop_reason but we handle it via tool_calls presence
+                        // Other stop reasons are handled via tool_calls presence
+                        if delta.stop_reason.as_deref() == Some("max_tokens") {
+                            truncated = true;
+                        }
                     }
                     StreamEvent::MessageStop {} => {
                         // Stream complete
@@ -1137,6 +1168,12 @@ impl ClaudeLLMExecutor {
 
         let content = current_content;
 
+        if truncated {
+            // A tool_use block cut off mid-input never parsed into an object
+            // and cannot be executed.
+            tool_calls.retain(|tc| !tc.input.is_string());
+        }
+
         // Save assistant message
         let mut assistant_msg = crate::types::Message::assistant(content.clone(), None);
         assistant_msg.agent_id = Some(self.context.agent_id.clone());
@@ -1155,7 +1192,9 @@ impl ClaudeLLMExecutor {
             }
         }
 
-        let finish_reason = if !tool_calls.is_empty() {
+        let finish_reason = if truncated {
+            async_openai::types::chat::FinishReason::Length
+        } else if !tool_calls.is_empty() {
             async_openai::types::chat::FinishReason::ToolCalls
         } else {
             async_openai::types::chat::FinishReason::Stop
@@ -1194,6 +1233,8 @@ impl ClaudeLLMExecutor {
             finish_reason,
             tool_calls,
             content,
+            usage: super::llm::stream_usage(stream_input_tokens, stream_output_tokens),
+            first_token_at,
         })
     }
 }
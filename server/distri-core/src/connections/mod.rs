@@ -12,3 +12,40 @@
 pub mod resolver;
 
 pub use resolver::{ConnectionResolver, DefaultResolver, ResolveCtx, ResolvedConnection};
+
+use std::collections::HashMap;
+
+use anyhow::{anyhow, Context};
+
+use crate::agent::ExecutorContext;
+
+/// HTTP headers of the connection the run's user has for `provider` in the
+/// run's workspace. Used by auth-bound MCP servers and output sinks.
+pub async fn provider_http_headers(
+    ctx: &ExecutorContext,
+    provider: &str,
+) -> anyhow::Result<HashMap<String, String>> {
+    let stores = &ctx
+        .orchestrator
+        .as_ref()
+        .context("no orchestrator on the context")?
+        .stores;
+    let connection_store = stores
+        .connection_store
+        .as_ref()
+        .context("no connection store is configured")?;
+    let workspace_id = ctx.workspace_id.as_deref().unwrap_or_default();
+    let connection = connection_store
+        .get_by_provider(workspace_id, provider)
+        .await?
+        .with_context(|| format!("no '{}' connection", provider))?;
+    let mut resolve_ctx = ResolveCtx::new(stores).with_user(ctx.user_id.as_str());
+    if let Some(workspace_id) = ctx.workspace_id.as_deref() {
+        resolve_ctx = resolve_ctx.with_workspace(workspace_id);
+    }
+    let resolved = DefaultResolver
+        .resolve(&connection.id.to_string(), &resolve_ctx)
+        .await
+        .map_err(|e| anyhow!(e))?;
+    Ok(resolved.http_headers)
+}
@@ -8,15 +8,21 @@ pub use distri_types::{ApiError, ApiResult};
 pub mod runner;
 pub mod worker;
 
+pub mod bedrock_llm;
 pub mod claude_llm;
 pub mod llm;
+pub mod llm_audit;
+pub mod llm_fallback;
+pub mod llm_metrics;
 pub mod llm_service;
 pub mod logging;
+pub mod mcp_facade;
 
 pub mod openai_responses_llm;
 pub mod secrets;
 
 // Re-export modules moved to llm-gateway
+pub use llm_gateway::bedrock_client;
 pub use llm_gateway::claude_client;
 pub use llm_gateway::gateway_config;
 pub use llm_gateway::openai_responses_client;
@@ -36,6 +42,9 @@ pub use types::{McpDefinition, McpSession, ModelSettings, StandardDefinition};
 #[cfg(test)]
 mod tests;
 
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
+
 #[cfg(test)]
 pub use logging::init_logging;
 
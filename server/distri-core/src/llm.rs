@@ -1,3 +1,6 @@
+pub mod embeddings;
+pub mod prompt_cache;
+
 use std::{
     collections::{HashMap, HashSet},
     sync::Arc,
@@ -65,7 +68,8 @@ use async_openai::{
     },
     Client,
 };
-use distri_parsers::{StreamParseResult, ToolCallParser};
+use distri_parsers::{StreamParseResult, ToolCallDelta, ToolCallParser};
+use distri_types::structured_stream::StructuredStream;
 use distri_types::{FileType, LlmDefinition, ToolCallFormat};
 use futures::{Stream, StreamExt};
 use serde_json::{Map, Value};
@@ -77,6 +81,86 @@ pub struct StreamResult {
     pub finish_reason: async_openai::types::chat::FinishReason,
     pub tool_calls: Vec<ToolCall>,
     pub content: String,
+    pub usage: Option<distri_types::TokenUsage>,
+    /// When the first text or tool call delta arrived.
+    pub first_token_at: Option<std::time::Instant>,
+}
+
+/// Usage of a streamed call, from the token counts its stream reported.
+pub(crate) fn stream_usage(
+    input_tokens: u32,
+    output_tokens: u32,
+) -> Option<distri_types::TokenUsage> {
+    (input_tokens > 0 || output_tokens > 0).then_some(distri_types::TokenUsage {
+        input_tokens,
+        output_tokens,
+        total_tokens: input_tokens + output_tokens,
+    })
+}
+
+/// Forward a streaming tool call's progress to the client.
+pub(crate) async fn emit_tool_call_delta(
+    context: &ExecutorContext,
+    step_id: &str,
+    delta: ToolCallDelta,
+) {
+    let event = match delta {
+        ToolCallDelta::Start {
+            tool_call_id,
+            tool_name,
+        } => AgentEventType::ToolCallStart {
+            step_id: step_id.to_string(),
+            tool_call_id,
+            tool_call_name: tool_name,
+        },
+        ToolCallDelta::Args {
+            tool_call_id,
+            delta,
+        } => AgentEventType::ToolCallArgs {
+            step_id: step_id.to_string(),
+            tool_call_id,
+            delta,
+        },
+    };
+    context.emit(event).await;
+}
+
+/// Feed a streamed text delta to the structured-output validator and forward
+/// the fields it completed, those before a divergence included. Fails as soon
+/// as the response diverges from the schema.
+pub(crate) async fn check_structured_delta(
+    context: &ExecutorContext,
+    structured: &mut StructuredStream,
+    message_id: &str,
+    step_id: &str,
+    delta: &str,
+) -> Result<(), AgentError> {
+    let (fields, divergence) = structured.feed(delta);
+    for field in fields {
+        context
+            .emit(AgentEventType::StructuredFieldCompleted {
+                message_id: message_id.to_string(),
+                step_id: step_id.to_string(),
+                field: field.name,
+                value: field.value,
+            })
+            .await;
+    }
+    match divergence {
+        Some(e) => Err(AgentError::StructuredOutput(
+            structured.text().to_string(),
+            e,
+        )),
+        None => Ok(()),
+    }
+}
+
+/// Check a complete structured response against the whole schema.
+pub(crate) fn finish_structured(structured: &StructuredStream) -> Result<(), AgentError> {
+    structured
+        .finish()
+        .map(|_| ())
+        .map_err(|e| AgentError::StructuredOutput(structured.text().to_string(), e))
 }
 
 #[derive(Debug, Clone)]
@@ -282,11 +366,14 @@ impl LLMExecutor {
         tracing::debug!(target: "llm.execute", "LLM model_settings = {:?}", self.llm_def.model_settings);
         tracing::trace!(target: "llm.execute.messages", "Messages = {:?}", sanitized_messages);
 
-        // Validate context size using the context manager
-        tracing::debug!("📏 Validating context size for completion...");
-        let context_manager = crate::agent::context_size_manager::ContextSizeManager::default();
-        context_manager.validate_context_size(&sanitized_messages, ms.effective_context_size())?;
-        tracing::debug!("✅ Context size validation passed for completion");
+        // Trim the request to the model's context window
+        let sanitized_messages = crate::agent::context_fit::fit_to_context(
+            &self.context,
+            ms,
+            &self.tools,
+            &sanitized_messages,
+        )
+        .await?;
 
         let llm_messages = self.map_messages(&sanitized_messages)?;
         let request = self.build_request(llm_messages)?;
@@ -341,9 +428,13 @@ impl LLMExecutor {
         });
 
         // Track usage and model in context
-        if let Some(u) = &usage {
+        if let Some(u) = &response.usage {
             self.context
-                .increment_usage(u.input_tokens, u.output_tokens)
+                .increment_usage_with_cache(
+                    u.prompt_tokens,
+                    u.completion_tokens,
+                    cached_prompt_tokens(u),
+                )
                 .await;
         }
         if !ms.model.is_empty() {
@@ -528,11 +619,14 @@ impl LLMExecutor {
         tracing::debug!(target: "llm.execute_stream", "LLM model_settings = {:?}", self.llm_def.model_settings);
         tracing::trace!(target: "llm.execute_stream.messages", "Messages = {:?}", sanitized_messages);
 
-        // Validate context size using the context manager
-        tracing::debug!("📏 Validating context size for streaming...");
-        let context_manager = crate::agent::context_size_manager::ContextSizeManager::default();
-        context_manager.validate_context_size(&sanitized_messages, ms.effective_context_size())?;
-        tracing::debug!("✅ Context size validation passed for streaming");
+        // Trim the request to the model's context window
+        let sanitized_messages = crate::agent::context_fit::fit_to_context(
+            &context,
+            ms,
+            &self.tools,
+            &sanitized_messages,
+        )
+        .await?;
 
         let step_id = context.get_current_step_id().await.unwrap_or_default();
         let llm_messages = self.map_messages(&sanitized_messages)?;
@@ -597,6 +691,8 @@ impl LLMExecutor {
             id: Option<String>,
             name: Option<String>,
             arguments: String,
+            /// Whether `ToolCallStart` was emitted for this call.
+            started: bool,
         }
         let partial_tool_calls: RwLock<HashMap<usize, PartialToolCall>> =
             RwLock::new(HashMap::new());
@@ -606,6 +702,21 @@ impl LLMExecutor {
         let mut parser = self.get_parser().await;
         let mut stream_input_tokens: u32 = 0;
         let mut stream_output_tokens: u32 = 0;
+        // Output budget: providers receive `max_tokens`, but not all honour
+        // it while streaming, so the stream is also cut off locally once the
+        // streamed text and tool arguments exceed it.
+        let max_output_tokens = ms.inner.max_tokens;
+        let mut streamed_bytes: usize = 0;
+        let mut truncated = false;
+        // With a `response_format` schema the text is validated as it
+        // streams, and the stream is aborted at the first divergence.
+        let mut structured = ms
+            .inner
+            .response_format
+            .as_ref()
+            .and_then(StructuredStream::from_response_format);
+        let mut diverged = None;
+        let mut first_token_at = None;
 
         while let Some(chunk) = stream.next().await {
             match chunk {
@@ -614,13 +725,29 @@ impl LLMExecutor {
                         let input_tokens = usage.prompt_tokens;
                         let output_tokens = usage.completion_tokens;
                         self.context
-                            .increment_usage(input_tokens, output_tokens)
+                            .increment_usage_with_cache(
+                                input_tokens,
+                                output_tokens,
+                                cached_prompt_tokens(&usage),
+                            )
                             .await;
                         stream_input_tokens += input_tokens;
                         stream_output_tokens += output_tokens;
                     }
                     if let Some(choice) = chunk.choices.first() {
                         let delta = &choice.delta;
+                        if first_token_at.is_none()
+                            && (delta.content.as_ref().is_some_and(|c| !c.is_empty())
+                                || delta.tool_calls.is_some())
+                        {
+                            first_token_at = Some(std::time::Instant::now());
+                        }
+                        if choice.finish_reason
+                            == Some(async_openai::types::chat::FinishReason::Length)
+                        {
+                            truncated = true;
+                        }
+                        streamed_bytes += delta.content.as_ref().map_or(0, |c| c.len());
 
                         if let Some(content) = &delta.content {
                             if !text_started {
@@ -640,12 +767,12 @@ impl LLMExecutor {
                             let (delta_to_emit, verbose_blocks) = match parser
                                 .as_mut()
                                 .map(|p| p.process_chunk(content))
-                                .unwrap_or(Ok(StreamParseResult {
-                                    new_tool_calls: Vec::new(),
-                                    stripped_content_blocks: None,
-                                    has_partial_tool_call: false,
-                                })) {
+                                .unwrap_or(Ok(StreamParseResult::default()))
+                            {
                                 Ok(parse_result) => {
+                                    for delta in parse_result.tool_call_deltas.clone() {
+                                        emit_tool_call_delta(&context, &step_id, delta).await;
+                                    }
                                     // Add any new tool calls discovered
                                     if !parse_result.new_tool_calls.is_empty() {
                                         aggregated_tool_calls
@@ -704,16 +831,31 @@ impl LLMExecutor {
                                     .emit(AgentEventType::TextMessageContent {
                                         message_id: message_id.clone(),
                                         step_id: step_id.clone(),
-                                        delta: delta_to_emit,
+                                        delta: delta_to_emit.clone(),
                                         stripped_content: verbose_blocks,
                                     })
                                     .await;
                             }
+                            if let Some(structured) = structured.as_mut() {
+                                diverged = check_structured_delta(
+                                    &context,
+                                    structured,
+                                    &message_id,
+                                    &step_id,
+                                    &delta_to_emit,
+                                )
+                                .await
+                                .err();
+                            }
+                            if diverged.is_some() {
+                                break;
+                            }
                         }
 
                         // Handle tool calls if present
                         if let Some(tool_calls) = &delta.tool_calls {
                             tracing::debug!("Tool call stream chunk: {:#?}", tool_calls);
+                            let mut deltas = Vec::new();
                             for tool_call in tool_calls {
                                 let mut partials = partial_tool_calls.write().await;
                                 let entry = partials
@@ -744,10 +886,40 @@ impl LLMExecutor {
                                     }
 
                                     if let Some(arguments) = function.arguments.clone() {
+                                        streamed_bytes += arguments.len();
                                         entry.arguments.push_str(&arguments);
+                                        if entry.started && !arguments.is_empty() {
+                                            deltas.push(ToolCallDelta::Args {
+                                                tool_call_id: entry.id.clone().unwrap_or_default(),
+                                                delta: arguments,
+                                            });
+                                        }
+                                    }
+                                }
+
+                                // Announce the call once its name is known;
+                                // arguments streamed before that go out with it.
+                                if let (false, Some(name)) = (entry.started, entry.name.clone()) {
+                                    entry.started = true;
+                                    let tool_call_id = entry
+                                        .id
+                                        .get_or_insert_with(|| uuid::Uuid::new_v4().to_string())
+                                        .clone();
+                                    deltas.push(ToolCallDelta::Start {
+                                        tool_call_id: tool_call_id.clone(),
+                                        tool_name: name,
+                                    });
+                                    if !entry.arguments.is_empty() {
+                                        deltas.push(ToolCallDelta::Args {
+                                            tool_call_id,
+                                            delta: entry.arguments.clone(),
+                                        });
                                     }
                                 }
                             }
+                            for delta in deltas {
+                                emit_tool_call_delta(&context, &step_id, delta).await;
+                            }
                         }
                     }
                 }
@@ -757,6 +929,30 @@ impl LLMExecutor {
                     return Err(AgentError::LLMError(e.to_string()));
                 }
             }
+
+            if let Some(max) = max_output_tokens {
+                if streamed_bytes.div_ceil(4) > max as usize {
+                    tracing::info!(
+                        "Stream exceeded the output budget of {} tokens, cutting it off",
+                        max
+                    );
+                    truncated = true;
+                    break;
+                }
+            }
+        }
+
+        if let Some(error) = diverged {
+            tracing::info!("Structured output diverged from its schema: {}", error);
+            if text_started {
+                context
+                    .emit(AgentEventType::TextMessageEnd {
+                        message_id: message_id.clone(),
+                        step_id: step_id.clone(),
+                    })
+                    .await;
+            }
+            return Err(error);
         }
 
         let mut tool_calls = aggregated_tool_calls.clone();
@@ -773,8 +969,12 @@ impl LLMExecutor {
                     .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
                 let tool_name = partial.name.clone().unwrap_or_default();
 
-                let input = serde_json::from_str::<serde_json::Value>(&partial.arguments)
-                    .unwrap_or_else(|_| serde_json::Value::String(partial.arguments.clone()));
+                let input = match serde_json::from_str::<serde_json::Value>(&partial.arguments) {
+                    Ok(input) => input,
+                    // A call cut off mid-arguments cannot be executed.
+                    Err(_) if truncated => continue,
+                    Err(_) => serde_json::Value::String(partial.arguments.clone()),
+                };
 
                 tool_calls.push(ToolCall {
                     tool_call_id,
@@ -819,6 +1019,11 @@ impl LLMExecutor {
         }
 
         let content = current_content.clone();
+        if let Some(structured) = &structured {
+            if !truncated && tool_calls.is_empty() {
+                finish_structured(structured)?;
+            }
+        }
 
         {
             use llm_gateway::observability::recorder::record_inference_output;
@@ -843,7 +1048,9 @@ impl LLMExecutor {
         if !tool_calls.is_empty() {
             Self::ensure_tool_call_ids(&mut tool_calls);
         }
-        let finish_reason = if !tool_calls.is_empty() {
+        let finish_reason = if truncated {
+            async_openai::types::chat::FinishReason::Length
+        } else if !tool_calls.is_empty() {
             async_openai::types::chat::FinishReason::ToolCalls
         } else {
             async_openai::types::chat::FinishReason::Stop
@@ -876,6 +1083,8 @@ impl LLMExecutor {
             finish_reason,
             tool_calls,
             content,
+            usage: stream_usage(stream_input_tokens, stream_output_tokens),
+            first_token_at,
         })
     }
     pub fn map_tools(&self) -> Vec<async_openai::types::chat::ChatCompletionTools> {
@@ -1060,6 +1269,11 @@ impl LLMExecutor {
             None
         };
 
+        let request_tools_parallel = tools
+            .is_some()
+            .then_some(settings.inner.parallel_tool_calls)
+            .flatten();
+
         // Models that use max_completion_tokens instead of max_tokens.
         // These are newer OpenAI models (o-series, gpt-4.1+) that reject the legacy parameter.
         let uses_max_completion_tokens = model.contains("o1")
@@ -1085,6 +1299,11 @@ impl LLMExecutor {
             max_completion_tokens: new_max_completion_tokens,
             frequency_penalty: settings.inner.frequency_penalty,
             presence_penalty: settings.inner.presence_penalty,
+            // Same lowercase names on the wire, so convert through serde.
+            reasoning_effort: settings
+                .inner
+                .reasoning_effort
+                .and_then(|effort| serde_json::from_value(serde_json::json!(effort.as_str())).ok()),
             response_format: settings.inner.response_format.clone().map(|r| {
                 // Unwrap user-provided response_format: expect { type: "json_schema", json_schema: { name, schema, strict } }
                 // Use the inner schema; sanitize name to match OpenAI requirements.
@@ -1126,6 +1345,8 @@ impl LLMExecutor {
                 }
             }),
             tool_choice,
+            parallel_tool_calls: request_tools_parallel,
+            prompt_cache_key: prompt_cache::openai_cache_key(settings, &self.llm_def.name),
             ..Default::default()
         };
 
@@ -1606,7 +1827,7 @@ async fn get_client_with_context(
         ));
     }
 
-    let pcc = crate::provider_config::ProviderClientConfig::from(&ms.inner.provider);
+    let pcc = crate::provider_config::ProviderClientConfig::for_model_settings(ms);
     let mut headers = get_headers(llm_def, additional_headers, label);
 
     // Resolve API key: inline from config or from secret store
@@ -1694,6 +1915,21 @@ impl LLMExecutorTrait for crate::claude_llm::ClaudeLLMExecutor {
     }
 }
 
+#[async_trait::async_trait]
+impl LLMExecutorTrait for crate::bedrock_llm::BedrockLLMExecutor {
+    async fn execute(&self, messages: &[Message]) -> Result<LLMResponse, AgentError> {
+        self.execute(messages).await
+    }
+
+    async fn execute_stream(
+        &self,
+        messages: &[Message],
+        context: Arc<ExecutorContext>,
+    ) -> Result<StreamResult, AgentError> {
+        self.execute_stream(messages, context).await
+    }
+}
+
 #[async_trait::async_trait]
 impl LLMExecutorTrait for crate::openai_responses_llm::OpenAIResponsesLLMExecutor {
     async fn execute(&self, messages: &[Message]) -> Result<LLMResponse, AgentError> {
@@ -1709,11 +1945,28 @@ impl LLMExecutorTrait for crate::openai_responses_llm::OpenAIResponsesLLMExecuto
     }
 }
 
+/// Builds the executor for every LLM call an orchestrator makes, in place of
+/// provider dispatch. Installed with
+/// [`AgentOrchestratorBuilder::with_llm_executor_factory`]; the `testing`
+/// module's `MockLlmProvider` is the main implementation.
+///
+/// [`AgentOrchestratorBuilder::with_llm_executor_factory`]: crate::AgentOrchestratorBuilder::with_llm_executor_factory
+pub trait LlmExecutorFactory: Send + Sync + std::fmt::Debug {
+    fn create_executor(
+        &self,
+        llm_def: &LlmDefinition,
+        tools: &[Arc<dyn crate::tools::Tool>],
+        context: Arc<ExecutorContext>,
+    ) -> Result<Box<dyn LLMExecutorTrait>, AgentError>;
+}
+
 /// Factory function to create the appropriate LLM executor based on provider and API format.
 /// Returns a trait object so callers don't need to match on provider type.
 ///
 /// Routing logic:
+/// - Orchestrator with an [`LlmExecutorFactory`] → whatever the factory builds
 /// - Anthropic provider → ClaudeLLMExecutor
+/// - AWS Bedrock → BedrockLLMExecutor (Converse API)
 /// - OpenAI-family providers with Responses API format → OpenAIResponsesLLMExecutor
 /// - Everything else → LLMExecutor (Chat Completions)
 ///
@@ -1726,20 +1979,39 @@ pub fn create_llm_executor(
     additional_headers: Option<HashMap<String, String>>,
     label: Option<String>,
 ) -> Result<Box<dyn LLMExecutorTrait>, AgentError> {
+    if let Some(factory) = context
+        .orchestrator
+        .as_ref()
+        .and_then(|o| o.llm_executor_factory.clone())
+    {
+        let meter = crate::llm_metrics::Meter::for_call(&llm_def, &context);
+        let executor = factory.create_executor(&llm_def, &tools, context)?;
+        return Ok(crate::llm_metrics::metered(executor, meter));
+    }
+    let meter = crate::llm_metrics::Meter::for_call(&llm_def, &context);
+
     let ms = llm_def.ms().map_err(AgentError::InvalidConfiguration)?;
     let provider = &ms.inner.provider;
+    let audit = crate::llm_audit::audit_store(&context).map(|store| {
+        (
+            store,
+            context.clone(),
+            provider.provider_id().to_string(),
+            ms.model.clone(),
+        )
+    });
 
-    match provider {
+    let executor: Box<dyn LLMExecutorTrait> = match provider {
         // Anthropic and Z.ai (Anthropic-compatible coding plan) both speak the
         // Messages wire format → ClaudeLLMExecutor.
         ModelProvider::Anthropic { .. } | ModelProvider::ZAi { .. } => {
-            Ok(Box::new(crate::claude_llm::ClaudeLLMExecutor::new(
+            Box::new(crate::claude_llm::ClaudeLLMExecutor::new(
                 llm_def,
                 tools,
                 context,
                 additional_headers,
                 label,
-            )))
+            ))
         }
         // OpenAI-family providers: check api_format to decide Completions vs Responses
         ModelProvider::OpenAI {}
@@ -1747,12 +2019,11 @@ pub fn create_llm_executor(
         | ModelProvider::AzureOpenAI { .. }
         | ModelProvider::Gemini { .. }
         | ModelProvider::AzureAiFoundry { .. }
-        | ModelProvider::AwsBedrock { .. }
         | ModelProvider::GoogleVertex { .. }
         | ModelProvider::AlibabaCloud { .. } => {
             let resolved = ms.inner.api_format.resolve(&ms.model);
             if resolved == distri_types::ResolvedOpenAiApiFormat::Responses {
-                Ok(Box::new(
+                Box::new(
                     crate::openai_responses_llm::OpenAIResponsesLLMExecutor::new(
                         llm_def,
                         tools,
@@ -1760,26 +2031,44 @@ pub fn create_llm_executor(
                         additional_headers,
                         label,
                     ),
-                ))
+                )
             } else {
-                Ok(Box::new(LLMExecutor::new(
+                Box::new(LLMExecutor::new(
                     llm_def,
                     tools,
                     context,
                     additional_headers,
                     label,
-                )))
+                ))
             }
         }
+        // Bedrock is SigV4-signed and speaks the Converse API.
+        ModelProvider::AwsBedrock { .. } => Box::new(crate::bedrock_llm::BedrockLLMExecutor::new(
+            llm_def,
+            tools,
+            context,
+            additional_headers,
+            label,
+        )),
         // fal.ai is image-only; pinning it as an agent's LLM is a config
         // error. Image generation goes through `POST /v1/images/generations`,
         // not the agent loop.
-        ModelProvider::FalAi { .. } => Err(AgentError::InvalidConfiguration(
-            "fal.ai is an image-generation provider and cannot be used as an agent's LLM; \
+        ModelProvider::FalAi { .. } => {
+            return Err(AgentError::InvalidConfiguration(
+                "fal.ai is an image-generation provider and cannot be used as an agent's LLM; \
              use `POST /v1/images/generations` instead"
-                .to_string(),
-        )),
-    }
+                    .to_string(),
+            ))
+        }
+    };
+
+    let executor = match audit {
+        Some((store, context, provider, model)) => Box::new(
+            crate::llm_audit::AuditedLlmExecutor::new(executor, store, context, provider, model),
+        ),
+        None => executor,
+    };
+    Ok(crate::llm_metrics::metered(executor, meter))
 }
 
 fn format_k(count: usize) -> String {
@@ -1790,6 +2079,15 @@ fn format_k(count: usize) -> String {
     }
 }
 
+/// Prompt tokens OpenAI served from its prompt cache.
+fn cached_prompt_tokens(usage: &async_openai::types::chat::CompletionUsage) -> u32 {
+    usage
+        .prompt_tokens_details
+        .as_ref()
+        .and_then(|details| details.cached_tokens)
+        .unwrap_or(0)
+}
+
 #[cfg(test)]
 mod chat_file_tests {
     use super::*;
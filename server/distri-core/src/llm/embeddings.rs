@@ -0,0 +1,385 @@
+//! The embedding service shared by every feature that needs vectors
+//! (semantic memory, tool selection, artifact search, plugins and the UI
+//! through `POST /embeddings`). See [`distri_types::embeddings`].
+//!
+//! Inputs already in the on-disk cache are answered from it; the others are
+//! deduplicated and sent to the [`EmbeddingProvider`] in batches of
+//! `batch_size`, then cached. A cache entry is one JSON file holding the
+//! vector, at `<cache_dir>/<model>/<sha256 of the text>.json`.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use anyhow::{anyhow, bail, Context, Result};
+use async_trait::async_trait;
+use distri_types::embeddings::{Embedding, EmbeddingRequest, EmbeddingResponse, EmbeddingsConfig};
+use distri_types::{stores::SecretStore, ModelProvider};
+use serde::Deserialize;
+use serde_json::json;
+use sha2::{Digest, Sha256};
+
+use crate::provider_config::ProviderClientConfig;
+use crate::secrets::SecretResolver;
+
+const DEFAULT_CACHE_DIR: &str = "/tmp/distri-embeddings";
+
+/// Turns texts into vectors.
+#[async_trait]
+pub trait EmbeddingProvider: Send + Sync {
+    /// One vector per input, in input order.
+    async fn embed(
+        &self,
+        model: &str,
+        dimensions: Option<u32>,
+        inputs: &[String],
+    ) -> Result<Vec<Vec<f32>>>;
+}
+
+/// Any provider serving OpenAI's `POST {base_url}/embeddings`.
+pub struct OpenAiEmbeddingProvider {
+    provider: ModelProvider,
+    secret_store: Option<Arc<dyn SecretStore>>,
+    http: reqwest::Client,
+}
+
+impl OpenAiEmbeddingProvider {
+    pub fn new(provider: ModelProvider, secret_store: Option<Arc<dyn SecretStore>>) -> Self {
+        Self {
+            provider,
+            secret_store,
+            http: reqwest::Client::new(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct ProviderResponse {
+    data: Vec<ProviderEmbedding>,
+}
+
+#[derive(Deserialize)]
+struct ProviderEmbedding {
+    index: usize,
+    embedding: Vec<f32>,
+}
+
+#[async_trait]
+impl EmbeddingProvider for OpenAiEmbeddingProvider {
+    async fn embed(
+        &self,
+        model: &str,
+        dimensions: Option<u32>,
+        inputs: &[String],
+    ) -> Result<Vec<Vec<f32>>> {
+        if matches!(self.provider, ModelProvider::Anthropic { .. }) {
+            bail!("the anthropic provider has no embeddings API");
+        }
+        let pcc = ProviderClientConfig::from(&self.provider);
+        if pcc.base_url.is_empty() {
+            bail!("base_url is empty for the embeddings provider");
+        }
+        let api_key = match &pcc.inline_api_key {
+            Some(key) => key.clone(),
+            None if !pcc.api_key_secret.is_empty() => {
+                SecretResolver::new(self.secret_store.clone())
+                    .resolve_or_empty(pcc.api_key_secret)
+                    .await
+            }
+            None => String::new(),
+        };
+
+        let mut body = json!({ "model": model, "input": inputs, "encoding_format": "float" });
+        if let Some(dimensions) = dimensions {
+            body["dimensions"] = json!(dimensions);
+        }
+        let url = format!("{}/embeddings", pcc.base_url.trim_end_matches('/'));
+        let mut request = self.http.post(&url).query(&pcc.query_params).json(&body);
+        if !api_key.is_empty() {
+            request = request.bearer_auth(&api_key);
+            if pcc.send_api_key_header {
+                request = request.header("api-key", &api_key);
+            }
+        }
+        for (name, value) in &pcc.extra_headers {
+            request = request.header(name, value);
+        }
+
+        let response = request.send().await?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            bail!("embeddings request failed ({}): {}", status, text);
+        }
+        let mut data = response
+            .json::<ProviderResponse>()
+            .await
+            .context("invalid embeddings response")?
+            .data;
+        data.sort_by_key(|e| e.index);
+        Ok(data.into_iter().map(|e| e.embedding).collect())
+    }
+}
+
+/// Batching and caching in front of an [`EmbeddingProvider`].
+pub struct EmbeddingService {
+    config: EmbeddingsConfig,
+    provider: Arc<dyn EmbeddingProvider>,
+    cache_dir: PathBuf,
+}
+
+impl EmbeddingService {
+    pub fn new(config: EmbeddingsConfig, provider: Arc<dyn EmbeddingProvider>) -> Self {
+        let cache_dir = config
+            .cache_dir
+            .as_deref()
+            .map(PathBuf::from)
+            .unwrap_or_else(|| PathBuf::from(DEFAULT_CACHE_DIR));
+        Self {
+            config,
+            provider,
+            cache_dir,
+        }
+    }
+
+    /// A service calling the configured OpenAI-compatible provider.
+    pub fn from_config(
+        config: EmbeddingsConfig,
+        secret_store: Option<Arc<dyn SecretStore>>,
+    ) -> Self {
+        let provider = OpenAiEmbeddingProvider::new(config.provider.clone(), secret_store);
+        Self::new(config, Arc::new(provider))
+    }
+
+    pub fn config(&self) -> &EmbeddingsConfig {
+        &self.config
+    }
+
+    pub async fn embed(&self, request: EmbeddingRequest) -> Result<EmbeddingResponse> {
+        let model = request.model.unwrap_or_else(|| self.config.model.clone());
+        let dimensions = request.dimensions.or(self.config.dimensions);
+        let inputs = request.input.into_vec();
+        let dir = self.model_dir(&model, dimensions);
+
+        let mut vectors: Vec<Option<Vec<f32>>> = vec![None; inputs.len()];
+        let mut cached = 0;
+        // Text → positions still waiting for a vector.
+        let mut missing: Vec<(&String, Vec<usize>)> = Vec::new();
+        let mut missing_index: HashMap<&String, usize> = HashMap::new();
+        for (index, input) in inputs.iter().enumerate() {
+            if let Some(vector) = self.cache_get(&dir, input).await {
+                vectors[index] = Some(vector);
+                cached += 1;
+            } else if let Some(&slot) = missing_index.get(input) {
+                missing[slot].1.push(index);
+            } else {
+                missing_index.insert(input, missing.len());
+                missing.push((input, vec![index]));
+            }
+        }
+
+        for batch in missing.chunks(self.config.batch_size.max(1)) {
+            let texts: Vec<String> = batch.iter().map(|(text, _)| (*text).clone()).collect();
+            let embedded = self.provider.embed(&model, dimensions, &texts).await?;
+            if embedded.len() != texts.len() {
+                bail!(
+                    "the embeddings provider returned {} vectors for {} inputs",
+                    embedded.len(),
+                    texts.len()
+                );
+            }
+            for ((text, positions), vector) in batch.iter().zip(embedded) {
+                self.cache_put(&dir, text, &vector).await;
+                for &index in positions {
+                    vectors[index] = Some(vector.clone());
+                }
+            }
+        }
+
+        let data = vectors
+            .into_iter()
+            .enumerate()
+            .map(|(index, vector)| Embedding {
+                index,
+                embedding: vector.unwrap_or_default(),
+            })
+            .collect();
+        Ok(EmbeddingResponse {
+            model,
+            data,
+            cached,
+        })
+    }
+
+    /// The vector of a single text with the configured model.
+    pub async fn embed_one(&self, text: &str) -> Result<Vec<f32>> {
+        let response = self
+            .embed(EmbeddingRequest::new(vec![text.to_string()]))
+            .await?;
+        response
+            .into_vectors()
+            .pop()
+            .ok_or_else(|| anyhow!("no embedding returned"))
+    }
+
+    fn model_dir(&self, model: &str, dimensions: Option<u32>) -> PathBuf {
+        let mut name: String = model
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '-' || c == '.' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        if let Some(dimensions) = dimensions {
+            name.push_str(&format!("-{}", dimensions));
+        }
+        self.cache_dir.join(name)
+    }
+
+    fn cache_path(dir: &Path, text: &str) -> PathBuf {
+        dir.join(format!("{:x}.json", Sha256::digest(text.as_bytes())))
+    }
+
+    async fn cache_get(&self, dir: &Path, text: &str) -> Option<Vec<f32>> {
+        if !self.config.cache {
+            return None;
+        }
+        let bytes = tokio::fs::read(Self::cache_path(dir, text)).await.ok()?;
+        serde_json::from_slice(&bytes).ok()
+    }
+
+    /// Best effort: a failed write only costs a provider call later.
+    async fn cache_put(&self, dir: &Path, text: &str, vector: &[f32]) {
+        if !self.config.cache {
+            return;
+        }
+        let result = async {
+            tokio::fs::create_dir_all(dir).await?;
+            tokio::fs::write(Self::cache_path(dir, text), serde_json::to_vec(vector)?).await?;
+            anyhow::Ok(())
+        }
+        .await;
+        if let Err(e) = result {
+            tracing::warn!(dir = %dir.display(), error = %e, "Failed to cache embedding");
+        }
+    }
+}
+
+/// Artifact indexing (see [`distri_filesystem::semantic`]) with the
+/// configured model.
+#[async_trait]
+impl distri_filesystem::Embedder for EmbeddingService {
+    async fn embed_texts(&self, texts: Vec<String>) -> Result<Vec<Vec<f32>>> {
+        Ok(self
+            .embed(EmbeddingRequest::new(texts))
+            .await?
+            .into_vectors())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    /// Embeds a text as `[len, batch number]` and records the batches.
+    #[derive(Default)]
+    struct MockProvider {
+        batches: Mutex<Vec<Vec<String>>>,
+    }
+
+    #[async_trait]
+    impl EmbeddingProvider for MockProvider {
+        async fn embed(
+            &self,
+            _model: &str,
+            _dimensions: Option<u32>,
+            inputs: &[String],
+        ) -> Result<Vec<Vec<f32>>> {
+            let mut batches = self.batches.lock().unwrap();
+            batches.push(inputs.to_vec());
+            let batch = batches.len() as f32;
+            Ok(inputs.iter().map(|t| vec![t.len() as f32, batch]).collect())
+        }
+    }
+
+    fn service(dir: &Path, batch_size: usize) -> (EmbeddingService, Arc<MockProvider>) {
+        let provider = Arc::new(MockProvider::default());
+        let config = EmbeddingsConfig {
+            batch_size,
+            cache_dir: Some(dir.to_string_lossy().to_string()),
+            ..Default::default()
+        };
+        (EmbeddingService::new(config, provider.clone()), provider)
+    }
+
+    fn texts(texts: &[&str]) -> EmbeddingRequest {
+        EmbeddingRequest::new(texts.iter().map(|t| t.to_string()).collect())
+    }
+
+    #[tokio::test]
+    async fn batches_and_deduplicates_inputs() {
+        let dir = tempfile::tempdir().unwrap();
+        let (service, provider) = service(dir.path(), 2);
+
+        let response = service
+            .embed(texts(&["a", "bb", "a", "ccc"]))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            *provider.batches.lock().unwrap(),
+            vec![vec!["a", "bb"], vec!["ccc"]]
+        );
+        assert_eq!(response.model, "text-embedding-3-small");
+        assert_eq!(response.cached, 0);
+        let indexes: Vec<usize> = response.data.iter().map(|e| e.index).collect();
+        assert_eq!(indexes, vec![0, 1, 2, 3]);
+        assert_eq!(
+            response.into_vectors(),
+            vec![
+                vec![1.0, 1.0],
+                vec![2.0, 1.0],
+                vec![1.0, 1.0],
+                vec![3.0, 2.0]
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn cached_texts_skip_the_provider() {
+        let dir = tempfile::tempdir().unwrap();
+        let (service, provider) = service(dir.path(), 10);
+        service.embed(texts(&["a", "bb"])).await.unwrap();
+
+        let response = service.embed(texts(&["bb", "dddd"])).await.unwrap();
+
+        assert_eq!(response.cached, 1);
+        assert_eq!(
+            *provider.batches.lock().unwrap(),
+            vec![vec!["a", "bb"], vec!["dddd"]]
+        );
+        assert_eq!(
+            response.into_vectors(),
+            vec![vec![2.0, 1.0], vec![4.0, 2.0]]
+        );
+    }
+
+    #[tokio::test]
+    async fn the_cache_is_per_model() {
+        let dir = tempfile::tempdir().unwrap();
+        let (service, provider) = service(dir.path(), 10);
+        service.embed(texts(&["a"])).await.unwrap();
+
+        let mut request = texts(&["a"]);
+        request.model = Some("text-embedding-3-large".to_string());
+        let response = service.embed(request).await.unwrap();
+
+        assert_eq!(response.cached, 0);
+        assert_eq!(response.model, "text-embedding-3-large");
+        assert_eq!(provider.batches.lock().unwrap().len(), 2);
+    }
+}
@@ -0,0 +1,100 @@
+//! Provider-agnostic prompt caching hints.
+//!
+//! Every executor marks the same stable prefix of a request as cacheable,
+//! each in its provider's wire format: the end of the system prompt, the
+//! end of the tool schemas, and a message a few turns back so the earlier
+//! conversation is reused by the next call. [`PromptCache::Off`] in the
+//! model settings turns all of it off.
+
+use distri_types::{ModelProvider, ModelSettings, PromptCache};
+
+/// Messages from the end of the conversation to the cached-prefix boundary;
+/// the latest turns change on every call.
+const CONVERSATION_BREAKPOINT_OFFSET: usize = 4;
+
+/// Longest `prompt_cache_key` OpenAI accepts.
+const MAX_CACHE_KEY_LEN: usize = 64;
+
+/// Where a request's cacheable prefix ends.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CacheHints {
+    /// Mark the last system block.
+    pub system: bool,
+    /// Mark the last tool schema.
+    pub tools: bool,
+    /// Index of the (provider-format) message closing the cached
+    /// conversation prefix.
+    pub conversation: Option<usize>,
+}
+
+impl CacheHints {
+    /// Hints for a request of `message_count` messages, after the executor
+    /// merged them into its provider's format.
+    pub fn plan(prompt_cache: PromptCache, message_count: usize) -> Self {
+        match prompt_cache {
+            PromptCache::Off => Self::default(),
+            PromptCache::Auto => Self {
+                system: true,
+                tools: true,
+                conversation: (message_count > CONVERSATION_BREAKPOINT_OFFSET)
+                    .then(|| message_count - CONVERSATION_BREAKPOINT_OFFSET),
+            },
+        }
+    }
+}
+
+/// `prompt_cache_key` of an OpenAI request. OpenAI caches prefixes on its
+/// own; requests sharing a key are routed to the same cache, so every call
+/// of one agent shares it. `None` for other providers, which may reject the
+/// field.
+pub fn openai_cache_key(settings: &ModelSettings, agent: &str) -> Option<String> {
+    if !settings.inner.prompt_cache.is_auto()
+        || !matches!(settings.inner.provider, ModelProvider::OpenAI { .. })
+    {
+        return None;
+    }
+    let mut key = format!("distri:{}", agent);
+    if key.len() > MAX_CACHE_KEY_LEN {
+        let mut end = MAX_CACHE_KEY_LEN;
+        while !key.is_char_boundary(end) {
+            end -= 1;
+        }
+        key.truncate(end);
+    }
+    Some(key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn auto_marks_system_tools_and_an_older_turn() {
+        let hints = CacheHints::plan(PromptCache::Auto, 10);
+        assert!(hints.system && hints.tools);
+        assert_eq!(hints.conversation, Some(6));
+        assert_eq!(CacheHints::plan(PromptCache::Auto, 4).conversation, None);
+        assert_eq!(
+            CacheHints::plan(PromptCache::Off, 10),
+            CacheHints::default()
+        );
+    }
+
+    #[test]
+    fn cache_key_is_openai_only_and_bounded() {
+        let mut settings = ModelSettings::new("gpt-5.1");
+        let key = openai_cache_key(&settings, &"a".repeat(100)).unwrap();
+        assert_eq!(key.len(), MAX_CACHE_KEY_LEN);
+        assert!(key.starts_with("distri:"));
+
+        settings.inner.prompt_cache = PromptCache::Off;
+        assert_eq!(openai_cache_key(&settings, "coder"), None);
+
+        settings.inner.prompt_cache = PromptCache::Auto;
+        settings.inner.provider = ModelProvider::Anthropic {
+            base_url: None,
+            api_key: None,
+        };
+        assert_eq!(openai_cache_key(&settings, "coder"), None);
+    }
+}
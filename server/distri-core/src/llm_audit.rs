@@ -0,0 +1,155 @@
+//! Audit decorator for LLM executors.
+//!
+//! [`AuditedLlmExecutor`] wraps whatever executor [`create_llm_executor`]
+//! picked for the provider and writes one [`LlmAuditRecord`] per call to the
+//! configured [`LlmAuditStore`]. Recording failures are logged and never
+//! fail the LLM call itself.
+//!
+//! [`create_llm_executor`]: crate::llm::create_llm_executor
+
+use std::sync::Arc;
+use std::time::Instant;
+
+use distri_types::api::audit::{redact_pii, LlmAuditRecord};
+use distri_types::stores::LlmAuditStore;
+use distri_types::{Message, ToolCall};
+use serde_json::{json, Value};
+
+use crate::agent::ExecutorContext;
+use crate::llm::{LLMExecutorTrait, LLMResponse, StreamResult};
+use crate::AgentError;
+
+/// Resolve the audit store for `context`: execution-scoped stores first,
+/// then the orchestrator's.
+pub fn audit_store(context: &ExecutorContext) -> Option<Arc<dyn LlmAuditStore>> {
+    context
+        .stores
+        .as_ref()
+        .and_then(|s| s.llm_audit_store.clone())
+        .or_else(|| {
+            context
+                .orchestrator
+                .as_ref()
+                .and_then(|o| o.stores.llm_audit_store.clone())
+        })
+}
+
+pub struct AuditedLlmExecutor {
+    inner: Box<dyn LLMExecutorTrait>,
+    store: Arc<dyn LlmAuditStore>,
+    context: Arc<ExecutorContext>,
+    provider: String,
+    model: String,
+}
+
+impl std::fmt::Debug for AuditedLlmExecutor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AuditedLlmExecutor")
+            .field("inner", &self.inner)
+            .field("provider", &self.provider)
+            .field("model", &self.model)
+            .finish()
+    }
+}
+
+impl AuditedLlmExecutor {
+    pub fn new(
+        inner: Box<dyn LLMExecutorTrait>,
+        store: Arc<dyn LlmAuditStore>,
+        context: Arc<ExecutorContext>,
+        provider: impl Into<String>,
+        model: impl Into<String>,
+    ) -> Self {
+        Self {
+            inner,
+            store,
+            context,
+            provider: provider.into(),
+            model: model.into(),
+        }
+    }
+
+    async fn write(
+        &self,
+        messages: &[Message],
+        streaming: bool,
+        started: Instant,
+        outcome: Result<(&str, &[ToolCall], Option<&distri_types::TokenUsage>), &AgentError>,
+    ) {
+        let mut request = serde_json::to_value(messages).unwrap_or(Value::Null);
+        let (mut response, error, usage) = match outcome {
+            Ok((content, tool_calls, usage)) => (
+                json!({ "content": content, "tool_calls": tool_calls }),
+                None,
+                usage,
+            ),
+            Err(e) => (Value::Null, Some(e.to_string()), None),
+        };
+        let redacted = self.store.redact();
+        let error = if redacted {
+            request = redact_pii(&request);
+            response = redact_pii(&response);
+            error.map(|e| distri_types::api::audit::redact_text(&e))
+        } else {
+            error
+        };
+
+        let record = LlmAuditRecord {
+            id: uuid::Uuid::new_v4(),
+            created_at: chrono::Utc::now(),
+            agent_id: self.context.agent_id.clone(),
+            user_id: self.context.user_id.clone(),
+            thread_id: self.context.thread_id.clone(),
+            task_id: self.context.task_id.clone(),
+            workspace_id: self.context.workspace_id.clone(),
+            provider: self.provider.clone(),
+            model: self.model.clone(),
+            streaming,
+            request,
+            response,
+            error,
+            input_tokens: usage.map(|u| u.input_tokens).unwrap_or_default(),
+            output_tokens: usage.map(|u| u.output_tokens).unwrap_or_default(),
+            duration_ms: started.elapsed().as_millis() as u64,
+            redacted,
+        };
+        if let Err(e) = self.store.record(record).await {
+            tracing::warn!("failed to write LLM audit record: {e}");
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl LLMExecutorTrait for AuditedLlmExecutor {
+    async fn execute(&self, messages: &[Message]) -> Result<LLMResponse, AgentError> {
+        let started = Instant::now();
+        let result = self.inner.execute(messages).await;
+        let outcome = result.as_ref().map(|r| {
+            (
+                r.content.as_str(),
+                r.tool_calls.as_slice(),
+                r.usage.as_ref(),
+            )
+        });
+        self.write(messages, false, started, outcome).await;
+        result
+    }
+
+    async fn execute_stream(
+        &self,
+        messages: &[Message],
+        context: Arc<ExecutorContext>,
+    ) -> Result<StreamResult, AgentError> {
+        let started = Instant::now();
+        let result = self.inner.execute_stream(messages, context).await;
+        let outcome = result.as_ref().map(|r| {
+            (
+                r.content.as_str(),
+                r.tool_calls.as_slice(),
+                r.usage.as_ref(),
+            )
+        });
+        self.write(messages, true, started, outcome).await;
+        result
+    }
+}
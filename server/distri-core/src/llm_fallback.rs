@@ -0,0 +1,159 @@
+//! Model fallbacks for agent LLM calls.
+//!
+//! [`FallbackLlmExecutor`] makes each call on the agent's model and, when
+//! the provider fails (an outage, a rate limit, a missing key), repeats it
+//! on each of the agent's `model_fallbacks` in turn. Executors for the
+//! fallbacks are built only when needed, with their provider's stored
+//! credentials. A streamed call that fails partway is repeated in full, so
+//! the text the failed model already streamed stays in the run's events.
+
+use std::sync::Arc;
+
+use distri_types::{LlmDefinition, Message};
+
+use crate::agent::ExecutorContext;
+use crate::llm::{create_llm_executor, LLMExecutorTrait, LLMResponse, StreamResult};
+use crate::tools::Tool;
+use crate::AgentError;
+
+pub struct FallbackLlmExecutor {
+    primary: Box<dyn LLMExecutorTrait>,
+    fallbacks: Vec<LlmDefinition>,
+    tools: Vec<Arc<dyn Tool>>,
+    context: Arc<ExecutorContext>,
+}
+
+impl std::fmt::Debug for FallbackLlmExecutor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FallbackLlmExecutor")
+            .field("primary", &self.primary)
+            .field(
+                "fallbacks",
+                &self
+                    .fallbacks
+                    .iter()
+                    .filter_map(|def| def.model_settings.as_ref().map(|ms| &ms.model))
+                    .collect::<Vec<_>>(),
+            )
+            .finish()
+    }
+}
+
+/// Whether a call failing with `error` may succeed on another model.
+/// Cancellation and answers the model got wrong are not retried.
+fn is_provider_failure(error: &AgentError) -> bool {
+    matches!(
+        error,
+        AgentError::LLMError(_)
+            | AgentError::LlmExecutionFailed(_)
+            | AgentError::OpenAIError(_)
+            | AgentError::InvalidConfiguration(_)
+            | AgentError::Other(_)
+    )
+}
+
+impl FallbackLlmExecutor {
+    pub fn new(
+        primary: Box<dyn LLMExecutorTrait>,
+        fallbacks: Vec<LlmDefinition>,
+        tools: Vec<Arc<dyn Tool>>,
+        context: Arc<ExecutorContext>,
+    ) -> Self {
+        Self {
+            primary,
+            fallbacks,
+            tools,
+            context,
+        }
+    }
+
+    async fn build(
+        &self,
+        definition: &LlmDefinition,
+    ) -> Result<Box<dyn LLMExecutorTrait>, AgentError> {
+        let mut definition = definition.clone();
+        let secret_store = self
+            .context
+            .orchestrator
+            .as_ref()
+            .and_then(|o| o.stores.secret_store.clone());
+        if let (Some(ms), Some(store)) = (definition.model_settings.as_mut(), secret_store) {
+            ms.hydrate_creds(store.as_ref())
+                .await
+                .map_err(AgentError::InvalidConfiguration)?;
+        }
+        create_llm_executor(
+            definition,
+            self.tools.clone(),
+            self.context.clone(),
+            None,
+            None,
+        )
+    }
+
+    /// Try `call` on each fallback after the primary failed with `error`.
+    async fn fall_back<T, F, Fut>(&self, mut error: AgentError, call: F) -> Result<T, AgentError>
+    where
+        F: Fn(Box<dyn LLMExecutorTrait>) -> Fut,
+        Fut: std::future::Future<Output = Result<T, AgentError>>,
+    {
+        for definition in &self.fallbacks {
+            if !is_provider_failure(&error) {
+                break;
+            }
+            let model = definition
+                .model_settings
+                .as_ref()
+                .map(|ms| ms.model.clone())
+                .unwrap_or_default();
+            tracing::warn!(
+                agent = %self.context.agent_id,
+                fallback = %model,
+                "model call failed ({}), falling back",
+                error
+            );
+            let result = match self.build(definition).await {
+                Ok(executor) => call(executor).await,
+                Err(e) => Err(e),
+            };
+            match result {
+                Ok(response) => return Ok(response),
+                Err(e) => error = e,
+            }
+        }
+        Err(error)
+    }
+}
+
+#[async_trait::async_trait]
+impl LLMExecutorTrait for FallbackLlmExecutor {
+    async fn execute(&self, messages: &[Message]) -> Result<LLMResponse, AgentError> {
+        match self.primary.execute(messages).await {
+            Ok(response) => Ok(response),
+            Err(error) => {
+                self.fall_back(
+                    error,
+                    |executor| async move { executor.execute(messages).await },
+                )
+                .await
+            }
+        }
+    }
+
+    async fn execute_stream(
+        &self,
+        messages: &[Message],
+        context: Arc<ExecutorContext>,
+    ) -> Result<StreamResult, AgentError> {
+        match self.primary.execute_stream(messages, context.clone()).await {
+            Ok(response) => Ok(response),
+            Err(error) => {
+                self.fall_back(error, |executor| {
+                    let context = context.clone();
+                    async move { executor.execute_stream(messages, context).await }
+                })
+                .await
+            }
+        }
+    }
+}
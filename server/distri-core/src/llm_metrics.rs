@@ -0,0 +1,114 @@
+//! Latency metrics decorator for LLM executors.
+//!
+//! [`MeteredLlmExecutor`] wraps the executor of every LLM call an
+//! orchestrator makes and records its duration, time to first token and
+//! output tokens in the orchestrator's
+//! [`LlmMetrics`](distri_types::llm_metrics::LlmMetrics), which back the
+//! `llm_latency` of the home stats and `/metrics`.
+
+use std::sync::Arc;
+use std::time::Instant;
+
+use distri_types::llm_metrics::{LlmCallTiming, LlmMetrics};
+use distri_types::{LlmDefinition, Message, TokenUsage};
+
+use crate::agent::ExecutorContext;
+use crate::llm::{LLMExecutorTrait, LLMResponse, StreamResult};
+use crate::AgentError;
+
+/// Where the calls of an executor are recorded, and under which label.
+pub struct Meter {
+    metrics: Arc<LlmMetrics>,
+    provider: String,
+    model: String,
+}
+
+impl Meter {
+    /// `None` outside an orchestrator or without a model.
+    pub fn for_call(llm_def: &LlmDefinition, context: &ExecutorContext) -> Option<Self> {
+        let ms = llm_def.ms().ok()?;
+        Some(Self {
+            metrics: context.orchestrator.as_ref()?.llm_metrics.clone(),
+            provider: ms.inner.provider.provider_id().to_string(),
+            model: ms.model.clone(),
+        })
+    }
+}
+
+/// `executor`, recording its calls with `meter` when there is one.
+pub fn metered(
+    executor: Box<dyn LLMExecutorTrait>,
+    meter: Option<Meter>,
+) -> Box<dyn LLMExecutorTrait> {
+    match meter {
+        Some(meter) => Box::new(MeteredLlmExecutor::new(executor, meter)),
+        None => executor,
+    }
+}
+
+pub struct MeteredLlmExecutor {
+    inner: Box<dyn LLMExecutorTrait>,
+    meter: Meter,
+}
+
+impl std::fmt::Debug for MeteredLlmExecutor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MeteredLlmExecutor")
+            .field("inner", &self.inner)
+            .field("provider", &self.meter.provider)
+            .field("model", &self.meter.model)
+            .finish()
+    }
+}
+
+impl MeteredLlmExecutor {
+    pub fn new(inner: Box<dyn LLMExecutorTrait>, meter: Meter) -> Self {
+        Self { inner, meter }
+    }
+
+    fn record(
+        &self,
+        started: Instant,
+        first_token_at: Option<Instant>,
+        outcome: Result<Option<&TokenUsage>, &AgentError>,
+    ) {
+        self.meter.metrics.record(LlmCallTiming {
+            provider: self.meter.provider.clone(),
+            model: self.meter.model.clone(),
+            success: outcome.is_ok(),
+            duration_ms: started.elapsed().as_millis() as u64,
+            ttft_ms: first_token_at.map(|at| at.duration_since(started).as_millis() as u64),
+            output_tokens: outcome
+                .ok()
+                .flatten()
+                .map(|u| u.output_tokens)
+                .unwrap_or_default(),
+        });
+    }
+}
+
+#[async_trait::async_trait]
+impl LLMExecutorTrait for MeteredLlmExecutor {
+    async fn execute(&self, messages: &[Message]) -> Result<LLMResponse, AgentError> {
+        let started = Instant::now();
+        let result = self.inner.execute(messages).await;
+        self.record(started, None, result.as_ref().map(|r| r.usage.as_ref()));
+        result
+    }
+
+    async fn execute_stream(
+        &self,
+        messages: &[Message],
+        context: Arc<ExecutorContext>,
+    ) -> Result<StreamResult, AgentError> {
+        let started = Instant::now();
+        let result = self.inner.execute_stream(messages, context).await;
+        let first_token_at = result.as_ref().ok().and_then(|r| r.first_token_at);
+        self.record(
+            started,
+            first_token_at,
+            result.as_ref().map(|r| r.usage.as_ref()),
+        );
+        result
+    }
+}
@@ -0,0 +1,339 @@
+//! Agents as MCP tools: the protocol behind the server's `/mcp` endpoint.
+//!
+//! MCP hosts (IDEs, desktop assistants) list the registered agents as tools
+//! and call them like any other tool. A tool takes the `message` to send and,
+//! to continue a conversation, the `thread_id` an earlier call returned in
+//! its `_meta`. A call runs the agent to its final answer. When the caller
+//! passes a `progressToken`, the run's steps and tool calls are reported as
+//! `notifications/progress` before the result, and the reply is a stream;
+//! dropping the stream cancels the run.
+
+use std::pin::Pin;
+use std::sync::Arc;
+
+use distri_a2a::{JsonRpcError, JsonRpcResponse};
+use distri_types::configuration::AgentConfig;
+use futures_util::stream::Stream;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tokio::sync::mpsc;
+
+use crate::agent::{AgentEvent, AgentEventType, AgentOrchestrator, ExecutorContext};
+use crate::types::{Message, ModelSettings};
+
+/// Protocol version answered when the client asks for one we do not know.
+pub const PROTOCOL_VERSION: &str = "2025-06-18";
+/// Protocol versions the facade speaks, newest first.
+pub const SUPPORTED_PROTOCOL_VERSIONS: &[&str] = &["2025-06-18", "2025-03-26", "2024-11-05"];
+/// `_meta` key of the thread a call ran on.
+pub const THREAD_META_KEY: &str = "distri/thread_id";
+/// `_meta` key of the task a call ran as.
+pub const TASK_META_KEY: &str = "distri/task_id";
+
+const EVENT_BUFFER: usize = 256;
+
+/// A JSON-RPC request or notification. Unlike A2A requests, MCP ones may
+/// leave out `params`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct McpRequest {
+    pub method: String,
+    #[serde(default)]
+    pub params: Value,
+    /// `None` for notifications.
+    #[serde(default)]
+    pub id: Option<Value>,
+}
+
+/// Arguments of an agent tool.
+#[derive(Debug, Clone, Deserialize)]
+pub struct AgentToolArgs {
+    pub message: String,
+    #[serde(default)]
+    pub thread_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CallToolParams {
+    name: String,
+    #[serde(default)]
+    arguments: Value,
+    #[serde(default, rename = "_meta")]
+    meta: Option<Value>,
+}
+
+/// Messages of a streamed reply: progress notifications, then the response.
+pub type McpMessageStream = Pin<Box<dyn Stream<Item = Value> + Send>>;
+
+/// How to answer an MCP request.
+pub enum McpReply {
+    /// A notification: nothing to answer.
+    Accepted,
+    Response(JsonRpcResponse),
+    Stream(McpMessageStream),
+}
+
+/// The MCP server of one caller.
+#[derive(Clone)]
+pub struct McpFacade {
+    orchestrator: Arc<AgentOrchestrator>,
+    user_id: String,
+    default_model_settings: Option<ModelSettings>,
+}
+
+/// The tool name of `agent`: MCP hosts accept letters, digits, `_` and `-`,
+/// so a namespaced `package/agent` becomes `package_agent`.
+pub fn tool_name(agent: &str) -> String {
+    agent
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '_' || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// What the caller sees of a step of the run, if anything.
+pub fn progress_message(event: &AgentEvent) -> Option<String> {
+    let message = match &event.event {
+        AgentEventType::PlanStarted { .. } => "Planning".to_string(),
+        AgentEventType::StepStarted { step_index, .. } => format!("Step {}", step_index + 1),
+        AgentEventType::ToolExecutionStart { tool_call_name, .. } => {
+            format!("Calling {}", tool_call_name)
+        }
+        AgentEventType::ToolExecutionEnd {
+            tool_call_name,
+            success: false,
+            ..
+        } => format!("{} failed", tool_call_name),
+        _ => return None,
+    };
+    // Sub-agents' steps are attributed to them.
+    Some(match event.parent_task_id {
+        Some(_) => format!("[{}] {}", event.agent_id, message),
+        None => message,
+    })
+}
+
+fn agent_tool(agent: &AgentConfig) -> Value {
+    json!({
+        "name": tool_name(agent.get_name()),
+        "title": agent.get_name(),
+        "description": agent.get_description(),
+        "inputSchema": {
+            "type": "object",
+            "properties": {
+                "message": {
+                    "type": "string",
+                    "description": "What to ask the agent"
+                },
+                "thread_id": {
+                    "type": "string",
+                    "description": "Continue the conversation of an earlier call (its _meta.\"distri/thread_id\")"
+                }
+            },
+            "required": ["message"]
+        }
+    })
+}
+
+fn tool_result(text: String, is_error: bool, meta: Value) -> Value {
+    json!({
+        "content": [{ "type": "text", "text": text }],
+        "isError": is_error,
+        "_meta": meta,
+    })
+}
+
+fn progress_notification(token: &Value, progress: u64, message: String) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "method": "notifications/progress",
+        "params": {
+            "progressToken": token,
+            "progress": progress,
+            "message": message,
+        }
+    })
+}
+
+fn respond(id: Option<Value>, result: Result<Value, JsonRpcError>) -> JsonRpcResponse {
+    match result {
+        Ok(result) => JsonRpcResponse::success(id, result),
+        Err(error) => JsonRpcResponse::error(id, error),
+    }
+}
+
+impl McpFacade {
+    pub fn new(orchestrator: Arc<AgentOrchestrator>, user_id: impl Into<String>) -> Self {
+        Self {
+            orchestrator,
+            user_id: user_id.into(),
+            default_model_settings: None,
+        }
+    }
+
+    /// Model settings for agents that do not name their own, such as a
+    /// workspace's default.
+    pub fn with_default_model_settings(mut self, settings: Option<ModelSettings>) -> Self {
+        self.default_model_settings = settings;
+        self
+    }
+
+    pub async fn handle(&self, request: McpRequest) -> McpReply {
+        let Some(id) = request.id else {
+            // `notifications/initialized`, `notifications/cancelled`, ...
+            return McpReply::Accepted;
+        };
+        let result = match request.method.as_str() {
+            "initialize" => Ok(self.initialize(&request.params)),
+            "ping" => Ok(json!({})),
+            "tools/list" => Ok(json!({ "tools": self.list_tools().await })),
+            "tools/call" => return self.call(id, request.params).await,
+            method => Err(JsonRpcError::method_not_found(method)),
+        };
+        McpReply::Response(respond(Some(id), result))
+    }
+
+    fn initialize(&self, params: &Value) -> Value {
+        let requested = params.get("protocolVersion").and_then(Value::as_str);
+        let version = requested
+            .and_then(|v| SUPPORTED_PROTOCOL_VERSIONS.iter().find(|s| **s == v))
+            .copied()
+            .unwrap_or(PROTOCOL_VERSION);
+        json!({
+            "protocolVersion": version,
+            "capabilities": { "tools": { "listChanged": false } },
+            "serverInfo": { "name": "distri", "version": env!("CARGO_PKG_VERSION") },
+            "instructions": "Each tool is a distri agent. Send it a message; pass the \
+                thread_id from a result's _meta to continue that conversation."
+        })
+    }
+
+    /// Every registered agent, as an MCP tool.
+    pub async fn list_tools(&self) -> Vec<Value> {
+        self.agents().await.iter().map(agent_tool).collect()
+    }
+
+    async fn agents(&self) -> Vec<AgentConfig> {
+        let mut agents = Vec::new();
+        let mut cursor = None;
+        loop {
+            let (page, next) = self.orchestrator.list_agents(cursor, None).await;
+            agents.extend(page);
+            match next {
+                Some(next) => cursor = Some(next),
+                None => return agents,
+            }
+        }
+    }
+
+    /// The agent a tool name stands for.
+    async fn resolve(&self, tool: &str) -> Option<String> {
+        if self.orchestrator.get_agent(tool).await.is_some() {
+            return Some(tool.to_string());
+        }
+        self.agents()
+            .await
+            .into_iter()
+            .map(|agent| agent.get_name().to_string())
+            .find(|name| tool_name(name) == tool)
+    }
+
+    async fn call(&self, id: Value, params: Value) -> McpReply {
+        let params: CallToolParams = match serde_json::from_value(params) {
+            Ok(params) => params,
+            Err(e) => {
+                let error = JsonRpcError::invalid_params(format!("Invalid params: {}", e));
+                return McpReply::Response(JsonRpcResponse::error(Some(id), error));
+            }
+        };
+        let token = params
+            .meta
+            .as_ref()
+            .and_then(|meta| meta.get("progressToken"))
+            .cloned();
+        let Some(token) = token else {
+            let result = self.call_tool(&params.name, params.arguments, None).await;
+            return McpReply::Response(respond(Some(id), result));
+        };
+
+        let facade = self.clone();
+        McpReply::Stream(Box::pin(async_stream::stream! {
+            let (tx, mut rx) = mpsc::channel(EVENT_BUFFER);
+            let call = facade.call_tool(&params.name, params.arguments, Some(tx));
+            tokio::pin!(call);
+            let mut progress = 0;
+            let result = loop {
+                tokio::select! {
+                    result = &mut call => break result,
+                    Some(event) = rx.recv() => {
+                        if let Some(message) = progress_message(&event) {
+                            progress += 1;
+                            yield progress_notification(&token, progress, message);
+                        }
+                    }
+                }
+            };
+            while let Ok(event) = rx.try_recv() {
+                if let Some(message) = progress_message(&event) {
+                    progress += 1;
+                    yield progress_notification(&token, progress, message);
+                }
+            }
+            yield serde_json::to_value(respond(Some(id), result)).unwrap_or_default();
+        }))
+    }
+
+    /// Run the agent behind `tool`. A failed run is a tool result with
+    /// `isError`, as MCP expects; only bad calls are protocol errors.
+    pub async fn call_tool(
+        &self,
+        tool: &str,
+        arguments: Value,
+        event_tx: Option<mpsc::Sender<AgentEvent>>,
+    ) -> Result<Value, JsonRpcError> {
+        let Some(agent) = self.resolve(tool).await else {
+            return Err(JsonRpcError::invalid_params(format!(
+                "Unknown tool: {}",
+                tool
+            )));
+        };
+        let args: AgentToolArgs = serde_json::from_value(arguments)
+            .map_err(|e| JsonRpcError::invalid_params(format!("Invalid arguments: {}", e)))?;
+
+        let mut context = ExecutorContext {
+            agent_id: agent.clone(),
+            user_id: self.user_id.clone(),
+            tenant_context: distri_types::TenantContext::new(self.user_id.clone(), None),
+            orchestrator: Some(self.orchestrator.clone()),
+            event_tx: event_tx.map(Arc::new),
+            default_model_settings: self.default_model_settings.clone(),
+            ..Default::default()
+        };
+        if let Some(thread_id) = args.thread_id {
+            context.thread_id = thread_id;
+        }
+        let meta = json!({
+            THREAD_META_KEY: context.thread_id,
+            TASK_META_KEY: context.task_id,
+        });
+
+        let message = Message::user(args.message, None);
+        let result = self
+            .orchestrator
+            .execute_stream(&agent, message, Arc::new(context), None)
+            .await;
+        Ok(match result {
+            Ok(result) if !result.tool_calls.is_empty() => tool_result(
+                "The agent asked for external tools, which MCP calls cannot provide".to_string(),
+                true,
+                meta,
+            ),
+            Ok(result) => tool_result(result.content.unwrap_or_default(), false, meta),
+            Err(e) => tool_result(e.to_string(), true, meta),
+        })
+    }
+}
@@ -18,7 +18,7 @@ use crate::{
     types::{Message, MessageRole, Part, ToolCall},
     AgentError,
 };
-use distri_parsers::{StreamParseResult, ToolCallParser};
+use distri_parsers::{StreamParseResult, ToolCallDelta, ToolCallParser};
 use distri_types::{FileType, LlmDefinition, ModelProvider, ToolCallFormat};
 use futures::StreamExt;
 use serde_json::Value;
@@ -121,8 +121,8 @@ impl OpenAIResponsesLLMExecutor {
             ModelProvider::AzureOpenAI {
                 base_url,
                 api_key,
-                deployment,
                 api_version,
+                ..
             } => {
                 let resolved_key = if let Some(key) = api_key {
                     key.clone()
@@ -136,7 +136,7 @@ impl OpenAIResponsesLLMExecutor {
                 let azure_base = format!(
                     "{}/openai/deployments/{}",
                     base_url.trim_end_matches('/'),
-                    deployment
+                    ms.provider_model_id()
                 );
                 // Add api-version query param via a custom URL
                 let url_with_version = format!("{}?api-version={}", azure_base, api_version);
@@ -419,8 +419,10 @@ impl OpenAIResponsesLLMExecutor {
             messages.len()
         );
 
-        let context_manager = crate::agent::context_size_manager::ContextSizeManager::default();
-        context_manager.validate_context_size(messages, ms.effective_context_size())?;
+        let fitted =
+            crate::agent::context_fit::fit_to_context(&self.context, ms, &self.tools, messages)
+                .await?;
+        let messages = &*fitted;
 
         let (instructions, input_items) = self.map_messages(messages);
 
@@ -452,6 +454,11 @@ impl OpenAIResponsesLLMExecutor {
             max_output_tokens: ms.inner.max_tokens.or(Some(DEFAULT_MAX_OUTPUT_TOKENS)),
             stream: None,
             truncation: Some(serde_json::json!("auto")),
+            reasoning: ms
+                .inner
+                .reasoning_effort
+                .map(|effort| serde_json::json!({ "effort": effort.as_str() })),
+            prompt_cache_key: crate::llm::prompt_cache::openai_cache_key(ms, &self.llm_def.name),
         };
 
         let client = self.build_client().await?;
@@ -480,7 +487,11 @@ impl OpenAIResponsesLLMExecutor {
         let input_tokens = response.usage.input_tokens;
         let output_tokens = response.usage.output_tokens;
         self.context
-            .increment_usage(input_tokens, output_tokens)
+            .increment_usage_with_cache(
+                input_tokens,
+                output_tokens,
+                response.usage.input_tokens_details.cached_tokens,
+            )
             .await;
 
         let usage = Some(distri_types::TokenUsage {
@@ -660,8 +671,9 @@ impl OpenAIResponsesLLMExecutor {
             messages.len()
         );
 
-        let context_manager = crate::agent::context_size_manager::ContextSizeManager::default();
-        context_manager.validate_context_size(messages, ms.effective_context_size())?;
+        let fitted =
+            crate::agent::context_fit::fit_to_context(&context, ms, &self.tools, messages).await?;
+        let messages = &*fitted;
 
         let step_id = context.get_current_step_id().await.unwrap_or_default();
         let (instructions, input_items) = self.map_messages(messages);
@@ -694,6 +706,11 @@ impl OpenAIResponsesLLMExecutor {
             max_output_tokens: ms.inner.max_tokens.or(Some(DEFAULT_MAX_OUTPUT_TOKENS)),
             stream: Some(true),
             truncation: Some(serde_json::json!("auto")),
+            reasoning: ms
+                .inner
+                .reasoning_effort
+                .map(|effort| serde_json::json!({ "effort": effort.as_str() })),
+            prompt_cache_key: crate::llm::prompt_cache::openai_cache_key(ms, &self.llm_def.name),
         };
 
         let client = self.build_client().await?;
@@ -734,10 +751,20 @@ impl OpenAIResponsesLLMExecutor {
         let mut partial_function_calls: HashMap<usize, PartialFunctionCall> = HashMap::new();
         let mut stream_input_tokens: u32 = 0;
         let mut stream_output_tokens: u32 = 0;
+        let mut first_token_at = None;
 
         tokio::pin!(stream);
 
         while let Some(event_result) = stream.next().await {
+            if first_token_at.is_none()
+                && matches!(
+                    event_result,
+                    Ok(TypedStreamEvent::OutputTextDelta { .. }
+                        | TypedStreamEvent::FunctionCallArgumentsDelta { .. })
+                )
+            {
+                first_token_at = Some(std::time::Instant::now());
+            }
             match event_result {
                 Ok(event) => match event {
                     TypedStreamEvent::ResponseCreated(resp) => {
@@ -749,12 +776,22 @@ impl OpenAIResponsesLLMExecutor {
                         }
                     }
                     TypedStreamEvent::ResponseCompleted(resp) => {
-                        if resp.usage.output_tokens > 0 {
-                            stream_output_tokens += resp.usage.output_tokens;
-                            self.context
-                                .increment_usage(0, resp.usage.output_tokens)
-                                .await;
-                        }
+                        // Usage usually only arrives here; take the input
+                        // tokens unless `response.created` already had them.
+                        let input_tokens = if stream_input_tokens == 0 {
+                            resp.usage.input_tokens
+                        } else {
+                            0
+                        };
+                        stream_input_tokens += input_tokens;
+                        stream_output_tokens += resp.usage.output_tokens;
+                        self.context
+                            .increment_usage_with_cache(
+                                input_tokens,
+                                resp.usage.output_tokens,
+                                resp.usage.input_tokens_details.cached_tokens,
+                            )
+                            .await;
                     }
                     TypedStreamEvent::ResponseFailed(resp) => {
                         return Err(AgentError::LLMError(format!(
@@ -764,6 +801,15 @@ impl OpenAIResponsesLLMExecutor {
                     }
                     TypedStreamEvent::OutputItemAdded { output_index, item } => {
                         if let OutputItem::FunctionCall(fc) = &item {
+                            crate::llm::emit_tool_call_delta(
+                                &context,
+                                &step_id,
+                                ToolCallDelta::Start {
+                                    tool_call_id: fc.call_id.clone(),
+                                    tool_name: fc.name.clone(),
+                                },
+                            )
+                            .await;
                             partial_function_calls.insert(
                                 output_index,
                                 PartialFunctionCall {
@@ -791,12 +837,15 @@ impl OpenAIResponsesLLMExecutor {
                         let (delta_to_emit, verbose_blocks) = match parser
                             .as_mut()
                             .map(|p| p.process_chunk(&delta))
-                            .unwrap_or(Ok(StreamParseResult {
-                                new_tool_calls: Vec::new(),
-                                stripped_content_blocks: None,
-                                has_partial_tool_call: false,
-                            })) {
+                            .unwrap_or(Ok(StreamParseResult::default()))
+                        {
                             Ok(parse_result) => {
+                                for tool_delta in parse_result.tool_call_deltas.clone() {
+                                    crate::llm::emit_tool_call_delta(
+                                        &context, &step_id, tool_delta,
+                                    )
+                                    .await;
+                                }
                                 if !parse_result.new_tool_calls.is_empty() {
                                     tool_calls.extend(parse_result.new_tool_calls.clone());
                                 }
@@ -864,6 +913,15 @@ impl OpenAIResponsesLLMExecutor {
                     } => {
                         if let Some(partial) = partial_function_calls.get_mut(&output_index) {
                             partial.arguments.push_str(&delta);
+                            crate::llm::emit_tool_call_delta(
+                                &context,
+                                &step_id,
+                                ToolCallDelta::Args {
+                                    tool_call_id: partial.call_id.clone(),
+                                    delta,
+                                },
+                            )
+                            .await;
                         }
                     }
                     TypedStreamEvent::FunctionCallArgumentsDone {
@@ -1002,6 +1060,8 @@ impl OpenAIResponsesLLMExecutor {
             finish_reason,
             tool_calls,
             content,
+            usage: super::llm::stream_usage(stream_input_tokens, stream_output_tokens),
+            first_token_at,
         })
     }
 }
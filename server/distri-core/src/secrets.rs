@@ -3,10 +3,17 @@
 //! This module provides utilities for:
 //! - Loading secrets from SecretStore with environment variable fallback
 //! - Validating that required provider secrets are configured
+//! - Resolving `{{secret:NAME}}` references in configs (see [`distri_types::secret_ref`])
 //! - Per-tenant secret isolation
 
 use crate::AgentError;
+use distri_types::secret_ref::{
+    collect_secret_refs, collect_secret_refs_in, secret_refs_outside, substitute_secret_refs,
+    substitute_secret_refs_in,
+};
 use distri_types::{stores::SecretStore, ModelProvider};
+use serde::{de::DeserializeOwned, Serialize};
+use std::collections::{BTreeSet, HashMap};
 use std::sync::Arc;
 
 /// Result of secret resolution
@@ -103,6 +110,106 @@ impl SecretResolver {
         missing
     }
 
+    /// Names referenced as `{{secret:NAME}}` in `config` that are not configured.
+    pub async fn missing_secret_refs<T: Serialize>(&self, config: &T) -> Vec<String> {
+        let Ok(value) = serde_json::to_value(config) else {
+            return Vec::new();
+        };
+        self.missing(collect_secret_refs(&value)).await
+    }
+
+    /// Replace every `{{secret:NAME}}` reference in `config` with the secret's
+    /// value. Fails, naming every missing secret, if any reference cannot be
+    /// resolved.
+    pub async fn resolve_secret_refs<T>(&self, config: &mut T) -> Result<(), AgentError>
+    where
+        T: Serialize + DeserializeOwned,
+    {
+        let mut value = serde_json::to_value(&*config)
+            .map_err(|e| AgentError::InvalidConfiguration(e.to_string()))?;
+        let names = collect_secret_refs(&value);
+        if names.is_empty() {
+            return Ok(());
+        }
+        let secrets = self.resolve_all(names).await?;
+        substitute_secret_refs(&mut value, &secrets);
+        *config = serde_json::from_value(value)
+            .map_err(|e| AgentError::InvalidConfiguration(e.to_string()))?;
+        Ok(())
+    }
+
+    /// [`Self::resolve_secret_refs`] for a config that may only reference
+    /// secrets in `fields` (JSON pointers, `*` for any key or index). Fails
+    /// when a reference is anywhere else, naming where.
+    pub async fn resolve_secret_refs_in<T>(
+        &self,
+        config: &mut T,
+        fields: &[&str],
+    ) -> Result<(), AgentError>
+    where
+        T: Serialize + DeserializeOwned,
+    {
+        let mut value = serde_json::to_value(&*config)
+            .map_err(|e| AgentError::InvalidConfiguration(e.to_string()))?;
+        let outside = secret_refs_outside(&value, fields);
+        if !outside.is_empty() {
+            return Err(AgentError::InvalidConfiguration(
+                Self::format_misplaced_refs_error(&outside, fields),
+            ));
+        }
+        let names = collect_secret_refs_in(&value, fields);
+        if names.is_empty() {
+            return Ok(());
+        }
+        let secrets = self.resolve_all(names).await?;
+        substitute_secret_refs_in(&mut value, fields, &secrets);
+        *config = serde_json::from_value(value)
+            .map_err(|e| AgentError::InvalidConfiguration(e.to_string()))?;
+        Ok(())
+    }
+
+    async fn missing(&self, names: BTreeSet<String>) -> Vec<String> {
+        let mut missing = Vec::new();
+        for name in names {
+            if self.resolve(&name).await.is_none() {
+                missing.push(name);
+            }
+        }
+        missing
+    }
+
+    /// The values of `names`, or an error naming every missing one.
+    async fn resolve_all(
+        &self,
+        names: BTreeSet<String>,
+    ) -> Result<HashMap<String, String>, AgentError> {
+        let mut secrets = HashMap::new();
+        let mut missing = Vec::new();
+        for name in names {
+            match self.resolve(&name).await {
+                Some(secret) => {
+                    secrets.insert(name, secret.value);
+                }
+                None => missing.push(name),
+            }
+        }
+        if !missing.is_empty() {
+            return Err(AgentError::InvalidConfiguration(
+                Self::format_missing_secrets_error(&missing),
+            ));
+        }
+        Ok(secrets)
+    }
+
+    /// Error for secret references outside the fields that may hold them.
+    pub fn format_misplaced_refs_error(pointers: &[String], fields: &[&str]) -> String {
+        format!(
+            "secret references are only allowed in {}; found in {}",
+            fields.join(", "),
+            pointers.join(", ")
+        )
+    }
+
     /// Get a friendly error message for missing secrets
     pub fn format_missing_secrets_error(missing: &[String]) -> String {
         if missing.is_empty() {
@@ -170,4 +277,32 @@ mod tests {
         let result = resolver.validate_provider(&provider).await;
         assert!(result.is_err());
     }
+
+    #[tokio::test]
+    async fn test_resolve_secret_refs() {
+        std::env::set_var("TEST_SECRET_REF_TOKEN", "tok");
+        let resolver = SecretResolver::new(None);
+
+        let mut config = HashMap::from([(
+            "url".to_string(),
+            "https://api.example.com?key={{secret:TEST_SECRET_REF_TOKEN}}".to_string(),
+        )]);
+        resolver.resolve_secret_refs(&mut config).await.unwrap();
+        assert_eq!(config["url"], "https://api.example.com?key=tok");
+
+        let mut config = vec![
+            "{{secret:TEST_SECRET_REF_TOKEN}}".to_string(),
+            "{{secret:MISSING_REF_B}} {{secret:MISSING_REF_A}}".to_string(),
+        ];
+        assert_eq!(
+            resolver.missing_secret_refs(&config).await,
+            vec!["MISSING_REF_A", "MISSING_REF_B"]
+        );
+        let err = resolver.resolve_secret_refs(&mut config).await.unwrap_err();
+        assert!(err.to_string().contains("MISSING_REF_A, MISSING_REF_B"));
+        // Nothing is substituted when resolution fails.
+        assert_eq!(config[0], "{{secret:TEST_SECRET_REF_TOKEN}}");
+
+        std::env::remove_var("TEST_SECRET_REF_TOKEN");
+    }
 }
@@ -0,0 +1,1311 @@
+//! `calendar` — an in-memory MCP server for a Google or Microsoft calendar.
+//!
+//! - `calendar_list_events` lists the events between two times, recurring
+//!   events expanded into their occurrences, optionally matching a query.
+//! - `calendar_create_event` and `calendar_update_event` write events with
+//!   attendees, location and popup reminders (`reminder_minutes`).
+//! - `calendar_create_reminder` adds a short event that does not block time,
+//!   with a popup at (or before) its start.
+//! - `calendar_free_busy` returns the busy intervals of one or more
+//!   calendars and the free slots they leave in the window.
+//!
+//! Requests authenticate with the workspace's OAuth connection to the
+//! provider (see [`WorkspaceConnectionAuth`]), resolved on every call so an
+//! expired token is refreshed. Times the model passes without an offset are
+//! read in the call's `timezone`, else the configured `default_timezone`, and
+//! the tools report times in that zone. Microsoft Graph keeps one reminder
+//! per event: the earliest of `reminder_minutes` is used.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{anyhow, bail, Context, Result};
+use async_mcp::server::{Server, ServerBuilder};
+use async_mcp::transport::Transport;
+use async_mcp::types::{
+    CallToolRequest, CallToolResponse, ListRequest, PromptsListResponse, ResourcesListResponse,
+    ServerCapabilities, Tool, ToolResponseContent,
+};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, SecondsFormat, Utc};
+use distri_types::calendar::{CalendarMcpConfig, CalendarProvider};
+use distri_types::datetime::{localize, parse_datetime, parse_timezone, Tz};
+use distri_types::stores::InitializedStores;
+use reqwest::{RequestBuilder, Url};
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+
+use crate::connections::{ConnectionResolver, DefaultResolver, ResolveCtx};
+
+/// Length of a `calendar_create_reminder` event.
+const REMINDER_LENGTH_MINUTES: i64 = 15;
+
+/// Length of a created event given neither `end` nor `duration_minutes`.
+const DEFAULT_EVENT_MINUTES: i64 = 30;
+
+/// Headers that authenticate a request to the calendar provider.
+#[async_trait::async_trait]
+pub trait CalendarAuth: Send + Sync {
+    async fn headers(&self) -> Result<HashMap<String, String>>;
+}
+
+/// A connection of the single-tenant workspace, looked up by name and
+/// resolved on every request.
+pub struct WorkspaceConnectionAuth {
+    stores: InitializedStores,
+    workspace_id: String,
+    connection: String,
+}
+
+impl WorkspaceConnectionAuth {
+    pub fn new(stores: InitializedStores, connection: &str) -> Self {
+        Self {
+            stores,
+            workspace_id: uuid::Uuid::nil().to_string(),
+            connection: connection.to_string(),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl CalendarAuth for WorkspaceConnectionAuth {
+    async fn headers(&self) -> Result<HashMap<String, String>> {
+        let connection_store = self
+            .stores
+            .connection_store
+            .as_ref()
+            .context("no connection store is configured")?;
+        let connection = connection_store
+            .get_by_provider(&self.workspace_id, &self.connection)
+            .await?
+            .with_context(|| {
+                format!(
+                    "no '{}' connection: connect the calendar account first",
+                    self.connection
+                )
+            })?;
+        let ctx = ResolveCtx::new(&self.stores).with_workspace(&self.workspace_id);
+        let resolved = DefaultResolver
+            .resolve(&connection.id.to_string(), &ctx)
+            .await
+            .map_err(|e| anyhow!(e))?;
+        Ok(resolved.http_headers)
+    }
+}
+
+/// An event as the tools report it.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CalendarEvent {
+    pub id: String,
+    pub title: String,
+    /// RFC 3339 in the call's timezone, or `YYYY-MM-DD` for all-day events.
+    pub start: String,
+    /// Exclusive: the day after the last one for all-day events.
+    pub end: String,
+    pub all_day: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub location: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub attendees: Vec<String>,
+    /// Minutes before the start at which a popup shows.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub reminder_minutes: Vec<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+}
+
+/// Start or end of an event.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum When {
+    Date(NaiveDate),
+    Time(DateTime<Tz>),
+}
+
+/// Fields of an event to write. `None` leaves a field unchanged.
+#[derive(Debug, Default)]
+struct EventFields {
+    title: Option<String>,
+    start: Option<When>,
+    end: Option<When>,
+    location: Option<String>,
+    description: Option<String>,
+    attendees: Option<Vec<String>>,
+    reminder_minutes: Option<Vec<i64>>,
+    /// Whether the event blocks its time in free/busy.
+    busy: Option<bool>,
+}
+
+impl EventFields {
+    fn is_empty(&self) -> bool {
+        self.title.is_none()
+            && self.start.is_none()
+            && self.location.is_none()
+            && self.description.is_none()
+            && self.attendees.is_none()
+            && self.reminder_minutes.is_none()
+            && self.busy.is_none()
+    }
+}
+
+type Interval = (DateTime<Utc>, DateTime<Utc>);
+
+/// The tool implementations behind the server.
+pub struct CalendarTools {
+    config: CalendarMcpConfig,
+    auth: Arc<dyn CalendarAuth>,
+    client: reqwest::Client,
+}
+
+const TOOLS: &[&str] = &[
+    "calendar_list_events",
+    "calendar_create_event",
+    "calendar_update_event",
+    "calendar_create_reminder",
+    "calendar_free_busy",
+];
+
+impl CalendarTools {
+    pub fn new(config: CalendarMcpConfig, auth: Arc<dyn CalendarAuth>) -> Result<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(config.timeout_secs))
+            .build()?;
+        Ok(Self {
+            config,
+            auth,
+            client,
+        })
+    }
+
+    /// Run tool `name` with `args`, returning the text shown to the model.
+    pub async fn call(&self, name: &str, args: &Value) -> Result<String> {
+        let tz = self.timezone(args)?;
+        let now = Utc::now();
+        let calendar = optional_str(args, "calendar_id").unwrap_or(&self.config.default_calendar);
+        let response = match name {
+            "calendar_list_events" => {
+                let (from, to) = window(args, tz, now)?;
+                let max_results = args
+                    .get("max_results")
+                    .and_then(Value::as_u64)
+                    .map(|n| n as usize)
+                    .unwrap_or(self.config.max_results)
+                    .clamp(1, self.config.max_results.max(1));
+                let query = optional_str(args, "query");
+                let events = self
+                    .list_events(calendar, (from, to), query, max_results, tz)
+                    .await?;
+                json!({
+                    "calendar_id": calendar,
+                    "timezone": tz.name(),
+                    "time_min": format_time(from, tz),
+                    "time_max": format_time(to, tz),
+                    "events": events,
+                })
+            }
+            "calendar_create_event" => {
+                let fields = event_fields(args, tz, now, true)?;
+                let event = self.write_event(calendar, None, &fields, tz).await?;
+                json!({ "status": "created", "event": event })
+            }
+            "calendar_update_event" => {
+                let event_id = required_str(args, "event_id")?;
+                let fields = event_fields(args, tz, now, false)?;
+                if fields.is_empty() {
+                    bail!("nothing to update: pass at least one field to change");
+                }
+                let event = self
+                    .write_event(calendar, Some(event_id), &fields, tz)
+                    .await?;
+                json!({ "status": "updated", "event": event })
+            }
+            "calendar_create_reminder" => {
+                let at = time_arg(required_str(args, "at")?, tz, now)?;
+                let minutes_before = args
+                    .get("minutes_before")
+                    .and_then(Value::as_i64)
+                    .unwrap_or(0);
+                if minutes_before < 0 {
+                    bail!("minutes_before cannot be negative");
+                }
+                let fields = EventFields {
+                    title: Some(required_str(args, "title")?.to_string()),
+                    start: Some(When::Time(at)),
+                    end: Some(When::Time(
+                        at + chrono::Duration::minutes(REMINDER_LENGTH_MINUTES),
+                    )),
+                    description: optional_str(args, "notes").map(str::to_string),
+                    reminder_minutes: Some(vec![minutes_before]),
+                    busy: Some(false),
+                    ..Default::default()
+                };
+                let event = self.write_event(calendar, None, &fields, tz).await?;
+                json!({ "status": "created", "event": event })
+            }
+            "calendar_free_busy" => {
+                let (from, to) = window(args, tz, now)?;
+                let calendars = match args.get("calendars").and_then(Value::as_array) {
+                    Some(ids) if !ids.is_empty() => ids
+                        .iter()
+                        .map(|id| {
+                            id.as_str()
+                                .map(str::to_string)
+                                .ok_or_else(|| anyhow!("calendars must be a list of strings"))
+                        })
+                        .collect::<Result<Vec<_>>>()?,
+                    _ => vec![calendar.to_string()],
+                };
+                let slot_minutes = args
+                    .get("slot_minutes")
+                    .and_then(Value::as_i64)
+                    .unwrap_or(30)
+                    .max(1);
+                let busy = self
+                    .free_busy(
+                        &calendars,
+                        (from.with_timezone(&Utc), to.with_timezone(&Utc)),
+                    )
+                    .await?;
+                let all_busy: Vec<Interval> = busy.iter().flat_map(|(_, b)| b.clone()).collect();
+                let free = free_slots(
+                    all_busy,
+                    (from.with_timezone(&Utc), to.with_timezone(&Utc)),
+                    chrono::Duration::minutes(slot_minutes),
+                );
+                let busy: Map<String, Value> = busy
+                    .into_iter()
+                    .map(|(calendar, intervals)| (calendar, intervals_json(&intervals, tz)))
+                    .collect();
+                json!({
+                    "timezone": tz.name(),
+                    "time_min": format_time(from, tz),
+                    "time_max": format_time(to, tz),
+                    "busy": busy,
+                    "free": intervals_json(&free, tz),
+                })
+            }
+            _ => bail!("unknown tool '{}'", name),
+        };
+        Ok(serde_json::to_string_pretty(&response)?)
+    }
+
+    fn timezone(&self, args: &Value) -> Result<Tz> {
+        let name = optional_str(args, "timezone").unwrap_or(&self.config.default_timezone);
+        parse_timezone(name).map_err(|e| anyhow!(e))
+    }
+
+    async fn list_events(
+        &self,
+        calendar: &str,
+        (from, to): (DateTime<Tz>, DateTime<Tz>),
+        query: Option<&str>,
+        max_results: usize,
+        tz: Tz,
+    ) -> Result<Vec<CalendarEvent>> {
+        match self.config.provider {
+            CalendarProvider::Google => {
+                let mut params = vec![
+                    ("timeMin", rfc3339(from)),
+                    ("timeMax", rfc3339(to)),
+                    ("singleEvents", "true".to_string()),
+                    ("orderBy", "startTime".to_string()),
+                    ("maxResults", max_results.to_string()),
+                    ("timeZone", tz.name().to_string()),
+                ];
+                if let Some(query) = query {
+                    params.push(("q", query.to_string()));
+                }
+                let url = self.url(&["calendars", calendar, "events"])?;
+                let body = self.send(self.client.get(url).query(&params)).await?;
+                array(&body, "items")
+                    .iter()
+                    .map(|item| google_event(item, tz))
+                    .collect()
+            }
+            CalendarProvider::Microsoft => {
+                let mut path = graph_calendar(calendar);
+                path.push("calendarView");
+                let url = self.url(&path)?;
+                let params = [
+                    ("startDateTime", rfc3339(from)),
+                    ("endDateTime", rfc3339(to)),
+                    ("$top", max_results.to_string()),
+                    ("$orderby", "start/dateTime".to_string()),
+                ];
+                let body = self.send(self.client.get(url).query(&params)).await?;
+                let events = array(&body, "value")
+                    .iter()
+                    .map(|item| graph_event(item, tz))
+                    .collect::<Result<Vec<_>>>()?;
+                // calendarView cannot be filtered, so the query is matched here.
+                Ok(match query.map(str::to_lowercase) {
+                    Some(query) => events
+                        .into_iter()
+                        .filter(|e| {
+                            e.title.to_lowercase().contains(&query)
+                                || e.description
+                                    .as_deref()
+                                    .is_some_and(|d| d.to_lowercase().contains(&query))
+                        })
+                        .collect(),
+                    None => events,
+                })
+            }
+        }
+    }
+
+    /// Create an event, or update `event_id` with `fields`.
+    async fn write_event(
+        &self,
+        calendar: &str,
+        event_id: Option<&str>,
+        fields: &EventFields,
+        tz: Tz,
+    ) -> Result<CalendarEvent> {
+        match self.config.provider {
+            CalendarProvider::Google => {
+                let body = google_body(fields, tz);
+                let request = match event_id {
+                    Some(id) => {
+                        self.client
+                            .patch(self.url(&["calendars", calendar, "events", id])?)
+                    }
+                    None => self
+                        .client
+                        .post(self.url(&["calendars", calendar, "events"])?),
+                };
+                google_event(&self.send(request.json(&body)).await?, tz)
+            }
+            CalendarProvider::Microsoft => {
+                let body = graph_body(fields, tz);
+                let request = match event_id {
+                    Some(id) => self.client.patch(self.url(&["me", "events", id])?),
+                    None => {
+                        let mut path = graph_calendar(calendar);
+                        path.push("events");
+                        self.client.post(self.url(&path)?)
+                    }
+                };
+                graph_event(&self.send(request.json(&body)).await?, tz)
+            }
+        }
+    }
+
+    /// Busy intervals of each of `calendars` within `window`.
+    async fn free_busy(
+        &self,
+        calendars: &[String],
+        (from, to): Interval,
+    ) -> Result<Vec<(String, Vec<Interval>)>> {
+        match self.config.provider {
+            CalendarProvider::Google => {
+                let body = json!({
+                    "timeMin": rfc3339(from),
+                    "timeMax": rfc3339(to),
+                    "items": calendars.iter().map(|id| json!({ "id": id })).collect::<Vec<_>>(),
+                });
+                let url = self.url(&["freeBusy"])?;
+                let response = self.send(self.client.post(url).json(&body)).await?;
+                calendars
+                    .iter()
+                    .map(|id| {
+                        let calendar = &response["calendars"][id];
+                        if let Some(error) = array(calendar, "errors").first() {
+                            bail!(
+                                "calendar '{}': {}",
+                                id,
+                                error["reason"].as_str().unwrap_or("unavailable")
+                            );
+                        }
+                        let busy = array(calendar, "busy")
+                            .iter()
+                            .map(|b| Ok((utc(&b["start"])?, utc(&b["end"])?)))
+                            .collect::<Result<Vec<_>>>()?;
+                        Ok((id.clone(), busy))
+                    })
+                    .collect()
+            }
+            CalendarProvider::Microsoft => {
+                // getSchedule takes mailboxes; `primary` is the user's own.
+                let mut schedules = Vec::new();
+                for id in calendars {
+                    if id == "primary" {
+                        let me = self.send(self.client.get(self.url(&["me"])?)).await?;
+                        let address = me["mail"]
+                            .as_str()
+                            .or_else(|| me["userPrincipalName"].as_str())
+                            .context("the account has no mail address")?;
+                        schedules.push((id.clone(), address.to_string()));
+                    } else {
+                        schedules.push((id.clone(), id.clone()));
+                    }
+                }
+                let body = json!({
+                    "schedules": schedules.iter().map(|(_, address)| address).collect::<Vec<_>>(),
+                    "startTime": { "dateTime": graph_time(from), "timeZone": "UTC" },
+                    "endTime": { "dateTime": graph_time(to), "timeZone": "UTC" },
+                    "availabilityViewInterval": 15,
+                });
+                let url = self.url(&["me", "calendar", "getSchedule"])?;
+                let response = self.send(self.client.post(url).json(&body)).await?;
+                let results = array(&response, "value");
+                schedules
+                    .into_iter()
+                    .map(|(id, address)| {
+                        let schedule = results
+                            .iter()
+                            .find(|s| {
+                                s["scheduleId"]
+                                    .as_str()
+                                    .is_some_and(|s| s.eq_ignore_ascii_case(&address))
+                            })
+                            .ok_or_else(|| anyhow!("no schedule returned for '{}'", address))?;
+                        if let Some(message) = schedule["error"]["message"].as_str() {
+                            bail!("calendar '{}': {}", id, message);
+                        }
+                        let busy = array(schedule, "scheduleItems")
+                            .iter()
+                            .filter(|item| item["status"].as_str() != Some("free"))
+                            .map(|item| Ok((graph_utc(&item["start"])?, graph_utc(&item["end"])?)))
+                            .collect::<Result<Vec<_>>>()?;
+                        Ok((id, busy))
+                    })
+                    .collect()
+            }
+        }
+    }
+
+    /// `segments` under the provider's API base URL, each escaped as needed.
+    fn url(&self, segments: &[&str]) -> Result<Url> {
+        let mut url = Url::parse(self.config.effective_api_url())?;
+        url.path_segments_mut()
+            .map_err(|_| anyhow!("invalid calendar api_url"))?
+            .pop_if_empty()
+            .extend(segments);
+        Ok(url)
+    }
+
+    async fn send(&self, mut request: RequestBuilder) -> Result<Value> {
+        for (name, value) in self.auth.headers().await? {
+            request = request.header(name, value);
+        }
+        if self.config.provider == CalendarProvider::Microsoft {
+            // Event times come back in UTC whatever the event's own timezone.
+            request = request.header("Prefer", "outlook.timezone=\"UTC\"");
+        }
+        let response = request.send().await.with_context(|| {
+            format!("{} calendar request failed", self.config.provider.as_str())
+        })?;
+        let status = response.status();
+        let body: Value = response.json().await.unwrap_or(Value::Null);
+        if !status.is_success() {
+            bail!(
+                "{} calendar API returned {}: {}",
+                self.config.provider.as_str(),
+                status.as_u16(),
+                body["error"]["message"]
+                    .as_str()
+                    .unwrap_or_else(|| status.canonical_reason().unwrap_or("request failed"))
+            );
+        }
+        Ok(body)
+    }
+}
+
+/// `time_min` and `time_max`: now and a week later when absent.
+fn window(args: &Value, tz: Tz, now: DateTime<Utc>) -> Result<(DateTime<Tz>, DateTime<Tz>)> {
+    let from = match optional_str(args, "time_min") {
+        Some(text) => time_arg(text, tz, now)?,
+        None => now.with_timezone(&tz),
+    };
+    let to = match optional_str(args, "time_max") {
+        Some(text) => time_arg(text, tz, now)?,
+        None => from + chrono::Duration::days(7),
+    };
+    if to <= from {
+        bail!("time_max must be after time_min");
+    }
+    Ok((from, to))
+}
+
+/// The fields of a create (`creating`) or update call.
+fn event_fields(args: &Value, tz: Tz, now: DateTime<Utc>, creating: bool) -> Result<EventFields> {
+    let all_day = args.get("all_day").and_then(Value::as_bool) == Some(true);
+    let start = match optional_str(args, "start") {
+        Some(text) => Some(time_arg(text, tz, now)?),
+        None if creating => bail!("start is required"),
+        None => None,
+    };
+    let end = optional_str(args, "end")
+        .map(|text| time_arg(text, tz, now))
+        .transpose()?;
+    let duration = args.get("duration_minutes").and_then(Value::as_i64);
+    let (start, end) = match (start, end) {
+        (Some(start), Some(end)) if end <= start && !all_day => {
+            bail!("end must be after start")
+        }
+        (Some(start), Some(end)) => (Some(start), Some(end)),
+        (Some(start), None) if all_day => (Some(start), None),
+        (Some(start), None) => match duration {
+            Some(minutes) if minutes > 0 => (
+                Some(start),
+                Some(start + chrono::Duration::minutes(minutes)),
+            ),
+            Some(_) => bail!("duration_minutes must be positive"),
+            None if creating => (
+                Some(start),
+                Some(start + chrono::Duration::minutes(DEFAULT_EVENT_MINUTES)),
+            ),
+            None => bail!("moving an event needs end or duration_minutes with start"),
+        },
+        (None, Some(_)) => bail!("end can only be changed together with start"),
+        (None, None) => (None, None),
+    };
+    let (start, end) = if all_day {
+        let first = start.map(|s| s.date_naive());
+        let last = end.map(|e| e.date_naive()).or(first);
+        match (first, last) {
+            (Some(first), Some(last)) if last < first => bail!("end must not be before start"),
+            // All-day ends are exclusive.
+            (Some(first), Some(last)) => (
+                Some(When::Date(first)),
+                Some(When::Date(last + chrono::Duration::days(1))),
+            ),
+            _ => (None, None),
+        }
+    } else {
+        (start.map(When::Time), end.map(When::Time))
+    };
+    let attendees = match args.get("attendees") {
+        None | Some(Value::Null) => None,
+        Some(Value::Array(list)) => Some(
+            list.iter()
+                .map(|a| match a.as_str().map(str::trim) {
+                    Some(email) if email.contains('@') => Ok(email.to_string()),
+                    _ => bail!("attendees must be email addresses"),
+                })
+                .collect::<Result<Vec<_>>>()?,
+        ),
+        Some(_) => bail!("attendees must be a list of email addresses"),
+    };
+    let reminder_minutes = match args.get("reminder_minutes") {
+        None | Some(Value::Null) => None,
+        Some(Value::Array(list)) => Some(
+            list.iter()
+                .map(|m| match m.as_i64() {
+                    Some(minutes) if minutes >= 0 => Ok(minutes),
+                    _ => bail!("reminder_minutes must be non-negative integers"),
+                })
+                .collect::<Result<Vec<_>>>()?,
+        ),
+        Some(_) => bail!("reminder_minutes must be a list of minutes"),
+    };
+    Ok(EventFields {
+        title: match optional_str(args, "title") {
+            Some(title) => Some(title.to_string()),
+            None if creating => bail!("title is required"),
+            None => None,
+        },
+        start,
+        end,
+        location: optional_str(args, "location").map(str::to_string),
+        description: optional_str(args, "description").map(str::to_string),
+        attendees,
+        reminder_minutes,
+        busy: None,
+    })
+}
+
+/// Merge `busy` and return the gaps of at least `min` within `window`.
+fn free_slots(
+    mut busy: Vec<Interval>,
+    (from, to): Interval,
+    min: chrono::Duration,
+) -> Vec<Interval> {
+    busy.sort();
+    let mut free = Vec::new();
+    let mut cursor = from;
+    for (start, end) in busy {
+        if start > cursor && start.min(to) - cursor >= min {
+            free.push((cursor, start.min(to)));
+        }
+        cursor = cursor.max(end);
+        if cursor >= to {
+            return free;
+        }
+    }
+    if to - cursor >= min {
+        free.push((cursor, to));
+    }
+    free
+}
+
+fn google_body(fields: &EventFields, tz: Tz) -> Value {
+    let mut body = Map::new();
+    if let Some(title) = &fields.title {
+        body.insert("summary".into(), json!(title));
+    }
+    if let Some(description) = &fields.description {
+        body.insert("description".into(), json!(description));
+    }
+    if let Some(location) = &fields.location {
+        body.insert("location".into(), json!(location));
+    }
+    for (key, when) in [("start", fields.start), ("end", fields.end)] {
+        match when {
+            Some(When::Date(date)) => {
+                body.insert(key.into(), json!({ "date": date.to_string() }));
+            }
+            Some(When::Time(time)) => {
+                body.insert(
+                    key.into(),
+                    json!({ "dateTime": rfc3339(time), "timeZone": tz.name() }),
+                );
+            }
+            None => {}
+        }
+    }
+    if let Some(attendees) = &fields.attendees {
+        let attendees: Vec<Value> = attendees.iter().map(|a| json!({ "email": a })).collect();
+        body.insert("attendees".into(), json!(attendees));
+    }
+    if let Some(minutes) = &fields.reminder_minutes {
+        let overrides: Vec<Value> = minutes
+            .iter()
+            .map(|m| json!({ "method": "popup", "minutes": m }))
+            .collect();
+        body.insert(
+            "reminders".into(),
+            json!({ "useDefault": false, "overrides": overrides }),
+        );
+    }
+    if let Some(busy) = fields.busy {
+        let transparency = if busy { "opaque" } else { "transparent" };
+        body.insert("transparency".into(), json!(transparency));
+    }
+    Value::Object(body)
+}
+
+fn google_event(item: &Value, tz: Tz) -> Result<CalendarEvent> {
+    let (start, all_day) = google_time(&item["start"], tz)?;
+    let (end, _) = google_time(&item["end"], tz)?;
+    Ok(CalendarEvent {
+        id: item["id"].as_str().unwrap_or_default().to_string(),
+        title: item["summary"].as_str().unwrap_or("(no title)").to_string(),
+        start,
+        end,
+        all_day,
+        location: non_empty(&item["location"]),
+        description: non_empty(&item["description"]),
+        attendees: array(item, "attendees")
+            .iter()
+            .filter_map(|a| a["email"].as_str().map(str::to_string))
+            .collect(),
+        reminder_minutes: array(&item["reminders"], "overrides")
+            .iter()
+            .filter_map(|r| r["minutes"].as_i64())
+            .collect(),
+        url: non_empty(&item["htmlLink"]),
+    })
+}
+
+/// A Google `start`/`end`, and whether it is a date.
+fn google_time(value: &Value, tz: Tz) -> Result<(String, bool)> {
+    match (value["dateTime"].as_str(), value["date"].as_str()) {
+        (Some(_), _) => Ok((format_time(utc(&value["dateTime"])?, tz), false)),
+        (None, Some(date)) => Ok((date.to_string(), true)),
+        (None, None) => bail!("event time has neither dateTime nor date: {}", value),
+    }
+}
+
+fn graph_body(fields: &EventFields, tz: Tz) -> Value {
+    let mut body = Map::new();
+    if let Some(title) = &fields.title {
+        body.insert("subject".into(), json!(title));
+    }
+    if let Some(description) = &fields.description {
+        body.insert(
+            "body".into(),
+            json!({ "contentType": "text", "content": description }),
+        );
+    }
+    if let Some(location) = &fields.location {
+        body.insert("location".into(), json!({ "displayName": location }));
+    }
+    for (key, when) in [("start", fields.start), ("end", fields.end)] {
+        let local = match when {
+            Some(When::Date(date)) => {
+                body.insert("isAllDay".into(), json!(true));
+                date.and_time(chrono::NaiveTime::MIN)
+            }
+            Some(When::Time(time)) => {
+                body.insert("isAllDay".into(), json!(false));
+                time.naive_local()
+            }
+            None => continue,
+        };
+        body.insert(
+            key.into(),
+            json!({
+                "dateTime": local.format("%Y-%m-%dT%H:%M:%S").to_string(),
+                "timeZone": tz.name(),
+            }),
+        );
+    }
+    if let Some(attendees) = &fields.attendees {
+        let attendees: Vec<Value> = attendees
+            .iter()
+            .map(|a| json!({ "emailAddress": { "address": a }, "type": "required" }))
+            .collect();
+        body.insert("attendees".into(), json!(attendees));
+    }
+    if let Some(minutes) = &fields.reminder_minutes {
+        match minutes.iter().max() {
+            Some(earliest) => {
+                body.insert("isReminderOn".into(), json!(true));
+                body.insert("reminderMinutesBeforeStart".into(), json!(earliest));
+            }
+            None => {
+                body.insert("isReminderOn".into(), json!(false));
+            }
+        }
+    }
+    if let Some(busy) = fields.busy {
+        body.insert("showAs".into(), json!(if busy { "busy" } else { "free" }));
+    }
+    Value::Object(body)
+}
+
+fn graph_event(item: &Value, tz: Tz) -> Result<CalendarEvent> {
+    let all_day = item["isAllDay"].as_bool() == Some(true);
+    let time = |value: &Value| -> Result<String> {
+        if all_day {
+            Ok(graph_naive(value)?.date().to_string())
+        } else {
+            Ok(format_time(graph_utc(value)?, tz))
+        }
+    };
+    Ok(CalendarEvent {
+        id: item["id"].as_str().unwrap_or_default().to_string(),
+        title: item["subject"].as_str().unwrap_or("(no title)").to_string(),
+        start: time(&item["start"])?,
+        end: time(&item["end"])?,
+        all_day,
+        location: non_empty(&item["location"]["displayName"]),
+        description: non_empty(&item["bodyPreview"]),
+        attendees: array(item, "attendees")
+            .iter()
+            .filter_map(|a| a["emailAddress"]["address"].as_str().map(str::to_string))
+            .collect(),
+        reminder_minutes: match item["isReminderOn"].as_bool() {
+            Some(true) => item["reminderMinutesBeforeStart"]
+                .as_i64()
+                .into_iter()
+                .collect(),
+            _ => Vec::new(),
+        },
+        url: non_empty(&item["webLink"]),
+    })
+}
+
+/// The `dateTime` of a Graph `dateTimeTimeZone`, without its zone.
+fn graph_naive(value: &Value) -> Result<NaiveDateTime> {
+    let text = value["dateTime"]
+        .as_str()
+        .ok_or_else(|| anyhow!("event time has no dateTime: {}", value))?;
+    NaiveDateTime::parse_from_str(text, "%Y-%m-%dT%H:%M:%S%.f")
+        .with_context(|| format!("invalid event time '{}'", text))
+}
+
+/// A Graph `dateTimeTimeZone`. Requests ask for UTC; another zone is read
+/// as IANA.
+fn graph_utc(value: &Value) -> Result<DateTime<Utc>> {
+    let naive = graph_naive(value)?;
+    match value["timeZone"].as_str() {
+        None | Some("UTC") => Ok(naive.and_utc()),
+        Some(zone) => {
+            let tz = parse_timezone(zone).map_err(|e| anyhow!(e))?;
+            Ok(localize(naive, tz)
+                .map_err(|e| anyhow!(e))?
+                .with_timezone(&Utc))
+        }
+    }
+}
+
+fn graph_time(time: DateTime<Utc>) -> String {
+    time.format("%Y-%m-%dT%H:%M:%S").to_string()
+}
+
+/// Graph path of `calendar`: the user's default one for `primary`.
+fn graph_calendar(calendar: &str) -> Vec<&str> {
+    match calendar {
+        "primary" => vec!["me"],
+        id => vec!["me", "calendars", id],
+    }
+}
+
+fn utc(value: &Value) -> Result<DateTime<Utc>> {
+    let text = value
+        .as_str()
+        .ok_or_else(|| anyhow!("expected a timestamp, got {}", value))?;
+    Ok(DateTime::parse_from_rfc3339(text)
+        .with_context(|| format!("invalid timestamp '{}'", text))?
+        .with_timezone(&Utc))
+}
+
+fn time_arg(text: &str, tz: Tz, now: DateTime<Utc>) -> Result<DateTime<Tz>> {
+    parse_datetime(text, tz, now).map_err(|e| anyhow!(e))
+}
+
+/// `time` as the APIs take it: RFC 3339 in UTC.
+fn rfc3339<T: chrono::TimeZone>(time: DateTime<T>) -> String {
+    time.with_timezone(&Utc)
+        .to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+fn format_time<T: chrono::TimeZone>(time: DateTime<T>, tz: Tz) -> String {
+    time.with_timezone(&tz)
+        .to_rfc3339_opts(SecondsFormat::Secs, false)
+}
+
+fn intervals_json(intervals: &[Interval], tz: Tz) -> Value {
+    intervals
+        .iter()
+        .map(|(start, end)| json!({ "start": format_time(*start, tz), "end": format_time(*end, tz) }))
+        .collect()
+}
+
+fn array<'a>(value: &'a Value, key: &str) -> &'a [Value] {
+    value[key].as_array().map(Vec::as_slice).unwrap_or_default()
+}
+
+fn non_empty(value: &Value) -> Option<String> {
+    value
+        .as_str()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+}
+
+fn optional_str<'a>(args: &'a Value, key: &str) -> Option<&'a str> {
+    args.get(key)
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+}
+
+fn required_str<'a>(args: &'a Value, key: &str) -> Result<&'a str> {
+    optional_str(args, key).ok_or_else(|| anyhow!("{} is required", key))
+}
+
+fn definition(name: &str) -> Tool {
+    let time = |what: &str| {
+        json!({
+            "type": "string",
+            "description": format!(
+                "{}: RFC 3339, or YYYY-MM-DD HH:MM / YYYY-MM-DD in `timezone`",
+                what
+            )
+        })
+    };
+    let calendar_id = json!({
+        "type": "string",
+        "description": "Calendar to use; the configured default (usually `primary`) when omitted"
+    });
+    let timezone = json!({
+        "type": "string",
+        "description": "IANA timezone for times without an offset and for the times returned"
+    });
+    let event_properties = json!({
+        "calendar_id": calendar_id,
+        "timezone": timezone,
+        "title": { "type": "string" },
+        "start": time("Start"),
+        "end": time("End"),
+        "duration_minutes": { "type": "integer", "minimum": 1, "description": "Length, when end is omitted" },
+        "all_day": { "type": "boolean", "description": "Use only the dates of start and end; end is the last day" },
+        "location": { "type": "string" },
+        "description": { "type": "string" },
+        "attendees": { "type": "array", "items": { "type": "string" }, "description": "Email addresses to invite" },
+        "reminder_minutes": {
+            "type": "array",
+            "items": { "type": "integer", "minimum": 0 },
+            "description": "Popup reminders, in minutes before the start"
+        }
+    });
+    let (description, properties, required) = match name {
+        "calendar_list_events" => (
+            "List calendar events between two times (the next 7 days by default), recurring events expanded.",
+            json!({
+                "calendar_id": calendar_id,
+                "timezone": timezone,
+                "time_min": time("Window start; now when omitted"),
+                "time_max": time("Window end; 7 days after time_min when omitted"),
+                "query": { "type": "string", "description": "Only events whose text contains this" },
+                "max_results": { "type": "integer", "minimum": 1 }
+            }),
+            json!([]),
+        ),
+        "calendar_create_event" => (
+            "Create a calendar event, optionally inviting attendees and setting reminders.",
+            event_properties,
+            json!(["title", "start"]),
+        ),
+        "calendar_update_event" => {
+            let mut properties = event_properties;
+            properties["event_id"] = json!({ "type": "string" });
+            (
+                "Change fields of a calendar event; omitted fields are kept. Moving it needs start with end or duration_minutes.",
+                properties,
+                json!(["event_id"]),
+            )
+        }
+        "calendar_create_reminder" => (
+            "Add a reminder: a short event that does not block time, with a popup notification.",
+            json!({
+                "calendar_id": calendar_id,
+                "timezone": timezone,
+                "title": { "type": "string", "description": "What to be reminded of" },
+                "at": time("When"),
+                "minutes_before": { "type": "integer", "minimum": 0, "description": "Notify this long before `at`; 0 by default" },
+                "notes": { "type": "string" }
+            }),
+            json!(["title", "at"]),
+        ),
+        _ => (
+            "Busy intervals of one or more calendars and the free slots they all share, for finding meeting times.",
+            json!({
+                "calendars": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Calendar ids (Google) or mailboxes (Microsoft); the default calendar when omitted"
+                },
+                "timezone": timezone,
+                "time_min": time("Window start; now when omitted"),
+                "time_max": time("Window end; 7 days after time_min when omitted"),
+                "slot_minutes": { "type": "integer", "minimum": 1, "description": "Shortest free slot to report; 30 by default" }
+            }),
+            json!([]),
+        ),
+    };
+    Tool {
+        name: name.to_string(),
+        description: Some(description.to_string()),
+        input_schema: json!({
+            "type": "object",
+            "properties": properties,
+            "required": required,
+            "additionalProperties": false
+        }),
+        output_schema: None,
+    }
+}
+
+pub fn build<T: Transport>(
+    t: T,
+    config: CalendarMcpConfig,
+    auth: Arc<dyn CalendarAuth>,
+) -> Result<Server<T>> {
+    let mut server = Server::builder(t)
+        .capabilities(ServerCapabilities {
+            tools: Some(json!({})),
+            ..Default::default()
+        })
+        .request_handler("resources/list", |_req: ListRequest| {
+            Box::pin(async move {
+                Ok(ResourcesListResponse {
+                    resources: Vec::new(),
+                    next_cursor: None,
+                    meta: None,
+                })
+            })
+        })
+        .request_handler("prompts/list", |_req: ListRequest| {
+            Box::pin(async move {
+                Ok(PromptsListResponse {
+                    prompts: Vec::new(),
+                    next_cursor: None,
+                    meta: None,
+                })
+            })
+        });
+
+    register_tools(&mut server, Arc::new(CalendarTools::new(config, auth)?));
+
+    Ok(server.build())
+}
+
+fn register_tools<T: Transport>(server: &mut ServerBuilder<T>, tools: Arc<CalendarTools>) {
+    for name in TOOLS {
+        let tools = tools.clone();
+        server.register_tool(definition(name), move |req: CallToolRequest| {
+            let tools = tools.clone();
+            Box::pin(async move {
+                let args = Value::Object(
+                    req.arguments
+                        .unwrap_or_default()
+                        .into_iter()
+                        .collect::<serde_json::Map<String, Value>>(),
+                );
+                let (text, is_error) = match tools.call(name, &args).await {
+                    Ok(text) => (text, None),
+                    Err(e) => (e.to_string(), Some(true)),
+                };
+                Ok(CallToolResponse {
+                    content: vec![ToolResponseContent::Text { text }],
+                    is_error,
+                    meta: None,
+                })
+            })
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{body_partial_json, header, method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    struct StaticAuth;
+
+    #[async_trait::async_trait]
+    impl CalendarAuth for StaticAuth {
+        async fn headers(&self) -> Result<HashMap<String, String>> {
+            Ok(HashMap::from([(
+                "Authorization".to_string(),
+                "Bearer test-token".to_string(),
+            )]))
+        }
+    }
+
+    fn tools(server: &MockServer, provider: CalendarProvider, timezone: &str) -> CalendarTools {
+        CalendarTools::new(
+            CalendarMcpConfig {
+                provider,
+                default_timezone: timezone.to_string(),
+                api_url: Some(server.uri()),
+                ..Default::default()
+            },
+            Arc::new(StaticAuth),
+        )
+        .unwrap()
+    }
+
+    async fn call(tools: &CalendarTools, name: &str, args: Value) -> Value {
+        serde_json::from_str(&tools.call(name, &args).await.unwrap()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn google_events_are_listed_in_the_call_timezone() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/calendars/team@example.com/events"))
+            .and(header("authorization", "Bearer test-token"))
+            .and(query_param("singleEvents", "true"))
+            .and(query_param("timeMin", "2026-10-19T22:00:00Z"))
+            .and(query_param("timeZone", "Europe/Berlin"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "items": [
+                    {
+                        "id": "ev1",
+                        "summary": "Standup",
+                        "start": { "dateTime": "2026-10-20T08:00:00Z" },
+                        "end": { "dateTime": "2026-10-20T08:15:00Z" },
+                        "attendees": [{ "email": "ana@example.com" }],
+                        "reminders": { "useDefault": false, "overrides": [{ "method": "popup", "minutes": 5 }] }
+                    },
+                    {
+                        "id": "ev2",
+                        "summary": "Offsite",
+                        "start": { "date": "2026-10-21" },
+                        "end": { "date": "2026-10-22" }
+                    }
+                ]
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let tools = tools(&server, CalendarProvider::Google, "UTC");
+
+        let listed = call(
+            &tools,
+            "calendar_list_events",
+            json!({
+                "calendar_id": "team@example.com",
+                "timezone": "Europe/Berlin",
+                "time_min": "2026-10-20",
+                "time_max": "2026-10-23"
+            }),
+        )
+        .await;
+
+        let events = listed["events"].as_array().unwrap();
+        assert_eq!(events[0]["start"], "2026-10-20T10:00:00+02:00");
+        assert_eq!(events[0]["attendees"], json!(["ana@example.com"]));
+        assert_eq!(events[0]["reminder_minutes"], json!([5]));
+        assert_eq!(events[1]["start"], "2026-10-21");
+        assert_eq!(events[1]["all_day"], true);
+    }
+
+    #[tokio::test]
+    async fn google_events_are_created_in_the_default_timezone() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/calendars/primary/events"))
+            .and(body_partial_json(json!({
+                "summary": "Design review",
+                "start": { "dateTime": "2026-10-20T16:00:00Z", "timeZone": "America/Los_Angeles" },
+                "end": { "dateTime": "2026-10-20T16:45:00Z" },
+                "reminders": { "useDefault": false, "overrides": [{ "method": "popup", "minutes": 10 }] }
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "id": "new",
+                "summary": "Design review",
+                "start": { "dateTime": "2026-10-20T09:00:00-07:00" },
+                "end": { "dateTime": "2026-10-20T09:45:00-07:00" }
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let tools = tools(&server, CalendarProvider::Google, "America/Los_Angeles");
+
+        let created = call(
+            &tools,
+            "calendar_create_event",
+            json!({
+                "title": "Design review",
+                "start": "2026-10-20 09:00",
+                "duration_minutes": 45,
+                "reminder_minutes": [10]
+            }),
+        )
+        .await;
+
+        assert_eq!(created["event"]["id"], "new");
+        assert_eq!(created["event"]["end"], "2026-10-20T09:45:00-07:00");
+    }
+
+    #[tokio::test]
+    async fn microsoft_reminders_do_not_block_time() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/me/events"))
+            .and(header("prefer", "outlook.timezone=\"UTC\""))
+            .and(body_partial_json(json!({
+                "subject": "Call the bank",
+                "start": { "dateTime": "2026-10-20T09:00:00", "timeZone": "Europe/Berlin" },
+                "end": { "dateTime": "2026-10-20T09:15:00", "timeZone": "Europe/Berlin" },
+                "isReminderOn": true,
+                "reminderMinutesBeforeStart": 10,
+                "showAs": "free"
+            })))
+            .respond_with(ResponseTemplate::new(201).set_body_json(json!({
+                "id": "AAMk",
+                "subject": "Call the bank",
+                "start": { "dateTime": "2026-10-20T07:00:00.0000000", "timeZone": "UTC" },
+                "end": { "dateTime": "2026-10-20T07:15:00.0000000", "timeZone": "UTC" },
+                "isReminderOn": true,
+                "reminderMinutesBeforeStart": 10
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let tools = tools(&server, CalendarProvider::Microsoft, "Europe/Berlin");
+
+        let created = call(
+            &tools,
+            "calendar_create_reminder",
+            json!({ "title": "Call the bank", "at": "2026-10-20 09:00", "minutes_before": 10 }),
+        )
+        .await;
+
+        assert_eq!(created["event"]["start"], "2026-10-20T09:00:00+02:00");
+        assert_eq!(created["event"]["reminder_minutes"], json!([10]));
+    }
+
+    #[tokio::test]
+    async fn free_busy_reports_the_slots_every_calendar_has_free() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/freeBusy"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "calendars": {
+                    "primary": { "busy": [
+                        { "start": "2026-10-20T09:00:00Z", "end": "2026-10-20T10:00:00Z" }
+                    ] },
+                    "ana@example.com": { "busy": [
+                        { "start": "2026-10-20T09:30:00Z", "end": "2026-10-20T11:00:00Z" },
+                        { "start": "2026-10-20T11:15:00Z", "end": "2026-10-20T12:00:00Z" }
+                    ] }
+                }
+            })))
+            .mount(&server)
+            .await;
+        let tools = tools(&server, CalendarProvider::Google, "UTC");
+
+        let result = call(
+            &tools,
+            "calendar_free_busy",
+            json!({
+                "calendars": ["primary", "ana@example.com"],
+                "time_min": "2026-10-20 08:00",
+                "time_max": "2026-10-20 13:00",
+                "slot_minutes": 30
+            }),
+        )
+        .await;
+
+        assert_eq!(
+            result["busy"]["ana@example.com"].as_array().unwrap().len(),
+            2
+        );
+        assert_eq!(
+            result["free"],
+            json!([
+                { "start": "2026-10-20T08:00:00+00:00", "end": "2026-10-20T09:00:00+00:00" },
+                { "start": "2026-10-20T12:00:00+00:00", "end": "2026-10-20T13:00:00+00:00" }
+            ]),
+            "the 15 minutes between 11:00 and 11:15 are too short"
+        );
+    }
+
+    #[tokio::test]
+    async fn invalid_arguments_are_rejected_before_any_request() {
+        let server = MockServer::start().await;
+        let tools = tools(&server, CalendarProvider::Google, "UTC");
+        let cases = [
+            (
+                "calendar_list_events",
+                json!({ "timezone": "Mars/Olympus" }),
+                "unknown timezone",
+            ),
+            (
+                "calendar_create_event",
+                json!({ "title": "x", "start": "2026-10-20 10:00", "end": "2026-10-20 09:00" }),
+                "end must be after start",
+            ),
+            (
+                "calendar_update_event",
+                json!({ "event_id": "ev1" }),
+                "nothing to update",
+            ),
+            (
+                "calendar_update_event",
+                json!({ "event_id": "ev1", "start": "2026-10-20 10:00" }),
+                "needs end or duration_minutes",
+            ),
+            (
+                "calendar_create_event",
+                json!({ "title": "x", "start": "2026-10-20", "attendees": ["ana"] }),
+                "email addresses",
+            ),
+        ];
+        for (name, args, expected) in cases {
+            let err = tools.call(name, &args).await.unwrap_err().to_string();
+            assert!(err.contains(expected), "{name}: {err}");
+        }
+        assert!(server.received_requests().await.unwrap().is_empty());
+    }
+}
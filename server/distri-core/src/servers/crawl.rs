@@ -0,0 +1,850 @@
+//! `crawl` — an in-memory MCP server that fetches web pages over plain HTTP.
+//!
+//! `crawl_fetch` fetches one URL and `crawl_site` follows same-host links
+//! from a start URL. Every request goes through:
+//!
+//! - an on-disk response cache shared by all agents, revalidated with
+//!   `ETag`/`Last-Modified` and kept under `max_cache_bytes` by evicting the
+//!   least recently used entries. `fresh: true` skips the cached copy.
+//! - a per-host rate limit of one request every `min_request_interval_ms`.
+//! - the site's robots.txt, unless `respect_robots` is off.
+//! - the global rules of the server's egress policy, for the URL and every
+//!   redirect. A blocked `crawl_fetch` returns the `egress_denied` error.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime};
+
+use anyhow::{anyhow, bail, Result};
+use async_mcp::server::{Server, ServerBuilder};
+use async_mcp::transport::Transport;
+use async_mcp::types::{
+    CallToolRequest, CallToolResponse, ListRequest, PromptsListResponse, ResourcesListResponse,
+    ServerCapabilities, Tool, ToolResponseContent,
+};
+use chrono::{DateTime, Utc};
+use distri_types::crawl::CrawlMcpConfig;
+use distri_types::egress::EgressDenied;
+use once_cell::sync::Lazy;
+use reqwest::header::{
+    CACHE_CONTROL, CONTENT_TYPE, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED,
+};
+use reqwest::{StatusCode, Url};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+
+use crate::tools::request::{send_error, EgressCheck};
+
+static HREF: Lazy<regex::Regex> =
+    Lazy::new(|| regex::Regex::new(r#"(?i)href\s*=\s*["']([^"'#]+)"#).unwrap());
+
+/// A response stored in the cache.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CacheEntry {
+    url: String,
+    status: u16,
+    #[serde(default)]
+    content_type: Option<String>,
+    #[serde(default)]
+    etag: Option<String>,
+    #[serde(default)]
+    last_modified: Option<String>,
+    /// Until when the entry may be served without revalidating.
+    #[serde(default)]
+    fresh_until: Option<DateTime<Utc>>,
+    body: String,
+}
+
+/// On-disk response cache, one JSON file per URL.
+pub struct HttpCache {
+    dir: PathBuf,
+    max_bytes: u64,
+}
+
+impl HttpCache {
+    pub fn new(dir: PathBuf, max_bytes: u64) -> Self {
+        Self { dir, max_bytes }
+    }
+
+    fn path(&self, url: &str) -> PathBuf {
+        self.dir
+            .join(format!("{:x}.json", Sha256::digest(url.as_bytes())))
+    }
+
+    fn get(&self, url: &str) -> Option<CacheEntry> {
+        let path = self.path(url);
+        let entry: CacheEntry = serde_json::from_slice(&std::fs::read(&path).ok()?).ok()?;
+        // Reads count as use for eviction.
+        if let Ok(file) = std::fs::File::options().write(true).open(&path) {
+            let _ = file.set_modified(SystemTime::now());
+        }
+        Some(entry)
+    }
+
+    fn put(&self, entry: &CacheEntry) -> Result<()> {
+        std::fs::create_dir_all(&self.dir)?;
+        std::fs::write(self.path(&entry.url), serde_json::to_vec(entry)?)?;
+        self.evict()
+    }
+
+    /// Drop the least recently used entries until the cache fits.
+    fn evict(&self) -> Result<()> {
+        let mut entries = Vec::new();
+        let mut total = 0;
+        for entry in std::fs::read_dir(&self.dir)? {
+            let entry = entry?;
+            let metadata = entry.metadata()?;
+            if !metadata.is_file() {
+                continue;
+            }
+            total += metadata.len();
+            entries.push((metadata.modified()?, metadata.len(), entry.path()));
+        }
+        entries.sort();
+        for (_, len, path) in entries {
+            if total <= self.max_bytes {
+                break;
+            }
+            std::fs::remove_file(path)?;
+            total -= len;
+        }
+        Ok(())
+    }
+}
+
+/// One request every `interval` per host.
+struct RateLimiter {
+    interval: Duration,
+    next: Mutex<HashMap<String, Instant>>,
+}
+
+impl RateLimiter {
+    async fn wait(&self, host: &str) {
+        let slot = {
+            let mut next = self.next.lock().unwrap();
+            let now = Instant::now();
+            let slot = next.get(host).copied().filter(|t| *t > now).unwrap_or(now);
+            next.insert(host.to_string(), slot + self.interval);
+            slot
+        };
+        tokio::time::sleep_until(slot.into()).await;
+    }
+}
+
+/// The robots.txt rules that apply to one user agent.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RobotsRules {
+    /// `(allow, pattern)` pairs.
+    rules: Vec<(bool, String)>,
+}
+
+impl RobotsRules {
+    /// Everything is disallowed, for a robots.txt that could not be read.
+    fn disallow_all() -> Self {
+        Self {
+            rules: vec![(false, "/".to_string())],
+        }
+    }
+
+    /// Rules of the group naming `user_agent`'s product token, else of the
+    /// `*` group.
+    pub fn parse(robots_txt: &str, user_agent: &str) -> Self {
+        let token = user_agent
+            .split('/')
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        let mut specific = Vec::new();
+        let mut wildcard = Vec::new();
+        let mut agents: Vec<String> = Vec::new();
+        let mut in_rules = false;
+        let mut named = false;
+        for line in robots_txt.lines() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            let (key, value) = (key.trim().to_ascii_lowercase(), value.trim());
+            match key.as_str() {
+                "user-agent" => {
+                    // A user-agent line after rules starts a new group.
+                    if in_rules {
+                        agents.clear();
+                        in_rules = false;
+                    }
+                    let agent = value.to_ascii_lowercase();
+                    named |= agent == token;
+                    agents.push(agent);
+                }
+                "allow" | "disallow" => {
+                    in_rules = true;
+                    // An empty `Disallow:` allows everything.
+                    if value.is_empty() {
+                        continue;
+                    }
+                    let rule = (key == "allow", value.to_string());
+                    if agents.contains(&token) {
+                        specific.push(rule.clone());
+                    }
+                    if agents.iter().any(|a| a == "*") {
+                        wildcard.push(rule);
+                    }
+                }
+                _ => {}
+            }
+        }
+        Self {
+            rules: if named { specific } else { wildcard },
+        }
+    }
+
+    /// Whether `path` (with its query) may be fetched. The longest matching
+    /// pattern wins, and `Allow` wins a tie.
+    pub fn allows(&self, path: &str) -> bool {
+        self.rules
+            .iter()
+            .filter(|(_, pattern)| pattern_matches(pattern, path))
+            .max_by_key(|(allow, pattern)| (pattern.len(), *allow))
+            .is_none_or(|(allow, _)| *allow)
+    }
+}
+
+/// robots.txt pattern match: a prefix match where `*` matches any run of
+/// characters and a trailing `$` anchors the end.
+fn pattern_matches(pattern: &str, path: &str) -> bool {
+    let (pattern, anchored) = match pattern.strip_suffix('$') {
+        Some(pattern) => (pattern, true),
+        None => (pattern, false),
+    };
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = path.strip_prefix(first) else {
+        return false;
+    };
+    let parts: Vec<&str> = parts.collect();
+    for (i, part) in parts.iter().enumerate() {
+        let last = i + 1 == parts.len();
+        if last && anchored {
+            return rest.ends_with(part);
+        }
+        match rest.find(part) {
+            Some(at) => rest = &rest[at + part.len()..],
+            None => return false,
+        }
+    }
+    !anchored || rest.is_empty()
+}
+
+/// How a page was served.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CacheStatus {
+    /// From the cache, still fresh.
+    Hit,
+    /// From the cache, after the server answered `304 Not Modified`.
+    Revalidated,
+    /// Fetched; there was no usable cached copy.
+    Miss,
+    /// Fetched because the call asked for a fresh copy.
+    Bypass,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Page {
+    pub url: String,
+    pub status: u16,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content_type: Option<String>,
+    pub cache: CacheStatus,
+    pub body: String,
+}
+
+/// The tool implementations behind the server.
+pub struct CrawlTools {
+    config: CrawlMcpConfig,
+    client: reqwest::Client,
+    cache: HttpCache,
+    limiter: RateLimiter,
+    robots: Mutex<HashMap<String, RobotsRules>>,
+    egress: Option<EgressCheck>,
+}
+
+const TOOLS: &[&str] = &["crawl_fetch", "crawl_site"];
+
+impl CrawlTools {
+    pub fn new(
+        config: CrawlMcpConfig,
+        cache_dir: PathBuf,
+        egress: Option<EgressCheck>,
+    ) -> Result<Self> {
+        let mut client = reqwest::Client::builder()
+            .user_agent(config.user_agent.clone())
+            .timeout(Duration::from_secs(config.timeout_secs));
+        if let Some(egress) = &egress {
+            client = client.redirect(egress.redirect_policy());
+        }
+        let client = client.build()?;
+        Ok(Self {
+            cache: HttpCache::new(cache_dir, config.max_cache_bytes),
+            limiter: RateLimiter {
+                interval: Duration::from_millis(config.min_request_interval_ms),
+                next: Mutex::new(HashMap::new()),
+            },
+            robots: Mutex::new(HashMap::new()),
+            client,
+            config,
+            egress,
+        })
+    }
+
+    /// Run tool `name` with `args`, returning the text shown to the model.
+    pub async fn call(&self, name: &str, args: &Value) -> Result<String> {
+        let url = args
+            .get("url")
+            .and_then(Value::as_str)
+            .ok_or_else(|| anyhow!("url is required"))?;
+        let fresh = args.get("fresh").and_then(Value::as_bool) == Some(true);
+        let response = match name {
+            "crawl_fetch" => {
+                let mut page = self.fetch(url, fresh).await?;
+                page.body = truncate(page.body, self.config.max_output_bytes);
+                serde_json::to_value(page)?
+            }
+            "crawl_site" => {
+                let max_pages = args
+                    .get("max_pages")
+                    .and_then(Value::as_u64)
+                    .map_or(self.config.max_pages, |n| n as usize)
+                    .min(self.config.max_pages);
+                self.crawl(url, max_pages, fresh).await?
+            }
+            _ => bail!("unknown tool '{}'", name),
+        };
+        Ok(serde_json::to_string_pretty(&response)?)
+    }
+
+    /// Fetch `url` through the cache. `fresh` ignores the cached copy.
+    pub async fn fetch(&self, url: &str, fresh: bool) -> Result<Page> {
+        let parsed = Url::parse(url).map_err(|e| anyhow!("invalid url '{}': {}", url, e))?;
+        if !matches!(parsed.scheme(), "http" | "https") {
+            bail!("only http and https urls can be fetched");
+        }
+        if let Some(egress) = &self.egress {
+            egress.check(url)?;
+        }
+        if self.config.respect_robots && !self.robots_allow(&parsed).await {
+            bail!("{} is disallowed by the site's robots.txt", url);
+        }
+        self.fetch_cached(&parsed, fresh).await
+    }
+
+    async fn fetch_cached(&self, url: &Url, fresh: bool) -> Result<Page> {
+        let cached = if fresh {
+            None
+        } else {
+            self.cache.get(url.as_str())
+        };
+        if let Some(entry) = &cached {
+            if entry.fresh_until.is_some_and(|until| until > Utc::now()) {
+                return Ok(page(entry.clone(), CacheStatus::Hit));
+            }
+        }
+
+        self.limiter.wait(url.host_str().unwrap_or_default()).await;
+        let mut request = self.client.get(url.clone());
+        if let Some(entry) = &cached {
+            if let Some(etag) = &entry.etag {
+                request = request.header(IF_NONE_MATCH, etag);
+            }
+            if let Some(last_modified) = &entry.last_modified {
+                request = request.header(IF_MODIFIED_SINCE, last_modified);
+            }
+        }
+        let response = request.send().await.map_err(send_error)?;
+        let headers = response.headers().clone();
+        let header = |name| {
+            headers
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(str::to_string)
+        };
+        let cache_control = header(CACHE_CONTROL)
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        let fresh_until = max_age(&cache_control).map(|age| Utc::now() + age);
+
+        if response.status() == StatusCode::NOT_MODIFIED {
+            if let Some(mut entry) = cached {
+                entry.fresh_until = fresh_until;
+                self.store(&entry);
+                return Ok(page(entry, CacheStatus::Revalidated));
+            }
+        }
+
+        let entry = CacheEntry {
+            url: url.to_string(),
+            status: response.status().as_u16(),
+            content_type: header(CONTENT_TYPE),
+            etag: header(ETAG),
+            last_modified: header(LAST_MODIFIED),
+            fresh_until,
+            body: response.text().await?,
+        };
+        let cacheable = (200..300).contains(&entry.status) && !cache_control.contains("no-store");
+        if cacheable {
+            self.store(&entry);
+        }
+        let status = if fresh {
+            CacheStatus::Bypass
+        } else {
+            CacheStatus::Miss
+        };
+        Ok(page(entry, status))
+    }
+
+    fn store(&self, entry: &CacheEntry) {
+        if let Err(e) = self.cache.put(entry) {
+            tracing::warn!(url = %entry.url, "Failed to cache response: {}", e);
+        }
+    }
+
+    /// Whether robots.txt lets us fetch `url`. The rules are read once per
+    /// origin: a missing file allows everything, an unreachable one nothing.
+    async fn robots_allow(&self, url: &Url) -> bool {
+        let origin = url.origin().ascii_serialization();
+        let known = self.robots.lock().unwrap().get(&origin).cloned();
+        let rules = match known {
+            Some(rules) => rules,
+            None => {
+                let robots_url = url.join("/robots.txt").expect("origin-relative url");
+                let rules = match self.fetch_cached(&robots_url, false).await {
+                    Ok(page) if (200..300).contains(&page.status) => {
+                        RobotsRules::parse(&page.body, &self.config.user_agent)
+                    }
+                    Ok(page) if (400..500).contains(&page.status) => RobotsRules::default(),
+                    _ => RobotsRules::disallow_all(),
+                };
+                self.robots.lock().unwrap().insert(origin, rules.clone());
+                rules
+            }
+        };
+        let mut path = url.path().to_string();
+        if let Some(query) = url.query() {
+            path.push('?');
+            path.push_str(query);
+        }
+        rules.allows(&path)
+    }
+
+    /// Breadth-first crawl of `start`'s host, up to `max_pages` pages.
+    async fn crawl(&self, start: &str, max_pages: usize, fresh: bool) -> Result<Value> {
+        let start = Url::parse(start).map_err(|e| anyhow!("invalid url '{}': {}", start, e))?;
+        let host = start.host_str().map(str::to_string);
+        let budget = self.config.max_output_bytes / max_pages.max(1);
+        let mut queue = VecDeque::from([start.to_string()]);
+        let mut seen: HashSet<String> = queue.iter().cloned().collect();
+        let mut pages = Vec::new();
+        let mut skipped = Vec::new();
+        while let Some(url) = queue.pop_front() {
+            if pages.len() >= max_pages {
+                break;
+            }
+            let mut page = match self.fetch(&url, fresh).await {
+                Ok(page) => page,
+                Err(e) => {
+                    skipped.push(json!({ "url": url, "reason": e.to_string() }));
+                    continue;
+                }
+            };
+            if page
+                .content_type
+                .as_deref()
+                .is_some_and(|t| t.contains("html"))
+            {
+                for link in links(&page.url, &page.body) {
+                    if link.host_str().map(str::to_string) == host && seen.insert(link.to_string())
+                    {
+                        queue.push_back(link.to_string());
+                    }
+                }
+            }
+            page.body = truncate(page.body, budget);
+            pages.push(page);
+        }
+        Ok(json!({ "pages": pages, "skipped": skipped }))
+    }
+}
+
+fn page(entry: CacheEntry, cache: CacheStatus) -> Page {
+    Page {
+        url: entry.url,
+        status: entry.status,
+        content_type: entry.content_type,
+        cache,
+        body: entry.body,
+    }
+}
+
+/// `max-age` of a `Cache-Control` value. `no-cache` always revalidates.
+fn max_age(cache_control: &str) -> Option<chrono::Duration> {
+    if cache_control.contains("no-cache") || cache_control.contains("no-store") {
+        return None;
+    }
+    cache_control.split(',').find_map(|directive| {
+        let seconds = directive.trim().strip_prefix("max-age=")?.parse().ok()?;
+        Some(chrono::Duration::seconds(seconds))
+    })
+}
+
+/// http(s) links of an HTML page, resolved against `base`, without fragments.
+fn links(base: &str, html: &str) -> Vec<Url> {
+    let Ok(base) = Url::parse(base) else {
+        return Vec::new();
+    };
+    HREF.captures_iter(html)
+        .filter_map(|c| base.join(c[1].trim()).ok())
+        .filter(|url| matches!(url.scheme(), "http" | "https"))
+        .map(|mut url| {
+            url.set_fragment(None);
+            url
+        })
+        .collect()
+}
+
+fn truncate(mut output: String, max_bytes: usize) -> String {
+    if output.len() <= max_bytes {
+        return output;
+    }
+    let mut end = max_bytes;
+    while !output.is_char_boundary(end) {
+        end -= 1;
+    }
+    output.truncate(end);
+    output.push_str("\n[output truncated]");
+    output
+}
+
+fn definition(name: &str) -> Tool {
+    let url = json!({ "type": "string", "description": "http(s) URL" });
+    let fresh = json!({
+        "type": "boolean",
+        "description": "Fetch a fresh copy instead of using the cache"
+    });
+    let (description, properties) = match name {
+        "crawl_fetch" => (
+            "Fetch a web page. Responses are cached and revalidated, so refetching is cheap.",
+            json!({ "url": url, "fresh": fresh }),
+        ),
+        _ => (
+            "Crawl a site: fetch a page and follow its links on the same host, breadth first.",
+            json!({
+                "url": url,
+                "max_pages": { "type": "integer", "minimum": 1, "description": "Most pages to fetch" },
+                "fresh": fresh
+            }),
+        ),
+    };
+    Tool {
+        name: name.to_string(),
+        description: Some(description.to_string()),
+        input_schema: json!({
+            "type": "object",
+            "properties": properties,
+            "required": ["url"],
+            "additionalProperties": false
+        }),
+        output_schema: None,
+    }
+}
+
+/// Where the cache lives for `config` in `workspace`.
+pub fn cache_dir(config: &CrawlMcpConfig, workspace: &Path) -> PathBuf {
+    match &config.cache_dir {
+        Some(dir) => workspace.join(dir),
+        None => workspace.join(".distri/cache/http"),
+    }
+}
+
+pub fn build<T: Transport>(
+    t: T,
+    config: CrawlMcpConfig,
+    cache_dir: PathBuf,
+    egress: Option<EgressCheck>,
+) -> Result<Server<T>> {
+    let mut server = Server::builder(t)
+        .capabilities(ServerCapabilities {
+            tools: Some(json!({})),
+            ..Default::default()
+        })
+        .request_handler("resources/list", |_req: ListRequest| {
+            Box::pin(async move {
+                Ok(ResourcesListResponse {
+                    resources: Vec::new(),
+                    next_cursor: None,
+                    meta: None,
+                })
+            })
+        })
+        .request_handler("prompts/list", |_req: ListRequest| {
+            Box::pin(async move {
+                Ok(PromptsListResponse {
+                    prompts: Vec::new(),
+                    next_cursor: None,
+                    meta: None,
+                })
+            })
+        });
+
+    register_tools(
+        &mut server,
+        Arc::new(CrawlTools::new(config, cache_dir, egress)?),
+    );
+
+    Ok(server.build())
+}
+
+fn register_tools<T: Transport>(server: &mut ServerBuilder<T>, tools: Arc<CrawlTools>) {
+    for name in TOOLS {
+        let tools = tools.clone();
+        server.register_tool(definition(name), move |req: CallToolRequest| {
+            let tools = tools.clone();
+            Box::pin(async move {
+                let args = Value::Object(
+                    req.arguments
+                        .unwrap_or_default()
+                        .into_iter()
+                        .collect::<serde_json::Map<String, Value>>(),
+                );
+                let (text, is_error) = match tools.call(name, &args).await {
+                    Ok(text) => (text, None),
+                    Err(e) => match e.downcast_ref::<EgressDenied>() {
+                        Some(denied) => (denied.to_json().to_string(), Some(true)),
+                        None => (e.to_string(), Some(true)),
+                    },
+                };
+                Ok(CallToolResponse {
+                    content: vec![ToolResponseContent::Text { text }],
+                    is_error,
+                    meta: None,
+                })
+            })
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn tools(dir: &Path) -> CrawlTools {
+        CrawlTools::new(
+            CrawlMcpConfig {
+                min_request_interval_ms: 0,
+                ..Default::default()
+            },
+            dir.to_path_buf(),
+            None,
+        )
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn cached_pages_are_revalidated_with_their_etag() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/docs"))
+            .and(header("if-none-match", "\"v1\""))
+            .respond_with(ResponseTemplate::new(304))
+            .with_priority(1)
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/docs"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("etag", "\"v1\"")
+                    .set_body_string("hello"),
+            )
+            .expect(2)
+            .mount(&server)
+            .await;
+        let dir = tempfile::tempdir().unwrap();
+        let tools = tools(dir.path());
+        let url = format!("{}/docs", server.uri());
+
+        let first = tools.fetch(&url, false).await.unwrap();
+        let second = tools.fetch(&url, false).await.unwrap();
+        let bypassed = tools.fetch(&url, true).await.unwrap();
+
+        assert_eq!(first.cache, CacheStatus::Miss);
+        assert_eq!(second.cache, CacheStatus::Revalidated);
+        assert_eq!(second.body, "hello");
+        assert_eq!(bypassed.cache, CacheStatus::Bypass);
+    }
+
+    #[tokio::test]
+    async fn fresh_responses_are_served_without_a_request() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/feed"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("cache-control", "public, max-age=600")
+                    .set_body_string("items"),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+        let dir = tempfile::tempdir().unwrap();
+        let tools = tools(dir.path());
+        let url = format!("{}/feed", server.uri());
+
+        tools.fetch(&url, false).await.unwrap();
+        let again = tools.fetch(&url, false).await.unwrap();
+
+        assert_eq!(again.cache, CacheStatus::Hit);
+        assert_eq!(again.body, "items");
+    }
+
+    #[tokio::test]
+    async fn robots_disallowed_pages_are_not_fetched() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/robots.txt"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_string("User-agent: *\nDisallow: /private\n"),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/private/report"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(0)
+            .mount(&server)
+            .await;
+        let dir = tempfile::tempdir().unwrap();
+        let tools = tools(dir.path());
+
+        let err = tools
+            .call(
+                "crawl_fetch",
+                &json!({ "url": format!("{}/private/report", server.uri()) }),
+            )
+            .await
+            .unwrap_err();
+
+        assert!(err.to_string().contains("robots.txt"));
+    }
+
+    #[tokio::test]
+    async fn crawl_follows_same_host_links() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(
+                r#"<a href="/a">a</a> <a href="/b#top">b</a> <a href="https://elsewhere.example/">x</a>"#,
+                "text/html",
+            ))
+            .mount(&server)
+            .await;
+        for page in ["/a", "/b"] {
+            Mock::given(method("GET"))
+                .and(path(page))
+                .respond_with(ResponseTemplate::new(200).set_body_raw(page, "text/plain"))
+                .mount(&server)
+                .await;
+        }
+        let dir = tempfile::tempdir().unwrap();
+        let tools = tools(dir.path());
+
+        let result = tools
+            .crawl(&format!("{}/", server.uri()), 10, false)
+            .await
+            .unwrap();
+
+        let urls: Vec<&str> = result["pages"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|p| p["url"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            urls,
+            vec![
+                format!("{}/", server.uri()),
+                format!("{}/a", server.uri()),
+                format!("{}/b", server.uri()),
+            ]
+        );
+    }
+
+    #[test]
+    fn cache_evicts_least_recently_used_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = HttpCache::new(dir.path().to_path_buf(), 500);
+        let entry = |url: &str| CacheEntry {
+            url: url.to_string(),
+            status: 200,
+            content_type: None,
+            etag: None,
+            last_modified: None,
+            fresh_until: None,
+            body: "x".repeat(100),
+        };
+
+        cache.put(&entry("https://a.example/1")).unwrap();
+        std::thread::sleep(Duration::from_millis(20));
+        cache.put(&entry("https://a.example/2")).unwrap();
+        std::thread::sleep(Duration::from_millis(20));
+        cache.get("https://a.example/1").unwrap();
+        std::thread::sleep(Duration::from_millis(20));
+        cache.put(&entry("https://a.example/3")).unwrap();
+
+        assert!(cache.get("https://a.example/1").is_some());
+        assert!(cache.get("https://a.example/2").is_none());
+        assert!(cache.get("https://a.example/3").is_some());
+    }
+
+    #[test]
+    fn robots_rules_pick_the_matching_group_and_longest_pattern() {
+        let robots = "\
+User-agent: *
+Disallow: /
+
+User-agent: distri-crawl
+Disallow: /admin
+Allow: /admin/public$
+Disallow: /*.pdf$
+";
+        let rules = RobotsRules::parse(robots, "distri-crawl/1.0");
+        assert!(rules.allows("/blog/post"));
+        assert!(!rules.allows("/admin/users"));
+        assert!(rules.allows("/admin/public"));
+        assert!(!rules.allows("/admin/public/x"));
+        assert!(!rules.allows("/files/report.pdf"));
+        assert!(rules.allows("/files/report.pdf?download=1"));
+
+        let others = RobotsRules::parse(robots, "OtherBot/2.0");
+        assert!(!others.allows("/blog/post"));
+    }
+
+    #[tokio::test]
+    async fn rate_limit_spaces_requests_to_the_same_host() {
+        let limiter = RateLimiter {
+            interval: Duration::from_millis(50),
+            next: Mutex::new(HashMap::new()),
+        };
+        let started = Instant::now();
+
+        limiter.wait("a.example").await;
+        limiter.wait("b.example").await;
+        assert!(started.elapsed() < Duration::from_millis(50));
+        limiter.wait("a.example").await;
+        assert!(started.elapsed() >= Duration::from_millis(50));
+    }
+}
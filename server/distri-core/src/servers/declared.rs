@@ -0,0 +1,440 @@
+//! MCP servers declared in the server config (`mcp_servers`).
+//!
+//! Servers without `auth` are process-wide: one connection (and for `stdio`
+//! one child process) is shared by every run. `eager` ones are connected and
+//! health-checked by [`DeclaredMcpServers::start`] at boot, `lazy` ones on
+//! the first run that uses them. Servers bound to an auth provider are
+//! connected per run instead, with the headers of the run user's connection,
+//! through the `McpClientPool` this provider builds for the run.
+//!
+//! The prompts of shared servers are registered as prompt templates named
+//! `mcp/<server>/<prompt>` when the server is first connected; see
+//! [`DeclaredMcpServers::with_prompt_registry`].
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use distri_auth::{OAuthHandler, ProviderRegistry, ProviderSessionStore};
+use distri_types::auth::ToolAuthStore;
+use distri_types::mcp_servers::{McpServerConfig, McpServerTransport, McpStartMode};
+use distri_types::stores::SecretStore;
+use distri_types::McpServerHandle;
+use tokio::sync::{Mutex, RwLock};
+
+use super::mcp_client::{connect, connect_stdio};
+use super::{McpClientPool, McpPoolProvider, McpPromptHandle, RemoteMcpClient};
+use crate::agent::prompt_registry::{PromptRegistry, PromptTemplate};
+use crate::agent::ExecutorContext;
+use crate::connections::provider_http_headers;
+
+/// Where OAuth flows of the server return to.
+const OAUTH_CALLBACK_PATH: &str = "/v1/connections/oauth/callback";
+
+#[derive(Clone)]
+pub struct DeclaredMcpServers {
+    servers: Arc<HashMap<String, McpServerConfig>>,
+    clients: Arc<RwLock<HashMap<String, Arc<RemoteMcpClient>>>>,
+    connect_lock: Arc<Mutex<()>>,
+    /// Where `{{secret:NAME}}` references in the entries are looked up.
+    secret_store: Option<Arc<dyn SecretStore>>,
+    /// Where the prompts of shared servers are registered.
+    prompt_registry: Option<Arc<PromptRegistry>>,
+}
+
+impl DeclaredMcpServers {
+    /// Validate `servers`. Disabled entries are left out.
+    pub fn new(servers: Vec<McpServerConfig>) -> Result<Self> {
+        let mut by_name = HashMap::new();
+        for server in servers.into_iter().filter(|s| s.enabled) {
+            server.validate().map_err(|e| anyhow!(e))?;
+            let name = server.name.clone();
+            if by_name.insert(name.clone(), server).is_some() {
+                return Err(anyhow!("MCP server '{}' is declared more than once", name));
+            }
+        }
+        Ok(Self {
+            servers: Arc::new(by_name),
+            clients: Arc::new(RwLock::new(HashMap::new())),
+            connect_lock: Arc::new(Mutex::new(())),
+            secret_store: None,
+            prompt_registry: None,
+        })
+    }
+
+    pub fn with_secret_store(mut self, store: Option<Arc<dyn SecretStore>>) -> Self {
+        self.secret_store = store;
+        self
+    }
+
+    /// Register the prompts of each shared server in `registry` once it is
+    /// connected. Prompts of auth-bound servers depend on the run's user and
+    /// are not registered.
+    pub fn with_prompt_registry(mut self, registry: Arc<PromptRegistry>) -> Self {
+        self.prompt_registry = Some(registry);
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.servers.is_empty()
+    }
+
+    /// Whether `name` is declared without `auth`, so its connection is
+    /// shared by every run.
+    pub fn is_shared(&self, name: &str) -> bool {
+        self.servers.get(name).is_some_and(|s| s.auth.is_none())
+    }
+
+    /// Connect the `eager` servers and run their health checks. Fails when
+    /// a check marked `required` fails; other failures are logged and the
+    /// server is connected again on first use.
+    pub async fn start(&self) -> Result<()> {
+        let mut eager: Vec<&McpServerConfig> = self
+            .servers
+            .values()
+            .filter(|s| s.start == McpStartMode::Eager)
+            .collect();
+        eager.sort_by(|a, b| a.name.cmp(&b.name));
+        for server in eager {
+            match self.health_check(server).await {
+                Ok(tools) => {
+                    tracing::info!(server = %server.name, tools = ?tools, "MCP server started")
+                }
+                Err(e) if server.health_check.required => {
+                    return Err(e.context(format!("MCP server '{}' failed to start", server.name)))
+                }
+                Err(e) => tracing::warn!(
+                    server = %server.name,
+                    error = ?e,
+                    "MCP server failed to start; it is retried on first use"
+                ),
+            }
+        }
+        Ok(())
+    }
+
+    /// Import the legacy `<SERVER>_SESSION_TOKEN` env vars of the declared
+    /// servers into `auth_store` as session tokens of `user_id`, keyed by
+    /// the server's provider (its own name when it has no `auth`). Returns
+    /// the servers whose token was imported.
+    pub async fn import_env_sessions(
+        &self,
+        auth_store: Arc<dyn ToolAuthStore>,
+        user_id: &str,
+        lookup: impl Fn(&str) -> Option<String>,
+    ) -> Result<Vec<String>> {
+        let registry = Arc::new(ProviderRegistry::new(OAUTH_CALLBACK_PATH));
+        registry.load_default_providers().await?;
+        let handler = Arc::new(OAuthHandler::new(
+            auth_store,
+            OAUTH_CALLBACK_PATH.to_string(),
+        ));
+        let sessions = ProviderSessionStore::new(registry, handler);
+        for server in self.servers.values() {
+            let provider = server
+                .auth
+                .as_ref()
+                .map_or(server.name.as_str(), |a| a.provider.as_str());
+            sessions
+                .register_tool_provider(server.name.clone(), provider.to_string())
+                .await;
+        }
+        Ok(sessions.import_env_sessions(user_id, lookup).await?)
+    }
+
+    /// The shared connection to `name`, connecting it on first use.
+    pub async fn client(&self, name: &str) -> Result<Arc<RemoteMcpClient>> {
+        if let Some(client) = self.clients.read().await.get(name).cloned() {
+            return Ok(client);
+        }
+        let _g = self.connect_lock.lock().await;
+        if let Some(client) = self.clients.read().await.get(name).cloned() {
+            return Ok(client);
+        }
+        let server = self
+            .servers
+            .get(name)
+            .filter(|s| s.auth.is_none())
+            .ok_or_else(|| anyhow!("MCP server '{}' is not a shared declared server", name))?;
+        let client = Arc::new(self.connect(server).await?);
+        self.clients
+            .write()
+            .await
+            .insert(name.to_string(), client.clone());
+        self.register_prompts(&client).await;
+        Ok(client)
+    }
+
+    /// Register the prompts of `client` as templates. A prompt's arguments
+    /// become handlebars variables of the same name, so they are filled in
+    /// from the template data when the template is rendered.
+    async fn register_prompts(&self, client: &RemoteMcpClient) {
+        let Some(registry) = &self.prompt_registry else {
+            return;
+        };
+        if !client.supports_prompts() {
+            return;
+        }
+        let prompts = match client.list_prompts().await {
+            Ok(prompts) => prompts,
+            Err(e) => {
+                tracing::warn!(server = %client.name(), error = ?e, "MCP list_prompts failed");
+                return;
+            }
+        };
+        for prompt in prompts {
+            let content = match client
+                .get_prompt(&prompt.name, prompt_placeholders(&prompt))
+                .await
+            {
+                Ok(content) => content,
+                Err(e) => {
+                    tracing::warn!(
+                        server = %prompt.server,
+                        prompt = %prompt.name,
+                        error = ?e,
+                        "MCP get_prompt failed; the prompt is not registered"
+                    );
+                    continue;
+                }
+            };
+            let template = PromptTemplate {
+                name: prompt_template_name(&prompt.server, &prompt.name),
+                content,
+                description: (!prompt.description.is_empty()).then_some(prompt.description),
+                version: None,
+            };
+            tracing::debug!(template = %template.name, "registered MCP prompt");
+            let _ = registry.register_template(template).await;
+        }
+    }
+
+    /// Connect `server` and list its tools within the check's timeout.
+    /// Returns the number of tools, or `None` when the check is disabled.
+    async fn health_check(&self, server: &McpServerConfig) -> Result<Option<usize>> {
+        if !server.health_check.enabled {
+            self.client(&server.name).await?;
+            return Ok(None);
+        }
+        let timeout = Duration::from_secs(server.health_check.timeout_secs);
+        let tools = tokio::time::timeout(timeout, async {
+            let client = self.client(&server.name).await?;
+            client.list_tools().await
+        })
+        .await
+        .unwrap_or_else(|_| Err(anyhow!("no tool list within {}s", timeout.as_secs())));
+        match tools {
+            Ok(tools) => Ok(Some(tools.len())),
+            Err(e) => {
+                // Drop a connection that came up but failed the check.
+                self.clients.write().await.remove(&server.name);
+                Err(e)
+            }
+        }
+    }
+
+    async fn connect(&self, server: &McpServerConfig) -> Result<RemoteMcpClient> {
+        let server = self.resolve_secret_refs(server).await?;
+        match server.transport {
+            McpServerTransport::Stdio => {
+                let command = server.command.as_deref().unwrap_or_default();
+                connect_stdio(&server.name, command, &server.args, &server.env).await
+            }
+            McpServerTransport::StreamableHttp | McpServerTransport::Sse => {
+                connect(&handle(&server, HashMap::new())?).await
+            }
+        }
+    }
+
+    async fn resolve_secret_refs(&self, server: &McpServerConfig) -> Result<McpServerConfig> {
+        let mut resolved = server.clone();
+        crate::secrets::SecretResolver::new(self.secret_store.clone())
+            .resolve_secret_refs(&mut resolved)
+            .await
+            .map_err(|e| anyhow!("MCP server '{}': {}", server.name, e))?;
+        Ok(resolved)
+    }
+
+    /// Handle of an auth-bound server, with the headers of the connection
+    /// the run's user has for the server's provider.
+    async fn auth_handle(
+        &self,
+        server: &McpServerConfig,
+        ctx: &ExecutorContext,
+    ) -> Result<McpServerHandle> {
+        let provider = server
+            .auth
+            .as_ref()
+            .map(|a| a.provider.as_str())
+            .unwrap_or_default();
+        let headers = provider_http_headers(ctx, provider).await?;
+        handle(server, headers)
+    }
+}
+
+/// Name of the prompt template registered for `prompt` of `server`.
+pub fn prompt_template_name(server: &str, prompt: &str) -> String {
+    format!("mcp/{}/{}", server, prompt)
+}
+
+/// `{{argument}}` for each argument of `prompt`.
+fn prompt_placeholders(prompt: &McpPromptHandle) -> serde_json::Map<String, serde_json::Value> {
+    prompt
+        .arguments
+        .iter()
+        .map(|(name, _)| {
+            (
+                name.clone(),
+                serde_json::Value::String(format!("{{{{{}}}}}", name)),
+            )
+        })
+        .collect()
+}
+
+fn handle(
+    server: &McpServerConfig,
+    resolved_headers: HashMap<String, String>,
+) -> Result<McpServerHandle> {
+    let transport = server
+        .client_transport()
+        .ok_or_else(|| anyhow!("MCP server '{}' has no remote transport", server.name))?;
+    Ok(McpServerHandle {
+        name: server.name.clone(),
+        transport,
+        resolved_headers,
+        enabled: true,
+    })
+}
+
+/// Every run gets a pool of the auth-bound servers its user has a connection
+/// for, backed by the shared connections of the others.
+#[async_trait::async_trait]
+impl McpPoolProvider for DeclaredMcpServers {
+    async fn build_pool(&self, ctx: &ExecutorContext) -> Option<Arc<McpClientPool>> {
+        let mut handles = Vec::new();
+        for server in self.servers.values().filter(|s| s.auth.is_some()) {
+            match self.auth_handle(server, ctx).await {
+                Ok(handle) => handles.push(handle),
+                Err(e) => tracing::warn!(
+                    server = %server.name,
+                    error = ?e,
+                    "MCP server auth could not be resolved; its tools are unavailable"
+                ),
+            }
+        }
+        Some(Arc::new(
+            McpClientPool::new(handles)
+                .with_secret_store(self.secret_store.clone())
+                .with_shared_servers(self.clone()),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn servers(raw: &str) -> DeclaredMcpServers {
+        DeclaredMcpServers::new(serde_yaml::from_str(raw).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn disabled_and_duplicate_entries() {
+        let declared = servers(
+            "- { name: fs, transport: stdio, command: mcp-fs, enabled: false }\n\
+             - { name: crm, transport: sse, url: 'https://crm.example.com/sse', auth: { provider: crm } }\n",
+        );
+        assert!(!declared.is_shared("fs"), "disabled servers are left out");
+        assert!(
+            !declared.is_shared("crm"),
+            "auth-bound servers connect per run"
+        );
+
+        let twice = serde_yaml::from_str(
+            "- { name: fs, transport: stdio, command: mcp-fs }\n\
+             - { name: fs, transport: stdio, command: mcp-fs2 }\n",
+        )
+        .unwrap();
+        let err = DeclaredMcpServers::new(twice).err().unwrap();
+        assert!(err.to_string().contains("more than once"), "{err}");
+    }
+
+    #[test]
+    fn prompt_arguments_become_template_variables() {
+        let prompt = McpPromptHandle {
+            server: "github".to_string(),
+            name: "review".to_string(),
+            description: String::new(),
+            arguments: vec![("repo".to_string(), true), ("focus".to_string(), false)],
+        };
+        let placeholders = prompt_placeholders(&prompt);
+        assert_eq!(placeholders["repo"], "{{repo}}");
+        assert_eq!(placeholders["focus"], "{{focus}}");
+        assert_eq!(
+            prompt_template_name("github", "review"),
+            "mcp/github/review"
+        );
+    }
+
+    #[tokio::test]
+    async fn required_health_check_fails_startup() {
+        let declared = servers(
+            "- name: broken\n  transport: stdio\n  command: distri-no-such-mcp-server\n  start: eager\n",
+        );
+        declared.start().await.unwrap();
+        assert!(declared.clients.read().await.is_empty());
+
+        let declared = servers(
+            "- name: broken\n  transport: stdio\n  command: distri-no-such-mcp-server\n  start: eager\n  health_check: { required: true }\n",
+        );
+        let err = declared.start().await.err().unwrap();
+        assert!(
+            err.to_string().contains("'broken' failed to start"),
+            "{err}"
+        );
+    }
+
+    #[tokio::test]
+    async fn env_sessions_are_imported_under_the_provider() {
+        let declared = servers(
+            "- { name: fs, transport: stdio, command: mcp-fs }\n\
+             - { name: crm-api, transport: sse, url: 'https://crm.example.com/sse', auth: { provider: crm } }\n",
+        );
+        let store: Arc<dyn ToolAuthStore> = Arc::new(distri_stores::InMemoryToolAuthStore::new());
+        let env = HashMap::from([
+            ("CRM_API_SESSION_TOKEN", "crm-token"),
+            ("FS_SESSION_TOKEN", " "),
+        ]);
+        let imported = declared
+            .import_env_sessions(store.clone(), "local_dev_user", |k| {
+                env.get(k).map(|v| v.to_string())
+            })
+            .await
+            .unwrap();
+        assert_eq!(imported, vec!["crm-api".to_string()]);
+
+        let secret = store
+            .get_secret(
+                "local_dev_user",
+                Some("crm"),
+                distri_types::auth::SESSION_TOKEN_SECRET_KEY,
+            )
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(secret.get_secret(), "crm-token");
+    }
+
+    #[tokio::test]
+    async fn pool_falls_back_to_shared_servers() {
+        let declared =
+            servers("- name: broken\n  transport: stdio\n  command: distri-no-such-mcp-server\n");
+        let pool = McpClientPool::new(Vec::new()).with_shared_servers(declared);
+
+        let err = pool.connect_named("broken").await.err().unwrap();
+        assert!(err.to_string().contains("spawning"), "{err}");
+        let err = pool.connect_named("other").await.err().unwrap();
+        assert!(err.to_string().contains("not configured"), "{err}");
+    }
+}
@@ -0,0 +1,461 @@
+//! Programmable in-memory MCP server for tests.
+//!
+//! Enabled with the `testing` feature, like [`crate::testing`]. A
+//! [`FakeMcpServer`] serves scripted [`FakeMcpTool`]s over the in-memory
+//! transport, so a test exercises the real `tools/list` / `tools/call`
+//! exchange without starting spider, tavily or any other real server:
+//!
+//! ```ignore
+//! let server = FakeMcpServer::new().tool(
+//!     FakeMcpTool::new("search")
+//!         .description("Search the web")
+//!         .input_schema(json!({ "type": "object", "properties": { "query": { "type": "string" } } }))
+//!         .expect_arguments(json!({ "query": "rust" }))
+//!         .respond(json!({ "results": [] })),
+//! );
+//! server.attach(&harness.orchestrator, "web_search", "researcher").await?;
+//!
+//! harness.run("researcher", "Search for rust").await.assert_success();
+//! assert_eq!(server.calls_to("search").len(), 1);
+//! server.assert_satisfied();
+//! ```
+//!
+//! [`FakeMcpServer::metadata`] is a [`ServerMetadataWrapper`] like the ones of
+//! the bundled servers; [`FakeMcpServer::attach`] registers it with the
+//! orchestrator's MCP registry and gives the agent its tools through a
+//! client connected to that registration.
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+
+use anyhow::Result;
+use async_mcp::client::{Client, ClientBuilder};
+use async_mcp::protocol::RequestOptions;
+use async_mcp::server::{Server, ServerBuilder};
+use async_mcp::transport::{ClientInMemoryTransport, Transport};
+use async_mcp::types::{
+    CallToolRequest, CallToolResponse, ListRequest, PromptsListResponse, ResourcesListResponse,
+    ServerCapabilities, ToolResponseContent,
+};
+use distri_types::{McpServerMetadata, Part, ServerMetadataWrapper, ServerTrait, TransportType};
+use serde_json::{json, Value};
+use tokio::sync::RwLock;
+
+use crate::agent::AgentOrchestrator;
+use crate::servers::registry::McpServerRegistry;
+use crate::tools::Tool;
+use crate::types::ToolCall;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+type Handler = dyn Fn(&Value) -> FakeResponse + Send + Sync;
+
+/// One scripted tool result.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FakeResponse {
+    /// A JSON result, sent as text content (strings as they are).
+    Json(Value),
+    /// A tool error (`isError: true`) with this message.
+    Error(String),
+}
+
+/// A `tools/call` as the fake server received it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FakeMcpCall {
+    pub tool: String,
+    pub arguments: Value,
+}
+
+/// A tool of a [`FakeMcpServer`]. Scripted responses are consumed in order;
+/// once they run out the [`FakeMcpTool::respond_with`] handler answers, and
+/// without one the call fails.
+pub struct FakeMcpTool {
+    name: String,
+    description: String,
+    input_schema: Value,
+    script: VecDeque<FakeResponse>,
+    handler: Option<Arc<Handler>>,
+    expected_arguments: Option<Value>,
+}
+
+impl FakeMcpTool {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            description: String::new(),
+            input_schema: json!({ "type": "object", "properties": {} }),
+            script: VecDeque::new(),
+            handler: None,
+            expected_arguments: None,
+        }
+    }
+
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = description.into();
+        self
+    }
+
+    pub fn input_schema(mut self, input_schema: Value) -> Self {
+        self.input_schema = input_schema;
+        self
+    }
+
+    /// Answer the next call with `result`.
+    pub fn respond(self, result: Value) -> Self {
+        self.respond_script(FakeResponse::Json(result))
+    }
+
+    /// Fail the next call with a tool error.
+    pub fn respond_error(self, message: impl Into<String>) -> Self {
+        self.respond_script(FakeResponse::Error(message.into()))
+    }
+
+    pub fn respond_script(mut self, response: FakeResponse) -> Self {
+        self.script.push_back(response);
+        self
+    }
+
+    /// Answer calls from their arguments once the script is used up.
+    pub fn respond_with(
+        mut self,
+        handler: impl Fn(&Value) -> FakeResponse + Send + Sync + 'static,
+    ) -> Self {
+        self.handler = Some(Arc::new(handler));
+        self
+    }
+
+    /// Every call must pass exactly these arguments. A call that does not
+    /// gets a tool error and is reported by
+    /// [`FakeMcpServer::assert_satisfied`].
+    pub fn expect_arguments(mut self, arguments: Value) -> Self {
+        self.expected_arguments = Some(arguments);
+        self
+    }
+
+    fn definition(&self) -> async_mcp::types::Tool {
+        async_mcp::types::Tool {
+            name: self.name.clone(),
+            description: Some(self.description.clone()),
+            input_schema: self.input_schema.clone(),
+            output_schema: None,
+        }
+    }
+}
+
+#[derive(Default)]
+struct FakeState {
+    tools: Vec<FakeMcpTool>,
+    calls: Vec<FakeMcpCall>,
+    failures: Vec<String>,
+}
+
+/// Scripted MCP server. Clones share the tools and the recorded calls, so
+/// keep one to inspect after registering another.
+#[derive(Clone, Default)]
+pub struct FakeMcpServer {
+    state: Arc<Mutex<FakeState>>,
+}
+
+impl std::fmt::Debug for FakeMcpServer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let state = self.lock();
+        f.debug_struct("FakeMcpServer")
+            .field(
+                "tools",
+                &state.tools.iter().map(|t| &t.name).collect::<Vec<_>>(),
+            )
+            .field("calls", &state.calls)
+            .finish()
+    }
+}
+
+impl FakeMcpServer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn tool(self, tool: FakeMcpTool) -> Self {
+        self.lock().tools.push(tool);
+        self
+    }
+
+    /// Every `tools/call` received so far, oldest first.
+    pub fn calls(&self) -> Vec<FakeMcpCall> {
+        self.lock().calls.clone()
+    }
+
+    pub fn calls_to(&self, tool: &str) -> Vec<FakeMcpCall> {
+        self.lock()
+            .calls
+            .iter()
+            .filter(|call| call.tool == tool)
+            .cloned()
+            .collect()
+    }
+
+    /// Calls that broke an expectation or found nothing to answer with.
+    pub fn failures(&self) -> Vec<String> {
+        self.lock().failures.clone()
+    }
+
+    /// Panic if a call broke an expectation or a scripted response was never
+    /// used.
+    pub fn assert_satisfied(&self) {
+        let state = self.lock();
+        let mut problems = state.failures.clone();
+        for tool in &state.tools {
+            if !tool.script.is_empty() {
+                problems.push(format!(
+                    "{} scripted response(s) of '{}' were never requested",
+                    tool.script.len(),
+                    tool.name
+                ));
+            }
+        }
+        assert!(problems.is_empty(), "{}", problems.join("\n"));
+    }
+
+    /// Build the server on `transport`, as the bundled servers' `build` do.
+    pub fn build<T: Transport>(&self, transport: T) -> Result<Server<T>> {
+        let mut server = Server::builder(transport)
+            .capabilities(ServerCapabilities {
+                tools: Some(json!({})),
+                ..Default::default()
+            })
+            .request_handler("resources/list", |_req: ListRequest| {
+                Box::pin(async move {
+                    Ok(ResourcesListResponse {
+                        resources: Vec::new(),
+                        next_cursor: None,
+                        meta: None,
+                    })
+                })
+            })
+            .request_handler("prompts/list", |_req: ListRequest| {
+                Box::pin(async move {
+                    Ok(PromptsListResponse {
+                        prompts: Vec::new(),
+                        next_cursor: None,
+                        meta: None,
+                    })
+                })
+            });
+        self.register_tools(&mut server);
+        Ok(server.build())
+    }
+
+    /// Registration of this server for [`McpServerRegistry`] or
+    /// [`AgentOrchestrator::register_mcp_server`].
+    pub fn metadata(&self) -> ServerMetadataWrapper {
+        let server = self.clone();
+        ServerMetadataWrapper {
+            server_metadata: McpServerMetadata {
+                auth_session_key: None,
+                mcp_transport: TransportType::InMemory,
+                auth_type: None,
+            },
+            builder: Some(Arc::new(move |_, transport| {
+                Ok(Box::new(server.build(transport)?) as Box<dyn ServerTrait>)
+            })),
+        }
+    }
+
+    /// Register the server as `server_name` and give `agent_id` its tools.
+    pub async fn attach(
+        &self,
+        orchestrator: &AgentOrchestrator,
+        server_name: &str,
+        agent_id: &str,
+    ) -> Result<()> {
+        orchestrator
+            .register_mcp_server(server_name.to_string(), self.metadata())
+            .await;
+        for tool in in_memory_tools(orchestrator.mcp_registry.clone(), server_name).await? {
+            orchestrator.register_tool(agent_id, tool).await;
+        }
+        Ok(())
+    }
+
+    fn register_tools<T: Transport>(&self, server: &mut ServerBuilder<T>) {
+        let definitions: Vec<_> = self.lock().tools.iter().map(|t| t.definition()).collect();
+        for definition in definitions {
+            let fake = self.clone();
+            let name = definition.name.clone();
+            server.register_tool(definition, move |req: CallToolRequest| {
+                let arguments = match serde_json::to_value(req.arguments).unwrap_or_default() {
+                    Value::Null => json!({}),
+                    arguments => arguments,
+                };
+                let response = fake.answer(&name, arguments);
+                Box::pin(async move { Ok(call_tool_response(response)) })
+            });
+        }
+    }
+
+    fn answer(&self, tool: &str, arguments: Value) -> FakeResponse {
+        let mut state = self.lock();
+        state.calls.push(FakeMcpCall {
+            tool: tool.to_string(),
+            arguments: arguments.clone(),
+        });
+        let Some(fake) = state.tools.iter_mut().find(|t| t.name == tool) else {
+            return FakeResponse::Error(format!("unknown tool '{}'", tool));
+        };
+        let failure = match &fake.expected_arguments {
+            Some(expected) if *expected != arguments => Some(format!(
+                "'{}' called with {} instead of {}",
+                tool, arguments, expected
+            )),
+            _ => None,
+        };
+        let response = match failure {
+            Some(failure) => Err(failure),
+            None => match (fake.script.pop_front(), &fake.handler) {
+                (Some(response), _) => Ok(response),
+                (None, Some(handler)) => Ok(handler(&arguments)),
+                (None, None) => Err(format!("'{}' has no response left", tool)),
+            },
+        };
+        response.unwrap_or_else(|failure| {
+            state.failures.push(failure.clone());
+            FakeResponse::Error(failure)
+        })
+    }
+
+    fn lock(&self) -> MutexGuard<'_, FakeState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+fn call_tool_response(response: FakeResponse) -> CallToolResponse {
+    let (text, is_error) = match response {
+        FakeResponse::Json(Value::String(text)) => (text, false),
+        FakeResponse::Json(value) => (value.to_string(), false),
+        FakeResponse::Error(message) => (message, true),
+    };
+    CallToolResponse {
+        content: vec![ToolResponseContent::Text { text }],
+        is_error: Some(is_error),
+        meta: None,
+    }
+}
+
+/// Connect to the in-memory server registered as `server_name` and wrap each
+/// tool it lists as a [`Tool`].
+pub async fn in_memory_tools(
+    registry: Arc<RwLock<McpServerRegistry>>,
+    server_name: &str,
+) -> Result<Vec<Arc<dyn Tool>>> {
+    let name = server_name.to_string();
+    let transport = ClientInMemoryTransport::new(move |transport| {
+        let registry = registry.clone();
+        let name = name.clone();
+        tokio::spawn(async move {
+            let server = registry.read().await.build(&name, transport);
+            if let Err(e) = async { server?.listen().await }.await {
+                tracing::warn!("in-memory MCP server '{}' stopped: {}", name, e);
+            }
+        })
+    });
+    transport.open().await?;
+    let client = ClientBuilder::new(transport).build();
+    let listener = client.clone();
+    tokio::spawn(async move { listener.start().await });
+
+    let listed = client
+        .request(
+            "tools/list",
+            Some(json!({})),
+            RequestOptions::default().timeout(REQUEST_TIMEOUT),
+        )
+        .await?;
+    let tools: Vec<async_mcp::types::Tool> =
+        serde_json::from_value(listed.get("tools").cloned().unwrap_or_default())?;
+    Ok(tools
+        .into_iter()
+        .map(|tool| {
+            Arc::new(InMemoryMcpTool {
+                server: server_name.to_string(),
+                tool,
+                client: client.clone(),
+            }) as Arc<dyn Tool>
+        })
+        .collect())
+}
+
+/// A tool of an in-memory MCP server, called over its client connection.
+#[derive(Clone)]
+pub struct InMemoryMcpTool {
+    server: String,
+    tool: async_mcp::types::Tool,
+    client: Client<ClientInMemoryTransport>,
+}
+
+impl std::fmt::Debug for InMemoryMcpTool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("InMemoryMcpTool")
+            .field("server", &self.server)
+            .field("tool", &self.tool.name)
+            .finish()
+    }
+}
+
+#[async_trait::async_trait]
+impl Tool for InMemoryMcpTool {
+    fn get_name(&self) -> String {
+        self.tool.name.clone()
+    }
+
+    fn get_description(&self) -> String {
+        self.tool.description.clone().unwrap_or_default()
+    }
+
+    fn get_parameters(&self) -> Value {
+        self.tool.input_schema.clone()
+    }
+
+    fn is_mcp(&self) -> bool {
+        true
+    }
+
+    async fn execute(
+        &self,
+        tool_call: ToolCall,
+        _context: Arc<distri_types::ToolContext>,
+    ) -> Result<Vec<Part>> {
+        let response = self
+            .client
+            .request(
+                "tools/call",
+                Some(json!({ "name": self.tool.name, "arguments": tool_call.input })),
+                RequestOptions::default().timeout(REQUEST_TIMEOUT),
+            )
+            .await?;
+        let response: CallToolResponse = serde_json::from_value(response)?;
+        let parts: Vec<Part> = response
+            .content
+            .into_iter()
+            .map(|content| match content {
+                ToolResponseContent::Text { text } => Part::Text(text),
+                other => Part::Data(serde_json::to_value(other).unwrap_or_default()),
+            })
+            .collect();
+        if response.is_error == Some(true) {
+            let message: Vec<String> = parts
+                .iter()
+                .filter_map(|part| match part {
+                    Part::Text(text) => Some(text.clone()),
+                    _ => None,
+                })
+                .collect();
+            anyhow::bail!(
+                "MCP tool '{}/{}' returned error: {}",
+                self.server,
+                self.tool.name,
+                message.join("\n")
+            );
+        }
+        Ok(parts)
+    }
+}
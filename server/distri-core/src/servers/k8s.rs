@@ -0,0 +1,796 @@
+//! `k8s` — an in-memory MCP server for debugging Kubernetes clusters.
+//!
+//! Read tools are always available and scoped to one namespace per call:
+//! `k8s_get`, `k8s_describe`, `k8s_logs` and `k8s_events`. Secrets cannot be
+//! read.
+//!
+//! Mutations (`k8s_scale`, `k8s_rollout_restart`, `k8s_delete_pod`) are only
+//! registered with `allow_mutations: true`, and none of them runs on the first
+//! call: the server answers with the exact `kubectl` command and an approval
+//! id. The agent gets the command approved (e.g. with `approval_request`) and
+//! repeats the call with the same arguments plus `approval_id`. Ids are
+//! single-use, bound to that command, and expire after ten minutes.
+//!
+//! Commands run through `kubectl`, against the kubeconfig found by
+//! [`discover_kubeconfig`].
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, bail, Result};
+use async_mcp::server::{Server, ServerBuilder};
+use async_mcp::transport::Transport;
+use async_mcp::types::{
+    CallToolRequest, CallToolResponse, ListRequest, PromptsListResponse, ResourcesListResponse,
+    ServerCapabilities, Tool, ToolResponseContent,
+};
+use distri_types::k8s::K8sMcpConfig;
+use serde_json::{json, Value};
+
+/// How long an approval id stays valid.
+const APPROVAL_TTL: Duration = Duration::from_secs(600);
+/// Service account token mounted into pods.
+const IN_CLUSTER_TOKEN: &str = "/var/run/secrets/kubernetes.io/serviceaccount/token";
+
+/// Where `kubectl` gets its cluster credentials from.
+#[derive(Debug, Clone, PartialEq)]
+pub enum KubeconfigSource {
+    /// `kubeconfig` from the server config.
+    Configured(PathBuf),
+    /// `$KUBECONFIG`, left for `kubectl` to read (it may list several files).
+    Env(String),
+    /// `~/.kube/config`.
+    Home(PathBuf),
+    /// The pod's service account.
+    InCluster,
+}
+
+impl KubeconfigSource {
+    /// `kubectl` arguments selecting this source.
+    fn args(&self) -> Vec<String> {
+        match self {
+            Self::Configured(path) | Self::Home(path) => {
+                vec![format!("--kubeconfig={}", path.display())]
+            }
+            Self::Env(_) | Self::InCluster => Vec::new(),
+        }
+    }
+}
+
+/// Find the kubeconfig to use: the configured file, then `$KUBECONFIG`, then
+/// `~/.kube/config`, then the in-cluster service account.
+pub fn discover_kubeconfig(configured: Option<&str>) -> Result<KubeconfigSource> {
+    let home = std::env::var_os("HOME")
+        .or_else(|| std::env::var_os("USERPROFILE"))
+        .map(PathBuf::from);
+    let in_cluster = std::env::var_os("KUBERNETES_SERVICE_HOST").is_some()
+        && Path::new(IN_CLUSTER_TOKEN).exists();
+    discover_from(
+        configured,
+        std::env::var("KUBECONFIG").ok(),
+        home,
+        in_cluster,
+        |path| path.exists(),
+    )
+}
+
+fn discover_from(
+    configured: Option<&str>,
+    kubeconfig_env: Option<String>,
+    home: Option<PathBuf>,
+    in_cluster: bool,
+    exists: impl Fn(&Path) -> bool,
+) -> Result<KubeconfigSource> {
+    if let Some(configured) = configured {
+        let path = match (configured.strip_prefix("~/"), &home) {
+            (Some(rest), Some(home)) => home.join(rest),
+            _ => PathBuf::from(configured),
+        };
+        if !exists(&path) {
+            bail!("kubeconfig '{}' does not exist", path.display());
+        }
+        return Ok(KubeconfigSource::Configured(path));
+    }
+    if let Some(env) = kubeconfig_env.filter(|v| !v.trim().is_empty()) {
+        return Ok(KubeconfigSource::Env(env));
+    }
+    if let Some(path) = home.map(|h| h.join(".kube").join("config")) {
+        if exists(&path) {
+            return Ok(KubeconfigSource::Home(path));
+        }
+    }
+    if in_cluster {
+        return Ok(KubeconfigSource::InCluster);
+    }
+    bail!("no kubeconfig found: set `k8s.kubeconfig`, $KUBECONFIG, or create ~/.kube/config")
+}
+
+/// Runs `kubectl` with the given arguments and returns its stdout.
+#[async_trait::async_trait]
+pub trait KubectlRunner: Send + Sync {
+    async fn run(&self, args: &[String]) -> Result<String>;
+}
+
+/// Runs the `kubectl` binary.
+pub struct KubectlCli {
+    config: K8sMcpConfig,
+}
+
+impl KubectlCli {
+    pub fn new(config: K8sMcpConfig) -> Self {
+        Self { config }
+    }
+}
+
+#[async_trait::async_trait]
+impl KubectlRunner for KubectlCli {
+    async fn run(&self, args: &[String]) -> Result<String> {
+        let source = discover_kubeconfig(self.config.kubeconfig.as_deref())?;
+        let mut command = tokio::process::Command::new(&self.config.kubectl);
+        command.args(source.args());
+        if let Some(context) = &self.config.context {
+            command.arg(format!("--context={}", context));
+        }
+        command.args(args).kill_on_drop(true);
+
+        let timeout = Duration::from_secs(self.config.timeout_secs);
+        let output = tokio::time::timeout(timeout, command.output())
+            .await
+            .map_err(|_| anyhow!("kubectl timed out after {}s", timeout.as_secs()))?
+            .map_err(|e| anyhow!("failed to run {}: {}", self.config.kubectl, e))?;
+        if !output.status.success() {
+            bail!(
+                "kubectl failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+}
+
+struct PendingMutation {
+    args: Vec<String>,
+    requested_at: Instant,
+}
+
+/// The tool implementations behind the server.
+pub struct K8sTools {
+    config: K8sMcpConfig,
+    runner: Arc<dyn KubectlRunner>,
+    pending: Mutex<HashMap<String, PendingMutation>>,
+}
+
+const READ_TOOLS: &[&str] = &["k8s_get", "k8s_describe", "k8s_logs", "k8s_events"];
+const MUTATION_TOOLS: &[&str] = &["k8s_scale", "k8s_rollout_restart", "k8s_delete_pod"];
+
+impl K8sTools {
+    pub fn new(config: K8sMcpConfig, runner: Arc<dyn KubectlRunner>) -> Self {
+        Self {
+            config,
+            runner,
+            pending: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Definitions of the tools this configuration exposes.
+    pub fn definitions(&self) -> Vec<Tool> {
+        let mut tools: Vec<Tool> = READ_TOOLS.iter().map(|name| definition(name)).collect();
+        if self.config.allow_mutations {
+            tools.extend(MUTATION_TOOLS.iter().map(|name| definition(name)));
+        }
+        tools
+    }
+
+    /// Run tool `name` with `args`, returning the text shown to the model.
+    pub async fn call(&self, name: &str, args: &Value) -> Result<String> {
+        let namespace = self.namespace(args)?;
+        let ns = format!("--namespace={}", namespace);
+        let command = match name {
+            "k8s_get" => {
+                let resource = resource_arg(args, "resource")?;
+                if is_secret(&resource) {
+                    bail!("reading secrets is not allowed");
+                }
+                let mut command = vec!["get".to_string(), resource];
+                if let Some(name) = optional_name(args, "name")? {
+                    command.push(name);
+                }
+                command.push(ns);
+                if let Some(selector) = optional_str(args, "label_selector") {
+                    command.push(format!("--selector={}", selector));
+                }
+                let output = optional_str(args, "output").unwrap_or("wide");
+                if !matches!(output, "wide" | "yaml" | "json") {
+                    bail!("output must be one of wide, yaml, json");
+                }
+                command.push(format!("--output={}", output));
+                command
+            }
+            "k8s_describe" => {
+                let resource = resource_arg(args, "resource")?;
+                if is_secret(&resource) {
+                    bail!("reading secrets is not allowed");
+                }
+                vec![
+                    "describe".to_string(),
+                    resource,
+                    name_arg(args, "name")?,
+                    ns,
+                ]
+            }
+            "k8s_logs" => {
+                let mut command = vec!["logs".to_string(), name_arg(args, "pod")?, ns];
+                if let Some(container) = optional_name(args, "container")? {
+                    command.push(format!("--container={}", container));
+                }
+                let tail = args
+                    .get("tail_lines")
+                    .and_then(Value::as_u64)
+                    .unwrap_or(200)
+                    .min(2000);
+                command.push(format!("--tail={}", tail));
+                if let Some(since) = optional_str(args, "since") {
+                    if !is_duration(since) {
+                        bail!("since must be a duration like 30s, 10m or 2h");
+                    }
+                    command.push(format!("--since={}", since));
+                }
+                if args.get("previous").and_then(Value::as_bool) == Some(true) {
+                    command.push("--previous".to_string());
+                }
+                command
+            }
+            "k8s_events" => {
+                let mut command = vec![
+                    "get".to_string(),
+                    "events".to_string(),
+                    ns,
+                    "--sort-by=.lastTimestamp".to_string(),
+                ];
+                if let Some(object) = optional_name(args, "object_name")? {
+                    command.push(format!("--field-selector=involvedObject.name={}", object));
+                }
+                command
+            }
+            _ if MUTATION_TOOLS.contains(&name) && self.config.allow_mutations => {
+                let command = mutation_command(name, args, ns)?;
+                return self.approve_or_run(command, args).await;
+            }
+            _ => bail!("unknown tool '{}'", name),
+        };
+        self.run(&command).await
+    }
+
+    fn namespace(&self, args: &Value) -> Result<String> {
+        let namespace = match optional_name(args, "namespace")? {
+            Some(namespace) => namespace,
+            None => self.config.effective_default_namespace().to_string(),
+        };
+        if !self.config.allows_namespace(&namespace) {
+            bail!(
+                "namespace '{}' is not allowed; allowed: {}",
+                namespace,
+                self.config.namespaces.join(", ")
+            );
+        }
+        Ok(namespace)
+    }
+
+    async fn approve_or_run(&self, command: Vec<String>, args: &Value) -> Result<String> {
+        match optional_str(args, "approval_id") {
+            Some(approval_id) => {
+                self.take_approval(approval_id, &command)?;
+                self.run(&command).await
+            }
+            None => self.request_approval(command),
+        }
+    }
+
+    fn request_approval(&self, command: Vec<String>) -> Result<String> {
+        let approval_id = uuid::Uuid::new_v4().to_string();
+        let response = json!({
+            "status": "approval_required",
+            "approval_id": approval_id,
+            "command": format!("kubectl {}", command.join(" ")),
+            "expires_in_secs": APPROVAL_TTL.as_secs(),
+            "next": "Ask the user to approve this command. Once approved, call this tool \
+                     again with the same arguments and this approval_id.",
+        });
+        let mut pending = self.pending()?;
+        pending.insert(
+            approval_id,
+            PendingMutation {
+                args: command,
+                requested_at: Instant::now(),
+            },
+        );
+        Ok(serde_json::to_string_pretty(&response)?)
+    }
+
+    /// Consume `approval_id`, which must have been issued for `command`.
+    fn take_approval(&self, approval_id: &str, command: &[String]) -> Result<()> {
+        match self.pending()?.remove(approval_id) {
+            Some(p) if p.args == command => Ok(()),
+            Some(_) => {
+                bail!("approval id was issued for a different command; request approval again")
+            }
+            None => bail!("unknown or expired approval id; request approval again"),
+        }
+    }
+
+    /// The pending approvals, with expired ones dropped.
+    fn pending(&self) -> Result<std::sync::MutexGuard<'_, HashMap<String, PendingMutation>>> {
+        let mut pending = self
+            .pending
+            .lock()
+            .map_err(|_| anyhow!("approvals lock poisoned"))?;
+        pending.retain(|_, p| p.requested_at.elapsed() < APPROVAL_TTL);
+        Ok(pending)
+    }
+
+    async fn run(&self, command: &[String]) -> Result<String> {
+        let output = self.runner.run(command).await?;
+        Ok(truncate(output, self.config.max_output_bytes))
+    }
+}
+
+fn mutation_command(name: &str, args: &Value, ns: String) -> Result<Vec<String>> {
+    Ok(match name {
+        "k8s_scale" => {
+            let kind = kind_arg(args, &["deployment", "statefulset", "replicaset"])?;
+            let replicas = args
+                .get("replicas")
+                .and_then(Value::as_u64)
+                .ok_or_else(|| anyhow!("replicas is required"))?;
+            vec![
+                "scale".to_string(),
+                format!("{}/{}", kind, name_arg(args, "name")?),
+                format!("--replicas={}", replicas),
+                ns,
+            ]
+        }
+        "k8s_rollout_restart" => {
+            let kind = kind_arg(args, &["deployment", "statefulset", "daemonset"])?;
+            vec![
+                "rollout".to_string(),
+                "restart".to_string(),
+                format!("{}/{}", kind, name_arg(args, "name")?),
+                ns,
+            ]
+        }
+        "k8s_delete_pod" => vec![
+            "delete".to_string(),
+            "pod".to_string(),
+            name_arg(args, "name")?,
+            ns,
+            "--wait=false".to_string(),
+        ],
+        _ => bail!("unknown tool '{}'", name),
+    })
+}
+
+fn optional_str<'a>(args: &'a Value, key: &str) -> Option<&'a str> {
+    args.get(key)
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+}
+
+/// A Kubernetes object name. Validated so it can never be read as a flag.
+fn optional_name(args: &Value, key: &str) -> Result<Option<String>> {
+    match optional_str(args, key) {
+        None => Ok(None),
+        Some(name) if is_object_name(name) => Ok(Some(name.to_string())),
+        Some(name) => bail!("invalid {} '{}'", key, name),
+    }
+}
+
+fn name_arg(args: &Value, key: &str) -> Result<String> {
+    optional_name(args, key)?.ok_or_else(|| anyhow!("{} is required", key))
+}
+
+/// A resource type such as `pods`, `deploy` or `certificates.cert-manager.io`.
+fn resource_arg(args: &Value, key: &str) -> Result<String> {
+    let resource = optional_str(args, key).ok_or_else(|| anyhow!("{} is required", key))?;
+    let valid = resource.starts_with(|c: char| c.is_ascii_alphanumeric())
+        && resource
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | ','));
+    if !valid {
+        bail!("invalid {} '{}'", key, resource);
+    }
+    Ok(resource.to_lowercase())
+}
+
+fn kind_arg<'a>(args: &Value, allowed: &[&'a str]) -> Result<&'a str> {
+    let kind = optional_str(args, "kind").unwrap_or("deployment");
+    allowed
+        .iter()
+        .find(|k| k.eq_ignore_ascii_case(kind))
+        .copied()
+        .ok_or_else(|| anyhow!("kind must be one of {}", allowed.join(", ")))
+}
+
+fn is_object_name(name: &str) -> bool {
+    name.len() <= 253
+        && name.starts_with(|c: char| c.is_ascii_alphanumeric())
+        && name.ends_with(|c: char| c.is_ascii_alphanumeric())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || matches!(c, '-' | '.'))
+}
+
+fn is_secret(resource: &str) -> bool {
+    resource.split(',').any(|r| {
+        r == "secret" || r == "secrets" || r.starts_with("secret.") || r.starts_with("secrets.")
+    })
+}
+
+fn is_duration(value: &str) -> bool {
+    let digits = value.trim_end_matches(['s', 'm', 'h']);
+    value.len() == digits.len() + 1
+        && !digits.is_empty()
+        && digits.chars().all(|c| c.is_ascii_digit())
+}
+
+fn truncate(mut output: String, max_bytes: usize) -> String {
+    if output.len() <= max_bytes {
+        return output;
+    }
+    let mut end = max_bytes;
+    while !output.is_char_boundary(end) {
+        end -= 1;
+    }
+    output.truncate(end);
+    output.push_str("\n[output truncated]");
+    output
+}
+
+fn definition(name: &str) -> Tool {
+    let namespace =
+        json!({ "type": "string", "description": "Namespace (defaults to the configured one)" });
+    let approval_id = json!({
+        "type": "string",
+        "description": "Id returned by the first call, once the user approved the command"
+    });
+    let (description, properties, required): (&str, Value, Vec<&str>) = match name {
+        "k8s_get" => (
+            "List or get Kubernetes resources in a namespace (kubectl get).",
+            json!({
+                "resource": { "type": "string", "description": "Resource type, e.g. pods, deployments, ingresses" },
+                "name": { "type": "string", "description": "A single object to get" },
+                "namespace": namespace,
+                "label_selector": { "type": "string", "description": "e.g. app=web,tier!=cache" },
+                "output": { "type": "string", "enum": ["wide", "yaml", "json"] }
+            }),
+            vec!["resource"],
+        ),
+        "k8s_describe" => (
+            "Describe a Kubernetes object, including its recent events (kubectl describe).",
+            json!({
+                "resource": { "type": "string" },
+                "name": { "type": "string" },
+                "namespace": namespace
+            }),
+            vec!["resource", "name"],
+        ),
+        "k8s_logs" => (
+            "Fetch the logs of a pod (kubectl logs).",
+            json!({
+                "pod": { "type": "string" },
+                "namespace": namespace,
+                "container": { "type": "string" },
+                "tail_lines": { "type": "integer", "description": "Lines from the end, at most 2000 (default 200)" },
+                "since": { "type": "string", "description": "Only logs newer than this, e.g. 10m" },
+                "previous": { "type": "boolean", "description": "Logs of the previous, crashed container" }
+            }),
+            vec!["pod"],
+        ),
+        "k8s_events" => (
+            "List the events of a namespace, oldest first (kubectl get events).",
+            json!({
+                "namespace": namespace,
+                "object_name": { "type": "string", "description": "Only events about this object" }
+            }),
+            vec![],
+        ),
+        "k8s_scale" => (
+            "Scale a workload. Requires approval: the first call returns the command to approve.",
+            json!({
+                "kind": { "type": "string", "enum": ["deployment", "statefulset", "replicaset"] },
+                "name": { "type": "string" },
+                "replicas": { "type": "integer", "minimum": 0 },
+                "namespace": namespace,
+                "approval_id": approval_id
+            }),
+            vec!["name", "replicas"],
+        ),
+        "k8s_rollout_restart" => (
+            "Restart a workload's pods. Requires approval: the first call returns the command to approve.",
+            json!({
+                "kind": { "type": "string", "enum": ["deployment", "statefulset", "daemonset"] },
+                "name": { "type": "string" },
+                "namespace": namespace,
+                "approval_id": approval_id
+            }),
+            vec!["name"],
+        ),
+        _ => (
+            "Delete a pod so its controller recreates it. Requires approval: the first call returns the command to approve.",
+            json!({
+                "name": { "type": "string" },
+                "namespace": namespace,
+                "approval_id": approval_id
+            }),
+            vec!["name"],
+        ),
+    };
+    Tool {
+        name: name.to_string(),
+        description: Some(description.to_string()),
+        input_schema: json!({
+            "type": "object",
+            "properties": properties,
+            "required": required,
+            "additionalProperties": false
+        }),
+        output_schema: None,
+    }
+}
+
+pub fn build<T: Transport>(t: T, config: K8sMcpConfig) -> Result<Server<T>> {
+    let runner = Arc::new(KubectlCli::new(config.clone()));
+    build_with_runner(t, config, runner)
+}
+
+pub fn build_with_runner<T: Transport>(
+    t: T,
+    config: K8sMcpConfig,
+    runner: Arc<dyn KubectlRunner>,
+) -> Result<Server<T>> {
+    let mut server = Server::builder(t)
+        .capabilities(ServerCapabilities {
+            tools: Some(json!({})),
+            ..Default::default()
+        })
+        .request_handler("resources/list", |_req: ListRequest| {
+            Box::pin(async move {
+                Ok(ResourcesListResponse {
+                    resources: Vec::new(),
+                    next_cursor: None,
+                    meta: None,
+                })
+            })
+        })
+        .request_handler("prompts/list", |_req: ListRequest| {
+            Box::pin(async move {
+                Ok(PromptsListResponse {
+                    prompts: Vec::new(),
+                    next_cursor: None,
+                    meta: None,
+                })
+            })
+        });
+
+    register_tools(&mut server, Arc::new(K8sTools::new(config, runner)));
+
+    Ok(server.build())
+}
+
+fn register_tools<T: Transport>(server: &mut ServerBuilder<T>, tools: Arc<K8sTools>) {
+    for definition in tools.definitions() {
+        let name = definition.name.clone();
+        let tools = tools.clone();
+        server.register_tool(definition, move |req: CallToolRequest| {
+            let name = name.clone();
+            let tools = tools.clone();
+            Box::pin(async move {
+                let args = Value::Object(
+                    req.arguments
+                        .unwrap_or_default()
+                        .into_iter()
+                        .collect::<serde_json::Map<String, Value>>(),
+                );
+                let (text, is_error) = match tools.call(&name, &args).await {
+                    Ok(text) => (text, None),
+                    Err(e) => (e.to_string(), Some(true)),
+                };
+                Ok(CallToolResponse {
+                    content: vec![ToolResponseContent::Text { text }],
+                    is_error,
+                    meta: None,
+                })
+            })
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Default)]
+    struct RecordingRunner {
+        calls: Mutex<Vec<Vec<String>>>,
+    }
+
+    #[async_trait::async_trait]
+    impl KubectlRunner for RecordingRunner {
+        async fn run(&self, args: &[String]) -> Result<String> {
+            self.calls.lock().unwrap().push(args.to_vec());
+            Ok("ok".to_string())
+        }
+    }
+
+    fn tools(config: K8sMcpConfig) -> (K8sTools, Arc<RecordingRunner>) {
+        let runner = Arc::new(RecordingRunner::default());
+        (K8sTools::new(config, runner.clone()), runner)
+    }
+
+    fn last_call(runner: &RecordingRunner) -> Vec<String> {
+        runner.calls.lock().unwrap().last().cloned().unwrap()
+    }
+
+    #[tokio::test]
+    async fn read_tools_build_namespaced_commands() {
+        let (tools, runner) = tools(K8sMcpConfig {
+            namespaces: vec!["shop".to_string()],
+            ..Default::default()
+        });
+
+        tools
+            .call(
+                "k8s_get",
+                &json!({ "resource": "Pods", "label_selector": "app=web" }),
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            last_call(&runner),
+            vec![
+                "get",
+                "pods",
+                "--namespace=shop",
+                "--selector=app=web",
+                "--output=wide"
+            ]
+        );
+
+        tools
+            .call(
+                "k8s_logs",
+                &json!({ "pod": "web-1", "tail_lines": 5000, "since": "10m" }),
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            last_call(&runner),
+            vec![
+                "logs",
+                "web-1",
+                "--namespace=shop",
+                "--tail=2000",
+                "--since=10m"
+            ]
+        );
+
+        let err = tools
+            .call("k8s_events", &json!({ "namespace": "kube-system" }))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("not allowed"));
+    }
+
+    #[tokio::test]
+    async fn arguments_cannot_smuggle_flags_or_read_secrets() {
+        let (tools, runner) = tools(K8sMcpConfig::default());
+
+        for args in [
+            json!({ "resource": "pods", "name": "--kubeconfig=/tmp/x" }),
+            json!({ "resource": "-A" }),
+            json!({ "resource": "secrets" }),
+            json!({ "resource": "pods,secret" }),
+        ] {
+            assert!(tools.call("k8s_get", &args).await.is_err(), "{args}");
+        }
+        assert!(runner.calls.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn mutations_are_off_by_default() {
+        let (tools, runner) = tools(K8sMcpConfig::default());
+        let names: Vec<String> = tools.definitions().into_iter().map(|t| t.name).collect();
+        assert_eq!(names, READ_TOOLS);
+
+        let err = tools
+            .call("k8s_delete_pod", &json!({ "name": "web-1" }))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("unknown tool"));
+        assert!(runner.calls.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn mutations_run_only_with_a_matching_approval() {
+        let (tools, runner) = tools(K8sMcpConfig {
+            allow_mutations: true,
+            ..Default::default()
+        });
+        let args = json!({ "name": "web", "replicas": 3 });
+
+        let first: Value =
+            serde_json::from_str(&tools.call("k8s_scale", &args).await.unwrap()).unwrap();
+        assert_eq!(first["status"], "approval_required");
+        assert_eq!(
+            first["command"],
+            "kubectl scale deployment/web --replicas=3 --namespace=default"
+        );
+        assert!(runner.calls.lock().unwrap().is_empty());
+        let approval_id = first["approval_id"].as_str().unwrap();
+
+        // The id does not approve a different command.
+        let err = tools
+            .call(
+                "k8s_scale",
+                &json!({ "name": "web", "replicas": 30, "approval_id": approval_id }),
+            )
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("different command"));
+
+        let first: Value =
+            serde_json::from_str(&tools.call("k8s_scale", &args).await.unwrap()).unwrap();
+        let approval_id = first["approval_id"].as_str().unwrap();
+        let mut approved = args.clone();
+        approved["approval_id"] = json!(approval_id);
+        tools.call("k8s_scale", &approved).await.unwrap();
+        assert_eq!(
+            last_call(&runner),
+            vec![
+                "scale",
+                "deployment/web",
+                "--replicas=3",
+                "--namespace=default"
+            ]
+        );
+
+        // Approvals are single-use.
+        assert!(tools.call("k8s_scale", &approved).await.is_err());
+        assert_eq!(runner.calls.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn kubeconfig_discovery_order() {
+        let home = Some(PathBuf::from("/home/sre"));
+        let all = |_: &Path| true;
+        let none = |_: &Path| false;
+
+        assert_eq!(
+            discover_from(
+                Some("~/kube/prod"),
+                Some("/env".into()),
+                home.clone(),
+                true,
+                all
+            )
+            .unwrap(),
+            KubeconfigSource::Configured(PathBuf::from("/home/sre/kube/prod"))
+        );
+        assert!(discover_from(Some("/missing"), None, home.clone(), true, none).is_err());
+        assert_eq!(
+            discover_from(None, Some("/a:/b".into()), home.clone(), true, all).unwrap(),
+            KubeconfigSource::Env("/a:/b".into())
+        );
+        assert_eq!(
+            discover_from(None, None, home.clone(), true, all).unwrap(),
+            KubeconfigSource::Home(PathBuf::from("/home/sre/.kube/config"))
+        );
+        assert_eq!(
+            discover_from(None, None, home.clone(), true, none).unwrap(),
+            KubeconfigSource::InCluster
+        );
+        assert!(discover_from(None, None, home, false, none).is_err());
+    }
+}
@@ -10,18 +10,31 @@
 //! Two transports are supported, matching `McpClientTransport`:
 //!   - `StreamableHttp` (single bidirectional HTTP endpoint, MCP 2025-03-26+ spec)
 //!   - `Sse` (legacy Server-Sent-Events transport)
+//!
+//! Stdio servers are only reachable through `mcp_servers` declared in the
+//! server config; see [`connect_stdio`] and `servers::declared`.
+//!
+//! Transport URLs and headers may contain `{{secret:NAME}}` references; the
+//! pool resolves them right before dialing.
 
 use std::collections::HashMap;
 use std::sync::Arc;
 
 use anyhow::{anyhow, Context, Result};
+use distri_types::stores::SecretStore;
 use distri_types::{McpClientTransport, McpServerHandle};
-use rmcp::model::{CallToolRequestParams, ClientCapabilities, ClientInfo, Implementation, Tool};
+use rmcp::model::{
+    CallToolRequestParams, ClientCapabilities, ClientInfo, GetPromptRequestParams, Implementation,
+    ReadResourceRequestParams, Tool,
+};
 use rmcp::service::{RoleClient, RunningService};
 use rmcp::transport::streamable_http_client::StreamableHttpClientTransport;
+use rmcp::transport::TokioChildProcess;
 use rmcp::ServiceExt;
 use tokio::sync::{Mutex, RwLock};
 
+use super::declared::DeclaredMcpServers;
+
 /// Lightweight handle describing one tool from a remote MCP server.
 #[derive(Debug, Clone)]
 pub struct McpToolHandle {
@@ -29,6 +42,44 @@ pub struct McpToolHandle {
     pub name: String,
     pub description: String,
     pub input_schema: serde_json::Value,
+    /// Schema of the tool's `structuredContent`, if the server declares one.
+    pub output_schema: Option<serde_json::Value>,
+}
+
+/// One prompt offered by a remote MCP server.
+#[derive(Debug, Clone)]
+pub struct McpPromptHandle {
+    pub server: String,
+    pub name: String,
+    pub description: String,
+    /// Argument names, paired with whether the argument is required.
+    pub arguments: Vec<(String, bool)>,
+}
+
+/// One resource offered by a remote MCP server.
+#[derive(Debug, Clone)]
+pub struct McpResourceHandle {
+    pub server: String,
+    pub uri: String,
+    pub name: String,
+    pub description: String,
+    pub mime_type: Option<String>,
+}
+
+/// One entry of a `resources/read` response.
+#[derive(Debug, Clone, PartialEq)]
+pub enum McpResourceContent {
+    Text {
+        uri: String,
+        mime_type: Option<String>,
+        text: String,
+    },
+    /// `blob` is base64-encoded.
+    Blob {
+        uri: String,
+        mime_type: Option<String>,
+        blob: String,
+    },
 }
 
 /// A connected MCP client. `Service` is the rmcp `RunningService` future-poller.
@@ -56,6 +107,9 @@ impl RemoteMcpClient {
                 name: t.name.to_string(),
                 description: t.description.map(|d| d.to_string()).unwrap_or_default(),
                 input_schema: serde_json::Value::Object((*t.input_schema).clone()),
+                output_schema: t
+                    .output_schema
+                    .map(|schema| serde_json::Value::Object((*schema).clone())),
             })
             .collect())
     }
@@ -103,6 +157,94 @@ impl RemoteMcpClient {
         })
     }
 
+    /// Whether the server advertised the `prompts` capability.
+    pub fn supports_prompts(&self) -> bool {
+        self.service
+            .peer_info()
+            .is_some_and(|info| info.capabilities.prompts.is_some())
+    }
+
+    /// Whether the server advertised the `resources` capability.
+    pub fn supports_resources(&self) -> bool {
+        self.service
+            .peer_info()
+            .is_some_and(|info| info.capabilities.resources.is_some())
+    }
+
+    /// Fetch the full prompt list from the server.
+    pub async fn list_prompts(&self) -> Result<Vec<McpPromptHandle>> {
+        let result = self
+            .service
+            .list_all_prompts()
+            .await
+            .with_context(|| format!("listing prompts on '{}'", self.server_name))?;
+        Ok(result
+            .into_iter()
+            .map(|p| McpPromptHandle {
+                server: self.server_name.clone(),
+                name: p.name.to_string(),
+                description: p.description.map(|d| d.to_string()).unwrap_or_default(),
+                arguments: p
+                    .arguments
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|a| (a.name.to_string(), a.required.unwrap_or(false)))
+                    .collect(),
+            })
+            .collect())
+    }
+
+    /// Render a prompt with `arguments`. Returns the text of its messages.
+    pub async fn get_prompt(
+        &self,
+        name: &str,
+        arguments: serde_json::Map<String, serde_json::Value>,
+    ) -> Result<String> {
+        let mut params = GetPromptRequestParams::new(name.to_string());
+        if !arguments.is_empty() {
+            params = params.with_arguments(arguments);
+        }
+        let resp = self
+            .service
+            .get_prompt(params)
+            .await
+            .with_context(|| format!("getting prompt '{}/{}'", self.server_name, name))?;
+        Ok(prompt_text(
+            &serde_json::to_value(&resp).unwrap_or(serde_json::Value::Null),
+        ))
+    }
+
+    /// Fetch the full resource list from the server.
+    pub async fn list_resources(&self) -> Result<Vec<McpResourceHandle>> {
+        let result = self
+            .service
+            .list_all_resources()
+            .await
+            .with_context(|| format!("listing resources on '{}'", self.server_name))?;
+        Ok(result
+            .into_iter()
+            .map(|r| McpResourceHandle {
+                server: self.server_name.clone(),
+                uri: r.uri.to_string(),
+                name: r.name.to_string(),
+                description: r.description.clone().unwrap_or_default(),
+                mime_type: r.mime_type.clone(),
+            })
+            .collect())
+    }
+
+    /// Read the resource at `uri`.
+    pub async fn read_resource(&self, uri: &str) -> Result<Vec<McpResourceContent>> {
+        let resp = self
+            .service
+            .read_resource(ReadResourceRequestParams::new(uri.to_string()))
+            .await
+            .with_context(|| format!("reading '{}' on '{}'", uri, self.server_name))?;
+        Ok(resource_contents(
+            &serde_json::to_value(&resp).unwrap_or(serde_json::Value::Null),
+        ))
+    }
+
     /// Gracefully cancel the underlying connection.
     pub async fn shutdown(self) {
         let _ = self.service.cancel().await;
@@ -116,6 +258,59 @@ pub struct McpCallResult {
     pub raw: serde_json::Value,
 }
 
+/// Text of the messages of a raw `prompts/get` response, one paragraph per
+/// text content item. Other content (images, embedded resources) is left
+/// out.
+fn prompt_text(raw: &serde_json::Value) -> String {
+    let mut paragraphs = Vec::new();
+    let messages = raw.get("messages").and_then(|v| v.as_array());
+    for message in messages.into_iter().flatten() {
+        let content = match message.get("content") {
+            Some(serde_json::Value::Array(items)) => items.iter().collect(),
+            Some(item) => vec![item],
+            None => Vec::new(),
+        };
+        for item in content {
+            if item.get("type").and_then(|v| v.as_str()) != Some("text") {
+                continue;
+            }
+            if let Some(text) = item.get("text").and_then(|v| v.as_str()) {
+                paragraphs.push(text.to_string());
+            }
+        }
+    }
+    paragraphs.join("\n\n")
+}
+
+/// Entries of a raw `resources/read` response.
+fn resource_contents(raw: &serde_json::Value) -> Vec<McpResourceContent> {
+    let contents = raw.get("contents").and_then(|v| v.as_array());
+    contents
+        .into_iter()
+        .flatten()
+        .filter_map(|item| {
+            let uri = item.get("uri")?.as_str()?.to_string();
+            let mime_type = item
+                .get("mimeType")
+                .and_then(|v| v.as_str())
+                .map(str::to_string);
+            if let Some(text) = item.get("text").and_then(|v| v.as_str()) {
+                return Some(McpResourceContent::Text {
+                    uri,
+                    mime_type,
+                    text: text.to_string(),
+                });
+            }
+            let blob = item.get("blob")?.as_str()?.to_string();
+            Some(McpResourceContent::Blob {
+                uri,
+                mime_type,
+                blob,
+            })
+        })
+        .collect()
+}
+
 /// Build a client identity advertised to the remote server during initialize.
 fn client_info() -> ClientInfo {
     ClientInfo::new(
@@ -177,6 +372,28 @@ pub async fn connect(handle: &McpServerHandle) -> Result<RemoteMcpClient> {
     })
 }
 
+/// Spawn `command` and speak MCP to it over its stdin/stdout. The child
+/// process is killed when the client is dropped.
+pub async fn connect_stdio(
+    name: &str,
+    command: &str,
+    args: &[String],
+    env: &HashMap<String, String>,
+) -> Result<RemoteMcpClient> {
+    let mut cmd = tokio::process::Command::new(command);
+    cmd.args(args).envs(env);
+    let transport = TokioChildProcess::new(cmd)
+        .with_context(|| format!("spawning '{}' for MCP server '{}'", command, name))?;
+    let service = client_info()
+        .serve(transport)
+        .await
+        .with_context(|| format!("initializing stdio MCP server '{}'", name))?;
+    Ok(RemoteMcpClient {
+        server_name: name.to_string(),
+        service,
+    })
+}
+
 fn merged_headers(
     transport: &McpClientTransport,
     extra: &HashMap<String, String>,
@@ -212,6 +429,11 @@ pub struct McpClientPool {
     handles: HashMap<String, McpServerHandle>,
     clients: RwLock<HashMap<String, Arc<RemoteMcpClient>>>,
     connect_lock: Mutex<()>,
+    /// Where `{{secret:NAME}}` references in handles are looked up.
+    secret_store: Option<Arc<dyn SecretStore>>,
+    /// Declared servers whose connection is shared by every run. Consulted
+    /// for names that have no handle in this pool.
+    shared: Option<DeclaredMcpServers>,
 }
 
 impl McpClientPool {
@@ -225,9 +447,25 @@ impl McpClientPool {
             handles,
             clients: RwLock::new(HashMap::new()),
             connect_lock: Mutex::new(()),
+            secret_store: None,
+            shared: None,
         }
     }
 
+    /// Resolve `{{secret:NAME}}` references in handles from `store`. Without
+    /// a store they are resolved from the environment only.
+    pub fn with_secret_store(mut self, store: Option<Arc<dyn SecretStore>>) -> Self {
+        self.secret_store = store;
+        self
+    }
+
+    /// Fall back to the shared connections of `servers` for names without a
+    /// handle.
+    pub fn with_shared_servers(mut self, servers: DeclaredMcpServers) -> Self {
+        self.shared = Some(servers);
+        self
+    }
+
     pub fn server_names(&self) -> Vec<String> {
         self.handles.keys().cloned().collect()
     }
@@ -236,6 +474,11 @@ impl McpClientPool {
         self.handles.get(name)
     }
 
+    /// Servers with a live connection in this pool.
+    pub async fn connected_servers(&self) -> Vec<String> {
+        self.clients.read().await.keys().cloned().collect()
+    }
+
     /// Connect (or reuse) a named server.
     pub async fn connect_named(&self, name: &str) -> Result<Arc<RemoteMcpClient>> {
         if let Some(client) = self.clients.read().await.get(name).cloned() {
@@ -245,11 +488,14 @@ impl McpClientPool {
         if let Some(client) = self.clients.read().await.get(name).cloned() {
             return Ok(client);
         }
-        let handle = self
-            .handles
-            .get(name)
-            .ok_or_else(|| anyhow!("MCP server '{}' not configured", name))?;
-        let client = Arc::new(connect(handle).await?);
+        let client = match (self.handles.get(name), &self.shared) {
+            (Some(handle), _) => {
+                let handle = self.resolve_secret_refs(handle).await?;
+                Arc::new(connect(&handle).await?)
+            }
+            (None, Some(shared)) if shared.is_shared(name) => shared.client(name).await?,
+            _ => return Err(anyhow!("MCP server '{}' not configured", name)),
+        };
         self.clients
             .write()
             .await
@@ -257,6 +503,21 @@ impl McpClientPool {
         Ok(client)
     }
 
+    /// Copy of `handle` with its secret references replaced by their values.
+    async fn resolve_secret_refs(&self, handle: &McpServerHandle) -> Result<McpServerHandle> {
+        let mut resolved = (handle.transport.clone(), handle.resolved_headers.clone());
+        crate::secrets::SecretResolver::new(self.secret_store.clone())
+            .resolve_secret_refs(&mut resolved)
+            .await
+            .map_err(|e| anyhow!("MCP server '{}': {}", handle.name, e))?;
+        let (transport, resolved_headers) = resolved;
+        Ok(McpServerHandle {
+            transport,
+            resolved_headers,
+            ..handle.clone()
+        })
+    }
+
     /// Enumerate all tools across every configured server. Servers that fail
     /// to connect are logged and skipped — one broken integration shouldn't
     /// take the whole resolver down.
@@ -280,3 +541,74 @@ impl Default for McpClientPool {
         Self::new(Vec::new())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prompt_text_joins_the_text_of_every_message() {
+        let raw = serde_json::json!({
+            "description": "Review code",
+            "messages": [
+                { "role": "user", "content": { "type": "text", "text": "Review the diff." } },
+                { "role": "user", "content": { "type": "image", "data": "AAAA", "mimeType": "image/png" } },
+                { "role": "assistant", "content": [{ "type": "text", "text": "Focus on {{area}}." }] }
+            ]
+        });
+        assert_eq!(prompt_text(&raw), "Review the diff.\n\nFocus on {{area}}.");
+        assert_eq!(prompt_text(&serde_json::Value::Null), "");
+    }
+
+    #[test]
+    fn resource_contents_keep_text_and_blobs() {
+        let raw = serde_json::json!({
+            "contents": [
+                { "uri": "file:///README.md", "mimeType": "text/markdown", "text": "# Readme" },
+                { "uri": "file:///logo.png", "mimeType": "image/png", "blob": "iVBORw0=" },
+                { "mimeType": "text/plain", "text": "no uri" }
+            ]
+        });
+        assert_eq!(
+            resource_contents(&raw),
+            vec![
+                McpResourceContent::Text {
+                    uri: "file:///README.md".to_string(),
+                    mime_type: Some("text/markdown".to_string()),
+                    text: "# Readme".to_string(),
+                },
+                McpResourceContent::Blob {
+                    uri: "file:///logo.png".to_string(),
+                    mime_type: Some("image/png".to_string()),
+                    blob: "iVBORw0=".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn secret_refs_are_resolved_before_connecting() {
+        std::env::set_var("TEST_MCP_REF_TOKEN", "tok");
+        let handle = McpServerHandle {
+            name: "crm".to_string(),
+            transport: McpClientTransport::StreamableHttp {
+                url: "https://mcp.example.com/{{secret:TEST_MCP_REF_TOKEN}}".to_string(),
+                headers: Some(HashMap::from([(
+                    "X-Api-Key".to_string(),
+                    "{{secret:TEST_MCP_REF_TOKEN}}".to_string(),
+                )])),
+            },
+            resolved_headers: HashMap::new(),
+            enabled: true,
+        };
+        let pool = McpClientPool::new(vec![handle.clone()]);
+
+        let resolved = pool.resolve_secret_refs(&handle).await.unwrap();
+        assert_eq!(resolved.transport.url(), "https://mcp.example.com/tok");
+        assert_eq!(resolved.transport.headers().unwrap()["X-Api-Key"], "tok");
+
+        std::env::remove_var("TEST_MCP_REF_TOKEN");
+        let err = pool.connect_named("crm").await.err().unwrap();
+        assert!(err.to_string().contains("TEST_MCP_REF_TOKEN"), "{err}");
+    }
+}
@@ -1,7 +1,17 @@
+pub mod calendar;
+pub mod crawl;
+pub mod declared;
+#[cfg(any(test, feature = "testing"))]
+pub mod fake;
+pub mod k8s;
 pub mod mcp_client;
 pub mod pool_provider;
 pub mod registry;
 pub mod tavily;
 
-pub use mcp_client::{McpClientPool, McpToolHandle, RemoteMcpClient};
+pub use declared::DeclaredMcpServers;
+pub use mcp_client::{
+    McpClientPool, McpPromptHandle, McpResourceContent, McpResourceHandle, McpToolHandle,
+    RemoteMcpClient,
+};
 pub use pool_provider::McpPoolProvider;
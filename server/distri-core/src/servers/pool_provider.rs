@@ -24,6 +24,10 @@ use crate::agent::ExecutorContext;
 /// scoped to a single run — `connect_named` caches one rmcp connection per
 /// server for the lifetime of the pool.
 ///
+/// Handles may reference workspace secrets as `{{secret:NAME}}`; build the
+/// pool with [`McpClientPool::with_secret_store`] so they resolve against the
+/// run's secret store.
+///
 /// Returning `None` is valid (no workspace, no connections, or the host
 /// chooses to opt out for this particular context).
 #[async_trait::async_trait]
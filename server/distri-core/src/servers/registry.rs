@@ -1,11 +1,16 @@
 use crate::{agent::AgentOrchestrator, types::TransportType};
 use anyhow::Result;
+use distri_types::calendar::CalendarMcpConfig;
+use distri_types::crawl::CrawlMcpConfig;
+use distri_types::k8s::K8sMcpConfig;
 use distri_types::McpServerMetadata;
 use distri_types::{ServerMetadataWrapper, ServerTrait};
 use std::collections::HashMap;
+use std::path::Path;
 use std::sync::Arc;
 
-use crate::servers::tavily;
+use crate::servers::{calendar, crawl, k8s, tavily};
+use crate::tools::request::EgressCheck;
 use async_mcp::transport::ServerInMemoryTransport;
 
 // This registry is only really for local running agents using async methos
@@ -31,13 +36,22 @@ impl McpServerRegistry {
     }
 
     pub async fn run(&self, mcp_server: &str, transport: ServerInMemoryTransport) -> Result<()> {
+        self.build(mcp_server, transport)?.listen().await
+    }
+
+    /// Build a registered server on `transport` without listening, so the
+    /// caller can release the registry before serving.
+    pub fn build(
+        &self,
+        mcp_server: &str,
+        transport: ServerInMemoryTransport,
+    ) -> Result<Box<dyn ServerTrait>> {
         match self.servers.get(mcp_server) {
             Some(metadata) => {
                 let builder = metadata.builder.as_ref().ok_or_else(|| {
                     anyhow::anyhow!("Server builder not found for {}", mcp_server)
                 })?;
-                let server = builder(metadata, transport)?;
-                server.listen().await
+                builder(metadata, transport)
             }
             None => Err(anyhow::anyhow!("MCP Server: {} is not found", mcp_server)),
         }
@@ -45,6 +59,7 @@ impl McpServerRegistry {
 }
 
 pub async fn register_tavily_mcp_server(executor: Arc<AgentOrchestrator>) {
+    let egress = EgressCheck::global(&executor);
     executor
         .register_mcp_server(
             "web_search".to_string(),
@@ -54,8 +69,85 @@ pub async fn register_tavily_mcp_server(executor: Arc<AgentOrchestrator>) {
                     mcp_transport: TransportType::InMemory,
                     auth_type: None,
                 },
-                builder: Some(Arc::new(|_, transport| {
-                    let server = tavily::build(transport)?;
+                builder: Some(Arc::new(move |_, transport| {
+                    let server = tavily::build(transport, egress.clone())?;
+                    Ok(Box::new(server) as Box<dyn ServerTrait>)
+                })),
+            },
+        )
+        .await;
+}
+
+/// Register the `k8s` server. Mutating tools are only exposed when
+/// `config.allow_mutations` is set.
+pub async fn register_k8s_mcp_server(executor: Arc<AgentOrchestrator>, config: K8sMcpConfig) {
+    executor
+        .register_mcp_server(
+            "k8s".to_string(),
+            ServerMetadataWrapper {
+                server_metadata: McpServerMetadata {
+                    auth_session_key: None,
+                    mcp_transport: TransportType::InMemory,
+                    auth_type: None,
+                },
+                builder: Some(Arc::new(move |_, transport| {
+                    let server = k8s::build(transport, config.clone())?;
+                    Ok(Box::new(server) as Box<dyn ServerTrait>)
+                })),
+            },
+        )
+        .await;
+}
+
+/// Register the `crawl` server, caching responses under the workspace's
+/// `.distri/cache` unless `config.cache_dir` says otherwise.
+pub async fn register_crawl_mcp_server(
+    executor: Arc<AgentOrchestrator>,
+    config: CrawlMcpConfig,
+    workspace: &Path,
+) {
+    let cache_dir = crawl::cache_dir(&config, workspace);
+    let egress = EgressCheck::global(&executor);
+    executor
+        .register_mcp_server(
+            "crawl".to_string(),
+            ServerMetadataWrapper {
+                server_metadata: McpServerMetadata {
+                    auth_session_key: None,
+                    mcp_transport: TransportType::InMemory,
+                    auth_type: None,
+                },
+                builder: Some(Arc::new(move |_, transport| {
+                    let server =
+                        crawl::build(transport, config.clone(), cache_dir.clone(), egress.clone())?;
+                    Ok(Box::new(server) as Box<dyn ServerTrait>)
+                })),
+            },
+        )
+        .await;
+}
+
+/// Register the `calendar` server, authenticated with the workspace
+/// connection `config.effective_connection()`.
+pub async fn register_calendar_mcp_server(
+    executor: Arc<AgentOrchestrator>,
+    config: CalendarMcpConfig,
+) {
+    let auth: Arc<dyn calendar::CalendarAuth> = Arc::new(calendar::WorkspaceConnectionAuth::new(
+        executor.stores.clone(),
+        config.effective_connection(),
+    ));
+    executor
+        .register_mcp_server(
+            "calendar".to_string(),
+            ServerMetadataWrapper {
+                server_metadata: McpServerMetadata {
+                    auth_session_key: None,
+                    mcp_transport: TransportType::InMemory,
+                    auth_type: None,
+                },
+                builder: Some(Arc::new(move |_, transport| {
+                    let server = calendar::build(transport, config.clone(), auth.clone())?;
                     Ok(Box::new(server) as Box<dyn ServerTrait>)
                 })),
             },
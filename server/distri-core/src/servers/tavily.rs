@@ -1,3 +1,9 @@
+//! `web_search` — an in-memory MCP server searching the web with Tavily.
+//!
+//! Requests to the Tavily API are checked against the global rules of the
+//! server's egress policy; a blocked search returns the `egress_denied`
+//! error.
+
 use anyhow::Result;
 use async_mcp::server::{Server, ServerBuilder};
 use async_mcp::transport::Transport;
@@ -5,12 +11,15 @@ use async_mcp::types::{
     CallToolRequest, CallToolResponse, ListRequest, PromptsListResponse, ResourcesListResponse,
     ServerCapabilities, Tool, ToolResponseContent,
 };
+use distri_types::egress::EgressDenied;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use std::env;
 use tracing::info;
 
+use crate::tools::request::EgressCheck;
+
 const TAVILY_API_URL: &str = "https://api.tavily.com/search";
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -54,7 +63,7 @@ async fn search_tavily(query: &str, api_key: &str) -> Result<Value> {
     Ok(result)
 }
 
-pub fn build<T: Transport>(t: T) -> Result<Server<T>> {
+pub fn build<T: Transport>(t: T, egress: Option<EgressCheck>) -> Result<Server<T>> {
     let mut server = Server::builder(t)
         .capabilities(ServerCapabilities {
             tools: Some(json!({})),
@@ -79,13 +88,16 @@ pub fn build<T: Transport>(t: T) -> Result<Server<T>> {
             })
         });
 
-    register_tools(&mut server)?;
+    register_tools(&mut server, egress)?;
 
     let server = server.build();
     Ok(server)
 }
 
-fn register_tools<T: Transport>(server: &mut ServerBuilder<T>) -> Result<()> {
+fn register_tools<T: Transport>(
+    server: &mut ServerBuilder<T>,
+    egress: Option<EgressCheck>,
+) -> Result<()> {
     // Search Tool
     let search_tool = Tool {
         name: "search".to_string(),
@@ -107,11 +119,15 @@ fn register_tools<T: Transport>(server: &mut ServerBuilder<T>) -> Result<()> {
     };
 
     // Register search tool
-    server.register_tool(search_tool, |req: CallToolRequest| {
+    server.register_tool(search_tool, move |req: CallToolRequest| {
+        let egress = egress.clone();
         Box::pin(async move {
             let args = req.arguments.unwrap_or_default();
 
             let result: Result<CallToolResponse, anyhow::Error> = async {
+                if let Some(egress) = &egress {
+                    egress.check(TAVILY_API_URL)?;
+                }
                 let api_key = env::var("TAVILY_API_KEY")
                     .map_err(|_| anyhow::anyhow!("TAVILY_API_KEY not found in environment"))?;
 
@@ -135,10 +151,12 @@ fn register_tools<T: Transport>(server: &mut ServerBuilder<T>) -> Result<()> {
                 Ok(response) => Ok(response),
                 Err(e) => {
                     info!("Error handling request: {:#?}", e);
+                    let text = match e.downcast_ref::<EgressDenied>() {
+                        Some(denied) => denied.to_json().to_string(),
+                        None => format!("{}", e),
+                    };
                     Ok(CallToolResponse {
-                        content: vec![ToolResponseContent::Text {
-                            text: format!("{}", e),
-                        }],
+                        content: vec![ToolResponseContent::Text { text }],
                         is_error: Some(true),
                         meta: None,
                     })
@@ -177,7 +195,7 @@ mod tests {
             .try_init();
 
         async fn async_server(transport: ServerInMemoryTransport) {
-            let server = build(transport.clone()).unwrap();
+            let server = build(transport.clone(), None).unwrap();
             server.listen().await.unwrap();
         }
 
@@ -0,0 +1,958 @@
+//! Deterministic integration tests for agents.
+//!
+//! Enabled by the `testing` feature (and always for this crate's own tests):
+//!
+//! ```toml
+//! [dev-dependencies]
+//! distri-core = { version = "...", features = ["testing"] }
+//! ```
+//!
+//! [`MockLlmProvider`] answers every model call an orchestrator makes from a
+//! script of canned replies. [`AgentTestHarness`] wires it into an
+//! orchestrator on a fresh in-memory database, and each run comes back as a
+//! [`TestRun`] holding the emitted events, with assertion helpers and a
+//! normalized snapshot that can be checked into the repository.
+//!
+//! ```ignore
+//! let llm = MockLlmProvider::new()
+//!     .respond_tool_call("search", json!({ "query": "rust" }))
+//!     .respond_final("Rust is a systems language.");
+//! let harness = AgentTestHarness::from_builder(
+//!     AgentOrchestratorBuilder::default().with_additional_tools(tools),
+//!     llm.clone(),
+//! )
+//! .await?;
+//! harness.register_agent(researcher_definition()).await?;
+//!
+//! let run = harness.run("researcher", "What is Rust?").await;
+//! run.assert_tool_called("search");
+//! assert_eq!(run.final_text(), Some("Rust is a systems language."));
+//! run.assert_snapshot("tests/snapshots/researcher.json");
+//! llm.assert_exhausted();
+//! ```
+//!
+//! The agent loop expects a tool call on every turn, so a scripted run ends
+//! with [`MockLlmProvider::respond_final`]. A run that outlives its script
+//! fails with an `LLMError` rather than hanging.
+//!
+//! Tools served over MCP can be scripted with
+//! [`crate::servers::fake::FakeMcpServer`].
+
+use std::collections::{HashMap, VecDeque};
+use std::path::Path;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use distri_types::structured_stream::StructuredStream;
+use distri_types::{LlmDefinition, Message, MessageRole, ModelSettings, Part, ToolCall};
+use serde_json::Value;
+
+use crate::agent::{AgentEvent, AgentEventType, ExecutorContext, InvokeResult};
+use crate::llm::{
+    check_structured_delta, finish_structured, LLMExecutorTrait, LLMResponse, LlmExecutorFactory,
+    StreamResult,
+};
+use crate::tools::Tool;
+use crate::types::StandardDefinition;
+use crate::{AgentError, AgentOrchestrator, AgentOrchestratorBuilder};
+
+/// Set to `1` to make [`TestRun::assert_snapshot`] rewrite snapshots instead
+/// of comparing against them.
+pub const UPDATE_SNAPSHOTS_ENV: &str = "DISTRI_UPDATE_SNAPSHOTS";
+
+/// Model name runs are configured with; no provider is ever contacted.
+pub const MOCK_MODEL: &str = "mock";
+
+/// User every run of the harness is made as.
+pub const TEST_USER_ID: &str = "test-user";
+const EVENT_BUFFER: usize = 10_000;
+
+/// One scripted model turn.
+#[derive(Debug, Clone)]
+pub enum MockResponse {
+    /// Assistant text plus the tool calls the model makes this turn.
+    Reply {
+        content: String,
+        tool_calls: Vec<ToolCall>,
+    },
+    /// Text cut off at the output budget (finish reason `length`).
+    Truncated(String),
+    /// The call fails with [`AgentError::LLMError`].
+    Error(String),
+}
+
+/// A model call as the mock received it.
+#[derive(Debug, Clone)]
+pub struct RecordedRequest {
+    pub agent_id: String,
+    pub messages: Vec<Message>,
+    /// Names of the tools offered to the model on this call.
+    pub tool_names: Vec<String>,
+}
+
+#[derive(Debug, Default)]
+struct MockState {
+    script: VecDeque<MockResponse>,
+    requests: Vec<RecordedRequest>,
+    next_tool_call_id: usize,
+}
+
+/// Scripted stand-in for every LLM provider. Replies are consumed in order,
+/// whichever agent or call site asks. Clones share the script and the
+/// recorded requests, so keep one to inspect after handing another to the
+/// harness.
+#[derive(Debug, Clone, Default)]
+pub struct MockLlmProvider {
+    state: Arc<Mutex<MockState>>,
+}
+
+impl MockLlmProvider {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reply with text only. The agent loop re-prompts on a turn without
+    /// tool calls, so this mostly suits direct calls such as titling.
+    pub fn respond_text(self, content: impl Into<String>) -> Self {
+        self.respond(MockResponse::Reply {
+            content: content.into(),
+            tool_calls: vec![],
+        })
+    }
+
+    /// Call one tool. Tool call ids are `call_1`, `call_2`, ... in script
+    /// order, so snapshots stay stable.
+    pub fn respond_tool_call(self, tool_name: impl Into<String>, input: Value) -> Self {
+        self.respond_tool_calls(vec![(tool_name.into(), input)])
+    }
+
+    /// Call several tools in the same turn.
+    pub fn respond_tool_calls(self, calls: Vec<(String, Value)>) -> Self {
+        let tool_calls = {
+            let mut state = self.lock();
+            calls
+                .into_iter()
+                .map(|(tool_name, input)| {
+                    state.next_tool_call_id += 1;
+                    ToolCall {
+                        tool_call_id: format!("call_{}", state.next_tool_call_id),
+                        tool_name,
+                        input,
+                    }
+                })
+                .collect()
+        };
+        self.respond(MockResponse::Reply {
+            content: String::new(),
+            tool_calls,
+        })
+    }
+
+    /// End the run with `answer` through the `final` tool.
+    pub fn respond_final(self, answer: impl Into<String>) -> Self {
+        self.respond_tool_call("final", Value::String(answer.into()))
+    }
+
+    /// Reply with text that stops at the output budget, as a provider does
+    /// when `max_tokens` is reached.
+    pub fn respond_truncated(self, content: impl Into<String>) -> Self {
+        self.respond(MockResponse::Truncated(content.into()))
+    }
+
+    /// Fail the next call.
+    pub fn respond_error(self, message: impl Into<String>) -> Self {
+        self.respond(MockResponse::Error(message.into()))
+    }
+
+    pub fn respond(self, response: MockResponse) -> Self {
+        self.lock().script.push_back(response);
+        self
+    }
+
+    /// Every call received so far, oldest first.
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        self.lock().requests.clone()
+    }
+
+    /// Scripted replies not consumed yet.
+    pub fn remaining(&self) -> usize {
+        self.lock().script.len()
+    }
+
+    /// Panic if part of the script was never used.
+    pub fn assert_exhausted(&self) {
+        let remaining = self.remaining();
+        assert!(
+            remaining == 0,
+            "{} scripted LLM response(s) were never requested",
+            remaining
+        );
+    }
+
+    fn lock(&self) -> MutexGuard<'_, MockState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn next_response(&self, request: RecordedRequest) -> Result<LLMResponse, AgentError> {
+        let mut state = self.lock();
+        state.requests.push(request);
+        match state.script.pop_front() {
+            Some(MockResponse::Reply {
+                content,
+                tool_calls,
+            }) => Ok(LLMResponse {
+                finish_reason: if tool_calls.is_empty() {
+                    async_openai::types::chat::FinishReason::Stop
+                } else {
+                    async_openai::types::chat::FinishReason::ToolCalls
+                },
+                tool_calls,
+                content,
+                usage: None,
+            }),
+            Some(MockResponse::Truncated(content)) => Ok(LLMResponse {
+                finish_reason: async_openai::types::chat::FinishReason::Length,
+                tool_calls: vec![],
+                content,
+                usage: None,
+            }),
+            Some(MockResponse::Error(message)) => Err(AgentError::LLMError(message)),
+            None => Err(AgentError::LLMError(format!(
+                "MockLlmProvider script exhausted at call {}",
+                state.requests.len()
+            ))),
+        }
+    }
+}
+
+impl LlmExecutorFactory for MockLlmProvider {
+    fn create_executor(
+        &self,
+        llm_def: &LlmDefinition,
+        tools: &[Arc<dyn Tool>],
+        context: Arc<ExecutorContext>,
+    ) -> Result<Box<dyn LLMExecutorTrait>, AgentError> {
+        Ok(Box::new(MockLlmExecutor {
+            provider: self.clone(),
+            tool_names: tools.iter().map(|t| t.get_name()).collect(),
+            response_format: llm_def
+                .model_settings
+                .as_ref()
+                .and_then(|ms| ms.inner.response_format.clone()),
+            context,
+        }))
+    }
+}
+
+#[derive(Debug)]
+struct MockLlmExecutor {
+    provider: MockLlmProvider,
+    tool_names: Vec<String>,
+    /// Validated while streaming, as provider executors do.
+    response_format: Option<Value>,
+    context: Arc<ExecutorContext>,
+}
+
+impl MockLlmExecutor {
+    /// Handle a reply the way provider executors do: stream it as text events
+    /// and persist the assistant message with its tool calls.
+    async fn reply(
+        &self,
+        messages: &[Message],
+        context: &ExecutorContext,
+        stream: bool,
+    ) -> Result<LLMResponse, AgentError> {
+        let response = self.provider.next_response(RecordedRequest {
+            agent_id: context.agent_id.clone(),
+            messages: messages.to_vec(),
+            tool_names: self.tool_names.clone(),
+        })?;
+
+        let message_id = uuid::Uuid::new_v4().to_string();
+        let step_id = context.get_current_step_id().await.unwrap_or_default();
+        context
+            .emit(AgentEventType::TextMessageStart {
+                message_id: message_id.clone(),
+                step_id: step_id.clone(),
+                role: MessageRole::Assistant,
+                is_final: None,
+            })
+            .await;
+        if !response.content.is_empty() {
+            context
+                .emit(AgentEventType::TextMessageContent {
+                    message_id: message_id.clone(),
+                    step_id: step_id.clone(),
+                    delta: response.content.clone(),
+                    stripped_content: None,
+                })
+                .await;
+        }
+        let mut structured = self
+            .response_format
+            .as_ref()
+            .filter(|_| stream)
+            .and_then(StructuredStream::from_response_format);
+        let checked = match structured.as_mut() {
+            Some(structured) => {
+                check_structured_delta(
+                    context,
+                    structured,
+                    &message_id,
+                    &step_id,
+                    &response.content,
+                )
+                .await
+            }
+            None => Ok(()),
+        };
+        context
+            .emit(AgentEventType::TextMessageEnd {
+                message_id,
+                step_id,
+            })
+            .await;
+        checked?;
+        if let Some(structured) = &structured {
+            if response.tool_calls.is_empty() {
+                finish_structured(structured)?;
+            }
+        }
+
+        let mut assistant_msg = Message::assistant(response.content.clone(), None);
+        assistant_msg.agent_id = Some(context.agent_id.clone());
+        for tool_call in &response.tool_calls {
+            assistant_msg.parts.push(Part::ToolCall(tool_call.clone()));
+        }
+        context.save_message(&assistant_msg).await;
+        context
+            .set_current_message_id(Some(assistant_msg.id.clone()))
+            .await;
+        Ok(response)
+    }
+}
+
+#[async_trait::async_trait]
+impl LLMExecutorTrait for MockLlmExecutor {
+    async fn execute(&self, messages: &[Message]) -> Result<LLMResponse, AgentError> {
+        self.reply(messages, &self.context, false).await
+    }
+
+    async fn execute_stream(
+        &self,
+        messages: &[Message],
+        context: Arc<ExecutorContext>,
+    ) -> Result<StreamResult, AgentError> {
+        let response = self.reply(messages, &context, true).await?;
+        Ok(StreamResult {
+            finish_reason: response.finish_reason,
+            tool_calls: response.tool_calls,
+            content: response.content,
+            usage: response.usage,
+            first_token_at: Some(std::time::Instant::now()),
+        })
+    }
+}
+
+/// An orchestrator on a fresh in-memory database whose LLM calls are all
+/// answered by a [`MockLlmProvider`].
+pub struct AgentTestHarness {
+    pub orchestrator: Arc<AgentOrchestrator>,
+    pub llm: MockLlmProvider,
+}
+
+impl AgentTestHarness {
+    pub async fn new(llm: MockLlmProvider) -> anyhow::Result<Self> {
+        Self::from_builder(AgentOrchestratorBuilder::default(), llm).await
+    }
+
+    /// Build on a preconfigured builder, e.g. one with extra tools or hooks.
+    /// Its store configuration is replaced by an in-memory database.
+    pub async fn from_builder(
+        builder: AgentOrchestratorBuilder,
+        llm: MockLlmProvider,
+    ) -> anyhow::Result<Self> {
+        let orchestrator = builder
+            .with_store_config(in_memory_store_config())
+            .with_llm_executor_factory(Arc::new(llm.clone()))
+            .build()
+            .await?;
+        Ok(Self {
+            orchestrator: Arc::new(orchestrator),
+            llm,
+        })
+    }
+
+    pub async fn register_agent(&self, definition: StandardDefinition) -> anyhow::Result<()> {
+        self.orchestrator
+            .register_agent_definition(definition)
+            .await
+    }
+
+    /// Send `text` to `agent` on a new thread.
+    pub async fn run(&self, agent: &str, text: &str) -> TestRun {
+        let thread_id = uuid::Uuid::new_v4().to_string();
+        self.run_on_thread(agent, &thread_id, text).await
+    }
+
+    /// Send `text` to `agent` on `thread_id`, continuing its history.
+    pub async fn run_on_thread(&self, agent: &str, thread_id: &str, text: &str) -> TestRun {
+        self.execute(agent, thread_id, text, false).await
+    }
+
+    /// Send `text` to `agent` on a new thread as a dry run (see
+    /// [`distri_types::dry_run`]).
+    pub async fn dry_run(&self, agent: &str, text: &str) -> TestRun {
+        let thread_id = uuid::Uuid::new_v4().to_string();
+        self.execute(agent, &thread_id, text, true).await
+    }
+
+    async fn execute(&self, agent: &str, thread_id: &str, text: &str, dry_run: bool) -> TestRun {
+        let (tx, mut rx) = tokio::sync::mpsc::channel(EVENT_BUFFER);
+        let ctx = ExecutorContext {
+            agent_id: agent.to_string(),
+            thread_id: thread_id.to_string(),
+            user_id: TEST_USER_ID.to_string(),
+            orchestrator: Some(self.orchestrator.clone()),
+            event_tx: Some(Arc::new(tx)),
+            dry_run,
+            default_model_settings: Some(ModelSettings {
+                model: MOCK_MODEL.to_string(),
+                inner: Default::default(),
+            }),
+            ..Default::default()
+        };
+
+        let run = self.orchestrator.execute_stream(
+            agent,
+            Message::user(text.to_string(), None),
+            Arc::new(ctx),
+            None,
+        );
+        tokio::pin!(run);
+        let mut events = Vec::new();
+        let result = loop {
+            tokio::select! {
+                result = &mut run => break result,
+                Some(event) = rx.recv() => events.push(event),
+            }
+        };
+        while let Ok(event) = rx.try_recv() {
+            events.push(event);
+        }
+
+        TestRun {
+            thread_id: thread_id.to_string(),
+            result,
+            events,
+        }
+    }
+}
+
+/// Store configuration for a private in-memory SQLite database. Runs keep
+/// their threads, tasks and sessions in it too, so they can be inspected
+/// through the orchestrator's stores.
+pub fn in_memory_store_config() -> distri_types::configuration::StoreConfig {
+    use distri_types::configuration::{
+        DbConnectionConfig, MetadataStoreConfig, SessionStoreConfig, StoreConfig,
+    };
+    let db_config = DbConnectionConfig {
+        database_url: format!("file:{}?mode=memory&cache=shared", uuid::Uuid::new_v4()),
+        ..Default::default()
+    };
+    StoreConfig {
+        metadata: MetadataStoreConfig {
+            db_config: Some(db_config.clone()),
+            ..Default::default()
+        },
+        session: SessionStoreConfig {
+            ephemeral: false,
+            db_config: Some(db_config),
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+}
+
+/// The outcome of one harness run.
+#[derive(Debug)]
+pub struct TestRun {
+    pub thread_id: String,
+    pub result: Result<InvokeResult, AgentError>,
+    /// Everything the run emitted, in order, sub-agents included.
+    pub events: Vec<AgentEvent>,
+}
+
+impl TestRun {
+    /// The final answer, when the run succeeded with one.
+    pub fn final_text(&self) -> Option<&str> {
+        self.result.as_ref().ok()?.content.as_deref()
+    }
+
+    /// Tool calls the model made, in order.
+    pub fn tool_calls(&self) -> Vec<&ToolCall> {
+        self.events
+            .iter()
+            .filter_map(|e| match &e.event {
+                AgentEventType::ToolCalls { tool_calls, .. } => Some(tool_calls),
+                _ => None,
+            })
+            .flatten()
+            .collect()
+    }
+
+    /// The event `type` tags, in order.
+    pub fn event_types(&self) -> Vec<String> {
+        self.events
+            .iter()
+            .filter_map(|e| {
+                serde_json::to_value(&e.event)
+                    .ok()?
+                    .get("type")?
+                    .as_str()
+                    .map(str::to_string)
+            })
+            .collect()
+    }
+
+    /// Panic unless the run finished without error.
+    pub fn assert_success(&self) -> &InvokeResult {
+        match &self.result {
+            Ok(result) => result,
+            Err(e) => panic!("run failed: {}", e),
+        }
+    }
+
+    /// The first call of `tool_name`. Panics with the tools that were called
+    /// when there is none.
+    pub fn assert_tool_called(&self, tool_name: &str) -> &ToolCall {
+        let calls = self.tool_calls();
+        match calls.iter().find(|c| c.tool_name == tool_name) {
+            Some(call) => call,
+            None => panic!(
+                "expected a call to '{}', got {:?}",
+                tool_name,
+                calls.iter().map(|c| &c.tool_name).collect::<Vec<_>>()
+            ),
+        }
+    }
+
+    pub fn assert_tool_not_called(&self, tool_name: &str) {
+        assert!(
+            !self.tool_calls().iter().any(|c| c.tool_name == tool_name),
+            "expected no call to '{}'",
+            tool_name
+        );
+    }
+
+    /// The event payloads with everything that varies between identical runs
+    /// taken out: timestamps, usage and context budgets are dropped, and ids
+    /// become `<id-1>`, `<id-2>`, ... in order of first appearance.
+    pub fn snapshot(&self) -> Value {
+        let mut ids = HashMap::new();
+        Value::Array(
+            self.events
+                .iter()
+                .map(|e| {
+                    let mut value = serde_json::to_value(&e.event).unwrap_or(Value::Null);
+                    normalize(&mut value, &mut ids);
+                    value
+                })
+                .collect(),
+        )
+    }
+
+    /// Compare [`Self::snapshot`] with the JSON file at `path`. A missing
+    /// file is written instead, as is any file when [`UPDATE_SNAPSHOTS_ENV`]
+    /// is `1`.
+    pub fn assert_snapshot(&self, path: impl AsRef<Path>) {
+        let path = path.as_ref();
+        let actual = format!(
+            "{}\n",
+            serde_json::to_string_pretty(&self.snapshot()).expect("snapshot serializes")
+        );
+        let update = std::env::var(UPDATE_SNAPSHOTS_ENV).is_ok_and(|v| v == "1");
+        match std::fs::read_to_string(path) {
+            Ok(expected) if !update => assert!(
+                expected == actual,
+                "snapshot {} does not match; rerun with {}=1 to accept.\n--- expected\n{}\n+++ actual\n{}",
+                path.display(),
+                UPDATE_SNAPSHOTS_ENV,
+                expected,
+                actual
+            ),
+            _ => {
+                if let Some(parent) = path.parent() {
+                    std::fs::create_dir_all(parent).expect("create snapshot directory");
+                }
+                std::fs::write(path, actual).expect("write snapshot");
+            }
+        }
+    }
+}
+
+fn normalize(value: &mut Value, ids: &mut HashMap<String, String>) {
+    match value {
+        Value::Object(map) => {
+            map.retain(|key, _| !matches!(key.as_str(), "timestamp" | "usage" | "context_budget"));
+            for (key, v) in map.iter_mut() {
+                if key == "id" || key.ends_with("_id") {
+                    if let Value::String(s) = v {
+                        let next = ids.len() + 1;
+                        *s = ids
+                            .entry(s.clone())
+                            .or_insert_with(|| format!("<id-{}>", next))
+                            .clone();
+                        continue;
+                    }
+                }
+                normalize(v, ids);
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(|v| normalize(v, ids)),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use distri_types::ToolContext;
+
+    fn agent(name: &str) -> StandardDefinition {
+        StandardDefinition {
+            name: name.to_string(),
+            description: "testing harness agent".to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[derive(Debug)]
+    struct LookupTool;
+
+    #[async_trait::async_trait]
+    impl Tool for LookupTool {
+        fn get_name(&self) -> String {
+            "lookup".to_string()
+        }
+
+        fn get_description(&self) -> String {
+            "Look up a topic".to_string()
+        }
+
+        fn get_parameters(&self) -> Value {
+            serde_json::json!({
+                "type": "object",
+                "properties": { "topic": { "type": "string" } }
+            })
+        }
+
+        async fn execute(
+            &self,
+            _tool_call: ToolCall,
+            _context: Arc<ToolContext>,
+        ) -> Result<Vec<Part>, anyhow::Error> {
+            Ok(vec![Part::Text("Rust is a systems language.".to_string())])
+        }
+    }
+
+    #[tokio::test]
+    async fn scripted_run_calls_tools_and_finishes() {
+        let llm = MockLlmProvider::new()
+            .respond_tool_call("lookup", serde_json::json!({ "topic": "rust" }))
+            .respond_final("Rust is a systems language.");
+        let harness = AgentTestHarness::new(llm.clone()).await.unwrap();
+        harness.register_agent(agent("researcher")).await.unwrap();
+        harness
+            .orchestrator
+            .register_tool("researcher", Arc::new(LookupTool))
+            .await;
+
+        let run = harness.run("researcher", "What is Rust?").await;
+
+        run.assert_success();
+        assert_eq!(run.final_text(), Some("Rust is a systems language."));
+        assert_eq!(run.assert_tool_called("lookup").tool_call_id, "call_1");
+        run.assert_tool_not_called("search");
+        llm.assert_exhausted();
+        let requests = llm.requests();
+        assert_eq!(requests.len(), 2);
+        assert!(requests[0].tool_names.iter().any(|t| t == "lookup"));
+        assert!(requests.iter().all(|r| r.agent_id == "researcher"));
+    }
+
+    #[tokio::test]
+    async fn exhausted_script_fails_the_run() {
+        let harness = AgentTestHarness::new(MockLlmProvider::new()).await.unwrap();
+        harness.register_agent(agent("silent")).await.unwrap();
+
+        let run = harness.run("silent", "hello").await;
+
+        assert!(run.result.is_err());
+        assert_eq!(run.final_text(), None);
+    }
+
+    fn truncated_events(run: &TestRun) -> Vec<(u32, bool)> {
+        run.events
+            .iter()
+            .filter_map(|e| match &e.event {
+                AgentEventType::Truncated {
+                    continuation,
+                    continuing,
+                    ..
+                } => Some((*continuation, *continuing)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn truncated_reply_is_resumed_with_auto_continue() {
+        let llm = MockLlmProvider::new()
+            .respond_truncated("Rust is a systems")
+            .respond_final("Rust is a systems language.");
+        let harness = AgentTestHarness::new(llm.clone()).await.unwrap();
+        harness
+            .register_agent(StandardDefinition {
+                max_output_tokens: Some(5),
+                auto_continue: Some(1),
+                ..agent("rambler")
+            })
+            .await
+            .unwrap();
+
+        let run = harness.run("rambler", "What is Rust?").await;
+
+        run.assert_success();
+        assert_eq!(run.final_text(), Some("Rust is a systems language."));
+        assert_eq!(truncated_events(&run), vec![(0, true)]);
+        llm.assert_exhausted();
+        let resumed = llm.requests()[1].messages.last().cloned().unwrap();
+        assert!(resumed.as_text().unwrap_or_default().contains("Continue"));
+    }
+
+    #[tokio::test]
+    async fn truncated_reply_without_auto_continue_becomes_the_answer() {
+        let llm = MockLlmProvider::new().respond_truncated("Rust is a systems");
+        let harness = AgentTestHarness::new(llm.clone()).await.unwrap();
+        harness
+            .register_agent(StandardDefinition {
+                max_output_tokens: Some(5),
+                ..agent("rambler")
+            })
+            .await
+            .unwrap();
+
+        let run = harness.run("rambler", "What is Rust?").await;
+
+        assert_eq!(run.final_text(), Some("Rust is a systems"));
+        assert_eq!(truncated_events(&run), vec![(0, false)]);
+        llm.assert_exhausted();
+    }
+
+    #[derive(Debug)]
+    struct CrmTool;
+
+    #[async_trait::async_trait]
+    impl Tool for CrmTool {
+        fn get_name(&self) -> String {
+            "crm_query".to_string()
+        }
+
+        fn get_description(&self) -> String {
+            "Query the CRM".to_string()
+        }
+
+        fn get_parameters(&self) -> Value {
+            serde_json::json!({ "type": "object" })
+        }
+
+        fn get_plugin_name(&self) -> Option<String> {
+            Some("crm".to_string())
+        }
+
+        async fn execute(
+            &self,
+            _tool_call: ToolCall,
+            context: Arc<ToolContext>,
+        ) -> Result<Vec<Part>, anyhow::Error> {
+            let mut span = context.span("query");
+            span.set_attribute("table", "accounts");
+            span.child("parse").fail("bad row");
+            span.log("2 rows");
+            span.end();
+            Ok(vec![Part::Text("2 accounts".to_string())])
+        }
+    }
+
+    #[tokio::test]
+    async fn plugin_spans_are_emitted_before_the_tool_ends() {
+        let llm = MockLlmProvider::new()
+            .respond_tool_call("crm_query", serde_json::json!({}))
+            .respond_final("2 accounts");
+        let harness = AgentTestHarness::new(llm).await.unwrap();
+        harness.register_agent(agent("sales")).await.unwrap();
+        harness
+            .orchestrator
+            .register_tool("sales", Arc::new(CrmTool))
+            .await;
+
+        let run = harness.run("sales", "How many accounts?").await;
+
+        run.assert_success();
+        let types = run.event_types();
+        let spans_at = types.iter().position(|t| t == "plugin_spans").unwrap();
+        let end_at = types
+            .iter()
+            .position(|t| t == "tool_execution_end")
+            .unwrap();
+        assert!(spans_at < end_at);
+
+        let (plugin, spans) = run
+            .events
+            .iter()
+            .find_map(|e| match &e.event {
+                AgentEventType::PluginSpans { plugin, spans, .. } => Some((plugin, spans)),
+                _ => None,
+            })
+            .unwrap();
+        assert_eq!(plugin.as_deref(), Some("crm"));
+        let names: Vec<&str> = spans.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["query", "parse"]);
+        assert_eq!(
+            spans[1].parent_span_id.as_deref(),
+            Some(spans[0].span_id.as_str())
+        );
+        assert_eq!(spans[1].error.as_deref(), Some("bad row"));
+        assert_eq!(spans[0].attributes["table"], "accounts");
+        assert_eq!(spans[0].logs[0].message, "2 rows");
+    }
+
+    #[derive(Debug, Default)]
+    struct SearchTool {
+        calls: std::sync::atomic::AtomicUsize,
+    }
+
+    #[async_trait::async_trait]
+    impl Tool for SearchTool {
+        fn get_name(&self) -> String {
+            "web_search".to_string()
+        }
+
+        fn get_description(&self) -> String {
+            "Search the web".to_string()
+        }
+
+        fn get_parameters(&self) -> Value {
+            serde_json::json!({ "type": "object" })
+        }
+
+        fn memoizable(&self) -> bool {
+            true
+        }
+
+        async fn execute(
+            &self,
+            _tool_call: ToolCall,
+            _context: Arc<ToolContext>,
+        ) -> Result<Vec<Part>, anyhow::Error> {
+            self.calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Ok(vec![Part::Text("3 results".to_string())])
+        }
+    }
+
+    fn tool_result_parts(run: &TestRun) -> Vec<Vec<Part>> {
+        run.events
+            .iter()
+            .filter_map(|e| match &e.event {
+                AgentEventType::ToolResults { results, .. } => Some(results),
+                _ => None,
+            })
+            .flatten()
+            .map(|r| r.parts.clone())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn repeated_identical_tool_calls_are_served_from_the_task_cache() {
+        let llm = MockLlmProvider::new()
+            .respond_tool_call("web_search", serde_json::json!({ "q": "rust", "n": 3 }))
+            .respond_tool_call("web_search", serde_json::json!({ "n": 3, "q": "rust" }))
+            .respond_final("3 results");
+        let harness = AgentTestHarness::new(llm.clone()).await.unwrap();
+        harness.register_agent(agent("researcher")).await.unwrap();
+        let search = Arc::new(SearchTool::default());
+        harness
+            .orchestrator
+            .register_tool("researcher", search.clone())
+            .await;
+
+        let run = harness.run("researcher", "Search for rust").await;
+
+        run.assert_success();
+        llm.assert_exhausted();
+        assert_eq!(search.calls.load(std::sync::atomic::Ordering::SeqCst), 1);
+        let results = tool_result_parts(&run);
+        assert_eq!(results[0], vec![Part::Text("3 results".to_string())]);
+        assert_eq!(
+            results[1],
+            vec![
+                Part::Text(distri_types::TOOL_CALL_CACHED_MARKER.to_string()),
+                Part::Text("3 results".to_string()),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn memoization_can_be_turned_off_per_agent() {
+        let llm = MockLlmProvider::new()
+            .respond_tool_call("web_search", serde_json::json!({ "q": "rust" }))
+            .respond_tool_call("web_search", serde_json::json!({ "q": "rust" }))
+            .respond_final("3 results");
+        let harness = AgentTestHarness::new(llm.clone()).await.unwrap();
+        harness
+            .register_agent(StandardDefinition {
+                tools: Some(distri_types::ToolsConfig {
+                    memoize: distri_types::ToolMemoizeConfig {
+                        exclude: vec!["web_search".to_string()],
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                }),
+                ..agent("researcher")
+            })
+            .await
+            .unwrap();
+        let search = Arc::new(SearchTool::default());
+        harness
+            .orchestrator
+            .register_tool("researcher", search.clone())
+            .await;
+
+        let run = harness.run("researcher", "Search for rust").await;
+
+        run.assert_success();
+        assert_eq!(search.calls.load(std::sync::atomic::Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn snapshot_replaces_ids_in_order_of_appearance() {
+        let mut ids = HashMap::new();
+        let mut value = serde_json::json!({
+            "type": "tool_calls",
+            "step_id": "9f1c",
+            "timestamp": "2026-01-01T00:00:00Z",
+            "tool_calls": [{ "tool_call_id": "abc", "tool_name": "final" }],
+            "parent_message_id": "9f1c",
+        });
+        normalize(&mut value, &mut ids);
+        assert_eq!(
+            value,
+            serde_json::json!({
+                "type": "tool_calls",
+                "step_id": "<id-1>",
+                "tool_calls": [{ "tool_call_id": "<id-2>", "tool_name": "final" }],
+                "parent_message_id": "<id-1>",
+            })
+        );
+    }
+}
@@ -489,6 +489,29 @@ async fn build_final_message_returns_none_when_no_final_result() {
     );
 }
 
+#[tokio::test]
+async fn build_final_message_reports_truncation_in_metadata() {
+    use crate::agent::ExecutorContext;
+
+    let ctx = ExecutorContext::default();
+    ctx.set_final_result(Some(json!("a long answer that")))
+        .await;
+    let msg = crate::a2a::service::build_final_message(&ctx)
+        .await
+        .unwrap();
+    assert_eq!(msg.metadata, None, "untruncated output carries no metadata");
+
+    ctx.record_truncation(true, true).await;
+    ctx.record_truncation(true, false).await;
+    let msg = crate::a2a::service::build_final_message(&ctx)
+        .await
+        .unwrap();
+    assert_eq!(
+        msg.metadata,
+        Some(json!({ "truncated": true, "continuations": 1 }))
+    );
+}
+
 // ── Sanity check on SseMessage frame builders ───────────────────────────────
 
 #[tokio::test]
@@ -510,3 +533,95 @@ async fn sse_message_success_and_error_frames_parse() {
         Some(-32602)
     );
 }
+
+// ── Background job queue (non-blocking message/send) ────────────────────────
+
+async fn build_service_with_background_jobs() -> Arc<A2AService> {
+    let orchestrator = Arc::new(
+        AgentOrchestratorBuilder::default()
+            .with_store_config(test_store_config())
+            .with_background_jobs(Some(distri_types::jobs::BackgroundJobsConfig {
+                poll_interval_ms: 20,
+                ..Default::default()
+            }))
+            .build()
+            .await
+            .unwrap(),
+    );
+    Arc::new(A2AService::new(orchestrator))
+}
+
+fn non_blocking_send(message_id: &str) -> ServiceRequest {
+    make_service_request(
+        "message/send",
+        json!({
+            "message": {
+                "kind": "message",
+                "messageId": message_id,
+                "role": "user",
+                "parts": [{ "kind": "text", "text": "summarize the backlog" }],
+            },
+            "configuration": { "acceptedOutputModes": [], "blocking": false },
+        }),
+    )
+}
+
+fn response_task(
+    resp: Either<crate::a2a::service::BoxedSseStream, distri_a2a::JsonRpcResponse>,
+) -> distri_a2a::Task {
+    match resp {
+        Either::Right(r) => serde_json::from_value(r.result.expect("task result")).unwrap(),
+        Either::Left(_) => panic!("message/send must answer with a JSON-RPC response"),
+    }
+}
+
+#[tokio::test]
+async fn non_blocking_send_enqueues_idempotently() {
+    let service = build_service_with_background_jobs().await;
+
+    let task = response_task(service.handle(non_blocking_send("m-queued")).await);
+    assert!(matches!(task.status.state, TaskState::Submitted));
+    let again = response_task(service.handle(non_blocking_send("m-queued")).await);
+    assert_eq!(task.id, again.id, "same messageId must not enqueue twice");
+
+    let fetched = service.get_task(json!({ "id": task.id })).await.unwrap();
+    assert!(matches!(fetched.status.state, TaskState::Submitted));
+    assert_eq!(fetched.context_id, task.context_id);
+
+    let canceled = service
+        .cancel_task(json!({ "id": task.id }))
+        .await
+        .expect("queued job cancels without a run");
+    assert!(matches!(canceled.status.state, TaskState::Canceled));
+}
+
+#[tokio::test]
+async fn background_runner_claims_and_finishes_jobs() {
+    let service = build_service_with_background_jobs().await;
+    let orchestrator = service.orchestrator.clone();
+    let store = orchestrator.stores.background_job_store.clone().unwrap();
+
+    // No agent is registered, so the run fails — what matters is that a
+    // worker picks the job up and records the outcome.
+    let task = response_task(service.handle(non_blocking_send("m-run")).await);
+    let handles = crate::worker::BackgroundRunner::new(orchestrator.clone())
+        .expect("runner is configured")
+        .start();
+
+    let mut job = None;
+    for _ in 0..200 {
+        let current = store.get_by_task_id(&task.id).await.unwrap().unwrap();
+        if current.status.is_terminal() {
+            job = Some(current);
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(25)).await;
+    }
+    for handle in handles {
+        handle.abort();
+    }
+    let job = job.expect("job reaches a terminal state");
+    assert_eq!(job.attempts, 1);
+    assert_eq!(job.status, distri_types::jobs::BackgroundJobStatus::Failed);
+    assert!(job.last_error.is_some());
+}
@@ -0,0 +1,130 @@
+use distri_types::agent_registry::{AgentRegistryConfig, CardPublication, SIGNATURE_ALGORITHM};
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+use crate::a2a::registry::{sign, RegistryPublisher};
+use crate::testing::{AgentTestHarness, MockLlmProvider};
+use crate::types::StandardDefinition;
+use crate::AgentOrchestratorBuilder;
+
+const SIGNING_KEY: &str = "registry-secret";
+
+async fn harness(registry_url: String) -> AgentTestHarness {
+    let config = AgentRegistryConfig {
+        url: registry_url,
+        public_url: "https://agents.example.com/v1/".to_string(),
+        publisher: "acme".to_string(),
+        token: Some("registry-token".to_string()),
+        signing_key: Some(SIGNING_KEY.to_string()),
+        refresh_secs: 300,
+    };
+    AgentTestHarness::from_builder(
+        AgentOrchestratorBuilder::default().with_agent_registry(Some(config)),
+        MockLlmProvider::new(),
+    )
+    .await
+    .unwrap()
+}
+
+fn agent(name: &str, description: &str) -> StandardDefinition {
+    StandardDefinition {
+        name: name.to_string(),
+        description: description.to_string(),
+        ..Default::default()
+    }
+}
+
+async fn requests_for(server: &MockServer, verb: &str, agent: &str) -> Vec<wiremock::Request> {
+    let agent_path = format!("/agents/{}", agent);
+    server
+        .received_requests()
+        .await
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|r| r.method.as_str() == verb && r.url.path() == agent_path)
+        .collect()
+}
+
+#[tokio::test]
+async fn publishes_signed_cards_and_skips_unchanged_ones() {
+    let server = MockServer::start().await;
+    Mock::given(method("PUT"))
+        .and(path("/agents/analyst"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&server)
+        .await;
+    let harness = harness(server.uri()).await;
+    harness
+        .register_agent(agent("analyst", "Answers sales questions"))
+        .await
+        .unwrap();
+    let publisher = RegistryPublisher::new(harness.orchestrator.clone()).unwrap();
+
+    publisher.sync(false).await;
+    publisher.sync(false).await;
+
+    let puts = requests_for(&server, "PUT", "analyst").await;
+    assert_eq!(puts.len(), 1, "unchanged card must not be republished");
+    assert_eq!(
+        puts[0].headers.get("authorization").unwrap(),
+        "Bearer registry-token"
+    );
+    let publication: CardPublication = serde_json::from_slice(&puts[0].body).unwrap();
+    assert_eq!(
+        publication.card.url,
+        "https://agents.example.com/v1/agents/analyst"
+    );
+    assert_eq!(publication.metadata.publisher, "acme");
+    assert_eq!(
+        publication.metadata.algorithm.as_deref(),
+        Some(SIGNATURE_ALGORITHM)
+    );
+    assert_eq!(
+        publication.metadata.signature,
+        Some(sign(SIGNING_KEY, &publication.metadata.signing_input()))
+    );
+
+    // A forced refresh republishes, and so does a changed card.
+    publisher.sync(true).await;
+    harness
+        .orchestrator
+        .update_agent_definition(agent("analyst", "Answers pipeline questions"))
+        .await
+        .unwrap();
+    publisher.sync(false).await;
+    assert_eq!(requests_for(&server, "PUT", "analyst").await.len(), 3);
+}
+
+#[tokio::test]
+async fn retracts_cards_of_deleted_agents() {
+    let server = MockServer::start().await;
+    Mock::given(method("PUT"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&server)
+        .await;
+    Mock::given(method("DELETE"))
+        .and(path("/agents/scratch"))
+        .respond_with(ResponseTemplate::new(404))
+        .mount(&server)
+        .await;
+    let harness = harness(server.uri()).await;
+    harness
+        .register_agent(agent("scratch", "Temporary agent"))
+        .await
+        .unwrap();
+    let publisher = RegistryPublisher::new(harness.orchestrator.clone()).unwrap();
+    publisher.sync(false).await;
+
+    harness
+        .orchestrator
+        .stores
+        .agent_store
+        .delete("scratch")
+        .await
+        .unwrap();
+    publisher.sync(false).await;
+    // Retracted (404 counts as already gone), so not retried.
+    publisher.sync(false).await;
+
+    assert_eq!(requests_for(&server, "DELETE", "scratch").await.len(), 1);
+}
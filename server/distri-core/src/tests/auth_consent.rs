@@ -0,0 +1,152 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use distri_types::{
+    AgentEventType, AuthConsentResponse, Part, ScopesRequired, Tool, ToolCall, ToolContext,
+};
+use serde_json::{json, Value};
+
+use crate::agent::AgentOrchestrator;
+use crate::testing::{AgentTestHarness, MockLlmProvider};
+use crate::types::StandardDefinition;
+
+/// Fails with `ScopesRequired` until the scopes have been granted once.
+#[derive(Debug, Default)]
+struct CreateIssueTool {
+    granted: AtomicBool,
+}
+
+#[async_trait::async_trait]
+impl Tool for CreateIssueTool {
+    fn get_name(&self) -> String {
+        "create_issue".to_string()
+    }
+
+    fn get_description(&self) -> String {
+        "Create a GitHub issue".to_string()
+    }
+
+    fn get_parameters(&self) -> Value {
+        json!({ "type": "object" })
+    }
+
+    async fn execute(
+        &self,
+        _tool_call: ToolCall,
+        _context: Arc<ToolContext>,
+    ) -> Result<Vec<Part>, anyhow::Error> {
+        if !self.granted.swap(true, Ordering::SeqCst) {
+            return Err(ScopesRequired {
+                provider: "github".to_string(),
+                current_scopes: vec!["read:user".to_string()],
+                required_scopes: vec!["read:user".to_string(), "repo".to_string()],
+            }
+            .into());
+        }
+        Ok(vec![Part::Text("issue #7 created".to_string())])
+    }
+}
+
+async fn harness(llm: MockLlmProvider) -> AgentTestHarness {
+    let harness = AgentTestHarness::new(llm).await.unwrap();
+    harness
+        .register_agent(StandardDefinition {
+            name: "triage".to_string(),
+            description: "files issues".to_string(),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    harness
+        .orchestrator
+        .register_tool("triage", Arc::new(CreateIssueTool::default()))
+        .await;
+    harness
+}
+
+/// Answer the first consent prompt the run raises, like a client would.
+fn answer_consent(orchestrator: Arc<AgentOrchestrator>, granted: bool) {
+    tokio::spawn(async move {
+        loop {
+            let pending = orchestrator
+                .auth_consents
+                .iter()
+                .next()
+                .map(|entry| entry.key().clone());
+            if let Some(auth_id) = pending {
+                orchestrator
+                    .complete_auth_consent(AuthConsentResponse {
+                        auth_id,
+                        granted,
+                        code: None,
+                        state: None,
+                    })
+                    .await
+                    .unwrap();
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    });
+}
+
+fn tool_result_parts(events: &[crate::agent::types::AgentEvent]) -> Vec<Part> {
+    events
+        .iter()
+        .filter_map(|e| match &e.event {
+            AgentEventType::ToolResults { results, .. } => Some(results),
+            _ => None,
+        })
+        .flatten()
+        .flat_map(|r| r.parts.clone())
+        .collect()
+}
+
+#[tokio::test]
+async fn granted_consent_resumes_the_tool_call() {
+    let llm = MockLlmProvider::new()
+        .respond_tool_call("create_issue", json!({ "title": "Login broken" }))
+        .respond_final("filed");
+    let harness = harness(llm.clone()).await;
+    answer_consent(harness.orchestrator.clone(), true);
+
+    let run = harness.run("triage", "File the login bug").await;
+
+    run.assert_success();
+    let request = run
+        .events
+        .iter()
+        .find_map(|e| match &e.event {
+            AgentEventType::AuthRequired { request } => Some(request.clone()),
+            _ => None,
+        })
+        .expect("the tool call pauses for consent");
+    assert_eq!(request.provider, "github");
+    assert_eq!(request.tool_name, "create_issue");
+    assert_eq!(request.missing_scopes, vec!["repo"]);
+    assert_eq!(
+        tool_result_parts(&run.events),
+        vec![Part::Text("issue #7 created".to_string())]
+    );
+}
+
+#[tokio::test]
+async fn declined_consent_reports_auth_required_to_the_model() {
+    let llm = MockLlmProvider::new()
+        .respond_tool_call("create_issue", json!({ "title": "Login broken" }))
+        .respond_final("could not file");
+    let harness = harness(llm.clone()).await;
+    answer_consent(harness.orchestrator.clone(), false);
+
+    let run = harness.run("triage", "File the login bug").await;
+
+    run.assert_success();
+    let parts = tool_result_parts(&run.events);
+    let [Part::Data(required)] = parts.as_slice() else {
+        panic!("expected a structured auth error, got {:?}", parts);
+    };
+    assert_eq!(required["error"], "auth_required");
+    assert_eq!(required["provider"], "github");
+    assert_eq!(required["missing_scopes"], json!(["repo"]));
+}
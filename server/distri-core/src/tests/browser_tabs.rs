@@ -0,0 +1,96 @@
+use std::time::Duration;
+
+use crate::agent::browser_tabs::{BrowserTab, BrowserTabs, MAIN_TAB};
+
+fn tab(name: &str) -> BrowserTab {
+    BrowserTab {
+        name: name.to_string(),
+        session_id: format!("sess-{}", name),
+        url: None,
+        borrowed: false,
+    }
+}
+
+fn names(tabs: &BrowserTabs, thread_id: &str) -> Vec<String> {
+    tabs.list(thread_id)
+        .tabs
+        .into_iter()
+        .map(|t| t.name)
+        .collect()
+}
+
+/// The thread's main session becomes the `main` tab when the first extra
+/// tab opens, and the new tab is active.
+#[test]
+fn first_tab_adopts_the_main_session() {
+    let tabs = BrowserTabs::default();
+
+    let list = tabs
+        .open("t1", tab("shop_b"), Some("sess-main".to_string()))
+        .unwrap();
+
+    assert_eq!(names(&tabs, "t1"), vec![MAIN_TAB, "shop_b"]);
+    assert_eq!(list.active.as_deref(), Some("shop_b"));
+    assert_eq!(
+        tabs.session("t1", None).unwrap().as_deref(),
+        Some("sess-shop_b")
+    );
+    assert_eq!(
+        tabs.session("t1", Some(MAIN_TAB)).unwrap().as_deref(),
+        Some("sess-main")
+    );
+}
+
+#[test]
+fn switching_changes_the_session_commands_run_in() {
+    let tabs = BrowserTabs::default();
+    tabs.open("t1", tab("a"), None).unwrap();
+    tabs.open("t1", tab("b"), None).unwrap();
+
+    tabs.switch("t1", "a").unwrap();
+
+    assert_eq!(tabs.session("t1", None).unwrap().as_deref(), Some("sess-a"));
+    assert!(tabs
+        .switch("t1", "c")
+        .unwrap_err()
+        .contains("open tabs: a, b"));
+}
+
+#[test]
+fn closing_the_active_tab_activates_the_latest_remaining_one() {
+    let tabs = BrowserTabs::default();
+    tabs.open("t1", tab("a"), None).unwrap();
+    tabs.open("t1", tab("b"), None).unwrap();
+    tabs.open("t1", tab("c"), None).unwrap();
+    tabs.switch("t1", "b").unwrap();
+
+    let (closed, list) = tabs.close("t1", "b").unwrap();
+
+    assert_eq!(closed.session_id, "sess-b");
+    assert_eq!(list.active.as_deref(), Some("c"));
+    assert_eq!(names(&tabs, "t1"), vec!["a", "c"]);
+}
+
+#[test]
+fn tabs_are_kept_per_thread() {
+    let tabs = BrowserTabs::default();
+    tabs.open("t1", tab("a"), None).unwrap();
+
+    assert!(tabs.list("t2").tabs.is_empty());
+    assert_eq!(tabs.session("t2", None).unwrap(), None);
+    assert!(tabs.session("t2", Some("a")).is_err());
+    assert!(tabs.open("t1", tab("a"), None).is_err(), "names are unique");
+}
+
+#[test]
+fn idle_threads_release_their_own_sessions_only() {
+    let tabs = BrowserTabs::default();
+    tabs.open("t1", tab("a"), Some("sess-main".to_string()))
+        .unwrap();
+
+    let taken = tabs.take_idle(Duration::ZERO);
+
+    let sessions: Vec<&str> = taken.iter().map(|(_, t)| t.session_id.as_str()).collect();
+    assert_eq!(sessions, vec!["sess-a"]);
+    assert!(tabs.list("t1").tabs.is_empty());
+}
@@ -0,0 +1,108 @@
+//! Model capability probing: an agent with native tool calls falls back to
+//! the XML tool format when its model fails the probe, and a model is
+//! probed once.
+
+use distri_types::capability_probe::{CapabilityProbeConfig, PROBE_TOOL};
+use distri_types::configuration::AgentConfig;
+use distri_types::{ModelSettings, ToolCallFormat};
+use serde_json::json;
+
+use crate::testing::{AgentTestHarness, MockLlmProvider, MOCK_MODEL};
+use crate::types::StandardDefinition;
+use crate::AgentOrchestratorBuilder;
+
+const XML_INSTRUCTIONS: &str = "CRITICAL XML REQUIREMENTS";
+
+async fn harness(llm: MockLlmProvider) -> AgentTestHarness {
+    let builder =
+        AgentOrchestratorBuilder::default().with_capability_probe(Some(CapabilityProbeConfig {
+            providers: vec!["*".to_string()],
+            ..Default::default()
+        }));
+    let harness = AgentTestHarness::from_builder(builder, llm).await.unwrap();
+    harness.register_agent(agent()).await.unwrap();
+    harness
+}
+
+fn agent() -> StandardDefinition {
+    StandardDefinition {
+        name: "local".to_string(),
+        tool_format: ToolCallFormat::Provider,
+        model_settings: Some(ModelSettings {
+            model: MOCK_MODEL.to_string(),
+            inner: Default::default(),
+        }),
+        ..Default::default()
+    }
+}
+
+/// Text of every message of model call `request`.
+fn prompt(harness: &AgentTestHarness, request: usize) -> String {
+    harness.llm.requests()[request]
+        .messages
+        .iter()
+        .filter_map(|m| m.as_text())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[tokio::test]
+async fn unreliable_native_tool_calls_fall_back_to_xml_once() {
+    let llm = MockLlmProvider::new()
+        // Probes: no tool call, so the parallel probe is skipped.
+        .respond_text("I cannot check the weather.")
+        .respond_text(r#"{"answer": 5}"#)
+        .respond_final("first")
+        .respond_final("second");
+    let harness = harness(llm).await;
+
+    harness.run("local", "hello").await.assert_success();
+    harness.run("local", "again").await.assert_success();
+
+    let requests = harness.llm.requests();
+    assert_eq!(requests.len(), 4, "the model is probed only once");
+    assert_eq!(requests[0].agent_id, "capability_probe");
+    assert_eq!(requests[0].tool_names, [PROBE_TOOL]);
+    assert!(requests[1].tool_names.is_empty());
+    assert!(prompt(&harness, 2).contains(XML_INSTRUCTIONS));
+    assert!(prompt(&harness, 3).contains(XML_INSTRUCTIONS));
+    harness.llm.assert_exhausted();
+
+    // The stored definition keeps the declared format.
+    let Some(AgentConfig::StandardAgent(stored)) = harness.orchestrator.get_agent("local").await
+    else {
+        panic!("agent not registered");
+    };
+    assert_eq!(stored.tool_format, ToolCallFormat::Provider);
+}
+
+#[tokio::test]
+async fn unreliable_parallel_tool_calls_are_turned_off() {
+    let llm = MockLlmProvider::new()
+        .respond_tool_call(PROBE_TOOL, json!({ "city": "Paris" }))
+        // Asked for Paris and Tokyo, the model only calls once.
+        .respond_tool_call(PROBE_TOOL, json!({ "city": "Paris" }))
+        .respond_text(r#"{"answer": 5}"#)
+        .respond_final("done");
+    let harness = harness(llm).await;
+
+    harness.run("local", "hello").await.assert_success();
+
+    assert_eq!(harness.llm.requests().len(), 4);
+    assert!(!prompt(&harness, 3).contains(XML_INSTRUCTIONS));
+
+    let mut config = AgentConfig::StandardAgent(agent());
+    harness
+        .orchestrator
+        .apply_model_capabilities(&mut config)
+        .await;
+    let AgentConfig::StandardAgent(adapted) = config else {
+        unreachable!()
+    };
+    assert_eq!(adapted.tool_format, ToolCallFormat::Provider);
+    assert_eq!(
+        adapted.model_settings.unwrap().inner.parallel_tool_calls,
+        Some(false)
+    );
+    harness.llm.assert_exhausted();
+}
@@ -0,0 +1,133 @@
+use std::sync::Arc;
+
+use distri_types::conversation_import::{parse_export, ImportFormat};
+use distri_types::stores::ThreadListFilter;
+use distri_types::{MessageRole, StandardDefinition, TaskMessage, TaskStatus};
+
+use crate::tests::helpers::test_store_config;
+use crate::{AgentError, AgentOrchestrator, AgentOrchestratorBuilder};
+
+const CLAUDE_EXPORT: &str = r#"[{
+    "uuid": "conv-1",
+    "name": "Lisbon trip",
+    "created_at": "2024-05-01T10:00:00Z",
+    "chat_messages": [
+        { "uuid": "m1", "sender": "human", "text": "Plan a trip" },
+        { "uuid": "m2", "sender": "assistant", "text": "Day 1: Alfama" },
+        { "uuid": "m3", "sender": "human", "text": "And day 2?" },
+        { "uuid": "m4", "sender": "assistant", "text": "Day 2: Belem" }
+    ]
+}]"#;
+
+async fn orchestrator() -> Arc<AgentOrchestrator> {
+    let orchestrator = AgentOrchestratorBuilder::default()
+        .with_store_config(test_store_config())
+        .build()
+        .await
+        .unwrap();
+    orchestrator
+        .register_agent_definition(StandardDefinition {
+            name: "assistant".to_string(),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    Arc::new(orchestrator)
+}
+
+#[tokio::test]
+async fn imports_conversation_as_thread_with_a_task_per_exchange() {
+    let orchestrator = orchestrator().await;
+    let conversations = parse_export(ImportFormat::Claude, CLAUDE_EXPORT).unwrap();
+
+    let summary = orchestrator
+        .import_conversations("assistant", conversations)
+        .await
+        .unwrap();
+    assert_eq!(summary.imported.len(), 1);
+    let imported = &summary.imported[0];
+    assert_eq!(imported.external_id, "claude:conv-1");
+    assert_eq!(imported.message_count, 4);
+
+    let thread = orchestrator
+        .get_thread(&imported.thread_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(thread.title, "Lisbon trip");
+    assert_eq!(thread.agent_id, "assistant");
+    assert_eq!(thread.last_message.as_deref(), Some("Day 2: Belem"));
+    assert_eq!(thread.active_task_id, None);
+
+    let history = orchestrator
+        .stores
+        .task_store
+        .get_history(&imported.thread_id, None)
+        .await
+        .unwrap();
+    assert_eq!(history.len(), 2);
+    let turns: Vec<Vec<(MessageRole, String)>> = history
+        .iter()
+        .map(|(task, messages)| {
+            assert_eq!(task.status, TaskStatus::Completed);
+            messages
+                .iter()
+                .filter_map(|m| match m {
+                    TaskMessage::Message(m) => Some((m.role.clone(), m.as_text().unwrap())),
+                    _ => None,
+                })
+                .collect()
+        })
+        .collect();
+    assert_eq!(
+        turns,
+        vec![
+            vec![
+                (MessageRole::User, "Plan a trip".to_string()),
+                (MessageRole::Assistant, "Day 1: Alfama".to_string()),
+            ],
+            vec![
+                (MessageRole::User, "And day 2?".to_string()),
+                (MessageRole::Assistant, "Day 2: Belem".to_string()),
+            ],
+        ]
+    );
+}
+
+#[tokio::test]
+async fn reimport_skips_conversations_already_imported() {
+    let orchestrator = orchestrator().await;
+    let conversations = parse_export(ImportFormat::Claude, CLAUDE_EXPORT).unwrap();
+    orchestrator
+        .import_conversations("assistant", conversations.clone())
+        .await
+        .unwrap();
+
+    let summary = orchestrator
+        .import_conversations("assistant", conversations)
+        .await
+        .unwrap();
+    assert!(summary.imported.is_empty());
+    assert_eq!(summary.skipped, vec!["claude:conv-1".to_string()]);
+
+    let filter = ThreadListFilter {
+        external_id: Some("claude:conv-1".to_string()),
+        ..Default::default()
+    };
+    let threads = orchestrator
+        .list_threads(&filter, None, None)
+        .await
+        .unwrap();
+    assert_eq!(threads.threads.len(), 1);
+}
+
+#[tokio::test]
+async fn import_requires_a_known_agent() {
+    let orchestrator = orchestrator().await;
+    let conversations = parse_export(ImportFormat::Claude, CLAUDE_EXPORT).unwrap();
+    let err = orchestrator
+        .import_conversations("missing", conversations)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, AgentError::AgentNotFound(_)), "{:?}", err);
+}
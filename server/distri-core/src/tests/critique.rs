@@ -0,0 +1,68 @@
+use distri_types::critique::CritiqueConfig;
+
+use crate::testing::{AgentTestHarness, MockLlmProvider};
+use crate::types::StandardDefinition;
+
+async fn harness(llm: MockLlmProvider) -> AgentTestHarness {
+    let harness = AgentTestHarness::new(llm).await.unwrap();
+    harness
+        .register_agent(StandardDefinition {
+            name: "support".to_string(),
+            critique: Some(CritiqueConfig {
+                guardrails: vec!["Never promise a delivery date.".to_string()],
+                ..Default::default()
+            }),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    harness
+}
+
+#[tokio::test]
+async fn a_critiqued_draft_is_revised_once() {
+    let llm = MockLlmProvider::new()
+        .respond_final("It arrives tomorrow, guaranteed.")
+        .respond_text(r#"{"approved": false, "issues": ["It promises a delivery date."]}"#)
+        .respond_final("It is on its way; tracking has the latest estimate.");
+    let harness = harness(llm.clone()).await;
+
+    let run = harness.run("support", "When will my order arrive?").await;
+
+    run.assert_success();
+    llm.assert_exhausted();
+    assert_eq!(
+        run.final_text(),
+        Some("It is on its way; tracking has the latest estimate.")
+    );
+    let requests = llm.requests();
+    let critique = format!("{:?}", requests[1].messages);
+    assert!(
+        critique.contains("Never promise a delivery date."),
+        "{critique}"
+    );
+    assert!(
+        critique.contains("It arrives tomorrow, guaranteed."),
+        "{critique}"
+    );
+    // The revision sees the critique in its scratchpad.
+    let revision = format!("{:?}", requests[2].messages);
+    assert!(
+        revision.contains("It promises a delivery date."),
+        "{revision}"
+    );
+}
+
+#[tokio::test]
+async fn an_approved_draft_is_the_answer() {
+    let llm = MockLlmProvider::new()
+        .respond_final("Tracking has the latest estimate.")
+        .respond_text(r#"{"approved": true, "issues": []}"#);
+    let harness = harness(llm.clone()).await;
+
+    let run = harness.run("support", "When will my order arrive?").await;
+
+    run.assert_success();
+    llm.assert_exhausted();
+    assert_eq!(run.final_text(), Some("Tracking has the latest estimate."));
+}
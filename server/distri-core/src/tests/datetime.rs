@@ -0,0 +1,128 @@
+use std::sync::Arc;
+
+use distri_types::datetime::{BusinessCalendar, DateTimeConfig};
+use distri_types::{MessageRole, Part, ToolCall};
+use serde_json::{json, Value};
+
+use crate::agent::ExecutorContext;
+use crate::testing::{AgentTestHarness, MockLlmProvider};
+use crate::tools::datetime::{DateTimeMathTool, TimezoneConvertTool};
+use crate::tools::ExecutorContextTool;
+use crate::types::StandardDefinition;
+
+fn scheduler(datetime: DateTimeConfig) -> StandardDefinition {
+    StandardDefinition {
+        name: "scheduler".to_string(),
+        datetime: Some(datetime),
+        ..Default::default()
+    }
+}
+
+async fn call(
+    tool: &dyn ExecutorContextTool,
+    input: Value,
+    context: &Arc<ExecutorContext>,
+) -> Value {
+    let call = ToolCall {
+        tool_call_id: uuid::Uuid::new_v4().to_string(),
+        tool_name: tool.get_name(),
+        input,
+    };
+    let parts = tool
+        .execute_with_executor_context(call, context.clone())
+        .await
+        .unwrap();
+    match parts.into_iter().next() {
+        Some(Part::Data(data)) => data,
+        other => panic!("expected a data part, got {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn current_datetime_is_in_the_system_prompt() {
+    let harness = AgentTestHarness::new(MockLlmProvider::new().respond_final("Saturday"))
+        .await
+        .unwrap();
+    harness
+        .register_agent(scheduler(DateTimeConfig {
+            timezone: Some("Asia/Tokyo".to_string()),
+            business_calendar: Some(BusinessCalendar::default()),
+        }))
+        .await
+        .unwrap();
+
+    harness
+        .run("scheduler", "What day is it?")
+        .await
+        .assert_success();
+    let requests = harness.llm.requests();
+    let system = requests[0]
+        .messages
+        .iter()
+        .find(|m| m.role == MessageRole::System)
+        .and_then(|m| m.as_text())
+        .unwrap();
+    assert!(system.contains("# CURRENT DATE AND TIME"), "{system}");
+    assert!(system.contains("Asia/Tokyo (UTC+09:00)"), "{system}");
+    assert!(system.contains("Business days: Mon, Tue, Wed, Thu, Fri."));
+}
+
+#[tokio::test]
+async fn tools_use_the_profile_timezone_and_agent_calendar() {
+    let harness = AgentTestHarness::new(MockLlmProvider::new()).await.unwrap();
+    harness
+        .register_agent(scheduler(DateTimeConfig {
+            timezone: Some("Europe/Berlin".to_string()),
+            business_calendar: Some(BusinessCalendar {
+                working_days: vec![chrono::Weekday::Mon, chrono::Weekday::Tue],
+                ..Default::default()
+            }),
+        }))
+        .await
+        .unwrap();
+    let context = Arc::new(ExecutorContext {
+        agent_id: "scheduler".to_string(),
+        thread_id: "thread-datetime".to_string(),
+        orchestrator: Some(harness.orchestrator.clone()),
+        ..Default::default()
+    });
+
+    // Without a profile the agent's timezone applies.
+    let converted = call(
+        &TimezoneConvertTool,
+        json!({ "datetime": "2026-10-17T12:00:00Z", "to": "UTC" }),
+        &context,
+    )
+    .await;
+    assert_eq!(converted["source"]["timezone"], "Europe/Berlin");
+    assert_eq!(converted["source"]["time"], "14:00:00");
+
+    context
+        .get_session_store()
+        .unwrap()
+        .set_value(
+            "thread-datetime",
+            "user_profile",
+            &json!({ "name": "Sam", "timezone": "America/New_York" }),
+        )
+        .await
+        .unwrap();
+    let converted = call(
+        &TimezoneConvertTool,
+        json!({ "datetime": "2026-10-17T12:00:00Z", "to": ["Asia/Tokyo"] }),
+        &context,
+    )
+    .await;
+    assert_eq!(converted["source"]["timezone"], "America/New_York");
+    assert_eq!(converted["converted"][0]["time"], "21:00:00");
+
+    // Business days follow the agent's calendar: Tuesday + 1 is Monday.
+    let result = call(
+        &DateTimeMathTool,
+        json!({ "operation": "add", "datetime": "2026-10-20 10:00", "business_days": 1 }),
+        &context,
+    )
+    .await;
+    assert_eq!(result["datetime"], "2026-10-26T10:00:00-04:00");
+    assert_eq!(result["is_business_day"], true);
+}
@@ -0,0 +1,53 @@
+use distri_types::{AgentEventType, TaskMessage, TaskStatus};
+
+use crate::tests::helpers::test_store_config;
+use crate::AgentOrchestratorBuilder;
+
+#[tokio::test]
+async fn seeds_sample_threads_once() {
+    let orchestrator = AgentOrchestratorBuilder::default()
+        .with_store_config(test_store_config())
+        .build()
+        .await
+        .unwrap();
+
+    let summary = orchestrator.seed_dev_fixtures().await.unwrap();
+    assert_eq!(summary.agents, vec!["sample_researcher", "sample_analyst"]);
+    assert_eq!(summary.threads.len(), 3);
+    assert!(orchestrator.get_agent("sample_analyst").await.is_some());
+
+    let history = orchestrator
+        .stores
+        .task_store
+        .get_history("sample-thread-research", None)
+        .await
+        .unwrap();
+    assert_eq!(history.len(), 2, "one task per user message");
+    let tool_results = history
+        .iter()
+        .flat_map(|(_, messages)| messages)
+        .filter(|m| {
+            matches!(
+                m,
+                TaskMessage::Event(e) if matches!(e.event, AgentEventType::ToolResults { .. })
+            )
+        })
+        .count();
+    assert_eq!(tool_results, 3);
+
+    let failed = orchestrator
+        .stores
+        .task_store
+        .get_history("sample-thread-failed", None)
+        .await
+        .unwrap();
+    assert_eq!(failed[0].0.status, TaskStatus::Failed);
+    assert!(failed[0].1.iter().any(|m| matches!(
+        m,
+        TaskMessage::Event(e) if matches!(e.event, AgentEventType::RunError { .. })
+    )));
+
+    let again = orchestrator.seed_dev_fixtures().await.unwrap();
+    assert!(again.threads.is_empty());
+    assert!(again.skipped.contains(&"sample-thread-report".to_string()));
+}
@@ -0,0 +1,171 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use distri_types::dry_run::DryRunReport;
+use distri_types::output_sinks::{OutputSinkConfig, OutputSinkKind};
+use distri_types::{AgentEventType, Part, Tool, ToolCall, ToolContext};
+use serde_json::{json, Value};
+
+use crate::testing::{AgentTestHarness, MockLlmProvider, TestRun};
+use crate::types::StandardDefinition;
+
+/// A tool counting its real calls, declaring `side_effect`.
+#[derive(Debug)]
+struct Counted {
+    name: &'static str,
+    side_effect: Option<bool>,
+    calls: Arc<AtomicUsize>,
+}
+
+#[async_trait::async_trait]
+impl Tool for Counted {
+    fn get_name(&self) -> String {
+        self.name.to_string()
+    }
+
+    fn get_description(&self) -> String {
+        format!("The {} tool", self.name)
+    }
+
+    fn get_parameters(&self) -> Value {
+        json!({ "type": "object" })
+    }
+
+    fn side_effect(&self) -> Option<bool> {
+        self.side_effect
+    }
+
+    async fn execute(
+        &self,
+        _tool_call: ToolCall,
+        _context: Arc<ToolContext>,
+    ) -> Result<Vec<Part>, anyhow::Error> {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+        Ok(vec![Part::Data(json!({ "ok": true }))])
+    }
+}
+
+struct Calls {
+    lookup_order: Arc<AtomicUsize>,
+    send_email: Arc<AtomicUsize>,
+    legacy_crm: Arc<AtomicUsize>,
+}
+
+async fn harness() -> (AgentTestHarness, Calls) {
+    let llm = MockLlmProvider::new()
+        .respond_tool_call("lookup_order", json!({ "order": 42 }))
+        .respond_tool_call("send_email", json!({ "to": "ana@example.com" }))
+        .respond_tool_call("legacy_crm", json!({ "note": "refunded" }))
+        .respond_final("Refund confirmed to the customer.");
+    let harness = AgentTestHarness::new(llm).await.unwrap();
+    harness
+        .register_agent(StandardDefinition {
+            name: "support".to_string(),
+            output_sinks: vec![OutputSinkConfig {
+                name: Some("audit".to_string()),
+                kind: OutputSinkKind::Artifact {
+                    filename: "audit.md".to_string(),
+                },
+            }],
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    let calls = Calls {
+        lookup_order: Arc::default(),
+        send_email: Arc::default(),
+        legacy_crm: Arc::default(),
+    };
+    for (name, side_effect, counter) in [
+        ("lookup_order", Some(false), &calls.lookup_order),
+        ("send_email", Some(true), &calls.send_email),
+        // Undeclared and not on the read-only list.
+        ("legacy_crm", None, &calls.legacy_crm),
+    ] {
+        harness
+            .orchestrator
+            .register_tool(
+                "support",
+                Arc::new(Counted {
+                    name,
+                    side_effect,
+                    calls: counter.clone(),
+                }),
+            )
+            .await;
+    }
+    (harness, calls)
+}
+
+fn report(run: &TestRun) -> DryRunReport {
+    let reports: Vec<&DryRunReport> = run
+        .events
+        .iter()
+        .filter_map(|e| match &e.event {
+            AgentEventType::DryRunReport { report } => Some(report),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(reports.len(), 1, "{:?}", run.events);
+    reports[0].clone()
+}
+
+#[tokio::test]
+async fn a_dry_run_intercepts_side_effects_and_reports_them() {
+    let (harness, calls) = harness().await;
+
+    let run = harness.dry_run("support", "Refund order 42").await;
+
+    run.assert_success();
+    assert_eq!(calls.lookup_order.load(Ordering::SeqCst), 1);
+    assert_eq!(calls.send_email.load(Ordering::SeqCst), 0);
+    assert_eq!(calls.legacy_crm.load(Ordering::SeqCst), 0);
+
+    let simulated: Vec<&str> = run
+        .events
+        .iter()
+        .filter_map(|e| match &e.event {
+            AgentEventType::SideEffectSimulated { tool_call_name, .. } => {
+                Some(tool_call_name.as_str())
+            }
+            _ => None,
+        })
+        .collect();
+    assert_eq!(simulated, ["send_email", "legacy_crm"]);
+
+    let report = report(&run);
+    let tools: Vec<&str> = report
+        .side_effects
+        .iter()
+        .map(|e| e.tool.as_str())
+        .collect();
+    assert_eq!(tools, ["send_email", "legacy_crm", "output_sink:audit"]);
+    assert_eq!(
+        report.side_effects[0].input,
+        json!({ "to": "ana@example.com" })
+    );
+    assert_eq!(
+        report.side_effects[2].input,
+        json!("Refund confirmed to the customer.")
+    );
+    // The sink was not delivered.
+    assert!(!run
+        .events
+        .iter()
+        .any(|e| matches!(e.event, AgentEventType::OutputSinkFinished { .. })));
+}
+
+#[tokio::test]
+async fn a_normal_run_has_no_report() {
+    let (harness, calls) = harness().await;
+
+    let run = harness.run("support", "Refund order 42").await;
+
+    run.assert_success();
+    assert_eq!(calls.send_email.load(Ordering::SeqCst), 1);
+    assert_eq!(calls.legacy_crm.load(Ordering::SeqCst), 1);
+    assert!(!run.events.iter().any(|e| matches!(
+        e.event,
+        AgentEventType::DryRunReport { .. } | AgentEventType::SideEffectSimulated { .. }
+    )));
+}
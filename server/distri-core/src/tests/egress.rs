@@ -0,0 +1,182 @@
+//! Egress policy: tools reaching a blocked host get a structured
+//! `egress_denied` result instead of a response, for plugin-style tools
+//! (through `ToolContext::check_network`) and inline HTTP tools alike.
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use distri_types::dynamic_tool::DynamicToolFactory;
+use distri_types::egress::{EgressPolicyConfig, EgressRules};
+use distri_types::{AgentEventType, Part, Tool, ToolCall, ToolContext};
+use serde_json::{json, Value};
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+use crate::agent::ExecutorContext;
+use crate::testing::{AgentTestHarness, MockLlmProvider};
+use crate::tools::dynamic_factory::create_dynamic_tool;
+use crate::types::StandardDefinition;
+use crate::{AgentError, AgentOrchestratorBuilder};
+
+#[derive(Debug)]
+struct FetchTool;
+
+#[async_trait::async_trait]
+impl Tool for FetchTool {
+    fn get_name(&self) -> String {
+        "fetch".to_string()
+    }
+
+    fn get_description(&self) -> String {
+        "Fetch a URL".to_string()
+    }
+
+    fn get_parameters(&self) -> Value {
+        json!({ "type": "object" })
+    }
+
+    async fn execute(
+        &self,
+        tool_call: ToolCall,
+        context: Arc<ToolContext>,
+    ) -> Result<Vec<Part>, anyhow::Error> {
+        let url = tool_call.input["url"].as_str().unwrap_or_default();
+        context.check_network(url)?;
+        Ok(vec![Part::Text(format!("fetched {}", url))])
+    }
+}
+
+async fn harness(llm: MockLlmProvider, policy: EgressPolicyConfig) -> AgentTestHarness {
+    let builder = AgentOrchestratorBuilder::default().with_egress_policy(Some(policy));
+    AgentTestHarness::from_builder(builder, llm).await.unwrap()
+}
+
+fn agent(name: &str) -> StandardDefinition {
+    StandardDefinition {
+        name: name.to_string(),
+        description: "fetches pages".to_string(),
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn tools_cannot_reach_blocked_hosts() {
+    let llm = MockLlmProvider::new()
+        .respond_tool_call(
+            "fetch",
+            json!({ "url": "https://en.wikipedia.org/wiki/Rust" }),
+        )
+        .respond_tool_call("fetch", json!({ "url": "http://169.254.169.254/latest" }))
+        .respond_tool_call("fetch", json!({ "url": "https://example.com/" }))
+        .respond_final("done");
+    let harness = harness(
+        llm,
+        EgressPolicyConfig {
+            deny: vec!["169.254.169.254".to_string()],
+            agents: BTreeMap::from([(
+                "researcher".to_string(),
+                EgressRules {
+                    allow: vec!["*.wikipedia.org".to_string()],
+                    ..Default::default()
+                },
+            )]),
+            ..Default::default()
+        },
+    )
+    .await;
+    harness.register_agent(agent("researcher")).await.unwrap();
+    harness
+        .orchestrator
+        .register_tool("researcher", Arc::new(FetchTool))
+        .await;
+
+    let run = harness.run("researcher", "Read up on Rust").await;
+
+    run.assert_success();
+    let results: Vec<Vec<Part>> = run
+        .events
+        .iter()
+        .filter_map(|e| match &e.event {
+            AgentEventType::ToolResults { results, .. } => Some(results),
+            _ => None,
+        })
+        .flatten()
+        .map(|r| r.parts.clone())
+        .collect();
+    assert_eq!(
+        results[0],
+        vec![Part::Text(
+            "fetched https://en.wikipedia.org/wiki/Rust".to_string()
+        )]
+    );
+    let [Part::Data(denied)] = results[1].as_slice() else {
+        panic!("expected a structured denial, got {:?}", results[1]);
+    };
+    assert_eq!(denied["error"], "egress_denied");
+    assert_eq!(denied["host"], "169.254.169.254");
+    assert_eq!(denied["reason"], "matches deny rule '169.254.169.254'");
+    // The agent's own allow list narrows it further.
+    let [Part::Data(outside)] = results[2].as_slice() else {
+        panic!("expected a structured denial, got {:?}", results[2]);
+    };
+    assert_eq!(outside["host"], "example.com");
+    assert_eq!(outside["reason"], "not in the allow list");
+}
+
+#[tokio::test]
+async fn inline_http_tools_are_checked_before_sending() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/orders"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "orders": [] })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    let harness = harness(
+        MockLlmProvider::new(),
+        EgressPolicyConfig {
+            agents: BTreeMap::from([(
+                "sandboxed".to_string(),
+                EgressRules {
+                    deny: vec!["127.0.0.1".to_string()],
+                    ..Default::default()
+                },
+            )]),
+            ..Default::default()
+        },
+    )
+    .await;
+    let tool = create_dynamic_tool(&DynamicToolFactory {
+        name: "orders".to_string(),
+        factory_type: "http_endpoint".to_string(),
+        config: json!({
+            "url": format!("{}/orders", server.uri())
+        }),
+        description: None,
+    })
+    .unwrap();
+    let call = |agent: &str| {
+        let context = ExecutorContext {
+            agent_id: agent.to_string(),
+            orchestrator: Some(harness.orchestrator.clone()),
+            ..Default::default()
+        };
+        tool.execute_with_executor_context(
+            ToolCall {
+                tool_call_id: "tc-1".into(),
+                tool_name: "orders".into(),
+                input: json!({}),
+            },
+            Arc::new(context),
+        )
+    };
+
+    // Only the restricted agent is blocked; the mock expects one request.
+    let denied = match call("sandboxed").await {
+        Err(AgentError::EgressDenied(denied)) => denied,
+        other => panic!("expected an egress denial, got {other:?}"),
+    };
+    assert_eq!(denied.agent.as_deref(), Some("sandboxed"));
+    assert_eq!(denied.host, "127.0.0.1");
+    call("other").await.unwrap();
+}
@@ -0,0 +1,94 @@
+use distri_types::ensemble::{EnsembleBranch, EnsembleConfig, EnsembleMode};
+use distri_types::{AgentEventType, EnsembleBranchOutcome};
+
+use crate::testing::{AgentTestHarness, MockLlmProvider, TestRun};
+use crate::types::StandardDefinition;
+
+async fn harness(llm: MockLlmProvider, branches: &[&str], mode: EnsembleMode) -> AgentTestHarness {
+    let harness = AgentTestHarness::new(llm).await.unwrap();
+    for name in ["analyst", "skeptic", "judge"] {
+        harness
+            .register_agent(StandardDefinition {
+                name: name.to_string(),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+    }
+    harness
+        .register_agent(StandardDefinition {
+            name: "panel".to_string(),
+            ensemble: Some(EnsembleConfig {
+                branches: branches
+                    .iter()
+                    .map(|agent| EnsembleBranch {
+                        agent: agent.to_string(),
+                        model: None,
+                    })
+                    .collect(),
+                judge: "judge".to_string(),
+                mode,
+            }),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    harness
+}
+
+/// `(branches, selected, rationale)` of the `EnsembleJudged` event.
+fn judged(run: &TestRun) -> (Vec<EnsembleBranchOutcome>, Option<usize>, Option<String>) {
+    run.events
+        .iter()
+        .find_map(|e| match &e.event {
+            AgentEventType::EnsembleJudged {
+                branches,
+                selected,
+                rationale,
+                ..
+            } => Some((branches.clone(), *selected, rationale.clone())),
+            _ => None,
+        })
+        .expect("an ensemble run emits EnsembleJudged")
+}
+
+#[tokio::test]
+async fn judge_merges_the_branch_answers() {
+    // Branches run concurrently, so which of them gets which reply is not fixed.
+    let llm = MockLlmProvider::new()
+        .respond_final("4")
+        .respond_final("four")
+        .respond_final(r#"{"selected": null, "rationale": "both agree", "answer": "4 (four)"}"#);
+    let harness = harness(llm.clone(), &["analyst", "skeptic"], EnsembleMode::Merge).await;
+
+    let run = harness.run("panel", "What is 2 + 2?").await;
+
+    run.assert_success();
+    llm.assert_exhausted();
+    assert_eq!(run.final_text(), Some("4 (four)"));
+    let (branches, selected, rationale) = judged(&run);
+    assert_eq!(selected, None);
+    assert_eq!(rationale.as_deref(), Some("both agree"));
+    let agents: Vec<&str> = branches.iter().map(|b| b.agent.as_str()).collect();
+    assert_eq!(agents, ["analyst", "skeptic"]);
+    assert!(branches.iter().all(|b| b.answered && b.task_id.is_some()));
+}
+
+#[tokio::test]
+async fn a_failed_branch_does_not_fail_the_ensemble() {
+    let llm = MockLlmProvider::new()
+        .respond_final("4")
+        .respond_final(r#"{"selected": 1, "rationale": "only answer"}"#);
+    let harness = harness(llm.clone(), &["missing", "analyst"], EnsembleMode::Pick).await;
+
+    let run = harness.run("panel", "What is 2 + 2?").await;
+
+    run.assert_success();
+    llm.assert_exhausted();
+    // Picking without an `answer` returns the chosen candidate's.
+    assert_eq!(run.final_text(), Some("4"));
+    let (branches, selected, _) = judged(&run);
+    assert!(!branches[0].answered && branches[0].error.is_some());
+    assert!(branches[1].answered);
+    assert_eq!(selected, Some(1));
+}
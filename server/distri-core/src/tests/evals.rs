@@ -0,0 +1,108 @@
+use std::sync::Arc;
+
+use distri_stores::FileEvalStore;
+use distri_types::evals::{short_hash, CompareEvalsRequest, EvalCase, EvalScore, EvalVerdict};
+use distri_types::ModelSettings;
+
+use crate::agent::evals::{compare_evals, eval_history, run_evals};
+use crate::testing::{AgentTestHarness, MockLlmProvider, MOCK_MODEL};
+use crate::types::StandardDefinition;
+use crate::AgentOrchestratorBuilder;
+
+fn support_agent(instructions: &str) -> StandardDefinition {
+    StandardDefinition {
+        name: "support".to_string(),
+        description: "answers billing questions".to_string(),
+        instructions: instructions.to_string(),
+        ..Default::default()
+    }
+}
+
+fn cases() -> Vec<EvalCase> {
+    ["refund", "invoice"]
+        .into_iter()
+        .map(|id| EvalCase {
+            id: id.to_string(),
+            task: format!("How do I get a {}?", id),
+            expected: None,
+        })
+        .collect()
+}
+
+fn mock_model() -> Option<ModelSettings> {
+    Some(ModelSettings {
+        model: MOCK_MODEL.to_string(),
+        inner: Default::default(),
+    })
+}
+
+#[tokio::test]
+async fn prompt_edit_is_judged_against_the_previous_version() {
+    let llm = MockLlmProvider::new()
+        .respond_final("Ask support.")
+        .respond_final("Ask support.")
+        .respond_final("Open Billing > Refunds and pick the order.")
+        .respond_final("Ask support.")
+        .respond_final(r#"{"winner": "B", "rationale": "B names the exact menu."}"#)
+        .respond_final("Both answers are the same.");
+    let dir = tempfile::tempdir().unwrap();
+    let builder = AgentOrchestratorBuilder::default()
+        .with_eval_store(Arc::new(FileEvalStore::new(dir.path())));
+    let harness = AgentTestHarness::from_builder(builder, llm.clone())
+        .await
+        .unwrap();
+    let orchestrator = harness.orchestrator.clone();
+
+    harness
+        .register_agent(support_agent("Help with billing."))
+        .await
+        .unwrap();
+    let before = run_evals(orchestrator.clone(), "support", cases(), None, mock_model())
+        .await
+        .unwrap();
+    harness
+        .register_agent(support_agent("Help with billing. Name the exact menu."))
+        .await
+        .unwrap();
+    let after = run_evals(orchestrator.clone(), "support", cases(), None, mock_model())
+        .await
+        .unwrap();
+    assert_ne!(before.definition_hash, after.definition_hash);
+
+    let request = CompareEvalsRequest {
+        baseline: short_hash(&before.definition_hash).to_string(),
+        candidate: after.id.clone(),
+    };
+    let comparison = compare_evals(
+        orchestrator.clone(),
+        "support",
+        &request,
+        None,
+        mock_model(),
+    )
+    .await
+    .unwrap();
+
+    llm.assert_exhausted();
+    assert_eq!(comparison.baseline.run_id, before.id);
+    assert_eq!(comparison.cases[0].verdict, EvalVerdict::Win);
+    assert_eq!(comparison.cases[0].rationale, "B names the exact menu.");
+    assert_eq!(comparison.cases[1].verdict, EvalVerdict::Tie);
+    assert_eq!(
+        comparison.score,
+        EvalScore {
+            wins: 1,
+            losses: 0,
+            ties: 1
+        }
+    );
+
+    let history = eval_history(orchestrator, "support").await.unwrap();
+    assert_eq!(history.runs.len(), 2);
+    assert_eq!(history.runs[0].score, None);
+    assert_eq!(
+        history.runs[1].compared_to.as_deref(),
+        Some(before.id.as_str())
+    );
+    assert_eq!(history.runs[1].score, Some(comparison.score));
+}
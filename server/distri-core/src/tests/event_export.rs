@@ -0,0 +1,104 @@
+//! `EventExporter`: run events reach the publisher in order, and a stalled
+//! broker drops events instead of slowing the run.
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use distri_types::event_export::{EventExportConfig, ExportMessage};
+
+use crate::agent::hooks::event_export::{partition_for, EventExporter, EventPublisher};
+use crate::agent::types::AgentHooks;
+use crate::testing::{AgentTestHarness, MockLlmProvider};
+use crate::types::StandardDefinition;
+use crate::AgentOrchestratorBuilder;
+
+#[derive(Default)]
+struct Recorder {
+    published: Mutex<Vec<ExportMessage>>,
+}
+
+#[async_trait::async_trait]
+impl EventPublisher for Recorder {
+    async fn publish(&self, batch: &[ExportMessage]) -> anyhow::Result<()> {
+        self.published.lock().unwrap().extend_from_slice(batch);
+        Ok(())
+    }
+}
+
+/// A broker that never acknowledges.
+struct Stalled;
+
+#[async_trait::async_trait]
+impl EventPublisher for Stalled {
+    async fn publish(&self, _batch: &[ExportMessage]) -> anyhow::Result<()> {
+        std::future::pending().await
+    }
+}
+
+fn config(yaml: &str) -> EventExportConfig {
+    serde_yaml::from_str(yaml).unwrap()
+}
+
+async fn harness(exporter: Arc<EventExporter>) -> AgentTestHarness {
+    let builder = AgentOrchestratorBuilder::default()
+        .with_system_hooks(vec![exporter as Arc<dyn AgentHooks>]);
+    let harness =
+        AgentTestHarness::from_builder(builder, MockLlmProvider::new().respond_final("done"))
+            .await
+            .unwrap();
+    harness
+        .register_agent(StandardDefinition {
+            name: "reporter".to_string(),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    harness
+}
+
+#[tokio::test]
+async fn run_lifecycle_events_are_published_in_order() {
+    let recorder = Arc::new(Recorder::default());
+    let exporter = EventExporter::with_publisher(
+        config("broker: { type: nats, url: 'nats://x' }\nevents: [run_started, run_finished]"),
+        recorder.clone(),
+    );
+    let harness = harness(exporter.clone()).await;
+
+    harness.run("reporter", "Report").await.assert_success();
+    let mut published = Vec::new();
+    for _ in 0..50 {
+        published = recorder.published.lock().unwrap().clone();
+        if published.len() >= 2 {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    let types: Vec<&str> = published.iter().map(|m| m.event_type.as_str()).collect();
+    assert_eq!(types, ["run_started", "run_finished"]);
+    assert_eq!(published[0].key, published[1].key);
+    assert_eq!(exporter.dropped(), 0);
+}
+
+#[tokio::test]
+async fn a_stalled_broker_drops_events_without_stalling_runs() {
+    let exporter = EventExporter::with_publisher(
+        config("broker: { type: nats, url: 'nats://x' }\nbuffer_size: 1\nbatch_size: 1"),
+        Arc::new(Stalled),
+    );
+    let harness = harness(exporter.clone()).await;
+
+    let run = tokio::time::timeout(Duration::from_secs(10), harness.run("reporter", "Report"))
+        .await
+        .expect("the run is not held up by the broker");
+    run.assert_success();
+    assert!(exporter.dropped() > 0);
+}
+
+#[test]
+fn threads_keep_their_partition() {
+    assert_eq!(partition_for("thread-1", 1), 0);
+    let partition = partition_for("thread-1", 8);
+    assert!((0..8).contains(&partition));
+    assert_eq!(partition_for("thread-1", 8), partition);
+}
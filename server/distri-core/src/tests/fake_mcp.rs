@@ -0,0 +1,138 @@
+//! `FakeMcpServer`: scripted tools reach an agent over the in-memory MCP
+//! transport, and calls are recorded and checked against expectations.
+
+use std::sync::Arc;
+
+use distri_types::{AgentEventType, Part};
+use serde_json::{json, Value};
+use tokio::sync::RwLock;
+
+use crate::servers::fake::{in_memory_tools, FakeMcpServer, FakeMcpTool, FakeResponse};
+use crate::servers::registry::McpServerRegistry;
+use crate::testing::{AgentTestHarness, MockLlmProvider, TestRun};
+use crate::types::StandardDefinition;
+
+fn search_server() -> FakeMcpServer {
+    FakeMcpServer::new().tool(
+        FakeMcpTool::new("search")
+            .description("Search the web")
+            .input_schema(json!({
+                "type": "object",
+                "properties": { "query": { "type": "string" } },
+                "required": ["query"]
+            }))
+            .expect_arguments(json!({ "query": "rust" }))
+            .respond(json!({ "results": [{ "title": "Rust" }] })),
+    )
+}
+
+async fn researcher(llm: MockLlmProvider, server: &FakeMcpServer) -> AgentTestHarness {
+    let harness = AgentTestHarness::new(llm).await.unwrap();
+    harness
+        .register_agent(StandardDefinition {
+            name: "researcher".to_string(),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    server
+        .attach(&harness.orchestrator, "web_search", "researcher")
+        .await
+        .unwrap();
+    harness
+}
+
+fn tool_result_text(run: &TestRun) -> String {
+    run.events
+        .iter()
+        .filter_map(|e| match &e.event {
+            AgentEventType::ToolResults { results, .. } => Some(results),
+            _ => None,
+        })
+        .flatten()
+        .flat_map(|r| r.parts.clone())
+        .filter_map(|p| match p {
+            Part::Text(text) => Some(text),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[tokio::test]
+async fn agent_calls_a_scripted_mcp_tool() {
+    let server = search_server();
+    let llm = MockLlmProvider::new()
+        .respond_tool_call("search", json!({ "query": "rust" }))
+        .respond_final("Found Rust.");
+    let harness = researcher(llm.clone(), &server).await;
+
+    let run = harness.run("researcher", "Search for rust").await;
+    run.assert_success();
+    assert!(llm.requests()[0].tool_names.contains(&"search".to_string()));
+    assert_eq!(tool_result_text(&run), r#"{"results":[{"title":"Rust"}]}"#);
+    assert_eq!(
+        server.calls_to("search")[0].arguments,
+        json!({ "query": "rust" })
+    );
+    server.assert_satisfied();
+}
+
+#[tokio::test]
+async fn unexpected_arguments_fail_the_call_and_are_reported() {
+    let server = search_server();
+    let llm = MockLlmProvider::new()
+        .respond_tool_call("search", json!({ "query": "go" }))
+        .respond_final("Nothing.");
+    let harness = researcher(llm, &server).await;
+
+    let run = harness.run("researcher", "Search for go").await;
+    assert!(tool_result_text(&run).contains("returned error"));
+    let failures = server.failures();
+    assert_eq!(failures.len(), 1);
+    assert!(
+        failures[0].contains(r#"called with {"query":"go"}"#),
+        "{failures:?}"
+    );
+}
+
+#[tokio::test]
+async fn scripts_run_in_order_then_fall_back_to_the_handler() {
+    let server = FakeMcpServer::new().tool(
+        FakeMcpTool::new("search")
+            .respond_error("rate limited")
+            .respond(json!("first"))
+            .respond_with(|args: &Value| FakeResponse::Json(args["query"].clone())),
+    );
+    let llm = MockLlmProvider::new()
+        .respond_tool_call("search", json!({ "query": "a" }))
+        .respond_tool_call("search", json!({ "query": "b" }))
+        .respond_tool_call("search", json!({ "query": "c" }))
+        .respond_final("done");
+    let harness = researcher(llm, &server).await;
+
+    let run = harness.run("researcher", "Search three times").await;
+    let results: Vec<String> = tool_result_text(&run).lines().map(str::to_string).collect();
+    assert_eq!(results.len(), 3, "{results:?}");
+    assert!(results[0].contains("rate limited"), "{results:?}");
+    assert_eq!(results[1..], ["first", "c"]);
+    assert_eq!(server.calls().len(), 3);
+    server.assert_satisfied();
+}
+
+#[tokio::test]
+async fn registered_servers_list_their_tools_over_the_transport() {
+    let server = search_server();
+    let mut registry = McpServerRegistry::new();
+    registry.register("web_search".to_string(), server.metadata());
+    let tools = in_memory_tools(Arc::new(RwLock::new(registry)), "web_search")
+        .await
+        .unwrap();
+
+    assert_eq!(tools.len(), 1);
+    assert_eq!(tools[0].get_name(), "search");
+    assert_eq!(tools[0].get_description(), "Search the web");
+    assert_eq!(tools[0].get_parameters()["required"], json!(["query"]));
+    assert!(tools[0].is_mcp());
+    assert!(server.calls().is_empty());
+}
@@ -0,0 +1,130 @@
+use distri_types::handoff::{HandoffConfig, HandoffIntent, HandoffRecord, THREAD_HANDOFFS_KEY};
+use distri_types::AgentEventType;
+
+use crate::testing::{AgentTestHarness, MockLlmProvider, TestRun};
+use crate::types::StandardDefinition;
+
+async fn harness(llm: MockLlmProvider, handoff: HandoffConfig) -> AgentTestHarness {
+    let harness = AgentTestHarness::new(llm).await.unwrap();
+    harness
+        .register_agent(StandardDefinition {
+            name: "billing".to_string(),
+            description: "Refunds and invoices".to_string(),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    harness
+        .register_agent(StandardDefinition {
+            name: "support".to_string(),
+            handoff: Some(handoff),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    harness
+}
+
+fn handovers(run: &TestRun) -> Vec<(String, String)> {
+    run.events
+        .iter()
+        .filter_map(|e| match &e.event {
+            AgentEventType::AgentHandover {
+                from_agent,
+                to_agent,
+                ..
+            } => Some((from_agent.clone(), to_agent.clone())),
+            _ => None,
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn a_matching_intent_hands_the_request_off() {
+    let llm = MockLlmProvider::new().respond_final("Your refund is on its way.");
+    let harness = harness(
+        llm.clone(),
+        HandoffConfig {
+            intents: vec![HandoffIntent {
+                agent: "billing".to_string(),
+                keywords: vec!["refund".to_string()],
+            }],
+            confirm: false,
+            ..Default::default()
+        },
+    )
+    .await;
+
+    let run = harness.run("support", "I want a refund for order 12").await;
+
+    run.assert_success();
+    llm.assert_exhausted();
+    assert_eq!(run.final_text(), Some("Your refund is on its way."));
+    assert_eq!(llm.requests()[0].agent_id, "billing");
+    assert_eq!(
+        handovers(&run),
+        [("support".to_string(), "billing".to_string())]
+    );
+
+    let thread = harness
+        .orchestrator
+        .get_thread(&run.thread_id)
+        .await
+        .unwrap()
+        .unwrap();
+    let records: Vec<HandoffRecord> =
+        serde_json::from_value(thread.metadata[THREAD_HANDOFFS_KEY].clone()).unwrap();
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].to_agent, "billing");
+    assert!(records[0].accepted);
+}
+
+#[tokio::test]
+async fn the_classifier_hands_off_only_to_a_candidate() {
+    let llm = MockLlmProvider::new()
+        .respond_text(r#"{"agent": "billing", "missing": ["issue_refund"]}"#)
+        .respond_final("Refund issued.");
+    let harness = harness(
+        llm.clone(),
+        HandoffConfig {
+            agents: vec!["*".to_string()],
+            confirm: false,
+            ..Default::default()
+        },
+    )
+    .await;
+
+    let run = harness
+        .run("support", "Please pay me back for order 12")
+        .await;
+
+    run.assert_success();
+    llm.assert_exhausted();
+    assert_eq!(run.final_text(), Some("Refund issued."));
+    let brief = format!("{:?}", llm.requests()[0].messages);
+    assert!(brief.contains("billing: Refunds and invoices"), "{brief}");
+}
+
+#[tokio::test]
+async fn the_agent_keeps_a_request_it_can_handle() {
+    let llm = MockLlmProvider::new()
+        .respond_text(r#"{"agent": null}"#)
+        .respond_final("Tracking has the latest estimate.");
+    let harness = harness(
+        llm.clone(),
+        HandoffConfig {
+            agents: vec!["billing".to_string()],
+            confirm: false,
+            ..Default::default()
+        },
+    )
+    .await;
+
+    let run = harness.run("support", "Where is my order?").await;
+
+    run.assert_success();
+    llm.assert_exhausted();
+    assert_eq!(run.final_text(), Some("Tracking has the latest estimate."));
+    assert_eq!(llm.requests()[1].agent_id, "support");
+    assert!(handovers(&run).is_empty());
+}
@@ -0,0 +1,138 @@
+use distri_types::handover_summary::HandoverSummary;
+use distri_types::{AgentEventType, ModelSettings, ToolsConfig};
+use serde_json::json;
+
+use crate::testing::{AgentTestHarness, MockLlmProvider, MOCK_MODEL};
+use crate::types::StandardDefinition;
+
+fn summary_reply(blocker: &str) -> String {
+    json!({
+        "customer_intent": "Get a refund for order 42",
+        "steps_taken": ["Checked the refund policy"],
+        "current_blocker": blocker,
+    })
+    .to_string()
+}
+
+async fn harness(llm: MockLlmProvider) -> AgentTestHarness {
+    let harness = AgentTestHarness::new(llm).await.unwrap();
+    harness
+        .register_agent(StandardDefinition {
+            name: "support".to_string(),
+            model_settings: Some(ModelSettings {
+                model: MOCK_MODEL.to_string(),
+                inner: Default::default(),
+            }),
+            tools: Some(ToolsConfig {
+                builtin: vec!["summarize_thread".to_string()],
+                ..Default::default()
+            }),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    harness
+}
+
+#[tokio::test]
+async fn the_summary_is_cached_until_the_thread_changes() {
+    let llm = MockLlmProvider::new()
+        .respond_final("Refunds over $100 need a manager.")
+        .respond_text(summary_reply("Needs a manager's approval"))
+        .respond_text(summary_reply("none"))
+        .respond_final("A manager will follow up.")
+        .respond_text(summary_reply("Waiting on the manager"));
+    let harness = harness(llm.clone()).await;
+    let run = harness
+        .run("support", "I want a refund of $150 for order 42")
+        .await;
+    run.assert_success();
+
+    let first = harness
+        .orchestrator
+        .handover_summary(&run.thread_id, false)
+        .await
+        .unwrap();
+    assert_eq!(first.customer_intent, "Get a refund for order 42");
+    assert_eq!(first.steps_taken, ["Checked the refund policy"]);
+    assert!(first.tools_used.is_empty(), "{:?}", first.tools_used);
+    assert_eq!(
+        first.current_blocker.as_deref(),
+        Some("Needs a manager's approval")
+    );
+
+    // Same thread version: served from the thread metadata.
+    let calls = llm.requests().len();
+    let cached = harness
+        .orchestrator
+        .handover_summary(&run.thread_id, false)
+        .await
+        .unwrap();
+    assert_eq!(cached, first);
+    assert_eq!(llm.requests().len(), calls);
+
+    let refreshed = harness
+        .orchestrator
+        .handover_summary(&run.thread_id, true)
+        .await
+        .unwrap();
+    assert_eq!(refreshed.current_blocker, None);
+    assert_eq!(refreshed.thread_version, first.thread_version);
+
+    harness
+        .run_on_thread("support", &run.thread_id, "Can it be faster?")
+        .await
+        .assert_success();
+    let updated = harness
+        .orchestrator
+        .handover_summary(&run.thread_id, false)
+        .await
+        .unwrap();
+    assert_ne!(updated.thread_version, first.thread_version);
+    assert_eq!(
+        updated.current_blocker.as_deref(),
+        Some("Waiting on the manager")
+    );
+}
+
+#[tokio::test]
+async fn the_agent_summarizes_its_thread_with_the_tool() {
+    let llm = MockLlmProvider::new()
+        .respond_tool_call("summarize_thread", json!({}))
+        .respond_text(summary_reply("Needs a manager's approval"))
+        .respond_final("Handing you over to a colleague.");
+    let harness = harness(llm).await;
+
+    let run = harness
+        .run("support", "I want a refund of $150 for order 42")
+        .await;
+
+    run.assert_success();
+    let summaries: Vec<HandoverSummary> = run
+        .events
+        .iter()
+        .filter_map(|e| match &e.event {
+            AgentEventType::ToolResults { results, .. } => Some(results),
+            _ => None,
+        })
+        .flatten()
+        .filter_map(|r| r.structured_output())
+        .filter_map(|v| serde_json::from_value(v).ok())
+        .collect();
+    assert_eq!(summaries.len(), 1, "{:?}", run.events);
+    assert_eq!(summaries[0].thread_id, run.thread_id);
+    assert_eq!(summaries[0].customer_intent, "Get a refund for order 42");
+}
+
+#[tokio::test]
+async fn an_unknown_thread_is_not_found() {
+    let harness = harness(MockLlmProvider::new()).await;
+
+    let err = harness
+        .orchestrator
+        .handover_summary("missing", false)
+        .await
+        .unwrap_err();
+
+    assert!(matches!(err, crate::AgentError::NotFound(_)), "{err:?}");
+}
@@ -7,7 +7,7 @@
 
 use std::sync::{Arc, Mutex};
 
-use distri_types::configuration::{DbConnectionConfig, MetadataStoreConfig, StoreConfig};
+use distri_types::configuration::StoreConfig;
 
 use crate::agent::ExecutorContext;
 use crate::llm::LLMResponse;
@@ -21,18 +21,7 @@ use crate::AgentOrchestratorBuilder;
 /// Each call produces a fresh DB (keyed by a random UUID) so tests never
 /// share state.
 pub fn test_store_config() -> StoreConfig {
-    let db_name = uuid::Uuid::new_v4();
-    let db_url = format!("file:{}?mode=memory&cache=shared", db_name);
-    StoreConfig {
-        metadata: MetadataStoreConfig {
-            db_config: Some(DbConnectionConfig {
-                database_url: db_url,
-                ..Default::default()
-            }),
-            ..Default::default()
-        },
-        ..Default::default()
-    }
+    crate::testing::in_memory_store_config()
 }
 
 // ── ExecutorContext builders ─────────────────────────────────────────────────
@@ -0,0 +1,114 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use distri_types::hibernation::HibernationConfig;
+use distri_types::warm_sessions::WarmSessionsConfig;
+
+use crate::agent::hibernation::{Hibernator, ThreadResources};
+use crate::servers::McpClientPool;
+use crate::tests::helpers::test_store_config;
+use crate::AgentOrchestratorBuilder;
+
+fn empty_pool() -> Option<Arc<McpClientPool>> {
+    Some(Arc::new(McpClientPool::default()))
+}
+
+#[tokio::test]
+async fn mcp_pool_is_reused_until_the_thread_goes_idle() {
+    let resources = Arc::new(ThreadResources::default());
+    let first = resources
+        .mcp_pool("t1", async { empty_pool() })
+        .await
+        .unwrap();
+    let second = resources.mcp_pool("t1", async { None }).await.unwrap();
+    assert!(Arc::ptr_eq(&first, &second));
+
+    let idle = resources.take_idle(Duration::ZERO);
+    assert_eq!(idle.len(), 1);
+    assert!(resources.is_hibernated("t1"));
+
+    let rebuilt = resources
+        .mcp_pool("t1", async { empty_pool() })
+        .await
+        .unwrap();
+    assert!(!Arc::ptr_eq(&first, &rebuilt));
+    assert!(!resources.is_hibernated("t1"), "next use wakes the thread");
+}
+
+#[tokio::test]
+async fn threads_with_a_run_in_progress_are_not_hibernated() {
+    let resources = Arc::new(ThreadResources::default());
+    let run = resources.enter("busy");
+    resources.set_browser_session("busy", "session-1".to_string());
+    assert!(resources.take_idle(Duration::ZERO).is_empty());
+
+    drop(run);
+    let idle = resources.take_idle(Duration::ZERO);
+    assert_eq!(idle.len(), 1);
+    assert_eq!(idle[0].browser_session_id.as_deref(), Some("session-1"));
+
+    resources.enter("busy");
+    assert!(!resources.is_hibernated("busy"));
+    assert_eq!(resources.browser_session("busy"), None);
+}
+
+#[tokio::test]
+async fn sweep_hibernates_only_idle_threads_holding_resources() {
+    let orchestrator = Arc::new(
+        AgentOrchestratorBuilder::default()
+            .with_store_config(test_store_config())
+            .with_hibernation(Some(HibernationConfig {
+                idle_secs: 0,
+                sweep_interval_secs: 60,
+            }))
+            .build()
+            .await
+            .unwrap(),
+    );
+    let resources = orchestrator.thread_resources.clone();
+    resources
+        .mcp_pool("with-pool", async { empty_pool() })
+        .await;
+    drop(resources.enter("bare"));
+
+    let hibernator = Hibernator::new(orchestrator.clone()).expect("hibernation configured");
+    assert_eq!(hibernator.sweep().await, 1);
+    assert!(resources.is_hibernated("with-pool"));
+    assert!(!resources.is_hibernated("bare"));
+    assert_eq!(hibernator.sweep().await, 0);
+}
+
+#[tokio::test]
+async fn hibernator_requires_configuration() {
+    let orchestrator = AgentOrchestratorBuilder::default()
+        .with_store_config(test_store_config())
+        .build()
+        .await
+        .unwrap();
+    assert!(Hibernator::new(Arc::new(orchestrator)).is_none());
+}
+
+#[tokio::test]
+async fn pinned_warm_sessions_are_not_hibernated() {
+    let orchestrator = Arc::new(
+        AgentOrchestratorBuilder::default()
+            .with_store_config(test_store_config())
+            .with_hibernation(Some(HibernationConfig {
+                idle_secs: 0,
+                sweep_interval_secs: 60,
+            }))
+            .with_warm_sessions(Some(WarmSessionsConfig::default()))
+            .build()
+            .await
+            .unwrap(),
+    );
+    let resources = orchestrator.thread_resources.clone();
+    resources.mcp_pool("pinned", async { empty_pool() }).await;
+    resources.mcp_pool("unpinned", async { empty_pool() }).await;
+    orchestrator.warm_sessions.as_ref().unwrap().pin("pinned");
+
+    let hibernator = Hibernator::new(orchestrator.clone()).expect("hibernation configured");
+    assert_eq!(hibernator.sweep().await, 1);
+    assert!(!resources.is_hibernated("pinned"));
+    assert!(resources.is_hibernated("unpinned"));
+}
@@ -0,0 +1,143 @@
+//! `http_endpoint` dynamic tools: `$VAR_NAME` bindings in the config are
+//! resolved, model input is sent as given, and both sides are checked
+//! against their schemas.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use distri_types::dynamic_tool::DynamicToolFactory;
+use distri_types::{Part, ToolCall};
+use serde_json::{json, Value};
+use wiremock::matchers::{body_json, header, method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+use crate::agent::ExecutorContext;
+use crate::tools::dynamic_factory::{create_dynamic_tool, validate_dynamic_tool};
+use crate::AgentError;
+
+fn factory(config: Value) -> DynamicToolFactory {
+    DynamicToolFactory {
+        name: "orders".to_string(),
+        factory_type: "http_endpoint".to_string(),
+        config,
+        description: Some("Order API".to_string()),
+    }
+}
+
+async fn call(factory: &DynamicToolFactory, input: Value) -> Result<Value, AgentError> {
+    let tool = create_dynamic_tool(factory).expect("factory ok");
+    let context = ExecutorContext {
+        env_vars: Arc::new(tokio::sync::RwLock::new(HashMap::from([(
+            "ORDERS_TOKEN".to_string(),
+            "secret-token".to_string(),
+        )]))),
+        ..Default::default()
+    };
+    let parts = tool
+        .execute_with_executor_context(
+            ToolCall {
+                tool_call_id: "tc-1".into(),
+                tool_name: "orders".into(),
+                input,
+            },
+            Arc::new(context),
+        )
+        .await?;
+    match parts.into_iter().next() {
+        Some(Part::Data(data)) => Ok(data),
+        other => panic!("expected a data part, got {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn get_resolves_bindings_and_fills_the_url() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/orders/42"))
+        .and(query_param("expand", "true"))
+        .and(header("authorization", "Bearer secret-token"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "status": "shipped" })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let factory = factory(json!({
+        "url": format!("{}/orders/{{id}}", server.uri()),
+        "headers": { "Authorization": "Bearer $ORDERS_TOKEN" },
+        "parameters": {
+            "type": "object",
+            "required": ["id"],
+            "properties": { "id": { "type": "string" }, "expand": { "type": "boolean" } }
+        },
+        "response": { "type": "object", "required": ["status"] }
+    }));
+    let tool = create_dynamic_tool(&factory).unwrap();
+    assert_eq!(tool.get_description(), "Order API");
+    assert_eq!(tool.get_parameters()["required"], json!(["id"]));
+
+    let result = call(&factory, json!({ "id": "42", "expand": true }))
+        .await
+        .unwrap();
+    assert_eq!(result["status"], 200);
+    assert_eq!(result["body"]["status"], "shipped");
+}
+
+#[tokio::test]
+async fn post_sends_input_unresolved_and_checks_the_response() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/orders/42/notes"))
+        .and(body_json(json!({ "note": "$ORDERS_TOKEN" })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "id": 7 })))
+        .mount(&server)
+        .await;
+
+    let factory = factory(json!({
+        "method": "POST",
+        "url": format!("{}/orders/{{id}}/notes", server.uri()),
+        "parameters": {
+            "type": "object",
+            "properties": { "id": { "type": "string" }, "note": { "type": "string" } }
+        },
+        "response": { "type": "object", "required": ["note_id"] }
+    }));
+
+    // The model's `$ORDERS_TOKEN` reaches the API verbatim, and the reply
+    // lacks the declared `note_id`.
+    let err = call(&factory, json!({ "id": "42", "note": "$ORDERS_TOKEN" }))
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("response schema"), "{err}");
+}
+
+#[tokio::test]
+async fn invalid_input_never_reaches_the_api() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(0)
+        .mount(&server)
+        .await;
+
+    let factory = factory(json!({
+        "url": format!("{}/orders/{{id}}", server.uri()),
+        "parameters": {
+            "type": "object",
+            "required": ["id"],
+            "properties": { "id": { "type": "string" } }
+        }
+    }));
+    let err = call(&factory, json!({ "id": 42 })).await.unwrap_err();
+    assert!(err.to_string().contains("invalid input"), "{err}");
+}
+
+#[test]
+fn undeclared_placeholders_fail_validation() {
+    let factory = factory(json!({
+        "url": "https://api.example.com/orders/{order_id}",
+        "parameters": { "type": "object", "properties": { "id": { "type": "string" } } }
+    }));
+    let err = validate_dynamic_tool(&factory).unwrap_err();
+    assert!(err.to_string().contains("{order_id}"), "{err}");
+    assert!(create_dynamic_tool(&factory).is_err());
+}
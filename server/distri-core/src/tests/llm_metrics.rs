@@ -0,0 +1,32 @@
+use crate::testing::{AgentTestHarness, MockLlmProvider, MOCK_MODEL};
+use crate::types::StandardDefinition;
+
+#[tokio::test]
+async fn every_llm_call_of_a_run_is_timed() {
+    let harness = AgentTestHarness::new(MockLlmProvider::new().respond_final("done"))
+        .await
+        .unwrap();
+    harness
+        .register_agent(StandardDefinition {
+            name: "timed".to_string(),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+    harness.run("timed", "Hi").await.assert_success();
+
+    let stats = harness.orchestrator.llm_metrics.snapshot();
+    let Some(model) = stats.iter().find(|s| s.model == MOCK_MODEL) else {
+        panic!("no calls of the mock model in {stats:?}");
+    };
+    assert!(model.calls >= 1);
+    assert_eq!(model.errors, 0);
+    assert!(model.duration.is_some());
+    assert!(model.time_to_first_token.is_some());
+    assert!(harness
+        .orchestrator
+        .llm_metrics
+        .prometheus()
+        .contains("distri_llm_calls_total{provider="));
+}
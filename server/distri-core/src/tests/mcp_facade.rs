@@ -0,0 +1,189 @@
+//! Agents as MCP tools: `tools/list` lists the registered agents, and
+//! `tools/call` runs one, streaming progress when the caller asks for it.
+
+use std::sync::Arc;
+
+use distri_types::{Part, Tool, ToolCall, ToolContext};
+use futures_util::StreamExt;
+use serde_json::{json, Value};
+
+use crate::mcp_facade::{McpFacade, McpReply, McpRequest, THREAD_META_KEY};
+use crate::testing::{AgentTestHarness, MockLlmProvider, MOCK_MODEL};
+use crate::types::{ModelSettings, StandardDefinition};
+
+#[derive(Debug)]
+struct LookupTool;
+
+#[async_trait::async_trait]
+impl Tool for LookupTool {
+    fn get_name(&self) -> String {
+        "lookup".to_string()
+    }
+
+    fn get_description(&self) -> String {
+        "Look up a city's forecast".to_string()
+    }
+
+    fn get_parameters(&self) -> Value {
+        json!({ "type": "object" })
+    }
+
+    async fn execute(
+        &self,
+        _tool_call: ToolCall,
+        _context: Arc<ToolContext>,
+    ) -> Result<Vec<Part>, anyhow::Error> {
+        Ok(vec![Part::Text("sunny, 21°C".to_string())])
+    }
+}
+
+async fn setup(llm: MockLlmProvider) -> (AgentTestHarness, McpFacade) {
+    let harness = AgentTestHarness::new(llm).await.unwrap();
+    harness
+        .register_agent(StandardDefinition {
+            name: "weather".to_string(),
+            description: "Answers questions about the weather".to_string(),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    harness
+        .orchestrator
+        .register_tool("weather", Arc::new(LookupTool))
+        .await;
+    let facade = McpFacade::new(harness.orchestrator.clone(), "mcp-user")
+        .with_default_model_settings(Some(ModelSettings {
+            model: MOCK_MODEL.to_string(),
+            inner: Default::default(),
+        }));
+    (harness, facade)
+}
+
+fn request(id: u64, method: &str, params: Value) -> McpRequest {
+    serde_json::from_value(
+        json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params }),
+    )
+    .unwrap()
+}
+
+async fn respond(facade: &McpFacade, request: McpRequest) -> Value {
+    match facade.handle(request).await {
+        McpReply::Response(response) => serde_json::to_value(response).unwrap(),
+        _ => panic!("expected a plain response"),
+    }
+}
+
+#[tokio::test]
+async fn agents_are_listed_and_called_as_tools() {
+    let llm = MockLlmProvider::new()
+        .respond_final("Sunny all weekend.")
+        .respond_final("Still sunny.");
+    let (_harness, facade) = setup(llm.clone()).await;
+
+    let init = respond(
+        &facade,
+        request(1, "initialize", json!({ "protocolVersion": "2025-03-26" })),
+    )
+    .await;
+    assert_eq!(init["result"]["protocolVersion"], "2025-03-26");
+    assert!(init["result"]["capabilities"]["tools"].is_object());
+    let notification: McpRequest =
+        serde_json::from_value(json!({ "jsonrpc": "2.0", "method": "notifications/initialized" }))
+            .unwrap();
+    assert!(matches!(
+        facade.handle(notification).await,
+        McpReply::Accepted
+    ));
+
+    let tools = respond(&facade, request(2, "tools/list", json!({}))).await;
+    let weather = tools["result"]["tools"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|t| t["name"] == "weather")
+        .unwrap()
+        .clone();
+    assert_eq!(
+        weather["description"],
+        "Answers questions about the weather"
+    );
+    assert_eq!(weather["inputSchema"]["required"], json!(["message"]));
+
+    let call = respond(
+        &facade,
+        request(
+            3,
+            "tools/call",
+            json!({ "name": "weather", "arguments": { "message": "Weekend forecast?" } }),
+        ),
+    )
+    .await;
+    let result = &call["result"];
+    assert_eq!(result["isError"], false);
+    assert_eq!(result["content"][0]["text"], "Sunny all weekend.");
+
+    // The returned thread continues the conversation.
+    let thread_id = result["_meta"][THREAD_META_KEY].as_str().unwrap();
+    let call = respond(
+        &facade,
+        request(
+            4,
+            "tools/call",
+            json!({
+                "name": "weather",
+                "arguments": { "message": "And Monday?", "thread_id": thread_id }
+            }),
+        ),
+    )
+    .await;
+    assert_eq!(call["result"]["_meta"][THREAD_META_KEY], thread_id);
+    assert!(format!("{:?}", llm.requests()[1].messages).contains("Weekend forecast?"));
+
+    let unknown = respond(
+        &facade,
+        request(5, "tools/call", json!({ "name": "nope", "arguments": {} })),
+    )
+    .await;
+    assert_eq!(unknown["error"]["code"], -32602);
+    let method = respond(&facade, request(6, "resources/list", json!({}))).await;
+    assert_eq!(method["error"]["code"], -32601);
+}
+
+#[tokio::test]
+async fn calls_with_a_progress_token_stream_progress() {
+    let llm = MockLlmProvider::new()
+        .respond_tool_call("lookup", json!({ "city": "Lisbon" }))
+        .respond_final("Sunny, 21°C.");
+    let (_harness, facade) = setup(llm).await;
+
+    let reply = facade
+        .handle(request(
+            7,
+            "tools/call",
+            json!({
+                "name": "weather",
+                "arguments": { "message": "Weather in Lisbon?" },
+                "_meta": { "progressToken": "tok-1" }
+            }),
+        ))
+        .await;
+    let McpReply::Stream(stream) = reply else {
+        panic!("expected a stream");
+    };
+    let messages: Vec<Value> = stream.collect().await;
+
+    let (response, notifications) = messages.split_last().unwrap();
+    assert_eq!(response["id"], 7);
+    assert_eq!(response["result"]["content"][0]["text"], "Sunny, 21°C.");
+    assert!(notifications.iter().all(
+        |n| n["method"] == "notifications/progress" && n["params"]["progressToken"] == "tok-1"
+    ));
+    let progress: Vec<u64> = notifications
+        .iter()
+        .map(|n| n["params"]["progress"].as_u64().unwrap())
+        .collect();
+    assert!(progress.windows(2).all(|w| w[0] < w[1]));
+    assert!(notifications
+        .iter()
+        .any(|n| n["params"]["message"] == "Calling lookup"));
+}
@@ -0,0 +1,94 @@
+//! Memory grants: the memory summary of an agent's prompt only lists the
+//! namespaces its `[memory]` section lets it read.
+
+use distri_types::configuration::MemoryStoreConfig;
+use distri_types::memory::{MemoryAccessConfig, MemoryGrant, MemoryIdentity, ScopedMemory};
+use distri_types::prompt::{PromptLayersConfig, RuntimeContextItem};
+use distri_types::stores::SessionMemory;
+
+use crate::testing::{in_memory_store_config, AgentTestHarness, MockLlmProvider, TEST_USER_ID};
+use crate::types::StandardDefinition;
+use crate::AgentOrchestratorBuilder;
+
+fn memory(summary: &str) -> SessionMemory {
+    SessionMemory {
+        agent_id: "research".to_string(),
+        thread_id: "t".to_string(),
+        session_summary: summary.to_string(),
+        key_insights: vec![],
+        important_facts: vec![],
+        timestamp: chrono::Utc::now(),
+    }
+}
+
+fn agent(name: &str, memory: Option<MemoryAccessConfig>) -> StandardDefinition {
+    StandardDefinition {
+        name: name.to_string(),
+        description: "answers questions".to_string(),
+        prompt_layers: Some(PromptLayersConfig {
+            runtime: vec![RuntimeContextItem::MemorySummary],
+            ..Default::default()
+        }),
+        memory,
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn agents_only_see_the_memories_they_may_read() {
+    let mut config = in_memory_store_config();
+    config.memory = Some(MemoryStoreConfig {
+        db_config: config.metadata.db_config.clone(),
+        ..Default::default()
+    });
+    let stores = crate::initialize_stores(&config).await.unwrap();
+    let store = stores.memory_store.clone().expect("memory store");
+
+    let llm = MockLlmProvider::new()
+        .respond_final("ok")
+        .respond_final("ok");
+    let harness = AgentTestHarness::from_builder(
+        AgentOrchestratorBuilder::default().with_stores(stores),
+        llm.clone(),
+    )
+    .await
+    .unwrap();
+
+    let team_only = MemoryAccessConfig {
+        read: vec![MemoryGrant::team("analysts")],
+        write: vec![MemoryGrant::team("analysts")],
+    };
+    ScopedMemory::new(
+        store.as_ref(),
+        team_only.clone(),
+        MemoryIdentity {
+            user_id: TEST_USER_ID.to_string(),
+            agent_id: "research".to_string(),
+            workspace_id: None,
+        },
+    )
+    .store(&MemoryGrant::team("analysts"), memory("churn is up"))
+    .await
+    .unwrap();
+    store
+        .store_memory(TEST_USER_ID, memory("prefers metric units"))
+        .await
+        .unwrap();
+
+    harness
+        .register_agent(agent("writer", Some(team_only)))
+        .await
+        .unwrap();
+    harness.register_agent(agent("plain", None)).await.unwrap();
+
+    harness.run("writer", "Hi").await.assert_success();
+    let prompt = format!("{:?}", llm.requests()[0].messages);
+    assert!(prompt.contains("churn is up"), "{prompt}");
+    assert!(!prompt.contains("prefers metric units"), "{prompt}");
+
+    // Without a `[memory]` section only the user's memories are read.
+    harness.run("plain", "Hi").await.assert_success();
+    let prompt = format!("{:?}", llm.requests()[1].messages);
+    assert!(prompt.contains("prefers metric units"), "{prompt}");
+    assert!(!prompt.contains("churn is up"), "{prompt}");
+}
@@ -213,6 +213,8 @@ impl MockLLM {
             finish_reason: response.finish_reason,
             tool_calls: response.tool_calls,
             content: response.content,
+            usage: response.usage,
+            first_token_at: Some(std::time::Instant::now()),
         })
     }
 
@@ -1,30 +1,75 @@
 mod a2a_service;
+mod agent_registry;
 mod agent_loop;
 mod agent_loop_store_integration;
+mod auth_consent;
 mod browser_sessions;
+mod browser_tabs;
 mod cancel_cascade;
+mod capability_probe;
 mod compaction_in_loop;
 mod compaction_integration;
+mod conversation_import;
 mod coordinator_integration;
+mod critique;
+mod datetime;
 mod deferred_tools_integration;
 mod definition;
+mod dev_seed;
+mod dry_run;
 mod early_stop;
+mod egress;
+mod ensemble;
+mod evals;
+mod event_export;
+mod fake_mcp;
 mod fixture_scenarios;
+mod handoff;
+mod handover_summary;
 pub mod helpers;
+mod http_endpoint;
+mod hibernation;
+mod injection_guard;
 mod invoke_agent_tool;
 mod invoke_entry;
 mod llm;
+mod llm_metrics;
 mod llm_service_subtask;
+mod mcp_facade;
+mod memory_access;
 pub mod mock_llm;
 mod mock_tool;
 mod orchestrator;
+mod output_sinks;
 pub mod otel_hooks_test;
+mod plugin_capabilities;
+mod plugin_storage;
 mod preload_skills;
+mod prompt_locale;
+mod regenerate;
 mod remote_agent;
 mod request_tool;
+mod secret_refs;
+mod structured_stream;
 mod supervisor_tools;
+mod task_diff;
+mod task_retry;
+mod thread_archive;
+mod thread_artifacts;
+mod thread_variables;
+mod todo_queue;
+mod tool_catalog;
+mod tool_output_schema;
+mod tool_recovery;
+mod tool_renderers;
 mod tool_result_format;
 mod tool_result_persistence;
+mod tool_timeouts;
 pub mod trace_replay;
 mod universal_agent_access;
 mod usage_tracking;
+mod user_profile;
+mod user_quotas;
+mod warm_sessions;
+mod working_memory;
+mod workspace_packages;
@@ -210,3 +210,76 @@ async fn test_merge_agent_explicit_provider_overrides_workspace() {
         "agent's explicit provider should be used"
     );
 }
+
+#[tokio::test]
+async fn test_message_overrides_flow_into_model_settings() {
+    let agent_md = r#"---
+name = "toggle_agent"
+description = "Agent with a creative/precise toggle"
+instructions = "You are a test agent."
+
+[model_settings]
+model = "gpt-4.1"
+temperature = 0.3
+
+[message_overrides]
+models = ["gpt-4.1-mini"]
+temperature = { min = 0.0, max = 1.2 }
+reasoning_efforts = ["low", "high"]
+---
+"#;
+    let def = parse_agent_markdown_content(agent_md).await.unwrap();
+    let agent = AgentConfig::StandardAgent(def);
+    let params = |configuration: serde_json::Value| -> distri_a2a::MessageSendParams {
+        serde_json::from_value(serde_json::json!({
+            "message": {
+                "kind": "message",
+                "messageId": "m-1",
+                "role": "user",
+                "parts": [{ "kind": "text", "text": "write a poem" }],
+            },
+            "configuration": configuration,
+        }))
+        .unwrap()
+    };
+
+    let requested = crate::a2a::stream::message_model_overrides(
+        Some(&agent),
+        &params(serde_json::json!({
+            "acceptedOutputModes": [],
+            "temperature": 1.1,
+            "reasoningEffort": "high",
+        })),
+    )
+    .unwrap()
+    .expect("overrides requested");
+    let mut overrides = distri_types::configuration::DefinitionOverrides::default();
+    requested.apply_to(&mut overrides);
+
+    let mut agent_config = agent.clone();
+    AgentOrchestrator::apply_agent_overrides(&mut agent_config, Some(overrides), &None);
+    let AgentConfig::StandardAgent(loaded_def) = &agent_config else {
+        panic!("expected StandardAgent")
+    };
+    let ms = loaded_def.model_settings().unwrap();
+    assert_eq!(ms.model, "gpt-4.1");
+    assert_eq!(ms.inner.temperature, Some(1.1));
+    assert_eq!(
+        ms.inner.reasoning_effort,
+        Some(distri_types::ReasoningEffort::High)
+    );
+
+    let rejected = crate::a2a::stream::message_model_overrides(
+        Some(&agent),
+        &params(serde_json::json!({ "acceptedOutputModes": [], "model": "gpt-4o" })),
+    );
+    assert!(rejected.is_err(), "model outside the allowlist");
+
+    // No overrides requested: nothing to check.
+    let none = crate::a2a::stream::message_model_overrides(
+        Some(&agent),
+        &params(serde_json::json!({ "acceptedOutputModes": [] })),
+    )
+    .unwrap();
+    assert!(none.is_none());
+}
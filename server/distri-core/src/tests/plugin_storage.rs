@@ -0,0 +1,113 @@
+use std::sync::Arc;
+
+use distri_types::integration::Integration;
+use distri_types::{AgentEventType, Part, Tool, ToolCall, ToolContext};
+use serde_json::{json, Value};
+
+use crate::testing::{AgentTestHarness, MockLlmProvider, TestRun};
+use crate::types::StandardDefinition;
+
+/// Counts its calls in the plugin's storage.
+#[derive(Debug)]
+struct CountTool;
+
+#[async_trait::async_trait]
+impl Tool for CountTool {
+    fn get_name(&self) -> String {
+        "acme_count".to_string()
+    }
+
+    fn get_description(&self) -> String {
+        "Count calls".to_string()
+    }
+
+    fn get_parameters(&self) -> Value {
+        json!({ "type": "object" })
+    }
+
+    async fn execute(
+        &self,
+        _tool_call: ToolCall,
+        context: Arc<ToolContext>,
+    ) -> Result<Vec<Part>, anyhow::Error> {
+        let storage = context.storage()?;
+        let calls = storage
+            .get("calls")
+            .await?
+            .and_then(|v| v.as_u64())
+            .unwrap_or(0)
+            + 1;
+        storage.set("calls", &json!(calls)).await?;
+        Ok(vec![Part::Text(format!("call {calls}"))])
+    }
+}
+
+#[derive(Debug)]
+struct AcmePlugin;
+
+impl Integration for AcmePlugin {
+    fn get_name(&self) -> String {
+        "acme".to_string()
+    }
+
+    fn get_description(&self) -> String {
+        "ACME API".to_string()
+    }
+
+    fn get_tools(&self) -> Vec<Arc<dyn Tool>> {
+        vec![Arc::new(CountTool)]
+    }
+}
+
+/// Results of the plugin's tool in `run`, leaving out the `final` call.
+fn results(run: &TestRun) -> Vec<Vec<Part>> {
+    run.events
+        .iter()
+        .filter_map(|e| match &e.event {
+            AgentEventType::ToolResults { results, .. } => Some(results),
+            _ => None,
+        })
+        .flatten()
+        .filter(|r| r.tool_name == "acme_count")
+        .map(|r| r.parts.clone())
+        .collect()
+}
+
+#[tokio::test]
+async fn plugin_storage_persists_across_runs() {
+    let llm = MockLlmProvider::new()
+        .respond_tool_call("acme_count", json!({}))
+        .respond_final("done")
+        .respond_tool_call("acme_count", json!({}))
+        .respond_final("done");
+    let harness = AgentTestHarness::new(llm).await.unwrap();
+    harness
+        .register_agent(StandardDefinition {
+            name: "acme_agent".to_string(),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    harness
+        .orchestrator
+        .register_integration("acme_agent", &AcmePlugin)
+        .await;
+
+    let first = harness.run("acme_agent", "Count").await;
+    let second = harness.run("acme_agent", "Count again").await;
+
+    first.assert_success();
+    second.assert_success();
+    assert_eq!(results(&first), [vec![Part::Text("call 1".to_string())]]);
+    assert_eq!(results(&second), [vec![Part::Text("call 2".to_string())]]);
+    let stored = harness
+        .orchestrator
+        .stores
+        .plugin_kv_store
+        .as_ref()
+        .unwrap()
+        .get("acme", "calls")
+        .await
+        .unwrap();
+    assert_eq!(stored, Some(json!(2)));
+}
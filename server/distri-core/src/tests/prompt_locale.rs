@@ -0,0 +1,79 @@
+//! Localized prompt templates: the planner renders the `planning@<locale>`
+//! variant for the user profile's locale or the message's language, and the
+//! default template otherwise.
+
+use serde_json::json;
+
+use crate::testing::{AgentTestHarness, MockLlmProvider};
+use crate::types::StandardDefinition;
+
+const GERMAN: &str = "Antworte immer auf Deutsch.";
+
+fn system_prompt(llm: &MockLlmProvider, request: usize) -> String {
+    format!("{:?}", llm.requests()[request].messages)
+}
+
+#[tokio::test]
+async fn the_planning_template_follows_the_user_locale() {
+    let llm = MockLlmProvider::new()
+        .respond_final("Sonnig.")
+        .respond_final("Sunny.")
+        .respond_final("Sonnig.");
+    let harness = AgentTestHarness::new(llm.clone()).await.unwrap();
+    harness
+        .register_agent(StandardDefinition {
+            name: "weather".to_string(),
+            description: "weather reports".to_string(),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    harness
+        .orchestrator
+        .register_prompt_template("planning@de".to_string(), GERMAN.to_string(), None, None)
+        .await
+        .unwrap();
+
+    // Detected from the message.
+    harness
+        .run("weather", "Kannst du mir bitte sagen, wie das Wetter ist?")
+        .await
+        .assert_success();
+    assert!(system_prompt(&llm, 0).contains(GERMAN));
+
+    // No German variant applies to an English message.
+    harness
+        .run("weather", "What is the weather like this weekend?")
+        .await
+        .assert_success();
+    assert!(!system_prompt(&llm, 1).contains(GERMAN));
+
+    // The profile's locale wins over the message's language.
+    harness
+        .orchestrator
+        .stores
+        .session_store
+        .set_value(
+            "thread-profile",
+            "user_profile",
+            &json!({ "name": "Sam", "locale": "de-AT" }),
+        )
+        .await
+        .unwrap();
+    harness
+        .run_on_thread(
+            "weather",
+            "thread-profile",
+            "What is the weather like this weekend?",
+        )
+        .await
+        .assert_success();
+    assert!(system_prompt(&llm, 2).contains(GERMAN));
+
+    let coverage = harness.orchestrator.prompt_translation_coverage().await;
+    let planning = coverage.iter().find(|c| c.name == "planning").unwrap();
+    assert!(planning.has_default);
+    assert_eq!(planning.locales, ["de"]);
+    let user = coverage.iter().find(|c| c.name == "user").unwrap();
+    assert_eq!(user.missing, ["de"]);
+}
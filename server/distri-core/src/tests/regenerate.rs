@@ -0,0 +1,117 @@
+use distri_types::regenerate::RegenerateRequest;
+use distri_types::{MessageRole, ModelSettings, TaskMessage};
+
+use crate::testing::{AgentTestHarness, MockLlmProvider, MOCK_MODEL};
+use crate::types::StandardDefinition;
+use crate::AgentError;
+
+async fn harness(llm: MockLlmProvider) -> AgentTestHarness {
+    let harness = AgentTestHarness::new(llm).await.unwrap();
+    harness
+        .register_agent(StandardDefinition {
+            name: "travel".to_string(),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    harness
+}
+
+fn mock_model() -> Option<ModelSettings> {
+    Some(ModelSettings {
+        model: MOCK_MODEL.to_string(),
+        inner: Default::default(),
+    })
+}
+
+fn user_message_id(messages: &[TaskMessage]) -> String {
+    messages
+        .iter()
+        .find_map(|m| match m {
+            TaskMessage::Message(m) if m.role == MessageRole::User => Some(m.id.clone()),
+            _ => None,
+        })
+        .unwrap()
+}
+
+#[tokio::test]
+async fn regenerating_archives_the_task_and_everything_after_it() {
+    let llm = MockLlmProvider::new()
+        .respond_final("Day 1: Alfama")
+        .respond_final("Day 2: Belem")
+        .respond_final("Saturday: Sintra");
+    let harness = harness(llm.clone()).await;
+    let thread_id = uuid::Uuid::new_v4().to_string();
+    harness
+        .run_on_thread("travel", &thread_id, "Plan a trip")
+        .await
+        .assert_success();
+    harness
+        .run_on_thread("travel", &thread_id, "And day 2?")
+        .await
+        .assert_success();
+
+    let task_store = &harness.orchestrator.stores.task_store;
+    let history = task_store.get_history(&thread_id, None).await.unwrap();
+    assert_eq!(history.len(), 2);
+    let first = history[0].0.id.clone();
+    let second = history[1].0.id.clone();
+
+    let regeneration = harness
+        .orchestrator
+        .regenerate_from(
+            &thread_id,
+            RegenerateRequest {
+                message_id: user_message_id(&history[0].1),
+                message: Some("Plan a weekend".to_string()),
+                overrides: None,
+            },
+            None,
+            mock_model(),
+        )
+        .await
+        .unwrap();
+    llm.assert_exhausted();
+    assert_eq!(regeneration.archived_task_ids, vec![first.clone(), second]);
+    assert_eq!(regeneration.reference_task_ids, vec![first.clone()]);
+    assert_eq!(regeneration.content.as_deref(), Some("Saturday: Sintra"));
+
+    // The new run only saw the edited message, not the archived turns.
+    let prompt = llm.requests()[2]
+        .messages
+        .iter()
+        .filter_map(|m| m.as_text())
+        .collect::<Vec<_>>()
+        .join("\n");
+    assert!(prompt.contains("Plan a weekend"), "{prompt}");
+    assert!(!prompt.contains("Alfama"), "{prompt}");
+
+    let history = task_store.get_history(&thread_id, None).await.unwrap();
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0].0.id, regeneration.task_id);
+    assert_eq!(history[0].0.reference_task_ids, vec![first.clone()]);
+    let archived = task_store.get_task(&first).await.unwrap().unwrap();
+    assert!(archived.archived_at.is_some());
+}
+
+#[tokio::test]
+async fn unknown_message_is_not_found() {
+    let harness = harness(MockLlmProvider::new().respond_final("Day 1: Alfama")).await;
+    let run = harness.run("travel", "Plan a trip").await;
+    run.assert_success();
+
+    let err = harness
+        .orchestrator
+        .regenerate_from(
+            &run.thread_id,
+            RegenerateRequest {
+                message_id: "missing".to_string(),
+                ..Default::default()
+            },
+            None,
+            mock_model(),
+        )
+        .await
+        .unwrap_err();
+    assert!(matches!(err, AgentError::NotFound(_)), "{err}");
+}
@@ -251,7 +251,7 @@ async fn test_request_tool_success_json() {
         body: None,
     };
 
-    let result = execute_http_request(&input, &ctx, None).await.unwrap();
+    let result = execute_http_request(&input, &ctx, None, None).await.unwrap();
 
     assert!(result.ok);
     assert_eq!(result.status, 200);
@@ -281,7 +281,7 @@ async fn test_request_tool_error_response() {
         body: Some(json!({"name": "test"})),
     };
 
-    let result = execute_http_request(&input, &ctx, None).await.unwrap();
+    let result = execute_http_request(&input, &ctx, None, None).await.unwrap();
 
     assert!(!result.ok);
     assert_eq!(result.status, 400);
@@ -311,7 +311,7 @@ async fn test_request_tool_non_json_response() {
         body: None,
     };
 
-    let result = execute_http_request(&input, &ctx, None).await.unwrap();
+    let result = execute_http_request(&input, &ctx, None, None).await.unwrap();
 
     assert!(!result.ok);
     assert_eq!(result.status, 500);
@@ -335,7 +335,7 @@ async fn test_request_tool_unresolved_var_errors() {
         body: None,
     };
 
-    let err = execute_http_request(&input, &ctx, None).await.unwrap_err();
+    let err = execute_http_request(&input, &ctx, None, None).await.unwrap_err();
 
     let msg = format!("{}", err);
     assert!(
@@ -369,7 +369,7 @@ async fn test_request_tool_secret_resolution() {
         body: None,
     };
 
-    let result = execute_http_request(&input, &ctx, None).await.unwrap();
+    let result = execute_http_request(&input, &ctx, None, None).await.unwrap();
 
     assert!(result.ok);
     assert_eq!(result.status, 200);
@@ -403,7 +403,7 @@ async fn test_request_tool_env_var_priority_over_secret() {
         body: None,
     };
 
-    let result = execute_http_request(&input, &ctx, None).await.unwrap();
+    let result = execute_http_request(&input, &ctx, None, None).await.unwrap();
 
     assert!(result.ok);
     assert_eq!(result.status, 200);
@@ -435,7 +435,7 @@ async fn test_request_tool_no_vars_plain_url() {
         body: None,
     };
 
-    let result = execute_http_request(&input, &ctx, None).await.unwrap();
+    let result = execute_http_request(&input, &ctx, None, None).await.unwrap();
 
     assert!(result.ok);
     assert_eq!(result.status, 200);
@@ -465,7 +465,7 @@ async fn test_request_tool_plain_text_response_not_parsed_as_json() {
         body: None,
     };
 
-    let result = execute_http_request(&input, &ctx, None).await.unwrap();
+    let result = execute_http_request(&input, &ctx, None, None).await.unwrap();
 
     assert!(result.ok);
     assert_eq!(result.status, 200);
@@ -499,7 +499,7 @@ async fn test_request_tool_response_headers_filtered() {
         body: None,
     };
 
-    let result = execute_http_request(&input, &ctx, None).await.unwrap();
+    let result = execute_http_request(&input, &ctx, None, None).await.unwrap();
 
     // Useful headers included
     assert!(result.headers.get("content-type").is_some());
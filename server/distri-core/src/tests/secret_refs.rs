@@ -0,0 +1,106 @@
+use distri_types::configuration::AgentConfig;
+use distri_types::dynamic_tool::DynamicToolFactory;
+
+use crate::testing::{AgentTestHarness, MockLlmProvider};
+use crate::types::{StandardDefinition, ToolsConfig};
+
+/// An agent whose dynamic `crm_request` tool sends `authorization`.
+fn agent(authorization: &str) -> StandardDefinition {
+    StandardDefinition {
+        name: "crm_agent".to_string(),
+        description: "CRM lookups".to_string(),
+        tools: Some(ToolsConfig {
+            dynamic: vec![DynamicToolFactory {
+                name: "crm_request".to_string(),
+                factory_type: "http".to_string(),
+                config: serde_json::json!({
+                    "base_url": "https://crm.example.com",
+                    "headers": { "Authorization": authorization },
+                }),
+                description: None,
+            }],
+            ..Default::default()
+        }),
+        ..Default::default()
+    }
+}
+
+fn authorization(config: &AgentConfig) -> String {
+    let AgentConfig::StandardAgent(definition) = config else {
+        panic!("expected a standard agent");
+    };
+    definition.tools.as_ref().unwrap().dynamic[0].config["headers"]["Authorization"]
+        .as_str()
+        .unwrap()
+        .to_string()
+}
+
+#[tokio::test]
+async fn registration_lists_every_missing_secret() {
+    let harness = AgentTestHarness::new(MockLlmProvider::new()).await.unwrap();
+
+    let err = harness
+        .register_agent(agent(
+            "{{secret:MISSING_CRM_TOKEN}} at {{secret:MISSING_CRM_HOST}}",
+        ))
+        .await
+        .unwrap_err();
+
+    assert_eq!(
+        err.to_string(),
+        "agent 'crm_agent' references secrets that are not configured: \
+         MISSING_CRM_HOST, MISSING_CRM_TOKEN"
+    );
+    assert!(harness.orchestrator.get_agent("crm_agent").await.is_none());
+}
+
+#[tokio::test]
+async fn secret_refs_are_stored_as_is_and_resolved_per_run() {
+    std::env::set_var("TEST_CRM_SECRET_REF", "s3cret");
+    let harness = AgentTestHarness::new(MockLlmProvider::new()).await.unwrap();
+    harness
+        .register_agent(agent("Bearer {{secret:TEST_CRM_SECRET_REF}}"))
+        .await
+        .unwrap();
+
+    let mut config = harness.orchestrator.get_agent("crm_agent").await.unwrap();
+    assert_eq!(
+        authorization(&config),
+        "Bearer {{secret:TEST_CRM_SECRET_REF}}"
+    );
+
+    harness
+        .orchestrator
+        .resolve_agent_secret_refs(&mut config)
+        .await
+        .unwrap();
+    assert_eq!(authorization(&config), "Bearer s3cret");
+
+    std::env::remove_var("TEST_CRM_SECRET_REF");
+}
+
+#[tokio::test]
+async fn secret_refs_outside_transport_and_tool_config_are_rejected() {
+    std::env::set_var("TEST_CRM_PROMPT_SECRET", "s3cret");
+    let harness = AgentTestHarness::new(MockLlmProvider::new()).await.unwrap();
+    let mut definition = agent("Bearer {{secret:TEST_CRM_PROMPT_SECRET}}");
+    definition.instructions = "The token is {{secret:TEST_CRM_PROMPT_SECRET}}.".to_string();
+
+    let err = harness
+        .register_agent(definition.clone())
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("found in /instructions"), "{err}");
+    assert!(harness.orchestrator.get_agent("crm_agent").await.is_none());
+
+    // Definitions stored before the rule are refused when they run.
+    let mut config = AgentConfig::StandardAgent(definition);
+    let err = harness
+        .orchestrator
+        .resolve_agent_secret_refs(&mut config)
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("/instructions"), "{err}");
+
+    std::env::remove_var("TEST_CRM_PROMPT_SECRET");
+}
@@ -0,0 +1,97 @@
+use distri_types::{AgentEventType, ModelSettings, ModelSettingsInner};
+use serde_json::{json, Value};
+
+use crate::testing::{AgentTestHarness, MockLlmProvider, TestRun, MOCK_MODEL};
+use crate::types::StandardDefinition;
+
+fn extractor() -> StandardDefinition {
+    StandardDefinition {
+        name: "extractor".to_string(),
+        model_settings: Some(ModelSettings {
+            model: MOCK_MODEL.to_string(),
+            inner: ModelSettingsInner {
+                response_format: Some(json!({
+                    "type": "json_schema",
+                    "json_schema": {
+                        "name": "contact",
+                        "schema": {
+                            "type": "object",
+                            "properties": {
+                                "name": { "type": "string" },
+                                "age": { "type": "integer" }
+                            },
+                            "required": ["name", "age"],
+                            "additionalProperties": false
+                        }
+                    }
+                })),
+                ..Default::default()
+            },
+        }),
+        ..Default::default()
+    }
+}
+
+fn fields(run: &TestRun) -> Vec<(String, Value)> {
+    run.events
+        .iter()
+        .filter_map(|e| match &e.event {
+            AgentEventType::StructuredFieldCompleted { field, value, .. } => {
+                Some((field.clone(), value.clone()))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+fn rejections(run: &TestRun) -> Vec<(usize, bool)> {
+    run.events
+        .iter()
+        .filter_map(|e| match &e.event {
+            AgentEventType::StructuredOutputRejected {
+                attempt, retrying, ..
+            } => Some((*attempt, *retrying)),
+            _ => None,
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn diverging_response_is_aborted_and_retried() {
+    let llm = MockLlmProvider::new()
+        .respond_text(r#"{"name": "Ada", "age": "thirty-six"}"#)
+        .respond_text(r#"{"name": "Ada", "age": 36}"#);
+    let harness = AgentTestHarness::new(llm).await.unwrap();
+    harness.register_agent(extractor()).await.unwrap();
+
+    let run = harness.run("extractor", "Ada is 36.").await;
+    run.assert_success();
+    assert_eq!(rejections(&run), [(1, true)]);
+    assert_eq!(
+        fields(&run),
+        [
+            ("name".to_string(), json!("Ada")),
+            ("name".to_string(), json!("Ada")),
+            ("age".to_string(), json!(36)),
+        ]
+    );
+
+    let requests = harness.llm.requests();
+    assert_eq!(requests.len(), 2);
+    let retry = requests[1].messages.last().unwrap().as_text().unwrap();
+    assert!(retry.contains("field 'age' should be integer"), "{}", retry);
+}
+
+#[tokio::test]
+async fn run_fails_once_retries_are_exhausted() {
+    let llm = MockLlmProvider::new()
+        .respond_text("Sure, here it is")
+        .respond_text(r#"{"name": "Ada", "nickname": "A"}"#);
+    let harness = AgentTestHarness::new(llm).await.unwrap();
+    harness.register_agent(extractor()).await.unwrap();
+
+    let run = harness.run("extractor", "Ada is 36.").await;
+    assert!(run.result.is_err());
+    assert_eq!(rejections(&run), [(1, true), (2, false)]);
+    harness.llm.assert_exhausted();
+}
@@ -0,0 +1,132 @@
+//! Comparing two tasks of an agent: a regenerated task against the archived
+//! original, step by step.
+
+use distri_types::regenerate::RegenerateRequest;
+use distri_types::task_diff::StepChange;
+use distri_types::{MessageRole, ModelSettings, TaskMessage};
+use serde_json::json;
+
+use crate::servers::fake::{FakeMcpServer, FakeMcpTool};
+use crate::testing::{AgentTestHarness, MockLlmProvider, MOCK_MODEL};
+use crate::types::StandardDefinition;
+use crate::AgentError;
+
+async fn harness(llm: MockLlmProvider) -> AgentTestHarness {
+    let harness = AgentTestHarness::new(llm).await.unwrap();
+    for name in ["researcher", "writer"] {
+        harness
+            .register_agent(StandardDefinition {
+                name: name.to_string(),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+    }
+    FakeMcpServer::new()
+        .tool(FakeMcpTool::new("search").respond(json!({ "results": ["Rust"] })))
+        .attach(&harness.orchestrator, "web_search", "researcher")
+        .await
+        .unwrap();
+    harness
+}
+
+async fn task_of(harness: &AgentTestHarness, thread_id: &str) -> String {
+    let tasks = harness
+        .orchestrator
+        .stores
+        .task_store
+        .list_tasks(Some(thread_id))
+        .await
+        .unwrap();
+    tasks[0].id.clone()
+}
+
+#[tokio::test]
+async fn regenerated_task_is_compared_with_the_original() {
+    let llm = MockLlmProvider::new()
+        .respond_tool_call("search", json!({ "query": "rust" }))
+        .respond_final("Rust is fast.")
+        .respond_final("Rust.");
+    let harness = harness(llm).await;
+    let run = harness.run("researcher", "Tell me about rust").await;
+    run.assert_success();
+
+    let history = harness
+        .orchestrator
+        .stores
+        .task_store
+        .get_history(&run.thread_id, None)
+        .await
+        .unwrap();
+    let (original, messages) = &history[0];
+    let message_id = messages
+        .iter()
+        .find_map(|m| match m {
+            TaskMessage::Message(m) if m.role == MessageRole::User => Some(m.id.clone()),
+            _ => None,
+        })
+        .unwrap();
+    let regeneration = harness
+        .orchestrator
+        .regenerate_from(
+            &run.thread_id,
+            RegenerateRequest {
+                message_id,
+                ..Default::default()
+            },
+            None,
+            Some(ModelSettings {
+                model: MOCK_MODEL.to_string(),
+                inner: Default::default(),
+            }),
+        )
+        .await
+        .unwrap();
+
+    // The original is archived by the regeneration and still compared.
+    let diff = harness
+        .orchestrator
+        .compare_tasks(&original.id, &regeneration.task_id)
+        .await
+        .unwrap();
+    assert_eq!(diff.baseline.agent_id, "researcher");
+    assert_eq!(diff.baseline.input.as_deref(), Some("Tell me about rust"));
+    assert_eq!(diff.baseline.steps.len(), 2);
+    assert_eq!(diff.baseline.steps[0].tool_calls[0].tool_name, "search");
+    assert!(!diff.baseline.steps[0].tool_results.is_empty());
+    assert_eq!(diff.candidate.steps.len(), 1);
+    let changes: Vec<_> = diff.steps.iter().map(|s| s.change).collect();
+    assert_eq!(changes, [StepChange::Removed, StepChange::Changed]);
+    assert_eq!(
+        diff.baseline.outcome.answer.as_deref(),
+        Some("Rust is fast.")
+    );
+    assert_eq!(diff.candidate.outcome.answer.as_deref(), Some("Rust."));
+    assert!(!diff.same_outcome);
+}
+
+#[tokio::test]
+async fn tasks_of_different_agents_are_not_compared() {
+    let llm = MockLlmProvider::new()
+        .respond_final("notes")
+        .respond_final("essay");
+    let harness = harness(llm).await;
+    let research = harness.run("researcher", "rust").await;
+    let writing = harness.run("writer", "rust").await;
+    let research = task_of(&harness, &research.thread_id).await;
+    let writing = task_of(&harness, &writing.thread_id).await;
+
+    let err = harness
+        .orchestrator
+        .compare_tasks(&research, &writing)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, AgentError::Validation(_)), "{err}");
+
+    let err = harness
+        .orchestrator
+        .compare_tasks(&research, "missing")
+        .await
+        .unwrap_err();
+    assert!(matches!(err, AgentError::NotFound(_)), "{err}");
+}
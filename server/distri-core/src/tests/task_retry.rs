@@ -0,0 +1,148 @@
+use std::sync::Arc;
+
+use distri_types::task_retry::RetryRequest;
+use distri_types::{AgentEventType, ModelSettings, Part, Tool, ToolCall, ToolContext};
+use serde_json::{json, Value};
+
+use crate::testing::{AgentTestHarness, MockLlmProvider, TestRun, MOCK_MODEL};
+use crate::types::StandardDefinition;
+use crate::AgentError;
+
+/// A `fetch` tool whose host is down.
+#[derive(Debug)]
+struct Fetch;
+
+#[async_trait::async_trait]
+impl Tool for Fetch {
+    fn get_name(&self) -> String {
+        "fetch".to_string()
+    }
+
+    fn get_description(&self) -> String {
+        "Fetch a URL".to_string()
+    }
+
+    fn get_parameters(&self) -> Value {
+        json!({ "type": "object", "properties": { "url": { "type": "string" } } })
+    }
+
+    async fn execute(
+        &self,
+        _tool_call: ToolCall,
+        _context: Arc<ToolContext>,
+    ) -> Result<Vec<Part>, anyhow::Error> {
+        anyhow::bail!("connection refused by example.com")
+    }
+}
+
+async fn harness(llm: MockLlmProvider) -> AgentTestHarness {
+    let harness = AgentTestHarness::new(llm).await.unwrap();
+    harness
+        .register_agent(StandardDefinition {
+            name: "reader".to_string(),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    harness
+        .orchestrator
+        .register_tool("reader", Arc::new(Fetch))
+        .await;
+    harness
+}
+
+fn mock_model() -> Option<ModelSettings> {
+    Some(ModelSettings {
+        model: MOCK_MODEL.to_string(),
+        inner: Default::default(),
+    })
+}
+
+fn task_id(run: &TestRun) -> String {
+    run.events
+        .iter()
+        .find(|e| matches!(e.event, AgentEventType::RunError { .. }))
+        .expect("the run failed")
+        .task_id
+        .clone()
+}
+
+#[tokio::test]
+async fn retry_reruns_the_task_with_its_failure() {
+    let llm = MockLlmProvider::new()
+        .respond_tool_call("fetch", json!({ "url": "https://example.com" }))
+        // The loop gives up after three planning failures in a row.
+        .respond_error("provider overloaded")
+        .respond_error("provider overloaded")
+        .respond_error("provider overloaded")
+        .respond_final("Read it from the cache");
+    let harness = harness(llm.clone()).await;
+    let run = harness.run("reader", "Summarize example.com").await;
+    assert!(run.result.is_err());
+    let failed = task_id(&run);
+
+    let retry = harness
+        .orchestrator
+        .retry_task(&failed, RetryRequest::default(), None, mock_model())
+        .await
+        .unwrap();
+    llm.assert_exhausted();
+    assert_eq!(retry.retry_of, failed);
+    assert_eq!(retry.agent_id, "reader");
+    assert_eq!(retry.archived_task_ids, vec![failed.clone()]);
+    assert_eq!(retry.content.as_deref(), Some("Read it from the cache"));
+    assert_eq!(retry.failure.failed_tool_calls.len(), 1);
+    let call = &retry.failure.failed_tool_calls[0];
+    assert_eq!(call.tool_name, "fetch");
+    assert!(
+        call.error
+            .as_deref()
+            .is_some_and(|e| e.contains("connection refused")),
+        "{call:?}"
+    );
+    assert!(retry
+        .failure
+        .error
+        .as_deref()
+        .is_some_and(|e| e.contains("provider overloaded")));
+
+    // The retry got the original input and the failure in its prompt.
+    let prompt = llm.requests()[4]
+        .messages
+        .iter()
+        .filter_map(|m| m.as_text())
+        .collect::<Vec<_>>()
+        .join("\n");
+    assert!(prompt.contains("Summarize example.com"), "{prompt}");
+    assert!(prompt.contains("Previous attempt failed"), "{prompt}");
+    assert!(prompt.contains("connection refused"), "{prompt}");
+
+    let task_store = &harness.orchestrator.stores.task_store;
+    let task = task_store.get_task(&retry.task_id).await.unwrap().unwrap();
+    assert_eq!(task.reference_task_ids, vec![failed]);
+    let history = task_store.get_history(&run.thread_id, None).await.unwrap();
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0].0.id, retry.task_id);
+}
+
+#[tokio::test]
+async fn only_failed_tasks_are_retried() {
+    let harness = harness(MockLlmProvider::new().respond_final("Done")).await;
+    let run = harness.run("reader", "Summarize example.com").await;
+    run.assert_success();
+    let task_id = run.events[0].task_id.clone();
+
+    let err = harness
+        .orchestrator
+        .retry_task(&task_id, RetryRequest::default(), None, mock_model())
+        .await
+        .unwrap_err();
+    assert!(matches!(err, AgentError::Validation(_)), "{err}");
+
+    let err = harness
+        .orchestrator
+        .retry_task("missing", RetryRequest::default(), None, mock_model())
+        .await
+        .unwrap_err();
+    assert!(matches!(err, AgentError::NotFound(_)), "{err}");
+}
@@ -0,0 +1,108 @@
+use distri_types::stores::ThreadListFilter;
+use distri_types::TaskMessage;
+
+use crate::testing::{AgentTestHarness, MockLlmProvider};
+use crate::types::StandardDefinition;
+use crate::AgentError;
+
+async fn harness(llm: MockLlmProvider) -> AgentTestHarness {
+    let harness = AgentTestHarness::new(llm).await.unwrap();
+    harness
+        .register_agent(StandardDefinition {
+            name: "notes".to_string(),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    harness
+}
+
+fn texts(history: &[(distri_types::Task, Vec<TaskMessage>)]) -> Vec<String> {
+    history
+        .iter()
+        .flat_map(|(_, messages)| messages)
+        .filter_map(|m| match m {
+            TaskMessage::Message(m) => m.as_text(),
+            _ => None,
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn archived_threads_are_listed_and_restored_on_access() {
+    let llm = MockLlmProvider::new().respond_final("Noted: buy milk");
+    let harness = harness(llm).await;
+    let run = harness.run("notes", "Remember to buy milk").await;
+    run.assert_success();
+    let thread_id = run.thread_id.clone();
+    let orchestrator = &harness.orchestrator;
+    let task_store = &orchestrator.stores.task_store;
+    let before = texts(&task_store.get_history(&thread_id, None).await.unwrap());
+
+    let summary = orchestrator.archive_thread(&thread_id).await.unwrap();
+    assert!(summary.archived_at.is_some());
+    assert_eq!(summary.tasks, 1);
+    assert!(task_store
+        .list_tasks(Some(&thread_id))
+        .await
+        .unwrap()
+        .is_empty());
+    let listed = orchestrator
+        .list_threads(&ThreadListFilter::default(), None, None)
+        .await
+        .unwrap();
+    assert!(listed.threads[0].archived_at.is_some());
+    assert!(matches!(
+        orchestrator.archive_thread(&thread_id).await,
+        Err(AgentError::Validation(_))
+    ));
+
+    // Reading the thread brings its history back.
+    let thread = orchestrator.get_thread(&thread_id).await.unwrap().unwrap();
+    assert!(thread.archived_at.is_none());
+    let after = texts(&task_store.get_history(&thread_id, None).await.unwrap());
+    assert_eq!(after, before);
+    assert!(matches!(
+        orchestrator.restore_thread(&thread_id).await,
+        Err(AgentError::Validation(_))
+    ));
+}
+
+#[tokio::test]
+async fn archived_threads_can_be_continued() {
+    let llm = MockLlmProvider::new()
+        .respond_final("Noted: buy milk")
+        .respond_final("Also noted: eggs");
+    let harness = harness(llm.clone()).await;
+    let thread_id = uuid::Uuid::new_v4().to_string();
+    harness
+        .run_on_thread("notes", &thread_id, "Remember to buy milk")
+        .await
+        .assert_success();
+    harness
+        .orchestrator
+        .archive_thread(&thread_id)
+        .await
+        .unwrap();
+
+    harness
+        .run_on_thread("notes", &thread_id, "And eggs")
+        .await
+        .assert_success();
+    llm.assert_exhausted();
+
+    let history = harness
+        .orchestrator
+        .stores
+        .task_store
+        .get_history(&thread_id, None)
+        .await
+        .unwrap();
+    assert_eq!(history.len(), 2);
+    let texts = texts(&history);
+    assert!(
+        texts.iter().any(|t| t == "Remember to buy milk"),
+        "{texts:?}"
+    );
+    assert!(texts.iter().any(|t| t == "And eggs"), "{texts:?}");
+}
@@ -0,0 +1,129 @@
+use std::sync::Arc;
+
+use distri_types::prompt::{PromptLayersConfig, RuntimeContextItem};
+use distri_types::{MessageRole, Part, ToolCall, ToolsConfig};
+use serde_json::json;
+
+use crate::agent::ExecutorContext;
+use crate::testing::{AgentTestHarness, MockLlmProvider};
+use crate::tools::load_artifact::LoadArtifactTool;
+use crate::tools::ExecutorContextTool;
+use crate::types::StandardDefinition;
+use crate::AgentError;
+
+const REPORT: &str = "# Q3 revenue\nTotal: $1.2M\nEMEA: $0.4M\nAPAC: $0.3M\n";
+
+async fn load(
+    input: serde_json::Value,
+    context: &Arc<ExecutorContext>,
+) -> Result<Vec<Part>, AgentError> {
+    let call = ToolCall {
+        tool_call_id: uuid::Uuid::new_v4().to_string(),
+        tool_name: "load_artifact".to_string(),
+        input,
+    };
+    LoadArtifactTool
+        .execute_with_executor_context(call, context.clone())
+        .await
+}
+
+fn text(parts: Vec<Part>) -> String {
+    match parts.into_iter().next() {
+        Some(Part::Text(text)) => text,
+        other => panic!("expected a text part, got {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn earlier_artifacts_are_listed_and_loaded_by_id() {
+    let llm = MockLlmProvider::new()
+        .respond_tool_call(
+            "save_artifact",
+            json!({ "content": REPORT, "filename": "report.md", "caption": "Q3 revenue report" }),
+        )
+        .respond_final("Saved the report.")
+        .respond_final("Loading it.");
+    let harness = AgentTestHarness::new(llm).await.unwrap();
+    harness
+        .register_agent(StandardDefinition {
+            name: "analyst".to_string(),
+            tools: Some(ToolsConfig {
+                builtin: vec!["save_artifact".to_string(), "load_artifact".to_string()],
+                ..Default::default()
+            }),
+            prompt_layers: Some(PromptLayersConfig {
+                runtime: vec![RuntimeContextItem::Artifacts],
+                ..Default::default()
+            }),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+    let thread_id = uuid::Uuid::new_v4().to_string();
+    harness
+        .run_on_thread("analyst", &thread_id, "Write the Q3 report")
+        .await
+        .assert_success();
+    harness
+        .run_on_thread("analyst", &thread_id, "What was in the report you made?")
+        .await
+        .assert_success();
+
+    // No artifacts yet on the first turn; the report on the next one.
+    let requests = harness.llm.requests();
+    let system = |index: usize| {
+        requests[index]
+            .messages
+            .iter()
+            .find(|m| m.role == MessageRole::System)
+            .and_then(|m| m.as_text())
+            .unwrap()
+    };
+    assert!(!system(0).contains("# THREAD ARTIFACTS"));
+    let prompt = system(requests.len() - 1);
+    assert!(prompt.contains("# THREAD ARTIFACTS"), "{prompt}");
+    assert!(
+        prompt.contains("- report.md: Q3 revenue report (id: `"),
+        "{prompt}"
+    );
+    let id = prompt
+        .split("(id: `")
+        .nth(1)
+        .and_then(|rest| rest.split('`').next())
+        .unwrap()
+        .to_string();
+    assert!(id.ends_with("/content/report.md"), "{id}");
+
+    let context = Arc::new(ExecutorContext {
+        agent_id: "analyst".to_string(),
+        thread_id: thread_id.clone(),
+        orchestrator: Some(harness.orchestrator.clone()),
+        ..Default::default()
+    });
+    assert_eq!(
+        text(load(json!({ "id": id }), &context).await.unwrap()),
+        REPORT
+    );
+    let excerpt = text(
+        load(
+            json!({ "id": id, "start_line": 2, "end_line": 2 }),
+            &context,
+        )
+        .await
+        .unwrap(),
+    );
+    assert!(excerpt.starts_with("Total: $1.2M\n"), "{excerpt}");
+    assert!(excerpt.contains("pass start_line = 3"), "{excerpt}");
+
+    // Another thread cannot load it.
+    let other = Arc::new(ExecutorContext {
+        thread_id: "another-thread".to_string(),
+        orchestrator: Some(harness.orchestrator.clone()),
+        ..Default::default()
+    });
+    assert!(matches!(
+        load(json!({ "id": id }), &other).await,
+        Err(AgentError::ToolExecution(_))
+    ));
+}
@@ -0,0 +1,108 @@
+use std::collections::BTreeMap;
+
+use distri_types::{CreateThreadRequest, MessageRole, UpdateThreadRequest};
+
+use crate::testing::{AgentTestHarness, MockLlmProvider, RecordedRequest};
+use crate::types::StandardDefinition;
+
+fn set_variables(variables: &[(&str, &str)]) -> UpdateThreadRequest {
+    UpdateThreadRequest {
+        title: None,
+        metadata: None,
+        attributes: None,
+        user_id: None,
+        variables: Some(
+            variables
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect::<BTreeMap<_, _>>(),
+        ),
+    }
+}
+
+fn texts(request: &RecordedRequest, role: MessageRole) -> Vec<String> {
+    request
+        .messages
+        .iter()
+        .filter(|m| m.role == role)
+        .filter_map(|m| m.as_text())
+        .collect()
+}
+
+#[tokio::test]
+async fn thread_variables_render_in_prompt_and_messages() {
+    let llm = MockLlmProvider::new()
+        .respond_final("Checked")
+        .respond_final("Checked again");
+    let harness = AgentTestHarness::new(llm).await.unwrap();
+    harness
+        .register_agent(StandardDefinition {
+            name: "support".to_string(),
+            instructions: "You support {{customer_name}}.".to_string(),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+    let thread = harness
+        .orchestrator
+        .stores
+        .thread_store
+        .create_thread(CreateThreadRequest {
+            agent_id: "support".to_string(),
+            title: None,
+            thread_id: None,
+            attributes: None,
+            user_id: None,
+            external_id: None,
+            channel_id: None,
+        })
+        .await
+        .unwrap();
+    harness
+        .orchestrator
+        .update_thread(
+            &thread.id,
+            set_variables(&[
+                ("customer_name", "O'Brien & Co"),
+                ("environment", "staging"),
+            ]),
+        )
+        .await
+        .unwrap();
+
+    harness
+        .run_on_thread(
+            "support",
+            &thread.id,
+            "Check {{environment}} for {{ticket}}",
+        )
+        .await
+        .assert_success();
+    let requests = harness.llm.requests();
+    assert!(texts(&requests[0], MessageRole::System)[0].contains("You support O'Brien & Co."));
+    let users = texts(&requests[0], MessageRole::User);
+    // Unknown placeholders are left as they are.
+    assert!(users
+        .iter()
+        .any(|t| t.contains("Check staging for {{ticket}}")));
+
+    // A changed variable applies to the next request.
+    harness
+        .orchestrator
+        .update_thread(
+            &thread.id,
+            set_variables(&[("customer_name", "Acme"), ("environment", "production")]),
+        )
+        .await
+        .unwrap();
+    harness
+        .run_on_thread("support", &thread.id, "And {{environment}}?")
+        .await
+        .assert_success();
+    let requests = harness.llm.requests();
+    assert!(texts(&requests[1], MessageRole::System)[0].contains("You support Acme."));
+    let users = texts(&requests[1], MessageRole::User);
+    assert!(users.iter().any(|t| t.contains("And production?")));
+    assert!(users.iter().all(|t| !t.contains("{{environment}}")));
+}
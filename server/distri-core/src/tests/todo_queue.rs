@@ -0,0 +1,113 @@
+use distri_types::{AgentEventType, TodoQueueSummary, ToolsConfig};
+use serde_json::json;
+
+use crate::testing::{AgentTestHarness, MockLlmProvider};
+use crate::types::StandardDefinition;
+
+async fn harness(llm: MockLlmProvider) -> AgentTestHarness {
+    let harness = AgentTestHarness::new(llm).await.unwrap();
+    harness
+        .register_agent(StandardDefinition {
+            name: "planner".to_string(),
+            description: "plans and works a todo list".to_string(),
+            tools: Some(ToolsConfig {
+                builtin: vec!["write_todos".to_string(), "work_todos".to_string()],
+                ..Default::default()
+            }),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    harness
+        .register_agent(StandardDefinition {
+            name: "_adhoc_base".to_string(),
+            description: "ad-hoc worker".to_string(),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    harness
+}
+
+fn queue_summary(events: &[crate::agent::types::AgentEvent]) -> TodoQueueSummary {
+    events
+        .iter()
+        .find_map(|e| match &e.event {
+            AgentEventType::TodoQueueFinished { summary } => Some(summary.clone()),
+            _ => None,
+        })
+        .expect("work_todos emits a TodoQueueFinished summary")
+}
+
+#[tokio::test]
+async fn work_todos_runs_items_in_dependency_and_priority_order() {
+    let llm = MockLlmProvider::new()
+        .respond_tool_call(
+            "write_todos",
+            json!({ "todos": [
+                { "content": "Ping team", "priority": "low" },
+                { "content": "Write report", "priority": "high", "depends_on": ["Collect data"] },
+                { "content": "Collect data" },
+            ]}),
+        )
+        .respond_tool_call("work_todos", json!({}))
+        .respond_final("42 orders")
+        .respond_final("report written")
+        .respond_final("team pinged")
+        .respond_final("all done");
+    let harness = harness(llm.clone()).await;
+
+    let run = harness
+        .run("planner", "Plan and do the weekly report")
+        .await;
+
+    run.assert_success();
+    llm.assert_exhausted();
+    let summary = queue_summary(&run.events);
+    let titles: Vec<&str> = summary.completed.iter().map(|o| o.title.as_str()).collect();
+    assert_eq!(titles, vec!["Collect data", "Write report", "Ping team"]);
+    assert_eq!(summary.completed[0].result.as_deref(), Some("42 orders"));
+    assert!(summary.failed.is_empty() && summary.blocked.is_empty());
+
+    // Each item ran as its own sub-task, and a dependent item is briefed
+    // with what its dependencies reported.
+    let requests = llm.requests();
+    let report_brief = requests[3]
+        .messages
+        .iter()
+        .filter_map(|m| m.as_text())
+        .collect::<Vec<_>>()
+        .join("\n");
+    assert_eq!(requests[3].agent_id, "_adhoc_base");
+    assert!(report_brief.contains("Todo: Write report"));
+    assert!(report_brief.contains("- Collect data: 42 orders"));
+    assert!(!requests[3].tool_names.contains(&"work_todos".to_string()));
+}
+
+#[tokio::test]
+async fn failed_item_blocks_its_dependents() {
+    let llm = MockLlmProvider::new()
+        .respond_tool_call(
+            "write_todos",
+            json!({ "todos": [
+                { "content": "Migrate schema" },
+                { "content": "Backfill rows", "depends_on": ["Migrate schema"] },
+            ]}),
+        )
+        .respond_tool_call("work_todos", json!({}))
+        // The item's run gives up after three planning failures in a row.
+        .respond_error("provider unavailable")
+        .respond_error("provider unavailable")
+        .respond_error("provider unavailable")
+        .respond_final("stopped");
+    let harness = harness(llm.clone()).await;
+
+    let run = harness.run("planner", "Migrate and backfill").await;
+
+    run.assert_success();
+    let summary = queue_summary(&run.events);
+    assert!(summary.completed.is_empty());
+    assert_eq!(summary.failed.len(), 1);
+    assert_eq!(summary.failed[0].title, "Migrate schema");
+    assert_eq!(summary.blocked, vec!["Backfill rows"]);
+}
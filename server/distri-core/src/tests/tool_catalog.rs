@@ -0,0 +1,153 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use distri_types::integration::Integration;
+use distri_types::tool_catalog::{CollisionRule, ToolCollisionConfig};
+use distri_types::{AgentEventType, Part, Tool, ToolCall, ToolContext, ToolsConfig};
+use serde_json::{json, Value};
+
+use crate::testing::{AgentTestHarness, MockLlmProvider, TestRun};
+use crate::types::StandardDefinition;
+
+/// A `lookup` tool answering with its origin.
+#[derive(Debug)]
+struct Lookup(&'static str);
+
+#[async_trait::async_trait]
+impl Tool for Lookup {
+    fn get_name(&self) -> String {
+        "lookup".to_string()
+    }
+
+    fn get_description(&self) -> String {
+        format!("Look things up in {}", self.0)
+    }
+
+    fn get_parameters(&self) -> Value {
+        json!({ "type": "object" })
+    }
+
+    async fn execute(
+        &self,
+        _tool_call: ToolCall,
+        _context: Arc<ToolContext>,
+    ) -> Result<Vec<Part>, anyhow::Error> {
+        Ok(vec![Part::Text(format!("from {}", self.0))])
+    }
+}
+
+#[derive(Debug)]
+struct AcmePlugin;
+
+impl Integration for AcmePlugin {
+    fn get_name(&self) -> String {
+        "acme".to_string()
+    }
+
+    fn get_description(&self) -> String {
+        "ACME directory".to_string()
+    }
+
+    fn get_tools(&self) -> Vec<Arc<dyn Tool>> {
+        vec![Arc::new(Lookup("acme"))]
+    }
+}
+
+async fn harness(llm: MockLlmProvider, collisions: ToolCollisionConfig) -> AgentTestHarness {
+    let harness = AgentTestHarness::new(llm).await.unwrap();
+    harness
+        .register_agent(StandardDefinition {
+            name: "researcher".to_string(),
+            tools: Some(ToolsConfig {
+                collisions,
+                ..Default::default()
+            }),
+            tool_aliases: BTreeMap::from([("host_lookup".to_string(), "host/lookup".to_string())]),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    harness
+        .orchestrator
+        .register_tool("researcher", Arc::new(Lookup("host")))
+        .await;
+    harness
+        .orchestrator
+        .register_integration("researcher", &AcmePlugin)
+        .await;
+    harness
+}
+
+fn tool_texts(run: &TestRun) -> Vec<Part> {
+    run.events
+        .iter()
+        .filter_map(|e| match &e.event {
+            AgentEventType::ToolResults { results, .. } => Some(results),
+            _ => None,
+        })
+        .flatten()
+        .flat_map(|r| r.parts.clone())
+        .collect()
+}
+
+#[tokio::test]
+async fn preferred_tool_wins_and_the_shadowed_one_runs_through_its_alias() {
+    let llm = MockLlmProvider::new()
+        .respond_tool_call("lookup", json!({}))
+        .respond_tool_call("host_lookup", json!({}))
+        .respond_final("done");
+    let collisions = ToolCollisionConfig {
+        prefer: BTreeMap::from([("lookup".to_string(), "acme".to_string())]),
+        ..Default::default()
+    };
+    let harness = harness(llm.clone(), collisions).await;
+
+    let run = harness.run("researcher", "Look it up").await;
+
+    run.assert_success();
+    let tool_names = &llm.requests()[0].tool_names;
+    assert_eq!(
+        tool_names.iter().filter(|n| n.as_str() == "lookup").count(),
+        1
+    );
+    assert!(tool_names.iter().any(|n| n == "host_lookup"));
+    assert_eq!(
+        tool_texts(&run),
+        [
+            Part::Text("from acme".to_string()),
+            Part::Text("from host".to_string())
+        ]
+    );
+
+    let resolution = harness
+        .orchestrator
+        .resolve_agent_tool("researcher", "lookup")
+        .await
+        .unwrap();
+    assert_eq!(resolution.tool.unwrap().qualified_name, "acme/lookup");
+    let collision = resolution.collision.unwrap();
+    assert_eq!(collision.rule, CollisionRule::Preferred);
+    assert_eq!(collision.shadowed[0].qualified_name, "host/lookup");
+}
+
+#[tokio::test]
+async fn strict_collisions_fail_the_agent() {
+    let harness = harness(
+        MockLlmProvider::new(),
+        ToolCollisionConfig {
+            strict: true,
+            ..Default::default()
+        },
+    )
+    .await;
+
+    let err = harness
+        .orchestrator
+        .resolve_agent_tool("researcher", "lookup")
+        .await
+        .unwrap_err();
+    assert!(
+        err.to_string().contains("host/lookup, acme/lookup"),
+        "{err}"
+    );
+}
@@ -0,0 +1,121 @@
+use std::sync::Arc;
+
+use distri_types::tool_output::ToolOutputValidation;
+use distri_types::{AgentEventType, Part, Tool, ToolCall, ToolContext, ToolsConfig};
+use serde_json::{json, Value};
+
+use crate::testing::{AgentTestHarness, MockLlmProvider, TestRun};
+use crate::types::StandardDefinition;
+
+/// A `lookup` tool declaring `{ "id": integer }` output, which answers
+/// with `result`.
+#[derive(Debug)]
+struct Lookup {
+    result: Value,
+}
+
+#[async_trait::async_trait]
+impl Tool for Lookup {
+    fn get_name(&self) -> String {
+        "lookup".to_string()
+    }
+
+    fn get_description(&self) -> String {
+        "Look up a record".to_string()
+    }
+
+    fn get_parameters(&self) -> Value {
+        json!({ "type": "object" })
+    }
+
+    fn get_output_schema(&self) -> Option<Value> {
+        Some(json!({
+            "type": "object",
+            "properties": { "id": { "type": "integer" } },
+            "required": ["id"]
+        }))
+    }
+
+    async fn execute(
+        &self,
+        _tool_call: ToolCall,
+        _context: Arc<ToolContext>,
+    ) -> Result<Vec<Part>, anyhow::Error> {
+        Ok(vec![Part::Data(self.result.clone())])
+    }
+}
+
+async fn run(output_validation: ToolOutputValidation, result: Value) -> TestRun {
+    let llm = MockLlmProvider::new()
+        .respond_tool_call("lookup", json!({}))
+        .respond_final("done");
+    let harness = AgentTestHarness::new(llm).await.unwrap();
+    harness
+        .register_agent(StandardDefinition {
+            name: "clerk".to_string(),
+            tools: Some(ToolsConfig {
+                output_validation,
+                ..Default::default()
+            }),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    harness
+        .orchestrator
+        .register_tool("clerk", Arc::new(Lookup { result }))
+        .await;
+    harness.run("clerk", "Find it").await
+}
+
+/// `violations` of each `ToolOutputInvalid` event.
+fn violations(run: &TestRun) -> Vec<Vec<String>> {
+    run.events
+        .iter()
+        .filter_map(|e| match &e.event {
+            AgentEventType::ToolOutputInvalid { violations, .. } => Some(violations.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+fn structured_outputs(run: &TestRun) -> Vec<Value> {
+    run.events
+        .iter()
+        .filter_map(|e| match &e.event {
+            AgentEventType::ToolResults { results, .. } => Some(results),
+            _ => None,
+        })
+        .flatten()
+        .filter_map(|r| r.structured_output())
+        .collect()
+}
+
+#[tokio::test]
+async fn dev_mode_reports_an_output_breaking_the_schema() {
+    let run = run(ToolOutputValidation::Dev, json!({ "id": "seven" })).await;
+
+    run.assert_success();
+    let violations = violations(&run);
+    assert_eq!(violations.len(), 1);
+    assert!(violations[0][0].contains("seven"), "{violations:?}");
+    // The model still gets the result.
+    assert_eq!(structured_outputs(&run), [json!({ "id": "seven" })]);
+}
+
+#[tokio::test]
+async fn a_conforming_output_is_handed_on_typed() {
+    let run = run(ToolOutputValidation::Dev, json!({ "id": 7 })).await;
+
+    run.assert_success();
+    assert!(violations(&run).is_empty());
+    assert_eq!(structured_outputs(&run), [json!({ "id": 7 })]);
+}
+
+#[tokio::test]
+async fn violations_are_only_logged_by_default() {
+    let run = run(ToolOutputValidation::default(), json!({})).await;
+
+    run.assert_success();
+    assert!(violations(&run).is_empty());
+}
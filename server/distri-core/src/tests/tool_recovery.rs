@@ -0,0 +1,199 @@
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use distri_types::tool_recovery::{ToolRecoveryConfig, ToolRecoveryStrategy};
+use distri_types::{AgentEventType, Part, Tool, ToolCall, ToolContext, ToolsConfig};
+use serde_json::{json, Value};
+
+use crate::testing::{AgentTestHarness, MockLlmProvider, TestRun};
+use crate::types::StandardDefinition;
+
+/// A `fetch` tool failing its first `failures` calls.
+#[derive(Debug)]
+struct Fetch {
+    failures: usize,
+    calls: AtomicUsize,
+}
+
+#[async_trait::async_trait]
+impl Tool for Fetch {
+    fn get_name(&self) -> String {
+        "fetch".to_string()
+    }
+
+    fn get_description(&self) -> String {
+        "Fetch a URL".to_string()
+    }
+
+    fn get_parameters(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": { "url": { "type": "string" } },
+            "required": ["url"]
+        })
+    }
+
+    async fn execute(
+        &self,
+        _tool_call: ToolCall,
+        _context: Arc<ToolContext>,
+    ) -> Result<Vec<Part>, anyhow::Error> {
+        if self.calls.fetch_add(1, Ordering::SeqCst) < self.failures {
+            anyhow::bail!("connection reset");
+        }
+        Ok(vec![Part::Text("fetched".to_string())])
+    }
+}
+
+/// A `mirror` tool that always succeeds.
+#[derive(Debug)]
+struct Mirror;
+
+#[async_trait::async_trait]
+impl Tool for Mirror {
+    fn get_name(&self) -> String {
+        "mirror".to_string()
+    }
+
+    fn get_description(&self) -> String {
+        "Fetch a URL from the mirror".to_string()
+    }
+
+    fn get_parameters(&self) -> Value {
+        json!({ "type": "object" })
+    }
+
+    async fn execute(
+        &self,
+        _tool_call: ToolCall,
+        _context: Arc<ToolContext>,
+    ) -> Result<Vec<Part>, anyhow::Error> {
+        Ok(vec![Part::Text("from mirror".to_string())])
+    }
+}
+
+async fn run(strategy: ToolRecoveryStrategy, failures: usize, input: Value) -> TestRun {
+    let llm = MockLlmProvider::new()
+        .respond_tool_call("fetch", input)
+        .respond_final("done");
+    let harness = AgentTestHarness::new(llm).await.unwrap();
+    harness
+        .register_agent(StandardDefinition {
+            name: "reader".to_string(),
+            tools: Some(ToolsConfig {
+                recovery: ToolRecoveryConfig {
+                    tools: BTreeMap::from([("fetch".to_string(), strategy)]),
+                    ..Default::default()
+                },
+                ..Default::default()
+            }),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    let fetch = Fetch {
+        failures,
+        calls: AtomicUsize::new(0),
+    };
+    harness
+        .orchestrator
+        .register_tool("reader", Arc::new(fetch))
+        .await;
+    harness
+        .orchestrator
+        .register_tool("reader", Arc::new(Mirror))
+        .await;
+    harness.run("reader", "Read it").await
+}
+
+fn tool_result_text(run: &TestRun) -> String {
+    run.events
+        .iter()
+        .filter_map(|e| match &e.event {
+            AgentEventType::ToolResults { results, .. } => Some(results),
+            _ => None,
+        })
+        .flatten()
+        .flat_map(|r| r.parts.clone())
+        .filter_map(|p| match p {
+            Part::Text(text) => Some(text),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// `(strategy, attempt, recovered)` of each `ToolRecovery` event.
+fn recoveries(run: &TestRun) -> Vec<(String, u32, bool)> {
+    run.events
+        .iter()
+        .filter_map(|e| match &e.event {
+            AgentEventType::ToolRecovery {
+                strategy,
+                attempt,
+                recovered,
+                ..
+            } => Some((strategy.clone(), *attempt, *recovered)),
+            _ => None,
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn errors_are_reported_as_is_by_default() {
+    let run = run(ToolRecoveryStrategy::Report, 1, json!({ "url": "a" })).await;
+    run.assert_success();
+    assert_eq!(tool_result_text(&run), "connection reset");
+    assert!(recoveries(&run).is_empty());
+}
+
+#[tokio::test]
+async fn retry_runs_the_call_again_until_it_succeeds() {
+    let strategy = ToolRecoveryStrategy::Retry {
+        attempts: 3,
+        backoff_ms: 0,
+    };
+    let run = run(strategy, 2, json!({ "url": "a" })).await;
+    run.assert_success();
+    assert_eq!(tool_result_text(&run), "fetched");
+    assert_eq!(
+        recoveries(&run),
+        [
+            ("retry".to_string(), 1, false),
+            ("retry".to_string(), 2, true)
+        ]
+    );
+}
+
+#[tokio::test]
+async fn correct_lists_the_schema_violations() {
+    let run = run(ToolRecoveryStrategy::Correct, 1, json!({})).await;
+    run.assert_success();
+    let text = tool_result_text(&run);
+    assert!(
+        text.starts_with("Tool 'fetch' failed: connection reset"),
+        "{text}"
+    );
+    assert!(text.contains("\"url\" is a required property"), "{text}");
+    assert!(text.contains("Parameters schema:"), "{text}");
+}
+
+#[tokio::test]
+async fn fallback_calls_the_alternate_tool() {
+    let strategy = ToolRecoveryStrategy::Fallback {
+        tool: "mirror".to_string(),
+    };
+    let run = run(strategy, 1, json!({ "url": "a" })).await;
+    run.assert_success();
+    assert!(tool_result_text(&run).ends_with("from mirror"));
+    assert_eq!(recoveries(&run), [("fallback".to_string(), 0, true)]);
+}
+
+#[tokio::test]
+async fn fail_stops_the_run() {
+    let run = run(ToolRecoveryStrategy::Fail, 1, json!({ "url": "a" })).await;
+    let err = run.result.as_ref().unwrap_err();
+    assert!(err.to_string().contains("connection reset"), "{err}");
+    assert_eq!(recoveries(&run), [("fail".to_string(), 0, false)]);
+}
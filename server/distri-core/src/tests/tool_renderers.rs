@@ -0,0 +1,99 @@
+use std::sync::Arc;
+
+use distri_types::integration::Integration;
+use distri_types::renderer_manifest::{CardKind, ToolRendererManifest};
+use distri_types::{Part, Tool, ToolCall, ToolContext};
+use serde_json::{json, Value};
+
+use crate::testing::{AgentTestHarness, MockLlmProvider};
+use crate::types::StandardDefinition;
+
+#[derive(Debug)]
+struct NamedTool(&'static str);
+
+#[async_trait::async_trait]
+impl Tool for NamedTool {
+    fn get_name(&self) -> String {
+        self.0.to_string()
+    }
+
+    fn get_description(&self) -> String {
+        format!("The {} tool", self.0)
+    }
+
+    fn get_parameters(&self) -> Value {
+        json!({ "type": "object" })
+    }
+
+    async fn execute(
+        &self,
+        _tool_call: ToolCall,
+        _context: Arc<ToolContext>,
+    ) -> Result<Vec<Part>, anyhow::Error> {
+        Ok(vec![Part::Data(json!({ "orders": [] }))])
+    }
+}
+
+#[derive(Debug)]
+struct CrmPlugin;
+
+impl Integration for CrmPlugin {
+    fn get_name(&self) -> String {
+        "crm".to_string()
+    }
+
+    fn get_description(&self) -> String {
+        "CRM API".to_string()
+    }
+
+    fn get_tools(&self) -> Vec<Arc<dyn Tool>> {
+        vec![
+            Arc::new(NamedTool("crm_get_orders")),
+            Arc::new(NamedTool("crm_get_customer")),
+            Arc::new(NamedTool("crm_ping")),
+        ]
+    }
+
+    fn get_ui_renderers(&self) -> Vec<ToolRendererManifest> {
+        serde_json::from_value(json!([
+            {
+                "tool": "crm_get_orders",
+                "icon": "shopping-cart",
+                "card": {
+                    "kind": "table",
+                    "items": "/orders",
+                    "fields": [{ "label": "Order", "path": "/id" }]
+                }
+            },
+            // A table without items is dropped.
+            { "tool": "crm_get_customer", "card": { "kind": "table" } }
+        ]))
+        .unwrap()
+    }
+}
+
+#[tokio::test]
+async fn plugin_renderers_are_served_for_registered_tools() {
+    let harness = AgentTestHarness::new(MockLlmProvider::new()).await.unwrap();
+    for agent in ["sales", "support"] {
+        harness
+            .register_agent(StandardDefinition {
+                name: agent.to_string(),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        harness
+            .orchestrator
+            .register_integration(agent, &CrmPlugin)
+            .await;
+    }
+
+    let renderers = harness.orchestrator.tool_renderers().await;
+
+    assert_eq!(renderers.len(), 1, "{renderers:?}");
+    assert_eq!(renderers[0].plugin.as_deref(), Some("crm"));
+    assert_eq!(renderers[0].manifest.tool, "crm_get_orders");
+    assert_eq!(renderers[0].manifest.icon.as_deref(), Some("shopping-cart"));
+    assert_eq!(renderers[0].manifest.card.kind, CardKind::Table);
+}
@@ -0,0 +1,119 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use distri_types::tool_timeouts::ToolTimeoutConfig;
+use distri_types::{AgentEventType, Part, Tool, ToolCall, ToolContext, ToolsConfig};
+use serde_json::{json, Value};
+
+use crate::testing::{AgentTestHarness, MockLlmProvider, TestRun};
+use crate::types::StandardDefinition;
+
+/// A `lookup` tool that answers after `delay`.
+#[derive(Debug)]
+struct Lookup {
+    delay: Duration,
+}
+
+#[async_trait::async_trait]
+impl Tool for Lookup {
+    fn get_name(&self) -> String {
+        "lookup".to_string()
+    }
+
+    fn get_description(&self) -> String {
+        "Look up a record".to_string()
+    }
+
+    fn get_parameters(&self) -> Value {
+        json!({ "type": "object" })
+    }
+
+    async fn execute(
+        &self,
+        _tool_call: ToolCall,
+        _context: Arc<ToolContext>,
+    ) -> Result<Vec<Part>, anyhow::Error> {
+        tokio::time::sleep(self.delay).await;
+        Ok(vec![Part::Text("found".to_string())])
+    }
+}
+
+async fn run(timeouts: ToolTimeoutConfig, delay: Duration) -> TestRun {
+    let llm = MockLlmProvider::new()
+        .respond_tool_call("lookup", json!({}))
+        .respond_final("done");
+    let harness = AgentTestHarness::new(llm).await.unwrap();
+    harness
+        .register_agent(StandardDefinition {
+            name: "clerk".to_string(),
+            tools: Some(ToolsConfig {
+                timeouts,
+                ..Default::default()
+            }),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    harness
+        .orchestrator
+        .register_tool("clerk", Arc::new(Lookup { delay }))
+        .await;
+    harness.run("clerk", "Find it").await
+}
+
+fn tool_result_parts(run: &TestRun) -> Vec<Part> {
+    run.events
+        .iter()
+        .filter_map(|e| match &e.event {
+            AgentEventType::ToolResults { results, .. } => Some(results),
+            _ => None,
+        })
+        .flatten()
+        .flat_map(|r| r.parts.clone())
+        .collect()
+}
+
+/// `timeout_secs` of each `ToolTimeout` event.
+fn timeouts(run: &TestRun) -> Vec<u64> {
+    run.events
+        .iter()
+        .filter_map(|e| match &e.event {
+            AgentEventType::ToolTimeout { timeout_secs, .. } => Some(*timeout_secs),
+            _ => None,
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn a_slow_call_times_out_with_a_result_the_model_sees() {
+    let config = ToolTimeoutConfig {
+        tools: BTreeMap::from([("lookup".to_string(), 1)]),
+        ..Default::default()
+    };
+    let started = std::time::Instant::now();
+    let run = run(config, Duration::from_secs(30)).await;
+
+    run.assert_success();
+    assert!(started.elapsed() < Duration::from_secs(10));
+    assert_eq!(timeouts(&run), [1]);
+    let parts = tool_result_parts(&run);
+    let Some(Part::Data(result)) = parts.first() else {
+        panic!("expected a data result, got {parts:?}");
+    };
+    assert_eq!(result["error"], "tool_timeout");
+    assert_eq!(result["timeout_secs"], 1);
+}
+
+#[tokio::test]
+async fn a_call_within_its_limit_is_not_affected() {
+    let config = ToolTimeoutConfig {
+        default_secs: Some(5),
+        ..Default::default()
+    };
+    let run = run(config, Duration::from_millis(10)).await;
+
+    run.assert_success();
+    assert!(timeouts(&run).is_empty());
+    assert_eq!(tool_result_parts(&run), [Part::Text("found".to_string())]);
+}
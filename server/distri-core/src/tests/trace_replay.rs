@@ -378,6 +378,8 @@ impl LLMExecutorTrait for TraceReplayExecutor {
             finish_reason: response.finish_reason,
             tool_calls: response.tool_calls,
             content: response.content,
+            usage: response.usage,
+            first_token_at: Some(std::time::Instant::now()),
         })
     }
 }
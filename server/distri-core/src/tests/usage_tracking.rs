@@ -86,6 +86,28 @@ async fn cached_tokens_delta_tracked_correctly() {
     assert_eq!(total.output_tokens, 200);
 }
 
+#[tokio::test]
+async fn cache_writes_tracked_per_step_and_in_total() {
+    let ctx = make_context();
+
+    ctx.snapshot_step_start().await;
+    ctx.increment_usage_with_cache(2000, 100, 0).await;
+    ctx.increment_cache_writes(1800).await;
+    let step1 = ctx.get_step_usage().await;
+    assert_eq!(step1.cache_write_tokens, 1800);
+
+    // The next call reads what the first one wrote.
+    ctx.snapshot_step_start().await;
+    ctx.increment_usage_with_cache(2100, 100, 1800).await;
+    let step2 = ctx.get_step_usage().await;
+    assert_eq!(step2.cache_write_tokens, 0);
+    assert_eq!(step2.cached_tokens, 1800);
+
+    let total = ctx.get_total_usage().await;
+    assert_eq!(total.cache_write_tokens, 1800);
+    assert_eq!(total.input_tokens, 4100, "cache writes are not extra input");
+}
+
 #[tokio::test]
 async fn cost_estimated_when_model_is_known() {
     let ctx = make_context();
@@ -0,0 +1,151 @@
+//! User profiles: the stored profile of the run's user reaches the system
+//! prompt of agents that opt into `user_profile`, with the thread's own
+//! `user_profile` session value overriding single fields.
+
+use distri_types::prompt::{PromptLayersConfig, RuntimeContextItem};
+use distri_types::user_profile::{ExpertiseLevel, UserProfile};
+use serde_json::json;
+
+use crate::testing::{AgentTestHarness, MockLlmProvider, TEST_USER_ID};
+use crate::types::StandardDefinition;
+use crate::AgentError;
+
+fn system_prompt(llm: &MockLlmProvider, request: usize) -> String {
+    format!("{:?}", llm.requests()[request].messages)
+}
+
+#[tokio::test]
+async fn stored_profiles_reach_opted_in_agents() {
+    let llm = MockLlmProvider::new()
+        .respond_final("Hallo.")
+        .respond_final("Hallo.")
+        .respond_final("Hello.");
+    let harness = AgentTestHarness::new(llm.clone()).await.unwrap();
+    let agent = |name: &str, runtime: Vec<RuntimeContextItem>| StandardDefinition {
+        name: name.to_string(),
+        description: "answers questions".to_string(),
+        prompt_layers: Some(PromptLayersConfig {
+            runtime,
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    harness
+        .register_agent(agent("assistant", vec![RuntimeContextItem::UserProfile]))
+        .await
+        .unwrap();
+    harness
+        .register_agent(agent("plain", vec![RuntimeContextItem::Date]))
+        .await
+        .unwrap();
+
+    let stored = harness
+        .orchestrator
+        .set_user_profile(
+            TEST_USER_ID,
+            UserProfile {
+                tone: Some("concise".to_string()),
+                language: Some("de_at".to_string()),
+                expertise: Some(ExpertiseLevel::Expert),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(stored.language.as_deref(), Some("de-AT"));
+
+    harness.run("assistant", "Hi").await.assert_success();
+    let prompt = system_prompt(&llm, 0);
+    assert!(prompt.contains("- Tone: concise"), "{prompt}");
+    assert!(prompt.contains("- Language: de-AT"));
+    assert!(prompt.contains("- Expertise: expert"));
+
+    // The thread's own value overrides single fields.
+    harness
+        .orchestrator
+        .stores
+        .session_store
+        .set_value(
+            "thread-formal",
+            "user_profile",
+            &json!({ "tone": "formal" }),
+        )
+        .await
+        .unwrap();
+    harness
+        .run_on_thread("assistant", "thread-formal", "Hi")
+        .await
+        .assert_success();
+    let prompt = system_prompt(&llm, 1);
+    assert!(prompt.contains("- Tone: formal"));
+    assert!(prompt.contains("- Expertise: expert"));
+
+    // Agents that do not opt in never see it.
+    harness.run("plain", "Hi").await.assert_success();
+    assert!(!system_prompt(&llm, 2).contains("Expertise"));
+}
+
+#[tokio::test]
+async fn profiles_are_validated_merged_and_removed() {
+    let harness = AgentTestHarness::new(MockLlmProvider::new()).await.unwrap();
+    let orchestrator = &harness.orchestrator;
+    assert!(orchestrator
+        .get_user_profile("ana")
+        .await
+        .unwrap()
+        .is_empty());
+
+    let invalid = orchestrator
+        .set_user_profile(
+            "ana",
+            UserProfile {
+                timezone: Some("Mars/Olympus".to_string()),
+                ..Default::default()
+            },
+        )
+        .await;
+    assert!(matches!(invalid, Err(AgentError::Validation(_))));
+
+    let mut patch = UserProfile {
+        timezone: Some("Europe/Lisbon".to_string()),
+        ..Default::default()
+    };
+    patch
+        .custom
+        .insert("units".to_string(), "metric".to_string());
+    orchestrator
+        .update_user_profile("ana", patch)
+        .await
+        .unwrap();
+    let updated = orchestrator
+        .update_user_profile(
+            "ana",
+            UserProfile {
+                tone: Some("friendly".to_string()),
+                timezone: Some(String::new()),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(updated.tone.as_deref(), Some("friendly"));
+    assert_eq!(updated.timezone, None);
+    assert_eq!(updated.custom["units"], "metric");
+    assert_eq!(orchestrator.get_user_profile("ana").await.unwrap(), updated);
+    // Profiles are per user.
+    assert!(orchestrator
+        .get_user_profile("bo")
+        .await
+        .unwrap()
+        .is_empty());
+
+    orchestrator
+        .set_user_profile("ana", UserProfile::default())
+        .await
+        .unwrap();
+    assert!(orchestrator
+        .get_user_profile("ana")
+        .await
+        .unwrap()
+        .is_empty());
+}
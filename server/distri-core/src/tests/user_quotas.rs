@@ -0,0 +1,129 @@
+use std::sync::Arc;
+
+use chrono::{Duration, TimeZone, Utc};
+use distri_types::user_quotas::{QuotaKind, RaiseQuotaRequest, UserQuotaLimits};
+
+use crate::agent::user_quotas::UserQuotas;
+use crate::agent::ExecutorContext;
+use crate::testing::{AgentTestHarness, MockLlmProvider};
+
+fn quotas(limits: UserQuotaLimits) -> Arc<UserQuotas> {
+    Arc::new(UserQuotas::new(limits))
+}
+
+#[test]
+fn concurrent_tasks_free_up_as_runs_finish() {
+    let quotas = quotas(UserQuotaLimits {
+        max_concurrent_tasks: Some(2),
+        ..Default::default()
+    });
+    let first = quotas.admit("alice").expect("first run");
+    let _second = quotas.admit("alice").expect("second run");
+
+    let exceeded = quotas.admit("alice").err().expect("third run is refused");
+    assert_eq!(exceeded.quota, QuotaKind::ConcurrentTasks);
+    assert_eq!((exceeded.limit, exceeded.used), (2, 2));
+    assert!(exceeded.resets_at.is_none());
+    assert!(quotas.admit("bob").is_ok(), "other users are not limited");
+
+    drop(first);
+    assert_eq!(quotas.status("alice").running_tasks, 1);
+    assert!(quotas.admit("alice").is_ok());
+}
+
+#[test]
+fn refunded_runs_do_not_count_towards_the_day() {
+    let quotas = quotas(UserQuotaLimits {
+        max_tasks_per_day: Some(1),
+        ..Default::default()
+    });
+    quotas.admit("alice").expect("first run").refund();
+    let status = quotas.status("alice");
+    assert_eq!((status.running_tasks, status.tasks_today), (0, 0));
+    assert!(quotas.admit("alice").is_ok());
+}
+
+#[test]
+fn daily_counts_start_over_at_utc_midnight() {
+    let quotas = quotas(UserQuotaLimits {
+        max_tasks_per_day: Some(1),
+        max_tokens_per_day: Some(1_000),
+        ..Default::default()
+    });
+    let morning = Utc.with_ymd_and_hms(2026, 3, 2, 9, 0, 0).unwrap();
+    let midnight = Utc.with_ymd_and_hms(2026, 3, 3, 0, 0, 0).unwrap();
+
+    drop(quotas.admit_at("alice", morning).expect("first run"));
+    let exceeded = quotas
+        .admit_at("alice", morning)
+        .err()
+        .expect("second run today is refused");
+    assert_eq!(exceeded.quota, QuotaKind::TasksPerDay);
+    assert_eq!(exceeded.resets_at, Some(midnight));
+
+    let tomorrow = midnight + Duration::hours(1);
+    drop(quotas.admit_at("alice", tomorrow).expect("new day"));
+    quotas.record_tokens_at("alice", 1_200, tomorrow);
+    let status = quotas.status_at("alice", tomorrow);
+    assert_eq!((status.tasks_today, status.tokens_today), (1, 1_200));
+}
+
+#[test]
+fn token_quota_refuses_the_next_run() {
+    let quotas = quotas(UserQuotaLimits {
+        max_tokens_per_day: Some(500),
+        ..Default::default()
+    });
+    let _run = quotas.admit("alice").expect("under quota");
+    quotas.record_tokens("alice", 700);
+
+    let exceeded = quotas.admit("alice").err().expect("over the token quota");
+    assert_eq!(exceeded.quota, QuotaKind::TokensPerDay);
+    assert_eq!((exceeded.limit, exceeded.used), (500, 700));
+}
+
+#[test]
+fn raises_add_to_the_limits_until_they_expire() {
+    let quotas = quotas(UserQuotaLimits {
+        max_tasks_per_day: Some(1),
+        ..Default::default()
+    });
+    drop(quotas.admit("alice").unwrap());
+    assert!(quotas.admit("alice").is_err());
+
+    let status = quotas.raise(
+        "alice",
+        &RaiseQuotaRequest {
+            extra_tasks_per_day: 2,
+            extra_tokens_per_day: 1_000,
+            duration_secs: 3600,
+            ..Default::default()
+        },
+    );
+    assert_eq!(status.limits.max_tasks_per_day, Some(3));
+    assert_eq!(status.limits.max_tokens_per_day, None, "stays unlimited");
+    drop(quotas.admit("alice").expect("raised"));
+
+    let expired = status.raise.expect("raise").expires_at + Duration::seconds(1);
+    let status = quotas.status_at("alice", expired);
+    assert!(status.raise.is_none());
+    assert_eq!(status.limits.max_tasks_per_day, Some(1));
+}
+
+#[tokio::test]
+async fn model_tokens_count_towards_the_user() -> anyhow::Result<()> {
+    let harness = AgentTestHarness::new(MockLlmProvider::new()).await?;
+    let orchestrator = harness.orchestrator.clone();
+    let context = ExecutorContext {
+        user_id: "alice".to_string(),
+        orchestrator: Some(orchestrator.clone()),
+        ..Default::default()
+    };
+
+    context.increment_usage(120, 30).await;
+    context.increment_usage_with_cache(50, 0, 40).await;
+
+    assert_eq!(orchestrator.user_quotas.status("alice").tokens_today, 200);
+    assert_eq!(orchestrator.user_quotas.status("bob").tokens_today, 0);
+    Ok(())
+}
@@ -0,0 +1,122 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use distri_types::warm_sessions::WarmSessionsConfig;
+
+use crate::agent::warm_sessions::WarmSessions;
+use crate::testing::{AgentTestHarness, MockLlmProvider};
+use crate::tools::{FinalTool, ResolvedTools};
+use crate::types::StandardDefinition;
+use crate::AgentOrchestratorBuilder;
+
+fn no_tools() -> ResolvedTools {
+    ResolvedTools {
+        full_schema_tools: vec![],
+        deferred_tools: vec![],
+        all_tools: vec![],
+        deferred_token_savings: 0,
+        catalog: Default::default(),
+    }
+}
+
+fn warm_sessions(capacity: usize) -> WarmSessions {
+    WarmSessions::new(&WarmSessionsConfig { capacity })
+}
+
+#[test]
+fn hits_need_the_same_fingerprint_and_count_the_time_saved() {
+    let warm = warm_sessions(4);
+    assert!(warm.get("t1", 7).is_none());
+    warm.insert("t1", "coder", 7, no_tools(), Duration::from_millis(40));
+
+    assert!(warm.get("t1", 7).is_some());
+    assert!(warm.get("t1", 8).is_none(), "definition changed");
+    assert!(warm.get("t1", 7).is_some());
+
+    let status = warm.status();
+    assert_eq!((status.hits, status.misses), (2, 2));
+    assert_eq!(status.saved_ms, 80);
+    assert_eq!(status.sessions[0].agent_id.as_deref(), Some("coder"));
+    assert_eq!(status.sessions[0].build_ms, 40);
+    assert_eq!(status.sessions[0].hits, 2);
+}
+
+#[test]
+fn least_recently_used_unpinned_thread_is_evicted() {
+    let warm = warm_sessions(2);
+    warm.pin("pinned");
+    warm.insert("pinned", "coder", 1, no_tools(), Duration::ZERO);
+    warm.insert("old", "coder", 1, no_tools(), Duration::ZERO);
+    warm.insert("new", "coder", 1, no_tools(), Duration::ZERO);
+
+    let threads: Vec<String> = warm
+        .status()
+        .sessions
+        .into_iter()
+        .map(|s| s.thread_id)
+        .collect();
+    assert_eq!(threads, ["new", "pinned"]);
+
+    assert!(warm.unpin("pinned"));
+    assert!(!warm.unpin("pinned"));
+    warm.insert("newest", "coder", 1, no_tools(), Duration::ZERO);
+    assert!(warm.get("pinned", 1).is_none());
+}
+
+#[test]
+fn invalidation_keeps_pins_but_drops_registries() {
+    let warm = warm_sessions(4);
+    warm.pin("pinned");
+    warm.insert("pinned", "coder", 1, no_tools(), Duration::ZERO);
+    warm.insert("other", "coder", 1, no_tools(), Duration::ZERO);
+
+    warm.invalidate_all();
+
+    let status = warm.status();
+    assert_eq!(status.sessions.len(), 1);
+    assert!(status.sessions[0].pinned);
+    assert_eq!(status.sessions[0].agent_id, None);
+    assert!(warm.get("pinned", 1).is_none());
+}
+
+#[tokio::test]
+async fn second_message_on_a_thread_reuses_its_tool_registry() {
+    let llm = MockLlmProvider::new()
+        .respond_final("one")
+        .respond_final("two")
+        .respond_final("three");
+    let builder = AgentOrchestratorBuilder::default()
+        .with_warm_sessions(Some(WarmSessionsConfig { capacity: 8 }));
+    let harness = AgentTestHarness::from_builder(builder, llm).await.unwrap();
+    harness
+        .register_agent(StandardDefinition {
+            name: "assistant".to_string(),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    let warm = harness.orchestrator.warm_sessions.clone().unwrap();
+
+    harness
+        .run_on_thread("assistant", "t1", "hello")
+        .await
+        .assert_success();
+    harness
+        .run_on_thread("assistant", "t1", "again")
+        .await
+        .assert_success();
+    let status = warm.status();
+    assert_eq!((status.hits, status.misses), (1, 1));
+    assert_eq!(status.sessions[0].agent_id.as_deref(), Some("assistant"));
+
+    // New tools may belong in the registry: it is assembled again.
+    harness
+        .orchestrator
+        .register_tool("assistant", Arc::new(FinalTool))
+        .await;
+    harness
+        .run_on_thread("assistant", "t1", "once more")
+        .await
+        .assert_success();
+    assert_eq!(warm.status().misses, 2);
+}
@@ -0,0 +1,161 @@
+use std::sync::Arc;
+
+use distri_types::{Part, ToolCall};
+use serde_json::{json, Value};
+
+use crate::agent::ExecutorContext;
+use crate::tests::helpers::test_store_config;
+use crate::tools::working_memory::{
+    MemoryGetTool, MemoryListTool, MemorySetTool, MAX_KEYS, MAX_VALUE_BYTES,
+};
+use crate::tools::ExecutorContextTool;
+use crate::{AgentError, AgentOrchestratorBuilder};
+
+async fn context(thread_id: &str) -> Arc<ExecutorContext> {
+    let orchestrator = Arc::new(
+        AgentOrchestratorBuilder::default()
+            .with_store_config(test_store_config())
+            .build()
+            .await
+            .unwrap(),
+    );
+    Arc::new(ExecutorContext {
+        thread_id: thread_id.to_string(),
+        orchestrator: Some(orchestrator),
+        ..Default::default()
+    })
+}
+
+async fn call(
+    tool: &dyn ExecutorContextTool,
+    input: Value,
+    context: &Arc<ExecutorContext>,
+) -> Result<Value, AgentError> {
+    let call = ToolCall {
+        tool_call_id: uuid::Uuid::new_v4().to_string(),
+        tool_name: tool.get_name(),
+        input,
+    };
+    let parts = tool
+        .execute_with_executor_context(call, context.clone())
+        .await?;
+    match parts.into_iter().next() {
+        Some(Part::Data(data)) => Ok(data),
+        other => panic!("expected a data part, got {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn values_persist_per_thread() {
+    let context = context("thread-a").await;
+    call(
+        &MemorySetTool,
+        json!({ "key": "plan", "value": { "step": 2 } }),
+        &context,
+    )
+    .await
+    .unwrap();
+
+    let got = call(&MemoryGetTool, json!({ "key": "plan" }), &context)
+        .await
+        .unwrap();
+    assert_eq!(got["value"], json!({ "step": 2 }));
+
+    // Another thread on the same store sees nothing.
+    let other = Arc::new(ExecutorContext {
+        thread_id: "thread-b".to_string(),
+        orchestrator: context.orchestrator.clone(),
+        ..Default::default()
+    });
+    let got = call(&MemoryGetTool, json!({ "key": "plan" }), &other)
+        .await
+        .unwrap();
+    assert_eq!(got["value"], Value::Null);
+
+    // A null value forgets the key.
+    call(
+        &MemorySetTool,
+        json!({ "key": "plan", "value": null }),
+        &context,
+    )
+    .await
+    .unwrap();
+    let listed = call(&MemoryListTool, json!({}), &context).await.unwrap();
+    assert_eq!(listed["keys"], json!([]));
+}
+
+#[tokio::test]
+async fn list_filters_by_prefix_and_reports_usage() {
+    let context = context("thread-list").await;
+    for key in ["user.name", "user.city", "draft"] {
+        call(
+            &MemorySetTool,
+            json!({ "key": key, "value": "x", "ttl_secs": 600 }),
+            &context,
+        )
+        .await
+        .unwrap();
+    }
+
+    let listed = call(&MemoryListTool, json!({ "prefix": "user." }), &context)
+        .await
+        .unwrap();
+    let keys: Vec<&str> = listed["keys"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|k| k["key"].as_str().unwrap())
+        .collect();
+    assert_eq!(keys, ["user.city", "user.name"]);
+    assert_eq!(listed["used"]["keys"], 3);
+    assert_eq!(listed["quota"]["keys"], MAX_KEYS);
+}
+
+#[tokio::test]
+async fn quotas_and_keys_are_enforced() {
+    let context = context("thread-quota").await;
+    let large = "x".repeat(MAX_VALUE_BYTES);
+    let err = call(
+        &MemorySetTool,
+        json!({ "key": "big", "value": large }),
+        &context,
+    )
+    .await
+    .unwrap_err();
+    assert!(err.to_string().contains("at most"), "{err}");
+
+    let err = call(
+        &MemorySetTool,
+        json!({ "key": "a b", "value": 1 }),
+        &context,
+    )
+    .await
+    .unwrap_err();
+    assert!(err.to_string().contains("invalid memory key"), "{err}");
+
+    for i in 0..MAX_KEYS {
+        call(
+            &MemorySetTool,
+            json!({ "key": format!("k{i}"), "value": i }),
+            &context,
+        )
+        .await
+        .unwrap();
+    }
+    let err = call(
+        &MemorySetTool,
+        json!({ "key": "one_more", "value": 1 }),
+        &context,
+    )
+    .await
+    .unwrap_err();
+    assert!(err.to_string().contains("forget one"), "{err}");
+    // Replacing an existing key is still allowed.
+    call(
+        &MemorySetTool,
+        json!({ "key": "k0", "value": "new" }),
+        &context,
+    )
+    .await
+    .unwrap();
+}
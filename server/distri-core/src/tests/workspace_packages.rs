@@ -0,0 +1,148 @@
+use std::path::Path;
+
+use distri_types::configuration::AgentConfig;
+
+use crate::tests::helpers::test_store_config;
+use crate::{AgentOrchestrator, AgentOrchestratorBuilder};
+
+fn write(root: &Path, path: &str, contents: &str) {
+    let path = root.join(path);
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, contents).unwrap();
+}
+
+fn agent(name: &str, extra: &str) -> String {
+    format!("---\nname = \"{name}\"\ndescription = \"{name}\"\n{extra}---\nYou are {name}.")
+}
+
+async fn orchestrator(root: &Path) -> AgentOrchestrator {
+    AgentOrchestratorBuilder::default()
+        .with_store_config(test_store_config())
+        .with_workspace_root(root.to_path_buf())
+        .build()
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn build_workspace_registers_every_member_namespaced() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    write(
+        root,
+        "distri.toml",
+        "[workspace]\nmembers = [\"support\", \"teams/*\"]\n",
+    );
+    write(root, "agents/planner.md", &agent("planner", ""));
+    write(
+        root,
+        "support/distri.toml",
+        "[package]\nname = \"support\"\nversion = \"0.3.0\"\n",
+    );
+    write(
+        root,
+        "support/agents/triage.md",
+        &agent("triage", "sub_agents = [\"escalation\", \"planner\"]\n"),
+    );
+    write(
+        root,
+        "support/agents/escalation.md",
+        &agent("escalation", "version = \"1.0.0\"\n"),
+    );
+    write(
+        root,
+        "teams/billing/distri.toml",
+        "[package]\nname = \"billing\"\nversion = \"2.1.0\"\n",
+    );
+    write(
+        root,
+        "teams/billing/agents/refunds.md",
+        &agent("refunds", ""),
+    );
+    // Not a package: no manifest.
+    write(root, "teams/notes/agents/draft.md", &agent("draft", ""));
+
+    let orchestrator = orchestrator(root).await;
+    let build = orchestrator.build_workspace().await.unwrap();
+    assert_eq!(build.agents, vec!["planner"]);
+    let packages: Vec<(&str, &str)> = build
+        .packages
+        .iter()
+        .map(|p| (p.package.name.as_str(), p.path.as_str()))
+        .collect();
+    assert_eq!(
+        packages,
+        vec![("support", "support"), ("billing", "teams/billing")]
+    );
+    assert_eq!(build.packages[1].agents, vec!["billing/refunds"]);
+
+    let Some(AgentConfig::StandardAgent(triage)) = orchestrator.get_agent("support/triage").await
+    else {
+        panic!("support/triage is not registered");
+    };
+    assert_eq!(triage.version.as_deref(), Some("0.3.0"));
+    // Siblings are namespaced; root agents keep their names.
+    assert_eq!(triage.sub_agents, vec!["support/escalation", "planner"]);
+    let Some(AgentConfig::StandardAgent(escalation)) =
+        orchestrator.get_agent("support/escalation").await
+    else {
+        panic!("support/escalation is not registered");
+    };
+    assert_eq!(escalation.version.as_deref(), Some("1.0.0"));
+    assert!(orchestrator.get_agent("planner").await.is_some());
+    assert!(orchestrator.get_agent("draft").await.is_none());
+}
+
+#[tokio::test]
+async fn build_workspace_rejects_invalid_members() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    write(
+        root,
+        "distri.toml",
+        "[workspace]\nmembers = [\"a\", \"b\"]\n",
+    );
+    write(
+        root,
+        "a/distri.toml",
+        "[package]\nname = \"shared\"\nversion = \"0.1.0\"\n",
+    );
+    write(
+        root,
+        "b/distri.toml",
+        "[package]\nname = \"shared\"\nversion = \"0.2.0\"\n",
+    );
+    let err = orchestrator(root)
+        .await
+        .build_workspace()
+        .await
+        .unwrap_err();
+    assert!(
+        err.to_string().contains("more than one workspace member"),
+        "{err}"
+    );
+
+    write(
+        root,
+        "b/distri.toml",
+        "[package]\nname = \"other\"\nversion = \"latest\"\n",
+    );
+    let err = orchestrator(root)
+        .await
+        .build_workspace()
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("MAJOR.MINOR.PATCH"), "{err}");
+
+    write(
+        root,
+        "distri.toml",
+        "[workspace]\nmembers = [\"../outside\"]\n",
+    );
+    let err = orchestrator(root)
+        .await
+        .build_workspace()
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("inside the workspace"), "{err}");
+}
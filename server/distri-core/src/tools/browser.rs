@@ -9,9 +9,12 @@ use browsr_client::{
     BrowserStepRequest, BrowsrClient, CrawlApiRequest, ScrapeApiRequest, ScrapeFormat,
 };
 use browsr_types::{BrowserContext, BrowserStepInput, BrowserToolOptions, Commands, SearchOptions};
+use distri_types::browser::BrowserPolicy;
+use distri_types::configuration::AgentConfig;
 use distri_types::{Part, Tool, ToolContext};
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
+use std::sync::atomic::Ordering;
 use std::sync::Arc;
 
 #[derive(Debug)]
@@ -77,6 +80,10 @@ impl Tool for DistriScrapeSharedTool {
         })
     }
 
+    fn memoizable(&self) -> bool {
+        true
+    }
+
     fn needs_executor_context(&self) -> bool {
         true
     }
@@ -116,7 +123,7 @@ impl ExecutorContextTool for DistriScrapeSharedTool {
     async fn execute_with_executor_context(
         &self,
         tool_call: ToolCall,
-        _context: Arc<ExecutorContext>,
+        context: Arc<ExecutorContext>,
     ) -> Result<Vec<Part>, AgentError> {
         let input = tool_call.input;
 
@@ -124,6 +131,7 @@ impl ExecutorContextTool for DistriScrapeSharedTool {
             .get("url")
             .and_then(|v| v.as_str())
             .ok_or_else(|| AgentError::ToolExecution("Missing 'url' parameter".to_string()))?;
+        check_page_url(&context, url).await?;
 
         let mut request = ScrapeApiRequest::new(url);
 
@@ -254,6 +262,7 @@ impl ExecutorContextTool for DistriBrowserSharedTool {
     ) -> Result<Vec<Part>, AgentError> {
         let options: BrowserToolOptions = serde_json::from_value(tool_call.input)
             .map_err(|e| AgentError::ToolExecution(format!("Invalid browser command: {}", e)))?;
+        check_commands(&context, &options.commands).await?;
 
         let client = BrowsrClient::from_env();
 
@@ -268,7 +277,7 @@ impl ExecutorContextTool for DistriBrowserSharedTool {
         let response = client
             .execute_commands(
                 options.commands,
-                context.get_browser_session_id(),
+                tab_session(&context, None)?,
                 None,
                 context_payload,
             )
@@ -313,6 +322,10 @@ impl Tool for SearchTool {
         })
     }
 
+    fn memoizable(&self) -> bool {
+        true
+    }
+
     fn needs_executor_context(&self) -> bool {
         false
     }
@@ -373,6 +386,9 @@ pub struct BrowserStepToolInput {
     /// Next goal to achieve
     #[serde(default)]
     pub next_goal: Option<String>,
+    /// Tab to run the commands in (default: the active tab)
+    #[serde(default)]
+    pub tab: Option<String>,
 }
 
 /// BrowserStepTool - Agent-oriented browser automation tool
@@ -472,6 +488,10 @@ impl Tool for BrowserStepTool {
                 "headless": {
                     "type": "boolean",
                     "description": "Whether to run browser in headless mode (default: true)"
+                },
+                "tab": {
+                    "type": "string",
+                    "description": "Name of the tab (see browser_tabs) to run the commands in. Defaults to the active tab."
                 }
             },
             "required": ["commands"],
@@ -515,6 +535,7 @@ impl ExecutorContextTool for BrowserStepTool {
         // Parse the tool input
         let input: BrowserStepToolInput = serde_json::from_value(tool_call.input.clone())
             .map_err(|e| AgentError::ToolExecution(format!("Invalid browser_step input: {}", e)))?;
+        check_commands(&context, &input.commands).await?;
 
         // Build the BrowserStepInput for the browsr client
         let browser_input = BrowserStepInput {
@@ -534,7 +555,7 @@ impl ExecutorContextTool for BrowserStepTool {
             .with_run_id(context.run_id.clone())
             .with_tool_call_id(tool_call.tool_call_id.clone());
 
-        let session_from_context = context.get_browser_session_id();
+        let session_from_context = tab_session(&context, input.tab.as_deref())?;
         tracing::info!(
             "[browser_step] browser_session_id from context: {:?}",
             session_from_context
@@ -559,6 +580,421 @@ impl ExecutorContextTool for BrowserStepTool {
     }
 }
 
+// ============================================================
+// Browser Tabs
+// ============================================================
+
+/// The browser session to run commands in: the named tab, else the active
+/// tab, else the thread's main session.
+fn tab_session(context: &ExecutorContext, tab: Option<&str>) -> Result<Option<String>, AgentError> {
+    let from_tabs = match &context.orchestrator {
+        Some(orchestrator) => orchestrator
+            .browser_tabs
+            .session(&context.thread_id, tab)
+            .map_err(AgentError::ToolExecution)?,
+        None if tab.is_some() => {
+            return Err(AgentError::ToolExecution(
+                "Browser tabs are not available in this context".to_string(),
+            ))
+        }
+        None => None,
+    };
+    Ok(from_tabs.or_else(|| context.get_browser_session_id()))
+}
+
+fn browser_command(command: &str, data: Value) -> Result<Commands, AgentError> {
+    serde_json::from_value(json!({ "command": command, "data": data }))
+        .map_err(|e| AgentError::ToolExecution(format!("Invalid {} command: {}", command, e)))
+}
+
+// ============================================================
+// Browser Policy
+// ============================================================
+
+/// The browser policy of the context's agent, if it has one.
+async fn browser_policy(context: &ExecutorContext) -> Option<BrowserPolicy> {
+    let orchestrator = context.orchestrator.as_ref()?;
+    match orchestrator.get_agent(&context.agent_id).await? {
+        AgentConfig::StandardAgent(definition) => definition.browser_policy().cloned(),
+        AgentConfig::WorkflowAgent(_) => None,
+    }
+}
+
+/// Count `pages` more pages loaded by the task, unless that takes it over
+/// the policy's limit.
+fn count_pages(
+    context: &ExecutorContext,
+    policy: &BrowserPolicy,
+    pages: usize,
+) -> Result<(), AgentError> {
+    if pages == 0 {
+        return Ok(());
+    }
+    context
+        .browser_pages
+        .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |loaded| {
+            policy
+                .check_pages(loaded, pages)
+                .ok()
+                .map(|_| loaded + pages)
+        })
+        .map(|_| ())
+        .map_err(|loaded| {
+            policy
+                .check_pages(loaded, pages)
+                .expect_err("the page count update is only refused over the limit")
+                .into()
+        })
+}
+
+/// Check a page the tool is about to load against the egress policy and
+/// the agent's browser policy, and count it.
+async fn check_page_url(context: &ExecutorContext, url: &str) -> Result<(), AgentError> {
+    context.check_egress(url)?;
+    if let Some(policy) = browser_policy(context).await {
+        policy.check_url(url)?;
+        count_pages(context, &policy, 1)?;
+    }
+    Ok(())
+}
+
+/// Check `commands` against the egress policy and the agent's browser
+/// policy, and count the pages they load. Nothing is counted when a command
+/// is blocked.
+async fn check_commands(
+    context: &ExecutorContext,
+    commands: &[Commands],
+) -> Result<(), AgentError> {
+    let commands: Vec<Value> = commands
+        .iter()
+        .map(|command| serde_json::to_value(command).unwrap_or_default())
+        .collect();
+    let urls: Vec<&str> = commands
+        .iter()
+        .filter_map(|command| command.pointer("/data/url").and_then(Value::as_str))
+        .collect();
+    for url in &urls {
+        context.check_egress(url)?;
+    }
+    if let Some(policy) = browser_policy(context).await {
+        for command in &commands {
+            policy.check_command(command)?;
+        }
+        count_pages(context, &policy, urls.len())?;
+    }
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum TabAction {
+    Open,
+    Switch,
+    Close,
+    List,
+}
+
+#[derive(Debug, Deserialize)]
+struct BrowserTabsInput {
+    action: TabAction,
+    #[serde(default)]
+    tab: Option<String>,
+    #[serde(default)]
+    url: Option<String>,
+}
+
+/// BrowserTabsTool - Open, switch and close browser tabs on the thread
+///
+/// Every tab is a separate browser session, so a reference page can stay
+/// open in one tab while the agent works in another. `browser_step` and
+/// `browsr_browser` run in the active tab unless told otherwise.
+#[derive(Debug)]
+pub struct BrowserTabsTool;
+
+#[async_trait::async_trait]
+impl Tool for BrowserTabsTool {
+    fn get_name(&self) -> String {
+        "browser_tabs".to_string()
+    }
+
+    fn get_description(&self) -> String {
+        "Manage browser tabs: open a new tab (optionally at a URL), switch the active tab, close a tab, or list open tabs. Each tab keeps its own page, so you can keep a reference page open while working in another tab.".to_string()
+    }
+
+    fn get_parameters(&self) -> Value {
+        json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "title": "BrowserTabsInput",
+            "type": "object",
+            "properties": {
+                "action": {
+                    "type": "string",
+                    "enum": ["open", "switch", "close", "list"]
+                },
+                "tab": {
+                    "type": "string",
+                    "description": "Tab name. Required for switch and close; optional for open (default: tab-N)."
+                },
+                "url": {
+                    "type": "string",
+                    "description": "URL to load in a newly opened tab"
+                }
+            },
+            "required": ["action"],
+            "additionalProperties": false
+        })
+    }
+
+    fn needs_executor_context(&self) -> bool {
+        true
+    }
+
+    fn get_tool_examples(&self) -> Option<String> {
+        Some(
+            r#"
+Open a tab on a second shop:
+{"action": "open", "tab": "shop_b", "url": "https://shop-b.example.com/item/42"}
+
+Go back to the first tab:
+{"action": "switch", "tab": "main"}
+
+Close a tab:
+{"action": "close", "tab": "shop_b"}
+"#
+            .to_string(),
+        )
+    }
+
+    async fn execute(
+        &self,
+        _tool_call: distri_types::ToolCall,
+        _context: Arc<ToolContext>,
+    ) -> Result<Vec<Part>, anyhow::Error> {
+        Err(anyhow::anyhow!("BrowserTabsTool requires ExecutorContext"))
+    }
+}
+
+#[async_trait::async_trait]
+impl ExecutorContextTool for BrowserTabsTool {
+    async fn execute_with_executor_context(
+        &self,
+        tool_call: ToolCall,
+        context: Arc<ExecutorContext>,
+    ) -> Result<Vec<Part>, AgentError> {
+        let input: BrowserTabsInput = serde_json::from_value(tool_call.input)
+            .map_err(|e| AgentError::ToolExecution(format!("Invalid browser_tabs input: {}", e)))?;
+        let tabs = &context.get_orchestrator()?.browser_tabs;
+        let thread_id = &context.thread_id;
+        let required_tab = || {
+            input.tab.clone().ok_or_else(|| {
+                AgentError::ToolExecution("'tab' is required for this action".to_string())
+            })
+        };
+
+        let list = match input.action {
+            TabAction::List => tabs.list(thread_id),
+            TabAction::Switch => tabs
+                .switch(thread_id, &required_tab()?)
+                .map_err(AgentError::ToolExecution)?,
+            TabAction::Close => {
+                let (tab, list) = tabs
+                    .close(thread_id, &required_tab()?)
+                    .map_err(AgentError::ToolExecution)?;
+                if !tab.borrowed {
+                    if let Err(e) = BrowsrClient::from_env()
+                        .destroy_session(&tab.session_id)
+                        .await
+                    {
+                        tracing::warn!("failed to close browser tab '{}': {}", tab.name, e);
+                    }
+                }
+                list
+            }
+            TabAction::Open => {
+                if let Some(url) = &input.url {
+                    check_page_url(&context, url).await?;
+                }
+                let name = input
+                    .tab
+                    .clone()
+                    .unwrap_or_else(|| format!("tab-{}", tabs.list(thread_id).tabs.len() + 1));
+                let client = BrowsrClient::from_env();
+                let session = client.create_session().await.map_err(|e| {
+                    AgentError::ToolExecution(format!("Failed to open browser tab: {}", e))
+                })?;
+                let tab = crate::agent::browser_tabs::BrowserTab {
+                    name: name.clone(),
+                    session_id: session.session_id.clone(),
+                    url: None,
+                    borrowed: false,
+                };
+                let list = match tabs.open(thread_id, tab, context.get_browser_session_id()) {
+                    Ok(list) => list,
+                    Err(e) => {
+                        let _ = client.destroy_session(&session.session_id).await;
+                        return Err(AgentError::ToolExecution(e));
+                    }
+                };
+                match &input.url {
+                    Some(url) => {
+                        let navigate = browser_command("navigate_to", json!({ "url": url }))?;
+                        client
+                            .execute_commands(vec![navigate], Some(session.session_id), None, None)
+                            .await
+                            .map_err(|e| {
+                                AgentError::ToolExecution(format!(
+                                    "Opened tab '{}' but failed to load {}: {}",
+                                    name, url, e
+                                ))
+                            })?;
+                        tabs.set_url(thread_id, &name, url.clone());
+                        tabs.list(thread_id)
+                    }
+                    None => list,
+                }
+            }
+        };
+
+        Ok(vec![Part::Data(serde_json::to_value(list).map_err(
+            |e| AgentError::ToolExecution(format!("Failed to serialize: {}", e)),
+        )?)])
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ExtractTabsInput {
+    query: String,
+    #[serde(default)]
+    tabs: Option<Vec<String>>,
+    #[serde(default)]
+    max_chars: Option<u64>,
+}
+
+/// BrowserExtractTabsTool - Run the same structured extraction on several
+/// tabs at once, e.g. to compare a product across shops
+#[derive(Debug)]
+pub struct BrowserExtractTabsTool;
+
+#[async_trait::async_trait]
+impl Tool for BrowserExtractTabsTool {
+    fn get_name(&self) -> String {
+        "browser_extract_tabs".to_string()
+    }
+
+    fn get_description(&self) -> String {
+        "Extract the same information from several open browser tabs in parallel and return the results keyed by tab name. Use it to compare pages side by side.".to_string()
+    }
+
+    fn get_parameters(&self) -> Value {
+        json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "title": "ExtractTabsInput",
+            "type": "object",
+            "properties": {
+                "query": {
+                    "type": "string",
+                    "description": "What to extract from each page"
+                },
+                "tabs": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Tabs to extract from (default: all open tabs)"
+                },
+                "max_chars": {
+                    "type": "integer",
+                    "description": "Maximum characters of page content considered per tab"
+                }
+            },
+            "required": ["query"],
+            "additionalProperties": false
+        })
+    }
+
+    fn needs_executor_context(&self) -> bool {
+        true
+    }
+
+    fn get_tool_examples(&self) -> Option<String> {
+        Some(
+            r#"
+Compare a product across two shops:
+{"query": "Product name, price and delivery time", "tabs": ["main", "shop_b"]}
+"#
+            .to_string(),
+        )
+    }
+
+    async fn execute(
+        &self,
+        _tool_call: distri_types::ToolCall,
+        _context: Arc<ToolContext>,
+    ) -> Result<Vec<Part>, anyhow::Error> {
+        Err(anyhow::anyhow!(
+            "BrowserExtractTabsTool requires ExecutorContext"
+        ))
+    }
+}
+
+#[async_trait::async_trait]
+impl ExecutorContextTool for BrowserExtractTabsTool {
+    async fn execute_with_executor_context(
+        &self,
+        tool_call: ToolCall,
+        context: Arc<ExecutorContext>,
+    ) -> Result<Vec<Part>, AgentError> {
+        let input: ExtractTabsInput = serde_json::from_value(tool_call.input).map_err(|e| {
+            AgentError::ToolExecution(format!("Invalid browser_extract_tabs input: {}", e))
+        })?;
+        let open = context
+            .get_orchestrator()?
+            .browser_tabs
+            .list(&context.thread_id);
+        if open.tabs.is_empty() {
+            return Err(AgentError::ToolExecution(
+                "No browser tabs are open; open some with browser_tabs first".to_string(),
+            ));
+        }
+        let selected = match &input.tabs {
+            Some(names) => names
+                .iter()
+                .map(|name| {
+                    open.tabs.iter().find(|t| &t.name == name).ok_or_else(|| {
+                        AgentError::ToolExecution(format!("Tab '{}' is not open", name))
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>()?,
+            None => open.tabs.iter().collect(),
+        };
+
+        let mut data = json!({ "query": input.query });
+        if let Some(max_chars) = input.max_chars {
+            data["max_chars"] = json!(max_chars);
+        }
+        let client = BrowsrClient::from_env();
+        let mut extractions = Vec::with_capacity(selected.len());
+        for tab in selected {
+            let extract = browser_command("extract_structured_content", data.clone())?;
+            let client = client.clone();
+            extractions.push(async move {
+                let result = match client
+                    .execute_commands(vec![extract], Some(tab.session_id.clone()), None, None)
+                    .await
+                {
+                    Ok(response) => json!({ "url": tab.url, "result": response }),
+                    Err(e) => json!({ "url": tab.url, "error": e.to_string() }),
+                };
+                (tab.name.clone(), result)
+            });
+        }
+        let results: serde_json::Map<String, Value> = futures::future::join_all(extractions)
+            .await
+            .into_iter()
+            .collect();
+
+        Ok(vec![Part::Data(Value::Object(results))])
+    }
+}
+
 // ============================================================
 // Crawl Tool
 // ============================================================
@@ -619,24 +1055,60 @@ impl Tool for CrawlTool {
         })
     }
 
+    fn memoizable(&self) -> bool {
+        true
+    }
+
     fn needs_executor_context(&self) -> bool {
-        false
+        true
     }
 
     async fn execute(
         &self,
-        tool_call: distri_types::ToolCall,
+        _tool_call: distri_types::ToolCall,
         _context: Arc<ToolContext>,
     ) -> Result<Vec<Part>, anyhow::Error> {
-        let request: CrawlApiRequest = serde_json::from_value(tool_call.input)
-            .map_err(|e| anyhow::anyhow!("Invalid crawl request: {}", e))?;
+        Err(anyhow::anyhow!("CrawlTool requires ExecutorContext"))
+    }
+}
+
+#[async_trait::async_trait]
+impl ExecutorContextTool for CrawlTool {
+    async fn execute_with_executor_context(
+        &self,
+        tool_call: ToolCall,
+        context: Arc<ExecutorContext>,
+    ) -> Result<Vec<Part>, AgentError> {
+        let mut input = tool_call.input;
+        if let Some(url) = input.get("url").and_then(Value::as_str) {
+            context.check_egress(url)?;
+            if let Some(policy) = browser_policy(&context).await {
+                policy.check_url(url)?;
+                // Every crawled page counts, so the crawl is capped at the
+                // pages the task has left.
+                let limit = input.get("limit").and_then(Value::as_u64).unwrap_or(10) as usize;
+                let limit = match policy.max_pages_per_task {
+                    Some(max) => limit.min(
+                        max.saturating_sub(context.browser_pages.load(Ordering::SeqCst))
+                            .max(1),
+                    ),
+                    None => limit,
+                };
+                count_pages(&context, &policy, limit)?;
+                input["limit"] = limit.into();
+            }
+        }
+        let request: CrawlApiRequest = serde_json::from_value(input)
+            .map_err(|e| AgentError::ToolExecution(format!("Invalid crawl request: {}", e)))?;
 
         let client = BrowsrClient::from_env();
         let response = client
             .crawl(request)
             .await
-            .map_err(|e| anyhow::anyhow!("Crawl failed: {}", e))?;
+            .map_err(|e| AgentError::ToolExecution(format!("Crawl failed: {}", e)))?;
 
-        Ok(vec![Part::Data(serde_json::to_value(response)?)])
+        Ok(vec![Part::Data(
+            serde_json::to_value(response).map_err(|e| AgentError::ToolExecution(e.to_string()))?,
+        )])
     }
 }
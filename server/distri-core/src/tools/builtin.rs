@@ -2,9 +2,10 @@ use distri_types::{Part, Tool, ToolContext};
 use serde_json::{json, Value};
 use std::sync::Arc;
 
-use crate::agent::todos::TodosTool;
+use crate::agent::todos::{TodosTool, WorkTodosTool};
 use crate::tools::browser::{
-    BrowserStepTool, CrawlTool, DistriBrowserSharedTool, DistriScrapeSharedTool, SearchTool,
+    BrowserExtractTabsTool, BrowserStepTool, BrowserTabsTool, CrawlTool, DistriBrowserSharedTool,
+    DistriScrapeSharedTool, SearchTool,
 };
 use crate::tools::save_artifact::SaveArtifactTool;
 use crate::tools::shell::{ExecuteShellTool, StartShellTool, StopShellTool};
@@ -23,6 +24,8 @@ use crate::{
 /// 2. **Wildcard expansion** when an agent declares `tools.builtin =
 ///    ["*"]` — all entries here are added to that agent's session.
 ///
+/// Tools of [`get_opt_in_builtin_tools`] are only found by name.
+///
 /// Supervisor tools (`get_task`, `wait_task`, `cancel_task`,
 /// `list_my_tasks`) live here so opt-in by name works. They are also
 /// auto-bundled by the orchestrator alongside `invoke_agent`: since
@@ -38,9 +41,12 @@ pub fn get_builtin_tools() -> Vec<Arc<dyn Tool>> {
         Arc::new(DistriScrapeSharedTool) as Arc<dyn Tool>,
         Arc::new(DistriBrowserSharedTool) as Arc<dyn Tool>,
         Arc::new(BrowserStepTool) as Arc<dyn Tool>,
+        Arc::new(BrowserTabsTool) as Arc<dyn Tool>,
+        Arc::new(BrowserExtractTabsTool) as Arc<dyn Tool>,
         Arc::new(SearchTool) as Arc<dyn Tool>,
         Arc::new(CrawlTool) as Arc<dyn Tool>,
         Arc::new(TodosTool) as Arc<dyn Tool>,
+        Arc::new(WorkTodosTool) as Arc<dyn Tool>,
         Arc::new(StartShellTool) as Arc<dyn Tool>,
         Arc::new(ExecuteShellTool) as Arc<dyn Tool>,
         Arc::new(StopShellTool) as Arc<dyn Tool>,
@@ -54,6 +60,17 @@ pub fn get_builtin_tools() -> Vec<Arc<dyn Tool>> {
         Arc::new(crate::tools::supervisor::ListMyTasksTool) as Arc<dyn Tool>,
         Arc::new(crate::tools::supervisor::GetTaskResultTool) as Arc<dyn Tool>,
         Arc::new(crate::tools::invoke_agent::InvokeAgentTool) as Arc<dyn Tool>,
+        Arc::new(crate::tools::sql::SqlQueryTool) as Arc<dyn Tool>,
+        Arc::new(crate::tools::sql::SqlSchemaTool) as Arc<dyn Tool>,
+        Arc::new(crate::tools::semantic_search::SemanticSearchArtifactsTool) as Arc<dyn Tool>,
+        Arc::new(crate::tools::working_memory::MemorySetTool) as Arc<dyn Tool>,
+        Arc::new(crate::tools::working_memory::MemoryGetTool) as Arc<dyn Tool>,
+        Arc::new(crate::tools::working_memory::MemoryListTool) as Arc<dyn Tool>,
+        Arc::new(crate::tools::datetime::DateTimeMathTool) as Arc<dyn Tool>,
+        Arc::new(crate::tools::datetime::TimezoneConvertTool) as Arc<dyn Tool>,
+        Arc::new(crate::tools::load_artifact::LoadArtifactTool) as Arc<dyn Tool>,
+        Arc::new(crate::tools::publish_document::PublishDocumentTool) as Arc<dyn Tool>,
+        Arc::new(crate::tools::summarize_thread::SummarizeThreadTool) as Arc<dyn Tool>,
         // load_skill is castable in `cast_to_executor_context_tool` but was
         // missing from this registry — agents that declared
         // `builtin = ["load_skill"]` (e.g. `_adhoc_base`) had the tool
@@ -65,6 +82,13 @@ pub fn get_builtin_tools() -> Vec<Arc<dyn Tool>> {
     ]
 }
 
+/// Builtin tools an agent only gets by naming them in `tools.builtin`;
+/// `["*"]` leaves them out. `python_exec` runs model-written code on the
+/// server.
+pub fn get_opt_in_builtin_tools() -> Vec<Arc<dyn Tool>> {
+    vec![Arc::new(crate::tools::code::PythonExecTool) as Arc<dyn Tool>]
+}
+
 /// Typed representation of the `final` tool's input.
 /// The LLM may pass the result as a bare string or wrapped as `{"input": ...}`.
 #[derive(Debug, serde::Deserialize)]
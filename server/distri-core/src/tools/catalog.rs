@@ -0,0 +1,389 @@
+//! Collision resolution and aliases of an agent's tools (see
+//! [`distri_types::tool_catalog`]).
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+
+use distri_types::tool_catalog::{
+    CollisionRule, ToolCandidate, ToolCatalog, ToolCollision, ToolCollisionConfig, ToolSourceKind,
+};
+use distri_types::{Part, ToolContext};
+
+use super::mcp_tool::{McpToolAdapter, ReadResourceTool};
+use super::{cast_to_executor_context_tool, DynExecutorTool, ExecutorContextTool, Tool};
+use crate::agent::ExecutorContext;
+use crate::types::ToolCall;
+use crate::AgentError;
+
+/// Namespace of tools the host application registers outside of a plugin.
+pub const HOST_NAMESPACE: &str = "host";
+
+/// Where `tool` comes from. `fallback` is used for tools that do not tell by
+/// themselves: builtins, and tools registered by the host application.
+pub fn tool_candidate(tool: &Arc<dyn Tool>, fallback: ToolSourceKind) -> ToolCandidate {
+    let name = tool.get_name();
+    if tool.is_external() {
+        return ToolCandidate::new(&name, "external", &name, ToolSourceKind::External);
+    }
+    if let Some(adapter) = (tool.as_ref() as &dyn std::any::Any).downcast_ref::<McpToolAdapter>() {
+        return ToolCandidate::new(
+            &name,
+            adapter.server(),
+            adapter.remote_name(),
+            ToolSourceKind::Mcp,
+        );
+    }
+    if (tool.as_ref() as &dyn std::any::Any).is::<ReadResourceTool>() {
+        return ToolCandidate::new(&name, "mcp", &name, ToolSourceKind::Mcp);
+    }
+    if let Some(plugin) = tool.get_plugin_name() {
+        let simple = name.split('.').next_back().unwrap_or(&name).to_string();
+        return ToolCandidate::new(&name, plugin, &simple, ToolSourceKind::Plugin);
+    }
+    if (tool.as_ref() as &dyn std::any::Any).is::<DynExecutorTool>() {
+        return ToolCandidate::new(&name, "dynamic", &name, ToolSourceKind::Dynamic);
+    }
+    let namespace = match fallback {
+        ToolSourceKind::Plugin => HOST_NAMESPACE,
+        other => other.as_str(),
+    };
+    ToolCandidate::new(&name, namespace, &name, fallback)
+}
+
+/// Keep one tool per name and add the aliases. `tools` pairs each tool with
+/// its candidate, in registration order.
+pub fn resolve_catalog(
+    tools: Vec<(Arc<dyn Tool>, ToolCandidate)>,
+    config: &ToolCollisionConfig,
+    aliases: &BTreeMap<String, String>,
+) -> Result<(Vec<Arc<dyn Tool>>, ToolCatalog), String> {
+    // The same tool added twice is not a collision.
+    let mut entries: Vec<(Arc<dyn Tool>, ToolCandidate)> = Vec::new();
+    for (tool, candidate) in tools {
+        if !entries.iter().any(|(t, _)| Arc::ptr_eq(t, &tool)) {
+            entries.push((tool, candidate));
+        }
+    }
+
+    let mut by_name: HashMap<&str, Vec<usize>> = HashMap::new();
+    for (index, (_, candidate)) in entries.iter().enumerate() {
+        by_name
+            .entry(candidate.name.as_str())
+            .or_default()
+            .push(index);
+    }
+
+    let mut catalog = ToolCatalog::default();
+    let mut winners: HashMap<&str, usize> = HashMap::new();
+    for (index, (_, candidate)) in entries.iter().enumerate() {
+        let name = candidate.name.as_str();
+        if winners.contains_key(name) {
+            continue;
+        }
+        let group = &by_name[name];
+        if group.len() == 1 {
+            winners.insert(name, index);
+            continue;
+        }
+        let candidates: Vec<ToolCandidate> = group.iter().map(|i| entries[*i].1.clone()).collect();
+        let (selected, rule) = config.select(name, &candidates)?;
+        let collision = ToolCollision {
+            name: name.to_string(),
+            winner: candidates[selected].clone(),
+            shadowed: candidates
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| *i != selected)
+                .map(|(_, c)| c.clone())
+                .collect(),
+            rule,
+        };
+        if rule != CollisionRule::Preferred {
+            tracing::warn!(
+                tool = %name,
+                winner = %collision.winner.qualified_name,
+                shadowed = ?collision.shadowed.iter().map(|c| &c.qualified_name).collect::<Vec<_>>(),
+                rule = ?rule,
+                "Tool name collision"
+            );
+        }
+        catalog.collisions.push(collision);
+        winners.insert(name, group[selected]);
+    }
+
+    let mut kept: Vec<Arc<dyn Tool>> = Vec::new();
+    for (index, (tool, candidate)) in entries.iter().enumerate() {
+        if winners.get(candidate.name.as_str()) == Some(&index) {
+            kept.push(tool.clone());
+            catalog.tools.push(candidate.clone());
+        }
+    }
+
+    for (alias, target) in aliases {
+        if catalog.tools.iter().any(|c| c.name == *alias) {
+            return Err(format!(
+                "tool alias '{}' clashes with a tool of the same name",
+                alias
+            ));
+        }
+        let (tool, candidate) = entries
+            .iter()
+            .find(|(_, c)| c.qualified_name == *target)
+            .or_else(|| winners.get(target.as_str()).map(|index| &entries[*index]))
+            .ok_or_else(|| format!("tool alias '{}' points to unknown tool '{}'", alias, target))?;
+        kept.push(Arc::new(AliasedTool::new(alias.clone(), tool.clone())));
+        catalog.tools.push(ToolCandidate {
+            name: alias.clone(),
+            ..candidate.clone()
+        });
+        catalog
+            .aliases
+            .insert(alias.clone(), candidate.qualified_name.clone());
+    }
+
+    Ok((kept, catalog))
+}
+
+/// A tool exposed to the model under another name.
+#[derive(Debug, Clone)]
+pub struct AliasedTool {
+    alias: String,
+    inner: Arc<dyn Tool>,
+}
+
+impl AliasedTool {
+    pub fn new(alias: String, inner: Arc<dyn Tool>) -> Self {
+        Self { alias, inner }
+    }
+
+    /// The call as the aliased tool expects it.
+    fn inner_call(&self, mut tool_call: ToolCall) -> ToolCall {
+        tool_call.tool_name = self.inner.get_name();
+        tool_call
+    }
+}
+
+#[async_trait::async_trait]
+impl Tool for AliasedTool {
+    fn get_name(&self) -> String {
+        self.alias.clone()
+    }
+
+    fn get_description(&self) -> String {
+        self.inner.get_description()
+    }
+
+    fn get_parameters(&self) -> serde_json::Value {
+        self.inner.get_parameters()
+    }
+
+    fn get_tool_examples(&self) -> Option<String> {
+        self.inner.get_tool_examples()
+    }
+
+    fn prompt(&self) -> Option<String> {
+        self.inner.prompt()
+    }
+
+    fn is_external(&self) -> bool {
+        self.inner.is_external()
+    }
+
+    fn is_mcp(&self) -> bool {
+        self.inner.is_mcp()
+    }
+
+    fn is_sync(&self) -> bool {
+        self.inner.is_sync()
+    }
+
+    fn is_final(&self) -> bool {
+        self.inner.is_final()
+    }
+
+    fn concurrency_safe(&self) -> bool {
+        self.inner.concurrency_safe()
+    }
+
+    fn memoizable(&self) -> bool {
+        self.inner.memoizable()
+    }
+
+    fn side_effect(&self) -> Option<bool> {
+        self.inner.side_effect()
+    }
+
+    fn needs_executor_context(&self) -> bool {
+        self.inner.needs_executor_context()
+    }
+
+    fn get_auth_metadata(&self) -> Option<Box<dyn distri_types::auth::AuthMetadata>> {
+        self.inner.get_auth_metadata()
+    }
+
+    fn get_plugin_name(&self) -> Option<String> {
+        self.inner.get_plugin_name()
+    }
+
+    fn get_plugin_capabilities(&self) -> Option<distri_types::PluginCapabilities> {
+        self.inner.get_plugin_capabilities()
+    }
+
+    async fn execute(
+        &self,
+        tool_call: ToolCall,
+        context: Arc<ToolContext>,
+    ) -> Result<Vec<Part>, anyhow::Error> {
+        self.inner
+            .execute(self.inner_call(tool_call), context)
+            .await
+    }
+
+    fn execute_sync(
+        &self,
+        tool_call: ToolCall,
+        context: Arc<ToolContext>,
+    ) -> Result<Vec<Part>, anyhow::Error> {
+        self.inner.execute_sync(self.inner_call(tool_call), context)
+    }
+}
+
+#[async_trait::async_trait]
+impl ExecutorContextTool for AliasedTool {
+    async fn execute_with_executor_context(
+        &self,
+        tool_call: ToolCall,
+        context: Arc<ExecutorContext>,
+    ) -> Result<Vec<Part>, AgentError> {
+        cast_to_executor_context_tool(self.inner.as_ref())?
+            .execute_with_executor_context(self.inner_call(tool_call), context)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::FinalTool;
+
+    #[derive(Debug)]
+    struct Named(&'static str, Option<&'static str>);
+
+    #[async_trait::async_trait]
+    impl Tool for Named {
+        fn get_name(&self) -> String {
+            self.0.to_string()
+        }
+
+        fn get_description(&self) -> String {
+            self.0.to_string()
+        }
+
+        fn get_parameters(&self) -> serde_json::Value {
+            serde_json::json!({ "type": "object" })
+        }
+
+        fn get_plugin_name(&self) -> Option<String> {
+            self.1.map(String::from)
+        }
+
+        async fn execute(
+            &self,
+            _tool_call: ToolCall,
+            _context: Arc<ToolContext>,
+        ) -> Result<Vec<Part>, anyhow::Error> {
+            Ok(vec![])
+        }
+    }
+
+    fn entry(tool: impl Tool, fallback: ToolSourceKind) -> (Arc<dyn Tool>, ToolCandidate) {
+        let tool: Arc<dyn Tool> = Arc::new(tool);
+        let candidate = tool_candidate(&tool, fallback);
+        (tool, candidate)
+    }
+
+    fn search_tools() -> Vec<(Arc<dyn Tool>, ToolCandidate)> {
+        vec![
+            entry(FinalTool, ToolSourceKind::Builtin),
+            entry(Named("search", None), ToolSourceKind::Builtin),
+            entry(Named("search", Some("acme")), ToolSourceKind::Plugin),
+            entry(Named("search", Some("globex")), ToolSourceKind::Plugin),
+        ]
+    }
+
+    fn names(tools: &[Arc<dyn Tool>]) -> Vec<String> {
+        tools.iter().map(|t| t.get_name()).collect()
+    }
+
+    #[test]
+    fn precedence_keeps_one_tool_per_name() {
+        let (tools, catalog) =
+            resolve_catalog(search_tools(), &Default::default(), &Default::default()).unwrap();
+        assert_eq!(names(&tools), ["final", "search"]);
+        assert_eq!(catalog.tools[1].qualified_name, "builtin/search");
+        let collision = &catalog.collisions[0];
+        assert_eq!(collision.rule, CollisionRule::Precedence);
+        assert_eq!(
+            collision
+                .shadowed
+                .iter()
+                .map(|c| c.qualified_name.as_str())
+                .collect::<Vec<_>>(),
+            ["acme/search", "globex/search"]
+        );
+    }
+
+    #[test]
+    fn same_source_falls_back_to_registration_order() {
+        let config: ToolCollisionConfig =
+            serde_json::from_value(serde_json::json!({ "precedence": ["plugin"] })).unwrap();
+        let (_, catalog) = resolve_catalog(search_tools(), &config, &Default::default()).unwrap();
+        assert_eq!(catalog.collisions[0].winner.qualified_name, "acme/search");
+        assert_eq!(catalog.collisions[0].rule, CollisionRule::FirstRegistered);
+    }
+
+    #[test]
+    fn prefer_and_strict() {
+        let config: ToolCollisionConfig = serde_json::from_value(serde_json::json!({
+            "prefer": { "search": "globex" },
+            "strict": true,
+        }))
+        .unwrap();
+        let (_, catalog) = resolve_catalog(search_tools(), &config, &Default::default()).unwrap();
+        assert_eq!(catalog.collisions[0].winner.qualified_name, "globex/search");
+        assert_eq!(catalog.collisions[0].rule, CollisionRule::Preferred);
+
+        let config = ToolCollisionConfig {
+            strict: true,
+            ..Default::default()
+        };
+        let err = resolve_catalog(search_tools(), &config, &Default::default()).unwrap_err();
+        assert!(
+            err.contains("builtin/search, acme/search, globex/search"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn alias_exposes_a_shadowed_tool() {
+        let aliases = BTreeMap::from([("acme_search".to_string(), "acme/search".to_string())]);
+        let (tools, catalog) =
+            resolve_catalog(search_tools(), &Default::default(), &aliases).unwrap();
+        assert_eq!(names(&tools), ["final", "search", "acme_search"]);
+
+        let resolution = catalog.resolve("acme_search");
+        assert_eq!(resolution.alias_of.as_deref(), Some("acme/search"));
+        assert_eq!(resolution.collision.unwrap().name, "search");
+
+        let aliased = AliasedTool::new("acme_search".to_string(), tools[1].clone());
+        let call = aliased.inner_call(ToolCall {
+            tool_call_id: "1".to_string(),
+            tool_name: "acme_search".to_string(),
+            input: serde_json::json!({}),
+        });
+        assert_eq!(call.tool_name, "search");
+
+        let unknown = BTreeMap::from([("x".to_string(), "nope/search".to_string())]);
+        assert!(resolve_catalog(search_tools(), &Default::default(), &unknown).is_err());
+    }
+}
@@ -1,2 +1,4 @@
 mod executor;
+mod python;
 pub use executor::{execute_code_with_tools, CodeExecutor};
+pub use python::{run_python, PythonExecTool, PythonRun};
@@ -0,0 +1,370 @@
+//! `python_exec` — run Python with a local, sandboxed CPython.
+//!
+//! Every call gets a scratch directory holding the code and `python_runner.py`,
+//! which applies the CPU and memory rlimits, installs the import allowlist,
+//! runs the code and saves the open matplotlib figures (Agg backend) as PNGs.
+//! The interpreter is wrapped in firejail as [`PythonSandbox`] says and gets
+//! none of the server's environment. The wall-clock limit is enforced here by
+//! killing the process. stdout and stderr come back as text parts, figures as
+//! image artifacts.
+
+use std::path::Path;
+use std::process::Stdio;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use anyhow::{bail, Context, Result};
+use base64::{engine::general_purpose, Engine as _};
+use distri_types::python_exec::{PythonExecConfig, PythonSandbox};
+use distri_types::{Part, Tool, ToolCall, ToolContext};
+use serde_json::{json, Value};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt};
+use tokio::process::Command;
+
+use crate::agent::ExecutorContext;
+use crate::tools::save_artifact::persist_artifact;
+use crate::tools::ExecutorContextTool;
+use crate::AgentError;
+
+const RUNNER: &str = include_str!("./python_runner.py");
+const FIGURES_DIR: &str = "figures";
+
+#[derive(Debug)]
+pub struct PythonExecTool;
+
+#[async_trait::async_trait]
+impl Tool for PythonExecTool {
+    fn get_name(&self) -> String {
+        "python_exec".to_string()
+    }
+
+    fn get_description(&self) -> String {
+        "Run a Python 3 script in a sandbox without network access and return its stdout and stderr. Use print() for results. Only the standard library and the allowed data packages (e.g. numpy, pandas, matplotlib) can be imported. Open matplotlib figures are returned as images; plt.show() is not needed. Each call starts from a clean interpreter and has CPU, memory and time limits.".to_string()
+    }
+
+    fn needs_executor_context(&self) -> bool {
+        true
+    }
+
+    fn get_parameters(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "code": {
+                    "type": "string",
+                    "description": "The Python script to run"
+                },
+                "stdin": {
+                    "type": "string",
+                    "description": "Text the script reads from standard input (optional)"
+                }
+            },
+            "required": ["code"]
+        })
+    }
+
+    async fn execute(
+        &self,
+        _tool_call: ToolCall,
+        _context: Arc<ToolContext>,
+    ) -> Result<Vec<Part>, anyhow::Error> {
+        Err(anyhow::anyhow!("PythonExecTool requires ExecutorContext"))
+    }
+}
+
+#[async_trait::async_trait]
+impl ExecutorContextTool for PythonExecTool {
+    async fn execute_with_executor_context(
+        &self,
+        tool_call: ToolCall,
+        context: Arc<ExecutorContext>,
+    ) -> Result<Vec<Part>, AgentError> {
+        let code = tool_call
+            .input
+            .get("code")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| AgentError::ToolExecution("Missing 'code' parameter".to_string()))?;
+        let stdin = tool_call.input.get("stdin").and_then(|v| v.as_str());
+
+        let config = context.get_orchestrator()?.python_exec.clone();
+        let run = run_python(&config, code, stdin)
+            .await
+            .map_err(|e| AgentError::ToolExecution(format!("python_exec failed: {:#}", e)))?;
+
+        let mut parts = Vec::new();
+        if !run.stdout.is_empty() {
+            parts.push(Part::Text(run.stdout.clone()));
+        }
+        if !run.stderr.is_empty() {
+            parts.push(Part::Text(format!("[stderr] {}", run.stderr)));
+        }
+        parts.push(Part::Data(json!({
+            "exit_code": run.exit_code,
+            "timed_out": run.timed_out,
+            "duration_ms": run.duration_ms,
+            "figures": run.figures.len(),
+        })));
+        for (filename, png) in &run.figures {
+            let encoded = general_purpose::STANDARD.encode(png);
+            let metadata =
+                persist_artifact(&context, filename, &encoded, png.len() as u64, None).await;
+            parts.push(Part::Artifact(metadata));
+        }
+        Ok(parts)
+    }
+}
+
+/// Outcome of one call.
+#[derive(Debug)]
+pub struct PythonRun {
+    pub stdout: String,
+    pub stderr: String,
+    /// `None` when the process was killed.
+    pub exit_code: Option<i32>,
+    pub timed_out: bool,
+    pub duration_ms: u64,
+    /// Saved figures as (artifact file name, PNG bytes).
+    pub figures: Vec<(String, Vec<u8>)>,
+}
+
+/// Run `code` under `config`, feeding it `stdin`.
+pub async fn run_python(
+    config: &PythonExecConfig,
+    code: &str,
+    stdin: Option<&str>,
+) -> Result<PythonRun> {
+    let run_id = uuid::Uuid::new_v4().simple().to_string();
+    let dir = std::env::temp_dir().join(format!("distri-python-{}", run_id));
+    tokio::fs::create_dir_all(dir.join(FIGURES_DIR))
+        .await
+        .context("creating the scratch directory")?;
+    let run = run_in(config, &dir, code, stdin, &run_id[..8]).await;
+    if let Err(e) = tokio::fs::remove_dir_all(&dir).await {
+        tracing::warn!(dir = %dir.display(), error = %e, "python_exec: scratch directory left behind");
+    }
+    run
+}
+
+async fn run_in(
+    config: &PythonExecConfig,
+    dir: &Path,
+    code: &str,
+    stdin: Option<&str>,
+    figure_prefix: &str,
+) -> Result<PythonRun> {
+    tokio::fs::write(dir.join("python_runner.py"), RUNNER).await?;
+    tokio::fs::write(dir.join("main.py"), code).await?;
+    let settings = json!({
+        "allowed_packages": config.allowed_packages,
+        "cpu_secs": config.cpu_secs,
+        "memory_bytes": config.memory_mb * 1024 * 1024,
+        "max_figures": config.max_figures,
+    });
+
+    let mut command = sandboxed(config, dir)?;
+    command
+        .arg("-I")
+        .arg("python_runner.py")
+        .arg(settings.to_string())
+        .current_dir(dir)
+        .env_clear()
+        .env("PATH", std::env::var_os("PATH").unwrap_or_default())
+        .env("MPLBACKEND", "Agg")
+        .env("MPLCONFIGDIR", ".matplotlib")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+
+    let started = Instant::now();
+    let mut child = command
+        .spawn()
+        .with_context(|| format!("spawning {}", config.interpreter))?;
+    if let Some(mut pipe) = child.stdin.take() {
+        let input = stdin.unwrap_or_default().as_bytes().to_vec();
+        tokio::spawn(async move {
+            let _ = pipe.write_all(&input).await;
+        });
+    }
+    let stdout = tokio::spawn(read_capped(child.stdout.take(), config.max_output_bytes));
+    let stderr = tokio::spawn(read_capped(child.stderr.take(), config.max_output_bytes));
+
+    let limit = Duration::from_secs(config.timeout_secs);
+    let (exit_code, timed_out) = match tokio::time::timeout(limit, child.wait()).await {
+        Ok(status) => (status?.code(), false),
+        Err(_) => {
+            let _ = child.kill().await;
+            (None, true)
+        }
+    };
+    let duration_ms = started.elapsed().as_millis() as u64;
+    let stdout = stdout.await?;
+    let mut stderr = stderr.await?;
+    if timed_out {
+        stderr.push_str(&format!(
+            "\nKilled after the {}s time limit",
+            config.timeout_secs
+        ));
+    }
+
+    let figures = if timed_out {
+        Vec::new()
+    } else {
+        read_figures(&dir.join(FIGURES_DIR), figure_prefix, config.max_figures).await?
+    };
+
+    Ok(PythonRun {
+        stdout,
+        stderr,
+        exit_code,
+        timed_out,
+        duration_ms,
+        figures,
+    })
+}
+
+/// The interpreter command, wrapped in firejail when the sandbox asks for it.
+fn sandboxed(config: &PythonExecConfig, dir: &Path) -> Result<Command> {
+    match config.sandbox {
+        PythonSandbox::None => return Ok(Command::new(&config.interpreter)),
+        PythonSandbox::Firejail => {
+            if !on_path("firejail") {
+                bail!(
+                    "firejail is not installed; install it, or set `python_exec.sandbox: none` \
+                     to run Python without a sandbox"
+                );
+            }
+        }
+    }
+
+    let mut command = Command::new("firejail");
+    command
+        .arg("--quiet")
+        .arg("--noprofile")
+        .arg("--net=none")
+        .arg("--private-tmp")
+        .arg(format!("--private={}", dir.display()))
+        .arg("--private-cwd")
+        .arg(format!("--rlimit-as={}", config.memory_mb * 1024 * 1024))
+        .arg(format!("--rlimit-cpu={}", config.cpu_secs))
+        .arg("--")
+        .arg(&config.interpreter);
+    Ok(command)
+}
+
+fn on_path(program: &str) -> bool {
+    std::env::var_os("PATH")
+        .is_some_and(|paths| std::env::split_paths(&paths).any(|p| p.join(program).is_file()))
+}
+
+/// Read a pipe to its end, keeping the first `max` bytes.
+async fn read_capped<R: AsyncRead + Unpin>(pipe: Option<R>, max: usize) -> String {
+    let Some(mut pipe) = pipe else {
+        return String::new();
+    };
+    let mut kept = Vec::new();
+    let mut dropped = 0usize;
+    let mut buf = [0u8; 8192];
+    loop {
+        match pipe.read(&mut buf).await {
+            Ok(0) | Err(_) => break,
+            Ok(n) => {
+                let room = max.saturating_sub(kept.len()).min(n);
+                kept.extend_from_slice(&buf[..room]);
+                dropped += n - room;
+            }
+        }
+    }
+    let mut text = String::from_utf8_lossy(&kept).into_owned();
+    if dropped > 0 {
+        text.push_str(&format!("\n… [{} more bytes cut]", dropped));
+    }
+    text
+}
+
+async fn read_figures(dir: &Path, prefix: &str, max: usize) -> Result<Vec<(String, Vec<u8>)>> {
+    let mut names = Vec::new();
+    let mut entries = tokio::fs::read_dir(dir).await?;
+    while let Some(entry) = entries.next_entry().await? {
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if name.ends_with(".png") {
+            names.push(name);
+        }
+    }
+    // figure_1.png … figure_N.png, in figure order.
+    names.sort_by_key(|n| (n.len(), n.clone()));
+    let mut figures = Vec::new();
+    for name in names.into_iter().take(max) {
+        let png = tokio::fs::read(dir.join(&name)).await?;
+        figures.push((format!("python-{}-{}", prefix, name), png));
+    }
+    Ok(figures)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The bare interpreter, or `None` when the host has no python3.
+    fn config() -> Option<PythonExecConfig> {
+        let output = std::process::Command::new("python3")
+            .args(["-c", "import sys; print(sys.executable)"])
+            .output()
+            .ok()?;
+        Some(PythonExecConfig {
+            interpreter: String::from_utf8(output.stdout).ok()?.trim().to_string(),
+            sandbox: PythonSandbox::None,
+            allowed_packages: Vec::new(),
+            ..Default::default()
+        })
+    }
+
+    #[tokio::test]
+    async fn captures_stdin_stdout_and_exit_code() {
+        let Some(config) = config() else { return };
+        let run = run_python(
+            &config,
+            "import sys, json\nprint(json.dumps(sys.stdin.read().split()))\nsys.exit(3)",
+            Some("a b"),
+        )
+        .await
+        .unwrap();
+        assert_eq!(run.stdout.trim(), r#"["a", "b"]"#);
+        assert_eq!(run.exit_code, Some(3));
+        assert!(!run.timed_out && run.figures.is_empty());
+    }
+
+    #[tokio::test]
+    async fn imports_outside_the_allowlist_fail() {
+        let Some(config) = config() else { return };
+        let run = run_python(&config, "import numpy", None).await.unwrap();
+        assert_eq!(run.exit_code, Some(1));
+        assert!(run.stderr.contains("python_exec allows"), "{}", run.stderr);
+    }
+
+    #[tokio::test]
+    async fn limits_are_enforced() {
+        let Some(config) = config() else { return };
+        let capped = PythonExecConfig {
+            timeout_secs: 1,
+            max_output_bytes: 10,
+            ..config.clone()
+        };
+        let run = run_python(
+            &capped,
+            "print('x' * 100, flush=True)\nwhile True: pass",
+            None,
+        )
+        .await
+        .unwrap();
+        assert!(run.timed_out);
+        assert_eq!(run.exit_code, None);
+        assert!(run.stdout.starts_with("xxxxxxxxxx\n…"), "{}", run.stdout);
+
+        // With the default output cap, so the MemoryError is not cut off.
+        let run = run_python(&config, "x = bytearray(4 * 1024 ** 3)", None)
+            .await
+            .unwrap();
+        assert!(run.stderr.contains("MemoryError"), "{}", run.stderr);
+    }
+}
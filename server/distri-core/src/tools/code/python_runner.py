@@ -0,0 +1,85 @@
+# Runs the code of one `python_exec` call (see python.rs).
+#
+# argv[1] holds the settings as JSON. The code is read from main.py and run
+# as __main__ after the rlimits and the import allowlist are in place; open
+# matplotlib figures are then saved to figures/ as PNGs.
+
+import builtins
+import json
+import os
+import resource
+import sys
+import traceback
+
+settings = json.loads(sys.argv[1])
+
+for kind, limit in (
+    (resource.RLIMIT_CPU, settings["cpu_secs"]),
+    (resource.RLIMIT_AS, settings["memory_bytes"]),
+):
+    try:
+        resource.setrlimit(kind, (limit, limit))
+    except (ValueError, OSError):
+        pass
+
+allowed = (
+    set(settings["allowed_packages"])
+    | set(sys.stdlib_module_names)
+    | set(sys.builtin_module_names)
+)
+code_globals = {"__name__": "__main__", "__file__": "main.py", "__builtins__": builtins}
+
+
+class AllowList:
+    """Refuses top-level imports the code makes itself. Imports made by an
+    allowed package (its own dependencies) go through."""
+
+    @staticmethod
+    def find_spec(name, path=None, target=None):
+        top = name.partition(".")[0]
+        if path is not None or top in allowed:
+            return None
+        frame = sys._getframe(1)
+        while frame is not None and (
+            frame.f_code.co_filename.startswith("<frozen")
+            or frame.f_globals.get("__name__", "").startswith("importlib")
+        ):
+            frame = frame.f_back
+        if frame is not None and frame.f_globals is code_globals:
+            raise ModuleNotFoundError(
+                f"No module named {top!r}: python_exec allows the standard library and "
+                + (", ".join(sorted(settings["allowed_packages"])) or "no other packages"),
+                name=top,
+            )
+        return None
+
+
+def save_figures():
+    pyplot = sys.modules.get("matplotlib.pyplot")
+    if pyplot is None:
+        return
+    numbers = pyplot.get_fignums()[: settings["max_figures"]]
+    for index, number in enumerate(numbers, 1):
+        path = os.path.join("figures", f"figure_{index}.png")
+        pyplot.figure(number).savefig(path, format="png")
+
+
+sys.meta_path.insert(0, AllowList)
+status = 0
+try:
+    with open("main.py", encoding="utf-8") as source:
+        exec(compile(source.read(), "main.py", "exec"), code_globals)
+except SystemExit as e:
+    if isinstance(e.code, str):
+        print(e.code, file=sys.stderr)
+    status = e.code if isinstance(e.code, int) else int(e.code is not None)
+except BaseException as e:
+    traceback.print_exception(type(e), e, e.__traceback__.tb_next)
+    status = 1
+finally:
+    try:
+        save_figures()
+    except Exception:
+        traceback.print_exc()
+sys.stdout.flush()
+sys.exit(status)
@@ -1,4 +1,10 @@
-use distri_types::ToolContext;
+use std::sync::Arc;
+
+use distri_types::{
+    CapabilityGuard, PluginSpanRecorder, PluginStorage, Tool, ToolContext, TraceContext,
+};
+use opentelemetry::trace::TraceContextExt;
+use tracing_opentelemetry::OpenTelemetrySpanExt;
 
 use crate::agent::ExecutorContext;
 
@@ -20,5 +26,46 @@ pub fn to_tool_context(executor_context: &ExecutorContext) -> ToolContext {
         session_store,
         event_tx: executor_context.event_tx.clone(),
         metadata: executor_context.tool_metadata.clone(),
+        trace: current_trace(executor_context),
+        spans: PluginSpanRecorder::default(),
+        capabilities: None,
+        egress: executor_context
+            .orchestrator
+            .as_ref()
+            .and_then(|orch| orch.egress_policy.clone()),
+        storage: None,
+    }
+}
+
+/// [`to_tool_context`] for running `tool`: a plugin tool gets a guard
+/// limiting it to its plugin's capability manifest, and its plugin's
+/// storage.
+pub fn to_tool_context_for(executor_context: &ExecutorContext, tool: &dyn Tool) -> ToolContext {
+    let mut context = to_tool_context(executor_context);
+    let Some(plugin) = tool.get_plugin_name() else {
+        return context;
+    };
+    context.storage = executor_context
+        .orchestrator
+        .as_ref()
+        .and_then(|orch| orch.stores.plugin_kv_store.clone())
+        .map(|store| PluginStorage::new(plugin.clone(), store));
+    let capabilities = tool.get_plugin_capabilities().unwrap_or_default();
+    context.capabilities = Some(Arc::new(CapabilityGuard::new(plugin, capabilities)));
+    context
+}
+
+/// The span the tool runs under, or the run's inbound trace context when no
+/// span is being exported.
+fn current_trace(executor_context: &ExecutorContext) -> Option<TraceContext> {
+    let context = tracing::Span::current().context();
+    let span = context.span();
+    let span_context = span.span_context();
+    if span_context.is_valid() {
+        return Some(TraceContext {
+            trace_id: span_context.trace_id().to_string(),
+            parent_span_id: span_context.span_id().to_string(),
+        });
     }
+    executor_context.trace_context.clone()
 }
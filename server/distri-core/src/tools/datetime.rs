@@ -0,0 +1,192 @@
+//! `datetime_math` / `timezone_convert`: date arithmetic and timezone
+//! conversion (see [`distri_types::datetime`]).
+//!
+//! Both work in the run's timezone — the `timezone` of the user's profile
+//! (see [`ExecutorContext::user_profile`]), else the agent's
+//! `datetime.timezone`, else UTC — and `datetime_math` counts business days
+//! with the agent's business calendar.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use distri_types::configuration::AgentConfig;
+use distri_types::datetime::{
+    datetime_math, profile_timezone, timezone_convert, DateTimeConfig, DateTimeMathInput,
+    TimezoneConvertInput, Tz,
+};
+use distri_types::{Part, Tool, ToolCall, ToolContext};
+use serde_json::{json, Value};
+
+use crate::agent::ExecutorContext;
+use crate::tools::ExecutorContextTool;
+use crate::AgentError;
+
+/// The agent's `datetime` config and the run's timezone.
+async fn run_settings(context: &ExecutorContext) -> (DateTimeConfig, Tz) {
+    let config = match &context.orchestrator {
+        Some(orchestrator) => match orchestrator.get_agent(&context.agent_id).await {
+            Some(AgentConfig::StandardAgent(definition)) => definition.datetime.unwrap_or_default(),
+            _ => DateTimeConfig::default(),
+        },
+        None => DateTimeConfig::default(),
+    };
+    let profile = context.user_profile().await;
+    let timezone = config.resolve_timezone(profile.as_ref().and_then(profile_timezone));
+    (config, timezone)
+}
+
+#[derive(Debug)]
+pub struct DateTimeMathTool;
+
+#[async_trait]
+impl Tool for DateTimeMathTool {
+    fn get_name(&self) -> String {
+        "datetime_math".to_string()
+    }
+
+    fn get_description(&self) -> String {
+        "Add a duration to a date and time, subtract one, or measure the time between two. \
+         Use this instead of working out dates yourself. Days, weeks, months, years and \
+         business days keep the time of day; hours, minutes and seconds are elapsed time. \
+         Dates without an offset are in the user's timezone."
+            .to_string()
+    }
+
+    fn get_parameters(&self) -> Value {
+        let amount = |description: &str| json!({ "type": "integer", "description": description });
+        json!({
+            "type": "object",
+            "properties": {
+                "operation": {
+                    "type": "string",
+                    "enum": ["add", "subtract", "diff"],
+                    "description": "Apply the duration fields to `datetime`, or diff it with `to`."
+                },
+                "datetime": {
+                    "type": "string",
+                    "description": "RFC 3339, YYYY-MM-DD HH:MM[:SS], YYYY-MM-DD or now (the default)."
+                },
+                "to": {
+                    "type": "string",
+                    "description": "End of a diff, in the same formats."
+                },
+                "timezone": {
+                    "type": "string",
+                    "description": "IANA timezone of the dates and the result, e.g. America/New_York."
+                },
+                "years": amount("Years to add or subtract."),
+                "months": amount("Months to add or subtract."),
+                "weeks": amount("Weeks to add or subtract."),
+                "days": amount("Calendar days to add or subtract."),
+                "business_days": amount("Business days, skipping weekends and holidays."),
+                "hours": amount("Hours to add or subtract."),
+                "minutes": amount("Minutes to add or subtract."),
+                "seconds": amount("Seconds to add or subtract.")
+            },
+            "required": ["operation"]
+        })
+    }
+
+    fn needs_executor_context(&self) -> bool {
+        true
+    }
+
+    async fn execute(
+        &self,
+        _tool_call: ToolCall,
+        _context: Arc<ToolContext>,
+    ) -> Result<Vec<Part>, anyhow::Error> {
+        anyhow::bail!("DateTimeMathTool requires ExecutorContext")
+    }
+}
+
+#[async_trait]
+impl ExecutorContextTool for DateTimeMathTool {
+    async fn execute_with_executor_context(
+        &self,
+        tool_call: ToolCall,
+        context: Arc<ExecutorContext>,
+    ) -> Result<Vec<Part>, AgentError> {
+        let input: DateTimeMathInput = serde_json::from_value(tool_call.input.clone())
+            .map_err(|e| AgentError::ToolExecution(format!("invalid datetime_math input: {e}")))?;
+        let (config, timezone) = run_settings(&context).await;
+        let result = datetime_math(
+            &input,
+            timezone,
+            config.business_calendar.as_ref(),
+            chrono::Utc::now(),
+        )
+        .map_err(AgentError::ToolExecution)?;
+        Ok(vec![Part::Data(result)])
+    }
+}
+
+#[derive(Debug)]
+pub struct TimezoneConvertTool;
+
+#[async_trait]
+impl Tool for TimezoneConvertTool {
+    fn get_name(&self) -> String {
+        "timezone_convert".to_string()
+    }
+
+    fn get_description(&self) -> String {
+        "Convert a date and time to one or more timezones, with the weekday and UTC offset \
+         in each. Dates without an offset are read in `from`, or the user's timezone."
+            .to_string()
+    }
+
+    fn get_parameters(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "datetime": {
+                    "type": "string",
+                    "description": "RFC 3339, YYYY-MM-DD HH:MM[:SS], YYYY-MM-DD or now (the default)."
+                },
+                "from": {
+                    "type": "string",
+                    "description": "IANA timezone of `datetime` when it has no offset."
+                },
+                "to": {
+                    "description": "IANA timezone, or a list of them, to convert to.",
+                    "anyOf": [
+                        { "type": "string" },
+                        { "type": "array", "items": { "type": "string" } }
+                    ]
+                }
+            },
+            "required": ["to"]
+        })
+    }
+
+    fn needs_executor_context(&self) -> bool {
+        true
+    }
+
+    async fn execute(
+        &self,
+        _tool_call: ToolCall,
+        _context: Arc<ToolContext>,
+    ) -> Result<Vec<Part>, anyhow::Error> {
+        anyhow::bail!("TimezoneConvertTool requires ExecutorContext")
+    }
+}
+
+#[async_trait]
+impl ExecutorContextTool for TimezoneConvertTool {
+    async fn execute_with_executor_context(
+        &self,
+        tool_call: ToolCall,
+        context: Arc<ExecutorContext>,
+    ) -> Result<Vec<Part>, AgentError> {
+        let input: TimezoneConvertInput =
+            serde_json::from_value(tool_call.input.clone()).map_err(|e| {
+                AgentError::ToolExecution(format!("invalid timezone_convert input: {e}"))
+            })?;
+        let (_, timezone) = run_settings(&context).await;
+        let result = timezone_convert(&input, timezone, chrono::Utc::now())
+            .map_err(AgentError::ToolExecution)?;
+        Ok(vec![Part::Data(result)])
+    }
+}
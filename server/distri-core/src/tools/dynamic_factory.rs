@@ -2,6 +2,8 @@ use std::sync::Arc;
 
 use anyhow::Result;
 use distri_types::dynamic_tool::DynamicToolFactory;
+use distri_types::egress::EgressDenied;
+use distri_types::http_endpoint::HttpEndpointConfig;
 use distri_types::http_request::{HttpFactoryConfig, HttpFactoryToolInput};
 use distri_types::mock_tool::MockFactoryConfig;
 use distri_types::{Part, Tool, ToolContext};
@@ -9,8 +11,8 @@ use serde_json::json;
 
 use crate::agent::ExecutorContext;
 use crate::tools::mock_tool::build_mock_tool;
-use crate::tools::request::execute_http_request;
-use crate::tools::resolve::ResolveContext;
+use crate::tools::request::{execute_http_request, send_http_request, EgressCheck};
+use crate::tools::resolve::{extract_vars, resolve_all, substitute_string, ResolveContext};
 use crate::tools::ExecutorContextTool;
 use crate::types::ToolCall;
 use crate::AgentError;
@@ -29,6 +31,14 @@ pub fn create_dynamic_tool(factory: &DynamicToolFactory) -> Result<Arc<dyn Execu
                 description: factory.description.clone(),
             }))
         }
+        "http_endpoint" => {
+            let config = http_endpoint_config(factory)?;
+            Ok(Arc::new(HttpEndpointTool {
+                name: factory.name.clone(),
+                config,
+                description: factory.description.clone(),
+            }))
+        }
         "mock" => {
             let config: MockFactoryConfig = serde_json::from_value(factory.config.clone())
                 .map_err(|e| {
@@ -61,6 +71,7 @@ pub fn validate_dynamic_tool(factory: &DynamicToolFactory) -> Result<()> {
             }
             Ok(())
         }
+        "http_endpoint" => http_endpoint_config(factory).map(|_| ()),
         "mock" => {
             // Cheap structural validation — body checks happen via the
             // typed deserialise. A missing scenario id is allowed (the
@@ -80,6 +91,21 @@ pub fn validate_dynamic_tool(factory: &DynamicToolFactory) -> Result<()> {
     }
 }
 
+fn http_endpoint_config(factory: &DynamicToolFactory) -> Result<HttpEndpointConfig> {
+    let config: HttpEndpointConfig =
+        serde_json::from_value(factory.config.clone()).map_err(|e| {
+            anyhow::anyhow!(
+                "Invalid http_endpoint factory config for '{}': {}",
+                factory.name,
+                e
+            )
+        })?;
+    config
+        .validate()
+        .map_err(|e| anyhow::anyhow!("Dynamic tool '{}': {}", factory.name, e))?;
+    Ok(config)
+}
+
 /// HTTP factory tool — created from a DynamicToolFactory with type = "http".
 #[derive(Debug)]
 struct HttpFactoryTool {
@@ -168,12 +194,117 @@ impl ExecutorContextTool for HttpFactoryTool {
             secret_store,
         };
 
-        let result = execute_http_request(&request, &resolve_ctx, orch_stores)
+        let egress = EgressCheck::for_context(&context);
+        let result = execute_http_request(&request, &resolve_ctx, orch_stores, egress.as_ref())
+            .await
+            .map_err(|e| request_error(&self.name, e))?;
+
+        Ok(vec![Part::Data(
+            serde_json::to_value(&result).unwrap_or_default(),
+        )])
+    }
+}
+
+/// HTTP endpoint tool — created from a DynamicToolFactory with
+/// type = "http_endpoint". One declared request, filled from the call input.
+#[derive(Debug)]
+struct HttpEndpointTool {
+    name: String,
+    config: HttpEndpointConfig,
+    description: Option<String>,
+}
+
+#[async_trait::async_trait]
+impl Tool for HttpEndpointTool {
+    fn get_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_description(&self) -> String {
+        self.description
+            .clone()
+            .unwrap_or_else(|| format!("Call {} {}", self.config.method, self.config.url))
+    }
+
+    fn needs_executor_context(&self) -> bool {
+        true
+    }
+
+    fn get_parameters(&self) -> serde_json::Value {
+        self.config.parameters.clone()
+    }
+
+    async fn execute(
+        &self,
+        _tool_call: distri_types::ToolCall,
+        _context: Arc<ToolContext>,
+    ) -> Result<Vec<Part>, anyhow::Error> {
+        Err(anyhow::anyhow!("{} requires ExecutorContext", self.name))
+    }
+}
+
+#[async_trait::async_trait]
+impl ExecutorContextTool for HttpEndpointTool {
+    async fn execute_with_executor_context(
+        &self,
+        tool_call: ToolCall,
+        context: Arc<ExecutorContext>,
+    ) -> Result<Vec<Part>, AgentError> {
+        let mut request = self
+            .config
+            .build_request(&tool_call.input)
+            .map_err(|e| AgentError::ToolExecution(format!("{}: {}", self.name, e)))?;
+
+        // Input values are percent-encoded into the URL and never resolved,
+        // so every `$VAR_NAME` left comes from the config's URL or headers.
+        let mut vars = extract_vars(&request.url);
+        for (k, v) in &request.headers {
+            vars.extend(extract_vars(k));
+            vars.extend(extract_vars(v));
+        }
+        vars.sort();
+        vars.dedup();
+        let orch_stores = context.orchestrator.as_ref().map(|o| &o.stores);
+        let resolve_ctx = ResolveContext {
+            env_vars: context.env_vars.read().await.clone(),
+            secret_store: orch_stores.and_then(|s| s.secret_store.clone()),
+        };
+        let resolved = resolve_all(&vars, &resolve_ctx)
             .await
             .map_err(|e| AgentError::ToolExecution(format!("{}: {}", self.name, e)))?;
+        request.url = substitute_string(&request.url, &resolved);
+        request.headers = request
+            .headers
+            .iter()
+            .map(|(k, v)| {
+                (
+                    substitute_string(k, &resolved),
+                    substitute_string(v, &resolved),
+                )
+            })
+            .collect();
+
+        let egress = EgressCheck::for_context(&context);
+        let result = send_http_request(&request, orch_stores, egress.as_ref())
+            .await
+            .map_err(|e| request_error(&self.name, e))?;
+        if result.ok {
+            self.config
+                .check_response(&result.body)
+                .map_err(|e| AgentError::ToolExecution(format!("{}: {}", self.name, e)))?;
+        }
 
         Ok(vec![Part::Data(
             serde_json::to_value(&result).unwrap_or_default(),
         )])
     }
 }
+
+/// A failed request of tool `name`; a request the egress policy blocks is
+/// reported as such.
+fn request_error(name: &str, error: anyhow::Error) -> AgentError {
+    match error.downcast::<EgressDenied>() {
+        Ok(denied) => AgentError::EgressDenied(denied),
+        Err(e) => AgentError::ToolExecution(format!("{}: {}", name, e)),
+    }
+}
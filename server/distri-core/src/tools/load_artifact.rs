@@ -0,0 +1,289 @@
+//! Artifacts of earlier turns of a thread: the listing of the runtime
+//! layer's `artifacts` item, and `load_artifact`, which reads one of them
+//! back into the conversation.
+//!
+//! The listing keeps only a name, a one-line summary and an id per
+//! artifact, so the base context stays small; the model loads what the user
+//! refers to ("the report you made yesterday") by id.
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use base64::{engine::general_purpose, Engine as _};
+use distri_filesystem::ArtifactWrapper;
+use distri_types::filesystem::FileSystemOps;
+use distri_types::{
+    AgentEventType, FileMetadata, Part, TaskEvent, TaskMessage, Tool, ToolCall, ToolContext,
+};
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::agent::ExecutorContext;
+use crate::tools::ExecutorContextTool;
+use crate::AgentError;
+
+/// Characters of an artifact returned by one `load_artifact` call.
+const MAX_LOAD_CHARS: usize = 40_000;
+/// Characters of an artifact's summary in the listing.
+const MAX_SUMMARY_CHARS: usize = 120;
+
+/// Artifacts of the thread's messages and tool results, newest first.
+pub(crate) async fn thread_artifacts(context: &ExecutorContext) -> Vec<FileMetadata> {
+    let Some(orchestrator) = &context.orchestrator else {
+        return Vec::new();
+    };
+    let history = match orchestrator
+        .stores
+        .task_store
+        .get_history(&context.thread_id, None)
+        .await
+    {
+        Ok(history) => history,
+        Err(e) => {
+            tracing::warn!("Failed to load thread history for artifacts: {}", e);
+            return Vec::new();
+        }
+    };
+
+    let mut artifacts = Vec::new();
+    for message in history.into_iter().flat_map(|(_, messages)| messages) {
+        match message {
+            TaskMessage::Message(message) => collect_artifacts(message.parts, &mut artifacts),
+            // Tool results are recorded as events, not messages.
+            TaskMessage::Event(TaskEvent {
+                event: AgentEventType::ToolResults { results, .. },
+                ..
+            }) => {
+                for result in results {
+                    collect_artifacts(result.parts, &mut artifacts);
+                }
+            }
+            TaskMessage::Event(_) => {}
+        }
+    }
+
+    // Unsaved artifacts have no path to load them from.
+    artifacts.retain(|a| !a.relative_path.is_empty());
+    artifacts.sort_by_key(|a| std::cmp::Reverse(a.created_at));
+    let mut seen = HashSet::new();
+    artifacts.retain(|a| seen.insert(a.relative_path.clone()));
+    artifacts
+}
+
+fn collect_artifacts(parts: Vec<Part>, artifacts: &mut Vec<FileMetadata>) {
+    for part in parts {
+        match part {
+            Part::Artifact(metadata) => artifacts.push(metadata),
+            Part::ToolResult(response) => collect_artifacts(response.parts, artifacts),
+            _ => {}
+        }
+    }
+}
+
+/// One line per artifact: name, summary, id, type and date.
+pub(crate) fn describe_artifacts(artifacts: &[FileMetadata]) -> String {
+    artifacts
+        .iter()
+        .map(|artifact| {
+            let name = artifact
+                .original_filename
+                .as_deref()
+                .unwrap_or(&artifact.file_id);
+            let mut line = format!("- {}", name);
+            if let Some(summary) = artifact.preview.as_deref().and_then(summarize) {
+                line.push_str(&format!(": {}", summary));
+            }
+            line.push_str(&format!(
+                " (id: `{}`, {}, {})",
+                artifact.relative_path,
+                artifact
+                    .content_type
+                    .as_deref()
+                    .unwrap_or("application/octet-stream"),
+                artifact.created_at.format("%Y-%m-%d %H:%M UTC")
+            ));
+            line
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// First non-empty line of `preview`, shortened.
+fn summarize(preview: &str) -> Option<String> {
+    let line = preview.lines().map(str::trim).find(|l| !l.is_empty())?;
+    if line.chars().count() <= MAX_SUMMARY_CHARS {
+        return Some(line.to_string());
+    }
+    let short: String = line.chars().take(MAX_SUMMARY_CHARS).collect();
+    Some(format!("{}…", short.trim_end()))
+}
+
+#[derive(Debug, Deserialize)]
+struct LoadArtifactInput {
+    id: String,
+    #[serde(default)]
+    start_line: Option<usize>,
+    #[serde(default)]
+    end_line: Option<usize>,
+}
+
+#[derive(Debug)]
+pub struct LoadArtifactTool;
+
+#[async_trait]
+impl Tool for LoadArtifactTool {
+    fn get_name(&self) -> String {
+        "load_artifact".to_string()
+    }
+
+    fn get_description(&self) -> String {
+        "Load an artifact saved earlier in this conversation — a report, chart, export or \
+         stored tool result — by its id from the THREAD ARTIFACTS list. Text comes back as \
+         text (use start_line/end_line for long files) and images as images."
+            .to_string()
+    }
+
+    fn get_parameters(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "id": {
+                    "type": "string",
+                    "description": "The artifact's id, e.g. threads/1a2b3c4d/tasks/5e6f7a8b/content/report.md"
+                },
+                "start_line": {
+                    "type": "integer",
+                    "minimum": 1,
+                    "description": "First line to return (1-based). Text artifacts only."
+                },
+                "end_line": {
+                    "type": "integer",
+                    "minimum": 1,
+                    "description": "Last line to return, inclusive. Text artifacts only."
+                }
+            },
+            "required": ["id"]
+        })
+    }
+
+    fn needs_executor_context(&self) -> bool {
+        true
+    }
+
+    async fn execute(
+        &self,
+        _tool_call: ToolCall,
+        _context: Arc<ToolContext>,
+    ) -> Result<Vec<Part>, anyhow::Error> {
+        anyhow::bail!("LoadArtifactTool requires ExecutorContext")
+    }
+}
+
+#[async_trait]
+impl ExecutorContextTool for LoadArtifactTool {
+    async fn execute_with_executor_context(
+        &self,
+        tool_call: ToolCall,
+        context: Arc<ExecutorContext>,
+    ) -> Result<Vec<Part>, AgentError> {
+        let input: LoadArtifactInput = serde_json::from_value(tool_call.input.clone())
+            .map_err(|e| AgentError::ToolExecution(format!("invalid load_artifact input: {e}")))?;
+        let orchestrator = context.get_orchestrator()?;
+        // Only artifacts of this thread can be loaded.
+        let Some(metadata) = thread_artifacts(&context)
+            .await
+            .into_iter()
+            .find(|a| a.relative_path == input.id)
+        else {
+            return Err(AgentError::ToolExecution(format!(
+                "no artifact '{}' in this thread; use an id from the THREAD ARTIFACTS list",
+                input.id
+            )));
+        };
+        let filesystem = orchestrator.session_filesystem.clone() as Arc<dyn FileSystemOps>;
+
+        if metadata
+            .content_type
+            .as_deref()
+            .is_some_and(|t| t.starts_with("image/"))
+        {
+            return Ok(vec![
+                ArtifactWrapper::load_artifact(filesystem, &metadata, true).await,
+            ]);
+        }
+
+        let Some((namespace, filename)) = metadata.relative_path.rsplit_once("/content/") else {
+            return Err(AgentError::ToolExecution(format!(
+                "artifact '{}' has an unexpected path",
+                input.id
+            )));
+        };
+        let raw = ArtifactWrapper::new(filesystem, namespace.to_string())
+            .await
+            .map_err(|e| AgentError::ToolExecution(e.to_string()))?
+            .read_artifact_raw(filename)
+            .await
+            .map_err(|e| {
+                AgentError::ToolExecution(format!("failed to read artifact '{}': {}", input.id, e))
+            })?;
+        let text = decode_text(raw).ok_or_else(|| {
+            AgentError::ToolExecution(format!(
+                "artifact '{}' is binary ({}, {} bytes) and cannot be loaded as text",
+                input.id,
+                metadata.content_type.as_deref().unwrap_or("unknown type"),
+                metadata.size
+            ))
+        })?;
+        Ok(vec![Part::Text(select_lines(
+            &text,
+            input.start_line,
+            input.end_line,
+        ))])
+    }
+}
+
+/// `save_artifact` stores content base64-encoded; stored tool results are
+/// plain text. `None` for binary content.
+fn decode_text(raw: String) -> Option<String> {
+    match general_purpose::STANDARD.decode(raw.trim()) {
+        Ok(bytes) if !raw.trim().is_empty() => String::from_utf8(bytes).ok(),
+        _ => Some(raw),
+    }
+}
+
+/// Lines `start..=end` (1-based) of `text`, cut at [`MAX_LOAD_CHARS`]
+/// with a note on how to read on.
+fn select_lines(text: &str, start: Option<usize>, end: Option<usize>) -> String {
+    let lines: Vec<&str> = text.lines().collect();
+    let total = lines.len();
+    let start = start.unwrap_or(1).max(1);
+    let end = end.unwrap_or(total).min(total);
+    if start > end {
+        return format!("[the artifact has {} lines]", total);
+    }
+    let mut selected = String::new();
+    let mut next = start;
+    while next <= end {
+        let line = lines[next - 1];
+        if selected.is_empty() {
+            selected.extend(line.chars().take(MAX_LOAD_CHARS));
+        } else if selected.len() + line.len() > MAX_LOAD_CHARS {
+            break;
+        } else {
+            selected.push_str(line);
+        }
+        selected.push('\n');
+        next += 1;
+    }
+    if next <= total {
+        selected.push_str(&format!(
+            "\n[lines {}-{} of {}; pass start_line = {} to read on]",
+            start,
+            next - 1,
+            total,
+            next
+        ));
+    }
+    selected
+}
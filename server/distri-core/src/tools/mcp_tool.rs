@@ -10,16 +10,24 @@
 //! `create_agent_from_config` path is the only place that asks the attached
 //! `McpPoolProvider` for a pool, and that pool is then threaded through tool
 //! resolution into every adapter it produces.
+//!
+//! Resources of the same servers are reached through one [`ReadResourceTool`]
+//! (`read_resource`), whose description lists them.
 
 use std::sync::Arc;
 
 use crate::agent::ExecutorContext;
-use crate::servers::{McpClientPool, McpToolHandle};
+use crate::servers::{McpClientPool, McpResourceContent, McpResourceHandle, McpToolHandle};
 use crate::tools::ExecutorContextTool;
 use crate::types::ToolCall;
 use crate::AgentError;
 use distri_types::tool::ToolContext;
-use distri_types::{Part, ResourceLink, Tool};
+use distri_types::{FileType, Part, ResourceLink, Tool};
+
+pub const READ_RESOURCE_TOOL_NAME: &str = "read_resource";
+
+/// Most resources listed in the description of `read_resource`.
+const MAX_LISTED_RESOURCES: usize = 50;
 
 #[derive(Clone)]
 pub struct McpToolAdapter {
@@ -75,6 +83,10 @@ impl Tool for McpToolAdapter {
         self.handle.input_schema.clone()
     }
 
+    fn get_output_schema(&self) -> Option<serde_json::Value> {
+        self.handle.output_schema.clone()
+    }
+
     fn is_mcp(&self) -> bool {
         true
     }
@@ -130,9 +142,15 @@ impl ExecutorContextTool for McpToolAdapter {
             )));
         }
 
+        // `structuredContent` is the typed result; the text content then
+        // only repeats it for clients without structured output.
         let mut parts: Vec<Part> = Vec::new();
-        if !result.text.is_empty() {
-            parts.push(Part::Text(result.text.clone()));
+        match result.raw.get("structuredContent") {
+            Some(structured) if !structured.is_null() => {
+                parts.push(Part::Data(structured.clone()));
+            }
+            _ if !result.text.is_empty() => parts.push(Part::Text(result.text.clone())),
+            _ => {}
         }
         parts.extend(extract_resource_links(&result.raw));
         if parts.is_empty() {
@@ -142,6 +160,171 @@ impl ExecutorContextTool for McpToolAdapter {
     }
 }
 
+/// Reads resources from the MCP servers an agent is configured with.
+#[derive(Clone)]
+pub struct ReadResourceTool {
+    resources: Vec<McpResourceHandle>,
+    pool: Arc<McpClientPool>,
+}
+
+impl std::fmt::Debug for ReadResourceTool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ReadResourceTool")
+            .field("resources", &self.resources)
+            .finish()
+    }
+}
+
+impl ReadResourceTool {
+    pub fn new(resources: Vec<McpResourceHandle>, pool: Arc<McpClientPool>) -> Self {
+        Self { resources, pool }
+    }
+
+    /// The server to read `uri` from: the one given, the one listing the
+    /// resource, or the only server with resources.
+    fn server_for(&self, uri: &str, server: Option<&str>) -> Result<String, AgentError> {
+        if let Some(server) = server {
+            return Ok(server.to_string());
+        }
+        if let Some(resource) = self.resources.iter().find(|r| r.uri == uri) {
+            return Ok(resource.server.clone());
+        }
+        let mut servers: Vec<&str> = self.resources.iter().map(|r| r.server.as_str()).collect();
+        servers.sort_unstable();
+        servers.dedup();
+        match servers.as_slice() {
+            [only] => Ok(only.to_string()),
+            _ => Err(AgentError::ToolExecution(format!(
+                "resource '{}' is not listed; pass `server` (one of: {})",
+                uri,
+                servers.join(", ")
+            ))),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Tool for ReadResourceTool {
+    fn get_name(&self) -> String {
+        READ_RESOURCE_TOOL_NAME.to_string()
+    }
+
+    fn get_description(&self) -> String {
+        let mut description = "Read a resource (a file, document or record) from a connected MCP server by its URI. Available resources:".to_string();
+        for resource in self.resources.iter().take(MAX_LISTED_RESOURCES) {
+            description.push_str(&format!(
+                "\n- {} ({}): {}",
+                resource.uri, resource.server, resource.name
+            ));
+            if !resource.description.is_empty() {
+                description.push_str(&format!(" — {}", resource.description));
+            }
+        }
+        if self.resources.len() > MAX_LISTED_RESOURCES {
+            description.push_str(&format!(
+                "\n- ... and {} more",
+                self.resources.len() - MAX_LISTED_RESOURCES
+            ));
+        }
+        description
+    }
+
+    fn get_parameters(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "uri": {
+                    "type": "string",
+                    "description": "URI of the resource to read"
+                },
+                "server": {
+                    "type": "string",
+                    "description": "MCP server to read from; only needed for URIs not listed"
+                }
+            },
+            "required": ["uri"]
+        })
+    }
+
+    fn is_mcp(&self) -> bool {
+        true
+    }
+
+    fn needs_executor_context(&self) -> bool {
+        true
+    }
+
+    async fn execute(
+        &self,
+        _tool_call: ToolCall,
+        _context: Arc<ToolContext>,
+    ) -> Result<Vec<Part>, anyhow::Error> {
+        Err(anyhow::anyhow!(
+            "ReadResourceTool requires ExecutorContext for execution"
+        ))
+    }
+}
+
+#[async_trait::async_trait]
+impl ExecutorContextTool for ReadResourceTool {
+    async fn execute_with_executor_context(
+        &self,
+        tool_call: ToolCall,
+        _context: Arc<ExecutorContext>,
+    ) -> Result<Vec<Part>, AgentError> {
+        let uri = tool_call
+            .input
+            .get("uri")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| AgentError::ToolExecution("`uri` is required".to_string()))?;
+        let server = tool_call.input.get("server").and_then(|v| v.as_str());
+        let server = self.server_for(uri, server)?;
+
+        let client = self
+            .pool
+            .connect_named(&server)
+            .await
+            .map_err(|e| AgentError::ToolExecution(format!("connect '{}': {e}", server)))?;
+        let contents = client
+            .read_resource(uri)
+            .await
+            .map_err(|e| AgentError::ToolExecution(e.to_string()))?;
+        let mut parts = resource_parts(contents);
+        if parts.is_empty() {
+            parts.push(Part::Text(format!("Resource '{}' is empty.", uri)));
+        }
+        Ok(parts)
+    }
+}
+
+/// Text contents become text parts; blobs become image or file parts.
+fn resource_parts(contents: Vec<McpResourceContent>) -> Vec<Part> {
+    contents
+        .into_iter()
+        .map(|content| match content {
+            McpResourceContent::Text { text, .. } => Part::Text(text),
+            McpResourceContent::Blob {
+                uri,
+                mime_type,
+                blob,
+            } => {
+                let mime_type = mime_type.unwrap_or_else(|| "application/octet-stream".to_string());
+                let is_image = mime_type.starts_with("image/");
+                let file = FileType::Bytes {
+                    bytes: blob,
+                    mime_type,
+                    name: Some(uri),
+                };
+                if is_image {
+                    Part::Image(file)
+                } else {
+                    Part::File(file)
+                }
+            }
+        })
+        .collect()
+}
+
 /// Walk the raw `tools/call` response and pull out any MCP resource
 /// references (per-content-item or top-level `_meta.ui`). Surfaces them as
 /// `Part::ResourceLink` so chat hosts that understand MCP-Apps (distrijs,
@@ -207,3 +390,70 @@ fn extract_resource_links(raw: &serde_json::Value) -> Vec<Part> {
     }
     out
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn resource(server: &str, uri: &str) -> McpResourceHandle {
+        McpResourceHandle {
+            server: server.to_string(),
+            uri: uri.to_string(),
+            name: uri.rsplit('/').next().unwrap_or(uri).to_string(),
+            description: String::new(),
+            mime_type: None,
+        }
+    }
+
+    #[test]
+    fn resources_are_read_from_the_server_listing_them() {
+        let tool = ReadResourceTool::new(
+            vec![
+                resource("docs", "file:///guide.md"),
+                resource("crm", "crm://accounts/1"),
+            ],
+            Arc::new(McpClientPool::default()),
+        );
+        assert_eq!(tool.server_for("crm://accounts/1", None).unwrap(), "crm");
+        assert_eq!(
+            tool.server_for("crm://accounts/2", Some("crm")).unwrap(),
+            "crm"
+        );
+        let err = tool.server_for("crm://accounts/2", None).unwrap_err();
+        assert!(err.to_string().contains("crm, docs"), "{err}");
+        assert!(tool.get_description().contains("file:///guide.md (docs)"));
+
+        let single = ReadResourceTool::new(
+            vec![resource("docs", "file:///guide.md")],
+            Arc::new(McpClientPool::default()),
+        );
+        assert_eq!(single.server_for("file:///other.md", None).unwrap(), "docs");
+    }
+
+    #[test]
+    fn blobs_become_image_or_file_parts() {
+        let parts = resource_parts(vec![
+            McpResourceContent::Text {
+                uri: "file:///a.md".to_string(),
+                mime_type: None,
+                text: "# A".to_string(),
+            },
+            McpResourceContent::Blob {
+                uri: "file:///a.png".to_string(),
+                mime_type: Some("image/png".to_string()),
+                blob: "iVBORw0=".to_string(),
+            },
+            McpResourceContent::Blob {
+                uri: "file:///a.pdf".to_string(),
+                mime_type: None,
+                blob: "JVBERi0=".to_string(),
+            },
+        ]);
+        assert!(matches!(&parts[0], Part::Text(text) if text == "# A"));
+        assert!(matches!(&parts[1], Part::Image(_)));
+        match &parts[2] {
+            Part::File(file) => assert_eq!(file.mime_type(), "application/octet-stream"),
+            other => panic!("expected a file part, got {:?}", other),
+        }
+    }
+}
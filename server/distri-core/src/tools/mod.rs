@@ -3,14 +3,18 @@ use std::sync::Arc;
 use anyhow::Result;
 use tokio::sync::RwLock;
 
-use crate::agent::todos::TodosTool;
+use crate::agent::todos::{TodosTool, WorkTodosTool};
 use crate::agent::token_estimator::TokenEstimator;
 use crate::agent::ExecutorContext;
 use crate::servers::registry::McpServerRegistry;
-use crate::tools::browser::{BrowserStepTool, DistriBrowserSharedTool, DistriScrapeSharedTool};
+use crate::tools::browser::{
+    BrowserExtractTabsTool, BrowserStepTool, BrowserTabsTool, CrawlTool, DistriBrowserSharedTool,
+    DistriScrapeSharedTool,
+};
 use crate::tools::builtin::ArtifactTool;
 use crate::types::{ToolCall, ToolsConfig};
 use crate::AgentError;
+use distri_types::tool_catalog::ToolCatalog;
 use distri_types::Part;
 use serde::{Deserialize, Serialize};
 mod browser;
@@ -21,21 +25,31 @@ pub mod context;
 pub mod shell;
 mod state;
 pub use code::execute_code_with_tools;
-pub use context::to_tool_context;
+pub use context::{to_tool_context, to_tool_context_for};
 pub(crate) mod builtin;
+pub mod catalog;
+pub mod datetime;
 pub mod dynamic_factory;
 pub mod inject_env;
 pub mod invoke_agent;
+pub mod load_artifact;
 pub mod mcp_tool;
 pub mod mock_tool;
+pub mod publish_document;
 pub mod request;
 pub mod resolve;
+pub mod semantic_search;
 pub mod send_message;
 pub mod simulator;
 pub mod skill_script;
+pub mod sql;
+pub mod summarize_thread;
 pub mod supervisor;
 pub mod tool_search;
-pub use builtin::{get_builtin_tools, ConsoleLogTool, DistriExecuteCodeTool, FinalTool};
+pub mod working_memory;
+pub use builtin::{
+    get_builtin_tools, get_opt_in_builtin_tools, ConsoleLogTool, DistriExecuteCodeTool, FinalTool,
+};
 pub use inject_env::InjectConnectionEnvTool;
 pub use invoke_agent::InvokeAgentTool;
 pub use send_message::SendMessageTool;
@@ -105,11 +119,19 @@ pub fn cast_to_executor_context_tool(
 
     use std::any::Any;
 
+    // Aliases dispatch to the tool they rename.
+    if let Some(aliased) = (tool as &dyn Any).downcast_ref::<catalog::AliasedTool>() {
+        return Ok(Box::new(aliased.clone()));
+    }
+
     // MCP-backed tools: dispatch through their concrete adapter.
     if tool.is_mcp() {
         if let Some(adapter) = (tool as &dyn Any).downcast_ref::<mcp_tool::McpToolAdapter>() {
             return Ok(Box::new(adapter.clone()));
         }
+        if let Some(reader) = (tool as &dyn Any).downcast_ref::<mcp_tool::ReadResourceTool>() {
+            return Ok(Box::new(reader.clone()));
+        }
         return Err(AgentError::ToolExecution(format!(
             "tool '{tool_name}' reports is_mcp() but is not an McpToolAdapter"
         )));
@@ -123,10 +145,14 @@ pub fn cast_to_executor_context_tool(
     match tool_name.as_str() {
         "final" => Ok(Box::new(FinalTool)),
         "write_todos" => Ok(Box::new(TodosTool)),
+        "work_todos" => Ok(Box::new(WorkTodosTool)),
         // Browsr tools
         "browsr_scrape" => Ok(Box::new(DistriScrapeSharedTool)),
         "browsr_browser" => Ok(Box::new(DistriBrowserSharedTool)),
         "browser_step" => Ok(Box::new(BrowserStepTool)),
+        "browser_tabs" => Ok(Box::new(BrowserTabsTool)),
+        "browser_extract_tabs" => Ok(Box::new(BrowserExtractTabsTool)),
+        "browsr_crawl" => Ok(Box::new(CrawlTool)),
         "artifact_tool" => Ok(Box::new(ArtifactTool)),
         // Shell execution tools
         "start_shell" => Ok(Box::new(shell::StartShellTool)),
@@ -135,12 +161,17 @@ pub fn cast_to_executor_context_tool(
         "load_skill" => Ok(Box::new(skill_script::LoadSkillTool)),
         // Code execution
         "distri_execute_code" => Ok(Box::new(DistriExecuteCodeTool)),
+        "python_exec" => Ok(Box::new(code::PythonExecTool)),
         // Tool discovery
         "tool_search" => Ok(Box::new(tool_search::ToolSearchTool)),
         // Connection env injection
         "inject_connection_env" => Ok(Box::new(inject_env::InjectConnectionEnvTool)),
         // Artifact sharing (reads a file, persists via ArtifactWrapper, returns Part::Artifact)
         "save_artifact" => Ok(Box::new(save_artifact::SaveArtifactTool)),
+        // Artifacts of earlier turns, by id from the runtime layer's listing
+        "load_artifact" => Ok(Box::new(load_artifact::LoadArtifactTool)),
+        // Handover summary of the thread for a human taking over
+        "summarize_thread" => Ok(Box::new(summarize_thread::SummarizeThreadTool)),
         // Sub-agent dispatch via typed Invocation (replaces call_agent / run_skill).
         "invoke_agent" => Ok(Box::new(InvokeAgentTool)),
         // Supervisor tools — query / wait / cancel / list children spawned via invoke_agent.
@@ -149,6 +180,20 @@ pub fn cast_to_executor_context_tool(
         "cancel_task" => Ok(Box::new(CancelTaskTool)),
         "list_my_tasks" => Ok(Box::new(ListMyTasksTool)),
         "get_task_result" => Ok(Box::new(GetTaskResultTool)),
+        // Configured SQL connections
+        "sql_query" => Ok(Box::new(sql::SqlQueryTool)),
+        "sql_schema" => Ok(Box::new(sql::SqlSchemaTool)),
+        // Embedding search over the thread's artifacts
+        "semantic_search_artifacts" => Ok(Box::new(semantic_search::SemanticSearchArtifactsTool)),
+        // Thread working memory
+        "memory_set" => Ok(Box::new(working_memory::MemorySetTool)),
+        "memory_get" => Ok(Box::new(working_memory::MemoryGetTool)),
+        "memory_list" => Ok(Box::new(working_memory::MemoryListTool)),
+        // Date arithmetic and timezone conversion
+        "datetime_math" => Ok(Box::new(datetime::DateTimeMathTool)),
+        "timezone_convert" => Ok(Box::new(datetime::TimezoneConvertTool)),
+        // Notion / Google Docs export
+        "publish_document" => Ok(Box::new(publish_document::PublishDocumentTool)),
         // Inter-agent communication
         "send_message" => Ok(Box::new(SendMessageTool)),
         _ => Err(AgentError::ToolExecution(format!(
@@ -225,6 +270,9 @@ pub struct ResolvedTools {
     pub all_tools: Vec<Arc<dyn Tool>>,
     /// Estimated token savings from deferral
     pub deferred_token_savings: usize,
+    /// Sources, collisions and aliases of the tools. Filled in by
+    /// `AgentOrchestrator::get_agent_tools_with_pool`.
+    pub catalog: ToolCatalog,
 }
 
 /// Minimal info for a deferred tool shown in the system prompt.
@@ -297,6 +345,11 @@ pub async fn resolve_tools_config_with_pool(
         // Wildcard: include all builtin tools
         all_tools.extend(builtin_tools.iter().cloned());
     } else {
+        let builtin_tools: Vec<_> = builtin_tools
+            .iter()
+            .cloned()
+            .chain(get_opt_in_builtin_tools())
+            .collect();
         let mut require_tool_names = vec!["final"];
         for builtin_name in &config.builtin {
             if !require_tool_names.contains(&builtin_name.as_str()) {
@@ -369,9 +422,11 @@ pub async fn resolve_tools_config_with_pool(
     }
 
     // Resolve MCP tools: connect to each configured server, list tools, and
-    // adapt the ones that match the agent's include/exclude globs.
+    // adapt the ones that match the agent's include/exclude globs. The
+    // servers' resources are collected for a single `read_resource` tool.
     if let Some(pool) = mcp_pool.clone() {
         if !config.mcp.is_empty() {
+            let mut resources = Vec::new();
             for mcp_cfg in &config.mcp {
                 let server_name = &mcp_cfg.server;
                 let client = match pool.connect_named(server_name).await {
@@ -385,6 +440,16 @@ pub async fn resolve_tools_config_with_pool(
                         continue;
                     }
                 };
+                if client.supports_resources() {
+                    match client.list_resources().await {
+                        Ok(listed) => resources.extend(listed),
+                        Err(e) => tracing::warn!(
+                            server = %server_name,
+                            error = ?e,
+                            "MCP server list_resources failed"
+                        ),
+                    }
+                }
                 let tools = match client.list_tools().await {
                     Ok(t) => t,
                     Err(e) => {
@@ -410,6 +475,12 @@ pub async fn resolve_tools_config_with_pool(
                     )));
                 }
             }
+            if !resources.is_empty() {
+                all_tools.push(Arc::new(mcp_tool::ReadResourceTool::new(
+                    resources,
+                    pool.clone(),
+                )));
+            }
         }
     }
 
@@ -480,6 +551,7 @@ pub async fn resolve_tools_with_deferral_and_pool(
                 deferred_tools: vec![],
                 all_tools,
                 deferred_token_savings: 0,
+                catalog: ToolCatalog::default(),
             })
         }
         ToolDeliveryMode::Deferred | ToolDeliveryMode::NamesOnly => {
@@ -529,6 +601,7 @@ pub async fn resolve_tools_with_deferral_and_pool(
                 deferred_tools: deferred,
                 all_tools,
                 deferred_token_savings: deferred_savings,
+                catalog: ToolCatalog::default(),
             })
         }
     }
@@ -621,6 +694,35 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn cast_python_exec_tool() {
+        let tool = code::PythonExecTool;
+        let result = cast_to_executor_context_tool(&tool);
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn python_exec_is_only_resolved_by_name() {
+        let registry = Arc::new(RwLock::new(McpServerRegistry::new()));
+        let resolve = |builtin: &str| ToolsConfig {
+            builtin: vec![builtin.to_string()],
+            ..Default::default()
+        };
+        let names = |tools: Vec<Arc<dyn Tool>>| -> Vec<String> {
+            tools.iter().map(|t| t.get_name()).collect()
+        };
+
+        let all = resolve_tools_config(&resolve("*"), registry.clone(), &[])
+            .await
+            .unwrap();
+        assert!(!names(all).contains(&"python_exec".to_string()));
+
+        let named = resolve_tools_config(&resolve("python_exec"), registry, &[])
+            .await
+            .unwrap();
+        assert!(names(named).contains(&"python_exec".to_string()));
+    }
+
     #[test]
     fn cast_tool_search_tool() {
         let tool = ToolSearchTool;
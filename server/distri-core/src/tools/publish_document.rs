@@ -0,0 +1,603 @@
+//! `publish_document`: write a markdown document into Notion or Google Docs
+//! through the run user's connection, and return its URL.
+//!
+//! Images and links pointing at artifacts of the thread (by their id from
+//! the THREAD ARTIFACTS list) are rewritten to presigned URLs. Google Docs
+//! copies images into the document when it imports the markdown; Notion
+//! links them, so they stay visible for [`ARTIFACT_URL_TTL`]. When the
+//! object store cannot sign URLs, the artifact is named in plain text.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{anyhow, Context, Result};
+use async_trait::async_trait;
+use distri_types::{Part, Tool, ToolCall, ToolContext};
+use regex::Regex;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::agent::ExecutorContext;
+use crate::connections::provider_http_headers;
+use crate::tools::load_artifact::thread_artifacts;
+use crate::tools::ExecutorContextTool;
+use crate::AgentError;
+
+/// How long the presigned URLs of linked artifacts stay valid (the longest
+/// S3 allows).
+const ARTIFACT_URL_TTL: Duration = Duration::from_secs(7 * 24 * 3600);
+const NOTION_API: &str = "https://api.notion.com/v1";
+const NOTION_VERSION: &str = "2022-06-28";
+/// Blocks Notion accepts in one request.
+const NOTION_MAX_BLOCKS: usize = 100;
+/// Characters of one Notion rich text object.
+const NOTION_MAX_TEXT: usize = 2000;
+const DRIVE_UPLOAD_URL: &str =
+    "https://www.googleapis.com/upload/drive/v3/files?uploadType=multipart&fields=id,webViewLink";
+
+/// Code languages Notion accepts, besides `plain text`.
+const NOTION_LANGUAGES: &[&str] = &[
+    "bash",
+    "c",
+    "c#",
+    "c++",
+    "css",
+    "diff",
+    "docker",
+    "go",
+    "graphql",
+    "html",
+    "java",
+    "javascript",
+    "json",
+    "kotlin",
+    "markdown",
+    "mermaid",
+    "python",
+    "ruby",
+    "rust",
+    "scala",
+    "shell",
+    "sql",
+    "swift",
+    "toml",
+    "typescript",
+    "xml",
+    "yaml",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum Destination {
+    Notion,
+    GoogleDocs,
+}
+
+impl Destination {
+    fn name(self) -> &'static str {
+        match self {
+            Destination::Notion => "notion",
+            Destination::GoogleDocs => "google_docs",
+        }
+    }
+
+    fn default_connection(self) -> &'static str {
+        match self {
+            Destination::Notion => "notion",
+            Destination::GoogleDocs => "google",
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct PublishDocumentInput {
+    destination: Destination,
+    title: String,
+    content: String,
+    /// Notion page or Google Drive folder to create the document in.
+    #[serde(default)]
+    parent: Option<String>,
+    /// Provider of the connection to publish with.
+    #[serde(default)]
+    connection: Option<String>,
+}
+
+#[derive(Debug)]
+pub struct PublishDocumentTool;
+
+#[async_trait]
+impl Tool for PublishDocumentTool {
+    fn get_name(&self) -> String {
+        "publish_document".to_string()
+    }
+
+    fn get_description(&self) -> String {
+        "Publish a markdown document — usually your final report — to Notion or Google Docs \
+         with the user's connection, and get back its URL to share. Reference images and \
+         files of this conversation by their id from the THREAD ARTIFACTS list, e.g. \
+         ![chart](threads/…/content/chart.png); they are embedded or linked."
+            .to_string()
+    }
+
+    fn get_parameters(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "destination": {
+                    "type": "string",
+                    "enum": ["notion", "google_docs"]
+                },
+                "title": {
+                    "type": "string",
+                    "description": "Title of the document."
+                },
+                "content": {
+                    "type": "string",
+                    "description": "The document in markdown, without the title."
+                },
+                "parent": {
+                    "type": "string",
+                    "description": "Notion: id of the page to create the document under (required). Google Docs: id of a Drive folder (optional)."
+                },
+                "connection": {
+                    "type": "string",
+                    "description": "Provider of the connection to publish with. Defaults to `notion` or `google`."
+                }
+            },
+            "required": ["destination", "title", "content"]
+        })
+    }
+
+    fn needs_executor_context(&self) -> bool {
+        true
+    }
+
+    async fn execute(
+        &self,
+        _tool_call: ToolCall,
+        _context: Arc<ToolContext>,
+    ) -> Result<Vec<Part>, anyhow::Error> {
+        anyhow::bail!("PublishDocumentTool requires ExecutorContext")
+    }
+}
+
+#[async_trait]
+impl ExecutorContextTool for PublishDocumentTool {
+    async fn execute_with_executor_context(
+        &self,
+        tool_call: ToolCall,
+        context: Arc<ExecutorContext>,
+    ) -> Result<Vec<Part>, AgentError> {
+        let input: PublishDocumentInput =
+            serde_json::from_value(tool_call.input.clone()).map_err(|e| {
+                AgentError::ToolExecution(format!("invalid publish_document input: {e}"))
+            })?;
+        let provider = input
+            .connection
+            .as_deref()
+            .unwrap_or(input.destination.default_connection());
+        let content = link_artifacts(&input.content, &context).await;
+        let published = publish(&input, provider, &content, &context)
+            .await
+            .map_err(|e| {
+                AgentError::ToolExecution(format!("failed to publish the document: {e:#}"))
+            })?;
+        Ok(vec![Part::Data(json!({
+            "destination": input.destination.name(),
+            "id": published.id,
+            "url": published.url,
+        }))])
+    }
+}
+
+struct Published {
+    id: String,
+    url: String,
+}
+
+async fn publish(
+    input: &PublishDocumentInput,
+    provider: &str,
+    content: &str,
+    context: &ExecutorContext,
+) -> Result<Published> {
+    let headers = provider_http_headers(context, provider)
+        .await
+        .with_context(|| format!("resolving the '{}' connection", provider))?;
+    let client = reqwest::Client::new();
+    let authorized = |request: reqwest::RequestBuilder| {
+        headers.iter().fold(request, |request, (name, value)| {
+            request.header(name, value)
+        })
+    };
+
+    match input.destination {
+        Destination::Notion => {
+            let parent = input
+                .parent
+                .as_deref()
+                .ok_or_else(|| anyhow!("Notion needs the id of a parent page in `parent`"))?;
+            let blocks = notion_blocks(content);
+            let mut chunks = blocks.chunks(NOTION_MAX_BLOCKS);
+            let page: Value = authorized(client.post(format!("{NOTION_API}/pages")))
+                .header("Notion-Version", NOTION_VERSION)
+                .json(&json!({
+                    "parent": { "page_id": parent },
+                    "properties": { "title": { "title": rich_text(&input.title) } },
+                    "children": chunks.next().unwrap_or_default(),
+                }))
+                .send()
+                .await?
+                .error_for_status()?
+                .json()
+                .await?;
+            let id = page["id"]
+                .as_str()
+                .context("Notion returned no page id")?
+                .to_string();
+            for chunk in chunks {
+                authorized(client.patch(format!("{NOTION_API}/blocks/{id}/children")))
+                    .header("Notion-Version", NOTION_VERSION)
+                    .json(&json!({ "children": chunk }))
+                    .send()
+                    .await?
+                    .error_for_status()?;
+            }
+            let url = page["url"].as_str().unwrap_or_default().to_string();
+            Ok(Published { id, url })
+        }
+        Destination::GoogleDocs => {
+            let mut metadata = json!({
+                "name": input.title,
+                "mimeType": "application/vnd.google-apps.document",
+            });
+            if let Some(folder) = &input.parent {
+                metadata["parents"] = json!([folder]);
+            }
+            let boundary = format!("distri-{}", uuid::Uuid::new_v4().simple());
+            let file: Value = authorized(client.post(DRIVE_UPLOAD_URL))
+                .header(
+                    "Content-Type",
+                    format!("multipart/related; boundary={boundary}"),
+                )
+                .body(drive_upload_body(&boundary, &metadata, content))
+                .send()
+                .await?
+                .error_for_status()?
+                .json()
+                .await?;
+            let id = file["id"]
+                .as_str()
+                .context("Google Drive returned no file id")?
+                .to_string();
+            let url = file["webViewLink"]
+                .as_str()
+                .map(str::to_string)
+                .unwrap_or_else(|| format!("https://docs.google.com/document/d/{id}/edit"));
+            Ok(Published { id, url })
+        }
+    }
+}
+
+/// Drive multipart upload: the file's metadata, then the markdown Drive
+/// converts into a document.
+fn drive_upload_body(boundary: &str, metadata: &Value, markdown: &str) -> String {
+    format!(
+        "--{boundary}\r\nContent-Type: application/json; charset=UTF-8\r\n\r\n{metadata}\r\n\
+         --{boundary}\r\nContent-Type: text/markdown; charset=UTF-8\r\n\r\n{markdown}\r\n\
+         --{boundary}--"
+    )
+}
+
+/// Point the links of `markdown` to thread artifacts at presigned URLs.
+async fn link_artifacts(markdown: &str, context: &ExecutorContext) -> String {
+    let Ok(orchestrator) = context.get_orchestrator() else {
+        return markdown.to_string();
+    };
+    let mut linked = Vec::new();
+    for artifact in thread_artifacts(context).await {
+        if !markdown.contains(&format!("]({})", artifact.relative_path)) {
+            continue;
+        }
+        let url = match orchestrator
+            .session_filesystem
+            .presigned_url(&artifact.relative_path, ARTIFACT_URL_TTL)
+            .await
+        {
+            Ok(url) => url,
+            Err(e) => {
+                tracing::warn!(
+                    "Failed to presign artifact {}: {}",
+                    artifact.relative_path,
+                    e
+                );
+                None
+            }
+        };
+        let name = artifact
+            .original_filename
+            .clone()
+            .unwrap_or_else(|| artifact.file_id.clone());
+        linked.push((artifact.relative_path, url, name));
+    }
+    rewrite_artifact_links(markdown, &linked)
+}
+
+/// Replace the targets of links to `(id, url, name)` artifacts with `url`;
+/// without one, the link becomes its text and the artifact's name.
+fn rewrite_artifact_links(
+    markdown: &str,
+    artifacts: &[(String, Option<String>, String)],
+) -> String {
+    let mut out = markdown.to_string();
+    for (id, url, name) in artifacts {
+        let link = Regex::new(&format!(r"(!?)\[([^\]]*)\]\({}\)", regex::escape(id)))
+            .expect("escaped artifact id");
+        out = match url {
+            Some(url) => link
+                .replace_all(&out, |c: &regex::Captures| {
+                    format!("{}[{}]({})", &c[1], &c[2], url)
+                })
+                .into_owned(),
+            None => link
+                .replace_all(&out, |c: &regex::Captures| {
+                    if c[2].is_empty() {
+                        format!("({name})")
+                    } else {
+                        format!("{} ({name})", &c[2])
+                    }
+                })
+                .into_owned(),
+        };
+    }
+    out
+}
+
+/// Notion blocks of a markdown document: headings, lists, to-dos, quotes,
+/// code, dividers, images and paragraphs.
+fn notion_blocks(markdown: &str) -> Vec<Value> {
+    let image = Regex::new(r"^!\[[^\]]*\]\((https?://[^)\s]+)\)$").expect("image pattern");
+    let numbered = Regex::new(r"^\d+[.)]\s+(.*)$").expect("numbered item pattern");
+    let mut blocks = Vec::new();
+    let mut paragraph: Vec<&str> = Vec::new();
+    let mut lines = markdown.lines();
+
+    while let Some(line) = lines.next() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            flush_paragraph(&mut paragraph, &mut blocks);
+            continue;
+        }
+        if let Some(info) = trimmed.strip_prefix("```") {
+            flush_paragraph(&mut paragraph, &mut blocks);
+            let code: Vec<&str> = lines
+                .by_ref()
+                .take_while(|l| !l.trim_start().starts_with("```"))
+                .collect();
+            let language = info.trim().to_lowercase();
+            let language = match language.as_str() {
+                "sh" => "shell",
+                "js" => "javascript",
+                "ts" => "typescript",
+                "py" => "python",
+                "rs" => "rust",
+                "yml" => "yaml",
+                l if NOTION_LANGUAGES.contains(&l) => l,
+                _ => "plain text",
+            };
+            blocks.push(json!({
+                "type": "code",
+                "code": { "rich_text": plain_text(&code.join("\n")), "language": language },
+            }));
+            continue;
+        }
+
+        let block = if let Some(text) = trimmed.strip_prefix("### ") {
+            block("heading_3", rich_text(text))
+        } else if let Some(text) = trimmed.strip_prefix("## ") {
+            block("heading_2", rich_text(text))
+        } else if let Some(text) = trimmed.strip_prefix("# ") {
+            block("heading_1", rich_text(text))
+        } else if matches!(trimmed, "---" | "***" | "___") {
+            json!({ "type": "divider", "divider": {} })
+        } else if let Some(captures) = image.captures(trimmed) {
+            json!({
+                "type": "image",
+                "image": { "type": "external", "external": { "url": &captures[1] } },
+            })
+        } else if let Some(text) = trimmed.strip_prefix("> ") {
+            block("quote", rich_text(text))
+        } else if let Some(item) = trimmed
+            .strip_prefix("- ")
+            .or_else(|| trimmed.strip_prefix("* "))
+        {
+            match item
+                .strip_prefix("[ ] ")
+                .map(|t| (t, false))
+                .or_else(|| item.strip_prefix("[x] ").map(|t| (t, true)))
+            {
+                Some((text, checked)) => json!({
+                    "type": "to_do",
+                    "to_do": { "rich_text": rich_text(text), "checked": checked },
+                }),
+                None => block("bulleted_list_item", rich_text(item)),
+            }
+        } else if let Some(captures) = numbered.captures(trimmed) {
+            block("numbered_list_item", rich_text(&captures[1]))
+        } else {
+            paragraph.push(trimmed);
+            continue;
+        };
+        flush_paragraph(&mut paragraph, &mut blocks);
+        blocks.push(block);
+    }
+    flush_paragraph(&mut paragraph, &mut blocks);
+    blocks
+}
+
+fn block(kind: &str, rich_text: Vec<Value>) -> Value {
+    json!({ "type": kind, kind: { "rich_text": rich_text } })
+}
+
+fn flush_paragraph(lines: &mut Vec<&str>, blocks: &mut Vec<Value>) {
+    if !lines.is_empty() {
+        blocks.push(block("paragraph", rich_text(&lines.join(" "))));
+        lines.clear();
+    }
+}
+
+/// Notion rich text of a line of markdown: `**bold**`, `*italic*`,
+/// `` `code` `` and `[links](…)`.
+fn rich_text(markdown: &str) -> Vec<Value> {
+    let inline = Regex::new(r"\*\*([^*]+)\*\*|\*([^*]+)\*|`([^`]+)`|\[([^\]]+)\]\(([^)\s]+)\)")
+        .expect("inline pattern");
+    let mut texts = Vec::new();
+    let mut rest = 0;
+    for c in inline.captures_iter(markdown) {
+        let whole = c.get(0).expect("whole match");
+        texts.extend(text_objects(
+            &markdown[rest..whole.start()],
+            json!({}),
+            None,
+        ));
+        let (content, annotations, link) = if let Some(bold) = c.get(1) {
+            (bold.as_str(), json!({ "bold": true }), None)
+        } else if let Some(italic) = c.get(2) {
+            (italic.as_str(), json!({ "italic": true }), None)
+        } else if let Some(code) = c.get(3) {
+            (code.as_str(), json!({ "code": true }), None)
+        } else {
+            (&c[4], json!({}), Some(&c[5]))
+        };
+        texts.extend(text_objects(content, annotations, link));
+        rest = whole.end();
+    }
+    texts.extend(text_objects(&markdown[rest..], json!({}), None));
+    texts
+}
+
+fn plain_text(text: &str) -> Vec<Value> {
+    text_objects(text, json!({}), None)
+}
+
+/// `text` as Notion text objects of at most [`NOTION_MAX_TEXT`] characters.
+fn text_objects(text: &str, annotations: Value, link: Option<&str>) -> Vec<Value> {
+    let chars: Vec<char> = text.chars().collect();
+    chars
+        .chunks(NOTION_MAX_TEXT)
+        .map(|chunk| {
+            let mut text = json!({ "content": chunk.iter().collect::<String>() });
+            if let Some(url) = link.filter(|u| u.starts_with("http")) {
+                text["link"] = json!({ "url": url });
+            }
+            let mut object = json!({ "type": "text", "text": text });
+            if annotations.as_object().is_some_and(|a| !a.is_empty()) {
+                object["annotations"] = annotations.clone();
+            }
+            object
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn types(blocks: &[Value]) -> Vec<&str> {
+        blocks.iter().map(|b| b["type"].as_str().unwrap()).collect()
+    }
+
+    #[test]
+    fn markdown_becomes_notion_blocks() {
+        let blocks = notion_blocks(
+            "# Findings\n\nThe market grew\nby 12%.\n\n## Sources\n- one\n* two\n1. first\n- [x] done\n> quoted\n---\n![chart](https://example.com/c.png)\n```py\nprint(1)\n```",
+        );
+        assert_eq!(
+            types(&blocks),
+            [
+                "heading_1",
+                "paragraph",
+                "heading_2",
+                "bulleted_list_item",
+                "bulleted_list_item",
+                "numbered_list_item",
+                "to_do",
+                "quote",
+                "divider",
+                "image",
+                "code",
+            ]
+        );
+        assert_eq!(
+            blocks[1]["paragraph"]["rich_text"][0]["text"]["content"],
+            "The market grew by 12%."
+        );
+        assert_eq!(blocks[6]["to_do"]["checked"], true);
+        assert_eq!(
+            blocks[9]["image"]["external"]["url"],
+            "https://example.com/c.png"
+        );
+        assert_eq!(blocks[10]["code"]["language"], "python");
+        assert_eq!(
+            blocks[10]["code"]["rich_text"][0]["text"]["content"],
+            "print(1)"
+        );
+    }
+
+    #[test]
+    fn inline_markdown_becomes_annotated_text() {
+        let texts = rich_text("See **this** and [the docs](https://example.com), `x`.");
+        let contents: Vec<&str> = texts
+            .iter()
+            .map(|t| t["text"]["content"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            contents,
+            ["See ", "this", " and ", "the docs", ", ", "x", "."]
+        );
+        assert_eq!(texts[1]["annotations"]["bold"], true);
+        assert_eq!(texts[3]["text"]["link"]["url"], "https://example.com");
+        assert_eq!(texts[5]["annotations"]["code"], true);
+    }
+
+    #[test]
+    fn long_text_is_split_for_notion() {
+        let texts = plain_text(&"a".repeat(NOTION_MAX_TEXT + 1));
+        assert_eq!(texts.len(), 2);
+        assert_eq!(texts[1]["text"]["content"], "a");
+    }
+
+    #[test]
+    fn artifact_links_point_at_presigned_urls_or_name_the_artifact() {
+        let markdown =
+            "![chart](threads/t/content/chart.png)\nSee [the data](threads/t/content/rows.csv).";
+        let rewritten = rewrite_artifact_links(
+            markdown,
+            &[
+                (
+                    "threads/t/content/chart.png".to_string(),
+                    Some("https://bucket.example.com/chart.png?sig=1".to_string()),
+                    "chart.png".to_string(),
+                ),
+                (
+                    "threads/t/content/rows.csv".to_string(),
+                    None,
+                    "rows.csv".to_string(),
+                ),
+            ],
+        );
+        assert_eq!(
+            rewritten,
+            "![chart](https://bucket.example.com/chart.png?sig=1)\nSee the data (rows.csv)."
+        );
+    }
+
+    #[test]
+    fn drive_upload_holds_metadata_and_markdown() {
+        let body = drive_upload_body("b", &json!({ "name": "Report" }), "# Hi");
+        assert_eq!(
+            body,
+            "--b\r\nContent-Type: application/json; charset=UTF-8\r\n\r\n{\"name\":\"Report\"}\r\n--b\r\nContent-Type: text/markdown; charset=UTF-8\r\n\r\n# Hi\r\n--b--"
+        );
+    }
+}
@@ -3,12 +3,20 @@
 //! Provides `execute_http_request` which resolves `$VAR_NAME` references,
 //! handles `x-connection-id` OAuth injection, and executes the request.
 //!
-//! Used by the `POST /request` server route.
+//! Used by the `POST /request` server route. `send_http_request` sends a
+//! request whose variables are already resolved.
+//!
+//! Requests sent for an agent's tools carry an [`EgressCheck`]: the URL and
+//! every redirect are checked against the server's egress policy, and a
+//! blocked one fails with [`EgressDenied`].
 
 use std::collections::HashMap;
+use std::sync::Arc;
 
+use distri_types::egress::{EgressDenied, EgressPolicy};
 use distri_types::http_request::{HttpMethod, HttpRequestInput, HttpRequestResponse};
 
+use crate::agent::{AgentOrchestrator, ExecutorContext};
 use crate::connections::{ConnectionResolver, DefaultResolver, ResolveCtx};
 use crate::tools::resolve::{
     extract_vars, extract_vars_from_value, resolve_all, substitute_string, ResolveContext,
@@ -28,6 +36,60 @@ const USEFUL_HEADERS: &[&str] = &[
     "link",
 ];
 
+/// Most redirects a request follows, as reqwest's default policy.
+const MAX_REDIRECTS: usize = 10;
+
+/// The egress policy a request is sent under, and the agent it is sent for.
+#[derive(Debug, Clone)]
+pub struct EgressCheck {
+    pub policy: Arc<EgressPolicy>,
+    pub agent: Option<String>,
+}
+
+impl EgressCheck {
+    /// For the tools of the agent of `context`; `None` when the server has
+    /// no egress policy.
+    pub fn for_context(context: &ExecutorContext) -> Option<Self> {
+        let policy = context.orchestrator.as_ref()?.egress_policy.clone()?;
+        Some(Self {
+            policy,
+            agent: Some(context.agent_id.clone()),
+        })
+    }
+
+    /// For requests not sent for one agent, such as those of the shared
+    /// MCP servers: only the global rules apply.
+    pub fn global(orchestrator: &AgentOrchestrator) -> Option<Self> {
+        orchestrator.egress_policy.clone().map(|policy| Self {
+            policy,
+            agent: None,
+        })
+    }
+
+    /// Whether the request may go to `url`. Blocked requests are logged.
+    pub fn check(&self, url: &str) -> Result<(), EgressDenied> {
+        self.policy
+            .check(self.agent.as_deref(), url)
+            .inspect_err(|denied| {
+                tracing::warn!(agent = ?self.agent, host = %denied.host, "{}", denied);
+            })
+    }
+
+    /// Follows redirects only to hosts the policy allows.
+    pub(crate) fn redirect_policy(&self) -> reqwest::redirect::Policy {
+        let check = self.clone();
+        reqwest::redirect::Policy::custom(move |attempt| {
+            if attempt.previous().len() >= MAX_REDIRECTS {
+                return attempt.error("too many redirects");
+            }
+            match check.check(attempt.url().as_str()) {
+                Ok(()) => attempt.follow(),
+                Err(denied) => attempt.error(denied),
+            }
+        })
+    }
+}
+
 /// Execute an HTTP request with variable resolution.
 ///
 /// Resolves `$VAR_NAME` references in url, headers, and body from the
@@ -37,11 +99,9 @@ pub async fn execute_http_request(
     input: &HttpRequestInput,
     resolve_ctx: &ResolveContext,
     stores: Option<&distri_types::stores::InitializedStores>,
+    egress: Option<&EgressCheck>,
 ) -> Result<HttpRequestResponse, anyhow::Error> {
-    // 1. Check for x-connection-id (consumed, not forwarded)
-    let connection_id = input.headers.get("x-connection-id").cloned();
-
-    // 2. Collect all $VAR references
+    // 1. Collect all $VAR references
     let mut all_vars = extract_vars(&input.url);
     for (k, v) in &input.headers {
         all_vars.extend(extract_vars(k));
@@ -53,30 +113,62 @@ pub async fn execute_http_request(
     all_vars.sort();
     all_vars.dedup();
 
-    // 3. Resolve variables
+    // 2. Resolve variables
     let resolved = resolve_all(&all_vars, resolve_ctx)
         .await
         .map_err(|e| anyhow::anyhow!(e))?;
 
-    // 4. Substitute resolved values
-    let url = substitute_string(&input.url, &resolved);
+    // 3. Substitute resolved values (the connection id is kept as given)
     let headers: HashMap<String, String> = input
         .headers
         .iter()
-        .filter(|(k, _)| k.as_str() != "x-connection-id")
         .map(|(k, v)| {
-            (
-                substitute_string(k, &resolved),
-                substitute_string(v, &resolved),
-            )
+            if k.as_str() == "x-connection-id" {
+                (k.clone(), v.clone())
+            } else {
+                (
+                    substitute_string(k, &resolved),
+                    substitute_string(v, &resolved),
+                )
+            }
         })
         .collect();
-    let body = input
-        .body
-        .as_ref()
-        .map(|b| distri_types::resolve::substitute_value(b, &resolved));
+    let request = HttpRequestInput {
+        url: substitute_string(&input.url, &resolved),
+        method: input.method.clone(),
+        headers,
+        body: input
+            .body
+            .as_ref()
+            .map(|b| distri_types::resolve::substitute_value(b, &resolved)),
+    };
+
+    send_http_request(&request, stores, egress).await
+}
+
+/// Send an HTTP request as given, without `$VAR_NAME` resolution. Handles
+/// `x-connection-id` for OAuth Bearer token injection.
+pub async fn send_http_request(
+    input: &HttpRequestInput,
+    stores: Option<&distri_types::stores::InitializedStores>,
+    egress: Option<&EgressCheck>,
+) -> Result<HttpRequestResponse, anyhow::Error> {
+    let url = &input.url;
+    // Checked before any credentials are resolved for it.
+    if let Some(egress) = egress {
+        egress.check(url)?;
+    }
+
+    // 1. Check for x-connection-id (consumed, not forwarded)
+    let connection_id = input.headers.get("x-connection-id").cloned();
+    let headers: HashMap<&String, &String> = input
+        .headers
+        .iter()
+        .filter(|(k, _)| k.as_str() != "x-connection-id")
+        .collect();
+    let body = input.body.as_ref();
 
-    // 5. Build request headers
+    // 2. Build request headers
     let mut header_map = reqwest::header::HeaderMap::new();
     let mut has_content_type = false;
 
@@ -92,7 +184,7 @@ pub async fn execute_http_request(
         }
     }
 
-    // 6. If x-connection-id was present, resolve via unified resolver and
+    // 3. If x-connection-id was present, resolve via unified resolver and
     // inject the resulting HTTP headers (Bearer for OAuth, templated header
     // for Custom with auth_header_template).
     if let Some(ref conn_id) = connection_id {
@@ -113,20 +205,25 @@ pub async fn execute_http_request(
         }
     }
 
-    // 7. Build and send request
-    let client = reqwest::Client::new();
+    // 4. Build and send request
+    let client = match egress {
+        Some(egress) => reqwest::Client::builder()
+            .redirect(egress.redirect_policy())
+            .build()?,
+        None => reqwest::Client::new(),
+    };
     let method_str = input.method.to_string();
     let mut request = match input.method {
-        HttpMethod::GET => client.get(&url),
-        HttpMethod::POST => client.post(&url),
-        HttpMethod::PUT => client.put(&url),
-        HttpMethod::PATCH => client.patch(&url),
-        HttpMethod::DELETE => client.delete(&url),
+        HttpMethod::GET => client.get(url),
+        HttpMethod::POST => client.post(url),
+        HttpMethod::PUT => client.put(url),
+        HttpMethod::PATCH => client.patch(url),
+        HttpMethod::DELETE => client.delete(url),
     };
 
     request = request.headers(header_map);
 
-    if let Some(ref body) = body {
+    if let Some(body) = body {
         if method_str != "GET" && method_str != "DELETE" {
             if !has_content_type {
                 request = request.json(body);
@@ -143,9 +240,10 @@ pub async fn execute_http_request(
     let response = request
         .timeout(std::time::Duration::from_secs(120))
         .send()
-        .await?;
+        .await
+        .map_err(send_error)?;
 
-    // 8. Read response
+    // 5. Read response
     let status = response.status().as_u16();
     let response_headers: HashMap<String, String> = response
         .headers()
@@ -177,3 +275,16 @@ pub async fn execute_http_request(
         body,
     })
 }
+
+/// `error` of sending a request, or the [`EgressDenied`] that stopped one
+/// of its redirects.
+pub(crate) fn send_error(error: reqwest::Error) -> anyhow::Error {
+    let mut source = std::error::Error::source(&error);
+    while let Some(e) = source {
+        if let Some(denied) = e.downcast_ref::<EgressDenied>() {
+            return denied.clone().into();
+        }
+        source = e.source();
+    }
+    error.into()
+}
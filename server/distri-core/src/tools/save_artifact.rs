@@ -166,61 +166,72 @@ impl ExecutorContextTool for SaveArtifactTool {
             ));
         };
 
-        let mime_type = mime_from_filename(&filename);
-
-        // Persist to artifact store
-        let artifact_path = if let Ok(orchestrator) = context.get_orchestrator() {
-            let base_path = distri_filesystem::ArtifactWrapper::task_namespace(
-                &context.thread_id,
-                &context.task_id,
-            );
-            match orchestrator
-                .session_filesystem
-                .create_artifact_wrapper(base_path)
-                .await
-            {
-                Ok(wrapper) => {
-                    let ap = format!("{}/content/{}", wrapper.prefix_path(), filename);
-                    if let Err(e) = wrapper.save_artifact(&filename, &base64_str).await {
-                        tracing::warn!("Failed to save artifact: {}", e);
-                    }
-                    Some(ap)
-                }
-                Err(e) => {
-                    tracing::warn!("Failed to create artifact wrapper: {}", e);
-                    None
-                }
-            }
-        } else {
-            None
-        };
-
+        let metadata = persist_artifact(&context, &filename, &base64_str, raw_size, caption).await;
         tracing::info!(
             filename = %filename,
             size = raw_size,
-            mime = %mime_type,
-            artifact_path = ?artifact_path,
+            mime = ?metadata.content_type,
+            artifact_path = %metadata.relative_path,
             mode = if path.is_some() { "path" } else { "content" },
             "save_artifact: artifact saved"
         );
 
-        let metadata = FileMetadata {
-            file_id: filename.clone(),
-            relative_path: artifact_path.unwrap_or_default(),
-            size: raw_size,
-            content_type: Some(mime_type.to_string()),
-            original_filename: Some(filename),
-            created_at: chrono::Utc::now(),
-            updated_at: chrono::Utc::now(),
-            checksum: None,
-            stats: None,
-            preview: caption.map(|c| c.to_string()),
-        };
-
         Ok(vec![Part::Artifact(metadata)])
     }
 }
 
+/// Persist base64 `content` in the task's artifact namespace and describe
+/// it as a [`FileMetadata`]. A storage failure is logged and leaves
+/// `relative_path` empty.
+pub(crate) async fn persist_artifact(
+    context: &ExecutorContext,
+    filename: &str,
+    base64_content: &str,
+    size: u64,
+    caption: Option<&str>,
+) -> FileMetadata {
+    let mime_type = mime_from_filename(filename);
+
+    let artifact_path = if let Ok(orchestrator) = context.get_orchestrator() {
+        let base_path = distri_filesystem::ArtifactWrapper::task_namespace(
+            &context.thread_id,
+            &context.task_id,
+        );
+        match orchestrator
+            .session_filesystem
+            .create_artifact_wrapper(base_path)
+            .await
+        {
+            Ok(wrapper) => {
+                let ap = format!("{}/content/{}", wrapper.prefix_path(), filename);
+                if let Err(e) = wrapper.save_artifact(filename, base64_content).await {
+                    tracing::warn!("Failed to save artifact: {}", e);
+                }
+                Some(ap)
+            }
+            Err(e) => {
+                tracing::warn!("Failed to create artifact wrapper: {}", e);
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    FileMetadata {
+        file_id: filename.to_string(),
+        relative_path: artifact_path.unwrap_or_default(),
+        size,
+        content_type: Some(mime_type.to_string()),
+        original_filename: Some(filename.to_string()),
+        created_at: chrono::Utc::now(),
+        updated_at: chrono::Utc::now(),
+        checksum: None,
+        stats: None,
+        preview: caption.map(|c| c.to_string()),
+    }
+}
+
 /// Infer MIME type from filename extension.
 fn mime_from_filename(filename: &str) -> &'static str {
     match std::path::Path::new(filename)
@@ -0,0 +1,119 @@
+//! `semantic_search_artifacts`: retrieval over the artifacts of the current
+//! thread (the thread's own and every task's) through the embedding index
+//! of `distri_filesystem::semantic`. Only available when the orchestrator
+//! indexes artifacts (`embeddings.index_artifacts`).
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use distri_filesystem::ArtifactWrapper;
+use distri_types::filesystem::FileSystemOps;
+use distri_types::{Part, Tool, ToolCall, ToolContext};
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::agent::ExecutorContext;
+use crate::tools::ExecutorContextTool;
+use crate::AgentError;
+
+const DEFAULT_LIMIT: usize = 5;
+const MAX_LIMIT: usize = 20;
+
+#[derive(Debug, Deserialize)]
+struct SemanticSearchInput {
+    query: String,
+    #[serde(default)]
+    limit: Option<usize>,
+}
+
+#[derive(Debug)]
+pub struct SemanticSearchArtifactsTool;
+
+#[async_trait]
+impl Tool for SemanticSearchArtifactsTool {
+    fn get_name(&self) -> String {
+        "semantic_search_artifacts".to_string()
+    }
+
+    fn get_description(&self) -> String {
+        "Search the artifacts of this conversation by meaning rather than exact words. \
+         Returns the best matching passages, best first, each with its artifact filename, \
+         byte offsets and line range (usable with `read_artifact`)."
+            .to_string()
+    }
+
+    fn get_parameters(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "query": {
+                    "type": "string",
+                    "description": "What to look for, in natural language."
+                },
+                "limit": {
+                    "type": "integer",
+                    "description": "Most passages to return (default 5, at most 20).",
+                    "minimum": 1
+                }
+            },
+            "required": ["query"]
+        })
+    }
+
+    fn needs_executor_context(&self) -> bool {
+        true
+    }
+
+    async fn execute(
+        &self,
+        _tool_call: ToolCall,
+        _context: Arc<ToolContext>,
+    ) -> Result<Vec<Part>, anyhow::Error> {
+        anyhow::bail!("SemanticSearchArtifactsTool requires ExecutorContext")
+    }
+}
+
+#[async_trait]
+impl ExecutorContextTool for SemanticSearchArtifactsTool {
+    async fn execute_with_executor_context(
+        &self,
+        tool_call: ToolCall,
+        context: Arc<ExecutorContext>,
+    ) -> Result<Vec<Part>, AgentError> {
+        let input: SemanticSearchInput =
+            serde_json::from_value(tool_call.input.clone()).map_err(|e| {
+                AgentError::ToolExecution(format!("invalid semantic_search_artifacts input: {e}"))
+            })?;
+        let filesystem = context.get_orchestrator()?.session_filesystem.clone();
+        let index = filesystem.artifact_index().ok_or_else(|| {
+            AgentError::ToolExecution(
+                "artifact search is not enabled on this server (embeddings.index_artifacts)"
+                    .to_string(),
+            )
+        })?;
+
+        let thread_namespace = ArtifactWrapper::thread_namespace(&context.thread_id);
+        let mut namespaces = vec![thread_namespace.clone()];
+        let tasks_dir = format!("{}/tasks", thread_namespace);
+        if let Ok(listing) = filesystem.list(&tasks_dir).await {
+            namespaces.extend(
+                listing
+                    .entries
+                    .iter()
+                    .filter(|e| e.is_dir)
+                    .map(|e| format!("{}/{}", tasks_dir, e.name)),
+            );
+        }
+
+        let limit = input.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+        let matches = index
+            .search(filesystem.as_ref(), &namespaces, &input.query, limit)
+            .await
+            .map_err(|e| {
+                AgentError::ToolExecution(format!("semantic_search_artifacts failed: {e:#}"))
+            })?;
+        Ok(vec![Part::Data(
+            json!({ "query": input.query, "results": matches }),
+        )])
+    }
+}
@@ -3,6 +3,7 @@
 //! Wraps tool execution: whitelisted tools execute normally,
 //! all others get a simulated response from a cheap LLM (gpt-5-nano).
 
+use crate::tools::Tool;
 use crate::types::{Part, ToolCall};
 use async_openai::types::chat::{
     ChatCompletionRequestSystemMessageArgs, ChatCompletionRequestUserMessageArgs,
@@ -15,6 +16,7 @@ use serde_json::json;
 const SAFE_TOOLS: &[&str] = &[
     "tool_search",
     "load_skill",
+    "load_artifact", // reads an artifact of the thread
     "get_task",      // read-only supervisor query
     "list_my_tasks", // read-only
     "search",        // web search (read-only)
@@ -28,6 +30,16 @@ pub fn is_safe_tool(tool_name: &str) -> bool {
     SAFE_TOOLS.contains(&tool_name)
 }
 
+/// Whether a dry run intercepts calls of `tool`: external tools always, the
+/// rest as they declare with [`Tool::side_effect`], or, without a
+/// declaration, unless they are on the safe list.
+pub fn simulates_in_dry_run(tool: &dyn Tool) -> bool {
+    tool.is_external()
+        || tool
+            .side_effect()
+            .unwrap_or_else(|| !is_safe_tool(&tool.get_name()))
+}
+
 /// Tools that should always be simulated (have side effects).
 #[allow(dead_code)]
 const ALWAYS_SIMULATE: &[&str] = &[
@@ -0,0 +1,776 @@
+//! `sql_query` / `sql_schema` — query the databases declared as
+//! [`SqlConnectionConfig`]s.
+//!
+//! Pools are opened lazily on first use and cached on [`SqlConnections`],
+//! which hangs off the orchestrator. Read-only connections are screened
+//! with [`check_read_only_statement`], but the statement keyword says
+//! nothing of the functions a `SELECT` calls, so the connection enforces it
+//! too: on Postgres/MySQL every query runs in a `READ ONLY` transaction that
+//! is rolled back, and SQLite databases are opened `SQLITE_OPEN_READ_ONLY`
+//! with `PRAGMA query_only`. A query running past the connection's timeout
+//! is interrupted on SQLite and dropped with its transaction elsewhere.
+//! Results come back as a `Part::Data` holding a [`SqlQueryResult`], cut
+//! short at the connection's `max_rows` / `max_bytes`.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use distri_types::sql::{
+    check_read_only_statement, SqlColumnSchema, SqlConnectionConfig, SqlDriver, SqlQueryResult,
+    SqlTableSchema,
+};
+use distri_types::{Part, Tool, ToolCall, ToolContext};
+use futures::StreamExt;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use sqlx::mysql::{MySqlPool, MySqlPoolOptions, MySqlRow};
+use sqlx::postgres::{PgPool, PgPoolOptions, PgRow};
+use sqlx::{Column, Executor, Row, TypeInfo};
+use tokio::sync::Mutex;
+
+use crate::agent::ExecutorContext;
+use crate::tools::ExecutorContextTool;
+use crate::AgentError;
+
+/// Connections configured on the orchestrator, with their lazily opened
+/// pools.
+#[derive(Default)]
+pub struct SqlConnections {
+    configs: Vec<SqlConnectionConfig>,
+    pools: Mutex<HashMap<String, Arc<SqlPool>>>,
+}
+
+impl std::fmt::Debug for SqlConnections {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SqlConnections")
+            .field(
+                "connections",
+                &self.configs.iter().map(|c| &c.name).collect::<Vec<_>>(),
+            )
+            .finish()
+    }
+}
+
+impl SqlConnections {
+    pub fn new(configs: Vec<SqlConnectionConfig>) -> Self {
+        Self {
+            configs,
+            pools: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn configs(&self) -> &[SqlConnectionConfig] {
+        &self.configs
+    }
+
+    /// Look up a connection by name. `None` picks the only configured
+    /// connection, if there is exactly one.
+    pub fn config(&self, name: Option<&str>) -> anyhow::Result<&SqlConnectionConfig> {
+        match name {
+            Some(name) => self.configs.iter().find(|c| c.name == name).ok_or_else(|| {
+                anyhow::anyhow!(
+                    "unknown SQL connection '{}' (available: {})",
+                    name,
+                    self.names()
+                )
+            }),
+            None => match self.configs.as_slice() {
+                [only] => Ok(only),
+                [] => anyhow::bail!("no SQL connections are configured"),
+                _ => anyhow::bail!(
+                    "several SQL connections are configured; pass `connection` (one of: {})",
+                    self.names()
+                ),
+            },
+        }
+    }
+
+    fn names(&self) -> String {
+        self.configs
+            .iter()
+            .map(|c| c.name.as_str())
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    async fn pool(&self, config: &SqlConnectionConfig) -> anyhow::Result<Arc<SqlPool>> {
+        let mut pools = self.pools.lock().await;
+        if let Some(pool) = pools.get(&config.name) {
+            return Ok(pool.clone());
+        }
+        let pool = Arc::new(SqlPool::connect(config).await?);
+        pools.insert(config.name.clone(), pool.clone());
+        Ok(pool)
+    }
+
+    /// Run `sql` on `connection`, enforcing its read-only mode and limits.
+    pub async fn query(
+        &self,
+        connection: Option<&str>,
+        sql: &str,
+    ) -> anyhow::Result<SqlQueryResult> {
+        let config = self.config(connection)?;
+        let reads = check_read_only_statement(sql);
+        if config.read_only {
+            reads.clone().map_err(|e| anyhow::anyhow!(e))?;
+        }
+        let pool = self.pool(config).await?;
+        let timeout = Duration::from_secs(config.timeout_secs);
+        let run = async {
+            if reads.is_ok() {
+                pool.fetch(sql, config.read_only, config.max_rows, config.max_bytes)
+                    .await
+            } else {
+                let affected = pool.execute(sql).await?;
+                Ok(SqlQueryResult {
+                    rows_affected: Some(affected),
+                    ..Default::default()
+                })
+            }
+        };
+        let Ok(result) = tokio::time::timeout(timeout, run).await else {
+            pool.interrupt();
+            anyhow::bail!("query timed out after {}s", config.timeout_secs);
+        };
+        let mut result = result?;
+        result.connection = config.name.clone();
+        Ok(result)
+    }
+
+    /// Tables (and views) of `connection` with their columns. `table`
+    /// narrows the result to names containing it, case-insensitively.
+    pub async fn schema(
+        &self,
+        connection: Option<&str>,
+        table: Option<&str>,
+    ) -> anyhow::Result<Vec<SqlTableSchema>> {
+        let config = self.config(connection)?;
+        let pool = self.pool(config).await?;
+        let sql = match config.driver {
+            SqlDriver::Sqlite => {
+                "SELECT m.name, p.name, p.type FROM sqlite_master m \
+                 JOIN pragma_table_info(m.name) p \
+                 WHERE m.type IN ('table', 'view') AND m.name NOT LIKE 'sqlite_%' \
+                 ORDER BY m.name, p.cid"
+            }
+            SqlDriver::Postgres => {
+                "SELECT table_schema || '.' || table_name, column_name::text, data_type::text \
+                 FROM information_schema.columns \
+                 WHERE table_schema NOT IN ('pg_catalog', 'information_schema') \
+                 ORDER BY table_schema, table_name, ordinal_position"
+            }
+            SqlDriver::Mysql => {
+                "SELECT CAST(table_name AS CHAR), CAST(column_name AS CHAR), CAST(data_type AS CHAR) \
+                 FROM information_schema.columns WHERE table_schema = DATABASE() \
+                 ORDER BY table_name, ordinal_position"
+            }
+        };
+        let timeout = Duration::from_secs(config.timeout_secs);
+        let fetch = pool.fetch(sql, config.read_only, usize::MAX, usize::MAX);
+        let Ok(result) = tokio::time::timeout(timeout, fetch).await else {
+            pool.interrupt();
+            anyhow::bail!("schema query timed out after {}s", config.timeout_secs);
+        };
+        let rows = result?.rows;
+
+        let filter = table.map(str::to_lowercase);
+        let mut tables: Vec<SqlTableSchema> = Vec::new();
+        for row in rows {
+            let text = |i: usize| row.get(i).and_then(Value::as_str).unwrap_or_default();
+            let (name, column, data_type) = (text(0), text(1), text(2));
+            if let Some(filter) = &filter {
+                if !name.to_lowercase().contains(filter) {
+                    continue;
+                }
+            }
+            if tables.last().map(|t| t.name.as_str()) != Some(name) {
+                tables.push(SqlTableSchema {
+                    name: name.to_string(),
+                    columns: Vec::new(),
+                });
+            }
+            if let Some(last) = tables.last_mut() {
+                last.columns.push(SqlColumnSchema {
+                    name: column.to_string(),
+                    data_type: data_type.to_string(),
+                });
+            }
+        }
+        Ok(tables)
+    }
+}
+
+enum SqlPool {
+    Sqlite {
+        conn: Arc<std::sync::Mutex<rusqlite::Connection>>,
+        interrupt: rusqlite::InterruptHandle,
+    },
+    Postgres(PgPool),
+    Mysql(MySqlPool),
+}
+
+impl SqlPool {
+    async fn connect(config: &SqlConnectionConfig) -> anyhow::Result<Self> {
+        let url = config.resolved_url()?;
+        let read_only = config.read_only;
+        let pool = match config.driver {
+            SqlDriver::Sqlite => {
+                use rusqlite::OpenFlags;
+                let path = url
+                    .strip_prefix("sqlite://")
+                    .or_else(|| url.strip_prefix("sqlite:"))
+                    .unwrap_or(&url)
+                    .to_string();
+                let flags = if read_only {
+                    OpenFlags::SQLITE_OPEN_READ_ONLY
+                } else {
+                    OpenFlags::SQLITE_OPEN_READ_WRITE | OpenFlags::SQLITE_OPEN_CREATE
+                } | OpenFlags::SQLITE_OPEN_URI
+                    | OpenFlags::SQLITE_OPEN_NO_MUTEX;
+                let conn = tokio::task::spawn_blocking(move || {
+                    let conn = rusqlite::Connection::open_with_flags(path, flags)?;
+                    if read_only {
+                        // Also covers databases ATTACHed to this one.
+                        conn.pragma_update(None, "query_only", true)?;
+                    }
+                    anyhow::Ok(conn)
+                })
+                .await??;
+                SqlPool::Sqlite {
+                    interrupt: conn.get_interrupt_handle(),
+                    conn: Arc::new(std::sync::Mutex::new(conn)),
+                }
+            }
+            SqlDriver::Postgres => SqlPool::Postgres(
+                PgPoolOptions::new()
+                    .max_connections(4)
+                    .after_connect(move |conn, _| {
+                        Box::pin(async move {
+                            if read_only {
+                                conn.execute(
+                                    "SET SESSION CHARACTERISTICS AS TRANSACTION READ ONLY",
+                                )
+                                .await?;
+                            }
+                            Ok(())
+                        })
+                    })
+                    .connect(&url)
+                    .await?,
+            ),
+            SqlDriver::Mysql => SqlPool::Mysql(
+                MySqlPoolOptions::new()
+                    .max_connections(4)
+                    .after_connect(move |conn, _| {
+                        Box::pin(async move {
+                            if read_only {
+                                conn.execute("SET SESSION TRANSACTION READ ONLY").await?;
+                            }
+                            Ok(())
+                        })
+                    })
+                    .connect(&url)
+                    .await?,
+            ),
+        };
+        tracing::info!(
+            "opened SQL connection '{}' ({})",
+            config.name,
+            config.driver
+        );
+        Ok(pool)
+    }
+
+    /// Stop the statement running on a SQLite connection, so a timed-out
+    /// query releases it. Queries on a pool stop when their future is
+    /// dropped.
+    fn interrupt(&self) {
+        if let SqlPool::Sqlite { interrupt, .. } = self {
+            interrupt.interrupt();
+        }
+    }
+
+    /// Rows of `sql`; with `read_only` it runs in a read-only transaction
+    /// that is rolled back, whatever the functions it calls try to change.
+    async fn fetch(
+        &self,
+        sql: &str,
+        read_only: bool,
+        max_rows: usize,
+        max_bytes: usize,
+    ) -> anyhow::Result<SqlQueryResult> {
+        let mut rows = RowCollector::new(max_rows, max_bytes);
+        match self {
+            SqlPool::Sqlite { conn, .. } => {
+                let conn = conn.clone();
+                let sql = sql.to_string();
+                return tokio::task::spawn_blocking(move || {
+                    let conn = conn
+                        .lock()
+                        .map_err(|_| anyhow::anyhow!("connection poisoned"))?;
+                    let mut stmt = conn.prepare(&sql)?;
+                    rows.columns = stmt.column_names().into_iter().map(String::from).collect();
+                    let width = stmt.column_count();
+                    let mut cursor = stmt.query([])?;
+                    while let Some(row) = cursor.next()? {
+                        let values = (0..width)
+                            .map(|i| row.get_ref(i).map(sqlite_cell))
+                            .collect::<Result<Vec<_>, _>>()?;
+                        if !rows.push(values) {
+                            break;
+                        }
+                    }
+                    Ok(rows.finish())
+                })
+                .await?;
+            }
+            SqlPool::Postgres(pool) if read_only => {
+                let mut tx = pool.begin_with("BEGIN TRANSACTION READ ONLY").await?;
+                {
+                    let mut stream = sqlx::query(sql).fetch(&mut *tx);
+                    while let Some(row) = stream.next().await {
+                        if !rows.push_row(&row?, pg_cell) {
+                            break;
+                        }
+                    }
+                }
+                tx.rollback().await?;
+            }
+            SqlPool::Postgres(pool) => {
+                let mut stream = sqlx::query(sql).fetch(pool);
+                while let Some(row) = stream.next().await {
+                    if !rows.push_row(&row?, pg_cell) {
+                        break;
+                    }
+                }
+            }
+            SqlPool::Mysql(pool) if read_only => {
+                let mut tx = pool.begin_with("START TRANSACTION READ ONLY").await?;
+                {
+                    let mut stream = sqlx::query(sql).fetch(&mut *tx);
+                    while let Some(row) = stream.next().await {
+                        if !rows.push_row(&row?, mysql_cell) {
+                            break;
+                        }
+                    }
+                }
+                tx.rollback().await?;
+            }
+            SqlPool::Mysql(pool) => {
+                let mut stream = sqlx::query(sql).fetch(pool);
+                while let Some(row) = stream.next().await {
+                    if !rows.push_row(&row?, mysql_cell) {
+                        break;
+                    }
+                }
+            }
+        }
+        Ok(rows.finish())
+    }
+
+    async fn execute(&self, sql: &str) -> anyhow::Result<u64> {
+        Ok(match self {
+            SqlPool::Sqlite { conn, .. } => {
+                let conn = conn.clone();
+                let sql = sql.to_string();
+                tokio::task::spawn_blocking(move || -> anyhow::Result<u64> {
+                    let conn = conn
+                        .lock()
+                        .map_err(|_| anyhow::anyhow!("connection poisoned"))?;
+                    Ok(conn.execute(&sql, [])? as u64)
+                })
+                .await??
+            }
+            SqlPool::Postgres(pool) => sqlx::query(sql).execute(pool).await?.rows_affected(),
+            SqlPool::Mysql(pool) => sqlx::query(sql).execute(pool).await?.rows_affected(),
+        })
+    }
+}
+
+/// Accumulates rows into a [`SqlQueryResult`] until the row or byte limit.
+struct RowCollector {
+    columns: Vec<String>,
+    rows: Vec<Vec<Value>>,
+    bytes: usize,
+    truncated: bool,
+    max_rows: usize,
+    max_bytes: usize,
+}
+
+impl RowCollector {
+    fn new(max_rows: usize, max_bytes: usize) -> Self {
+        Self {
+            columns: Vec::new(),
+            rows: Vec::new(),
+            bytes: 0,
+            truncated: false,
+            max_rows,
+            max_bytes,
+        }
+    }
+
+    /// Add a row; `false` means a limit was hit and the caller should stop.
+    fn push(&mut self, values: Vec<Value>) -> bool {
+        if self.rows.len() >= self.max_rows {
+            self.truncated = true;
+            return false;
+        }
+        self.bytes += serde_json::to_string(&values).map(|s| s.len()).unwrap_or(0);
+        if self.bytes > self.max_bytes {
+            self.truncated = true;
+            return false;
+        }
+        self.rows.push(values);
+        true
+    }
+
+    fn push_row<R: Row>(&mut self, row: &R, cell: fn(&R, usize) -> Value) -> bool {
+        if self.columns.is_empty() {
+            self.columns = row.columns().iter().map(|c| c.name().to_string()).collect();
+        }
+        self.push((0..row.len()).map(|i| cell(row, i)).collect())
+    }
+
+    fn finish(self) -> SqlQueryResult {
+        SqlQueryResult {
+            row_count: self.rows.len(),
+            columns: self.columns,
+            rows: self.rows,
+            truncated: self.truncated,
+            ..Default::default()
+        }
+    }
+}
+
+fn sqlite_cell(value: rusqlite::types::ValueRef<'_>) -> Value {
+    use rusqlite::types::ValueRef;
+    match value {
+        ValueRef::Null => Value::Null,
+        ValueRef::Integer(i) => json!(i),
+        ValueRef::Real(f) => json!(f),
+        ValueRef::Text(t) => Value::String(String::from_utf8_lossy(t).into_owned()),
+        ValueRef::Blob(b) => json!(b),
+    }
+}
+
+/// Decode column `$i` of `$row` as the first of `$ty` that fits; columns of
+/// any other type come back as `"<TYPE_NAME>"`.
+macro_rules! decode_cell {
+    ($row:expr, $i:expr, $($ty:ty),+ $(,)?) => {{
+        $(
+            if let Ok(value) = $row.try_get::<Option<$ty>, _>($i) {
+                return value.map(|v| json!(v)).unwrap_or(Value::Null);
+            }
+        )+
+        Value::String(format!("<{}>", $row.column($i).type_info().name()))
+    }};
+}
+
+fn pg_cell(row: &PgRow, i: usize) -> Value {
+    decode_cell!(
+        row,
+        i,
+        i64,
+        i32,
+        i16,
+        f64,
+        f32,
+        bool,
+        String,
+        chrono::DateTime<chrono::Utc>,
+        chrono::NaiveDateTime,
+        chrono::NaiveDate,
+        chrono::NaiveTime,
+        Value,
+    )
+}
+
+fn mysql_cell(row: &MySqlRow, i: usize) -> Value {
+    decode_cell!(
+        row,
+        i,
+        i64,
+        u64,
+        f64,
+        f32,
+        bool,
+        String,
+        chrono::DateTime<chrono::Utc>,
+        chrono::NaiveDateTime,
+        chrono::NaiveDate,
+        chrono::NaiveTime,
+        Value,
+    )
+}
+
+fn sql_connections(context: &ExecutorContext) -> Result<Arc<SqlConnections>, AgentError> {
+    Ok(context.get_orchestrator()?.sql_connections.clone())
+}
+
+// ── sql_query ───────────────────────────────────────────────────────────
+
+#[derive(Debug, Deserialize)]
+struct SqlQueryInput {
+    #[serde(default)]
+    connection: Option<String>,
+    sql: String,
+}
+
+/// Run one SQL statement against a configured connection.
+#[derive(Debug)]
+pub struct SqlQueryTool;
+
+#[async_trait]
+impl Tool for SqlQueryTool {
+    fn get_name(&self) -> String {
+        "sql_query".to_string()
+    }
+
+    fn get_description(&self) -> String {
+        "Run a single SQL statement against a configured database connection and return the \
+         rows as a table. Connections are read-only unless configured otherwise, and results \
+         are capped in rows and size (`truncated: true` means more rows exist — aggregate or \
+         add a LIMIT). Call `sql_schema` first to discover tables and columns."
+            .to_string()
+    }
+
+    fn get_parameters(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "connection": {
+                    "type": "string",
+                    "description": "Connection name. Optional when only one connection is configured."
+                },
+                "sql": {
+                    "type": "string",
+                    "description": "A single SQL statement in the connection's dialect."
+                }
+            },
+            "required": ["sql"]
+        })
+    }
+
+    fn needs_executor_context(&self) -> bool {
+        true
+    }
+
+    async fn execute(
+        &self,
+        _tool_call: ToolCall,
+        _context: Arc<ToolContext>,
+    ) -> Result<Vec<Part>, anyhow::Error> {
+        anyhow::bail!("SqlQueryTool requires ExecutorContext")
+    }
+}
+
+#[async_trait]
+impl ExecutorContextTool for SqlQueryTool {
+    async fn execute_with_executor_context(
+        &self,
+        tool_call: ToolCall,
+        context: Arc<ExecutorContext>,
+    ) -> Result<Vec<Part>, AgentError> {
+        let input: SqlQueryInput = serde_json::from_value(tool_call.input.clone())
+            .map_err(|e| AgentError::ToolExecution(format!("invalid sql_query input: {e}")))?;
+        let result = sql_connections(&context)?
+            .query(input.connection.as_deref(), &input.sql)
+            .await
+            .map_err(|e| AgentError::ToolExecution(format!("sql_query failed: {e}")))?;
+        Ok(vec![Part::Data(serde_json::to_value(result).map_err(
+            |e| AgentError::ToolExecution(format!("serialize sql_query: {e}")),
+        )?)])
+    }
+}
+
+// ── sql_schema ──────────────────────────────────────────────────────────
+
+#[derive(Debug, Deserialize)]
+struct SqlSchemaInput {
+    #[serde(default)]
+    connection: Option<String>,
+    #[serde(default)]
+    table: Option<String>,
+}
+
+/// List configured connections, or the tables and columns of one.
+#[derive(Debug)]
+pub struct SqlSchemaTool;
+
+#[async_trait]
+impl Tool for SqlSchemaTool {
+    fn get_name(&self) -> String {
+        "sql_schema".to_string()
+    }
+
+    fn get_description(&self) -> String {
+        "Discover what can be queried with `sql_query`. Without arguments (and several \
+         connections configured) lists the connections; with `connection` lists its tables \
+         and their columns, optionally narrowed by `table`."
+            .to_string()
+    }
+
+    fn get_parameters(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "connection": {
+                    "type": "string",
+                    "description": "Connection to introspect."
+                },
+                "table": {
+                    "type": "string",
+                    "description": "Only tables whose name contains this text."
+                }
+            },
+            "required": []
+        })
+    }
+
+    fn memoizable(&self) -> bool {
+        true
+    }
+
+    fn needs_executor_context(&self) -> bool {
+        true
+    }
+
+    async fn execute(
+        &self,
+        _tool_call: ToolCall,
+        _context: Arc<ToolContext>,
+    ) -> Result<Vec<Part>, anyhow::Error> {
+        anyhow::bail!("SqlSchemaTool requires ExecutorContext")
+    }
+}
+
+#[async_trait]
+impl ExecutorContextTool for SqlSchemaTool {
+    async fn execute_with_executor_context(
+        &self,
+        tool_call: ToolCall,
+        context: Arc<ExecutorContext>,
+    ) -> Result<Vec<Part>, AgentError> {
+        let input: SqlSchemaInput = serde_json::from_value(tool_call.input.clone())
+            .map_err(|e| AgentError::ToolExecution(format!("invalid sql_schema input: {e}")))?;
+        let connections = sql_connections(&context)?;
+
+        if input.connection.is_none() && connections.configs().len() > 1 {
+            let listed: Vec<Value> = connections
+                .configs()
+                .iter()
+                .map(|c| {
+                    json!({
+                        "name": c.name,
+                        "driver": c.driver,
+                        "description": c.description,
+                        "read_only": c.read_only,
+                    })
+                })
+                .collect();
+            return Ok(vec![Part::Data(json!({ "connections": listed }))]);
+        }
+
+        let tables = connections
+            .schema(input.connection.as_deref(), input.table.as_deref())
+            .await
+            .map_err(|e| AgentError::ToolExecution(format!("sql_schema failed: {e}")))?;
+        let name = connections
+            .config(input.connection.as_deref())
+            .map(|c| c.name.clone())
+            .unwrap_or_default();
+        Ok(vec![Part::Data(
+            json!({ "connection": name, "tables": tables }),
+        )])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sqlite(name: &str, path: &std::path::Path, read_only: bool) -> SqlConnectionConfig {
+        SqlConnectionConfig {
+            name: name.to_string(),
+            driver: SqlDriver::Sqlite,
+            url: format!("sqlite://{}", path.display()),
+            description: None,
+            read_only,
+            max_rows: 2,
+            max_bytes: 1024,
+            timeout_secs: 5,
+        }
+    }
+
+    #[tokio::test]
+    async fn sqlite_query_schema_and_read_only() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("shop.db");
+        std::fs::File::create(&path).unwrap();
+
+        let admin = SqlConnections::new(vec![sqlite("admin", &path, false)]);
+        admin
+            .query(
+                None,
+                "CREATE TABLE orders (id INTEGER, customer TEXT, total REAL)",
+            )
+            .await
+            .unwrap();
+        let inserted = admin
+            .query(
+                None,
+                "INSERT INTO orders VALUES (1, 'ada', 9.5), (2, 'bob', NULL), (3, 'cy', 1.0)",
+            )
+            .await
+            .unwrap();
+        assert_eq!(inserted.rows_affected, Some(3));
+
+        let ro = SqlConnections::new(vec![sqlite("shop", &path, true)]);
+        let result = ro
+            .query(
+                Some("shop"),
+                "SELECT id, customer, total FROM orders ORDER BY id",
+            )
+            .await
+            .unwrap();
+        assert_eq!(result.columns, vec!["id", "customer", "total"]);
+        assert_eq!(result.rows[0], vec![json!(1), json!("ada"), json!(9.5)]);
+        assert_eq!(result.rows[1][2], Value::Null);
+        assert_eq!(result.row_count, 2);
+        assert!(result.truncated);
+
+        let err = ro.query(None, "DELETE FROM orders").await.unwrap_err();
+        assert!(err.to_string().contains("read-only"), "{err}");
+
+        let tables = ro.schema(None, Some("ORD")).await.unwrap();
+        assert_eq!(tables.len(), 1);
+        assert_eq!(tables[0].name, "orders");
+        assert_eq!(tables[0].columns[1].name, "customer");
+        assert_eq!(tables[0].columns[1].data_type, "TEXT");
+
+        assert!(ro.query(Some("missing"), "SELECT 1").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn sqlite_queries_past_the_timeout_are_interrupted() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("slow.db");
+        std::fs::File::create(&path).unwrap();
+        let mut config = sqlite("slow", &path, true);
+        config.timeout_secs = 1;
+        let connections = SqlConnections::new(vec![config]);
+
+        let endless = "WITH RECURSIVE n(i) AS (SELECT 1 UNION ALL SELECT i + 1 FROM n) \
+                       SELECT count(*) FROM n";
+        let err = connections.query(None, endless).await.unwrap_err();
+        assert!(err.to_string().contains("timed out"), "{err}");
+
+        // The interrupted query released the connection.
+        let started = std::time::Instant::now();
+        let result = connections.query(None, "SELECT 1").await.unwrap();
+        assert_eq!(result.rows[0], vec![json!(1)]);
+        assert!(started.elapsed() < Duration::from_millis(500));
+    }
+}
@@ -0,0 +1,91 @@
+//! `summarize_thread`: the handover summary of the current thread, so an
+//! agent can hand the conversation to a human with a recap.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use distri_types::handover_summary::{HandoverSummary, SUMMARIZE_THREAD_TOOL_NAME};
+use distri_types::{Part, Tool, ToolCall, ToolContext};
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::agent::ExecutorContext;
+use crate::tools::ExecutorContextTool;
+use crate::AgentError;
+
+#[derive(Debug, Default, Deserialize)]
+struct SummarizeThreadInput {
+    #[serde(default)]
+    refresh: bool,
+}
+
+#[derive(Debug)]
+pub struct SummarizeThreadTool;
+
+#[async_trait]
+impl Tool for SummarizeThreadTool {
+    fn get_name(&self) -> String {
+        SUMMARIZE_THREAD_TOOL_NAME.to_string()
+    }
+
+    fn get_description(&self) -> String {
+        "Summarize this conversation for a human taking over: the customer's intent, the \
+         steps taken, the tools used and what is currently blocking. Use it before handing \
+         the conversation to a person."
+            .to_string()
+    }
+
+    fn get_parameters(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "refresh": {
+                    "type": "boolean",
+                    "description": "Write a new summary even if one exists for the conversation as it is."
+                }
+            }
+        })
+    }
+
+    fn get_output_schema(&self) -> Option<Value> {
+        serde_json::to_value(schemars::schema_for!(HandoverSummary)).ok()
+    }
+
+    fn needs_executor_context(&self) -> bool {
+        true
+    }
+
+    async fn execute(
+        &self,
+        _tool_call: ToolCall,
+        _context: Arc<ToolContext>,
+    ) -> Result<Vec<Part>, anyhow::Error> {
+        anyhow::bail!("SummarizeThreadTool requires ExecutorContext")
+    }
+}
+
+#[async_trait]
+impl ExecutorContextTool for SummarizeThreadTool {
+    async fn execute_with_executor_context(
+        &self,
+        tool_call: ToolCall,
+        context: Arc<ExecutorContext>,
+    ) -> Result<Vec<Part>, AgentError> {
+        let input: SummarizeThreadInput = if tool_call.input.is_null() {
+            SummarizeThreadInput::default()
+        } else {
+            serde_json::from_value(tool_call.input.clone()).map_err(|e| {
+                AgentError::ToolExecution(format!("invalid summarize_thread input: {e}"))
+            })?
+        };
+        // The thread is read from the stores of the run calling the tool.
+        let orchestrator = context.get_orchestrator()?;
+        let stores = context.stores.as_ref().unwrap_or(&orchestrator.stores);
+        let summary = orchestrator
+            .handover_summary_in(stores, &context.thread_id, input.refresh)
+            .await?;
+        let value =
+            serde_json::to_value(&summary).map_err(|e| AgentError::ToolExecution(e.to_string()))?;
+        Ok(vec![Part::Data(value)])
+    }
+}
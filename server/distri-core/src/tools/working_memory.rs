@@ -0,0 +1,334 @@
+//! `memory_set` / `memory_get` / `memory_list`: a key/value working memory
+//! for the current thread, kept in the session store under its own
+//! namespace so the agent cannot touch the thread's internal session keys.
+//!
+//! Values are any JSON and may expire after `ttl_secs`. Each thread holds at
+//! most [`MAX_KEYS`] keys and [`MAX_TOTAL_BYTES`] of values, and no value is
+//! larger than [`MAX_VALUE_BYTES`]; a write over a quota fails with an error
+//! the model can act on.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use distri_types::{Part, Tool, ToolCall, ToolContext};
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::agent::ExecutorContext;
+use crate::tools::ExecutorContextTool;
+use crate::AgentError;
+
+/// Most keys a thread's working memory holds.
+pub const MAX_KEYS: usize = 100;
+/// Largest serialized value.
+pub const MAX_VALUE_BYTES: usize = 16 * 1024;
+/// Largest total of a thread's serialized values.
+pub const MAX_TOTAL_BYTES: usize = 256 * 1024;
+const MAX_KEY_LEN: usize = 128;
+
+/// Session store namespace of a thread's working memory.
+pub fn memory_namespace(thread_id: &str) -> String {
+    format!("memory:{}", thread_id)
+}
+
+fn validate_key(key: &str) -> Result<(), AgentError> {
+    let valid = !key.is_empty()
+        && key.len() <= MAX_KEY_LEN
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.' | ':' | '/'));
+    if valid {
+        Ok(())
+    } else {
+        Err(AgentError::ToolExecution(format!(
+            "invalid memory key '{}': use 1-{} letters, digits or _ - . : /",
+            key, MAX_KEY_LEN
+        )))
+    }
+}
+
+fn value_bytes(value: &Value) -> usize {
+    serde_json::to_string(value).map(|s| s.len()).unwrap_or(0)
+}
+
+fn store_error(e: anyhow::Error) -> AgentError {
+    AgentError::ToolExecution(format!("working memory is unavailable: {}", e))
+}
+
+#[derive(Debug, Deserialize)]
+struct MemorySetInput {
+    key: String,
+    value: Value,
+    #[serde(default)]
+    ttl_secs: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MemoryGetInput {
+    key: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct MemoryListInput {
+    #[serde(default)]
+    prefix: Option<String>,
+}
+
+#[derive(Debug)]
+pub struct MemorySetTool;
+
+#[async_trait]
+impl Tool for MemorySetTool {
+    fn get_name(&self) -> String {
+        "memory_set".to_string()
+    }
+
+    fn get_description(&self) -> String {
+        format!(
+            "Remember a value for later turns of this conversation under `key`, replacing any \
+             value already there. Set `value` to null to forget the key. Values may be any \
+             JSON up to {} KiB; the conversation keeps at most {} keys.",
+            MAX_VALUE_BYTES / 1024,
+            MAX_KEYS
+        )
+    }
+
+    fn get_parameters(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "key": {
+                    "type": "string",
+                    "description": "Name of the value: letters, digits and _ - . : /"
+                },
+                "value": {
+                    "description": "The value to remember, or null to forget the key."
+                },
+                "ttl_secs": {
+                    "type": "integer",
+                    "description": "Forget the value after this many seconds.",
+                    "minimum": 1
+                }
+            },
+            "required": ["key", "value"]
+        })
+    }
+
+    fn needs_executor_context(&self) -> bool {
+        true
+    }
+
+    async fn execute(
+        &self,
+        _tool_call: ToolCall,
+        _context: Arc<ToolContext>,
+    ) -> Result<Vec<Part>, anyhow::Error> {
+        anyhow::bail!("MemorySetTool requires ExecutorContext")
+    }
+}
+
+#[async_trait]
+impl ExecutorContextTool for MemorySetTool {
+    async fn execute_with_executor_context(
+        &self,
+        tool_call: ToolCall,
+        context: Arc<ExecutorContext>,
+    ) -> Result<Vec<Part>, AgentError> {
+        let input: MemorySetInput = serde_json::from_value(tool_call.input.clone())
+            .map_err(|e| AgentError::ToolExecution(format!("invalid memory_set input: {e}")))?;
+        validate_key(&input.key)?;
+        let namespace = memory_namespace(&context.thread_id);
+        let store = context.get_session_store()?;
+
+        if input.value.is_null() {
+            store
+                .delete_value(&namespace, &input.key)
+                .await
+                .map_err(store_error)?;
+            return Ok(vec![Part::Data(
+                json!({ "key": input.key, "deleted": true }),
+            )]);
+        }
+
+        let bytes = value_bytes(&input.value);
+        if bytes > MAX_VALUE_BYTES {
+            return Err(AgentError::ToolExecution(format!(
+                "the value of '{}' is {} bytes; values are at most {} bytes",
+                input.key, bytes, MAX_VALUE_BYTES
+            )));
+        }
+        let existing = store
+            .get_all_values(&namespace)
+            .await
+            .map_err(store_error)?;
+        let others = existing.iter().filter(|(key, _)| **key != input.key);
+        if !existing.contains_key(&input.key) && existing.len() >= MAX_KEYS {
+            return Err(AgentError::ToolExecution(format!(
+                "working memory already holds {} keys; forget one before adding '{}'",
+                MAX_KEYS, input.key
+            )));
+        }
+        let total = others.map(|(_, value)| value_bytes(value)).sum::<usize>() + bytes;
+        if total > MAX_TOTAL_BYTES {
+            return Err(AgentError::ToolExecution(format!(
+                "storing '{}' would bring working memory to {} bytes, over its {} byte quota; \
+                 forget some keys first",
+                input.key, total, MAX_TOTAL_BYTES
+            )));
+        }
+
+        let expires_at = input
+            .ttl_secs
+            .map(|ttl| chrono::Utc::now() + chrono::Duration::seconds(ttl as i64));
+        store
+            .set_value_with_expiry(&namespace, &input.key, &input.value, expires_at)
+            .await
+            .map_err(store_error)?;
+        Ok(vec![Part::Data(json!({
+            "key": input.key,
+            "bytes": bytes,
+            "expires_at": expires_at,
+        }))])
+    }
+}
+
+#[derive(Debug)]
+pub struct MemoryGetTool;
+
+#[async_trait]
+impl Tool for MemoryGetTool {
+    fn get_name(&self) -> String {
+        "memory_get".to_string()
+    }
+
+    fn get_description(&self) -> String {
+        "Read a value remembered with `memory_set` in this conversation. Returns null when \
+         the key is not set or has expired."
+            .to_string()
+    }
+
+    fn get_parameters(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "key": { "type": "string", "description": "Name of the value." }
+            },
+            "required": ["key"]
+        })
+    }
+
+    fn needs_executor_context(&self) -> bool {
+        true
+    }
+
+    async fn execute(
+        &self,
+        _tool_call: ToolCall,
+        _context: Arc<ToolContext>,
+    ) -> Result<Vec<Part>, anyhow::Error> {
+        anyhow::bail!("MemoryGetTool requires ExecutorContext")
+    }
+}
+
+#[async_trait]
+impl ExecutorContextTool for MemoryGetTool {
+    async fn execute_with_executor_context(
+        &self,
+        tool_call: ToolCall,
+        context: Arc<ExecutorContext>,
+    ) -> Result<Vec<Part>, AgentError> {
+        let input: MemoryGetInput = serde_json::from_value(tool_call.input.clone())
+            .map_err(|e| AgentError::ToolExecution(format!("invalid memory_get input: {e}")))?;
+        validate_key(&input.key)?;
+        let value = context
+            .get_session_store()?
+            .get_value(&memory_namespace(&context.thread_id), &input.key)
+            .await
+            .map_err(store_error)?;
+        Ok(vec![Part::Data(
+            json!({ "key": input.key, "value": value }),
+        )])
+    }
+}
+
+#[derive(Debug)]
+pub struct MemoryListTool;
+
+#[async_trait]
+impl Tool for MemoryListTool {
+    fn get_name(&self) -> String {
+        "memory_list".to_string()
+    }
+
+    fn get_description(&self) -> String {
+        "List the keys remembered with `memory_set` in this conversation, with the size of \
+         each value and how much of the quota is used."
+            .to_string()
+    }
+
+    fn get_parameters(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "prefix": {
+                    "type": "string",
+                    "description": "Only list keys starting with this."
+                }
+            }
+        })
+    }
+
+    fn needs_executor_context(&self) -> bool {
+        true
+    }
+
+    async fn execute(
+        &self,
+        _tool_call: ToolCall,
+        _context: Arc<ToolContext>,
+    ) -> Result<Vec<Part>, anyhow::Error> {
+        anyhow::bail!("MemoryListTool requires ExecutorContext")
+    }
+}
+
+#[async_trait]
+impl ExecutorContextTool for MemoryListTool {
+    async fn execute_with_executor_context(
+        &self,
+        tool_call: ToolCall,
+        context: Arc<ExecutorContext>,
+    ) -> Result<Vec<Part>, AgentError> {
+        let input: MemoryListInput = match &tool_call.input {
+            Value::Null => MemoryListInput::default(),
+            input => serde_json::from_value(input.clone()).map_err(|e| {
+                AgentError::ToolExecution(format!("invalid memory_list input: {e}"))
+            })?,
+        };
+        let values = context
+            .get_session_store()?
+            .get_all_values(&memory_namespace(&context.thread_id))
+            .await
+            .map_err(store_error)?;
+
+        let total_bytes: usize = values.values().map(value_bytes).sum();
+        let mut keys: Vec<(String, usize)> = values
+            .iter()
+            .filter(|(key, _)| {
+                input
+                    .prefix
+                    .as_deref()
+                    .is_none_or(|prefix| key.starts_with(prefix))
+            })
+            .map(|(key, value)| (key.clone(), value_bytes(value)))
+            .collect();
+        keys.sort();
+        Ok(vec![Part::Data(json!({
+            "keys": keys
+                .into_iter()
+                .map(|(key, bytes)| json!({ "key": key, "bytes": bytes }))
+                .collect::<Vec<_>>(),
+            "used": { "keys": values.len(), "bytes": total_bytes },
+            "quota": { "keys": MAX_KEYS, "bytes": MAX_TOTAL_BYTES, "value_bytes": MAX_VALUE_BYTES },
+        }))])
+    }
+}
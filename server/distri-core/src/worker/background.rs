@@ -0,0 +1,231 @@
+//! Background job runner — executes queued `message/send` requests.
+//!
+//! `A2AService::enqueue_message` persists a job and wakes a worker. Each
+//! worker claims one job at a time under a lease, runs it through the same
+//! `initialize_task` bootstrap as a live request (under the job's user and
+//! workspace), and renews the lease until the root run ends. A reaper
+//! re-queues jobs whose lease lapsed — the claiming process died — so they
+//! run again under the same task id.
+
+use crate::a2a::service::{A2AService, ServiceRequest};
+use crate::agent::{AgentEventType, AgentOrchestrator};
+use distri_a2a::JsonRpcRequest;
+use distri_auth::context::with_user_and_workspace;
+use distri_types::jobs::{BackgroundJob, BackgroundJobStatus, BackgroundJobsConfig};
+use distri_types::stores::BackgroundJobStore;
+use distri_types::TaskStatus;
+use futures_util::StreamExt;
+use std::sync::Arc;
+use std::time::Duration;
+
+pub struct BackgroundRunner {
+    orchestrator: Arc<AgentOrchestrator>,
+    store: Arc<dyn BackgroundJobStore>,
+    config: BackgroundJobsConfig,
+    /// Distinguishes this process's workers from other replicas sharing
+    /// the queue.
+    instance_id: String,
+}
+
+impl BackgroundRunner {
+    /// `None` when background jobs are disabled or the store has no queue.
+    pub fn new(orchestrator: Arc<AgentOrchestrator>) -> Option<Self> {
+        let config = orchestrator.background_jobs.clone()?;
+        let store = orchestrator.stores.background_job_store.clone()?;
+        Some(Self {
+            orchestrator,
+            store,
+            config,
+            instance_id: uuid::Uuid::new_v4().to_string(),
+        })
+    }
+
+    /// Spawn the configured number of workers plus the orphan reaper.
+    pub fn start(self) -> Vec<tokio::task::JoinHandle<()>> {
+        let runner = Arc::new(self);
+        let mut handles = Vec::new();
+        for n in 0..runner.config.workers.max(1) {
+            let runner = runner.clone();
+            let worker_id = format!("{}/{}", runner.instance_id, n);
+            handles.push(tokio::spawn(
+                async move { runner.worker_loop(worker_id).await },
+            ));
+        }
+        let reaper = runner.clone();
+        handles.push(tokio::spawn(async move { reaper.reaper_loop().await }));
+        tracing::info!(
+            workers = runner.config.workers.max(1),
+            "background job runner started"
+        );
+        handles
+    }
+
+    fn lease(&self) -> Duration {
+        Duration::from_secs(self.config.lease_secs.max(3))
+    }
+
+    fn lease_until(&self) -> chrono::DateTime<chrono::Utc> {
+        chrono::Utc::now() + chrono::Duration::seconds(self.lease().as_secs() as i64)
+    }
+
+    async fn worker_loop(&self, worker_id: String) {
+        let poll = Duration::from_millis(self.config.poll_interval_ms.max(10));
+        loop {
+            match self.store.claim(&worker_id, self.lease_until()).await {
+                Ok(Some(job)) => self.run_job(job, &worker_id).await,
+                Ok(None) => {
+                    tokio::select! {
+                        _ = self.orchestrator.background_jobs_wake.notified() => {}
+                        _ = tokio::time::sleep(poll) => {}
+                    }
+                }
+                Err(e) => {
+                    tracing::warn!("background worker {} failed to claim: {}", worker_id, e);
+                    tokio::time::sleep(poll).await;
+                }
+            }
+        }
+    }
+
+    async fn reaper_loop(&self) {
+        let interval = self.lease() / 2;
+        loop {
+            match self.store.requeue_expired(chrono::Utc::now()).await {
+                Ok(0) => {}
+                Ok(n) => {
+                    tracing::info!("re-queued {} orphaned background job(s)", n);
+                    self.orchestrator.background_jobs_wake.notify_waiters();
+                }
+                Err(e) => tracing::warn!("failed to re-queue orphaned background jobs: {}", e),
+            }
+            tokio::time::sleep(interval).await;
+        }
+    }
+
+    async fn run_job(&self, job: BackgroundJob, worker_id: &str) {
+        tracing::info!(
+            job_id = %job.id,
+            task_id = %job.task_id,
+            attempt = job.attempts,
+            "running background job"
+        );
+        let workspace_id = job
+            .workspace_id
+            .as_deref()
+            .and_then(|ws| uuid::Uuid::parse_str(ws).ok());
+        let outcome = with_user_and_workspace(
+            job.user_id.clone(),
+            workspace_id,
+            self.execute(&job, worker_id),
+        )
+        .await;
+
+        let (status, error) = match outcome {
+            Ok(Some(result)) => result,
+            // Lease lost: another worker owns the job now.
+            Ok(None) => return,
+            Err(e) => (BackgroundJobStatus::Failed, Some(e.to_string())),
+        };
+        if let Err(e) = self.store.finish(&job.id, worker_id, status, error).await {
+            tracing::warn!("failed to record outcome of job {}: {}", job.id, e);
+        }
+    }
+
+    /// Run the job to its root terminal event. Returns `None` when the
+    /// lease was lost mid-run.
+    async fn execute(
+        &self,
+        job: &BackgroundJob,
+        worker_id: &str,
+    ) -> anyhow::Result<Option<(BackgroundJobStatus, Option<String>)>> {
+        let service = A2AService::new(self.orchestrator.clone());
+        let req = JsonRpcRequest {
+            jsonrpc: "2.0".to_string(),
+            method: "message/send".to_string(),
+            params: job.params.clone(),
+            id: None,
+        };
+        let mut executor_context = service
+            .build_executor_context(
+                &req,
+                job.agent_id.clone(),
+                job.user_id.clone(),
+                job.workspace_id.clone(),
+                false,
+            )
+            .await?;
+        executor_context.task_id = job.task_id.clone();
+
+        let session = service
+            .initialize_task(ServiceRequest {
+                agent_id: job.agent_id.clone(),
+                user_id: job.user_id.clone(),
+                workspace_id: job.workspace_id.clone(),
+                req,
+                executor_context: Some(executor_context),
+                verbose: false,
+                workspace_model_settings: None,
+            })
+            .await?;
+
+        let mut events = session.event_stream;
+        let mut renew = tokio::time::interval(self.lease() / 3);
+        renew.tick().await;
+        // `None` until the root run reports an outcome.
+        let mut run_outcome: Option<Option<String>> = None;
+        loop {
+            tokio::select! {
+                event = events.next() => match event {
+                    Some(event) if event.parent_task_id.is_none() => match event.event {
+                        AgentEventType::RunFinished { .. } => {
+                            run_outcome = Some(None);
+                            break;
+                        }
+                        AgentEventType::RunError { message, .. } => {
+                            run_outcome = Some(Some(message));
+                            break;
+                        }
+                        _ => {}
+                    },
+                    Some(_) => {}
+                    None => break,
+                },
+                _ = renew.tick() => {
+                    match self.store.renew(&job.id, worker_id, self.lease_until()).await {
+                        Ok(true) => {}
+                        Ok(false) => {
+                            tracing::warn!(
+                                "lost lease on job {}; cancelling task {}",
+                                job.id,
+                                job.task_id
+                            );
+                            let _ = self.orchestrator.coordinator().cancel(&job.task_id).await;
+                            return Ok(None);
+                        }
+                        Err(e) => tracing::warn!("failed to renew lease on job {}: {}", job.id, e),
+                    }
+                }
+            }
+        }
+
+        let task = self
+            .orchestrator
+            .stores
+            .task_store
+            .get_task(&job.task_id)
+            .await?;
+        Ok(Some(match (task.map(|t| t.status), run_outcome) {
+            (Some(TaskStatus::Canceled), _) => (BackgroundJobStatus::Canceled, None),
+            (_, Some(Some(message))) => (BackgroundJobStatus::Failed, Some(message)),
+            (Some(TaskStatus::Failed), _) => (
+                BackgroundJobStatus::Failed,
+                Some("agent run failed".to_string()),
+            ),
+            (_, Some(None)) => (BackgroundJobStatus::Completed, None),
+            (_, None) => (
+                BackgroundJobStatus::Failed,
+                Some("event stream ended before the run finished".to_string()),
+            ),
+        }))
+    }
+}
@@ -1,5 +1,8 @@
+pub mod background;
 pub mod mailbox;
 
+pub use background::BackgroundRunner;
+
 pub use mailbox::{
     in_memory_mailbox, AgentMessage, InMemoryMailbox, InMemoryMailboxSender, Mailbox,
     MailboxReceiver, MailboxSender,
@@ -1,3 +1,4 @@
+use crate::semantic::ArtifactIndex;
 use crate::ArtifactStorageConfig;
 use anyhow::Result;
 use distri_types::{filesystem::FileSystemOps, Part, ToolResponse};
@@ -16,6 +17,7 @@ use std::sync::Arc;
 pub struct ArtifactWrapper {
     filesystem: Arc<dyn FileSystemOps>,
     prefix_path: String,
+    index: Option<Arc<ArtifactIndex>>,
 }
 
 impl ArtifactWrapper {
@@ -23,9 +25,16 @@ impl ArtifactWrapper {
         Ok(Self {
             filesystem,
             prefix_path,
+            index: None,
         })
     }
 
+    /// Keep `index` up to date as artifacts are saved and deleted.
+    pub fn with_index(mut self, index: Option<Arc<ArtifactIndex>>) -> Self {
+        self.index = index;
+        self
+    }
+
     /// Generate namespace path for thread_id/task_id using short hex IDs.
     /// Returns: `threads/{short_thread}/tasks/{short_task}`
     pub fn task_namespace(thread_id: &str, task_id: &str) -> String {
@@ -34,6 +43,12 @@ impl ArtifactWrapper {
         format!("threads/{}/tasks/{}", short_thread, short_task)
     }
 
+    /// Namespace of artifacts shared by every task of a thread.
+    /// Returns: `threads/{short_thread}`
+    pub fn thread_namespace(thread_id: &str) -> String {
+        format!("threads/{}", Self::short_hex(thread_id))
+    }
+
     /// Convert ID to short hex (8 chars like git commits)
     fn short_hex(id: &str) -> String {
         use std::collections::hash_map::DefaultHasher;
@@ -200,9 +215,37 @@ impl ArtifactWrapper {
 
     /// Save artifact with filename and content.
     /// Returns the filename that was saved.
+    /// Indexing failures are logged; the artifact is saved regardless.
     pub async fn save_artifact(&self, filename: &str, content: &str) -> Result<()> {
         let path = self.artifact_path(filename);
-        self.filesystem.write(&path, content).await
+        self.filesystem.write(&path, content).await?;
+        if let Some(index) = &self.index {
+            if let Err(e) = index
+                .index(
+                    self.filesystem.as_ref(),
+                    &self.prefix_path,
+                    filename,
+                    content,
+                )
+                .await
+            {
+                tracing::warn!(filename, error = %e, "Failed to index artifact");
+            }
+        }
+        Ok(())
+    }
+
+    /// Delete one artifact and its index.
+    pub async fn delete_artifact(&self, filename: &str) -> Result<()> {
+        self.filesystem
+            .delete(&self.artifact_path(filename), false)
+            .await?;
+        if let Some(index) = &self.index {
+            index
+                .remove(self.filesystem.as_ref(), &self.prefix_path, filename)
+                .await?;
+        }
+        Ok(())
     }
 
     /// Clean up the entire task namespace folder
@@ -0,0 +1,589 @@
+//! Structural code search.
+//!
+//! `fs_code_search` parses source files with tree-sitter and matches language
+//! constructs instead of lines: definitions (functions, methods, classes,
+//! structs, traits, ...), references to a symbol, and TODO-style comments.
+//! References only match identifier nodes, so mentions in strings and
+//! comments are not reported. Before parsing, files are pre-filtered with the
+//! same grep engine as `fs_search_within_files`.
+//!
+//! Every match carries byte and line ranges plus its enclosing definition, so
+//! an agent can read or edit exactly that construct.
+
+use anyhow::{anyhow, Context, Result};
+use distri_types::filesystem::FileSystemOps;
+use distri_types::{Tool, ToolContext};
+use grep::matcher::Matcher;
+use grep::regex::RegexMatcher;
+use schemars::{schema_for, JsonSchema};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::ops::Range;
+use std::sync::Arc;
+use tree_sitter::{Language, Node, Parser, Query, QueryCursor};
+
+const DEFAULT_MAX_RESULTS: usize = 200;
+/// Files larger than this are skipped rather than parsed.
+const MAX_FILE_BYTES: u64 = 1024 * 1024;
+/// Directories that hold dependencies or build output, never worth parsing.
+const SKIPPED_DIRS: &[&str] = &[
+    "node_modules",
+    "target",
+    "dist",
+    "build",
+    "vendor",
+    "__pycache__",
+];
+const TODO_PATTERN: &str = r"\b(TODO|FIXME|XXX|HACK)\b";
+
+/// Languages `fs_code_search` can parse.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CodeLanguage {
+    Rust,
+    Python,
+    JavaScript,
+    TypeScript,
+    Tsx,
+    Go,
+}
+
+impl CodeLanguage {
+    /// Language of `path`, judged by its extension.
+    pub fn from_path(path: &str) -> Option<Self> {
+        let (_, ext) = path.rsplit_once('.')?;
+        match ext.to_ascii_lowercase().as_str() {
+            "rs" => Some(Self::Rust),
+            "py" | "pyi" => Some(Self::Python),
+            "js" | "jsx" | "mjs" | "cjs" => Some(Self::JavaScript),
+            "ts" | "mts" | "cts" => Some(Self::TypeScript),
+            "tsx" => Some(Self::Tsx),
+            "go" => Some(Self::Go),
+            _ => None,
+        }
+    }
+
+    /// Parse a language name as given in tool parameters.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "rust" | "rs" => Some(Self::Rust),
+            "python" | "py" => Some(Self::Python),
+            "javascript" | "js" | "jsx" => Some(Self::JavaScript),
+            "typescript" | "ts" => Some(Self::TypeScript),
+            "tsx" => Some(Self::Tsx),
+            "go" | "golang" => Some(Self::Go),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Rust => "rust",
+            Self::Python => "python",
+            Self::JavaScript => "javascript",
+            Self::TypeScript => "typescript",
+            Self::Tsx => "tsx",
+            Self::Go => "go",
+        }
+    }
+
+    fn grammar(&self) -> Language {
+        match self {
+            Self::Rust => tree_sitter_rust::LANGUAGE.into(),
+            Self::Python => tree_sitter_python::LANGUAGE.into(),
+            Self::JavaScript => tree_sitter_javascript::LANGUAGE.into(),
+            Self::TypeScript => tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into(),
+            Self::Tsx => tree_sitter_typescript::LANGUAGE_TSX.into(),
+            Self::Go => tree_sitter_go::LANGUAGE.into(),
+        }
+    }
+
+    /// Definitions query. Each pattern captures the definition node under
+    /// its kind and the name node as `@name`.
+    fn definitions_query(&self) -> &'static str {
+        match self {
+            Self::Rust => RUST_DEFINITIONS,
+            Self::Python => PYTHON_DEFINITIONS,
+            Self::JavaScript => JAVASCRIPT_DEFINITIONS,
+            Self::TypeScript | Self::Tsx => TYPESCRIPT_DEFINITIONS,
+            Self::Go => GO_DEFINITIONS,
+        }
+    }
+}
+
+const RUST_DEFINITIONS: &str = r#"
+(function_item name: (identifier) @name) @function
+(function_signature_item name: (identifier) @name) @function
+(struct_item name: (type_identifier) @name) @struct
+(enum_item name: (type_identifier) @name) @enum
+(union_item name: (type_identifier) @name) @struct
+(trait_item name: (type_identifier) @name) @trait
+(type_item name: (type_identifier) @name) @type
+(mod_item name: (identifier) @name) @module
+(const_item name: (identifier) @name) @constant
+(static_item name: (identifier) @name) @constant
+(macro_definition name: (identifier) @name) @macro
+"#;
+
+const PYTHON_DEFINITIONS: &str = r#"
+(function_definition name: (identifier) @name) @function
+(class_definition name: (identifier) @name) @class
+"#;
+
+const JAVASCRIPT_DEFINITIONS: &str = r#"
+(function_declaration name: (identifier) @name) @function
+(generator_function_declaration name: (identifier) @name) @function
+(class_declaration name: (identifier) @name) @class
+(method_definition name: (property_identifier) @name) @method
+(variable_declarator name: (identifier) @name value: [(arrow_function) (function_expression)]) @function
+"#;
+
+const TYPESCRIPT_DEFINITIONS: &str = r#"
+(function_declaration name: (identifier) @name) @function
+(generator_function_declaration name: (identifier) @name) @function
+(class_declaration name: (type_identifier) @name) @class
+(abstract_class_declaration name: (type_identifier) @name) @class
+(method_definition name: (property_identifier) @name) @method
+(interface_declaration name: (type_identifier) @name) @interface
+(type_alias_declaration name: (type_identifier) @name) @type
+(enum_declaration name: (identifier) @name) @enum
+(variable_declarator name: (identifier) @name value: [(arrow_function) (function_expression)]) @function
+"#;
+
+const GO_DEFINITIONS: &str = r#"
+(function_declaration name: (identifier) @name) @function
+(method_declaration name: (field_identifier) @name) @method
+(type_spec name: (type_identifier) @name) @type
+"#;
+
+/// Node kinds that make a nested function a method.
+const CONTAINER_KINDS: &[&str] = &[
+    "impl_item",
+    "trait_item",
+    "class_definition",
+    "class_declaration",
+    "abstract_class_declaration",
+];
+const FUNCTION_KINDS: &[&str] = &[
+    "function_item",
+    "function_definition",
+    "function_declaration",
+    "method_definition",
+    "arrow_function",
+    "function_expression",
+];
+const IDENTIFIER_KINDS: &[&str] = &[
+    "identifier",
+    "type_identifier",
+    "field_identifier",
+    "property_identifier",
+    "shorthand_property_identifier",
+    "shorthand_property_identifier_pattern",
+    "package_identifier",
+];
+
+/// What to look for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CodeQueryKind {
+    /// Functions, methods, classes, structs, traits, types and the like.
+    Definitions,
+    /// Identifiers equal to `symbol`, excluding its definitions.
+    References,
+    /// Comments containing TODO, FIXME, XXX or HACK.
+    Todos,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CodeSearchParams {
+    /// Root directory to search under relative to CODE_HOME.
+    #[schemars(description = "Root directory to search under relative to CODE_HOME")]
+    pub path: String,
+    /// What to look for.
+    #[schemars(
+        description = "What to look for: 'definitions', 'references' (requires symbol) or 'todos'"
+    )]
+    pub query: CodeQueryKind,
+    /// Exact symbol name. Filters definitions; required for references.
+    #[serde(default)]
+    #[schemars(description = "Exact symbol name. Filters definitions; required for references")]
+    pub symbol: Option<String>,
+    /// Only definitions of this kind, e.g. 'function', 'method', 'class', 'struct'.
+    #[serde(default)]
+    #[schemars(
+        description = "Only definitions of this kind, e.g. 'function', 'method', 'class', 'struct', 'trait'"
+    )]
+    pub kind: Option<String>,
+    /// Only files of this language.
+    #[serde(default)]
+    #[schemars(
+        description = "Only files of this language: rust, python, javascript, typescript, tsx or go"
+    )]
+    pub language: Option<String>,
+    /// Optional cap on the number of matches to return.
+    #[serde(default)]
+    #[schemars(description = "Optional cap on the number of matches to return (default 200)")]
+    pub max_results: Option<usize>,
+}
+
+/// A construct found by `fs_code_search`. Lines and columns are 1-based;
+/// byte ranges are offsets into the file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CodeMatch {
+    pub path: String,
+    pub language: String,
+    /// Definition kind (`function`, `class`, ...), `reference` or `todo`.
+    pub kind: String,
+    /// Defined or referenced name, or the TODO tag.
+    pub name: String,
+    pub start_byte: usize,
+    pub end_byte: usize,
+    pub start_line: usize,
+    pub end_line: usize,
+    pub start_column: usize,
+    /// First line of the construct, or the comment text for TODOs.
+    pub text: String,
+    /// Name of the innermost definition enclosing the match.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub container: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CodeSearchResponse {
+    pub path: String,
+    pub query: CodeQueryKind,
+    pub matches: Vec<CodeMatch>,
+    pub files_scanned: usize,
+    /// Whether matches were dropped to honour `max_results`.
+    pub truncated: bool,
+}
+
+/// Parse `source` and return the matches of `params` in it. `params.path`,
+/// `language` and `max_results` are not consulted here.
+pub fn search_source(
+    language: CodeLanguage,
+    path: &str,
+    source: &str,
+    params: &CodeSearchParams,
+) -> Result<Vec<CodeMatch>> {
+    let grammar = language.grammar();
+    let mut parser = Parser::new();
+    parser
+        .set_language(&grammar)
+        .with_context(|| format!("Failed to load {} grammar", language.name()))?;
+    let tree = parser
+        .parse(source, None)
+        .ok_or_else(|| anyhow!("Failed to parse {}", path))?;
+    let root = tree.root_node();
+    let bytes = source.as_bytes();
+
+    let definitions = find_definitions(language, &grammar, root, path, source)?;
+
+    let mut matches: Vec<CodeMatch> = match params.query {
+        CodeQueryKind::Definitions => definitions
+            .iter()
+            .map(|(d, _)| d)
+            .filter(|d| params.symbol.as_deref().is_none_or(|s| d.name == s))
+            .filter(|d| params.kind.as_deref().is_none_or(|k| d.kind == k))
+            .cloned()
+            .collect(),
+        CodeQueryKind::References => {
+            let symbol = params
+                .symbol
+                .as_deref()
+                .ok_or_else(|| anyhow!("'symbol' is required for references"))?;
+            let mut found = Vec::new();
+            walk(root, &mut |node| {
+                if node.child_count() == 0
+                    && IDENTIFIER_KINDS.contains(&node.kind())
+                    && node.utf8_text(bytes).ok() == Some(symbol)
+                    && !definitions
+                        .iter()
+                        .any(|(_, name)| *name == node.byte_range())
+                {
+                    found.push(code_match(
+                        language,
+                        path,
+                        node,
+                        "reference",
+                        symbol,
+                        line_at(source, node.start_byte()),
+                    ));
+                }
+            });
+            found
+        }
+        CodeQueryKind::Todos => {
+            let todo = regex::Regex::new(TODO_PATTERN).expect("valid TODO pattern");
+            let mut found = Vec::new();
+            walk(root, &mut |node| {
+                if !node.kind().contains("comment") {
+                    return;
+                }
+                let Ok(text) = node.utf8_text(bytes) else {
+                    return;
+                };
+                if let Some(tag) = todo.find(text) {
+                    found.push(code_match(
+                        language,
+                        path,
+                        node,
+                        "todo",
+                        tag.as_str(),
+                        text.trim().to_string(),
+                    ));
+                }
+            });
+            found
+        }
+    };
+
+    for m in &mut matches {
+        m.container = definitions
+            .iter()
+            .map(|(d, _)| d)
+            .filter(|d| {
+                d.start_byte <= m.start_byte
+                    && m.end_byte <= d.end_byte
+                    && (d.start_byte, d.end_byte) != (m.start_byte, m.end_byte)
+            })
+            .min_by_key(|d| d.end_byte - d.start_byte)
+            .map(|d| d.name.clone());
+    }
+    Ok(matches)
+}
+
+fn find_definitions(
+    language: CodeLanguage,
+    grammar: &Language,
+    root: Node<'_>,
+    path: &str,
+    source: &str,
+) -> Result<Vec<(CodeMatch, Range<usize>)>> {
+    let query = Query::new(grammar, language.definitions_query())
+        .with_context(|| format!("Invalid {} definitions query", language.name()))?;
+    let capture_names = query.capture_names();
+    let bytes = source.as_bytes();
+
+    let mut definitions = Vec::new();
+    let mut cursor = QueryCursor::new();
+    for m in cursor.matches(&query, root, bytes) {
+        let mut name = None;
+        let mut definition = None;
+        for capture in m.captures {
+            match capture_names[capture.index as usize] {
+                "name" => name = Some(capture.node),
+                kind => definition = Some((kind, capture.node)),
+            }
+        }
+        let (Some(name), Some((kind, node))) = (name, definition) else {
+            continue;
+        };
+        let kind = if kind == "function" && is_method(node) {
+            "method"
+        } else {
+            kind
+        };
+        let definition = code_match(
+            language,
+            path,
+            node,
+            kind,
+            name.utf8_text(bytes).unwrap_or_default(),
+            line_at(source, node.start_byte()),
+        );
+        definitions.push((definition, name.byte_range()));
+    }
+    Ok(definitions)
+}
+
+fn code_match(
+    language: CodeLanguage,
+    path: &str,
+    node: Node<'_>,
+    kind: &str,
+    name: &str,
+    text: String,
+) -> CodeMatch {
+    CodeMatch {
+        path: path.to_string(),
+        language: language.name().to_string(),
+        kind: kind.to_string(),
+        name: name.to_string(),
+        start_byte: node.start_byte(),
+        end_byte: node.end_byte(),
+        start_line: node.start_position().row + 1,
+        end_line: node.end_position().row + 1,
+        start_column: node.start_position().column + 1,
+        text,
+        container: None,
+    }
+}
+
+/// Whether a function node sits directly inside a class, impl or trait.
+fn is_method(node: Node<'_>) -> bool {
+    let mut current = node.parent();
+    while let Some(parent) = current {
+        if CONTAINER_KINDS.contains(&parent.kind()) {
+            return true;
+        }
+        if FUNCTION_KINDS.contains(&parent.kind()) {
+            return false;
+        }
+        current = parent.parent();
+    }
+    false
+}
+
+/// Visit every node of the tree in document order.
+fn walk<'t>(root: Node<'t>, visit: &mut impl FnMut(Node<'t>)) {
+    let mut cursor = root.walk();
+    loop {
+        visit(cursor.node());
+        if cursor.goto_first_child() || cursor.goto_next_sibling() {
+            continue;
+        }
+        loop {
+            if !cursor.goto_parent() {
+                return;
+            }
+            if cursor.goto_next_sibling() {
+                break;
+            }
+        }
+    }
+}
+
+/// The trimmed source line containing `byte`.
+fn line_at(source: &str, byte: usize) -> String {
+    let start = source[..byte].rfind('\n').map_or(0, |i| i + 1);
+    let end = source[byte..].find('\n').map_or(source.len(), |i| byte + i);
+    source[start..end].trim().to_string()
+}
+
+/// Structural code search tool
+#[derive(Debug)]
+pub struct CodeSearchTool {
+    filesystem: Arc<dyn FileSystemOps>,
+}
+
+impl CodeSearchTool {
+    pub fn new(filesystem: Arc<dyn FileSystemOps>) -> Self {
+        Self { filesystem }
+    }
+
+    /// Walk `params.path` and collect matches from every supported source file.
+    pub async fn search(&self, params: &CodeSearchParams) -> Result<CodeSearchResponse> {
+        let language = match params.language.as_deref() {
+            Some(name) => Some(
+                CodeLanguage::from_name(name)
+                    .ok_or_else(|| anyhow!("Unsupported language: {}", name))?,
+            ),
+            None => None,
+        };
+        if params.query == CodeQueryKind::References && params.symbol.is_none() {
+            return Err(anyhow!("'symbol' is required for references"));
+        }
+        let prefilter = match (params.query, params.symbol.as_deref()) {
+            (CodeQueryKind::Todos, _) => Some(RegexMatcher::new(TODO_PATTERN)?),
+            (_, Some(symbol)) => Some(RegexMatcher::new(&format!(
+                r"\b{}\b",
+                regex::escape(symbol)
+            ))?),
+            _ => None,
+        };
+        let max_results = params.max_results.unwrap_or(DEFAULT_MAX_RESULTS);
+
+        let mut files = Vec::new();
+        self.collect_files(&params.path, language, &mut files)
+            .await?;
+
+        let mut response = CodeSearchResponse {
+            path: params.path.clone(),
+            query: params.query,
+            matches: Vec::new(),
+            files_scanned: 0,
+            truncated: false,
+        };
+        for (path, file_language) in files {
+            let Ok(source) = self.filesystem.read_raw(&path).await else {
+                continue;
+            };
+            if let Some(matcher) = &prefilter {
+                if !matcher.is_match(source.as_bytes()).unwrap_or(true) {
+                    continue;
+                }
+            }
+            response.files_scanned += 1;
+            match search_source(file_language, &path, &source, params) {
+                Ok(found) => response.matches.extend(found),
+                Err(e) => tracing::debug!("code search skipped {}: {}", path, e),
+            }
+            if response.matches.len() > max_results {
+                response.matches.truncate(max_results);
+                response.truncated = true;
+                break;
+            }
+        }
+        Ok(response)
+    }
+
+    fn collect_files<'a>(
+        &'a self,
+        dir_path: &'a str,
+        language: Option<CodeLanguage>,
+        files: &'a mut Vec<(String, CodeLanguage)>,
+    ) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<()>> + Send + 'a>> {
+        Box::pin(async move {
+            let listing = self.filesystem.list(dir_path).await?;
+            for entry in listing.entries {
+                let entry_path = if dir_path.is_empty() {
+                    entry.name.clone()
+                } else {
+                    format!("{}/{}", dir_path.trim_end_matches('/'), entry.name)
+                };
+                if entry.is_dir {
+                    if entry.name.starts_with('.') || SKIPPED_DIRS.contains(&entry.name.as_str()) {
+                        continue;
+                    }
+                    self.collect_files(&entry_path, language, files).await?;
+                } else if entry.size.unwrap_or(0) <= MAX_FILE_BYTES {
+                    match CodeLanguage::from_path(&entry.name) {
+                        Some(found) if language.is_none_or(|l| l == found) => {
+                            files.push((entry_path, found))
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            Ok(())
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl Tool for CodeSearchTool {
+    fn get_name(&self) -> String {
+        "fs_code_search".to_string()
+    }
+
+    fn get_description(&self) -> String {
+        "Search source code by language construct: find definitions (functions, methods, classes, structs, ...), references to a symbol, or TODO comments. Supports Rust, Python, JavaScript, TypeScript and Go, and returns byte and line ranges for each match".to_string()
+    }
+
+    fn get_parameters(&self) -> Value {
+        let schema = schema_for!(CodeSearchParams);
+        serde_json::to_value(schema).unwrap_or_else(|_| json!({}))
+    }
+
+    async fn execute(
+        &self,
+        tool_call: distri_types::ToolCall,
+        _context: Arc<ToolContext>,
+    ) -> Result<Vec<distri_types::Part>, anyhow::Error> {
+        let params: CodeSearchParams = serde_json::from_value(tool_call.input)?;
+        let response = self.search(&params).await?;
+        Ok(vec![distri_types::Part::Data(serde_json::to_value(
+            response,
+        )?)])
+    }
+}
@@ -1,8 +1,10 @@
 pub mod artifact;
 pub mod artifact_tools;
+pub mod code_search;
 pub mod config;
 mod object_store;
 pub mod search;
+pub mod semantic;
 pub mod store;
 pub mod tools;
 pub mod traits;
@@ -14,12 +16,14 @@ pub use artifact_tools::{
     create_artifact_tools, DeleteArtifactTool, ListArtifactsTool, ReadArtifactTool,
     SearchArtifactsTool,
 };
+pub use code_search::{CodeLanguage, CodeSearchTool};
 pub use config::{
     ArtifactStorageConfig, DirectoryEntry, DirectoryListing, FileReadResult, FileSystemConfig,
     ReadParams, SearchMatch, SearchResult,
 };
 pub use search::FileSystemGrepSearcher;
-pub use store::FileSystemStore;
+pub use semantic::{ArtifactIndex, ChunkMatch, Embedder};
+pub use store::{FileSystemStore, ObjectStat};
 pub use tools::{create_core_filesystem_tools, create_filesystem_tools};
 pub use traits::GrepSearcher;
 pub use wrapper::{create_file_system, FileSystem};
@@ -2,10 +2,18 @@ use std::sync::Arc;
 
 use anyhow::{anyhow, Context, Result};
 use distri_types::configuration::ObjectStorageConfig;
+use object_store::signer::Signer;
 use object_store::ObjectStore;
 
+/// An object store and, for backends that support it, a signer for
+/// presigned URLs.
+pub struct ObjectBackend {
+    pub store: Arc<dyn ObjectStore>,
+    pub signer: Option<Arc<dyn Signer>>,
+}
+
 /// Build an object store instance from configuration
-pub fn build_object_store(config: &ObjectStorageConfig) -> Result<Arc<dyn ObjectStore>> {
+pub fn build_object_store(config: &ObjectStorageConfig) -> Result<ObjectBackend> {
     match config {
         ObjectStorageConfig::FileSystem { base_path } => {
             std::fs::create_dir_all(base_path).with_context(|| {
@@ -15,7 +23,10 @@ pub fn build_object_store(config: &ObjectStorageConfig) -> Result<Arc<dyn Object
                 .with_context(|| {
                     format!("failed to build filesystem object store at {}", base_path)
                 })?;
-            Ok(Arc::new(store))
+            Ok(ObjectBackend {
+                store: Arc::new(store),
+                signer: None,
+            })
         }
         ObjectStorageConfig::S3 {
             bucket,
@@ -35,10 +46,15 @@ pub fn build_object_store(config: &ObjectStorageConfig) -> Result<Arc<dyn Object
                 builder = builder.with_endpoint(endpoint);
             }
 
-            let store = builder
-                .build()
-                .context("failed to build amazon s3 object store")?;
-            Ok(Arc::new(store))
+            let store = Arc::new(
+                builder
+                    .build()
+                    .context("failed to build amazon s3 object store")?,
+            );
+            Ok(ObjectBackend {
+                store: store.clone(),
+                signer: Some(store),
+            })
         }
         ObjectStorageConfig::GoogleCloudStorage { .. } => Err(anyhow!(
             "Google Cloud Storage object store not yet supported"
@@ -0,0 +1,287 @@
+//! Embedding index of artifacts for semantic search.
+//!
+//! When an [`ArtifactIndex`] is attached (see [`crate::FileSystem::with_artifact_index`]),
+//! every artifact saved through an [`crate::ArtifactWrapper`] is split into
+//! overlapping chunks and each chunk is embedded. The vectors are kept next
+//! to the content, at `{prefix_path}/index/{filename}.json`, so saving the
+//! artifact again re-indexes it and deleting it (or its namespace) drops the
+//! index with it. Artifacts stored base64-encoded are indexed by their
+//! decoded text; binary ones are skipped.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use base64::{engine::general_purpose, Engine as _};
+use distri_types::filesystem::FileSystemOps;
+use serde::{Deserialize, Serialize};
+
+/// Largest chunk, in bytes.
+const CHUNK_BYTES: usize = 1500;
+/// Bytes shared by two consecutive chunks.
+const CHUNK_OVERLAP: usize = 200;
+/// Chunks indexed per artifact; the rest of a larger artifact is not searchable.
+const MAX_CHUNKS: usize = 400;
+
+/// Turns texts into vectors, one per text in order.
+#[async_trait]
+pub trait Embedder: Send + Sync {
+    async fn embed_texts(&self, texts: Vec<String>) -> Result<Vec<Vec<f32>>>;
+}
+
+/// A span of an artifact's text. Offsets are bytes, lines are 1-based and
+/// inclusive, as `read_artifact` takes them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TextChunk {
+    pub start: usize,
+    pub end: usize,
+    pub start_line: u64,
+    pub end_line: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct IndexedChunk {
+    #[serde(flatten)]
+    span: TextChunk,
+    embedding: Vec<f32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct IndexFile {
+    filename: String,
+    chunks: Vec<IndexedChunk>,
+}
+
+/// One result of [`ArtifactIndex::search`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChunkMatch {
+    /// Namespace (`prefix_path`) the artifact lives in.
+    pub namespace: String,
+    pub filename: String,
+    /// Cosine similarity to the query.
+    pub score: f32,
+    #[serde(flatten)]
+    pub span: TextChunk,
+    pub text: String,
+}
+
+/// Chunking, embedding and search of artifacts. See the module docs.
+#[derive(Clone)]
+pub struct ArtifactIndex {
+    embedder: Arc<dyn Embedder>,
+}
+
+impl std::fmt::Debug for ArtifactIndex {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ArtifactIndex").finish()
+    }
+}
+
+impl ArtifactIndex {
+    pub fn new(embedder: Arc<dyn Embedder>) -> Self {
+        Self { embedder }
+    }
+
+    fn index_path(prefix_path: &str, filename: &str) -> String {
+        format!("{}/index/{}.json", prefix_path, filename)
+    }
+
+    /// (Re-)index `filename` of `prefix_path` with its new `content`.
+    pub async fn index(
+        &self,
+        filesystem: &dyn FileSystemOps,
+        prefix_path: &str,
+        filename: &str,
+        content: &str,
+    ) -> Result<()> {
+        let path = Self::index_path(prefix_path, filename);
+        let Some(text) = artifact_text(content) else {
+            // Binary now: drop what an earlier text version left.
+            return self.remove(filesystem, prefix_path, filename).await;
+        };
+        let mut spans = chunk_text(&text, CHUNK_BYTES, CHUNK_OVERLAP);
+        if spans.len() > MAX_CHUNKS {
+            tracing::warn!(
+                filename,
+                chunks = spans.len(),
+                "Artifact too large to index fully; indexing its first {} chunks",
+                MAX_CHUNKS
+            );
+            spans.truncate(MAX_CHUNKS);
+        }
+        let texts = spans
+            .iter()
+            .map(|s| text[s.start..s.end].to_string())
+            .collect();
+        let embeddings = self.embedder.embed_texts(texts).await?;
+        let file = IndexFile {
+            filename: filename.to_string(),
+            chunks: spans
+                .into_iter()
+                .zip(embeddings)
+                .map(|(span, embedding)| IndexedChunk { span, embedding })
+                .collect(),
+        };
+        filesystem
+            .write(&path, &serde_json::to_string(&file)?)
+            .await
+    }
+
+    /// Drop the index of `filename` of `prefix_path`, if any.
+    pub async fn remove(
+        &self,
+        filesystem: &dyn FileSystemOps,
+        prefix_path: &str,
+        filename: &str,
+    ) -> Result<()> {
+        let path = Self::index_path(prefix_path, filename);
+        if filesystem.info(&path).await.is_ok() {
+            filesystem.delete(&path, false).await?;
+        }
+        Ok(())
+    }
+
+    /// The `limit` chunks of the artifacts of `namespaces` closest to `query`,
+    /// best first.
+    pub async fn search(
+        &self,
+        filesystem: &dyn FileSystemOps,
+        namespaces: &[String],
+        query: &str,
+        limit: usize,
+    ) -> Result<Vec<ChunkMatch>> {
+        let query = self
+            .embedder
+            .embed_texts(vec![query.to_string()])
+            .await?
+            .pop()
+            .unwrap_or_default();
+
+        let mut scored = Vec::new();
+        for namespace in namespaces {
+            let Ok(listing) = filesystem.list(&format!("{}/index", namespace)).await else {
+                continue;
+            };
+            for entry in listing.entries.iter().filter(|e| e.is_file) {
+                let path = format!("{}/index/{}", namespace, entry.name);
+                let Some(file) = filesystem
+                    .read_raw(&path)
+                    .await
+                    .ok()
+                    .and_then(|raw| serde_json::from_str::<IndexFile>(&raw).ok())
+                else {
+                    continue;
+                };
+                for chunk in file.chunks {
+                    let score = cosine(&query, &chunk.embedding);
+                    scored.push((namespace.clone(), file.filename.clone(), chunk.span, score));
+                }
+            }
+        }
+        scored.sort_by(|a, b| b.3.total_cmp(&a.3));
+
+        let mut texts: HashMap<(String, String), Option<String>> = HashMap::new();
+        let mut matches = Vec::new();
+        for (namespace, filename, span, score) in scored {
+            if matches.len() == limit {
+                break;
+            }
+            let key = (namespace.clone(), filename.clone());
+            if !texts.contains_key(&key) {
+                let content = filesystem
+                    .read_raw(&format!("{}/content/{}", namespace, filename))
+                    .await
+                    .ok();
+                texts.insert(key.clone(), content.as_deref().and_then(artifact_text));
+            }
+            // Skip chunks of an artifact changed behind the index's back.
+            let Some(text) = texts[&key]
+                .as_deref()
+                .and_then(|t| t.get(span.start..span.end))
+            else {
+                continue;
+            };
+            matches.push(ChunkMatch {
+                namespace,
+                filename,
+                score,
+                span,
+                text: text.to_string(),
+            });
+        }
+        Ok(matches)
+    }
+}
+
+/// The text of an artifact: `content` itself, or its decoding when it is
+/// base64-encoded UTF-8. `None` for binary content. Short strings are never
+/// taken for base64.
+pub fn artifact_text(content: &str) -> Option<String> {
+    let encoded = content.len() >= 64
+        && content.len().is_multiple_of(4)
+        && !content.contains(|c: char| c.is_whitespace());
+    if encoded {
+        if let Ok(bytes) = general_purpose::STANDARD.decode(content) {
+            return String::from_utf8(bytes).ok();
+        }
+    }
+    (!content.contains('\0')).then(|| content.to_string())
+}
+
+/// Split `text` into chunks of at most `max_bytes`, ending on a line break
+/// when one falls in the second half of the chunk, each overlapping the
+/// previous one by about `overlap` bytes.
+pub fn chunk_text(text: &str, max_bytes: usize, overlap: usize) -> Vec<TextChunk> {
+    let newlines: Vec<usize> = text.match_indices('\n').map(|(i, _)| i).collect();
+    let line_of = |offset: usize| newlines.partition_point(|&n| n < offset) as u64 + 1;
+
+    let mut chunks = Vec::new();
+    let mut start = 0;
+    while start < text.len() {
+        let mut end = (start + max_bytes.max(1)).min(text.len());
+        while !text.is_char_boundary(end) {
+            end -= 1;
+        }
+        if end == start {
+            // A character wider than `max_bytes`.
+            end = start + text[start..].chars().next().map_or(1, char::len_utf8);
+        }
+        if end < text.len() {
+            if let Some(newline) = text[start..end].rfind('\n') {
+                if newline >= (end - start) / 2 {
+                    end = start + newline + 1;
+                }
+            }
+        }
+        chunks.push(TextChunk {
+            start,
+            end,
+            start_line: line_of(start),
+            end_line: line_of(end - 1),
+        });
+        if end == text.len() {
+            break;
+        }
+        let mut next = end.saturating_sub(overlap).max(start + 1);
+        while !text.is_char_boundary(next) {
+            next += 1;
+        }
+        start = next;
+    }
+    chunks
+}
+
+fn cosine(a: &[f32], b: &[f32]) -> f32 {
+    if a.len() != b.len() || a.is_empty() {
+        return 0.0;
+    }
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm = |v: &[f32]| v.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let denominator = norm(a) * norm(b);
+    if denominator == 0.0 {
+        0.0
+    } else {
+        dot / denominator
+    }
+}
@@ -1,22 +1,36 @@
 use crate::{DirectoryEntry, DirectoryListing, FileReadResult, FileSystemConfig, ReadParams};
 use anyhow::{anyhow, Context, Result};
 use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use futures::stream::BoxStream;
 use futures::TryStreamExt;
 use object_store::path::Path;
-use object_store::ObjectStore;
+use object_store::signer::Signer;
+use object_store::{GetOptions, ObjectStore};
+use std::ops::Range;
 use std::sync::Arc;
+use std::time::Duration;
 
 /// Object-store backed file storage implementation
 #[derive(Debug)]
 pub struct FileSystemStore {
     store: Arc<dyn ObjectStore>,
+    signer: Option<Arc<dyn Signer>>,
     root: Option<Path>,
 }
 
+/// Size and version of a stored file, without reading it.
+#[derive(Debug, Clone)]
+pub struct ObjectStat {
+    pub size: u64,
+    pub last_modified: DateTime<Utc>,
+    pub e_tag: Option<String>,
+}
+
 impl FileSystemStore {
     /// Create a new FileSystemStore using the provided configuration
     pub async fn new(config: FileSystemConfig) -> Result<Self> {
-        let store = crate::object_store::build_object_store(&config.object_store)?;
+        let backend = crate::object_store::build_object_store(&config.object_store)?;
 
         let root = config.root_prefix.as_ref().and_then(|prefix| {
             let trimmed = prefix.trim_matches('/');
@@ -27,7 +41,11 @@ impl FileSystemStore {
             }
         });
 
-        Ok(Self { store, root })
+        Ok(Self {
+            store: backend.store,
+            signer: backend.signer,
+            root,
+        })
     }
 
     pub fn root_prefix(&self) -> Option<String> {
@@ -41,6 +59,7 @@ impl FileSystemStore {
         };
         Ok(Self {
             store: self.store.clone(),
+            signer: self.signer.clone(),
             root: new_root,
         })
     }
@@ -265,6 +284,63 @@ impl FileSystemStore {
         Ok(bytes.to_vec())
     }
 
+    /// Size and version of a file.
+    pub async fn stat(&self, path: &str) -> Result<ObjectStat> {
+        let object_path = self
+            .sanitize_object_path(path)
+            .with_context(|| format!("invalid file path: {}", path))?;
+        let meta = self
+            .store
+            .head(&object_path)
+            .await
+            .with_context(|| format!("failed to fetch metadata for {}", path))?;
+        Ok(ObjectStat {
+            size: meta.size as u64,
+            last_modified: meta.last_modified,
+            e_tag: meta.e_tag,
+        })
+    }
+
+    /// Stream a file, or the byte `range` of it, without buffering it whole.
+    pub async fn read_stream(
+        &self,
+        path: &str,
+        range: Option<Range<u64>>,
+    ) -> Result<BoxStream<'static, Result<Bytes>>> {
+        let object_path = self
+            .sanitize_object_path(path)
+            .with_context(|| format!("invalid file path: {}", path))?;
+        let options = GetOptions {
+            range: range.map(|r| (r.start as usize..r.end as usize).into()),
+            ..Default::default()
+        };
+        let get_result = self
+            .store
+            .get_opts(&object_path, options)
+            .await
+            .with_context(|| format!("failed to fetch object for {path}"))?;
+        Ok(Box::pin(
+            get_result.into_stream().map_err(anyhow::Error::from),
+        ))
+    }
+
+    /// A URL that reads the file straight from the object store for
+    /// `expires_in`. `None` when the backend cannot sign URLs, as for the
+    /// local filesystem.
+    pub async fn presigned_url(&self, path: &str, expires_in: Duration) -> Result<Option<String>> {
+        let Some(signer) = &self.signer else {
+            return Ok(None);
+        };
+        let object_path = self
+            .sanitize_object_path(path)
+            .with_context(|| format!("invalid file path: {}", path))?;
+        let url = signer
+            .signed_url(http::Method::GET, &object_path, expires_in)
+            .await
+            .with_context(|| format!("failed to presign {}", path))?;
+        Ok(Some(url.to_string()))
+    }
+
     pub async fn list(&self, path: &str) -> Result<DirectoryListing> {
         let prefix = self.sanitize_prefix(path)?;
         let list_result = match &prefix {
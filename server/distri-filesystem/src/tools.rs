@@ -1081,6 +1081,7 @@ pub fn create_core_filesystem_tools(filesystem: Arc<dyn FileSystemOps>) -> Vec<A
         Arc::new(CreateDirectoryTool::new(filesystem.clone())) as Arc<dyn Tool>,
         Arc::new(TreeTool::new(filesystem.clone())) as Arc<dyn Tool>,
         Arc::new(SearchWithinFilesTool::new(filesystem.clone())) as Arc<dyn Tool>,
+        Arc::new(crate::CodeSearchTool::new(filesystem.clone())) as Arc<dyn Tool>,
     ];
 
     tools
@@ -1,3 +1,4 @@
+use crate::semantic::ArtifactIndex;
 use crate::GrepSearcher;
 use anyhow::Result;
 use async_trait::async_trait;
@@ -12,6 +13,7 @@ pub struct FileSystem {
     file_store: Arc<crate::FileSystemStore>,
     grep_searcher: Arc<dyn GrepSearcher>,
     root_prefix: Option<String>,
+    artifact_index: Option<Arc<ArtifactIndex>>,
 }
 
 impl FileSystem {
@@ -24,9 +26,21 @@ impl FileSystem {
             file_store,
             grep_searcher,
             root_prefix,
+            artifact_index: None,
         }
     }
 
+    /// Index the artifacts saved through this filesystem's artifact wrappers
+    /// for semantic search.
+    pub fn with_artifact_index(mut self, index: ArtifactIndex) -> Self {
+        self.artifact_index = Some(Arc::new(index));
+        self
+    }
+
+    pub fn artifact_index(&self) -> Option<Arc<ArtifactIndex>> {
+        self.artifact_index.clone()
+    }
+
     pub fn root_prefix(&self) -> Option<String> {
         self.root_prefix.clone()
     }
@@ -43,6 +57,7 @@ impl FileSystem {
             root_prefix: store.root_prefix(),
             file_store: store,
             grep_searcher,
+            artifact_index: self.artifact_index.clone(),
         })
     }
 
@@ -51,7 +66,11 @@ impl FileSystem {
         &self,
         base_path: String,
     ) -> Result<crate::ArtifactWrapper, anyhow::Error> {
-        crate::ArtifactWrapper::new(Arc::new(self.clone()), base_path).await
+        Ok(
+            crate::ArtifactWrapper::new(Arc::new(self.clone()), base_path)
+                .await?
+                .with_index(self.artifact_index.clone()),
+        )
     }
 
     /// Write binary data to a file (for screenshots, images, etc.)
@@ -63,6 +82,29 @@ impl FileSystem {
     pub async fn read_binary(&self, path: &str) -> Result<Vec<u8>> {
         self.file_store.read_binary(path).await
     }
+
+    /// Size and version of a file, without reading it.
+    pub async fn stat(&self, path: &str) -> Result<crate::store::ObjectStat> {
+        self.file_store.stat(path).await
+    }
+
+    /// Stream a file, or a byte range of it (for large artifact downloads)
+    pub async fn read_stream(
+        &self,
+        path: &str,
+        range: Option<std::ops::Range<u64>>,
+    ) -> Result<futures::stream::BoxStream<'static, Result<bytes::Bytes>>> {
+        self.file_store.read_stream(path, range).await
+    }
+
+    /// A presigned object-store URL for the file, when the backend has one.
+    pub async fn presigned_url(
+        &self,
+        path: &str,
+        expires_in: std::time::Duration,
+    ) -> Result<Option<String>> {
+        self.file_store.presigned_url(path, expires_in).await
+    }
 }
 
 #[async_trait]
@@ -0,0 +1,138 @@
+#[cfg(test)]
+mod tests {
+    use distri_filesystem::code_search::{search_source, CodeQueryKind, CodeSearchParams};
+    use distri_filesystem::{create_file_system, CodeLanguage, CodeSearchTool, FileSystemConfig};
+    use distri_types::configuration::ObjectStorageConfig;
+    use distri_types::filesystem::FileSystemOps;
+    use std::sync::Arc;
+    use tempfile::TempDir;
+
+    const RUST_SOURCE: &str = r#"
+struct Parser {
+    depth: usize,
+}
+
+impl Parser {
+    fn parse(&self) -> usize {
+        // TODO: handle nesting
+        helper(self.depth)
+    }
+}
+
+fn helper(depth: usize) -> usize {
+    let label = "helper";
+    depth + label.len()
+}
+"#;
+
+    fn params(query: CodeQueryKind, symbol: Option<&str>) -> CodeSearchParams {
+        CodeSearchParams {
+            path: String::new(),
+            query,
+            symbol: symbol.map(str::to_string),
+            kind: None,
+            language: None,
+            max_results: None,
+        }
+    }
+
+    #[test]
+    fn finds_rust_definitions_with_ranges() {
+        let found = search_source(
+            CodeLanguage::Rust,
+            "src/lib.rs",
+            RUST_SOURCE,
+            &params(CodeQueryKind::Definitions, None),
+        )
+        .unwrap();
+
+        let summary: Vec<(&str, &str)> = found
+            .iter()
+            .map(|m| (m.kind.as_str(), m.name.as_str()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("struct", "Parser"),
+                ("method", "parse"),
+                ("function", "helper")
+            ]
+        );
+
+        let helper = &found[2];
+        assert_eq!(helper.start_line, 13);
+        assert_eq!(helper.text, "fn helper(depth: usize) -> usize {");
+        assert!(RUST_SOURCE[helper.start_byte..helper.end_byte].starts_with("fn helper"));
+        assert_eq!(found[1].container.as_deref(), None);
+    }
+
+    #[test]
+    fn references_skip_definitions_strings_and_comments() {
+        let found = search_source(
+            CodeLanguage::Rust,
+            "src/lib.rs",
+            RUST_SOURCE,
+            &params(CodeQueryKind::References, Some("helper")),
+        )
+        .unwrap();
+
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].start_line, 9);
+        assert_eq!(found[0].container.as_deref(), Some("parse"));
+    }
+
+    #[test]
+    fn finds_todos_in_python() {
+        let source = "class Job:\n    def run(self):\n        # FIXME: retry on timeout\n        return 'TODO'\n";
+        let found = search_source(
+            CodeLanguage::Python,
+            "job.py",
+            source,
+            &params(CodeQueryKind::Todos, None),
+        )
+        .unwrap();
+
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].name, "FIXME");
+        assert_eq!(found[0].text, "# FIXME: retry on timeout");
+        assert_eq!(found[0].container.as_deref(), Some("run"));
+    }
+
+    #[tokio::test]
+    async fn tool_walks_the_filesystem() {
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+        let filesystem = create_file_system(FileSystemConfig {
+            object_store: ObjectStorageConfig::FileSystem {
+                base_path: temp_dir.path().to_string_lossy().to_string(),
+            },
+            root_prefix: Some("testrun".to_string()),
+        })
+        .await
+        .unwrap();
+        filesystem
+            .write("repo/src/lib.rs", RUST_SOURCE)
+            .await
+            .unwrap();
+        filesystem
+            .write(
+                "repo/web/app.ts",
+                "export function helper(n: number) { return n; }\n",
+            )
+            .await
+            .unwrap();
+        filesystem
+            .write("repo/node_modules/dep/index.js", "function helper() {}\n")
+            .await
+            .unwrap();
+
+        let tool = CodeSearchTool::new(Arc::new(filesystem));
+        let mut search = params(CodeQueryKind::Definitions, Some("helper"));
+        search.path = "repo".to_string();
+        let response = tool.search(&search).await.unwrap();
+
+        let mut paths: Vec<&str> = response.matches.iter().map(|m| m.path.as_str()).collect();
+        paths.sort();
+        assert_eq!(paths, vec!["repo/src/lib.rs", "repo/web/app.ts"]);
+        assert!(!response.truncated);
+    }
+}
@@ -0,0 +1,186 @@
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use anyhow::Result;
+    use async_trait::async_trait;
+    use distri_filesystem::semantic::{artifact_text, chunk_text};
+    use distri_filesystem::{ArtifactIndex, ArtifactWrapper, Embedder, FileSystemConfig};
+    use distri_types::configuration::ObjectStorageConfig;
+    use distri_types::filesystem::FileSystemOps;
+    use tempfile::TempDir;
+
+    const WORDS: &[&str] = &["penguin", "ice", "desert", "camel", "sand", "snow"];
+
+    /// Counts the words of `WORDS` in each text.
+    struct WordCounter;
+
+    #[async_trait]
+    impl Embedder for WordCounter {
+        async fn embed_texts(&self, texts: Vec<String>) -> Result<Vec<Vec<f32>>> {
+            Ok(texts
+                .iter()
+                .map(|text| {
+                    let text = text.to_lowercase();
+                    WORDS
+                        .iter()
+                        .map(|w| text.matches(w).count() as f32)
+                        .collect()
+                })
+                .collect())
+        }
+    }
+
+    async fn setup() -> (TempDir, distri_filesystem::FileSystem) {
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+        let config = FileSystemConfig {
+            object_store: ObjectStorageConfig::FileSystem {
+                base_path: temp_dir.path().to_string_lossy().to_string(),
+            },
+            root_prefix: Some("testrun".to_string()),
+        };
+        let filesystem = distri_filesystem::create_file_system(config)
+            .await
+            .unwrap()
+            .with_artifact_index(ArtifactIndex::new(Arc::new(WordCounter)));
+        (temp_dir, filesystem)
+    }
+
+    async fn search(
+        filesystem: &distri_filesystem::FileSystem,
+        namespace: &str,
+        query: &str,
+    ) -> Vec<distri_filesystem::ChunkMatch> {
+        filesystem
+            .artifact_index()
+            .unwrap()
+            .search(filesystem, &[namespace.to_string()], query, 3)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn saved_artifacts_are_searchable() {
+        let (_dir, filesystem) = setup().await;
+        let namespace = ArtifactWrapper::thread_namespace("thread-1");
+        let wrapper = filesystem
+            .create_artifact_wrapper(namespace.clone())
+            .await
+            .unwrap();
+        wrapper
+            .save_artifact("arctic.md", "# Arctic\n\nThe penguin walks on the ice.\n")
+            .await
+            .unwrap();
+        wrapper
+            .save_artifact("sahara.md", "# Sahara\n\nA camel crosses the sand.\n")
+            .await
+            .unwrap();
+
+        let matches = search(&filesystem, &namespace, "camel in the sand").await;
+        assert_eq!(matches[0].filename, "sahara.md");
+        assert_eq!(matches[0].namespace, namespace);
+        assert_eq!(matches[0].span.start, 0);
+        assert_eq!(
+            (matches[0].span.start_line, matches[0].span.end_line),
+            (1, 3)
+        );
+        assert!(matches[0].text.contains("camel crosses"));
+        assert!(matches[0].score > matches[1].score);
+
+        // The index is kept out of the artifact listing.
+        let names: Vec<String> = wrapper
+            .list_artifacts()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|e| e.name)
+            .collect();
+        assert_eq!(names.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn saving_again_reindexes_and_deleting_drops_the_index() {
+        let (_dir, filesystem) = setup().await;
+        let namespace = ArtifactWrapper::thread_namespace("thread-2");
+        let wrapper = filesystem
+            .create_artifact_wrapper(namespace.clone())
+            .await
+            .unwrap();
+        wrapper
+            .save_artifact("notes.md", "penguin on the ice")
+            .await
+            .unwrap();
+        wrapper
+            .save_artifact("notes.md", "camel on the sand")
+            .await
+            .unwrap();
+
+        let matches = search(&filesystem, &namespace, "penguin").await;
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].text, "camel on the sand");
+        assert_eq!(matches[0].score, 0.0);
+
+        wrapper.delete_artifact("notes.md").await.unwrap();
+        assert!(search(&filesystem, &namespace, "camel").await.is_empty());
+        assert!(filesystem
+            .info(&format!("{}/index/notes.md.json", namespace))
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn base64_artifacts_are_indexed_by_their_text() {
+        use base64::{engine::general_purpose, Engine as _};
+
+        let (_dir, filesystem) = setup().await;
+        let namespace = ArtifactWrapper::thread_namespace("thread-3");
+        let wrapper = filesystem
+            .create_artifact_wrapper(namespace.clone())
+            .await
+            .unwrap();
+        let text = "Snow falls on the ice shelf where the penguin colony lives.";
+        wrapper
+            .save_artifact("report.txt", &general_purpose::STANDARD.encode(text))
+            .await
+            .unwrap();
+
+        let matches = search(&filesystem, &namespace, "penguin").await;
+        assert_eq!(matches[0].text, text);
+    }
+
+    #[test]
+    fn chunks_overlap_and_end_on_line_breaks() {
+        let text = "first line\nsecond line\nthird line\nfourth line\n";
+        let chunks = chunk_text(text, 30, 8);
+        assert_eq!(
+            &text[chunks[0].start..chunks[0].end],
+            "first line\nsecond line\n"
+        );
+        assert_eq!((chunks[0].start_line, chunks[0].end_line), (1, 2));
+        assert!(chunks[1].start < chunks[0].end, "chunks overlap");
+        assert_eq!(chunks.last().unwrap().end, text.len());
+        assert_eq!(chunks.last().unwrap().end_line, 4);
+        for chunk in &chunks {
+            assert!(chunk.end - chunk.start <= 30);
+        }
+    }
+
+    #[test]
+    fn chunks_respect_char_boundaries() {
+        let text = "é".repeat(50);
+        let chunks = chunk_text(&text, 15, 4);
+        for chunk in &chunks {
+            assert!(text.is_char_boundary(chunk.start) && text.is_char_boundary(chunk.end));
+        }
+        assert_eq!(chunks.last().unwrap().end, text.len());
+    }
+
+    #[test]
+    fn binary_content_has_no_text() {
+        use base64::{engine::general_purpose, Engine as _};
+
+        let png = general_purpose::STANDARD.encode([0x89, b'P', b'N', b'G', 0xff, 0xfe].repeat(20));
+        assert_eq!(artifact_text(&png), None);
+        assert_eq!(artifact_text("plain").as_deref(), Some("plain"));
+    }
+}
@@ -7,7 +7,7 @@
 //! {"name":"final","arguments":{"message":"done"}}
 //! ```
 
-use super::{StreamParseResult, ToolCallParser};
+use super::{PartialCallTracker, StreamParseResult, ToolCallParser};
 use distri_types::{AgentError, ToolCall};
 use regex::Regex;
 use serde::{Deserialize, Serialize};
@@ -18,6 +18,7 @@ pub struct JsonParser {
     buffer: String,
     partial_tool_calls: Vec<ToolCall>,
     valid_tool_names: Vec<String>,
+    streaming: PartialCallTracker,
 }
 
 /// JSONL format tool call structure
@@ -118,7 +119,7 @@ impl ToolCallParser for JsonParser {
                 let start_pos = self.buffer[..self.buffer.find(line).unwrap_or(0)].len();
 
                 new_tool_calls.push(ToolCall {
-                    tool_call_id: uuid::Uuid::new_v4().to_string(),
+                    tool_call_id: self.streaming.complete(&tool_call.name),
                     tool_name: tool_call.name,
                     input: tool_call.arguments,
                 });
@@ -142,6 +143,11 @@ impl ToolCallParser for JsonParser {
         let has_partial_tool_call =
             self.buffer.trim_start().starts_with('{') && !self.buffer.trim().is_empty();
 
+        let mut tool_call_deltas = Vec::new();
+        if let Some((name, args)) = Self::partial_tool_call(&self.buffer) {
+            self.streaming.update(&name, args, &mut tool_call_deltas);
+        }
+
         Ok(StreamParseResult {
             new_tool_calls,
             stripped_content_blocks: if stripped_content_blocks.is_empty() {
@@ -150,6 +156,7 @@ impl ToolCallParser for JsonParser {
                 Some(stripped_content_blocks)
             },
             has_partial_tool_call,
+            tool_call_deltas,
         })
     }
 
@@ -159,7 +166,10 @@ impl ToolCallParser for JsonParser {
         if !self.buffer.trim().is_empty() {
             // Try to parse any remaining content
             if let Ok(tool_calls) = self.parse(&self.buffer) {
-                final_tool_calls.extend(tool_calls);
+                final_tool_calls.extend(tool_calls.into_iter().map(|mut call| {
+                    call.tool_call_id = self.streaming.complete(&call.tool_name);
+                    call
+                }));
             }
         }
 
@@ -172,6 +182,7 @@ impl ToolCallParser for JsonParser {
     fn reset(&mut self) {
         self.buffer.clear();
         self.partial_tool_calls.clear();
+        self.streaming.reset();
     }
 }
 
@@ -181,8 +192,23 @@ impl JsonParser {
             buffer: String::new(),
             partial_tool_calls: Vec::new(),
             valid_tool_names,
+            streaming: PartialCallTracker::default(),
         }
     }
+
+    /// The tool call line still being written: its name, once complete,
+    /// and the argument text so far.
+    fn partial_tool_call(buffer: &str) -> Option<(String, &str)> {
+        let line = buffer
+            .lines()
+            .map(str::trim_start)
+            .find(|line| line.starts_with('{'))?;
+        let name_re = Regex::new(r#""name"\s*:\s*"((?:[^"\\]|\\.)*)""#).unwrap();
+        let name = name_re.captures(line)?.get(1)?.as_str().to_string();
+        let args_re = Regex::new(r#""arguments"\s*:\s*"#).unwrap();
+        let args = args_re.find(line).map_or("", |m| &line[m.end()..]);
+        Some((name, args))
+    }
     /// Extract JSONL content from tool_calls code blocks
     fn find_tool_calls_block<'a>(&self, text: &'a str) -> Option<&'a str> {
         // This regex matches a markdown code block with tool_calls, e.g. ```tool_calls ... ```
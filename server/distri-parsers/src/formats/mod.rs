@@ -6,7 +6,7 @@
 use distri_types::{AgentError, ToolCall, ToolCallFormat};
 
 /// Result of streaming parsing operation
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct StreamParseResult {
     /// Newly completed tool calls from this chunk
     pub new_tool_calls: Vec<ToolCall>,
@@ -14,6 +14,73 @@ pub struct StreamParseResult {
     pub stripped_content_blocks: Option<Vec<(usize, String)>>,
     /// Whether the parser is currently in the middle of parsing a tool call
     pub has_partial_tool_call: bool,
+    /// Progress of the tool call still being written, if any
+    pub tool_call_deltas: Vec<ToolCallDelta>,
+}
+
+/// Progress of a tool call that is still streaming
+#[derive(Debug, Clone, PartialEq)]
+pub enum ToolCallDelta {
+    /// The call's name is known. `tool_call_id` is the id the completed call
+    /// will carry.
+    Start {
+        tool_call_id: String,
+        tool_name: String,
+    },
+    /// More of the call's raw argument text
+    Args { tool_call_id: String, delta: String },
+}
+
+/// Tracks the tool call currently being streamed, so its name and argument
+/// text can be reported before the call is complete.
+#[derive(Debug, Default)]
+pub(crate) struct PartialCallTracker {
+    /// Id, name and bytes of argument text reported so far.
+    current: Option<(String, String, usize)>,
+}
+
+impl PartialCallTracker {
+    /// Report the partial call `tool_name` whose argument text so far is `args`.
+    pub(crate) fn update(&mut self, tool_name: &str, args: &str, out: &mut Vec<ToolCallDelta>) {
+        if self
+            .current
+            .as_ref()
+            .is_none_or(|(_, name, _)| name != tool_name)
+        {
+            let tool_call_id = uuid::Uuid::new_v4().to_string();
+            out.push(ToolCallDelta::Start {
+                tool_call_id: tool_call_id.clone(),
+                tool_name: tool_name.to_string(),
+            });
+            self.current = Some((tool_call_id, tool_name.to_string(), 0));
+        }
+        if let Some((tool_call_id, _, reported)) = self.current.as_mut()
+            && args.len() > *reported
+            && args.is_char_boundary(*reported)
+        {
+            out.push(ToolCallDelta::Args {
+                tool_call_id: tool_call_id.clone(),
+                delta: args[*reported..].to_string(),
+            });
+            *reported = args.len();
+        }
+    }
+
+    /// Id for a completed call named `tool_name`: the streamed call's id if
+    /// it was this call, otherwise a fresh one.
+    pub(crate) fn complete(&mut self, tool_name: &str) -> String {
+        match self.current.take() {
+            Some((tool_call_id, name, _)) if name == tool_name => tool_call_id,
+            other => {
+                self.current = other;
+                uuid::Uuid::new_v4().to_string()
+            }
+        }
+    }
+
+    pub(crate) fn reset(&mut self) {
+        self.current = None;
+    }
 }
 
 /// Unified trait for tool call parsers with streaming support
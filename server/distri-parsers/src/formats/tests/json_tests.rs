@@ -1,7 +1,7 @@
 //! Tests for new JSON parser (JSONL)
 
-use super::super::ToolCallParser;
 use super::super::json::JsonParser;
+use super::super::{ToolCallDelta, ToolCallParser};
 use super::TestData;
 
 #[test]
@@ -152,3 +152,44 @@ fn test_jsonl_example_usage() {
     assert!(example.contains(r#""name":"#));
     assert!(example.contains(r#""arguments":"#));
 }
+
+#[test]
+fn test_jsonl_streaming_reports_tool_call_deltas() {
+    let mut parser = JsonParser::new(TestData::get_builtin_tool_names());
+    let chunks = [
+        "```tool_calls\n{\"na",
+        "me\":\"search\",\"argu",
+        "ments\":{\"query\":\"ru",
+        "st\"}}\n```",
+    ];
+
+    let mut deltas = Vec::new();
+    let mut tool_calls = Vec::new();
+    for chunk in chunks {
+        let result = parser.process_chunk(chunk).unwrap();
+        deltas.extend(result.tool_call_deltas);
+        tool_calls.extend(result.new_tool_calls);
+    }
+
+    let tool_call_id = match deltas.as_slice() {
+        [
+            ToolCallDelta::Start {
+                tool_call_id,
+                tool_name,
+            },
+            ToolCallDelta::Args {
+                tool_call_id: args_id,
+                delta,
+            },
+        ] => {
+            assert_eq!(tool_name, "search");
+            assert_eq!(args_id, tool_call_id);
+            assert_eq!(delta, r#"{"query":"ru"#);
+            tool_call_id
+        }
+        other => panic!("unexpected deltas {:?}", other),
+    };
+    assert_eq!(tool_calls.len(), 1);
+    assert_eq!(&tool_calls[0].tool_call_id, tool_call_id);
+    assert_eq!(tool_calls[0].input["query"], "rust");
+}
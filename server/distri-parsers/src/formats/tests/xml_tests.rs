@@ -1,7 +1,7 @@
 //! Tests for new XML parser
 
-use super::super::ToolCallParser;
 use super::super::xml::XmlParser;
+use super::super::{ToolCallDelta, ToolCallParser};
 use super::TestData;
 
 #[test]
@@ -528,3 +528,47 @@ fn test_xml_example_usage() {
     assert!(example.contains("<search>"));
     assert!(example.contains("<query>"));
 }
+
+#[test]
+fn test_xml_streaming_reports_tool_call_deltas() {
+    let mut parser = XmlParser::new(TestData::get_builtin_tool_names());
+    let chunks = [
+        "Let me search. <sea",
+        "rch>\n<query>rust",
+        " async</query>\n<li",
+        "mit>5</limit>\n</search>",
+    ];
+
+    let mut deltas = Vec::new();
+    let mut tool_calls = Vec::new();
+    for chunk in chunks {
+        let result = parser.process_chunk(chunk).unwrap();
+        deltas.extend(result.tool_call_deltas);
+        tool_calls.extend(result.new_tool_calls);
+    }
+
+    let Some(ToolCallDelta::Start {
+        tool_call_id,
+        tool_name,
+    }) = deltas.first()
+    else {
+        panic!("expected a start delta first, got {:?}", deltas);
+    };
+    assert_eq!(tool_name, "search");
+    let args: String = deltas[1..]
+        .iter()
+        .map(|d| match d {
+            ToolCallDelta::Args {
+                tool_call_id: id,
+                delta,
+            } if id == tool_call_id => delta.as_str(),
+            other => panic!("unexpected delta {:?}", other),
+        })
+        .collect();
+    // The tag still being written (`<li`) is held back.
+    assert_eq!(args, "\n<query>rust async</query>\n");
+
+    assert_eq!(tool_calls.len(), 1);
+    assert_eq!(&tool_calls[0].tool_call_id, tool_call_id);
+    assert_eq!(tool_calls[0].input["limit"], 5);
+}
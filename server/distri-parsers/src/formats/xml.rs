@@ -13,7 +13,7 @@
 //! Each tool call is represented by its own top-level XML element,
 //! with parameters as nested elements.
 
-use super::{StreamParseResult, ToolCallParser};
+use super::{PartialCallTracker, StreamParseResult, ToolCallParser};
 use distri_types::{AgentError, ToolCall};
 use serde_json::{Map, Value};
 
@@ -21,6 +21,7 @@ pub struct XmlParser {
     buffer: String,
     partial_tool_calls: Vec<ToolCall>,
     valid_tool_names: Vec<String>,
+    streaming: PartialCallTracker,
 }
 
 impl ToolCallParser for XmlParser {
@@ -88,7 +89,7 @@ impl ToolCallParser for XmlParser {
                         let match_end = match_start + robust_call.raw_content.len();
 
                         let tool_call = ToolCall {
-                            tool_call_id: uuid::Uuid::new_v4().to_string(),
+                            tool_call_id: self.streaming.complete(&tool_name),
                             tool_name: tool_name.clone(),
                             input: robust_call.parameters,
                         };
@@ -114,6 +115,11 @@ impl ToolCallParser for XmlParser {
         // Check if we have partial tool calls
         let has_partial_tool_call = self.buffer.contains('<') && !self.buffer.trim().is_empty();
 
+        let mut tool_call_deltas = Vec::new();
+        if let Some((name, args)) = self.partial_tool_call(&self.buffer) {
+            self.streaming.update(&name, args, &mut tool_call_deltas);
+        }
+
         Ok(StreamParseResult {
             new_tool_calls,
             stripped_content_blocks: if stripped_content_blocks.is_empty() {
@@ -122,6 +128,7 @@ impl ToolCallParser for XmlParser {
                 Some(stripped_content_blocks)
             },
             has_partial_tool_call,
+            tool_call_deltas,
         })
     }
 
@@ -139,6 +146,10 @@ impl ToolCallParser for XmlParser {
                 }
             }
         }
+        // A call that was streaming keeps the id its deltas carried.
+        for call in &mut final_tool_calls {
+            call.tool_call_id = self.streaming.complete(&call.tool_name);
+        }
 
         // Include any partial tool calls we've been accumulating
         final_tool_calls.append(&mut self.partial_tool_calls);
@@ -149,6 +160,7 @@ impl ToolCallParser for XmlParser {
     fn reset(&mut self) {
         self.buffer.clear();
         self.partial_tool_calls.clear();
+        self.streaming.reset();
     }
 }
 
@@ -165,7 +177,31 @@ impl XmlParser {
             buffer: String::new(),
             partial_tool_calls: Vec::new(),
             valid_tool_names,
+            streaming: PartialCallTracker::default(),
+        }
+    }
+
+    /// The tool call still open in `buffer`: its name and the argument text
+    /// so far, up to a tag that is still being written.
+    fn partial_tool_call<'a>(&self, buffer: &'a str) -> Option<(String, &'a str)> {
+        let re = regex::Regex::new(r"<(\w+)>").unwrap();
+        for caps in re.captures_iter(buffer) {
+            let name = caps.get(1)?.as_str();
+            if !self.valid_tool_names.is_empty() && !self.valid_tool_names.iter().any(|n| n == name)
+            {
+                continue;
+            }
+            let args = &buffer[caps.get(0)?.end()..];
+            if args.contains(&format!("</{}>", name)) {
+                continue;
+            }
+            let args = match args.rfind('<') {
+                Some(i) if !args[i..].contains('>') => &args[..i],
+                _ => args,
+            };
+            return Some((name.to_string(), args));
         }
+        None
     }
 
     /// Extract tool names from content by finding XML tags (both opening and closing)
@@ -8,8 +8,8 @@ pub mod agent_response;
 
 // New trait-based parser formats
 pub mod formats;
-pub use formats::StreamParseResult;
 pub use formats::{ParserFactory, ToolCallParser};
+pub use formats::{StreamParseResult, ToolCallDelta};
 
 #[cfg(test)]
 mod streaming_test;
@@ -28,6 +28,10 @@ pub struct Cli {
     #[clap(long, help = "Path to a UI dist directory to serve under /ui/")]
     pub ui_dist: Option<std::path::PathBuf>,
 
+    /// `distri.yaml` profile to apply (e.g. dev, staging, prod)
+    #[clap(long, env = "DISTRI_PROFILE")]
+    pub profile: Option<String>,
+
     /// Emit the OpenAPI spec to <PATH> as YAML and exit.
     #[clap(long, help = "Write the OpenAPI spec to PATH as YAML and exit")]
     pub emit_openapi: Option<std::path::PathBuf>,
@@ -9,6 +9,58 @@
 //!   a single combined file (e.g. a GitHub-release artifact).
 //! - `default_model` — seeded into the runtime store when none is set yet.
 //! - `agents` — agent definition files to load and register on startup.
+//! - `llm_audit` — record every outbound LLM request/response (with PII
+//!   redaction and a retention window) for `GET /v1/audit/llm`.
+//! - `sql_connections` — databases the `sql_query` / `sql_schema` tools
+//!   may query (read-only unless stated otherwise).
+//! - `background_jobs` — queue non-blocking `message/send` requests and run
+//!   them on background workers, independent of the HTTP connection.
+//! - `prompt_policy` — a workspace policy layered into every agent's system
+//!   prompt, after the agent's own instructions.
+//! - `agent_registry` — publish the agent cards to an A2A registry and keep
+//!   them in sync as agents change.
+//! - `k8s` — settings of the built-in `k8s` MCP server (kubeconfig, allowed
+//!   namespaces, opt-in mutations). Read-only defaults when absent.
+//! - `calendar` — provider, connection and default timezone of the built-in
+//!   `calendar` MCP server. Google through the `google` connection when
+//!   absent.
+//! - `mcp_servers` — external MCP servers (stdio commands or remote
+//!   endpoints) with their auth provider, start mode and health check.
+//! - `hibernation` — keep threads' MCP connections and browser sessions
+//!   between messages and release them once a thread goes idle.
+//! - `python_exec` — interpreter, sandbox, package allowlist and limits of
+//!   the `python_exec` tool. Defaults apply when absent.
+//! - `embeddings` — provider, model, batch size and cache of the shared
+//!   embedding service behind `POST /v1/embeddings`; `index_artifacts`
+//!   enables the `semantic_search_artifacts` tool.
+//! - `warm_sessions` — keep the tool registries of recently used threads in
+//!   memory between messages; `POST /v1/threads/{id}/warm` pins a thread.
+//! - `thread_archive` — move the history of threads not updated for
+//!   `retention_days` to the session object store.
+//! - `tool_redaction` — regex and JSONPath rules that redact tool output
+//!   before it reaches the model, for every tool or per tool.
+//! - `admission` — cap concurrent agent runs, queue the overflow and answer
+//!   `429` with `Retry-After` once the queue is full.
+//! - `user_quotas` — per-user limits on concurrent runs, runs per day and
+//!   tokens per day.
+//! - `event_export` — publish agent events to NATS or Kafka, as JSON or
+//!   CloudEvents.
+//! - `capability_probe` — test local models' tool calling once and fall back
+//!   to the XML tool format for models that get it wrong.
+//! - `egress` — allow and deny lists of the hosts tools and plugins may
+//!   reach, globally and per agent.
+//! - `stores` — metadata and session store types and database URLs. The
+//!   local SQLite stores when absent.
+//! - `secrets` — dotenv files the secret resolver reads, on top of the
+//!   process environment.
+//! - `profiles` — named overrides of the keys above (`dev`, `staging`,
+//!   `prod`, ...), selected with `--profile` or `DISTRI_PROFILE`.
+//!
+//! The file is versioned (`version`, see [`distri_types::workspace_config`]).
+//! Older files are migrated in memory on load, with a warning for each
+//! deprecated or unknown key; `distri config migrate` rewrites them. The
+//! selected profile is merged in after the migration, see
+//! [`workspace_config::apply_profile`].
 //!
 //! Provider extensions are also picked up, with no `distri.yaml` needed,
 //! from a `providers/` directory in the workspace and from the
@@ -17,9 +69,28 @@
 
 use anyhow::{Context, Result};
 use distri_core::AgentOrchestrator;
-use distri_types::configuration::AgentConfig;
+use distri_types::agent_registry::AgentRegistryConfig;
+use distri_types::api::audit::LlmAuditConfig;
+use distri_types::calendar::CalendarMcpConfig;
+use distri_types::capability_probe::CapabilityProbeConfig;
+use distri_types::configuration::{AdmissionLimits, AgentConfig, StoreConfig};
+use distri_types::crawl::CrawlMcpConfig;
+use distri_types::egress::EgressPolicyConfig;
+use distri_types::embeddings::EmbeddingsConfig;
+use distri_types::event_export::EventExportConfig;
+use distri_types::hibernation::HibernationConfig;
+use distri_types::jobs::BackgroundJobsConfig;
+use distri_types::k8s::K8sMcpConfig;
+use distri_types::mcp_servers::McpServerConfig;
 use distri_types::model_catalog::{self, ProviderCatalogEntry};
+use distri_types::python_exec::PythonExecConfig;
+use distri_types::sql::SqlConnectionConfig;
 use distri_types::stores::UpsertProviderRequest;
+use distri_types::thread_archive::ThreadArchiveConfig;
+use distri_types::tool_redaction::ToolRedactionConfig;
+use distri_types::user_quotas::UserQuotaLimits;
+use distri_types::warm_sessions::WarmSessionsConfig;
+use distri_types::workspace_config;
 use serde::Deserialize;
 use std::path::Path;
 
@@ -34,6 +105,8 @@ const CATALOG_ENV: &str = "DISTRI_MODEL_CATALOG";
 #[derive(Debug, Default, Deserialize)]
 #[serde(default)]
 pub struct DistriYamlConfig {
+    /// Schema version of the file. Unset means version 1.
+    pub version: Option<u32>,
     /// Provider/model definitions inline, in the catalog section format.
     pub model_providers: Vec<ProviderCatalogEntry>,
     /// A directory of per-provider catalog files, or a single combined file.
@@ -44,6 +117,64 @@ pub struct DistriYamlConfig {
     pub default_model: Option<String>,
     /// Agent definition files to load and register on startup.
     pub agents: Vec<AgentSeed>,
+    /// LLM audit-log settings. Auditing is off when absent.
+    pub llm_audit: Option<LlmAuditConfig>,
+    /// Databases exposed to agents through the `sql_query` tool.
+    pub sql_connections: Vec<SqlConnectionConfig>,
+    /// Background job queue settings. Non-blocking sends run inline when
+    /// absent.
+    pub background_jobs: Option<BackgroundJobsConfig>,
+    /// Workspace policy prompt (a handlebars template) added to every
+    /// agent's system prompt.
+    pub prompt_policy: Option<String>,
+    /// A2A registry the agent cards are published to. Nothing is published
+    /// when absent.
+    pub agent_registry: Option<AgentRegistryConfig>,
+    /// Settings of the `k8s` MCP server. The server is always available;
+    /// it is read-only and uses the discovered kubeconfig when absent.
+    pub k8s: Option<K8sMcpConfig>,
+    /// Settings of the `crawl` MCP server. The server is always available;
+    /// it uses the default cache, rate limit and robots.txt policy when
+    /// absent.
+    pub crawl: Option<CrawlMcpConfig>,
+    /// Settings of the `calendar` MCP server. The server is always
+    /// available; it uses the `google` connection when absent.
+    pub calendar: Option<CalendarMcpConfig>,
+    /// External MCP servers agents can use as `tools.mcp[].server`.
+    pub mcp_servers: Vec<McpServerConfig>,
+    /// Idle thread hibernation. Thread resources are created per run when
+    /// absent.
+    pub hibernation: Option<HibernationConfig>,
+    /// Settings of the `python_exec` tool.
+    pub python_exec: Option<PythonExecConfig>,
+    /// Settings of the embedding service.
+    pub embeddings: Option<EmbeddingsConfig>,
+    /// Warm session cache. Every run assembles its tool registry when
+    /// absent.
+    pub warm_sessions: Option<WarmSessionsConfig>,
+    /// Periodic thread archival. Threads are only archived by hand when
+    /// absent.
+    pub thread_archive: Option<ThreadArchiveConfig>,
+    /// Redaction rules for tool output. Tool output is passed on as is when
+    /// absent.
+    pub tool_redaction: Option<ToolRedactionConfig>,
+    /// Concurrent run limits of the HTTP server. Every run is admitted when
+    /// absent.
+    pub admission: Option<AdmissionLimits>,
+    /// Per-user run and token limits. Users are not limited when absent.
+    pub user_quotas: Option<UserQuotaLimits>,
+    /// Broker agent events are published to. Nothing is published when
+    /// absent.
+    pub event_export: Option<EventExportConfig>,
+    /// Model capability probing. Agents use the tool format they declare
+    /// when absent.
+    pub capability_probe: Option<CapabilityProbeConfig>,
+    /// Hosts tools and plugins may reach. Any host when absent.
+    pub egress: Option<EgressPolicyConfig>,
+    /// Metadata and session stores. Persistent local SQLite when absent.
+    pub stores: Option<StoreConfig>,
+    /// Where secrets are read from besides the secret store.
+    pub secrets: SecretsConfig,
 }
 
 /// A single agent seed entry.
@@ -53,20 +184,101 @@ pub struct AgentSeed {
     pub file: String,
 }
 
-/// Load `distri.yaml` from the workspace directory, if present.
-pub fn load(workspace_path: &Path) -> Result<Option<DistriYamlConfig>> {
+/// Secret sources besides the secret store.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct SecretsConfig {
+    /// Dotenv files, relative to the workspace directory, loaded into the
+    /// environment the secret resolver falls back to. Variables that are
+    /// already set, including from the workspace `.env`, are kept.
+    pub env_files: Vec<String>,
+}
+
+/// Load `distri.yaml` from the workspace directory, if present, with
+/// `profile` merged in.
+pub fn load(workspace_path: &Path, profile: Option<&str>) -> Result<Option<DistriYamlConfig>> {
     let path = workspace_path.join(DISTRI_YAML);
     if !path.exists() {
+        if let Some(profile) = profile {
+            anyhow::bail!(
+                "profile `{profile}` was requested but {} does not exist",
+                path.display()
+            );
+        }
         return Ok(None);
     }
     let raw =
         std::fs::read_to_string(&path).with_context(|| format!("reading {}", path.display()))?;
-    let config: DistriYamlConfig =
-        serde_yaml::from_str(&raw).with_context(|| format!("parsing {}", path.display()))?;
-    tracing::info!("loaded {}", path.display());
+    let config = parse(&raw, profile).with_context(|| format!("parsing {}", path.display()))?;
+    match profile {
+        Some(profile) => tracing::info!("loaded {} (profile {profile})", path.display()),
+        None => tracing::info!("loaded {}", path.display()),
+    }
     Ok(Some(config))
 }
 
+/// Parse `distri.yaml`, migrating older schema versions and warning about
+/// keys that would otherwise be dropped silently.
+fn parse(raw: &str, profile: Option<&str>) -> Result<DistriYamlConfig> {
+    let mut value: serde_yaml::Value = serde_yaml::from_str(raw)?;
+    for deprecated in workspace_config::deprecated_keys(&value) {
+        match deprecated.replacement {
+            Some(replacement) => tracing::warn!(
+                "{DISTRI_YAML}: `{}` is deprecated, use `{replacement}` ({})",
+                deprecated.key,
+                deprecated.hint
+            ),
+            None => tracing::warn!(
+                "{DISTRI_YAML}: `{}` is deprecated ({})",
+                deprecated.key,
+                deprecated.hint
+            ),
+        }
+    }
+    let migration = workspace_config::migrate(&mut value)?;
+    if !migration.is_noop() {
+        tracing::warn!(
+            "{DISTRI_YAML} uses config version {}; run `distri config migrate` to upgrade it to {}",
+            migration.from_version,
+            migration.to_version
+        );
+    }
+    workspace_config::apply_profile(&mut value, profile)?;
+    for key in workspace_config::unknown_keys(&value) {
+        tracing::warn!("{DISTRI_YAML}: unknown key `{key}` is ignored");
+    }
+    Ok(serde_yaml::from_value(value)?)
+}
+
+/// Load the `secrets.env_files` into the process environment.
+pub fn load_secret_env_files(
+    workspace_path: &Path,
+    config: Option<&DistriYamlConfig>,
+) -> Result<()> {
+    let Some(config) = config else {
+        return Ok(());
+    };
+    for file in &config.secrets.env_files {
+        let path = workspace_path.join(file);
+        dotenv::from_path(&path)
+            .with_context(|| format!("loading secrets from {}", path.display()))?;
+        tracing::info!("loaded secrets from {}", path.display());
+    }
+    Ok(())
+}
+
+/// The `llm_audit` settings to build the audit store with, when enabled.
+/// Records are kept in the metadata database, so they outlive restarts.
+pub fn llm_audit_config(config: Option<&DistriYamlConfig>) -> Option<LlmAuditConfig> {
+    let audit = config?.llm_audit.as_ref().filter(|a| a.enabled)?;
+    tracing::info!(
+        "LLM audit log enabled (redact: {}, retention_days: {:?})",
+        audit.redact,
+        audit.retention_days
+    );
+    Some(audit.clone())
+}
+
 /// Gather provider/model extensions from every source and fold them into the
 /// global provider registry. Call once, before the server serves the
 /// catalog. Sources, lowest-to-highest precedence on `id` collisions:
@@ -201,6 +413,8 @@ async fn seed_agents(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use distri_types::event_export::{BrokerConfig, EventFormat};
+    use distri_types::python_exec::PythonSandbox;
 
     /// A full `distri.yaml` deserializes into all sections, including the
     /// catalog section format for inline providers.
@@ -223,6 +437,54 @@ model_providers_path: providers
 default_model: openai/gpt-4.1-mini
 agents:
   - file: agents/coder.md
+llm_audit:
+  enabled: true
+  retention_days: 30
+sql_connections:
+  - { name: analytics, driver: postgres, url: "postgres://ro@db/analytics" }
+background_jobs:
+  workers: 4
+hibernation:
+  idle_secs: 300
+python_exec:
+  sandbox: firejail
+  allowed_packages: [numpy, pandas, sklearn]
+  memory_mb: 2048
+embeddings:
+  model: text-embedding-3-large
+  batch_size: 32
+warm_sessions:
+  capacity: 16
+thread_archive:
+  retention_days: 30
+tool_redaction:
+  rules:
+    - { pattern: "sk-[A-Za-z0-9]+", label: api_key }
+  tools:
+    crm__get_customer:
+      - { path: "$.email", label: email }
+admission:
+  max_concurrent_runs: 8
+  max_queued: 32
+user_quotas:
+  max_concurrent_tasks: 2
+  max_tokens_per_day: 100000
+event_export:
+  broker:
+    type: kafka
+    brokers: ["localhost:9092"]
+    topic: distri-events
+  format: cloudevents
+  events: [run_started, run_finished, run_error]
+capability_probe:
+  cache_file: .distri/model_capabilities.json
+egress:
+  deny: ["169.254.169.254", "*.internal"]
+  agents:
+    researcher:
+      allow: ["*.wikipedia.org"]
+prompt_policy: |
+  Never share credentials.
 "#;
         let config: DistriYamlConfig = serde_yaml::from_str(yaml).expect("distri.yaml parses");
         assert_eq!(config.model_providers.len(), 1);
@@ -233,6 +495,137 @@ agents:
         assert_eq!(config.default_model.as_deref(), Some("openai/gpt-4.1-mini"));
         assert_eq!(config.agents.len(), 1);
         assert_eq!(config.agents[0].file, "agents/coder.md");
+        let audit = config.llm_audit.as_ref().expect("llm_audit section");
+        assert!(audit.enabled && audit.redact, "redaction defaults on");
+        assert_eq!(audit.retention_days, Some(30));
+        assert!(llm_audit_config(Some(&config)).is_some());
+        assert_eq!(config.sql_connections.len(), 1);
+        assert!(config.sql_connections[0].read_only, "read-only by default");
+        let jobs = config.background_jobs.as_ref().expect("background_jobs");
+        assert_eq!(jobs.workers, 4);
+        assert_eq!(jobs.max_attempts, 3);
+        let hibernation = config.hibernation.as_ref().expect("hibernation");
+        assert_eq!(hibernation.idle_secs, 300);
+        assert_eq!(hibernation.sweep_interval_secs, 60);
+        let python = config.python_exec.as_ref().expect("python_exec");
+        assert_eq!(python.sandbox, PythonSandbox::Firejail);
+        assert_eq!(python.allowed_packages, ["numpy", "pandas", "sklearn"]);
+        assert_eq!((python.memory_mb, python.timeout_secs), (2048, 30));
+        let embeddings = config.embeddings.as_ref().expect("embeddings");
+        assert_eq!(embeddings.model, "text-embedding-3-large");
+        assert_eq!(embeddings.batch_size, 32);
+        assert!(embeddings.cache, "cache defaults on");
+        let warm = config.warm_sessions.as_ref().expect("warm_sessions");
+        assert_eq!(warm.capacity, 16);
+        let archive = config.thread_archive.as_ref().expect("thread_archive");
+        assert_eq!(archive.retention_days, 30);
+        assert_eq!(archive.sweep_interval_secs, 3600);
+        let redaction = config.tool_redaction.as_ref().expect("tool_redaction");
+        assert_eq!(redaction.rules[0].label.as_deref(), Some("api_key"));
+        assert_eq!(
+            redaction.tools["crm__get_customer"][0].path.as_deref(),
+            Some("$.email")
+        );
+        let admission = config.admission.as_ref().expect("admission");
+        assert_eq!(admission.max_concurrent_runs, Some(8));
+        assert_eq!(admission.max_queued, 32);
+        assert_eq!(
+            (admission.queue_timeout_secs, admission.retry_after_secs),
+            (30, 5)
+        );
+        let quotas = config.user_quotas.as_ref().expect("user_quotas");
+        assert_eq!(quotas.max_concurrent_tasks, Some(2));
+        assert_eq!(quotas.max_tasks_per_day, None);
+        assert_eq!(quotas.max_tokens_per_day, Some(100_000));
+        let export = config.event_export.as_ref().expect("event_export");
+        assert!(matches!(
+            &export.broker,
+            BrokerConfig::Kafka { topic, partitions: 1, .. } if topic == "distri-events"
+        ));
+        assert_eq!(export.format, EventFormat::Cloudevents);
+        assert_eq!(export.buffer_size, 10_000);
+        export.validate().unwrap();
+        let probe = config.capability_probe.as_ref().expect("capability_probe");
+        assert_eq!(probe.providers, ["openai_compat"]);
+        assert_eq!(
+            probe.cache_file.as_deref(),
+            Some(".distri/model_capabilities.json")
+        );
+        assert_eq!(probe.timeout_secs, 60);
+        let egress = config.egress.as_ref().expect("egress");
+        assert_eq!(egress.deny, ["169.254.169.254", "*.internal"]);
+        assert_eq!(egress.agents["researcher"].allow, ["*.wikipedia.org"]);
+        egress.validate().unwrap();
+        assert_eq!(
+            config.prompt_policy.as_deref(),
+            Some("Never share credentials.\n")
+        );
+    }
+
+    /// A version 1 file still loads: `model_settings` becomes `default_model`.
+    #[test]
+    fn migrates_legacy_model_settings() {
+        let yaml = r#"
+name: example-workspace
+model_settings:
+  model: gpt-4.1-mini
+  provider:
+    name: openai
+agents:
+  - file: agents/coder.md
+"#;
+        let config = parse(yaml, None).expect("legacy config parses");
+        assert_eq!(config.version, Some(workspace_config::CURRENT_VERSION));
+        assert_eq!(config.default_model.as_deref(), Some("openai/gpt-4.1-mini"));
+        assert_eq!(config.agents.len(), 1);
+        assert!(parse("version: 99\n", None).is_err());
+    }
+
+    /// The selected profile overrides the model, stores, MCP servers and
+    /// secret files of the base config.
+    #[test]
+    fn applies_the_selected_profile() {
+        let yaml = r#"
+version: 2
+default_model: openai/gpt-4.1-mini
+mcp_servers:
+  - { name: github, transport: stdio, command: github-mcp }
+profiles:
+  prod:
+    default_model: anthropic/claude-sonnet-4
+    mcp_servers: []
+    stores:
+      metadata:
+        store_type: { type: postgres }
+        db_config: { database_url: "postgres://distri@db/distri" }
+      session:
+        ephemeral: false
+        store_type: { type: postgres }
+        db_config: { database_url: "postgres://distri@db/distri" }
+    secrets:
+      env_files: [.env.prod]
+"#;
+        let dev = parse(yaml, None).expect("base config parses");
+        assert_eq!(dev.default_model.as_deref(), Some("openai/gpt-4.1-mini"));
+        assert_eq!(dev.mcp_servers.len(), 1);
+        assert!(dev.stores.is_none());
+        assert!(dev.secrets.env_files.is_empty());
+
+        let prod = parse(yaml, Some("prod")).expect("prod profile parses");
+        assert_eq!(
+            prod.default_model.as_deref(),
+            Some("anthropic/claude-sonnet-4")
+        );
+        assert!(prod.mcp_servers.is_empty());
+        let stores = prod.stores.as_ref().expect("stores");
+        assert_eq!(
+            stores.metadata.db_config.as_ref().unwrap().database_url,
+            "postgres://distri@db/distri"
+        );
+        assert!(!stores.session.ephemeral);
+        assert_eq!(prod.secrets.env_files, [".env.prod"]);
+
+        assert!(parse(yaml, Some("staging")).is_err());
     }
 
     /// Every section is optional — an empty file is a valid (no-op) config.
@@ -243,5 +636,6 @@ agents:
         assert!(config.model_providers_path.is_none());
         assert!(config.default_model.is_none());
         assert!(config.agents.is_empty());
+        assert!(llm_audit_config(Some(&config)).is_none());
     }
 }
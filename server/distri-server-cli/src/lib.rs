@@ -4,7 +4,6 @@ use distri_core::{
     AgentOrchestratorBuilder,
 };
 use distri_types::browser::BrowsrClientConfig;
-use distri_types::configuration::AgentConfig;
 pub mod workspace;
 use std::{path::Path, sync::Arc};
 
@@ -15,10 +14,15 @@ mod seed;
 
 pub use cli::Cli;
 
-/// Initialize the orchestrator for the OSS server.
+/// User of the OSS server when no auth layer is in front of it.
+const LOCAL_USER_ID: &str = "local_dev_user";
+
+/// Initialize the orchestrator for the OSS server, with the `distri.yaml`
+/// settings of `profile`.
 pub async fn init_orchestrator(
     home_dir: &Path,
     workspace_path: &Path,
+    profile: Option<&str>,
 ) -> Result<Arc<AgentOrchestrator>> {
     use distri_types::configuration::StoreConfig;
 
@@ -32,13 +36,23 @@ pub async fn init_orchestrator(
     // DISTRI_MODEL_CATALOG) must be registered before the server serves the
     // catalog, so this happens up front; the default-model and agent seeds
     // are applied after the orchestrator is built.
-    let distri_config = distri_yaml::load(workspace_path)?;
+    let distri_config = distri_yaml::load(workspace_path, profile)?;
     distri_yaml::register_extensions(workspace_path, distri_config.as_ref());
+    distri_yaml::load_secret_env_files(workspace_path, distri_config.as_ref())?;
 
-    let mut store_config = StoreConfig::default();
-    store_config.session.ephemeral = false;
+    let store_config = match distri_config.as_ref().and_then(|c| c.stores.clone()) {
+        Some(store_config) => store_config,
+        None => {
+            let mut store_config = StoreConfig::default();
+            store_config.session.ephemeral = false;
+            store_config
+        }
+    };
 
-    let stores = distri_core::initialize_stores(&store_config).await?;
+    let stores = distri_core::StoreBuilder::new(store_config.clone())
+        .with_llm_audit(distri_yaml::llm_audit_config(distri_config.as_ref()))
+        .build()
+        .await?;
 
     let prompt_registry = Arc::new(PromptRegistry::with_defaults().await?);
 
@@ -67,41 +81,174 @@ pub async fn init_orchestrator(
         Arc::new(distri_filesystem::create_file_system(fs_config).await?)
     };
 
-    let orchestrator = AgentOrchestratorBuilder::default()
+    // `mcp_servers` from distri.yaml. Runs reach them through the pool
+    // provider; the eager ones are started once the orchestrator is built.
+    // Their prompts become templates of the prompt registry.
+    let declared_mcp = distri_core::servers::DeclaredMcpServers::new(
+        distri_config
+            .as_ref()
+            .map(|c| c.mcp_servers.clone())
+            .unwrap_or_default(),
+    )?
+    .with_secret_store(stores.secret_store.clone())
+    .with_prompt_registry(prompt_registry.clone());
+
+    // The embeddings cache lives in the workspace unless configured elsewhere.
+    let mut embeddings = distri_config
+        .as_ref()
+        .and_then(|c| c.embeddings.clone())
+        .unwrap_or_default();
+    let embeddings_cache = workspace_path.join(
+        embeddings
+            .cache_dir
+            .as_deref()
+            .unwrap_or(".distri/cache/embeddings"),
+    );
+    embeddings.cache_dir = Some(embeddings_cache.to_string_lossy().to_string());
+
+    let mut builder = AgentOrchestratorBuilder::default()
         .with_browser_config(BrowsrClientConfig::default())
         .with_stores(stores)
         .with_prompt_registry(prompt_registry)
         .with_store_config(store_config)
         .with_session_storage_path(workspace_path.join(".distri/session_storage"))
         .with_workspace_filesystem(workspace_fs)
-        .build()
-        .await?;
+        .with_workspace_root(workspace_path.to_path_buf())
+        .with_sql_connections(
+            distri_config
+                .as_ref()
+                .map(|c| c.sql_connections.clone())
+                .unwrap_or_default(),
+        )
+        .with_background_jobs(
+            distri_config
+                .as_ref()
+                .and_then(|c| c.background_jobs.clone()),
+        )
+        .with_prompt_policy(distri_config.as_ref().and_then(|c| c.prompt_policy.clone()))
+        .with_agent_registry(
+            distri_config
+                .as_ref()
+                .and_then(|c| c.agent_registry.clone()),
+        )
+        .with_hibernation(distri_config.as_ref().and_then(|c| c.hibernation.clone()))
+        .with_python_exec(distri_config.as_ref().and_then(|c| c.python_exec.clone()))
+        .with_embeddings(Some(embeddings))
+        .with_warm_sessions(distri_config.as_ref().and_then(|c| c.warm_sessions.clone()))
+        .with_thread_archive(
+            distri_config
+                .as_ref()
+                .and_then(|c| c.thread_archive.clone()),
+        )
+        .with_tool_redaction(
+            distri_config
+                .as_ref()
+                .and_then(|c| c.tool_redaction.clone()),
+        )
+        .with_event_export(distri_config.as_ref().and_then(|c| c.event_export.clone()))
+        .with_capability_probe(
+            distri_config
+                .as_ref()
+                .and_then(|c| c.capability_probe.clone()),
+        )
+        .with_egress_policy(distri_config.as_ref().and_then(|c| c.egress.clone()))
+        .with_eval_store(Arc::new(distri_core::FileEvalStore::new(
+            workspace_path.join(".distri/evals"),
+        )));
+    if !declared_mcp.is_empty() {
+        builder = builder.with_mcp_pool_provider(Arc::new(declared_mcp.clone()));
+    }
+    let orchestrator = builder.build().await?;
 
     let orchestrator = Arc::new(orchestrator);
+    let k8s = distri_config
+        .as_ref()
+        .and_then(|c| c.k8s.clone())
+        .unwrap_or_default();
+    distri_core::servers::registry::register_k8s_mcp_server(orchestrator.clone(), k8s).await;
+    let crawl = distri_config
+        .as_ref()
+        .and_then(|c| c.crawl.clone())
+        .unwrap_or_default();
+    distri_core::servers::registry::register_crawl_mcp_server(
+        orchestrator.clone(),
+        crawl,
+        workspace_path,
+    )
+    .await;
+    let calendar = distri_config
+        .as_ref()
+        .and_then(|c| c.calendar.clone())
+        .unwrap_or_default();
+    distri_core::servers::registry::register_calendar_mcp_server(orchestrator.clone(), calendar)
+        .await;
+    declared_mcp.start().await?;
+    let imported = declared_mcp
+        .import_env_sessions(
+            orchestrator.stores.tool_auth_store.clone(),
+            LOCAL_USER_ID,
+            |k| std::env::var(k).ok(),
+        )
+        .await?;
+    if !imported.is_empty() {
+        tracing::info!("imported env sessions for MCP servers {:?}", imported);
+    }
+    if let Some(runner) = distri_core::worker::BackgroundRunner::new(orchestrator.clone()) {
+        runner.start();
+    }
+    if let Some(hibernator) = distri_core::agent::hibernation::Hibernator::new(orchestrator.clone())
+    {
+        hibernator.start();
+    }
+    if let Some(archiver) = distri_core::agent::thread_archive::Archiver::new(orchestrator.clone())
+    {
+        archiver.start();
+    }
     seed::seed_bundled_defaults(orchestrator.as_ref()).await?;
-    register_workspace_agents(&orchestrator, workspace_path).await?;
+    register_workspace_agents(&orchestrator).await?;
+    register_workspace_commands(&orchestrator, workspace_path).await;
 
     if let Some(config) = &distri_config {
         distri_yaml::apply_runtime_seeds(config, orchestrator.as_ref(), workspace_path).await?;
     }
+    // Started once the workspace agents are registered, so the first sync
+    // publishes all of them.
+    if let Some(publisher) =
+        distri_core::a2a::registry::RegistryPublisher::new(orchestrator.clone())
+    {
+        publisher.start();
+    }
 
     Ok(orchestrator)
 }
 
-async fn register_workspace_agents(
-    orchestrator: &Arc<AgentOrchestrator>,
-    workspace_path: &Path,
-) -> Result<()> {
-    let agents_dir = workspace_path.join("agents");
-    if agents_dir.exists() {
-        let local_agents = distri_core::agent::load_agents_from_dir(&agents_dir).await?;
-        for definition in local_agents {
-            orchestrator
-                .stores
-                .agent_store
-                .register(AgentConfig::StandardAgent(definition))
-                .await?;
-        }
+/// Register the agents of the workspace `agents/` directory and of the
+/// member packages its `distri.toml` lists.
+async fn register_workspace_agents(orchestrator: &Arc<AgentOrchestrator>) -> Result<()> {
+    let build = orchestrator.build_workspace().await?;
+    for package in &build.packages {
+        tracing::info!(
+            "registered {} agent(s) of package {} {} ({})",
+            package.agents.len(),
+            package.package.name,
+            package.package.version,
+            package.path
+        );
     }
     Ok(())
 }
+
+/// Register user-defined slash commands from the workspace `commands/`
+/// directory (`*.md` with TOML frontmatter, or `*.toml`).
+async fn register_workspace_commands(orchestrator: &Arc<AgentOrchestrator>, workspace_path: &Path) {
+    let commands_dir = workspace_path.join("commands");
+    let commands = distri_types::channel_commands::load_slash_commands_from_dir(&commands_dir);
+    if !commands.is_empty() {
+        tracing::info!(
+            "registered {} slash command(s) from {}",
+            commands.len(),
+            commands_dir.display()
+        );
+        orchestrator.register_slash_commands(commands).await;
+    }
+}
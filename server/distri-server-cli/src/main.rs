@@ -30,10 +30,21 @@ async fn main() -> Result<()> {
     let workspace_path = distri_server_cli::workspace::resolve_workspace_path();
 
     // Initialize orchestrator
-    let orchestrator = init_orchestrator(&workspace_path, &workspace_path).await?;
+    let profile = cli.profile.as_deref();
+    let orchestrator = init_orchestrator(&workspace_path, &workspace_path, profile).await?;
 
+    let distri_config = distri_server_cli::distri_yaml::load(&workspace_path, profile)?;
+    let admission = distri_config
+        .as_ref()
+        .and_then(|config| config.admission.clone())
+        .unwrap_or_default();
+    let user_quotas = distri_config
+        .and_then(|config| config.user_quotas)
+        .unwrap_or_default();
     let server_config = distri_types::configuration::ServerConfig {
         base_url: format!("http://{}:{}/v1", cli.host, cli.port),
+        admission,
+        user_quotas,
         ..Default::default()
     };
 
@@ -0,0 +1,217 @@
+//! Admission control for agent runs (see [`AdmissionLimits`]).
+//!
+//! Every run — `message/send`, `message/stream`, WebSocket, MCP
+//! `tools/call`, task retry and thread regeneration — goes through
+//! [`admit_run`]: it takes a slot before it starts and holds it until its
+//! response or stream ends. Without a limit every run is admitted straight
+//! away. Runs of a user over one of the `user_quotas` are refused before
+//! they take a slot, with [`reject_quota`]; a run refused a slot is not
+//! counted against the user's daily quota.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use actix_web::HttpResponse;
+use distri_a2a::{JsonRpcError, JsonRpcResponse};
+use distri_core::agent::user_quotas::{QuotaPermit, UserQuotas};
+use distri_types::configuration::AdmissionLimits;
+use distri_types::user_quotas::QuotaExceeded;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+/// JSON-RPC error code sent with a rejected run.
+pub const OVERLOADED_CODE: i32 = -32029;
+
+/// JSON-RPC error code sent with a run refused by a user quota.
+pub const QUOTA_EXCEEDED_CODE: i32 = -32030;
+
+/// `Retry-After` of a run refused by the concurrent task quota, in seconds.
+const CONCURRENT_QUOTA_RETRY_AFTER_SECS: i64 = 5;
+
+/// Held by an admitted run; dropping it frees the slot.
+#[derive(Debug)]
+pub struct AdmissionPermit {
+    _slot: Option<OwnedSemaphorePermit>,
+}
+
+/// Why a run was not admitted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rejection {
+    /// `max_queued` runs were already waiting.
+    QueueFull,
+    /// No slot freed up within `queue_timeout_secs`.
+    TimedOut,
+}
+
+impl std::fmt::Display for Rejection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Rejection::QueueFull => write!(f, "server is at capacity; too many runs are queued"),
+            Rejection::TimedOut => write!(f, "server is at capacity; timed out waiting to start"),
+        }
+    }
+}
+
+/// Shared run slots and wait queue.
+#[derive(Debug, Clone)]
+pub struct Admission {
+    limits: AdmissionLimits,
+    slots: Option<Arc<Semaphore>>,
+    queued: Arc<AtomicUsize>,
+}
+
+impl Admission {
+    pub fn new(limits: AdmissionLimits) -> Self {
+        let slots = limits
+            .max_concurrent_runs
+            .map(|max| Arc::new(Semaphore::new(max.max(1))));
+        Self {
+            limits,
+            slots,
+            queued: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    /// Wait for a run slot, or reject when the queue is full or the wait
+    /// times out.
+    pub async fn admit(&self) -> Result<AdmissionPermit, Rejection> {
+        let Some(slots) = self.slots.clone() else {
+            return Ok(AdmissionPermit { _slot: None });
+        };
+        if let Ok(permit) = slots.clone().try_acquire_owned() {
+            return Ok(AdmissionPermit {
+                _slot: Some(permit),
+            });
+        }
+
+        let waiting = QueuedRun::enter(&self.queued);
+        if waiting.position >= self.limits.max_queued {
+            return Err(Rejection::QueueFull);
+        }
+        let timeout = Duration::from_secs(self.limits.queue_timeout_secs);
+        let acquired = tokio::time::timeout(timeout, slots.acquire_owned()).await;
+        drop(waiting);
+        match acquired {
+            Ok(Ok(permit)) => Ok(AdmissionPermit {
+                _slot: Some(permit),
+            }),
+            _ => Err(Rejection::TimedOut),
+        }
+    }
+
+    /// Runs currently executing.
+    pub fn running(&self) -> usize {
+        match (&self.slots, self.limits.max_concurrent_runs) {
+            (Some(slots), Some(max)) => max.max(1) - slots.available_permits(),
+            _ => 0,
+        }
+    }
+
+    /// Runs currently waiting for a slot.
+    pub fn queued(&self) -> usize {
+        self.queued.load(Ordering::SeqCst)
+    }
+
+    /// `429 Too Many Requests` with `Retry-After` and a JSON-RPC error body.
+    pub fn reject(&self, id: Option<serde_json::Value>, rejection: Rejection) -> HttpResponse {
+        tracing::warn!(
+            running = self.running(),
+            queued = self.queued(),
+            "rejecting run: {}",
+            rejection
+        );
+        HttpResponse::TooManyRequests()
+            .insert_header(("Retry-After", self.limits.retry_after_secs.to_string()))
+            .json(JsonRpcResponse::error(
+                id,
+                JsonRpcError::new(OVERLOADED_CODE, rejection.to_string()),
+            ))
+    }
+}
+
+/// A run counted in the wait queue until dropped, so the count is also
+/// released when the waiting request is cancelled.
+struct QueuedRun<'a> {
+    queued: &'a AtomicUsize,
+    /// Runs that were already waiting.
+    position: usize,
+}
+
+impl<'a> QueuedRun<'a> {
+    fn enter(queued: &'a AtomicUsize) -> Self {
+        let position = queued.fetch_add(1, Ordering::SeqCst);
+        Self { queued, position }
+    }
+}
+
+impl Drop for QueuedRun<'_> {
+    fn drop(&mut self) {
+        self.queued.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+impl Default for Admission {
+    fn default() -> Self {
+        Self::new(AdmissionLimits::default())
+    }
+}
+
+/// Held by a run admitted by [`admit_run`]; dropping it ends the run for
+/// the user's quota and frees its slot.
+pub struct RunPermit {
+    _quota: QuotaPermit,
+    _slot: Option<AdmissionPermit>,
+}
+
+/// Admit a run of `user_id`: check the user's quotas, then wait for a slot
+/// of `admission`. Refusals are the `429` response to send, with the
+/// JSON-RPC `id`.
+pub async fn admit_run(
+    quotas: &Arc<UserQuotas>,
+    admission: Option<&Admission>,
+    user_id: &str,
+    id: Option<serde_json::Value>,
+) -> Result<RunPermit, HttpResponse> {
+    let quota = quotas
+        .admit(user_id)
+        .map_err(|exceeded| reject_quota(id.clone(), &exceeded))?;
+    let slot = match admission {
+        Some(admission) => match admission.admit().await {
+            Ok(slot) => Some(slot),
+            Err(rejection) => {
+                quota.refund();
+                return Err(admission.reject(id, rejection));
+            }
+        },
+        None => None,
+    };
+    Ok(RunPermit {
+        _quota: quota,
+        _slot: slot,
+    })
+}
+
+/// `429 Too Many Requests` with a JSON-RPC error whose `data` is
+/// `exceeded`. `Retry-After` points at the reset of a daily quota.
+pub fn reject_quota(id: Option<serde_json::Value>, exceeded: &QuotaExceeded) -> HttpResponse {
+    tracing::warn!(
+        user_id = %exceeded.user_id,
+        quota = exceeded.quota.as_str(),
+        "rejecting run: {}",
+        exceeded
+    );
+    let retry_after = exceeded
+        .resets_at
+        .map(|at| (at - chrono::Utc::now()).num_seconds().max(1))
+        .unwrap_or(CONCURRENT_QUOTA_RETRY_AFTER_SECS);
+    let mut error = JsonRpcError::new(QUOTA_EXCEEDED_CODE, exceeded.to_string());
+    error.data = serde_json::to_value(exceeded).ok();
+    HttpResponse::TooManyRequests()
+        .insert_header(("Retry-After", retry_after.to_string()))
+        .json(JsonRpcResponse::error(id, error))
+}
+
+/// Whether a JSON-RPC method starts a run.
+pub fn starts_run(method: &str) -> bool {
+    matches!(method, "message/send" | "message/stream")
+}
@@ -15,6 +15,7 @@ use distri_types::configuration::ServerConfig;
 use serde_json::json;
 use std::sync::Arc;
 
+use crate::admission::Admission;
 use crate::context::UserContext;
 use crate::routes;
 
@@ -98,6 +99,13 @@ impl DistriAgentServer {
             tracing::info!("");
         }
 
+        // Run slots are shared by every worker.
+        let admission = web::Data::new(Admission::new(server_config.admission.clone()));
+        executor
+            .user_quotas
+            .set_limits(server_config.user_quotas.clone());
+        let task_store = executor.stores.task_store.clone();
+
         HttpServer::new(move || {
             let executor = executor.clone();
             let service_name = self.service_name.clone();
@@ -119,6 +127,7 @@ impl DistriAgentServer {
                     srv.call(req)
                 })
                 .app_data(web::Data::new(server_config.clone()))
+                .app_data(admission.clone())
                 .wrap(
                     Cors::default()
                         .allow_any_origin()
@@ -148,6 +157,11 @@ impl DistriAgentServer {
                     "/openapi.json",
                     web::get().to(crate::openapi::serve_openapi),
                 )
+                .route("/api/docs", web::get().to(crate::openapi::serve_swagger_ui))
+                .route(
+                    "/api/docs/openapi.json",
+                    web::get().to(crate::openapi::serve_openapi),
+                )
                 .service(utoipa_scalar::Scalar::with_url(
                     "/docs",
                     crate::openapi::ServerApiDoc::openapi(),
@@ -244,6 +258,8 @@ impl DistriAgentServer {
         .run()
         .await?;
 
+        // Store the task events still queued before the process exits.
+        task_store.shutdown().await?;
         Ok(())
     }
 }
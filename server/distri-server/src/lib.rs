@@ -1,3 +1,4 @@
+pub mod admission;
 pub mod agent_server;
 pub mod auth_routes;
 pub mod context;
@@ -1,8 +1,13 @@
 //! OpenAPI specification for the Distri Server API.
 //!
 //! Generates a complete OpenAPI 3.1 spec from utoipa path annotations
-//! and ToSchema derives. Served at `/openapi.json` and browsable via
-//! Scalar UI at `/docs`.
+//! and ToSchema derives. Served at `/openapi.json` (and
+//! `/api/docs/openapi.json`) and browsable via Scalar UI at `/docs` and
+//! Swagger UI at `/api/docs`.
+//!
+//! `tests/test_openapi_spec.rs` checks that every route of the catalog is
+//! documented and that the spec matches the snapshot in
+//! `tests/snapshots/openapi.json`.
 
 use utoipa::OpenApi;
 
@@ -23,6 +28,7 @@ use utoipa::OpenApi;
         (name = "Agents", description = "Agent CRUD and execution"),
         (name = "Threads", description = "Conversation threads and messages"),
         (name = "Tools", description = "Tool listing and invocation"),
+        (name = "Auth", description = "Scope consent for tool calls"),
         (name = "Sessions", description = "Key-value session storage"),
         (name = "Secrets", description = "Secret/API key management"),
         (name = "Skills", description = "Skill management"),
@@ -32,8 +38,14 @@ use utoipa::OpenApi;
         (name = "Prompt Templates", description = "Reusable prompt templates"),
         (name = "Artifacts", description = "Task artifact storage"),
         (name = "Notes", description = "Note CRUD"),
+        (name = "Users", description = "Per-user preferences injected into agent prompts, and per-user quotas"),
         (name = "Spans", description = "OTel span and trace read access"),
         (name = "Usage", description = "Usage stats aggregation"),
+        (name = "Analytics", description = "Run, agent and tool analytics per day"),
+        (name = "Audit", description = "Outbound LLM request/response audit log"),
+        (name = "Commands", description = "Slash commands available in chat"),
+        (name = "Embeddings", description = "Text embeddings with the server's shared cache"),
+        (name = "GraphQL", description = "Queries over agents, threads, tasks, messages and artifacts"),
         (name = "Health", description = "Health checks"),
     ),
     paths(
@@ -41,18 +53,41 @@ use utoipa::OpenApi;
         crate::routes::list_agents,
         crate::routes::get_agent_definition,
         crate::routes::create_agent,
+        crate::routes::list_agent_cards,
+        crate::routes::get_agent_card,
+        crate::routes::a2a_handler,
+        crate::routes::mcp::mcp_handler,
+        crate::routes::llm_execute,
+        crate::routes::complete_hook_handler,
+        crate::routes::run_agent_evals,
+        crate::routes::agent_eval_history,
+        crate::routes::compare_agent_evals,
         crate::routes::update_agent,
         crate::routes::delete_agent,
         crate::routes::validate_agent_handler,
+        crate::routes::preview_agent_prompt,
         crate::routes::get_agent_dag,
+        crate::routes::resolve_agent_tool_handler,
         crate::routes::get_agent_schema,
         // Threads
         crate::routes::list_threads_handler,
         crate::routes::list_agents_by_usage,
+        crate::routes::search_threads_handler,
+        crate::routes::import_threads_handler,
         crate::routes::get_thread_handler,
         crate::routes::update_thread_handler,
         crate::routes::delete_thread_handler,
+        crate::routes::share_thread_handler,
+        crate::routes::revoke_thread_shares_handler,
+        crate::routes::pin_warm_session_handler,
+        crate::routes::unpin_warm_session_handler,
+        crate::routes::warm_sessions_handler,
+        crate::routes::watch_thread_handler,
         crate::routes::get_thread_messages,
+        crate::routes::regenerate_thread_handler,
+        crate::routes::archive_thread_handler,
+        crate::routes::restore_thread_handler,
+        crate::routes::thread_summary_handler,
         // Message interactions
         crate::routes::mark_message_read_handler,
         crate::routes::get_message_read_status_handler,
@@ -63,11 +98,36 @@ use utoipa::OpenApi;
         crate::routes::get_message_votes_handler,
         // Tasks
         crate::routes::list_tasks,
+        crate::routes::get_task_handler,
+        crate::routes::compare_tasks_handler,
+        crate::routes::task_events_handler,
+        crate::routes::compact_task_handler,
+        crate::routes::rerun_workflow_handler,
+        crate::routes::retry_task_handler,
         // Tools
         crate::routes::list_tools,
+        crate::routes::list_plugins,
+        crate::routes::list_tool_renderers,
+        crate::routes::tools::call_tool_handler,
+        crate::routes::complete_tool_handler,
+        crate::routes::proxy_request_handler,
+        crate::routes::create_browser_session,
+        // Auth
+        crate::routes::complete_auth_handler,
+        crate::routes::build_workspace,
 
         crate::routes::get_device_info,
         crate::routes::get_home_stats,
+        crate::routes::get_metrics,
+        crate::routes::graphql::execute,
+        crate::routes::dev_seed_handler,
+        // Users
+        crate::routes::get_user_profile_handler,
+        crate::routes::set_user_profile_handler,
+        crate::routes::update_user_profile_handler,
+        crate::routes::delete_user_profile_handler,
+        crate::routes::user_quota_handler,
+        crate::routes::raise_user_quota_handler,
         // Sessions
         crate::routes::session::list_sessions,
         crate::routes::session::get_all_values,
@@ -103,6 +163,7 @@ use utoipa::OpenApi;
         crate::routes::prompt_templates::get_prompt_template,
         crate::routes::prompt_templates::update_prompt_template,
         crate::routes::prompt_templates::delete_prompt_template,
+        crate::routes::prompt_templates::prompt_translation_coverage,
         // Connections
         crate::routes::connections::list_connections,
         crate::routes::connections::get_connection,
@@ -122,6 +183,19 @@ use utoipa::OpenApi;
         crate::routes::spans::list_traces,
         // Usage
         crate::routes::usage::get_usage_stats,
+        // Analytics
+        crate::routes::analytics::get_run_analytics,
+        crate::routes::analytics::get_agent_analytics,
+        crate::routes::analytics::get_tool_analytics,
+        // Audit
+        crate::routes::audit::list_llm_audit,
+        crate::routes::audit::export_llm_audit,
+        // Commands
+        crate::routes::commands::list_commands,
+        crate::routes::commands::render_command,
+        crate::routes::commands::run_command,
+        // Embeddings
+        crate::routes::create_embeddings,
     ),
     components(schemas(
         // Route-level types
@@ -143,6 +217,10 @@ use utoipa::OpenApi;
         // Prompt template types
         crate::routes::prompt_templates::SyncPromptTemplatesRequest,
         crate::routes::prompt_templates::SyncPromptTemplatesResponse,
+        distri_types::prompt_locale::TemplateCoverage,
+        // User profile types
+        distri_types::user_profile::UserProfile,
+        distri_types::user_profile::ExpertiseLevel,
         // Connection wire types
         distri_types::api::connections::CreateConnectionRequest,
         distri_types::api::connections::CreateConnectionResponse,
@@ -152,6 +230,38 @@ use utoipa::OpenApi;
         distri_types::api::connections::TokenResponse,
         distri_types::api::connections::ConnectionConfig,
         // Spans / Traces wire types
+        distri_types::api::preview::AgentPromptPreviewRequest,
+        distri_types::api::preview::AgentPromptPreviewResponse,
+        distri_types::api::preview::PromptSectionTokens,
+        distri_types::api::share::CreateThreadShareRequest,
+        distri_types::api::share::ThreadShareResponse,
+        distri_types::warm_sessions::WarmSession,
+        distri_types::warm_sessions::WarmSessionsStatus,
+        distri_types::conversation_import::ConversationImportSummary,
+        distri_types::regenerate::RegenerateRequest,
+        distri_types::regenerate::Regeneration,
+        distri_types::task_retry::RetryRequest,
+        distri_types::task_retry::TaskRetry,
+        distri_types::task_retry::FailureSummary,
+        distri_types::task_retry::FailedToolCall,
+        distri_types::thread_archive::ThreadArchiveSummary,
+        distri_types::handover_summary::HandoverSummary,
+        distri_types::AuthConsentResponse,
+        distri_types::user_quotas::UserQuotaStatus,
+        distri_types::user_quotas::UserQuotaLimits,
+        distri_types::user_quotas::QuotaRaise,
+        distri_types::user_quotas::RaiseQuotaRequest,
+        distri_types::user_quotas::QuotaExceeded,
+        distri_types::user_quotas::QuotaKind,
+        distri_types::renderer_manifest::ToolRenderer,
+        distri_types::renderer_manifest::ToolRendererManifest,
+        distri_types::tool_catalog::ToolResolution,
+        distri_types::tool_catalog::ToolCandidate,
+        distri_types::tool_catalog::ToolCollision,
+        distri_types::tool_catalog::ToolSourceKind,
+        distri_types::tool_catalog::CollisionRule,
+        distri_types::conversation_import::ImportedThread,
+        distri_types::dev_seed::DevSeedSummary,
         distri_types::api::spans::SpanRecord,
         distri_types::api::spans::TraceRecord,
         distri_types::api::spans::SpansResponse,
@@ -168,6 +278,32 @@ use utoipa::OpenApi;
         distri_types::api::usage::UsageBucket,
         distri_types::api::usage::AppliedFilters,
         distri_types::api::usage::Bucket,
+        // Analytics wire types
+        distri_types::api::analytics::DateRange,
+        distri_types::api::analytics::RunCounters,
+        distri_types::api::analytics::RunStats,
+        distri_types::api::analytics::DailyRunStats,
+        distri_types::api::analytics::AgentRunStats,
+        distri_types::api::analytics::ToolUsageStats,
+        distri_types::api::analytics::RunAnalytics,
+        distri_types::api::analytics::AgentAnalytics,
+        distri_types::api::analytics::ToolAnalytics,
+        // Audit wire types
+        distri_types::api::audit::LlmAuditRecord,
+        distri_types::api::audit::LlmAuditQuery,
+        distri_types::api::audit::ListLlmAuditResponse,
+        // Slash command wire types
+        distri_types::channel_commands::SlashCommand,
+        distri_types::channel_commands::SlashCommandArg,
+        distri_types::channel_commands::ListSlashCommandsResponse,
+        distri_types::channel_commands::RenderSlashCommandRequest,
+        distri_types::channel_commands::RenderSlashCommandResponse,
+        distri_types::channel_commands::RunSlashCommandResponse,
+        // Embedding wire types
+        distri_types::embeddings::EmbeddingRequest,
+        distri_types::embeddings::EmbeddingInput,
+        distri_types::embeddings::EmbeddingResponse,
+        distri_types::embeddings::Embedding,
     ))
 )]
 pub struct ServerApiDoc;
@@ -179,3 +315,30 @@ pub async fn serve_openapi() -> actix_web::HttpResponse {
         .content_type("application/json")
         .body(spec)
 }
+
+/// Swagger UI page; the assets load from the swagger-ui-dist CDN.
+const SWAGGER_UI_HTML: &str = r##"<!DOCTYPE html>
+<html lang="en">
+<head>
+  <meta charset="utf-8" />
+  <title>Distri Server API</title>
+  <link rel="stylesheet" href="https://unpkg.com/swagger-ui-dist@5/swagger-ui.css" />
+</head>
+<body>
+  <div id="swagger-ui"></div>
+  <script src="https://unpkg.com/swagger-ui-dist@5/swagger-ui-bundle.js" crossorigin></script>
+  <script>
+    window.onload = () => {
+      window.ui = SwaggerUIBundle({ url: "/api/docs/openapi.json", dom_id: "#swagger-ui" });
+    };
+  </script>
+</body>
+</html>
+"##;
+
+/// Serve Swagger UI over the generated spec
+pub async fn serve_swagger_ui() -> actix_web::HttpResponse {
+    actix_web::HttpResponse::Ok()
+        .content_type("text/html; charset=utf-8")
+        .body(SWAGGER_UI_HTML)
+}
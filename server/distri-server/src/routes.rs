@@ -1,21 +1,37 @@
 use actix_web::Either;
-use actix_web::{web, HttpMessage, HttpRequest, HttpResponse};
+use actix_web::{guard, web, HttpMessage, HttpRequest, HttpResponse};
 use actix_web_lab::sse::{self, Sse};
+use base64::{engine::general_purpose, Engine as _};
 use chrono::{DateTime, Utc};
 use dirs::home_dir;
 use distri_a2a::AgentCard;
 use distri_a2a::JsonRpcRequest;
 use distri_core::a2a::messages::get_a2a_messages;
+use distri_core::a2a::uploads;
 use distri_core::a2a::A2AHandler;
 use distri_core::agent::{parse_agent_markdown_content, AgentOrchestrator};
 use distri_core::secrets::SecretResolver;
 use distri_core::types::UpdateThreadRequest;
 use distri_core::{AgentError, MessageFilter};
+use distri_types::agent_registry::{self, CapabilityQuery};
+use distri_types::api::preview::{AgentPromptPreviewRequest, AgentPromptPreviewResponse};
+use distri_types::api::share::{CreateThreadShareRequest, ThreadShareResponse};
 use distri_types::configuration::AgentConfigWithTools;
 use distri_types::configuration::ServerConfig;
+use distri_types::conversation_import::{parse_export, ConversationImportSummary, ImportFormat};
+use distri_types::dev_seed::DevSeedSummary;
+use distri_types::embeddings::{EmbeddingInput, EmbeddingRequest, EmbeddingResponse};
+use distri_types::handover_summary::HandoverSummary;
+use distri_types::regenerate::{RegenerateRequest, Regeneration};
 use distri_types::stores::{VoteMessageRequest, VoteType};
+use distri_types::task_retry::{RetryRequest, TaskRetry};
+use distri_types::thread_archive::ThreadArchiveSummary;
+use distri_types::tool_catalog::ToolResolution;
+use distri_types::user_profile::UserProfile;
+use distri_types::user_quotas::{RaiseQuotaRequest, UserQuotaStatus};
+use distri_types::warm_sessions::{WarmSession, WarmSessionsStatus};
 use distri_types::StandardDefinition;
-use distri_types::{ExternalTool, InlineHookResponse, Message, ModelSettings};
+use distri_types::{AuthConsentResponse, ExternalTool, InlineHookResponse, Message, ModelSettings};
 use futures_util::StreamExt;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -27,15 +43,21 @@ use tokio::fs;
 use utoipa::ToSchema;
 use uuid::Uuid;
 
+use crate::admission::{admit_run, starts_run, Admission};
 use crate::agent_server::VerboseLog;
 use crate::auth_routes;
 use crate::context::UserContext;
 use crate::routes_catalog::Route;
 
+pub mod analytics;
 pub mod artifacts;
+pub mod audit;
+pub mod commands;
 pub mod connections;
 mod files;
+pub mod graphql;
 mod llm_helpers;
+pub(crate) mod mcp;
 pub mod models;
 pub mod notes;
 pub mod prompt_templates;
@@ -44,6 +66,7 @@ pub mod secrets;
 pub mod session;
 pub mod skills;
 pub mod spans;
+mod stream_ws;
 pub mod tools;
 pub mod usage;
 
@@ -63,14 +86,39 @@ pub fn distri(cfg: &mut web::ServiceConfig) {
         .service(
             web::resource(Route::AgentValidate.path()).route(web::get().to(validate_agent_handler)),
         )
+        .service(
+            web::resource(Route::AgentPreview.path()).route(web::post().to(preview_agent_prompt)),
+        )
         .service(
             web::resource(Route::AgentCompleteTool.path())
                 .route(web::post().to(complete_tool_handler)),
         )
         .service(web::resource(Route::AgentDag.path()).route(web::get().to(get_agent_dag)))
+        .service(
+            web::resource(Route::AgentEvalsCompare.path())
+                .route(web::post().to(compare_agent_evals)),
+        )
+        .service(
+            web::resource(Route::AgentEvals.path())
+                .route(web::get().to(agent_eval_history))
+                .route(web::post().to(run_agent_evals)),
+        )
+        .service(
+            web::resource(Route::AgentToolResolve.path())
+                .route(web::get().to(resolve_agent_tool_handler)),
+        )
+        .service(
+            web::resource(Route::AgentStream.path())
+                .route(web::get().to(stream_ws::agent_stream_ws)),
+        )
         .service(
             web::resource(Route::AgentDispatch.path())
                 .route(web::get().to(get_agent_definition))
+                .route(
+                    web::post()
+                        .guard(guard::fn_guard(is_multipart))
+                        .to(a2a_multipart_handler),
+                )
                 .route(web::post().to(a2a_handler))
                 .route(web::put().to(update_agent))
                 .route(web::delete().to(delete_agent)),
@@ -78,29 +126,82 @@ pub fn distri(cfg: &mut web::ServiceConfig) {
         .service(
             web::resource(Route::EventHooks.path()).route(web::post().to(complete_hook_handler)),
         )
+        .service(
+            web::resource(Route::EventAuth.path()).route(web::post().to(complete_auth_handler)),
+        )
         .service(web::resource(Route::Tasks.path()).route(web::get().to(list_tasks)))
+        // Before the bare /tasks/{id} resource, which would match it.
+        .service(
+            web::resource(Route::TasksCompare.path()).route(web::get().to(compare_tasks_handler)),
+        )
         .service(
             web::resource(Route::TaskCompact.path()).route(web::post().to(compact_task_handler)),
         )
+        .service(
+            web::resource(Route::TaskWorkflowRerun.path())
+                .route(web::post().to(rerun_workflow_handler)),
+        )
+        .service(web::resource(Route::TaskRetry.path()).route(web::post().to(retry_task_handler)))
         // Specific /tasks/{id}/events before the bare /tasks/{id} resource.
         .service(web::resource(Route::TaskEvents.path()).route(web::get().to(task_events_handler)))
         .service(web::resource(Route::TaskGet.path()).route(web::get().to(get_task_handler)))
         .service(web::resource(Route::Tools.path()).route(web::get().to(list_tools)))
+        .service(web::resource(Route::Plugins.path()).route(web::get().to(list_plugins)))
+        .service(
+            web::resource(Route::ToolRenderers.path()).route(web::get().to(list_tool_renderers)),
+        )
+        .service(web::resource(Route::Mcp.path()).route(web::post().to(mcp::mcp_handler)))
         // Webhook endpoint for triggering agents
         // Thread endpoints
         .service(web::resource(Route::Threads.path()).route(web::get().to(list_threads_handler)))
         .service(
             web::resource(Route::ThreadsAgents.path()).route(web::get().to(list_agents_by_usage)),
         )
+        .service(
+            web::resource(Route::ThreadsSearch.path()).route(web::get().to(search_threads_handler)),
+        )
+        .service(
+            web::resource(Route::ThreadsImport.path())
+                .app_data(web::PayloadConfig::new(MAX_IMPORT_BYTES))
+                .route(web::post().to(import_threads_handler)),
+        )
         .service(
             web::resource(Route::ThreadMessages.path()).route(web::get().to(get_thread_messages)),
         )
+        .service(
+            web::resource(Route::ThreadRegenerate.path())
+                .route(web::post().to(regenerate_thread_handler)),
+        )
         .service(
             web::resource(Route::Thread.path())
                 .route(web::get().to(get_thread_handler))
                 .route(web::put().to(update_thread_handler))
                 .route(web::delete().to(delete_thread_handler)),
         )
+        .service(
+            web::resource(Route::ThreadShare.path())
+                .route(web::post().to(share_thread_handler))
+                .route(web::delete().to(revoke_thread_shares_handler)),
+        )
+        .service(
+            web::resource(Route::ThreadWatch.path()).route(web::get().to(watch_thread_handler)),
+        )
+        .service(
+            web::resource(Route::ThreadArchive.path())
+                .route(web::post().to(archive_thread_handler))
+                .route(web::delete().to(restore_thread_handler)),
+        )
+        .service(
+            web::resource(Route::ThreadWarm.path())
+                .route(web::post().to(pin_warm_session_handler))
+                .route(web::delete().to(unpin_warm_session_handler)),
+        )
+        .service(
+            web::resource(Route::ThreadSummary.path()).route(web::get().to(thread_summary_handler)),
+        )
+        .service(
+            web::resource(Route::WarmSessions.path()).route(web::get().to(warm_sessions_handler)),
+        )
         // Message read status endpoints
         .service(
             web::resource(Route::ThreadMessageRead.path())
@@ -142,9 +243,34 @@ pub fn distri(cfg: &mut web::ServiceConfig) {
         )
         // LLM execute
         .service(web::resource(Route::LlmExecute.path()).route(web::post().to(llm_execute)))
+        .service(web::resource(Route::Embeddings.path()).route(web::post().to(create_embeddings)))
         // Configuration endpoints
         .service(web::resource(Route::Device.path()).route(web::get().to(get_device_info)))
         .service(web::resource(Route::HomeStats.path()).route(web::get().to(get_home_stats)))
+        .service(web::resource(Route::Metrics.path()).route(web::get().to(get_metrics)))
+        .service(
+            web::resource(Route::GraphQL.path())
+                .route(
+                    web::get()
+                        .guard(guard::Header("upgrade", "websocket"))
+                        .to(graphql::subscriptions),
+                )
+                .route(web::get().to(graphql::execute))
+                .route(web::post().to(graphql::execute)),
+        )
+        .service(web::resource(Route::DevSeed.path()).route(web::post().to(dev_seed_handler)))
+        .service(
+            web::resource(Route::UserProfile.path())
+                .route(web::get().to(get_user_profile_handler))
+                .route(web::put().to(set_user_profile_handler))
+                .route(web::patch().to(update_user_profile_handler))
+                .route(web::delete().to(delete_user_profile_handler)),
+        )
+        .service(
+            web::resource(Route::AdminUserQuota.path())
+                .route(web::get().to(user_quota_handler))
+                .route(web::post().to(raise_user_quota_handler)),
+        )
         .configure(prompt_templates::configure_prompt_template_routes)
         // HTTP request proxy — resolves secrets/connections server-side
         .service(web::resource(Route::Request.path()).route(web::post().to(proxy_request_handler)))
@@ -160,6 +286,12 @@ pub fn distri(cfg: &mut web::ServiceConfig) {
         .configure(spans::configure_spans_routes)
         // Usage stats endpoint
         .configure(usage::configure_usage_routes)
+        // Analytics dashboard endpoints
+        .configure(analytics::configure_analytics_routes)
+        // LLM audit log endpoints
+        .configure(audit::configure_audit_routes)
+        // Slash command discovery
+        .configure(commands::configure_command_routes)
         // Authentication endpoints
         .configure(auth_routes::configure_auth_routes);
 }
@@ -253,9 +385,23 @@ async fn list_agents(
 /// for each agent (name, description, version, icon, skills) — never the system
 /// prompt, tools, or model settings. The full-definition list (`GET /agents`)
 /// is the admin/console surface.
+///
+/// Cards are negotiated against the client's [`CapabilityQuery`]; agents the
+/// client cannot talk to are left out.
+#[utoipa::path(
+    get,
+    path = "/v1/agents/cards",
+    tag = "Agents",
+    params(
+        ("extensions" = Option<String>, Query, description = "Comma-separated A2A extensions the client supports; defaults to the X-A2A-Extensions header"),
+    ),
+    responses((status = 200, description = "A2A agent cards of the agents the client can talk to"))
+)]
 async fn list_agent_cards(
     executor: web::Data<Arc<AgentOrchestrator>>,
     server_config: web::Data<ServerConfig>,
+    query: web::Query<CapabilityQuery>,
+    http_request: HttpRequest,
 ) -> HttpResponse {
     let (agents_with_metadata, _) = executor
         .stores
@@ -264,14 +410,36 @@ async fn list_agent_cards(
         .await;
 
     let server_config = server_config.get_ref();
+    let query = capability_query(query.into_inner(), &http_request);
     let cards: Vec<AgentCard> = agents_with_metadata
         .into_iter()
-        .map(|(config, _cloud)| config.to_card(server_config))
+        .filter_map(|(config, _cloud)| {
+            agent_registry::negotiate_card(config.to_card(server_config), &query).ok()
+        })
         .collect();
 
     HttpResponse::Ok().json(cards)
 }
 
+/// The client's capabilities from the query string, taking the extensions
+/// from the `X-A2A-Extensions` header when the query has none.
+fn capability_query(mut query: CapabilityQuery, http_request: &HttpRequest) -> CapabilityQuery {
+    if query.extensions.is_none() {
+        query.extensions = http_request
+            .headers()
+            .get(agent_registry::EXTENSIONS_HEADER)
+            .and_then(|v| v.to_str().ok())
+            .map(|v| {
+                v.split(',')
+                    .map(str::trim)
+                    .filter(|s| !s.is_empty())
+                    .map(str::to_string)
+                    .collect()
+            });
+    }
+    query
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, ToSchema, JsonSchema)]
 pub struct DeviceMetadata {
     #[serde(default = "new_device_id")]
@@ -545,8 +713,44 @@ async fn list_tools(
     HttpResponse::Ok().json(json!({ "tools": items }))
 }
 
-async fn build_workspace(_executor: web::Data<Arc<AgentOrchestrator>>) -> HttpResponse {
-    HttpResponse::Ok().json(json!({ "status": "built" }))
+#[utoipa::path(
+    get,
+    path = "/v1/plugins",
+    tag = "Tools",
+    responses((status = 200, description = "Capabilities granted to each plugin"))
+)]
+async fn list_plugins(executor: web::Data<Arc<AgentOrchestrator>>) -> HttpResponse {
+    HttpResponse::Ok().json(json!({ "plugins": executor.plugin_grants().await }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/tool-renderers",
+    tag = "Tools",
+    responses((status = 200, description = "UI renderer manifests of the registered tools"))
+)]
+async fn list_tool_renderers(executor: web::Data<Arc<AgentOrchestrator>>) -> HttpResponse {
+    HttpResponse::Ok().json(json!({ "renderers": executor.tool_renderers().await }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/build",
+    tag = "Agents",
+    responses(
+        (status = 200, description = "Agents registered from the workspace and its member packages"),
+        (status = 400, description = "A manifest or agent definition is invalid")
+    )
+)]
+async fn build_workspace(executor: web::Data<Arc<AgentOrchestrator>>) -> HttpResponse {
+    match executor.build_workspace().await {
+        Ok(build) => HttpResponse::Ok().json(json!({
+            "status": "built",
+            "agents": build.agents,
+            "packages": build.packages,
+        })),
+        Err(e) => HttpResponse::BadRequest().json(json!({ "error": e.to_string() })),
+    }
 }
 
 #[utoipa::path(
@@ -729,19 +933,192 @@ async fn validate_agent_handler(
     })
 }
 
+/// Assemble the prompt an agent would send for a hypothetical message —
+/// rendered system prompt, packed thread history and tool schemas — with
+/// per-section token estimates, without calling the model.
+#[utoipa::path(
+    post,
+    path = "/v1/agents/{id}/preview",
+    tag = "Agents",
+    params(("id" = String, Path, description = "Agent ID")),
+    request_body = AgentPromptPreviewRequest,
+    responses(
+        (status = 200, description = "Assembled prompt", body = AgentPromptPreviewResponse),
+        (status = 400, description = "Prompt could not be assembled"),
+        (status = 404, description = "Agent not found"),
+    )
+)]
+async fn preview_agent_prompt(
+    id: web::Path<String>,
+    body: web::Json<AgentPromptPreviewRequest>,
+    executor: web::Data<Arc<AgentOrchestrator>>,
+    http_request: HttpRequest,
+) -> HttpResponse {
+    let agent_id = id.into_inner();
+    if executor.get_agent(&agent_id).await.is_none() {
+        return HttpResponse::NotFound().json(json!({ "error": "Agent not found" }));
+    }
+    let user_id = http_request
+        .extensions()
+        .get::<UserContext>()
+        .map(|ctx| ctx.user_id());
+    let req = body.into_inner();
+
+    match distri_core::agent::debug::preview_agent_prompt(
+        executor.get_ref().clone(),
+        &agent_id,
+        &req.message,
+        req.thread_id,
+        user_id,
+    )
+    .await
+    {
+        Ok(preview) => HttpResponse::Ok().json(preview),
+        Err(e) => HttpResponse::BadRequest().json(json!({ "error": e.to_string() })),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/agents/{id}/evals",
+    tag = "Agents",
+    params(("id" = String, Path, description = "Agent ID")),
+    request_body(content = Object, description = "The eval cases to run, or none for the agent's own"),
+    responses(
+        (status = 200, description = "The eval run with a verdict per case"),
+        (status = 404, description = "Agent not found"),
+    )
+)]
+async fn run_agent_evals(
+    id: web::Path<String>,
+    body: web::Json<distri_types::evals::RunEvalsRequest>,
+    executor: web::Data<Arc<AgentOrchestrator>>,
+    http_request: HttpRequest,
+) -> HttpResponse {
+    let agent_id = id.into_inner();
+    if executor.get_agent(&agent_id).await.is_none() {
+        return HttpResponse::NotFound().json(json!({ "error": "Agent not found" }));
+    }
+    let user_id = http_request
+        .extensions()
+        .get::<UserContext>()
+        .map(|ctx| ctx.user_id());
+    let model_settings = http_request
+        .extensions()
+        .get::<distri_types::ModelSettings>()
+        .cloned();
+
+    match distri_core::agent::evals::run_evals(
+        executor.get_ref().clone(),
+        &agent_id,
+        body.into_inner().cases,
+        user_id,
+        model_settings,
+    )
+    .await
+    {
+        Ok(run) => HttpResponse::Ok().json(run),
+        Err(e) => HttpResponse::InternalServerError().json(json!({ "error": e.to_string() })),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/agents/{id}/evals/compare",
+    tag = "Agents",
+    params(("id" = String, Path, description = "Agent ID")),
+    request_body(content = Object, description = "The two eval runs to compare"),
+    responses(
+        (status = 200, description = "Judged comparison of the two eval runs"),
+        (status = 400, description = "Unknown eval run"),
+    )
+)]
+async fn compare_agent_evals(
+    id: web::Path<String>,
+    body: web::Json<distri_types::evals::CompareEvalsRequest>,
+    executor: web::Data<Arc<AgentOrchestrator>>,
+    http_request: HttpRequest,
+) -> HttpResponse {
+    let user_id = http_request
+        .extensions()
+        .get::<UserContext>()
+        .map(|ctx| ctx.user_id());
+    let model_settings = http_request
+        .extensions()
+        .get::<distri_types::ModelSettings>()
+        .cloned();
+
+    match distri_core::agent::evals::compare_evals(
+        executor.get_ref().clone(),
+        &id.into_inner(),
+        &body.into_inner(),
+        user_id,
+        model_settings,
+    )
+    .await
+    {
+        Ok(comparison) => HttpResponse::Ok().json(comparison),
+        Err(e) => HttpResponse::BadRequest().json(json!({ "error": e.to_string() })),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/agents/{id}/evals",
+    tag = "Agents",
+    params(("id" = String, Path, description = "Agent ID")),
+    responses((status = 200, description = "Eval runs and comparisons of the agent, oldest first"))
+)]
+async fn agent_eval_history(
+    id: web::Path<String>,
+    executor: web::Data<Arc<AgentOrchestrator>>,
+) -> HttpResponse {
+    match distri_core::agent::evals::eval_history(executor.get_ref().clone(), &id.into_inner())
+        .await
+    {
+        Ok(history) => HttpResponse::Ok().json(history),
+        Err(e) => HttpResponse::InternalServerError().json(json!({ "error": e.to_string() })),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/agents/{agent_name}/.well-known/agent.json",
+    tag = "Agents",
+    params(
+        ("agent_name" = String, Path, description = "Agent name"),
+        ("extensions" = Option<String>, Query, description = "Comma-separated A2A extensions the client supports; defaults to the X-A2A-Extensions header"),
+    ),
+    responses(
+        (status = 200, description = "The agent's A2A card"),
+        (status = 406, description = "The client lacks a capability the agent requires"),
+    )
+)]
 async fn get_agent_card(
     agent_name: web::Path<String>,
     executor: web::Data<Arc<AgentOrchestrator>>,
     server_config: web::Data<ServerConfig>,
+    query: web::Query<CapabilityQuery>,
+    http_request: HttpRequest,
 ) -> HttpResponse {
     let agent_name = agent_name.into_inner();
+    let query = capability_query(query.into_inner(), &http_request);
 
     let handler = A2AHandler::new(executor.get_ref().clone());
     match handler
         .agent_def_to_card(agent_name.clone(), Some(server_config.get_ref().clone()))
         .await
     {
-        Ok(card) => HttpResponse::Ok().json(card),
+        Ok(card) => match agent_registry::negotiate_card(card, &query) {
+            Ok(card) => HttpResponse::Ok()
+                .insert_header((
+                    agent_registry::EXTENSIONS_HEADER,
+                    agent_registry::active_extensions(&card),
+                ))
+                .insert_header(("Vary", agent_registry::EXTENSIONS_HEADER))
+                .json(card),
+            Err(e) => HttpResponse::NotAcceptable().json(json!({ "error": e.to_string() })),
+        },
         Err(e) => {
             let e: distri_a2a::JsonRpcError = e.into();
             HttpResponse::InternalServerError().json(e)
@@ -752,18 +1129,185 @@ async fn get_agent_card(
 /// A2A handler for processing JSON-RPC requests to agents.
 /// To provide a custom context implementation:
 /// 1. Implement the `GetContext` trait for your custom type
+#[utoipa::path(
+    post,
+    path = "/v1/agents/{id}",
+    tag = "Agents",
+    params(("id" = String, Path, description = "Agent ID")),
+    request_body(content = Object, description = "A JSON-RPC request (`message/send`, `message/stream`, …)"),
+    responses(
+        (status = 200, description = "JSON-RPC response; `message/stream` answers with a text/event-stream of JSON-RPC responses"),
+        (status = 429, description = "Server at capacity or user over a quota; see Retry-After"),
+    )
+)]
 async fn a2a_handler(
     id: web::Path<String>,
     req: web::Json<JsonRpcRequest>,
     executor: web::Data<Arc<AgentOrchestrator>>,
+    server_config: Option<web::Data<ServerConfig>>,
+    admission: Option<web::Data<Admission>>,
     http_request: HttpRequest,
     verbose: Option<web::Data<Option<VerboseLog>>>,
 ) -> Either<
     Sse<impl futures_util::stream::Stream<Item = Result<sse::Event, std::convert::Infallible>>>,
     HttpResponse,
 > {
-    let agent_id = id.into_inner();
     let req = req.into_inner();
+    let limits = server_config.map(|c| c.uploads.clone()).unwrap_or_default();
+    if let Err(e) = uploads::check_upload_limits(&req, &limits) {
+        return Either::Right(upload_error(req.id, e));
+    }
+    dispatch_a2a(
+        id.into_inner(),
+        req,
+        executor,
+        admission,
+        http_request,
+        verbose,
+    )
+    .await
+}
+
+/// `message/send` and `message/stream` with files as multipart form fields.
+/// The `request` field carries the JSON-RPC body; every other field with a
+/// filename is attached to the message as a file part.
+async fn a2a_multipart_handler(
+    id: web::Path<String>,
+    mut payload: actix_multipart::Multipart,
+    executor: web::Data<Arc<AgentOrchestrator>>,
+    server_config: Option<web::Data<ServerConfig>>,
+    admission: Option<web::Data<Admission>>,
+    http_request: HttpRequest,
+    verbose: Option<web::Data<Option<VerboseLog>>>,
+) -> Either<
+    Sse<impl futures_util::stream::Stream<Item = Result<sse::Event, std::convert::Infallible>>>,
+    HttpResponse,
+> {
+    let limits = server_config.map(|c| c.uploads.clone()).unwrap_or_default();
+    let mut request: Option<JsonRpcRequest> = None;
+    let mut files = Vec::new();
+    while let Some(field) = payload.next().await {
+        let mut field = match field {
+            Ok(field) => field,
+            Err(e) => {
+                return Either::Right(upload_error(None, AgentError::Validation(e.to_string())))
+            }
+        };
+        let name = field.name().unwrap_or_default().to_string();
+        let filename = field
+            .content_disposition()
+            .and_then(|cd| cd.get_filename())
+            .map(str::to_string);
+        let mime_type = field.content_type().map(|m| m.essence_str().to_string());
+
+        let mut data = Vec::new();
+        while let Some(chunk) = field.next().await {
+            match chunk {
+                Ok(chunk) => data.extend_from_slice(&chunk),
+                Err(e) => {
+                    return Either::Right(upload_error(None, AgentError::Validation(e.to_string())))
+                }
+            }
+            if filename.is_some() && data.len() as u64 > limits.max_file_bytes {
+                let e = AgentError::Validation(format!(
+                    "{}: exceeds the {} byte limit",
+                    filename.unwrap_or_default(),
+                    limits.max_file_bytes
+                ));
+                return Either::Right(upload_error(None, e));
+            }
+        }
+
+        if name == "request" {
+            match serde_json::from_slice(&data) {
+                Ok(req) => request = Some(req),
+                Err(e) => {
+                    let e = AgentError::Validation(format!("Invalid request field: {}", e));
+                    return Either::Right(upload_error(None, e));
+                }
+            }
+        } else if filename.is_some() {
+            files.push(distri_a2a::FilePart {
+                file: distri_a2a::FileObject::WithBytes {
+                    bytes: general_purpose::STANDARD.encode(&data),
+                    mime_type: Some(
+                        mime_type.unwrap_or_else(|| "application/octet-stream".to_string()),
+                    ),
+                    name: filename,
+                },
+                metadata: None,
+            });
+        }
+    }
+
+    let Some(mut req) = request else {
+        let e = AgentError::Validation("Missing 'request' field".to_string());
+        return Either::Right(upload_error(None, e));
+    };
+    if let Err(e) = uploads::attach_files(&mut req, files)
+        .and_then(|_| uploads::check_upload_limits(&req, &limits))
+    {
+        return Either::Right(upload_error(req.id, e));
+    }
+    dispatch_a2a(
+        id.into_inner(),
+        req,
+        executor,
+        admission,
+        http_request,
+        verbose,
+    )
+    .await
+}
+
+fn is_multipart(ctx: &guard::GuardContext) -> bool {
+    ctx.head()
+        .headers()
+        .get(actix_web::http::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|ct| ct.starts_with("multipart/form-data"))
+}
+
+fn upload_error(id: Option<serde_json::Value>, e: AgentError) -> HttpResponse {
+    HttpResponse::Ok().json(distri_a2a::JsonRpcResponse::error(
+        id,
+        distri_a2a::JsonRpcError::invalid_params(e.to_string()),
+    ))
+}
+
+async fn dispatch_a2a(
+    agent_id: String,
+    req: JsonRpcRequest,
+    executor: web::Data<Arc<AgentOrchestrator>>,
+    admission: Option<web::Data<Admission>>,
+    http_request: HttpRequest,
+    verbose: Option<web::Data<Option<VerboseLog>>>,
+) -> Either<
+    Sse<impl futures_util::stream::Stream<Item = Result<sse::Event, std::convert::Infallible>>>,
+    HttpResponse,
+> {
+    let (user_id, workspace_id) = http_request
+        .extensions()
+        .get::<UserContext>()
+        .map(|ctx| (ctx.user_id(), ctx.workspace_id()))
+        .unwrap_or_else(|| ("local_dev_user".to_string(), None));
+
+    // The permit is held until the response is built or the stream ends.
+    let permit = if starts_run(&req.method) {
+        match admit_run(
+            &executor.user_quotas,
+            admission.as_ref().map(|a| a.get_ref()),
+            &user_id,
+            req.id.clone(),
+        )
+        .await
+        {
+            Ok(permit) => Some(permit),
+            Err(rejection) => return Either::Right(rejection),
+        }
+    } else {
+        None
+    };
     let executor = executor.get_ref();
     let verbose = verbose
         .as_ref()
@@ -773,12 +1317,6 @@ async fn a2a_handler(
 
     let handler = A2AHandler::new(executor.clone());
 
-    let (user_id, workspace_id) = http_request
-        .extensions()
-        .get::<UserContext>()
-        .map(|ctx| (ctx.user_id(), ctx.workspace_id()))
-        .unwrap_or_else(|| ("local_dev_user".to_string(), None));
-
     // Workspace-level default model settings, injected by cloud middleware.
     let workspace_model_settings = http_request
         .extensions()
@@ -798,18 +1336,22 @@ async fn a2a_handler(
         .await;
     match result {
         futures_util::future::Either::Left(stream) => {
-            actix_web::Either::Left(Sse::from_stream(stream.map(|r| match r {
-                Ok(m) => {
-                    let mut data = sse::Data::new(m.data);
-                    if m.event.is_some() {
-                        data.set_event(m.event.unwrap());
+            actix_web::Either::Left(Sse::from_stream(stream.map(move |r| {
+                let _permit = &permit;
+                match r {
+                    Ok(m) => {
+                        let mut data = sse::Data::new(m.data);
+                        if let Some(event) = m.event {
+                            data.set_event(event);
+                        }
+                        Ok(sse::Event::Data(data))
                     }
-                    Ok(sse::Event::Data(data))
+                    Err(e) => Err(e),
                 }
-                Err(e) => Err(e),
             })))
         }
         futures_util::future::Either::Right(response) => {
+            drop(permit);
             actix_web::Either::Right(HttpResponse::Ok().json(response))
         }
     }
@@ -853,6 +1395,16 @@ fn default_load_history() -> bool {
     true
 }
 
+#[utoipa::path(
+    post,
+    path = "/v1/llm/execute",
+    tag = "Agents",
+    request_body(content = Object, description = "Messages, tools and model settings of the call"),
+    responses(
+        (status = 200, description = "The model's reply, with the thread and task it was recorded on"),
+        (status = 400, description = "Invalid request"),
+    )
+)]
 async fn llm_execute(
     executor: web::Data<Arc<AgentOrchestrator>>,
     http_request: HttpRequest,
@@ -1101,6 +1653,34 @@ async fn llm_execute(
     }
 }
 
+#[utoipa::path(
+    post,
+    path = "/v1/embeddings",
+    tag = "Embeddings",
+    request_body = EmbeddingRequest,
+    responses(
+        (status = 200, description = "One vector per input, in input order", body = EmbeddingResponse),
+        (status = 400, description = "No input"),
+        (status = 502, description = "The embeddings provider failed")
+    )
+)]
+async fn create_embeddings(
+    executor: web::Data<Arc<AgentOrchestrator>>,
+    payload: web::Json<EmbeddingRequest>,
+) -> HttpResponse {
+    let request = payload.into_inner();
+    if matches!(&request.input, EmbeddingInput::Many(inputs) if inputs.is_empty()) {
+        return HttpResponse::BadRequest().json(json!({ "error": "input is empty" }));
+    }
+    match executor.embeddings.embed(request).await {
+        Ok(response) => HttpResponse::Ok().json(response),
+        Err(e) => {
+            tracing::error!("[/embeddings] embedding failed: {:#}", e);
+            HttpResponse::BadGateway().json(json!({ "error": format!("{:#}", e) }))
+        }
+    }
+}
+
 // Thread handlers
 #[derive(Deserialize)]
 struct ListThreadsQuery {
@@ -1164,22 +1744,124 @@ async fn list_threads_handler(
     }
 }
 
+#[derive(Deserialize)]
+struct SearchThreadsQuery {
+    q: String,
+    agent_id: Option<String>,
+    limit: Option<u32>,
+    offset: Option<u32>,
+}
+
 #[utoipa::path(
     get,
-    path = "/v1/threads/agents",
+    path = "/v1/threads/search",
     tag = "Threads",
-    responses((status = 200, description = "List agents by usage"))
+    params(("q" = String, Query, description = "Text matched against title, last message and tags")),
+    responses((status = 200, description = "Matching threads"))
 )]
-async fn list_agents_by_usage(
+async fn search_threads_handler(
+    query: web::Query<SearchThreadsQuery>,
     coordinator: web::Data<Arc<AgentOrchestrator>>,
-    query: web::Query<std::collections::HashMap<String, String>>,
 ) -> HttpResponse {
-    let search = query.get("search").map(|s| s.as_str());
-    match coordinator.get_agents_by_usage(search).await {
-        Ok(agents) => HttpResponse::Ok().json(agents),
-        Err(e) => HttpResponse::InternalServerError().json(json!({
-            "error": format!("Failed to get agents by usage: {}", e)
-        })),
+    let q = query.q.trim();
+    if q.is_empty() {
+        return HttpResponse::BadRequest().json(json!({
+            "error": "q must not be empty"
+        }));
+    }
+    let filter = distri_types::stores::ThreadListFilter {
+        agent_id: query.agent_id.clone(),
+        search: Some(q.to_string()),
+        ..Default::default()
+    };
+
+    match coordinator
+        .list_threads(&filter, query.limit, query.offset)
+        .await
+    {
+        Ok(response) => HttpResponse::Ok().json(response),
+        Err(e) => HttpResponse::InternalServerError().json(json!({
+            "error": format!("Failed to search threads: {}", e)
+        })),
+    }
+}
+
+/// Largest export accepted by `POST /threads/import`.
+const MAX_IMPORT_BYTES: usize = 64 * 1024 * 1024;
+
+#[derive(Deserialize)]
+struct ImportThreadsQuery {
+    format: ImportFormat,
+    agent_id: String,
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/threads/import",
+    tag = "Threads",
+    params(
+        ("format" = String, Query, description = "Export format: chatgpt or claude"),
+        ("agent_id" = String, Query, description = "Agent the imported threads belong to")
+    ),
+    request_body(content = String, content_type = "application/json", description = "The export's conversations.json"),
+    responses(
+        (status = 200, description = "Imported and skipped conversations", body = ConversationImportSummary),
+        (status = 400, description = "Not a valid export of the given format"),
+        (status = 404, description = "Unknown agent")
+    )
+)]
+async fn import_threads_handler(
+    query: web::Query<ImportThreadsQuery>,
+    body: web::Bytes,
+    coordinator: web::Data<Arc<AgentOrchestrator>>,
+) -> HttpResponse {
+    let json = match std::str::from_utf8(&body) {
+        Ok(json) => json,
+        Err(e) => {
+            return HttpResponse::BadRequest().json(json!({
+                "error": format!("Invalid UTF-8 body: {}", e)
+            }))
+        }
+    };
+    let conversations = match parse_export(query.format, json) {
+        Ok(conversations) => conversations,
+        Err(e) => {
+            return HttpResponse::BadRequest().json(json!({
+                "error": e.to_string()
+            }))
+        }
+    };
+
+    match coordinator
+        .import_conversations(&query.agent_id, conversations)
+        .await
+    {
+        Ok(summary) => HttpResponse::Ok().json(summary),
+        Err(AgentError::AgentNotFound(agent_id)) => HttpResponse::NotFound().json(json!({
+            "error": format!("Agent '{}' not found", agent_id)
+        })),
+        Err(e) => HttpResponse::InternalServerError().json(json!({
+            "error": format!("Failed to import conversations: {}", e)
+        })),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/threads/agents",
+    tag = "Threads",
+    responses((status = 200, description = "List agents by usage"))
+)]
+async fn list_agents_by_usage(
+    coordinator: web::Data<Arc<AgentOrchestrator>>,
+    query: web::Query<std::collections::HashMap<String, String>>,
+) -> HttpResponse {
+    let search = query.get("search").map(|s| s.as_str());
+    match coordinator.get_agents_by_usage(search).await {
+        Ok(agents) => HttpResponse::Ok().json(agents),
+        Err(e) => HttpResponse::InternalServerError().json(json!({
+            "error": format!("Failed to get agents by usage: {}", e)
+        })),
     }
 }
 
@@ -1252,6 +1934,239 @@ async fn delete_thread_handler(
     }
 }
 
+// ========== Observer Share Handlers ==========
+
+#[utoipa::path(
+    post,
+    path = "/v1/threads/{thread_id}/share",
+    tag = "Threads",
+    params(("thread_id" = String, Path, description = "Thread ID")),
+    request_body = CreateThreadShareRequest,
+    responses(
+        (status = 200, description = "Read-only share token", body = ThreadShareResponse),
+        (status = 404, description = "Thread not found"),
+    )
+)]
+async fn share_thread_handler(
+    path: web::Path<String>,
+    body: Option<web::Json<CreateThreadShareRequest>>,
+    coordinator: web::Data<Arc<AgentOrchestrator>>,
+) -> HttpResponse {
+    let thread_id = path.into_inner();
+    let request = body.map(|b| b.into_inner()).unwrap_or_default();
+    match distri_core::agent::observer::create_thread_share(
+        &coordinator,
+        &thread_id,
+        request.ttl_secs(),
+    )
+    .await
+    {
+        Ok(share) => HttpResponse::Ok().json(share),
+        Err(AgentError::NotFound(_)) => {
+            HttpResponse::NotFound().json(json!({ "error": "Thread not found" }))
+        }
+        Err(e) => HttpResponse::InternalServerError().json(json!({
+            "error": format!("Failed to share thread: {}", e)
+        })),
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/v1/threads/{thread_id}/share",
+    tag = "Threads",
+    params(("thread_id" = String, Path, description = "Thread ID")),
+    responses(
+        (status = 204, description = "All share tokens revoked"),
+        (status = 404, description = "Thread not found"),
+    )
+)]
+async fn revoke_thread_shares_handler(
+    path: web::Path<String>,
+    coordinator: web::Data<Arc<AgentOrchestrator>>,
+) -> HttpResponse {
+    let thread_id = path.into_inner();
+    match distri_core::agent::observer::revoke_thread_shares(&coordinator, &thread_id).await {
+        Ok(()) => HttpResponse::NoContent().finish(),
+        Err(AgentError::NotFound(_)) => {
+            HttpResponse::NotFound().json(json!({ "error": "Thread not found" }))
+        }
+        Err(e) => HttpResponse::InternalServerError().json(json!({
+            "error": format!("Failed to revoke shares: {}", e)
+        })),
+    }
+}
+
+// ========== Warm Session Handlers ==========
+
+fn warm_sessions_disabled() -> HttpResponse {
+    HttpResponse::BadRequest().json(json!({
+        "error": "warm sessions are not enabled on this server (warm_sessions)"
+    }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/threads/{thread_id}/warm",
+    tag = "Threads",
+    params(("thread_id" = String, Path, description = "Thread ID")),
+    responses(
+        (status = 200, description = "The pinned warm session", body = WarmSession),
+        (status = 400, description = "Warm sessions are not enabled"),
+        (status = 404, description = "Thread not found"),
+    )
+)]
+async fn pin_warm_session_handler(
+    path: web::Path<String>,
+    coordinator: web::Data<Arc<AgentOrchestrator>>,
+) -> HttpResponse {
+    let thread_id = path.into_inner();
+    let Some(warm) = coordinator.warm_sessions.as_ref() else {
+        return warm_sessions_disabled();
+    };
+    match coordinator.get_thread(&thread_id).await {
+        Ok(Some(_)) => HttpResponse::Ok().json(warm.pin(&thread_id)),
+        Ok(None) => HttpResponse::NotFound().json(json!({ "error": "Thread not found" })),
+        Err(e) => HttpResponse::InternalServerError().json(json!({
+            "error": format!("Failed to get thread: {}", e)
+        })),
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/v1/threads/{thread_id}/warm",
+    tag = "Threads",
+    params(("thread_id" = String, Path, description = "Thread ID")),
+    responses(
+        (status = 204, description = "Unpinned; the session may now be evicted"),
+        (status = 400, description = "Warm sessions are not enabled"),
+        (status = 404, description = "Thread was not pinned"),
+    )
+)]
+async fn unpin_warm_session_handler(
+    path: web::Path<String>,
+    coordinator: web::Data<Arc<AgentOrchestrator>>,
+) -> HttpResponse {
+    let thread_id = path.into_inner();
+    let Some(warm) = coordinator.warm_sessions.as_ref() else {
+        return warm_sessions_disabled();
+    };
+    if warm.unpin(&thread_id) {
+        HttpResponse::NoContent().finish()
+    } else {
+        HttpResponse::NotFound().json(json!({ "error": "Thread was not pinned" }))
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/warm-sessions",
+    tag = "Threads",
+    responses(
+        (status = 200, description = "Warm sessions and the time they saved", body = WarmSessionsStatus),
+        (status = 400, description = "Warm sessions are not enabled"),
+    )
+)]
+async fn warm_sessions_handler(coordinator: web::Data<Arc<AgentOrchestrator>>) -> HttpResponse {
+    match coordinator.warm_sessions.as_ref() {
+        Some(warm) => HttpResponse::Ok().json(warm.status()),
+        None => warm_sessions_disabled(),
+    }
+}
+
+// ========== User Quota Handlers ==========
+
+#[utoipa::path(
+    get,
+    path = "/v1/admin/users/{user_id}/quota",
+    tag = "Users",
+    params(("user_id" = String, Path, description = "User ID")),
+    responses(
+        (status = 200, description = "The user's usage today and the limits in effect", body = UserQuotaStatus),
+    )
+)]
+async fn user_quota_handler(
+    path: web::Path<String>,
+    coordinator: web::Data<Arc<AgentOrchestrator>>,
+) -> HttpResponse {
+    HttpResponse::Ok().json(coordinator.user_quotas.status(&path.into_inner()))
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/admin/users/{user_id}/quota",
+    tag = "Users",
+    params(("user_id" = String, Path, description = "User ID")),
+    request_body = RaiseQuotaRequest,
+    responses(
+        (status = 200, description = "The user's usage and the raised limits", body = UserQuotaStatus),
+        (status = 400, description = "The raise has no duration"),
+    )
+)]
+async fn raise_user_quota_handler(
+    path: web::Path<String>,
+    body: web::Json<RaiseQuotaRequest>,
+    coordinator: web::Data<Arc<AgentOrchestrator>>,
+) -> HttpResponse {
+    if body.duration_secs == 0 {
+        return HttpResponse::BadRequest().json(json!({
+            "error": "duration_secs must be greater than 0"
+        }));
+    }
+    HttpResponse::Ok().json(coordinator.user_quotas.raise(&path.into_inner(), &body))
+}
+
+#[derive(Deserialize)]
+struct WatchThreadQuery {
+    token: String,
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/threads/{thread_id}/watch",
+    tag = "Threads",
+    params(
+        ("thread_id" = String, Path, description = "Thread ID"),
+        ("token" = String, Query, description = "Share token from POST /threads/{thread_id}/share"),
+    ),
+    responses(
+        (status = 200, description = "SSE stream of AgentEvents for the thread's runs"),
+        (status = 401, description = "Missing, expired or revoked token"),
+    )
+)]
+async fn watch_thread_handler(
+    path: web::Path<String>,
+    query: web::Query<WatchThreadQuery>,
+    coordinator: web::Data<Arc<AgentOrchestrator>>,
+) -> Either<
+    Sse<impl futures_util::stream::Stream<Item = Result<sse::Event, std::convert::Infallible>>>,
+    HttpResponse,
+> {
+    let thread_id = path.into_inner();
+    let token = query.into_inner().token;
+    match distri_core::agent::observer::verify_thread_share(&coordinator, &thread_id, &token).await
+    {
+        Ok(true) => {}
+        Ok(false) => {
+            return Either::Right(
+                HttpResponse::Unauthorized().json(json!({ "error": "Invalid or expired token" })),
+            )
+        }
+        Err(e) => {
+            return Either::Right(HttpResponse::InternalServerError().json(json!({
+                "error": format!("Failed to verify token: {}", e)
+            })))
+        }
+    }
+    let stream =
+        distri_core::agent::observer::watch_thread(coordinator.get_ref().clone(), thread_id, token);
+    Either::Left(Sse::from_stream(stream.map(|event| {
+        let payload = serde_json::to_string(&event).unwrap_or_else(|_| "{}".to_string());
+        Ok(sse::Event::Data(sse::Data::new(payload)))
+    })))
+}
+
 // ========== Message Read Status Handlers ==========
 
 #[utoipa::path(
@@ -1587,6 +2502,37 @@ async fn get_task_handler(
     }
 }
 
+#[utoipa::path(
+    get,
+    path = "/v1/tasks/compare",
+    tag = "Agents",
+    params(
+        ("baseline" = String, Query, description = "Task ID of the baseline"),
+        ("candidate" = String, Query, description = "Task ID of the candidate")
+    ),
+    responses(
+        (status = 200, description = "Both tasks step by step, aligned, with token and cost deltas"),
+        (status = 400, description = "The tasks were run by different agents"),
+        (status = 404, description = "Unknown task")
+    )
+)]
+async fn compare_tasks_handler(
+    query: web::Query<distri_types::task_diff::CompareTasksQuery>,
+    executor: web::Data<Arc<AgentOrchestrator>>,
+) -> HttpResponse {
+    match executor
+        .compare_tasks(&query.baseline, &query.candidate)
+        .await
+    {
+        Ok(comparison) => HttpResponse::Ok().json(comparison),
+        Err(AgentError::NotFound(e)) => HttpResponse::NotFound().json(json!({ "error": e })),
+        Err(AgentError::Validation(e)) => HttpResponse::BadRequest().json(json!({ "error": e })),
+        Err(e) => HttpResponse::InternalServerError().json(json!({
+            "error": format!("Failed to compare tasks: {}", e)
+        })),
+    }
+}
+
 #[utoipa::path(
     get,
     path = "/v1/tasks/{task_id}/events",
@@ -1647,6 +2593,96 @@ async fn compact_task_handler(
     }
 }
 
+#[utoipa::path(
+    post,
+    path = "/v1/tasks/{task_id}/workflow/rerun",
+    tag = "Agents",
+    params(("task_id" = String, Path, description = "Task ID of the workflow run")),
+    request_body(content = Object, description = "The step to re-run, with every step downstream of it"),
+    responses(
+        (status = 202, description = "Re-run started; lists the steps that run again"),
+        (status = 400, description = "Unknown step, changed upstream steps, or run still in progress"),
+        (status = 404, description = "Unknown workflow run")
+    )
+)]
+async fn rerun_workflow_handler(
+    path: web::Path<String>,
+    body: web::Json<distri_workflow::WorkflowRerunRequest>,
+    executor: web::Data<Arc<AgentOrchestrator>>,
+) -> HttpResponse {
+    let run_task_id = path.into_inner();
+    let from = body.into_inner().from;
+    let orchestrator: Arc<AgentOrchestrator> = executor.get_ref().clone();
+    match orchestrator.rerun_workflow(&run_task_id, &from).await {
+        Ok(steps) => HttpResponse::Accepted().json(distri_workflow::WorkflowRerunResponse {
+            run_task_id,
+            from,
+            steps,
+        }),
+        Err(AgentError::NotFound(e)) => HttpResponse::NotFound().json(json!({ "error": e })),
+        Err(AgentError::Validation(e)) => HttpResponse::BadRequest().json(json!({ "error": e })),
+        Err(e) => HttpResponse::InternalServerError().json(json!({
+            "error": format!("Failed to re-run workflow: {}", e)
+        })),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/tasks/{task_id}/retry",
+    tag = "Agents",
+    params(("task_id" = String, Path, description = "Task ID of the failed run")),
+    request_body = RetryRequest,
+    responses(
+        (status = 200, description = "The retry and the failure it was told about", body = TaskRetry),
+        (status = 400, description = "The task has not failed or has no user message"),
+        (status = 404, description = "Task not found"),
+        (status = 429, description = "Server at capacity or user over a quota; see Retry-After")
+    )
+)]
+async fn retry_task_handler(
+    path: web::Path<String>,
+    body: web::Json<RetryRequest>,
+    executor: web::Data<Arc<AgentOrchestrator>>,
+    admission: Option<web::Data<Admission>>,
+    http_request: HttpRequest,
+) -> HttpResponse {
+    let task_id = path.into_inner();
+    let user_id = http_request
+        .extensions()
+        .get::<UserContext>()
+        .map(|ctx| ctx.user_id());
+    let model_settings = http_request
+        .extensions()
+        .get::<distri_types::ModelSettings>()
+        .cloned();
+
+    // The run is admitted like a new message; the permit is held until it ends.
+    let _permit = match admit_run(
+        &executor.user_quotas,
+        admission.as_ref().map(|a| a.get_ref()),
+        user_id.as_deref().unwrap_or("local_dev_user"),
+        None,
+    )
+    .await
+    {
+        Ok(permit) => permit,
+        Err(rejection) => return rejection,
+    };
+
+    match executor
+        .retry_task(&task_id, body.into_inner(), user_id, model_settings)
+        .await
+    {
+        Ok(retry) => HttpResponse::Ok().json(retry),
+        Err(AgentError::NotFound(e)) => HttpResponse::NotFound().json(json!({ "error": e })),
+        Err(AgentError::Validation(e)) => HttpResponse::BadRequest().json(json!({ "error": e })),
+        Err(e) => HttpResponse::InternalServerError().json(json!({
+            "error": format!("Failed to retry task: {}", e)
+        })),
+    }
+}
+
 // Thread messages endpoint
 #[utoipa::path(
     get,
@@ -1662,6 +2698,13 @@ async fn get_thread_messages(
 ) -> HttpResponse {
     let thread_id = path.into_inner();
 
+    // Restores the thread's history when it is archived.
+    if let Err(e) = executor.get_thread(&thread_id).await {
+        return HttpResponse::InternalServerError().json(json!({
+            "error": format!("Failed to get thread: {}", e)
+        }));
+    }
+
     let query = query.map(|q| q.into_inner());
     match get_a2a_messages(executor.stores.task_store.clone(), &thread_id, query).await {
         Ok(messages) => HttpResponse::Ok().json(messages),
@@ -1671,6 +2714,152 @@ async fn get_thread_messages(
     }
 }
 
+#[utoipa::path(
+    post,
+    path = "/v1/threads/{thread_id}/regenerate",
+    tag = "Threads",
+    params(("thread_id" = String, Path, description = "Thread ID")),
+    request_body = RegenerateRequest,
+    responses(
+        (status = 200, description = "The new run and the archived tasks", body = Regeneration),
+        (status = 400, description = "The message's task has no user message"),
+        (status = 404, description = "Thread or message not found"),
+        (status = 429, description = "Server at capacity or user over a quota; see Retry-After")
+    )
+)]
+async fn regenerate_thread_handler(
+    path: web::Path<String>,
+    body: web::Json<RegenerateRequest>,
+    executor: web::Data<Arc<AgentOrchestrator>>,
+    admission: Option<web::Data<Admission>>,
+    http_request: HttpRequest,
+) -> HttpResponse {
+    let thread_id = path.into_inner();
+    let user_id = http_request
+        .extensions()
+        .get::<UserContext>()
+        .map(|ctx| ctx.user_id());
+    let model_settings = http_request
+        .extensions()
+        .get::<distri_types::ModelSettings>()
+        .cloned();
+
+    // The run is admitted like a new message; the permit is held until it ends.
+    let _permit = match admit_run(
+        &executor.user_quotas,
+        admission.as_ref().map(|a| a.get_ref()),
+        user_id.as_deref().unwrap_or("local_dev_user"),
+        None,
+    )
+    .await
+    {
+        Ok(permit) => permit,
+        Err(rejection) => return rejection,
+    };
+
+    match executor
+        .regenerate_from(&thread_id, body.into_inner(), user_id, model_settings)
+        .await
+    {
+        Ok(regeneration) => HttpResponse::Ok().json(regeneration),
+        Err(AgentError::NotFound(e)) => HttpResponse::NotFound().json(json!({ "error": e })),
+        Err(AgentError::Validation(e)) => HttpResponse::BadRequest().json(json!({ "error": e })),
+        Err(e) => HttpResponse::InternalServerError().json(json!({
+            "error": format!("Failed to regenerate thread: {}", e)
+        })),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/threads/{thread_id}/archive",
+    tag = "Threads",
+    params(("thread_id" = String, Path, description = "Thread ID")),
+    responses(
+        (status = 200, description = "The archived thread", body = ThreadArchiveSummary),
+        (status = 400, description = "The thread is already archived or has running tasks"),
+        (status = 404, description = "Thread not found")
+    )
+)]
+async fn archive_thread_handler(
+    path: web::Path<String>,
+    executor: web::Data<Arc<AgentOrchestrator>>,
+) -> HttpResponse {
+    thread_archive_response(executor.archive_thread(&path.into_inner()).await, "archive")
+}
+
+#[utoipa::path(
+    delete,
+    path = "/v1/threads/{thread_id}/archive",
+    tag = "Threads",
+    params(("thread_id" = String, Path, description = "Thread ID")),
+    responses(
+        (status = 200, description = "The restored thread", body = ThreadArchiveSummary),
+        (status = 400, description = "The thread is not archived"),
+        (status = 404, description = "Thread not found")
+    )
+)]
+async fn restore_thread_handler(
+    path: web::Path<String>,
+    executor: web::Data<Arc<AgentOrchestrator>>,
+) -> HttpResponse {
+    thread_archive_response(executor.restore_thread(&path.into_inner()).await, "restore")
+}
+
+fn thread_archive_response(
+    result: Result<ThreadArchiveSummary, AgentError>,
+    action: &str,
+) -> HttpResponse {
+    match result {
+        Ok(summary) => HttpResponse::Ok().json(summary),
+        Err(AgentError::NotFound(e)) => HttpResponse::NotFound().json(json!({ "error": e })),
+        Err(AgentError::Validation(e)) => HttpResponse::BadRequest().json(json!({ "error": e })),
+        Err(e) => HttpResponse::InternalServerError().json(json!({
+            "error": format!("Failed to {} thread: {}", action, e)
+        })),
+    }
+}
+
+#[derive(Deserialize)]
+struct ThreadSummaryQuery {
+    #[serde(default)]
+    refresh: bool,
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/threads/{thread_id}/summary",
+    tag = "Threads",
+    params(
+        ("thread_id" = String, Path, description = "Thread ID"),
+        ("refresh" = Option<bool>, Query, description = "Write a new summary even if the cached one is current")
+    ),
+    responses(
+        (status = 200, description = "Handover summary of the thread", body = HandoverSummary),
+        (status = 400, description = "The thread has no messages or its agent no model settings"),
+        (status = 404, description = "Thread not found")
+    )
+)]
+async fn thread_summary_handler(
+    path: web::Path<String>,
+    query: web::Query<ThreadSummaryQuery>,
+    executor: web::Data<Arc<AgentOrchestrator>>,
+) -> HttpResponse {
+    match executor
+        .handover_summary(&path.into_inner(), query.refresh)
+        .await
+    {
+        Ok(summary) => HttpResponse::Ok().json(summary),
+        Err(AgentError::NotFound(e)) => HttpResponse::NotFound().json(json!({ "error": e })),
+        Err(AgentError::Validation(e) | AgentError::InvalidConfiguration(e)) => {
+            HttpResponse::BadRequest().json(json!({ "error": e }))
+        }
+        Err(e) => HttpResponse::InternalServerError().json(json!({
+            "error": format!("Failed to summarize thread: {}", e)
+        })),
+    }
+}
+
 #[utoipa::path(
     get,
     path = "/v1/home/stats",
@@ -1679,7 +2868,10 @@ async fn get_thread_messages(
 )]
 async fn get_home_stats(executor: web::Data<Arc<AgentOrchestrator>>) -> HttpResponse {
     match executor.stores.thread_store.get_home_stats().await {
-        Ok(stats) => HttpResponse::Ok().json(stats),
+        Ok(mut stats) => {
+            stats.llm_latency = Some(executor.llm_metrics.snapshot());
+            HttpResponse::Ok().json(stats)
+        }
         Err(e) => {
             tracing::error!(error = ?e, "Failed to get home stats");
             HttpResponse::InternalServerError().json(json!({
@@ -1689,6 +2881,134 @@ async fn get_home_stats(executor: web::Data<Arc<AgentOrchestrator>>) -> HttpResp
     }
 }
 
+#[utoipa::path(
+    get,
+    path = "/v1/metrics",
+    tag = "Configuration",
+    responses((status = 200, description = "LLM latency metrics in the Prometheus text format", body = String, content_type = "text/plain"))
+)]
+async fn get_metrics(executor: web::Data<Arc<AgentOrchestrator>>) -> HttpResponse {
+    HttpResponse::Ok()
+        .content_type("text/plain; version=0.0.4")
+        .body(executor.llm_metrics.prometheus())
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/dev/seed",
+    tag = "Configuration",
+    responses((status = 200, description = "Seeded and skipped fixtures", body = DevSeedSummary))
+)]
+async fn dev_seed_handler(executor: web::Data<Arc<AgentOrchestrator>>) -> HttpResponse {
+    match executor.seed_dev_fixtures().await {
+        Ok(summary) => HttpResponse::Ok().json(summary),
+        Err(e) => HttpResponse::InternalServerError().json(json!({
+            "error": format!("Failed to seed dev fixtures: {}", e)
+        })),
+    }
+}
+
+/// The caller's user id, `local_dev_user` without an auth layer.
+fn request_user_id(http_request: &HttpRequest) -> String {
+    http_request
+        .extensions()
+        .get::<UserContext>()
+        .map(|ctx| ctx.user_id())
+        .unwrap_or_else(|| "local_dev_user".to_string())
+}
+
+fn user_profile_response(result: Result<UserProfile, AgentError>) -> HttpResponse {
+    match result {
+        Ok(profile) => HttpResponse::Ok().json(profile),
+        Err(AgentError::Validation(e)) => HttpResponse::BadRequest().json(json!({ "error": e })),
+        Err(e) => HttpResponse::InternalServerError().json(json!({
+            "error": format!("Failed to update user profile: {}", e)
+        })),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/users/me/profile",
+    tag = "Users",
+    responses((status = 200, description = "The caller's profile; empty when none is stored", body = UserProfile))
+)]
+async fn get_user_profile_handler(
+    executor: web::Data<Arc<AgentOrchestrator>>,
+    http_request: HttpRequest,
+) -> HttpResponse {
+    match executor
+        .get_user_profile(&request_user_id(&http_request))
+        .await
+    {
+        Ok(profile) => HttpResponse::Ok().json(profile),
+        Err(e) => HttpResponse::InternalServerError().json(json!({
+            "error": format!("Failed to load user profile: {}", e)
+        })),
+    }
+}
+
+#[utoipa::path(
+    put,
+    path = "/v1/users/me/profile",
+    tag = "Users",
+    request_body = UserProfile,
+    responses(
+        (status = 200, description = "The stored profile", body = UserProfile),
+        (status = 400, description = "Unknown language or timezone, or a preference too long")
+    )
+)]
+async fn set_user_profile_handler(
+    body: web::Json<UserProfile>,
+    executor: web::Data<Arc<AgentOrchestrator>>,
+    http_request: HttpRequest,
+) -> HttpResponse {
+    user_profile_response(
+        executor
+            .set_user_profile(&request_user_id(&http_request), body.into_inner())
+            .await,
+    )
+}
+
+#[utoipa::path(
+    patch,
+    path = "/v1/users/me/profile",
+    tag = "Users",
+    request_body(content = UserProfile, description = "Preferences to change; an empty string clears one"),
+    responses(
+        (status = 200, description = "The stored profile", body = UserProfile),
+        (status = 400, description = "Unknown language or timezone, or a preference too long")
+    )
+)]
+async fn update_user_profile_handler(
+    body: web::Json<UserProfile>,
+    executor: web::Data<Arc<AgentOrchestrator>>,
+    http_request: HttpRequest,
+) -> HttpResponse {
+    user_profile_response(
+        executor
+            .update_user_profile(&request_user_id(&http_request), body.into_inner())
+            .await,
+    )
+}
+
+#[utoipa::path(
+    delete,
+    path = "/v1/users/me/profile",
+    tag = "Users",
+    responses((status = 200, description = "Profile removed", body = UserProfile))
+)]
+async fn delete_user_profile_handler(
+    executor: web::Data<Arc<AgentOrchestrator>>,
+    http_request: HttpRequest,
+) -> HttpResponse {
+    user_profile_response(
+        executor
+            .set_user_profile(&request_user_id(&http_request), UserProfile::default())
+            .await,
+    )
+}
+
 #[utoipa::path(
     post,
     path = "/v1/agents",
@@ -1961,13 +3281,25 @@ async fn update_agent(
 ///
 /// Accepts `HttpRequestInput`, resolves `$VAR_NAME` from secrets/connections,
 /// executes the request, and returns `HttpRequestResponse`. Secrets never
-/// appear in the response.
+/// appear in the response. The global egress rules apply: a blocked request
+/// is answered with 403 and the `egress_denied` error.
+#[utoipa::path(
+    post,
+    path = "/v1/request",
+    tag = "Tools",
+    request_body(content = Object, description = "The request to send: method, url, headers and body"),
+    responses(
+        (status = 200, description = "The upstream response, with secrets resolved server-side"),
+        (status = 403, description = "The host is blocked by the egress rules"),
+    )
+)]
 async fn proxy_request_handler(
     executor: web::Data<Arc<AgentOrchestrator>>,
     body: web::Json<distri_types::http_request::HttpRequestInput>,
 ) -> HttpResponse {
-    use distri_core::tools::request::execute_http_request;
+    use distri_core::tools::request::{execute_http_request, EgressCheck};
     use distri_core::tools::resolve::ResolveContext;
+    use distri_types::egress::EgressDenied;
 
     let secret_store = executor.stores.secret_store.clone();
 
@@ -1975,9 +3307,14 @@ async fn proxy_request_handler(
         env_vars: std::collections::HashMap::new(),
         secret_store,
     };
+    let egress = EgressCheck::global(&executor);
 
-    match execute_http_request(&body, &resolve_ctx, Some(&executor.stores)).await {
+    match execute_http_request(&body, &resolve_ctx, Some(&executor.stores), egress.as_ref()).await {
         Ok(response) => HttpResponse::Ok().json(response),
+        Err(e) if e.is::<EgressDenied>() => {
+            let denied = e.downcast::<EgressDenied>().expect("checked above");
+            HttpResponse::Forbidden().json(denied.to_json())
+        }
         Err(e) => {
             tracing::warn!(error = ?e, "Request proxy failed");
             HttpResponse::BadRequest().json(serde_json::json!({ "error": e.to_string() }))
@@ -1998,7 +3335,10 @@ async fn delete_agent(
 ) -> HttpResponse {
     let id = path.into_inner();
     match executor.stores.agent_store.delete(&id).await {
-        Ok(()) => HttpResponse::NoContent().finish(),
+        Ok(()) => {
+            executor.notify_agents_changed();
+            HttpResponse::NoContent().finish()
+        }
         Err(err) => {
             tracing::warn!(error = ?err, "Failed to delete agent");
             HttpResponse::NotFound().json(json!({ "error": format!("Agent not found: {}", id) }))
@@ -2018,6 +3358,47 @@ async fn get_agent_schema() -> HttpResponse {
     HttpResponse::Ok().json(schema)
 }
 
+#[derive(Debug, Deserialize)]
+pub struct ToolResolveQuery {
+    /// Tool name, alias or qualified `namespace/tool` name.
+    name: String,
+}
+
+/// Which of an agent's tools a call to `name` runs, and what it shadows.
+#[utoipa::path(
+    get,
+    path = "/v1/agents/{id}/tools/resolve",
+    tag = "Agents",
+    params(
+        ("id" = String, Path, description = "Agent ID"),
+        ("name" = String, Query, description = "Tool name, alias or qualified name")
+    ),
+    responses(
+        (status = 200, description = "How the name resolves", body = ToolResolution),
+        (status = 400, description = "The agent's tools are misconfigured"),
+        (status = 404, description = "Agent not found")
+    )
+)]
+async fn resolve_agent_tool_handler(
+    id: web::Path<String>,
+    query: web::Query<ToolResolveQuery>,
+    executor: web::Data<Arc<AgentOrchestrator>>,
+) -> HttpResponse {
+    match executor
+        .resolve_agent_tool(&id.into_inner(), &query.name)
+        .await
+    {
+        Ok(resolution) => HttpResponse::Ok().json(resolution),
+        Err(AgentError::NotFound(e)) => HttpResponse::NotFound().json(json!({ "error": e })),
+        Err(AgentError::Validation(e) | AgentError::InvalidConfiguration(e)) => {
+            HttpResponse::BadRequest().json(json!({ "error": e }))
+        }
+        Err(e) => HttpResponse::InternalServerError().json(json!({
+            "error": format!("Failed to resolve tool: {}", e)
+        })),
+    }
+}
+
 /// Get DAG representation for an agent
 #[utoipa::path(
     get,
@@ -2071,6 +3452,17 @@ struct CompleteToolRequest {
 }
 
 /// Complete an external tool execution
+#[utoipa::path(
+    post,
+    path = "/v1/agents/{id}/complete-tool",
+    tag = "Tools",
+    params(("id" = String, Path, description = "Agent ID")),
+    request_body(content = Object, description = "The tool call id and its response"),
+    responses(
+        (status = 200, description = "The waiting run resumes with the tool response"),
+        (status = 400, description = "No run is waiting for the tool call"),
+    )
+)]
 async fn complete_tool_handler(
     request: web::Json<CompleteToolRequest>,
     executor: web::Data<Arc<AgentOrchestrator>>,
@@ -2095,6 +3487,16 @@ async fn complete_tool_handler(
 
 /// Complete an inline hook call. This is used when hooks are configured as `inline`
 /// and the agent is awaiting a mutation before resuming execution.
+#[utoipa::path(
+    post,
+    path = "/v1/event/hooks",
+    tag = "Agents",
+    request_body(content = Object, description = "The hook id and the mutation to apply"),
+    responses(
+        (status = 200, description = "The waiting run resumes with the mutation"),
+        (status = 400, description = "No run is waiting for the hook"),
+    )
+)]
 async fn complete_hook_handler(
     request: web::Json<InlineHookResponse>,
     executor: web::Data<Arc<AgentOrchestrator>>,
@@ -2115,8 +3517,42 @@ async fn complete_hook_handler(
     }
 }
 
+/// Answer an `AuthRequired` consent prompt; the paused tool call resumes
+/// (or fails with the missing scopes) once this lands.
+#[utoipa::path(
+    post,
+    path = "/v1/event/auth",
+    tag = "Auth",
+    request_body = AuthConsentResponse,
+    responses(
+        (status = 200, description = "Consent recorded; the paused tool call resumes"),
+        (status = 400, description = "No tool call is waiting for the consent"),
+    )
+)]
+async fn complete_auth_handler(
+    request: web::Json<AuthConsentResponse>,
+    executor: web::Data<Arc<AgentOrchestrator>>,
+) -> HttpResponse {
+    match executor.complete_auth_consent(request.into_inner()).await {
+        Ok(()) => HttpResponse::Ok().json(json!({
+            "success": true,
+            "message": "Auth consent recorded"
+        })),
+        Err(e) => HttpResponse::BadRequest().json(json!({
+            "success": false,
+            "error": e
+        })),
+    }
+}
+
 /// Create a new browser session via browsr
 /// Returns the session info directly from browsr (session_id, viewer_url, stream_url)
+#[utoipa::path(
+    post,
+    path = "/v1/browser/session",
+    tag = "Tools",
+    responses((status = 200, description = "The browser session (session_id, viewer_url, stream_url)"))
+)]
 async fn create_browser_session() -> HttpResponse {
     let client = browsr_client::BrowsrClient::from_env();
     tracing::info!(
@@ -0,0 +1,156 @@
+//! Agent analytics endpoints for the dashboard.
+//!
+//! ```text
+//! GET /v1/analytics/runs?from=2026-10-01&to=2026-10-17&agent_id=X → RunAnalytics
+//! GET /v1/analytics/agents?from=…&to=…                             → AgentAnalytics
+//! GET /v1/analytics/tools?from=…&to=…&agent_id=X&limit=10          → ToolAnalytics
+//! ```
+//!
+//! Served from the daily counters the task store keeps as it stores run and
+//! tool events, so the cost of a request grows with the range rather than
+//! with the history. When the task store keeps no counters
+//! (`analytics_store` is `None`), the endpoints return 503.
+
+use actix_web::{web, HttpResponse};
+use chrono::Utc;
+use distri_core::agent::AgentOrchestrator;
+use distri_types::api::analytics::{
+    AgentAnalytics, AnalyticsQuery, DateRange, RunAnalytics, ToolAnalytics, DEFAULT_TOOL_LIMIT,
+};
+use distri_types::stores::AnalyticsStore;
+use serde_json::json;
+use std::sync::Arc;
+
+/// Most tools `/analytics/tools` lists.
+const MAX_TOOL_LIMIT: usize = 100;
+
+// ── Route registration ────────────────────────────────────────────────────────
+
+pub fn configure_analytics_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(web::resource("/analytics/runs").route(web::get().to(get_run_analytics)))
+        .service(web::resource("/analytics/agents").route(web::get().to(get_agent_analytics)))
+        .service(web::resource("/analytics/tools").route(web::get().to(get_tool_analytics)));
+}
+
+/// The analytics store and the query's range, or the error response.
+fn resolve<'a>(
+    executor: &'a AgentOrchestrator,
+    query: &AnalyticsQuery,
+) -> Result<(&'a Arc<dyn AnalyticsStore>, DateRange), HttpResponse> {
+    let Some(store) = &executor.stores.analytics_store else {
+        return Err(HttpResponse::ServiceUnavailable()
+            .json(json!({"error": "Analytics store not configured"})));
+    };
+    let range = query
+        .range(Utc::now().date_naive())
+        .map_err(|e| HttpResponse::BadRequest().json(json!({"error": e})))?;
+    Ok((store, range))
+}
+
+fn query_failed(e: anyhow::Error) -> HttpResponse {
+    tracing::error!(error = ?e, "Failed to query analytics");
+    HttpResponse::InternalServerError().json(json!({"error": "Failed to query analytics"}))
+}
+
+// ── GET /analytics/runs ───────────────────────────────────────────────────────
+
+/// Runs per day: counts, success rate, steps per run, tokens and cost.
+#[utoipa::path(
+    get,
+    path = "/v1/analytics/runs",
+    tag = "Analytics",
+    params(
+        ("from" = Option<String>, Query, description = "First day (YYYY-MM-DD, UTC). Default: 30 days before `to`."),
+        ("to" = Option<String>, Query, description = "Last day (YYYY-MM-DD, UTC). Default: today."),
+        ("agent_id" = Option<String>, Query, description = "Only count this agent's runs."),
+    ),
+    responses(
+        (status = 200, description = "Totals and one entry per day of the range", body = RunAnalytics),
+        (status = 400, description = "Invalid range"),
+        (status = 503, description = "Analytics store not configured"),
+        (status = 500, description = "Internal server error"),
+    )
+)]
+pub async fn get_run_analytics(
+    executor: web::Data<Arc<AgentOrchestrator>>,
+    query: web::Query<AnalyticsQuery>,
+) -> HttpResponse {
+    let (store, range) = match resolve(&executor, &query) {
+        Ok(resolved) => resolved,
+        Err(response) => return response,
+    };
+    match store.run_counters(&range, query.agent_id.as_deref()).await {
+        Ok(rows) => HttpResponse::Ok().json(RunAnalytics::from_rows(range, &rows)),
+        Err(e) => query_failed(e),
+    }
+}
+
+// ── GET /analytics/agents ─────────────────────────────────────────────────────
+
+/// Run stats per agent, most runs first.
+#[utoipa::path(
+    get,
+    path = "/v1/analytics/agents",
+    tag = "Analytics",
+    params(
+        ("from" = Option<String>, Query, description = "First day (YYYY-MM-DD, UTC). Default: 30 days before `to`."),
+        ("to" = Option<String>, Query, description = "Last day (YYYY-MM-DD, UTC). Default: today."),
+    ),
+    responses(
+        (status = 200, description = "Agents with runs in the range", body = AgentAnalytics),
+        (status = 400, description = "Invalid range"),
+        (status = 503, description = "Analytics store not configured"),
+        (status = 500, description = "Internal server error"),
+    )
+)]
+pub async fn get_agent_analytics(
+    executor: web::Data<Arc<AgentOrchestrator>>,
+    query: web::Query<AnalyticsQuery>,
+) -> HttpResponse {
+    let (store, range) = match resolve(&executor, &query) {
+        Ok(resolved) => resolved,
+        Err(response) => return response,
+    };
+    match store.run_counters(&range, None).await {
+        Ok(rows) => HttpResponse::Ok().json(AgentAnalytics::from_rows(range, &rows)),
+        Err(e) => query_failed(e),
+    }
+}
+
+// ── GET /analytics/tools ──────────────────────────────────────────────────────
+
+/// The most called tools, with their failure rates.
+#[utoipa::path(
+    get,
+    path = "/v1/analytics/tools",
+    tag = "Analytics",
+    params(
+        ("from" = Option<String>, Query, description = "First day (YYYY-MM-DD, UTC). Default: 30 days before `to`."),
+        ("to" = Option<String>, Query, description = "Last day (YYYY-MM-DD, UTC). Default: today."),
+        ("agent_id" = Option<String>, Query, description = "Only count this agent's calls."),
+        ("limit" = Option<usize>, Query, description = "Most tools listed (max 100). Default: 10."),
+    ),
+    responses(
+        (status = 200, description = "Tools by number of calls", body = ToolAnalytics),
+        (status = 400, description = "Invalid range"),
+        (status = 503, description = "Analytics store not configured"),
+        (status = 500, description = "Internal server error"),
+    )
+)]
+pub async fn get_tool_analytics(
+    executor: web::Data<Arc<AgentOrchestrator>>,
+    query: web::Query<AnalyticsQuery>,
+) -> HttpResponse {
+    let (store, range) = match resolve(&executor, &query) {
+        Ok(resolved) => resolved,
+        Err(response) => return response,
+    };
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_TOOL_LIMIT)
+        .min(MAX_TOOL_LIMIT);
+    match store.tool_counters(&range, query.agent_id.as_deref()).await {
+        Ok(rows) => HttpResponse::Ok().json(ToolAnalytics::from_rows(range, &rows, limit)),
+        Err(e) => query_failed(e),
+    }
+}
@@ -1,9 +1,14 @@
+use std::ops::Range;
 use std::sync::Arc;
+use std::time::Duration;
 
-use actix_web::{web, HttpResponse};
+use actix_files::HttpRange;
+use actix_web::middleware::DefaultHeaders;
+use actix_web::{http::header, web, HttpRequest, HttpResponse};
 use distri_core::agent::AgentOrchestrator;
-use distri_filesystem::ArtifactWrapper;
+use distri_filesystem::{ArtifactWrapper, FileSystem, ObjectStat};
 use distri_types::filesystem::FileSystemOps;
+use futures_util::StreamExt;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 
@@ -18,28 +23,59 @@ use serde_json::json;
 /// - GET /artifacts - List all accessible artifact namespaces
 /// - GET /artifacts/{artifact_id...} - List artifacts in a namespace
 /// - GET /artifacts/{artifact_id...}/content/{filename} - Read artifact content
+/// - GET /artifacts/{artifact_id...}/download/{filename} - Stream the raw file (supports `Range`)
 /// - PUT /artifacts/{artifact_id...}/content/{filename} - Save artifact
 /// - DELETE /artifacts/{artifact_id...}/content/{filename} - Delete artifact
 /// - POST /artifacts/{artifact_id...}/search - Search within artifacts
+///
+/// Every response carries [`artifact_security_headers`], so artifact bytes
+/// are never sniffed or run as a page of the server's origin.
 pub fn configure_artifact_routes(cfg: &mut web::ServiceConfig) {
     cfg
         // List all accessible artifact namespaces
-        .route("", web::get().to(list_all_namespaces))
+        .service(
+            web::resource("")
+                .wrap(artifact_security_headers())
+                .route(web::get().to(list_all_namespaces)),
+        )
         // Compute task namespace from thread_id and task_id (convenience endpoint)
-        .route(
-            "/task/{thread_id}/{task_id}",
-            web::get().to(get_task_namespace),
+        .service(
+            web::resource("/task/{thread_id}/{task_id}")
+                .wrap(artifact_security_headers())
+                .route(web::get().to(get_task_namespace)),
         )
         // Operations on a specific namespace (artifact_id is the full path like "threads/abc/tasks/def")
         .service(
             web::resource("/{artifact_id:.*}/content/{filename}")
+                .wrap(artifact_security_headers())
                 .route(web::get().to(read_artifact))
                 .route(web::put().to(save_artifact))
                 .route(web::delete().to(delete_artifact)),
         )
-        .service(web::resource("/{artifact_id:.*}/search").route(web::post().to(search_artifacts)))
+        .service(
+            web::resource("/{artifact_id:.*}/download/{filename}")
+                .wrap(artifact_security_headers())
+                .route(web::get().to(download_artifact)),
+        )
+        .service(
+            web::resource("/{artifact_id:.*}/search")
+                .wrap(artifact_security_headers())
+                .route(web::post().to(search_artifacts)),
+        )
         // List artifacts in a namespace (must come last due to catch-all pattern)
-        .service(web::resource("/{artifact_id:.*}").route(web::get().to(list_artifacts)));
+        .service(
+            web::resource("/{artifact_id:.*}")
+                .wrap(artifact_security_headers())
+                .route(web::get().to(list_artifacts)),
+        );
+}
+
+/// `nosniff`, and a `sandbox` CSP so that an artifact opened in the browser
+/// runs no scripts and gets an opaque origin.
+fn artifact_security_headers() -> DefaultHeaders {
+    DefaultHeaders::new()
+        .add((header::X_CONTENT_TYPE_OPTIONS, "nosniff"))
+        .add((header::CONTENT_SECURITY_POLICY, "sandbox"))
 }
 
 // ─────────────────────────────────────────────────────────────────────────────
@@ -84,16 +120,16 @@ struct ArtifactListResponse {
 }
 
 #[derive(Debug, Serialize)]
-struct ArtifactEntry {
+pub(crate) struct ArtifactEntry {
     /// Just the filename (e.g., "data.json")
-    filename: String,
+    pub(crate) filename: String,
     /// Whether this is a file
-    is_file: bool,
+    pub(crate) is_file: bool,
     /// File size in bytes
     #[serde(skip_serializing_if = "Option::is_none")]
-    size: Option<u64>,
+    pub(crate) size: Option<u64>,
     /// Full path to read this artifact
-    read_path: String,
+    pub(crate) read_path: String,
 }
 
 #[derive(Debug, Serialize)]
@@ -120,6 +156,16 @@ struct ReadArtifactQuery {
     end_line: Option<u64>,
 }
 
+#[derive(Debug, Deserialize)]
+struct DownloadArtifactQuery {
+    /// Redirect to a presigned object-store URL when the backend can sign one
+    #[serde(default)]
+    presigned: bool,
+    /// Serve as an attachment instead of inline
+    #[serde(default)]
+    download: bool,
+}
+
 #[derive(Debug, Deserialize)]
 struct SaveArtifactRequest {
     content: String,
@@ -223,10 +269,23 @@ async fn list_artifacts(
         return list_all_namespaces(executor).await;
     }
 
+    HttpResponse::Ok().json(ArtifactListResponse {
+        artifacts: collect_artifacts(&executor, &artifact_id).await,
+        artifact_id: artifact_id.clone(),
+        content_path: format!("{}/content", artifact_id),
+    })
+}
+
+/// The artifacts of a namespace, from the task level and the thread level
+/// (see [`ArtifactWrapper::get_paths_to_check`]).
+pub(crate) async fn collect_artifacts(
+    executor: &AgentOrchestrator,
+    artifact_id: &str,
+) -> Vec<ArtifactEntry> {
     let filesystem = executor.session_filesystem.clone();
 
     // Use ArtifactWrapper helper to list artifacts from all paths (thread and task level)
-    let paths_to_check = ArtifactWrapper::get_paths_to_check(&artifact_id);
+    let paths_to_check = ArtifactWrapper::get_paths_to_check(artifact_id);
     let mut all_artifacts: Vec<ArtifactEntry> = Vec::new();
     let mut seen_filenames = std::collections::HashSet::new();
 
@@ -255,11 +314,7 @@ async fn list_artifacts(
         }
     }
 
-    HttpResponse::Ok().json(ArtifactListResponse {
-        artifact_id: artifact_id.clone(),
-        artifacts: all_artifacts,
-        content_path: format!("{}/content", artifact_id),
-    })
+    all_artifacts
 }
 
 /// Read a specific artifact
@@ -309,6 +364,238 @@ async fn read_artifact(
     }
 }
 
+/// How long a presigned download URL stays valid
+const PRESIGNED_URL_TTL: Duration = Duration::from_secs(300);
+
+/// Bytes read to guess the type of a file without a known extension
+const SNIFF_LEN: u64 = 512;
+
+/// Stream the raw bytes of an artifact without loading it into memory.
+///
+/// Honors single `Range` requests (206 with `Content-Range`) and `If-Range`,
+/// so interrupted downloads can resume, and sends `ETag`/`Last-Modified` for
+/// the client to validate against. With `?presigned=true` the client is
+/// redirected to the object store when the backend can sign URLs.
+async fn download_artifact(
+    req: HttpRequest,
+    path: web::Path<(String, String)>,
+    query: web::Query<DownloadArtifactQuery>,
+    executor: web::Data<Arc<AgentOrchestrator>>,
+) -> HttpResponse {
+    let (artifact_id, filename) = path.into_inner();
+
+    let filesystem = executor.session_filesystem.clone();
+
+    // Same lookup order as read_artifact: thread level, then task level
+    let mut found = None;
+    for path_id in ArtifactWrapper::get_paths_to_check(&artifact_id) {
+        let object_path = format!("{}/content/{}", path_id, filename);
+        if let Ok(stat) = filesystem.stat(&object_path).await {
+            found = Some((object_path, stat));
+            break;
+        }
+    }
+    let Some((object_path, stat)) = found else {
+        return HttpResponse::NotFound().json(json!({
+            "error": format!("Artifact not found: {}", filename),
+            "artifact_id": artifact_id
+        }));
+    };
+
+    if query.presigned {
+        match filesystem
+            .presigned_url(&object_path, PRESIGNED_URL_TTL)
+            .await
+        {
+            Ok(Some(url)) => {
+                return HttpResponse::TemporaryRedirect()
+                    .insert_header((header::LOCATION, url))
+                    .finish();
+            }
+            // The backend cannot sign URLs; serve it from here
+            Ok(None) => {}
+            Err(e) => tracing::warn!("Failed to presign artifact {}: {}", object_path, e),
+        }
+    }
+
+    let range = if range_applies(&req, &stat) {
+        let value = req
+            .headers()
+            .get(header::RANGE)
+            .and_then(|v| v.to_str().ok());
+        match value.map(|v| HttpRange::parse(v, stat.size)) {
+            None => None,
+            Some(Ok(ranges)) => ranges.first().map(|r| r.start..r.start + r.length),
+            Some(Err(_)) => {
+                return HttpResponse::RangeNotSatisfiable()
+                    .insert_header((header::CONTENT_RANGE, format!("bytes */{}", stat.size)))
+                    .finish();
+            }
+        }
+    } else {
+        None
+    };
+
+    let content_type = artifact_content_type(&filesystem, &object_path, &filename, &stat).await;
+    // Markup and scripts are only ever downloaded, never rendered inline.
+    let disposition = if query.download || is_active_content(&content_type) {
+        "attachment"
+    } else {
+        "inline"
+    };
+    let length = range.as_ref().map_or(stat.size, |r| r.end - r.start);
+
+    let stream = match filesystem.read_stream(&object_path, range.clone()).await {
+        Ok(stream) => stream,
+        Err(e) => {
+            return HttpResponse::InternalServerError().json(json!({
+                "error": format!("Failed to read artifact: {}", e)
+            }));
+        }
+    };
+
+    let mut response = match &range {
+        Some(r) => {
+            let mut response = HttpResponse::PartialContent();
+            response.insert_header((
+                header::CONTENT_RANGE,
+                format!("bytes {}-{}/{}", r.start, r.end - 1, stat.size),
+            ));
+            response
+        }
+        None => HttpResponse::Ok(),
+    };
+    response
+        .insert_header((header::ACCEPT_RANGES, "bytes"))
+        .insert_header((header::CONTENT_TYPE, content_type))
+        .insert_header((
+            header::CONTENT_DISPOSITION,
+            format!(
+                "{}; filename=\"{}\"",
+                disposition,
+                filename.replace(['"', '\\'], "")
+            ),
+        ))
+        .insert_header((header::LAST_MODIFIED, stat.last_modified.to_rfc2822()));
+    if let Some(etag) = entity_tag(&stat) {
+        response.insert_header((header::ETAG, etag));
+    }
+    response.no_chunking(length).streaming(stream)
+}
+
+/// The artifact's ETag as a quoted header value
+fn entity_tag(stat: &ObjectStat) -> Option<String> {
+    stat.e_tag.as_ref().map(|tag| {
+        if tag.starts_with('"') || tag.starts_with("W/") {
+            tag.clone()
+        } else {
+            format!("\"{}\"", tag)
+        }
+    })
+}
+
+/// Whether a `Range` header may be honored: always without `If-Range`,
+/// otherwise only while the artifact still matches its validator.
+fn range_applies(req: &HttpRequest, stat: &ObjectStat) -> bool {
+    let Some(validator) = req
+        .headers()
+        .get(header::IF_RANGE)
+        .and_then(|v| v.to_str().ok())
+    else {
+        return true;
+    };
+    if validator.starts_with('"') || validator.starts_with("W/") {
+        // Weak tags never match for ranges
+        return !validator.starts_with("W/") && entity_tag(stat).as_deref() == Some(validator);
+    }
+    chrono::DateTime::parse_from_rfc2822(validator)
+        .map(|date| date.timestamp() == stat.last_modified.timestamp())
+        .unwrap_or(false)
+}
+
+/// Content type from the file extension, else sniffed from its first bytes
+async fn artifact_content_type(
+    filesystem: &FileSystem,
+    path: &str,
+    filename: &str,
+    stat: &ObjectStat,
+) -> String {
+    if let Some((_, ext)) = filename.rsplit_once('.') {
+        let mime = actix_files::file_extension_to_mime(ext);
+        if mime.essence_str() != "application/octet-stream" {
+            return mime.to_string();
+        }
+    }
+    let head = read_prefix(filesystem, path, 0..stat.size.min(SNIFF_LEN))
+        .await
+        .unwrap_or_default();
+    sniff_content_type(&head).to_string()
+}
+
+/// Whether a browser would run `content_type` as a document or script:
+/// HTML, XML (which covers SVG) and JavaScript.
+fn is_active_content(content_type: &str) -> bool {
+    let essence = content_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase();
+    ["html", "xml", "javascript", "ecmascript"]
+        .iter()
+        .any(|kind| essence.contains(kind))
+}
+
+async fn read_prefix(
+    filesystem: &FileSystem,
+    path: &str,
+    range: Range<u64>,
+) -> anyhow::Result<Vec<u8>> {
+    if range.is_empty() {
+        return Ok(Vec::new());
+    }
+    let mut stream = filesystem.read_stream(path, Some(range)).await?;
+    let mut bytes = Vec::new();
+    while let Some(chunk) = stream.next().await {
+        bytes.extend_from_slice(&chunk?);
+    }
+    Ok(bytes)
+}
+
+/// Guess a content type from the first bytes of a file
+fn sniff_content_type(head: &[u8]) -> &'static str {
+    const SIGNATURES: &[(&[u8], &str)] = &[
+        (b"\x89PNG\r\n\x1a\n", "image/png"),
+        (b"\xff\xd8\xff", "image/jpeg"),
+        (b"GIF8", "image/gif"),
+        (b"%PDF-", "application/pdf"),
+        (b"PK\x03\x04", "application/zip"),
+        (b"\x1f\x8b", "application/gzip"),
+    ];
+    if let Some(mime) = SIGNATURES
+        .iter()
+        .find(|(magic, _)| head.starts_with(magic))
+        .map(|(_, mime)| *mime)
+    {
+        return mime;
+    }
+    // The prefix may end inside a multi-byte character
+    let text = match std::str::from_utf8(head) {
+        Ok(text) => text,
+        Err(e) if e.error_len().is_none() => {
+            std::str::from_utf8(&head[..e.valid_up_to()]).unwrap_or_default()
+        }
+        Err(_) => return "application/octet-stream",
+    };
+    if text.contains('\0') {
+        return "application/octet-stream";
+    }
+    match text.trim_start().chars().next() {
+        Some('{') | Some('[') => "application/json",
+        _ => "text/plain; charset=utf-8",
+    }
+}
+
 /// Save an artifact
 async fn save_artifact(
     path: web::Path<(String, String)>,
@@ -328,7 +615,7 @@ async fn save_artifact(
     )
     .await
     {
-        Ok(w) => w,
+        Ok(w) => w.with_index(filesystem.artifact_index()),
         Err(e) => {
             return HttpResponse::InternalServerError().json(json!({
                 "error": format!("Failed to create artifact wrapper: {}", e)
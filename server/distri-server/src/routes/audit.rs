@@ -0,0 +1,115 @@
+//! LLM audit-log read endpoints for OSS distri-server.
+//!
+//! ```text
+//! GET /v1/audit/llm?agent_id=X&user_id=Y&from=T&to=T&limit=N  → ListLlmAuditResponse
+//! GET /v1/audit/llm/export?...                                 → JSON Lines
+//! ```
+//!
+//! Records are written by `distri_core::llm_audit::AuditedLlmExecutor`.
+//! When `llm_audit_store` is `None` (auditing disabled) the endpoints
+//! return 503.
+
+use actix_web::{web, HttpResponse};
+use distri_core::agent::AgentOrchestrator;
+use distri_types::api::audit::{to_jsonl, ListLlmAuditResponse, LlmAuditQuery};
+use serde_json::json;
+use std::sync::Arc;
+
+// ── Route registration ────────────────────────────────────────────────────
+
+pub fn configure_audit_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(web::resource("/audit/llm").route(web::get().to(list_llm_audit)))
+        .service(web::resource("/audit/llm/export").route(web::get().to(export_llm_audit)));
+}
+
+// ── GET /audit/llm ────────────────────────────────────────────────────────
+
+/// List recorded LLM exchanges, newest first.
+#[utoipa::path(
+    get,
+    path = "/v1/audit/llm",
+    tag = "Audit",
+    params(
+        ("agent_id" = Option<String>, Query, description = "Filter by agent id"),
+        ("user_id" = Option<String>, Query, description = "Filter by user id"),
+        ("from" = Option<String>, Query, description = "RFC 3339 lower bound (inclusive)"),
+        ("to" = Option<String>, Query, description = "RFC 3339 upper bound (exclusive)"),
+        ("limit" = Option<usize>, Query, description = "Maximum records returned"),
+    ),
+    responses(
+        (status = 200, description = "Audit records", body = ListLlmAuditResponse),
+        (status = 503, description = "LLM audit log not enabled"),
+        (status = 500, description = "Internal server error"),
+    )
+)]
+pub async fn list_llm_audit(
+    executor: web::Data<Arc<AgentOrchestrator>>,
+    query: web::Query<LlmAuditQuery>,
+) -> HttpResponse {
+    let Some(store) = &executor.stores.llm_audit_store else {
+        return HttpResponse::ServiceUnavailable()
+            .json(json!({"error": "LLM audit log not enabled"}));
+    };
+
+    match store.list(&query.into_inner()).await {
+        Ok(records) => HttpResponse::Ok().json(ListLlmAuditResponse { records }),
+        Err(e) => {
+            tracing::error!("Failed to list LLM audit records: {}", e);
+            HttpResponse::InternalServerError()
+                .json(json!({"error": "Failed to list LLM audit records"}))
+        }
+    }
+}
+
+// ── GET /audit/llm/export ─────────────────────────────────────────────────
+
+/// Export recorded LLM exchanges as JSON Lines for compliance review.
+#[utoipa::path(
+    get,
+    path = "/v1/audit/llm/export",
+    tag = "Audit",
+    params(
+        ("agent_id" = Option<String>, Query, description = "Filter by agent id"),
+        ("user_id" = Option<String>, Query, description = "Filter by user id"),
+        ("from" = Option<String>, Query, description = "RFC 3339 lower bound (inclusive)"),
+        ("to" = Option<String>, Query, description = "RFC 3339 upper bound (exclusive)"),
+        ("limit" = Option<usize>, Query, description = "Maximum records exported"),
+    ),
+    responses(
+        (status = 200, description = "One JSON audit record per line", content_type = "application/x-ndjson"),
+        (status = 503, description = "LLM audit log not enabled"),
+        (status = 500, description = "Internal server error"),
+    )
+)]
+pub async fn export_llm_audit(
+    executor: web::Data<Arc<AgentOrchestrator>>,
+    query: web::Query<LlmAuditQuery>,
+) -> HttpResponse {
+    let Some(store) = &executor.stores.llm_audit_store else {
+        return HttpResponse::ServiceUnavailable()
+            .json(json!({"error": "LLM audit log not enabled"}));
+    };
+
+    let records = match store.list(&query.into_inner()).await {
+        Ok(records) => records,
+        Err(e) => {
+            tracing::error!("Failed to export LLM audit records: {}", e);
+            return HttpResponse::InternalServerError()
+                .json(json!({"error": "Failed to export LLM audit records"}));
+        }
+    };
+    match to_jsonl(&records) {
+        Ok(body) => HttpResponse::Ok()
+            .content_type("application/x-ndjson")
+            .insert_header((
+                "Content-Disposition",
+                "attachment; filename=\"llm-audit.jsonl\"",
+            ))
+            .body(body),
+        Err(e) => {
+            tracing::error!("Failed to serialize LLM audit records: {}", e);
+            HttpResponse::InternalServerError()
+                .json(json!({"error": "Failed to export LLM audit records"}))
+        }
+    }
+}
@@ -0,0 +1,231 @@
+//! Slash-command discovery for chat UIs.
+//!
+//! ```text
+//! GET  /v1/commands?agent=X   → ListSlashCommandsResponse
+//! POST /v1/commands/render    → RenderSlashCommandResponse
+//! POST /v1/commands/run       → RunSlashCommandResponse
+//! ```
+//!
+//! The command surface is the built-ins, then the user-defined commands
+//! registered on the orchestrator (the workspace `commands/` directory),
+//! then — when `agent` is given — the agent's own declared commands.
+//! Commands bound to a `tool` are run, not rendered: the agent's tool is
+//! called directly with the command's args.
+
+use actix_web::{web, HttpMessage, HttpRequest, HttpResponse};
+use distri_core::agent::{AgentOrchestrator, ExecutorContext};
+use distri_core::types::ToolCall;
+use distri_types::channel_commands::{
+    resolve_commands_with, system_commands, ListSlashCommandsResponse, RenderSlashCommandRequest,
+    RenderSlashCommandResponse, RunSlashCommandResponse, SlashCommand,
+};
+use distri_types::configuration::AgentConfig;
+use serde::Deserialize;
+use serde_json::json;
+use std::sync::Arc;
+
+use crate::admission::{admit_run, Admission};
+use crate::context::UserContext;
+
+#[derive(Debug, Deserialize)]
+pub struct ListCommandsQuery {
+    /// Include this agent's declared commands.
+    pub agent: Option<String>,
+}
+
+// ── Route registration ────────────────────────────────────────────────────
+
+pub fn configure_command_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(web::resource("/commands").route(web::get().to(list_commands)))
+        .service(web::resource("/commands/render").route(web::post().to(render_command)))
+        .service(web::resource("/commands/run").route(web::post().to(run_command)));
+}
+
+async fn resolve(
+    executor: &AgentOrchestrator,
+    agent: Option<&str>,
+) -> Result<Vec<SlashCommand>, HttpResponse> {
+    let extra = executor.slash_commands.read().await.clone();
+    match agent {
+        Some(name) => match executor.get_agent(name).await {
+            Some(config) => Ok(resolve_commands_with(&config, &extra)),
+            None => Err(HttpResponse::NotFound()
+                .json(json!({"error": format!("Agent '{}' not found", name)}))),
+        },
+        None => {
+            let mut out = system_commands();
+            out.retain(|c| !extra.iter().any(|e| e.name == c.name));
+            out.extend(extra);
+            Ok(out)
+        }
+    }
+}
+
+/// The command `req` invokes; later layers shadow earlier ones.
+async fn find(
+    executor: &AgentOrchestrator,
+    req: &RenderSlashCommandRequest,
+) -> Result<SlashCommand, HttpResponse> {
+    let commands = resolve(executor, req.agent.as_deref()).await?;
+    commands
+        .into_iter()
+        .rev()
+        .find(|c| c.matches(&req.name))
+        .ok_or_else(|| {
+            HttpResponse::NotFound()
+                .json(json!({"error": format!("Unknown command '{}'", req.name)}))
+        })
+}
+
+// ── GET /commands ─────────────────────────────────────────────────────────
+
+/// List the slash commands available in chat.
+#[utoipa::path(
+    get,
+    path = "/v1/commands",
+    tag = "Commands",
+    params(
+        ("agent" = Option<String>, Query, description = "Include this agent's declared commands"),
+    ),
+    responses(
+        (status = 200, description = "Available slash commands", body = ListSlashCommandsResponse),
+        (status = 404, description = "Agent not found"),
+    )
+)]
+pub async fn list_commands(
+    executor: web::Data<Arc<AgentOrchestrator>>,
+    query: web::Query<ListCommandsQuery>,
+) -> HttpResponse {
+    match resolve(&executor, query.agent.as_deref()).await {
+        Ok(commands) => HttpResponse::Ok().json(ListSlashCommandsResponse { commands }),
+        Err(resp) => resp,
+    }
+}
+
+// ── POST /commands/render ─────────────────────────────────────────────────
+
+/// Render a slash command invocation into the message sent to the agent.
+#[utoipa::path(
+    post,
+    path = "/v1/commands/render",
+    tag = "Commands",
+    request_body = RenderSlashCommandRequest,
+    responses(
+        (status = 200, description = "Rendered message", body = RenderSlashCommandResponse),
+        (status = 400, description = "Missing or invalid arguments"),
+        (status = 404, description = "Unknown command or agent"),
+    )
+)]
+pub async fn render_command(
+    executor: web::Data<Arc<AgentOrchestrator>>,
+    body: web::Json<RenderSlashCommandRequest>,
+) -> HttpResponse {
+    let req = body.into_inner();
+    let command = match find(&executor, &req).await {
+        Ok(command) => command,
+        Err(resp) => return resp,
+    };
+    match command.render(&req.input) {
+        Ok(message) => HttpResponse::Ok().json(RenderSlashCommandResponse { message }),
+        Err(e) => HttpResponse::BadRequest().json(json!({"error": e.to_string()})),
+    }
+}
+
+// ── POST /commands/run ────────────────────────────────────────────────────
+
+/// Run a slash command bound to a tool: call the tool with the command's
+/// args, among the tools of the `agent` the command is run for. The call is
+/// admitted and charged to the user's quotas like a run.
+#[utoipa::path(
+    post,
+    path = "/v1/commands/run",
+    tag = "Commands",
+    request_body = RenderSlashCommandRequest,
+    responses(
+        (status = 200, description = "The tool's result", body = RunSlashCommandResponse),
+        (status = 400, description = "Missing arguments or agent, or the command has no tool"),
+        (status = 404, description = "Unknown command or agent"),
+        (status = 429, description = "Server at capacity or user over a quota; see Retry-After"),
+        (status = 500, description = "The tool failed"),
+    )
+)]
+pub async fn run_command(
+    http_req: HttpRequest,
+    executor: web::Data<Arc<AgentOrchestrator>>,
+    admission: Option<web::Data<Admission>>,
+    body: web::Json<RenderSlashCommandRequest>,
+) -> HttpResponse {
+    let req = body.into_inner();
+    let command = match find(&executor, &req).await {
+        Ok(command) => command,
+        Err(resp) => return resp,
+    };
+    let Some(tool) = command.tool.clone() else {
+        return HttpResponse::BadRequest().json(json!({
+            "error": format!("{} is not bound to a tool; render it instead", command.name)
+        }));
+    };
+    let Some(agent) = req.agent.as_deref() else {
+        return HttpResponse::BadRequest().json(json!({
+            "error": format!("{} runs a tool; pass the agent to run it for", command.name)
+        }));
+    };
+    let definition = match executor.get_agent(agent).await {
+        Some(AgentConfig::StandardAgent(definition)) => definition,
+        Some(_) => {
+            return HttpResponse::BadRequest()
+                .json(json!({"error": format!("Agent '{}' has no tools", agent)}))
+        }
+        None => {
+            return HttpResponse::NotFound()
+                .json(json!({"error": format!("Agent '{}' not found", agent)}))
+        }
+    };
+    let input = match command.tool_input(&req.input) {
+        Ok(input) => input,
+        Err(e) => return HttpResponse::BadRequest().json(json!({"error": e.to_string()})),
+    };
+
+    let (user_id, workspace_id) = http_req
+        .extensions()
+        .get::<UserContext>()
+        .map(|c| (c.user_id(), c.workspace_id()))
+        .unwrap_or_else(|| ("local_dev_user".to_string(), None));
+    let _permit = match admit_run(
+        &executor.user_quotas,
+        admission.as_ref().map(|a| a.get_ref()),
+        &user_id,
+        None,
+    )
+    .await
+    {
+        Ok(permit) => permit,
+        Err(resp) => return resp,
+    };
+
+    let ctx = ExecutorContext {
+        session_id: uuid::Uuid::new_v4().to_string(),
+        user_id,
+        workspace_id,
+        agent_id: definition.name.clone(),
+        orchestrator: Some(executor.get_ref().clone()),
+        stores: Some(executor.stores.clone()),
+        ..Default::default()
+    };
+    let tool_call = ToolCall {
+        tool_call_id: uuid::Uuid::new_v4().to_string(),
+        tool_name: tool.clone(),
+        input,
+    };
+    match executor
+        .call_agent_tool_with_context(&definition, &tool_call, Arc::new(ctx))
+        .await
+    {
+        Ok(result) => HttpResponse::Ok().json(RunSlashCommandResponse { tool, result }),
+        Err(e) => {
+            tracing::error!("{} failed: {}", command.name, e);
+            HttpResponse::InternalServerError()
+                .json(json!({"error": format!("tool execution failed: {}", e)}))
+        }
+    }
+}
@@ -0,0 +1,653 @@
+//! GraphQL surface over agents, threads, tasks, messages and artifacts.
+//!
+//! One endpoint for clients that would otherwise stitch several REST calls
+//! together:
+//! ```text
+//! POST /v1/graphql                         → queries (GET works for queries too)
+//! GET  /v1/graphql  (Upgrade: websocket)   → subscriptions (graphql-ws / graphql-transport-ws)
+//! ```
+//!
+//! Lists take `limit`/`offset` and return a page with the `total` count.
+//! Filters mirror the query parameters of the REST endpoints, and the
+//! resolvers read through the same stores, so both surfaces agree.
+//! `taskEvents` follows the task's events on the orchestrator's
+//! broadcaster, like `GET /v1/tasks/{task_id}/events`, and completes when
+//! the task reaches a terminal state.
+
+use std::sync::Arc;
+
+use actix_web::{web, HttpRequest, HttpResponse};
+use async_graphql::{
+    ComplexObject, Context, Data, EmptyMutation, Error, InputObject, Json, Object, Schema,
+    SimpleObject, Subscription,
+};
+use async_graphql_actix_web::{GraphQLRequest, GraphQLResponse, GraphQLSubscription};
+use distri_core::a2a::messages::get_a2a_messages;
+use distri_core::agent::AgentOrchestrator;
+use distri_filesystem::ArtifactWrapper;
+use distri_types::stores::ThreadListFilter;
+use distri_types::AgentEvent;
+use futures_util::{Stream, StreamExt};
+use once_cell::sync::Lazy;
+use serde_json::Value;
+
+use super::artifacts::{collect_artifacts, ArtifactEntry};
+
+/// Page size when a list is queried without a `limit`.
+const DEFAULT_LIMIT: u32 = 50;
+
+pub type DistriSchema = Schema<QueryRoot, EmptyMutation, SubscriptionRoot>;
+
+/// The schema holds no data; the orchestrator is attached per request.
+static SCHEMA: Lazy<DistriSchema> = Lazy::new(schema);
+
+pub fn schema() -> DistriSchema {
+    Schema::build(QueryRoot, EmptyMutation, SubscriptionRoot).finish()
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/graphql",
+    tag = "GraphQL",
+    responses((status = 200, description = "GraphQL response; errors are reported in its `errors` field"))
+)]
+pub async fn execute(
+    executor: web::Data<Arc<AgentOrchestrator>>,
+    request: GraphQLRequest,
+) -> GraphQLResponse {
+    let request = request.into_inner().data(executor.get_ref().clone());
+    SCHEMA.execute(request).await.into()
+}
+
+pub async fn subscriptions(
+    executor: web::Data<Arc<AgentOrchestrator>>,
+    req: HttpRequest,
+    payload: web::Payload,
+) -> actix_web::Result<HttpResponse> {
+    let mut data = Data::default();
+    data.insert(executor.get_ref().clone());
+    GraphQLSubscription::new(SCHEMA.clone())
+        .with_data(data)
+        .start(&req, payload)
+}
+
+fn orchestrator<'a>(ctx: &Context<'a>) -> &'a Arc<AgentOrchestrator> {
+    ctx.data_unchecked::<Arc<AgentOrchestrator>>()
+}
+
+/// `items[offset..offset + limit]`, and whether more items follow.
+fn paginate<T>(items: Vec<T>, limit: Option<u32>, offset: Option<u32>) -> (Vec<T>, bool) {
+    let offset = offset.unwrap_or(0) as usize;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT) as usize;
+    let has_more = items.len() > offset.saturating_add(limit);
+    (
+        items.into_iter().skip(offset).take(limit).collect(),
+        has_more,
+    )
+}
+
+fn rfc3339(at: chrono::DateTime<chrono::Utc>) -> String {
+    at.to_rfc3339()
+}
+
+// ── Objects ───────────────────────────────────────────────────────────────────
+
+#[derive(SimpleObject)]
+#[graphql(complex)]
+pub struct Agent {
+    name: String,
+    description: String,
+    thread_count: i64,
+    last_used_at: Option<String>,
+    /// The full definition, as returned by `GET /v1/agents`.
+    definition: Json<Value>,
+}
+
+#[ComplexObject]
+impl Agent {
+    /// Threads of this agent, most recently updated first.
+    async fn threads(
+        &self,
+        ctx: &Context<'_>,
+        filter: Option<ThreadFilter>,
+        limit: Option<u32>,
+        offset: Option<u32>,
+    ) -> async_graphql::Result<ThreadPage> {
+        let filter = ThreadFilter {
+            agent_id: Some(self.name.clone()),
+            ..filter.unwrap_or_default()
+        };
+        list_threads(orchestrator(ctx), filter, limit, offset).await
+    }
+}
+
+#[derive(SimpleObject)]
+pub struct AgentPage {
+    items: Vec<Agent>,
+    total: i64,
+    has_more: bool,
+}
+
+#[derive(SimpleObject)]
+#[graphql(complex)]
+pub struct Thread {
+    id: String,
+    title: String,
+    agent_id: String,
+    updated_at: String,
+    message_count: u32,
+    last_message: Option<String>,
+    user_id: Option<String>,
+    external_id: Option<String>,
+    tags: Option<Vec<String>>,
+    input_tokens: u64,
+    output_tokens: u64,
+    total_tokens: u64,
+    archived_at: Option<String>,
+}
+
+impl From<distri_types::ThreadSummary> for Thread {
+    fn from(thread: distri_types::ThreadSummary) -> Self {
+        Self {
+            id: thread.id,
+            title: thread.title,
+            agent_id: thread.agent_id,
+            updated_at: rfc3339(thread.updated_at),
+            message_count: thread.message_count,
+            last_message: thread.last_message,
+            user_id: thread.user_id,
+            external_id: thread.external_id,
+            tags: thread.tags,
+            input_tokens: thread.input_tokens,
+            output_tokens: thread.output_tokens,
+            total_tokens: thread.total_tokens,
+            archived_at: thread.archived_at.map(rfc3339),
+        }
+    }
+}
+
+impl From<distri_types::Thread> for Thread {
+    fn from(thread: distri_types::Thread) -> Self {
+        Self {
+            id: thread.id,
+            title: thread.title,
+            agent_id: thread.agent_id,
+            updated_at: rfc3339(thread.updated_at),
+            message_count: thread.message_count,
+            last_message: thread.last_message,
+            user_id: thread.user_id,
+            external_id: thread.external_id,
+            tags: None,
+            input_tokens: thread.input_tokens,
+            output_tokens: thread.output_tokens,
+            total_tokens: thread.total_tokens,
+            archived_at: thread.archived_at.map(rfc3339),
+        }
+    }
+}
+
+#[ComplexObject]
+impl Thread {
+    /// Messages in the A2A shape of `GET /v1/threads/{thread_id}/messages`,
+    /// oldest first. Restores the thread's history when it is archived.
+    async fn messages(
+        &self,
+        ctx: &Context<'_>,
+        limit: Option<u32>,
+        offset: Option<u32>,
+    ) -> async_graphql::Result<MessagePage> {
+        let executor = orchestrator(ctx);
+        executor.get_thread(&self.id).await?;
+        let messages = get_a2a_messages(executor.stores.task_store.clone(), &self.id, None)
+            .await
+            .map_err(|e| Error::new(format!("Failed to get thread messages: {e}")))?;
+        let total = messages.len() as i64;
+        let (page, has_more) = paginate(messages, limit, offset);
+        Ok(MessagePage {
+            items: page.into_iter().map(Message::from).collect(),
+            total,
+            has_more,
+        })
+    }
+
+    /// Tasks of this thread, most recently updated first.
+    async fn tasks(
+        &self,
+        ctx: &Context<'_>,
+        status: Option<String>,
+        limit: Option<u32>,
+        offset: Option<u32>,
+    ) -> async_graphql::Result<TaskPage> {
+        let filter = TaskFilter {
+            thread_id: Some(self.id.clone()),
+            parent_task_id: None,
+            status,
+        };
+        list_tasks(orchestrator(ctx), filter, limit, offset).await
+    }
+
+    /// Artifacts shared by every task of the thread.
+    async fn artifacts(&self, ctx: &Context<'_>) -> Vec<Artifact> {
+        let namespace = ArtifactWrapper::thread_namespace(&self.id);
+        artifacts(orchestrator(ctx), &namespace).await
+    }
+}
+
+#[derive(SimpleObject)]
+pub struct ThreadPage {
+    items: Vec<Thread>,
+    total: i64,
+    has_more: bool,
+}
+
+#[derive(SimpleObject)]
+#[graphql(complex)]
+pub struct Task {
+    id: String,
+    thread_id: String,
+    parent_task_id: Option<String>,
+    /// `pending`, `running`, `input_required`, `completed`, `failed` or
+    /// `canceled`.
+    status: String,
+    created_at: i64,
+    updated_at: i64,
+    /// The latest update of the task.
+    preview: Option<String>,
+    last_event_at: Option<i64>,
+    /// The task's first user message.
+    intent: Option<String>,
+}
+
+impl Task {
+    fn new(task: distri_types::Task, activity: distri_types::stores::TaskActivity) -> Self {
+        Self {
+            status: status_name(&task.status),
+            id: task.id,
+            thread_id: task.thread_id,
+            parent_task_id: task.parent_task_id,
+            created_at: task.created_at,
+            updated_at: task.updated_at,
+            preview: activity.preview,
+            last_event_at: activity.last_event_at,
+            intent: activity.intent,
+        }
+    }
+}
+
+#[ComplexObject]
+impl Task {
+    async fn thread(&self, ctx: &Context<'_>) -> async_graphql::Result<Option<Thread>> {
+        Ok(orchestrator(ctx)
+            .get_thread(&self.thread_id)
+            .await?
+            .map(Thread::from))
+    }
+
+    /// Tasks dispatched under this one, at any depth, most recently updated
+    /// first.
+    async fn subtasks(
+        &self,
+        ctx: &Context<'_>,
+        status: Option<String>,
+        limit: Option<u32>,
+        offset: Option<u32>,
+    ) -> async_graphql::Result<TaskPage> {
+        let filter = TaskFilter {
+            thread_id: None,
+            parent_task_id: Some(self.id.clone()),
+            status,
+        };
+        list_tasks(orchestrator(ctx), filter, limit, offset).await
+    }
+
+    /// Artifacts of the task, including the ones shared by its thread.
+    async fn artifacts(&self, ctx: &Context<'_>) -> Vec<Artifact> {
+        let namespace = ArtifactWrapper::task_namespace(&self.thread_id, &self.id);
+        artifacts(orchestrator(ctx), &namespace).await
+    }
+}
+
+#[derive(SimpleObject)]
+pub struct TaskPage {
+    items: Vec<Task>,
+    total: i64,
+    has_more: bool,
+}
+
+#[derive(SimpleObject)]
+pub struct Message {
+    id: Option<String>,
+    role: Option<String>,
+    task_id: Option<String>,
+    parts: Json<Value>,
+    /// The whole A2A message.
+    raw: Json<Value>,
+}
+
+impl From<Value> for Message {
+    fn from(message: Value) -> Self {
+        let field = |name: &str| message.get(name).and_then(Value::as_str).map(String::from);
+        Self {
+            id: field("messageId"),
+            role: field("role"),
+            task_id: field("taskId"),
+            parts: Json(message.get("parts").cloned().unwrap_or(Value::Null)),
+            raw: Json(message),
+        }
+    }
+}
+
+#[derive(SimpleObject)]
+pub struct MessagePage {
+    items: Vec<Message>,
+    total: i64,
+    has_more: bool,
+}
+
+#[derive(SimpleObject)]
+pub struct Artifact {
+    filename: String,
+    is_file: bool,
+    size: Option<u64>,
+    /// Path of the artifact's content, under `/v1`.
+    read_path: String,
+}
+
+impl From<ArtifactEntry> for Artifact {
+    fn from(entry: ArtifactEntry) -> Self {
+        Self {
+            filename: entry.filename,
+            is_file: entry.is_file,
+            size: entry.size,
+            read_path: entry.read_path,
+        }
+    }
+}
+
+#[derive(SimpleObject)]
+pub struct TaskEvent {
+    /// The event variant, e.g. `run_started` or `text_message_content`.
+    r#type: Option<String>,
+    task_id: String,
+    thread_id: String,
+    agent_id: String,
+    parent_task_id: Option<String>,
+    timestamp: String,
+    /// The event as sent on `GET /v1/tasks/{task_id}/events`.
+    data: Json<Value>,
+}
+
+impl From<AgentEvent> for TaskEvent {
+    fn from(event: AgentEvent) -> Self {
+        let data = serde_json::to_value(&event).unwrap_or(Value::Null);
+        Self {
+            r#type: data
+                .pointer("/event/type")
+                .and_then(Value::as_str)
+                .map(String::from),
+            task_id: event.task_id,
+            thread_id: event.thread_id,
+            agent_id: event.agent_id,
+            parent_task_id: event.parent_task_id,
+            timestamp: rfc3339(event.timestamp),
+            data: Json(data),
+        }
+    }
+}
+
+// ── Filters ───────────────────────────────────────────────────────────────────
+
+/// Same filters as `GET /v1/threads`.
+#[derive(InputObject, Default)]
+pub struct ThreadFilter {
+    agent_id: Option<String>,
+    external_id: Option<String>,
+    /// Matched against the title, the last message and the tags.
+    search: Option<String>,
+    /// RFC 3339.
+    from_date: Option<String>,
+    /// RFC 3339.
+    to_date: Option<String>,
+    tags: Option<Vec<String>>,
+    /// Thread attributes to match.
+    attributes: Option<Json<Value>>,
+}
+
+/// Same filters as `GET /v1/tasks`.
+#[derive(InputObject, Default)]
+pub struct TaskFilter {
+    thread_id: Option<String>,
+    /// Only the tasks dispatched under this one, at any depth.
+    parent_task_id: Option<String>,
+    status: Option<String>,
+}
+
+// ── Roots ─────────────────────────────────────────────────────────────────────
+
+pub struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    /// Agents by name, optionally matching `search` in their name or
+    /// description.
+    async fn agents(
+        &self,
+        ctx: &Context<'_>,
+        search: Option<String>,
+        limit: Option<u32>,
+        offset: Option<u32>,
+    ) -> AgentPage {
+        let mut agents = list_agents(orchestrator(ctx)).await;
+        if let Some(search) = search.map(|s| s.to_lowercase()) {
+            agents.retain(|agent| {
+                agent.name.to_lowercase().contains(&search)
+                    || agent.description.to_lowercase().contains(&search)
+            });
+        }
+        let total = agents.len() as i64;
+        let (items, has_more) = paginate(agents, limit, offset);
+        AgentPage {
+            items,
+            total,
+            has_more,
+        }
+    }
+
+    async fn agent(&self, ctx: &Context<'_>, name: String) -> Option<Agent> {
+        list_agents(orchestrator(ctx))
+            .await
+            .into_iter()
+            .find(|agent| agent.name == name)
+    }
+
+    /// Threads, most recently updated first.
+    async fn threads(
+        &self,
+        ctx: &Context<'_>,
+        filter: Option<ThreadFilter>,
+        limit: Option<u32>,
+        offset: Option<u32>,
+    ) -> async_graphql::Result<ThreadPage> {
+        list_threads(orchestrator(ctx), filter.unwrap_or_default(), limit, offset).await
+    }
+
+    async fn thread(&self, ctx: &Context<'_>, id: String) -> async_graphql::Result<Option<Thread>> {
+        Ok(orchestrator(ctx).get_thread(&id).await?.map(Thread::from))
+    }
+
+    /// Tasks, most recently updated first.
+    async fn tasks(
+        &self,
+        ctx: &Context<'_>,
+        filter: Option<TaskFilter>,
+        limit: Option<u32>,
+        offset: Option<u32>,
+    ) -> async_graphql::Result<TaskPage> {
+        list_tasks(orchestrator(ctx), filter.unwrap_or_default(), limit, offset).await
+    }
+
+    async fn task(&self, ctx: &Context<'_>, id: String) -> async_graphql::Result<Option<Task>> {
+        let store = &orchestrator(ctx).stores.task_store;
+        let Some(task) = store.get_task(&id).await? else {
+            return Ok(None);
+        };
+        let activity = store
+            .task_activity(&task.id)
+            .await
+            .unwrap_or(None)
+            .unwrap_or_default();
+        Ok(Some(Task::new(task, activity)))
+    }
+
+    /// Artifacts of a namespace, as listed by `GET /v1/artifacts/{artifact_id}`.
+    async fn artifacts(&self, ctx: &Context<'_>, artifact_id: String) -> Vec<Artifact> {
+        artifacts(orchestrator(ctx), &artifact_id).await
+    }
+}
+
+pub struct SubscriptionRoot;
+
+#[Subscription]
+impl SubscriptionRoot {
+    /// Events of a task as they happen; completes when the task reaches a
+    /// terminal state.
+    async fn task_events(
+        &self,
+        ctx: &Context<'_>,
+        task_id: String,
+    ) -> async_graphql::Result<impl Stream<Item = TaskEvent>> {
+        let events = orchestrator(ctx)
+            .broadcaster()
+            .follow_stream(&task_id)
+            .await
+            .map_err(|e| Error::new(format!("Failed to subscribe to task events: {e}")))?;
+        Ok(events.map(TaskEvent::from))
+    }
+}
+
+// ── Resolvers shared by the roots and the objects ─────────────────────────────
+
+async fn list_agents(executor: &AgentOrchestrator) -> Vec<Agent> {
+    let (agents, _) = executor
+        .stores
+        .agent_store
+        .list_with_cloud_metadata(None, None)
+        .await;
+    let stats = executor
+        .stores
+        .thread_store
+        .get_agent_stats_map()
+        .await
+        .unwrap_or_default();
+
+    let mut agents: Vec<Agent> = agents
+        .into_iter()
+        .map(|(config, _)| {
+            let name = config.get_name().to_string();
+            let stats = stats.get(&name).cloned().unwrap_or_default();
+            Agent {
+                description: config.get_description().to_string(),
+                thread_count: stats.thread_count,
+                last_used_at: stats.last_used_at.map(rfc3339),
+                definition: Json(serde_json::to_value(&config).unwrap_or(Value::Null)),
+                name,
+            }
+        })
+        .collect();
+    agents.sort_by(|a, b| a.name.cmp(&b.name));
+    agents
+}
+
+async fn list_threads(
+    executor: &AgentOrchestrator,
+    filter: ThreadFilter,
+    limit: Option<u32>,
+    offset: Option<u32>,
+) -> async_graphql::Result<ThreadPage> {
+    let date = |value: Option<String>, name: &str| {
+        value
+            .map(|s| {
+                chrono::DateTime::parse_from_rfc3339(&s)
+                    .map(|dt| dt.with_timezone(&chrono::Utc))
+                    .map_err(|e| Error::new(format!("Invalid {name} '{s}': {e}")))
+            })
+            .transpose()
+    };
+    let filter = ThreadListFilter {
+        agent_id: filter.agent_id,
+        external_id: filter.external_id,
+        attributes: filter.attributes.map(|a| a.0),
+        search: filter.search,
+        from_date: date(filter.from_date, "fromDate")?,
+        to_date: date(filter.to_date, "toDate")?,
+        tags: filter.tags,
+    };
+    let limit = limit.unwrap_or(DEFAULT_LIMIT);
+    let offset = offset.unwrap_or(0);
+    let response = executor
+        .list_threads(&filter, Some(limit), Some(offset))
+        .await?;
+    Ok(ThreadPage {
+        has_more: (offset as usize + response.threads.len()) < response.total as usize,
+        items: response.threads.into_iter().map(Thread::from).collect(),
+        total: response.total,
+    })
+}
+
+async fn list_tasks(
+    executor: &AgentOrchestrator,
+    filter: TaskFilter,
+    limit: Option<u32>,
+    offset: Option<u32>,
+) -> async_graphql::Result<TaskPage> {
+    let store = &executor.stores.task_store;
+    let mut tasks: Vec<distri_types::Task> = match filter.parent_task_id.as_deref() {
+        // The sub-tree of the task, without the task itself.
+        Some(root) => store
+            .list_descendant_tasks(root)
+            .await?
+            .into_iter()
+            .filter(|t| t.id != root)
+            .collect(),
+        None => store.list_tasks(filter.thread_id.as_deref()).await?,
+    };
+    if let Some(thread_id) = filter.thread_id.as_deref() {
+        tasks.retain(|t| t.thread_id == thread_id);
+    }
+    if let Some(status) = filter.status.as_deref() {
+        tasks.retain(|t| status_name(&t.status) == status);
+    }
+    tasks.sort_by_key(|t| std::cmp::Reverse(t.updated_at));
+
+    let total = tasks.len() as i64;
+    let (page, has_more) = paginate(tasks, limit, offset);
+    let mut items = Vec::with_capacity(page.len());
+    for task in page {
+        let activity = store
+            .task_activity(&task.id)
+            .await
+            .unwrap_or(None)
+            .unwrap_or_default();
+        items.push(Task::new(task, activity));
+    }
+    Ok(TaskPage {
+        items,
+        total,
+        has_more,
+    })
+}
+
+async fn artifacts(executor: &AgentOrchestrator, artifact_id: &str) -> Vec<Artifact> {
+    collect_artifacts(executor, artifact_id)
+        .await
+        .into_iter()
+        .map(Artifact::from)
+        .collect()
+}
+
+fn status_name(status: &distri_types::TaskStatus) -> String {
+    serde_json::to_value(status)
+        .ok()
+        .and_then(|v| v.as_str().map(String::from))
+        .unwrap_or_default()
+}
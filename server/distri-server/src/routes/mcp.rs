@@ -0,0 +1,95 @@
+//! `/mcp`: the registered agents as an MCP server (Streamable HTTP transport,
+//! see [`distri_core::mcp_facade`]).
+//!
+//! ```text
+//! POST /mcp   JSON-RPC request
+//!   ← application/json response, or
+//!   ← text/event-stream of notifications/progress, then the response
+//!     (tools/call with a progressToken)
+//! POST /mcp   JSON-RPC notification  ← 202 Accepted
+//! ```
+//!
+//! The server keeps no MCP session: every request stands on its own.
+
+use std::sync::Arc;
+
+use actix_web::{web, Either, HttpMessage, HttpRequest, HttpResponse};
+use actix_web_lab::sse::{self, Sse};
+use distri_a2a::{JsonRpcError, JsonRpcResponse};
+use distri_core::agent::AgentOrchestrator;
+use distri_core::mcp_facade::{McpFacade, McpReply, McpRequest};
+use futures_util::StreamExt;
+
+use crate::admission::{admit_run, Admission};
+use crate::context::UserContext;
+
+/// Answer one MCP message.
+#[utoipa::path(
+    post,
+    path = "/v1/mcp",
+    tag = "Agents",
+    request_body(content = Object, description = "A JSON-RPC message"),
+    responses(
+        (status = 200, description = "JSON-RPC response; a `tools/call` with a progressToken answers with a text/event-stream of progress notifications, then the response"),
+        (status = 202, description = "The message was a notification"),
+        (status = 400, description = "The body is not a JSON-RPC message"),
+        (status = 429, description = "Server at capacity or user over a quota; see Retry-After"),
+    )
+)]
+pub(crate) async fn mcp_handler(
+    body: web::Bytes,
+    executor: web::Data<Arc<AgentOrchestrator>>,
+    admission: Option<web::Data<Admission>>,
+    http_request: HttpRequest,
+) -> Either<
+    Sse<impl futures_util::stream::Stream<Item = Result<sse::Event, std::convert::Infallible>>>,
+    HttpResponse,
+> {
+    let request: McpRequest = match serde_json::from_slice(&body) {
+        Ok(request) => request,
+        Err(e) => {
+            let error = JsonRpcError::new(-32700, format!("Parse error: {}", e));
+            return Either::Right(
+                HttpResponse::BadRequest().json(JsonRpcResponse::error(None, error)),
+            );
+        }
+    };
+    let user_id = http_request
+        .extensions()
+        .get::<UserContext>()
+        .map(|ctx| ctx.user_id())
+        .unwrap_or_else(|| "local_dev_user".to_string());
+    // Like A2A runs, tool calls are admitted against the user's quotas and
+    // wait for a run slot; the permit is held until the response is built
+    // or the stream ends.
+    let permit = if request.method == "tools/call" {
+        match admit_run(
+            &executor.user_quotas,
+            admission.as_ref().map(|a| a.get_ref()),
+            &user_id,
+            request.id.clone(),
+        )
+        .await
+        {
+            Ok(permit) => Some(permit),
+            Err(rejection) => return Either::Right(rejection),
+        }
+    } else {
+        None
+    };
+    let workspace_model_settings = http_request
+        .extensions()
+        .get::<distri_types::ModelSettings>()
+        .cloned();
+    let facade = McpFacade::new(executor.get_ref().clone(), user_id)
+        .with_default_model_settings(workspace_model_settings);
+
+    match facade.handle(request).await {
+        McpReply::Accepted => Either::Right(HttpResponse::Accepted().finish()),
+        McpReply::Response(response) => Either::Right(HttpResponse::Ok().json(response)),
+        McpReply::Stream(stream) => Either::Left(Sse::from_stream(stream.map(move |message| {
+            let _permit = &permit;
+            Ok(sse::Event::Data(sse::Data::new(message.to_string())))
+        }))),
+    }
+}
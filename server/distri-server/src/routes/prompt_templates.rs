@@ -1,5 +1,6 @@
 use actix_web::{web, HttpResponse};
 use distri_core::agent::AgentOrchestrator;
+use distri_types::prompt_locale::TemplateCoverage;
 use distri_types::stores::{NewPromptTemplate, UpdatePromptTemplate};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -44,6 +45,7 @@ pub fn configure_prompt_template_routes(cfg: &mut web::ServiceConfig) {
             .route(web::post().to(upsert_prompt_template)),
     )
     .service(web::resource("/prompts/sync").route(web::post().to(sync_prompt_templates)))
+    .service(web::resource("/prompts/coverage").route(web::get().to(prompt_translation_coverage)))
     .service(
         web::resource("/prompts/{id}")
             .route(web::get().to(get_prompt_template))
@@ -75,6 +77,18 @@ async fn list_prompt_templates(executor: web::Data<Arc<AgentOrchestrator>>) -> H
     }
 }
 
+#[utoipa::path(
+    get,
+    path = "/v1/prompts/coverage",
+    tag = "Prompt Templates",
+    responses(
+        (status = 200, description = "Locales each prompt template is translated into", body = [TemplateCoverage]),
+    )
+)]
+async fn prompt_translation_coverage(executor: web::Data<Arc<AgentOrchestrator>>) -> HttpResponse {
+    HttpResponse::Ok().json(executor.prompt_translation_coverage().await)
+}
+
 #[utoipa::path(
     get,
     path = "/v1/prompt-templates/{id}",
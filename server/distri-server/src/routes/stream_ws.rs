@@ -0,0 +1,280 @@
+//! WebSocket run streams (see [`distri_types::stream_control`]).
+//!
+//! ```text
+//! GET /agents/{id}/ws   (upgrade)
+//!   → first text frame: JSON-RPC `message/stream` or `tasks/resubscribe`
+//!   ← the stream's JSON-RPC frames, then close
+//!   → StreamControl frames at any time, each answered with a StreamControlReply
+//! ```
+//!
+//! Control frames are applied on their own tasks, so a slow one never holds
+//! up the stream. Tool completions are retried while the run has not yet
+//! registered the call it answers, since the run announces a call before it
+//! starts waiting on it.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use actix_web::{web, HttpMessage, HttpRequest, HttpResponse};
+use actix_ws::{AggregatedMessage, CloseCode, CloseReason, Session};
+use distri_a2a::{JsonRpcError, JsonRpcRequest, JsonRpcResponse, TaskIdParams};
+use distri_core::a2a::{A2AService, ServiceRequest};
+use distri_core::agent::AgentOrchestrator;
+use distri_types::stream_control::{StreamControl, StreamControlReply};
+use futures_util::StreamExt;
+use serde_json::Value;
+use tokio::sync::Mutex;
+
+use crate::admission::{admit_run, Admission};
+use crate::agent_server::VerboseLog;
+use crate::context::UserContext;
+
+/// Attempts at completing a tool call the run has not registered yet.
+const COMPLETE_ATTEMPTS: u32 = 10;
+
+/// Upgrade to a WebSocket carrying one run's stream and its control frames.
+pub(crate) async fn agent_stream_ws(
+    id: web::Path<String>,
+    http_request: HttpRequest,
+    body: web::Payload,
+    executor: web::Data<Arc<AgentOrchestrator>>,
+    admission: Option<web::Data<Admission>>,
+    verbose: Option<web::Data<Option<VerboseLog>>>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let (user_id, workspace_id) = http_request
+        .extensions()
+        .get::<UserContext>()
+        .map(|ctx| (ctx.user_id(), ctx.workspace_id()))
+        .unwrap_or_else(|| ("local_dev_user".to_string(), None));
+
+    // Admitted before the upgrade so a rejection is a plain 429; the session
+    // holds the permit until it closes.
+    let permit = match admit_run(
+        &executor.user_quotas,
+        admission.as_ref().map(|a| a.get_ref()),
+        &user_id,
+        None,
+    )
+    .await
+    {
+        Ok(permit) => permit,
+        Err(rejection) => return Ok(rejection),
+    };
+    let (response, session, stream) = actix_ws::handle(&http_request, body)?;
+    let workspace_model_settings = http_request
+        .extensions()
+        .get::<distri_types::ModelSettings>()
+        .cloned();
+    let verbose = verbose
+        .as_ref()
+        .and_then(|data| data.get_ref().as_ref())
+        .map(|v| v.is_verbose())
+        .unwrap_or(false);
+
+    let request = ServiceRequest {
+        agent_id: id.into_inner(),
+        user_id,
+        workspace_id,
+        req: JsonRpcRequest {
+            jsonrpc: "2.0".to_string(),
+            id: None,
+            method: String::new(),
+            params: Value::Null,
+        },
+        executor_context: None,
+        verbose,
+        workspace_model_settings,
+    };
+    let stream = stream.aggregate_continuations();
+    let orchestrator = executor.get_ref().clone();
+    actix_web::rt::spawn(async move {
+        run_session(orchestrator, request, session, stream).await;
+        drop(permit);
+    });
+    Ok(response)
+}
+
+async fn run_session(
+    orchestrator: Arc<AgentOrchestrator>,
+    mut request: ServiceRequest,
+    mut session: Session,
+    mut inbound: actix_ws::AggregatedMessageStream,
+) {
+    // The first text frame is the JSON-RPC request to stream.
+    let rpc = loop {
+        match inbound.next().await {
+            Some(Ok(AggregatedMessage::Text(text))) => {
+                match serde_json::from_str::<JsonRpcRequest>(&text) {
+                    Ok(rpc) => break rpc,
+                    Err(e) => {
+                        let error = JsonRpcError::invalid_params(e.to_string());
+                        close_with(session, None, error).await;
+                        return;
+                    }
+                }
+            }
+            Some(Ok(AggregatedMessage::Ping(bytes))) => {
+                if session.pong(&bytes).await.is_err() {
+                    return;
+                }
+            }
+            Some(Ok(AggregatedMessage::Close(_))) | Some(Err(_)) | None => return,
+            Some(Ok(_)) => {}
+        }
+    };
+    if rpc.method != "message/stream" && rpc.method != "tasks/resubscribe" {
+        let error = JsonRpcError::invalid_params(format!(
+            "{} cannot be streamed; send message/stream or tasks/resubscribe",
+            rpc.method
+        ));
+        close_with(session, rpc.id, error).await;
+        return;
+    }
+
+    // The run's task, for `cancel` frames without a task id.
+    let task_id = Arc::new(Mutex::new(
+        serde_json::from_value::<TaskIdParams>(rpc.params.clone())
+            .ok()
+            .filter(|_| rpc.method == "tasks/resubscribe")
+            .map(|params| params.id),
+    ));
+    request.req = rpc;
+    let service = A2AService::new(orchestrator.clone());
+    let mut outbound = match service.handle(request).await {
+        futures_util::future::Either::Left(stream) => stream,
+        futures_util::future::Either::Right(response) => {
+            if let Ok(text) = serde_json::to_string(&response) {
+                let _ = session.text(text).await;
+            }
+            let _ = session.close(None).await;
+            return;
+        }
+    };
+
+    loop {
+        tokio::select! {
+            frame = outbound.next() => {
+                let Some(Ok(frame)) = frame else { break };
+                {
+                    let mut task_id = task_id.lock().await;
+                    if task_id.is_none() {
+                        *task_id = frame_task_id(&frame.data);
+                    }
+                }
+                if session.text(frame.data).await.is_err() {
+                    return;
+                }
+            }
+            message = inbound.next() => match message {
+                Some(Ok(AggregatedMessage::Text(text))) => {
+                    let session = session.clone();
+                    let orchestrator = orchestrator.clone();
+                    let task_id = task_id.clone();
+                    actix_web::rt::spawn(async move {
+                        let reply = match serde_json::from_str::<StreamControl>(&text) {
+                            Ok(control) => {
+                                let task_id = task_id.lock().await.clone();
+                                StreamControlReply {
+                                    control: control.name().to_string(),
+                                    error: apply(&orchestrator, control, task_id).await.err(),
+                                }
+                            }
+                            Err(e) => StreamControlReply {
+                                control: String::new(),
+                                error: Some(format!("invalid control frame: {}", e)),
+                            },
+                        };
+                        send_reply(session, &reply).await;
+                    });
+                }
+                Some(Ok(AggregatedMessage::Ping(bytes))) => {
+                    if session.pong(&bytes).await.is_err() {
+                        return;
+                    }
+                }
+                // A closed socket only stops the stream; the run goes on, as
+                // it does when an SSE client disconnects.
+                Some(Ok(AggregatedMessage::Close(_))) | Some(Err(_)) | None => return,
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+    let _ = session.close(None).await;
+}
+
+/// Apply a control frame.
+async fn apply(
+    orchestrator: &Arc<AgentOrchestrator>,
+    control: StreamControl,
+    task_id: Option<String>,
+) -> Result<(), String> {
+    match control {
+        StreamControl::Cancel { task_id: target } => {
+            let id = target
+                .or(task_id)
+                .ok_or_else(|| "the stream has no task to cancel yet".to_string())?;
+            let params = serde_json::to_value(TaskIdParams { id }).map_err(|e| e.to_string())?;
+            A2AService::new(orchestrator.clone())
+                .cancel_task(params)
+                .await
+                .map(|_| ())
+                .map_err(|e| e.to_string())
+        }
+        StreamControl::Auth { response } => orchestrator.complete_auth_consent(response).await,
+        control => {
+            let Some(tool_response) = control.tool_response() else {
+                return Ok(());
+            };
+            complete_tool(orchestrator, tool_response).await
+        }
+    }
+}
+
+async fn complete_tool(
+    orchestrator: &Arc<AgentOrchestrator>,
+    tool_response: distri_types::ToolResponse,
+) -> Result<(), String> {
+    let tool_call_id = tool_response.tool_call_id.clone();
+    let mut attempt = 0;
+    loop {
+        match orchestrator
+            .clone()
+            .complete_tool(&tool_call_id, tool_response.clone())
+            .await
+        {
+            Err(e) if e.contains("No pending") && attempt + 1 < COMPLETE_ATTEMPTS => {
+                tokio::time::sleep(Duration::from_millis(100 * (1 << attempt.min(4)))).await;
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+/// `taskId` of a streamed JSON-RPC frame.
+fn frame_task_id(data: &str) -> Option<String> {
+    let frame: Value = serde_json::from_str(data).ok()?;
+    frame
+        .pointer("/result/taskId")
+        .and_then(Value::as_str)
+        .map(str::to_string)
+}
+
+async fn send_reply(mut session: Session, reply: &StreamControlReply) {
+    if let Ok(text) = serde_json::to_string(reply) {
+        let _ = session.text(text).await;
+    }
+}
+
+/// Send a JSON-RPC error and close the socket.
+async fn close_with(mut session: Session, id: Option<Value>, error: JsonRpcError) {
+    if let Ok(text) = serde_json::to_string(&JsonRpcResponse::error(id, error)) {
+        let _ = session.text(text).await;
+    }
+    let _ = session
+        .close(Some(CloseReason {
+            code: CloseCode::Policy,
+            description: None,
+        }))
+        .await;
+}
@@ -52,6 +52,17 @@ pub fn configure(cfg: &mut web::ServiceConfig) {
         );
 }
 
+/// Run one tool outside of an agent run.
+#[utoipa::path(
+    post,
+    path = "/v1/tools/call",
+    tag = "Tools",
+    request_body(content = Object, description = "The tool name, its input and the calling agent"),
+    responses(
+        (status = 200, description = "The tool's result"),
+        (status = 500, description = "The tool failed"),
+    )
+)]
 async fn call_tool_handler(
     req: HttpRequest,
     executor: web::Data<Arc<AgentOrchestrator>>,
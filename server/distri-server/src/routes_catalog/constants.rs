@@ -93,34 +93,96 @@ define_routes! {
     AgentCards        => "/agents/cards" { GET: Read },
     Agents            => "/agents" { GET: Read, POST: Write },
     AgentValidate     => "/agents/{id:.*}/validate" { GET: Execute },
+    /// Assemble an agent's prompt for a hypothetical message; no model call.
+    AgentPreview      => "/agents/{id:.*}/preview" { POST: Execute },
     AgentCompleteTool => "/agents/{id:.*}/complete-tool" { POST: Execute },
     AgentDag          => "/agents/{id:.*}/dag" { GET: Execute },
+    /// Judge one eval run of an agent against another.
+    AgentEvalsCompare => "/agents/{id:.*}/evals/compare" { POST: Execute },
+    /// Eval history (GET) or a new eval run (POST) of an agent.
+    AgentEvals        => "/agents/{id:.*}/evals" { GET: Read, POST: Execute },
+    /// Which of an agent's tools a call to a name runs (`?name=`).
+    AgentToolResolve  => "/agents/{id:.*}/tools/resolve" { GET: Read },
+    /// WebSocket run stream: the SSE frames plus inbound control frames.
+    AgentStream       => "/agents/{id:.*}/ws" { GET: Execute },
     /// a2a JSON-RPC dispatch (POST=run) + agent definition CRUD.
     AgentDispatch     => "/agents/{id:.*}" { GET: Read, POST: Execute, PUT: Write, DELETE: Manage },
+    /// The registered agents as an MCP server (Streamable HTTP): each agent
+    /// is a tool; `tools/call` runs it.
+    Mcp               => "/mcp" { POST: Execute },
 
     // ── Hooks / tasks / tools (run surface) ─────────────────────────────────
     EventHooks        => "/event/hooks" { POST: Execute },
+    /// Answer a tool call's `AuthRequired` scope consent prompt.
+    EventAuth         => "/event/auth" { POST: Execute },
     Tasks             => "/tasks" { GET: Execute },
+    /// Step-by-step diff of two tasks of one agent (`?baseline=&candidate=`).
+    TasksCompare      => "/tasks/compare" { GET: Execute },
     TaskCompact       => "/tasks/{task_id}/compact" { POST: Execute },
+    /// Re-run a finished workflow run from one step, reusing upstream outputs.
+    TaskWorkflowRerun => "/tasks/{task_id}/workflow/rerun" { POST: Execute },
+    /// Run a failed task again, told what went wrong the first time.
+    TaskRetry         => "/tasks/{task_id}/retry" { POST: Execute },
     /// Live event stream (SSE) for one task — a monitor's per-child feed.
     TaskEvents        => "/tasks/{task_id}/events" { GET: Execute },
     TaskGet           => "/tasks/{task_id}" { GET: Execute },
     Tools             => "/tools" { GET: Execute },
+    /// Capabilities granted to each plugin, for review.
+    Plugins           => "/plugins" { GET: Read },
+    /// UI renderer manifests shipped by plugins for their tools.
+    ToolRenderers     => "/tool-renderers" { GET: Read },
 
     // ── Threads + messages (run surface) ────────────────────────────────────
     Threads           => "/threads" { GET: Execute },
     ThreadsAgents     => "/threads/agents" { GET: Execute },
+    /// Search titles, last messages and auto-generated tags.
+    ThreadsSearch     => "/threads/search" { GET: Execute },
+    /// Upload a ChatGPT or Claude `conversations.json` export.
+    ThreadsImport     => "/threads/import" { POST: Write },
     ThreadMessages    => "/threads/{thread_id}/messages" { GET: Execute },
+    /// Archive the history from a message on and run its task again.
+    ThreadRegenerate  => "/threads/{thread_id}/regenerate" { POST: Execute },
     Thread            => "/threads/{thread_id}" { GET: Execute, PUT: Execute, DELETE: Execute },
+    /// Mint (POST) or revoke all (DELETE) read-only observer tokens.
+    ThreadShare       => "/threads/{thread_id}/share" { POST: Write, DELETE: Write },
+    /// Live event stream (SSE) for observers; the share token is the only
+    /// credential and grants no input.
+    ThreadWatch       => "/threads/{thread_id}/watch" { GET: Public },
+    /// Move the thread's history to cold storage (POST) or restore it
+    /// (DELETE).
+    ThreadArchive     => "/threads/{thread_id}/archive" { POST: Execute, DELETE: Execute },
+    /// Pin (POST) or unpin (DELETE) the thread's warm session.
+    ThreadWarm        => "/threads/{thread_id}/warm" { POST: Execute, DELETE: Execute },
+    /// Handover summary for a human taking over; `?refresh=true` writes a
+    /// new one.
+    ThreadSummary     => "/threads/{thread_id}/summary" { GET: Execute },
+    /// Warm session cache: pinned and recent threads, hits and time saved.
+    WarmSessions      => "/warm-sessions" { GET: Read },
     ThreadMessageRead => "/threads/{thread_id}/messages/{message_id}/read" { GET: Execute, POST: Execute },
     ThreadReadStatus  => "/threads/{thread_id}/read-status" { GET: Execute },
     ThreadMessageVote => "/threads/{thread_id}/messages/{message_id}/vote" { GET: Execute, POST: Execute, DELETE: Execute },
     ThreadMessageVotes=> "/threads/{thread_id}/messages/{message_id}/votes" { GET: Execute },
 
+    // ── Users ───────────────────────────────────────────────────────────────
+    /// The caller's preferences (tone, language, expertise, timezone),
+    /// injected into the prompts of agents that opt in.
+    UserProfile       => "/users/me/profile" { GET: Read, PUT: Write, PATCH: Write, DELETE: Write },
+    /// A user's quota usage (GET) or a temporary raise of their quotas
+    /// (POST).
+    AdminUserQuota    => "/admin/users/{user_id}/quota" { GET: Manage, POST: Manage },
+
     // ── Schema / meta (read-only) ───────────────────────────────────────────
     SchemaAgent       => "/schema/agent" { GET: Read },
     Device            => "/device" { GET: Read },
     HomeStats         => "/home/stats" { GET: Read },
+    /// LLM latency per provider and model, in the Prometheus text format.
+    Metrics           => "/metrics" { GET: Read },
+    /// GraphQL over agents, threads, tasks, messages and artifacts; a
+    /// WebSocket upgrade on GET serves the task event subscriptions.
+    GraphQL           => "/graphql" { GET: Read, POST: Read },
+    /// Write sample agents, threads, prompt templates and placeholder
+    /// secrets for local development.
+    DevSeed           => "/dev/seed" { POST: Manage },
 
     // ── Sub-scopes — the sandbox/run surface; submodules register the leaves,
     //    an embedder's `<base>/*` rule covers the subtree ────────────────────
@@ -132,6 +194,7 @@ define_routes! {
     Build             => "/build" { POST: Execute },
     BrowserSession    => "/browser/session" { POST: Execute },
     LlmExecute        => "/llm/execute" { POST: Execute },
+    Embeddings        => "/embeddings" { POST: Execute },
     Request           => "/request" { POST: Execute },
 }
 
@@ -0,0 +1,160 @@
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::admission::{
+        admit_run, reject_quota, Admission, Rejection, OVERLOADED_CODE, QUOTA_EXCEEDED_CODE,
+    };
+    use distri_core::agent::user_quotas::UserQuotas;
+    use distri_types::configuration::AdmissionLimits;
+    use distri_types::user_quotas::{QuotaExceeded, QuotaKind, UserQuotaLimits};
+
+    fn limited(max_concurrent_runs: usize, max_queued: usize) -> Admission {
+        Admission::new(AdmissionLimits {
+            max_concurrent_runs: Some(max_concurrent_runs),
+            max_queued,
+            queue_timeout_secs: 1,
+            retry_after_secs: 7,
+        })
+    }
+
+    #[tokio::test]
+    async fn test_runs_beyond_the_limit_wait_for_a_slot() {
+        let admission = limited(1, 4);
+        let first = admission.admit().await.expect("first run is admitted");
+        assert_eq!(admission.running(), 1);
+
+        let waiting = tokio::spawn({
+            let admission = admission.clone();
+            async move { admission.admit().await }
+        });
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        assert_eq!(admission.queued(), 1);
+
+        drop(first);
+        let second = waiting.await.unwrap().expect("queued run is admitted");
+        assert_eq!((admission.running(), admission.queued()), (1, 0));
+        drop(second);
+        assert_eq!(admission.running(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_a_full_queue_or_a_long_wait_is_rejected() {
+        let admission = limited(1, 0);
+        let _running = admission.admit().await.unwrap();
+        assert_eq!(admission.admit().await.unwrap_err(), Rejection::QueueFull);
+
+        let admission = limited(1, 1);
+        let _running = admission.admit().await.unwrap();
+        assert_eq!(admission.admit().await.unwrap_err(), Rejection::TimedOut);
+        assert_eq!(admission.queued(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_a_cancelled_wait_leaves_the_queue() {
+        let admission = limited(1, 1);
+        let _running = admission.admit().await.unwrap();
+
+        let waiting = tokio::spawn({
+            let admission = admission.clone();
+            async move { admission.admit().await }
+        });
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        assert_eq!(admission.queued(), 1);
+
+        waiting.abort();
+        let _ = waiting.await;
+        assert_eq!(admission.queued(), 0);
+    }
+
+    #[actix_web::test]
+    async fn test_runs_refused_a_slot_are_not_charged() {
+        let quotas = Arc::new(UserQuotas::new(UserQuotaLimits {
+            max_tasks_per_day: Some(1),
+            ..Default::default()
+        }));
+        let admission = limited(1, 0);
+        let running = admission.admit().await.unwrap();
+
+        let refused = admit_run(&quotas, Some(&admission), "alice", None)
+            .await
+            .err()
+            .expect("no slot is free");
+        assert_eq!(
+            refused.status(),
+            actix_web::http::StatusCode::TOO_MANY_REQUESTS
+        );
+        let status = quotas.status("alice");
+        assert_eq!((status.running_tasks, status.tasks_today), (0, 0));
+
+        drop(running);
+        let _permit = admit_run(&quotas, Some(&admission), "alice", None)
+            .await
+            .expect("the day's run is still available");
+        assert_eq!(quotas.status("alice").tasks_today, 1);
+        assert!(admit_run(&quotas, Some(&admission), "alice", None)
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_without_a_limit_every_run_is_admitted() {
+        let admission = Admission::default();
+        let permits: Vec<_> = futures_util::future::join_all((0..64).map(|_| admission.admit()))
+            .await
+            .into_iter()
+            .collect::<Result<_, _>>()
+            .expect("every run is admitted");
+        assert_eq!(permits.len(), 64);
+    }
+
+    #[actix_web::test]
+    async fn test_rejections_are_429_with_retry_after() {
+        let response = limited(1, 0).reject(Some(serde_json::json!(3)), Rejection::QueueFull);
+        assert_eq!(
+            response.status(),
+            actix_web::http::StatusCode::TOO_MANY_REQUESTS
+        );
+        assert_eq!(response.headers().get("Retry-After").unwrap(), "7");
+
+        let body = actix_web::body::to_bytes(response.into_body())
+            .await
+            .unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["id"], 3);
+        assert_eq!(body["error"]["code"], OVERLOADED_CODE);
+    }
+
+    #[actix_web::test]
+    async fn test_quota_rejections_carry_the_quota() {
+        let exceeded = QuotaExceeded {
+            user_id: "alice".to_string(),
+            quota: QuotaKind::TasksPerDay,
+            limit: 10,
+            used: 10,
+            resets_at: Some(chrono::Utc::now() + chrono::Duration::hours(2)),
+        };
+        let response = reject_quota(Some(serde_json::json!(4)), &exceeded);
+        assert_eq!(
+            response.status(),
+            actix_web::http::StatusCode::TOO_MANY_REQUESTS
+        );
+        let retry_after: i64 = response
+            .headers()
+            .get("Retry-After")
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .parse()
+            .unwrap();
+        assert!((7100..=7200).contains(&retry_after), "{}", retry_after);
+
+        let body = actix_web::body::to_bytes(response.into_body())
+            .await
+            .unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["error"]["code"], QUOTA_EXCEEDED_CODE);
+        assert_eq!(body["error"]["data"]["quota"], "tasks_per_day");
+        assert_eq!(body["error"]["data"]["used"], 10);
+    }
+}
@@ -0,0 +1,143 @@
+//! Integration tests for the `/v1/analytics/*` handlers.
+
+#[cfg(test)]
+mod tests {
+    use actix_web::{test, web, App};
+    use chrono::{TimeZone, Utc};
+    use distri_core::initialize_stores;
+    use distri_core::AgentOrchestratorBuilder;
+    use distri_types::configuration::{
+        DbConnectionConfig, MetadataStoreConfig, ServerConfig, StoreConfig,
+    };
+    use distri_types::{AgentEvent, AgentEventType};
+    use serde_json::Value;
+    use std::sync::Arc;
+
+    fn test_store_config() -> StoreConfig {
+        let db_name = uuid::Uuid::new_v4();
+        let db_url = format!("file:{}?mode=memory&cache=shared", db_name);
+        StoreConfig {
+            metadata: MetadataStoreConfig {
+                db_config: Some(DbConnectionConfig {
+                    database_url: db_url,
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    async fn make_orchestrator() -> Arc<distri_core::agent::AgentOrchestrator> {
+        let stores = initialize_stores(&test_store_config())
+            .await
+            .expect("stores");
+
+        Arc::new(
+            AgentOrchestratorBuilder::default()
+                .with_store_config(test_store_config())
+                .with_stores(stores)
+                .build()
+                .await
+                .expect("orchestrator"),
+        )
+    }
+
+    fn finished(agent_id: &str, success: bool) -> AgentEvent {
+        let mut event = AgentEvent::new(AgentEventType::RunFinished {
+            success,
+            total_steps: 2,
+            failed_steps: 0,
+            usage: None,
+            context_budget: None,
+        });
+        event.timestamp = Utc.with_ymd_and_hms(2026, 10, 3, 9, 0, 0).unwrap();
+        event.agent_id = agent_id.to_string();
+        event
+    }
+
+    async fn get(
+        orchestrator: Arc<distri_core::agent::AgentOrchestrator>,
+        uri: &str,
+    ) -> (u16, Value) {
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(ServerConfig::default()))
+                .configure(|cfg| {
+                    cfg.app_data(web::Data::new(orchestrator))
+                        .service(web::scope("/v1").configure(crate::routes::distri));
+                }),
+        )
+        .await;
+        let resp = test::call_service(&app, test::TestRequest::get().uri(uri).to_request()).await;
+        let status = resp.status().as_u16();
+        (status, test::read_body_json(resp).await)
+    }
+
+    #[actix_web::test]
+    async fn test_runs_and_agents_are_served_from_stored_events() {
+        let orchestrator = make_orchestrator().await;
+        let task_store = orchestrator.stores.task_store.clone();
+        for event in [
+            finished("researcher", true),
+            finished("researcher", false),
+            finished("writer", true),
+        ] {
+            let task_id = event.task_id.clone();
+            task_store.add_event_to_task(&task_id, event).await.unwrap();
+        }
+
+        let (status, body) = get(
+            orchestrator.clone(),
+            "/v1/analytics/runs?from=2026-10-02&to=2026-10-04",
+        )
+        .await;
+        assert_eq!(status, 200, "body: {}", body);
+        assert_eq!(body["totals"]["runs"], 3);
+        assert_eq!(body["totals"]["failed"], 1);
+        assert_eq!(body["totals"]["avg_steps_per_run"], 2.0);
+        let days = body["days"].as_array().unwrap();
+        assert_eq!(days.len(), 3);
+        assert_eq!(days[0]["runs"], 0);
+        assert_eq!(days[1]["day"], "2026-10-03");
+        assert_eq!(days[1]["runs"], 3);
+
+        let (_, body) = get(
+            orchestrator.clone(),
+            "/v1/analytics/runs?from=2026-10-03&to=2026-10-03&agent_id=writer",
+        )
+        .await;
+        assert_eq!(body["totals"]["runs"], 1);
+
+        let (status, body) = get(
+            orchestrator,
+            "/v1/analytics/agents?from=2026-10-01&to=2026-10-31",
+        )
+        .await;
+        assert_eq!(status, 200);
+        let agents = body["agents"].as_array().unwrap();
+        assert_eq!(agents[0]["agent_id"], "researcher");
+        assert_eq!(agents[0]["success_rate"], 0.5);
+        assert_eq!(agents[1]["agent_id"], "writer");
+    }
+
+    #[actix_web::test]
+    async fn test_tools_default_to_an_empty_list() {
+        let orchestrator = make_orchestrator().await;
+        let (status, body) = get(orchestrator, "/v1/analytics/tools").await;
+        assert_eq!(status, 200);
+        assert_eq!(body["tools"], Value::Array(vec![]));
+        assert!(body["range"]["from"].is_string());
+    }
+
+    #[actix_web::test]
+    async fn test_invalid_ranges_are_rejected() {
+        let orchestrator = make_orchestrator().await;
+        let (status, _) = get(
+            orchestrator,
+            "/v1/analytics/runs?from=2026-10-05&to=2026-10-01",
+        )
+        .await;
+        assert_eq!(status, 400);
+    }
+}
@@ -184,4 +184,182 @@ mod tests {
             "Should only return duplicate filename once"
         );
     }
+
+    async fn make_app_orchestrator(
+        filesystem: distri_filesystem::FileSystem,
+    ) -> Arc<distri_core::agent::AgentOrchestrator> {
+        use distri_types::configuration::{DbConnectionConfig, MetadataStoreConfig, StoreConfig};
+
+        let store_config = StoreConfig {
+            metadata: MetadataStoreConfig {
+                db_config: Some(DbConnectionConfig {
+                    database_url: format!("file:{}?mode=memory&cache=shared", uuid::Uuid::new_v4()),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let stores = distri_core::initialize_stores(&store_config)
+            .await
+            .expect("stores");
+        let orchestrator = distri_core::AgentOrchestratorBuilder::default()
+            .with_store_config(store_config)
+            .with_stores(stores)
+            .with_session_filesystem(Arc::new(filesystem))
+            .build()
+            .await
+            .expect("orchestrator");
+        Arc::new(orchestrator)
+    }
+
+    #[actix_web::test]
+    async fn test_download_streams_ranges_and_resumes() {
+        use actix_web::{http::header, test, web, App};
+
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+        let filesystem =
+            distri_filesystem::create_file_system(distri_filesystem::FileSystemConfig {
+                object_store: distri_types::configuration::ObjectStorageConfig::FileSystem {
+                    base_path: temp_dir.path().to_string_lossy().to_string(),
+                },
+                root_prefix: None,
+            })
+            .await
+            .expect("Failed to create filesystem");
+        let log: String = (0..200).map(|i| format!("line {:04}\n", i)).collect();
+        filesystem
+            .write("shared/reports/content/build-output", &log)
+            .await
+            .unwrap();
+        filesystem
+            .write("shared/reports/content/rows.json", r#"[{"id": 1}]"#)
+            .await
+            .unwrap();
+        filesystem
+            .write(
+                "shared/reports/content/report.html",
+                "<script>alert(1)</script>",
+            )
+            .await
+            .unwrap();
+
+        let orchestrator = make_app_orchestrator(filesystem).await;
+        let app = test::init_service(App::new().app_data(web::Data::new(orchestrator)).service(
+            web::scope("/artifacts").configure(crate::routes::artifacts::configure_artifact_routes),
+        ))
+        .await;
+        let uri = "/artifacts/shared/reports/download/build-output";
+
+        // Whole file, type sniffed from the content.
+        let resp = test::call_service(&app, test::TestRequest::get().uri(uri).to_request()).await;
+        assert_eq!(resp.status(), 200);
+        let headers = resp.headers().clone();
+        assert_eq!(headers.get(header::ACCEPT_RANGES).unwrap(), "bytes");
+        assert_eq!(
+            headers.get(header::CONTENT_TYPE).unwrap(),
+            "text/plain; charset=utf-8"
+        );
+        assert_eq!(
+            headers.get(header::CONTENT_DISPOSITION).unwrap(),
+            "inline; filename=\"build-output\""
+        );
+        assert_eq!(
+            headers.get(header::X_CONTENT_TYPE_OPTIONS).unwrap(),
+            "nosniff"
+        );
+        assert_eq!(
+            headers.get(header::CONTENT_SECURITY_POLICY).unwrap(),
+            "sandbox"
+        );
+        assert_eq!(test::read_body(resp).await, log.as_bytes());
+        let etag = headers
+            .get(header::ETAG)
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .to_string();
+
+        // A byte range.
+        let req = test::TestRequest::get()
+            .uri(uri)
+            .insert_header((header::RANGE, "bytes=10-19"))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 206);
+        assert_eq!(
+            resp.headers().get(header::CONTENT_RANGE).unwrap(),
+            format!("bytes 10-19/{}", log.len()).as_str()
+        );
+        assert_eq!(test::read_body(resp).await, log.as_bytes()[10..20]);
+
+        // Resuming applies the range only while the ETag still matches.
+        let req = test::TestRequest::get()
+            .uri(uri)
+            .insert_header((header::RANGE, "bytes=1990-"))
+            .insert_header((header::IF_RANGE, etag.as_str()))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 206);
+        assert_eq!(test::read_body(resp).await, log.as_bytes()[1990..]);
+        let req = test::TestRequest::get()
+            .uri(uri)
+            .insert_header((header::RANGE, "bytes=1990-"))
+            .insert_header((header::IF_RANGE, "\"stale\""))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 200);
+        assert_eq!(test::read_body(resp).await.len(), log.len());
+
+        let req = test::TestRequest::get()
+            .uri(uri)
+            .insert_header((header::RANGE, "bytes=99999-"))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 416);
+        assert_eq!(
+            resp.headers().get(header::CONTENT_RANGE).unwrap(),
+            format!("bytes */{}", log.len()).as_str()
+        );
+
+        // The local backend cannot presign, so the server streams it instead.
+        let req = test::TestRequest::get()
+            .uri("/artifacts/shared/reports/download/rows.json?presigned=true&download=true")
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 200);
+        assert_eq!(
+            resp.headers().get(header::CONTENT_TYPE).unwrap(),
+            "application/json"
+        );
+        assert_eq!(
+            resp.headers().get(header::CONTENT_DISPOSITION).unwrap(),
+            "attachment; filename=\"rows.json\""
+        );
+
+        // Markup is never rendered inline.
+        let req = test::TestRequest::get()
+            .uri("/artifacts/shared/reports/download/report.html")
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 200);
+        assert_eq!(
+            resp.headers().get(header::CONTENT_DISPOSITION).unwrap(),
+            "attachment; filename=\"report.html\""
+        );
+
+        let req = test::TestRequest::get()
+            .uri("/artifacts/shared/reports/download/missing.csv")
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 404);
+        assert_eq!(
+            resp.headers().get(header::X_CONTENT_TYPE_OPTIONS).unwrap(),
+            "nosniff"
+        );
+        assert_eq!(
+            resp.headers().get(header::CONTENT_SECURITY_POLICY).unwrap(),
+            "sandbox"
+        );
+    }
 }
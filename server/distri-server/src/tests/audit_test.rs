@@ -0,0 +1,141 @@
+//! Integration tests for the LLM audit route module.
+//!
+//! Uses `InMemoryLlmAuditStore` wired into an `AgentOrchestrator` to exercise
+//! the list and JSONL export handlers via the actix-web test harness.
+
+#[cfg(test)]
+mod tests {
+    use actix_web::{test, web, App};
+    use distri_core::initialize_stores;
+    use distri_core::{AgentOrchestratorBuilder, InMemoryLlmAuditStore};
+    use distri_types::api::audit::{LlmAuditConfig, LlmAuditRecord};
+    use distri_types::configuration::{
+        DbConnectionConfig, MetadataStoreConfig, ServerConfig, StoreConfig,
+    };
+    use distri_types::stores::LlmAuditStore;
+    use serde_json::{json, Value};
+    use std::sync::Arc;
+
+    fn test_store_config() -> StoreConfig {
+        let db_name = uuid::Uuid::new_v4();
+        let db_url = format!("file:{}?mode=memory&cache=shared", db_name);
+        StoreConfig {
+            metadata: MetadataStoreConfig {
+                db_config: Some(DbConnectionConfig {
+                    database_url: db_url,
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    fn record(agent: &str, user: &str) -> LlmAuditRecord {
+        LlmAuditRecord {
+            id: uuid::Uuid::new_v4(),
+            created_at: chrono::Utc::now(),
+            agent_id: agent.into(),
+            user_id: user.into(),
+            thread_id: "thread-1".into(),
+            task_id: "task-1".into(),
+            workspace_id: None,
+            provider: "openai".into(),
+            model: "gpt-4.1-mini".into(),
+            streaming: false,
+            request: json!([{"role": "user", "content": "hi"}]),
+            response: json!({"content": "hello", "tool_calls": []}),
+            error: None,
+            input_tokens: 3,
+            output_tokens: 1,
+            duration_ms: 12,
+            redacted: true,
+        }
+    }
+
+    async fn make_orchestrator(
+        audit: Option<Arc<dyn LlmAuditStore>>,
+    ) -> Arc<distri_core::agent::AgentOrchestrator> {
+        let mut stores = initialize_stores(&test_store_config())
+            .await
+            .expect("stores");
+        stores.llm_audit_store = audit;
+
+        Arc::new(
+            AgentOrchestratorBuilder::default()
+                .with_store_config(test_store_config())
+                .with_stores(stores)
+                .build()
+                .await
+                .expect("orchestrator"),
+        )
+    }
+
+    macro_rules! app {
+        ($orchestrator:expr) => {{
+            let orchestrator = $orchestrator;
+            test::init_service(
+                App::new()
+                    .app_data(web::Data::new(ServerConfig::default()))
+                    .configure(|cfg| {
+                        cfg.app_data(web::Data::new(orchestrator))
+                            .service(web::scope("/v1").configure(crate::routes::distri));
+                    }),
+            )
+            .await
+        }};
+    }
+
+    #[actix_web::test]
+    async fn test_audit_returns_503_when_disabled() {
+        let app = app!(make_orchestrator(None).await);
+        let req = test::TestRequest::get().uri("/v1/audit/llm").to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 503);
+    }
+
+    #[actix_web::test]
+    async fn test_list_audit_filters_by_user() {
+        let store = Arc::new(InMemoryLlmAuditStore::new(LlmAuditConfig {
+            enabled: true,
+            ..Default::default()
+        }));
+        store.record(record("research", "alice")).await.unwrap();
+        store.record(record("research", "bob")).await.unwrap();
+        let app = app!(make_orchestrator(Some(store)).await);
+
+        let req = test::TestRequest::get()
+            .uri("/v1/audit/llm?user_id=alice")
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 200);
+        let body: Value = test::read_body_json(resp).await;
+        let records = body["records"].as_array().expect("records array");
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0]["user_id"], "alice");
+    }
+
+    #[actix_web::test]
+    async fn test_export_audit_is_jsonl() {
+        let store = Arc::new(InMemoryLlmAuditStore::default());
+        store.record(record("a", "u")).await.unwrap();
+        store.record(record("b", "u")).await.unwrap();
+        let app = app!(make_orchestrator(Some(store)).await);
+
+        let req = test::TestRequest::get()
+            .uri("/v1/audit/llm/export")
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 200);
+        assert_eq!(
+            resp.headers().get("content-type").unwrap(),
+            "application/x-ndjson"
+        );
+        let body = test::read_body(resp).await;
+        let text = std::str::from_utf8(&body).unwrap();
+        assert_eq!(text.lines().count(), 2);
+        for line in text.lines() {
+            let _: LlmAuditRecord = serde_json::from_str(line).expect("valid record line");
+        }
+    }
+}
@@ -0,0 +1,184 @@
+//! Integration tests for the slash-command route module.
+
+#[cfg(test)]
+mod tests {
+    use actix_web::{test, web, App};
+    use distri_core::initialize_stores;
+    use distri_core::AgentOrchestratorBuilder;
+    use distri_types::channel_commands::SlashCommand;
+    use distri_types::configuration::{
+        DbConnectionConfig, MetadataStoreConfig, ServerConfig, StoreConfig,
+    };
+    use distri_types::StandardDefinition;
+    use serde_json::{json, Value};
+    use std::sync::Arc;
+
+    fn test_store_config() -> StoreConfig {
+        let db_name = uuid::Uuid::new_v4();
+        let db_url = format!("file:{}?mode=memory&cache=shared", db_name);
+        StoreConfig {
+            metadata: MetadataStoreConfig {
+                db_config: Some(DbConnectionConfig {
+                    database_url: db_url,
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    async fn make_orchestrator() -> Arc<distri_core::agent::AgentOrchestrator> {
+        let stores = initialize_stores(&test_store_config())
+            .await
+            .expect("stores");
+        let orchestrator = AgentOrchestratorBuilder::default()
+            .with_store_config(test_store_config())
+            .with_stores(stores)
+            .build()
+            .await
+            .expect("orchestrator");
+        let review: SlashCommand = serde_json::from_value(json!({
+            "name": "/review",
+            "description": "Review a file",
+            "prompt": "Review {{path}}.",
+            "args": [{"name": "path", "required": true}]
+        }))
+        .unwrap();
+        let tz: SlashCommand = serde_json::from_value(json!({
+            "name": "/tz",
+            "description": "Convert a time to another timezone",
+            "prompt": "",
+            "args": [{"name": "to", "required": true}, {"name": "datetime"}],
+            "tool": "timezone_convert"
+        }))
+        .unwrap();
+        orchestrator.register_slash_commands(vec![review, tz]).await;
+        Arc::new(orchestrator)
+    }
+
+    #[actix_web::test]
+    async fn test_list_and_render_workspace_commands() {
+        let orchestrator = make_orchestrator().await;
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(ServerConfig::default()))
+                .configure(|cfg| {
+                    cfg.app_data(web::Data::new(orchestrator))
+                        .service(web::scope("/v1").configure(crate::routes::distri));
+                }),
+        )
+        .await;
+
+        let req = test::TestRequest::get().uri("/v1/commands").to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 200);
+        let body: Value = test::read_body_json(resp).await;
+        let names: Vec<&str> = body["commands"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|c| c["name"].as_str().unwrap())
+            .collect();
+        assert!(names.contains(&"/help"));
+        assert!(names.contains(&"/review"));
+
+        let req = test::TestRequest::post()
+            .uri("/v1/commands/render")
+            .set_json(json!({"name": "/review", "input": "src/lib.rs"}))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 200);
+        let body: Value = test::read_body_json(resp).await;
+        assert_eq!(body["message"], "Review src/lib.rs.");
+
+        let req = test::TestRequest::post()
+            .uri("/v1/commands/render")
+            .set_json(json!({"name": "/review"}))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 400, "missing required arg");
+
+        let req = test::TestRequest::get()
+            .uri("/v1/commands?agent=missing")
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 404);
+    }
+
+    #[actix_web::test]
+    async fn test_run_calls_the_bound_tool() {
+        let orchestrator = make_orchestrator().await;
+        for (name, builtin) in [("clock", vec!["timezone_convert"]), ("plain", vec![])] {
+            let agent: StandardDefinition = serde_json::from_value(json!({
+                "name": name,
+                "description": "d",
+                "tools": {"builtin": builtin}
+            }))
+            .unwrap();
+            orchestrator.register_agent_definition(agent).await.unwrap();
+        }
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(ServerConfig::default()))
+                .configure(|cfg| {
+                    cfg.app_data(web::Data::new(orchestrator))
+                        .service(web::scope("/v1").configure(crate::routes::distri));
+                }),
+        )
+        .await;
+
+        let req = test::TestRequest::post()
+            .uri("/v1/commands/run")
+            .set_json(json!({
+                "name": "/tz",
+                "input": "Asia/Tokyo 2026-01-01T00:00:00Z",
+                "agent": "clock"
+            }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 200);
+        let body: Value = test::read_body_json(resp).await;
+        assert_eq!(body["tool"], "timezone_convert");
+        assert!(
+            body["result"]
+                .to_string()
+                .contains("2026-01-01T09:00:00+09:00"),
+            "{body}"
+        );
+
+        let req = test::TestRequest::post()
+            .uri("/v1/commands/run")
+            .set_json(json!({"name": "/tz", "agent": "clock"}))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 400, "missing required arg");
+
+        // The tool runs among the agent's tools, and only for an agent.
+        let req = test::TestRequest::post()
+            .uri("/v1/commands/run")
+            .set_json(json!({"name": "/tz", "input": "UTC", "agent": "plain"}))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 500);
+        let body: Value = test::read_body_json(resp).await;
+        assert!(
+            body["error"].as_str().unwrap().contains("has no tool"),
+            "{body}"
+        );
+        let req = test::TestRequest::post()
+            .uri("/v1/commands/run")
+            .set_json(json!({"name": "/tz", "input": "UTC"}))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 400, "no agent");
+
+        // Prompt commands are rendered, not run.
+        let req = test::TestRequest::post()
+            .uri("/v1/commands/run")
+            .set_json(json!({"name": "/review", "input": "src/lib.rs", "agent": "clock"}))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 400);
+    }
+}
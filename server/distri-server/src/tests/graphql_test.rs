@@ -0,0 +1,173 @@
+//! Integration tests for the `POST /v1/graphql` endpoint.
+
+#[cfg(test)]
+mod tests {
+    use actix_web::{test, web, App};
+    use distri_core::initialize_stores;
+    use distri_core::AgentOrchestratorBuilder;
+    use distri_types::configuration::{
+        DbConnectionConfig, MetadataStoreConfig, ServerConfig, StoreConfig,
+    };
+    use distri_types::stores::CreateTaskInput;
+    use distri_types::{CreateThreadRequest, TaskStatus};
+    use serde_json::{json, Value};
+    use std::sync::Arc;
+
+    fn test_store_config() -> StoreConfig {
+        let db_name = uuid::Uuid::new_v4();
+        let db_url = format!("file:{}?mode=memory&cache=shared", db_name);
+        StoreConfig {
+            metadata: MetadataStoreConfig {
+                db_config: Some(DbConnectionConfig {
+                    database_url: db_url,
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    /// A thread of `support` with a completed task, which ran a failed
+    /// sub-task.
+    async fn make_orchestrator() -> Arc<distri_core::agent::AgentOrchestrator> {
+        let stores = initialize_stores(&test_store_config())
+            .await
+            .expect("stores");
+        let orchestrator = AgentOrchestratorBuilder::default()
+            .with_store_config(test_store_config())
+            .with_stores(stores)
+            .build()
+            .await
+            .expect("orchestrator");
+        orchestrator
+            .stores
+            .thread_store
+            .create_thread(CreateThreadRequest {
+                agent_id: "support".to_string(),
+                title: Some("Refund".to_string()),
+                thread_id: Some("refund-thread".to_string()),
+                attributes: None,
+                user_id: None,
+                external_id: None,
+                channel_id: None,
+            })
+            .await
+            .expect("create thread");
+        let tasks = &orchestrator.stores.task_store;
+        tasks
+            .create_task(
+                CreateTaskInput::local("refund-thread")
+                    .with_id("root-task")
+                    .with_status(TaskStatus::Completed),
+            )
+            .await
+            .expect("create task");
+        tasks
+            .create_task(
+                CreateTaskInput::local("refund-thread")
+                    .with_id("lookup-task")
+                    .with_parent("root-task")
+                    .with_status(TaskStatus::Failed),
+            )
+            .await
+            .expect("create sub-task");
+        Arc::new(orchestrator)
+    }
+
+    async fn query(orchestrator: Arc<distri_core::agent::AgentOrchestrator>, query: &str) -> Value {
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(ServerConfig::default()))
+                .configure(|cfg| {
+                    cfg.app_data(web::Data::new(orchestrator))
+                        .service(web::scope("/v1").configure(crate::routes::distri));
+                }),
+        )
+        .await;
+        let req = test::TestRequest::post()
+            .uri("/v1/graphql")
+            .set_json(json!({ "query": query }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 200);
+        let body: Value = test::read_body_json(resp).await;
+        assert!(body.get("errors").is_none(), "unexpected errors: {body}");
+        body["data"].clone()
+    }
+
+    #[actix_web::test]
+    async fn test_thread_with_its_tasks_in_one_query() {
+        let data = query(
+            make_orchestrator().await,
+            r#"{
+                threads(filter: { agentId: "support" }) {
+                    total
+                    hasMore
+                    items {
+                        id
+                        title
+                        tasks { total items { id status } }
+                        messages { total }
+                    }
+                }
+                thread(id: "missing") { id }
+            }"#,
+        )
+        .await;
+
+        assert_eq!(data["threads"]["total"], 1);
+        assert_eq!(data["threads"]["hasMore"], false);
+        let thread = &data["threads"]["items"][0];
+        assert_eq!(thread["id"], "refund-thread");
+        assert_eq!(thread["title"], "Refund");
+        assert_eq!(thread["tasks"]["total"], 2);
+        assert_eq!(thread["messages"]["total"], 0);
+        assert_eq!(data["thread"], Value::Null);
+    }
+
+    #[actix_web::test]
+    async fn test_tasks_filter_and_paginate() {
+        let data = query(
+            make_orchestrator().await,
+            r#"{
+                failed: tasks(filter: { threadId: "refund-thread", status: "failed" }) {
+                    total
+                    items { id parentTaskId }
+                }
+                firstPage: tasks(filter: { threadId: "refund-thread" }, limit: 1) {
+                    total
+                    hasMore
+                    items { id }
+                }
+                task(id: "root-task") {
+                    status
+                    thread { id }
+                    subtasks { items { id status } }
+                }
+            }"#,
+        )
+        .await;
+
+        assert_eq!(
+            data["failed"],
+            json!({ "total": 1, "items": [{ "id": "lookup-task", "parentTaskId": "root-task" }] })
+        );
+        assert_eq!(data["firstPage"]["total"], 2);
+        assert_eq!(data["firstPage"]["hasMore"], true);
+        assert_eq!(data["firstPage"]["items"].as_array().unwrap().len(), 1);
+        assert_eq!(data["task"]["status"], "completed");
+        assert_eq!(data["task"]["thread"]["id"], "refund-thread");
+        assert_eq!(
+            data["task"]["subtasks"]["items"],
+            json!([{ "id": "lookup-task", "status": "failed" }])
+        );
+    }
+
+    #[actix_web::test]
+    async fn test_schema_exposes_task_event_subscription() {
+        let sdl = crate::routes::graphql::schema().sdl();
+        assert!(sdl.contains("taskEvents(taskId: String!): TaskEvent!"));
+        assert!(sdl.contains("type SubscriptionRoot"));
+    }
+}
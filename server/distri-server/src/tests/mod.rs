@@ -1,6 +1,13 @@
+pub mod admission_test;
+pub mod analytics_test;
 pub mod artifacts_test;
+pub mod audit_test;
+pub mod commands_test;
 pub mod connections_test;
+pub mod graphql_test;
 pub mod notes_test;
+pub mod preview_test;
+pub mod share_test;
 pub mod skills_test;
 pub mod spans_test;
 pub mod thread_tokens_test;
@@ -0,0 +1,162 @@
+//! Integration tests for the agent prompt preview route.
+
+#[cfg(test)]
+mod tests {
+    use actix_web::{test, web, App};
+    use distri_core::initialize_stores;
+    use distri_core::AgentOrchestratorBuilder;
+    use distri_types::configuration::{
+        DbConnectionConfig, MetadataStoreConfig, ServerConfig, StoreConfig,
+    };
+    use distri_types::prompt::{PromptLayersConfig, RuntimeContextItem};
+    use distri_types::StandardDefinition;
+    use serde_json::{json, Value};
+    use std::sync::Arc;
+
+    fn test_store_config() -> StoreConfig {
+        let db_name = uuid::Uuid::new_v4();
+        let db_url = format!("file:{}?mode=memory&cache=shared", db_name);
+        StoreConfig {
+            metadata: MetadataStoreConfig {
+                db_config: Some(DbConnectionConfig {
+                    database_url: db_url,
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    async fn make_orchestrator() -> Arc<distri_core::agent::AgentOrchestrator> {
+        let stores = initialize_stores(&test_store_config())
+            .await
+            .expect("stores");
+        let orchestrator = AgentOrchestratorBuilder::default()
+            .with_store_config(test_store_config())
+            .with_stores(stores)
+            .build()
+            .await
+            .expect("orchestrator");
+        orchestrator
+            .register_agent_definition(StandardDefinition {
+                name: "support".to_string(),
+                description: "Answers support tickets".to_string(),
+                instructions: "You are the support agent. Be brief.".to_string(),
+                ..Default::default()
+            })
+            .await
+            .expect("register agent");
+        Arc::new(orchestrator)
+    }
+
+    #[actix_web::test]
+    async fn test_preview_assembles_prompt_without_model_call() {
+        let orchestrator = make_orchestrator().await;
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(ServerConfig::default()))
+                .configure(|cfg| {
+                    cfg.app_data(web::Data::new(orchestrator))
+                        .service(web::scope("/v1").configure(crate::routes::distri));
+                }),
+        )
+        .await;
+
+        let req = test::TestRequest::post()
+            .uri("/v1/agents/support/preview")
+            .set_json(json!({"message": "Where is my refund?"}))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 200);
+        let body: Value = test::read_body_json(resp).await;
+
+        let messages = body["messages"].as_array().unwrap();
+        assert_eq!(messages[0]["role"], "system");
+        assert!(messages[0]
+            .to_string()
+            .contains("You are the support agent"));
+        assert!(messages
+            .last()
+            .unwrap()
+            .to_string()
+            .contains("Where is my refund?"));
+        assert_eq!(
+            body["message_tokens"].as_array().unwrap().len(),
+            messages.len()
+        );
+        assert!(body["tokens"]["system_static"].as_u64().unwrap() > 0);
+        assert!(body["tokens"]["user_message"].as_u64().unwrap() > 0);
+        assert!(
+            body["tokens"]["total"].as_u64().unwrap()
+                >= body["tokens"]["system_static"].as_u64().unwrap()
+        );
+
+        let req = test::TestRequest::post()
+            .uri("/v1/agents/nobody/preview")
+            .set_json(json!({"message": "hi"}))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 404);
+    }
+
+    #[actix_web::test]
+    async fn test_preview_lists_prompt_layers_in_order() {
+        let stores = initialize_stores(&test_store_config())
+            .await
+            .expect("stores");
+        let orchestrator = AgentOrchestratorBuilder::default()
+            .with_store_config(test_store_config())
+            .with_stores(stores)
+            .with_prompt_policy(Some("Never share credentials.".to_string()))
+            .build()
+            .await
+            .expect("orchestrator");
+        orchestrator
+            .register_agent_definition(StandardDefinition {
+                name: "dated".to_string(),
+                description: "Knows the date".to_string(),
+                instructions: "You are the dated agent.".to_string(),
+                prompt_layers: Some(PromptLayersConfig {
+                    runtime: vec![RuntimeContextItem::Date],
+                    ..Default::default()
+                }),
+                ..Default::default()
+            })
+            .await
+            .expect("register agent");
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(ServerConfig::default()))
+                .configure(|cfg| {
+                    cfg.app_data(web::Data::new(Arc::new(orchestrator)))
+                        .service(web::scope("/v1").configure(crate::routes::distri));
+                }),
+        )
+        .await;
+
+        let req = test::TestRequest::post()
+            .uri("/v1/agents/dated/preview")
+            .set_json(json!({"message": "What day is it?"}))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 200);
+        let body: Value = test::read_body_json(resp).await;
+
+        let kinds: Vec<&str> = body["layers"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|l| l["kind"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            kinds,
+            vec!["persona", "workspace_policy", "framework", "runtime"]
+        );
+        let system = body["messages"][0].to_string();
+        let persona = system.find("You are the dated agent").unwrap();
+        let policy = system.find("Never share credentials").unwrap();
+        let date = system.find("CURRENT DATE").unwrap();
+        assert!(persona < policy && policy < date);
+    }
+}
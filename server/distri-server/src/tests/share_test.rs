@@ -0,0 +1,112 @@
+//! Integration tests for read-only observer shares.
+
+#[cfg(test)]
+mod tests {
+    use actix_web::{test, web, App};
+    use distri_core::initialize_stores;
+    use distri_core::AgentOrchestratorBuilder;
+    use distri_types::configuration::{
+        DbConnectionConfig, MetadataStoreConfig, ServerConfig, StoreConfig,
+    };
+    use distri_types::CreateThreadRequest;
+    use serde_json::{json, Value};
+    use std::sync::Arc;
+
+    fn test_store_config() -> StoreConfig {
+        let db_name = uuid::Uuid::new_v4();
+        let db_url = format!("file:{}?mode=memory&cache=shared", db_name);
+        StoreConfig {
+            metadata: MetadataStoreConfig {
+                db_config: Some(DbConnectionConfig {
+                    database_url: db_url,
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    async fn make_orchestrator() -> Arc<distri_core::agent::AgentOrchestrator> {
+        let stores = initialize_stores(&test_store_config())
+            .await
+            .expect("stores");
+        let orchestrator = AgentOrchestratorBuilder::default()
+            .with_store_config(test_store_config())
+            .with_stores(stores)
+            .build()
+            .await
+            .expect("orchestrator");
+        orchestrator
+            .stores
+            .thread_store
+            .create_thread(CreateThreadRequest {
+                agent_id: "support".to_string(),
+                title: Some("Pairing".to_string()),
+                thread_id: Some("shared-thread".to_string()),
+                attributes: None,
+                user_id: None,
+                external_id: None,
+                channel_id: None,
+            })
+            .await
+            .expect("create thread");
+        Arc::new(orchestrator)
+    }
+
+    #[actix_web::test]
+    async fn test_share_token_grants_watch_until_revoked() {
+        let orchestrator = make_orchestrator().await;
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(ServerConfig::default()))
+                .configure(|cfg| {
+                    cfg.app_data(web::Data::new(orchestrator))
+                        .service(web::scope("/v1").configure(crate::routes::distri));
+                }),
+        )
+        .await;
+
+        let req = test::TestRequest::post()
+            .uri("/v1/threads/shared-thread/share")
+            .set_json(json!({"ttl_secs": 600}))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 200);
+        let share: Value = test::read_body_json(resp).await;
+        let token = share["token"].as_str().unwrap().to_string();
+        assert_eq!(
+            share["watch_path"],
+            format!("/threads/shared-thread/watch?token={}", token)
+        );
+
+        // The stored grant never contains the token itself.
+        let req = test::TestRequest::get()
+            .uri("/v1/threads/shared-thread")
+            .to_request();
+        let thread: Value = test::read_body_json(test::call_service(&app, req).await).await;
+        assert!(!thread["metadata"].to_string().contains(&token));
+
+        let watch = |token: &str| {
+            test::TestRequest::get()
+                .uri(&format!("/v1/threads/shared-thread/watch?token={}", token))
+                .to_request()
+        };
+        let resp = test::call_service(&app, watch(&token)).await;
+        assert_eq!(resp.status(), 200);
+        let resp = test::call_service(&app, watch("not-the-token")).await;
+        assert_eq!(resp.status(), 401);
+
+        let req = test::TestRequest::delete()
+            .uri("/v1/threads/shared-thread/share")
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 204);
+        let resp = test::call_service(&app, watch(&token)).await;
+        assert_eq!(resp.status(), 401);
+
+        let req = test::TestRequest::post()
+            .uri("/v1/threads/nowhere/share")
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 404);
+    }
+}
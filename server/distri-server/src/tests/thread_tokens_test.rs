@@ -42,6 +42,7 @@ mod tests {
             input_tokens: 1500,
             output_tokens: 3000,
             total_tokens: 4500,
+            archived_at: None,
         };
 
         let json = serde_json::to_value(&summary).expect("Failed to serialize summary");
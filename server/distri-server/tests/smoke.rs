@@ -44,8 +44,9 @@ const SMOKE_ROUTES: &[(&str, &str)] = &[
     ("GET", "/v1/schema/agent"),
     // Connections — verifies connection_store is wired (Task 5)
     ("GET", "/v1/connections"),
-    // OpenAPI spec
+    // OpenAPI spec and Swagger UI
     ("GET", "/openapi.json"),
+    ("GET", "/api/docs"),
 ];
 
 fn test_store_config() -> StoreConfig {
@@ -100,6 +101,10 @@ async fn smoke_all_route_groups_have_app_data() {
                 "/openapi.json",
                 web::get().to(distri_server::openapi::serve_openapi),
             )
+            .route(
+                "/api/docs",
+                web::get().to(distri_server::openapi::serve_swagger_ui),
+            )
             .app_data(web::Data::new(executor))
             .app_data(web::Data::new(verbose.clone()))
             .service(web::scope("/v1").configure(distri_server::routes::distri))
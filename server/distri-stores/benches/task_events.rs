@@ -0,0 +1,101 @@
+//! Throughput of storing a run's task events on SQLite, one INSERT per event
+//! against the batched writes of `BufferedTaskStore`.
+//!
+//! ```sh
+//! cargo bench -p distri-stores --features sqlite --bench task_events
+//! ```
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use distri_stores::BufferedTaskStore;
+use distri_stores::diesel_store::SqliteStoreBuilder;
+use distri_types::configuration::EventWriteConfig;
+use distri_types::stores::{CreateTaskInput, TaskStore, ThreadStore};
+use distri_types::{AgentEvent, AgentEventType, CreateThreadRequest};
+
+/// Events of one simulated run: a token stream's worth of step events.
+const EVENTS: usize = 5_000;
+
+/// A task store on a fresh SQLite file, with a thread and task to write to.
+async fn task_store(dir: &tempfile::TempDir, name: &str) -> Arc<dyn TaskStore> {
+    let url = dir.path().join(format!("{name}.db"));
+    let store = SqliteStoreBuilder::sqlite(url.to_str().unwrap(), 4)
+        .await
+        .expect("sqlite store");
+    store
+        .thread_store()
+        .create_thread(CreateThreadRequest {
+            agent_id: "bench".to_string(),
+            title: None,
+            thread_id: Some("thread".to_string()),
+            attributes: None,
+            user_id: None,
+            external_id: None,
+            channel_id: None,
+        })
+        .await
+        .expect("thread");
+    let task_store = Arc::new(store.task_store()) as Arc<dyn TaskStore>;
+    task_store
+        .create_task(CreateTaskInput::local("thread").with_id("task"))
+        .await
+        .expect("task");
+    task_store
+}
+
+/// Store `EVENTS` events and the run's final event; the time until all are
+/// stored.
+async fn run(store: &dyn TaskStore) -> Duration {
+    let started = Instant::now();
+    for step_index in 0..EVENTS {
+        let event = AgentEvent::new(AgentEventType::StepStarted {
+            step_id: format!("step-{step_index}"),
+            step_index,
+        });
+        store.add_event_to_task("task", event).await.expect("event");
+    }
+    let finished = AgentEvent::new(AgentEventType::RunFinished {
+        success: true,
+        total_steps: EVENTS,
+        failed_steps: 0,
+        usage: None,
+        context_budget: None,
+    });
+    store
+        .add_event_to_task("task", finished)
+        .await
+        .expect("event");
+    started.elapsed()
+}
+
+fn report(label: &str, elapsed: Duration) {
+    println!(
+        "{label:<10} {:>8.1} ms  {:>10.0} events/s",
+        elapsed.as_secs_f64() * 1000.0,
+        (EVENTS + 1) as f64 / elapsed.as_secs_f64()
+    );
+}
+
+#[tokio::main]
+async fn main() {
+    let dir = tempfile::tempdir().expect("temp dir");
+
+    let direct = run(task_store(&dir, "direct").await.as_ref()).await;
+    report("direct", direct);
+
+    let buffered = BufferedTaskStore::wrap(
+        task_store(&dir, "buffered").await,
+        &EventWriteConfig {
+            max_batch: 256,
+            ..Default::default()
+        },
+    );
+    let batched = run(buffered.as_ref()).await;
+    report("buffered", batched);
+
+    println!(
+        "speedup    {:.1}x",
+        direct.as_secs_f64() / batched.as_secs_f64()
+    );
+}
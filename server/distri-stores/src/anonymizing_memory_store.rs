@@ -0,0 +1,401 @@
+//! Pseudonymization of memories before they are stored.
+//!
+//! [`AnonymizingMemoryStore`] replaces the identifiers of each memory with
+//! pseudonyms (see [`distri_types::memory_anonymization`]) before handing it
+//! to the inner store. A pseudonym's id is an HMAC-SHA256 of the owner and
+//! the normalized identifier, so search queries are pseudonymized the same
+//! way and still match.
+//!
+//! Outside strict mode, each pseudonym's value is sealed with AES-256-GCM
+//! (bound to its owner and pseudonym) and kept in the session store, under
+//! the `memory_pseudonyms:<owner>` namespace, apart from the memories. Reads
+//! restore the values of the owner's pseudonyms; clearing an owner's
+//! memories drops their mapping too. Both keys are derived from the secret
+//! in `key_env`.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use anyhow::{Context, Result, anyhow, bail};
+use async_trait::async_trait;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use distri_types::memory_anonymization::{
+    EntityDetector, MemoryAnonymizationConfig, pseudonym, pseudonyms, reidentify,
+};
+use distri_types::stores::{MemoryStore, SessionMemory, SessionStore};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Length of a sealed value's nonce.
+const NONCE_LEN: usize = 12;
+
+/// A [`MemoryStore`] that stores memories with their identifiers
+/// pseudonymized.
+pub struct AnonymizingMemoryStore {
+    inner: Arc<dyn MemoryStore>,
+    /// Where the sealed values of pseudonyms are kept; `None` in strict
+    /// mode.
+    mapping: Option<Arc<dyn SessionStore>>,
+    detector: EntityDetector,
+    pseudonym_key: [u8; 32],
+    sealing_key: [u8; 32],
+}
+
+impl AnonymizingMemoryStore {
+    /// Wrap `inner`, keeping the mapping in `mapping`. The secret is read
+    /// from `config.key_env`; fails when it is unset or a pattern is
+    /// invalid.
+    pub fn wrap(
+        inner: Arc<dyn MemoryStore>,
+        mapping: Arc<dyn SessionStore>,
+        config: &MemoryAnonymizationConfig,
+    ) -> Result<Arc<dyn MemoryStore>> {
+        let secret = std::env::var(&config.key_env)
+            .ok()
+            .filter(|s| !s.trim().is_empty())
+            .ok_or_else(|| {
+                anyhow!(
+                    "memory anonymization needs a secret key in ${}",
+                    config.key_env
+                )
+            })?;
+        Ok(Arc::new(Self::new(inner, mapping, config, &secret)?))
+    }
+
+    pub fn new(
+        inner: Arc<dyn MemoryStore>,
+        mapping: Arc<dyn SessionStore>,
+        config: &MemoryAnonymizationConfig,
+        secret: &str,
+    ) -> Result<Self> {
+        Ok(Self {
+            inner,
+            mapping: (!config.strict).then_some(mapping),
+            detector: EntityDetector::new(config)?,
+            pseudonym_key: derive_key(secret, "pseudonym"),
+            sealing_key: derive_key(secret, "mapping"),
+        })
+    }
+
+    /// `text` with its identifiers replaced, and the new pseudonyms with
+    /// their values.
+    fn pseudonymize(&self, owner: &str, text: &str) -> (String, Vec<(String, String)>) {
+        let (text, replaced) = self.detector.pseudonymize(text, |entity| {
+            let mut mac = <HmacSha256 as Mac>::new_from_slice(&self.pseudonym_key)
+                .expect("HMAC takes keys of any length");
+            for part in [owner, &entity.label, &entity.normalized()] {
+                mac.update(part.as_bytes());
+                mac.update(&[0]);
+            }
+            let digest = mac.finalize().into_bytes();
+            let id: String = digest[..6].iter().map(|b| format!("{:02x}", b)).collect();
+            pseudonym(&entity.label, &id)
+        });
+        let values = replaced
+            .into_iter()
+            .map(|(token, entity)| (token, entity.text))
+            .collect();
+        (text, values)
+    }
+
+    fn cipher(&self) -> Aes256Gcm {
+        Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&self.sealing_key))
+    }
+
+    fn seal(&self, owner: &str, token: &str, value: &str) -> Result<String> {
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let aad = format!("{}\0{}", owner, token);
+        let sealed = self
+            .cipher()
+            .encrypt(
+                &nonce,
+                Payload {
+                    msg: value.as_bytes(),
+                    aad: aad.as_bytes(),
+                },
+            )
+            .map_err(|_| anyhow!("failed to seal pseudonym value"))?;
+        let mut bytes = nonce.to_vec();
+        bytes.extend(sealed);
+        Ok(BASE64.encode(bytes))
+    }
+
+    fn open(&self, owner: &str, token: &str, sealed: &str) -> Result<String> {
+        let bytes = BASE64.decode(sealed).context("invalid sealed value")?;
+        if bytes.len() <= NONCE_LEN {
+            bail!("sealed value is too short");
+        }
+        let (nonce, ciphertext) = bytes.split_at(NONCE_LEN);
+        let aad = format!("{}\0{}", owner, token);
+        let value = self
+            .cipher()
+            .decrypt(
+                Nonce::from_slice(nonce),
+                Payload {
+                    msg: ciphertext,
+                    aad: aad.as_bytes(),
+                },
+            )
+            .map_err(|_| anyhow!("pseudonym value does not open with the configured key"))?;
+        Ok(String::from_utf8(value)?)
+    }
+
+    /// `texts` with the owner's pseudonyms restored. Unchanged in strict
+    /// mode, and for pseudonyms whose value does not open.
+    async fn reidentify(&self, owner: &str, texts: Vec<String>) -> Result<Vec<String>> {
+        let Some(mapping) = &self.mapping else {
+            return Ok(texts);
+        };
+        if texts.iter().all(|text| pseudonyms(text).is_empty()) {
+            return Ok(texts);
+        }
+        let sealed = mapping.get_all_values(&namespace(owner)).await?;
+        let mut values: HashMap<String, Option<String>> = HashMap::new();
+        Ok(texts
+            .into_iter()
+            .map(|text| {
+                reidentify(&text, |token| {
+                    values
+                        .entry(token.to_string())
+                        .or_insert_with(|| {
+                            let value = sealed.get(token)?.as_str()?;
+                            self.open(owner, token, value)
+                                .inspect_err(|e| {
+                                    tracing::warn!(
+                                        target: "memory.anonymization",
+                                        "cannot restore {}: {}",
+                                        token,
+                                        e
+                                    )
+                                })
+                                .ok()
+                        })
+                        .clone()
+                })
+            })
+            .collect())
+    }
+}
+
+#[async_trait]
+impl MemoryStore for AnonymizingMemoryStore {
+    async fn store_memory(&self, user_id: &str, memory: SessionMemory) -> Result<()> {
+        let mut values = Vec::new();
+        let mut anonymize = |text: &str| {
+            let (text, found) = self.pseudonymize(user_id, text);
+            values.extend(found);
+            text
+        };
+        let memory = SessionMemory {
+            session_summary: anonymize(&memory.session_summary),
+            key_insights: memory.key_insights.iter().map(|s| anonymize(s)).collect(),
+            important_facts: memory
+                .important_facts
+                .iter()
+                .map(|s| anonymize(s))
+                .collect(),
+            ..memory
+        };
+        tracing::debug!(
+            target: "memory.anonymization",
+            identifiers = values.len(),
+            strict = self.mapping.is_none(),
+            "pseudonymized memory"
+        );
+        if let Some(mapping) = &self.mapping {
+            let namespace = namespace(user_id);
+            for (token, value) in values {
+                let sealed = self.seal(user_id, &token, &value)?;
+                mapping
+                    .set_value(&namespace, &token, &serde_json::Value::String(sealed))
+                    .await?;
+            }
+        }
+        self.inner.store_memory(user_id, memory).await
+    }
+
+    async fn search_memories(
+        &self,
+        user_id: &str,
+        query: &str,
+        limit: Option<usize>,
+    ) -> Result<Vec<String>> {
+        let (query, _) = self.pseudonymize(user_id, query);
+        let results = self.inner.search_memories(user_id, &query, limit).await?;
+        self.reidentify(user_id, results).await
+    }
+
+    async fn get_user_memories(&self, user_id: &str) -> Result<Vec<String>> {
+        let memories = self.inner.get_user_memories(user_id).await?;
+        self.reidentify(user_id, memories).await
+    }
+
+    async fn clear_user_memories(&self, user_id: &str) -> Result<()> {
+        self.inner.clear_user_memories(user_id).await?;
+        if let Some(mapping) = &self.mapping {
+            mapping.clear_session(&namespace(user_id)).await?;
+        }
+        Ok(())
+    }
+}
+
+/// Session store namespace of `owner`'s pseudonyms.
+fn namespace(owner: &str) -> String {
+    format!("memory_pseudonyms:{}", owner)
+}
+
+fn derive_key(secret: &str, purpose: &str) -> [u8; 32] {
+    let mut mac = <HmacSha256 as Mac>::new_from_slice(secret.as_bytes())
+        .expect("HMAC takes keys of any length");
+    mac.update(purpose.as_bytes());
+    mac.finalize().into_bytes().into()
+}
+
+#[cfg(test)]
+#[cfg(feature = "sqlite")]
+mod tests {
+    use super::*;
+    use crate::diesel_store::DieselStoreBuilder;
+
+    struct Stores {
+        memory: Arc<dyn MemoryStore>,
+        session: Arc<dyn SessionStore>,
+    }
+
+    async fn stores() -> Stores {
+        let db_url = format!("file:{}?mode=memory&cache=shared", uuid::Uuid::new_v4());
+        let store = DieselStoreBuilder::sqlite(&db_url, 1).await.unwrap();
+        Stores {
+            memory: Arc::new(store.memory_store()),
+            session: Arc::new(store.session_store()),
+        }
+    }
+
+    fn anonymizing(stores: &Stores, strict: bool) -> AnonymizingMemoryStore {
+        AnonymizingMemoryStore::new(
+            stores.memory.clone(),
+            stores.session.clone(),
+            &MemoryAnonymizationConfig {
+                strict,
+                ..Default::default()
+            },
+            "test-secret",
+        )
+        .unwrap()
+    }
+
+    fn memory(summary: &str) -> SessionMemory {
+        SessionMemory {
+            agent_id: "assistant".to_string(),
+            thread_id: "t1".to_string(),
+            session_summary: summary.to_string(),
+            key_insights: vec!["Prefers email over calls".to_string()],
+            important_facts: vec!["Phone: +1 415 555 0132".to_string()],
+            timestamp: chrono::Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn identifiers_are_stored_as_pseudonyms_and_read_back() {
+        let stores = stores().await;
+        let store = anonymizing(&stores, false);
+
+        store
+            .store_memory("u1", memory("Ana (ana@example.com) booked a demo"))
+            .await
+            .unwrap();
+
+        let raw = stores.memory.get_user_memories("u1").await.unwrap();
+        assert!(!raw[0].contains("ana@example.com"), "{}", raw[0]);
+        assert!(!raw[0].contains("555 0132"), "{}", raw[0]);
+        assert!(raw[0].contains("[EMAIL_"), "{}", raw[0]);
+        let sealed = stores
+            .session
+            .get_all_values("memory_pseudonyms:u1")
+            .await
+            .unwrap();
+        assert_eq!(sealed.len(), 2);
+        assert!(
+            sealed
+                .values()
+                .all(|v| !v.as_str().unwrap().contains("example.com"))
+        );
+
+        let read = store.get_user_memories("u1").await.unwrap();
+        assert!(read[0].contains("Ana (ana@example.com) booked a demo"));
+        assert!(read[0].contains("Phone: +1 415 555 0132"));
+
+        // A search for the raw identifier finds the memory.
+        let hits = store
+            .search_memories("u1", "ANA@example.com", None)
+            .await
+            .unwrap();
+        assert_eq!(hits, read);
+    }
+
+    #[tokio::test]
+    async fn strict_mode_keeps_no_mapping() {
+        let stores = stores().await;
+        let store = anonymizing(&stores, true);
+
+        store
+            .store_memory("u1", memory("Reach ana@example.com"))
+            .await
+            .unwrap();
+
+        let read = store.get_user_memories("u1").await.unwrap();
+        assert!(!read[0].contains("ana@example.com"), "{}", read[0]);
+        assert!(
+            stores
+                .session
+                .get_all_values("memory_pseudonyms:u1")
+                .await
+                .unwrap()
+                .is_empty()
+        );
+        let hits = store
+            .search_memories("u1", "ana@example.com", None)
+            .await
+            .unwrap();
+        assert_eq!(
+            hits.len(),
+            1,
+            "pseudonyms are stable, so search still works"
+        );
+    }
+
+    #[tokio::test]
+    async fn pseudonyms_differ_per_user_and_clear_with_their_memories() {
+        let stores = stores().await;
+        let store = anonymizing(&stores, false);
+        for user in ["u1", "u2"] {
+            store
+                .store_memory(user, memory("Reach ana@example.com"))
+                .await
+                .unwrap();
+        }
+
+        let first = stores.memory.get_user_memories("u1").await.unwrap();
+        let second = stores.memory.get_user_memories("u2").await.unwrap();
+        assert_ne!(pseudonyms(&first[0]), pseudonyms(&second[0]));
+
+        store.clear_user_memories("u1").await.unwrap();
+        assert!(
+            stores
+                .session
+                .get_all_values("memory_pseudonyms:u1")
+                .await
+                .unwrap()
+                .is_empty()
+        );
+        assert!(
+            store.get_user_memories("u2").await.unwrap()[0].contains("ana@example.com"),
+            "the other user's mapping is kept"
+        );
+    }
+}
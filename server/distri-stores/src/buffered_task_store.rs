@@ -0,0 +1,487 @@
+//! Batched writes of task events.
+//!
+//! A streaming run emits events in bursts, and an INSERT per event thrashes
+//! SQLite. [`BufferedTaskStore`] queues the events and stores them with
+//! [`TaskStore::add_task_events`], one transaction per batch: when
+//! `max_batch` events are queued, every `flush_interval_ms`, and at once for
+//! the events ending a run. Reads of tasks and their messages, new messages
+//! and status changes store the queue first, so callers never see a task
+//! without the events emitted before. A batch that fails to store goes back
+//! to the front of the queue, and [`TaskStore::shutdown`] stores what is left
+//! before the process exits.
+
+use std::sync::{Arc, Mutex, Weak};
+use std::time::Duration;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use distri_types::configuration::EventWriteConfig;
+use distri_types::stores::{CreateTaskInput, MessageFilter, TaskActivity, TaskStore};
+use distri_types::thread_archive::{ArchivedTask, ArchivedTaskMessage};
+use distri_types::{AgentEvent, AgentEventType, Message, Task, TaskMessage, TaskStatus};
+
+/// A [`TaskStore`] that stores task events in batches.
+pub struct BufferedTaskStore {
+    queue: Arc<EventQueue>,
+}
+
+struct EventQueue {
+    inner: Arc<dyn TaskStore>,
+    max_batch: usize,
+    pending: Mutex<Vec<(String, AgentEvent)>>,
+    /// Held while a batch is written, so batches are stored in order.
+    writing: tokio::sync::Mutex<()>,
+}
+
+impl EventQueue {
+    async fn flush(&self) -> Result<()> {
+        let _writing = self.writing.lock().await;
+        let batch = std::mem::take(&mut *self.pending.lock().unwrap());
+        if batch.is_empty() {
+            return Ok(());
+        }
+        if let Err(e) = self.inner.add_task_events(batch.clone()).await {
+            // Queued again ahead of the events added meanwhile, so the next
+            // flush stores them in order.
+            let mut pending = self.pending.lock().unwrap();
+            let newer = std::mem::replace(&mut *pending, batch);
+            pending.extend(newer);
+            return Err(e);
+        }
+        Ok(())
+    }
+}
+
+impl BufferedTaskStore {
+    /// Wrap `inner`. Without batching (`max_batch` of 1 or less, or no Tokio
+    /// runtime to flush from) `inner` is returned as is.
+    pub fn wrap(inner: Arc<dyn TaskStore>, config: &EventWriteConfig) -> Arc<dyn TaskStore> {
+        if config.max_batch <= 1 {
+            return inner;
+        }
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            return inner;
+        };
+        let queue = Arc::new(EventQueue {
+            inner,
+            max_batch: config.max_batch,
+            pending: Mutex::new(Vec::new()),
+            writing: tokio::sync::Mutex::new(()),
+        });
+        runtime.spawn(flush_periodically(
+            Arc::downgrade(&queue),
+            Duration::from_millis(config.flush_interval_ms.max(1)),
+        ));
+        Arc::new(Self { queue })
+    }
+
+    /// Store every queued event.
+    async fn flush(&self) -> Result<()> {
+        self.queue.flush().await
+    }
+
+    fn inner(&self) -> &dyn TaskStore {
+        self.queue.inner.as_ref()
+    }
+}
+
+/// Flush `queue` every `interval` until the store is dropped.
+async fn flush_periodically(queue: Weak<EventQueue>, interval: Duration) {
+    let mut ticker = tokio::time::interval_at(tokio::time::Instant::now() + interval, interval);
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    loop {
+        ticker.tick().await;
+        let Some(queue) = queue.upgrade() else {
+            break;
+        };
+        if let Err(e) = queue.flush().await {
+            tracing::error!("Failed to save task events: {}", e);
+        }
+    }
+}
+
+impl Drop for BufferedTaskStore {
+    fn drop(&mut self) {
+        let queue = self.queue.clone();
+        if let Ok(runtime) = tokio::runtime::Handle::try_current() {
+            runtime.spawn(async move {
+                if let Err(e) = queue.flush().await {
+                    tracing::error!("Failed to save task events: {}", e);
+                }
+            });
+        }
+    }
+}
+
+/// Whether `event` ends a run, so it and the events before it are stored at
+/// once.
+fn ends_run(event: &AgentEvent) -> bool {
+    matches!(
+        event.event,
+        AgentEventType::RunFinished { .. } | AgentEventType::RunError { .. }
+    )
+}
+
+#[async_trait]
+impl TaskStore for BufferedTaskStore {
+    fn init_task(&self, input: &CreateTaskInput) -> Task {
+        self.inner().init_task(input)
+    }
+
+    async fn create_task(&self, input: CreateTaskInput) -> Result<Task> {
+        self.inner().create_task(input).await
+    }
+
+    async fn get_task(&self, task_id: &str) -> Result<Option<Task>> {
+        self.inner().get_task(task_id).await
+    }
+
+    async fn update_task_status(&self, task_id: &str, status: TaskStatus) -> Result<()> {
+        self.flush().await?;
+        self.inner().update_task_status(task_id, status).await
+    }
+
+    async fn add_event_to_task(&self, task_id: &str, event: AgentEvent) -> Result<()> {
+        let flush_now = ends_run(&event);
+        let queued = {
+            let mut pending = self.queue.pending.lock().unwrap();
+            pending.push((task_id.to_string(), event));
+            pending.len()
+        };
+        if flush_now || queued >= self.queue.max_batch {
+            self.flush().await?;
+        }
+        Ok(())
+    }
+
+    async fn add_task_events(&self, events: Vec<(String, AgentEvent)>) -> Result<()> {
+        self.flush().await?;
+        self.inner().add_task_events(events).await
+    }
+
+    async fn add_message_to_task(&self, task_id: &str, message: &Message) -> Result<()> {
+        self.flush().await?;
+        self.inner().add_message_to_task(task_id, message).await
+    }
+
+    async fn cancel_task(&self, task_id: &str) -> Result<Task> {
+        self.flush().await?;
+        self.inner().cancel_task(task_id).await
+    }
+
+    async fn cancel_task_cascade(&self, root_task_id: &str) -> Result<Vec<Task>> {
+        self.flush().await?;
+        self.inner().cancel_task_cascade(root_task_id).await
+    }
+
+    async fn list_descendant_tasks(&self, root_task_id: &str) -> Result<Vec<Task>> {
+        self.inner().list_descendant_tasks(root_task_id).await
+    }
+
+    async fn list_running_tasks(&self, thread_id: Option<&str>) -> Result<Vec<Task>> {
+        self.inner().list_running_tasks(thread_id).await
+    }
+
+    async fn list_tasks(&self, thread_id: Option<&str>) -> Result<Vec<Task>> {
+        self.inner().list_tasks(thread_id).await
+    }
+
+    async fn get_history(
+        &self,
+        thread_id: &str,
+        filter: Option<MessageFilter>,
+    ) -> Result<Vec<(Task, Vec<TaskMessage>)>> {
+        self.flush().await?;
+        self.inner().get_history(thread_id, filter).await
+    }
+
+    async fn archive_tasks_from(&self, thread_id: &str, task_id: &str) -> Result<Vec<String>> {
+        self.flush().await?;
+        self.inner().archive_tasks_from(thread_id, task_id).await
+    }
+
+    async fn export_thread_tasks(
+        &self,
+        thread_id: &str,
+    ) -> Result<(Vec<ArchivedTask>, Vec<ArchivedTaskMessage>)> {
+        self.flush().await?;
+        self.inner().export_thread_tasks(thread_id).await
+    }
+
+    async fn import_thread_tasks(
+        &self,
+        tasks: &[ArchivedTask],
+        messages: &[ArchivedTaskMessage],
+    ) -> Result<()> {
+        self.inner().import_thread_tasks(tasks, messages).await
+    }
+
+    async fn delete_thread_tasks(&self, thread_id: &str, task_ids: &[String]) -> Result<usize> {
+        self.flush().await?;
+        self.inner().delete_thread_tasks(thread_id, task_ids).await
+    }
+
+    async fn update_parent_task(&self, task_id: &str, parent_task_id: Option<&str>) -> Result<()> {
+        self.inner()
+            .update_parent_task(task_id, parent_task_id)
+            .await
+    }
+
+    async fn latest_task_activity(&self, task_id: &str) -> Result<Option<(String, i64)>> {
+        self.flush().await?;
+        self.inner().latest_task_activity(task_id).await
+    }
+
+    async fn task_activity(&self, task_id: &str) -> Result<Option<TaskActivity>> {
+        self.flush().await?;
+        self.inner().task_activity(task_id).await
+    }
+
+    async fn shutdown(&self) -> Result<()> {
+        self.flush().await?;
+        self.inner().shutdown().await
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "sqlite")]
+mod tests {
+    use super::*;
+    use crate::diesel_store::DieselStoreBuilder;
+    use distri_types::CreateThreadRequest;
+    use distri_types::stores::ThreadStore;
+
+    /// A SQLite task store with a thread `t` and its task `task-1`.
+    async fn task_store() -> Arc<dyn TaskStore> {
+        let db_url = format!("file:{}?mode=memory&cache=shared", uuid::Uuid::new_v4());
+        let store = DieselStoreBuilder::sqlite(&db_url, 1).await.unwrap();
+        store
+            .thread_store()
+            .create_thread(CreateThreadRequest {
+                agent_id: "agent".to_string(),
+                title: None,
+                thread_id: Some("t".to_string()),
+                attributes: None,
+                user_id: None,
+                external_id: None,
+                channel_id: None,
+            })
+            .await
+            .unwrap();
+        let task_store = Arc::new(store.task_store()) as Arc<dyn TaskStore>;
+        task_store
+            .create_task(CreateTaskInput::local("t").with_id("task-1"))
+            .await
+            .unwrap();
+        task_store
+    }
+
+    async fn stored_events(store: &dyn TaskStore) -> usize {
+        store
+            .get_history("t", None)
+            .await
+            .unwrap()
+            .iter()
+            .flat_map(|(_, messages)| messages)
+            .filter(|m| matches!(m, TaskMessage::Event(_)))
+            .count()
+    }
+
+    fn step(id: &str) -> AgentEvent {
+        AgentEvent::new(AgentEventType::StepStarted {
+            step_id: id.to_string(),
+            step_index: 0,
+        })
+    }
+
+    fn config(max_batch: usize, flush_interval_ms: u64) -> EventWriteConfig {
+        EventWriteConfig {
+            max_batch,
+            flush_interval_ms,
+        }
+    }
+
+    #[tokio::test]
+    async fn events_are_written_in_batches() {
+        let inner = task_store().await;
+        let buffered = BufferedTaskStore::wrap(inner.clone(), &config(3, 60_000));
+
+        for id in ["a", "b"] {
+            buffered
+                .add_event_to_task("task-1", step(id))
+                .await
+                .unwrap();
+        }
+        assert_eq!(stored_events(inner.as_ref()).await, 0);
+
+        buffered
+            .add_event_to_task("task-1", step("c"))
+            .await
+            .unwrap();
+        assert_eq!(stored_events(inner.as_ref()).await, 3);
+    }
+
+    #[tokio::test]
+    async fn reads_and_the_end_of_a_run_store_queued_events() {
+        let inner = task_store().await;
+        let buffered = BufferedTaskStore::wrap(inner.clone(), &config(100, 60_000));
+
+        buffered
+            .add_event_to_task("task-1", step("a"))
+            .await
+            .unwrap();
+        assert_eq!(stored_events(buffered.as_ref()).await, 1);
+
+        buffered
+            .add_event_to_task("task-1", step("b"))
+            .await
+            .unwrap();
+        let finished = AgentEvent::new(AgentEventType::RunFinished {
+            success: true,
+            total_steps: 1,
+            failed_steps: 0,
+            usage: None,
+            context_budget: None,
+        });
+        buffered
+            .add_event_to_task("task-1", finished)
+            .await
+            .unwrap();
+        assert_eq!(stored_events(inner.as_ref()).await, 3);
+    }
+
+    /// `inner`, failing to store batches while `failing` is set.
+    struct Flaky {
+        inner: Arc<dyn TaskStore>,
+        failing: std::sync::atomic::AtomicBool,
+    }
+
+    #[async_trait]
+    impl TaskStore for Flaky {
+        async fn create_task(&self, input: CreateTaskInput) -> Result<Task> {
+            self.inner.create_task(input).await
+        }
+        async fn get_task(&self, task_id: &str) -> Result<Option<Task>> {
+            self.inner.get_task(task_id).await
+        }
+        async fn update_task_status(&self, task_id: &str, status: TaskStatus) -> Result<()> {
+            self.inner.update_task_status(task_id, status).await
+        }
+        async fn add_event_to_task(&self, task_id: &str, event: AgentEvent) -> Result<()> {
+            self.inner.add_event_to_task(task_id, event).await
+        }
+        async fn add_task_events(&self, events: Vec<(String, AgentEvent)>) -> Result<()> {
+            if self.failing.load(std::sync::atomic::Ordering::SeqCst) {
+                anyhow::bail!("database is locked");
+            }
+            self.inner.add_task_events(events).await
+        }
+        async fn add_message_to_task(&self, task_id: &str, message: &Message) -> Result<()> {
+            self.inner.add_message_to_task(task_id, message).await
+        }
+        async fn cancel_task(&self, task_id: &str) -> Result<Task> {
+            self.inner.cancel_task(task_id).await
+        }
+        async fn cancel_task_cascade(&self, root_task_id: &str) -> Result<Vec<Task>> {
+            self.inner.cancel_task_cascade(root_task_id).await
+        }
+        async fn list_descendant_tasks(&self, root_task_id: &str) -> Result<Vec<Task>> {
+            self.inner.list_descendant_tasks(root_task_id).await
+        }
+        async fn list_running_tasks(&self, thread_id: Option<&str>) -> Result<Vec<Task>> {
+            self.inner.list_running_tasks(thread_id).await
+        }
+        async fn list_tasks(&self, thread_id: Option<&str>) -> Result<Vec<Task>> {
+            self.inner.list_tasks(thread_id).await
+        }
+        async fn get_history(
+            &self,
+            thread_id: &str,
+            filter: Option<MessageFilter>,
+        ) -> Result<Vec<(Task, Vec<TaskMessage>)>> {
+            self.inner.get_history(thread_id, filter).await
+        }
+        async fn update_parent_task(
+            &self,
+            task_id: &str,
+            parent_task_id: Option<&str>,
+        ) -> Result<()> {
+            self.inner.update_parent_task(task_id, parent_task_id).await
+        }
+    }
+
+    #[tokio::test]
+    async fn a_failed_batch_is_stored_by_a_later_flush_in_order() {
+        let inner = task_store().await;
+        let flaky = Arc::new(Flaky {
+            inner: inner.clone(),
+            failing: true.into(),
+        });
+        let buffered = BufferedTaskStore::wrap(flaky.clone(), &config(2, 60_000));
+
+        buffered
+            .add_event_to_task("task-1", step("a"))
+            .await
+            .unwrap();
+        buffered
+            .add_event_to_task("task-1", step("b"))
+            .await
+            .unwrap_err();
+        buffered
+            .add_event_to_task("task-1", step("c"))
+            .await
+            .unwrap_err();
+
+        flaky
+            .failing
+            .store(false, std::sync::atomic::Ordering::SeqCst);
+        buffered.shutdown().await.unwrap();
+        let steps: Vec<String> = inner
+            .get_history("t", None)
+            .await
+            .unwrap()
+            .into_iter()
+            .flat_map(|(_, messages)| messages)
+            .filter_map(|m| match m {
+                TaskMessage::Event(event) => match event.event {
+                    AgentEventType::StepStarted { step_id, .. } => Some(step_id),
+                    _ => None,
+                },
+                _ => None,
+            })
+            .collect();
+        assert_eq!(steps, ["a", "b", "c"]);
+    }
+
+    #[tokio::test]
+    async fn shutdown_stores_the_queued_events() {
+        let inner = task_store().await;
+        let buffered = BufferedTaskStore::wrap(inner.clone(), &config(100, 60_000));
+
+        buffered
+            .add_event_to_task("task-1", step("a"))
+            .await
+            .unwrap();
+        assert_eq!(stored_events(inner.as_ref()).await, 0);
+        buffered.shutdown().await.unwrap();
+        assert_eq!(stored_events(inner.as_ref()).await, 1);
+    }
+
+    #[test]
+    fn events_are_not_batched_by_default() {
+        assert_eq!(EventWriteConfig::default().max_batch, 1);
+    }
+
+    #[tokio::test]
+    async fn queued_events_are_stored_after_the_interval() {
+        let inner = task_store().await;
+        let buffered = BufferedTaskStore::wrap(inner.clone(), &config(100, 20));
+
+        buffered
+            .add_event_to_task("task-1", step("a"))
+            .await
+            .unwrap();
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert_eq!(stored_events(inner.as_ref()).await, 1);
+    }
+}
@@ -0,0 +1,122 @@
+#[cfg(test)]
+#[cfg(feature = "sqlite")]
+mod tests {
+    use crate::diesel_store::DieselStoreBuilder;
+    use chrono::{NaiveDate, TimeZone, Utc};
+    use distri_types::api::analytics::{DateRange, RunAnalytics, ToolAnalytics};
+    use distri_types::stores::{AnalyticsStore, CreateTaskInput, TaskStore, ThreadStore};
+    use distri_types::{AgentEvent, AgentEventType, CreateThreadRequest};
+
+    async fn test_store() -> DieselStoreBuilder<crate::diesel_store::SqliteConnectionWrapper> {
+        let db_name = uuid::Uuid::new_v4();
+        let db_url = format!("file:{}?mode=memory&cache=shared", db_name);
+        let store = DieselStoreBuilder::sqlite(&db_url, 1)
+            .await
+            .expect("Failed to create test store");
+        store
+            .thread_store()
+            .create_thread(CreateThreadRequest {
+                agent_id: "researcher".to_string(),
+                title: None,
+                thread_id: Some("t".to_string()),
+                attributes: None,
+                user_id: None,
+                external_id: None,
+                channel_id: None,
+            })
+            .await
+            .unwrap();
+        store
+            .task_store()
+            .create_task(CreateTaskInput::local("t").with_id("task-1"))
+            .await
+            .unwrap();
+        store
+    }
+
+    fn event(day: u32, event: AgentEventType) -> AgentEvent {
+        let mut event = AgentEvent::new(event);
+        event.timestamp = Utc.with_ymd_and_hms(2026, 10, day, 12, 0, 0).unwrap();
+        event.task_id = "task-1".to_string();
+        event.agent_id = "researcher".to_string();
+        event
+    }
+
+    fn finished(day: u32, success: bool, total_steps: usize) -> AgentEvent {
+        event(
+            day,
+            AgentEventType::RunFinished {
+                success,
+                total_steps,
+                failed_steps: 0,
+                usage: None,
+                context_budget: None,
+            },
+        )
+    }
+
+    fn tool_end(day: u32, name: &str, success: bool) -> AgentEvent {
+        event(
+            day,
+            AgentEventType::ToolExecutionEnd {
+                step_id: "s".to_string(),
+                tool_call_id: uuid::Uuid::new_v4().to_string(),
+                tool_call_name: name.to_string(),
+                success,
+            },
+        )
+    }
+
+    fn range(from: u32, to: u32) -> DateRange {
+        DateRange {
+            from: NaiveDate::from_ymd_opt(2026, 10, from).unwrap(),
+            to: NaiveDate::from_ymd_opt(2026, 10, to).unwrap(),
+        }
+    }
+
+    #[tokio::test]
+    async fn stored_events_update_the_daily_counters() {
+        let store = test_store().await;
+        let task_store = store.task_store();
+
+        task_store
+            .add_event_to_task("task-1", finished(1, true, 3))
+            .await
+            .unwrap();
+        task_store
+            .add_event_to_task("task-1", tool_end(1, "search", true))
+            .await
+            .unwrap();
+        task_store
+            .add_task_events(vec![
+                ("task-1".to_string(), finished(1, false, 5)),
+                ("task-1".to_string(), tool_end(1, "search", false)),
+                ("task-1".to_string(), finished(2, true, 1)),
+                // A sub-agent's event relayed into its parent's log.
+                ("parent".to_string(), finished(2, true, 1)),
+            ])
+            .await
+            .unwrap();
+
+        let runs = task_store.run_counters(&range(1, 2), None).await.unwrap();
+        let analytics = RunAnalytics::from_rows(range(1, 2), &runs);
+        assert_eq!(analytics.totals.counters.runs, 3);
+        assert_eq!(analytics.days[0].stats.counters.runs, 2);
+        assert_eq!(analytics.days[0].stats.counters.failed, 1);
+        assert_eq!(analytics.days[0].stats.avg_steps_per_run, Some(4.0));
+        assert_eq!(analytics.days[1].stats.counters.runs, 1);
+
+        let only_second_day = task_store.run_counters(&range(2, 2), None).await.unwrap();
+        assert_eq!(only_second_day.len(), 1);
+        let other_agent = task_store
+            .run_counters(&range(1, 2), Some("writer"))
+            .await
+            .unwrap();
+        assert!(other_agent.is_empty());
+
+        let tools = task_store.tool_counters(&range(1, 2), None).await.unwrap();
+        let tools = ToolAnalytics::from_rows(range(1, 2), &tools, 10);
+        assert_eq!(tools.tools.len(), 1);
+        assert_eq!((tools.tools[0].calls, tools.tools[0].failures), (2, 1));
+    }
+}
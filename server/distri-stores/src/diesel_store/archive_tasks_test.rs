@@ -0,0 +1,83 @@
+#[cfg(test)]
+#[cfg(feature = "sqlite")]
+mod tests {
+    use std::time::Duration;
+
+    use crate::diesel_store::DieselStoreBuilder;
+    use distri_types::stores::{CreateTaskInput, TaskStore, ThreadStore};
+    use distri_types::{CreateThreadRequest, Message, TaskStatus};
+
+    async fn test_store() -> DieselStoreBuilder<crate::diesel_store::SqliteConnectionWrapper> {
+        let db_name = uuid::Uuid::new_v4();
+        let db_url = format!("file:{}?mode=memory&cache=shared", db_name);
+        DieselStoreBuilder::sqlite(&db_url, 1)
+            .await
+            .expect("Failed to create test store")
+    }
+
+    /// Archiving hides the task and every later one from the history, but
+    /// keeps the rows; new tasks keep their reference ids.
+    #[tokio::test]
+    async fn archived_tasks_leave_the_history_but_are_kept() {
+        let store = test_store().await;
+        let thread_store = store.thread_store();
+        let task_store = store.task_store();
+        let thread = thread_store
+            .create_thread(CreateThreadRequest {
+                agent_id: "test-agent".to_string(),
+                title: Some("Regenerate".to_string()),
+                thread_id: None,
+                attributes: None,
+                user_id: None,
+                external_id: None,
+                channel_id: None,
+            })
+            .await
+            .expect("create thread");
+
+        for id in ["t1", "t2", "t3"] {
+            task_store
+                .create_task(
+                    CreateTaskInput::local(&thread.id)
+                        .with_id(id)
+                        .with_status(TaskStatus::Completed),
+                )
+                .await
+                .expect("create task");
+            task_store
+                .add_message_to_task(id, &Message::user(format!("turn {id}"), None))
+                .await
+                .expect("add message");
+            // Tasks are ordered by their millisecond timestamp.
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+
+        let archived = task_store
+            .archive_tasks_from(&thread.id, "t2")
+            .await
+            .expect("archive");
+        assert_eq!(archived, vec!["t2", "t3"]);
+
+        let regenerated = task_store
+            .create_task(
+                CreateTaskInput::local(&thread.id)
+                    .with_id("t4")
+                    .with_reference_tasks(vec!["t2".to_string()]),
+            )
+            .await
+            .expect("create task");
+        assert_eq!(regenerated.reference_task_ids, vec!["t2"]);
+
+        let history = task_store
+            .get_history(&thread.id, None)
+            .await
+            .expect("history");
+        let ids: Vec<&str> = history.iter().map(|(t, _)| t.id.as_str()).collect();
+        assert_eq!(ids, vec!["t1", "t4"]);
+        assert_eq!(history[1].0.reference_task_ids, vec!["t2"]);
+
+        let t2 = task_store.get_task("t2").await.expect("get").expect("kept");
+        assert!(t2.archived_at.is_some());
+        assert!(task_store.archive_tasks_from("other", "t1").await.is_err());
+    }
+}
@@ -0,0 +1,103 @@
+#[cfg(test)]
+#[cfg(feature = "sqlite")]
+mod tests {
+    use crate::diesel_store::DieselStoreBuilder;
+    use chrono::{Duration, Utc};
+    use distri_types::jobs::{BackgroundJobStatus, NewBackgroundJob};
+    use distri_types::stores::BackgroundJobStore;
+
+    async fn test_store() -> DieselStoreBuilder<crate::diesel_store::SqliteConnectionWrapper> {
+        let db_name = uuid::Uuid::new_v4();
+        let db_url = format!("file:{}?mode=memory&cache=shared", db_name);
+        DieselStoreBuilder::sqlite(&db_url, 1)
+            .await
+            .expect("Failed to create test store")
+    }
+
+    fn new_job(key: &str, max_attempts: u32) -> NewBackgroundJob {
+        NewBackgroundJob {
+            idempotency_key: key.to_string(),
+            agent_id: "agent".to_string(),
+            user_id: "user".to_string(),
+            workspace_id: None,
+            task_id: format!("task-{key}"),
+            thread_id: format!("thread-{key}"),
+            params: serde_json::json!({"message": {"parts": []}}),
+            max_attempts,
+        }
+    }
+
+    #[tokio::test]
+    async fn enqueue_is_idempotent_and_claims_are_exclusive() {
+        let store = test_store().await.background_job_store();
+
+        let first = store.enqueue(new_job("k1", 3)).await.unwrap();
+        let again = store.enqueue(new_job("k1", 3)).await.unwrap();
+        assert_eq!(first.id, again.id);
+        assert_eq!(first.status, BackgroundJobStatus::Queued);
+
+        let lease = Utc::now() + Duration::seconds(30);
+        let claimed = store.claim("w1", lease).await.unwrap().expect("job");
+        assert_eq!(claimed.id, first.id);
+        assert_eq!(claimed.status, BackgroundJobStatus::Running);
+        assert_eq!(claimed.attempts, 1);
+        assert!(store.claim("w2", lease).await.unwrap().is_none());
+
+        assert!(store.renew(&first.id, "w1", lease).await.unwrap());
+        assert!(!store.renew(&first.id, "w2", lease).await.unwrap());
+
+        store
+            .finish(&first.id, "w1", BackgroundJobStatus::Completed, None)
+            .await
+            .unwrap();
+        let done = store.get_by_task_id("task-k1").await.unwrap().unwrap();
+        assert_eq!(done.status, BackgroundJobStatus::Completed);
+        assert!(done.lease_expires_at.is_none());
+    }
+
+    #[tokio::test]
+    async fn expired_leases_are_requeued_until_attempts_run_out() {
+        let store = test_store().await.background_job_store();
+        let job = store.enqueue(new_job("k2", 2)).await.unwrap();
+        let expired = Utc::now() - Duration::seconds(1);
+
+        store
+            .claim("w1", expired)
+            .await
+            .unwrap()
+            .expect("first claim");
+        assert_eq!(store.requeue_expired(Utc::now()).await.unwrap(), 1);
+        let requeued = store.get(&job.id).await.unwrap().unwrap();
+        assert_eq!(requeued.status, BackgroundJobStatus::Queued);
+        assert!(requeued.worker_id.is_none());
+
+        let second = store.claim("w2", expired).await.unwrap().unwrap();
+        assert_eq!(second.attempts, 2);
+        assert_eq!(store.requeue_expired(Utc::now()).await.unwrap(), 1);
+        let failed = store.get(&job.id).await.unwrap().unwrap();
+        assert_eq!(failed.status, BackgroundJobStatus::Failed);
+        assert!(failed.last_error.is_some());
+    }
+
+    #[tokio::test]
+    async fn only_unclaimed_jobs_can_be_canceled() {
+        let store = test_store().await.background_job_store();
+        let queued = store.enqueue(new_job("k3", 3)).await.unwrap();
+        assert!(store.cancel_queued(&queued.id).await.unwrap());
+        assert!(
+            store
+                .claim("w1", Utc::now() + Duration::seconds(30))
+                .await
+                .unwrap()
+                .is_none()
+        );
+
+        let running = store.enqueue(new_job("k4", 3)).await.unwrap();
+        store
+            .claim("w1", Utc::now() + Duration::seconds(30))
+            .await
+            .unwrap()
+            .expect("claim");
+        assert!(!store.cancel_queued(&running.id).await.unwrap());
+    }
+}
@@ -0,0 +1,98 @@
+#[cfg(test)]
+#[cfg(feature = "sqlite")]
+mod tests {
+    use crate::diesel_store::DieselStoreBuilder;
+    use chrono::{DateTime, Duration, Utc};
+    use distri_types::api::audit::{LlmAuditConfig, LlmAuditQuery, LlmAuditRecord};
+    use distri_types::stores::LlmAuditStore;
+    use serde_json::json;
+    use uuid::Uuid;
+
+    fn record(agent: &str, at: DateTime<Utc>) -> LlmAuditRecord {
+        LlmAuditRecord {
+            id: Uuid::new_v4(),
+            created_at: at,
+            agent_id: agent.into(),
+            user_id: "u".into(),
+            thread_id: "t".into(),
+            task_id: "k".into(),
+            workspace_id: None,
+            provider: "openai".into(),
+            model: "gpt-4.1-mini".into(),
+            streaming: true,
+            request: json!([{ "role": "user", "content": "hi" }]),
+            response: json!({ "content": "hello" }),
+            error: None,
+            input_tokens: 3,
+            output_tokens: 2,
+            duration_ms: 40,
+            redacted: true,
+        }
+    }
+
+    #[tokio::test]
+    async fn records_outlive_the_store_and_respect_retention() {
+        let db_url = format!("file:{}?mode=memory&cache=shared", Uuid::new_v4());
+        let builder = DieselStoreBuilder::sqlite(&db_url, 1).await.unwrap();
+        let config = LlmAuditConfig {
+            enabled: true,
+            retention_days: Some(7),
+            ..Default::default()
+        };
+        let now = Utc::now();
+
+        let store = builder.llm_audit_store(config.clone());
+        let kept = record("a", now - Duration::days(1));
+        store.record(record("b", now)).await.unwrap();
+        store.record(kept.clone()).await.unwrap();
+        drop(store);
+
+        // A new store on the same database sees the records, newest first.
+        let store = builder.llm_audit_store(config);
+        let listed = store.list(&LlmAuditQuery::default()).await.unwrap();
+        assert_eq!(
+            listed
+                .iter()
+                .map(|r| r.agent_id.as_str())
+                .collect::<Vec<_>>(),
+            ["b", "a"]
+        );
+        let a = store
+            .list(&LlmAuditQuery {
+                agent_id: Some("a".into()),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(a.len(), 1);
+        assert_eq!(a[0].id, kept.id);
+        assert_eq!(a[0].request, kept.request);
+        assert_eq!(a[0].duration_ms, 40);
+
+        // Appending drops records past the retention window.
+        store
+            .record(record("old", now - Duration::days(30)))
+            .await
+            .unwrap();
+        store.record(record("c", now)).await.unwrap();
+        let listed = store.list(&LlmAuditQuery::default()).await.unwrap();
+        assert_eq!(listed.len(), 3);
+        assert!(listed.iter().all(|r| r.agent_id != "old"));
+
+        let limited = store
+            .list(&LlmAuditQuery {
+                limit: Some(1),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(limited.len(), 1);
+        assert_eq!(
+            store
+                .purge_before(now + Duration::seconds(1))
+                .await
+                .unwrap(),
+            3
+        );
+    }
+}
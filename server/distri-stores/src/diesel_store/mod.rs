@@ -1,17 +1,34 @@
 #![allow(dead_code)]
 
+#[cfg(test)]
+mod analytics_test;
+#[cfg(test)]
+mod archive_tasks_test;
+#[cfg(test)]
+mod background_jobs_test;
 #[cfg(test)]
 mod cancel_task_test;
 #[cfg(test)]
+mod llm_audit_test;
+#[cfg(test)]
+mod plugin_kv_test;
+#[cfg(test)]
 mod provider_store_test;
 #[cfg(test)]
+mod thread_archive_test;
+#[cfg(test)]
+mod thread_search_test;
+#[cfg(test)]
 mod thread_tokens_test;
+#[cfg(test)]
+mod thread_variables_test;
 use std::{collections::HashMap, fmt::Display, sync::Arc};
 
 use crate::models::*;
 use crate::schema::{
-    agent_configs, external_tool_calls, integrations, memory_entries, message_reads, message_votes,
-    scratchpad_entries, session_entries, task_messages, tasks, threads,
+    agent_configs, analytics_daily_runs, analytics_daily_tools, external_tool_calls, integrations,
+    memory_entries, message_reads, message_votes, scratchpad_entries, session_entries,
+    task_messages, tasks, threads,
 };
 use anyhow::{Context, Result, anyhow};
 use async_trait::async_trait;
@@ -32,19 +49,26 @@ use diesel_async::pooled_connection::{AsyncDieselConnectionManager, PoolableConn
 #[cfg(feature = "sqlite")]
 use diesel_async::sync_connection_wrapper::SyncConnectionWrapper;
 use diesel_migrations::{EmbeddedMigrations, embed_migrations};
+use distri_types::api::analytics::{
+    AnalyticsDelta, DailyAgentRuns, DailyAgentTools, DateRange, RunCounters,
+};
+use distri_types::api::audit::{LlmAuditConfig, LlmAuditQuery, LlmAuditRecord};
 use distri_types::auth::{AuthError, AuthSecret, AuthSession, OAuth2State, ToolAuthStore};
 use distri_types::connections::{AuthScope, Connection, ConnectionStatus, NewConnection};
 use distri_types::connections::{ConnectionAuth, ConnectionToken};
+use distri_types::plugin_storage::{PluginStorageConfig, PluginStorageUsage};
 use distri_types::stores::SessionSummary;
 use distri_types::stores::{
-    AgentStatsInfo, AgentStore, AgentUsageInfo, ConnectionStore, ConnectionTokenStore,
-    ExternalToolCallsStore, FilterMessageType, MemoryStore, MessageFilter, MessageReadStatus,
-    MessageVote, MessageVoteSummary, NewPromptTemplate, NewSecret, NewSkill, NoteStore,
-    PromptTemplateRecord, PromptTemplateStore, ProviderStore, ScratchpadStore, SecretRecord,
-    SecretStore, ServerSettings, SessionMemory, SessionStore, SkillRecord, SkillStore, TaskStore,
-    ThreadListFilter, ThreadListResponse, ThreadStore, UpdatePromptTemplate, UpdateSkill,
-    UpsertProviderRequest, UpsertProviderResponse, VoteMessageRequest, VoteType,
+    AgentStatsInfo, AgentStore, AgentUsageInfo, AnalyticsStore, ConnectionStore,
+    ConnectionTokenStore, ExternalToolCallsStore, FilterMessageType, LlmAuditStore, MemoryStore,
+    MessageFilter, MessageReadStatus, MessageVote, MessageVoteSummary, NewPromptTemplate,
+    NewSecret, NewSkill, NoteStore, PluginKvStore, PromptTemplateRecord, PromptTemplateStore,
+    ProviderStore, ScratchpadStore, SecretRecord, SecretStore, ServerSettings, SessionMemory,
+    SessionStore, SkillRecord, SkillStore, TaskStore, ThreadListFilter, ThreadListResponse,
+    ThreadStore, UpdatePromptTemplate, UpdateSkill, UpsertProviderRequest, UpsertProviderResponse,
+    VoteMessageRequest, VoteType,
 };
+use distri_types::thread_archive::{ArchivedTask, ArchivedTaskMessage};
 use distri_types::{
     AgentError, AgentEvent, AgentEventType, CreateThreadRequest, Message, ScratchpadEntry, Task,
     TaskEvent, TaskMessage, TaskStatus, Thread, ThreadSummary, ToolResponse, UpdateThreadRequest,
@@ -159,6 +183,8 @@ fn to_task(model: TaskModel) -> Task {
         status: task_status_from_str(&model.status),
         created_at: model.created_at,
         updated_at: model.updated_at,
+        archived_at: model.archived_at,
+        reference_task_ids: serde_json::from_str(&model.reference_task_ids).unwrap_or_default(),
     }
 }
 
@@ -189,6 +215,8 @@ fn to_thread(model: ThreadModel) -> Thread {
             .last_context_budget
             .as_deref()
             .and_then(|s| serde_json::from_str(s).ok()),
+        variables: serde_json::from_str(&model.variables).unwrap_or_default(),
+        archived_at: model.archived_at.map(from_naive),
     }
 }
 
@@ -205,13 +233,33 @@ fn to_thread_summary(thread: &Thread) -> ThreadSummary {
         external_id: thread.external_id.clone(),
         channel_id: thread.channel_id.clone(),
         channel_name: None,
-        tags: None,
+        tags: Some(thread.tags()).filter(|tags| !tags.is_empty()),
         input_tokens: thread.input_tokens,
         output_tokens: thread.output_tokens,
         total_tokens: thread.total_tokens,
+        archived_at: thread.archived_at,
     }
 }
 
+fn thread_matches_filter(thread: &Thread, filter: &ThreadListFilter) -> bool {
+    let search_ok = match filter.search.as_deref().map(str::trim) {
+        Some(q) if !q.is_empty() => thread.matches_search(q),
+        _ => true,
+    };
+    let tags_ok = filter.tags.as_ref().is_none_or(|wanted| {
+        let tags = thread.tags();
+        wanted
+            .iter()
+            .all(|w| tags.iter().any(|t| t.eq_ignore_ascii_case(w.trim())))
+    });
+    search_ok
+        && tags_ok
+        && filter
+            .attributes
+            .as_ref()
+            .is_none_or(|f| attributes_match(&thread.attributes, f))
+}
+
 fn to_task_message(model: &TaskMessageModel) -> Result<TaskMessage> {
     match model.kind.as_str() {
         "message" => {
@@ -232,8 +280,8 @@ fn serialize_agent_event(event: AgentEvent) -> TaskEvent {
     let is_final = matches!(event.event, AgentEventType::RunFinished { .. })
         || matches!(event.event, AgentEventType::RunError { .. });
     TaskEvent {
+        created_at: event.timestamp.timestamp_millis(),
         event: event.event,
-        created_at: Utc::now().timestamp_millis(),
         is_final,
     }
 }
@@ -793,6 +841,7 @@ where
         }
 
         let metadata_value = metadata_to_value_str(&thread.metadata)?;
+        let variables = serde_json::to_string(&thread.variables)?;
         let created_at = to_naive(thread.created_at);
         let updated_at = to_naive(thread.updated_at);
 
@@ -813,6 +862,7 @@ where
             input_tokens: thread.input_tokens as i64,
             output_tokens: thread.output_tokens as i64,
             total_tokens: thread.total_tokens as i64,
+            variables: &variables,
         };
 
         let mut connection = self
@@ -886,6 +936,13 @@ where
         if let Some(attributes) = request.attributes {
             thread.attributes = attributes;
         }
+        let variables = match request.variables {
+            Some(variables) => {
+                thread.variables = variables;
+                Some(serde_json::to_string(&thread.variables)?)
+            }
+            None => None,
+        };
 
         thread.updated_at = Utc::now();
 
@@ -902,6 +959,7 @@ where
             external_id: None,
             channel_id: None,
             last_context_budget: None,
+            variables: variables.as_deref(),
         };
 
         diesel::update(threads::table.find(thread_id))
@@ -922,6 +980,40 @@ where
         Ok(())
     }
 
+    async fn set_thread_archived(
+        &self,
+        thread_id: &str,
+        archived_at: Option<DateTime<Utc>>,
+    ) -> Result<()> {
+        let mut connection = self.conn().await?;
+        let updated = diesel::update(threads::table.find(thread_id))
+            .set(threads::archived_at.eq(archived_at.map(to_naive)))
+            .execute(&mut connection)
+            .await
+            .context("failed to update thread archive state")?;
+        if updated == 0 {
+            return Err(anyhow!("thread {thread_id} not found"));
+        }
+        Ok(())
+    }
+
+    async fn list_archivable_threads(
+        &self,
+        before: DateTime<Utc>,
+        limit: u32,
+    ) -> Result<Vec<String>> {
+        let mut connection = self.conn().await?;
+        threads::table
+            .filter(threads::archived_at.is_null())
+            .filter(threads::updated_at.lt(to_naive(before)))
+            .order(threads::updated_at.asc())
+            .limit(limit as i64)
+            .select(threads::id)
+            .load::<String>(&mut connection)
+            .await
+            .context("failed to list threads to archive")
+    }
+
     async fn list_threads(
         &self,
         filter: &ThreadListFilter,
@@ -945,6 +1037,33 @@ where
             query = query.filter(threads::external_id.eq(ext_id.as_str()));
         }
 
+        // Search and tags match against JSON metadata, so filter in memory
+        // and paginate the matches.
+        if filter.search.is_some() || filter.tags.is_some() {
+            let rows = query
+                .order(threads::updated_at.desc())
+                .load::<ThreadModel>(&mut connection)
+                .await?;
+            let matches: Vec<ThreadSummary> = rows
+                .into_iter()
+                .map(to_thread)
+                .filter(|t| thread_matches_filter(t, filter))
+                .map(|t| to_thread_summary(&t))
+                .collect();
+            let total = matches.len() as i64;
+            let threads = matches
+                .into_iter()
+                .skip(offset_val as usize)
+                .take(page_size as usize)
+                .collect();
+            return Ok(ThreadListResponse {
+                threads,
+                total,
+                page: (offset_val / page_size) + 1,
+                page_size,
+            });
+        }
+
         // Get total count first
         let total: i64 = {
             let mut count_query = threads::table.into_boxed();
@@ -1231,6 +1350,7 @@ where
             external_id: None,
             channel_id: None,
             last_context_budget: None,
+            variables: None,
         };
 
         diesel::update(threads::table.find(thread_id))
@@ -1402,6 +1522,7 @@ where
             latest_threads: Some(latest_threads),
             recently_used_agents: Some(recently_used_agents),
             custom_metrics: None,
+            llm_latency: None,
         })
     }
 
@@ -1767,6 +1888,143 @@ where
             .await
             .context("failed to acquire diesel connection")
     }
+
+    /// Add `delta` to its day's analytics row, creating the row if needed.
+    async fn record_analytics(
+        conn: &mut DieselConn<'_, Conn>,
+        delta: &AnalyticsDelta,
+    ) -> Result<(), DieselError> {
+        use diesel::upsert::excluded;
+
+        match delta {
+            AnalyticsDelta::Run(row) => {
+                use crate::schema::analytics_daily_runs::dsl as t;
+                let counters = &row.counters;
+                diesel::insert_into(analytics_daily_runs::table)
+                    .values(&AnalyticsDailyRunsModel {
+                        day: row.day.to_string(),
+                        agent_id: row.agent_id.clone(),
+                        runs: counters.runs,
+                        succeeded: counters.succeeded,
+                        failed: counters.failed,
+                        steps: counters.steps,
+                        input_tokens: counters.input_tokens,
+                        output_tokens: counters.output_tokens,
+                        cost_usd: counters.cost_usd,
+                    })
+                    .on_conflict((t::day, t::agent_id))
+                    .do_update()
+                    .set((
+                        t::runs.eq(t::runs + excluded(t::runs)),
+                        t::succeeded.eq(t::succeeded + excluded(t::succeeded)),
+                        t::failed.eq(t::failed + excluded(t::failed)),
+                        t::steps.eq(t::steps + excluded(t::steps)),
+                        t::input_tokens.eq(t::input_tokens + excluded(t::input_tokens)),
+                        t::output_tokens.eq(t::output_tokens + excluded(t::output_tokens)),
+                        t::cost_usd.eq(t::cost_usd + excluded(t::cost_usd)),
+                    ))
+                    .execute(conn)
+                    .await?;
+            }
+            AnalyticsDelta::Tool(row) => {
+                use crate::schema::analytics_daily_tools::dsl as t;
+                diesel::insert_into(analytics_daily_tools::table)
+                    .values(&AnalyticsDailyToolsModel {
+                        day: row.day.to_string(),
+                        agent_id: row.agent_id.clone(),
+                        tool_name: row.tool_name.clone(),
+                        calls: row.calls,
+                        failures: row.failures,
+                    })
+                    .on_conflict((t::day, t::agent_id, t::tool_name))
+                    .do_update()
+                    .set((
+                        t::calls.eq(t::calls + excluded(t::calls)),
+                        t::failures.eq(t::failures + excluded(t::failures)),
+                    ))
+                    .execute(conn)
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<Conn> AnalyticsStore for DieselTaskStore<Conn>
+where
+    Conn: DieselBackendConnection,
+    diesel::dsl::select<diesel::dsl::AsExprOf<i32, diesel::sql_types::Integer>>: ExecuteDsl<Conn>,
+    diesel::query_builder::SqlQuery: QueryFragment<<Conn as AsyncConnectionCore>::Backend>,
+    <Conn as AsyncConnectionCore>::Backend: diesel::backend::DieselReserveSpecialization,
+{
+    async fn run_counters(
+        &self,
+        range: &DateRange,
+        agent_id: Option<&str>,
+    ) -> Result<Vec<DailyAgentRuns>> {
+        let mut connection = self.conn().await?;
+        let mut query = analytics_daily_runs::table
+            .filter(analytics_daily_runs::day.ge(range.from.to_string()))
+            .filter(analytics_daily_runs::day.le(range.to.to_string()))
+            .into_boxed();
+        if let Some(agent_id) = agent_id {
+            query = query.filter(analytics_daily_runs::agent_id.eq(agent_id.to_string()));
+        }
+        let rows = query
+            .select(AnalyticsDailyRunsModel::as_select())
+            .load::<AnalyticsDailyRunsModel>(&mut connection)
+            .await
+            .context("failed to load run analytics")?;
+        rows.into_iter()
+            .map(|row| {
+                Ok(DailyAgentRuns {
+                    day: row.day.parse().context("invalid analytics day")?,
+                    agent_id: row.agent_id,
+                    counters: RunCounters {
+                        runs: row.runs,
+                        succeeded: row.succeeded,
+                        failed: row.failed,
+                        steps: row.steps,
+                        input_tokens: row.input_tokens,
+                        output_tokens: row.output_tokens,
+                        cost_usd: row.cost_usd,
+                    },
+                })
+            })
+            .collect()
+    }
+
+    async fn tool_counters(
+        &self,
+        range: &DateRange,
+        agent_id: Option<&str>,
+    ) -> Result<Vec<DailyAgentTools>> {
+        let mut connection = self.conn().await?;
+        let mut query = analytics_daily_tools::table
+            .filter(analytics_daily_tools::day.ge(range.from.to_string()))
+            .filter(analytics_daily_tools::day.le(range.to.to_string()))
+            .into_boxed();
+        if let Some(agent_id) = agent_id {
+            query = query.filter(analytics_daily_tools::agent_id.eq(agent_id.to_string()));
+        }
+        let rows = query
+            .select(AnalyticsDailyToolsModel::as_select())
+            .load::<AnalyticsDailyToolsModel>(&mut connection)
+            .await
+            .context("failed to load tool analytics")?;
+        rows.into_iter()
+            .map(|row| {
+                Ok(DailyAgentTools {
+                    day: row.day.parse().context("invalid analytics day")?,
+                    agent_id: row.agent_id,
+                    tool_name: row.tool_name,
+                    calls: row.calls,
+                    failures: row.failures,
+                })
+            })
+            .collect()
+    }
 }
 
 #[async_trait]
@@ -1787,6 +2045,8 @@ where
         let now = Utc::now().timestamp_millis();
         let invocation_text =
             serde_json::to_string(&input.invocation).context("failed to serialize invocation")?;
+        let reference_task_ids = serde_json::to_string(&input.reference_task_ids)
+            .context("failed to serialize reference task ids")?;
 
         let new_task = NewTaskModel {
             id: &task_id,
@@ -1798,6 +2058,7 @@ where
             remote: input.remote,
             inner_task_id: input.inner_task_id.as_deref(),
             invocation: &invocation_text,
+            reference_task_ids: &reference_task_ids,
         };
 
         diesel::insert_into(tasks::table)
@@ -1813,6 +2074,8 @@ where
             status,
             created_at: now,
             updated_at: now,
+            archived_at: None,
+            reference_task_ids: input.reference_task_ids,
         })
     }
 
@@ -1829,6 +2092,7 @@ where
 
     async fn add_event_to_task(&self, task_id: &str, event: AgentEvent) -> Result<()> {
         let mut connection = self.conn().await?;
+        let delta = AnalyticsDelta::from_event(task_id, &event);
         let task_event = serialize_agent_event(event);
         let payload =
             serde_json::to_string(&task_event).context("failed to serialize task event")?;
@@ -1846,9 +2110,57 @@ where
             .await
             .context("failed to insert task event")?;
 
+        if let Some(delta) = delta
+            && let Err(e) = Self::record_analytics(&mut connection, &delta).await
+        {
+            warn!("failed to update analytics of task {}: {}", task_id, e);
+        }
+
         Ok(())
     }
 
+    async fn add_task_events(&self, events: Vec<(String, AgentEvent)>) -> Result<()> {
+        use diesel_async::AsyncConnection;
+
+        let rows = events
+            .into_iter()
+            .map(|(task_id, event)| {
+                let delta = AnalyticsDelta::from_event(&task_id, &event);
+                let task_event = serialize_agent_event(event);
+                let payload =
+                    serde_json::to_string(&task_event).context("failed to serialize task event")?;
+                Ok((task_id, payload, task_event.created_at, delta))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        if rows.is_empty() {
+            return Ok(());
+        }
+
+        let mut connection = self.conn().await?;
+        connection
+            .transaction::<_, DieselError, _>(|conn| {
+                Box::pin(async move {
+                    for (task_id, payload, created_at, delta) in &rows {
+                        diesel::insert_into(task_messages::table)
+                            .values(&NewTaskMessageModel {
+                                task_id,
+                                kind: "event",
+                                payload,
+                                created_at: *created_at,
+                            })
+                            .execute(conn)
+                            .await?;
+                        if let Some(delta) = delta {
+                            Self::record_analytics(conn, delta).await?;
+                        }
+                    }
+                    Ok(())
+                })
+            })
+            .await
+            .context("failed to insert task events")
+    }
+
     async fn update_task_status(&self, task_id: &str, status: TaskStatus) -> Result<()> {
         let mut connection = self.conn().await?;
         let changeset = TaskStatusChangeset {
@@ -1948,6 +2260,7 @@ where
                 status: TaskStatus::Canceled,
                 created_at: r.created_at,
                 updated_at: r.updated_at,
+                ..Default::default()
             })
             .collect())
     }
@@ -2054,6 +2367,7 @@ where
                 status: task_status_from_str(&r.status),
                 created_at: r.created_at,
                 updated_at: r.updated_at,
+                ..Default::default()
             })
             .collect())
     }
@@ -2084,6 +2398,7 @@ where
         let mut connection = self.conn().await?;
         let task_rows = tasks::table
             .filter(tasks::thread_id.eq(thread_id))
+            .filter(tasks::archived_at.is_null())
             .order(tasks::created_at.asc())
             .load::<TaskModel>(&mut connection)
             .await
@@ -2142,6 +2457,153 @@ where
 
         Ok(history)
     }
+
+    async fn archive_tasks_from(&self, thread_id: &str, task_id: &str) -> Result<Vec<String>> {
+        let mut connection = self.conn().await?;
+        let from = tasks::table
+            .find(task_id)
+            .filter(tasks::thread_id.eq(thread_id))
+            .first::<TaskModel>(&mut connection)
+            .await
+            .optional()
+            .context("failed to load task")?
+            .ok_or_else(|| anyhow!("task {task_id} not found in thread {thread_id}"))?;
+        let ids = tasks::table
+            .filter(tasks::thread_id.eq(thread_id))
+            .filter(tasks::archived_at.is_null())
+            .filter(tasks::created_at.ge(from.created_at))
+            .order(tasks::created_at.asc())
+            .select(tasks::id)
+            .load::<String>(&mut connection)
+            .await
+            .context("failed to list tasks to archive")?;
+        diesel::update(tasks::table.filter(tasks::id.eq_any(&ids)))
+            .set(tasks::archived_at.eq(Some(Utc::now().timestamp_millis())))
+            .execute(&mut connection)
+            .await
+            .context("failed to archive tasks")?;
+        Ok(ids)
+    }
+
+    async fn export_thread_tasks(
+        &self,
+        thread_id: &str,
+    ) -> Result<(Vec<ArchivedTask>, Vec<ArchivedTaskMessage>)> {
+        let mut connection = self.conn().await?;
+        let task_rows = tasks::table
+            .filter(tasks::thread_id.eq(thread_id))
+            .order(tasks::created_at.asc())
+            .load::<TaskModel>(&mut connection)
+            .await
+            .context("failed to load tasks to export")?;
+        let task_ids: Vec<String> = task_rows.iter().map(|task| task.id.clone()).collect();
+        let message_rows = task_messages::table
+            .filter(task_messages::task_id.eq_any(&task_ids))
+            .order((task_messages::created_at.asc(), task_messages::id.asc()))
+            .load::<TaskMessageModel>(&mut connection)
+            .await
+            .context("failed to load task messages to export")?;
+
+        let tasks = task_rows
+            .into_iter()
+            .map(|row| ArchivedTask {
+                remote: row.remote,
+                inner_task_id: row.inner_task_id.clone(),
+                ended_at: row.ended_at,
+                invocation: serde_json::from_str(&row.invocation).unwrap_or(JsonValue::Null),
+                task: to_task(row),
+            })
+            .collect();
+        let messages = message_rows
+            .into_iter()
+            .map(|row| {
+                let payload = serde_json::from_str(&row.payload)
+                    .with_context(|| format!("invalid payload of task message {}", row.id))?;
+                Ok(ArchivedTaskMessage {
+                    task_id: row.task_id,
+                    kind: row.kind,
+                    payload,
+                    created_at: row.created_at,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok((tasks, messages))
+    }
+
+    async fn import_thread_tasks(
+        &self,
+        archived_tasks: &[ArchivedTask],
+        messages: &[ArchivedTaskMessage],
+    ) -> Result<()> {
+        let mut connection = self.conn().await?;
+        for archived in archived_tasks {
+            let task = &archived.task;
+            let invocation = archived.invocation.to_string();
+            let reference_task_ids = serde_json::to_string(&task.reference_task_ids)
+                .context("failed to serialize reference task ids")?;
+            let new_task = NewTaskModel {
+                id: &task.id,
+                thread_id: &task.thread_id,
+                parent_task_id: task.parent_task_id.as_deref(),
+                status: task_status_to_str(&task.status),
+                created_at: task.created_at,
+                updated_at: task.updated_at,
+                remote: archived.remote,
+                inner_task_id: archived.inner_task_id.as_deref(),
+                invocation: &invocation,
+                reference_task_ids: &reference_task_ids,
+            };
+            diesel::insert_into(tasks::table)
+                .values(&new_task)
+                .execute(&mut connection)
+                .await
+                .with_context(|| format!("failed to insert task {}", task.id))?;
+            if archived.ended_at.is_some() || task.archived_at.is_some() {
+                diesel::update(tasks::table.find(task.id.as_str()))
+                    .set((
+                        tasks::ended_at.eq(archived.ended_at),
+                        tasks::archived_at.eq(task.archived_at),
+                    ))
+                    .execute(&mut connection)
+                    .await
+                    .with_context(|| format!("failed to update task {}", task.id))?;
+            }
+        }
+        for message in messages {
+            let payload = message.payload.to_string();
+            diesel::insert_into(task_messages::table)
+                .values(&NewTaskMessageModel {
+                    task_id: &message.task_id,
+                    kind: &message.kind,
+                    payload: &payload,
+                    created_at: message.created_at,
+                })
+                .execute(&mut connection)
+                .await
+                .context("failed to insert task message")?;
+        }
+        Ok(())
+    }
+
+    async fn delete_thread_tasks(&self, thread_id: &str, task_ids: &[String]) -> Result<usize> {
+        let mut connection = self.conn().await?;
+        let thread_tasks = tasks::table
+            .filter(tasks::thread_id.eq(thread_id))
+            .filter(tasks::id.eq_any(task_ids))
+            .select(tasks::id);
+        diesel::delete(task_messages::table.filter(task_messages::task_id.eq_any(thread_tasks)))
+            .execute(&mut connection)
+            .await
+            .context("failed to delete task messages")?;
+        diesel::delete(
+            tasks::table
+                .filter(tasks::thread_id.eq(thread_id))
+                .filter(tasks::id.eq_any(task_ids)),
+        )
+        .execute(&mut connection)
+        .await
+        .context("failed to delete tasks")
+    }
 }
 
 #[derive(Clone)]
@@ -3407,6 +3869,18 @@ where
     pub fn note_store(&self) -> DieselNoteStore<Conn> {
         DieselNoteStore::new(self.pool.clone_store_pool())
     }
+
+    pub fn background_job_store(&self) -> DieselBackgroundJobStore<Conn> {
+        DieselBackgroundJobStore::new(self.pool.clone_store_pool())
+    }
+
+    pub fn plugin_kv_store(&self, quota: PluginStorageConfig) -> DieselPluginKvStore<Conn> {
+        DieselPluginKvStore::new(self.pool.clone_store_pool(), quota)
+    }
+
+    pub fn llm_audit_store(&self, config: LlmAuditConfig) -> DieselLlmAuditStore<Conn> {
+        DieselLlmAuditStore::new(self.pool.clone_store_pool(), config)
+    }
 }
 
 // ========== Prompt Template Store ==========
@@ -4996,3 +5470,613 @@ where
         .await
     }
 }
+
+// ========== Plugin KV Store ==========
+
+pub struct DieselPluginKvStore<Conn>
+where
+    Conn: DieselBackendConnection,
+    diesel::dsl::select<diesel::dsl::AsExprOf<i32, diesel::sql_types::Integer>>: ExecuteDsl<Conn>,
+    diesel::query_builder::SqlQuery: QueryFragment<<Conn as AsyncConnectionCore>::Backend>,
+    <Conn as AsyncConnectionCore>::Backend: diesel::backend::DieselReserveSpecialization,
+{
+    pool: DieselStorePool<Conn>,
+    quota: PluginStorageConfig,
+}
+
+impl<Conn> DieselPluginKvStore<Conn>
+where
+    Conn: DieselBackendConnection,
+    diesel::dsl::select<diesel::dsl::AsExprOf<i32, diesel::sql_types::Integer>>: ExecuteDsl<Conn>,
+    diesel::query_builder::SqlQuery: QueryFragment<<Conn as AsyncConnectionCore>::Backend>,
+    <Conn as AsyncConnectionCore>::Backend: diesel::backend::DieselReserveSpecialization,
+{
+    pub fn new(pool: DieselStorePool<Conn>, quota: PluginStorageConfig) -> Self {
+        Self { pool, quota }
+    }
+
+    async fn conn(&self) -> Result<DieselConn<'_, Conn>> {
+        self.pool
+            .get()
+            .await
+            .context("failed to acquire diesel connection for plugin storage")
+    }
+}
+
+#[async_trait]
+impl<Conn> PluginKvStore for DieselPluginKvStore<Conn>
+where
+    Conn: DieselBackendConnection,
+    diesel::dsl::select<diesel::dsl::AsExprOf<i32, diesel::sql_types::Integer>>: ExecuteDsl<Conn>,
+    diesel::query_builder::SqlQuery: QueryFragment<<Conn as AsyncConnectionCore>::Backend>,
+    <Conn as AsyncConnectionCore>::Backend: diesel::backend::DieselReserveSpecialization,
+{
+    async fn get(&self, plugin: &str, key: &str) -> Result<Option<JsonValue>> {
+        use crate::schema::plugin_kv;
+        let mut connection = self.conn().await?;
+        let stored = plugin_kv::table
+            .filter(plugin_kv::plugin.eq(plugin))
+            .filter(plugin_kv::key.eq(key))
+            .select(plugin_kv::value)
+            .first::<String>(&mut connection)
+            .await
+            .optional()
+            .context("failed to get plugin value")?;
+        stored
+            .map(|value| serde_json::from_str(&value).context("invalid stored plugin value"))
+            .transpose()
+    }
+
+    async fn set(&self, plugin: &str, key: &str, value: &JsonValue) -> Result<()> {
+        use crate::schema::plugin_kv;
+        use diesel_async::AsyncConnection;
+
+        let value = serde_json::to_string(value).context("failed to serialize plugin value")?;
+        let size = key.len() + value.len();
+        let quota = self.quota.clone();
+        let mut connection = self.conn().await?;
+        connection
+            .transaction::<_, anyhow::Error, _>(|conn| {
+                Box::pin(async move {
+                    let replaced: Option<i32> = plugin_kv::table
+                        .filter(plugin_kv::plugin.eq(plugin))
+                        .filter(plugin_kv::key.eq(key))
+                        .select(plugin_kv::size)
+                        .first(conn)
+                        .await
+                        .optional()?;
+                    let keys: i64 = plugin_kv::table
+                        .filter(plugin_kv::plugin.eq(plugin))
+                        .count()
+                        .get_result(conn)
+                        .await?;
+                    let bytes: Option<i64> = plugin_kv::table
+                        .filter(plugin_kv::plugin.eq(plugin))
+                        .select(diesel::dsl::sum(plugin_kv::size))
+                        .first(conn)
+                        .await?;
+                    let usage = PluginStorageUsage {
+                        keys: keys as usize + usize::from(replaced.is_none()),
+                        bytes: bytes.unwrap_or(0) as usize - replaced.unwrap_or(0) as usize + size,
+                    };
+                    quota.check(plugin, value.len(), usage)?;
+
+                    let now = now_naive();
+                    diesel::insert_into(plugin_kv::table)
+                        .values(&NewPluginKvModel {
+                            plugin,
+                            key,
+                            value: &value,
+                            size: size as i32,
+                            created_at: now,
+                            updated_at: now,
+                        })
+                        .on_conflict((plugin_kv::plugin, plugin_kv::key))
+                        .do_update()
+                        .set(&PluginKvChangeset {
+                            value: &value,
+                            size: size as i32,
+                            updated_at: now,
+                        })
+                        .execute(conn)
+                        .await?;
+                    Ok(())
+                })
+            })
+            .await
+    }
+
+    async fn delete(&self, plugin: &str, key: &str) -> Result<bool> {
+        use crate::schema::plugin_kv;
+        let mut connection = self.conn().await?;
+        let deleted = diesel::delete(
+            plugin_kv::table
+                .filter(plugin_kv::plugin.eq(plugin))
+                .filter(plugin_kv::key.eq(key)),
+        )
+        .execute(&mut connection)
+        .await
+        .context("failed to delete plugin value")?;
+        Ok(deleted > 0)
+    }
+
+    async fn list(&self, plugin: &str, prefix: &str) -> Result<Vec<String>> {
+        use crate::schema::plugin_kv;
+        let mut connection = self.conn().await?;
+        let keys = plugin_kv::table
+            .filter(plugin_kv::plugin.eq(plugin))
+            .order(plugin_kv::key.asc())
+            .select(plugin_kv::key)
+            .load::<String>(&mut connection)
+            .await
+            .context("failed to list plugin keys")?;
+        Ok(keys
+            .into_iter()
+            .filter(|key| key.starts_with(prefix))
+            .collect())
+    }
+}
+
+// ========== Background Job Store ==========
+
+fn to_background_job(model: BackgroundJobModel) -> Result<distri_types::jobs::BackgroundJob> {
+    Ok(distri_types::jobs::BackgroundJob {
+        id: model.id,
+        idempotency_key: model.idempotency_key,
+        agent_id: model.agent_id,
+        user_id: model.user_id,
+        workspace_id: model.workspace_id,
+        task_id: model.task_id,
+        thread_id: model.thread_id,
+        params: serde_json::from_str(&model.params).context("invalid background job params")?,
+        status: model.status.parse()?,
+        attempts: model.attempts.max(0) as u32,
+        max_attempts: model.max_attempts.max(0) as u32,
+        worker_id: model.worker_id,
+        lease_expires_at: model.lease_expires_at.map(from_naive),
+        last_error: model.last_error,
+        created_at: from_naive(model.created_at),
+        updated_at: from_naive(model.updated_at),
+    })
+}
+
+pub struct DieselBackgroundJobStore<Conn>
+where
+    Conn: DieselBackendConnection,
+    diesel::dsl::select<diesel::dsl::AsExprOf<i32, diesel::sql_types::Integer>>: ExecuteDsl<Conn>,
+    diesel::query_builder::SqlQuery: QueryFragment<<Conn as AsyncConnectionCore>::Backend>,
+    <Conn as AsyncConnectionCore>::Backend: diesel::backend::DieselReserveSpecialization,
+{
+    pool: DieselStorePool<Conn>,
+}
+
+impl<Conn> DieselBackgroundJobStore<Conn>
+where
+    Conn: DieselBackendConnection,
+    diesel::dsl::select<diesel::dsl::AsExprOf<i32, diesel::sql_types::Integer>>: ExecuteDsl<Conn>,
+    diesel::query_builder::SqlQuery: QueryFragment<<Conn as AsyncConnectionCore>::Backend>,
+    <Conn as AsyncConnectionCore>::Backend: diesel::backend::DieselReserveSpecialization,
+{
+    pub fn new(pool: DieselStorePool<Conn>) -> Self {
+        Self { pool }
+    }
+
+    async fn conn(&self) -> Result<DieselConn<'_, Conn>> {
+        self.pool
+            .get()
+            .await
+            .context("failed to acquire diesel connection for background jobs")
+    }
+
+    async fn find_by_idempotency_key(
+        &self,
+        key: &str,
+    ) -> Result<Option<distri_types::jobs::BackgroundJob>> {
+        use crate::schema::background_jobs::dsl::*;
+        let mut conn = self.conn().await?;
+        background_jobs
+            .filter(idempotency_key.eq(key))
+            .select(BackgroundJobModel::as_select())
+            .first::<BackgroundJobModel>(&mut conn)
+            .await
+            .optional()
+            .context("failed to load background job")?
+            .map(to_background_job)
+            .transpose()
+    }
+}
+
+/// Claim attempts before giving up when other workers keep winning the race
+/// for the head of the queue.
+const CLAIM_RETRIES: usize = 5;
+
+#[async_trait]
+impl<Conn> distri_types::stores::BackgroundJobStore for DieselBackgroundJobStore<Conn>
+where
+    Conn: DieselBackendConnection,
+    diesel::dsl::select<diesel::dsl::AsExprOf<i32, diesel::sql_types::Integer>>: ExecuteDsl<Conn>,
+    diesel::query_builder::SqlQuery: QueryFragment<<Conn as AsyncConnectionCore>::Backend>,
+    <Conn as AsyncConnectionCore>::Backend: diesel::backend::DieselReserveSpecialization,
+{
+    async fn enqueue(
+        &self,
+        job: distri_types::jobs::NewBackgroundJob,
+    ) -> anyhow::Result<distri_types::jobs::BackgroundJob> {
+        use crate::schema::background_jobs::dsl::*;
+
+        if let Some(existing) = self.find_by_idempotency_key(&job.idempotency_key).await? {
+            return Ok(existing);
+        }
+
+        let record = distri_types::jobs::BackgroundJob::queued(job);
+        let params_json = serde_json::to_string(&record.params)?;
+        let model = NewBackgroundJobModel {
+            id: &record.id,
+            idempotency_key: &record.idempotency_key,
+            agent_id: &record.agent_id,
+            user_id: &record.user_id,
+            workspace_id: record.workspace_id.as_deref(),
+            task_id: &record.task_id,
+            thread_id: &record.thread_id,
+            params: &params_json,
+            status: record.status.as_str(),
+            attempts: 0,
+            max_attempts: record.max_attempts as i32,
+            created_at: to_naive(record.created_at),
+            updated_at: to_naive(record.updated_at),
+        };
+
+        let mut conn = self.conn().await?;
+        match diesel::insert_into(background_jobs)
+            .values(&model)
+            .execute(&mut conn)
+            .await
+        {
+            Ok(_) => Ok(record),
+            // Lost a race with a concurrent enqueue of the same key.
+            Err(DieselError::DatabaseError(DatabaseErrorKind::UniqueViolation, _)) => {
+                drop(conn);
+                self.find_by_idempotency_key(&record.idempotency_key)
+                    .await?
+                    .ok_or_else(|| anyhow!("background job vanished after unique violation"))
+            }
+            Err(e) => Err(e).context("failed to insert background job"),
+        }
+    }
+
+    async fn get(&self, job_id: &str) -> anyhow::Result<Option<distri_types::jobs::BackgroundJob>> {
+        use crate::schema::background_jobs::dsl::*;
+        let mut conn = self.conn().await?;
+        background_jobs
+            .filter(id.eq(job_id))
+            .select(BackgroundJobModel::as_select())
+            .first::<BackgroundJobModel>(&mut conn)
+            .await
+            .optional()
+            .context("failed to load background job")?
+            .map(to_background_job)
+            .transpose()
+    }
+
+    async fn get_by_task_id(
+        &self,
+        job_task_id: &str,
+    ) -> anyhow::Result<Option<distri_types::jobs::BackgroundJob>> {
+        use crate::schema::background_jobs::dsl::*;
+        let mut conn = self.conn().await?;
+        background_jobs
+            .filter(task_id.eq(job_task_id))
+            .order_by(created_at.desc())
+            .select(BackgroundJobModel::as_select())
+            .first::<BackgroundJobModel>(&mut conn)
+            .await
+            .optional()
+            .context("failed to load background job")?
+            .map(to_background_job)
+            .transpose()
+    }
+
+    async fn claim(
+        &self,
+        claimant: &str,
+        lease_until: DateTime<Utc>,
+    ) -> anyhow::Result<Option<distri_types::jobs::BackgroundJob>> {
+        use crate::schema::background_jobs::dsl::*;
+        let mut conn = self.conn().await?;
+
+        for _ in 0..CLAIM_RETRIES {
+            let candidate = background_jobs
+                .filter(status.eq("queued"))
+                .order_by(created_at.asc())
+                .select(id)
+                .first::<String>(&mut conn)
+                .await
+                .optional()
+                .context("failed to scan background job queue")?;
+            let Some(candidate) = candidate else {
+                return Ok(None);
+            };
+
+            // Conditional on `status = 'queued'` so only one worker wins.
+            let claimed = diesel::update(
+                background_jobs
+                    .filter(id.eq(&candidate))
+                    .filter(status.eq("queued")),
+            )
+            .set((
+                status.eq("running"),
+                worker_id.eq(Some(claimant)),
+                lease_expires_at.eq(Some(to_naive(lease_until))),
+                attempts.eq(attempts + 1),
+                updated_at.eq(now_naive()),
+            ))
+            .execute(&mut conn)
+            .await
+            .context("failed to claim background job")?;
+
+            if claimed == 1 {
+                let model = background_jobs
+                    .filter(id.eq(&candidate))
+                    .select(BackgroundJobModel::as_select())
+                    .first::<BackgroundJobModel>(&mut conn)
+                    .await
+                    .context("failed to load claimed background job")?;
+                return to_background_job(model).map(Some);
+            }
+        }
+        Ok(None)
+    }
+
+    async fn renew(
+        &self,
+        job_id: &str,
+        claimant: &str,
+        lease_until: DateTime<Utc>,
+    ) -> anyhow::Result<bool> {
+        use crate::schema::background_jobs::dsl::*;
+        let mut conn = self.conn().await?;
+        let updated = diesel::update(
+            background_jobs
+                .filter(id.eq(job_id))
+                .filter(status.eq("running"))
+                .filter(worker_id.eq(claimant)),
+        )
+        .set((
+            lease_expires_at.eq(Some(to_naive(lease_until))),
+            updated_at.eq(now_naive()),
+        ))
+        .execute(&mut conn)
+        .await
+        .context("failed to renew background job lease")?;
+        Ok(updated == 1)
+    }
+
+    async fn finish(
+        &self,
+        job_id: &str,
+        claimant: &str,
+        outcome: distri_types::jobs::BackgroundJobStatus,
+        error: Option<String>,
+    ) -> anyhow::Result<()> {
+        use crate::schema::background_jobs::dsl::*;
+        let mut conn = self.conn().await?;
+        diesel::update(
+            background_jobs
+                .filter(id.eq(job_id))
+                .filter(worker_id.eq(claimant)),
+        )
+        .set((
+            status.eq(outcome.as_str()),
+            lease_expires_at.eq(None::<NaiveDateTime>),
+            last_error.eq(error),
+            updated_at.eq(now_naive()),
+        ))
+        .execute(&mut conn)
+        .await
+        .context("failed to finish background job")?;
+        Ok(())
+    }
+
+    async fn cancel_queued(&self, job_id: &str) -> anyhow::Result<bool> {
+        use crate::schema::background_jobs::dsl::*;
+        let mut conn = self.conn().await?;
+        let updated = diesel::update(
+            background_jobs
+                .filter(id.eq(job_id))
+                .filter(status.eq("queued")),
+        )
+        .set((status.eq("canceled"), updated_at.eq(now_naive())))
+        .execute(&mut conn)
+        .await
+        .context("failed to cancel background job")?;
+        Ok(updated == 1)
+    }
+
+    async fn requeue_expired(&self, now: DateTime<Utc>) -> anyhow::Result<usize> {
+        use crate::schema::background_jobs::dsl::*;
+        let mut conn = self.conn().await?;
+        let now = to_naive(now);
+
+        let failed = diesel::update(
+            background_jobs
+                .filter(status.eq("running"))
+                .filter(lease_expires_at.lt(now))
+                .filter(attempts.ge(max_attempts)),
+        )
+        .set((
+            status.eq("failed"),
+            worker_id.eq(None::<String>),
+            lease_expires_at.eq(None::<NaiveDateTime>),
+            last_error.eq(Some("worker lease expired; no attempts left")),
+            updated_at.eq(now),
+        ))
+        .execute(&mut conn)
+        .await
+        .context("failed to fail exhausted background jobs")?;
+
+        let requeued = diesel::update(
+            background_jobs
+                .filter(status.eq("running"))
+                .filter(lease_expires_at.lt(now)),
+        )
+        .set((
+            status.eq("queued"),
+            worker_id.eq(None::<String>),
+            lease_expires_at.eq(None::<NaiveDateTime>),
+            last_error.eq(Some("worker lease expired")),
+            updated_at.eq(now),
+        ))
+        .execute(&mut conn)
+        .await
+        .context("failed to re-queue orphaned background jobs")?;
+
+        Ok(failed + requeued)
+    }
+}
+
+// ========== LLM Audit Store ==========
+
+fn to_llm_audit_record(model: LlmAuditRecordModel) -> Result<LlmAuditRecord> {
+    Ok(LlmAuditRecord {
+        id: Uuid::parse_str(&model.id).context("invalid audit record id")?,
+        created_at: from_naive(model.created_at),
+        agent_id: model.agent_id,
+        user_id: model.user_id,
+        thread_id: model.thread_id,
+        task_id: model.task_id,
+        workspace_id: model.workspace_id,
+        provider: model.provider,
+        model: model.model,
+        streaming: model.streaming,
+        request: serde_json::from_str(&model.request).context("invalid audit request")?,
+        response: serde_json::from_str(&model.response).context("invalid audit response")?,
+        error: model.error,
+        input_tokens: model.input_tokens.max(0) as u32,
+        output_tokens: model.output_tokens.max(0) as u32,
+        duration_ms: model.duration_ms.max(0) as u64,
+        redacted: model.redacted,
+    })
+}
+
+/// The LLM audit log in the `llm_audit_records` table, applying the
+/// retention window of its [`LlmAuditConfig`] as records are appended.
+pub struct DieselLlmAuditStore<Conn>
+where
+    Conn: DieselBackendConnection,
+    diesel::dsl::select<diesel::dsl::AsExprOf<i32, diesel::sql_types::Integer>>: ExecuteDsl<Conn>,
+    diesel::query_builder::SqlQuery: QueryFragment<<Conn as AsyncConnectionCore>::Backend>,
+    <Conn as AsyncConnectionCore>::Backend: diesel::backend::DieselReserveSpecialization,
+{
+    pool: DieselStorePool<Conn>,
+    config: LlmAuditConfig,
+}
+
+impl<Conn> DieselLlmAuditStore<Conn>
+where
+    Conn: DieselBackendConnection,
+    diesel::dsl::select<diesel::dsl::AsExprOf<i32, diesel::sql_types::Integer>>: ExecuteDsl<Conn>,
+    diesel::query_builder::SqlQuery: QueryFragment<<Conn as AsyncConnectionCore>::Backend>,
+    <Conn as AsyncConnectionCore>::Backend: diesel::backend::DieselReserveSpecialization,
+{
+    pub fn new(pool: DieselStorePool<Conn>, config: LlmAuditConfig) -> Self {
+        Self { pool, config }
+    }
+
+    async fn conn(&self) -> Result<DieselConn<'_, Conn>> {
+        self.pool
+            .get()
+            .await
+            .context("failed to acquire diesel connection for the LLM audit log")
+    }
+}
+
+#[async_trait]
+impl<Conn> LlmAuditStore for DieselLlmAuditStore<Conn>
+where
+    Conn: DieselBackendConnection,
+    diesel::dsl::select<diesel::dsl::AsExprOf<i32, diesel::sql_types::Integer>>: ExecuteDsl<Conn>,
+    diesel::query_builder::SqlQuery: QueryFragment<<Conn as AsyncConnectionCore>::Backend>,
+    <Conn as AsyncConnectionCore>::Backend: diesel::backend::DieselReserveSpecialization,
+{
+    fn redact(&self) -> bool {
+        self.config.redact
+    }
+
+    async fn record(&self, record: LlmAuditRecord) -> Result<()> {
+        use crate::schema::llm_audit_records;
+
+        let row = LlmAuditRecordModel {
+            id: record.id.to_string(),
+            created_at: to_naive(record.created_at),
+            agent_id: record.agent_id,
+            user_id: record.user_id,
+            thread_id: record.thread_id,
+            task_id: record.task_id,
+            workspace_id: record.workspace_id,
+            provider: record.provider,
+            model: record.model,
+            streaming: record.streaming,
+            request: serde_json::to_string(&record.request)
+                .context("failed to serialize audit request")?,
+            response: serde_json::to_string(&record.response)
+                .context("failed to serialize audit response")?,
+            error: record.error,
+            input_tokens: i64::from(record.input_tokens),
+            output_tokens: i64::from(record.output_tokens),
+            duration_ms: i64::try_from(record.duration_ms).unwrap_or(i64::MAX),
+            redacted: record.redacted,
+        };
+        if let Some(cutoff) = self.config.retention_cutoff(Utc::now()) {
+            self.purge_before(cutoff).await?;
+        }
+        let mut connection = self.conn().await?;
+        diesel::insert_into(llm_audit_records::table)
+            .values(&row)
+            .execute(&mut connection)
+            .await
+            .context("failed to insert LLM audit record")?;
+        Ok(())
+    }
+
+    async fn list(&self, query: &LlmAuditQuery) -> Result<Vec<LlmAuditRecord>> {
+        use crate::schema::llm_audit_records;
+
+        let mut rows = llm_audit_records::table.into_boxed();
+        if let Some(agent_id) = &query.agent_id {
+            rows = rows.filter(llm_audit_records::agent_id.eq(agent_id.as_str()));
+        }
+        if let Some(user_id) = &query.user_id {
+            rows = rows.filter(llm_audit_records::user_id.eq(user_id.as_str()));
+        }
+        if let Some(from) = query.from {
+            rows = rows.filter(llm_audit_records::created_at.ge(to_naive(from)));
+        }
+        if let Some(to) = query.to {
+            rows = rows.filter(llm_audit_records::created_at.lt(to_naive(to)));
+        }
+        if let Some(limit) = query.limit {
+            rows = rows.limit(i64::try_from(limit).unwrap_or(i64::MAX));
+        }
+        let mut connection = self.conn().await?;
+        rows.order(llm_audit_records::created_at.desc())
+            .select(LlmAuditRecordModel::as_select())
+            .load::<LlmAuditRecordModel>(&mut connection)
+            .await
+            .context("failed to list LLM audit records")?
+            .into_iter()
+            .map(to_llm_audit_record)
+            .collect()
+    }
+
+    async fn purge_before(&self, cutoff: DateTime<Utc>) -> Result<usize> {
+        use crate::schema::llm_audit_records;
+
+        let mut connection = self.conn().await?;
+        diesel::delete(
+            llm_audit_records::table.filter(llm_audit_records::created_at.lt(to_naive(cutoff))),
+        )
+        .execute(&mut connection)
+        .await
+        .context("failed to purge LLM audit records")
+    }
+}
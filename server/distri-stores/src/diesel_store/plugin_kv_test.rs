@@ -0,0 +1,99 @@
+#[cfg(test)]
+#[cfg(feature = "sqlite")]
+mod tests {
+    use crate::diesel_store::DieselStoreBuilder;
+    use distri_types::plugin_storage::{PluginStorageConfig, PluginStorageError};
+    use distri_types::stores::PluginKvStore;
+    use serde_json::json;
+
+    async fn test_store(quota: PluginStorageConfig) -> impl PluginKvStore {
+        let db_name = uuid::Uuid::new_v4();
+        let db_url = format!("file:{}?mode=memory&cache=shared", db_name);
+        DieselStoreBuilder::sqlite(&db_url, 1)
+            .await
+            .expect("Failed to create test store")
+            .plugin_kv_store(quota)
+    }
+
+    fn quota_error(error: anyhow::Error) -> String {
+        match error.downcast::<PluginStorageError>() {
+            Ok(PluginStorageError::QuotaExceeded { reason, .. }) => reason,
+            other => panic!("expected a quota error, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn values_are_isolated_per_plugin() {
+        let store = test_store(PluginStorageConfig::default()).await;
+
+        store
+            .set("acme", "cursor", &json!({ "page": 3 }))
+            .await
+            .unwrap();
+        store
+            .set("acme", "cache/orders", &json!([1, 2]))
+            .await
+            .unwrap();
+        store.set("other", "cursor", &json!("c_9")).await.unwrap();
+
+        assert_eq!(
+            store.get("acme", "cursor").await.unwrap(),
+            Some(json!({ "page": 3 }))
+        );
+        assert_eq!(
+            store.get("other", "cursor").await.unwrap(),
+            Some(json!("c_9"))
+        );
+        assert_eq!(
+            store.list("acme", "").await.unwrap(),
+            ["cache/orders", "cursor"]
+        );
+        assert_eq!(
+            store.list("acme", "cache/").await.unwrap(),
+            ["cache/orders"]
+        );
+
+        store
+            .set("acme", "cursor", &json!({ "page": 4 }))
+            .await
+            .unwrap();
+        assert_eq!(
+            store.get("acme", "cursor").await.unwrap(),
+            Some(json!({ "page": 4 }))
+        );
+
+        assert!(store.delete("acme", "cursor").await.unwrap());
+        assert!(!store.delete("acme", "cursor").await.unwrap());
+        assert_eq!(store.get("acme", "cursor").await.unwrap(), None);
+        assert_eq!(
+            store.get("other", "cursor").await.unwrap(),
+            Some(json!("c_9"))
+        );
+    }
+
+    #[tokio::test]
+    async fn quotas_are_enforced_per_plugin() {
+        let store = test_store(PluginStorageConfig {
+            max_value_bytes: 16,
+            max_keys: 2,
+            max_total_bytes: 24,
+        })
+        .await;
+
+        let too_big = store.set("acme", "a", &json!("x".repeat(20))).await;
+        assert!(quota_error(too_big.unwrap_err()).contains("value of 22 bytes"));
+
+        store.set("acme", "a", &json!("0123456789")).await.unwrap();
+        // Replacing a value only counts the new one.
+        store.set("acme", "a", &json!("9876543210")).await.unwrap();
+        let over_total = store.set("acme", "b", &json!("0123456789")).await;
+        assert!(quota_error(over_total.unwrap_err()).contains("24 bytes"));
+
+        store.set("acme", "b", &json!(1)).await.unwrap();
+        let over_keys = store.set("acme", "c", &json!(2)).await;
+        assert!(quota_error(over_keys.unwrap_err()).contains("2 keys"));
+
+        // Another plugin has its own quota.
+        store.set("other", "c", &json!(2)).await.unwrap();
+    }
+}
@@ -0,0 +1,142 @@
+#[cfg(test)]
+#[cfg(feature = "sqlite")]
+mod tests {
+    use chrono::{Duration, Utc};
+
+    use crate::diesel_store::DieselStoreBuilder;
+    use distri_types::stores::{CreateTaskInput, TaskStore, ThreadListFilter, ThreadStore};
+    use distri_types::{CreateThreadRequest, Message, TaskMessage, TaskStatus};
+
+    async fn test_store() -> DieselStoreBuilder<crate::diesel_store::SqliteConnectionWrapper> {
+        let db_name = uuid::Uuid::new_v4();
+        let db_url = format!("file:{}?mode=memory&cache=shared", db_name);
+        DieselStoreBuilder::sqlite(&db_url, 1)
+            .await
+            .expect("Failed to create test store")
+    }
+
+    /// Exported tasks can be deleted and imported back unchanged; the
+    /// archived thread stays listed with its flag.
+    #[tokio::test]
+    async fn thread_tasks_round_trip_through_an_export() {
+        let store = test_store().await;
+        let thread_store = store.thread_store();
+        let task_store = store.task_store();
+        let thread = thread_store
+            .create_thread(CreateThreadRequest {
+                agent_id: "test-agent".to_string(),
+                title: Some("Old thread".to_string()),
+                thread_id: None,
+                attributes: None,
+                user_id: None,
+                external_id: None,
+                channel_id: None,
+            })
+            .await
+            .expect("create thread");
+        task_store
+            .create_task(
+                CreateTaskInput::local(&thread.id)
+                    .with_id("t1")
+                    .with_status(TaskStatus::Completed),
+            )
+            .await
+            .expect("create task");
+        task_store
+            .add_message_to_task("t1", &Message::user("hello".to_string(), None))
+            .await
+            .expect("add message");
+
+        let later = Utc::now() + Duration::seconds(1);
+        assert_eq!(
+            thread_store
+                .list_archivable_threads(later, 10)
+                .await
+                .expect("archivable"),
+            vec![thread.id.clone()]
+        );
+
+        let (tasks, messages) = task_store
+            .export_thread_tasks(&thread.id)
+            .await
+            .expect("export");
+        assert_eq!(tasks.len(), 1);
+        assert_eq!(messages.len(), 1);
+        assert_eq!(
+            task_store
+                .delete_thread_tasks(&thread.id, &["t1".to_string()])
+                .await
+                .expect("delete"),
+            1
+        );
+        thread_store
+            .set_thread_archived(&thread.id, Some(Utc::now()))
+            .await
+            .expect("archive");
+        assert!(task_store.get_task("t1").await.expect("get").is_none());
+
+        let listed = thread_store
+            .list_threads(&ThreadListFilter::default(), None, None)
+            .await
+            .expect("list");
+        assert!(listed.threads[0].archived_at.is_some());
+        assert!(
+            thread_store
+                .list_archivable_threads(later, 10)
+                .await
+                .expect("archivable")
+                .is_empty()
+        );
+
+        task_store
+            .import_thread_tasks(&tasks, &messages)
+            .await
+            .expect("import");
+        thread_store
+            .set_thread_archived(&thread.id, None)
+            .await
+            .expect("restore");
+        let history = task_store
+            .get_history(&thread.id, None)
+            .await
+            .expect("history");
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].0.status, TaskStatus::Completed);
+        match &history[0].1[..] {
+            [TaskMessage::Message(message)] => {
+                assert_eq!(message.as_text().as_deref(), Some("hello"))
+            }
+            other => panic!("unexpected messages: {other:?}"),
+        }
+        let thread = thread_store
+            .get_thread(&thread.id)
+            .await
+            .expect("get")
+            .expect("thread");
+        assert!(thread.archived_at.is_none());
+    }
+
+    /// Only the exported tasks are deleted; a task started after the export
+    /// stays in the database.
+    #[tokio::test]
+    async fn tasks_started_after_an_export_are_kept() {
+        let store = test_store().await;
+        let task_store = store.task_store();
+        for id in ["t1", "t2"] {
+            task_store
+                .create_task(CreateTaskInput::local("thread-1").with_id(id))
+                .await
+                .expect("create task");
+        }
+
+        assert_eq!(
+            task_store
+                .delete_thread_tasks("thread-1", &["t1".to_string()])
+                .await
+                .expect("delete"),
+            1
+        );
+        assert!(task_store.get_task("t1").await.expect("get").is_none());
+        assert!(task_store.get_task("t2").await.expect("get").is_some());
+    }
+}
@@ -0,0 +1,140 @@
+#[cfg(test)]
+#[cfg(feature = "sqlite")]
+mod tests {
+    use crate::diesel_store::DieselStoreBuilder;
+    use distri_types::stores::{ThreadListFilter, ThreadStore};
+    use distri_types::{CreateThreadRequest, THREAD_TAGS_KEY, UpdateThreadRequest};
+    use std::collections::HashMap;
+
+    async fn test_store() -> DieselStoreBuilder<crate::diesel_store::SqliteConnectionWrapper> {
+        let db_name = uuid::Uuid::new_v4();
+        let db_url = format!("file:{}?mode=memory&cache=shared", db_name);
+        DieselStoreBuilder::sqlite(&db_url, 1)
+            .await
+            .expect("Failed to create test store")
+    }
+
+    async fn create(store: &dyn ThreadStore, id: &str, title: &str, tags: &[&str]) {
+        store
+            .create_thread(CreateThreadRequest {
+                agent_id: "test-agent".to_string(),
+                title: Some(title.to_string()),
+                thread_id: Some(id.to_string()),
+                attributes: None,
+                user_id: None,
+                external_id: None,
+                channel_id: None,
+            })
+            .await
+            .expect("Failed to create thread");
+        if !tags.is_empty() {
+            let mut metadata = HashMap::new();
+            metadata.insert(THREAD_TAGS_KEY.to_string(), serde_json::json!(tags));
+            store
+                .update_thread(
+                    id,
+                    UpdateThreadRequest {
+                        title: None,
+                        metadata: Some(metadata),
+                        attributes: None,
+                        user_id: None,
+                        variables: None,
+                    },
+                )
+                .await
+                .expect("Failed to tag thread");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_search_matches_title_and_tags() {
+        let store = test_store().await;
+        let thread_store = store.thread_store();
+        create(
+            &thread_store,
+            "t-1",
+            "Quarterly revenue report",
+            &["finance"],
+        )
+        .await;
+        create(&thread_store, "t-2", "Fix login bug", &["auth", "bugfix"]).await;
+        create(&thread_store, "t-3", "Trip ideas", &[]).await;
+
+        let by_title = thread_store
+            .list_threads(
+                &ThreadListFilter {
+                    search: Some("REVENUE".to_string()),
+                    ..Default::default()
+                },
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+        assert_eq!(by_title.total, 1);
+        assert_eq!(by_title.threads[0].id, "t-1");
+        assert_eq!(
+            by_title.threads[0].tags.as_deref(),
+            Some(&["finance".to_string()][..])
+        );
+
+        let by_tag = thread_store
+            .list_threads(
+                &ThreadListFilter {
+                    search: Some("bugfix".to_string()),
+                    ..Default::default()
+                },
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+        assert_eq!(by_tag.total, 1);
+        assert_eq!(by_tag.threads[0].id, "t-2");
+
+        let tagged = thread_store
+            .list_threads(
+                &ThreadListFilter {
+                    tags: Some(vec!["Auth".to_string()]),
+                    ..Default::default()
+                },
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+        assert_eq!(tagged.total, 1);
+        assert_eq!(tagged.threads[0].id, "t-2");
+    }
+
+    #[tokio::test]
+    async fn test_search_paginates_matches() {
+        let store = test_store().await;
+        let thread_store = store.thread_store();
+        for i in 0..5 {
+            create(
+                &thread_store,
+                &format!("t-{i}"),
+                &format!("Report {i}"),
+                &[],
+            )
+            .await;
+        }
+        create(&thread_store, "other", "Unrelated", &[]).await;
+
+        let page = thread_store
+            .list_threads(
+                &ThreadListFilter {
+                    search: Some("report".to_string()),
+                    ..Default::default()
+                },
+                Some(2),
+                Some(2),
+            )
+            .await
+            .unwrap();
+        assert_eq!(page.total, 5);
+        assert_eq!(page.threads.len(), 2);
+        assert_eq!(page.page, 2);
+    }
+}
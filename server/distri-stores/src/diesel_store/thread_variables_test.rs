@@ -0,0 +1,78 @@
+#[cfg(test)]
+#[cfg(feature = "sqlite")]
+mod tests {
+    use crate::diesel_store::DieselStoreBuilder;
+    use distri_types::stores::ThreadStore;
+    use distri_types::{CreateThreadRequest, UpdateThreadRequest};
+    use std::collections::BTreeMap;
+
+    async fn test_store() -> DieselStoreBuilder<crate::diesel_store::SqliteConnectionWrapper> {
+        let db_name = uuid::Uuid::new_v4();
+        let db_url = format!("file:{}?mode=memory&cache=shared", db_name);
+        DieselStoreBuilder::sqlite(&db_url, 1)
+            .await
+            .expect("Failed to create test store")
+    }
+
+    fn update(variables: Option<BTreeMap<String, String>>) -> UpdateThreadRequest {
+        UpdateThreadRequest {
+            title: None,
+            metadata: None,
+            attributes: None,
+            user_id: None,
+            variables,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_thread_variables_round_trip() {
+        let store = test_store().await;
+        let thread_store = store.thread_store();
+
+        let thread = thread_store
+            .create_thread(CreateThreadRequest {
+                agent_id: "test-agent".to_string(),
+                title: Some("Support".to_string()),
+                thread_id: Some("thread-vars-1".to_string()),
+                attributes: None,
+                user_id: None,
+                external_id: None,
+                channel_id: None,
+            })
+            .await
+            .expect("Failed to create thread");
+        assert!(thread.variables.is_empty());
+
+        let variables = BTreeMap::from([
+            ("customer_name".to_string(), "Acme".to_string()),
+            ("environment".to_string(), "staging".to_string()),
+        ]);
+        thread_store
+            .update_thread(&thread.id, update(Some(variables.clone())))
+            .await
+            .expect("Failed to set variables");
+
+        // Updates that leave `variables` out keep them.
+        thread_store
+            .update_thread_with_message(&thread.id, "hello")
+            .await
+            .expect("Failed to record message");
+        let fetched = thread_store
+            .update_thread(&thread.id, update(None))
+            .await
+            .expect("Failed to update thread");
+        assert_eq!(fetched.variables, variables);
+
+        let cleared = thread_store
+            .update_thread(&thread.id, update(Some(BTreeMap::new())))
+            .await
+            .expect("Failed to clear variables");
+        assert!(cleared.variables.is_empty());
+        let fetched = thread_store
+            .get_thread(&thread.id)
+            .await
+            .expect("Failed to get thread")
+            .expect("Thread not found");
+        assert!(fetched.variables.is_empty());
+    }
+}
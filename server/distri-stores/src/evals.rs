@@ -0,0 +1,170 @@
+//! File-backed [`EvalStore`].
+//!
+//! Each agent gets a directory holding two append-only JSON-lines files,
+//! `runs.jsonl` and `comparisons.jsonl`, so eval history survives restarts
+//! and can be checked into the workspace alongside the agent definitions.
+
+use std::path::{Path, PathBuf};
+
+use async_trait::async_trait;
+use distri_types::evals::{EvalComparison, EvalRun};
+use distri_types::stores::EvalStore;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Mutex;
+
+const RUNS_FILE: &str = "runs.jsonl";
+const COMPARISONS_FILE: &str = "comparisons.jsonl";
+
+pub struct FileEvalStore {
+    dir: PathBuf,
+    /// Serializes appends so concurrent writers never interleave lines.
+    write_lock: Mutex<()>,
+}
+
+impl FileEvalStore {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            write_lock: Mutex::new(()),
+        }
+    }
+
+    /// Directory of `agent_id`. Characters outside `[A-Za-z0-9_.-]` are
+    /// replaced so package-qualified ids stay a single path component.
+    fn agent_dir(&self, agent_id: &str) -> PathBuf {
+        let name: String = agent_id
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.') {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        self.dir.join(name.trim_start_matches('.'))
+    }
+
+    async fn append<T: Serialize>(
+        &self,
+        agent_id: &str,
+        file: &str,
+        value: &T,
+    ) -> anyhow::Result<()> {
+        let dir = self.agent_dir(agent_id);
+        let mut line = serde_json::to_string(value)?;
+        line.push('\n');
+        let _guard = self.write_lock.lock().await;
+        tokio::fs::create_dir_all(&dir).await?;
+        let mut out = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(dir.join(file))
+            .await?;
+        out.write_all(line.as_bytes()).await?;
+        Ok(())
+    }
+
+    async fn read<T: DeserializeOwned>(
+        &self,
+        agent_id: &str,
+        file: &str,
+    ) -> anyhow::Result<Vec<T>> {
+        read_lines(&self.agent_dir(agent_id).join(file)).await
+    }
+}
+
+/// Parse a JSON-lines file, skipping lines that do not parse (e.g. a line
+/// cut short by a crash).
+async fn read_lines<T: DeserializeOwned>(path: &Path) -> anyhow::Result<Vec<T>> {
+    let raw = match tokio::fs::read_to_string(path).await {
+        Ok(raw) => raw,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+    Ok(raw
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| match serde_json::from_str(line) {
+            Ok(value) => Some(value),
+            Err(e) => {
+                tracing::warn!(path = %path.display(), "Skipping unreadable eval record: {}", e);
+                None
+            }
+        })
+        .collect())
+}
+
+#[async_trait]
+impl EvalStore for FileEvalStore {
+    async fn record_run(&self, run: &EvalRun) -> anyhow::Result<()> {
+        self.append(&run.agent_id, RUNS_FILE, run).await
+    }
+
+    async fn list_runs(&self, agent_id: &str) -> anyhow::Result<Vec<EvalRun>> {
+        self.read(agent_id, RUNS_FILE).await
+    }
+
+    async fn record_comparison(&self, comparison: &EvalComparison) -> anyhow::Result<()> {
+        self.append(&comparison.agent_id, COMPARISONS_FILE, comparison)
+            .await
+    }
+
+    async fn list_comparisons(&self, agent_id: &str) -> anyhow::Result<Vec<EvalComparison>> {
+        self.read(agent_id, COMPARISONS_FILE).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn run(agent: &str, id: &str) -> EvalRun {
+        EvalRun {
+            id: id.to_string(),
+            agent_id: agent.to_string(),
+            definition_hash: "0123456789abcdef".to_string(),
+            created_at: Utc::now(),
+            results: Vec::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn runs_are_kept_per_agent_in_order() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = FileEvalStore::new(dir.path());
+
+        store.record_run(&run("pkg/support", "r1")).await.unwrap();
+        store.record_run(&run("triage", "r2")).await.unwrap();
+        store.record_run(&run("pkg/support", "r3")).await.unwrap();
+
+        let ids: Vec<String> = store
+            .list_runs("pkg/support")
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|r| r.id)
+            .collect();
+        assert_eq!(ids, vec!["r1", "r3"]);
+        assert!(dir.path().join("pkg_support").join(RUNS_FILE).exists());
+        assert!(store.list_comparisons("triage").await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn unreadable_lines_are_skipped() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = FileEvalStore::new(dir.path());
+        store.record_run(&run("support", "r1")).await.unwrap();
+        let path = dir.path().join("support").join(RUNS_FILE);
+        let mut raw = std::fs::read_to_string(&path).unwrap();
+        raw.push_str("{\"id\": \"trunc");
+        std::fs::write(&path, raw).unwrap();
+
+        let runs = store.list_runs("support").await.unwrap();
+
+        assert_eq!(runs.len(), 1);
+    }
+}
@@ -3,14 +3,19 @@ use std::future::Future;
 use std::pin::Pin;
 use std::sync::Arc;
 
+use crate::AnonymizingMemoryStore;
+use crate::BufferedTaskStore;
 use crate::InMemoryExternalToolCallsStore;
+use crate::InMemoryLlmAuditStore;
 use crate::diesel_store::DieselStoreBuilder;
 #[cfg(all(not(feature = "sqlite"), feature = "postgres"))]
 use crate::diesel_store::PgStoreBuilder;
 #[cfg(feature = "sqlite")]
 use crate::diesel_store::SqliteStoreBuilder;
 use anyhow::{Result, anyhow};
+use distri_types::api::audit::LlmAuditConfig;
 use distri_types::configuration::{DbConnectionConfig, StoreType};
+use distri_types::plugin_storage::PluginStorageConfig;
 pub use distri_types::stores::*;
 use distri_types::{ToolAuthStore, configuration::StoreConfig};
 
@@ -44,6 +49,28 @@ pub trait StoreFactory: Send + Sync {
     fn provider_store(&self) -> Option<Arc<dyn ProviderStore>> {
         None
     }
+    /// Optional background job queue. Diesel backends persist it in the
+    /// `background_jobs` table; other backends leave non-blocking
+    /// `message/send` unavailable.
+    fn background_job_store(&self) -> Option<Arc<dyn BackgroundJobStore>> {
+        None
+    }
+    /// Optional plugin key-value storage, enforcing `quota`. Diesel backends
+    /// persist it in the `plugin_kv` table.
+    fn plugin_kv_store(&self, _quota: &PluginStorageConfig) -> Option<Arc<dyn PluginKvStore>> {
+        None
+    }
+    /// Optional analytics counters, maintained by this factory's task store.
+    /// Diesel backends keep them in the `analytics_daily_*` tables.
+    fn analytics_store(&self) -> Option<Arc<dyn AnalyticsStore>> {
+        None
+    }
+    /// Optional persistent LLM audit log. Diesel backends keep it in the
+    /// `llm_audit_records` table; for other backends the log is kept in
+    /// memory.
+    fn llm_audit_store(&self, _config: &LlmAuditConfig) -> Option<Arc<dyn LlmAuditStore>> {
+        None
+    }
 }
 
 impl<Conn> StoreFactory for DieselStoreBuilder<Conn>
@@ -107,6 +134,28 @@ where
     fn provider_store(&self) -> Option<Arc<dyn ProviderStore>> {
         Some(Arc::new(DieselStoreBuilder::provider_store(self)) as Arc<dyn ProviderStore>)
     }
+
+    fn background_job_store(&self) -> Option<Arc<dyn BackgroundJobStore>> {
+        Some(Arc::new(DieselStoreBuilder::background_job_store(self)) as Arc<dyn BackgroundJobStore>)
+    }
+
+    fn plugin_kv_store(&self, quota: &PluginStorageConfig) -> Option<Arc<dyn PluginKvStore>> {
+        Some(
+            Arc::new(DieselStoreBuilder::plugin_kv_store(self, quota.clone()))
+                as Arc<dyn PluginKvStore>,
+        )
+    }
+
+    fn analytics_store(&self) -> Option<Arc<dyn AnalyticsStore>> {
+        Some(Arc::new(DieselStoreBuilder::task_store(self)) as Arc<dyn AnalyticsStore>)
+    }
+
+    fn llm_audit_store(&self, config: &LlmAuditConfig) -> Option<Arc<dyn LlmAuditStore>> {
+        Some(
+            Arc::new(DieselStoreBuilder::llm_audit_store(self, config.clone()))
+                as Arc<dyn LlmAuditStore>,
+        )
+    }
 }
 
 fn boxed_initializer<F, Fut, Factory>(initializer: F) -> StoreInitializer
@@ -142,6 +191,8 @@ pub struct StoreBuilder {
     pub prompt_template_store: Option<Arc<dyn PromptTemplateStore>>,
     pub secret_store: Option<Arc<dyn SecretStore>>,
     pub skill_store: Option<Arc<dyn SkillStore>>,
+    /// LLM audit-log settings; no audit store is built unless enabled.
+    pub llm_audit: Option<LlmAuditConfig>,
 }
 
 impl StoreBuilder {
@@ -160,6 +211,7 @@ impl StoreBuilder {
             prompt_template_store: None,
             secret_store: None,
             skill_store: None,
+            llm_audit: None,
         }
         .register_default_store_types()
     }
@@ -255,6 +307,13 @@ impl StoreBuilder {
         self
     }
 
+    /// Record LLM calls in the metadata store's audit log when
+    /// `config.enabled`.
+    pub fn with_llm_audit(mut self, config: Option<LlmAuditConfig>) -> Self {
+        self.llm_audit = config;
+        self
+    }
+
     /// Build InitializedStores, initializing only stores that weren't pre-provided
     pub async fn build(self) -> Result<InitializedStores> {
         let metadata_factory = self
@@ -285,20 +344,30 @@ impl StoreBuilder {
             .clone()
             .or_else(|| Some(metadata_factory.skill_store()));
 
-        // Initialize memory store if configured and not provided
+        // Initialize memory store if configured and not provided; its
+        // pseudonym mapping lives in the same database's session store.
         let memory_store = if let Some(store) = self.memory_store.clone() {
             store
         } else if let Some(memory_config) = &self.config.memory {
             let factory = self
                 .resolve_factory(&memory_config.store_type, memory_config.db_config.clone())
                 .await?;
-            Some(factory.memory_store())
+            let store = factory.memory_store();
+            Some(match &memory_config.anonymization {
+                Some(anonymization) => {
+                    AnonymizingMemoryStore::wrap(store, factory.session_store(), anonymization)?
+                }
+                None => store,
+            })
         } else {
             None
         };
 
-        // Initialize session stores if not provided
-        let (thread_store, task_store, scratchpad_store, session_store) =
+        // Initialize session stores if not provided; a task store built
+        // here stores its events in batches, and its analytics are served
+        // when the factory has them.
+        let task_store_provided = self.task_store.is_some();
+        let (thread_store, task_store, scratchpad_store, session_store, analytics_store) =
             if self.thread_store.is_some()
                 && self.task_store.is_some()
                 && self.scratchpad_store.is_some()
@@ -310,6 +379,7 @@ impl StoreBuilder {
                     self.task_store.unwrap(),
                     self.scratchpad_store.unwrap(),
                     self.session_store.unwrap(),
+                    None,
                 )
             } else if self.config.session.ephemeral {
                 #[cfg(not(feature = "sqlite"))]
@@ -331,6 +401,11 @@ impl StoreBuilder {
                             .unwrap_or_else(|| placeholder_factory.scratchpad_store()),
                         self.session_store
                             .unwrap_or_else(|| placeholder_factory.session_store()),
+                        if task_store_provided {
+                            None
+                        } else {
+                            placeholder_factory.analytics_store()
+                        },
                     )
                 }
             } else {
@@ -348,9 +423,27 @@ impl StoreBuilder {
                         .unwrap_or_else(|| factory.scratchpad_store()),
                     self.session_store
                         .unwrap_or_else(|| factory.session_store()),
+                    if task_store_provided {
+                        None
+                    } else {
+                        factory.analytics_store()
+                    },
                 )
             };
 
+        // The LLM audit log, when enabled, lives in the metadata database.
+        let llm_audit_store = self.llm_audit.filter(|audit| audit.enabled).map(|audit| {
+            metadata_factory
+                .llm_audit_store(&audit)
+                .unwrap_or_else(|| Arc::new(InMemoryLlmAuditStore::new(audit)))
+        });
+
+        let task_store = if task_store_provided {
+            task_store
+        } else {
+            BufferedTaskStore::wrap(task_store, &self.config.session.event_writes)
+        };
+
         // Initialize external tool calls store (always in-memory) if not provided
         let external_tool_calls_store = self.external_tool_calls_store.unwrap_or_else(|| {
             Arc::new(InMemoryExternalToolCallsStore::new()) as Arc<dyn ExternalToolCallsStore>
@@ -379,6 +472,10 @@ impl StoreBuilder {
             span_store: None,
             note_store,
             provider_store: metadata_factory.provider_store(),
+            llm_audit_store,
+            background_job_store: metadata_factory.background_job_store(),
+            plugin_kv_store: metadata_factory.plugin_kv_store(&self.config.metadata.plugin_storage),
+            analytics_store,
         })
     }
 }
@@ -492,6 +589,10 @@ pub async fn create_ephemeral_execution_stores(
         span_store: base_stores.span_store.clone(),
         note_store: base_stores.note_store.clone(),
         provider_store: base_stores.provider_store.clone(),
+        llm_audit_store: base_stores.llm_audit_store.clone(),
+        background_job_store: base_stores.background_job_store.clone(),
+        plugin_kv_store: base_stores.plugin_kv_store.clone(),
+        analytics_store: base_stores.analytics_store.clone(),
     })
 }
 
@@ -540,5 +641,9 @@ pub async fn prepare_stores_for_execution(
         span_store: base_stores.span_store.clone(),
         note_store: base_stores.note_store.clone(),
         provider_store: base_stores.provider_store.clone(),
+        llm_audit_store: base_stores.llm_audit_store.clone(),
+        background_job_store: base_stores.background_job_store.clone(),
+        plugin_kv_store: base_stores.plugin_kv_store.clone(),
+        analytics_store: base_stores.analytics_store.clone(),
     })
 }
@@ -1,11 +1,19 @@
+mod anonymizing_memory_store;
 mod auth;
+mod buffered_task_store;
+pub mod evals;
 pub mod external_tool_calls;
+pub mod llm_audit;
 pub mod prompt;
 use std::collections::HashMap;
 
+pub use anonymizing_memory_store::AnonymizingMemoryStore;
 pub use auth::*;
+pub use buffered_task_store::BufferedTaskStore;
 // Re-export the main store traits and types
+pub use evals::FileEvalStore;
 pub use external_tool_calls::*;
+pub use llm_audit::InMemoryLlmAuditStore;
 
 pub mod diesel_store;
 mod instrumentation;
@@ -0,0 +1,148 @@
+//! In-process [`LlmAuditStore`].
+//!
+//! Keeps records in memory for the lifetime of the process and applies the
+//! retention policy from [`LlmAuditConfig`] on every append, so a
+//! long-running server never holds more than `retention_days` of history.
+//! Used for store backends without a persistent audit log; diesel backends
+//! keep it in the `llm_audit_records` table.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use distri_types::api::audit::{LlmAuditConfig, LlmAuditQuery, LlmAuditRecord};
+use distri_types::stores::LlmAuditStore;
+use tokio::sync::RwLock;
+
+#[derive(Default)]
+pub struct InMemoryLlmAuditStore {
+    config: LlmAuditConfig,
+    records: RwLock<Vec<LlmAuditRecord>>,
+}
+
+impl InMemoryLlmAuditStore {
+    pub fn new(config: LlmAuditConfig) -> Self {
+        Self {
+            config,
+            records: RwLock::new(Vec::new()),
+        }
+    }
+}
+
+#[async_trait]
+impl LlmAuditStore for InMemoryLlmAuditStore {
+    fn redact(&self) -> bool {
+        self.config.redact
+    }
+
+    async fn record(&self, record: LlmAuditRecord) -> anyhow::Result<()> {
+        let mut records = self.records.write().await;
+        if let Some(cutoff) = self.config.retention_cutoff(Utc::now()) {
+            records.retain(|r| r.created_at >= cutoff);
+        }
+        records.push(record);
+        Ok(())
+    }
+
+    async fn list(&self, query: &LlmAuditQuery) -> anyhow::Result<Vec<LlmAuditRecord>> {
+        let records = self.records.read().await;
+        let mut matched: Vec<LlmAuditRecord> = records
+            .iter()
+            .filter(|r| query.matches(r))
+            .cloned()
+            .collect();
+        matched.sort_by_key(|r| std::cmp::Reverse(r.created_at));
+        if let Some(limit) = query.limit {
+            matched.truncate(limit);
+        }
+        Ok(matched)
+    }
+
+    async fn purge_before(&self, cutoff: DateTime<Utc>) -> anyhow::Result<usize> {
+        let mut records = self.records.write().await;
+        let before = records.len();
+        records.retain(|r| r.created_at >= cutoff);
+        Ok(before - records.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    fn record(agent: &str, at: DateTime<Utc>) -> LlmAuditRecord {
+        LlmAuditRecord {
+            id: Uuid::new_v4(),
+            created_at: at,
+            agent_id: agent.into(),
+            user_id: "u".into(),
+            thread_id: "t".into(),
+            task_id: "k".into(),
+            workspace_id: None,
+            provider: "openai".into(),
+            model: "gpt-4.1-mini".into(),
+            streaming: true,
+            request: serde_json::json!([]),
+            response: serde_json::Value::Null,
+            error: None,
+            input_tokens: 1,
+            output_tokens: 1,
+            duration_ms: 5,
+            redacted: true,
+        }
+    }
+
+    #[tokio::test]
+    async fn list_is_newest_first_and_limited() {
+        let store = InMemoryLlmAuditStore::default();
+        let now = Utc::now();
+        for (i, agent) in ["a", "b", "c"].iter().enumerate() {
+            store
+                .record(record(agent, now + chrono::Duration::seconds(i as i64)))
+                .await
+                .unwrap();
+        }
+        let out = store
+            .list(&LlmAuditQuery {
+                limit: Some(2),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        let agents: Vec<_> = out.iter().map(|r| r.agent_id.as_str()).collect();
+        assert_eq!(agents, vec!["c", "b"]);
+    }
+
+    #[tokio::test]
+    async fn retention_drops_expired_records_on_append() {
+        let store = InMemoryLlmAuditStore::new(LlmAuditConfig {
+            enabled: true,
+            redact: true,
+            retention_days: Some(7),
+        });
+        let now = Utc::now();
+        store
+            .record(record("old", now - chrono::Duration::days(30)))
+            .await
+            .unwrap();
+        store.record(record("new", now)).await.unwrap();
+        let out = store.list(&LlmAuditQuery::default()).await.unwrap();
+        assert_eq!(out.len(), 1);
+        assert_eq!(out[0].agent_id, "new");
+    }
+
+    #[tokio::test]
+    async fn purge_before_reports_removed_count() {
+        let store = InMemoryLlmAuditStore::default();
+        let now = Utc::now();
+        store
+            .record(record("a", now - chrono::Duration::days(2)))
+            .await
+            .unwrap();
+        store.record(record("b", now)).await.unwrap();
+        let removed = store
+            .purge_before(now - chrono::Duration::days(1))
+            .await
+            .unwrap();
+        assert_eq!(removed, 1);
+    }
+}
@@ -48,6 +48,10 @@ pub struct ThreadModel {
     /// JSON-serialized `ContextBudget` from the most recent run. Updated by
     /// the orchestrator's usage hook; `None` until the first event lands.
     pub last_context_budget: Option<String>,
+    /// JSON object of the thread's template variables.
+    pub variables: String,
+    /// Set while the thread's tasks are in cold storage.
+    pub archived_at: Option<NaiveDateTime>,
 }
 
 #[derive(Debug, Clone, Insertable)]
@@ -68,6 +72,7 @@ pub struct NewThreadModel<'a> {
     pub input_tokens: i64,
     pub output_tokens: i64,
     pub total_tokens: i64,
+    pub variables: &'a str,
 }
 
 #[derive(Debug, Clone, AsChangeset)]
@@ -82,6 +87,7 @@ pub struct ThreadChangeset<'a> {
     pub external_id: Option<Option<&'a str>>,
     pub channel_id: Option<Option<&'a str>>,
     pub last_context_budget: Option<Option<&'a str>>,
+    pub variables: Option<&'a str>,
 }
 
 #[derive(Debug, Clone, Queryable, Identifiable, Selectable, Associations)]
@@ -98,6 +104,9 @@ pub struct TaskModel {
     pub inner_task_id: Option<String>,
     pub ended_at: Option<i64>,
     pub invocation: String,
+    pub archived_at: Option<i64>,
+    /// JSON array of task ids.
+    pub reference_task_ids: String,
 }
 
 #[derive(Debug, Clone, Insertable)]
@@ -112,6 +121,7 @@ pub struct NewTaskModel<'a> {
     pub remote: bool,
     pub inner_task_id: Option<&'a str>,
     pub invocation: &'a str,
+    pub reference_task_ids: &'a str,
 }
 
 #[derive(Debug, Clone, AsChangeset)]
@@ -633,3 +643,115 @@ pub struct NoteChangeset<'a> {
     pub tags: Option<&'a str>,
     pub updated_at: NaiveDateTime,
 }
+
+// ── Background job models ──────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Queryable, Identifiable, Selectable)]
+#[diesel(table_name = crate::schema::background_jobs)]
+pub struct BackgroundJobModel {
+    pub id: String,
+    pub idempotency_key: String,
+    pub agent_id: String,
+    pub user_id: String,
+    pub workspace_id: Option<String>,
+    pub task_id: String,
+    pub thread_id: String,
+    pub params: String, // JSON MessageSendParams
+    pub status: String,
+    pub attempts: i32,
+    pub max_attempts: i32,
+    pub worker_id: Option<String>,
+    pub lease_expires_at: Option<NaiveDateTime>,
+    pub last_error: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Debug, Clone, Insertable)]
+#[diesel(table_name = crate::schema::background_jobs)]
+pub struct NewBackgroundJobModel<'a> {
+    pub id: &'a str,
+    pub idempotency_key: &'a str,
+    pub agent_id: &'a str,
+    pub user_id: &'a str,
+    pub workspace_id: Option<&'a str>,
+    pub task_id: &'a str,
+    pub thread_id: &'a str,
+    pub params: &'a str,
+    pub status: &'a str,
+    pub attempts: i32,
+    pub max_attempts: i32,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+// ── Plugin storage models ──────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Insertable)]
+#[diesel(table_name = crate::schema::plugin_kv)]
+pub struct NewPluginKvModel<'a> {
+    pub plugin: &'a str,
+    pub key: &'a str,
+    pub value: &'a str,
+    pub size: i32,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Debug, Clone, AsChangeset)]
+#[diesel(table_name = crate::schema::plugin_kv)]
+pub struct PluginKvChangeset<'a> {
+    pub value: &'a str,
+    pub size: i32,
+    pub updated_at: NaiveDateTime,
+}
+
+// ── Analytics models ───────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Queryable, Selectable, Insertable)]
+#[diesel(table_name = crate::schema::analytics_daily_runs)]
+pub struct AnalyticsDailyRunsModel {
+    pub day: String,
+    pub agent_id: String,
+    pub runs: i64,
+    pub succeeded: i64,
+    pub failed: i64,
+    pub steps: i64,
+    pub input_tokens: i64,
+    pub output_tokens: i64,
+    pub cost_usd: f64,
+}
+
+#[derive(Debug, Clone, Queryable, Selectable, Insertable)]
+#[diesel(table_name = crate::schema::analytics_daily_tools)]
+pub struct AnalyticsDailyToolsModel {
+    pub day: String,
+    pub agent_id: String,
+    pub tool_name: String,
+    pub calls: i64,
+    pub failures: i64,
+}
+
+// ── LLM audit models ───────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Queryable, Selectable, Insertable)]
+#[diesel(table_name = crate::schema::llm_audit_records)]
+pub struct LlmAuditRecordModel {
+    pub id: String,
+    pub created_at: NaiveDateTime,
+    pub agent_id: String,
+    pub user_id: String,
+    pub thread_id: String,
+    pub task_id: String,
+    pub workspace_id: Option<String>,
+    pub provider: String,
+    pub model: String,
+    pub streaming: bool,
+    pub request: String,  // JSON
+    pub response: String, // JSON
+    pub error: Option<String>,
+    pub input_tokens: i64,
+    pub output_tokens: i64,
+    pub duration_ms: i64,
+    pub redacted: bool,
+}
@@ -37,6 +37,8 @@ diesel::table! {
         output_tokens -> BigInt,
         total_tokens -> BigInt,
         last_context_budget -> Nullable<Jsonb>,
+        variables -> Text,
+        archived_at -> Nullable<Timestamp>,
     }
 }
 
@@ -54,6 +56,8 @@ diesel::table! {
         inner_task_id -> Nullable<Text>,
         ended_at -> Nullable<BigInt>,
         invocation -> Text,
+        archived_at -> Nullable<BigInt>,
+        reference_task_ids -> Text,
     }
 }
 
@@ -328,6 +332,94 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    use diesel::sql_types::*;
+
+    background_jobs (id) {
+        id -> Text,
+        idempotency_key -> Text,
+        agent_id -> Text,
+        user_id -> Text,
+        workspace_id -> Nullable<Text>,
+        task_id -> Text,
+        thread_id -> Text,
+        params -> Text,         // JSON MessageSendParams
+        status -> Text,
+        attempts -> Integer,
+        max_attempts -> Integer,
+        worker_id -> Nullable<Text>,
+        lease_expires_at -> Nullable<Timestamp>,
+        last_error -> Nullable<Text>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+
+    plugin_kv (plugin, key) {
+        plugin -> Text,
+        key -> Text,
+        value -> Text,          // JSON
+        size -> Integer,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+
+    analytics_daily_runs (day, agent_id) {
+        day -> Text,            // YYYY-MM-DD, UTC
+        agent_id -> Text,
+        runs -> BigInt,
+        succeeded -> BigInt,
+        failed -> BigInt,
+        steps -> BigInt,
+        input_tokens -> BigInt,
+        output_tokens -> BigInt,
+        cost_usd -> Double,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+
+    analytics_daily_tools (day, agent_id, tool_name) {
+        day -> Text,            // YYYY-MM-DD, UTC
+        agent_id -> Text,
+        tool_name -> Text,
+        calls -> BigInt,
+        failures -> BigInt,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+
+    llm_audit_records (id) {
+        id -> Text,
+        created_at -> Timestamp,
+        agent_id -> Text,
+        user_id -> Text,
+        thread_id -> Text,
+        task_id -> Text,
+        workspace_id -> Nullable<Text>,
+        provider -> Text,
+        model -> Text,
+        streaming -> Bool,
+        request -> Text,        // JSON
+        response -> Text,       // JSON
+        error -> Nullable<Text>,
+        input_tokens -> BigInt,
+        output_tokens -> BigInt,
+        duration_ms -> BigInt,
+        redacted -> Bool,
+    }
+}
+
 diesel::allow_tables_to_appear_in_same_query!(
     agent_configs,
     threads,
@@ -351,4 +443,9 @@ diesel::allow_tables_to_appear_in_same_query!(
     connection_tokens,
     connection_oauth_states,
     notes,
+    background_jobs,
+    plugin_kv,
+    analytics_daily_runs,
+    analytics_daily_tools,
+    llm_audit_records,
 );
@@ -0,0 +1,724 @@
+//! AWS Bedrock Runtime client for the Converse API, built on reqwest.
+//!
+//! One request shape covers every Bedrock chat model:
+//! - `POST /model/{modelId}/converse` — a whole response
+//! - `POST /model/{modelId}/converse-stream` — an `application/vnd.amazon.eventstream`
+//!   body of binary frames, decoded here into [`ConverseStreamEvent`]s
+//! - Tool use through `toolConfig`, for the models that support it
+//!
+//! Requests are signed with SigV4 (service `bedrock`).
+//!
+//! Reference: https://docs.aws.amazon.com/bedrock/latest/APIReference/API_runtime_Converse.html
+//! Reference: https://docs.aws.amazon.com/bedrock/latest/APIReference/API_runtime_ConverseStream.html
+
+use crate::sigv4::{self, AwsCredentials, SigningRequest};
+use futures::Stream;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use std::pin::Pin;
+
+const SIGNING_SERVICE: &str = "bedrock";
+
+// ─── Request Types ───────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConverseRequest {
+    pub messages: Vec<BedrockMessage>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub system: Vec<SystemBlock>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub inference_config: Option<InferenceConfig>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_config: Option<ToolConfig>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BedrockMessage {
+    /// `user` or `assistant`.
+    pub role: String,
+    pub content: Vec<ContentBlock>,
+}
+
+/// A content block of a request message.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ContentBlock {
+    Text(String),
+    Image(ImageBlock),
+    Document(DocumentBlock),
+    ToolUse(ToolUseBlock),
+    ToolResult(ToolResultBlock),
+    CachePoint(CachePoint),
+}
+
+/// A block of the system prompt.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SystemBlock {
+    Text(String),
+    CachePoint(CachePoint),
+}
+
+/// Ends a cacheable prefix of the system prompt, the tools or the messages.
+/// Only models with prompt caching accept it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachePoint {
+    /// Always `default`.
+    #[serde(rename = "type")]
+    pub cache_type: String,
+}
+
+impl Default for CachePoint {
+    fn default() -> Self {
+        Self {
+            cache_type: "default".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImageBlock {
+    /// `png`, `jpeg`, `gif` or `webp`.
+    pub format: String,
+    pub source: BytesSource,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DocumentBlock {
+    /// `pdf`, `csv`, `doc`, `docx`, `xls`, `xlsx`, `html`, `txt` or `md`.
+    pub format: String,
+    /// Letters, digits, spaces, hyphens, parentheses and brackets only.
+    pub name: String,
+    pub source: BytesSource,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BytesSource {
+    /// Base64-encoded content.
+    pub bytes: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolUseBlock {
+    pub tool_use_id: String,
+    pub name: String,
+    pub input: Value,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolResultBlock {
+    pub tool_use_id: String,
+    pub content: Vec<ToolResultContent>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ToolResultContent {
+    Text(String),
+    Json(Value),
+    Image(ImageBlock),
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InferenceConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_tokens: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub top_p: Option<f32>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolConfig {
+    pub tools: Vec<BedrockTool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_choice: Option<ToolChoice>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BedrockTool {
+    ToolSpec(ToolSpec),
+    CachePoint(CachePoint),
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolSpec {
+    pub name: String,
+    pub description: String,
+    pub input_schema: ToolInputSchema,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ToolInputSchema {
+    pub json: Value,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ToolChoice {
+    /// The model decides whether to call a tool.
+    Auto {},
+    /// The model must call at least one tool. Anthropic and Mistral Large
+    /// models only.
+    Any {},
+}
+
+// ─── Response Types ──────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConverseResponse {
+    pub output: ConverseOutput,
+    pub stop_reason: String,
+    #[serde(default)]
+    pub usage: Usage,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ConverseOutput {
+    pub message: ResponseMessage,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ResponseMessage {
+    #[serde(default)]
+    pub content: Vec<ResponseContentBlock>,
+}
+
+/// A content block of a response. Blocks of other kinds (reasoning,
+/// citations) leave both fields empty.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResponseContentBlock {
+    #[serde(default)]
+    pub text: Option<String>,
+    #[serde(default)]
+    pub tool_use: Option<ToolUseBlock>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Usage {
+    #[serde(default)]
+    pub input_tokens: u32,
+    #[serde(default)]
+    pub output_tokens: u32,
+    #[serde(default)]
+    pub cache_read_input_tokens: Option<u32>,
+    #[serde(default)]
+    pub cache_write_input_tokens: Option<u32>,
+}
+
+// ─── Streaming Types ─────────────────────────────────────────────────────────
+
+/// One event of a ConverseStream response, named by the frame's
+/// `:event-type` header.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", rename_all_fields = "camelCase")]
+pub enum ConverseStreamEvent {
+    MessageStart {
+        role: String,
+    },
+    ContentBlockStart {
+        content_block_index: u32,
+        start: ContentBlockStart,
+    },
+    ContentBlockDelta {
+        content_block_index: u32,
+        delta: ContentBlockDelta,
+    },
+    ContentBlockStop {
+        content_block_index: u32,
+    },
+    MessageStop {
+        stop_reason: String,
+    },
+    Metadata {
+        #[serde(default)]
+        usage: Usage,
+    },
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContentBlockStart {
+    #[serde(default)]
+    pub tool_use: Option<ToolUseStart>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolUseStart {
+    pub tool_use_id: String,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContentBlockDelta {
+    #[serde(default)]
+    pub text: Option<String>,
+    #[serde(default)]
+    pub tool_use: Option<ToolUseDelta>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ToolUseDelta {
+    /// A fragment of the tool input's JSON.
+    pub input: String,
+}
+
+// ─── Client ──────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone)]
+pub struct BedrockClient {
+    client: reqwest::Client,
+    base_url: String,
+    region: String,
+    credentials: AwsCredentials,
+    additional_headers: HashMap<String, String>,
+}
+
+impl BedrockClient {
+    /// `base_url` defaults to the region's `bedrock-runtime` endpoint.
+    pub fn new(
+        credentials: AwsCredentials,
+        region: String,
+        base_url: Option<String>,
+        additional_headers: HashMap<String, String>,
+    ) -> Self {
+        let base_url = base_url
+            .filter(|url| !url.is_empty())
+            .unwrap_or_else(|| distri_types::ModelProvider::aws_bedrock_base_url(&region));
+        Self {
+            client: reqwest::Client::new(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            region,
+            credentials,
+            additional_headers,
+        }
+    }
+
+    /// Non-streaming Converse call
+    pub async fn converse(
+        &self,
+        model_id: &str,
+        request: &ConverseRequest,
+    ) -> Result<ConverseResponse, distri_types::AgentError> {
+        let response = self.send(model_id, "converse", request).await?;
+        let body = response.text().await.map_err(|e| {
+            distri_types::AgentError::LLMError(format!("Failed to read Bedrock response: {}", e))
+        })?;
+
+        serde_json::from_str(&body).map_err(|e| {
+            tracing::error!(
+                "Failed to parse Bedrock response: {} body={}",
+                e,
+                &body[..body.len().min(500)]
+            );
+            distri_types::AgentError::LLMError(format!("Failed to parse Bedrock response: {}", e))
+        })
+    }
+
+    /// Streaming Converse call - returns the decoded event stream
+    pub async fn converse_stream(
+        &self,
+        model_id: &str,
+        request: &ConverseRequest,
+    ) -> Result<
+        Pin<Box<dyn Stream<Item = Result<ConverseStreamEvent, distri_types::AgentError>> + Send>>,
+        distri_types::AgentError,
+    > {
+        let response = self.send(model_id, "converse-stream", request).await?;
+        Ok(Self::parse_event_stream(response))
+    }
+
+    async fn send(
+        &self,
+        model_id: &str,
+        action: &str,
+        request: &ConverseRequest,
+    ) -> Result<reqwest::Response, distri_types::AgentError> {
+        let url = reqwest::Url::parse(&self.base_url).map_err(|e| {
+            distri_types::AgentError::InvalidConfiguration(format!(
+                "Invalid Bedrock endpoint '{}': {}",
+                self.base_url, e
+            ))
+        })?;
+        let host = match url.port() {
+            Some(port) => format!("{}:{}", url.host_str().unwrap_or_default(), port),
+            None => url.host_str().unwrap_or_default().to_string(),
+        };
+        // Model ids contain `:`, so the path is percent-encoded once on the
+        // wire and once more in the canonical request, as for every AWS
+        // service other than S3.
+        let base_path = url.path().trim_end_matches('/');
+        let path = format!(
+            "{}/model/{}/{}",
+            base_path,
+            sigv4::uri_encode(model_id),
+            action
+        );
+        let canonical_uri = path
+            .split('/')
+            .map(sigv4::uri_encode)
+            .collect::<Vec<_>>()
+            .join("/");
+
+        let body = serde_json::to_vec(request).map_err(|e| {
+            distri_types::AgentError::LLMError(format!("Failed to encode Bedrock request: {}", e))
+        })?;
+        let amz_date = sigv4::amz_date_now();
+        let mut signed = BTreeMap::from([
+            ("content-type".to_string(), "application/json".to_string()),
+            ("host".to_string(), host),
+            ("x-amz-content-sha256".to_string(), sigv4::sha256_hex(&body)),
+            ("x-amz-date".to_string(), amz_date.clone()),
+        ]);
+        if let Some(token) = &self.credentials.session_token {
+            signed.insert("x-amz-security-token".to_string(), token.clone());
+        }
+        let authorization = sigv4::authorization(
+            &self.credentials,
+            &self.region,
+            SIGNING_SERVICE,
+            &amz_date,
+            &SigningRequest {
+                method: "POST",
+                canonical_uri: &canonical_uri,
+                headers: &signed,
+                payload: &body,
+            },
+        );
+
+        let mut headers = HeaderMap::new();
+        for (key, value) in self.additional_headers.iter().chain(signed.iter()) {
+            if let (Ok(name), Ok(val)) = (
+                HeaderName::from_bytes(key.as_bytes()),
+                HeaderValue::from_str(value),
+            ) {
+                headers.insert(name, val);
+            }
+        }
+        // reqwest sets Host from the URL.
+        headers.remove(reqwest::header::HOST);
+        if let Ok(val) = HeaderValue::from_str(&authorization) {
+            headers.insert(reqwest::header::AUTHORIZATION, val);
+        }
+
+        let endpoint = format!(
+            "{}://{}{}",
+            url.scheme(),
+            signed.get("host").map(String::as_str).unwrap_or_default(),
+            path
+        );
+        let response = self
+            .client
+            .post(&endpoint)
+            .headers(headers)
+            .body(body)
+            .send()
+            .await
+            .map_err(|e| {
+                distri_types::AgentError::LLMError(format!("Bedrock request failed: {}", e))
+            })?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            tracing::error!("Bedrock API error ({}): {}", status, body);
+            return Err(distri_types::AgentError::LLMError(format!(
+                "Bedrock API error ({}): {}",
+                status, body
+            )));
+        }
+        Ok(response)
+    }
+
+    /// Decode an `application/vnd.amazon.eventstream` body into typed events
+    fn parse_event_stream(
+        response: reqwest::Response,
+    ) -> Pin<Box<dyn Stream<Item = Result<ConverseStreamEvent, distri_types::AgentError>> + Send>>
+    {
+        use futures::StreamExt;
+
+        let byte_stream = response.bytes_stream();
+
+        let stream = async_stream::stream! {
+            let mut buffer: Vec<u8> = Vec::new();
+
+            tokio::pin!(byte_stream);
+
+            while let Some(chunk_result) = byte_stream.next().await {
+                let chunk = match chunk_result {
+                    Ok(c) => c,
+                    Err(e) => {
+                        yield Err(distri_types::AgentError::LLMError(format!("Stream read error: {}", e)));
+                        return;
+                    }
+                };
+                buffer.extend_from_slice(&chunk);
+
+                loop {
+                    let frame = match EventFrame::decode(&buffer) {
+                        Ok(Some((frame, used))) => {
+                            buffer.drain(..used);
+                            frame
+                        }
+                        Ok(None) => break,
+                        Err(e) => {
+                            yield Err(e);
+                            return;
+                        }
+                    };
+                    match frame.into_event() {
+                        Ok(Some(event)) => yield Ok(event),
+                        Ok(None) => {}
+                        Err(e) => {
+                            yield Err(e);
+                            return;
+                        }
+                    }
+                }
+            }
+        };
+
+        Box::pin(stream)
+    }
+}
+
+/// One frame of an AWS event stream: a prelude (total length, headers
+/// length, prelude CRC), headers, payload, and a trailing message CRC. The
+/// CRCs are not checked; TLS already protects the bytes in transit.
+#[derive(Debug)]
+struct EventFrame {
+    headers: HashMap<String, String>,
+    payload: Vec<u8>,
+}
+
+impl EventFrame {
+    const PRELUDE_LEN: usize = 12;
+    const CRC_LEN: usize = 4;
+
+    /// Decode the frame at the start of `buf`, returning it and its length,
+    /// or `None` when `buf` does not hold a whole frame yet.
+    fn decode(buf: &[u8]) -> Result<Option<(Self, usize)>, distri_types::AgentError> {
+        if buf.len() < Self::PRELUDE_LEN {
+            return Ok(None);
+        }
+        let total_len = u32::from_be_bytes([buf[0], buf[1], buf[2], buf[3]]) as usize;
+        let headers_len = u32::from_be_bytes([buf[4], buf[5], buf[6], buf[7]]) as usize;
+        if total_len < Self::PRELUDE_LEN + headers_len + Self::CRC_LEN {
+            return Err(distri_types::AgentError::LLMError(format!(
+                "Malformed Bedrock event frame (length {}, headers {})",
+                total_len, headers_len
+            )));
+        }
+        if buf.len() < total_len {
+            return Ok(None);
+        }
+
+        let headers_end = Self::PRELUDE_LEN + headers_len;
+        let headers = Self::decode_headers(&buf[Self::PRELUDE_LEN..headers_end])?;
+        let payload = buf[headers_end..total_len - Self::CRC_LEN].to_vec();
+        Ok(Some((Self { headers, payload }, total_len)))
+    }
+
+    /// Decode header entries, keeping the string-valued ones (all the
+    /// headers Bedrock sends are strings).
+    fn decode_headers(mut buf: &[u8]) -> Result<HashMap<String, String>, distri_types::AgentError> {
+        let malformed =
+            || distri_types::AgentError::LLMError("Malformed Bedrock event headers".to_string());
+        let mut headers = HashMap::new();
+        while !buf.is_empty() {
+            let name_len = buf[0] as usize;
+            let name = buf.get(1..1 + name_len).ok_or_else(malformed)?;
+            let name = String::from_utf8_lossy(name).to_string();
+            buf = &buf[1 + name_len..];
+            let (&value_type, rest) = buf.split_first().ok_or_else(malformed)?;
+            buf = rest;
+            let value_len = match value_type {
+                0 | 1 => 0,
+                2 => 1,
+                3 => 2,
+                4 => 4,
+                5 | 8 => 8,
+                9 => 16,
+                6 | 7 => {
+                    let len = buf.get(..2).ok_or_else(malformed)?;
+                    buf = &buf[2..];
+                    u16::from_be_bytes([len[0], len[1]]) as usize
+                }
+                _ => return Err(malformed()),
+            };
+            let value = buf.get(..value_len).ok_or_else(malformed)?;
+            if value_type == 7 {
+                headers.insert(name, String::from_utf8_lossy(value).to_string());
+            }
+            buf = &buf[value_len..];
+        }
+        Ok(headers)
+    }
+
+    /// The event carried by this frame. Exceptions become errors; event
+    /// types this client does not know are skipped.
+    fn into_event(self) -> Result<Option<ConverseStreamEvent>, distri_types::AgentError> {
+        let header = |name: &str| self.headers.get(name).map(String::as_str);
+        if header(":message-type") == Some("exception") {
+            let message = serde_json::from_slice::<Value>(&self.payload)
+                .ok()
+                .and_then(|v| {
+                    v.get("message")
+                        .and_then(|m| m.as_str())
+                        .map(str::to_string)
+                })
+                .unwrap_or_else(|| String::from_utf8_lossy(&self.payload).to_string());
+            return Err(distri_types::AgentError::LLMError(format!(
+                "Bedrock stream error ({}): {}",
+                header(":exception-type").unwrap_or("unknown"),
+                message
+            )));
+        }
+        let Some(event_type) = header(":event-type") else {
+            return Ok(None);
+        };
+        let payload: Value = serde_json::from_slice(&self.payload).map_err(|e| {
+            distri_types::AgentError::LLMError(format!(
+                "Failed to parse Bedrock {} event: {}",
+                event_type, e
+            ))
+        })?;
+        let mut tagged = serde_json::Map::new();
+        tagged.insert(event_type.to_string(), payload);
+        match serde_json::from_value(Value::Object(tagged)) {
+            Ok(event) => Ok(Some(event)),
+            Err(e) => {
+                tracing::debug!("Skipping Bedrock stream event {}: {}", event_type, e);
+                Ok(None)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(event_type: &str, payload: &str) -> Vec<u8> {
+        let mut headers = Vec::new();
+        for (name, value) in [(":event-type", event_type), (":message-type", "event")] {
+            headers.push(name.len() as u8);
+            headers.extend_from_slice(name.as_bytes());
+            headers.push(7);
+            headers.extend_from_slice(&(value.len() as u16).to_be_bytes());
+            headers.extend_from_slice(value.as_bytes());
+        }
+        let total = 12 + headers.len() + payload.len() + 4;
+        let mut out = Vec::new();
+        out.extend_from_slice(&(total as u32).to_be_bytes());
+        out.extend_from_slice(&(headers.len() as u32).to_be_bytes());
+        out.extend_from_slice(&[0; 4]);
+        out.extend_from_slice(&headers);
+        out.extend_from_slice(payload.as_bytes());
+        out.extend_from_slice(&[0; 4]);
+        out
+    }
+
+    #[test]
+    fn decodes_stream_frames() {
+        let mut buf = frame(
+            "contentBlockStart",
+            r#"{"contentBlockIndex":1,"start":{"toolUse":{"toolUseId":"t1","name":"search"}},"p":"ab"}"#,
+        );
+        buf.extend(frame(
+            "contentBlockDelta",
+            r#"{"contentBlockIndex":1,"delta":{"toolUse":{"input":"{\"q\":"}}}"#,
+        ));
+
+        assert!(EventFrame::decode(&buf[..10]).unwrap().is_none());
+        let (first, used) = EventFrame::decode(&buf).unwrap().unwrap();
+        match first.into_event().unwrap() {
+            Some(ConverseStreamEvent::ContentBlockStart { start, .. }) => {
+                assert_eq!(start.tool_use.unwrap().name, "search");
+            }
+            other => panic!("unexpected event {:?}", other),
+        }
+        let (second, _) = EventFrame::decode(&buf[used..]).unwrap().unwrap();
+        match second.into_event().unwrap() {
+            Some(ConverseStreamEvent::ContentBlockDelta { delta, .. }) => {
+                assert_eq!(delta.tool_use.unwrap().input, "{\"q\":");
+            }
+            other => panic!("unexpected event {:?}", other),
+        }
+    }
+
+    #[test]
+    fn request_serializes_in_converse_shape() {
+        let request = ConverseRequest {
+            messages: vec![BedrockMessage {
+                role: "user".to_string(),
+                content: vec![
+                    ContentBlock::Text("hi".to_string()),
+                    ContentBlock::ToolResult(ToolResultBlock {
+                        tool_use_id: "t1".to_string(),
+                        content: vec![ToolResultContent::Text("ok".to_string())],
+                        status: None,
+                    }),
+                ],
+            }],
+            system: vec![],
+            inference_config: None,
+            tool_config: Some(ToolConfig {
+                tools: vec![],
+                tool_choice: Some(ToolChoice::Any {}),
+            }),
+        };
+        let v = serde_json::to_value(&request).unwrap();
+        assert_eq!(v["messages"][0]["content"][0]["text"], "hi");
+        assert_eq!(
+            v["messages"][0]["content"][1]["toolResult"]["toolUseId"],
+            "t1"
+        );
+        assert!(v.get("system").is_none());
+        assert_eq!(
+            v["toolConfig"]["toolChoice"],
+            serde_json::json!({"any": {}})
+        );
+    }
+
+    #[test]
+    fn cache_points_serialize_as_their_own_blocks() {
+        let system = vec![
+            SystemBlock::Text("be brief".to_string()),
+            SystemBlock::CachePoint(CachePoint::default()),
+        ];
+        assert_eq!(
+            serde_json::to_value(&system).unwrap(),
+            serde_json::json!([
+                {"text": "be brief"},
+                {"cachePoint": {"type": "default"}}
+            ])
+        );
+        let tool = serde_json::to_value(BedrockTool::ToolSpec(ToolSpec {
+            name: "search".to_string(),
+            description: "Search".to_string(),
+            input_schema: ToolInputSchema {
+                json: serde_json::json!({"type": "object"}),
+            },
+        }))
+        .unwrap();
+        assert_eq!(tool["toolSpec"]["name"], "search");
+    }
+}
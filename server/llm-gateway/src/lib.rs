@@ -1,3 +1,4 @@
+pub mod bedrock_client;
 pub mod claude_client;
 pub mod gateway_config;
 mod image;
@@ -6,6 +7,7 @@ pub mod observability;
 pub mod openai_responses_client;
 pub mod provider_config;
 mod providers_builder;
+pub mod sigv4;
 mod tts;
 mod tts_types;
 
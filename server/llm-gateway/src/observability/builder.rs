@@ -344,6 +344,30 @@ pub fn tool_span(attrs: &GenAiToolSpan) -> tracing::Span {
     span
 }
 
+/// Create a tracing span for a step a plugin tool recorded. Enter the tool
+/// span (or the enclosing plugin span) before calling this.
+pub fn plugin_span(
+    name: &str,
+    plugin: Option<&str>,
+    span_id: &str,
+    duration_ms: u64,
+) -> tracing::Span {
+    let span = tracing::trace_span!(
+        target: "gen_ai",
+        "distri.plugin_span",
+        "otel.name" = name,
+        "otel.status_code" = tracing::field::Empty,
+        "distri.plugin.name" = tracing::field::Empty,
+        "distri.plugin.span_id" = span_id,
+        "distri.plugin.duration_ms" = duration_ms as i64,
+        "error.message" = tracing::field::Empty,
+    );
+    if let Some(v) = plugin {
+        span.record("distri.plugin.name", v);
+    }
+    span
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
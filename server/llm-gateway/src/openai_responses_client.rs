@@ -110,6 +110,12 @@ pub struct CreateResponseRequest {
     pub stream: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub truncation: Option<Value>,
+    /// `{"effort": "low" | ...}` for reasoning models.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reasoning: Option<Value>,
+    /// Routes requests sharing a prefix to the same prompt cache.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prompt_cache_key: Option<String>,
 }
 
 // ─── Response Types ──────────────────────────────────────────────────────────
@@ -163,6 +169,15 @@ pub struct ResponseUsage {
     pub output_tokens: u32,
     #[serde(default)]
     pub total_tokens: u32,
+    #[serde(default)]
+    pub input_tokens_details: InputTokensDetails,
+}
+
+#[derive(Debug, Clone, Deserialize, Default)]
+pub struct InputTokensDetails {
+    /// Input tokens served from the prompt cache.
+    #[serde(default)]
+    pub cached_tokens: u32,
 }
 
 // ─── Streaming Types ─────────────────────────────────────────────────────────
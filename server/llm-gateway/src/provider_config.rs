@@ -4,7 +4,7 @@
 //! headers needed to build a `GatewayConfig`. Keeps all the provider-specific
 //! logic in one place instead of a giant match in llm.rs.
 
-use distri_types::ModelProvider;
+use distri_types::{ModelProvider, ModelSettings};
 use std::collections::HashMap;
 
 /// Resolved connection config for an LLM provider.
@@ -97,8 +97,22 @@ impl From<&ModelProvider> for ProviderClientConfig {
                 query_params: vec![],
                 send_api_key_header: true,
             },
-            ModelProvider::AwsBedrock { base_url, api_key } => Self {
-                base_url: base_url.clone(),
+            // Bedrock is called through `bedrock_client`, not an OpenAI
+            // client; this records where its requests go.
+            ModelProvider::AwsBedrock {
+                base_url,
+                api_key,
+                region,
+            } => Self {
+                base_url: if base_url.is_empty() {
+                    ModelProvider::aws_bedrock_base_url(
+                        region
+                            .as_deref()
+                            .unwrap_or(ModelProvider::aws_default_region()),
+                    )
+                } else {
+                    base_url.clone()
+                },
                 api_key_secret,
                 inline_api_key: api_key.clone(),
                 project_id: None,
@@ -157,6 +171,22 @@ impl From<&ModelProvider> for ProviderClientConfig {
     }
 }
 
+impl ProviderClientConfig {
+    /// Client config for `ms`: the provider's config, with Azure OpenAI
+    /// routed to the deployment `ms` maps its model to.
+    pub fn for_model_settings(ms: &ModelSettings) -> Self {
+        let mut config = Self::from(&ms.inner.provider);
+        if let ModelProvider::AzureOpenAI { base_url, .. } = &ms.inner.provider {
+            config.base_url = format!(
+                "{}/openai/deployments/{}",
+                base_url.trim_end_matches('/'),
+                ms.provider_model_id()
+            );
+        }
+        config
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -273,11 +303,43 @@ mod tests {
         let provider = ModelProvider::AwsBedrock {
             base_url: "https://bedrock-runtime.us-east-1.amazonaws.com/v1".to_string(),
             api_key: None,
+            region: None,
         };
         let config = ProviderClientConfig::from(&provider);
         assert_eq!(config.api_key_secret, "AWS_ACCESS_KEY_ID");
     }
 
+    #[test]
+    fn test_aws_bedrock_config_derives_endpoint_from_region() {
+        let provider = ModelProvider::AwsBedrock {
+            base_url: String::new(),
+            api_key: None,
+            region: Some("eu-west-1".to_string()),
+        };
+        let config = ProviderClientConfig::from(&provider);
+        assert_eq!(
+            config.base_url,
+            "https://bedrock-runtime.eu-west-1.amazonaws.com"
+        );
+    }
+
+    #[test]
+    fn test_azure_config_uses_mapped_deployment() {
+        let mut ms = ModelSettings::from_provider_model_str("azure_openai/gpt-4o")
+            .unwrap()
+            .unwrap();
+        if let ModelProvider::AzureOpenAI { base_url, .. } = &mut ms.inner.provider {
+            *base_url = "https://myresource.openai.azure.com".to_string();
+        }
+        ms.inner.provider_model = Some("prod-gpt4o".to_string());
+        let config = ProviderClientConfig::for_model_settings(&ms);
+        assert_eq!(
+            config.base_url,
+            "https://myresource.openai.azure.com/openai/deployments/prod-gpt4o"
+        );
+        assert_eq!(config.query_params[0].0, "api-version");
+    }
+
     #[test]
     fn test_google_vertex_config() {
         let provider = ModelProvider::GoogleVertex {
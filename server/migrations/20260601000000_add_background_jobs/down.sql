@@ -0,0 +1,5 @@
+DROP INDEX IF EXISTS idx_background_jobs_running;
+DROP INDEX IF EXISTS idx_background_jobs_queued;
+DROP INDEX IF EXISTS idx_background_jobs_task_id;
+DROP INDEX IF EXISTS idx_background_jobs_idempotency_key;
+DROP TABLE IF EXISTS background_jobs;
@@ -0,0 +1,30 @@
+-- Background jobs: persistent queue behind non-blocking `message/send`.
+-- Workers claim rows by flipping status to 'running' with a lease; rows
+-- whose lease lapses are re-queued until attempts reach max_attempts.
+CREATE TABLE IF NOT EXISTS background_jobs (
+    id               TEXT PRIMARY KEY NOT NULL,
+    idempotency_key  TEXT NOT NULL,
+    agent_id         TEXT NOT NULL,
+    user_id          TEXT NOT NULL,
+    workspace_id     TEXT,
+    task_id          TEXT NOT NULL,
+    thread_id        TEXT NOT NULL,
+    params           TEXT NOT NULL,               -- JSON MessageSendParams
+    status           TEXT NOT NULL DEFAULT 'queued'
+        CHECK (status IN ('queued', 'running', 'completed', 'failed', 'canceled')),
+    attempts         INTEGER NOT NULL DEFAULT 0,
+    max_attempts     INTEGER NOT NULL DEFAULT 3,
+    worker_id        TEXT,
+    lease_expires_at TIMESTAMP,
+    last_error       TEXT,
+    created_at       TIMESTAMP NOT NULL,
+    updated_at       TIMESTAMP NOT NULL
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_background_jobs_idempotency_key
+    ON background_jobs(idempotency_key);
+CREATE INDEX IF NOT EXISTS idx_background_jobs_task_id ON background_jobs(task_id);
+CREATE INDEX IF NOT EXISTS idx_background_jobs_queued
+    ON background_jobs(created_at) WHERE status = 'queued';
+CREATE INDEX IF NOT EXISTS idx_background_jobs_running
+    ON background_jobs(lease_expires_at) WHERE status = 'running';
@@ -0,0 +1,2 @@
+ALTER TABLE tasks DROP COLUMN reference_task_ids;
+ALTER TABLE tasks DROP COLUMN archived_at;
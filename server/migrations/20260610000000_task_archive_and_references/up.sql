@@ -0,0 +1,5 @@
+-- Regeneration: the tasks a regeneration replaces are soft-archived
+-- (hidden from the thread history, rows kept), and the new task records
+-- the task it was derived from.
+ALTER TABLE tasks ADD COLUMN archived_at BIGINT;
+ALTER TABLE tasks ADD COLUMN reference_task_ids TEXT NOT NULL DEFAULT '[]';
@@ -0,0 +1,4 @@
+-- Per-thread template variables ({{customer_name}}, {{environment}}…),
+-- rendered into the thread's user messages and system prompt. JSON object
+-- of string values.
+ALTER TABLE threads ADD COLUMN variables TEXT NOT NULL DEFAULT '{}';
@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS idx_threads_updated_at;
+ALTER TABLE threads DROP COLUMN archived_at;
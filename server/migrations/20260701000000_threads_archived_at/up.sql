@@ -0,0 +1,4 @@
+-- Thread archival: an archived thread keeps its row (so it is still
+-- listed) while its tasks and messages live in the object store.
+ALTER TABLE threads ADD COLUMN archived_at TIMESTAMP;
+CREATE INDEX IF NOT EXISTS idx_threads_updated_at ON threads(updated_at);
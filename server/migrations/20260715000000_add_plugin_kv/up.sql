@@ -0,0 +1,11 @@
+-- Plugin storage: persistent key-value entries of each plugin. `size` is
+-- the bytes of the key and its JSON value, summed for the plugin's quota.
+CREATE TABLE IF NOT EXISTS plugin_kv (
+    plugin     TEXT NOT NULL,
+    key        TEXT NOT NULL,
+    value      TEXT NOT NULL,               -- JSON
+    size       INTEGER NOT NULL,
+    created_at TIMESTAMP NOT NULL,
+    updated_at TIMESTAMP NOT NULL,
+    PRIMARY KEY (plugin, key)
+);
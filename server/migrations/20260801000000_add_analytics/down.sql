@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS analytics_daily_tools;
+DROP TABLE IF EXISTS analytics_daily_runs;
@@ -0,0 +1,23 @@
+-- Analytics: run and tool call counters per UTC day (`YYYY-MM-DD`) and
+-- agent, incremented as the task store stores run and tool events.
+CREATE TABLE IF NOT EXISTS analytics_daily_runs (
+    day           TEXT NOT NULL,
+    agent_id      TEXT NOT NULL,
+    runs          BIGINT NOT NULL DEFAULT 0,
+    succeeded     BIGINT NOT NULL DEFAULT 0,
+    failed        BIGINT NOT NULL DEFAULT 0,
+    steps         BIGINT NOT NULL DEFAULT 0,
+    input_tokens  BIGINT NOT NULL DEFAULT 0,
+    output_tokens BIGINT NOT NULL DEFAULT 0,
+    cost_usd      DOUBLE PRECISION NOT NULL DEFAULT 0,
+    PRIMARY KEY (day, agent_id)
+);
+
+CREATE TABLE IF NOT EXISTS analytics_daily_tools (
+    day       TEXT NOT NULL,
+    agent_id  TEXT NOT NULL,
+    tool_name TEXT NOT NULL,
+    calls     BIGINT NOT NULL DEFAULT 0,
+    failures  BIGINT NOT NULL DEFAULT 0,
+    PRIMARY KEY (day, agent_id, tool_name)
+);
@@ -0,0 +1,4 @@
+DROP INDEX IF EXISTS idx_llm_audit_records_user_id;
+DROP INDEX IF EXISTS idx_llm_audit_records_agent_id;
+DROP INDEX IF EXISTS idx_llm_audit_records_created_at;
+DROP TABLE IF EXISTS llm_audit_records;
@@ -0,0 +1,28 @@
+-- LLM audit log: one row per outbound LLM call, for `GET /v1/audit/llm`.
+-- Rows older than `llm_audit.retention_days` are purged as new ones arrive.
+CREATE TABLE IF NOT EXISTS llm_audit_records (
+    id            TEXT PRIMARY KEY NOT NULL,
+    created_at    TIMESTAMP NOT NULL,
+    agent_id      TEXT NOT NULL,
+    user_id       TEXT NOT NULL,
+    thread_id     TEXT NOT NULL,
+    task_id       TEXT NOT NULL,
+    workspace_id  TEXT,
+    provider      TEXT NOT NULL,
+    model         TEXT NOT NULL,
+    streaming     BOOLEAN NOT NULL DEFAULT FALSE,
+    request       TEXT NOT NULL,                -- JSON
+    response      TEXT NOT NULL,                -- JSON
+    error         TEXT,
+    input_tokens  BIGINT NOT NULL DEFAULT 0,
+    output_tokens BIGINT NOT NULL DEFAULT 0,
+    duration_ms   BIGINT NOT NULL DEFAULT 0,
+    redacted      BOOLEAN NOT NULL DEFAULT FALSE
+);
+
+CREATE INDEX IF NOT EXISTS idx_llm_audit_records_created_at
+    ON llm_audit_records(created_at);
+CREATE INDEX IF NOT EXISTS idx_llm_audit_records_agent_id
+    ON llm_audit_records(agent_id, created_at);
+CREATE INDEX IF NOT EXISTS idx_llm_audit_records_user_id
+    ON llm_audit_records(user_id, created_at);